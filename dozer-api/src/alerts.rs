@@ -0,0 +1,465 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dozer_types::chrono::{DateTime, Utc};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::{error, info};
+use dozer_types::models::api_config::{
+    default_alerts_evaluation_interval_in_millis, default_alerts_smtp_port, AlertChannelType,
+    AlertCondition, AlertRule, AlertsOptions, EmailAlertChannel,
+};
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::Serialize;
+use dozer_types::serde_json::json;
+use futures_util::Future;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::errors::ApiInitError;
+use crate::metrics_history::now_millis;
+use crate::CacheEndpoint;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Alert {
+    pub rule: String,
+    pub endpoint: String,
+    /// Whether the rule started firing, or stopped.
+    pub firing: bool,
+    pub message: String,
+}
+
+/// An alert channel, with its email addresses parsed.
+#[derive(Debug)]
+enum Channel {
+    Webhook(String),
+    Slack(String),
+    Email {
+        options: EmailAlertChannel,
+        from: Mailbox,
+        to: Vec<Mailbox>,
+    },
+}
+
+impl Channel {
+    fn new(channel: &AlertChannelType) -> Result<Self, ApiInitError> {
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| ApiInitError::InvalidAlertEmail(address.to_string(), e))
+        };
+        Ok(match channel {
+            AlertChannelType::Webhook(url) => Channel::Webhook(url.clone()),
+            AlertChannelType::Slack(url) => Channel::Slack(url.clone()),
+            AlertChannelType::Email(options) => Channel::Email {
+                from: parse(&options.from)?,
+                to: options
+                    .to
+                    .iter()
+                    .map(|address| parse(address))
+                    .collect::<Result<_, _>>()?,
+                options: options.clone(),
+            },
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Silence {
+    rule: Option<String>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct AlertState {
+    /// The (rule, endpoint) pairs that are firing.
+    firing: HashSet<(String, String)>,
+    /// The request counts of each endpoint at the last evaluation.
+    request_counts: HashMap<String, (u64, u64)>,
+}
+
+/// Evaluates the alert rules against the endpoint metrics, and notifies the channels of the rules that start or stop
+/// firing.
+#[derive(Debug)]
+pub struct AlertManager {
+    rules: Vec<AlertRule>,
+    channels: Vec<Channel>,
+    silences: Vec<Silence>,
+    interval: Duration,
+    /// Endpoints that never committed are stale since then.
+    started_at_millis: u64,
+    client: reqwest::Client,
+    state: Mutex<AlertState>,
+}
+
+impl AlertManager {
+    pub fn new(options: &AlertsOptions) -> Result<Self, ApiInitError> {
+        let parse = |timestamp: &str| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .map_err(|e| ApiInitError::InvalidAlertSilence(timestamp.to_string(), e))
+        };
+        let silences = options
+            .silences
+            .iter()
+            .map(|silence| {
+                Ok(Silence {
+                    rule: silence.rule.clone(),
+                    starts_at: parse(&silence.starts_at)?,
+                    ends_at: parse(&silence.ends_at)?,
+                })
+            })
+            .collect::<Result<_, ApiInitError>>()?;
+        Ok(Self {
+            rules: options.rules.clone(),
+            channels: options
+                .channels
+                .iter()
+                .filter_map(|channel| channel.channel.as_ref())
+                .map(Channel::new)
+                .collect::<Result<_, _>>()?,
+            silences,
+            interval: Duration::from_millis(
+                options
+                    .evaluation_interval_in_millis
+                    .unwrap_or_else(default_alerts_evaluation_interval_in_millis),
+            ),
+            started_at_millis: now_millis(),
+            client: reqwest::Client::new(),
+            state: Mutex::new(AlertState::default()),
+        })
+    }
+
+    /// Evaluates the rules against `cache_endpoints` every evaluation interval, until `shutdown`.
+    pub async fn evaluate_periodically(
+        self: Arc<Self>,
+        cache_endpoints: Vec<Arc<CacheEndpoint>>,
+        shutdown: impl Future<Output = ()>,
+    ) {
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(self.interval) => (),
+            }
+            for alert in self.evaluate(&cache_endpoints, Utc::now()) {
+                self.notify(&alert).await;
+            }
+        }
+    }
+
+    /// Returns the alerts of the rules that started or stopped firing since the last evaluation, except silenced
+    /// ones. Rules keep their state while silenced, so a rule firing through a silence isn't sent after it.
+    fn evaluate(&self, cache_endpoints: &[Arc<CacheEndpoint>], now: DateTime<Utc>) -> Vec<Alert> {
+        let now_millis = now.timestamp_millis().max(0) as u64;
+        let mut state = self.state.lock();
+        let mut alerts = vec![];
+        for cache_endpoint in cache_endpoints {
+            let endpoint = &cache_endpoint.endpoint().name;
+            let request_counts = cache_endpoint.request_counts().get();
+            let (previous_requests, previous_errors) = state
+                .request_counts
+                .insert(endpoint.clone(), request_counts)
+                .unwrap_or_default();
+            let requests = request_counts.0.saturating_sub(previous_requests);
+            let errors = request_counts.1.saturating_sub(previous_errors);
+
+            for rule in &self.rules {
+                if rule
+                    .endpoint
+                    .as_ref()
+                    .map_or(false, |name| name != endpoint)
+                {
+                    continue;
+                }
+                let Some(condition) = &rule.condition else {
+                    continue;
+                };
+                let (firing, message) = match *condition {
+                    AlertCondition::LagInMillis(max) => match cache_endpoint.lag().get() {
+                        Some(lag) => (
+                            lag > max,
+                            format!("Lag is {lag} ms, the maximum is {max} ms"),
+                        ),
+                        None => (false, "No changes were committed".to_string()),
+                    },
+                    AlertCondition::ErrorPercentage(max) => {
+                        let percentage = if requests == 0 {
+                            0
+                        } else {
+                            errors * 100 / requests
+                        };
+                        let message = format!("{percentage}% of {requests} requests failed");
+                        (
+                            percentage > u64::from(max),
+                            format!("{message}, the maximum is {max}%"),
+                        )
+                    }
+                    AlertCondition::StaleInSeconds(max) => {
+                        let committed_at_millis = cache_endpoint
+                            .lag()
+                            .committed_at_millis()
+                            .unwrap_or(self.started_at_millis);
+                        let stale = now_millis.saturating_sub(committed_at_millis) / 1000;
+                        let message = format!("No changes were committed for {stale} s");
+                        (stale > max, format!("{message}, the maximum is {max} s"))
+                    }
+                };
+                if let Some(alert) = transition(&mut state.firing, rule, endpoint, firing, message)
+                {
+                    if !self.is_silenced(&alert.rule, now) {
+                        alerts.push(alert);
+                    }
+                }
+            }
+        }
+        alerts
+    }
+
+    fn is_silenced(&self, rule: &str, now: DateTime<Utc>) -> bool {
+        self.silences.iter().any(|silence| {
+            silence.rule.as_deref().map_or(true, |name| name == rule)
+                && silence.starts_at <= now
+                && now < silence.ends_at
+        })
+    }
+
+    async fn notify(&self, alert: &Alert) {
+        info!(
+            "Alert {} on endpoint {} {}: {}",
+            alert.rule,
+            alert.endpoint,
+            if alert.firing { "firing" } else { "resolved" },
+            alert.message
+        );
+        for channel in &self.channels {
+            let result = match channel {
+                Channel::Webhook(url) => self.post(url, alert).await,
+                Channel::Slack(url) => {
+                    let text = format!("{}: {}", alert_subject(alert), alert.message);
+                    self.post(url, &json!({ "text": text })).await
+                }
+                Channel::Email { options, from, to } => send_email(options, from, to, alert).await,
+            };
+            if let Err(e) = result {
+                error!("Failed to send alert {}: {e}", alert.rule);
+            }
+        }
+    }
+
+    async fn post(&self, url: &str, body: &impl Serialize) -> Result<(), BoxedError> {
+        self.client
+            .post(url)
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+fn alert_subject(alert: &Alert) -> String {
+    format!(
+        "[{}] {} on endpoint {}",
+        if alert.firing { "FIRING" } else { "RESOLVED" },
+        alert.rule,
+        alert.endpoint
+    )
+}
+
+async fn send_email(
+    options: &EmailAlertChannel,
+    from: &Mailbox,
+    to: &[Mailbox],
+    alert: &Alert,
+) -> Result<(), BoxedError> {
+    let mut message = Message::builder()
+        .from(from.clone())
+        .subject(alert_subject(alert));
+    for to in to {
+        message = message.to(to.clone());
+    }
+    let message = message.body(alert.message.clone())?;
+
+    let port = options.smtp_port.unwrap_or_else(default_alerts_smtp_port);
+    let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&options.smtp_host)?
+        .port(u16::try_from(port)?);
+    if let Some(username) = &options.username {
+        transport = transport.credentials(Credentials::new(
+            username.clone(),
+            options.password.clone().unwrap_or_default(),
+        ));
+    }
+    transport.build().send(message).await?;
+    Ok(())
+}
+
+/// Records whether the rule fires on the endpoint, returning an alert if it started or stopped.
+fn transition(
+    firing_rules: &mut HashSet<(String, String)>,
+    rule: &AlertRule,
+    endpoint: &str,
+    firing: bool,
+    message: String,
+) -> Option<Alert> {
+    let key = (rule.name.clone(), endpoint.to_string());
+    let changed = if firing {
+        firing_rules.insert(key)
+    } else {
+        firing_rules.remove(&key)
+    };
+    changed.then(|| Alert {
+        rule: rule.name.clone(),
+        endpoint: endpoint.to_string(),
+        firing,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::api_config::{AlertChannel, AlertSilence};
+
+    use crate::test_utils;
+
+    use super::*;
+
+    fn rule(name: &str) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            endpoint: None,
+            condition: Some(AlertCondition::LagInMillis(1000)),
+        }
+    }
+
+    #[test]
+    fn test_transition() {
+        let mut firing_rules = HashSet::new();
+        let lag = rule("lag");
+
+        let alert = transition(&mut firing_rules, &lag, "films", true, "Late".to_string());
+        assert_eq!(
+            alert,
+            Some(Alert {
+                rule: "lag".to_string(),
+                endpoint: "films".to_string(),
+                firing: true,
+                message: "Late".to_string(),
+            })
+        );
+        // Only changes are alerted.
+        assert_eq!(
+            transition(&mut firing_rules, &lag, "films", true, "Late".to_string()),
+            None
+        );
+        assert!(transition(&mut firing_rules, &lag, "actors", true, String::new()).is_some());
+
+        let alert = transition(&mut firing_rules, &lag, "films", false, String::new()).unwrap();
+        assert!(!alert.firing);
+        assert_eq!(
+            transition(&mut firing_rules, &lag, "films", false, String::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let endpoint = test_utils::get_endpoint();
+        let cache_endpoint = Arc::new(
+            CacheEndpoint::open(
+                &*test_utils::initialize_cache(&endpoint.name, None),
+                vec![],
+                endpoint,
+            )
+            .unwrap(),
+        );
+        let cache_endpoints = [cache_endpoint.clone()];
+        let manager = AlertManager::new(&AlertsOptions {
+            rules: vec![rule("lag")],
+            ..Default::default()
+        })
+        .unwrap();
+        let now = Utc::now();
+
+        // Nothing committed yet.
+        assert_eq!(manager.evaluate(&cache_endpoints, now), vec![]);
+
+        cache_endpoint.lag().set(Duration::from_millis(1500));
+        assert_eq!(
+            manager.evaluate(&cache_endpoints, now),
+            vec![Alert {
+                rule: "lag".to_string(),
+                endpoint: cache_endpoint.endpoint().name.clone(),
+                firing: true,
+                message: "Lag is 1500 ms, the maximum is 1000 ms".to_string(),
+            }]
+        );
+        assert_eq!(manager.evaluate(&cache_endpoints, now), vec![]);
+
+        cache_endpoint.lag().set(Duration::from_millis(500));
+        let alerts = manager.evaluate(&cache_endpoints, now);
+        assert_eq!(alerts.len(), 1);
+        assert!(!alerts[0].firing);
+    }
+
+    #[test]
+    fn test_email_addresses() {
+        let email = |to: &str| AlertsOptions {
+            channels: vec![AlertChannel {
+                channel: Some(AlertChannelType::Email(EmailAlertChannel {
+                    smtp_host: "smtp.example.com".to_string(),
+                    from: "Dozer <alerts@example.com>".to_string(),
+                    to: vec![to.to_string()],
+                    ..Default::default()
+                })),
+            }],
+            ..Default::default()
+        };
+        assert!(AlertManager::new(&email("oncall@example.com")).is_ok());
+        assert!(matches!(
+            AlertManager::new(&email("oncall")),
+            Err(ApiInitError::InvalidAlertEmail(address, _)) if address == "oncall"
+        ));
+    }
+
+    #[test]
+    fn test_silences() {
+        let manager = AlertManager::new(&AlertsOptions {
+            rules: vec![rule("lag"), rule("errors")],
+            channels: vec![],
+            silences: vec![AlertSilence {
+                rule: Some("lag".to_string()),
+                starts_at: "2023-06-01T22:00:00Z".to_string(),
+                ends_at: "2023-06-02T02:00:00+02:00".to_string(),
+            }],
+            evaluation_interval_in_millis: None,
+        })
+        .unwrap();
+        let at = |timestamp: &str| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        assert!(manager.is_silenced("lag", at("2023-06-01T22:00:00Z")));
+        assert!(manager.is_silenced("lag", at("2023-06-01T23:59:59Z")));
+        assert!(!manager.is_silenced("lag", at("2023-06-02T00:00:00Z")));
+        assert!(!manager.is_silenced("lag", at("2023-06-01T21:59:59Z")));
+        assert!(!manager.is_silenced("errors", at("2023-06-01T23:00:00Z")));
+
+        assert!(matches!(
+            AlertManager::new(&AlertsOptions {
+                silences: vec![AlertSilence {
+                    rule: None,
+                    starts_at: "tonight".to_string(),
+                    ends_at: "2023-06-02T00:00:00Z".to_string(),
+                }],
+                ..Default::default()
+            }),
+            Err(ApiInitError::InvalidAlertSilence(..))
+        ));
+    }
+}
@@ -1,49 +1,275 @@
-use crate::auth::Access;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::auth::{Access, Subject};
+use crate::change_log::{Change, ChangeLog};
 use crate::errors::{ApiError, AuthError};
-use dozer_cache::cache::expression::QueryExpression;
+use crate::hot_keys::HotKeys;
+use crate::naming::{api_field_name, camel_case_fields, rename_query_fields};
+use crate::prepared_queries::{PreparedQueries, PreparedQuery};
+use dozer_cache::cache::expression::{QueryExpression, SortOption, SortOptions};
+use dozer_cache::cache::plan::{Plan, QueryPlanner};
+use dozer_cache::cache::stable_hash;
 use dozer_cache::cache::CacheRecord;
+use dozer_cache::errors::CacheError;
 use dozer_cache::{AccessFilter, CacheReader};
+use dozer_types::models::api_endpoint::{default_slow_query_threshold_in_millis, ApiEndpoint};
+use dozer_types::serde_json::{self, Value};
+use dozer_types::tracing::{debug, warn};
+use dozer_types::types::Field;
+use metrics::increment_counter;
 
 pub const API_LATENCY_HISTOGRAM_NAME: &str = "api_latency";
 pub const API_REQUEST_COUNTER_NAME: &str = "api_requests";
+pub const CACHE_READERS_FULL_COUNTER_NAME: &str = "cache_readers_full";
+/// The header, or gRPC metadata key, of the timeout a request asks for, in milliseconds.
+pub const QUERY_TIMEOUT_HEADER: &str = "x-dozer-query-timeout-ms";
+/// The header, or gRPC metadata key, of the order query results are in, in the syntax of `$order_by`.
+pub const ORDER_BY_HEADER: &str = "x-dozer-order-by";
+
+/// Get a record by its single field primary key, through the hot keys kept in memory if there are any.
 pub fn get_record(
     cache_reader: &CacheReader,
-    key: &[u8],
+    key: &Field,
+    hot_keys: Option<&HotKeys>,
     endpoint: &str,
     access: Option<Access>,
 ) -> Result<CacheRecord, ApiError> {
     let access_filter = get_access_filter(access, endpoint)?;
-    let record = cache_reader
-        .get(key, &access_filter)
-        .map_err(ApiError::NotFound)?;
-    Ok(record)
+    // This implementation must be consistent with `dozer_cache::cache::index::get_primary_key`
+    let read_from_cache = || {
+        cache_reader
+            .get(&key.encode(), &access_filter)
+            .map_err(|e| read_failed(endpoint, e, ApiError::NotFound))
+    };
+    let check_access = |record: &CacheRecord| {
+        cache_reader
+            .check_access(&record.record, &access_filter)
+            .map_err(|e| read_failed(endpoint, e, ApiError::NotFound))
+    };
+    match hot_keys {
+        Some(hot_keys) => hot_keys.get(key, check_access, read_from_cache),
+        None => read_from_cache(),
+    }
 }
 
 pub fn get_records_count(
     cache_reader: &CacheReader,
     exp: &mut QueryExpression,
-    endpoint: &str,
+    endpoint: &ApiEndpoint,
     access: Option<Access>,
+    subject: Option<&Subject>,
 ) -> Result<usize, ApiError> {
-    let access_filter = get_access_filter(access, endpoint)?;
-    cache_reader
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let request_log = RequestLog::new(endpoint, "count", exp, subject);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    let result = cache_reader
         .count(exp, access_filter)
-        .map_err(ApiError::CountFailed)
+        .map_err(|e| read_failed(&endpoint.name, e, ApiError::CountFailed));
+    request_log.finish(cache_reader, exp, result.as_ref().ok().copied());
+    result
 }
 
 /// Get multiple records
 pub fn get_records(
     cache_reader: &CacheReader,
     exp: &mut QueryExpression,
-    endpoint: &str,
+    endpoint: &ApiEndpoint,
     access: Option<Access>,
+    subject: Option<&Subject>,
 ) -> Result<Vec<CacheRecord>, ApiError> {
-    let access_filter = get_access_filter(access, endpoint)?;
-    cache_reader
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let request_log = RequestLog::new(endpoint, "query", exp, subject);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    let result = cache_reader
         .query(exp, access_filter)
+        .map_err(|e| read_failed(&endpoint.name, e, ApiError::QueryFailed));
+    request_log.finish(
+        cache_reader,
+        exp,
+        result.as_ref().ok().map(|records| records.len()),
+    );
+    result
+}
+
+/// Calls `f` with every record of a query in order until it breaks, reading them in one transaction if the cache can.
+///
+/// It's not logged as a request, because how long it takes depends on how fast `f` consumes the records.
+pub fn for_each_record(
+    cache_reader: &CacheReader,
+    exp: &mut QueryExpression,
+    endpoint: &ApiEndpoint,
+    access: Option<Access>,
+    f: &mut dyn FnMut(CacheRecord) -> ControlFlow<()>,
+) -> Result<(), ApiError> {
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    cache_reader
+        .for_each_record(exp, access_filter, f)
+        .map_err(|e| read_failed(&endpoint.name, e, ApiError::QueryFailed))
+}
+
+/// Count the records of a query template, with the plan of its previous queries.
+pub fn get_prepared_records_count(
+    cache_reader: &CacheReader,
+    prepared: &PreparedQuery,
+    exp: &mut QueryExpression,
+    endpoint: &ApiEndpoint,
+    access: Option<Access>,
+    subject: Option<&Subject>,
+) -> Result<usize, ApiError> {
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let request_log = RequestLog::new(endpoint, "count", exp, subject);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    let result = cache_reader
+        .count_prepared(exp, access_filter, prepared.plan())
+        .map_err(|e| read_failed(&endpoint.name, e, ApiError::CountFailed));
+    request_log.finish(cache_reader, exp, result.as_ref().ok().copied());
+    result
+}
+
+/// Get the records of a query template, with the plan of its previous queries.
+pub fn get_prepared_records(
+    cache_reader: &CacheReader,
+    prepared: &PreparedQuery,
+    exp: &mut QueryExpression,
+    endpoint: &ApiEndpoint,
+    access: Option<Access>,
+    subject: Option<&Subject>,
+) -> Result<Vec<CacheRecord>, ApiError> {
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let request_log = RequestLog::new(endpoint, "query", exp, subject);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    let result = cache_reader
+        .query_prepared(exp, access_filter, prepared.plan())
+        .map_err(|e| read_failed(&endpoint.name, e, ApiError::QueryFailed));
+    request_log.finish(
+        cache_reader,
+        exp,
+        result.as_ref().ok().map(|records| records.len()),
+    );
+    result
+}
+
+/// The order the records of `exp` are returned in, in the syntax of `$order_by` with the names the endpoint serves:
+/// the sort options followed by the fields breaking ties, see `QueryPlanner::tiebreaker`. `None` without sort options,
+/// the records being in the order of the scan. The fields of `exp` must have been renamed like `get_records` does.
+pub fn effective_order_by(
+    cache_reader: &CacheReader,
+    exp: &QueryExpression,
+    endpoint: &ApiEndpoint,
+) -> Option<String> {
+    if exp.order_by.0.is_empty() {
+        return None;
+    }
+    let camel_case = camel_case_fields(endpoint);
+    let (schema, secondary_indexes) = cache_reader.get_schema();
+    let order_by = QueryPlanner::new(
+        schema,
+        secondary_indexes,
+        exp.filter.as_ref(),
+        &exp.order_by,
+    )
+    .order_with_tiebreaker()
+    .ok()?
+    .0
+    .into_iter()
+    .map(|option| {
+        SortOption::new(
+            api_field_name(&option.field_name, camel_case),
+            option.direction,
+        )
+    })
+    .collect();
+    serde_json::to_string(&SortOptions(order_by)).ok()
+}
+
+/// Get the plan the cache would use to answer the query, without running it.
+pub fn explain_query(
+    cache_reader: &CacheReader,
+    exp: &mut QueryExpression,
+    endpoint: &ApiEndpoint,
+    access: Option<Access>,
+) -> Result<Plan, ApiError> {
+    rename_query_fields(exp, &cache_reader.get_schema().0, endpoint);
+    let access_filter = get_access_filter(access, &endpoint.name)?;
+    cache_reader
+        .explain(exp, access_filter)
         .map_err(ApiError::QueryFailed)
 }
 
+/// Get the changes after log position `since`, and the position to continue from.
+///
+/// Changes can't be filtered, so they're only readable with unrestricted access to the endpoint.
+pub fn get_changes(
+    change_log: Option<&ChangeLog>,
+    since: Option<u64>,
+    limit: usize,
+    endpoint: &str,
+    access: Option<Access>,
+) -> Result<(Vec<Change>, u64), ApiError> {
+    let access_filter = get_access_filter(access, endpoint)?;
+    if access_filter.filter.is_some() {
+        return Err(ApiError::ApiAuthError(AuthError::Unauthorized));
+    }
+    let change_log = change_log.ok_or(ApiError::ChangesNotRetained)?;
+    change_log
+        .since(since, limit)
+        .ok_or_else(|| ApiError::ChangesExpired(since.unwrap_or_default()))
+}
+
+/// Register a query template as `name`.
+///
+/// Templates are shared by all clients of the endpoint, so they're only registered with unrestricted access.
+/// Access filters still apply to every query of a template.
+pub fn register_prepared_query(
+    prepared_queries: &PreparedQueries,
+    name: String,
+    template: Value,
+    endpoint: &str,
+    access: Option<Access>,
+) -> Result<Arc<PreparedQuery>, ApiError> {
+    let access_filter = get_access_filter(access, endpoint)?;
+    if access_filter.filter.is_some() {
+        return Err(ApiError::ApiAuthError(AuthError::Unauthorized));
+    }
+    prepared_queries.register(name, template)
+}
+
+/// The instant a query of the endpoint must finish by, from the endpoint timeout and the timeout the request asks for,
+/// whichever is shorter.
+pub fn query_deadline(
+    endpoint: &ApiEndpoint,
+    requested: Option<&str>,
+) -> Result<Option<Instant>, ApiError> {
+    let requested = requested
+        .map(|millis| {
+            millis
+                .parse()
+                .map_err(|_| ApiError::InvalidQueryTimeout(millis.to_string()))
+        })
+        .transpose()?;
+    let timeout = match (endpoint.query_timeout_in_millis, requested) {
+        (Some(configured), Some(requested)) => Some(configured.min(requested)),
+        (configured, requested) => configured.or(requested),
+    };
+    Ok(timeout.map(|millis| Instant::now() + Duration::from_millis(millis)))
+}
+
+/// Maps a failed read to `into`, or to a timeout if it timed out.
+///
+/// Counts reads failing because all reader slots of the cache are taken, a sign `cache_max_readers` is too low.
+fn read_failed(endpoint: &str, error: CacheError, into: fn(CacheError) -> ApiError) -> ApiError {
+    if error.is_readers_full() {
+        increment_counter!(CACHE_READERS_FULL_COUNTER_NAME, "endpoint" => endpoint.to_string());
+    }
+    if let CacheError::QueryTimeout = error {
+        return ApiError::QueryTimeout;
+    }
+    into(error)
+}
+
 fn get_access_filter(access: Option<Access>, endpoint: &str) -> Result<AccessFilter, ApiError> {
     match access {
         None | Some(Access::All) => Ok(AccessFilter {
@@ -59,3 +285,85 @@ fn get_access_filter(access: Option<Access>, endpoint: &str) -> Result<AccessFil
         }
     }
 }
+
+/// Structured log of a `count` or `query` request, emitted when the request finishes.
+struct RequestLog<'a> {
+    endpoint: &'a str,
+    kind: &'static str,
+    filter_hash: String,
+    /// The subject of the request's token, `anonymous` without API security.
+    principal: &'a str,
+    slow_query_threshold: Duration,
+    start_time: Instant,
+}
+
+impl<'a> RequestLog<'a> {
+    fn new(
+        endpoint: &'a ApiEndpoint,
+        kind: &'static str,
+        query: &QueryExpression,
+        subject: Option<&'a Subject>,
+    ) -> Self {
+        let principal = subject.map_or("anonymous", |subject| subject.0.as_str());
+        let slow_query_threshold = Duration::from_millis(
+            endpoint
+                .slow_query_threshold_in_millis
+                .unwrap_or_else(default_slow_query_threshold_in_millis),
+        );
+        Self {
+            endpoint: &endpoint.name,
+            kind,
+            filter_hash: filter_hash(query),
+            principal,
+            slow_query_threshold,
+            start_time: Instant::now(),
+        }
+    }
+
+    /// `query` is the query after applying the access filter. `result_count` is `None` if the request failed.
+    fn finish(
+        self,
+        cache_reader: &CacheReader,
+        query: &QueryExpression,
+        result_count: Option<usize>,
+    ) {
+        let duration = self.start_time.elapsed();
+        if duration >= self.slow_query_threshold {
+            // The access filter is already applied to `query`, so we don't apply it again.
+            let no_access_filter = AccessFilter {
+                filter: None,
+                fields: vec![],
+            };
+            let plan = cache_reader
+                .explain(&mut query.clone(), no_access_filter)
+                .map_or_else(|e| format!("unavailable: {e}"), |plan| format!("{plan:?}"));
+            warn!(
+                endpoint = self.endpoint,
+                kind = self.kind,
+                filter_hash = %self.filter_hash,
+                duration_ms = duration.as_millis() as u64,
+                result_count = ?result_count,
+                principal = self.principal,
+                plan = %plan,
+                "Slow API request"
+            );
+        } else {
+            debug!(
+                endpoint = self.endpoint,
+                kind = self.kind,
+                filter_hash = %self.filter_hash,
+                duration_ms = duration.as_millis() as u64,
+                result_count = ?result_count,
+                principal = self.principal,
+                "API request"
+            );
+        }
+    }
+}
+
+/// Hashes the filter of `query`, so requests with the same filter can be grouped without logging filter values.
+fn filter_hash(query: &QueryExpression) -> String {
+    // The hash is stable across processes and builds, so it can be compared between logs of different runs.
+    let filter = serde_json::to_string(&query.filter).unwrap_or_default();
+    format!("{:016x}", stable_hash([filter]))
+}
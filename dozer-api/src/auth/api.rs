@@ -4,18 +4,23 @@ use actix_web::{
     Error, HttpMessage, HttpRequest, HttpResponse,
 };
 use actix_web_httpauth::extractors::bearer::BearerAuth;
-use dozer_types::{models::api_security::ApiSecurity, serde_json::json};
+use dozer_types::serde::Deserialize;
+use dozer_types::serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
 use tonic::{Response, Status};
 
 use crate::errors::{ApiError, AuthError};
 
-use super::{Access, Authorizer};
-use dozer_types::grpc_types::auth::GetAuthTokenResponse;
+use super::{Access, JwtSecrets, Subject, Tenant, DEFAULT_ROTATION_OVERLAP};
+use dozer_types::grpc_types::auth::{GetAuthTokenResponse, RotateSecretResponse};
 
 pub fn auth_grpc(
     access: Option<&Access>,
+    caller_tenant: Option<&Tenant>,
     tenant_access: String,
-    api_security: Option<ApiSecurity>,
+    tenant: Option<String>,
+    secrets: Option<&JwtSecrets>,
 ) -> Result<Response<GetAuthTokenResponse>, Status> {
     let access = match access {
         Some(access) => access.clone(),
@@ -28,23 +33,71 @@ pub fn auth_grpc(
             let tenant_access = dozer_types::serde_json::from_str(tenant_access.as_str())
                 .map_err(ApiError::InvalidAccessFilter)?;
 
-            let api_security = api_security
-                .ok_or_else(|| Status::permission_denied("Cannot access this method."))?;
+            let secrets =
+                secrets.ok_or_else(|| Status::permission_denied("Cannot access this method."))?;
+            let tenant = token_tenant(caller_tenant, tenant)
+                .map_err(|_| Status::permission_denied("Cannot create tokens for this tenant."))?;
 
-            let ApiSecurity::Jwt(secret) = api_security;
-
-            let auth = Authorizer::new(&secret, None, None);
-            let token = auth.generate_token(tenant_access, None).unwrap();
+            let token = secrets
+                .generate_tenant_token(tenant_access, tenant, None)
+                .unwrap();
             Ok(Response::new(GetAuthTokenResponse { token }))
         }
         Access::Custom(_) => Err(Status::permission_denied("Cannot access this method.")),
     }
 }
 
+/// Rotates the JWT secret. Only allowed with a master token that isn't scoped to a tenant.
+pub fn rotate_secret_grpc(
+    access: Option<&Access>,
+    caller_tenant: Option<&Tenant>,
+    secret: String,
+    overlap_in_secs: Option<u64>,
+    secrets: Option<&JwtSecrets>,
+) -> Result<Response<RotateSecretResponse>, Status> {
+    match access.unwrap_or(&Access::All) {
+        Access::All if caller_tenant.is_none() => {
+            let secrets =
+                secrets.ok_or_else(|| Status::permission_denied("Cannot access this method."))?;
+            if secret.is_empty() {
+                return Err(Status::invalid_argument("Secret cannot be empty"));
+            }
+
+            let overlap = overlap_in_secs.map_or(DEFAULT_ROTATION_OVERLAP, Duration::from_secs);
+            secrets.rotate(secret, overlap);
+            Ok(Response::new(RotateSecretResponse {}))
+        }
+        _ => Err(Status::permission_denied("Cannot access this method.")),
+    }
+}
+
+/// The tenant of a token minted by a caller. Only callers not scoped to a tenant can choose one, others can only mint
+/// tokens for their own tenant.
+fn token_tenant(
+    caller_tenant: Option<&Tenant>,
+    tenant: Option<String>,
+) -> Result<Option<String>, AuthError> {
+    match (caller_tenant, tenant) {
+        (None, tenant) => Ok(tenant),
+        (Some(Tenant(caller_tenant)), None) => Ok(Some(caller_tenant.clone())),
+        (Some(Tenant(caller_tenant)), Some(tenant)) if *caller_tenant == tenant => Ok(Some(tenant)),
+        (Some(_), Some(_)) => Err(AuthError::Unauthorized),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct TokenParams {
+    /// The tenant whose caches the token can query on endpoints with tenancy.
+    tenant: Option<String>,
+}
+
 pub async fn auth_route(
     access: Option<ReqData<Access>>,
+    caller_tenant: Option<ReqData<Tenant>>,
     req: HttpRequest,
     tenant_access: web::Json<Access>,
+    params: web::Query<TokenParams>,
 ) -> Result<HttpResponse, ApiError> {
     let access = match access {
         Some(access) => access.into_inner(),
@@ -54,46 +107,137 @@ pub async fn auth_route(
     match access {
         // Master Key or Uninitialized
         Access::All => {
-            let secret = get_secret(&req)?;
-            let auth = Authorizer::new(secret, None, None);
-            let token = auth.generate_token(tenant_access.0, None).unwrap();
+            let secrets = get_secrets(&req)?;
+            let tenant = token_tenant(caller_tenant.as_deref(), params.into_inner().tenant)?;
+            let token = secrets
+                .generate_tenant_token(tenant_access.0, tenant, None)
+                .unwrap();
             Ok(HttpResponse::Ok().body(json!({ "token": token }).to_string()))
         }
         Access::Custom(_) => Err(ApiError::ApiAuthError(AuthError::Unauthorized)),
     }
 }
 
-fn get_secret(req: &HttpRequest) -> Result<&str, AuthError> {
-    let api_security = req
-        .app_data::<ApiSecurity>()
-        .ok_or(AuthError::Unauthorized)?;
-
-    match api_security {
-        ApiSecurity::Jwt(secret) => Ok(secret.as_str()),
-    }
+fn get_secrets(req: &HttpRequest) -> Result<&JwtSecrets, AuthError> {
+    req.app_data::<Arc<JwtSecrets>>()
+        .map(|secrets| &**secrets)
+        .ok_or(AuthError::Unauthorized)
 }
 pub async fn validate(
     req: ServiceRequest,
     credentials: BearerAuth,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
-    let api_security = req
-        .app_data::<ApiSecurity>()
+    let secrets = req
+        .app_data::<Arc<JwtSecrets>>()
         .expect("We only validate bearer tokens if ApiSecurity is set");
-    match api_security {
-        ApiSecurity::Jwt(secret) => {
-            let api_auth = Authorizer::new(secret, None, None);
-            let res = api_auth
-                .validate_token(credentials.token())
-                .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
-
-            match res {
-                Ok(claims) => {
-                    // Provide access to all
-                    req.extensions_mut().insert(claims.access);
-                    Ok(req)
-                }
-                Err(e) => Err((e, req)),
+    let res = secrets
+        .validate_token(credentials.token())
+        .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
+
+    match res {
+        Ok(claims) => {
+            // Provide access to all
+            req.extensions_mut().insert(claims.access);
+            req.extensions_mut().insert(Subject(claims.sub));
+            if let Some(tenant) = claims.tenant {
+                req.extensions_mut().insert(Tenant(tenant));
             }
+            Ok(req)
         }
+        Err(e) => Err((e, req)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant(name: &str) -> Tenant {
+        Tenant(name.to_string())
+    }
+
+    fn token_claims(
+        secrets: &JwtSecrets,
+        response: Response<GetAuthTokenResponse>,
+    ) -> Option<String> {
+        secrets
+            .validate_token(&response.into_inner().token)
+            .unwrap()
+            .tenant
+    }
+
+    #[test]
+    fn scoped_master_token_mints_tokens_for_its_tenant_only() {
+        let secrets = JwtSecrets::new("secret".to_string());
+        let access = "\"All\"".to_string();
+
+        let response = auth_grpc(
+            Some(&Access::All),
+            Some(&tenant("a")),
+            access.clone(),
+            Some("a".to_string()),
+            Some(&secrets),
+        )
+        .unwrap();
+        assert_eq!(token_claims(&secrets, response), Some("a".to_string()));
+
+        // Without a tenant, the token is still scoped to the caller's.
+        let response = auth_grpc(
+            Some(&Access::All),
+            Some(&tenant("a")),
+            access.clone(),
+            None,
+            Some(&secrets),
+        )
+        .unwrap();
+        assert_eq!(token_claims(&secrets, response), Some("a".to_string()));
+
+        let error = auth_grpc(
+            Some(&Access::All),
+            Some(&tenant("a")),
+            access,
+            Some("b".to_string()),
+            Some(&secrets),
+        )
+        .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[test]
+    fn unscoped_master_token_chooses_tenant() {
+        let secrets = JwtSecrets::new("secret".to_string());
+        let response = auth_grpc(
+            Some(&Access::All),
+            None,
+            "\"All\"".to_string(),
+            Some("b".to_string()),
+            Some(&secrets),
+        )
+        .unwrap();
+        assert_eq!(token_claims(&secrets, response), Some("b".to_string()));
+    }
+
+    #[test]
+    fn only_unscoped_master_token_rotates_secret() {
+        let secrets = JwtSecrets::new("secret".to_string());
+
+        let error = rotate_secret_grpc(
+            Some(&Access::All),
+            Some(&tenant("a")),
+            "secret2".to_string(),
+            None,
+            Some(&secrets),
+        )
+        .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::PermissionDenied);
+
+        assert!(rotate_secret_grpc(
+            Some(&Access::All),
+            None,
+            "secret2".to_string(),
+            None,
+            Some(&secrets),
+        )
+        .is_ok());
     }
 }
@@ -41,6 +41,16 @@ impl<'a> Authorizer<'a> {
         &self,
         access: Access,
         dur: Option<Duration>,
+    ) -> Result<String, AuthError> {
+        self.generate_tenant_token(access, None, dur)
+    }
+
+    /// Generates a token which can only query the caches of `tenant` on endpoints with tenancy.
+    pub fn generate_tenant_token(
+        &self,
+        access: Access,
+        tenant: Option<String>,
+        dur: Option<Duration>,
     ) -> Result<String, AuthError> {
         let exp = Self::get_expiry(dur);
 
@@ -49,6 +59,7 @@ impl<'a> Authorizer<'a> {
             access,
             aud: self.aud.to_owned(),
             sub: self.sub.to_owned(),
+            tenant,
         };
 
         encode(
@@ -91,5 +102,18 @@ mod tests {
 
         let token_data = auth_utils.validate_token(&token).unwrap();
         assert_eq!(token_data.access, Access::All, "must be equal");
+        assert_eq!(token_data.tenant, None);
+    }
+
+    #[test]
+    fn generate_and_verify_tenant_claim() {
+        let auth_utils = Authorizer::new("secret", None, None);
+
+        let token = auth_utils
+            .generate_tenant_token(Access::All, Some("acme".to_string()), None)
+            .unwrap();
+
+        let token_data = auth_utils.validate_token(&token).unwrap();
+        assert_eq!(token_data.tenant.as_deref(), Some("acme"));
     }
 }
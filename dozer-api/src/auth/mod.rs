@@ -5,7 +5,9 @@ use dozer_types::serde;
 use serde::{Deserialize, Serialize};
 pub mod api;
 pub mod authorizer;
+mod secrets;
 pub use authorizer::Authorizer;
+pub use secrets::{JwtSecrets, DEFAULT_ROTATION_OVERLAP};
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[serde(crate = "self::serde")]
 pub struct Claims {
@@ -13,7 +15,18 @@ pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub access: Access,
+    /// The tenant whose cache is queried on endpoints with tenancy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
 }
+
+/// The `tenant` claim of a request's token.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tenant(pub String);
+
+/// The `sub` claim of a request's token.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Subject(pub String);
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[serde(crate = "self::serde")]
 // Access gets resolved in cache query, get and list functions
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use dozer_types::{models::api_security::ApiSecurity, parking_lot::RwLock};
+
+use crate::errors::AuthError;
+
+use super::{Access, Authorizer, Claims};
+
+/// Overlap window of a secret rotation if the request doesn't set one. Matches the default token expiry.
+pub const DEFAULT_ROTATION_OVERLAP: Duration = Duration::from_secs(300);
+
+/// The JWT secret of a running API server, shared by the REST and gRPC servers so it can be rotated without a restart.
+///
+/// After a rotation, tokens signed with the previous secret are still accepted until the overlap window ends.
+/// Rotations are not persisted, so the configured secret must be updated before the next restart.
+#[derive(Debug)]
+pub struct JwtSecrets {
+    inner: RwLock<JwtSecretsInner>,
+}
+
+#[derive(Debug)]
+struct JwtSecretsInner {
+    current: String,
+    /// The previous secret and the end of its overlap window.
+    previous: Option<(String, Instant)>,
+}
+
+impl JwtSecrets {
+    pub fn new(secret: String) -> Self {
+        Self {
+            inner: RwLock::new(JwtSecretsInner {
+                current: secret,
+                previous: None,
+            }),
+        }
+    }
+
+    /// Signs new tokens with `secret`, accepting tokens signed with the current secret for `overlap` longer.
+    pub fn rotate(&self, secret: String, overlap: Duration) {
+        let mut inner = self.inner.write();
+        let previous = std::mem::replace(&mut inner.current, secret);
+        inner.previous = Some((previous, Instant::now() + overlap));
+    }
+
+    /// Generates a token signed with the current secret.
+    pub fn generate_tenant_token(
+        &self,
+        access: Access,
+        tenant: Option<String>,
+        dur: Option<Duration>,
+    ) -> Result<String, AuthError> {
+        let inner = self.inner.read();
+        Authorizer::new(&inner.current, None, None).generate_tenant_token(access, tenant, dur)
+    }
+
+    /// Validates a token signed with the current secret, or with the previous one during the overlap window.
+    pub fn validate_token(&self, token: &str) -> Result<Claims, AuthError> {
+        let inner = self.inner.read();
+        let error = match Authorizer::new(&inner.current, None, None).validate_token(token) {
+            Ok(claims) => return Ok(claims),
+            Err(error) => error,
+        };
+        match &inner.previous {
+            Some((previous, until)) if Instant::now() < *until => {
+                Authorizer::new(previous, None, None)
+                    .validate_token(token)
+                    .map_err(|_| error)
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+impl From<&ApiSecurity> for JwtSecrets {
+    fn from(value: &ApiSecurity) -> Self {
+        match value {
+            ApiSecurity::Jwt(secret) => JwtSecrets::new(secret.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_secret() {
+        let secrets = JwtSecrets::new("secret1".to_string());
+        let old_token = secrets
+            .generate_tenant_token(Access::All, None, None)
+            .unwrap();
+
+        // Tokens signed with the previous secret are accepted during the overlap window.
+        secrets.rotate("secret2".to_string(), Duration::from_secs(60));
+        let new_token = secrets
+            .generate_tenant_token(Access::All, None, None)
+            .unwrap();
+        assert!(secrets.validate_token(&old_token).is_ok());
+        assert!(secrets.validate_token(&new_token).is_ok());
+        assert!(Authorizer::new("secret2", None, None)
+            .validate_token(&new_token)
+            .is_ok());
+
+        // And rejected after it.
+        secrets.rotate("secret3".to_string(), Duration::ZERO);
+        assert!(secrets.validate_token(&old_token).is_err());
+        assert!(secrets.validate_token(&new_token).is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::change_log::{Change, ChangeLog};
 use crate::grpc::types_helper;
+use crate::hot_keys::HotKeys;
+use crate::metrics_history::Lag;
 use dozer_cache::dozer_log::reader::{LogReader, LogReaderBuilder};
 use dozer_cache::dozer_log::replication::LogOperation;
 use dozer_cache::{
@@ -10,7 +14,8 @@ use dozer_cache::{
 };
 use dozer_types::indicatif::MultiProgress;
 use dozer_types::labels::Labels;
-use dozer_types::log::debug;
+use dozer_types::log::{debug, info};
+use dozer_types::node::NodeHandle;
 use dozer_types::types::SchemaWithIndex;
 use dozer_types::{
     grpc_types::types::Operation as GrpcOperation,
@@ -27,19 +32,34 @@ use tokio::sync::broadcast::Sender;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 
+mod tenant_caches;
+
+pub use tenant_caches::TenantCaches;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn build_cache(
     cache: Box<dyn RwCache>,
+    tenant_caches: Option<TenantCaches>,
+    change_log: Option<Arc<ChangeLog>>,
+    hot_keys: Option<Arc<HotKeys>>,
+    lag: Arc<Lag>,
     cancel: impl Future<Output = ()> + Unpin + Send + 'static,
     log_reader_builder: LogReaderBuilder,
     operations_sender: Option<(String, Sender<GrpcOperation>)>,
     multi_pb: Option<MultiProgress>,
+    max_commits_per_txn: u32,
 ) -> Result<(), CacheError> {
     // Create log reader.
+    // Only the operations after the last commit are replayed into an existing cache.
     let pos = cache.get_metadata()?.unwrap_or(0);
-    debug!(
-        "Starting log reader {} from position {pos}",
-        log_reader_builder.options.endpoint
-    );
+    if pos > 0 {
+        info!("Resuming cache {} from log position {pos}", cache.labels());
+    } else {
+        debug!(
+            "Starting log reader {} from position {pos}",
+            log_reader_builder.options.endpoint
+        );
+    }
     let log_reader = log_reader_builder.build(pos, multi_pb);
 
     // Spawn tasks
@@ -50,7 +70,18 @@ pub async fn build_cache(
         Ok(())
     }));
     futures.push({
-        tokio::task::spawn_blocking(|| build_cache_task(cache, receiver, operations_sender))
+        tokio::task::spawn_blocking(move || {
+            build_cache_task(
+                cache,
+                tenant_caches,
+                change_log,
+                hot_keys,
+                lag,
+                receiver,
+                operations_sender,
+                max_commits_per_txn,
+            )
+        })
     });
 
     while let Some(result) = futures.next().await {
@@ -64,28 +95,36 @@ pub async fn build_cache(
     Ok(())
 }
 
+/// Opens the cache with `labels`, or creates it with `num_partitions` partitions if it doesn't exist.
 pub fn open_or_create_cache(
     cache_manager: &dyn RwCacheManager,
     labels: Labels,
     schema: SchemaWithIndex,
     connections: &HashSet<String>,
     write_options: CacheWriteOptions,
+    num_partitions: usize,
 ) -> Result<Box<dyn RwCache>, CacheError> {
     match cache_manager.open_rw_cache(labels.clone(), write_options)? {
         Some(cache) => {
-            debug_assert!(cache.get_schema() == &schema);
-            Ok(cache)
-        }
-        None => {
-            let cache = cache_manager.create_cache(
-                labels,
-                schema.0,
-                schema.1,
-                connections,
-                write_options,
-            )?;
+            // A cache can only be resumed if it was built with the same schema.
+            if cache.get_schema() != &schema {
+                return Err(CacheError::SchemaMismatch {
+                    name: labels.to_string(),
+                    given: Box::new(schema),
+                    stored: Box::new(cache.get_schema().clone()),
+                });
+            }
             Ok(cache)
         }
+        None if num_partitions > 1 => cache_manager.create_partitioned_cache(
+            labels,
+            schema.0,
+            schema.1,
+            connections,
+            write_options,
+            num_partitions,
+        ),
+        None => cache_manager.create_cache(labels, schema.0, schema.1, connections, write_options),
     }
 }
 
@@ -123,18 +162,32 @@ async fn read_log_task(
     }
 }
 
+const CACHE_OPERATION_COUNTER_NAME: &str = "cache_operation";
+const TENANT_DROPPED_OPERATION_COUNTER_NAME: &str = "tenant_dropped_operation";
+const OPERATION_TYPE_LABEL: &str = "operation_type";
+const SNAPSHOTTING_LABEL: &str = "snapshotting";
+
+#[allow(clippy::too_many_arguments)]
 fn build_cache_task(
     mut cache: Box<dyn RwCache>,
+    mut tenant_caches: Option<TenantCaches>,
+    change_log: Option<Arc<ChangeLog>>,
+    hot_keys: Option<Arc<HotKeys>>,
+    lag: Arc<Lag>,
     mut receiver: mpsc::Receiver<(LogOperation, u64)>,
     operations_sender: Option<(String, Sender<GrpcOperation>)>,
+    max_commits_per_txn: u32,
 ) -> Result<(), CacheError> {
     let schema = cache.get_schema().0.clone();
 
-    const CACHE_OPERATION_COUNTER_NAME: &str = "cache_operation";
     describe_counter!(
         CACHE_OPERATION_COUNTER_NAME,
         "Number of message processed by cache builder"
     );
+    describe_counter!(
+        TENANT_DROPPED_OPERATION_COUNTER_NAME,
+        "Number of operations dropped because of an invalid tenant or the per tenant record limit"
+    );
 
     const DATA_LATENCY_HISTOGRAM_NAME: &str = "data_latency";
     describe_histogram!(
@@ -142,97 +195,315 @@ fn build_cache_task(
         "End-to-end data latency in seconds"
     );
 
-    const OPERATION_TYPE_LABEL: &str = "operation_type";
-    const SNAPSHOTTING_LABEL: &str = "snapshotting";
-
     let mut snapshotting = !cache.is_snapshotting_done()?;
+    // Records of these keys are dropped from the hot keys kept in memory when their changes are committed.
+    let mut uncommitted_keys = vec![];
+    // Log commits are committed to the cache together, up to `max_commits_per_txn` of them, while the next operation is already queued.
+    let mut pending_decision_instants = vec![];
+    let mut held_transactions = HeldTransactions::default();
+    let mut next = None;
 
-    while let Some((op, pos)) = receiver.blocking_recv() {
+    while let Some((op, pos)) = next.take().or_else(|| receiver.blocking_recv()) {
+        let (op, open_transactions) = match op {
+            LogOperation::CommitInTransaction {
+                decision_instant,
+                open_transactions,
+            } => (LogOperation::Commit { decision_instant }, open_transactions),
+            op => (op, vec![]),
+        };
         match op {
-            LogOperation::Op { op } => match op {
-                Operation::Delete { old } => {
-                    if let Some(meta) = cache.delete(&old)? {
-                        if let Some((endpoint_name, operations_sender)) = operations_sender.as_ref()
-                        {
-                            let operation = types_helper::map_delete_operation(
-                                endpoint_name.clone(),
-                                CacheRecord::new(meta.id, meta.version, old),
-                            );
-                            send_and_log_error(operations_sender, operation);
-                        }
-                    }
-                    let mut labels = cache.labels().clone();
-                    labels.push(OPERATION_TYPE_LABEL, "delete");
-                    labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
-                    increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
-                }
-                Operation::Insert { new } => {
-                    let result = cache.insert(&new)?;
-                    let mut labels = cache.labels().clone();
-                    labels.push(OPERATION_TYPE_LABEL, "insert");
-                    labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
-                    increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
-
-                    if let Some((endpoint_name, operations_sender)) = operations_sender.as_ref() {
-                        send_upsert_result(
-                            endpoint_name,
-                            operations_sender,
-                            result,
-                            &schema,
-                            None,
-                            new,
+            LogOperation::Op { op } => match tenant_caches.as_mut() {
+                Some(tenant_caches) => apply_tenant_operation(
+                    tenant_caches,
+                    cache.labels(),
+                    op,
+                    pos,
+                    &schema,
+                    snapshotting,
+                )?,
+                None => {
+                    // Changes are logged before they're broadcast, so a resuming subscriber that misses the broadcast finds them in the change log.
+                    if let Some(change_log) = change_log.as_ref() {
+                        change_log.append(
+                            [Change {
+                                seq: pos,
+                                op: op.clone(),
+                            }],
+                            pos,
                         );
                     }
-                }
-                Operation::Update { old, new } => {
-                    let upsert_result = cache.update(&old, &new)?;
-                    let mut labels = cache.labels().clone();
-                    labels.push(OPERATION_TYPE_LABEL, "update");
-                    labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
-                    increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
-
-                    if let Some((endpoint_name, operations_sender)) = operations_sender.as_ref() {
-                        send_upsert_result(
-                            endpoint_name,
-                            operations_sender,
-                            upsert_result,
-                            &schema,
-                            Some(old),
-                            new,
-                        );
+                    if hot_keys.is_some() {
+                        uncommitted_keys.extend(operation_keys(&op, &schema));
                     }
+                    cache.set_operation_position(pos);
+                    apply_operation(
+                        &mut *cache,
+                        op,
+                        pos,
+                        &schema,
+                        operations_sender.as_ref(),
+                        snapshotting,
+                    )?;
                 }
             },
             LogOperation::Commit { decision_instant } => {
-                cache.set_metadata(pos)?;
-                cache.commit()?;
-                if let Ok(duration) = decision_instant.elapsed() {
-                    histogram!(
-                        DATA_LATENCY_HISTOGRAM_NAME,
-                        duration,
-                        cache.labels().clone()
-                    );
+                pending_decision_instants.push(decision_instant);
+                if held_transactions.hold(&open_transactions) {
+                    continue;
+                }
+                if pending_decision_instants.len() < max_commits_per_txn as usize {
+                    next = receiver.try_recv().ok();
+                    if next.is_some() {
+                        continue;
+                    }
+                }
+
+                commit(
+                    &mut *cache,
+                    tenant_caches.as_mut(),
+                    change_log.as_deref(),
+                    hot_keys.as_deref(),
+                    &mut uncommitted_keys,
+                    pos,
+                )?;
+                for decision_instant in pending_decision_instants.drain(..) {
+                    if let Ok(duration) = decision_instant.elapsed() {
+                        lag.set(duration);
+                        histogram!(
+                            DATA_LATENCY_HISTOGRAM_NAME,
+                            duration,
+                            cache.labels().clone()
+                        );
+                    }
                 }
             }
             LogOperation::SnapshottingDone { connection_name } => {
-                cache.set_metadata(pos)?;
                 cache.set_connection_snapshotting_done(&connection_name)?;
-                cache.commit()?;
+                commit(
+                    &mut *cache,
+                    tenant_caches.as_mut(),
+                    change_log.as_deref(),
+                    hot_keys.as_deref(),
+                    &mut uncommitted_keys,
+                    pos,
+                )?;
                 snapshotting = !cache.is_snapshotting_done()?;
             }
             LogOperation::Terminate => {
                 break;
             }
+            LogOperation::CommitInTransaction { .. } => {
+                unreachable!("Commits in transactions are handled as commits")
+            }
         }
     }
 
     Ok(())
 }
 
+/// The source transactions the cache transaction stays open for, so readers never see part of them.
+///
+/// Only the transactions open when the cache starts holding its transaction are waited for. Otherwise interleaved
+/// transactions of several sources could hold it forever, at the cost of exposing a transaction that began meanwhile
+/// in parts.
+#[derive(Debug, Default)]
+struct HeldTransactions(Vec<(NodeHandle, u64)>);
+
+impl HeldTransactions {
+    /// Returns whether the commit of a log position where `open_transactions` are open must be held.
+    fn hold(&mut self, open_transactions: &[(NodeHandle, u64)]) -> bool {
+        if self.0.is_empty() {
+            self.0 = open_transactions.to_vec();
+        } else {
+            self.0
+                .retain(|transaction| open_transactions.contains(transaction));
+        }
+        !self.0.is_empty()
+    }
+}
+
+/// Commits the cache at log position `pos`, then drops the records changed since the last commit from the hot keys
+/// kept in memory.
+fn commit(
+    cache: &mut dyn RwCache,
+    tenant_caches: Option<&mut TenantCaches>,
+    change_log: Option<&ChangeLog>,
+    hot_keys: Option<&HotKeys>,
+    uncommitted_keys: &mut Vec<Field>,
+    pos: u64,
+) -> Result<(), CacheError> {
+    if let Some(tenant_caches) = tenant_caches {
+        tenant_caches.commit(pos)?;
+    }
+    cache.set_metadata(pos)?;
+    cache.commit()?;
+    if let Some(change_log) = change_log {
+        change_log.append([], pos);
+    }
+    if let Some(hot_keys) = hot_keys {
+        hot_keys.invalidate(uncommitted_keys.drain(..));
+    }
+    Ok(())
+}
+
+/// Returns the primary keys of the records touched by `op`, if the primary key has a single field like the hot keys kept in memory.
+fn operation_keys(op: &Operation, schema: &Schema) -> Vec<Field> {
+    let [index] = schema.primary_index[..] else {
+        return vec![];
+    };
+    match op {
+        Operation::Delete { old } => vec![old.values[index].clone()],
+        Operation::Insert { new } => vec![new.values[index].clone()],
+        Operation::Update { old, new } => {
+            vec![old.values[index].clone(), new.values[index].clone()]
+        }
+    }
+}
+
+fn apply_operation(
+    cache: &mut dyn RwCache,
+    op: Operation,
+    pos: u64,
+    schema: &Schema,
+    operations_sender: Option<&(String, Sender<GrpcOperation>)>,
+    snapshotting: bool,
+) -> Result<i64, CacheError> {
+    let change = match op {
+        Operation::Delete { old } => {
+            let meta = cache.delete(&old)?;
+            let change = if meta.is_some() { -1 } else { 0 };
+            if let Some(meta) = meta {
+                if let Some((endpoint_name, operations_sender)) = operations_sender {
+                    let operation = types_helper::map_delete_operation(
+                        endpoint_name.clone(),
+                        CacheRecord::new(meta.id, meta.version, old),
+                        pos,
+                    );
+                    send_and_log_error(operations_sender, operation);
+                }
+            }
+            let mut labels = cache.labels().clone();
+            labels.push(OPERATION_TYPE_LABEL, "delete");
+            labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
+            increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
+            change
+        }
+        Operation::Insert { new } => {
+            let result = cache.insert(&new)?;
+            let change = inserted_records(&result);
+            let mut labels = cache.labels().clone();
+            labels.push(OPERATION_TYPE_LABEL, "insert");
+            labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
+            increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
+
+            if let Some((endpoint_name, operations_sender)) = operations_sender {
+                send_upsert_result(
+                    endpoint_name,
+                    operations_sender,
+                    result,
+                    pos,
+                    schema,
+                    None,
+                    new,
+                );
+            }
+            change
+        }
+        Operation::Update { old, new } => {
+            let upsert_result = cache.update(&old, &new)?;
+            let change = inserted_records(&upsert_result);
+            let mut labels = cache.labels().clone();
+            labels.push(OPERATION_TYPE_LABEL, "update");
+            labels.push(SNAPSHOTTING_LABEL, snapshotting_str(snapshotting));
+            increment_counter!(CACHE_OPERATION_COUNTER_NAME, labels);
+
+            if let Some((endpoint_name, operations_sender)) = operations_sender {
+                send_upsert_result(
+                    endpoint_name,
+                    operations_sender,
+                    upsert_result,
+                    pos,
+                    schema,
+                    Some(old),
+                    new,
+                );
+            }
+            change
+        }
+    };
+    Ok(change)
+}
+
+/// 1 if an upsert inserted a record, 0 if it updated one.
+fn inserted_records(result: &UpsertResult) -> i64 {
+    match result {
+        UpsertResult::Inserted { .. } => 1,
+        _ => 0,
+    }
+}
+
+/// Applies `op` to the caches of the tenants it touches.
+///
+/// Operations on tenant caches are not broadcast, because event subscribers are not scoped to a tenant.
+fn apply_tenant_operation(
+    tenant_caches: &mut TenantCaches,
+    labels: &Labels,
+    op: Operation,
+    pos: u64,
+    schema: &Schema,
+    snapshotting: bool,
+) -> Result<(), CacheError> {
+    // An update moving a record to another tenant deletes it from the old tenant and inserts it into the new one.
+    let ops = match op {
+        Operation::Update { old, new }
+            if tenant_caches.tenant(&old) != tenant_caches.tenant(&new) =>
+        {
+            vec![Operation::Delete { old }, Operation::Insert { new }]
+        }
+        op => vec![op],
+    };
+
+    for op in ops {
+        let record = match &op {
+            Operation::Delete { old } => old,
+            Operation::Insert { new } | Operation::Update { new, .. } => new,
+        };
+        let Some(tenant) = tenant_caches.tenant(record) else {
+            debug!("Dropping operation without a valid tenant: {op:?}");
+            increment_counter!(TENANT_DROPPED_OPERATION_COUNTER_NAME, labels.clone());
+            continue;
+        };
+
+        let max_records = tenant_caches.max_records();
+        let Some(tenant_cache) = tenant_caches.cache(&tenant, pos)? else {
+            continue;
+        };
+        if matches!(op, Operation::Insert { .. }) && tenant_cache.is_full(max_records) {
+            debug!("Dropping insert into full cache of tenant {tenant}");
+            increment_counter!(
+                TENANT_DROPPED_OPERATION_COUNTER_NAME,
+                tenant_cache.cache.labels().clone()
+            );
+            continue;
+        }
+        let change = apply_operation(
+            &mut *tenant_cache.cache,
+            op,
+            pos,
+            schema,
+            None,
+            snapshotting,
+        )?;
+        tenant_cache.records_changed(change);
+    }
+    Ok(())
+}
+
 fn send_upsert_result(
     endpoint_name: &str,
     operations_sender: &Sender<GrpcOperation>,
     upsert_result: UpsertResult,
+    pos: u64,
     schema: &Schema,
     old: Option<Record>,
     new: Record,
@@ -242,6 +513,7 @@ fn send_upsert_result(
             let op = types_helper::map_insert_operation(
                 endpoint_name.to_string(),
                 CacheRecord::new(meta.id, meta.version, new),
+                pos,
             );
             send_and_log_error(operations_sender, op);
         }
@@ -260,6 +532,7 @@ fn send_upsert_result(
                 endpoint_name.to_string(),
                 CacheRecord::new(old_meta.id, old_meta.version, old),
                 CacheRecord::new(new_meta.id, new_meta.version, new),
+                pos,
             );
             send_and_log_error(operations_sender, op);
         }
@@ -280,3 +553,87 @@ fn snapshotting_str(snapshotting: bool) -> &'static str {
         "false"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::LmdbRwCacheManager;
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use crate::test_utils::get_schema;
+
+    use super::*;
+
+    #[test]
+    fn test_open_or_create_cache() {
+        let cache_manager = LmdbRwCacheManager::new(Default::default()).unwrap();
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        let schema = get_schema();
+
+        let mut cache = open_or_create_cache(
+            &cache_manager,
+            labels.clone(),
+            schema.clone(),
+            &Default::default(),
+            Default::default(),
+            1,
+        )
+        .unwrap();
+        cache.set_metadata(5).unwrap();
+        cache.commit().unwrap();
+        drop(cache);
+
+        // The existing cache resumes from its committed position.
+        let cache = open_or_create_cache(
+            &cache_manager,
+            labels.clone(),
+            schema.clone(),
+            &Default::default(),
+            Default::default(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(cache.get_metadata().unwrap(), Some(5));
+        drop(cache);
+
+        // But not with another schema.
+        let mut other_schema = schema;
+        other_schema.0.fields.push(FieldDefinition::new(
+            "rating".to_string(),
+            FieldType::String,
+            true,
+            SourceDefinition::Dynamic,
+        ));
+        assert!(matches!(
+            open_or_create_cache(
+                &cache_manager,
+                labels,
+                other_schema,
+                &Default::default(),
+                Default::default(),
+                1,
+            ),
+            Err(CacheError::SchemaMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_held_transactions_of_interleaved_sources() {
+        let a = |txid| (NodeHandle::new(None, "a".to_string()), txid);
+        let b = |txid| (NodeHandle::new(None, "b".to_string()), txid);
+        let mut held_transactions = HeldTransactions::default();
+
+        // Transaction 1 of `a` is held until it ends, even though `b` is in a transaction then.
+        assert!(held_transactions.hold(&[a(1)]));
+        assert!(held_transactions.hold(&[a(1), b(1)]));
+        assert!(!held_transactions.hold(&[b(1), a(2)]));
+
+        // Then the transactions open at that commit are held.
+        assert!(held_transactions.hold(&[b(1), a(2)]));
+        assert!(held_transactions.hold(&[a(2)]));
+        assert!(!held_transactions.hold(&[b(2)]));
+        assert!(held_transactions.hold(&[b(2)]));
+        assert!(!held_transactions.hold(&[]));
+        assert!(!held_transactions.hold(&[]));
+    }
+}
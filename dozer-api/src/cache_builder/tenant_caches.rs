@@ -0,0 +1,212 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use dozer_cache::{
+    cache::{expression::QueryExpression, CacheWriteOptions, RwCache, RwCacheManager},
+    errors::CacheError,
+};
+use dozer_types::{
+    labels::Labels,
+    types::{Record, SchemaWithIndex},
+};
+
+use crate::tenancy::{tenant_cache_labels, tenant_of};
+
+use super::open_or_create_cache;
+
+/// Routes the records of an endpoint with tenancy to the caches of their tenants.
+///
+/// The endpoint cache keeps the log position and snapshotting state, while tenant caches only hold records.
+/// Every tenant cache also records the log position it last committed at, so operations replayed after a restart are applied to it at most once.
+#[derive(Debug)]
+pub struct TenantCaches {
+    cache_manager: Arc<dyn RwCacheManager>,
+    labels: Labels,
+    schema: SchemaWithIndex,
+    write_options: CacheWriteOptions,
+    column_index: usize,
+    max_records: Option<u64>,
+    caches: HashMap<String, TenantCache>,
+}
+
+#[derive(Debug)]
+pub struct TenantCache {
+    pub cache: Box<dyn RwCache>,
+    /// Number of records in `cache`, so inserts are checked against the limit without counting them.
+    num_records: u64,
+    committed_pos: Option<u64>,
+    dirty: bool,
+}
+
+impl TenantCache {
+    /// Returns if the cache already holds `max_records` records.
+    pub fn is_full(&self, max_records: Option<u64>) -> bool {
+        max_records.map_or(false, |max_records| self.num_records >= max_records)
+    }
+
+    /// Records that applying an operation changed the number of records by `change`.
+    pub fn records_changed(&mut self, change: i64) {
+        self.num_records = self.num_records.saturating_add_signed(change);
+    }
+}
+
+impl TenantCaches {
+    pub fn new(
+        cache_manager: Arc<dyn RwCacheManager>,
+        labels: Labels,
+        schema: SchemaWithIndex,
+        write_options: CacheWriteOptions,
+        column_index: usize,
+        max_records: Option<u64>,
+    ) -> Self {
+        Self {
+            cache_manager,
+            labels,
+            schema,
+            write_options,
+            column_index,
+            max_records,
+            caches: HashMap::new(),
+        }
+    }
+
+    /// Returns the tenant of `record`, or `None` if its tenant column doesn't name a valid tenant.
+    pub fn tenant(&self, record: &Record) -> Option<String> {
+        tenant_of(&record.values[self.column_index])
+    }
+
+    /// Returns the cache of `tenant`, or `None` if the operation at log position `pos` was already committed to it.
+    pub fn cache(
+        &mut self,
+        tenant: &str,
+        pos: u64,
+    ) -> Result<Option<&mut TenantCache>, CacheError> {
+        if !self.caches.contains_key(tenant) {
+            // Snapshotting state lives in the endpoint cache, so tenant caches don't track any connection.
+            let cache = open_or_create_cache(
+                &*self.cache_manager,
+                tenant_cache_labels(&self.labels, tenant),
+                self.schema.clone(),
+                &HashSet::new(),
+                self.write_options,
+                1,
+            )?;
+            let committed_pos = cache.get_metadata()?;
+            // Counted once when the cache is opened, and kept up to date by `records_changed`.
+            let num_records = cache.count(&QueryExpression::with_no_limit())? as u64;
+            self.caches.insert(
+                tenant.to_string(),
+                TenantCache {
+                    cache,
+                    num_records,
+                    committed_pos,
+                    dirty: false,
+                },
+            );
+        }
+
+        let tenant_cache = self
+            .caches
+            .get_mut(tenant)
+            .expect("We just inserted the cache");
+        if tenant_cache
+            .committed_pos
+            .map_or(false, |committed_pos| pos <= committed_pos)
+        {
+            return Ok(None);
+        }
+        tenant_cache.dirty = true;
+        Ok(Some(tenant_cache))
+    }
+
+    pub fn max_records(&self) -> Option<u64> {
+        self.max_records
+    }
+
+    /// Commits all tenant caches written since the last commit at log position `pos`.
+    pub fn commit(&mut self, pos: u64) -> Result<(), CacheError> {
+        for tenant_cache in self.caches.values_mut() {
+            if tenant_cache.dirty {
+                tenant_cache.cache.set_metadata(pos)?;
+                tenant_cache.cache.commit()?;
+                tenant_cache.committed_pos = Some(pos);
+                tenant_cache.dirty = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::{cache::LmdbRwCacheManager, AccessFilter};
+
+    use crate::tenancy::TenantCacheReaders;
+    use crate::test_utils::{get_sample_records, get_schema};
+
+    use super::*;
+
+    #[test]
+    fn test_tenant_caches() {
+        let cache_manager: Arc<dyn RwCacheManager> =
+            Arc::new(LmdbRwCacheManager::new(Default::default()).unwrap());
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        // Partition films by `release_year`.
+        let mut tenant_caches = TenantCaches::new(
+            cache_manager.clone(),
+            labels.clone(),
+            get_schema(),
+            Default::default(),
+            3,
+            None,
+        );
+
+        let record = get_sample_records().remove(0).record;
+        let tenant = tenant_caches.tenant(&record).unwrap();
+        tenant_caches
+            .cache(&tenant, 1)
+            .unwrap()
+            .unwrap()
+            .cache
+            .insert(&record)
+            .unwrap();
+        tenant_caches.commit(1).unwrap();
+
+        // Operations at or before the committed log position are not applied again.
+        assert!(tenant_caches.cache(&tenant, 1).unwrap().is_none());
+        assert!(tenant_caches.cache(&tenant, 2).unwrap().is_some());
+
+        // Records already in a tenant cache count towards its limit when it's opened again.
+        drop(tenant_caches);
+        let mut tenant_caches = TenantCaches::new(
+            cache_manager.clone(),
+            labels.clone(),
+            get_schema(),
+            Default::default(),
+            3,
+            Some(2),
+        );
+        let tenant_cache = tenant_caches.cache(&tenant, 2).unwrap().unwrap();
+        assert!(!tenant_cache.is_full(Some(2)));
+        tenant_cache.records_changed(1);
+        assert!(tenant_cache.is_full(Some(2)));
+        tenant_cache.records_changed(-1);
+        assert!(!tenant_cache.is_full(Some(2)));
+
+        let tenant_cache_readers = TenantCacheReaders::new(cache_manager, labels);
+        let count = tenant_cache_readers
+            .get(&tenant)
+            .unwrap()
+            .count(
+                &mut QueryExpression::with_no_limit(),
+                AccessFilter {
+                    filter: None,
+                    fields: vec![],
+                },
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(tenant_cache_readers.get("unknown").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use dozer_types::{
+    models::api_endpoint::{
+        default_change_retention_max_age_in_secs, default_change_retention_max_changes,
+        ChangeRetentionOptions,
+    },
+    parking_lot::Mutex,
+    types::Operation,
+};
+
+/// A change applied to an endpoint cache. `seq` is the change's position in the log, so it survives restarts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub seq: u64,
+    pub op: Operation,
+}
+
+/// Recent changes of an endpoint, bounded by count and age.
+#[derive(Debug)]
+pub struct ChangeLog {
+    max_changes: usize,
+    max_age: Duration,
+    inner: Mutex<ChangeLogInner>,
+}
+
+#[derive(Debug)]
+struct ChangeLogInner {
+    changes: VecDeque<(Instant, Change)>,
+    /// All changes after this position are retained.
+    retained_since: u64,
+    /// Position of the last appended change or commit.
+    last_seq: u64,
+}
+
+impl ChangeLog {
+    /// Creates an empty change log, whose history starts at log position `seq`.
+    pub fn new(options: &ChangeRetentionOptions, seq: u64) -> Self {
+        Self {
+            max_changes: options
+                .max_changes
+                .unwrap_or_else(default_change_retention_max_changes)
+                as usize,
+            max_age: Duration::from_secs(
+                options
+                    .max_age_in_secs
+                    .unwrap_or_else(default_change_retention_max_age_in_secs),
+            ),
+            inner: Mutex::new(ChangeLogInner {
+                changes: VecDeque::new(),
+                retained_since: seq,
+                last_seq: seq,
+            }),
+        }
+    }
+
+    /// Appends changes up to log position `seq`.
+    pub fn append(&self, changes: impl IntoIterator<Item = Change>, seq: u64) {
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        inner
+            .changes
+            .extend(changes.into_iter().map(|change| (now, change)));
+        inner.last_seq = seq;
+        self.trim(&mut inner, now);
+    }
+
+    /// Returns at most `limit` changes after log position `since`, and the position to continue from.
+    ///
+    /// If `since` is `None`, returns no change and the current position.
+    /// Returns `None` if some changes after `since` are no longer retained.
+    pub fn since(&self, since: Option<u64>, limit: usize) -> Option<(Vec<Change>, u64)> {
+        let mut inner = self.inner.lock();
+        self.trim(&mut inner, Instant::now());
+
+        let Some(since) = since else {
+            return Some((vec![], inner.last_seq));
+        };
+        if since < inner.retained_since {
+            return None;
+        }
+
+        let start = inner
+            .changes
+            .partition_point(|(_, change)| change.seq <= since);
+        let changes: Vec<Change> = inner
+            .changes
+            .range(start..)
+            .take(limit)
+            .map(|(_, change)| change.clone())
+            .collect();
+        let next = if changes.len() == limit {
+            changes.last().map_or(since, |change| change.seq)
+        } else {
+            inner.last_seq.max(since)
+        };
+        Some((changes, next))
+    }
+
+    fn trim(&self, inner: &mut ChangeLogInner, now: Instant) {
+        while let Some(&(time, Change { seq, .. })) = inner.changes.front() {
+            if inner.changes.len() <= self.max_changes && now.duration_since(time) <= self.max_age {
+                break;
+            }
+            inner.retained_since = seq;
+            inner.changes.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Record;
+
+    use super::*;
+
+    fn change(seq: u64) -> Change {
+        Change {
+            seq,
+            op: Operation::Insert {
+                new: Record::new(vec![]),
+            },
+        }
+    }
+
+    #[test]
+    fn test_change_log() {
+        let change_log = ChangeLog::new(
+            &ChangeRetentionOptions {
+                max_changes: Some(3),
+                max_age_in_secs: None,
+            },
+            10,
+        );
+        assert_eq!(change_log.since(None, 10), Some((vec![], 10)));
+        assert_eq!(change_log.since(Some(9), 10), None);
+
+        change_log.append([change(11), change(12)], 13);
+        assert_eq!(
+            change_log.since(Some(10), 10),
+            Some((vec![change(11), change(12)], 13))
+        );
+        assert_eq!(change_log.since(Some(10), 1), Some((vec![change(11)], 11)));
+        assert_eq!(change_log.since(Some(13), 10), Some((vec![], 13)));
+
+        // `change(11)` is dropped because only 3 changes are retained.
+        change_log.append([change(14), change(15)], 16);
+        assert_eq!(change_log.since(Some(10), 10), None);
+        assert_eq!(
+            change_log.since(Some(11), 10),
+            Some((vec![change(12), change(14), change(15)], 16))
+        );
+    }
+}
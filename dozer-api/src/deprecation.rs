@@ -0,0 +1,99 @@
+use dozer_cache::cache::expression::{FilterExpression, QueryExpression};
+use dozer_types::models::api_endpoint::{ApiEndpoint, DeprecatedField};
+use dozer_types::types::IndexFunction;
+
+use crate::naming::{api_field_name, camel_case_fields};
+
+/// The header counting the references of a query to deprecated fields.
+pub const DEPRECATED_FIELDS_HEADER: &str = "x-dozer-deprecated-fields";
+
+/// Whether `name` refers to the deprecated field, by its name in the schema or the name it's served with.
+fn refers_to(name: &str, deprecated: &DeprecatedField, camel_case: bool) -> bool {
+    let name = IndexFunction::parse_call(name)
+        .map(|(_, field)| field)
+        .unwrap_or(name);
+    name == deprecated.field || name == api_field_name(&deprecated.field, camel_case)
+}
+
+/// Counts the references of the query's filter and sort options to deprecated fields of the endpoint.
+pub fn deprecated_field_usage(query: &QueryExpression, endpoint: &ApiEndpoint) -> usize {
+    if endpoint.deprecated_fields.is_empty() {
+        return 0;
+    }
+    let camel_case = camel_case_fields(endpoint);
+    let is_deprecated = |name: &str| {
+        endpoint
+            .deprecated_fields
+            .iter()
+            .any(|deprecated| refers_to(name, deprecated, camel_case))
+    };
+
+    let mut names = vec![];
+    if let Some(filter) = &query.filter {
+        filter_field_names(filter, &mut names);
+    }
+    names.extend(
+        query
+            .order_by
+            .0
+            .iter()
+            .map(|option| option.field_name.as_str()),
+    );
+    names.into_iter().filter(|name| is_deprecated(name)).count()
+}
+
+fn filter_field_names<'a>(filter: &'a FilterExpression, names: &mut Vec<&'a str>) {
+    match filter {
+        FilterExpression::Simple(field_name, _, _) => names.push(field_name),
+        FilterExpression::And(filters) | FilterExpression::Or(filters) => {
+            for filter in filters {
+                filter_field_names(filter, names);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::expression::{Operator, SortDirection, SortOption, SortOptions};
+    use dozer_types::models::api_endpoint::EndpointNaming;
+    use dozer_types::serde_json::Value;
+
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn test_deprecated_field_usage() {
+        let mut endpoint = ApiEndpoint {
+            deprecated_fields: vec![DeprecatedField {
+                field: "release_year".to_string(),
+                message: Some("Use updated_at".to_string()),
+            }],
+            ..test_utils::get_endpoint()
+        };
+        let query = |field_name: &str| QueryExpression {
+            filter: Some(FilterExpression::Or(vec![
+                FilterExpression::Simple(field_name.to_string(), Operator::EQ, Value::from(2006)),
+                FilterExpression::Simple("film_id".to_string(), Operator::EQ, Value::from(1)),
+            ])),
+            order_by: SortOptions(vec![SortOption::new(
+                format!("lower({field_name})"),
+                SortDirection::Ascending,
+            )]),
+            ..QueryExpression::with_no_limit()
+        };
+
+        assert_eq!(deprecated_field_usage(&query("release_year"), &endpoint), 2);
+        assert_eq!(deprecated_field_usage(&query("releaseYear"), &endpoint), 0);
+        assert_eq!(
+            deprecated_field_usage(&QueryExpression::with_no_limit(), &endpoint),
+            0
+        );
+
+        endpoint.naming = Some(EndpointNaming {
+            camel_case_fields: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(deprecated_field_usage(&query("releaseYear"), &endpoint), 2);
+    }
+}
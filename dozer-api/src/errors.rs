@@ -27,8 +27,26 @@ pub enum ApiInitError {
     OpenOrCreateCache(#[source] CacheError),
     #[error("Failed to find cache: {0}")]
     CacheNotFound(Labels),
+    #[error("Cache {labels} is at log position {position}, but the log only has {log_end} operations. Remove the cache to rebuild it")]
+    CacheAheadOfLog {
+        labels: Labels,
+        position: u64,
+        log_end: u64,
+    },
     #[error("Failed to bind to address {0}: {1}")]
     FailedToBindToAddress(String, #[source] std::io::Error),
+    #[error("Tenant column {0} not found in endpoint schema")]
+    TenantColumnNotFound(String),
+    #[error("Tenant column {0} must be a string, text, int or uint column")]
+    InvalidTenantColumnType(String),
+    #[error("Invalid gateway app url {0}")]
+    InvalidGatewayUrl(String),
+    #[error("Failed to serve gateway: {0}")]
+    GatewayServe(#[source] hyper::Error),
+    #[error("Invalid alert silence timestamp {0}: {1}")]
+    InvalidAlertSilence(String, #[source] dozer_types::chrono::ParseError),
+    #[error("Invalid alert email address {0}: {1}")]
+    InvalidAlertEmail(String, #[source] lettre::address::AddressError),
 }
 
 #[derive(Error, Debug)]
@@ -47,12 +65,44 @@ pub enum ApiError {
     QueryFailed(#[source] CacheError),
     #[error("Failed to get cache phase: {0}")]
     GetPhaseFailed(#[source] CacheError),
+    #[error("Failed to get cache stats: {0}")]
+    GetStatsFailed(#[source] CacheError),
     #[error("Invalid primary key: {0}")]
     InvalidPrimaryKey(#[source] TypeError),
     #[error("Invalid access filter: {0}")]
     InvalidAccessFilter(#[source] serde_json::Error),
     #[error(transparent)]
     CannotConvertF64ToJson(#[from] CannotConvertF64ToJson),
+    #[error("Endpoint is partitioned by tenant, but the token has no tenant claim")]
+    TenantRequired,
+    #[error("No records found for tenant {0}")]
+    TenantNotFound(String),
+    #[error("Failed to open tenant cache: {0}")]
+    OpenTenantCacheFailed(#[source] CacheError),
+    #[error("Change retention is not enabled for this endpoint")]
+    ChangesNotRetained,
+    #[error("Changes since {0} are no longer retained. Read the endpoint again and continue from the current position")]
+    ChangesExpired(u64),
+    #[error("Invalid query template: {0}")]
+    InvalidQueryTemplate(String),
+    #[error("Invalid query parameters: {0}")]
+    InvalidQueryParams(String),
+    #[error("Query template {0} is not registered")]
+    PreparedQueryNotFound(String),
+    #[error("At most {0} query templates can be registered per endpoint")]
+    TooManyPreparedQueries(usize),
+    #[error("Invalid query timeout {0:?}, expected milliseconds")]
+    InvalidQueryTimeout(String),
+    #[error("Query timed out")]
+    QueryTimeout,
+    #[error("Provenance is not recorded for this endpoint")]
+    ProvenanceNotRecorded,
+    #[error("No provenance is recorded for this record")]
+    ProvenanceNotFound,
+    #[error("Failed to get provenance from the app: {0}")]
+    GetProvenanceFailed(#[source] tonic::Status),
+    #[error("Invalid provenance: {0}")]
+    InvalidProvenance(#[source] serde_json::Error),
 }
 
 #[derive(Error, Debug)]
@@ -67,7 +117,56 @@ pub enum GrpcError {
 
 impl From<ApiError> for tonic::Status {
     fn from(input: ApiError) -> Self {
-        tonic::Status::new(tonic::Code::Unknown, input.to_string())
+        let code = match input {
+            ApiError::QueryTimeout => tonic::Code::DeadlineExceeded,
+            ApiError::InvalidQueryTimeout(_) => tonic::Code::InvalidArgument,
+            _ => tonic::Code::Unknown,
+        };
+        tonic::Status::new(code, input.to_string())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GatewayError {
+    #[error("No app serves {0}")]
+    RouteNotFound(String),
+    #[error("No healthy app serves {0}")]
+    NoHealthyApp(String),
+    #[error("Failed to forward request to {0}: {1}")]
+    Forward(String, #[source] hyper::Error),
+    #[error("Invalid gRPC request: {0}")]
+    InvalidGrpcRequest(String),
+    #[error("{0} is not supported through the gateway")]
+    Unsupported(String),
+}
+
+impl actix_web::error::ResponseError for GatewayError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::json())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match *self {
+            GatewayError::RouteNotFound(_) => StatusCode::NOT_FOUND,
+            GatewayError::NoHealthyApp(_) => StatusCode::SERVICE_UNAVAILABLE,
+            GatewayError::Forward(_, _) => StatusCode::BAD_GATEWAY,
+            GatewayError::InvalidGrpcRequest(_) => StatusCode::BAD_REQUEST,
+            GatewayError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+        }
+    }
+}
+
+impl From<GatewayError> for tonic::Status {
+    fn from(input: GatewayError) -> Self {
+        let code = match input {
+            GatewayError::RouteNotFound(_) => tonic::Code::NotFound,
+            GatewayError::NoHealthyApp(_) | GatewayError::Forward(_, _) => tonic::Code::Unavailable,
+            GatewayError::InvalidGrpcRequest(_) => tonic::Code::InvalidArgument,
+            GatewayError::Unsupported(_) => tonic::Code::Unimplemented,
+        };
+        tonic::Status::new(code, input.to_string())
     }
 }
 
@@ -122,18 +221,32 @@ impl actix_web::error::ResponseError for ApiError {
 
     fn status_code(&self) -> StatusCode {
         match *self {
-            ApiError::InvalidPrimaryKey(_) | ApiError::InvalidAccessFilter(_) => {
-                StatusCode::BAD_REQUEST
-            }
+            ApiError::InvalidPrimaryKey(_)
+            | ApiError::InvalidAccessFilter(_)
+            | ApiError::InvalidQueryTemplate(_)
+            | ApiError::InvalidQueryParams(_)
+            | ApiError::InvalidQueryTimeout(_) => StatusCode::BAD_REQUEST,
             ApiError::ApiAuthError(_) => StatusCode::UNAUTHORIZED,
-            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
-            ApiError::NoPrimaryKey | ApiError::MultiIndexFetch(_) => {
-                StatusCode::UNPROCESSABLE_ENTITY
-            }
+            ApiError::TenantRequired => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_)
+            | ApiError::TenantNotFound(_)
+            | ApiError::ChangesNotRetained
+            | ApiError::PreparedQueryNotFound(_)
+            | ApiError::ProvenanceNotRecorded
+            | ApiError::ProvenanceNotFound => StatusCode::NOT_FOUND,
+            ApiError::ChangesExpired(_) => StatusCode::GONE,
+            ApiError::QueryTimeout => StatusCode::GATEWAY_TIMEOUT,
+            ApiError::NoPrimaryKey
+            | ApiError::MultiIndexFetch(_)
+            | ApiError::TooManyPreparedQueries(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ApiError::QueryFailed(_)
             | ApiError::CountFailed(_)
             | ApiError::GetPhaseFailed(_)
-            | ApiError::CannotConvertF64ToJson(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            | ApiError::GetStatsFailed(_)
+            | ApiError::CannotConvertF64ToJson(_)
+            | ApiError::OpenTenantCacheFailed(_)
+            | ApiError::InvalidProvenance(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::GetProvenanceFailed(_) => StatusCode::BAD_GATEWAY,
         }
     }
 }
@@ -0,0 +1,217 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use dozer_types::grpc_types::common::{
+    GetDescriptorRequest, GetEndpointsResponse, GetFieldsRequest, GetMetricsHistoryRequest,
+    GetStatsRequest, OnEventRequest, QueryRequest,
+};
+use dozer_types::log::info;
+use dozer_types::models::api_config::GrpcApiOptions;
+use futures_util::Future;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, HeaderMap, Request, Response, Server};
+use prost::Message;
+
+use super::Gateway;
+use crate::errors::{ApiInitError, GatewayError, GrpcError};
+
+const COMMON_SERVICE_NAME: &str = "dozer.common.CommonGrpcService";
+/// Typed services are named `dozer.generated.<endpoint>.<Service>`.
+const TYPED_PACKAGE_PREFIX: &str = "dozer.generated.";
+
+/// Serves the gRPC services of the apps behind `gateway`, forwarding each call to an app serving its endpoint.
+///
+/// Calls of the common service are routed by the endpoint in their request, and `getEndpoints` lists the endpoints
+/// of all apps. Client streaming `queryStream` calls are not supported.
+pub async fn run(
+    gateway: Arc<Gateway>,
+    grpc_config: GrpcApiOptions,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), ApiInitError> {
+    let address = format!("{}:{}", grpc_config.host, grpc_config.port);
+    info!("Starting gRPC Gateway on {address}");
+    let addr: SocketAddr = address
+        .parse()
+        .map_err(|e| GrpcError::AddrParse(address.clone(), e))?;
+
+    let make_service = make_service_fn(move |_| {
+        let gateway = gateway.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let gateway = gateway.clone();
+                async move {
+                    Ok::<_, Infallible>(
+                        route(&gateway, request)
+                            .await
+                            .unwrap_or_else(|e| error_response(e.into())),
+                    )
+                }
+            }))
+        }
+    });
+    Server::try_bind(&addr)
+        .map_err(ApiInitError::GatewayServe)?
+        .http2_only(true)
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(ApiInitError::GatewayServe)
+}
+
+async fn route(gateway: &Gateway, request: Request<Body>) -> Result<Response<Body>, GatewayError> {
+    let path = request.uri().path().to_string();
+    let Some((service, method)) = path.trim_start_matches('/').split_once('/') else {
+        return Err(GatewayError::InvalidGrpcRequest(format!(
+            "Invalid path {path}"
+        )));
+    };
+
+    if service == COMMON_SERVICE_NAME {
+        return match method {
+            "getEndpoints" => Ok(unary_response(&GetEndpointsResponse {
+                endpoints: gateway.grpc.routes(),
+            })),
+            "queryStream" => Err(GatewayError::Unsupported(path.clone())),
+            _ => {
+                let (parts, body) = request.into_parts();
+                let body = hyper::body::to_bytes(body)
+                    .await
+                    .map_err(|e| GatewayError::InvalidGrpcRequest(e.to_string()))?;
+                let endpoint = request_endpoint(method, &body)?;
+                let upstream = gateway
+                    .grpc
+                    .pick(&endpoint, |name| (name == endpoint).then_some(0))?;
+                gateway
+                    .forward_grpc(upstream, Request::from_parts(parts, Body::from(body)))
+                    .await
+            }
+        };
+    }
+
+    let upstream = match service
+        .strip_prefix(TYPED_PACKAGE_PREFIX)
+        .and_then(|service| service.rsplit_once('.'))
+    {
+        Some((package, _)) => gateway
+            .grpc
+            .pick(service, |name| (package_name(name) == package).then_some(0))?,
+        // Other services, like health and reflection, are answered by any healthy app.
+        None => gateway.grpc.pick(service, |_| Some(0))?,
+    };
+    gateway.forward_grpc(upstream, request).await
+}
+
+/// The package name of the typed service of `endpoint`, as the proto generator names it.
+fn package_name(endpoint: &str) -> String {
+    endpoint.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+}
+
+/// The endpoint a unary call of the common service is for.
+fn request_endpoint(method: &str, body: &[u8]) -> Result<String, GatewayError> {
+    let message = grpc_message(body)?;
+    let endpoint = match method {
+        "count" | "query" => QueryRequest::decode(message).map(|request| request.endpoint),
+        "OnEvent" => OnEventRequest::decode(message).map(|request| request.endpoint),
+        "getFields" => GetFieldsRequest::decode(message).map(|request| request.endpoint),
+        "getStats" => GetStatsRequest::decode(message).map(|request| request.endpoint),
+        "getDescriptor" => GetDescriptorRequest::decode(message).map(|request| request.endpoint),
+        "getMetricsHistory" => {
+            GetMetricsHistoryRequest::decode(message).map(|request| request.endpoint)
+        }
+        _ => {
+            return Err(GatewayError::Unsupported(format!(
+                "{COMMON_SERVICE_NAME}/{method}"
+            )))
+        }
+    };
+    endpoint.map_err(|e| GatewayError::InvalidGrpcRequest(e.to_string()))
+}
+
+/// The message of a unary gRPC request body, which frames it with a compression flag and its length.
+fn grpc_message(body: &[u8]) -> Result<&[u8], GatewayError> {
+    let [compressed, a, b, c, d, message @ ..] = body else {
+        return Err(GatewayError::InvalidGrpcRequest(
+            "Missing message".to_string(),
+        ));
+    };
+    if *compressed != 0 {
+        return Err(GatewayError::Unsupported(
+            "Compressed gRPC requests".to_string(),
+        ));
+    }
+    let len = u32::from_be_bytes([*a, *b, *c, *d]) as usize;
+    message
+        .get(..len)
+        .ok_or_else(|| GatewayError::InvalidGrpcRequest("Truncated message".to_string()))
+}
+
+/// A successful response of a unary call, answered by the gateway itself.
+fn unary_response(message: &impl Message) -> Response<Body> {
+    let mut frame = vec![0];
+    frame.extend_from_slice(&(message.encoded_len() as u32).to_be_bytes());
+    message
+        .encode(&mut frame)
+        .expect("Vec grows to fit the message");
+
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        if sender.send_data(frame.into()).await.is_ok() {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from_static("0"));
+            let _ = sender.send_trailers(trailers).await;
+        }
+    });
+    Response::builder()
+        .header(CONTENT_TYPE, "application/grpc")
+        .body(body)
+        .expect("Static headers are valid")
+}
+
+fn error_response(status: tonic::Status) -> Response<Body> {
+    status.to_http().map(|_| Body::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(message: &impl Message) -> Vec<u8> {
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(message.encoded_len() as u32).to_be_bytes());
+        message.encode(&mut frame).unwrap();
+        frame
+    }
+
+    #[test]
+    fn test_request_endpoint() {
+        let query = frame(&QueryRequest {
+            endpoint: "films".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(request_endpoint("query", &query).unwrap(), "films");
+        assert_eq!(request_endpoint("count", &query).unwrap(), "films");
+
+        let on_event = frame(&OnEventRequest {
+            endpoint: "users".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(request_endpoint("OnEvent", &on_event).unwrap(), "users");
+
+        assert!(matches!(
+            request_endpoint("query", &query[..query.len() - 1]),
+            Err(GatewayError::InvalidGrpcRequest(_))
+        ));
+        assert!(matches!(
+            request_endpoint("queryStream", &query),
+            Err(GatewayError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("film_events"), "film_events");
+        assert_eq!(package_name("film-events"), "film_events");
+    }
+}
@@ -0,0 +1,367 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dozer_types::grpc_types::common::{
+    common_grpc_service_client::CommonGrpcServiceClient, GetEndpointsRequest,
+};
+use dozer_types::log::{info, warn};
+use dozer_types::models::api_config::{default_gateway_refresh_interval_in_millis, GatewayOptions};
+use dozer_types::parking_lot::RwLock;
+use dozer_types::serde_json;
+use futures_util::future::{join, join_all};
+use hyper::client::HttpConnector;
+use hyper::header::AUTHORIZATION;
+use hyper::http::uri::PathAndQuery;
+use hyper::{Body, Client, Request, Response, Uri};
+
+use crate::errors::{ApiInitError, GatewayError};
+
+pub mod grpc;
+pub mod rest;
+
+/// A dozer app the gateway forwards requests to, with the routes it serves as last seen by the gateway.
+#[derive(Debug)]
+pub struct Upstream {
+    url: String,
+    token: Option<String>,
+    state: RwLock<UpstreamState>,
+}
+
+#[derive(Debug, Default)]
+struct UpstreamState {
+    healthy: bool,
+    /// REST endpoint paths or gRPC endpoint names. Kept while the app is unhealthy, so its routes don't go to other
+    /// apps.
+    routes: Vec<String>,
+}
+
+impl Upstream {
+    fn new(url: &str, token: Option<String>) -> Result<Self, ApiInitError> {
+        let url = url.trim_end_matches('/').to_string();
+        url.parse::<Uri>()
+            .map_err(|_| ApiInitError::InvalidGatewayUrl(url.clone()))?;
+        Ok(Self {
+            url,
+            token,
+            state: Default::default(),
+        })
+    }
+
+    /// Records the routes of a successful refresh, or marks the app unhealthy if the refresh failed.
+    fn update(&self, routes: Option<Vec<String>>) {
+        let mut state = self.state.write();
+        let healthy = routes.is_some();
+        if healthy != state.healthy {
+            if healthy {
+                info!("Gateway app {} is healthy", self.url);
+            } else {
+                warn!("Gateway app {} is unhealthy", self.url);
+            }
+        }
+        state.healthy = healthy;
+        if let Some(routes) = routes {
+            state.routes = routes;
+        }
+    }
+
+    /// The uri of `uri` on this app.
+    fn uri(&self, uri: &Uri) -> Uri {
+        let path_and_query = uri.path_and_query().map_or("/", PathAndQuery::as_str);
+        format!("{}{path_and_query}", self.url)
+            .parse()
+            .expect("Upstream urls are validated")
+    }
+}
+
+/// The routes of the apps behind the gateway, for one protocol.
+#[derive(Debug, Default)]
+pub struct RouteTable {
+    upstreams: Vec<Upstream>,
+    /// Round robin counter for picking between apps serving the same route.
+    next: AtomicUsize,
+}
+
+impl RouteTable {
+    pub fn new<'a>(
+        upstreams: impl IntoIterator<Item = (&'a str, Option<String>)>,
+    ) -> Result<Self, ApiInitError> {
+        Ok(Self {
+            upstreams: upstreams
+                .into_iter()
+                .map(|(url, token)| Upstream::new(url, token))
+                .collect::<Result<_, _>>()?,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// All routes served by the apps, sorted and deduplicated.
+    pub fn routes(&self) -> Vec<String> {
+        let mut routes = self
+            .upstreams
+            .iter()
+            .flat_map(|upstream| upstream.state.read().routes.clone())
+            .collect::<Vec<_>>();
+        routes.sort();
+        routes.dedup();
+        routes
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.upstreams
+            .iter()
+            .any(|upstream| upstream.state.read().healthy)
+    }
+
+    /// Picks the app to forward a request for `target` to.
+    ///
+    /// `score` scores how well a route matches the request, `None` if it doesn't. Of the apps serving the best
+    /// matching route, healthy ones take turns.
+    pub fn pick(
+        &self,
+        target: &str,
+        score: impl Fn(&str) -> Option<usize>,
+    ) -> Result<&Upstream, GatewayError> {
+        let scores = self
+            .upstreams
+            .iter()
+            .map(|upstream| {
+                let state = upstream.state.read();
+                let score = state.routes.iter().filter_map(|route| score(route)).max();
+                (upstream, state.healthy, score)
+            })
+            .collect::<Vec<_>>();
+        let Some(best) = scores.iter().filter_map(|(_, _, score)| *score).max() else {
+            return Err(GatewayError::RouteNotFound(target.to_string()));
+        };
+        let candidates = scores
+            .into_iter()
+            .filter(|(_, healthy, score)| *healthy && *score == Some(best))
+            .map(|(upstream, _, _)| upstream)
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Err(GatewayError::NoHealthyApp(target.to_string()));
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        Ok(candidates[next % candidates.len()])
+    }
+}
+
+/// Scores REST endpoint path `route` by its length if it's a prefix of request path `path`, so the most specific
+/// endpoint wins.
+pub fn rest_route_score(route: &str, path: &str) -> Option<usize> {
+    let rest = path.strip_prefix(route)?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(route.len())
+}
+
+/// Serves the endpoints of several dozer apps as if they were one.
+#[derive(Debug)]
+pub struct Gateway {
+    pub rest: RouteTable,
+    pub grpc: RouteTable,
+    refresh_interval: Duration,
+    rest_client: Client<HttpConnector>,
+    grpc_client: Client<HttpConnector>,
+}
+
+impl Gateway {
+    pub fn new(options: &GatewayOptions) -> Result<Self, ApiInitError> {
+        let rest = RouteTable::new(
+            options
+                .apps
+                .iter()
+                .filter_map(|app| app.rest_url.as_deref().map(|url| (url, app.token.clone()))),
+        )?;
+        let grpc = RouteTable::new(
+            options
+                .apps
+                .iter()
+                .filter_map(|app| app.grpc_url.as_deref().map(|url| (url, app.token.clone()))),
+        )?;
+        Ok(Self {
+            rest,
+            grpc,
+            refresh_interval: Duration::from_millis(
+                options
+                    .refresh_interval_in_millis
+                    .unwrap_or_else(default_gateway_refresh_interval_in_millis),
+            ),
+            rest_client: Client::new(),
+            grpc_client: Client::builder().http2_only(true).build_http(),
+        })
+    }
+
+    /// Refreshes the routes and health of the apps every refresh interval, until `shutdown` resolves.
+    pub async fn refresh_periodically(self: Arc<Self>, shutdown: impl Future<Output = ()>) {
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            self.refresh().await;
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(self.refresh_interval) => (),
+            }
+        }
+    }
+
+    pub async fn refresh(&self) {
+        let rest = join_all(self.rest.upstreams.iter().map(|upstream| async move {
+            upstream.update(self.with_timeout(self.rest_routes(upstream)).await);
+        }));
+        let grpc = join_all(self.grpc.upstreams.iter().map(|upstream| async move {
+            upstream.update(self.with_timeout(grpc_routes(upstream)).await);
+        }));
+        join(rest, grpc).await;
+    }
+
+    async fn with_timeout<T>(&self, routes: impl Future<Output = Option<T>>) -> Option<T> {
+        tokio::time::timeout(self.refresh_interval, routes)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// The endpoint paths of a REST app, if it's healthy.
+    async fn rest_routes(&self, upstream: &Upstream) -> Option<Vec<String>> {
+        self.rest_get(upstream, "/health").await?;
+        let body = self.rest_get(upstream, "/").await?;
+        serde_json::from_slice(&body).ok()
+    }
+
+    async fn rest_get(&self, upstream: &Upstream, path: &str) -> Option<hyper::body::Bytes> {
+        let mut request = Request::get(format!("{}{path}", upstream.url));
+        if let Some(token) = &upstream.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = self
+            .rest_client
+            .request(request.body(Body::empty()).ok()?)
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        hyper::body::to_bytes(response.into_body()).await.ok()
+    }
+
+    pub async fn forward_rest(
+        &self,
+        upstream: &Upstream,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, GatewayError> {
+        forward(&self.rest_client, upstream, request).await
+    }
+
+    pub async fn forward_grpc(
+        &self,
+        upstream: &Upstream,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, GatewayError> {
+        forward(&self.grpc_client, upstream, request).await
+    }
+}
+
+/// The endpoint names of a gRPC app, if it's healthy.
+async fn grpc_routes(upstream: &Upstream) -> Option<Vec<String>> {
+    let mut client = CommonGrpcServiceClient::connect(upstream.url.clone())
+        .await
+        .ok()?;
+    let mut request = tonic::Request::new(GetEndpointsRequest {});
+    if let Some(token) = &upstream.token {
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {token}").parse().ok()?);
+    }
+    let response = client.get_endpoints(request).await.ok()?;
+    Some(response.into_inner().endpoints)
+}
+
+/// Forwards `request` to `upstream`, marking it unhealthy if it can't be reached.
+async fn forward(
+    client: &Client<HttpConnector>,
+    upstream: &Upstream,
+    mut request: Request<Body>,
+) -> Result<Response<Body>, GatewayError> {
+    *request.uri_mut() = upstream.uri(request.uri());
+    client.request(request).await.map_err(|e| {
+        if e.is_connect() {
+            upstream.update(None);
+        }
+        GatewayError::Forward(upstream.url.clone(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route_table(routes: &[(&str, bool, &[&str])]) -> RouteTable {
+        let table = RouteTable::new(routes.iter().map(|(url, _, _)| (*url, None))).unwrap();
+        for (upstream, (_, healthy, routes)) in table.upstreams.iter().zip(routes) {
+            upstream.update(Some(routes.iter().map(|route| route.to_string()).collect()));
+            if !healthy {
+                upstream.update(None);
+            }
+        }
+        table
+    }
+
+    fn pick<'a>(table: &'a RouteTable, path: &str) -> Result<&'a str, GatewayError> {
+        table
+            .pick(path, |route| rest_route_score(route, path))
+            .map(|upstream| upstream.url.as_str())
+    }
+
+    #[test]
+    fn test_pick_most_specific_route() {
+        let table = route_table(&[
+            ("http://films:8080", true, &["/films"]),
+            ("http://events:8080", true, &["/films/events", "/users"]),
+        ]);
+        assert_eq!(pick(&table, "/films/query").unwrap(), "http://films:8080");
+        assert_eq!(
+            pick(&table, "/films/events/count").unwrap(),
+            "http://events:8080"
+        );
+        assert_eq!(pick(&table, "/users").unwrap(), "http://events:8080");
+        assert!(matches!(
+            pick(&table, "/filmsx"),
+            Err(GatewayError::RouteNotFound(_))
+        ));
+        assert_eq!(table.routes(), vec!["/films", "/films/events", "/users"]);
+    }
+
+    #[test]
+    fn test_pick_healthy_app() {
+        let table = route_table(&[
+            ("http://a:8080", true, &["/films"]),
+            ("http://b:8080", false, &["/films"]),
+            ("http://c:8080", true, &["/films"]),
+            ("http://d:8080", false, &["/users"]),
+        ]);
+        let picked = (0..4)
+            .map(|_| pick(&table, "/films").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            picked,
+            vec![
+                "http://a:8080",
+                "http://c:8080",
+                "http://a:8080",
+                "http://c:8080"
+            ]
+        );
+        assert!(matches!(
+            pick(&table, "/users/1"),
+            Err(GatewayError::NoHealthyApp(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(matches!(
+            RouteTable::new([("not a url", None)]),
+            Err(ApiInitError::InvalidGatewayUrl(_))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use actix_web::dev::Server;
+use actix_web::http::header::HeaderName;
+use actix_web::middleware::Logger;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use dozer_types::log::info;
+use dozer_types::models::api_config::RestApiOptions;
+use futures_util::Future;
+use hyper::header::{CONNECTION, CONTENT_LENGTH, HOST, TE, TRAILER, TRANSFER_ENCODING, UPGRADE};
+use hyper::Body;
+
+use super::{rest_route_score, Gateway};
+use crate::errors::{ApiInitError, GatewayError};
+
+/// Headers describing a single connection, which are not forwarded.
+fn is_hop_by_hop(name: &HeaderName) -> bool {
+    [
+        CONNECTION,
+        CONTENT_LENGTH,
+        HOST,
+        TE,
+        TRAILER,
+        TRANSFER_ENCODING,
+        UPGRADE,
+    ]
+    .contains(name)
+}
+
+/// Serves the REST endpoints of the apps behind `gateway`, forwarding each request to an app serving its endpoint.
+pub fn run(
+    gateway: Arc<Gateway>,
+    rest_config: RestApiOptions,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<Server, ApiInitError> {
+    info!(
+        "Starting Rest Gateway on http://{}:{}",
+        rest_config.host, rest_config.port
+    );
+    let address = format!("{}:{}", rest_config.host, rest_config.port);
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(gateway.clone()))
+            .wrap(Logger::default())
+            .route("/health", web::get().to(health))
+            .route("/", web::get().to(list_endpoint_paths))
+            .route("", web::get().to(list_endpoint_paths))
+            .default_service(web::to(proxy))
+    })
+    .bind(&address)
+    .map_err(|e| ApiInitError::FailedToBindToAddress(address, e))?
+    .disable_signals()
+    .shutdown_timeout(0)
+    .run();
+
+    let server_handle = server.handle();
+    tokio::spawn(async move {
+        shutdown.await;
+        server_handle.stop(true).await;
+    });
+
+    Ok(server)
+}
+
+async fn list_endpoint_paths(gateway: web::Data<Arc<Gateway>>) -> web::Json<Vec<String>> {
+    web::Json(gateway.rest.routes())
+}
+
+async fn health(gateway: web::Data<Arc<Gateway>>) -> HttpResponse {
+    if gateway.rest.is_healthy() {
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::ServiceUnavailable().finish()
+    }
+}
+
+async fn proxy(
+    request: HttpRequest,
+    body: web::Bytes,
+    gateway: web::Data<Arc<Gateway>>,
+) -> Result<HttpResponse, GatewayError> {
+    let path = request.uri().path();
+    let upstream = gateway
+        .rest
+        .pick(path, |route| rest_route_score(route, path))?;
+
+    let mut forwarded = hyper::Request::builder()
+        .method(request.method().clone())
+        .uri(request.uri().clone());
+    for (name, value) in request.headers() {
+        if !is_hop_by_hop(name) {
+            forwarded = forwarded.header(name, value);
+        }
+    }
+    let forwarded = forwarded
+        .body(Body::from(body))
+        .expect("Method, uri and headers are from a valid request");
+    let response = gateway.forward_rest(upstream, forwarded).await?;
+
+    let mut builder = HttpResponse::build(response.status());
+    for (name, value) in response.headers() {
+        if !is_hop_by_hop(name) {
+            builder.append_header((name.clone(), value.clone()));
+        }
+    }
+    Ok(builder.streaming(response.into_body()))
+}
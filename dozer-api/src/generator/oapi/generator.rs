@@ -65,6 +65,16 @@ impl<'a> OpenApiGenerator<'a> {
                         );
                         Value::Object(m)
                     }
+                    FieldType::Money => {
+                        let mut m = Map::new();
+                        m.insert("amount".to_string(), Value::from("12.34"));
+                        m.insert("currency".to_string(), Value::from("USD"));
+                        Value::Object(m)
+                    }
+                    FieldType::Enum => field_def
+                        .enum_values
+                        .first()
+                        .map_or(Value::Null, |value| Value::from(value.as_str())),
                 };
                 json!({ name: val })
             } else {
@@ -233,8 +243,7 @@ impl<'a> OpenApiGenerator<'a> {
     }
 
     fn generate_component_schema(&self) -> Components {
-        let generated_schema =
-            convert_cache_to_oapi_schema(self.schema.to_owned(), &self.endpoint.name);
+        let generated_schema = convert_cache_to_oapi_schema(self.schema.to_owned(), &self.endpoint);
 
         let schemas = indexmap::indexmap! {
             self.get_singular_name() => ReferenceOr::Item(generated_schema),
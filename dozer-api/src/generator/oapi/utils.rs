@@ -1,6 +1,7 @@
 use dozer_types::{
     indexmap::{self, IndexMap},
-    types::{FieldType, DATE_FORMAT},
+    models::api_endpoint::ApiEndpoint,
+    types::{FieldDefinition, FieldType, DATE_FORMAT},
 };
 use openapiv3::{
     AnySchema, ArrayType, Contact, IntegerFormat, IntegerType, MediaType, NumberFormat, NumberType,
@@ -9,6 +10,8 @@ use openapiv3::{
     VariantOrUnknownOrEmpty,
 };
 
+use crate::naming::{api_field_name, camel_case_fields};
+
 const CONTACT_NAME: &str = "Dozer Team";
 const CONTACT_WEB_URL: &str = "https://getdozer.io";
 const CONTACT_EMAIL: &str = "api@getdozer.io";
@@ -68,28 +71,39 @@ pub fn create_reference_response(description: String, schema_reference_path: Str
     }
 }
 
+/// `cache_schema` must have the field names the endpoint serves.
 pub fn convert_cache_to_oapi_schema(
     cache_schema: dozer_types::types::Schema,
-    name: &str,
+    endpoint: &ApiEndpoint,
 ) -> Schema {
+    let camel_case = camel_case_fields(endpoint);
     let mut properties: IndexMap<String, ReferenceOr<Box<Schema>>> = IndexMap::new();
     let mut required_properties: Vec<String> = Vec::new();
     for field in cache_schema.fields {
         if !field.nullable {
             required_properties.push(field.name.to_owned());
         }
+        let deprecated = endpoint
+            .deprecated_fields
+            .iter()
+            .find(|deprecated| api_field_name(&deprecated.field, camel_case) == field.name);
+        let schema_kind = convert_field_to_schema_type(&field);
         properties.insert(
             field.name,
             ReferenceOr::boxed_item(Schema {
-                schema_data: Default::default(),
-                schema_kind: convert_cache_type_to_schema_type(field.typ),
+                schema_data: SchemaData {
+                    deprecated: deprecated.is_some(),
+                    description: deprecated.and_then(|deprecated| deprecated.message.clone()),
+                    ..Default::default()
+                },
+                schema_kind,
             }),
         );
     }
 
     Schema {
         schema_data: SchemaData {
-            description: Some(format!("A representation of {name}")),
+            description: Some(format!("A representation of {}", endpoint.name)),
             ..Default::default()
         },
         schema_kind: SchemaKind::Type(Type::Object(ObjectType {
@@ -100,6 +114,17 @@ pub fn convert_cache_to_oapi_schema(
     }
 }
 
+/// Should be consistent with `defined_field_to_json_value`.
+fn convert_field_to_schema_type(field: &FieldDefinition) -> SchemaKind {
+    match field.typ {
+        FieldType::Enum => SchemaKind::Type(Type::String(StringType {
+            enumeration: field.enum_values.iter().cloned().map(Some).collect(),
+            ..Default::default()
+        })),
+        typ => convert_cache_type_to_schema_type(typ),
+    }
+}
+
 /// Should be consistent with `field_to_json_value`.
 fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType) -> SchemaKind {
     match field_type {
@@ -115,6 +140,10 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
             format: VariantOrUnknownOrEmpty::Item(NumberFormat::Double),
             ..Default::default()
         })),
+        FieldType::Enum => SchemaKind::Type(Type::Integer(IntegerType {
+            format: VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32),
+            ..Default::default()
+        })),
         FieldType::Boolean => SchemaKind::Type(Type::Boolean {}),
         FieldType::String
         | FieldType::Text
@@ -167,9 +196,13 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
                 max_properties: None,
             }))
         }
-        FieldType::Duration => {
+        FieldType::Duration | FieldType::Money => {
             let mut properties: IndexMap<String, ReferenceOr<Box<Schema>>> = IndexMap::new();
-            let required: Vec<String> = vec!["value".to_string(), "time_unit".to_string()];
+            let required: Vec<String> = if field_type == FieldType::Duration {
+                vec!["value".to_string(), "time_unit".to_string()]
+            } else {
+                vec!["amount".to_string(), "currency".to_string()]
+            };
             for key in &required {
                 properties.insert(
                     key.clone(),
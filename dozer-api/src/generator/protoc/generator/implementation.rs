@@ -1,13 +1,14 @@
 use crate::errors::GenerationError;
 use crate::errors::GenerationError::ServiceNotFound;
 use crate::generator::protoc::generator::{
-    CountMethodDesc, DecimalDesc, DurationDesc, EventDesc, OnEventMethodDesc, PointDesc,
+    CountMethodDesc, DecimalDesc, DurationDesc, EventDesc, MoneyDesc, OnEventMethodDesc, PointDesc,
     QueryMethodDesc, RecordWithIdDesc, TokenMethodDesc, TokenResponseDesc,
 };
+use crate::naming::api_field_name;
 use dozer_cache::dozer_log::schemas::BuildSchema;
 use dozer_types::log::error;
 use dozer_types::serde::{self, Deserialize, Serialize};
-use dozer_types::types::{FieldType, Schema};
+use dozer_types::types::FieldType;
 use handlebars::Handlebars;
 use inflector::Inflector;
 use prost_reflect::{DescriptorPool, FieldDescriptor, Kind, MessageDescriptor};
@@ -17,6 +18,7 @@ use super::{CountResponseDesc, QueryResponseDesc, RecordDesc, ServiceDesc};
 
 const POINT_TYPE_CLASS: &str = "dozer.types.PointType";
 const DURATION_TYPE_CLASS: &str = "dozer.types.DurationType";
+const MONEY_TYPE_CLASS: &str = "dozer.types.MoneyType";
 const DECIMAL_TYPE_CLASS: &str = "dozer.types.RustDecimal";
 const TIMESTAMP_TYPE_CLASS: &str = "google.protobuf.Timestamp";
 const JSON_TYPE_CLASS: &str = "google.protobuf.Value";
@@ -48,7 +50,7 @@ impl<'a> ProtoGeneratorImpl<'a> {
         schema: &'a BuildSchema,
         folder_path: &'a Path,
     ) -> Result<Self, GenerationError> {
-        let names = Names::new(schema_name, &schema.schema);
+        let names = Names::new(schema_name, schema);
         let mut generator = Self {
             handlebars: Handlebars::new(),
             schema,
@@ -76,8 +78,48 @@ impl<'a> ProtoGeneratorImpl<'a> {
             .zip(&self.names.record_field_names)
             .map(|((idx, field), field_name)| -> String {
                 let optional = if field.nullable { "optional " } else { "" };
-                let proto_type = convert_dozer_type_to_proto_type(field.typ.to_owned()).unwrap();
-                format!("{optional}{proto_type} {field_name} = {};", idx + 1)
+                // An enum field has a nested enum of its values, whose numbers are their ordinals.
+                let (enum_definition, proto_type) = if field.typ == FieldType::Enum {
+                    let enum_name = field_name.to_pascal_case();
+                    let prefix = field_name.to_screaming_snake_case();
+                    let values = field
+                        .enum_values
+                        .iter()
+                        .enumerate()
+                        .map(|(ordinal, value)| {
+                            format!(
+                                "    {prefix}_{} = {ordinal};\n",
+                                sanitize(value).to_screaming_snake_case()
+                            )
+                        })
+                        .collect::<String>();
+                    (format!("enum {enum_name} {{\n{values}  }}\n  "), enum_name)
+                } else {
+                    (
+                        String::new(),
+                        convert_dozer_type_to_proto_type(field.typ.to_owned()).unwrap(),
+                    )
+                };
+                let deprecated = self
+                    .schema
+                    .deprecated_fields
+                    .iter()
+                    .find(|deprecated| deprecated.field == field.name);
+                match deprecated {
+                    Some(deprecated) => format!(
+                        "{enum_definition}// Deprecated{}\n  {optional}{proto_type} {field_name} = {} [deprecated = true];",
+                        deprecated
+                            .message
+                            .as_ref()
+                            .map(|message| format!(": {}", message.replace('\n', " ")))
+                            .unwrap_or_default(),
+                        idx + 1
+                    ),
+                    None => format!(
+                        "{enum_definition}{optional}{proto_type} {field_name} = {};",
+                        idx + 1
+                    ),
+                }
             })
             .collect()
     }
@@ -165,6 +207,9 @@ impl<'a> ProtoGeneratorImpl<'a> {
         let record_desc_from_message =
             |message: MessageDescriptor| -> Result<RecordDesc, GenerationError> {
                 let version_field = get_field(&message, "__dozer_record_version")?;
+                let money_values = descriptor
+                    .get_message_by_name(MONEY_TYPE_CLASS)
+                    .ok_or_else(|| ServiceNotFound(MONEY_TYPE_CLASS.to_string()))?;
 
                 if let Some(point_values) = descriptor.get_message_by_name(POINT_TYPE_CLASS) {
                     let pv = point_values;
@@ -193,6 +238,11 @@ impl<'a> ProtoGeneratorImpl<'a> {
                                     value: get_field(&durv, "value")?,
                                     time_unit: get_field(&durv, "time_unit")?,
                                 },
+                                money_field: MoneyDesc {
+                                    amount: get_field(&money_values, "amount")?,
+                                    currency: get_field(&money_values, "currency")?,
+                                    message: money_values,
+                                },
                             })
                         } else {
                             Err(ServiceNotFound(DURATION_TYPE_CLASS.to_string()))
@@ -205,11 +255,12 @@ impl<'a> ProtoGeneratorImpl<'a> {
                 }
             };
 
-        let names = Names::new(schema_name, &Schema::default());
-        let service_name = format!("{}.{}", &names.package_name, &names.plural_pascal_name);
+        // The service may be renamed, but it's the only one in the package of the endpoint.
+        let package_name = Names::package_name(schema_name);
         let service = descriptor
-            .get_service_by_name(&service_name)
-            .ok_or(GenerationError::ServiceNotFound(service_name))?;
+            .services()
+            .find(|service| service.package_name() == package_name)
+            .ok_or(GenerationError::ServiceNotFound(package_name))?;
 
         let mut count = None;
         let mut query = None;
@@ -267,6 +318,8 @@ impl<'a> ProtoGeneratorImpl<'a> {
                     let old_field = get_field(&message, "old")?;
                     let new_field = get_field(&message, "new")?;
                     let new_id_field = get_field(&message, "new_id")?;
+                    let seq_field = message.get_field_by_name("seq");
+                    let changed_fields_field = message.get_field_by_name("changed_fields");
                     let old_field_kind = old_field.kind();
                     let Kind::Message(record_message) = old_field_kind else {
                         return Err(GenerationError::ExpectedMessageField {
@@ -282,6 +335,8 @@ impl<'a> ProtoGeneratorImpl<'a> {
                             old_field,
                             new_field,
                             new_id_field,
+                            seq_field,
+                            changed_fields_field,
                             record_desc: record_desc_from_message(record_message)?,
                         },
                     });
@@ -336,26 +391,33 @@ struct Names {
 }
 
 impl Names {
-    fn new(schema_name: &str, schema: &Schema) -> Self {
+    fn new(schema_name: &str, schema: &BuildSchema) -> Self {
         if schema_name.contains('-') {
             error!("Name of the endpoint should not contain `-`.");
         }
-        let schema_name = schema_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        let package_name = Self::package_name(schema_name);
+        let schema_name = sanitize(schema_name);
 
-        let package_name = format!("dozer.generated.{schema_name}");
         let lower_name = schema_name.to_lowercase();
-        let plural_pascal_name = schema_name.to_pascal_case().to_plural();
-        let pascal_name = schema_name.to_pascal_case().to_singular();
+        let plural_pascal_name = schema
+            .grpc_service_name
+            .as_deref()
+            .map(sanitize)
+            .unwrap_or_else(|| schema_name.to_pascal_case().to_plural());
+        let pascal_name = schema
+            .grpc_message_name
+            .as_deref()
+            .map(sanitize)
+            .unwrap_or_else(|| schema_name.to_pascal_case().to_singular());
         let record_field_names = schema
+            .schema
             .fields
             .iter()
             .map(|field| {
                 if field.name.contains('-') {
                     error!("Name of the field should not contain `-`.");
                 }
-                field
-                    .name
-                    .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+                sanitize(&api_field_name(&field.name, schema.camel_case_fields))
             })
             .collect::<Vec<_>>();
         Self {
@@ -367,6 +429,14 @@ impl Names {
             record_field_names,
         }
     }
+
+    fn package_name(schema_name: &str) -> String {
+        format!("dozer.generated.{}", sanitize(schema_name))
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
 }
 
 fn convert_dozer_type_to_proto_type(field_type: FieldType) -> Result<String, GenerationError> {
@@ -386,5 +456,8 @@ fn convert_dozer_type_to_proto_type(field_type: FieldType) -> Result<String, Gen
         FieldType::Json => Ok(JSON_TYPE_CLASS.to_owned()),
         FieldType::Point => Ok(POINT_TYPE_CLASS.to_owned()),
         FieldType::Duration => Ok(DURATION_TYPE_CLASS.to_owned()),
+        FieldType::Money => Ok(MONEY_TYPE_CLASS.to_owned()),
+        // The ordinal, when the field's nested enum isn't available.
+        FieldType::Enum => Ok("uint32".to_owned()),
     }
 }
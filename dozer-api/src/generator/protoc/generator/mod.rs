@@ -51,6 +51,7 @@ pub struct RecordDesc {
     pub point_field: PointDesc,
     pub decimal_field: DecimalDesc,
     pub duration_field: DurationDesc,
+    pub money_field: MoneyDesc,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +68,13 @@ pub struct DurationDesc {
     pub time_unit: FieldDescriptor,
 }
 
+#[derive(Debug, Clone)]
+pub struct MoneyDesc {
+    pub message: MessageDescriptor,
+    pub amount: FieldDescriptor,
+    pub currency: FieldDescriptor,
+}
+
 #[derive(Debug, Clone)]
 pub struct DecimalDesc {
     pub message: MessageDescriptor,
@@ -99,6 +107,10 @@ pub struct EventDesc {
     pub old_field: FieldDescriptor,
     pub new_field: FieldDescriptor,
     pub new_id_field: FieldDescriptor,
+    /// `None` for descriptors generated before events carried their `seq`.
+    pub seq_field: Option<FieldDescriptor>,
+    /// `None` for descriptors generated before events carried their changed fields.
+    pub changed_fields_field: Option<FieldDescriptor>,
     pub record_desc: RecordDesc,
 }
 
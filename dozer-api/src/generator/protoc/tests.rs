@@ -3,6 +3,9 @@ use std::path::Path;
 use super::generator::{ProtoGenerator, ServiceDesc};
 use crate::test_utils;
 use dozer_cache::dozer_log::schemas::BuildSchema;
+use dozer_types::models::api_endpoint::DeprecatedField;
+use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+use prost_reflect::Kind;
 use tempdir::TempDir;
 
 fn read_service_desc(proto_folder_path: &Path, endpoint_name: &str) -> ServiceDesc {
@@ -23,6 +26,10 @@ fn test_generate_proto_and_descriptor() {
         enable_token: false,
         enable_on_event: false,
         connections: Default::default(),
+        grpc_service_name: None,
+        grpc_message_name: None,
+        camel_case_fields: false,
+        deprecated_fields: vec![],
     };
 
     let endpoint = test_utils::get_endpoint();
@@ -57,6 +64,10 @@ fn test_generate_proto_and_descriptor_with_security() {
         enable_token: true,
         enable_on_event: true,
         connections: Default::default(),
+        grpc_service_name: None,
+        grpc_message_name: None,
+        camel_case_fields: false,
+        deprecated_fields: vec![],
     };
 
     let endpoint = test_utils::get_endpoint();
@@ -99,6 +110,10 @@ fn test_generate_proto_and_descriptor_with_push_event_off() {
         enable_token: true,
         enable_on_event: false,
         connections: Default::default(),
+        grpc_service_name: None,
+        grpc_message_name: None,
+        camel_case_fields: false,
+        deprecated_fields: vec![],
     };
 
     let endpoint = test_utils::get_endpoint();
@@ -130,3 +145,134 @@ fn test_generate_proto_and_descriptor_with_push_event_off() {
     );
     assert!(service_desc.on_event.is_none());
 }
+
+#[test]
+fn test_generate_proto_and_descriptor_with_naming() {
+    let schema_name = "films";
+    let (schema, secondary_indexes) = test_utils::get_schema();
+    let schema = BuildSchema {
+        schema,
+        secondary_indexes,
+        enable_token: false,
+        enable_on_event: false,
+        connections: Default::default(),
+        grpc_service_name: Some("FilmCatalog".to_string()),
+        grpc_message_name: Some("Movie".to_string()),
+        camel_case_fields: true,
+        deprecated_fields: vec![],
+    };
+
+    let tmp_dir = TempDir::new("proto_generated").unwrap();
+    let tmp_dir_path = tmp_dir.path();
+    ProtoGenerator::generate(tmp_dir_path, schema_name, &schema).unwrap();
+
+    let service_desc = read_service_desc(tmp_dir_path, schema_name);
+
+    assert_eq!(
+        service_desc.service.full_name(),
+        "dozer.generated.films.FilmCatalog"
+    );
+    let record_message = service_desc
+        .query
+        .response_desc
+        .record_with_id_desc
+        .record_desc
+        .message;
+    assert_eq!(record_message.full_name(), "dozer.generated.films.Movie");
+    assert!(record_message.get_field_by_name("releaseYear").is_some());
+    assert!(record_message.get_field_by_name("release_year").is_none());
+}
+
+#[test]
+fn test_generate_proto_and_descriptor_with_deprecated_fields() {
+    let schema_name = "films";
+    let (schema, secondary_indexes) = test_utils::get_schema();
+    let schema = BuildSchema {
+        schema,
+        secondary_indexes,
+        enable_token: false,
+        enable_on_event: false,
+        connections: Default::default(),
+        grpc_service_name: None,
+        grpc_message_name: None,
+        camel_case_fields: false,
+        deprecated_fields: vec![DeprecatedField {
+            field: "release_year".to_string(),
+            message: Some("Use updated_at".to_string()),
+        }],
+    };
+
+    let tmp_dir = TempDir::new("proto_generated").unwrap();
+    let tmp_dir_path = tmp_dir.path();
+    ProtoGenerator::generate(tmp_dir_path, schema_name, &schema).unwrap();
+
+    let service_desc = read_service_desc(tmp_dir_path, schema_name);
+    let record_message = service_desc
+        .query
+        .response_desc
+        .record_with_id_desc
+        .record_desc
+        .message;
+    let is_deprecated = |name: &str| {
+        record_message
+            .get_field_by_name(name)
+            .unwrap()
+            .field_descriptor_proto()
+            .options
+            .as_ref()
+            .and_then(|options| options.deprecated)
+            .unwrap_or(false)
+    };
+    assert!(is_deprecated("release_year"));
+    assert!(!is_deprecated("film_id"));
+}
+
+#[test]
+fn test_generate_proto_and_descriptor_with_enum_field() {
+    let schema_name = "films";
+    let (mut schema, secondary_indexes) = test_utils::get_schema();
+    schema.field(
+        FieldDefinition::new(
+            "rating".to_string(),
+            FieldType::Enum,
+            true,
+            SourceDefinition::Dynamic,
+        )
+        .with_enum_values(vec!["G".to_string(), "PG-13".to_string()]),
+        false,
+    );
+    let schema = BuildSchema {
+        schema,
+        secondary_indexes,
+        enable_token: false,
+        enable_on_event: false,
+        connections: Default::default(),
+        grpc_service_name: None,
+        grpc_message_name: None,
+        camel_case_fields: false,
+        deprecated_fields: vec![],
+    };
+
+    let tmp_dir = TempDir::new("proto_generated").unwrap();
+    let tmp_dir_path = tmp_dir.path();
+    ProtoGenerator::generate(tmp_dir_path, schema_name, &schema).unwrap();
+
+    let service_desc = read_service_desc(tmp_dir_path, schema_name);
+    let record_message = service_desc
+        .query
+        .response_desc
+        .record_with_id_desc
+        .record_desc
+        .message;
+    let Kind::Enum(rating) = record_message.get_field_by_name("rating").unwrap().kind() else {
+        panic!("rating should be an enum");
+    };
+    let values = rating
+        .values()
+        .map(|value| (value.name().to_string(), value.number()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![("RATING_G".to_string(), 0), ("RATING_PG_13".to_string(), 1)]
+    );
+}
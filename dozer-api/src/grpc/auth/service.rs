@@ -1,21 +1,22 @@
-use crate::auth::Access;
+use std::sync::Arc;
+
+use crate::auth::{Access, JwtSecrets, Tenant};
 
 use tonic::{Request, Response, Status};
 
-use crate::auth::api::auth_grpc;
+use crate::auth::api::{auth_grpc, rotate_secret_grpc};
 use dozer_types::grpc_types::auth::auth_grpc_service_server::AuthGrpcService;
-use dozer_types::grpc_types::auth::GetAuthTokenRequest;
-use dozer_types::grpc_types::auth::GetAuthTokenResponse;
-use dozer_types::models::api_security::ApiSecurity;
+use dozer_types::grpc_types::auth::{
+    GetAuthTokenRequest, GetAuthTokenResponse, RotateSecretRequest, RotateSecretResponse,
+};
 
 // #[derive(Clone)]
 pub struct AuthService {
-    /// For look up endpoint from its name. `key == value.endpoint.name`.
-    pub security: Option<ApiSecurity>,
+    pub security: Option<Arc<JwtSecrets>>,
 }
 
 impl AuthService {
-    pub fn new(security: Option<ApiSecurity>) -> Self {
+    pub fn new(security: Option<Arc<JwtSecrets>>) -> Self {
         Self { security }
     }
 }
@@ -29,6 +30,28 @@ impl AuthGrpcService for AuthService {
         let (_, extensions, request) = request.into_parts();
         let access = extensions.get::<Access>();
 
-        auth_grpc(access, request.access_filter, self.security.clone())
+        auth_grpc(
+            access,
+            extensions.get::<Tenant>(),
+            request.access_filter,
+            request.tenant,
+            self.security.as_deref(),
+        )
+    }
+
+    async fn rotate_secret(
+        &self,
+        request: Request<RotateSecretRequest>,
+    ) -> Result<Response<RotateSecretResponse>, Status> {
+        let (_, extensions, request) = request.into_parts();
+        let access = extensions.get::<Access>();
+
+        rotate_secret_grpc(
+            access,
+            extensions.get::<Tenant>(),
+            request.secret,
+            request.overlap_in_secs,
+            self.security.as_deref(),
+        )
     }
 }
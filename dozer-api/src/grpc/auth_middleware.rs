@@ -1,6 +1,6 @@
-use dozer_types::models::api_security::ApiSecurity;
 use futures_util::future::BoxFuture;
 use hyper::{Body, Method};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tonic::{
     body::{empty_body, BoxBody},
@@ -9,14 +9,14 @@ use tonic::{
 };
 use tower::{Layer, Service};
 
-use crate::auth::Authorizer;
+use crate::auth::{JwtSecrets, Subject, Tenant};
 
 #[derive(Debug, Clone, Default)]
 pub struct AuthMiddlewareLayer {
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
 }
 impl AuthMiddlewareLayer {
-    pub fn new(security: Option<ApiSecurity>) -> Self {
+    pub fn new(security: Option<Arc<JwtSecrets>>) -> Self {
         Self { security }
     }
 }
@@ -35,7 +35,7 @@ impl<S> Layer<S> for AuthMiddlewareLayer {
 #[derive(Debug, Clone)]
 pub struct AuthMiddleware<S> {
     inner: S,
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
 }
 
 impl<S> Service<hyper::Request<Body>> for AuthMiddleware<S>
@@ -67,14 +67,19 @@ where
                     let auth_header = req.headers().get("authorization");
                     if let Some(auth_header) = auth_header {
                         let auth_header_str = auth_header.to_str().unwrap();
-                        let authorizer = Authorizer::from(&security);
                         if auth_header_str.starts_with("Bearer ") {
                             let token_array: Vec<&str> = auth_header_str.split(' ').collect();
-                            let token_data = authorizer.validate_token(token_array[1]);
+                            let token_data = security.validate_token(token_array[1]);
                             return match token_data {
                                 Ok(claims) => {
                                     let mut modified_request = req;
                                     modified_request.extensions_mut().insert(claims.access);
+                                    modified_request
+                                        .extensions_mut()
+                                        .insert(Subject(claims.sub));
+                                    if let Some(tenant) = claims.tenant {
+                                        modified_request.extensions_mut().insert(Tenant(tenant));
+                                    }
                                     let response = inner.call(modified_request).await?;
                                     Ok(response)
                                 }
@@ -1,9 +1,11 @@
 use super::metric_middleware::MetricMiddlewareLayer;
 use super::{auth_middleware::AuthMiddlewareLayer, common::CommonService, typed::TypedService};
+use crate::auth::JwtSecrets;
 use crate::errors::ApiInitError;
 use crate::grpc::auth::AuthService;
 use crate::grpc::health::HealthService;
 use crate::grpc::{common, typed};
+use crate::metrics_history::MetricsHistory;
 use crate::{errors::GrpcError, CacheEndpoint};
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
 use dozer_types::grpc_types::types::Operation;
@@ -15,7 +17,7 @@ use dozer_types::grpc_types::{
 use dozer_types::tracing::Level;
 use dozer_types::{
     log::info,
-    models::{api_config::GrpcApiOptions, api_security::ApiSecurity, flags::Flags},
+    models::{api_config::GrpcApiOptions, flags::Flags},
 };
 use futures_util::stream::{AbortHandle, Abortable, Aborted};
 use futures_util::Future;
@@ -29,8 +31,9 @@ use tower_http::trace::{self, TraceLayer};
 pub struct ApiServer {
     port: u16,
     host: String,
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
     flags: Flags,
+    metrics_history: Option<Arc<MetricsHistory>>,
 }
 
 impl ApiServer {
@@ -70,12 +73,18 @@ impl ApiServer {
         Ok((typed_service, inflection_service))
     }
 
-    pub fn new(grpc_config: GrpcApiOptions, security: Option<ApiSecurity>, flags: Flags) -> Self {
+    pub fn new(
+        grpc_config: GrpcApiOptions,
+        security: Option<Arc<JwtSecrets>>,
+        flags: Flags,
+        metrics_history: Option<Arc<MetricsHistory>>,
+    ) -> Self {
         Self {
             port: grpc_config.port as u16,
             host: grpc_config.host,
             security,
             flags,
+            metrics_history,
         }
     }
 
@@ -94,6 +103,7 @@ impl ApiServer {
         let common_service = CommonGrpcServiceServer::new(CommonService::new(
             cache_endpoints.clone(),
             operations_receiver.as_ref().map(|r| r.resubscribe()),
+            self.metrics_history.clone(),
         ));
         let common_service = web_config.enable(common_service);
 
@@ -176,9 +186,7 @@ impl ApiServer {
             "Starting gRPC server on {addr} with security: {}",
             self.security
                 .as_ref()
-                .map_or("None".to_string(), |s| match s {
-                    ApiSecurity::Jwt(_) => "JWT".to_string(),
-                })
+                .map_or("None".to_string(), |_| "JWT".to_string())
         );
 
         let addr = addr
@@ -1,3 +1,4 @@
+mod query_stream;
 mod service;
 pub use service::CommonService;
 
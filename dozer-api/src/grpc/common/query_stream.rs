@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use dozer_cache::cache::expression::QueryExpression;
+use dozer_cache::cache::CacheRecord;
+use dozer_types::grpc_types::common::{
+    query_stream_request, QueryStreamRequest, QueryStreamResponse, QueryStreamStart,
+};
+use futures_util::{Stream, StreamExt};
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+
+use crate::api_helper::for_each_record;
+use crate::auth::{Access, Tenant};
+use crate::grpc::shared_impl;
+use crate::grpc::types_helper::{map_field_definitions, map_record};
+use crate::naming::api_schema;
+use crate::CacheEndpoint;
+
+const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+pub type QueryStream = ReceiverStream<Result<QueryStreamResponse, Status>>;
+
+/// Serves a `queryStream` call. The first request frame must be `start`, and the following frames must be `credit`s.
+pub async fn query_stream(
+    endpoint_map: &HashMap<String, Arc<CacheEndpoint>>,
+    access: Option<Access>,
+    tenant: Option<Tenant>,
+    mut requests: impl Stream<Item = Result<QueryStreamRequest, Status>> + Send + Unpin + 'static,
+) -> Result<QueryStream, Status> {
+    let start = match requests.next().await.transpose()? {
+        Some(QueryStreamRequest {
+            request: Some(query_stream_request::Request::Start(start)),
+        }) => start,
+        _ => {
+            return Err(Status::invalid_argument(
+                "The first frame of `queryStream` must be `start`",
+            ))
+        }
+    };
+    let QueryStreamStart {
+        endpoint,
+        query,
+        chunk_size,
+        credits,
+    } = start;
+
+    let cache_endpoint = endpoint_map
+        .get(&endpoint)
+        .ok_or_else(|| Status::invalid_argument(&endpoint))?
+        .clone();
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_ref())?;
+    let mut query = shared_impl::parse_query(query.as_deref(), QueryExpression::with_no_limit)?;
+    let chunk_size = if chunk_size == 0 {
+        DEFAULT_CHUNK_SIZE
+    } else {
+        chunk_size as usize
+    };
+
+    let credits = Arc::new(Semaphore::new(credits as usize));
+    let mut credit_receiver = receive_credits(requests, credits.clone());
+
+    let (tx, rx) = mpsc::channel(1);
+    let runtime = Handle::current();
+    // All records are read in one cache transaction, which is kept while waiting for credits.
+    tokio::task::spawn_blocking(move || {
+        let mut fields = Some(map_field_definitions(
+            api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint)
+                .fields
+                .clone(),
+        ));
+        // Sends a chunk once there's a credit for it, or returns the status to end the stream with, if the client is
+        // still there.
+        let mut send = |records: Vec<CacheRecord>, last: bool| -> Result<(), Option<Status>> {
+            runtime.block_on(async {
+                tokio::select! {
+                    permit = credits.acquire() => match permit {
+                        Ok(permit) => permit.forget(),
+                        // The credit receiver closes the semaphore when the request stream fails.
+                        Err(_) => {
+                            return Err(Some(
+                                (&mut credit_receiver)
+                                    .await
+                                    .ok()
+                                    .flatten()
+                                    .unwrap_or_else(|| Status::internal("Credit receiver failed")),
+                            ))
+                        }
+                    },
+                    _ = tx.closed() => return Err(None),
+                }
+                let response = QueryStreamResponse {
+                    fields: fields.take().unwrap_or_default(),
+                    records: records.into_iter().map(map_record).collect(),
+                    last,
+                };
+                tx.send(Ok(response)).await.map_err(|_| None)
+            })
+        };
+
+        let mut chunk = Vec::with_capacity(chunk_size);
+        let mut stopped = None;
+        let result = for_each_record(
+            &cache_reader,
+            &mut query,
+            &cache_endpoint.endpoint,
+            access,
+            &mut |record| {
+                chunk.push(record);
+                if chunk.len() < chunk_size {
+                    return ControlFlow::Continue(());
+                }
+                match send(std::mem::take(&mut chunk), false) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(status) => {
+                        stopped = Some(status);
+                        ControlFlow::Break(())
+                    }
+                }
+            },
+        );
+
+        let status = match (result, stopped) {
+            (Err(e), _) => Some(e.into()),
+            (Ok(()), Some(status)) => status,
+            (Ok(()), None) => send(chunk, true).err().flatten(),
+        };
+        if let Some(status) = status {
+            let _ = tx.blocking_send(Err(status));
+        }
+    });
+
+    Ok(ReceiverStream::new(rx))
+}
+
+/// Adds the credits granted by the client to `credits`.
+///
+/// If the request stream fails or contains a frame other than `credit`, closes `credits` and returns the error.
+fn receive_credits(
+    mut requests: impl Stream<Item = Result<QueryStreamRequest, Status>> + Send + Unpin + 'static,
+    credits: Arc<Semaphore>,
+) -> JoinHandle<Option<Status>> {
+    tokio::spawn(async move {
+        while let Some(request) = requests.next().await {
+            let status = match request {
+                Ok(QueryStreamRequest {
+                    request: Some(query_stream_request::Request::Credit(credit)),
+                }) => {
+                    let available = Semaphore::MAX_PERMITS - credits.available_permits();
+                    credits.add_permits((credit.credits as usize).min(available));
+                    continue;
+                }
+                Ok(_) => Status::invalid_argument(
+                    "Only `credit` frames can follow the `start` frame of `queryStream`",
+                ),
+                Err(status) => status,
+            };
+            credits.close();
+            return Some(status);
+        }
+        None
+    })
+}
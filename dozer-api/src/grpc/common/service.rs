@@ -1,20 +1,31 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
-use crate::auth::Access;
+use crate::auth::{Access, Subject, Tenant};
+
+use super::query_stream::{self, QueryStream};
 
 use crate::grpc::shared_impl;
-use crate::grpc::types_helper::{map_field_definitions, map_record};
+use crate::grpc::types_helper::{field_to_prost_value, map_field_definitions, map_record};
+use crate::hot_keys::HOT_KEYS_IN_STATS;
+use crate::metrics_history::MetricsHistory;
+use crate::naming::api_schema;
 use crate::CacheEndpoint;
+use dozer_cache::CacheReader;
 use dozer_types::grpc_types::common::common_grpc_service_server::CommonGrpcService;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 
 use dozer_types::grpc_types::common::{
-    CountResponse, GetEndpointsRequest, GetEndpointsResponse, GetFieldsRequest, GetFieldsResponse,
-    OnEventRequest, QueryRequest, QueryResponse,
+    CountResponse, GetDescriptorRequest, GetDescriptorResponse, GetEndpointsRequest,
+    GetEndpointsResponse, GetFieldsRequest, GetFieldsResponse, GetMetricsHistoryRequest,
+    GetMetricsHistoryResponse, GetStatsRequest, GetStatsResponse, HotKey, IndexStats,
+    MetricsSnapshot, OnEventRequest, QueryRequest, QueryResponse, QueryStreamRequest,
 };
 use dozer_types::grpc_types::types::Operation;
+use dozer_types::types::IndexDefinition;
+use prost_reflect::prost_types::Timestamp;
 
 type EventResult<T> = Result<Response<T>, Status>;
 type ResponseStream = ReceiverStream<Result<Operation, tonic::Status>>;
@@ -24,12 +35,14 @@ pub struct CommonService {
     /// For look up endpoint from its name. `key == value.endpoint.name`.
     pub endpoint_map: HashMap<String, Arc<CacheEndpoint>>,
     pub event_notifier: Option<tokio::sync::broadcast::Receiver<Operation>>,
+    pub metrics_history: Option<Arc<MetricsHistory>>,
 }
 
 impl CommonService {
     pub fn new(
         endpoints: Vec<Arc<CacheEndpoint>>,
         event_notifier: Option<tokio::sync::broadcast::Receiver<Operation>>,
+        metrics_history: Option<Arc<MetricsHistory>>,
     ) -> Self {
         let endpoint_map = endpoints
             .into_iter()
@@ -38,23 +51,45 @@ impl CommonService {
         Self {
             endpoint_map,
             event_notifier,
+            metrics_history,
         }
     }
 
     fn parse_request(
         &self,
         request: Request<QueryRequest>,
-    ) -> Result<(&CacheEndpoint, QueryRequest, Option<Access>), Status> {
+    ) -> Result<
+        (
+            &Arc<CacheEndpoint>,
+            Arc<CacheReader>,
+            QueryRequest,
+            Option<Instant>,
+            Option<Access>,
+            Option<Subject>,
+        ),
+        Status,
+    > {
         let parts = request.into_parts();
+        let metadata = parts.0;
         let mut extensions = parts.1;
         let query_request = parts.2;
         let access = extensions.remove::<Access>();
+        let subject = extensions.remove::<Subject>();
         let endpoint = &query_request.endpoint;
         let cache_endpoint = self
             .endpoint_map
             .get(endpoint)
             .map_or(Err(Status::invalid_argument(endpoint)), Ok)?;
-        Ok((cache_endpoint, query_request, access))
+        let cache_reader = cache_endpoint.tenant_cache_reader(extensions.get::<Tenant>())?;
+        let deadline = shared_impl::parse_deadline(&cache_endpoint.endpoint, &metadata)?;
+        Ok((
+            cache_endpoint,
+            cache_reader,
+            query_request,
+            deadline,
+            access,
+            subject,
+        ))
     }
 }
 
@@ -64,14 +99,22 @@ impl CommonGrpcService for CommonService {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<CountResponse>, Status> {
-        let (cache_endpoint, query_request, access) = self.parse_request(request)?;
+        let (cache_endpoint, cache_reader, query_request, deadline, access, subject) =
+            self.parse_request(request)?;
 
-        let count = shared_impl::count(
-            &cache_endpoint.cache_reader(),
-            query_request.query.as_deref(),
-            &cache_endpoint.endpoint.name,
-            access,
-        )?;
+        let endpoint = cache_endpoint.clone();
+        let count = cache_endpoint
+            .read(move || {
+                shared_impl::count(
+                    &cache_reader,
+                    query_request.query.as_deref(),
+                    deadline,
+                    &endpoint.endpoint,
+                    access,
+                    subject.as_ref(),
+                )
+            })
+            .await?;
 
         let reply = CountResponse {
             count: count as u64,
@@ -83,22 +126,46 @@ impl CommonGrpcService for CommonService {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
-        let (cache_endpoint, query_request, access) = self.parse_request(request)?;
+        let (cache_endpoint, cache_reader, query_request, deadline, access, subject) =
+            self.parse_request(request)?;
 
-        let cache_reader = cache_endpoint.cache_reader();
-        let records = shared_impl::query(
-            &cache_reader,
-            query_request.query.as_deref(),
-            &cache_endpoint.endpoint.name,
-            access,
-        )?;
-        let schema = &cache_reader.get_schema().0;
+        let endpoint = cache_endpoint.clone();
+        let (records, order_by) = cache_endpoint
+            .read({
+                let cache_reader = cache_reader.clone();
+                move || {
+                    shared_impl::query(
+                        &cache_reader,
+                        query_request.query.as_deref(),
+                        deadline,
+                        &endpoint.endpoint,
+                        access,
+                        subject.as_ref(),
+                    )
+                }
+            })
+            .await?;
+        let schema = api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint);
 
         let fields = map_field_definitions(schema.fields.clone());
         let records = records.into_iter().map(map_record).collect();
         let reply = QueryResponse { fields, records };
 
-        Ok(Response::new(reply))
+        Ok(shared_impl::with_order_by(Response::new(reply), order_by))
+    }
+
+    type QueryStreamStream = QueryStream;
+
+    async fn query_stream(
+        &self,
+        request: Request<Streaming<QueryStreamRequest>>,
+    ) -> Result<Response<Self::QueryStreamStream>, Status> {
+        let (_, mut extensions, requests) = request.into_parts();
+        let access = extensions.remove::<Access>();
+        let tenant = extensions.remove::<Tenant>();
+        query_stream::query_stream(&self.endpoint_map, access, tenant, requests)
+            .await
+            .map(Response::new)
     }
 
     type OnEventStream = ResponseStream;
@@ -115,8 +182,9 @@ impl CommonGrpcService for CommonService {
             .ok_or_else(|| Status::invalid_argument(endpoint))?;
 
         shared_impl::on_event(
-            &cache_endpoint.cache_reader(),
+            cache_endpoint,
             query_request.filter.as_deref(),
+            query_request.resume_from,
             self.event_notifier.as_ref().map(|r| r.resubscribe()),
             access.cloned(),
             move |op| {
@@ -149,7 +217,7 @@ impl CommonGrpcService for CommonService {
             .map_or(Err(Status::invalid_argument(&endpoint)), Ok)?;
 
         let cache_reader = cache_endpoint.cache_reader();
-        let schema = &cache_reader.get_schema().0;
+        let schema = api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint);
 
         let fields = map_field_definitions(schema.fields.clone());
 
@@ -159,4 +227,116 @@ impl CommonGrpcService for CommonService {
             fields,
         }))
     }
+
+    async fn get_stats(
+        &self,
+        request: Request<GetStatsRequest>,
+    ) -> Result<Response<GetStatsResponse>, Status> {
+        let (_, extensions, request) = request.into_parts();
+        let endpoint = request.endpoint;
+        let cache_endpoint = self
+            .endpoint_map
+            .get(&endpoint)
+            .map_or(Err(Status::invalid_argument(&endpoint)), Ok)?;
+
+        let stats = cache_endpoint
+            .tenant_cache_reader(extensions.get::<Tenant>())?
+            .get_stats()
+            .map_err(shared_impl::from_error)?;
+
+        let indexes = stats
+            .indexes
+            .into_iter()
+            .map(|index| {
+                let (r#type, fields) = match index.index_definition {
+                    IndexDefinition::SortedInverted(fields) => ("sorted_inverted", fields),
+                    IndexDefinition::FullText(field) => ("full_text", vec![field]),
+                    IndexDefinition::Expression(expression) => {
+                        ("expression", vec![expression.field_index])
+                    }
+                };
+                IndexStats {
+                    r#type: r#type.to_string(),
+                    fields: fields.into_iter().map(|f| f as u32).collect(),
+                    entries: index.entries as u64,
+                    distinct_keys: index.distinct_keys as u64,
+                }
+            })
+            .collect();
+        let hot_keys = cache_endpoint
+            .hot_keys()
+            .map(|hot_keys| hot_keys.top(HOT_KEYS_IN_STATS))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hot_key| HotKey {
+                key: Some(field_to_prost_value(hot_key.key)),
+                reads: hot_key.reads,
+                last_read: Some(Timestamp {
+                    seconds: hot_key.last_read.timestamp(),
+                    nanos: hot_key.last_read.timestamp_subsec_nanos() as i32,
+                }),
+            })
+            .collect();
+        Ok(Response::new(GetStatsResponse {
+            record_count: stats.record_count as u64,
+            total_bytes: stats.total_bytes,
+            last_updated: stats.last_updated,
+            indexes,
+            hot_keys,
+        }))
+    }
+
+    async fn get_descriptor(
+        &self,
+        request: Request<GetDescriptorRequest>,
+    ) -> Result<Response<GetDescriptorResponse>, Status> {
+        let endpoint = request.into_inner().endpoint;
+        let cache_endpoint = self
+            .endpoint_map
+            .get(&endpoint)
+            .map_or(Err(Status::invalid_argument(&endpoint)), Ok)?;
+
+        Ok(Response::new(GetDescriptorResponse {
+            descriptor: cache_endpoint.descriptor().to_vec(),
+        }))
+    }
+
+    async fn get_metrics_history(
+        &self,
+        request: Request<GetMetricsHistoryRequest>,
+    ) -> Result<Response<GetMetricsHistoryResponse>, Status> {
+        let metrics_history = self
+            .metrics_history
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Metrics history is not enabled"))?;
+        let request = request.into_inner();
+        if !self.endpoint_map.contains_key(&request.endpoint) {
+            return Err(Status::invalid_argument(request.endpoint));
+        }
+
+        let from_millis = request.from.as_ref().map_or(0, timestamp_millis);
+        let to_millis = request.to.as_ref().map_or(u64::MAX, timestamp_millis);
+        let snapshots = metrics_history
+            .query(&request.endpoint, from_millis, to_millis)
+            .into_iter()
+            .map(|snapshot| MetricsSnapshot {
+                time: Some(Timestamp {
+                    seconds: (snapshot.timestamp_millis / 1000) as i64,
+                    nanos: (snapshot.timestamp_millis % 1000 * 1_000_000) as i32,
+                }),
+                record_count: snapshot.record_count,
+                total_bytes: snapshot.total_bytes,
+                log_position: snapshot.log_position,
+                throughput: snapshot.throughput,
+                lag_in_millis: snapshot.lag_in_millis,
+            })
+            .collect();
+        Ok(Response::new(GetMetricsHistoryResponse { snapshots }))
+    }
+}
+
+/// Milliseconds since the Unix epoch, with times before it clamped to it.
+fn timestamp_millis(timestamp: &Timestamp) -> u64 {
+    let millis = timestamp.seconds.saturating_mul(1000) + (timestamp.nanos / 1_000_000) as i64;
+    millis.max(0) as u64
 }
@@ -4,18 +4,21 @@ use crate::grpc::typed::tests::service::setup_pipeline;
 
 use dozer_types::grpc_types::{
     common::{
-        common_grpc_service_server::CommonGrpcService, GetEndpointsRequest, GetFieldsRequest,
-        OnEventRequest, QueryRequest,
+        common_grpc_service_server::CommonGrpcService, query_stream_request, GetDescriptorRequest,
+        GetEndpointsRequest, GetFieldsRequest, GetStatsRequest, OnEventRequest, QueryRequest,
+        QueryStreamCredit, QueryStreamRequest, QueryStreamStart,
     },
     types::{value, EventType, FieldDefinition, OperationType, RecordWithId, Type, Value},
 };
+use futures_util::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::Request;
 
-use super::CommonService;
+use super::{query_stream::query_stream, CommonService};
 
 async fn setup_common_service() -> CommonService {
     let (endpoints, rx1) = setup_pipeline().await;
-    CommonService::new(endpoints, Some(rx1))
+    CommonService::new(endpoints, Some(rx1), None)
 }
 
 async fn count_and_query(
@@ -88,6 +91,22 @@ async fn test_grpc_common_get_endpoints() {
     assert_eq!(response.endpoints, vec!["films".to_string()]);
 }
 
+#[tokio::test]
+async fn test_grpc_common_get_descriptor() {
+    let service = setup_common_service().await;
+    let response = service
+        .get_descriptor(Request::new(GetDescriptorRequest {
+            endpoint: "films".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let pool = prost_reflect::DescriptorPool::decode(response.descriptor.as_slice()).unwrap();
+    assert!(pool
+        .get_service_by_name("dozer.generated.films.Films")
+        .is_some());
+}
+
 #[tokio::test]
 async fn test_grpc_common_get_fields() {
     let service = setup_common_service().await;
@@ -105,31 +124,54 @@ async fn test_grpc_common_get_fields() {
                 typ: Type::UInt as i32,
                 name: "film_id".to_string(),
                 nullable: false
+                enum_values: vec![],
             },
             FieldDefinition {
                 typ: Type::String as i32,
                 name: "description".to_string(),
                 nullable: true
+                enum_values: vec![],
             },
             FieldDefinition {
                 typ: Type::Float as i32,
                 name: "rental_rate".to_string(),
                 nullable: true
+                enum_values: vec![],
             },
             FieldDefinition {
                 typ: Type::UInt as i32,
                 name: "release_year".to_string(),
                 nullable: true
+                enum_values: vec![],
             },
             FieldDefinition {
                 typ: Type::Timestamp as i32,
                 name: "updated_at".to_string(),
                 nullable: true
+                enum_values: vec![],
             }
         ]
     );
 }
 
+#[tokio::test]
+async fn test_grpc_common_get_stats() {
+    let service = setup_common_service().await;
+    let response = service
+        .get_stats(Request::new(GetStatsRequest {
+            endpoint: "films".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.record_count, 52);
+    assert!(response.total_bytes > 0);
+    assert!(!response.indexes.is_empty());
+    for index in response.indexes {
+        assert!(index.distinct_keys <= index.entries);
+    }
+}
+
 #[tokio::test]
 async fn test_grpc_common_on_event() {
     tokio::time::sleep(Duration::from_millis(100)).await; // wait for the mock server to start.
@@ -139,6 +181,7 @@ async fn test_grpc_common_on_event() {
             endpoint: "films".to_string(),
             r#type: EventType::All as i32,
             filter: Some(r#"{ "film_id": 32 }"#.to_string()),
+            resume_from: None,
         }))
         .await
         .unwrap()
@@ -155,3 +198,71 @@ async fn test_grpc_common_on_event() {
         }
     );
 }
+
+#[tokio::test]
+async fn test_grpc_common_on_event_resume_requires_change_retention() {
+    let service = setup_common_service().await;
+    let Err(status) = service
+        .on_event(Request::new(OnEventRequest {
+            endpoint: "films".to_string(),
+            r#type: EventType::All as i32,
+            filter: None,
+            resume_from: Some(0),
+        }))
+        .await
+    else {
+        panic!("Resuming without retained changes must fail");
+    };
+    assert_eq!(
+        status.message(),
+        "Change retention is not enabled for this endpoint"
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_common_query_stream() {
+    let service = setup_common_service().await;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(10);
+    tx.send(Ok(QueryStreamRequest {
+        request: Some(query_stream_request::Request::Start(QueryStreamStart {
+            endpoint: "films".to_string(),
+            query: None,
+            chunk_size: 20,
+            credits: 1,
+        })),
+    }))
+    .await
+    .unwrap();
+    let mut responses = query_stream(&service.endpoint_map, None, None, ReceiverStream::new(rx))
+        .await
+        .unwrap();
+
+    let response = responses.next().await.unwrap().unwrap();
+    assert!(!response.fields.is_empty());
+    assert_eq!(response.records.len(), 20);
+    assert!(!response.last);
+
+    // No more chunks without credits.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), responses.next())
+            .await
+            .is_err()
+    );
+
+    tx.send(Ok(QueryStreamRequest {
+        request: Some(query_stream_request::Request::Credit(QueryStreamCredit {
+            credits: 2,
+        })),
+    }))
+    .await
+    .unwrap();
+    let response = responses.next().await.unwrap().unwrap();
+    assert!(response.fields.is_empty());
+    assert_eq!(response.records.len(), 20);
+    assert!(!response.last);
+    let response = responses.next().await.unwrap().unwrap();
+    assert_eq!(response.records.len(), 12);
+    assert!(response.last);
+    assert!(responses.next().await.is_none());
+}
@@ -1,15 +1,22 @@
 use dozer_cache::dozer_log::home_dir::BuildPath;
-use dozer_cache::dozer_log::replication::Log;
-use dozer_types::bincode;
+use dozer_cache::dozer_log::provenance::ProvenanceStore;
+use dozer_cache::dozer_log::replication::{encode_log_response, negotiate_encoding, Log};
+use dozer_core::memory;
 use dozer_types::grpc_types::internal::internal_pipeline_service_server::{
     InternalPipelineService, InternalPipelineServiceServer,
 };
 use dozer_types::grpc_types::internal::{
-    BuildRequest, BuildResponse, LogRequest, LogResponse, StorageRequest, StorageResponse,
+    self, BuildRequest, BuildResponse, LogRequest, LogResponse, MemoryRequest, MemoryResponse,
+    ProvenanceRequest, ProvenanceResponse, SchemaDriftRequest, SchemaDriftResponse, StorageRequest,
+    StorageResponse,
 };
+use dozer_types::ingestion_types::{SchemaDriftAlert, SchemaDriftKind, SchemaDriftSeverity};
 use dozer_types::log::info;
 use dozer_types::models::api_config::AppGrpcOptions;
 use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::parking_lot;
+use dozer_types::serde_json;
+use dozer_types::types::Field;
 use futures_util::future::Either;
 use futures_util::stream::{AbortHandle, Abortable, Aborted, BoxStream};
 use futures_util::{Future, StreamExt, TryStreamExt};
@@ -26,16 +33,25 @@ use crate::errors::GrpcError;
 pub struct BuildAndLog {
     pub build: BuildPath,
     pub log: Arc<Mutex<Log>>,
+    /// The provenance of the endpoint's records, if the endpoint records it.
+    pub provenance: Option<ProvenanceStore>,
 }
 
 #[derive(Debug)]
 pub struct InternalPipelineServer {
     endpoints: HashMap<String, BuildAndLog>,
+    schema_drift_alerts: Arc<parking_lot::Mutex<Vec<SchemaDriftAlert>>>,
 }
 
 impl InternalPipelineServer {
-    pub fn new(endpoints: HashMap<String, BuildAndLog>) -> Self {
-        Self { endpoints }
+    pub fn new(
+        endpoints: HashMap<String, BuildAndLog>,
+        schema_drift_alerts: Arc<parking_lot::Mutex<Vec<SchemaDriftAlert>>>,
+    ) -> Self {
+        Self {
+            endpoints,
+            schema_drift_alerts,
+        }
     }
 }
 
@@ -58,7 +74,8 @@ impl InternalPipelineService for InternalPipelineServer {
         request: Request<BuildRequest>,
     ) -> Result<Response<BuildResponse>, Status> {
         let endpoint = request.into_inner().endpoint;
-        let build = &find_build_and_log(&self.endpoints, &endpoint)?.build;
+        let build_and_log = find_build_and_log(&self.endpoints, &endpoint)?;
+        let build = &build_and_log.build;
         let name = build.id.name().to_string();
         let schema_string = tokio::fs::read_to_string(&build.schema_path)
             .await
@@ -74,10 +91,12 @@ impl InternalPipelineService for InternalPipelineServer {
                 format!("Failed to read descriptor: {}", e),
             )
         })?;
+        let log_end = build_and_log.log.lock().await.end() as u64;
         Ok(Response::new(BuildResponse {
             name,
             schema_string,
             descriptor_bytes,
+            log_end,
         }))
     }
 
@@ -102,6 +121,98 @@ impl InternalPipelineService for InternalPipelineServer {
                 .boxed(),
         ))
     }
+
+    async fn describe_schema_drift(
+        &self,
+        _request: Request<SchemaDriftRequest>,
+    ) -> Result<Response<SchemaDriftResponse>, Status> {
+        let alerts = self
+            .schema_drift_alerts
+            .lock()
+            .iter()
+            .map(map_schema_drift_alert)
+            .collect();
+        Ok(Response::new(SchemaDriftResponse { alerts }))
+    }
+
+    async fn describe_memory(
+        &self,
+        _request: Request<MemoryRequest>,
+    ) -> Result<Response<MemoryResponse>, Status> {
+        let nodes = memory::node_memory()
+            .into_iter()
+            .map(|node| internal::NodeMemory {
+                resident_bytes: node.resident_bytes(),
+                node: node.node,
+                allocated_bytes: node.allocated_bytes,
+                freed_bytes: node.freed_bytes,
+                allocations: node.allocations,
+            })
+            .collect();
+        Ok(Response::new(MemoryResponse {
+            enabled: memory::is_enabled(),
+            nodes,
+        }))
+    }
+
+    async fn get_provenance(
+        &self,
+        request: Request<ProvenanceRequest>,
+    ) -> Result<Response<ProvenanceResponse>, Status> {
+        let request = request.into_inner();
+        let build_and_log = find_build_and_log(&self.endpoints, &request.endpoint)?;
+        let Some(store) = &build_and_log.provenance else {
+            return Err(Status::new(
+                tonic::Code::FailedPrecondition,
+                format!("Endpoint {} doesn't record provenance", request.endpoint),
+            ));
+        };
+        let key: Vec<Field> = serde_json::from_str(&request.key_string).map_err(|e| {
+            Status::new(
+                tonic::Code::InvalidArgument,
+                format!("Failed to deserialize key: {}", e),
+            )
+        })?;
+        let provenance = store.get(&key).map_err(|e| {
+            Status::new(
+                tonic::Code::Internal,
+                format!("Failed to read provenance: {}", e),
+            )
+        })?;
+        let provenance_string = provenance
+            .map(|provenance| serde_json::to_string(&provenance))
+            .transpose()
+            .map_err(|e| {
+                Status::new(
+                    tonic::Code::Internal,
+                    format!("Failed to serialize provenance: {}", e),
+                )
+            })?;
+        Ok(Response::new(ProvenanceResponse { provenance_string }))
+    }
+}
+
+fn map_schema_drift_alert(alert: &SchemaDriftAlert) -> internal::SchemaDriftAlert {
+    let (old_type, new_type) = match &alert.drift.kind {
+        SchemaDriftKind::TypeChanged {
+            old_type, new_type, ..
+        } => (Some(old_type.clone()), Some(new_type.clone())),
+        SchemaDriftKind::ColumnAdded | SchemaDriftKind::ColumnDropped => (None, None),
+    };
+    let severity = match alert.severity {
+        SchemaDriftSeverity::Info => internal::SchemaDriftSeverity::Info,
+        SchemaDriftSeverity::Warning => internal::SchemaDriftSeverity::Warning,
+        SchemaDriftSeverity::Error => internal::SchemaDriftSeverity::Error,
+    };
+    internal::SchemaDriftAlert {
+        connection: alert.connection.clone(),
+        table: alert.table.clone(),
+        column: alert.drift.column_name.clone(),
+        kind: alert.drift.kind.to_string(),
+        old_type,
+        new_type,
+        severity: severity as i32,
+    }
 }
 
 fn find_build_and_log<'a>(
@@ -117,6 +228,7 @@ fn find_build_and_log<'a>(
 }
 
 async fn get_log(log: Arc<Mutex<Log>>, request: LogRequest) -> Result<LogResponse, Status> {
+    let encoding = negotiate_encoding(&request.accepted_encodings);
     let mut log_mut = log.lock().await;
     let response = log_mut.read(
         request.start as usize..request.end as usize,
@@ -128,17 +240,21 @@ async fn get_log(log: Arc<Mutex<Log>>, request: LogRequest) -> Result<LogRespons
     let response = response
         .await
         .map_err(|e| Status::new(tonic::Code::Internal, e.to_string()))?;
-    let data = bincode::serialize(&response).map_err(|e| {
+    let data = encode_log_response(&response, encoding).map_err(|e| {
         Status::new(
             tonic::Code::Internal,
             format!("Failed to serialize response: {}", e),
         )
     })?;
-    Ok(LogResponse { data })
+    Ok(LogResponse {
+        data,
+        encoding: encoding as i32,
+    })
 }
 
 pub async fn start_internal_pipeline_server(
     endpoint_and_logs: Vec<(ApiEndpoint, BuildAndLog)>,
+    schema_drift_alerts: Arc<parking_lot::Mutex<Vec<SchemaDriftAlert>>>,
     options: &AppGrpcOptions,
     shutdown: impl Future<Output = ()> + Send + 'static,
 ) -> Result<(), GrpcError> {
@@ -146,7 +262,7 @@ pub async fn start_internal_pipeline_server(
         .into_iter()
         .map(|(endpoint, log)| (endpoint.name, log))
         .collect();
-    let server = InternalPipelineServer::new(endpoints);
+    let server = InternalPipelineServer::new(endpoints, schema_drift_alerts);
 
     // Tonic graceful shutdown doesn't allow us to set a timeout, resulting in hanging if a client doesn't close the connection.
     // So we just abort the server when the shutdown signal is received.
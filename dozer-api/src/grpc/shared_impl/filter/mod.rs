@@ -1,6 +1,6 @@
 use dozer_cache::cache::expression::{FilterExpression, Operator};
 use dozer_types::{
-    json_value_to_field,
+    helper::defined_json_value_to_field,
     ordered_float::OrderedFloat,
     types::{Field, Schema},
 };
@@ -31,6 +31,9 @@ fn record_satisfies_filter(record: &Record, filter: &FilterExpression, schema: &
         FilterExpression::And(filters) => filters
             .iter()
             .all(|filter| record_satisfies_filter(record, filter, schema)),
+        FilterExpression::Or(filters) => filters
+            .iter()
+            .any(|filter| record_satisfies_filter(record, filter, schema)),
         FilterExpression::Simple(field_name, operator, value) => {
             let Some((field_index, field_definition)) = schema
                 .fields
@@ -45,11 +48,7 @@ fn record_satisfies_filter(record: &Record, filter: &FilterExpression, schema: &
                 return false;
             };
 
-            let Ok(value) = json_value_to_field(
-                value.clone(),
-                field_definition.typ,
-                field_definition.nullable,
-            ) else {
+            let Ok(value) = defined_json_value_to_field(value.clone(), field_definition) else {
                 return false;
             };
 
@@ -67,6 +66,7 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             (value::Value::BoolValue(n), Field::Boolean(m)) => n < m,
             (value::Value::StringValue(n), Field::String(m)) => n < m,
             (value::Value::BytesValue(n), Field::Binary(m)) => n < m,
+            (value::Value::EnumValue(n), Field::Enum(m)) => n < m,
             _ => false,
         },
         Operator::LTE => match (field.value.as_ref().unwrap(), value) {
@@ -76,6 +76,7 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             (value::Value::BoolValue(n), Field::Boolean(m)) => n <= m,
             (value::Value::StringValue(n), Field::String(m)) => n <= m,
             (value::Value::BytesValue(n), Field::Binary(m)) => n <= m,
+            (value::Value::EnumValue(n), Field::Enum(m)) => n <= m,
             _ => false,
         },
         Operator::EQ => match (field.value.as_ref().unwrap(), value) {
@@ -85,6 +86,7 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             (value::Value::BoolValue(n), Field::Boolean(m)) => n == m,
             (value::Value::StringValue(n), Field::String(m)) => n == m,
             (value::Value::BytesValue(n), Field::Binary(m)) => n == m,
+            (value::Value::EnumValue(n), Field::Enum(m)) => n == m,
             _ => false,
         },
         Operator::GT => match (field.value.as_ref().unwrap(), value) {
@@ -94,6 +96,7 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             (value::Value::BoolValue(n), Field::Boolean(m)) => n > m,
             (value::Value::StringValue(n), Field::String(m)) => n > m,
             (value::Value::BytesValue(n), Field::Binary(m)) => n > m,
+            (value::Value::EnumValue(n), Field::Enum(m)) => n > m,
             _ => false,
         },
         Operator::GTE => match (field.value.as_ref().unwrap(), value) {
@@ -103,6 +106,7 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             (value::Value::BoolValue(n), Field::Boolean(m)) => n >= m,
             (value::Value::StringValue(n), Field::String(m)) => n >= m,
             (value::Value::BytesValue(n), Field::Binary(m)) => n >= m,
+            (value::Value::EnumValue(n), Field::Enum(m)) => n >= m,
             _ => false,
         },
         Operator::Contains => match (field.value.as_ref().unwrap(), value) {
@@ -764,6 +764,20 @@ fn test_record_satisfies_filter() {
         ]),
         false,
     );
+    check(
+        FilterExpression::Or(vec![
+            FilterExpression::Simple("a".into(), Operator::EQ, json!(2)),
+            FilterExpression::Simple("b".into(), Operator::EQ, "b".into()),
+        ]),
+        true,
+    );
+    check(
+        FilterExpression::Or(vec![
+            FilterExpression::Simple("a".into(), Operator::EQ, json!(2)),
+            FilterExpression::Simple("b".into(), Operator::EQ, "c".into()),
+        ]),
+        false,
+    );
 }
 
 #[test]
@@ -809,7 +823,9 @@ fn test_op_satisfies_filter() {
                     old: old.cloned(),
                     new: Some(new.clone()),
                     new_id: None,
-                    endpoint_name: "".into()
+                    endpoint_name: "".into(),
+                    seq: None,
+                    changed_fields: None,
                 },
                 filter,
                 &schema
@@ -1,16 +1,29 @@
-use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression};
+use std::time::Instant;
+
+use dozer_cache::cache::expression::{default_limit_for_query, FilterExpression, QueryExpression};
 use dozer_cache::cache::CacheRecord;
 use dozer_cache::CacheReader;
 use dozer_types::grpc_types::types::Operation;
 use dozer_types::log::warn;
+use dozer_types::models::api_endpoint::ApiEndpoint;
 use dozer_types::serde_json;
+use dozer_types::types::Schema;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc::Sender;
 use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataMap;
 use tonic::{Code, Response, Status};
 
-use crate::api_helper::{get_records, get_records_count};
-use crate::auth::Access;
+use crate::api_helper::{
+    effective_order_by, get_changes, get_records, get_records_count, query_deadline,
+    ORDER_BY_HEADER, QUERY_TIMEOUT_HEADER,
+};
+use crate::auth::{Access, Subject};
+use crate::errors::ApiError;
+use crate::grpc::types_helper;
+use crate::naming::rename_filter_fields;
+use crate::CacheEndpoint;
 
 mod filter;
 
@@ -18,7 +31,7 @@ pub fn from_error(error: impl std::error::Error) -> Status {
     Status::new(Code::Internal, error.to_string())
 }
 
-fn parse_query(
+pub fn parse_query(
     query: Option<&str>,
     default: impl FnOnce() -> QueryExpression,
 ) -> Result<QueryExpression, Status> {
@@ -34,46 +47,79 @@ fn parse_query(
     }
 }
 
+/// The deadline of a query, from the timeout of the endpoint and the timeout requested in the metadata.
+pub fn parse_deadline(
+    endpoint: &ApiEndpoint,
+    metadata: &MetadataMap,
+) -> Result<Option<Instant>, Status> {
+    let requested = metadata
+        .get(QUERY_TIMEOUT_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ApiError::InvalidQueryTimeout(format!("{value:?}")))
+        })
+        .transpose()?;
+    Ok(query_deadline(endpoint, requested)?)
+}
+
 pub fn count(
     reader: &CacheReader,
     query: Option<&str>,
-    endpoint: &str,
+    deadline: Option<Instant>,
+    endpoint: &ApiEndpoint,
     access: Option<Access>,
+    subject: Option<&Subject>,
 ) -> Result<usize, Status> {
     let mut query = parse_query(query, QueryExpression::with_no_limit)?;
-    Ok(get_records_count(reader, &mut query, endpoint, access)?)
+    query.deadline = deadline;
+    Ok(get_records_count(
+        reader, &mut query, endpoint, access, subject,
+    )?)
 }
 
+/// Queries the records, returning them with the order they're in, see `effective_order_by`.
 pub fn query(
     reader: &CacheReader,
     query: Option<&str>,
-    endpoint: &str,
+    deadline: Option<Instant>,
+    endpoint: &ApiEndpoint,
     access: Option<Access>,
-) -> Result<Vec<CacheRecord>, Status> {
+    subject: Option<&Subject>,
+) -> Result<(Vec<CacheRecord>, Option<String>), Status> {
     let mut query = parse_query(query, QueryExpression::with_default_limit)?;
+    query.deadline = deadline;
     if query.limit.is_none() {
         query.limit = Some(default_limit_for_query());
     }
-    let records = get_records(reader, &mut query, endpoint, access)?;
-    Ok(records)
+    let records = get_records(reader, &mut query, endpoint, access, subject)?;
+    Ok((records, effective_order_by(reader, &query, endpoint)))
+}
+
+/// Adds the order of the records to the metadata of a query response, if they're sorted.
+pub fn with_order_by<T>(mut response: Response<T>, order_by: Option<String>) -> Response<T> {
+    if let Some(value) = order_by.and_then(|order_by| order_by.parse().ok()) {
+        response.metadata_mut().insert(ORDER_BY_HEADER, value);
+    }
+    response
 }
 
 pub fn on_event<T: Send + 'static>(
-    reader: &CacheReader,
+    cache_endpoint: &CacheEndpoint,
     filter: Option<&str>,
+    resume_from: Option<u64>,
     mut broadcast_receiver: Option<Receiver<Operation>>,
-    _access: Option<Access>,
+    access: Option<Access>,
     event_mapper: impl Fn(Operation) -> Option<T> + Send + Sync + 'static,
 ) -> Result<Response<ReceiverStream<T>>, Status> {
-    // TODO: Use access.
-
     if broadcast_receiver.is_none() {
         return Err(Status::unavailable(
             "on_event is not enabled. This is currently an experimental feature. Enable it in the config.",
         ));
     }
 
-    let filter = match filter {
+    let schema = cache_endpoint.cache_reader().get_schema().0.clone();
+    let mut filter: Option<FilterExpression> = match filter {
         Some(filter) => {
             if filter.is_empty() {
                 None
@@ -83,24 +129,50 @@ pub fn on_event<T: Send + 'static>(
         }
         None => None,
     };
-    let schema = reader.get_schema().0.clone();
+    if let Some(filter) = &mut filter {
+        rename_filter_fields(filter, &schema, &cache_endpoint.endpoint);
+    }
+
+    // `broadcast_receiver` subscribed before we read the change log, so every event is either replayed or received.
+    // Events received up to `resumed_seq` were already replayed.
+    let endpoint_name = cache_endpoint.endpoint.name.clone();
+    let (replayed, resumed_seq) = match resume_from {
+        Some(resume_from) => {
+            let (changes, next) = get_changes(
+                cache_endpoint.change_log(),
+                Some(resume_from),
+                usize::MAX,
+                &endpoint_name,
+                access,
+            )?;
+            (changes, Some(next))
+        }
+        None => (vec![], None),
+    };
 
     let (tx, rx) = tokio::sync::mpsc::channel(1);
 
     tokio::spawn(async move {
+        for change in replayed {
+            let op = types_helper::map_change(endpoint_name.clone(), change);
+            if !send_event(&tx, op, filter.as_ref(), &schema, &event_mapper).await {
+                return;
+            }
+        }
+
         loop {
             if let Some(broadcast_receiver) = broadcast_receiver.as_mut() {
                 let event = broadcast_receiver.recv().await;
                 match event {
                     Ok(op) => {
-                        if filter::op_satisfies_filter(&op, filter.as_ref(), &schema) {
-                            if let Some(event) = event_mapper(op) {
-                                if (tx.send(event).await).is_err() {
-                                    // receiver dropped
-                                    break;
-                                }
+                        if let (Some(seq), Some(resumed_seq)) = (op.seq, resumed_seq) {
+                            if seq <= resumed_seq {
+                                continue;
                             }
                         }
+                        if !send_event(&tx, op, filter.as_ref(), &schema, &event_mapper).await {
+                            break;
+                        }
                     }
                     Err(e) => {
                         warn!("Failed to receive event from broadcast channel: {}", e);
@@ -115,3 +187,19 @@ pub fn on_event<T: Send + 'static>(
 
     Ok(Response::new(ReceiverStream::new(rx)))
 }
+
+/// Sends `op` if it satisfies `filter`. Returns `false` if the receiver is dropped.
+async fn send_event<T>(
+    tx: &Sender<T>,
+    op: Operation,
+    filter: Option<&FilterExpression>,
+    schema: &Schema,
+    event_mapper: &impl Fn(Operation) -> Option<T>,
+) -> bool {
+    if filter::op_satisfies_filter(&op, filter, schema) {
+        if let Some(event) = event_mapper(op) {
+            return tx.send(event).await.is_ok();
+        }
+    }
+    true
+}
@@ -37,6 +37,19 @@ pub fn on_event_to_typed_response(
         event.try_set_field(&event_desc.new_id_field, prost_reflect::Value::U64(new_id))?;
     }
 
+    if let (Some(seq), Some(seq_field)) = (op.seq, &event_desc.seq_field) {
+        event.try_set_field(seq_field, prost_reflect::Value::U64(seq))?;
+    }
+
+    if let (Some(changed_fields), Some(changed_fields_field)) =
+        (op.changed_fields, &event_desc.changed_fields_field)
+    {
+        event.try_set_field(
+            changed_fields_field,
+            prost_reflect::Value::Bytes(changed_fields.into()),
+        )?;
+    }
+
     Ok(TypedResponse::new(event))
 }
 
@@ -99,6 +112,20 @@ fn interval_value_to_pb(
             );
             Value::Message(duration)
         }
+        GrpcTypes::value::Value::MoneyValue(m) => {
+            let money_type_desc = descriptor.money_field.message.clone();
+            let mut money = DynamicMessage::new(money_type_desc);
+            money.set_field(
+                &descriptor.money_field.amount,
+                prost_reflect::Value::String(m.amount),
+            );
+            money.set_field(
+                &descriptor.money_field.currency,
+                prost_reflect::Value::String(m.currency),
+            );
+            Value::Message(money)
+        }
+        GrpcTypes::value::Value::EnumValue(e) => Value::EnumNumber(e as i32),
         GrpcTypes::value::Value::DecimalValue(d) => {
             let decimal_type_desc = descriptor.decimal_field.message.clone();
             let scale_field_desc = &descriptor.decimal_field.scale;
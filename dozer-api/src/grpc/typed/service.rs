@@ -7,7 +7,7 @@ use super::{
     DynamicMessage, TypedResponse,
 };
 use crate::{
-    auth::{Access, Authorizer},
+    auth::{Access, JwtSecrets, Subject, Tenant},
     errors::ApiInitError,
     generator::protoc::generator::{
         CountResponseDesc, EventDesc, ProtoGenerator, QueryResponseDesc, ServiceDesc,
@@ -18,7 +18,7 @@ use crate::{
 };
 use dozer_cache::CacheReader;
 use dozer_types::log::error;
-use dozer_types::{grpc_types::types::Operation, models::api_security::ApiSecurity};
+use dozer_types::{grpc_types::types::Operation, models::api_endpoint::ApiEndpoint};
 use futures_util::future;
 use prost_reflect::{MethodDescriptor, Value};
 use std::{borrow::Cow, collections::HashMap, convert::Infallible};
@@ -41,7 +41,7 @@ pub struct TypedService {
     /// For look up endpoint from its full service name. `key == value.service_desc.service.full_name()`.
     endpoint_map: HashMap<String, TypedEndpoint>,
     event_notifier: Option<tokio::sync::broadcast::Receiver<Operation>>,
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
 }
 
 impl Clone for TypedService {
@@ -60,7 +60,7 @@ impl TypedService {
     pub fn new(
         cache_endpoints: Vec<Arc<CacheEndpoint>>,
         event_notifier: Option<tokio::sync::broadcast::Receiver<Operation>>,
-        security: Option<ApiSecurity>,
+        security: Option<Arc<JwtSecrets>>,
     ) -> Result<Self, ApiInitError> {
         let endpoint_map = cache_endpoints
             .into_iter()
@@ -118,17 +118,23 @@ impl TypedService {
             }
             impl tonic::server::UnaryService<DynamicMessage> for CountService {
                 type Response = TypedResponse;
-                type Future = future::Ready<Result<Response<TypedResponse>, Status>>;
+                type Future = BoxFuture<Response<TypedResponse>, Status>;
                 fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
-                    let response = count(
-                        request,
-                        &self.cache_endpoint.cache_reader(),
-                        &self.cache_endpoint.endpoint.name,
-                        self.response_desc
-                            .take()
-                            .expect("This future shouldn't be polled twice"),
-                    );
-                    future::ready(response)
+                    let cache_endpoint = self.cache_endpoint.clone();
+                    let response_desc = self
+                        .response_desc
+                        .take()
+                        .expect("This future shouldn't be polled twice");
+                    Box::pin(async move {
+                        let cache_reader = cache_endpoint
+                            .tenant_cache_reader(request.extensions().get::<Tenant>())?;
+                        let endpoint = cache_endpoint.clone();
+                        cache_endpoint
+                            .read(move || {
+                                count(request, &cache_reader, &endpoint.endpoint, response_desc)
+                            })
+                            .await
+                    })
                 }
             }
 
@@ -148,17 +154,23 @@ impl TypedService {
             }
             impl tonic::server::UnaryService<DynamicMessage> for QueryService {
                 type Response = TypedResponse;
-                type Future = future::Ready<Result<Response<TypedResponse>, Status>>;
+                type Future = BoxFuture<Response<TypedResponse>, Status>;
                 fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
-                    let response = query(
-                        request,
-                        &self.cache_endpoint.cache_reader(),
-                        &self.cache_endpoint.endpoint.name,
-                        self.response_desc
-                            .take()
-                            .expect("This future shouldn't be polled twice"),
-                    );
-                    future::ready(response)
+                    let cache_endpoint = self.cache_endpoint.clone();
+                    let response_desc = self
+                        .response_desc
+                        .take()
+                        .expect("This future shouldn't be polled twice");
+                    Box::pin(async move {
+                        let cache_reader = cache_endpoint
+                            .tenant_cache_reader(request.extensions().get::<Tenant>())?;
+                        let endpoint = cache_endpoint.clone();
+                        cache_endpoint
+                            .read(move || {
+                                query(request, &cache_reader, &endpoint.endpoint, response_desc)
+                            })
+                            .await
+                    })
                 }
             }
 
@@ -188,8 +200,7 @@ impl TypedService {
                     fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
                         future::ready(on_event(
                             request,
-                            &self.cache_endpoint.cache_reader(),
-                            &self.cache_endpoint.endpoint.name,
+                            &self.cache_endpoint,
                             self.event_desc
                                 .take()
                                 .expect("This future shouldn't be polled twice"),
@@ -215,7 +226,7 @@ impl TypedService {
             if method_name == token_method_desc.method.name() {
                 struct AuthService {
                     response_desc: Option<TokenResponseDesc>,
-                    security: Option<ApiSecurity>,
+                    security: Option<Arc<JwtSecrets>>,
                 }
                 impl tonic::server::UnaryService<DynamicMessage> for AuthService {
                     type Response = TypedResponse;
@@ -261,8 +272,28 @@ where
         Poll::Ready(Ok(()))
     }
     fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let cache_endpoint = req
+            .uri()
+            .path()
+            .split('/')
+            .nth(1)
+            .and_then(|full_service_name| self.endpoint_map.get(full_service_name))
+            .map(|typed_endpoint| typed_endpoint.cache_endpoint.clone());
         match self.call_impl(req) {
-            Some(fut) => fut,
+            Some(fut) => Box::pin(async move {
+                let response = fut.await?;
+                if let Some(cache_endpoint) = cache_endpoint {
+                    // Errors of unary calls are in the headers. Streams that fail later are counted as succeeded.
+                    let code = response
+                        .headers()
+                        .get("grpc-status")
+                        .map_or(Code::Ok, |status| Code::from_bytes(status.as_bytes()));
+                    cache_endpoint
+                        .request_counts()
+                        .record(is_server_error(code));
+                }
+                Ok(response)
+            }),
             None => Box::pin(async move {
                 Ok(http::Response::builder()
                     .status(200)
@@ -279,10 +310,24 @@ impl tonic::server::NamedService for TypedService {
     const NAME: &'static str = ":dozer.generated";
 }
 
+/// Whether a status reports a failure of the server, rather than an invalid request.
+fn is_server_error(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unknown
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Internal
+            | Code::Unavailable
+            | Code::DataLoss
+    )
+}
+
 fn parse_request(
     (_, extensions, query_request): &mut (MetadataMap, Extensions, DynamicMessage),
-) -> Result<(Option<Cow<str>>, Option<Access>), Status> {
+) -> Result<(Option<Cow<str>>, Option<Access>, Option<Subject>), Status> {
     let access = extensions.remove::<Access>();
+    let subject = extensions.remove::<Subject>();
 
     let query = query_request.get_field_by_name("query");
     let query = query
@@ -306,13 +351,21 @@ fn parse_request(
 fn count(
     request: Request<DynamicMessage>,
     reader: &CacheReader,
-    endpoint: &str,
+    endpoint: &ApiEndpoint,
     response_desc: CountResponseDesc,
 ) -> Result<Response<TypedResponse>, Status> {
     let mut parts = request.into_parts();
-    let (query, access) = parse_request(&mut parts)?;
+    let (query, access, subject) = parse_request(&mut parts)?;
+    let deadline = shared_impl::parse_deadline(endpoint, &parts.0)?;
 
-    let count = shared_impl::count(reader, query.as_deref(), endpoint, access)?;
+    let count = shared_impl::count(
+        reader,
+        query.as_deref(),
+        deadline,
+        endpoint,
+        access,
+        subject.as_ref(),
+    )?;
     let res = count_response_to_typed_response(count, response_desc).map_err(|e| {
         error!("Count API error: {:?}", e);
         Status::internal("Count API error")
@@ -323,24 +376,31 @@ fn count(
 fn query(
     request: Request<DynamicMessage>,
     reader: &CacheReader,
-    endpoint: &str,
+    endpoint: &ApiEndpoint,
     response_desc: QueryResponseDesc,
 ) -> Result<Response<TypedResponse>, Status> {
     let mut parts = request.into_parts();
-    let (query, access) = parse_request(&mut parts)?;
+    let (query, access, subject) = parse_request(&mut parts)?;
+    let deadline = shared_impl::parse_deadline(endpoint, &parts.0)?;
 
-    let records = shared_impl::query(reader, query.as_deref(), endpoint, access)?;
+    let (records, order_by) = shared_impl::query(
+        reader,
+        query.as_deref(),
+        deadline,
+        endpoint,
+        access,
+        subject.as_ref(),
+    )?;
     let res = query_response_to_typed_response(records, response_desc).map_err(|e| {
         error!("Query API error: {:?}", e);
         Status::internal("Query API error")
     })?;
-    Ok(Response::new(res))
+    Ok(shared_impl::with_order_by(Response::new(res), order_by))
 }
 
 fn on_event(
     request: Request<DynamicMessage>,
-    reader: &CacheReader,
-    endpoint_name: &str,
+    cache_endpoint: &CacheEndpoint,
     event_desc: EventDesc,
     event_notifier: Option<tokio::sync::broadcast::Receiver<Operation>>,
 ) -> Result<Response<ReceiverStream<Result<TypedResponse, tonic::Status>>>, Status> {
@@ -357,33 +417,57 @@ fn on_event(
                 .ok_or_else(|| Status::new(Code::InvalidArgument, "filter must be a string"))
         })
         .transpose()?;
+    let resume_from = if query_request.has_field_by_name("resume_from") {
+        let resume_from = query_request.get_field_by_name("resume_from");
+        Some(
+            resume_from
+                .as_ref()
+                .and_then(|resume_from| resume_from.as_u64())
+                .ok_or_else(|| {
+                    Status::new(
+                        Code::InvalidArgument,
+                        "resume_from must be an unsigned integer",
+                    )
+                })?,
+        )
+    } else {
+        None
+    };
 
-    let endpoint_to_be_streamed = endpoint_name.to_string();
-    shared_impl::on_event(reader, filter, event_notifier, access.cloned(), move |op| {
-        if endpoint_to_be_streamed == op.endpoint_name {
-            match on_event_to_typed_response(op, event_desc.clone()) {
-                Ok(event) => Some(Ok(event)),
-                Err(e) => {
-                    error!("On event error: {:?}", e);
-                    None
+    let endpoint_to_be_streamed = cache_endpoint.endpoint.name.clone();
+    shared_impl::on_event(
+        cache_endpoint,
+        filter,
+        resume_from,
+        event_notifier,
+        access.cloned(),
+        move |op| {
+            if endpoint_to_be_streamed == op.endpoint_name {
+                match on_event_to_typed_response(op, event_desc.clone()) {
+                    Ok(event) => Some(Ok(event)),
+                    Err(e) => {
+                        error!("On event error: {:?}", e);
+                        None
+                    }
                 }
+            } else {
+                None
             }
-        } else {
-            None
-        }
-    })
+        },
+    )
 }
 
 fn token(
     request: Request<DynamicMessage>,
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
     response_desc: TokenResponseDesc,
 ) -> Result<Response<TypedResponse>, Status> {
     if let Some(security) = security {
         let _parts = request.into_parts();
 
-        let auth = Authorizer::from(&security);
-        let token = auth.generate_token(Access::All, None).unwrap();
+        let token = security
+            .generate_tenant_token(Access::All, None, None)
+            .unwrap();
         let res = token_response(token, response_desc);
         Ok(Response::new(res))
     } else {
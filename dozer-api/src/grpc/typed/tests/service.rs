@@ -1,5 +1,5 @@
 use crate::{
-    auth::{Access, Authorizer},
+    auth::{Access, Authorizer, JwtSecrets},
     grpc::{auth_middleware::AuthMiddlewareLayer, typed::TypedService},
     CacheEndpoint,
 };
@@ -66,6 +66,8 @@ pub async fn setup_pipeline() -> (Vec<Arc<CacheEndpoint>>, Receiver<Operation>)
                 }),
                 new_id: Some(0),
                 endpoint_name: "films".to_string(),
+                seq: None,
+                changed_fields: None,
             };
             if sender.send(op).is_err() {
                 break;
@@ -76,7 +78,7 @@ pub async fn setup_pipeline() -> (Vec<Arc<CacheEndpoint>>, Receiver<Operation>)
     (vec![Arc::new(cache_endpoint)], receiver)
 }
 
-async fn setup_typed_service(security: Option<ApiSecurity>) -> TypedService {
+async fn setup_typed_service(security: Option<Arc<JwtSecrets>>) -> TypedService {
     let (endpoints, rx1) = setup_pipeline().await;
 
     TypedService::new(endpoints, Some(rx1), security).unwrap()
@@ -88,11 +90,14 @@ async fn test_grpc_count_and_query_common(
     api_security: Option<ApiSecurity>,
     access_token: Option<String>,
 ) -> Result<(CountFilmsResponse, QueryFilmsResponse), tonic::Status> {
-    let typed_service = setup_typed_service(api_security.to_owned()).await;
+    let security = api_security
+        .as_ref()
+        .map(|api_security| Arc::new(JwtSecrets::from(api_security)));
+    let typed_service = setup_typed_service(security.clone()).await;
     let (_tx, rx) = oneshot::channel::<()>();
     // middleware
     let layer = tower::ServiceBuilder::new()
-        .layer(AuthMiddlewareLayer::new(api_security.to_owned()))
+        .layer(AuthMiddlewareLayer::new(security))
         .into_inner();
     let _jh = tokio::spawn(async move {
         Server::builder()
@@ -237,6 +242,7 @@ async fn test_typed_streaming1() {
     let request = FilmEventRequest {
         r#type: EventType::All as i32,
         filter: None,
+        resume_from: None,
     };
     let stream = client
         .on_event(Request::new(request))
@@ -266,6 +272,7 @@ async fn test_typed_streaming2() {
     let request = FilmEventRequest {
         r#type: EventType::All as i32,
         filter: Some(r#"{ "film_id": 32 }"#.into()),
+        resume_from: None,
     };
     let mut client = FilmsClient::connect(address.to_owned()).await.unwrap();
     let stream = client
@@ -297,6 +304,7 @@ async fn test_typed_streaming3() {
     let request = FilmEventRequest {
         r#type: EventType::All as i32,
         filter: Some(r#"{ "film_id": 0 }"#.into()),
+        resume_from: None,
     };
     let mut stream = client
         .on_event(Request::new(request))
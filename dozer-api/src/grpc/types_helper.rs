@@ -1,31 +1,36 @@
+use crate::change_log::Change;
 use dozer_cache::cache::CacheRecord;
 use dozer_types::grpc_types::types::{
-    value, DurationType, Operation, OperationType, PointType, Record, RecordWithId, RustDecimal,
-    Type, Value,
+    value, DurationType, MoneyType, Operation, OperationType, PointType, Record, RecordWithId,
+    RustDecimal, Type, Value,
 };
 use dozer_types::json_types::json_value_to_prost;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::{DozerDuration, Field, FieldType, DATE_FORMAT};
+use dozer_types::types::{self, DozerDuration, DozerMoney, Field, FieldType, DATE_FORMAT};
 use prost_reflect::prost_types::Timestamp;
 
-pub fn map_insert_operation(endpoint_name: String, record: CacheRecord) -> Operation {
+pub fn map_insert_operation(endpoint_name: String, record: CacheRecord, seq: u64) -> Operation {
     Operation {
         typ: OperationType::Insert as i32,
         old: None,
         new_id: Some(record.id),
         new: Some(record_to_internal_record(record)),
         endpoint_name,
+        seq: Some(seq),
+        changed_fields: None,
     }
 }
 
-pub fn map_delete_operation(endpoint_name: String, record: CacheRecord) -> Operation {
+pub fn map_delete_operation(endpoint_name: String, record: CacheRecord, seq: u64) -> Operation {
     Operation {
         typ: OperationType::Delete as i32,
         old: None,
         new: Some(record_to_internal_record(record)),
         new_id: None,
         endpoint_name,
+        seq: Some(seq),
+        changed_fields: None,
     }
 }
 
@@ -33,13 +38,47 @@ pub fn map_update_operation(
     endpoint_name: String,
     old: CacheRecord,
     new: CacheRecord,
+    seq: u64,
 ) -> Operation {
+    let changed_fields = old.record.diff(&new.record).as_bytes().to_vec();
     Operation {
         typ: OperationType::Update as i32,
         old: Some(record_to_internal_record(old)),
         new: Some(record_to_internal_record(new)),
         new_id: None,
         endpoint_name,
+        seq: Some(seq),
+        changed_fields: Some(changed_fields),
+    }
+}
+
+/// Maps a retained change to an event. Retained changes don't know record ids and versions, so they're left empty.
+pub fn map_change(endpoint_name: String, change: Change) -> Operation {
+    let changed_fields = change
+        .op
+        .changed_fields()
+        .map(|changed_fields| changed_fields.as_bytes().to_vec());
+    let (typ, old, new) = match change.op {
+        types::Operation::Insert { new } => (OperationType::Insert, None, new),
+        types::Operation::Delete { old } => (OperationType::Delete, None, old),
+        types::Operation::Update { old, new } => (OperationType::Update, Some(old), new),
+    };
+    let map_record = |record: types::Record| Record {
+        values: record
+            .values
+            .into_iter()
+            .map(field_to_prost_value)
+            .collect(),
+        version: 0,
+    };
+    Operation {
+        typ: typ as i32,
+        old: old.map(map_record),
+        new: Some(map_record(new)),
+        new_id: None,
+        endpoint_name,
+        seq: Some(change.seq),
+        changed_fields,
     }
 }
 
@@ -79,6 +118,15 @@ fn map_duration_to_prost_coord_map(d: DozerDuration) -> Value {
     }
 }
 
+fn map_money(m: DozerMoney) -> Value {
+    Value {
+        value: Some(value::Value::MoneyValue(MoneyType {
+            amount: m.amount.to_string(),
+            currency: m.currency.to_string(),
+        })),
+    }
+}
+
 fn map_decimal(d: Decimal) -> Value {
     Value {
         value: Some(value::Value::DecimalValue(RustDecimal {
@@ -138,6 +186,10 @@ pub fn field_to_prost_value(f: Field) -> Value {
         },
         Field::Point(point) => map_x_y_to_prost_coord_map(point.0.x_y()),
         Field::Duration(d) => map_duration_to_prost_coord_map(d),
+        Field::Money(m) => map_money(m),
+        Field::Enum(e) => Value {
+            value: Some(value::Value::EnumValue(e)),
+        },
     }
 }
 
@@ -150,6 +202,7 @@ pub fn map_field_definitions(
             typ: field_type_to_internal_type(f.typ) as i32,
             name: f.name,
             nullable: f.nullable,
+            enum_values: f.enum_values,
         })
         .collect()
 }
@@ -171,5 +224,67 @@ fn field_type_to_internal_type(typ: FieldType) -> Type {
         FieldType::Date => Type::String,
         FieldType::Point => Type::Point,
         FieldType::Duration => Type::Duration,
+        FieldType::Money => Type::Money,
+        FieldType::Enum => Type::Enum,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(values: Vec<Field>) -> Record {
+        Record {
+            values: values.into_iter().map(field_to_prost_value).collect(),
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_map_change() {
+        let insert = map_change(
+            "films".to_string(),
+            Change {
+                seq: 3,
+                op: types::Operation::Insert {
+                    new: types::Record::new(vec![Field::UInt(1)]),
+                },
+            },
+        );
+        assert_eq!(insert.typ, OperationType::Insert as i32);
+        assert_eq!(insert.old, None);
+        assert_eq!(insert.new, Some(record(vec![Field::UInt(1)])));
+        assert_eq!(insert.endpoint_name, "films");
+        assert_eq!(insert.seq, Some(3));
+
+        let update = map_change(
+            "films".to_string(),
+            Change {
+                seq: 4,
+                op: types::Operation::Update {
+                    old: types::Record::new(vec![Field::UInt(1)]),
+                    new: types::Record::new(vec![Field::UInt(2)]),
+                },
+            },
+        );
+        assert_eq!(update.typ, OperationType::Update as i32);
+        assert_eq!(update.old, Some(record(vec![Field::UInt(1)])));
+        assert_eq!(update.new, Some(record(vec![Field::UInt(2)])));
+        assert_eq!(update.seq, Some(4));
+
+        // Deleted records are sent as `new`, like deletes of the cache.
+        let delete = map_change(
+            "films".to_string(),
+            Change {
+                seq: 5,
+                op: types::Operation::Delete {
+                    old: types::Record::new(vec![Field::UInt(2)]),
+                },
+            },
+        );
+        assert_eq!(delete.typ, OperationType::Delete as i32);
+        assert_eq!(delete.old, None);
+        assert_eq!(delete.new, Some(record(vec![Field::UInt(2)])));
+        assert_eq!(delete.seq, Some(5));
     }
 }
@@ -0,0 +1,309 @@
+use std::num::NonZeroUsize;
+
+use dozer_cache::cache::CacheRecord;
+use dozer_types::{
+    chrono::{DateTime, Utc},
+    models::api_endpoint::{
+        default_hot_keys_max_tracked_keys, default_hot_keys_read_cache_size, HotKeyOptions,
+    },
+    parking_lot::Mutex,
+    types::Field,
+};
+use lru::LruCache;
+
+/// Number of hot keys reported in endpoint stats.
+pub const HOT_KEYS_IN_STATS: usize = 10;
+
+/// Read statistics of a primary key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotKey {
+    pub key: Field,
+    pub reads: u64,
+    pub last_read: DateTime<Utc>,
+}
+
+/// Counts reads by primary key and keeps the records of the most recently read keys in memory.
+///
+/// Both the counted keys and the records kept in memory are LRU caches, so every read is O(1). When
+/// `max_tracked_keys` keys are counted, a new key replaces the least recently read one.
+///
+/// Records kept in memory are bounded by their number, their estimated size in bytes, or both.
+///
+/// Reads don't wait for each other: a read finding the state locked by another one is neither counted nor kept in
+/// memory, and goes to the cache.
+#[derive(Debug)]
+pub struct HotKeys {
+    read_cache_size: Option<usize>,
+    read_cache_size_in_bytes: Option<usize>,
+    inner: Mutex<HotKeysInner>,
+}
+
+#[derive(Debug)]
+struct HotKeysInner {
+    reads: LruCache<Field, (u64, DateTime<Utc>)>,
+    /// Records kept in memory, with their estimated size.
+    read_cache: LruCache<Field, (CacheRecord, usize)>,
+    read_cache_bytes: usize,
+    /// Incremented on every invalidation, so a record read before an invalidation is not kept after it.
+    generation: u64,
+}
+
+impl HotKeys {
+    /// Without `options`, records are kept in memory up to `read_cache_size_in_bytes` only.
+    pub fn new(options: Option<&HotKeyOptions>, read_cache_size_in_bytes: Option<u64>) -> Self {
+        let max_tracked_keys = options
+            .and_then(|options| options.max_tracked_keys)
+            .unwrap_or_else(default_hot_keys_max_tracked_keys)
+            as usize;
+        Self {
+            read_cache_size: options.map(|options| {
+                options
+                    .read_cache_size
+                    .unwrap_or_else(default_hot_keys_read_cache_size) as usize
+            }),
+            read_cache_size_in_bytes: read_cache_size_in_bytes.map(|size| size as usize),
+            inner: Mutex::new(HotKeysInner {
+                reads: LruCache::new(
+                    NonZeroUsize::new(max_tracked_keys).unwrap_or(NonZeroUsize::MIN),
+                ),
+                read_cache: LruCache::unbounded(),
+                read_cache_bytes: 0,
+                generation: 0,
+            }),
+        }
+    }
+
+    /// Counts a read of `key`, and returns its record from memory, or from `read` if it's not kept in memory.
+    ///
+    /// A record kept in memory was read by another caller, so it's returned only if `check_access` accepts it.
+    pub fn get<E>(
+        &self,
+        key: &Field,
+        check_access: impl FnOnce(&CacheRecord) -> Result<(), E>,
+        read: impl FnOnce() -> Result<CacheRecord, E>,
+    ) -> Result<CacheRecord, E> {
+        let Some(mut inner) = self.inner.try_lock() else {
+            return read();
+        };
+        inner.count_read(key);
+        if let Some((record, _)) = inner.read_cache.get(key).cloned() {
+            drop(inner);
+            check_access(&record)?;
+            return Ok(record);
+        }
+        let generation = inner.generation;
+        drop(inner);
+
+        let record = read()?;
+
+        if let Some(mut inner) = self.inner.try_lock() {
+            if inner.generation == generation {
+                inner.admit(
+                    key,
+                    &record,
+                    self.read_cache_size,
+                    self.read_cache_size_in_bytes,
+                );
+            }
+        }
+        Ok(record)
+    }
+
+    /// Drops the records of `keys` from memory. Must be called after their changes are committed.
+    pub fn invalidate(&self, keys: impl IntoIterator<Item = Field>) {
+        let mut inner = self.inner.lock();
+        for key in keys {
+            inner.remove(&key);
+        }
+        inner.generation += 1;
+    }
+
+    /// Returns the `n` most read keys, most read first.
+    pub fn top(&self, n: usize) -> Vec<HotKey> {
+        let inner = self.inner.lock();
+        let mut hot_keys = inner
+            .reads
+            .iter()
+            .map(|(key, (reads, last_read))| HotKey {
+                key: key.clone(),
+                reads: *reads,
+                last_read: *last_read,
+            })
+            .collect::<Vec<_>>();
+        hot_keys.sort_by(|a, b| b.reads.cmp(&a.reads));
+        hot_keys.truncate(n);
+        hot_keys
+    }
+}
+
+impl HotKeysInner {
+    fn count_read(&mut self, key: &Field) {
+        let now = Utc::now();
+        if let Some((reads, last_read)) = self.reads.get_mut(key) {
+            *reads += 1;
+            *last_read = now;
+        } else {
+            self.reads.push(key.clone(), (1, now));
+        }
+    }
+
+    /// Keeps `record` in memory, replacing the least recently read records if there's no room.
+    fn admit(
+        &mut self,
+        key: &Field,
+        record: &CacheRecord,
+        read_cache_size: Option<usize>,
+        read_cache_size_in_bytes: Option<usize>,
+    ) {
+        let size = record_size(key, record);
+        if read_cache_size == Some(0) || read_cache_size_in_bytes.map_or(false, |max| size > max) {
+            return;
+        }
+        self.remove(key);
+
+        while read_cache_size.map_or(false, |max| self.read_cache.len() >= max)
+            || read_cache_size_in_bytes.map_or(false, |max| self.read_cache_bytes + size > max)
+        {
+            let Some((_, (_, replaced_size))) = self.read_cache.pop_lru() else {
+                break;
+            };
+            self.read_cache_bytes -= replaced_size;
+        }
+        self.read_cache.push(key.clone(), (record.clone(), size));
+        self.read_cache_bytes += size;
+    }
+
+    fn remove(&mut self, key: &Field) {
+        if let Some((_, size)) = self.read_cache.pop(key) {
+            self.read_cache_bytes -= size;
+        }
+    }
+}
+
+/// Size of a record kept in memory, estimated by the encoded length of its key and values.
+fn record_size(key: &Field, record: &CacheRecord) -> usize {
+    key.encoding_len()
+        + record
+            .record
+            .values
+            .iter()
+            .map(Field::encoding_len)
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Record;
+
+    use super::*;
+
+    fn record(id: u64) -> CacheRecord {
+        CacheRecord::new(id, 1, Record::new(vec![Field::UInt(id)]))
+    }
+
+    fn get(hot_keys: &HotKeys, id: u64) -> (CacheRecord, bool) {
+        let mut read = false;
+        let record = hot_keys
+            .get(
+                &Field::UInt(id),
+                |_| Ok::<_, ()>(()),
+                || {
+                    read = true;
+                    Ok(record(id))
+                },
+            )
+            .unwrap();
+        (record, read)
+    }
+
+    #[test]
+    fn test_hot_keys() {
+        let hot_keys = HotKeys::new(
+            Some(&HotKeyOptions {
+                max_tracked_keys: Some(2),
+                read_cache_size: Some(1),
+            }),
+            None,
+        );
+
+        // The first read of key 1 goes to the cache and keeps the record in memory.
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+        assert_eq!(get(&hot_keys, 1), (record(1), false));
+
+        // Key 2 replaces key 1, the least recently read, in memory.
+        assert_eq!(get(&hot_keys, 2), (record(2), true));
+        assert_eq!(get(&hot_keys, 2), (record(2), false));
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+
+        // Key 3 replaces key 2, the least recently read, in the counted keys.
+        get(&hot_keys, 3);
+        let top = hot_keys.top(HOT_KEYS_IN_STATS);
+        assert_eq!(
+            top.iter()
+                .map(|hot_key| (hot_key.key.clone(), hot_key.reads))
+                .collect::<Vec<_>>(),
+            vec![(Field::UInt(1), 3), (Field::UInt(3), 1)]
+        );
+
+        // Invalidated records are read from the cache again.
+        hot_keys.invalidate([Field::UInt(1)]);
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+    }
+
+    #[test]
+    fn test_hot_keys_read_cache_size_in_bytes() {
+        // Room for two records, each being an encoded `UInt` key and value.
+        let size = Field::UInt(0).encoding_len() * 2;
+        let hot_keys = HotKeys::new(None, Some(size as u64 * 2));
+
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+        assert_eq!(get(&hot_keys, 2), (record(2), true));
+        assert_eq!(get(&hot_keys, 1), (record(1), false));
+        assert_eq!(get(&hot_keys, 2), (record(2), false));
+
+        // Key 3 replaces key 1, the least recently read.
+        assert_eq!(get(&hot_keys, 3), (record(3), true));
+        assert_eq!(get(&hot_keys, 3), (record(3), false));
+        assert_eq!(get(&hot_keys, 2), (record(2), false));
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+
+        // A record larger than the whole budget is never kept.
+        let hot_keys = HotKeys::new(None, Some(size as u64 - 1));
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+    }
+
+    #[test]
+    fn test_hot_keys_check_access_of_kept_records() {
+        let hot_keys = HotKeys::new(
+            Some(&HotKeyOptions {
+                max_tracked_keys: Some(1),
+                read_cache_size: Some(1),
+            }),
+            None,
+        );
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+
+        // A restricted caller doesn't get the record another caller read.
+        let result = hot_keys.get(
+            &Field::UInt(1),
+            |_| Err("denied"),
+            || panic!("kept record should be checked, not read again"),
+        );
+        assert_eq!(result, Err("denied"));
+        assert_eq!(get(&hot_keys, 1), (record(1), false));
+    }
+
+    #[test]
+    fn test_hot_keys_dont_wait_for_each_other() {
+        let hot_keys = HotKeys::new(None, None);
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+
+        // A read while the state is locked goes to the cache and isn't counted.
+        let inner = hot_keys.inner.lock();
+        assert_eq!(get(&hot_keys, 1), (record(1), true));
+        drop(inner);
+        assert_eq!(get(&hot_keys, 1), (record(1), false));
+        assert_eq!(hot_keys.top(1)[0].reads, 2);
+    }
+}
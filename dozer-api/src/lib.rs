@@ -1,5 +1,5 @@
 use arc_swap::ArcSwap;
-use cache_builder::open_or_create_cache;
+use change_log::ChangeLog;
 use dozer_cache::{
     cache::{CacheWriteOptions, RwCacheManager},
     dozer_log::reader::{LogReaderBuilder, LogReaderOptions},
@@ -7,78 +7,192 @@ use dozer_cache::{
     CacheReader,
 };
 use dozer_types::{
+    grpc_types::internal::internal_pipeline_service_client::InternalPipelineServiceClient,
     grpc_types::types::Operation,
     labels::Labels,
-    models::api_endpoint::{
-        default_log_reader_batch_size, default_log_reader_buffer_size,
-        default_log_reader_timeout_in_millis, ApiEndpoint,
+    models::{
+        api_endpoint::{
+            default_log_reader_batch_size, default_log_reader_buffer_size,
+            default_log_reader_timeout_in_millis, default_max_commits_per_txn, ApiEndpoint,
+        },
+        app_config::KafkaLogConfig,
     },
+    types::Timezone,
 };
 use futures_util::Future;
+use hot_keys::HotKeys;
+use metrics_history::{Lag, RequestCounts};
+use prepared_queries::PreparedQueries;
+use read_pool::ReadPool;
 use std::{ops::Deref, sync::Arc};
+use tenancy::{tenant_column_index, TenantCacheReaders};
 
+pub use cache_builder::open_or_create_cache;
 pub use tonic_reflection;
 pub use tonic_web;
 mod api_helper;
+mod change_log;
+mod deprecation;
+mod hot_keys;
+mod naming;
+mod prepared_queries;
+mod read_pool;
+mod tenancy;
 
 #[derive(Debug)]
 pub struct CacheEndpoint {
     cache_reader: ArcSwap<CacheReader>,
+    tenant_cache_readers: Option<TenantCacheReaders>,
+    change_log: Option<Arc<ChangeLog>>,
+    hot_keys: Option<Arc<HotKeys>>,
+    prepared_queries: PreparedQueries,
+    read_pool: Option<ReadPool>,
+    lag: Arc<Lag>,
+    request_counts: RequestCounts,
     descriptor: Vec<u8>,
     endpoint: ApiEndpoint,
+    /// Client of the app, which records the provenance of the endpoint's records if it's enabled.
+    provenance_client: Option<InternalPipelineServiceClient<Channel>>,
+    /// The time zone timestamps are rendered in by the REST API.
+    timezone: Timezone,
 }
 
 const ENDPOINT_LABEL: &str = "endpoint";
 const BUILD_LABEL: &str = "build";
 
 impl CacheEndpoint {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         app_server_addr: String,
-        cache_manager: &dyn RwCacheManager,
+        cache_manager: Arc<dyn RwCacheManager>,
         endpoint: ApiEndpoint,
         cancel: impl Future<Output = ()> + Unpin + Send + 'static,
         operations_sender: Option<Sender<Operation>>,
         multi_pb: Option<MultiProgress>,
+        log_kafka: Option<KafkaLogConfig>,
+        timezone: Timezone,
     ) -> Result<(Self, JoinHandle<Result<(), CacheError>>), ApiInitError> {
+        let provenance_client = if endpoint.provenance.unwrap_or(false) {
+            Some(
+                InternalPipelineServiceClient::connect(app_server_addr.clone())
+                    .await
+                    .map_err(GrpcError::Transport)?,
+            )
+        } else {
+            None
+        };
+
         // Create log reader builder.
-        let log_reader_builder =
-            LogReaderBuilder::new(app_server_addr, get_log_reader_options(&endpoint)).await?;
+        let log_reader_builder = LogReaderBuilder::new(
+            app_server_addr,
+            get_log_reader_options(&endpoint, log_kafka),
+        )
+        .await?;
         let descriptor = log_reader_builder.descriptor.clone();
 
         // Open or create cache.
         let cache_labels =
             cache_labels(endpoint.name.clone(), log_reader_builder.build_name.clone());
         let schema = log_reader_builder.schema.clone();
-        let conflict_resolution = endpoint.conflict_resolution.unwrap_or_default();
-        let write_options = CacheWriteOptions {
-            insert_resolution: conflict_resolution.on_insert.unwrap_or_default(),
-            delete_resolution: conflict_resolution.on_delete.unwrap_or_default(),
-            update_resolution: conflict_resolution.on_update.unwrap_or_default(),
-            ..Default::default()
-        };
+        let write_options = cache_write_options(&endpoint);
         let cache = open_or_create_cache(
-            cache_manager,
+            &*cache_manager,
             cache_labels.clone(),
-            (schema.schema, schema.secondary_indexes),
+            (schema.schema.clone(), schema.secondary_indexes.clone()),
             &schema.connections,
             write_options,
+            num_partitions(&endpoint),
         )
         .map_err(ApiInitError::OpenOrCreateCache)?;
 
+        // An existing cache resumes from its last committed position, which must still be in the log.
+        let position = cache
+            .get_metadata()
+            .map_err(ApiInitError::OpenOrCreateCache)?
+            .unwrap_or(0);
+        if position > log_reader_builder.log_end {
+            return Err(ApiInitError::CacheAheadOfLog {
+                labels: cache_labels,
+                position,
+                log_end: log_reader_builder.log_end,
+            });
+        }
+
+        // With tenancy, records are stored in a cache per tenant and the endpoint cache only tracks the log position.
+        let (tenant_caches, tenant_cache_readers) = match &endpoint.tenancy {
+            Some(tenancy) => {
+                let column_index = tenant_column_index(&schema.schema, tenancy)?;
+                let tenant_caches = TenantCaches::new(
+                    cache_manager.clone(),
+                    cache_labels.clone(),
+                    (schema.schema, schema.secondary_indexes),
+                    write_options,
+                    column_index,
+                    tenancy.max_records_per_tenant,
+                );
+                let tenant_cache_readers =
+                    TenantCacheReaders::new(cache_manager.clone(), cache_labels.clone());
+                (Some(tenant_caches), Some(tenant_cache_readers))
+            }
+            None => (None, None),
+        };
+
+        // Tenant caches are written separately, so changes are only logged for endpoints without tenancy.
+        let change_log = match (&endpoint.change_retention, &endpoint.tenancy) {
+            (Some(change_retention), None) => {
+                let seq = cache
+                    .get_metadata()
+                    .map_err(ApiInitError::OpenOrCreateCache)?
+                    .unwrap_or(0);
+                Some(Arc::new(ChangeLog::new(change_retention, seq)))
+            }
+            _ => None,
+        };
+
+        // Reads of tenant caches are not tracked, because hot keys are reported per endpoint.
+        let hot_keys = match (
+            &endpoint.hot_keys,
+            endpoint.read_cache_size_in_bytes,
+            &endpoint.tenancy,
+        ) {
+            (None, None, _) | (_, _, Some(_)) => None,
+            (hot_keys, read_cache_size_in_bytes, None) => Some(Arc::new(HotKeys::new(
+                hot_keys.as_ref(),
+                read_cache_size_in_bytes,
+            ))),
+        };
+
+        let read_pool = read_pool(&endpoint);
+        let max_commits_per_txn = endpoint
+            .concurrency
+            .as_ref()
+            .and_then(|concurrency| concurrency.max_commits_per_txn)
+            .unwrap_or_else(default_max_commits_per_txn);
+
+        let lag = Arc::new(Lag::default());
+
         // Open cache reader.
         let cache_reader =
-            open_cache_reader(cache_manager, cache_labels)?.expect("We just created the cache");
+            open_cache_reader(&*cache_manager, cache_labels)?.expect("We just created the cache");
 
         // Start cache builder.
         let handle = {
             let operations_sender = operations_sender.map(|sender| (endpoint.name.clone(), sender));
+            let change_log = change_log.clone();
+            let hot_keys = hot_keys.clone();
+            let lag = lag.clone();
             tokio::spawn(async move {
                 cache_builder::build_cache(
                     cache,
+                    tenant_caches,
+                    change_log,
+                    hot_keys,
+                    lag,
                     cancel,
                     log_reader_builder,
                     operations_sender,
                     multi_pb,
+                    max_commits_per_txn,
                 )
                 .await
             })
@@ -87,8 +201,17 @@ impl CacheEndpoint {
         Ok((
             Self {
                 cache_reader: ArcSwap::from_pointee(cache_reader),
+                tenant_cache_readers,
+                change_log,
+                hot_keys,
+                prepared_queries: PreparedQueries::default(),
+                read_pool,
+                lag,
+                request_counts: RequestCounts::default(),
                 descriptor,
                 endpoint,
+                provenance_client,
+                timezone,
             },
             handle,
         ))
@@ -103,8 +226,17 @@ impl CacheEndpoint {
         labels.push(endpoint.name.clone(), endpoint.name.clone());
         Ok(Self {
             cache_reader: ArcSwap::from_pointee(open_existing_cache_reader(cache_manager, labels)?),
+            tenant_cache_readers: None,
+            change_log: None,
+            hot_keys: None,
+            prepared_queries: PreparedQueries::default(),
+            read_pool: read_pool(&endpoint),
+            lag: Default::default(),
+            request_counts: RequestCounts::default(),
             descriptor,
             endpoint,
+            provenance_client: None,
+            timezone: Timezone::Utc,
         })
     }
 
@@ -112,6 +244,51 @@ impl CacheEndpoint {
         self.cache_reader.load()
     }
 
+    /// Returns the cache reader of `tenant` if the endpoint has tenancy, or the endpoint cache reader otherwise.
+    pub fn tenant_cache_reader(
+        &self,
+        tenant: Option<&Tenant>,
+    ) -> Result<Arc<CacheReader>, ApiError> {
+        match &self.tenant_cache_readers {
+            Some(tenant_cache_readers) => {
+                tenant_cache_readers.get(&tenant.ok_or(ApiError::TenantRequired)?.0)
+            }
+            None => Ok(self.cache_reader().clone()),
+        }
+    }
+
+    pub fn change_log(&self) -> Option<&ChangeLog> {
+        self.change_log.as_deref()
+    }
+
+    pub fn hot_keys(&self) -> Option<&HotKeys> {
+        self.hot_keys.as_deref()
+    }
+
+    pub fn timezone(&self) -> &Timezone {
+        &self.timezone
+    }
+
+    pub fn lag(&self) -> &Lag {
+        &self.lag
+    }
+
+    pub fn request_counts(&self) -> &RequestCounts {
+        &self.request_counts
+    }
+
+    pub fn prepared_queries(&self) -> &PreparedQueries {
+        &self.prepared_queries
+    }
+
+    /// Runs `read` on the read threads of the endpoint if it has any, or in place otherwise.
+    pub async fn read<T: Send + 'static>(&self, read: impl FnOnce() -> T + Send + 'static) -> T {
+        match &self.read_pool {
+            Some(read_pool) => read_pool.run(read).await,
+            None => read(),
+        }
+    }
+
     pub fn descriptor(&self) -> &[u8] {
         &self.descriptor
     }
@@ -119,6 +296,10 @@ impl CacheEndpoint {
     pub fn endpoint(&self) -> &ApiEndpoint {
         &self.endpoint
     }
+
+    pub fn provenance_client(&self) -> Option<&InternalPipelineServiceClient<Channel>> {
+        self.provenance_client.as_ref()
+    }
 }
 
 pub fn cache_labels(endpoint: String, build: String) -> Labels {
@@ -128,6 +309,25 @@ pub fn cache_labels(endpoint: String, build: String) -> Labels {
     labels
 }
 
+/// The number of partitions of the endpoint cache. With tenancy, the endpoint cache holds no records and isn't
+/// partitioned.
+pub fn num_partitions(endpoint: &ApiEndpoint) -> usize {
+    match (endpoint.partitions, &endpoint.tenancy) {
+        (Some(partitions), None) => partitions.max(1) as usize,
+        _ => 1,
+    }
+}
+
+pub fn cache_write_options(endpoint: &ApiEndpoint) -> CacheWriteOptions {
+    let conflict_resolution = endpoint.conflict_resolution.unwrap_or_default();
+    CacheWriteOptions {
+        insert_resolution: conflict_resolution.on_insert.unwrap_or_default(),
+        delete_resolution: conflict_resolution.on_delete.unwrap_or_default(),
+        update_resolution: conflict_resolution.on_update.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
 fn open_cache_reader(
     cache_manager: &dyn RwCacheManager,
     labels: Labels,
@@ -146,7 +346,18 @@ fn open_existing_cache_reader(
         .ok_or_else(|| ApiInitError::CacheNotFound(labels))
 }
 
-fn get_log_reader_options(endpoint: &ApiEndpoint) -> LogReaderOptions {
+fn read_pool(endpoint: &ApiEndpoint) -> Option<ReadPool> {
+    endpoint
+        .concurrency
+        .as_ref()
+        .and_then(|concurrency| concurrency.read_threads)
+        .map(ReadPool::new)
+}
+
+fn get_log_reader_options(
+    endpoint: &ApiEndpoint,
+    kafka: Option<KafkaLogConfig>,
+) -> LogReaderOptions {
     LogReaderOptions {
         endpoint: endpoint.name.clone(),
         batch_size: endpoint
@@ -164,15 +375,19 @@ fn get_log_reader_options(endpoint: &ApiEndpoint) -> LogReaderOptions {
             .as_ref()
             .and_then(|options| options.buffer_size)
             .unwrap_or_else(default_log_reader_buffer_size),
+        kafka,
     }
 }
 
 // Exports
+pub mod alerts;
 pub mod auth;
 mod cache_builder;
 pub mod errors;
+pub mod gateway;
 pub mod generator;
 pub mod grpc;
+pub mod metrics_history;
 pub mod rest;
 // Re-exports
 pub use actix_cors;
@@ -180,13 +395,17 @@ pub use actix_web;
 pub use actix_web_httpauth;
 pub use api_helper::API_LATENCY_HISTOGRAM_NAME;
 pub use api_helper::API_REQUEST_COUNTER_NAME;
+pub use api_helper::CACHE_READERS_FULL_COUNTER_NAME;
 pub use async_trait;
+use auth::Tenant;
+use cache_builder::TenantCaches;
 use dozer_types::indicatif::MultiProgress;
-use errors::ApiInitError;
+use errors::{ApiError, ApiInitError, GrpcError};
 pub use openapiv3;
 pub use tokio;
 use tokio::{sync::broadcast::Sender, task::JoinHandle};
 pub use tonic;
+use tonic::transport::Channel;
 pub use tracing_actix_web;
 #[cfg(test)]
 mod test_utils;
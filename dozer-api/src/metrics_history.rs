@@ -0,0 +1,304 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dozer_types::bincode;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::{error, warn};
+use dozer_types::models::api_config::{
+    default_metrics_history_interval_in_millis, default_metrics_history_retention_in_hours,
+    MetricsHistoryOptions,
+};
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::{Deserialize, Serialize};
+use futures_util::Future;
+
+use crate::CacheEndpoint;
+
+/// The end-to-end latency and time of the last changes a cache builder committed.
+#[derive(Debug, Default)]
+pub struct Lag {
+    /// Milliseconds plus one, or zero if nothing was committed yet.
+    millis: AtomicU64,
+    /// Milliseconds since the Unix epoch, or zero if nothing was committed yet.
+    committed_at_millis: AtomicU64,
+}
+
+impl Lag {
+    pub fn set(&self, lag: Duration) {
+        let millis = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX);
+        self.millis
+            .store(millis.saturating_add(1), Ordering::Relaxed);
+        self.committed_at_millis
+            .store(now_millis(), Ordering::Relaxed);
+    }
+
+    pub fn committed_at_millis(&self) -> Option<u64> {
+        match self.committed_at_millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(millis),
+        }
+    }
+
+    pub fn get(&self) -> Option<u64> {
+        match self.millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(millis - 1),
+        }
+    }
+}
+
+/// Counts the API requests of an endpoint, and how many of them failed.
+#[derive(Debug, Default)]
+pub struct RequestCounts {
+    requests: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl RequestCounts {
+    pub fn record(&self, failed: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// The number of requests and failed requests so far.
+    pub fn get(&self) -> (u64, u64) {
+        (
+            self.requests.load(Ordering::Relaxed),
+            self.errors.load(Ordering::Relaxed),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct MetricsSnapshot {
+    pub endpoint: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_millis: u64,
+    pub record_count: u64,
+    pub total_bytes: u64,
+    pub log_position: Option<u64>,
+    /// Log entries applied per second since the previous snapshot of the endpoint.
+    pub throughput: f64,
+    pub lag_in_millis: Option<u64>,
+}
+
+/// Snapshots of the endpoint metrics over the retention period, persisted in a file so a restarted app keeps them.
+#[derive(Debug)]
+pub struct MetricsHistory {
+    path: PathBuf,
+    interval: Duration,
+    retention_millis: u64,
+    /// Oldest first.
+    snapshots: Mutex<VecDeque<MetricsSnapshot>>,
+}
+
+impl MetricsHistory {
+    /// Opens the history persisted at `path`. A history that can't be read is started over, as it's only used for
+    /// charts.
+    pub fn open(path: PathBuf, options: &MetricsHistoryOptions) -> Self {
+        let snapshots = match fs::read(&path) {
+            Ok(bytes) => bincode::deserialize(&bytes).unwrap_or_else(|e| {
+                warn!("Starting over metrics history {path:?}, which can't be decoded: {e}");
+                VecDeque::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => {
+                warn!("Starting over metrics history {path:?}, which can't be read: {e}");
+                VecDeque::new()
+            }
+        };
+        let interval_in_millis = options
+            .interval_in_millis
+            .unwrap_or_else(default_metrics_history_interval_in_millis);
+        let retention_in_hours = options
+            .retention_in_hours
+            .unwrap_or_else(default_metrics_history_retention_in_hours);
+        Self {
+            path,
+            interval: Duration::from_millis(interval_in_millis),
+            retention_millis: retention_in_hours.saturating_mul(60 * 60 * 1000),
+            snapshots: Mutex::new(snapshots),
+        }
+    }
+
+    /// Snapshots the metrics of `cache_endpoints` every interval, until `shutdown`.
+    pub async fn record_periodically(
+        self: Arc<Self>,
+        cache_endpoints: Vec<Arc<CacheEndpoint>>,
+        shutdown: impl Future<Output = ()>,
+    ) {
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(self.interval) => (),
+            }
+            self.record(&cache_endpoints, now_millis());
+        }
+    }
+
+    fn record(&self, cache_endpoints: &[Arc<CacheEndpoint>], timestamp_millis: u64) {
+        let mut new_snapshots = vec![];
+        for cache_endpoint in cache_endpoints {
+            let name = &cache_endpoint.endpoint().name;
+            let stats = match cache_endpoint.cache_reader().get_stats() {
+                Ok(stats) => stats,
+                Err(e) => {
+                    error!("Failed to snapshot the metrics of endpoint {name}: {e}");
+                    continue;
+                }
+            };
+            let previous_position = self
+                .latest(name)
+                .and_then(|previous| Some((previous.timestamp_millis, previous.log_position?)));
+            let throughput = match (previous_position, stats.last_updated) {
+                (Some((previous_millis, previous_position)), Some(position))
+                    if timestamp_millis > previous_millis =>
+                {
+                    position.saturating_sub(previous_position) as f64 * 1000.0
+                        / (timestamp_millis - previous_millis) as f64
+                }
+                _ => 0.0,
+            };
+            new_snapshots.push(MetricsSnapshot {
+                endpoint: name.clone(),
+                timestamp_millis,
+                record_count: stats.record_count as u64,
+                total_bytes: stats.total_bytes,
+                log_position: stats.last_updated,
+                throughput,
+                lag_in_millis: cache_endpoint.lag().get(),
+            });
+        }
+        self.append(new_snapshots, timestamp_millis);
+    }
+
+    fn latest(&self, endpoint: &str) -> Option<MetricsSnapshot> {
+        self.snapshots
+            .lock()
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.endpoint == endpoint)
+            .cloned()
+    }
+
+    /// Adds snapshots taken at `timestamp_millis`, dropping the ones older than the retention period, and persists
+    /// the history.
+    fn append(&self, new_snapshots: Vec<MetricsSnapshot>, timestamp_millis: u64) {
+        let mut snapshots = self.snapshots.lock();
+        snapshots.extend(new_snapshots);
+        let oldest_millis = timestamp_millis.saturating_sub(self.retention_millis);
+        while snapshots
+            .front()
+            .map_or(false, |snapshot| snapshot.timestamp_millis < oldest_millis)
+        {
+            snapshots.pop_front();
+        }
+        if let Err(e) = self.save(&snapshots) {
+            error!("Failed to save metrics history {:?}: {e}", self.path);
+        }
+    }
+
+    /// Written to a temporary file first, so a crash doesn't leave a partial history.
+    fn save(&self, snapshots: &VecDeque<MetricsSnapshot>) -> Result<(), BoxedError> {
+        let bytes = bincode::serialize(snapshots)?;
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, bytes)?;
+        fs::rename(temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// The snapshots of `endpoint` taken from `from_millis`, inclusive, to `to_millis`, exclusive, oldest first.
+    pub fn query(&self, endpoint: &str, from_millis: u64, to_millis: u64) -> Vec<MetricsSnapshot> {
+        self.snapshots
+            .lock()
+            .iter()
+            .filter(|snapshot| {
+                snapshot.endpoint == endpoint
+                    && (from_millis..to_millis).contains(&snapshot.timestamp_millis)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    const HOUR_MILLIS: u64 = 60 * 60 * 1000;
+
+    fn snapshot(endpoint: &str, timestamp_millis: u64) -> MetricsSnapshot {
+        MetricsSnapshot {
+            endpoint: endpoint.to_string(),
+            timestamp_millis,
+            record_count: 10,
+            total_bytes: 4096,
+            log_position: Some(20),
+            throughput: 1.5,
+            lag_in_millis: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_metrics_history() {
+        let dir = TempDir::new("metrics_history").unwrap();
+        let path = dir.path().join("metrics_history");
+        let options = MetricsHistoryOptions {
+            interval_in_millis: None,
+            retention_in_hours: Some(1),
+        };
+
+        let history = MetricsHistory::open(path.clone(), &options);
+        history.append(vec![snapshot("films", 0), snapshot("actors", 0)], 0);
+        history.append(vec![snapshot("films", HOUR_MILLIS)], HOUR_MILLIS);
+        assert_eq!(history.query("films", 0, u64::MAX).len(), 2);
+        assert_eq!(
+            history.query("films", 1, u64::MAX),
+            vec![snapshot("films", HOUR_MILLIS)]
+        );
+
+        // Snapshots older than the retention period are dropped.
+        history.append(vec![snapshot("films", HOUR_MILLIS + 1)], HOUR_MILLIS + 1);
+        assert!(history.query("actors", 0, u64::MAX).is_empty());
+
+        // The history is kept across restarts.
+        let history = MetricsHistory::open(path, &options);
+        assert_eq!(
+            history.query("films", 0, u64::MAX),
+            vec![
+                snapshot("films", HOUR_MILLIS),
+                snapshot("films", HOUR_MILLIS + 1)
+            ]
+        );
+        assert_eq!(
+            history.latest("films"),
+            Some(snapshot("films", HOUR_MILLIS + 1))
+        );
+    }
+
+    #[test]
+    fn test_lag() {
+        let lag = Lag::default();
+        assert_eq!(lag.get(), None);
+        lag.set(Duration::ZERO);
+        assert_eq!(lag.get(), Some(0));
+        lag.set(Duration::from_millis(250));
+        assert_eq!(lag.get(), Some(250));
+    }
+}
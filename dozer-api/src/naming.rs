@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use dozer_cache::cache::expression::{FilterExpression, QueryExpression};
+use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::types::{IndexFunction, Schema};
+use inflector::Inflector;
+
+/// Whether the endpoint serves its field names in camelCase.
+pub fn camel_case_fields(endpoint: &ApiEndpoint) -> bool {
+    endpoint
+        .naming
+        .as_ref()
+        .and_then(|naming| naming.camel_case_fields)
+        .unwrap_or(false)
+}
+
+/// The name a field is served with, e.g. `firstName` of `first_name` in camelCase.
+pub fn api_field_name(name: &str, camel_case: bool) -> String {
+    if camel_case {
+        name.to_camel_case()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `schema` with the field names the endpoint serves.
+pub fn api_schema<'a>(schema: &'a Schema, endpoint: &ApiEndpoint) -> Cow<'a, Schema> {
+    if !camel_case_fields(endpoint) {
+        return Cow::Borrowed(schema);
+    }
+    let mut schema = schema.clone();
+    for field in &mut schema.fields {
+        field.name = api_field_name(&field.name, true);
+    }
+    Cow::Owned(schema)
+}
+
+/// Renames the fields a query refers to by their served names to their names in `schema`. Names of `schema` are
+/// left as they are, so they keep working.
+pub fn rename_query_fields(query: &mut QueryExpression, schema: &Schema, endpoint: &ApiEndpoint) {
+    let Some(names) = schema_names(schema, endpoint) else {
+        return;
+    };
+    if let Some(filter) = &mut query.filter {
+        rename(filter, &names);
+    }
+    for option in &mut query.order_by.0 {
+        option.field_name = schema_name(&option.field_name, &names);
+    }
+}
+
+/// Renames the fields a filter refers to, like `rename_query_fields`.
+pub fn rename_filter_fields(
+    filter: &mut FilterExpression,
+    schema: &Schema,
+    endpoint: &ApiEndpoint,
+) {
+    if let Some(names) = schema_names(schema, endpoint) {
+        rename(filter, &names);
+    }
+}
+
+/// Names of `schema` by the served names that differ from them.
+fn schema_names(schema: &Schema, endpoint: &ApiEndpoint) -> Option<HashMap<String, String>> {
+    if !camel_case_fields(endpoint) {
+        return None;
+    }
+    Some(
+        schema
+            .fields
+            .iter()
+            .map(|field| (api_field_name(&field.name, true), field.name.clone()))
+            .filter(|(api_name, name)| api_name != name)
+            .collect(),
+    )
+}
+
+fn rename(filter: &mut FilterExpression, names: &HashMap<String, String>) {
+    match filter {
+        FilterExpression::Simple(field_name, _, _) => *field_name = schema_name(field_name, names),
+        FilterExpression::And(filters) | FilterExpression::Or(filters) => {
+            for filter in filters {
+                rename(filter, names);
+            }
+        }
+    }
+}
+
+/// Also renames the field of index expressions, e.g. `lower(firstName)`.
+fn schema_name(name: &str, names: &HashMap<String, String>) -> String {
+    if let Some(name) = names.get(name) {
+        return name.clone();
+    }
+    match IndexFunction::parse_call(name) {
+        Some((function, field)) => match names.get(field) {
+            Some(field) => format!("{}({field})", function.name()),
+            None => name.to_string(),
+        },
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::expression::{Operator, SortDirection, SortOption, SortOptions};
+    use dozer_types::models::api_endpoint::EndpointNaming;
+    use dozer_types::serde_json::Value;
+
+    use super::*;
+    use crate::test_utils;
+
+    fn endpoint(camel_case_fields: bool) -> ApiEndpoint {
+        ApiEndpoint {
+            naming: Some(EndpointNaming {
+                camel_case_fields: Some(camel_case_fields),
+                ..Default::default()
+            }),
+            ..test_utils::get_endpoint()
+        }
+    }
+
+    #[test]
+    fn test_api_schema() {
+        let (schema, _) = test_utils::get_schema();
+        let api_schema = api_schema(&schema, &endpoint(true));
+        assert_eq!(api_schema.fields[3].name, "releaseYear");
+        assert!(matches!(
+            super::api_schema(&schema, &endpoint(false)),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_rename_query_fields() {
+        let mut query = QueryExpression {
+            filter: Some(FilterExpression::And(vec![
+                FilterExpression::Simple(
+                    "releaseYear".to_string(),
+                    Operator::GT,
+                    Value::from(2000),
+                ),
+                FilterExpression::Simple(
+                    "lower(releaseYear)".to_string(),
+                    Operator::EQ,
+                    Value::from(2000),
+                ),
+                FilterExpression::Simple(
+                    "release_year".to_string(),
+                    Operator::LT,
+                    Value::from(2010),
+                ),
+            ])),
+            order_by: SortOptions(vec![SortOption::new(
+                "releaseYear".to_string(),
+                SortDirection::Descending,
+            )]),
+            ..QueryExpression::with_no_limit()
+        };
+        let (schema, _) = test_utils::get_schema();
+        rename_query_fields(&mut query, &schema, &endpoint(true));
+
+        let Some(FilterExpression::And(filters)) = &query.filter else {
+            panic!("Filter must stay a conjunction");
+        };
+        let names = filters
+            .iter()
+            .map(|filter| match filter {
+                FilterExpression::Simple(name, _, _) => name.as_str(),
+                _ => panic!("Filters must stay simple"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["release_year", "lower(release_year)", "release_year"]
+        );
+        assert_eq!(query.order_by.0[0].field_name, "release_year");
+    }
+}
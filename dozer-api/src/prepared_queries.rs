@@ -0,0 +1,274 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use dozer_cache::cache::expression::QueryExpression;
+use dozer_cache::cache::plan::PreparedPlan;
+use dozer_types::parking_lot::RwLock;
+use dozer_types::serde_json::{self, Map, Value};
+
+use crate::errors::ApiError;
+
+/// The key of a placeholder in a query template, e.g. `{"$filter": {"name": {"$param": "name"}}}`.
+const PARAM_KEY: &str = "$param";
+/// Placeholders are only allowed in the filter, so all queries of a template have the same shape.
+const FILTER_KEY: &str = "$filter";
+/// Maximum number of templates registered per endpoint.
+pub const MAX_PREPARED_QUERIES: usize = 1000;
+
+/// Query templates registered by clients, by name.
+#[derive(Debug, Default)]
+pub struct PreparedQueries {
+    queries: RwLock<HashMap<String, Arc<PreparedQuery>>>,
+}
+
+impl PreparedQueries {
+    /// Registers `template` as `name`, replacing the template registered as `name` if there is one.
+    pub fn register(&self, name: String, template: Value) -> Result<Arc<PreparedQuery>, ApiError> {
+        let query = Arc::new(PreparedQuery::new(template)?);
+        let mut queries = self.queries.write();
+        if queries.len() >= MAX_PREPARED_QUERIES && !queries.contains_key(&name) {
+            return Err(ApiError::TooManyPreparedQueries(MAX_PREPARED_QUERIES));
+        }
+        queries.insert(name, query.clone());
+        Ok(query)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Arc<PreparedQuery>, ApiError> {
+        self.queries
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ApiError::PreparedQueryNotFound(name.to_string()))
+    }
+}
+
+/// A query template, with the plan of its first query reused by the following ones.
+#[derive(Debug)]
+pub struct PreparedQuery {
+    template: Value,
+    params: BTreeSet<String>,
+    plan: PreparedPlan,
+}
+
+impl PreparedQuery {
+    pub fn new(template: Value) -> Result<Self, ApiError> {
+        let Value::Object(fields) = &template else {
+            return Err(ApiError::InvalidQueryTemplate(
+                "Query template must be an object".to_string(),
+            ));
+        };
+        let mut params = BTreeSet::new();
+        for (key, value) in fields {
+            if key == FILTER_KEY {
+                collect_params(value, &mut params)?;
+            } else if contains_param(value) {
+                return Err(ApiError::InvalidQueryTemplate(format!(
+                    "Placeholders are only allowed in {FILTER_KEY}, found one in {key}"
+                )));
+            }
+        }
+
+        // Any filter value parses, so binding nulls checks the template itself.
+        let nulls: Map<String, Value> = params
+            .iter()
+            .map(|param| (param.clone(), Value::Null))
+            .collect();
+        serde_json::from_value::<QueryExpression>(substitute(&template, &nulls))
+            .map_err(|e| ApiError::InvalidQueryTemplate(e.to_string()))?;
+        Ok(Self {
+            template,
+            params,
+            plan: PreparedPlan::default(),
+        })
+    }
+
+    /// Names of the placeholders of the template.
+    pub fn params(&self) -> &BTreeSet<String> {
+        &self.params
+    }
+
+    pub fn plan(&self) -> &PreparedPlan {
+        &self.plan
+    }
+
+    /// The query of the template with the placeholders replaced by `values`, which must have a value for every
+    /// placeholder and nothing else.
+    pub fn bind(&self, values: Map<String, Value>) -> Result<QueryExpression, ApiError> {
+        if let Some(param) = self
+            .params
+            .iter()
+            .find(|param| !values.contains_key(*param))
+        {
+            return Err(ApiError::InvalidQueryParams(format!(
+                "Missing value of {param}"
+            )));
+        }
+        if let Some(param) = values.keys().find(|param| !self.params.contains(*param)) {
+            return Err(ApiError::InvalidQueryParams(format!(
+                "Unknown parameter {param}"
+            )));
+        }
+        let query = substitute(&self.template, &values);
+        serde_json::from_value(query).map_err(|e| ApiError::InvalidQueryParams(e.to_string()))
+    }
+}
+
+/// The name of `value` if it's a placeholder.
+fn as_param(value: &Value) -> Option<Result<&str, ApiError>> {
+    let Value::Object(fields) = value else {
+        return None;
+    };
+    let param = fields.get(PARAM_KEY)?;
+    Some(match (param, fields.len()) {
+        (Value::String(name), 1) => Ok(name),
+        _ => Err(ApiError::InvalidQueryTemplate(format!(
+            "Invalid placeholder {value}, expected {{\"{PARAM_KEY}\": <name>}}"
+        ))),
+    })
+}
+
+fn collect_params(value: &Value, params: &mut BTreeSet<String>) -> Result<(), ApiError> {
+    if let Some(name) = as_param(value) {
+        params.insert(name?.to_string());
+        return Ok(());
+    }
+    match value {
+        Value::Object(fields) => fields
+            .values()
+            .try_for_each(|value| collect_params(value, params)),
+        Value::Array(values) => values
+            .iter()
+            .try_for_each(|value| collect_params(value, params)),
+        _ => Ok(()),
+    }
+}
+
+fn contains_param(value: &Value) -> bool {
+    if as_param(value).is_some() {
+        return true;
+    }
+    match value {
+        Value::Object(fields) => fields.values().any(contains_param),
+        Value::Array(values) => values.iter().any(contains_param),
+        _ => false,
+    }
+}
+
+fn substitute(value: &Value, values: &Map<String, Value>) -> Value {
+    if let Some(Ok(name)) = as_param(value) {
+        return values[name].clone();
+    }
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), substitute(value, values)))
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(
+            array
+                .iter()
+                .map(|value| substitute(value, values))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::expression::{FilterExpression, Operator, Skip};
+    use dozer_types::serde_json::{json, Value};
+
+    use super::{PreparedQueries, PreparedQuery};
+    use crate::errors::ApiError;
+
+    fn values(value: Value) -> dozer_types::serde_json::Map<String, Value> {
+        match value {
+            Value::Object(values) => values,
+            _ => panic!("Expected an object"),
+        }
+    }
+
+    #[test]
+    fn test_bind() {
+        let query = PreparedQuery::new(json!({
+            "$filter": { "film_id": { "$gt": { "$param": "min_id" } }, "title": { "$param": "title" } },
+            "$limit": 10,
+        }))
+        .unwrap();
+        assert_eq!(
+            query.params().iter().collect::<Vec<_>>(),
+            vec!["min_id", "title"]
+        );
+
+        let exp = query
+            .bind(values(json!({ "min_id": 100, "title": "ACE" })))
+            .unwrap();
+        assert_eq!(
+            exp.filter,
+            Some(FilterExpression::And(vec![
+                FilterExpression::Simple("film_id".to_string(), Operator::GT, json!(100)),
+                FilterExpression::Simple("title".to_string(), Operator::EQ, json!("ACE")),
+            ]))
+        );
+        assert_eq!(exp.limit, Some(10));
+        assert_eq!(exp.skip, Skip::Skip(0));
+
+        assert!(matches!(
+            query.bind(values(json!({ "min_id": 100 }))),
+            Err(ApiError::InvalidQueryParams(_))
+        ));
+        assert!(matches!(
+            query.bind(values(
+                json!({ "min_id": 100, "title": "ACE", "year": 2000 })
+            )),
+            Err(ApiError::InvalidQueryParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        for template in [
+            json!([]),
+            json!({ "$order_by": { "$param": "field" } }),
+            json!({ "$limit": { "$param": "limit" } }),
+            json!({ "$filter": { "film_id": { "$param": 1 } } }),
+            json!({ "$filter": { "film_id": { "$param": "id", "$gt": 1 } } }),
+            json!({ "$filter": { "film_id": { "$unknown": { "$param": "id" } } } }),
+        ] {
+            assert!(
+                matches!(
+                    PreparedQuery::new(template.clone()),
+                    Err(ApiError::InvalidQueryTemplate(_))
+                ),
+                "{template}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_register() {
+        let queries = PreparedQueries::default();
+        assert!(matches!(
+            queries.get("by_title"),
+            Err(ApiError::PreparedQueryNotFound(_))
+        ));
+        queries
+            .register(
+                "by_title".to_string(),
+                json!({ "$filter": { "title": { "$param": "title" } } }),
+            )
+            .unwrap();
+        let query = queries.get("by_title").unwrap();
+        let exp = query.bind(values(json!({ "title": "ACE" }))).unwrap();
+        assert_eq!(
+            exp.filter,
+            Some(FilterExpression::Simple(
+                "title".to_string(),
+                Operator::EQ,
+                json!("ACE")
+            ))
+        );
+    }
+}
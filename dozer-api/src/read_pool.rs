@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+
+/// Runs the reads of an endpoint on at most `num_threads` blocking threads, so slow reads of one endpoint don't hold
+/// the workers of the API servers, nor starve the reads of other endpoints.
+#[derive(Debug)]
+pub struct ReadPool {
+    permits: Arc<Semaphore>,
+}
+
+impl ReadPool {
+    pub fn new(num_threads: u32) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(num_threads.max(1) as usize)),
+        }
+    }
+
+    /// Waits for a free thread and runs `read` on it.
+    pub async fn run<T: Send + 'static>(&self, read: impl FnOnce() -> T + Send + 'static) -> T {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("We never close the semaphore");
+        tokio::task::spawn_blocking(read)
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::ReadPool;
+
+    #[tokio::test]
+    async fn test_read_pool_limits_threads() {
+        let pool = Arc::new(ReadPool::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let reads = (0..8).map(|i| {
+            let pool = pool.clone();
+            let running = running.clone();
+            let max_running = max_running.clone();
+            tokio::spawn(async move {
+                pool.run(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i
+                })
+                .await
+            })
+        });
+        let results = futures_util::future::join_all(reads).await;
+
+        assert_eq!(
+            results.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            (0..8).collect::<Vec<_>>()
+        );
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+}
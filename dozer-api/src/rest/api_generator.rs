@@ -1,29 +1,46 @@
 use std::sync::Arc;
 
+use actix_web::http::header::{HeaderName, HeaderValue};
 use actix_web::web::ReqData;
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression, Skip};
-use dozer_cache::cache::CacheRecord;
+use dozer_cache::cache::{CacheRecord, CacheStats};
 use dozer_cache::{CacheReader, Phase};
 use dozer_types::errors::types::CannotConvertF64ToJson;
 use dozer_types::indexmap::IndexMap;
 use dozer_types::models::api_endpoint::ApiEndpoint;
-use dozer_types::types::{Field, Schema};
+use dozer_types::node::Provenance;
+use dozer_types::types::{Field, Operation, Schema, Timezone};
 use openapiv3::OpenAPI;
 
-use crate::api_helper::{get_record, get_records, get_records_count};
+use crate::api_helper::{
+    effective_order_by, explain_query, get_changes, get_prepared_records,
+    get_prepared_records_count, get_record, get_records, get_records_count, query_deadline,
+    register_prepared_query, ORDER_BY_HEADER, QUERY_TIMEOUT_HEADER,
+};
+use crate::change_log::Change;
+use crate::deprecation::{deprecated_field_usage, DEPRECATED_FIELDS_HEADER};
 use crate::generator::oapi::generator::OpenApiGenerator;
+use crate::hot_keys::HOT_KEYS_IN_STATS;
+use crate::naming::api_schema;
+use crate::rest::json_stream::records_response;
 use crate::CacheEndpoint;
-use crate::{auth::Access, errors::ApiError};
+use crate::{
+    auth::{Access, Subject, Tenant},
+    errors::ApiError,
+};
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
-use dozer_types::json_types::field_to_json_value;
-use dozer_types::serde_json::{json, Value};
+use dozer_types::grpc_types::internal::ProvenanceRequest;
+use dozer_types::json_types::{defined_field_to_json_value, field_to_json_value};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json::{self, json, Map, Value};
 
 fn generate_oapi3(reader: &CacheReader, endpoint: ApiEndpoint) -> Result<OpenAPI, ApiError> {
     let (schema, secondary_indexes) = reader.get_schema();
+    let schema = api_schema(schema, &endpoint).into_owned();
 
     let oapi_generator = OpenApiGenerator::new(
-        schema,
+        &schema,
         secondary_indexes,
         endpoint,
         vec![format!("http://localhost:{}", "8080")],
@@ -43,46 +60,119 @@ pub async fn generate_oapi(
     .map(|result| HttpResponse::Ok().json(result))
 }
 
+/// Returns the protobuf descriptor set of the endpoint's typed gRPC service, for generating typed clients.
+pub async fn get_descriptor(cache_endpoint: ReqData<Arc<CacheEndpoint>>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/x-protobuf")
+        .body(cache_endpoint.descriptor().to_vec())
+}
+
 // Generated Get function to return a single record in JSON format
 pub async fn get(
     access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
     path: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
-    let cache_reader = &cache_endpoint.cache_reader();
+    let cache_reader = &cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
     let schema = &cache_reader.get_schema().0;
 
-    let key = path.as_str();
-    let key = if schema.primary_index.is_empty() {
-        return Err(ApiError::NoPrimaryKey);
+    let key = parse_primary_key(schema, path.as_str())?;
+
+    let record = get_record(
+        cache_reader,
+        &key,
+        cache_endpoint.hot_keys(),
+        &cache_endpoint.endpoint.name,
+        access.map(|a| a.into_inner()),
+    )?;
+
+    record_to_map(
+        record,
+        &api_schema(schema, &cache_endpoint.endpoint),
+        cache_endpoint.timezone(),
+    )
+    .map(|map| HttpResponse::Ok().json(map))
+    .map_err(Into::into)
+}
+
+fn parse_primary_key(schema: &Schema, key: &str) -> Result<Field, ApiError> {
+    if schema.primary_index.is_empty() {
+        Err(ApiError::NoPrimaryKey)
     } else if schema.primary_index.len() == 1 {
         let field = &schema.fields[schema.primary_index[0]];
-        Field::from_str(key, field.typ, field.nullable).map_err(ApiError::InvalidPrimaryKey)?
+        Field::from_str(key, field.typ, field.nullable).map_err(ApiError::InvalidPrimaryKey)
     } else {
-        return Err(ApiError::MultiIndexFetch(key.to_string()));
+        Err(ApiError::MultiIndexFetch(key.to_string()))
+    }
+}
+
+/// Returns the source row of the last operation that changed a single record.
+pub async fn get_provenance(
+    access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(client) = cache_endpoint.provenance_client() else {
+        return Err(ApiError::ProvenanceNotRecorded);
     };
+    let cache_reader = &cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let key = parse_primary_key(&cache_reader.get_schema().0, path.as_str())?;
 
-    // This implementation must be consistent with `dozer_cache::cache::index::get_primary_key`
-    let key = key.encode();
-    let record = get_record(
-        &cache_endpoint.cache_reader(),
+    // Only the provenance of records the caller can read is served.
+    get_record(
+        cache_reader,
         &key,
+        cache_endpoint.hot_keys(),
         &cache_endpoint.endpoint.name,
         access.map(|a| a.into_inner()),
     )?;
 
-    record_to_map(record, schema)
-        .map(|map| HttpResponse::Ok().json(map))
-        .map_err(Into::into)
+    let request = ProvenanceRequest {
+        endpoint: cache_endpoint.endpoint.name.clone(),
+        key_string: serde_json::to_string(&[key]).map_err(ApiError::InvalidProvenance)?,
+    };
+    let response = client
+        .clone()
+        .get_provenance(request)
+        .await
+        .map_err(ApiError::GetProvenanceFailed)?
+        .into_inner();
+    let provenance: Provenance = serde_json::from_str(
+        &response
+            .provenance_string
+            .ok_or(ApiError::ProvenanceNotFound)?,
+    )
+    .map_err(ApiError::InvalidProvenance)?;
+
+    let primary_key = provenance
+        .primary_key
+        .into_iter()
+        .map(|field| field_to_json_value(field, cache_endpoint.timezone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(HttpResponse::Ok().json(json!({
+        "connection": provenance.connection,
+        "table": provenance.table,
+        "primary_key": primary_key,
+        "op_id": {
+            "txid": provenance.op_id.txid,
+            "seq_in_tx": provenance.op_id.seq_in_tx,
+        },
+    })))
 }
 
 // Generated list function for multiple records with a default query expression
 pub async fn list(
+    req: HttpRequest,
     access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
 ) -> Result<HttpResponse, ApiError> {
     let mut exp = QueryExpression::new(None, vec![], Some(50), Skip::Skip(0));
-    get_records_map(access, cache_endpoint, &mut exp).map(|map| HttpResponse::Ok().json(map))
+    exp.deadline = query_deadline(&cache_endpoint.endpoint, requested_timeout(&req)?)?;
+    get_records_response(access, tenant, subject, cache_endpoint, exp).await
 }
 
 // Generated get function for health check
@@ -93,29 +183,53 @@ pub async fn health_route() -> Result<HttpResponse, ApiError> {
 }
 
 pub async fn count(
+    req: HttpRequest,
     access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
     query_info: Option<web::Json<QueryExpression>>,
+    params: web::Query<QueryParams>,
 ) -> Result<HttpResponse, ApiError> {
     let mut query_expression = match query_info {
         Some(query_info) => query_info.0,
         None => QueryExpression::with_no_limit(),
     };
 
-    get_records_count(
-        &cache_endpoint.cache_reader(),
-        &mut query_expression,
-        &cache_endpoint.endpoint.name,
-        access.map(|a| a.into_inner()),
-    )
-    .map(|count| HttpResponse::Ok().json(count))
+    if params.explain {
+        return explain(access, tenant, cache_endpoint, &mut query_expression);
+    }
+
+    query_expression.deadline = query_deadline(&cache_endpoint.endpoint, requested_timeout(&req)?)?;
+    let usage = deprecated_field_usage(&query_expression, &cache_endpoint.endpoint);
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let access = access.map(|a| a.into_inner());
+    let subject = subject.map(|s| s.into_inner());
+    let cache_endpoint = cache_endpoint.into_inner();
+    cache_endpoint
+        .clone()
+        .read(move || {
+            get_records_count(
+                &cache_reader,
+                &mut query_expression,
+                &cache_endpoint.endpoint,
+                access,
+                subject.as_ref(),
+            )
+        })
+        .await
+        .map(|count| with_deprecated_field_usage(HttpResponse::Ok().json(count), usage))
 }
 
 // Generated query function for multiple records
 pub async fn query(
+    req: HttpRequest,
     access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
     query_info: Option<web::Json<QueryExpression>>,
+    params: web::Query<QueryParams>,
 ) -> Result<HttpResponse, ApiError> {
     let mut query_expression = match query_info {
         Some(query_info) => query_info.0,
@@ -125,43 +239,94 @@ pub async fn query(
         query_expression.limit = Some(default_limit_for_query());
     }
 
-    get_records_map(access, cache_endpoint, &mut query_expression)
-        .map(|maps| HttpResponse::Ok().json(maps))
+    if params.explain {
+        return explain(access, tenant, cache_endpoint, &mut query_expression);
+    }
+
+    query_expression.deadline = query_deadline(&cache_endpoint.endpoint, requested_timeout(&req)?)?;
+    let usage = deprecated_field_usage(&query_expression, &cache_endpoint.endpoint);
+    get_records_response(access, tenant, subject, cache_endpoint, query_expression)
+        .await
+        .map(|response| with_deprecated_field_usage(response, usage))
+}
+
+/// The timeout the request asks for in the query timeout header.
+fn requested_timeout(req: &HttpRequest) -> Result<Option<&str>, ApiError> {
+    req.headers()
+        .get(QUERY_TIMEOUT_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ApiError::InvalidQueryTimeout(format!("{value:?}")))
+        })
+        .transpose()
+}
+
+/// Query string parameters of `count` and `query`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct QueryParams {
+    /// Return the query plan instead of running the query.
+    #[serde(default)]
+    explain: bool,
 }
 
-/// Get multiple records
-fn get_records_map(
+fn explain(
     access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
     exp: &mut QueryExpression,
-) -> Result<Vec<IndexMap<String, Value>>, ApiError> {
-    let mut maps = vec![];
-    let cache_reader = &cache_endpoint.cache_reader();
-    let records = get_records(
-        cache_reader,
+) -> Result<HttpResponse, ApiError> {
+    explain_query(
+        &cache_endpoint.tenant_cache_reader(tenant.as_deref())?,
         exp,
-        &cache_endpoint.endpoint.name,
+        &cache_endpoint.endpoint,
         access.map(|a| a.into_inner()),
-    )?;
-    let schema = &cache_reader.get_schema().0;
-    for record in records.into_iter() {
-        let map = record_to_map(record, schema)?;
-        maps.push(map);
-    }
-    Ok(maps)
+    )
+    .map(|plan| HttpResponse::Ok().json(plan))
+}
+
+/// Get multiple records, streamed if they're many
+async fn get_records_response(
+    access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    mut exp: QueryExpression,
+) -> Result<HttpResponse, ApiError> {
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let schema = api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint).into_owned();
+    let timezone = *cache_endpoint.timezone();
+    let access = access.map(|a| a.into_inner());
+    let subject = subject.map(|s| s.into_inner());
+    let cache_endpoint = cache_endpoint.into_inner();
+    let (records, order_by) = cache_endpoint
+        .clone()
+        .read({
+            let cache_reader = cache_reader.clone();
+            move || {
+                let records = get_records(
+                    &cache_reader,
+                    &mut exp,
+                    &cache_endpoint.endpoint,
+                    access,
+                    subject.as_ref(),
+                )?;
+                let order_by = effective_order_by(&cache_reader, &exp, &cache_endpoint.endpoint);
+                Ok::<_, ApiError>((records, order_by))
+            }
+        })
+        .await?;
+    records_response(records, schema, timezone).map(|response| with_order_by(response, order_by))
 }
 
 /// Used in REST APIs for converting to JSON
-fn record_to_map(
+pub fn record_to_map(
     record: CacheRecord,
     schema: &Schema,
+    timezone: &Timezone,
 ) -> Result<IndexMap<String, Value>, CannotConvertF64ToJson> {
-    let mut map = IndexMap::new();
-
-    for (field_def, field) in schema.fields.iter().zip(record.record.values) {
-        let val = field_to_json_value(field)?;
-        map.insert(field_def.name.clone(), val);
-    }
+    let mut map = values_to_map(record.record.values, schema, timezone)?;
 
     map.insert("__dozer_record_id".to_string(), Value::from(record.id));
     map.insert(
@@ -172,6 +337,199 @@ fn record_to_map(
     Ok(map)
 }
 
+fn values_to_map(
+    values: Vec<Field>,
+    schema: &Schema,
+    timezone: &Timezone,
+) -> Result<IndexMap<String, Value>, CannotConvertF64ToJson> {
+    let mut map = IndexMap::new();
+
+    for (field_def, field) in schema.fields.iter().zip(values) {
+        let val = defined_field_to_json_value(field, field_def, timezone)?;
+        map.insert(field_def.name.clone(), val);
+    }
+
+    Ok(map)
+}
+
+/// Registers the query template in the body as `name`, returning the names of its placeholders.
+pub async fn prepare(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    path: web::Path<String>,
+    template: web::Json<Value>,
+) -> Result<HttpResponse, ApiError> {
+    let prepared = register_prepared_query(
+        cache_endpoint.prepared_queries(),
+        path.into_inner(),
+        template.into_inner(),
+        &cache_endpoint.endpoint.name,
+        access.map(|a| a.into_inner()),
+    )?;
+    Ok(HttpResponse::Ok().json(json!({ "params": prepared.params() })))
+}
+
+/// Counts the records of the query template `name`, with the placeholder values in the body.
+pub async fn prepared_count(
+    req: HttpRequest,
+    access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    path: web::Path<String>,
+    values: Option<web::Json<Map<String, Value>>>,
+) -> Result<HttpResponse, ApiError> {
+    let prepared = cache_endpoint.prepared_queries().get(&path)?;
+    let mut query_expression = prepared.bind(values.map(|v| v.0).unwrap_or_default())?;
+    query_expression.deadline = query_deadline(&cache_endpoint.endpoint, requested_timeout(&req)?)?;
+    let usage = deprecated_field_usage(&query_expression, &cache_endpoint.endpoint);
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let access = access.map(|a| a.into_inner());
+    let subject = subject.map(|s| s.into_inner());
+    let cache_endpoint = cache_endpoint.into_inner();
+    cache_endpoint
+        .clone()
+        .read(move || {
+            get_prepared_records_count(
+                &cache_reader,
+                &prepared,
+                &mut query_expression,
+                &cache_endpoint.endpoint,
+                access,
+                subject.as_ref(),
+            )
+        })
+        .await
+        .map(|count| with_deprecated_field_usage(HttpResponse::Ok().json(count), usage))
+}
+
+/// Queries the records of the query template `name`, with the placeholder values in the body.
+pub async fn prepared_query(
+    req: HttpRequest,
+    access: Option<ReqData<Access>>,
+    tenant: Option<ReqData<Tenant>>,
+    subject: Option<ReqData<Subject>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    path: web::Path<String>,
+    values: Option<web::Json<Map<String, Value>>>,
+) -> Result<HttpResponse, ApiError> {
+    let prepared = cache_endpoint.prepared_queries().get(&path)?;
+    let mut query_expression = prepared.bind(values.map(|v| v.0).unwrap_or_default())?;
+    query_expression.deadline = query_deadline(&cache_endpoint.endpoint, requested_timeout(&req)?)?;
+    if query_expression.limit.is_none() {
+        query_expression.limit = Some(default_limit_for_query());
+    }
+    let usage = deprecated_field_usage(&query_expression, &cache_endpoint.endpoint);
+
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let schema = api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint).into_owned();
+    let timezone = *cache_endpoint.timezone();
+    let access = access.map(|a| a.into_inner());
+    let subject = subject.map(|s| s.into_inner());
+    let cache_endpoint = cache_endpoint.into_inner();
+    let (records, order_by) = cache_endpoint
+        .clone()
+        .read({
+            let cache_reader = cache_reader.clone();
+            move || {
+                let records = get_prepared_records(
+                    &cache_reader,
+                    &prepared,
+                    &mut query_expression,
+                    &cache_endpoint.endpoint,
+                    access,
+                    subject.as_ref(),
+                )?;
+                let order_by =
+                    effective_order_by(&cache_reader, &query_expression, &cache_endpoint.endpoint);
+                Ok::<_, ApiError>((records, order_by))
+            }
+        })
+        .await?;
+    records_response(records, schema, timezone)
+        .map(|response| with_deprecated_field_usage(with_order_by(response, order_by), usage))
+}
+
+/// Adds the number of references to deprecated fields to the response, if there are any.
+fn with_deprecated_field_usage(mut response: HttpResponse, usage: usize) -> HttpResponse {
+    if usage > 0 {
+        response.headers_mut().insert(
+            HeaderName::from_static(DEPRECATED_FIELDS_HEADER),
+            HeaderValue::from(usage),
+        );
+    }
+    response
+}
+
+/// Adds the order of the records to the response, if they're sorted.
+fn with_order_by(mut response: HttpResponse, order_by: Option<String>) -> HttpResponse {
+    if let Some(value) = order_by.and_then(|order_by| HeaderValue::from_str(&order_by).ok()) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(ORDER_BY_HEADER), value);
+    }
+    response
+}
+
+/// Query string parameters of `changes`.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ChangesParams {
+    /// Return the changes after this position. Without it, only the current position is returned.
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+const DEFAULT_CHANGES_LIMIT: usize = 1000;
+
+/// Returns the retained changes after `since`, and the position to pass as `since` in the next call.
+pub async fn changes(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    params: web::Query<ChangesParams>,
+) -> Result<HttpResponse, ApiError> {
+    let (changes, next) = get_changes(
+        cache_endpoint.change_log(),
+        params.since,
+        params.limit.unwrap_or(DEFAULT_CHANGES_LIMIT),
+        &cache_endpoint.endpoint.name,
+        access.map(|a| a.into_inner()),
+    )?;
+
+    let cache_reader = cache_endpoint.cache_reader();
+    let schema = api_schema(&cache_reader.get_schema().0, &cache_endpoint.endpoint);
+    let changes = changes
+        .into_iter()
+        .map(|change| change_to_json(change, &schema, cache_endpoint.timezone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(HttpResponse::Ok().json(json!({ "changes": changes, "next": next })))
+}
+
+fn change_to_json(
+    change: Change,
+    schema: &Schema,
+    timezone: &Timezone,
+) -> Result<Value, CannotConvertF64ToJson> {
+    Ok(match change.op {
+        Operation::Insert { new } => json!({
+            "seq": change.seq,
+            "type": "insert",
+            "new": values_to_map(new.values, schema, timezone)?,
+        }),
+        Operation::Delete { old } => json!({
+            "seq": change.seq,
+            "type": "delete",
+            "old": values_to_map(old.values, schema, timezone)?,
+        }),
+        Operation::Update { old, new } => json!({
+            "seq": change.seq,
+            "type": "update",
+            "old": values_to_map(old.values, schema, timezone)?,
+            "new": values_to_map(new.values, schema, timezone)?,
+        }),
+    })
+}
+
 pub async fn get_phase(
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
 ) -> Result<web::Json<Phase>, ApiError> {
@@ -179,3 +537,35 @@ pub async fn get_phase(
     let phase = cache_reader.get_phase().map_err(ApiError::GetPhaseFailed)?;
     Ok(web::Json(phase))
 }
+
+pub async fn get_stats(
+    tenant: Option<ReqData<Tenant>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+) -> Result<HttpResponse, ApiError> {
+    let cache_reader = cache_endpoint.tenant_cache_reader(tenant.as_deref())?;
+    let stats = cache_reader.get_stats().map_err(ApiError::GetStatsFailed)?;
+    let Some(hot_keys) = cache_endpoint.hot_keys() else {
+        return Ok(HttpResponse::Ok().json(stats));
+    };
+
+    let hot_keys = hot_keys
+        .top(HOT_KEYS_IN_STATS)
+        .into_iter()
+        .map(|hot_key| {
+            Ok(json!({
+                "key": field_to_json_value(hot_key.key, cache_endpoint.timezone())?,
+                "reads": hot_key.reads,
+                "last_read": hot_key.last_read,
+            }))
+        })
+        .collect::<Result<Vec<_>, CannotConvertF64ToJson>>()?;
+    Ok(HttpResponse::Ok().json(EndpointStats { stats, hot_keys }))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+struct EndpointStats {
+    #[serde(flatten)]
+    stats: CacheStats,
+    hot_keys: Vec<Value>,
+}
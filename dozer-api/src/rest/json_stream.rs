@@ -0,0 +1,149 @@
+use actix_web::http::header::ContentType;
+use actix_web::web::Bytes;
+use actix_web::HttpResponse;
+use dozer_cache::cache::CacheRecord;
+use dozer_types::serde_json;
+use dozer_types::types::{Schema, Timezone};
+
+use super::api_generator::record_to_map;
+use crate::errors::ApiError;
+
+/// Size of the chunks a JSON array of records is streamed in.
+pub const CHUNK_SIZE_IN_BYTES: usize = 64 * 1024;
+
+/// Serializes records as a JSON array, one chunk at a time, so only a chunk of the response is in memory at once.
+#[derive(Debug)]
+pub struct JsonArrayChunks {
+    records: std::vec::IntoIter<CacheRecord>,
+    schema: Schema,
+    timezone: Timezone,
+    chunk_size: usize,
+    /// Whether the first chunk, which opens the array, is written.
+    started: bool,
+    /// Whether a record is written, so the following ones are separated by commas.
+    has_records: bool,
+    done: bool,
+}
+
+impl JsonArrayChunks {
+    pub fn new(
+        records: Vec<CacheRecord>,
+        schema: Schema,
+        timezone: Timezone,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            records: records.into_iter(),
+            schema,
+            timezone,
+            chunk_size,
+            started: false,
+            has_records: false,
+            done: false,
+        }
+    }
+
+    fn write_chunk(&mut self) -> Result<Vec<u8>, ApiError> {
+        let mut chunk = vec![];
+        if !self.started {
+            chunk.push(b'[');
+            self.started = true;
+        }
+        while chunk.len() < self.chunk_size {
+            let Some(record) = self.records.next() else {
+                chunk.push(b']');
+                self.done = true;
+                break;
+            };
+            if self.has_records {
+                chunk.push(b',');
+            }
+            self.has_records = true;
+            let map = record_to_map(record, &self.schema, &self.timezone)?;
+            serde_json::to_writer(&mut chunk, &map).expect("Json values are always serializable");
+        }
+        Ok(chunk)
+    }
+}
+
+impl Iterator for JsonArrayChunks {
+    type Item = Result<Bytes, ApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let chunk = self.write_chunk();
+        if chunk.is_err() {
+            self.done = true;
+        }
+        Some(chunk.map(Bytes::from))
+    }
+}
+
+/// Responds with the records as a JSON array. Responses larger than a chunk are streamed with chunked transfer
+/// encoding instead of being buffered.
+pub fn records_response(
+    records: Vec<CacheRecord>,
+    schema: Schema,
+    timezone: Timezone,
+) -> Result<HttpResponse, ApiError> {
+    let mut chunks = JsonArrayChunks::new(records, schema, timezone, CHUNK_SIZE_IN_BYTES);
+    let first = chunks.next().expect("There's always a first chunk")?;
+    let mut response = HttpResponse::Ok();
+    response.insert_header(ContentType::json());
+    if chunks.done {
+        Ok(response.body(first))
+    } else {
+        Ok(response.streaming(futures_util::stream::iter(
+            std::iter::once(Ok(first)).chain(chunks),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::{self, Value};
+    use dozer_types::types::Timezone;
+
+    use super::JsonArrayChunks;
+    use crate::test_utils;
+
+    fn collect(chunks: JsonArrayChunks) -> (usize, Value) {
+        let chunks = chunks.collect::<Result<Vec<_>, _>>().unwrap();
+        let bytes = chunks.concat();
+        (chunks.len(), serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[test]
+    fn test_json_array_chunks() {
+        let (schema, _) = test_utils::get_schema();
+        let records = test_utils::get_sample_records();
+
+        let (num_chunks, buffered) = collect(JsonArrayChunks::new(
+            records.clone(),
+            schema.clone(),
+            Timezone::Utc,
+            usize::MAX,
+        ));
+        assert_eq!(num_chunks, 1);
+        assert_eq!(buffered.as_array().unwrap().len(), records.len());
+
+        let (num_chunks, streamed) = collect(JsonArrayChunks::new(
+            records.clone(),
+            schema,
+            Timezone::Utc,
+            100,
+        ));
+        assert!(num_chunks > 1);
+        assert_eq!(streamed, buffered);
+    }
+
+    #[test]
+    fn test_empty_json_array_chunks() {
+        let (schema, _) = test_utils::get_schema();
+        let (num_chunks, value) = collect(JsonArrayChunks::new(vec![], schema, Timezone::Utc, 100));
+        assert_eq!(num_chunks, 1);
+        assert_eq!(value, serde_json::json!([]));
+    }
+}
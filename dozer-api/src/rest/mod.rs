@@ -4,7 +4,10 @@ use std::sync::Arc;
 use crate::errors::ApiInitError;
 use crate::rest::api_generator::health_route;
 use crate::{
-    auth::api::{auth_route, validate},
+    auth::{
+        api::{auth_route, validate},
+        JwtSecrets,
+    },
     CacheEndpoint,
 };
 use actix_cors::Cors;
@@ -17,15 +20,13 @@ use actix_web::{
     web, App, HttpMessage, HttpServer,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use dozer_types::serde::{self, Deserialize, Serialize};
 use dozer_types::{log::info, models::api_config::RestApiOptions};
-use dozer_types::{
-    models::api_security::ApiSecurity,
-    serde::{self, Deserialize, Serialize},
-};
 use futures_util::Future;
 use tracing_actix_web::TracingLogger;
 
 mod api_generator;
+mod json_stream;
 mod rest_metric_middleware;
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -43,7 +44,7 @@ pub struct ApiServer {
     shutdown_timeout: u64,
     port: u16,
     cors: CorsOptions,
-    security: Option<ApiSecurity>,
+    security: Option<Arc<JwtSecrets>>,
     host: String,
 }
 
@@ -60,7 +61,7 @@ impl Default for ApiServer {
 }
 
 impl ApiServer {
-    pub fn new(rest_config: RestApiOptions, security: Option<ApiSecurity>) -> Self {
+    pub fn new(rest_config: RestApiOptions, security: Option<Arc<JwtSecrets>>) -> Self {
         Self {
             shutdown_timeout: 0,
             port: rest_config.port as u16,
@@ -80,9 +81,9 @@ impl ApiServer {
     }
 
     fn create_app_entry(
-        security: Option<ApiSecurity>,
+        security: Option<Arc<JwtSecrets>>,
         cors: CorsOptions,
-        mut cache_endpoints: Vec<Arc<CacheEndpoint>>,
+        cache_endpoints: Vec<Arc<CacheEndpoint>>,
     ) -> App<
         impl ServiceFactory<
             ServiceRequest,
@@ -92,10 +93,22 @@ impl ApiServer {
             Error = actix_web::Error,
         >,
     > {
-        let endpoint_paths: Vec<String> = cache_endpoints
-            .iter()
-            .map(|cache_endpoint| cache_endpoint.endpoint.path.clone())
+        // Endpoints are served at their path and at each of their aliases.
+        let mut scopes: Vec<(String, Arc<CacheEndpoint>)> = cache_endpoints
+            .into_iter()
+            .flat_map(|cache_endpoint| {
+                let aliases = cache_endpoint
+                    .endpoint
+                    .naming
+                    .iter()
+                    .flat_map(|naming| naming.rest_aliases.clone());
+                std::iter::once(cache_endpoint.endpoint.path.clone())
+                    .chain(aliases)
+                    .map(move |path| (path, cache_endpoint.clone()))
+                    .collect::<Vec<_>>()
+            })
             .collect();
+        let endpoint_paths: Vec<String> = scopes.iter().map(|(path, _)| path.clone()).collect();
 
         let mut app = App::new()
             .app_data(web::Data::new(endpoint_paths))
@@ -106,9 +119,9 @@ impl ApiServer {
                 gethostname::gethostname().to_string_lossy().into_owned(),
             )));
 
-        let is_auth_configured = if let Some(api_security) = security {
+        let is_auth_configured = if let Some(secrets) = security {
             // Injecting API Security
-            app = app.app_data(api_security);
+            app = app.app_data(secrets);
             true
         } else {
             false
@@ -119,15 +132,13 @@ impl ApiServer {
         let cors_middleware = Self::get_cors(cors);
 
         //reverse sort cache endpoints by path length to ensure that the most specific path is matched first
-        cache_endpoints.sort_by(|a, b| b.endpoint.path.len().cmp(&a.endpoint.path.len()));
+        scopes.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
 
-        cache_endpoints
+        scopes
             .into_iter()
-            .fold(app, |app, cache_endpoint| {
-                let endpoint = &cache_endpoint.endpoint;
-                let scope = &endpoint.path;
+            .fold(app, |app, (scope, cache_endpoint)| {
                 app.service(
-                    web::scope(scope)
+                    web::scope(&scope)
                         .wrap(rest_metric_middleware::RestMetric)
                         // Inject cache_endpoint for generated functions
                         .wrap_fn(move |req, srv| {
@@ -137,7 +148,23 @@ impl ApiServer {
                         .route("/count", web::post().to(api_generator::count))
                         .route("/query", web::post().to(api_generator::query))
                         .route("/phase", web::post().to(api_generator::get_phase))
+                        .route("/stats", web::post().to(api_generator::get_stats))
                         .route("/oapi", web::post().to(api_generator::generate_oapi))
+                        .route("/changes", web::get().to(api_generator::changes))
+                        .route("/descriptor", web::get().to(api_generator::get_descriptor))
+                        .route("/prepared/{name}", web::put().to(api_generator::prepare))
+                        .route(
+                            "/prepared/{name}/count",
+                            web::post().to(api_generator::prepared_count),
+                        )
+                        .route(
+                            "/prepared/{name}/query",
+                            web::post().to(api_generator::prepared_query),
+                        )
+                        .route(
+                            "/{id}/provenance",
+                            web::get().to(api_generator::get_provenance),
+                        )
                         .route("/{id}", web::get().to(api_generator::get))
                         .route("/", web::get().to(api_generator::list))
                         .route("", web::get().to(api_generator::list)),
@@ -166,9 +193,7 @@ impl ApiServer {
             self.port,
             self.security
                 .as_ref()
-                .map_or("None".to_string(), |s| match s {
-                    ApiSecurity::Jwt(_) => "JWT".to_string(),
-                })
+                .map_or("None".to_string(), |_| "JWT".to_string())
         );
         let cors = self.cors;
         let security = self.security;
@@ -59,6 +59,10 @@ where
             let start_time = std::time::Instant::now();
             let res: Result<ServiceResponse<B>, Error> = fut.await;
             if let Some(endpoint) = cache_data {
+                let failed = res
+                    .as_ref()
+                    .map_or(true, |response| response.status().is_server_error());
+                endpoint.request_counts().record(failed);
                 let labels: [(&str, String); 2] = [
                     ("endpoint", endpoint.endpoint.table_name.to_owned()),
                     ("api_type", "rest".to_owned()),
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use super::super::{ApiServer, CorsOptions};
 use crate::{
-    auth::{Access, Authorizer},
+    auth::{Access, Authorizer, JwtSecrets},
     test_utils, CacheEndpoint,
 };
 use actix_web::{body::MessageBody, dev::ServiceResponse};
@@ -23,14 +23,14 @@ async fn call_auth_token_api() {
     let secret = "secret";
 
     // Shouldnt be able to create token without Master Token
-    let res = _call_auth_token_api(secret.to_string(), None, None).await;
+    let res = _call_auth_token_api(secret.to_string(), None, None, None).await;
     assert_eq!(res.status().as_u16(), 401, "Should be unauthorized.");
 
     let auth = Authorizer::new(secret, None, None);
     let token = auth.generate_token(Access::All, None).unwrap();
 
     let json = json!({"Custom":{"films":{"filter":null,"fields":[]}}});
-    let res = _call_auth_token_api(secret.to_string(), Some(token), Some(json)).await;
+    let res = _call_auth_token_api(secret.to_string(), Some(token), Some(json), None).await;
     assert_eq!(
         res.status().as_u16(),
         200,
@@ -41,6 +41,46 @@ async fn call_auth_token_api() {
     assert!(body.token.len() > 1, "Token must be present");
 }
 
+#[actix_web::test]
+async fn call_auth_token_api_with_tenant() {
+    let secret = "secret";
+    let auth = Authorizer::new(secret, None, None);
+    let json = json!({"Custom":{"films":{"filter":null,"fields":[]}}});
+
+    // A master token scoped to a tenant can't create tokens for another one.
+    let token = auth
+        .generate_tenant_token(Access::All, Some("a".to_string()), None)
+        .unwrap();
+    let res = _call_auth_token_api(
+        secret.to_string(),
+        Some(token.clone()),
+        Some(json.clone()),
+        Some("b"),
+    )
+    .await;
+    assert_eq!(res.status().as_u16(), 401, "Should be unauthorized.");
+
+    let res = _call_auth_token_api(
+        secret.to_string(),
+        Some(token),
+        Some(json.clone()),
+        Some("a"),
+    )
+    .await;
+    assert_eq!(res.status().as_u16(), 200);
+    let body: TokenResponse = actix_web::test::read_body_json(res).await;
+    let claims = auth.validate_token(&body.token).unwrap();
+    assert_eq!(claims.tenant, Some("a".to_string()));
+
+    // An unscoped one can.
+    let token = auth.generate_token(Access::All, None).unwrap();
+    let res = _call_auth_token_api(secret.to_string(), Some(token), Some(json), Some("b")).await;
+    assert_eq!(res.status().as_u16(), 200);
+    let body: TokenResponse = actix_web::test::read_body_json(res).await;
+    let claims = auth.validate_token(&body.token).unwrap();
+    assert_eq!(claims.tenant, Some("b".to_string()));
+}
+
 #[actix_web::test]
 async fn verify_token_test() {
     let secret = "secret";
@@ -67,7 +107,7 @@ async fn check_status(
     let endpoint = test_utils::get_endpoint();
     let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
     let api_server = ApiServer::create_app_entry(
-        security,
+        security.as_ref().map(|security| Arc::new(security.into())),
         CorsOptions::Permissive,
         vec![Arc::new(
             CacheEndpoint::open(&*cache_manager, Default::default(), endpoint.clone()).unwrap(),
@@ -90,12 +130,13 @@ async fn _call_auth_token_api(
     secret: String,
     token: Option<String>,
     body: Option<Value>,
+    tenant: Option<&str>,
 ) -> ServiceResponse<impl MessageBody> {
     let endpoint = test_utils::get_endpoint();
     let schema_name = endpoint.name.clone();
     let cache_manager = test_utils::initialize_cache(&schema_name, None);
     let api_server = ApiServer::create_app_entry(
-        Some(ApiSecurity::Jwt(secret)),
+        Some(Arc::new(JwtSecrets::new(secret))),
         CorsOptions::Permissive,
         vec![Arc::new(
             CacheEndpoint::open(&*cache_manager, Default::default(), endpoint).unwrap(),
@@ -103,7 +144,11 @@ async fn _call_auth_token_api(
     );
     let app = actix_web::test::init_service(api_server).await;
 
-    let req = actix_web::test::TestRequest::post().uri("/auth/token");
+    let uri = match tenant {
+        Some(tenant) => format!("/auth/token?tenant={tenant}"),
+        None => "/auth/token".to_string(),
+    };
+    let req = actix_web::test::TestRequest::post().uri(&uri);
 
     let req = match token {
         Some(token) => req.append_header(("Authorization", format!("Bearer {token}"))),
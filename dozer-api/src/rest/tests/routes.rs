@@ -5,7 +5,7 @@ use crate::{generator::oapi::generator::OpenApiGenerator, test_utils, CacheEndpo
 use actix_http::{body::MessageBody, Request};
 use actix_web::dev::{Service, ServiceResponse};
 use dozer_cache::Phase;
-use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::models::api_endpoint::{ApiEndpoint, EndpointNaming};
 use dozer_types::serde_json::{json, Value};
 
 #[test]
@@ -128,6 +128,32 @@ async fn count_and_query_route() {
     assert_eq!(records.len(), 11);
 }
 
+#[actix_web::test]
+async fn explain_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(&*cache_manager, Default::default(), endpoint.clone()).unwrap(),
+        )],
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    for method in ["count", "query"] {
+        let req = actix_web::test::TestRequest::post()
+            .uri(&format!("{}/{method}?explain=true", endpoint.path))
+            .set_json(json!({"$filter": {"film_id": 268}}))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+        assert!(res.status().is_success());
+
+        let body: Value = actix_web::test::read_body_json(res).await;
+        assert_eq!(body["IndexScans"][0]["index_id"], json!(0));
+    }
+}
+
 #[actix_web::test]
 async fn get_route() {
     let endpoint = test_utils::get_endpoint();
@@ -181,6 +207,31 @@ async fn get_phase_test() {
     assert_eq!(phase, Phase::Streaming);
 }
 
+#[actix_web::test]
+async fn get_descriptor_test() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let descriptor = b"descriptor".to_vec();
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(&*cache_manager, descriptor.clone(), endpoint.clone()).unwrap(),
+        )],
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/{}", endpoint.path, "descriptor"))
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    let body = actix_web::test::read_body(res).await;
+    assert_eq!(body, descriptor);
+}
+
 #[actix_web::test]
 async fn get_endpoint_paths_test() {
     let endpoint = test_utils::get_endpoint();
@@ -202,6 +253,40 @@ async fn get_endpoint_paths_test() {
     assert_eq!(body, vec![endpoint.path.clone()]);
 }
 
+#[actix_web::test]
+async fn alias_and_camel_case_route() {
+    let endpoint = ApiEndpoint {
+        naming: Some(EndpointNaming {
+            rest_aliases: vec!["/v1/films".to_string()],
+            camel_case_fields: Some(true),
+            ..Default::default()
+        }),
+        ..test_utils::get_endpoint()
+    };
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(&*cache_manager, Default::default(), endpoint.clone()).unwrap(),
+        )],
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get().uri("/").to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    let body: Vec<String> = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, vec![endpoint.path.clone(), "/v1/films".to_string()]);
+
+    for path in [endpoint.path.as_str(), "/v1/films"] {
+        let (count, records) =
+            count_and_query(path, &app, Some(json!({"$filter": {"filmId": 268}}))).await;
+        assert_eq!(count, 1);
+        assert_eq!(records[0]["filmId"], json!(268));
+        assert!(records[0].get("film_id").is_none());
+    }
+}
+
 #[actix_web::test]
 async fn path_collision_test() {
     let first_endpoint = ApiEndpoint {
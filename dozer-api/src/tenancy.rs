@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dozer_cache::{cache::RwCacheManager, CacheReader};
+use dozer_types::{
+    labels::Labels,
+    models::api_endpoint::TenancyOptions,
+    parking_lot::RwLock,
+    types::{Field, FieldType, Schema},
+};
+
+use crate::errors::{ApiError, ApiInitError};
+
+const TENANT_LABEL: &str = "tenant";
+const MAX_TENANT_LEN: usize = 64;
+
+/// Returns the labels of the cache storing the records of `tenant`.
+pub fn tenant_cache_labels(labels: &Labels, tenant: &str) -> Labels {
+    let mut labels = labels.clone();
+    labels.push(TENANT_LABEL, tenant.to_string());
+    labels
+}
+
+/// Returns the index of the tenant column in `schema`.
+pub fn tenant_column_index(
+    schema: &Schema,
+    tenancy: &TenancyOptions,
+) -> Result<usize, ApiInitError> {
+    let (index, field) = schema
+        .fields
+        .iter()
+        .enumerate()
+        .find(|(_, field)| field.name == tenancy.column)
+        .ok_or_else(|| ApiInitError::TenantColumnNotFound(tenancy.column.clone()))?;
+    match field.typ {
+        FieldType::String | FieldType::Text | FieldType::Int | FieldType::UInt => Ok(index),
+        _ => Err(ApiInitError::InvalidTenantColumnType(
+            tenancy.column.clone(),
+        )),
+    }
+}
+
+/// Returns the tenant named by a tenant column value, or `None` if the value can't name a tenant.
+pub fn tenant_of(value: &Field) -> Option<String> {
+    let tenant = match value {
+        Field::String(tenant) | Field::Text(tenant) => tenant.clone(),
+        Field::Int(tenant) => tenant.to_string(),
+        Field::UInt(tenant) => tenant.to_string(),
+        _ => return None,
+    };
+    is_valid_tenant(&tenant).then_some(tenant)
+}
+
+/// Tenant names become part of cache directory names, so they're limited to ASCII alphanumerics, `-` and `_`.
+fn is_valid_tenant(tenant: &str) -> bool {
+    !tenant.is_empty()
+        && tenant.len() <= MAX_TENANT_LEN
+        && tenant
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// Cache readers of the tenants of an endpoint. A tenant's cache is opened when the tenant is first queried.
+#[derive(Debug)]
+pub struct TenantCacheReaders {
+    cache_manager: Arc<dyn RwCacheManager>,
+    labels: Labels,
+    readers: RwLock<HashMap<String, Arc<CacheReader>>>,
+}
+
+impl TenantCacheReaders {
+    pub fn new(cache_manager: Arc<dyn RwCacheManager>, labels: Labels) -> Self {
+        Self {
+            cache_manager,
+            labels,
+            readers: Default::default(),
+        }
+    }
+
+    pub fn get(&self, tenant: &str) -> Result<Arc<CacheReader>, ApiError> {
+        if let Some(reader) = self.readers.read().get(tenant) {
+            return Ok(reader.clone());
+        }
+
+        // The cache builder creates a tenant's cache when the tenant's first record arrives.
+        if !is_valid_tenant(tenant) {
+            return Err(ApiError::TenantNotFound(tenant.to_string()));
+        }
+        let cache = self
+            .cache_manager
+            .open_ro_cache(tenant_cache_labels(&self.labels, tenant))
+            .map_err(ApiError::OpenTenantCacheFailed)?
+            .ok_or_else(|| ApiError::TenantNotFound(tenant.to_string()))?;
+        let reader = Arc::new(CacheReader::new(cache));
+        Ok(self
+            .readers
+            .write()
+            .entry(tenant.to_string())
+            .or_insert(reader)
+            .clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_of() {
+        assert_eq!(
+            tenant_of(&Field::String("acme-1_a".to_string())),
+            Some("acme-1_a".to_string())
+        );
+        assert_eq!(tenant_of(&Field::Int(-1)), Some("-1".to_string()));
+        assert_eq!(tenant_of(&Field::UInt(42)), Some("42".to_string()));
+        assert_eq!(tenant_of(&Field::String("../acme".to_string())), None);
+        assert_eq!(tenant_of(&Field::String(String::new())), None);
+        assert_eq!(tenant_of(&Field::Null), None);
+        assert_eq!(tenant_of(&Field::Boolean(true)), None);
+    }
+}
@@ -15,30 +15,35 @@ pub fn get_schema() -> SchemaWithIndex {
             typ: FieldType::UInt,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "description".to_string(),
             typ: FieldType::String,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "rental_rate".to_string(),
             typ: FieldType::Float,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "release_year".to_string(),
             typ: FieldType::UInt,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "updated_at".to_string(),
             typ: FieldType::Timestamp,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
     ];
     let secondary_indexes = fields
@@ -67,6 +72,18 @@ pub fn get_endpoint() -> ApiEndpoint {
         conflict_resolution: None,
         log_reader_options: None,
         version: None,
+        slow_query_threshold_in_millis: None,
+        tenancy: None,
+        change_retention: None,
+        rollups: vec![],
+        hot_keys: None,
+        read_cache_size_in_bytes: None,
+        concurrency: None,
+        query_timeout_in_millis: None,
+        partitions: None,
+        naming: None,
+        deprecated_fields: vec![],
+        provenance: None,
     }
 }
 
@@ -0,0 +1,20 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use dozer_bench::scenarios::{register, BenchOptions};
+
+fn suites(c: &mut Criterion) {
+    let mut options = BenchOptions::default();
+    if let Some(records) = std::env::var("DOZER_BENCH_RECORDS")
+        .ok()
+        .and_then(|records| records.parse().ok())
+    {
+        options.records = records;
+    }
+    register(c, &options).unwrap();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = suites
+}
+criterion_main!(benches);
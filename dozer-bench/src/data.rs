@@ -0,0 +1,83 @@
+//! Generated tables of the scenarios. Records only depend on their index, so every run reads the same data.
+
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+const STATUSES: [&str; 3] = ["pending", "shipped", "delivered"];
+const COUNTRIES: [&str; 5] = ["SG", "US", "DE", "IN", "BR"];
+
+/// Number of customers the orders of the scenarios are spread over.
+pub fn customer_count(order_count: usize) -> usize {
+    (order_count / 10).max(1)
+}
+
+fn schema(fields: &[(&str, FieldType)]) -> Schema {
+    let mut schema = Schema::new();
+    for (index, (name, typ)) in fields.iter().enumerate() {
+        schema.field(
+            FieldDefinition::new(name.to_string(), *typ, false, SourceDefinition::Dynamic),
+            index == 0,
+        );
+    }
+    schema
+}
+
+pub fn customers_schema() -> Schema {
+    schema(&[
+        ("id", FieldType::Int),
+        ("name", FieldType::String),
+        ("country", FieldType::String),
+    ])
+}
+
+pub fn orders_schema() -> Schema {
+    schema(&[
+        ("id", FieldType::Int),
+        ("customer_id", FieldType::Int),
+        ("amount", FieldType::Float),
+        ("status", FieldType::String),
+    ])
+}
+
+pub fn customers(count: usize) -> Vec<Record> {
+    (0..count)
+        .map(|index| {
+            Record::new(vec![
+                Field::Int(index as i64),
+                Field::String(format!("customer_{index}")),
+                Field::String(COUNTRIES[index % COUNTRIES.len()].to_string()),
+            ])
+        })
+        .collect()
+}
+
+/// Orders of `customer_count` customers, spread over them by a multiplicative hash so that consecutive orders are of
+/// different customers.
+pub fn orders(count: usize, customer_count: usize) -> Vec<Record> {
+    (0..count)
+        .map(|index| {
+            Record::new(vec![
+                Field::Int(index as i64),
+                Field::Int(((index * 7919) % customer_count) as i64),
+                Field::Float(OrderedFloat((index % 1000) as f64 / 10.0)),
+                Field::String(STATUSES[index % STATUSES.len()].to_string()),
+            ])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orders() {
+        let orders = orders(100, customer_count(100));
+        assert_eq!(orders.len(), 100);
+        assert_eq!(orders, super::orders(100, 10));
+        assert_eq!(orders[3].values[1], Field::Int(7));
+        assert!(orders
+            .iter()
+            .all(|order| matches!(order.values[1], Field::Int(id) if (0..10).contains(&id))));
+    }
+}
@@ -0,0 +1,73 @@
+//! Reproducible performance scenarios of dozer: snapshot throughput, join and aggregation rates of SQL pipelines, and
+//! cache query latency.
+//!
+//! Scenarios read generated data that only depends on the number of records, and are measured with criterion.
+//! [`run`] collects the results in a [`Report`] that can be written as json and compared with a report of another
+//! version.
+
+use std::path::{Path, PathBuf};
+
+use criterion::Criterion;
+use dozer_cache::errors::CacheError;
+use dozer_core::errors::ExecutionError;
+use dozer_sql::pipeline::errors::PipelineError;
+use dozer_types::serde_json;
+use dozer_types::thiserror::{self, Error};
+
+pub mod data;
+pub mod pipeline;
+pub mod report;
+pub mod scenarios;
+
+pub use report::{Comparison, Report, ScenarioResult};
+pub use scenarios::{BenchOptions, SCENARIOS};
+
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("Unknown scenario {0}, expected one of {SCENARIOS:?}")]
+    UnknownScenario(String),
+    #[error("Pipeline has no output table")]
+    NoOutputTable,
+    #[error("Table {0} isn't generated")]
+    TableNotFound(String),
+    #[error("Pipeline error: {0}")]
+    Pipeline(#[from] PipelineError),
+    #[error("Execution error: {0}")]
+    Execution(#[from] ExecutionError),
+    #[error("Cache error: {0}")]
+    Cache(#[from] CacheError),
+    #[error("Failed to create cache directory: {0}")]
+    CacheDirectory(#[source] std::io::Error),
+    #[error("Failed to read benchmark results {0:?}: {1}")]
+    ReadResults(PathBuf, #[source] std::io::Error),
+    #[error("Invalid benchmark results {0:?}: no {1}")]
+    InvalidResults(PathBuf, String),
+    #[error("Failed to read report {0:?}: {1}")]
+    ReadReport(PathBuf, #[source] std::io::Error),
+    #[error("Failed to parse report {0:?}: {1}")]
+    ParseReport(PathBuf, #[source] serde_json::Error),
+    #[error("Failed to write report {0:?}: {1}")]
+    WriteReport(PathBuf, #[source] std::io::Error),
+}
+
+/// Runs the scenarios of `options` and collects their results.
+pub fn run(options: &BenchOptions) -> Result<Report, BenchError> {
+    let scenarios = options.scenarios()?;
+    let output_directory = Path::new(&options.output_directory);
+
+    let mut criterion = Criterion::default()
+        .output_directory(output_directory)
+        .sample_size(options.sample_size)
+        .without_plots();
+    if let Some(measurement_time) = options.measurement_time {
+        criterion = criterion.measurement_time(measurement_time);
+    }
+    scenarios::register(&mut criterion, options)?;
+    criterion.final_summary();
+
+    let ids = scenarios
+        .into_iter()
+        .map(scenarios::result_id)
+        .collect::<Vec<_>>();
+    Report::collect(output_directory, options.records, &ids)
+}
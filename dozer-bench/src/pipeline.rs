@@ -0,0 +1,282 @@
+//! Pipelines running SQL over generated tables, from an in-memory source to a sink counting the operations it gets.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use dozer_core::app::{App, AppPipeline};
+use dozer_core::appsource::{AppSourceManager, AppSourceMappings};
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{
+    OutputPortDef, OutputPortType, PortHandle, Sink, SinkFactory, Source, SourceFactory,
+};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::{Dag, DEFAULT_PORT_HANDLE};
+use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::{Operation, Record, Schema, SourceDefinition};
+
+use crate::BenchError;
+
+const CONNECTION_NAME: &str = "bench";
+const OUTPUT_TABLE: &str = "results";
+
+/// A generated table read by a pipeline.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name: String,
+    pub schema: Schema,
+    pub records: Arc<Vec<Record>>,
+}
+
+impl Table {
+    pub fn new(name: &str, schema: Schema, records: Vec<Record>) -> Self {
+        Self {
+            name: name.to_string(),
+            schema,
+            records: Arc::new(records),
+        }
+    }
+}
+
+/// A pipeline built and ready to run, so that building it isn't measured.
+#[derive(Debug)]
+pub struct BenchPipeline {
+    dag: Dag<SchemaSQLContext>,
+    count: Arc<AtomicU64>,
+}
+
+impl BenchPipeline {
+    /// Builds the pipeline of `sql`, which selects into `results` from `tables`.
+    pub fn new(sql: &str, tables: &[Table]) -> Result<Self, BenchError> {
+        let mut pipeline = AppPipeline::new();
+        let query_context = statement_to_pipeline(
+            sql,
+            &mut pipeline,
+            Some(OUTPUT_TABLE.to_string()),
+            SqlOptions::default(),
+        )?;
+        let output_table = query_context
+            .output_tables_map
+            .get(OUTPUT_TABLE)
+            .ok_or(BenchError::NoOutputTable)?;
+
+        let mut mappings = HashMap::new();
+        let mut source_tables = vec![];
+        for name in &query_context.used_sources {
+            if mappings.contains_key(name) {
+                continue;
+            }
+            let table = tables
+                .iter()
+                .find(|table| table.name == *name)
+                .ok_or_else(|| BenchError::TableNotFound(name.clone()))?;
+            mappings.insert(name.clone(), source_tables.len() as PortHandle);
+            source_tables.push(table.clone());
+        }
+
+        let mut asm = AppSourceManager::new();
+        asm.add(
+            Box::new(BenchSourceFactory {
+                tables: source_tables,
+            }),
+            AppSourceMappings::new(CONNECTION_NAME.to_string(), mappings),
+        )?;
+
+        let count = Arc::new(AtomicU64::new(0));
+        pipeline.add_sink(
+            Box::new(CountingSinkFactory {
+                count: count.clone(),
+            }),
+            "sink",
+            None,
+        );
+        pipeline.connect_nodes(
+            &output_table.node,
+            output_table.port,
+            "sink",
+            DEFAULT_PORT_HANDLE,
+        );
+
+        let mut app = App::new(asm);
+        app.add_pipeline(pipeline);
+        Ok(Self {
+            dag: app.into_dag()?,
+            count,
+        })
+    }
+
+    /// Runs the pipeline until the source has sent all records and they're processed. Returns the number of
+    /// operations the sink got.
+    pub fn run(self) -> Result<u64, BenchError> {
+        let executor = DagExecutor::new(self.dag, ExecutorOptions::default())?;
+        executor.start(Arc::new(AtomicBool::new(true)))?.join()?;
+        Ok(self.count.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug)]
+struct BenchSourceFactory {
+    /// The tables, in the order of their ports.
+    tables: Vec<Table>,
+}
+
+impl SourceFactory<SchemaSQLContext> for BenchSourceFactory {
+    fn get_output_schema(
+        &self,
+        port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let table = &self.tables[*port as usize];
+        let mut schema = table.schema.clone();
+        for field in &mut schema.fields {
+            field.source = SourceDefinition::Table {
+                connection: CONNECTION_NAME.to_string(),
+                name: table.name.clone(),
+            };
+        }
+        Ok((schema, SchemaSQLContext::default()))
+    }
+
+    fn get_output_port_name(&self, port: &PortHandle) -> String {
+        self.tables[*port as usize].name.clone()
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        (0..self.tables.len())
+            .map(|port| {
+                OutputPortDef::new(
+                    port as PortHandle,
+                    OutputPortType::StatefulWithPrimaryKeyLookup,
+                )
+            })
+            .collect()
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, BoxedError> {
+        Ok(Box::new(BenchSource {
+            tables: self.tables.clone(),
+        }))
+    }
+}
+
+/// Sends the records of its tables as inserts, one table after the other, then ends.
+#[derive(Debug)]
+struct BenchSource {
+    tables: Vec<Table>,
+}
+
+impl Source for BenchSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, BoxedError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), BoxedError> {
+        let mut txid = 0;
+        for (port, table) in self.tables.iter().enumerate() {
+            for record in table.records.iter() {
+                let op = Operation::Insert {
+                    new: record.clone(),
+                };
+                fw.send(
+                    IngestionMessage::new_op(txid, 0, port, op),
+                    port as PortHandle,
+                )?;
+                txid += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CountingSinkFactory {
+    count: Arc<AtomicU64>,
+}
+
+impl SinkFactory<SchemaSQLContext> for CountingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(CountingSink {
+            count: self.count.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct CountingSink {
+    count: Arc<AtomicU64>,
+}
+
+impl Sink for CountingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        _record_store: &ProcessorRecordStore,
+        _op: ProcessorOperation,
+    ) -> Result<(), BoxedError> {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self, _connection_name: String) -> Result<(), BoxedError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data;
+
+    #[test]
+    fn test_bench_pipeline() {
+        let tables = [
+            Table::new("customers", data::customers_schema(), data::customers(10)),
+            Table::new("orders", data::orders_schema(), data::orders(100, 10)),
+        ];
+
+        let pipeline =
+            BenchPipeline::new("SELECT id, amount INTO results FROM orders", &tables).unwrap();
+        assert_eq!(pipeline.run().unwrap(), 100);
+
+        let pipeline = BenchPipeline::new(
+            "SELECT o.id, c.name INTO results FROM orders o JOIN customers c ON o.customer_id = c.id",
+            &tables,
+        )
+        .unwrap();
+        assert_eq!(pipeline.run().unwrap(), 100);
+
+        assert!(matches!(
+            BenchPipeline::new("SELECT id INTO results FROM users", &tables),
+            Err(BenchError::TableNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+//! Json reports of the results of the scenarios, collected from criterion's results so that runs of different versions
+//! can be compared.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json::{self, Value};
+
+use crate::BenchError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Report {
+    /// Version of dozer that ran the scenarios.
+    pub version: String,
+    /// Number of orders of the generated tables.
+    pub records: usize,
+    pub results: Vec<ScenarioResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ScenarioResult {
+    /// `<group>/<function>`, e.g. `pipeline/join`.
+    pub id: String,
+    /// Mean time of an iteration, with its confidence interval.
+    pub mean_ns: f64,
+    pub mean_lower_ns: f64,
+    pub mean_upper_ns: f64,
+    pub median_ns: f64,
+    pub std_dev_ns: f64,
+    /// Records processed per second, for scenarios that process all records in an iteration.
+    pub throughput_per_sec: Option<f64>,
+}
+
+/// Change of the mean time of a scenario from a baseline report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub id: String,
+    pub baseline_ns: f64,
+    pub mean_ns: f64,
+    /// Relative change, e.g. `-0.1` for 10% faster.
+    pub change: f64,
+}
+
+impl Report {
+    /// Collects the latest results of the scenarios `ids` from the criterion output directory.
+    pub fn collect(
+        output_directory: &Path,
+        records: usize,
+        ids: &[&str],
+    ) -> Result<Self, BenchError> {
+        let results = ids
+            .iter()
+            .map(|id| ScenarioResult::read(&output_directory.join(id), id))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            records,
+            results,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, BenchError> {
+        let json = fs::read(path).map_err(|e| BenchError::ReadReport(path.to_path_buf(), e))?;
+        serde_json::from_slice(&json).map_err(|e| BenchError::ParseReport(path.to_path_buf(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), BenchError> {
+        let json = serde_json::to_vec_pretty(self).expect("report must be serializable");
+        fs::write(path, json).map_err(|e| BenchError::WriteReport(path.to_path_buf(), e))
+    }
+
+    /// Compares the scenarios that are in both reports. Comparing reports of different numbers of records isn't
+    /// meaningful, so it's up to the caller to check them.
+    pub fn compare(&self, baseline: &Report) -> Vec<Comparison> {
+        self.results
+            .iter()
+            .filter_map(|result| {
+                let baseline = baseline
+                    .results
+                    .iter()
+                    .find(|baseline| baseline.id == result.id)?;
+                Some(Comparison {
+                    id: result.id.clone(),
+                    baseline_ns: baseline.mean_ns,
+                    mean_ns: result.mean_ns,
+                    change: (result.mean_ns - baseline.mean_ns) / baseline.mean_ns,
+                })
+            })
+            .collect()
+    }
+}
+
+impl ScenarioResult {
+    /// Reads `<dir>/new/estimates.json` and `<dir>/new/benchmark.json`, which criterion writes for every benchmark.
+    fn read(dir: &Path, id: &str) -> Result<Self, BenchError> {
+        let estimates = read_json(&dir.join("new").join("estimates.json"))?;
+        let benchmark = read_json(&dir.join("new").join("benchmark.json"))?;
+
+        let estimate = |statistic: &str, value: &[&str]| {
+            let mut estimate = &estimates[statistic];
+            for key in value {
+                estimate = &estimate[key];
+            }
+            estimate
+                .as_f64()
+                .ok_or_else(|| BenchError::InvalidResults(dir.to_path_buf(), statistic.to_string()))
+        };
+        let mean_ns = estimate("mean", &["point_estimate"])?;
+        let throughput_per_sec = benchmark["throughput"]["Elements"]
+            .as_u64()
+            .map(|elements| elements as f64 * 1e9 / mean_ns);
+        Ok(Self {
+            id: id.to_string(),
+            mean_ns,
+            mean_lower_ns: estimate("mean", &["confidence_interval", "lower_bound"])?,
+            mean_upper_ns: estimate("mean", &["confidence_interval", "upper_bound"])?,
+            median_ns: estimate("median", &["point_estimate"])?,
+            std_dev_ns: estimate("std_dev", &["point_estimate"])?,
+            throughput_per_sec,
+        })
+    }
+}
+
+fn read_json(path: &Path) -> Result<Value, BenchError> {
+    let json = fs::read(path).map_err(|e| BenchError::ReadResults(path.to_path_buf(), e))?;
+    serde_json::from_slice(&json)
+        .map_err(|_| BenchError::InvalidResults(path.to_path_buf(), "json".to_string()))
+}
+
+/// Default path of the report of the current version.
+pub fn default_report_path() -> PathBuf {
+    PathBuf::from(format!("dozer-bench-{}.json", env!("CARGO_PKG_VERSION")))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn estimate(point_estimate: f64) -> Value {
+        json!({
+            "confidence_interval": {
+                "confidence_level": 0.95,
+                "lower_bound": point_estimate * 0.9,
+                "upper_bound": point_estimate * 1.1,
+            },
+            "point_estimate": point_estimate,
+            "standard_error": 1.0,
+        })
+    }
+
+    fn write_results(dir: &Path, id: &str, mean_ns: f64, elements: Option<u64>) {
+        let dir = dir.join(id).join("new");
+        fs::create_dir_all(&dir).unwrap();
+        let estimates = json!({
+            "mean": estimate(mean_ns),
+            "median": estimate(mean_ns),
+            "median_abs_dev": estimate(1.0),
+            "slope": null,
+            "std_dev": estimate(10.0),
+        });
+        fs::write(dir.join("estimates.json"), estimates.to_string()).unwrap();
+        let throughput = elements.map(|elements| json!({ "Elements": elements }));
+        let benchmark = json!({ "full_id": id, "throughput": throughput });
+        fs::write(dir.join("benchmark.json"), benchmark.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_report() {
+        let dir = TempDir::new("report").unwrap();
+        write_results(dir.path(), "pipeline/join", 2e9, Some(1000));
+        write_results(dir.path(), "cache/query", 1e4, None);
+
+        let report = Report::collect(dir.path(), 1000, &["pipeline/join", "cache/query"]).unwrap();
+        assert_eq!(report.results[0].mean_ns, 2e9);
+        assert_eq!(report.results[0].mean_upper_ns, 2.2e9);
+        assert_eq!(report.results[0].throughput_per_sec, Some(500.0));
+        assert_eq!(report.results[1].throughput_per_sec, None);
+
+        let path = dir.path().join("report.json");
+        report.write(&path).unwrap();
+        let baseline = Report::read(&path).unwrap();
+        assert_eq!(baseline, report);
+
+        let mut faster = report.clone();
+        faster.results[0].mean_ns = 1.5e9;
+        faster.results.pop();
+        let comparisons = faster.compare(&baseline);
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].change, -0.25);
+
+        assert!(matches!(
+            Report::collect(dir.path(), 1000, &["pipeline/snapshot"]),
+            Err(BenchError::ReadResults(..))
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+//! The scenarios, registered as criterion benchmarks. Pipeline scenarios are in the `pipeline` group and cache
+//! scenarios in the `cache` group.
+
+use std::time::Duration;
+
+use criterion::{BatchSize, Criterion, Throughput};
+use dozer_cache::cache::expression::{
+    FilterExpression, Operator, QueryExpression, Skip, SortDirection, SortOption,
+};
+use dozer_cache::cache::{CacheManagerOptions, LmdbRwCacheManager, RwCache, RwCacheManager};
+use dozer_types::serde_json::Value;
+use dozer_types::types::IndexDefinition;
+use tempdir::TempDir;
+
+use crate::data;
+use crate::pipeline::{BenchPipeline, Table};
+use crate::BenchError;
+
+pub const SNAPSHOT: &str = "snapshot";
+pub const JOIN: &str = "join";
+pub const AGGREGATION: &str = "aggregation";
+pub const CACHE_QUERY: &str = "cache_query";
+
+pub const SCENARIOS: [&str; 4] = [SNAPSHOT, JOIN, AGGREGATION, CACHE_QUERY];
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Scenarios to run. All scenarios if empty.
+    pub scenarios: Vec<String>,
+    /// Number of orders of the generated tables. There's a customer every 10 orders.
+    pub records: usize,
+    /// Number of samples criterion takes of every scenario, at least 10.
+    pub sample_size: usize,
+    /// Time criterion spends measuring every scenario, or its default.
+    pub measurement_time: Option<Duration>,
+    /// Directory criterion writes its results to.
+    pub output_directory: String,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            scenarios: vec![],
+            records: 100_000,
+            sample_size: 10,
+            measurement_time: None,
+            output_directory: ".dozer/bench".to_string(),
+        }
+    }
+}
+
+impl BenchOptions {
+    /// The scenarios to run, in the order of `SCENARIOS`.
+    pub fn scenarios(&self) -> Result<Vec<&'static str>, BenchError> {
+        if let Some(unknown) = self
+            .scenarios
+            .iter()
+            .find(|scenario| !SCENARIOS.contains(&scenario.as_str()))
+        {
+            return Err(BenchError::UnknownScenario(unknown.clone()));
+        }
+        Ok(SCENARIOS
+            .into_iter()
+            .filter(|scenario| {
+                self.scenarios.is_empty() || self.scenarios.iter().any(|s| s == scenario)
+            })
+            .collect())
+    }
+}
+
+/// Id of a scenario's results, `<group>/<function>`.
+pub fn result_id(scenario: &str) -> &'static str {
+    match scenario {
+        SNAPSHOT => "pipeline/snapshot",
+        JOIN => "pipeline/join",
+        AGGREGATION => "pipeline/aggregation",
+        _ => "cache/query",
+    }
+}
+
+fn pipeline_sql(scenario: &str) -> &'static str {
+    match scenario {
+        SNAPSHOT => "SELECT id, customer_id, amount, status INTO results FROM orders",
+        JOIN => {
+            "SELECT o.id, o.amount, c.name, c.country INTO results \
+             FROM orders o JOIN customers c ON o.customer_id = c.id"
+        }
+        _ => {
+            "SELECT customer_id, COUNT(id), SUM(amount) INTO results \
+             FROM orders GROUP BY customer_id"
+        }
+    }
+}
+
+/// Registers the scenarios of `options` with `c`.
+pub fn register(c: &mut Criterion, options: &BenchOptions) -> Result<(), BenchError> {
+    let scenarios = options.scenarios()?;
+    let customer_count = data::customer_count(options.records);
+    let tables = [
+        Table::new(
+            "customers",
+            data::customers_schema(),
+            data::customers(customer_count),
+        ),
+        Table::new(
+            "orders",
+            data::orders_schema(),
+            data::orders(options.records, customer_count),
+        ),
+    ];
+
+    let pipeline_scenarios = scenarios
+        .iter()
+        .filter(|scenario| **scenario != CACHE_QUERY)
+        .collect::<Vec<_>>();
+    if !pipeline_scenarios.is_empty() {
+        // Pipelines that can't be built fail before measuring anything.
+        for scenario in &pipeline_scenarios {
+            BenchPipeline::new(pipeline_sql(scenario), &tables)?;
+        }
+
+        let mut group = c.benchmark_group("pipeline");
+        group.throughput(Throughput::Elements(options.records as u64));
+        for scenario in pipeline_scenarios {
+            let sql = pipeline_sql(scenario);
+            group.bench_function(*scenario, |b| {
+                b.iter_batched(
+                    || BenchPipeline::new(sql, &tables).expect("pipeline was built before"),
+                    |pipeline| pipeline.run().expect("pipeline failed"),
+                    BatchSize::PerIteration,
+                )
+            });
+        }
+        group.finish();
+    }
+
+    if scenarios.contains(&CACHE_QUERY) {
+        let dir = TempDir::new("dozer-bench").map_err(BenchError::CacheDirectory)?;
+        let cache_manager = LmdbRwCacheManager::new(CacheManagerOptions {
+            path: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        })?;
+        let mut cache = cache_manager.create_cache(
+            Default::default(),
+            data::orders_schema(),
+            vec![
+                IndexDefinition::SortedInverted(vec![0]),
+                IndexDefinition::SortedInverted(vec![1, 2]),
+            ],
+            &Default::default(),
+            Default::default(),
+        )?;
+        for record in &tables[1].records[..] {
+            cache.insert(record)?;
+        }
+        cache.commit()?;
+        cache_manager.wait_until_indexing_catchup();
+
+        let mut group = c.benchmark_group("cache");
+        let mut customer_id = 0;
+        group.bench_function("query", |b| {
+            b.iter(|| {
+                customer_id = (customer_id + 1) % customer_count;
+                query(cache.as_ref(), customer_id).expect("query failed")
+            })
+        });
+        group.finish();
+    }
+    Ok(())
+}
+
+/// The 50 largest orders of a customer.
+fn query(cache: &dyn RwCache, customer_id: usize) -> Result<usize, BenchError> {
+    let query = QueryExpression::new(
+        Some(FilterExpression::Simple(
+            "customer_id".to_string(),
+            Operator::EQ,
+            Value::from(customer_id),
+        )),
+        vec![SortOption::new(
+            "amount".to_string(),
+            SortDirection::Descending,
+        )],
+        Some(50),
+        Skip::Skip(0),
+    );
+    Ok(cache.query(&query)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenarios() {
+        let mut options = BenchOptions::default();
+        assert_eq!(options.scenarios().unwrap(), SCENARIOS);
+
+        options.scenarios = vec![CACHE_QUERY.to_string(), JOIN.to_string()];
+        assert_eq!(options.scenarios().unwrap(), [JOIN, CACHE_QUERY]);
+
+        options.scenarios = vec!["sort".to_string()];
+        assert!(matches!(
+            options.scenarios(),
+            Err(BenchError::UnknownScenario(_))
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::serde_json::Value;
 mod query_helper;
@@ -24,6 +26,9 @@ pub struct QueryExpression {
     pub order_by: SortOptions,
     pub limit: Option<usize>,
     pub skip: Skip,
+    /// Scans of the query stop with `CacheError::QueryTimeout` after this instant. Not part of the query language,
+    /// it's set from the timeout of the request.
+    pub deadline: Option<Instant>,
 }
 
 pub fn default_limit_for_query() -> usize {
@@ -37,6 +42,7 @@ impl QueryExpression {
             order_by: Default::default(),
             limit: Some(default_limit_for_query()),
             skip: Default::default(),
+            deadline: None,
         }
     }
 
@@ -46,6 +52,7 @@ impl QueryExpression {
             order_by: Default::default(),
             limit: None,
             skip: Default::default(),
+            deadline: None,
         }
     }
 }
@@ -68,6 +75,7 @@ impl QueryExpression {
             order_by: SortOptions(order_by),
             limit,
             skip,
+            deadline: None,
         }
     }
 }
@@ -77,6 +85,7 @@ pub enum FilterExpression {
     // a = 1, a containts "s", a > 4
     Simple(String, Operator, Value),
     And(Vec<FilterExpression>),
+    Or(Vec<FilterExpression>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +29,8 @@ impl<'de> Deserialize<'de> for FilterExpression {
                 while let Some(key) = map.next_key::<String>()? {
                     if key == "$and" {
                         expressions.push(FilterExpression::And(map.next_value()?));
+                    } else if key == "$or" {
+                        expressions.push(FilterExpression::Or(map.next_value()?));
                     } else {
                         let operator_and_value = map.next_value::<OperatorAndValue>()?;
                         expressions.push(FilterExpression::Simple(
@@ -65,6 +67,11 @@ impl Serialize for FilterExpression {
                 state.serialize_entry("$and", &expressions)?;
                 state.end()
             }
+            FilterExpression::Or(expressions) => {
+                let mut state = serializer.serialize_map(Some(1))?;
+                state.serialize_entry("$or", &expressions)?;
+                state.end()
+            }
         }
     }
 }
@@ -164,6 +171,7 @@ impl<'de> Deserialize<'de> for QueryExpression {
                     order_by: order_by.unwrap_or_default(),
                     limit,
                     skip: skip.unwrap_or_default(),
+                    deadline: None,
                 })
             }
         }
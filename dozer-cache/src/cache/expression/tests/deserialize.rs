@@ -124,7 +124,19 @@ fn test_filter_query_deserialize_complex() {
         ]),
     );
 
+    test_deserialize_filter(
+        json!({"$or": [{"a": 1}, {"b": {"$gt": 1}, "c": 2}]}),
+        FilterExpression::Or(vec![
+            FilterExpression::Simple("a".to_string(), Operator::EQ, Value::from(1)),
+            FilterExpression::And(vec![
+                FilterExpression::Simple("b".to_string(), Operator::GT, Value::from(1)),
+                FilterExpression::Simple("c".to_string(), Operator::EQ, Value::from(2)),
+            ]),
+        ]),
+    );
+
     test_deserialize_filter_error(json!({"$and": {}}));
+    test_deserialize_filter_error(json!({"$or": {"a": 1}}));
     test_deserialize_filter_error(json!({"$and": [{"a":  {"lt": 1}}, {"b":  {"$gt": 1}}]}));
     test_deserialize_filter_error(json!({"and": [{"a":  {"$lt": 1}}]}));
 }
@@ -67,6 +67,24 @@ pub fn compare_composite_secondary_index(a: &[u8], b: &[u8]) -> Result<Ordering,
     })
 }
 
+/// The first `num_fields` fields of a composite secondary index key, as a composite key, or the whole key if it has
+/// fewer fields.
+pub fn composite_secondary_index_prefix(
+    key: &[u8],
+    num_fields: usize,
+) -> Result<&[u8], CompareError> {
+    let mut prefix = CompositeSecondaryIndexKey::new(key);
+    for _ in 0..num_fields {
+        match prefix.next() {
+            Some(field) => {
+                field?;
+            }
+            None => break,
+        }
+    }
+    Ok(&key[..prefix.offset])
+}
+
 pub fn get_full_text_secondary_index(token: &str) -> Vec<u8> {
     token.as_bytes().to_vec()
 }
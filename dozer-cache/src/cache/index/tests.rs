@@ -1,6 +1,8 @@
-use dozer_types::types::field_test_cases;
+use dozer_types::types::{field_test_cases, Field};
 
-use crate::cache::index::{get_composite_secondary_index, CompositeSecondaryIndexKey};
+use crate::cache::index::{
+    composite_secondary_index_prefix, get_composite_secondary_index, CompositeSecondaryIndexKey,
+};
 
 use super::get_full_text_secondary_index;
 
@@ -41,3 +43,17 @@ fn composite_key_encode_preserves_prefix() {
         }
     }
 }
+
+#[test]
+fn test_composite_secondary_index_prefix() {
+    let fields = [Field::Int(1), Field::String("a".to_string()), Field::Null];
+    let fields = fields.iter().collect::<Vec<_>>();
+    let key = get_composite_secondary_index(&fields);
+    for num_fields in 0..=fields.len() {
+        assert_eq!(
+            composite_secondary_index_prefix(&key, num_fields).unwrap(),
+            get_composite_secondary_index(&fields[..num_fields])
+        );
+    }
+    assert_eq!(composite_secondary_index_prefix(&key, 4).unwrap(), key);
+}
@@ -98,10 +98,12 @@ pub async fn restore(
 
     let mut rw_secondary_envs = vec![];
     let mut ro_secondary_envs = vec![];
+    let primary_index = &ro_main_env.schema().0.primary_index;
     for index in 0..ro_main_env.schema().1.len() {
         let name = secondary_environment_name(index);
         let rw_secondary_env =
-            secondary_environment::dump_restore::restore(name, &options, reader).await?;
+            secondary_environment::dump_restore::restore(primary_index, name, &options, reader)
+                .await?;
         let ro_secondary_env = rw_secondary_env.share();
 
         rw_secondary_envs.push(rw_secondary_env);
@@ -116,6 +118,7 @@ pub async fn restore(
         main_env: rw_main_env,
         secondary_envs: ro_secondary_envs,
         indexing_thread_pool,
+        index_stats: Default::default(),
     })
 }
 
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use dozer_storage::LmdbEnvironment;
+use dozer_types::parking_lot::Mutex;
+
+use crate::cache::IndexStats;
+use crate::errors::CacheError;
+
+use super::{LmdbCache, MainEnvironment, SecondaryEnvironment};
+
+/// Index statistics shared by all queries on a cache, reused for the cache's `index_stats_ttl`.
+///
+/// Counting distinct keys walks the whole index, so it's too expensive to do for every query. The walk is done
+/// without holding the lock, and while one query collects expired statistics, the others use the expired ones.
+#[derive(Debug, Clone, Default)]
+pub struct IndexStatsCache(Arc<Mutex<IndexStatsState>>);
+
+#[derive(Debug, Default)]
+struct IndexStatsState {
+    collected: Option<(Instant, Vec<IndexStats>)>,
+    collecting: bool,
+}
+
+impl IndexStatsCache {
+    pub fn get<C: LmdbCache>(&self, cache: &C) -> Result<Vec<IndexStats>, CacheError> {
+        {
+            let mut state = self.0.lock();
+            if let Some((collected_at, index_stats)) = state.collected.as_ref() {
+                if state.collecting || collected_at.elapsed() < cache.main_env().index_stats_ttl() {
+                    return Ok(index_stats.clone());
+                }
+            }
+            state.collecting = true;
+        }
+
+        let result = collect_index_stats(cache);
+
+        let mut state = self.0.lock();
+        state.collecting = false;
+        let index_stats = result?;
+        state.collected = Some((Instant::now(), index_stats.clone()));
+        Ok(index_stats)
+    }
+}
+
+pub fn collect_index_stats<C: LmdbCache>(cache: &C) -> Result<Vec<IndexStats>, CacheError> {
+    let mut indexes = vec![];
+    for (index, index_definition) in cache.main_env().schema().1.iter().enumerate() {
+        let secondary_env = cache.secondary_env(index);
+        let txn = secondary_env.begin_txn()?;
+        let database = secondary_env.database();
+        indexes.push(IndexStats {
+            index_definition: index_definition.clone(),
+            entries: database.count_data(&txn)?,
+            distinct_keys: secondary_env.count_distinct_keys(&txn)?,
+        });
+    }
+    Ok(indexes)
+}
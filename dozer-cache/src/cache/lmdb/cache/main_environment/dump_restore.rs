@@ -84,6 +84,9 @@ pub async fn restore(
             connection_snapshotting_done,
             operation_log,
             intersection_chunk_size: options.intersection_chunk_size,
+            parallel_scan_threshold: options.parallel_scan_threshold,
+            parallel_scan_chunk_size: options.parallel_scan_chunk_size,
+            index_stats_ttl: options.index_stats_ttl,
         },
         _temp_dir: temp_dir,
         write_options,
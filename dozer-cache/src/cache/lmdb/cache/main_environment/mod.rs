@@ -2,6 +2,7 @@ use std::{
     collections::HashSet,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use dozer_storage::{
@@ -64,6 +65,18 @@ pub trait MainEnvironment: LmdbEnvironment {
         self.common().intersection_chunk_size
     }
 
+    fn parallel_scan_threshold(&self) -> Option<usize> {
+        self.common().parallel_scan_threshold
+    }
+
+    fn parallel_scan_chunk_size(&self) -> usize {
+        self.common().parallel_scan_chunk_size
+    }
+
+    fn index_stats_ttl(&self) -> Duration {
+        self.common().index_stats_ttl
+    }
+
     fn count(&self) -> Result<usize, CacheError> {
         let txn = self.begin_txn()?;
         self.operation_log()
@@ -120,6 +133,9 @@ pub struct MainEnvironmentCommon {
     /// The operation log.
     operation_log: OperationLog,
     intersection_chunk_size: usize,
+    parallel_scan_threshold: Option<usize>,
+    parallel_scan_chunk_size: usize,
+    index_stats_ttl: Duration,
 }
 
 #[derive(Debug)]
@@ -217,6 +233,9 @@ impl RwMainEnvironment {
                 connection_snapshotting_done,
                 operation_log,
                 intersection_chunk_size: options.intersection_chunk_size,
+                parallel_scan_threshold: options.parallel_scan_threshold,
+                parallel_scan_chunk_size: options.parallel_scan_chunk_size,
+                index_stats_ttl: options.index_stats_ttl,
             },
             _temp_dir: temp_dir,
             write_options,
@@ -605,6 +624,9 @@ impl RoMainEnvironment {
                 connection_snapshotting_done,
                 operation_log,
                 intersection_chunk_size: options.intersection_chunk_size,
+                parallel_scan_threshold: options.parallel_scan_threshold,
+                parallel_scan_chunk_size: options.parallel_scan_chunk_size,
+                index_stats_ttl: options.index_stats_ttl,
             },
         })
     }
@@ -644,6 +666,8 @@ fn debug_check_schema_record_consistency(schema: &Schema, record: &Record) {
             FieldType::Json => debug_assert!(value.as_json().is_some()),
             FieldType::Point => debug_assert!(value.as_point().is_some()),
             FieldType::Duration => debug_assert!(value.as_duration().is_some()),
+            FieldType::Money => debug_assert!(value.as_money().is_some()),
+            FieldType::Enum => debug_assert!(value.as_enum().is_some()),
         }
     }
 }
@@ -1,7 +1,7 @@
 use dozer_storage::{
     errors::StorageError,
     lmdb::{RoCursor, RwTransaction, Transaction},
-    KeyIterator, LmdbCounter, LmdbEnvironment, LmdbMap, LmdbSet, RwLmdbEnvironment,
+    Decode, KeyIterator, LmdbCounter, LmdbEnvironment, LmdbMap, LmdbSet, RwLmdbEnvironment,
 };
 use dozer_types::{
     borrow::{Borrow, Cow, IntoOwned},
@@ -199,10 +199,32 @@ impl OperationLog {
         txn: &T,
         operation_id: u64,
     ) -> Result<CacheRecord, StorageError> {
-        let Some(Cow::Owned(Operation::Insert {
+        Self::decode_record(self.get_encoded_record_by_operation_id_unchecked(txn, operation_id)?)
+    }
+
+    /// Like `get_record_by_operation_id_unchecked`, but returns the encoded operation for `decode_record`, so records
+    /// read in one transaction can be decoded on other threads.
+    pub fn get_encoded_record_by_operation_id_unchecked<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        operation_id: u64,
+    ) -> Result<&'txn [u8], StorageError> {
+        let Some(encoded) = self
+            .operation_id_to_operation
+            .get_encoded(txn, &operation_id)?
+        else {
+            panic!(
+                "Inconsistent state: primary_key_metadata, hash_metadata or present_operation_ids contains an operation id that is not in the operation log"
+            );
+        };
+        Ok(encoded)
+    }
+
+    pub fn decode_record(encoded: &[u8]) -> Result<CacheRecord, StorageError> {
+        let Cow::Owned(Operation::Insert {
             record_meta,
             record,
-        })) = self.operation_id_to_operation.get(txn, &operation_id)?
+        }) = Operation::decode(encoded)?
         else {
             panic!(
                 "Inconsistent state: primary_key_metadata, hash_metadata or present_operation_ids contains an insert operation id that is not an Insert operation"
@@ -1,9 +1,13 @@
 use dozer_types::labels::Labels;
 use dozer_types::parking_lot::Mutex;
 use std::collections::HashSet;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{fmt::Debug, sync::Arc};
 
+use dozer_storage::LmdbEnvironment;
+
 use dozer_types::types::{Record, SchemaWithIndex};
 
 use super::{
@@ -11,14 +15,17 @@ use super::{
     indexing::IndexingThreadPool,
 };
 use crate::cache::expression::QueryExpression;
-use crate::cache::{CacheRecord, CacheWriteOptions, RecordMeta, UpsertResult};
+use crate::cache::plan::{Plan, PreparedPlan};
+use crate::cache::{CacheRecord, CacheStats, CacheWriteOptions, RecordMeta, UpsertResult};
 use crate::errors::CacheError;
 
 pub mod dump_restore;
+mod index_stats;
 mod main_environment;
 mod query;
 mod secondary_environment;
 
+pub use index_stats::IndexStatsCache;
 pub use main_environment::{MainEnvironment, RoMainEnvironment, RwMainEnvironment};
 use query::LmdbQueryHandler;
 pub use secondary_environment::{
@@ -38,6 +45,15 @@ pub struct CacheOptions {
     /// The chunk size when calculating intersection of index queries.
     pub intersection_chunk_size: usize,
 
+    /// Full scans that can return more records than this decode their records on multiple threads. Disabled if `None`.
+    pub parallel_scan_threshold: Option<usize>,
+
+    /// The number of records each thread decodes when a full scan is partitioned across threads.
+    pub parallel_scan_chunk_size: usize,
+
+    /// How long collected index statistics are reused by the query planner before they're collected again.
+    pub index_stats_ttl: Duration,
+
     /// Provide a path where db will be created. If nothing is provided, will default to a temp location.
     /// Db path will be `PathBuf.join(Labels.to_non_empty_string())`.
     pub path: Option<(PathBuf, Labels)>,
@@ -50,6 +66,9 @@ impl Default for CacheOptions {
             max_db_size: 1000,
             max_size: 1024 * 1024 * 1024,
             intersection_chunk_size: 100,
+            parallel_scan_threshold: None,
+            parallel_scan_chunk_size: 10_000,
+            index_stats_ttl: Duration::from_secs(10),
             path: None,
         }
     }
@@ -59,17 +78,26 @@ impl Default for CacheOptions {
 pub struct LmdbRoCache {
     pub main_env: RoMainEnvironment,
     pub secondary_envs: Vec<RoSecondaryEnvironment>,
+    pub index_stats: IndexStatsCache,
 }
 
 impl LmdbRoCache {
     pub fn new(options: &CacheOptions) -> Result<Self, CacheError> {
         let main_env = RoMainEnvironment::new(options)?;
-        let secondary_envs = (0..main_env.schema().1.len())
-            .map(|index| RoSecondaryEnvironment::new(secondary_environment_name(index), options))
+        let (schema, secondary_indexes) = main_env.schema();
+        let secondary_envs = (0..secondary_indexes.len())
+            .map(|index| {
+                RoSecondaryEnvironment::new(
+                    &schema.primary_index,
+                    secondary_environment_name(index),
+                    options,
+                )
+            })
             .collect::<Result<_, _>>()?;
         Ok(Self {
             main_env,
             secondary_envs,
+            index_stats: Default::default(),
         })
     }
 }
@@ -79,6 +107,7 @@ pub struct LmdbRwCache {
     main_env: RwMainEnvironment,
     secondary_envs: Vec<RoSecondaryEnvironment>,
     indexing_thread_pool: Arc<Mutex<IndexingThreadPool>>,
+    index_stats: IndexStatsCache,
 }
 
 impl LmdbRwCache {
@@ -102,10 +131,15 @@ impl LmdbRwCache {
 
         let mut rw_secondary_envs = vec![];
         let mut ro_secondary_envs = vec![];
-        for (index, index_definition) in ro_main_env.schema().1.iter().enumerate() {
+        let (schema, secondary_indexes) = ro_main_env.schema();
+        for (index, index_definition) in secondary_indexes.iter().enumerate() {
             let name = secondary_environment_name(index);
-            let rw_secondary_env =
-                RwSecondaryEnvironment::new(index_definition, name.clone(), &options)?;
+            let rw_secondary_env = RwSecondaryEnvironment::new(
+                index_definition,
+                &schema.primary_index,
+                name.clone(),
+                &options,
+            )?;
             let ro_secondary_env = rw_secondary_env.share();
 
             rw_secondary_envs.push(rw_secondary_env);
@@ -120,6 +154,7 @@ impl LmdbRwCache {
             main_env: rw_main_env,
             secondary_envs: ro_secondary_envs,
             indexing_thread_pool,
+            index_stats: Default::default(),
         })
     }
 }
@@ -141,6 +176,48 @@ impl<C: LmdbCache> RoCache for C {
         LmdbQueryHandler::new(self, query).query()
     }
 
+    fn count_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<usize, CacheError> {
+        LmdbQueryHandler::new(self, query)
+            .with_prepared_plan(prepared)
+            .count()
+    }
+
+    fn query_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        LmdbQueryHandler::new(self, query)
+            .with_prepared_plan(prepared)
+            .query()
+    }
+
+    fn explain(&self, query: &QueryExpression) -> Result<Plan, CacheError> {
+        LmdbQueryHandler::new(self, query)
+            .plan()
+            .map_err(Into::into)
+    }
+
+    fn for_each_record(
+        &self,
+        query: &QueryExpression,
+        f: &mut dyn FnMut(CacheRecord) -> ControlFlow<()>,
+    ) -> Result<(), CacheError> {
+        // `None` stops the iteration without an error.
+        let result = LmdbQueryHandler::new(self, query).for_each_record(|record| match f(record) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(None::<CacheError>),
+        });
+        match result {
+            Err(Some(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
+
     fn get_schema(&self) -> &SchemaWithIndex {
         self.main_env().schema()
     }
@@ -152,6 +229,21 @@ impl<C: LmdbCache> RoCache for C {
     fn is_snapshotting_done(&self) -> Result<bool, CacheError> {
         self.main_env().is_snapshotting_done()
     }
+
+    fn get_stats(&self) -> Result<CacheStats, CacheError> {
+        let main_env = self.main_env();
+        let mut total_bytes = main_env.used_bytes()?;
+        for index in 0..main_env.schema().1.len() {
+            total_bytes += self.secondary_env(index).used_bytes()?;
+        }
+
+        Ok(CacheStats {
+            record_count: main_env.count()?,
+            total_bytes,
+            last_updated: main_env.metadata()?,
+            indexes: index_stats::collect_index_stats(self)?,
+        })
+    }
 }
 
 impl RwCache for LmdbRwCache {
@@ -188,6 +280,15 @@ impl RwCache for LmdbRwCache {
     }
 }
 
+/// Calls `f` with every record satisfying `query`, reading all of them in one transaction.
+pub fn for_each_record<C: LmdbCache, E: From<CacheError>>(
+    cache: &C,
+    query: &QueryExpression,
+    f: impl FnMut(CacheRecord) -> Result<(), E>,
+) -> Result<(), E> {
+    LmdbQueryHandler::new(cache, query).for_each_record(f)
+}
+
 pub trait LmdbCache: Send + Sync + Debug {
     type MainEnvironment: MainEnvironment;
 
@@ -196,6 +297,8 @@ pub trait LmdbCache: Send + Sync + Debug {
     type SecondaryEnvironment: SecondaryEnvironment;
 
     fn secondary_env(&self, index: usize) -> &Self::SecondaryEnvironment;
+
+    fn index_stats(&self) -> &IndexStatsCache;
 }
 
 impl LmdbCache for LmdbRoCache {
@@ -208,6 +311,10 @@ impl LmdbCache for LmdbRoCache {
     fn secondary_env(&self, index: usize) -> &Self::SecondaryEnvironment {
         &self.secondary_envs[index]
     }
+
+    fn index_stats(&self) -> &IndexStatsCache {
+        &self.index_stats
+    }
 }
 
 impl LmdbCache for LmdbRwCache {
@@ -220,6 +327,10 @@ impl LmdbCache for LmdbRwCache {
     fn secondary_env(&self, index: usize) -> &Self::SecondaryEnvironment {
         &self.secondary_envs[index]
     }
+
+    fn index_stats(&self) -> &IndexStatsCache {
+        &self.index_stats
+    }
 }
 
 fn secondary_environment_name(index: usize) -> String {
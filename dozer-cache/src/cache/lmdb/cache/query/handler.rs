@@ -1,36 +1,94 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Instant;
+
 use super::intersection::intersection;
-use crate::cache::expression::Skip;
-use crate::cache::lmdb::cache::main_environment::MainEnvironment;
+use super::union::union;
+use crate::cache::expression::{Skip, SortDirection};
+use crate::cache::lmdb::cache::main_environment::{MainEnvironment, OperationLog};
 use crate::cache::lmdb::cache::query::secondary::build_index_scan;
 use crate::cache::lmdb::cache::LmdbCache;
 use crate::cache::CacheRecord;
 use crate::cache::{
     expression::QueryExpression,
-    plan::{IndexScan, Plan, QueryPlanner},
+    plan::{
+        field_value, index_expressions, IndexScan, IndexScanUnion, Plan, PreparedPlan, QueryPlanner,
+    },
 };
 use crate::errors::{CacheError, PlanError};
 use dozer_storage::errors::StorageError;
 use dozer_storage::lmdb::{RoTransaction, Transaction};
 use dozer_storage::LmdbEnvironment;
 use dozer_types::borrow::IntoOwned;
+use dozer_types::tracing::debug;
+use dozer_types::types::Field;
 use itertools::Either;
+use rayon::prelude::*;
+
+/// The sort options of a query as field indexes, and the fields that order records with equal sort keys, see
+/// `QueryPlanner::tiebreaker`.
+struct Order {
+    sort_fields: Vec<(usize, SortDirection)>,
+    tiebreaker: Vec<(usize, SortDirection)>,
+}
+
+impl Order {
+    /// The direction of index scans without range query, which return records with equal sort keys.
+    fn direction(&self) -> SortDirection {
+        self.tiebreaker
+            .first()
+            .map_or(SortDirection::Ascending, |(_, direction)| *direction)
+    }
+}
 
 pub struct LmdbQueryHandler<'a, C: LmdbCache> {
     cache: &'a C,
     query: &'a QueryExpression,
+    prepared: Option<&'a PreparedPlan>,
 }
 
 impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
     pub fn new(cache: &'a C, query: &'a QueryExpression) -> Self {
-        Self { cache, query }
+        Self {
+            cache,
+            query,
+            prepared: None,
+        }
+    }
+
+    /// Binds the query to `prepared` instead of planning it, if `prepared` has a plan of its shape, and prepares the
+    /// plan otherwise.
+    pub fn with_prepared_plan(mut self, prepared: &'a PreparedPlan) -> Self {
+        self.prepared = Some(prepared);
+        self
     }
 
     pub fn count(&self) -> Result<usize, CacheError> {
         match self.plan()? {
             Plan::IndexScans(index_scans) => {
+                let order = self.order()?;
                 let secondary_txns = self.create_secondary_txns(&index_scans)?;
-                let ids = self.combine_secondary_queries(&index_scans, &secondary_txns)?;
-                self.count_secondary_queries(ids)
+                let main_txn = self.cache.main_env().begin_txn()?;
+                let ids = self.combine_secondary_queries(&index_scans, &secondary_txns, &order)?;
+                #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
+                let result = self.count_secondary_queries(&main_txn, ids);
+                result
+            }
+            Plan::Union(index_scan_union) => {
+                let order = self.order()?;
+                let secondary_txns =
+                    self.create_secondary_txns(index_scan_union.branches.iter().flatten())?;
+                let main_txn = self.cache.main_env().begin_txn()?;
+                let ids = self.union_secondary_queries(
+                    &index_scan_union,
+                    &secondary_txns,
+                    self.sorting_txn(&main_txn),
+                    &order,
+                )?;
+                #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
+                let result = self.count_secondary_queries(&main_txn, ids);
+                result
             }
             Plan::SeqScan(_) => Ok(match self.query.skip {
                 Skip::Skip(skip) => self
@@ -48,16 +106,42 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
     pub fn query(&self) -> Result<Vec<CacheRecord>, CacheError> {
         match self.plan()? {
             Plan::IndexScans(index_scans) => {
+                let order = self.order()?;
                 let secondary_txns = self.create_secondary_txns(&index_scans)?;
                 let main_txn = self.cache.main_env().begin_txn()?;
                 #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
                 let result = self.collect_records(
                     &main_txn,
-                    self.combine_secondary_queries(&index_scans, &secondary_txns)?,
+                    self.combine_secondary_queries(&index_scans, &secondary_txns, &order)?,
+                );
+                result
+            }
+            Plan::Union(index_scan_union) => {
+                let order = self.order()?;
+                let secondary_txns =
+                    self.create_secondary_txns(index_scan_union.branches.iter().flatten())?;
+                let main_txn = self.cache.main_env().begin_txn()?;
+                #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
+                let result = self.collect_records(
+                    &main_txn,
+                    self.union_secondary_queries(
+                        &index_scan_union,
+                        &secondary_txns,
+                        Some(&main_txn),
+                        &order,
+                    )?,
                 );
                 result
             }
             Plan::SeqScan(_seq_scan) => {
+                let main_env = self.cache.main_env();
+                if let Some(threshold) = main_env.parallel_scan_threshold() {
+                    if self.query.limit.map_or(true, |limit| limit > threshold) {
+                        return self
+                            .collect_records_in_parallel(main_env.parallel_scan_chunk_size());
+                    }
+                }
+
                 let main_txn = self.cache.main_env().begin_txn()?;
                 #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
                 let result = self.collect_records(&main_txn, self.all_ids(&main_txn)?);
@@ -67,7 +151,90 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
         }
     }
 
-    fn plan(&self) -> Result<Plan, PlanError> {
+    /// Calls `f` with every record satisfying the query, in query order.
+    ///
+    /// All records are read in one main environment transaction, so they're a consistent snapshot of the cache,
+    /// and records are not collected in memory.
+    pub fn for_each_record<E: From<CacheError>>(
+        &self,
+        mut f: impl FnMut(CacheRecord) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let main_txn = self
+            .cache
+            .main_env()
+            .begin_txn()
+            .map_err(CacheError::from)?;
+        let operation_log = self.cache.main_env().operation_log();
+        let mut read_record = |id: Result<u64, CacheError>| -> Result<(), E> {
+            let record = operation_log
+                .get_record_by_operation_id_unchecked(&main_txn, id?)
+                .map_err(CacheError::from)?;
+            f(record)
+        };
+
+        let order = self.order().map_err(CacheError::from)?;
+        match self.plan().map_err(CacheError::from)? {
+            Plan::IndexScans(index_scans) => {
+                let secondary_txns = self
+                    .create_secondary_txns(&index_scans)
+                    .map_err(CacheError::from)?;
+                let ids = self.combine_secondary_queries(&index_scans, &secondary_txns, &order)?;
+                for id in self.filter_secondary_queries(&main_txn, ids) {
+                    read_record(id)?;
+                }
+            }
+            Plan::Union(index_scan_union) => {
+                let secondary_txns = self
+                    .create_secondary_txns(index_scan_union.branches.iter().flatten())
+                    .map_err(CacheError::from)?;
+                let ids = self.union_secondary_queries(
+                    &index_scan_union,
+                    &secondary_txns,
+                    Some(&main_txn),
+                    &order,
+                )?;
+                for id in self.filter_secondary_queries(&main_txn, ids) {
+                    read_record(id)?;
+                }
+            }
+            Plan::SeqScan(_) => {
+                for id in self.all_ids(&main_txn)? {
+                    read_record(id)?;
+                }
+            }
+            Plan::ReturnEmpty => (),
+        }
+        Ok(())
+    }
+
+    pub fn plan(&self) -> Result<Plan, PlanError> {
+        let (schema, secondary_indexes) = self.cache.main_env().schema();
+        let planner = QueryPlanner::new(
+            schema,
+            secondary_indexes,
+            self.query.filter.as_ref(),
+            &self.query.order_by,
+        );
+        if let Some(prepared) = self.prepared {
+            if let Some(plan) = prepared.bind(&planner)? {
+                return Ok(plan);
+            }
+        }
+        // Statistics only improve the plan, so the planner falls back to the first matching indexes without them.
+        let plan = match self.cache.index_stats().get(self.cache) {
+            Ok(index_stats) => planner.with_index_stats(&index_stats).plan()?,
+            Err(e) => {
+                debug!("Failed to collect index statistics for query planning: {e}");
+                planner.plan()?
+            }
+        };
+        if let Some(prepared) = self.prepared {
+            prepared.prepare(&plan);
+        }
+        Ok(plan)
+    }
+
+    fn order(&self) -> Result<Order, PlanError> {
         let (schema, secondary_indexes) = self.cache.main_env().schema();
         let planner = QueryPlanner::new(
             schema,
@@ -75,7 +242,15 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
             self.query.filter.as_ref(),
             &self.query.order_by,
         );
-        planner.plan()
+        Ok(Order {
+            sort_fields: planner.sort_fields()?,
+            tiebreaker: planner.tiebreaker()?,
+        })
+    }
+
+    /// Counting only needs the ids in order, read from `main_txn`, to find the id to skip after.
+    fn sorting_txn<'txn, M: Transaction>(&self, main_txn: &'txn M) -> Option<&'txn M> {
+        Some(main_txn).filter(|_| matches!(self.query.skip, Skip::After(_)))
     }
 
     fn all_ids<'txn, T: Transaction>(
@@ -93,53 +268,184 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
                     .map(|id| id.into_owned())
                     .map_err(CacheError::Storage)
             });
-        Ok(skip(all_ids, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
+        Ok(
+            skip(with_deadline(all_ids, self.query.deadline), self.query.skip)
+                .take(self.query.limit.unwrap_or(usize::MAX)),
+        )
     }
 
-    fn create_secondary_txns(
+    fn check_deadline(&self) -> Result<(), CacheError> {
+        match self.query.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(CacheError::QueryTimeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads the records to return in one transaction, then decodes consecutive ranges of them on the rayon thread
+    /// pool.
+    ///
+    /// A transaction can't be shared between threads, but the encoded records it reads stay valid while it's open.
+    fn collect_records_in_parallel(
         &self,
-        index_scans: &[IndexScan],
-    ) -> Result<Vec<RoTransaction<'_>>, StorageError> {
-        index_scans
-            .iter()
-            .map(|index_scan| self.cache.secondary_env(index_scan.index_id).begin_txn())
-            .collect()
+        chunk_size: usize,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        let main_txn = self.cache.main_env().begin_txn()?;
+        let operation_log = self.cache.main_env().operation_log();
+        let encoded = self
+            .all_ids(&main_txn)?
+            .map(|id| {
+                operation_log
+                    .get_encoded_record_by_operation_id_unchecked(&main_txn, id?)
+                    .map_err(CacheError::from)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let chunks = encoded
+            .par_chunks(chunk_size.max(1))
+            .map(|encoded| {
+                self.check_deadline()?;
+                encoded
+                    .iter()
+                    .map(|encoded| OperationLog::decode_record(encoded).map_err(Into::into))
+                    .collect::<Result<Vec<_>, CacheError>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(chunks.into_iter().flatten().collect())
+    }
+
+    /// Begins one transaction per index, because a thread can only have one read transaction of an environment.
+    fn create_secondary_txns<'a>(
+        &self,
+        index_scans: impl IntoIterator<Item = &'a IndexScan>,
+    ) -> Result<HashMap<usize, RoTransaction<'_>>, StorageError> {
+        let mut secondary_txns = HashMap::new();
+        for index_scan in index_scans {
+            if let Entry::Vacant(entry) = secondary_txns.entry(index_scan.index_id) {
+                entry.insert(self.cache.secondary_env(index_scan.index_id).begin_txn()?);
+            }
+        }
+        Ok(secondary_txns)
     }
 
     fn combine_secondary_queries<'txn, T: Transaction>(
         &self,
         index_scans: &[IndexScan],
-        secondary_txns: &'txn [T],
+        secondary_txns: &'txn HashMap<usize, T>,
+        order: &Order,
+    ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
+        let combined = self.intersect_index_scans(index_scans, secondary_txns, order)?;
+        Ok(skip(combined, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
+    }
+
+    /// Reads the sort key and the tiebreaker key of the record of an id.
+    fn record_keys<'txn, M: Transaction>(
+        &'txn self,
+        main_txn: &'txn M,
+        order: &'txn Order,
+    ) -> impl FnMut(u64) -> Result<(Vec<Field>, Vec<Field>), CacheError> + 'txn {
+        let expressions = index_expressions(self.cache.main_env().schema().1);
+        let operation_log = self.cache.main_env().operation_log();
+        move |id| {
+            let record = operation_log.get_record_by_operation_id_unchecked(main_txn, id)?;
+            let values = &record.record.values;
+            Ok((
+                order
+                    .sort_fields
+                    .iter()
+                    .map(|(field_index, _)| field_value(values, &expressions, *field_index))
+                    .collect(),
+                order
+                    .tiebreaker
+                    .iter()
+                    .map(|(field_index, _)| field_value(values, &expressions, *field_index))
+                    .collect(),
+            ))
+        }
+    }
+
+    fn intersect_index_scans<'txn, T: Transaction>(
+        &self,
+        index_scans: &[IndexScan],
+        secondary_txns: &'txn HashMap<usize, T>,
+        order: &Order,
     ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
         debug_assert!(
             !index_scans.is_empty(),
             "Planner should not generate empty index scan"
         );
-        let combined = if index_scans.len() == 1 {
+        Ok(if index_scans.len() == 1 {
             // The fast path, without intersection calculation.
-            Either::Left(build_index_scan(
-                &secondary_txns[0],
-                self.cache.secondary_env(index_scans[0].index_id),
-                &index_scans[0].kind,
-            )?)
+            Either::Left(with_deadline(
+                build_index_scan(
+                    &secondary_txns[&index_scans[0].index_id],
+                    self.cache.secondary_env(index_scans[0].index_id),
+                    &index_scans[0].kind,
+                    order.direction(),
+                )?,
+                self.query.deadline,
+            ))
         } else {
             // Intersection of multiple index scans.
             let iterators = index_scans
                 .iter()
-                .zip(secondary_txns)
-                .map(|(index_scan, secondary_txn)| {
-                    build_index_scan(
-                        secondary_txn,
-                        self.cache.secondary_env(index_scan.index_id),
-                        &index_scan.kind,
-                    )
+                .map(|index_scan| {
+                    Ok(with_deadline(
+                        build_index_scan(
+                            &secondary_txns[&index_scan.index_id],
+                            self.cache.secondary_env(index_scan.index_id),
+                            &index_scan.kind,
+                            order.direction(),
+                        )?,
+                        self.query.deadline,
+                    ))
                 })
                 .collect::<Result<Vec<_>, CacheError>>()?;
             Either::Right(intersection(
                 iterators,
                 self.cache.main_env().intersection_chunk_size(),
             ))
+        })
+    }
+
+    /// Merges the ids of the branches of `index_scan_union`, sorted by `order` if `main_txn` is given to read the sort
+    /// keys from.
+    fn union_secondary_queries<'txn, T: Transaction, M: Transaction>(
+        &'txn self,
+        index_scan_union: &'txn IndexScanUnion,
+        secondary_txns: &'txn HashMap<usize, T>,
+        main_txn: Option<&'txn M>,
+        order: &'txn Order,
+    ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
+        let main_txn = main_txn.filter(|_| !order.sort_fields.is_empty());
+        let iterators = index_scan_union
+            .branches
+            .iter()
+            .map(|index_scans| self.intersect_index_scans(index_scans, secondary_txns, order))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut record_keys = main_txn.map(|main_txn| self.record_keys(main_txn, order));
+        let key = move |id: u64| match &mut record_keys {
+            Some(record_keys) => record_keys(id),
+            None => Ok((vec![], vec![])),
         };
+        let compare = move |(a, a_tiebreaker): &(Vec<Field>, Vec<Field>),
+                            (b, b_tiebreaker): &(Vec<Field>, Vec<Field>)| {
+            a.iter()
+                .zip(b)
+                .zip(&order.sort_fields)
+                .map(|((a, b), (_, direction))| match direction {
+                    SortDirection::Ascending => a.cmp(b),
+                    SortDirection::Descending => b.cmp(a),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| match order.direction() {
+                    SortDirection::Ascending => a_tiebreaker.cmp(b_tiebreaker),
+                    SortDirection::Descending => b_tiebreaker.cmp(a_tiebreaker),
+                })
+        };
+
+        let combined = union(iterators, key, compare);
         Ok(skip(combined, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
     }
 
@@ -163,14 +469,13 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
         })
     }
 
-    fn count_secondary_queries(
-        &self,
-        ids: impl Iterator<Item = Result<u64, CacheError>>,
+    fn count_secondary_queries<'txn, T: Transaction>(
+        &'txn self,
+        main_txn: &'txn T,
+        ids: impl Iterator<Item = Result<u64, CacheError>> + 'txn,
     ) -> Result<usize, CacheError> {
-        let main_txn = self.cache.main_env().begin_txn()?;
-
         let mut result = 0;
-        for maybe_id in self.filter_secondary_queries(&main_txn, ids) {
+        for maybe_id in self.filter_secondary_queries(main_txn, ids) {
             maybe_id?;
             result += 1;
         }
@@ -206,6 +511,51 @@ fn skip(
     }
 }
 
+/// The clock is read every this many ids, because reading it for every id slows down scans.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+fn with_deadline<T: Iterator<Item = Result<u64, CacheError>>>(
+    iter: T,
+    deadline: Option<Instant>,
+) -> Either<WithDeadline<T>, T> {
+    match deadline {
+        Some(deadline) => Either::Left(WithDeadline {
+            inner: iter,
+            deadline,
+            count: 0,
+            expired: false,
+        }),
+        None => Either::Right(iter),
+    }
+}
+
+/// Stops a scan once `deadline` passes. After that, it only returns `CacheError::QueryTimeout`, so the error isn't
+/// lost to a skip.
+struct WithDeadline<T: Iterator<Item = Result<u64, CacheError>>> {
+    inner: T,
+    deadline: Instant,
+    count: usize,
+    expired: bool,
+}
+
+impl<T: Iterator<Item = Result<u64, CacheError>>> Iterator for WithDeadline<T> {
+    type Item = Result<u64, CacheError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.expired
+            && self.count % DEADLINE_CHECK_INTERVAL == 0
+            && Instant::now() >= self.deadline
+        {
+            self.expired = true;
+        }
+        if self.expired {
+            return Some(Err(CacheError::QueryTimeout));
+        }
+        self.count += 1;
+        self.inner.next()
+    }
+}
+
 struct SkipAfter<T: Iterator<Item = Result<u64, CacheError>>> {
     inner: T,
     after: Option<u64>,
@@ -2,6 +2,7 @@ mod handler;
 mod intersection;
 mod lmdb_cmp;
 mod secondary;
+mod union;
 
 pub use handler::LmdbQueryHandler;
 
@@ -3,13 +3,13 @@ use std::{cmp::Ordering, ops::Bound};
 use dozer_storage::lmdb::Transaction;
 use dozer_types::{
     borrow::{Borrow, IntoOwned},
-    types::{Field, IndexDefinition},
+    types::Field,
 };
 
 use crate::{
     cache::{
         expression::{Operator, SortDirection},
-        index,
+        index::{self, compare_composite_secondary_index, composite_secondary_index_prefix},
         lmdb::cache::secondary_environment::SecondaryEnvironment,
         plan::{IndexScanKind, SortedInvertedRangeQuery},
     },
@@ -18,59 +18,70 @@ use crate::{
 
 use super::lmdb_cmp::lmdb_cmp;
 
+/// Scans the ids of the records in the range of `index_scan_kind`.
+///
+/// The keys of the index end with the primary key, so records with equal indexed fields are in primary key order, in
+/// the direction of the scan. A scan without range query, whose records all have equal indexed fields, is in
+/// `direction`.
 pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
     secondary_txn: &'txn T,
     secondary_env: &S,
     index_scan_kind: &IndexScanKind,
+    direction: SortDirection,
 ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
-    let is_single_field_sorted_inverted =
-        is_single_field_sorted_inverted(secondary_env.index_definition());
-    let range = get_range_spec(index_scan_kind, is_single_field_sorted_inverted)?;
+    let num_indexed_fields = secondary_env.num_indexed_fields();
+    let range = get_range_spec(index_scan_kind, num_indexed_fields.is_none(), direction)?;
+    let ascending = range.direction == SortDirection::Ascending;
 
-    let start = match &range.start {
-        Some(KeyEndpoint::Including(key)) => Bound::Included(key.as_slice()),
-        Some(KeyEndpoint::Excluding(key)) => Bound::Excluded(key.as_slice()),
-        None => Bound::Unbounded,
+    // Keys of the range are compared by their indexed fields, without the primary key fields appended to them.
+    let database = secondary_env.database().database();
+    let compare = move |key: &[u8], range_key: &[u8]| match num_indexed_fields {
+        Some(num_indexed_fields) => composite_secondary_index_prefix(key, num_indexed_fields)
+            .and_then(|key| compare_composite_secondary_index(key, range_key))
+            .map_err(|e| CacheError::Index(IndexError::InvalidKey(e))),
+        None => Ok(lmdb_cmp(secondary_txn, database, key, range_key)),
+    };
+
+    // Descending from a key including it starts after all the keys that only differ from it in the primary key.
+    let start = match (&range.start, num_indexed_fields) {
+        (Some(KeyEndpoint::Including(key)), Some(num_indexed_fields)) if !ascending => {
+            let num_key_fields = num_indexed_fields + secondary_env.common().primary_index.len();
+            let nulls = vec![&Field::Null; num_key_fields];
+            Bound::Included([key.as_slice(), &index::get_secondary_index(&nulls, false)].concat())
+        }
+        (Some(KeyEndpoint::Including(key)), _) => Bound::Included(key.clone()),
+        (Some(KeyEndpoint::Excluding(key)), _) => Bound::Excluded(key.clone()),
+        (None, _) => Bound::Unbounded,
     };
 
-    let database = secondary_env.database().database();
     Ok(secondary_env
         .database()
-        .range(
-            secondary_txn,
-            start,
-            range.direction == SortDirection::Ascending,
-        )?
-        .take_while(move |result| match result {
-            Ok((key, _)) => {
-                if let Some(end_key) = &range.end {
-                    match lmdb_cmp(secondary_txn, database, key.borrow(), end_key.key()) {
-                        Ordering::Less => {
-                            matches!(range.direction, SortDirection::Ascending)
-                        }
-                        Ordering::Equal => matches!(end_key, KeyEndpoint::Including(_)),
-                        Ordering::Greater => {
-                            matches!(range.direction, SortDirection::Descending)
-                        }
-                    }
-                } else {
-                    true
-                }
-            }
-            Err(_) => true,
+        .range(secondary_txn, bound_as_ref(&start), ascending)?
+        .map(move |result| -> Result<_, CacheError> {
+            let (key, id) = result?;
+            let key = key.borrow();
+            let includes = |endpoint: &Option<KeyEndpoint>, below: bool| -> Result<_, CacheError> {
+                Ok(match endpoint {
+                    Some(endpoint) => endpoint.includes(compare(key, endpoint.key())?, below),
+                    None => true,
+                })
+            };
+            Ok((
+                includes(&range.start, !ascending)?,
+                includes(&range.end, ascending)?,
+                id.into_owned(),
+            ))
         })
-        .map(|result| {
-            result
-                .map(|(_, id)| id.into_owned())
-                .map_err(CacheError::Storage)
-        }))
+        .skip_while(|result| matches!(result, Ok((false, _, _))))
+        .take_while(|result| !matches!(result, Ok((_, false, _))))
+        .map(|result| result.map(|(_, _, id)| id)))
 }
 
-fn is_single_field_sorted_inverted(index: &IndexDefinition) -> bool {
-    match index {
-        // `fields.len() == 1` criteria must be kept the same with `comparator.rs`.
-        IndexDefinition::SortedInverted(fields) => fields.len() == 1,
-        _ => false,
+fn bound_as_ref(bound: &Bound<Vec<u8>>) -> Bound<&[u8]> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.as_slice()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_slice()),
+        Bound::Unbounded => Bound::Unbounded,
     }
 }
 
@@ -87,6 +98,16 @@ impl KeyEndpoint {
             KeyEndpoint::Excluding(key) => key,
         }
     }
+
+    /// Whether a key that compares `ordering` to this endpoint is in the range, which is `below` the endpoint or above
+    /// it.
+    fn includes(&self, ordering: Ordering, below: bool) -> bool {
+        match ordering {
+            Ordering::Less => below,
+            Ordering::Equal => matches!(self, KeyEndpoint::Including(_)),
+            Ordering::Greater => !below,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -99,6 +120,7 @@ struct RangeSpec {
 fn get_range_spec(
     index_scan_kind: &IndexScanKind,
     is_single_field_sorted_inverted: bool,
+    direction: SortDirection,
 ) -> Result<RangeSpec, CacheError> {
     match &index_scan_kind {
         IndexScanKind::SortedInverted {
@@ -180,7 +202,7 @@ fn get_range_spec(
                 RangeSpec {
                     start: Some(KeyEndpoint::Including(comparison_key.clone())),
                     end: Some(KeyEndpoint::Including(comparison_key)),
-                    direction: SortDirection::Ascending, // doesn't matter
+                    direction,
                 }
             })
         }
@@ -1,12 +1,22 @@
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use crate::cache::{
-    expression::{FilterExpression, Operator, QueryExpression},
-    lmdb::tests::utils::{create_cache, insert_rec_1},
+    expression::{FilterExpression, Operator, QueryExpression, Skip},
+    lmdb::{
+        cache::{for_each_record, CacheOptions, LmdbRwCache},
+        indexing::IndexingThreadPool,
+        tests::utils::{create_cache, insert_rec_1},
+    },
     test_utils::{query_from_filter, schema_1, schema_full_text, schema_multi_indices},
     CacheRecord, RoCache, RwCache,
 };
+use crate::errors::CacheError;
 use dozer_types::{
+    parking_lot::Mutex,
     serde_json::{from_value, json, Value},
-    types::{Field, Record},
+    types::{Field, IndexDefinition, IndexExpression, IndexFunction, Record, SchemaWithIndex},
 };
 
 #[test]
@@ -179,6 +189,186 @@ fn query_secondary_vars() {
     );
 }
 
+#[test]
+fn query_secondary_or() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+
+    let items = vec![
+        (1, Some("yuri".to_string()), Some(521)),
+        (2, Some("mega".to_string()), Some(521)),
+        (3, Some("james".to_string()), Some(523)),
+        (4, Some("james".to_string()), Some(524)),
+        (5, Some("steff".to_string()), Some(526)),
+        (6, Some("mega".to_string()), Some(527)),
+    ];
+    for val in items {
+        insert_rec_1(&mut cache, val);
+    }
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    test_query(
+        json!({"$filter": {"$or": [{"b": "james"}, {"c": {"$lt": 522}}]}}),
+        4,
+        &cache,
+    );
+
+    // Records matching more than one branch are returned once.
+    test_query(
+        json!({"$filter": {"$or": [{"c": 521}, {"a": 1}]}}),
+        2,
+        &cache,
+    );
+
+    test_query(
+        json!({"$filter": {"a": 2, "$or": [{"b": "mega"}, {"b": "james"}]}}),
+        1,
+        &cache,
+    );
+
+    test_query_err(
+        json!({"$filter": {"$or": [{"a": 1}, {"a": 2, "c": 521}]}}),
+        &cache,
+    );
+
+    test_query_record(
+        json!({
+            "$filter": {"$or": [{"a": 5}, {"a": {"$lt": 3}}]},
+            "$order_by": { "a": "desc" }
+        }),
+        vec![
+            (4, 5, "steff".to_string(), 526),
+            (1, 2, "mega".to_string(), 521),
+            (0, 1, "yuri".to_string(), 521),
+        ],
+        &cache,
+    );
+
+    test_query_record(
+        json!({
+            "$filter": {"$or": [{"a": 5}, {"a": {"$lt": 3}}]},
+            "$order_by": { "a": "asc" },
+            "$skip": 1,
+            "$limit": 1
+        }),
+        vec![(1, 2, "mega".to_string(), 521)],
+        &cache,
+    );
+}
+
+#[test]
+fn query_tiebreaker() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+
+    let items = vec![
+        (4, Some("james".to_string()), Some(524)),
+        (3, Some("james".to_string()), Some(523)),
+        (7, Some("james".to_string()), Some(528)),
+        (1, Some("ava".to_string()), Some(521)),
+    ];
+    for val in items {
+        insert_rec_1(&mut cache, val);
+    }
+    // Updating a record doesn't move it among the records it ties with.
+    let old = Record::new(vec![
+        Field::Int(3),
+        Field::String("james".to_string()),
+        Field::Int(523),
+    ]);
+    let mut new = old.clone();
+    new.values[2] = Field::Int(525);
+    cache.update(&old, &new).unwrap();
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    // Records with equal sort keys are sorted by their primary key `a` in the direction of the sort, before skipping.
+    let primary_keys = |query: Value| {
+        let query = from_value::<QueryExpression>(query).unwrap();
+        cache
+            .query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.record.values[0].clone())
+            .collect::<Vec<_>>()
+    };
+    let ints = |values: &[i64]| values.iter().copied().map(Field::Int).collect::<Vec<_>>();
+    assert_eq!(
+        primary_keys(json!({"$order_by": {"b": "asc"}})),
+        ints(&[1, 3, 4, 7])
+    );
+    assert_eq!(
+        primary_keys(json!({"$order_by": {"b": "desc"}})),
+        ints(&[7, 4, 3, 1])
+    );
+    assert_eq!(
+        primary_keys(json!({"$order_by": {"b": "asc"}, "$skip": 1, "$limit": 2})),
+        ints(&[3, 4])
+    );
+    assert_eq!(
+        primary_keys(json!({
+            "$filter": {"$or": [{"b": "james"}, {"a": 1}]},
+            "$order_by": {"b": "desc"}
+        })),
+        ints(&[7, 4, 3, 1])
+    );
+}
+
+#[test]
+fn query_secondary_expression() {
+    let schema = || -> SchemaWithIndex {
+        let (schema, mut secondary_indexes) = schema_1();
+        secondary_indexes.push(IndexDefinition::Expression(IndexExpression {
+            function: IndexFunction::Lower,
+            field_index: 1,
+        }));
+        (schema, secondary_indexes)
+    };
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema);
+
+    for val in [
+        (1, Some("Yuri".to_string()), Some(521)),
+        (2, Some("MEGA".to_string()), Some(522)),
+        (3, Some("mega".to_string()), Some(523)),
+        (4, None, Some(524)),
+    ] {
+        insert_rec_1(&mut cache, val);
+    }
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    test_query_record(
+        json!({"$filter": {"lower(b)": "mega"}}),
+        vec![
+            (1, 2, "MEGA".to_string(), 522),
+            (2, 3, "mega".to_string(), 523),
+        ],
+        &cache,
+    );
+    test_query(json!({"$filter": {"lower(b)": "MEGA"}}), 0, &cache);
+    test_query(json!({"$filter": {"lower(b)": null}}), 1, &cache);
+
+    test_query_record(
+        json!({
+            "$filter": {"lower(b)": {"$gt": "mega"}},
+            "$order_by": {"lower(b)": "asc"}
+        }),
+        vec![(0, 1, "Yuri".to_string(), 521)],
+        &cache,
+    );
+
+    // Deleted records are removed from the expression index.
+    cache
+        .delete(&Record::new(vec![
+            Field::Int(2),
+            Field::String("MEGA".to_string()),
+            Field::Int(522),
+        ]))
+        .unwrap();
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+    test_query(json!({"$filter": {"lower(b)": "mega"}}), 1, &cache);
+}
+
 #[test]
 fn query_secondary_multi_indices() {
     let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_multi_indices);
@@ -231,6 +421,92 @@ fn query_secondary_multi_indices() {
     );
 }
 
+#[test]
+fn query_parallel_scan() {
+    let mut cache = LmdbRwCache::new(
+        Some(&schema_1()),
+        None,
+        &CacheOptions {
+            parallel_scan_threshold: Some(3),
+            parallel_scan_chunk_size: 3,
+            ..Default::default()
+        },
+        Default::default(),
+        Arc::new(Mutex::new(IndexingThreadPool::new(1))),
+    )
+    .unwrap();
+
+    for a in 0..10 {
+        insert_rec_1(&mut cache, (a, Some(a.to_string()), Some(a)));
+    }
+    cache
+        .delete(&Record::new(vec![
+            Field::Int(4),
+            Field::String("4".to_string()),
+            Field::Int(4),
+        ]))
+        .unwrap();
+    cache.commit().unwrap();
+
+    let get_a = |records: Vec<CacheRecord>| {
+        records
+            .into_iter()
+            .map(|record| record.record.values[0].clone())
+            .collect::<Vec<_>>()
+    };
+
+    let records = cache.query(&QueryExpression::with_no_limit()).unwrap();
+    assert_eq!(
+        get_a(records),
+        [0, 1, 2, 3, 5, 6, 7, 8, 9].map(Field::Int).to_vec()
+    );
+
+    let query = QueryExpression::new(None, Default::default(), Some(5), Skip::Skip(2));
+    let records = cache.query(&query).unwrap();
+    assert_eq!(get_a(records), [2, 3, 5, 6, 7].map(Field::Int).to_vec());
+}
+
+#[test]
+fn query_for_each_record() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+    for a in 0..10 {
+        insert_rec_1(&mut cache, (a, Some((a % 2).to_string()), Some(a)));
+    }
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    for query in [
+        QueryExpression::with_no_limit(),
+        query_from_filter(FilterExpression::Simple(
+            "b".into(),
+            Operator::EQ,
+            Value::from("1"),
+        )),
+    ] {
+        let mut records = vec![];
+        for_each_record(&cache, &query, |record| {
+            records.push(record);
+            Ok::<_, crate::errors::CacheError>(())
+        })
+        .unwrap();
+        assert_eq!(records, cache.query(&query).unwrap());
+    }
+
+    // The `RoCache` method stops when the callback breaks.
+    let query = QueryExpression::with_no_limit();
+    let mut records = vec![];
+    RoCache::for_each_record(&cache, &query, &mut |record| {
+        records.push(record);
+        if records.len() == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+    assert_eq!(records, cache.query(&query).unwrap()[..3]);
+}
+
 fn test_query_err(query: Value, cache: &dyn RwCache) {
     let query = from_value::<QueryExpression>(query).unwrap();
     let count_result = cache.count(&query);
@@ -269,3 +545,46 @@ fn test_query_record(query: Value, expected: Vec<(u64, i64, String, i64)>, cache
         .collect::<Vec<_>>();
     assert_eq!(records, expected);
 }
+
+#[test]
+fn query_timeout() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+    insert_rec_1(&mut cache, (1, Some("a".to_string()), Some(1)));
+    insert_rec_1(&mut cache, (2, Some("b".to_string()), Some(2)));
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    let expired = QueryExpression {
+        deadline: Some(Instant::now()),
+        ..query_from_filter(FilterExpression::Simple(
+            "a".to_string(),
+            Operator::GT,
+            Value::from(0),
+        ))
+    };
+    assert!(matches!(
+        cache.query(&expired),
+        Err(CacheError::QueryTimeout)
+    ));
+    assert!(matches!(
+        cache.count(&expired),
+        Err(CacheError::QueryTimeout)
+    ));
+
+    // Skipped ids don't hide the timeout.
+    let expired = QueryExpression {
+        deadline: Some(Instant::now()),
+        skip: Skip::Skip(1),
+        ..QueryExpression::with_no_limit()
+    };
+    assert!(matches!(
+        cache.query(&expired),
+        Err(CacheError::QueryTimeout)
+    ));
+
+    let unexpired = QueryExpression {
+        deadline: Some(Instant::now() + Duration::from_secs(60)),
+        ..QueryExpression::with_no_limit()
+    };
+    assert_eq!(cache.query(&unexpired).unwrap().len(), 2);
+}
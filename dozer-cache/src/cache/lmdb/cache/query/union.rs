@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+use roaring::RoaringTreemap;
+
+/// Merges `iterators` by the keys of their ids, returning every id once.
+///
+/// The union is sorted by `compare` if every iterator is. Ids with equal keys are returned in ascending order.
+pub fn union<E, I, K, F, C>(iterators: Vec<I>, key: F, compare: C) -> Union<E, I, K, F, C>
+where
+    I: Iterator<Item = Result<u64, E>>,
+    F: FnMut(u64) -> Result<K, E>,
+    C: FnMut(&K, &K) -> Ordering,
+{
+    let heads = iterators.iter().map(|_| None).collect();
+    let to_advance = (0..iterators.len()).collect();
+    Union {
+        iterators,
+        heads,
+        to_advance,
+        returned_ids: RoaringTreemap::new(),
+        key,
+        compare,
+    }
+}
+
+pub struct Union<E, I: Iterator<Item = Result<u64, E>>, K, F, C> {
+    iterators: Vec<I>,
+    /// The next id of every iterator with its key, `None` if the iterator is exhausted.
+    heads: Vec<Option<(K, u64)>>,
+    /// Iterators to advance before picking the next id.
+    to_advance: Vec<usize>,
+    returned_ids: RoaringTreemap,
+    key: F,
+    compare: C,
+}
+
+impl<E, I, K, F, C> Iterator for Union<E, I, K, F, C>
+where
+    I: Iterator<Item = Result<u64, E>>,
+    F: FnMut(u64) -> Result<K, E>,
+    C: FnMut(&K, &K) -> Ordering,
+{
+    type Item = Result<u64, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while let Some(index) = self.to_advance.pop() {
+                let head = match self.iterators[index].next() {
+                    Some(Ok(id)) => match (self.key)(id) {
+                        Ok(key) => Some((key, id)),
+                        Err(e) => return Some(Err(e)),
+                    },
+                    Some(Err(e)) => {
+                        // Errors like timeouts repeat, so they are not lost if this one is skipped.
+                        self.to_advance.push(index);
+                        return Some(Err(e));
+                    }
+                    None => None,
+                };
+                self.heads[index] = head;
+            }
+
+            let compare = &mut self.compare;
+            let (index, _) = self
+                .heads
+                .iter()
+                .enumerate()
+                .filter_map(|(index, head)| head.as_ref().map(|head| (index, head)))
+                .min_by(|(_, (key_a, id_a)), (_, (key_b, id_b))| {
+                    compare(key_a, key_b).then(id_a.cmp(id_b))
+                })?;
+            let (_, id) = self.heads[index].take().expect("We just found this head");
+            self.to_advance.push(index);
+            if self.returned_ids.insert(id) {
+                return Some(Ok(id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let a = vec![1, 3, 5, 7];
+        let b = vec![2, 3, 6, 7, 8];
+        let c = vec![];
+        let union = union(
+            vec![
+                a.into_iter().map(Ok),
+                b.into_iter().map(Ok),
+                c.into_iter().map(Ok),
+            ],
+            |_| Ok(()),
+            |_: &(), _: &()| Ordering::Equal,
+        );
+        assert_eq!(
+            union.collect::<Result<Vec<_>, Infallible>>().unwrap(),
+            vec![1, 2, 3, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_sorted_union() {
+        // Ids sorted by descending key, the key of an id being `id % 10`.
+        let a = vec![19, 7, 5];
+        let b = vec![9, 8, 7, 15, 1];
+        let union = union(
+            vec![a.into_iter().map(Ok), b.into_iter().map(Ok)],
+            |id| Ok::<_, Infallible>(id % 10),
+            |a: &u64, b: &u64| b.cmp(a),
+        );
+        assert_eq!(
+            union.collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![9, 19, 8, 7, 5, 15, 1]
+        );
+    }
+}
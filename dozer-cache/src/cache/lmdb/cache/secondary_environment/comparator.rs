@@ -1,27 +1,16 @@
 use dozer_storage::errors::StorageError;
 use dozer_storage::lmdb::{Database, Transaction};
-use dozer_storage::lmdb_sys::{mdb_set_compare, MDB_cmp_func, MDB_val, MDB_SUCCESS};
+use dozer_storage::lmdb_sys::{mdb_set_compare, MDB_val, MDB_SUCCESS};
 
 use crate::cache::index::compare_composite_secondary_index;
 
-pub fn set_sorted_inverted_comparator<T: Transaction>(
-    txn: &T,
-    db: Database,
-    fields: &[usize],
-) -> Result<(), StorageError> {
-    let comparator: MDB_cmp_func = if fields.len() == 1 {
-        None
-    } else {
-        Some(compare_composite_key)
-    };
-
-    if let Some(comparator) = comparator {
-        unsafe {
-            assert_eq!(
-                mdb_set_compare(txn.txn(), db.dbi(), Some(comparator)),
-                MDB_SUCCESS
-            );
-        }
+/// Makes `db` compare its keys as composite secondary index keys, see `compare_composite_secondary_index`.
+pub fn set_composite_comparator<T: Transaction>(txn: &T, db: Database) -> Result<(), StorageError> {
+    unsafe {
+        assert_eq!(
+            mdb_set_compare(txn.txn(), db.dbi(), Some(compare_composite_key)),
+            MDB_SUCCESS
+        );
     }
     Ok(())
 }
@@ -127,7 +116,7 @@ mod tests {
     }
 
     #[test]
-    fn test_set_sorted_inverted_comparator() {
+    fn test_set_composite_comparator() {
         let mut check_single = get_single_key_checker();
         check_single(Some(1), Some(1), Equal);
         check_single(Some(1), Some(2), Less);
@@ -136,24 +125,25 @@ mod tests {
         check_single(None, Some(1), Greater);
         check_single(None, None, Equal);
 
-        let check_composite = get_composite_key_checker(2);
+        let check_composite = get_composite_key_checker();
         check_test_cases(check_composite);
     }
 
-    fn setup(num_fields: usize) -> (RwLmdbEnvironment, Database) {
+    fn setup(is_composite: bool) -> (RwLmdbEnvironment, Database) {
         let mut env = utils::create_env(&Default::default()).unwrap().0;
         let db = env
             .create_database(Some("test"), DatabaseFlags::DUP_SORT)
             .unwrap();
-        let fields = (0..num_fields).collect::<Vec<_>>();
         let txn = env.begin_txn().unwrap();
-        set_sorted_inverted_comparator(&txn, db, &fields).unwrap();
+        if is_composite {
+            set_composite_comparator(&txn, db).unwrap();
+        }
         txn.commit().unwrap();
         (env, db)
     }
 
     fn get_single_key_checker() -> impl FnMut(Option<i64>, Option<i64>, Ordering) {
-        let (env, db) = setup(1);
+        let (env, db) = setup(false);
         move |a: Option<i64>, b: Option<i64>, expected: Ordering| {
             let serialize =
                 |a: Option<i64>| get_secondary_index(&[&a.map_or(Field::Null, Field::Int)], true);
@@ -175,10 +165,8 @@ mod tests {
         }
     }
 
-    fn get_composite_key_checker<'a>(
-        num_fields: usize,
-    ) -> impl FnMut(&[i64], &[i64], Ordering) + 'a {
-        let (env, db) = setup(num_fields);
+    fn get_composite_key_checker<'a>() -> impl FnMut(&[i64], &[i64], Ordering) + 'a {
+        let (env, db) = setup(true);
         move |a: &[i64], b: &[i64], expected: Ordering| {
             let serialize = |a: &[i64]| {
                 let fields = a.iter().map(|a| Field::Int(*a)).collect::<Vec<_>>();
@@ -205,7 +193,7 @@ mod tests {
 
     #[test]
     fn null_is_greater_than_other_thing() {
-        let (env, db) = setup(1);
+        let (env, db) = setup(false);
         let txn = env.begin_txn().unwrap();
         let check = |field: &Field| {
             let serialize = |a| get_secondary_index(&[a], true);
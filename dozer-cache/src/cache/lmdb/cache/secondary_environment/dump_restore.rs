@@ -8,9 +8,9 @@ use tokio::io::AsyncRead;
 use crate::{cache::lmdb::utils::create_env, errors::CacheError};
 
 use super::{
-    get_cache_options, set_comparator, CacheOptions, RwSecondaryEnvironment, SecondaryEnvironment,
-    SecondaryEnvironmentCommon, DATABASE_DB_NAME, INDEX_DEFINITION_DB_NAME,
-    NEXT_OPERATION_ID_DB_NAME,
+    appended_primary_index, get_cache_options, set_comparator, CacheOptions,
+    RwSecondaryEnvironment, SecondaryEnvironment, SecondaryEnvironmentCommon, DATABASE_DB_NAME,
+    INDEX_DEFINITION_DB_NAME, NEXT_OPERATION_ID_DB_NAME,
 };
 
 pub async fn dump<'txn, E: SecondaryEnvironment, T: Transaction>(
@@ -42,6 +42,7 @@ pub async fn dump<'txn, E: SecondaryEnvironment, T: Transaction>(
 }
 
 pub async fn restore(
+    primary_index: &[usize],
     name: String,
     options: &CacheOptions,
     reader: &mut (impl AsyncRead + Unpin),
@@ -65,12 +66,14 @@ pub async fn restore(
         .map(IntoOwned::into_owned)
         .ok_or(CacheError::IndexDefinitionNotFound(name))?;
 
-    set_comparator(&env, &index_definition, database)?;
+    let primary_index = appended_primary_index(&index_definition, primary_index);
+    set_comparator(&env, &index_definition, &primary_index, database)?;
 
     Ok(RwSecondaryEnvironment {
         env,
         common: SecondaryEnvironmentCommon {
             index_definition,
+            primary_index,
             index_definition_option,
             database,
             next_operation_id,
@@ -103,6 +106,7 @@ pub mod tests {
             env1.common().index_definition,
             env2.common().index_definition
         );
+        assert_eq!(env1.common().primary_index, env2.common().primary_index);
         let txn1 = env1.begin_txn().unwrap();
         let txn2 = env2.begin_txn().unwrap();
         assert_database_equal(
@@ -133,6 +137,7 @@ pub mod tests {
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             }],
             primary_index: vec![0],
         };
@@ -159,6 +164,7 @@ pub mod tests {
 
         let mut env = RwSecondaryEnvironment::new(
             &IndexDefinition::SortedInverted(vec![0]),
+            &[0],
             "0".to_string(),
             &Default::default(),
         )
@@ -186,9 +192,14 @@ pub mod tests {
             }
         }
 
-        let restored_env = restore("0".to_string(), &Default::default(), &mut data.as_slice())
-            .await
-            .unwrap();
+        let restored_env = restore(
+            &[0],
+            "0".to_string(),
+            &Default::default(),
+            &mut data.as_slice(),
+        )
+        .await
+        .unwrap();
         assert_secondary_env_equal(&env, &restored_env);
     }
 }
@@ -1,7 +1,7 @@
 use crate::errors::{CacheError, IndexError};
 
 use dozer_storage::lmdb::RwTransaction;
-use dozer_types::types::{Field, IndexDefinition, Record};
+use dozer_types::types::{Field, IndexDefinition, IndexExpression, Record};
 
 use dozer_storage::LmdbMultimap;
 
@@ -15,11 +15,12 @@ pub fn build_index(
     database: LmdbMultimap<Vec<u8>, u64>,
     record: &Record,
     index_definition: &IndexDefinition,
+    primary_index: &[usize],
     operation_id: u64,
 ) -> Result<(), CacheError> {
     match index_definition {
         IndexDefinition::SortedInverted(fields) => {
-            let secondary_key = build_index_sorted_inverted(fields, &record.values);
+            let secondary_key = build_index_sorted_inverted(fields, primary_index, &record.values);
             // Ignore existing pair.
             database.insert(txn, &secondary_key, &operation_id)?;
         }
@@ -29,6 +30,11 @@ pub fn build_index(
                 database.insert(txn, &secondary_key, &operation_id)?;
             }
         }
+        IndexDefinition::Expression(expression) => {
+            let secondary_key = build_index_expression(expression, primary_index, &record.values);
+            // Ignore existing pair.
+            database.insert(txn, &secondary_key, &operation_id)?;
+        }
     }
     Ok(())
 }
@@ -38,11 +44,12 @@ pub fn delete_index(
     database: LmdbMultimap<Vec<u8>, u64>,
     record: &Record,
     index_definition: &IndexDefinition,
+    primary_index: &[usize],
     operation_id: u64,
 ) -> Result<(), CacheError> {
     match index_definition {
         IndexDefinition::SortedInverted(fields) => {
-            let secondary_key = build_index_sorted_inverted(fields, &record.values);
+            let secondary_key = build_index_sorted_inverted(fields, primary_index, &record.values);
             // Ignore if not found.
             database.remove(txn, &secondary_key, &operation_id)?;
         }
@@ -52,17 +59,40 @@ pub fn delete_index(
                 database.remove(txn, &secondary_key, &operation_id)?;
             }
         }
+        IndexDefinition::Expression(expression) => {
+            let secondary_key = build_index_expression(expression, primary_index, &record.values);
+            // Ignore if not found.
+            database.remove(txn, &secondary_key, &operation_id)?;
+        }
     }
     Ok(())
 }
 
-fn build_index_sorted_inverted(fields: &[usize], values: &[Field]) -> Vec<u8> {
+fn build_index_sorted_inverted(
+    fields: &[usize],
+    primary_index: &[usize],
+    values: &[Field],
+) -> Vec<u8> {
     let values = fields
         .iter()
+        .chain(primary_index)
         .copied()
         .filter_map(|index| (values.get(index)))
         .collect::<Vec<_>>();
-    // `values.len() == 1` criteria must be kept the same with `comparator.rs`.
+    // `values.len() == 1` criteria must be kept the same with `num_indexed_fields`.
+    index::get_secondary_index(&values, values.len() == 1)
+}
+
+fn build_index_expression(
+    expression: &IndexExpression,
+    primary_index: &[usize],
+    values: &[Field],
+) -> Vec<u8> {
+    let value = expression.evaluate(values);
+    let values = std::iter::once(&value)
+        .chain(primary_index.iter().filter_map(|index| values.get(*index)))
+        .collect::<Vec<_>>();
+    // `values.len() == 1` criteria must be kept the same with `num_indexed_fields`.
     index::get_secondary_index(&values, values.len() == 1)
 }
 
@@ -3,12 +3,20 @@ use dozer_storage::{
     lmdb_storage::{RoLmdbEnvironment, RwLmdbEnvironment},
     LmdbCounter, LmdbEnvironment, LmdbMultimap, LmdbOption,
 };
-use dozer_types::{borrow::IntoOwned, labels::Labels, log::debug, types::IndexDefinition};
+use dozer_types::{
+    borrow::{Borrow, IntoOwned},
+    labels::Labels,
+    log::debug,
+    types::IndexDefinition,
+};
 use metrics::increment_counter;
 
 use crate::{
-    cache::lmdb::utils::{create_env, open_env},
-    errors::CacheError,
+    cache::{
+        index::composite_secondary_index_prefix,
+        lmdb::utils::{create_env, open_env},
+    },
+    errors::{CacheError, IndexError},
 };
 
 use super::{
@@ -24,6 +32,9 @@ pub type SecondaryIndexDatabase = LmdbMultimap<Vec<u8>, u64>;
 #[derive(Debug, Clone)]
 pub struct SecondaryEnvironmentCommon {
     pub index_definition: IndexDefinition,
+    /// The primary key fields appended to the keys after the indexed fields, so entries with equal indexed fields are
+    /// in primary key order. Empty for full text indexes, see `appended_primary_index`.
+    pub primary_index: Vec<usize>,
     pub index_definition_option: LmdbOption<IndexDefinition>,
     pub database: SecondaryIndexDatabase,
     pub next_operation_id: LmdbCounter,
@@ -44,11 +55,40 @@ pub trait SecondaryEnvironment: LmdbEnvironment {
         self.common().database
     }
 
+    /// See `num_indexed_fields`.
+    fn num_indexed_fields(&self) -> Option<usize> {
+        num_indexed_fields(self.index_definition(), &self.common().primary_index)
+    }
+
     fn count_data(&self) -> Result<usize, CacheError> {
         let txn = self.begin_txn()?;
         self.database().count_data(&txn).map_err(Into::into)
     }
 
+    /// Counts the distinct values of the indexed fields, ignoring the primary key fields appended to the keys.
+    fn count_distinct_keys<T: Transaction>(&self, txn: &T) -> Result<usize, CacheError> {
+        let database = self.database();
+        let num_indexed_fields = match self.num_indexed_fields() {
+            Some(num_indexed_fields) if !self.common().primary_index.is_empty() => {
+                num_indexed_fields
+            }
+            _ => return database.count_keys(txn).map_err(Into::into),
+        };
+        let mut count = 0;
+        let mut last_prefix = None;
+        for result in database.iter(txn)? {
+            let (key, _) = result?;
+            let prefix = composite_secondary_index_prefix(key.borrow(), num_indexed_fields)
+                .map_err(IndexError::InvalidKey)?
+                .to_vec();
+            if last_prefix.as_ref() != Some(&prefix) {
+                count += 1;
+                last_prefix = Some(prefix);
+            }
+        }
+        Ok(count)
+    }
+
     fn next_operation_id<T: Transaction>(&self, txn: &T) -> Result<u64, CacheError> {
         self.common()
             .next_operation_id
@@ -78,6 +118,7 @@ impl SecondaryEnvironment for RwSecondaryEnvironment {
 impl RwSecondaryEnvironment {
     pub fn new(
         index_definition: &IndexDefinition,
+        primary_index: &[usize],
         name: String,
         options: &CacheOptions,
     ) -> Result<Self, CacheError> {
@@ -106,12 +147,14 @@ impl RwSecondaryEnvironment {
             index_definition.clone()
         };
 
-        set_comparator(&env, &index_definition, database)?;
+        let primary_index = appended_primary_index(&index_definition, primary_index);
+        set_comparator(&env, &index_definition, &primary_index, database)?;
 
         Ok(Self {
             env,
             common: SecondaryEnvironmentCommon {
                 index_definition,
+                primary_index,
                 index_definition_option,
                 database,
                 next_operation_id,
@@ -157,6 +200,7 @@ impl RwSecondaryEnvironment {
                         self.common.database,
                         &record,
                         &self.common.index_definition,
+                        &self.common.primary_index,
                         operation_id,
                     )?;
                 }
@@ -177,6 +221,7 @@ impl RwSecondaryEnvironment {
                         self.common.database,
                         &record,
                         &self.common.index_definition,
+                        &self.common.primary_index,
                         operation_id,
                     )?;
                 }
@@ -211,7 +256,11 @@ impl SecondaryEnvironment for RoSecondaryEnvironment {
 }
 
 impl RoSecondaryEnvironment {
-    pub fn new(name: String, options: &CacheOptions) -> Result<Self, CacheError> {
+    pub fn new(
+        primary_index: &[usize],
+        name: String,
+        options: &CacheOptions,
+    ) -> Result<Self, CacheError> {
         let env = open_env(&get_cache_options(name.clone(), options))?.0;
 
         let database = LmdbMultimap::open(&env, Some(DATABASE_DB_NAME))?;
@@ -223,11 +272,13 @@ impl RoSecondaryEnvironment {
             .map(IntoOwned::into_owned)
             .ok_or(CacheError::IndexDefinitionNotFound(name))?;
 
-        set_comparator(&env, &index_definition, database)?;
+        let primary_index = appended_primary_index(&index_definition, primary_index);
+        set_comparator(&env, &index_definition, &primary_index, database)?;
         Ok(Self {
             env,
             common: SecondaryEnvironmentCommon {
                 index_definition,
+                primary_index,
                 index_definition_option,
                 database,
                 next_operation_id,
@@ -248,13 +299,47 @@ fn get_cache_options(name: String, options: &CacheOptions) -> CacheOptions {
     CacheOptions { path, ..*options }
 }
 
+/// The primary key fields `index_definition` appends to its keys, see `SecondaryEnvironmentCommon::primary_index`.
+/// Fields that are indexed already are not appended again.
+fn appended_primary_index(
+    index_definition: &IndexDefinition,
+    primary_index: &[usize],
+) -> Vec<usize> {
+    match index_definition {
+        IndexDefinition::SortedInverted(fields) => primary_index
+            .iter()
+            .copied()
+            .filter(|index| !fields.contains(index))
+            .collect(),
+        IndexDefinition::Expression(_) => primary_index.to_vec(),
+        IndexDefinition::FullText(_) => vec![],
+    }
+}
+
+/// The number of indexed fields at the start of the composite keys of an index with `primary_index` appended, or
+/// `None` if the keys are a single field, compared as they're encoded.
+///
+/// The criteria must be kept the same with `indexer.rs`, which builds the keys.
+pub fn num_indexed_fields(
+    index_definition: &IndexDefinition,
+    primary_index: &[usize],
+) -> Option<usize> {
+    let num_fields = match index_definition {
+        IndexDefinition::SortedInverted(fields) => fields.len(),
+        IndexDefinition::Expression(_) => 1,
+        IndexDefinition::FullText(_) => return None,
+    };
+    (num_fields + primary_index.len() != 1).then_some(num_fields)
+}
+
 fn set_comparator<E: LmdbEnvironment>(
     env: &E,
     index_definition: &IndexDefinition,
+    primary_index: &[usize],
     database: SecondaryIndexDatabase,
 ) -> Result<(), CacheError> {
-    if let IndexDefinition::SortedInverted(fields) = index_definition {
-        comparator::set_sorted_inverted_comparator(&env.begin_txn()?, database.database(), fields)?;
+    if num_indexed_fields(index_definition, primary_index).is_some() {
+        comparator::set_composite_comparator(&env.begin_txn()?, database.database())?;
     }
     Ok(())
 }
@@ -1,4 +1,6 @@
 use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
 use std::{path::PathBuf, sync::Arc};
 
 use dozer_storage::{lmdb_storage::LmdbEnvironmentManager, LmdbMap, RwLmdbEnvironment};
@@ -6,12 +8,12 @@ use dozer_types::labels::Labels;
 use dozer_types::parking_lot::RwLock;
 use dozer_types::{
     parking_lot::Mutex,
-    types::{IndexDefinition, Schema},
+    types::{IndexDefinition, Schema, SchemaWithIndex},
 };
 use tempdir::TempDir;
 use tokio::io::AsyncRead;
 
-use crate::cache::CacheWriteOptions;
+use crate::cache::{partition_labels, CacheWriteOptions, PartitionedCache};
 use crate::{
     cache::{RoCache, RoCacheManager, RwCache, RwCacheManager},
     errors::CacheError,
@@ -36,11 +38,24 @@ pub struct CacheManagerOptions {
     /// The chunk size when calculating intersection of index queries.
     pub intersection_chunk_size: usize,
 
+    /// Full scans that can return more records than this decode their records on multiple threads. Disabled if `None`.
+    pub parallel_scan_threshold: Option<usize>,
+
+    /// The number of records each thread decodes when a full scan is partitioned across threads.
+    pub parallel_scan_chunk_size: usize,
+
+    /// How long collected index statistics are reused by the query planner before they're collected again.
+    pub index_stats_ttl: Duration,
+
     /// Provide a path where db will be created. If nothing is provided, will default to a temp directory.
     pub path: Option<PathBuf>,
 
     /// Number of threads in the indexing thread pool.
     pub num_indexing_threads: usize,
+
+    /// Directories the partitions of partitioned caches are spread over, partition `i` being in
+    /// `partition_paths[i % partition_paths.len()]`. If empty, partitions are created in `path`.
+    pub partition_paths: Vec<PathBuf>,
 }
 
 impl Default for CacheManagerOptions {
@@ -50,9 +65,13 @@ impl Default for CacheManagerOptions {
             max_readers: cache_options.max_readers,
             max_db_size: cache_options.max_db_size,
             intersection_chunk_size: cache_options.intersection_chunk_size,
+            parallel_scan_threshold: cache_options.parallel_scan_threshold,
+            parallel_scan_chunk_size: cache_options.parallel_scan_chunk_size,
+            index_stats_ttl: cache_options.index_stats_ttl,
             max_size: cache_options.max_size,
             path: None,
             num_indexing_threads: 4,
+            partition_paths: vec![],
         }
     }
 }
@@ -81,11 +100,33 @@ impl LmdbRoCacheManager {
 
 // HACK: We're leaking internal types here.
 pub use super::cache::dump_restore::{begin_dump_txn, dump};
+pub use super::cache::for_each_record;
 
 impl RoCacheManager for LmdbRoCacheManager {
     fn open_ro_cache(&self, labels: Labels) -> Result<Option<Box<dyn RoCache>>, CacheError> {
-        self.open_lmdb_cache(labels)
-            .map(|cache| cache.map(|cache| Box::new(cache) as _))
+        if let Some(cache) = self.open_lmdb_cache(labels.clone())? {
+            return Ok(Some(Box::new(cache)));
+        }
+
+        let num_partitions = num_partitions(&self.options, &self.base_path, &labels);
+        if num_partitions == 0 {
+            return Ok(None);
+        }
+        let partitions = (0..num_partitions)
+            .map(|partition| {
+                LmdbRoCache::new(&partition_cache_options(
+                    &self.options,
+                    &self.base_path,
+                    &labels,
+                    partition,
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Box::new(PartitionedCache::new(
+            labels,
+            partitions,
+            Default::default(),
+        )?)))
     }
 }
 
@@ -112,6 +153,9 @@ impl LmdbRwCacheManager {
                 (Some(temp_dir), base_path)
             }
         };
+        for path in &options.partition_paths {
+            std::fs::create_dir_all(path).map_err(|e| CacheError::Io(path.clone(), e))?;
+        }
 
         let mut env = LmdbEnvironmentManager::create_rw(
             &base_path,
@@ -156,17 +200,71 @@ impl LmdbRwCacheManager {
         .await?;
         Ok(())
     }
-}
 
-impl RoCacheManager for LmdbRwCacheManager {
-    fn open_ro_cache(&self, labels: Labels) -> Result<Option<Box<dyn RoCache>>, CacheError> {
+    fn open_lmdb_ro_cache(&self, labels: Labels) -> Result<Option<LmdbRoCache>, CacheError> {
         // Check if the cache is already opened.
         if let Some(cache) = self.indexing_thread_pool.lock().find_cache(&labels) {
-            return Ok(Some(Box::new(cache) as _));
+            return Ok(Some(cache));
         }
 
         open_ro_cache(self.base_path.clone(), labels, &self.options)
-            .map(|cache| cache.map(|cache| Box::new(cache) as _))
+    }
+
+    fn open_partitioned_rw_cache(
+        &self,
+        labels: Labels,
+        schema: Option<&SchemaWithIndex>,
+        connections: Option<&HashSet<String>>,
+        write_options: CacheWriteOptions,
+        num_partitions: usize,
+    ) -> Result<PartitionedCache<LmdbRwCache>, CacheError> {
+        let partitions = (0..num_partitions)
+            .map(|partition| {
+                LmdbRwCache::new(
+                    schema,
+                    connections,
+                    &partition_cache_options(&self.options, &self.base_path, &labels, partition),
+                    write_options,
+                    self.indexing_thread_pool.clone(),
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        PartitionedCache::new(labels, partitions, write_options)
+    }
+}
+
+impl RoCacheManager for LmdbRwCacheManager {
+    fn open_ro_cache(&self, labels: Labels) -> Result<Option<Box<dyn RoCache>>, CacheError> {
+        if let Some(cache) = self.open_lmdb_ro_cache(labels.clone())? {
+            return Ok(Some(Box::new(cache)));
+        }
+
+        let num_partitions = num_partitions(&self.options, &self.base_path, &labels);
+        if num_partitions == 0 {
+            return Ok(None);
+        }
+        let partitions = (0..num_partitions)
+            .map(|partition| {
+                let cache = self
+                    .indexing_thread_pool
+                    .lock()
+                    .find_cache(&partition_labels(&labels, partition));
+                match cache {
+                    Some(cache) => Ok(cache),
+                    None => LmdbRoCache::new(&partition_cache_options(
+                        &self.options,
+                        &self.base_path,
+                        &labels,
+                        partition,
+                    )),
+                }
+            })
+            .collect::<Result<_, CacheError>>()?;
+        Ok(Some(Box::new(PartitionedCache::new(
+            labels,
+            partitions,
+            Default::default(),
+        )?)))
     }
 }
 
@@ -187,7 +285,16 @@ impl RwCacheManager for LmdbRwCacheManager {
                 )?;
                 Some(Box::new(cache))
             } else {
-                None
+                match num_partitions(&self.options, &self.base_path, &labels) {
+                    0 => None,
+                    num_partitions => Some(Box::new(self.open_partitioned_rw_cache(
+                        labels,
+                        None,
+                        None,
+                        write_options,
+                        num_partitions,
+                    )?)),
+                }
             };
         Ok(cache)
     }
@@ -210,6 +317,25 @@ impl RwCacheManager for LmdbRwCacheManager {
         Ok(Box::new(cache))
     }
 
+    fn create_partitioned_cache(
+        &self,
+        labels: Labels,
+        schema: Schema,
+        indexes: Vec<IndexDefinition>,
+        connections: &HashSet<String>,
+        write_options: CacheWriteOptions,
+        num_partitions: usize,
+    ) -> Result<Box<dyn RwCache>, CacheError> {
+        let cache = self.open_partitioned_rw_cache(
+            labels,
+            Some(&(schema, indexes)),
+            Some(connections),
+            write_options,
+            num_partitions.max(1),
+        )?;
+        Ok(Box::new(cache))
+    }
+
     fn create_alias(&self, name: &str, alias: &str) -> Result<(), CacheError> {
         let mut env = self.env.write();
         self.alias_to_real_name
@@ -231,10 +357,51 @@ fn cache_options(
         max_readers: options.max_readers,
         max_size: options.max_size,
         intersection_chunk_size: options.intersection_chunk_size,
+        parallel_scan_threshold: options.parallel_scan_threshold,
+        parallel_scan_chunk_size: options.parallel_scan_chunk_size,
+        index_stats_ttl: options.index_stats_ttl,
         path: Some((base_path, labels)),
     }
 }
 
+/// The directory of partition `partition` of partitioned caches.
+fn partition_base_path(
+    options: &CacheManagerOptions,
+    base_path: &Path,
+    partition: usize,
+) -> PathBuf {
+    if options.partition_paths.is_empty() {
+        base_path.to_path_buf()
+    } else {
+        options.partition_paths[partition % options.partition_paths.len()].clone()
+    }
+}
+
+fn partition_cache_options(
+    options: &CacheManagerOptions,
+    base_path: &Path,
+    labels: &Labels,
+    partition: usize,
+) -> CacheOptions {
+    cache_options(
+        options,
+        partition_base_path(options, base_path, partition),
+        partition_labels(labels, partition),
+    )
+}
+
+/// The number of partitions of the partitioned cache with `labels`, 0 if there's no such cache.
+fn num_partitions(options: &CacheManagerOptions, base_path: &Path, labels: &Labels) -> usize {
+    (0..)
+        .take_while(|&partition| {
+            LmdbEnvironmentManager::exists(
+                &partition_base_path(options, base_path, partition),
+                &partition_labels(labels, partition).to_non_empty_string(),
+            )
+        })
+        .count()
+}
+
 fn open_ro_cache(
     base_path: PathBuf,
     labels: Labels,
@@ -285,4 +452,161 @@ mod tests {
             &labels
         );
     }
+    #[test]
+    fn test_partitioned_cache() {
+        use crate::cache::expression::{
+            FilterExpression, Operator, QueryExpression, Skip, SortDirection, SortOption,
+        };
+        use crate::cache::test_utils::schema_1;
+        use dozer_types::serde_json::json;
+        use dozer_types::types::{Field, Record};
+
+        let cache_manager = LmdbRwCacheManager::new(Default::default()).unwrap();
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        let (schema, indexes) = schema_1();
+        let mut cache = cache_manager
+            .create_partitioned_cache(
+                labels.clone(),
+                schema,
+                indexes,
+                &Default::default(),
+                Default::default(),
+                3,
+            )
+            .unwrap();
+        for a in 0..20 {
+            let record = Record::new(vec![Field::Int(a), Field::Null, Field::Int(a % 5)]);
+            cache.insert(&record).unwrap();
+        }
+        cache.set_metadata(1).unwrap();
+        cache.commit().unwrap();
+        cache_manager.wait_until_indexing_catchup();
+
+        let cache = cache_manager.open_ro_cache(labels).unwrap().unwrap();
+        assert_eq!(cache.get_metadata().unwrap(), Some(1));
+        let all = cache.query(&QueryExpression::with_no_limit()).unwrap();
+        let ids = all.iter().map(|record| record.id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 20);
+        assert_eq!(cache.count(&QueryExpression::with_no_limit()).unwrap(), 20);
+
+        let record = cache.get(&Field::Int(7).encode()).unwrap();
+        assert_eq!(record.record.values[0], Field::Int(7));
+        assert!(ids.contains(&record.id));
+
+        let query = QueryExpression::new(
+            None,
+            vec![SortOption::new("a".to_string(), SortDirection::Descending)],
+            Some(5),
+            Skip::Skip(5),
+        );
+        let values = cache
+            .query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.record.values[0].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(values, (10..15).rev().map(Field::Int).collect::<Vec<_>>());
+        assert_eq!(cache.count(&query).unwrap(), 5);
+
+        let query = QueryExpression::new(
+            Some(FilterExpression::Simple(
+                "c".to_string(),
+                Operator::EQ,
+                json!(1),
+            )),
+            vec![],
+            None,
+            Skip::Skip(0),
+        );
+        assert_eq!(cache.count(&query).unwrap(), 4);
+
+        // Pages after a record are the same as pages skipping the records before it.
+        let sorted = |limit, skip| {
+            QueryExpression::new(
+                None,
+                vec![SortOption::new("c".to_string(), SortDirection::Ascending)],
+                limit,
+                skip,
+            )
+        };
+        let first_page = cache.query(&sorted(Some(6), Skip::Skip(0))).unwrap();
+        let after = first_page.last().unwrap().id;
+        assert_eq!(
+            cache.query(&sorted(Some(6), Skip::After(after))).unwrap(),
+            cache.query(&sorted(Some(6), Skip::Skip(6))).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_partitioned_cache_skips_committed_operations() {
+        use crate::cache::expression::QueryExpression;
+        use crate::cache::test_utils::schema_1;
+        use crate::cache::UpsertResult;
+        use dozer_types::models::api_endpoint::OnInsertResolutionTypes;
+        use dozer_types::types::{Field, Record};
+
+        let cache_manager = LmdbRwCacheManager::new(Default::default()).unwrap();
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        let (schema, indexes) = schema_1();
+        let write_options = CacheWriteOptions {
+            insert_resolution: OnInsertResolutionTypes::Panic(()),
+            ..Default::default()
+        };
+        let record = Record::new(vec![Field::Int(1), Field::Null, Field::Int(1)]);
+        let all = QueryExpression::with_no_limit();
+
+        let mut cache = cache_manager
+            .create_partitioned_cache(
+                labels.clone(),
+                schema,
+                indexes,
+                &Default::default(),
+                write_options,
+                2,
+            )
+            .unwrap();
+        cache.set_operation_position(1);
+        cache.insert(&record).unwrap();
+        cache.set_metadata(2).unwrap();
+        cache.commit().unwrap();
+        drop(cache);
+
+        // Roll the partition without the record back, as if the commit stopped before committing it.
+        let empty_partition = (0..2)
+            .find(|partition| {
+                cache_manager
+                    .open_ro_cache(partition_labels(&labels, *partition))
+                    .unwrap()
+                    .unwrap()
+                    .count(&all)
+                    .unwrap()
+                    == 0
+            })
+            .unwrap();
+        let mut partition = cache_manager
+            .open_rw_cache(partition_labels(&labels, empty_partition), write_options)
+            .unwrap()
+            .unwrap();
+        partition.set_metadata(0).unwrap();
+        partition.commit().unwrap();
+        drop(partition);
+
+        // The insert is replayed from the earliest partition metadata, and skipped by the partition that committed it.
+        let mut cache = cache_manager
+            .open_rw_cache(labels, write_options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cache.get_metadata().unwrap(), Some(0));
+        cache.set_operation_position(1);
+        assert!(matches!(
+            cache.insert(&record).unwrap(),
+            UpsertResult::Ignored
+        ));
+        cache.set_metadata(2).unwrap();
+        cache.commit().unwrap();
+        assert_eq!(cache.get_metadata().unwrap(), Some(2));
+        assert_eq!(cache.count(&all).unwrap(), 1);
+    }
 }
@@ -74,6 +74,7 @@ impl IndexingThreadPool {
                 return Some(LmdbRoCache {
                     main_env: cache.main_env.clone(),
                     secondary_envs,
+                    index_stats: Default::default(),
                 });
             }
         }
@@ -219,5 +220,6 @@ fn secondary_index_type(secondary_env: &Mutex<RwSecondaryEnvironment>) -> &'stat
     match secondary_env.lock().index_definition() {
         IndexDefinition::SortedInverted(_) => "SortedInverted",
         IndexDefinition::FullText(_) => "FullText",
+        IndexDefinition::Expression(_) => "Expression",
     }
 }
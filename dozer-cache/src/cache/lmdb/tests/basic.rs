@@ -4,13 +4,14 @@ use crate::cache::{
     expression::{self, FilterExpression, QueryExpression, Skip},
     index,
     lmdb::{cache::LmdbRwCache, indexing::IndexingThreadPool},
+    plan::Plan,
     test_utils::{self, query_from_filter},
     RoCache, RwCache, UpsertResult,
 };
 use dozer_types::{
     parking_lot::Mutex,
     serde_json::Value,
-    types::{Field, Record, Schema},
+    types::{Field, IndexDefinition, Record, Schema},
 };
 
 use super::utils::create_cache;
@@ -184,3 +185,47 @@ fn test_cache_metadata() {
     cache.commit().unwrap();
     assert_eq!(cache.get_metadata().unwrap().unwrap(), 32);
 }
+
+#[test]
+fn test_cache_stats() {
+    let (mut cache, indexing_thread_pool, _) = _setup();
+    for val in ["foo", "bar"] {
+        cache
+            .insert(&Record::new(vec![Field::String(val.to_string())]))
+            .unwrap();
+    }
+    cache.set_metadata(7).unwrap();
+    cache.commit().unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    let stats = cache.get_stats().unwrap();
+    assert_eq!(stats.record_count, 2);
+    assert!(stats.total_bytes > 0);
+    assert_eq!(stats.last_updated, Some(7));
+    assert_eq!(stats.indexes.len(), 1);
+    assert_eq!(
+        stats.indexes[0].index_definition,
+        IndexDefinition::SortedInverted(vec![0])
+    );
+    assert_eq!(stats.indexes[0].entries, 2);
+    assert_eq!(stats.indexes[0].distinct_keys, 2);
+}
+
+#[test]
+fn test_cache_explain() {
+    let (cache, _, _) = _setup();
+
+    assert!(matches!(
+        cache.explain(&QueryExpression::with_no_limit()).unwrap(),
+        Plan::SeqScan(_)
+    ));
+    let query = query_from_filter(FilterExpression::Simple(
+        "foo".to_string(),
+        expression::Operator::EQ,
+        Value::from("bar".to_string()),
+    ));
+    assert!(matches!(
+        cache.explain(&query).unwrap(),
+        Plan::IndexScans(_)
+    ));
+}
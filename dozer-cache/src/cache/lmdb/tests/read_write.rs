@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::cache::expression::{FilterExpression, Operator, QueryExpression};
 use crate::cache::lmdb::cache::{CacheOptions, LmdbRoCache, LmdbRwCache};
@@ -27,6 +28,9 @@ fn read_and_write() {
             max_size: 1024 * 1024,
             path: Some(path.clone()),
             intersection_chunk_size: 1,
+            parallel_scan_threshold: Some(1),
+            parallel_scan_chunk_size: 1,
+            index_stats_ttl: Duration::from_secs(10),
         },
         Default::default(),
         indexing_thread_pool.clone(),
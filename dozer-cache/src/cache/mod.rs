@@ -1,8 +1,10 @@
 mod lmdb;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::ops::ControlFlow;
 
 use self::expression::QueryExpression;
+use self::plan::{Plan, PreparedPlan};
 use crate::errors::CacheError;
 use dozer_types::labels::Labels;
 use dozer_types::models::api_endpoint::{
@@ -13,11 +15,14 @@ use dozer_types::{
     types::{IndexDefinition, Record, Schema, SchemaWithIndex},
 };
 pub use lmdb::cache_manager::{
-    begin_dump_txn, dump, CacheManagerOptions, LmdbRoCacheManager, LmdbRwCacheManager,
+    begin_dump_txn, dump, for_each_record, CacheManagerOptions, LmdbRoCacheManager,
+    LmdbRwCacheManager,
 };
+pub use partitioned::{partition_labels, stable_hash, PartitionedCache};
 pub mod expression;
 mod index;
-mod plan;
+mod partitioned;
+pub mod plan;
 pub mod test_utils;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -73,6 +78,18 @@ pub trait RwCacheManager: RoCacheManager {
         write_options: CacheWriteOptions,
     ) -> Result<Box<dyn RwCache>, CacheError>;
 
+    /// Like `create_cache`, but hash partitions the records of the cache across `num_partitions` caches, which are
+    /// opened together by `open_rw_cache` and `open_ro_cache` with `labels`.
+    fn create_partitioned_cache(
+        &self,
+        labels: Labels,
+        schema: Schema,
+        indexes: Vec<IndexDefinition>,
+        connections: &HashSet<String>,
+        write_options: CacheWriteOptions,
+        num_partitions: usize,
+    ) -> Result<Box<dyn RwCache>, CacheError>;
+
     /// Creates an alias `alias` for a cache with name `name`.
     ///
     /// If `alias` already exists, it's overwritten. If cache with name `name` doesn't exist, the alias is still recorded.
@@ -90,10 +107,66 @@ pub trait RoCache: Send + Sync + Debug {
     fn get(&self, key: &[u8]) -> Result<CacheRecord, CacheError>;
     fn count(&self, query: &QueryExpression) -> Result<usize, CacheError>;
     fn query(&self, query: &QueryExpression) -> Result<Vec<CacheRecord>, CacheError>;
+    /// Like `count`, but binds `query` to `prepared` if it has a plan of the same shape, and prepares it otherwise.
+    fn count_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<usize, CacheError>;
+    /// Like `query`, but binds `query` to `prepared` if it has a plan of the same shape, and prepares it otherwise.
+    fn query_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<Vec<CacheRecord>, CacheError>;
+    /// Returns the plan that `count` and `query` use to execute `query`.
+    fn explain(&self, query: &QueryExpression) -> Result<Plan, CacheError>;
+    /// Like `query`, but calls `f` with every record in order until it breaks.
+    ///
+    /// Caches that can read all the records in one transaction, so they're a consistent snapshot of the cache,
+    /// without collecting them in memory. Others collect the records of `query` first.
+    fn for_each_record(
+        &self,
+        query: &QueryExpression,
+        f: &mut dyn FnMut(CacheRecord) -> ControlFlow<()>,
+    ) -> Result<(), CacheError> {
+        for record in self.query(query)? {
+            if f(record).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
 
     // Cache metadata
     fn get_metadata(&self) -> Result<Option<u64>, CacheError>;
     fn is_snapshotting_done(&self) -> Result<bool, CacheError>;
+
+    /// Collects record, storage and index statistics of the cache.
+    fn get_stats(&self) -> Result<CacheStats, CacheError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct CacheStats {
+    /// Number of records present in the cache.
+    pub record_count: usize,
+    /// Bytes used by the main environment and all secondary index environments.
+    pub total_bytes: u64,
+    /// The metadata of the last commit, i.e. the log position the cache is up to date with.
+    pub last_updated: Option<u64>,
+    /// Statistics of the secondary indexes, in the order of the index definitions.
+    pub indexes: Vec<IndexStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct IndexStats {
+    pub index_definition: IndexDefinition,
+    /// Number of (key, record id) pairs in the index.
+    pub entries: usize,
+    /// Number of distinct index keys, i.e. the cardinality of the index.
+    pub distinct_keys: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -143,6 +216,12 @@ pub trait RwCache: RoCache {
 
     /// Sets the metadata of the cache. Implicitly starts a transaction if there's no active transaction.
     fn set_metadata(&mut self, metadata: u64) -> Result<(), CacheError>;
+
+    /// Sets the log position of the operations written next, whose commit sets the metadata to a later position.
+    ///
+    /// Caches committed in parts use it to skip the operations replayed into parts that committed them before a
+    /// restart.
+    fn set_operation_position(&mut self, _position: u64) {}
     fn set_connection_snapshotting_done(&mut self, connection_name: &str)
         -> Result<(), CacheError>;
 
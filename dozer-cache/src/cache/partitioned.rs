@@ -0,0 +1,625 @@
+use std::cmp::Ordering;
+
+use dozer_types::json_types::field_to_json_value;
+use dozer_types::labels::Labels;
+use dozer_types::types::{
+    Field, IndexDefinition, IndexExpression, Record, SchemaWithIndex, Timezone,
+};
+use rayon::prelude::*;
+
+use super::expression::{FilterExpression, Operator, QueryExpression, Skip, SortDirection};
+use super::index;
+use super::plan::{Plan, PreparedPlan};
+use super::{
+    CacheRecord, CacheStats, CacheWriteOptions, RecordMeta, RoCache, RwCache, UpsertResult,
+};
+use crate::errors::{CacheError, PlanError};
+use dozer_types::models::api_endpoint::OnUpdateResolutionTypes;
+
+const PARTITION_LABEL: &str = "partition";
+
+/// Labels of partition `partition` of the partitioned cache with labels `labels`.
+pub fn partition_labels(labels: &Labels, partition: usize) -> Labels {
+    let mut labels = labels.clone();
+    labels.push(PARTITION_LABEL, partition.to_string());
+    labels
+}
+
+/// The partition of `num_partitions` the record with encoded primary key, or encoded values, `key` is routed to.
+fn partition_of_key(
+    key: impl IntoIterator<Item = impl AsRef<[u8]>>,
+    num_partitions: usize,
+) -> usize {
+    (stable_hash(key) % num_partitions as u64) as usize
+}
+
+/// FNV-1a hash of the concatenation of `chunks`.
+///
+/// Records stay in the partition they were routed to across restarts, so the hash must be the same in every process
+/// and build, unlike `ahash`'s.
+pub fn stable_hash(chunks: impl IntoIterator<Item = impl AsRef<[u8]>>) -> u64 {
+    let mut hash = 0xcbf29ce484222325;
+    for chunk in chunks {
+        for byte in chunk.as_ref() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// A cache whose records are hash partitioned across several caches by primary key, or by the whole record if the
+/// schema has no primary key.
+///
+/// Record ids are unique across partitions: the record with id `id` in partition `p` of `n` partitions has id
+/// `id * n + p`. Queries run on every partition in parallel and their results are merged.
+///
+/// The number of partitions can't change after the cache is created, because records are routed by their hash.
+#[derive(Debug)]
+pub struct PartitionedCache<C> {
+    labels: Labels,
+    partitions: Vec<C>,
+    write_options: CacheWriteOptions,
+    /// The metadata each partition was last committed with. Partitions are committed one after another, so they can
+    /// be at different log positions after a restart.
+    committed: Vec<Option<u64>>,
+    /// The log position of the operations being written, see `RwCache::set_operation_position`.
+    position: Option<u64>,
+    /// The metadata the next commit is made with.
+    pending_metadata: Option<u64>,
+}
+
+impl<C: RoCache> PartitionedCache<C> {
+    pub fn new(
+        labels: Labels,
+        partitions: Vec<C>,
+        write_options: CacheWriteOptions,
+    ) -> Result<Self, CacheError> {
+        debug_assert!(!partitions.is_empty(), "A cache has at least one partition");
+        let committed = partitions
+            .iter()
+            .map(|partition| partition.get_metadata())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            labels,
+            partitions,
+            write_options,
+            committed,
+            position: None,
+            pending_metadata: None,
+        })
+    }
+
+    /// Whether the operation being written was committed to `partition` before a restart, and is only replayed
+    /// because another partition wasn't committed yet.
+    fn is_committed(&self, partition: usize) -> bool {
+        match (self.position, self.committed[partition]) {
+            (Some(position), Some(committed)) => position <= committed,
+            _ => false,
+        }
+    }
+
+    fn partition_of_key(&self, key: &[u8]) -> usize {
+        partition_of_key([key], self.partitions.len())
+    }
+
+    fn partition_of_record(&self, record: &Record) -> usize {
+        let primary_index = &self.get_schema().0.primary_index;
+        if primary_index.is_empty() {
+            partition_of_key(
+                record.values.iter().map(Field::encode),
+                self.partitions.len(),
+            )
+        } else {
+            self.partition_of_key(&index::get_primary_key(primary_index, &record.values))
+        }
+    }
+
+    /// Whether the keys of the index `index_definition` are in one partition only, because they include the primary key.
+    fn has_disjoint_keys(&self, index_definition: &IndexDefinition) -> bool {
+        let primary_index = &self.get_schema().0.primary_index;
+        match index_definition {
+            IndexDefinition::SortedInverted(fields) => {
+                !primary_index.is_empty()
+                    && primary_index.iter().all(|field| fields.contains(field))
+            }
+            IndexDefinition::FullText(_) | IndexDefinition::Expression(_) => false,
+        }
+    }
+
+    fn global_id(&self, id: u64, partition: usize) -> u64 {
+        id * self.partitions.len() as u64 + partition as u64
+    }
+
+    fn global_meta(&self, meta: RecordMeta, partition: usize) -> RecordMeta {
+        RecordMeta::new(self.global_id(meta.id, partition), meta.version)
+    }
+
+    fn global_record(&self, mut record: CacheRecord, partition: usize) -> CacheRecord {
+        record.id = self.global_id(record.id, partition);
+        record
+    }
+
+    fn global_result(&self, result: UpsertResult, partition: usize) -> UpsertResult {
+        match result {
+            UpsertResult::Updated { old_meta, new_meta } => UpsertResult::Updated {
+                old_meta: self.global_meta(old_meta, partition),
+                new_meta: self.global_meta(new_meta, partition),
+            },
+            UpsertResult::Inserted { meta } => UpsertResult::Inserted {
+                meta: self.global_meta(meta, partition),
+            },
+            UpsertResult::Ignored => UpsertResult::Ignored,
+        }
+    }
+
+    /// The query each partition runs: every record that can be in the first `skip + limit` records of the merged
+    /// result.
+    fn partition_query(query: &QueryExpression) -> QueryExpression {
+        let limit = match query.skip {
+            Skip::Skip(skip) => query.limit.map(|limit| limit.saturating_add(skip)),
+            Skip::After(_) => None,
+        };
+        QueryExpression {
+            limit,
+            skip: Skip::Skip(0),
+            ..query.clone()
+        }
+    }
+
+    /// Merges the results of the partitions by the sort options of `query`, then by id, and applies its skip and
+    /// limit.
+    fn merge(
+        &self,
+        query: &QueryExpression,
+        results: Vec<Vec<CacheRecord>>,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        let sort_keys = self.sort_keys(query)?;
+        let mut records = results
+            .into_iter()
+            .enumerate()
+            .flat_map(|(partition, records)| {
+                records.into_iter().map(move |record| (partition, record))
+            })
+            .map(|(partition, record)| self.global_record(record, partition))
+            .map(|record| {
+                let key = sort_keys
+                    .iter()
+                    .map(|(key, _)| key.value(&record.record.values))
+                    .collect::<Vec<_>>();
+                (key, record)
+            })
+            .collect::<Vec<_>>();
+        records.sort_by(|(key_a, record_a), (key_b, record_b)| {
+            key_a
+                .iter()
+                .zip(key_b)
+                .zip(&sort_keys)
+                .map(|((a, b), (_, direction))| match direction {
+                    SortDirection::Ascending => a.cmp(b),
+                    SortDirection::Descending => b.cmp(a),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+                .then(record_a.id.cmp(&record_b.id))
+        });
+
+        let records = records.into_iter().map(|(_, record)| record);
+        let skipped = match query.skip {
+            Skip::Skip(skip) => records.skip(skip).collect::<Vec<_>>(),
+            Skip::After(after) => records
+                .skip_while(|record| record.id != after)
+                .skip(1)
+                .collect(),
+        };
+        Ok(skipped
+            .into_iter()
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// What the records of `query` are sorted by, before their id.
+    fn sort_keys(
+        &self,
+        query: &QueryExpression,
+    ) -> Result<Vec<(SortKey, SortDirection)>, CacheError> {
+        let fields = &self.get_schema().0.fields;
+        query
+            .order_by
+            .0
+            .iter()
+            .map(|option| {
+                let key = match fields
+                    .iter()
+                    .position(|field| field.name == option.field_name)
+                {
+                    Some(field_index) => SortKey::Field(field_index),
+                    None => IndexExpression::parse(&option.field_name, fields)
+                        .map(SortKey::Expression)
+                        .ok_or_else(|| PlanError::FieldNotFound(option.field_name.clone()))?,
+                };
+                Ok::<_, CacheError>((key, option.direction))
+            })
+            .collect()
+    }
+
+    fn count_impl(
+        &self,
+        query: &QueryExpression,
+        count: impl Fn(&C, &QueryExpression) -> Result<usize, CacheError> + Sync,
+    ) -> Result<usize, CacheError> {
+        let Skip::Skip(skip) = query.skip else {
+            // Where a record id falls in the merged result is only known after merging.
+            return Ok(self.query(query)?.len());
+        };
+        let partition_query = QueryExpression {
+            limit: None,
+            skip: Skip::Skip(0),
+            ..query.clone()
+        };
+        let total = self
+            .partitions
+            .par_iter()
+            .map(|partition| count(partition, &partition_query))
+            .try_reduce(|| 0, |a, b| Ok(a + b))?;
+        Ok(total
+            .saturating_sub(skip)
+            .min(query.limit.unwrap_or(usize::MAX)))
+    }
+
+    fn query_impl(
+        &self,
+        query: &QueryExpression,
+        run: impl Fn(&C, &QueryExpression) -> Result<Vec<CacheRecord>, CacheError> + Sync,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        let results = match query.skip {
+            Skip::Skip(_) => {
+                let partition_query = Self::partition_query(query);
+                self.partitions
+                    .par_iter()
+                    .map(|partition| run(partition, &partition_query))
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            Skip::After(after) => self.query_after(query, after, &run)?,
+        };
+        self.merge(query, results)
+    }
+
+    /// The results of the partitions for a query that skips the records up to the one with id `after`.
+    ///
+    /// Only the partition of that record is read as a whole, to find it. The other partitions only read the records
+    /// sorted after it, by filtering on its sort key, unless the query isn't sorted. Then records are sorted by id,
+    /// which can't be filtered on.
+    fn query_after(
+        &self,
+        query: &QueryExpression,
+        after: u64,
+        run: &(impl Fn(&C, &QueryExpression) -> Result<Vec<CacheRecord>, CacheError> + Sync),
+    ) -> Result<Vec<Vec<CacheRecord>>, CacheError> {
+        let num_partitions = self.partitions.len();
+        let cursor_partition = (after % num_partitions as u64) as usize;
+        let whole_query = QueryExpression {
+            limit: None,
+            skip: Skip::Skip(0),
+            ..query.clone()
+        };
+        let cursor_records = run(&self.partitions[cursor_partition], &whole_query)?;
+        let Some(cursor) = cursor_records
+            .iter()
+            .find(|record| self.global_id(record.id, cursor_partition) == after)
+        else {
+            // Like in a single cache, nothing follows a record that doesn't exist.
+            return Ok(vec![vec![]; num_partitions]);
+        };
+
+        let sort_keys = self.sort_keys(query)?;
+        let after_queries = after_queries(query, &sort_keys, &cursor.record.values)
+            .unwrap_or_else(|| vec![whole_query.clone()]);
+        let mut results = self
+            .partitions
+            .par_iter()
+            .enumerate()
+            .map(|(partition_index, partition)| {
+                if partition_index == cursor_partition {
+                    return Ok(vec![]);
+                }
+                let mut records = vec![];
+                for after_query in &after_queries {
+                    match run(partition, after_query) {
+                        Ok(after_records) => records.extend(after_records),
+                        // The filter on the sort key can need an index the query doesn't.
+                        Err(CacheError::Plan(_)) => return run(partition, &whole_query),
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(records)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        results[cursor_partition] = cursor_records;
+        Ok(results)
+    }
+}
+
+/// The queries of the records sorted after a record with `values`: the first `limit` records with a greater sort
+/// key, and all records with the same sort key, whose order depends on their ids.
+///
+/// Returns `None` if the query isn't sorted, or the sort key has nulls, which can't be compared in filters.
+fn after_queries(
+    query: &QueryExpression,
+    sort_keys: &[(SortKey, SortDirection)],
+    values: &[Field],
+) -> Option<Vec<QueryExpression>> {
+    if sort_keys.is_empty() {
+        return None;
+    }
+    let cursor_key = sort_keys
+        .iter()
+        .zip(&query.order_by.0)
+        .map(|((key, _), option)| match key.value(values) {
+            Field::Null => None,
+            value => Some((
+                option.field_name.clone(),
+                field_to_json_value(value, &Timezone::Utc).ok()?,
+            )),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let equal = |len: usize| {
+        cursor_key[..len]
+            .iter()
+            .map(|(name, value)| {
+                FilterExpression::Simple(name.clone(), Operator::EQ, value.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+    let greater = (0..cursor_key.len())
+        .map(|index| {
+            let (name, value) = &cursor_key[index];
+            let operator = match sort_keys[index].1 {
+                SortDirection::Ascending => Operator::GT,
+                SortDirection::Descending => Operator::LT,
+            };
+            let mut filters = equal(index);
+            filters.push(FilterExpression::Simple(
+                name.clone(),
+                operator,
+                value.clone(),
+            ));
+            FilterExpression::And(filters)
+        })
+        .collect();
+    let with_filter = |filter: FilterExpression, limit: Option<usize>| QueryExpression {
+        filter: Some(match &query.filter {
+            Some(query_filter) => FilterExpression::And(vec![query_filter.clone(), filter]),
+            None => filter,
+        }),
+        limit,
+        skip: Skip::Skip(0),
+        ..query.clone()
+    };
+    Some(vec![
+        with_filter(FilterExpression::Or(greater), query.limit),
+        with_filter(FilterExpression::And(equal(cursor_key.len())), None),
+    ])
+}
+
+/// What records of a partitioned cache are sorted by.
+enum SortKey {
+    Field(usize),
+    Expression(IndexExpression),
+}
+
+impl SortKey {
+    fn value(&self, values: &[Field]) -> Field {
+        match self {
+            SortKey::Field(field_index) => values[*field_index].clone(),
+            SortKey::Expression(expression) => expression.evaluate(values),
+        }
+    }
+}
+
+impl<C: RoCache> RoCache for PartitionedCache<C> {
+    fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    fn get_schema(&self) -> &SchemaWithIndex {
+        self.partitions[0].get_schema()
+    }
+
+    fn get(&self, key: &[u8]) -> Result<CacheRecord, CacheError> {
+        let partition = self.partition_of_key(key);
+        let record = self.partitions[partition].get(key)?;
+        Ok(self.global_record(record, partition))
+    }
+
+    fn count(&self, query: &QueryExpression) -> Result<usize, CacheError> {
+        self.count_impl(query, |partition, query| partition.count(query))
+    }
+
+    fn query(&self, query: &QueryExpression) -> Result<Vec<CacheRecord>, CacheError> {
+        self.query_impl(query, |partition, query| partition.query(query))
+    }
+
+    fn count_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<usize, CacheError> {
+        self.count_impl(query, |partition, query| {
+            partition.count_prepared(query, prepared)
+        })
+    }
+
+    fn query_prepared(
+        &self,
+        query: &QueryExpression,
+        prepared: &PreparedPlan,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        self.query_impl(query, |partition, query| {
+            partition.query_prepared(query, prepared)
+        })
+    }
+
+    fn explain(&self, query: &QueryExpression) -> Result<Plan, CacheError> {
+        // All partitions have the same schema and indexes, so they plan queries the same.
+        self.partitions[0].explain(&Self::partition_query(query))
+    }
+
+    /// The earliest metadata of the partitions, which are committed one after another.
+    fn get_metadata(&self) -> Result<Option<u64>, CacheError> {
+        let mut metadata = Some(u64::MAX);
+        for partition in &self.partitions {
+            metadata = metadata.min(partition.get_metadata()?);
+        }
+        Ok(metadata)
+    }
+
+    fn is_snapshotting_done(&self) -> Result<bool, CacheError> {
+        for partition in &self.partitions {
+            if !partition.is_snapshotting_done()? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Sums the statistics of the partitions. Distinct keys of an index are summed too, so keys present in several
+    /// partitions are counted once per partition.
+    fn get_stats(&self) -> Result<CacheStats, CacheError> {
+        let mut stats = self.partitions[0].get_stats()?;
+        stats.last_updated = self.get_metadata()?;
+        for partition in &self.partitions[1..] {
+            let partition_stats = partition.get_stats()?;
+            stats.record_count += partition_stats.record_count;
+            stats.total_bytes += partition_stats.total_bytes;
+            for (index, partition_index) in stats.indexes.iter_mut().zip(partition_stats.indexes) {
+                index.entries += partition_index.entries;
+                // A key can be in several partitions, unless the index includes the primary key. Then the most
+                // distinct keys of a partition is the closest count we have without merging the indexes.
+                if self.has_disjoint_keys(&index.index_definition) {
+                    index.distinct_keys += partition_index.distinct_keys;
+                } else {
+                    index.distinct_keys = index.distinct_keys.max(partition_index.distinct_keys);
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+impl<C: RwCache> RwCache for PartitionedCache<C> {
+    fn insert(&mut self, record: &Record) -> Result<UpsertResult, CacheError> {
+        let partition = self.partition_of_record(record);
+        if self.is_committed(partition) {
+            return Ok(UpsertResult::Ignored);
+        }
+        let result = self.partitions[partition].insert(record)?;
+        Ok(self.global_result(result, partition))
+    }
+
+    fn delete(&mut self, record: &Record) -> Result<Option<RecordMeta>, CacheError> {
+        let partition = self.partition_of_record(record);
+        if self.is_committed(partition) {
+            return Ok(None);
+        }
+        let meta = self.partitions[partition].delete(record)?;
+        Ok(meta.map(|meta| self.global_meta(meta, partition)))
+    }
+
+    /// Updates moving a record to another partition delete it from the old partition and insert it into the new one.
+    fn update(&mut self, old: &Record, record: &Record) -> Result<UpsertResult, CacheError> {
+        let old_partition = self.partition_of_record(old);
+        let new_partition = self.partition_of_record(record);
+        if old_partition == new_partition {
+            if self.is_committed(old_partition) {
+                return Ok(UpsertResult::Ignored);
+            }
+            let result = self.partitions[old_partition].update(old, record)?;
+            return Ok(self.global_result(result, old_partition));
+        }
+
+        // A replayed update can be committed to one of the partitions only.
+        let old_meta = if self.is_committed(old_partition) {
+            None
+        } else {
+            let old_meta = self.partitions[old_partition].delete(old)?;
+            if old_meta.is_none() {
+                match self.write_options.update_resolution {
+                    OnUpdateResolutionTypes::Nothing(()) => return Ok(UpsertResult::Ignored),
+                    OnUpdateResolutionTypes::Upsert(()) => (),
+                    OnUpdateResolutionTypes::Panic(()) => {
+                        return Err(CacheError::PrimaryKeyNotFound)
+                    }
+                }
+            }
+            old_meta
+        };
+        if self.is_committed(new_partition) {
+            return Ok(UpsertResult::Ignored);
+        }
+        let result = self.partitions[new_partition].insert(record)?;
+        Ok(match (old_meta, result) {
+            (Some(old_meta), UpsertResult::Inserted { meta }) => UpsertResult::Updated {
+                old_meta: self.global_meta(old_meta, old_partition),
+                new_meta: self.global_meta(meta, new_partition),
+            },
+            (_, result) => self.global_result(result, new_partition),
+        })
+    }
+
+    fn set_metadata(&mut self, metadata: u64) -> Result<(), CacheError> {
+        for partition in &mut self.partitions {
+            partition.set_metadata(metadata)?;
+        }
+        self.pending_metadata = Some(metadata);
+        Ok(())
+    }
+
+    fn set_connection_snapshotting_done(
+        &mut self,
+        connection_name: &str,
+    ) -> Result<(), CacheError> {
+        for partition in &mut self.partitions {
+            partition.set_connection_snapshotting_done(connection_name)?;
+        }
+        Ok(())
+    }
+
+    fn set_operation_position(&mut self, position: u64) {
+        self.position = Some(position);
+    }
+
+    /// Commits the partitions one after another. If this fails midway, the cache resumes from the earliest partition
+    /// metadata, and the operations replayed into partitions that were already committed are skipped by their
+    /// position.
+    fn commit(&mut self) -> Result<(), CacheError> {
+        for (partition, committed) in self.partitions.iter_mut().zip(&mut self.committed) {
+            partition.commit()?;
+            if self.pending_metadata.is_some() {
+                *committed = self.pending_metadata;
+            }
+        }
+        self.pending_metadata = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        assert_eq!(stable_hash([b""]), 0xcbf29ce484222325);
+        assert_eq!(stable_hash([b"a"]), 0xaf63dc4c8601ec8c);
+        assert_eq!(stable_hash([&b"a"[..], b""]), stable_hash([b"a"]));
+    }
+
+    #[test]
+    fn test_partition_of_key_is_fixed() {
+        let key = index::get_primary_key(&[0], &[Field::UInt(1)]);
+        assert_eq!(partition_of_key([&key], 4), 0);
+        assert_eq!(partition_of_key([&key], 7), 6);
+    }
+}
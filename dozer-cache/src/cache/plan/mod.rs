@@ -1,26 +1,44 @@
 mod helper;
 mod planner;
-use dozer_types::types::Field;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::Serialize;
+use dozer_types::types::{Field, IndexDefinition, IndexExpression};
 pub use planner::QueryPlanner;
 
+use crate::errors::PlanError;
+
 use super::expression::{Operator, SortDirection};
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub enum Plan {
     IndexScans(Vec<IndexScan>),
+    /// Plan of a filter with `$or`.
+    Union(IndexScanUnion),
     SeqScan(SeqScan),
     ReturnEmpty,
 }
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct IndexScan {
     pub index_id: usize,
     pub kind: IndexScanKind,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Records matching any of `branches`, each branch being index scans to intersect like `Plan::IndexScans`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct IndexScanUnion {
+    pub branches: Vec<Vec<IndexScan>>,
+    /// The sort options as field indexes. Every branch is sorted by them, and the branches are merged by them.
+    pub order_by: Vec<(usize, SortDirection)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub enum IndexScanKind {
     SortedInverted {
         eq_filters: Vec<(usize, Field)>,
@@ -31,19 +49,22 @@ pub enum IndexScanKind {
     },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct SortedInvertedRangeQuery {
     pub field_index: usize,
     pub sort_direction: SortDirection,
     pub operator_and_value: Option<(Operator, Field)>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct SeqScan {
     pub direction: SortDirection,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct IndexFilter {
     pub field_index: usize,
     pub op: Operator,
@@ -59,3 +80,50 @@ impl IndexFilter {
         }
     }
 }
+
+/// The expressions of the expression indexes of `secondary_indexes`, which plans refer to as the fields numbered after
+/// the fields of the schema, in this order.
+pub fn index_expressions(secondary_indexes: &[IndexDefinition]) -> Vec<IndexExpression> {
+    let mut expressions = vec![];
+    for index in secondary_indexes {
+        if let IndexDefinition::Expression(expression) = index {
+            if !expressions.contains(expression) {
+                expressions.push(*expression);
+            }
+        }
+    }
+    expressions
+}
+
+/// The value of field `field_index` of a record, numbering the fields like `index_expressions`.
+pub fn field_value(values: &[Field], expressions: &[IndexExpression], field_index: usize) -> Field {
+    match values.get(field_index) {
+        Some(value) => value.clone(),
+        None => expressions
+            .get(field_index - values.len())
+            .map_or(Field::Null, |expression| expression.evaluate(values)),
+    }
+}
+
+/// The plan of a query template, planned for the first query of the template and bound to the filter values of the
+/// following ones.
+#[derive(Debug, Default)]
+pub struct PreparedPlan(Mutex<Option<Plan>>);
+
+impl PreparedPlan {
+    /// Binds the query of `planner` to the prepared plan, or returns `None` if there's no plan for its shape yet.
+    pub fn bind(&self, planner: &QueryPlanner) -> Result<Option<Plan>, PlanError> {
+        match self.0.lock().as_ref() {
+            Some(plan) => planner.bind(plan),
+            None => Ok(None),
+        }
+    }
+
+    /// Prepares `plan` for the following queries, unless it only applies to its `null` filter values or it's a
+    /// union, which isn't prepared.
+    pub fn prepare(&self, plan: &Plan) {
+        if matches!(plan, Plan::IndexScans(_) | Plan::SeqScan(_)) {
+            *self.0.lock() = Some(plan.clone());
+        }
+    }
+}
@@ -1,22 +1,47 @@
-use crate::cache::expression::{FilterExpression, Operator, SortDirection, SortOptions};
+use std::borrow::Cow;
+
+use crate::cache::expression::{
+    FilterExpression, Operator, SortDirection, SortOption, SortOptions,
+};
+use crate::cache::IndexStats;
 use crate::errors::PlanError;
+use dozer_types::helper::defined_json_value_to_field;
 use dozer_types::models::api_endpoint::{
     CreateSecondaryIndex, FullText, SecondaryIndex, SortedInverted,
 };
 use dozer_types::types::{Field, FieldDefinition, Schema};
-use dozer_types::types::{FieldType, IndexDefinition};
+use dozer_types::types::{FieldType, IndexDefinition, IndexExpression};
 use dozer_types::{json_value_to_field, serde_yaml};
 
 use super::helper::{RangeQuery, RangeQueryKind};
-use super::{helper, IndexScan, Plan, SeqScan};
-use super::{IndexFilter, IndexScanKind};
+use super::{helper, index_expressions, IndexScan, IndexScanUnion, Plan, SeqScan};
+use super::{IndexFilter, IndexScanKind, SortedInvertedRangeQuery};
 
 pub struct QueryPlanner<'a> {
     schema: &'a Schema,
-    secondary_indexes: &'a [IndexDefinition],
+    /// Expression indexes are planned as single field `SortedInverted` indexes of the fields numbered after the
+    /// fields of the schema, see `index_expressions`.
+    secondary_indexes: Cow<'a, [IndexDefinition]>,
+    expressions: Vec<IndexExpression>,
     filter: Option<&'a FilterExpression>,
     order_by: &'a SortOptions,
+    index_stats: Option<&'a [IndexStats]>,
 }
+
+/// Maximum number of candidate scans the planner costs when index statistics are available.
+///
+/// The number of candidates grows factorially with the number of `Eq` filters. If none of the costed candidates is
+/// supported by the indexes, the rest are planned as without statistics.
+const MAX_COSTED_CANDIDATES: usize = 1000;
+
+/// Estimated fraction of an index prefix matched by a range filter.
+const RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Maximum number of conjunctions a filter with `$or` expands to, each planned as a branch of a union.
+///
+/// The number of conjunctions grows exponentially with the number of `$or`s in an `$and`.
+pub const MAX_DISJUNCTS: usize = 32;
+
 impl<'a> QueryPlanner<'a> {
     pub fn new(
         schema: &'a Schema,
@@ -24,30 +49,158 @@ impl<'a> QueryPlanner<'a> {
         filter: Option<&'a FilterExpression>,
         order_by: &'a SortOptions,
     ) -> Self {
+        let expressions = index_expressions(secondary_indexes);
+        let secondary_indexes = if expressions.is_empty() {
+            Cow::Borrowed(secondary_indexes)
+        } else {
+            Cow::Owned(
+                secondary_indexes
+                    .iter()
+                    .map(|index| match index {
+                        IndexDefinition::Expression(expression) => {
+                            let position = expressions
+                                .iter()
+                                .position(|e| e == expression)
+                                .expect("All expressions are collected");
+                            IndexDefinition::SortedInverted(vec![schema.fields.len() + position])
+                        }
+                        index => index.clone(),
+                    })
+                    .collect(),
+            )
+        };
         Self {
             schema,
             secondary_indexes,
+            expressions,
             filter,
             order_by,
+            index_stats: None,
         }
     }
 
+    /// Makes the planner choose the cheapest plan according to `index_stats`, instead of the first plan that
+    /// existing indexes can satisfy.
+    ///
+    /// `index_stats` must be in the same order as `secondary_indexes`, otherwise it's ignored.
+    pub fn with_index_stats(mut self, index_stats: &'a [IndexStats]) -> Self {
+        if index_stats.len() == self.secondary_indexes.len() {
+            self.index_stats = Some(index_stats);
+        }
+        self
+    }
+
     pub fn plan(&self) -> Result<Plan, PlanError> {
+        let mut conjunctions = self.conjunctions()?;
+        if conjunctions.len() == 1 {
+            return self.plan_conjunction(&conjunctions.remove(0));
+        }
+
+        // Every conjunction is a branch of a union.
+        let mut branches: Vec<Vec<IndexScan>> = vec![];
+        for conjunction in &conjunctions {
+            match self.plan_conjunction(conjunction)? {
+                Plan::IndexScans(index_scans) => {
+                    if !branches.contains(&index_scans) {
+                        branches.push(index_scans);
+                    }
+                }
+                // The conjunction has no filter and no sort option, so all records match.
+                Plan::SeqScan(seq_scan) => return Ok(Plan::SeqScan(seq_scan)),
+                Plan::ReturnEmpty => (),
+                Plan::Union(_) => unreachable!("Conjunctions are not planned as unions"),
+            }
+        }
+        Ok(match branches.len() {
+            0 => Plan::ReturnEmpty,
+            1 => Plan::IndexScans(branches.remove(0)),
+            _ => Plan::Union(IndexScanUnion {
+                branches,
+                order_by: self.sort_fields()?,
+            }),
+        })
+    }
+
+    /// The filter as a disjunction of conjunctions of simple filters.
+    fn conjunctions(&self) -> Result<Vec<Vec<&'a FilterExpression>>, PlanError> {
+        match self.filter {
+            Some(expression) => disjunctive_normal_form(expression),
+            None => Ok(vec![vec![]]),
+        }
+    }
+
+    /// The fields that order records with equal sort keys, in the direction the index is scanned in: the field of a
+    /// range filter that isn't a sort option, then the primary key fields that aren't sort options. Secondary index keys
+    /// end with the primary key, so index scans return records in this order. Empty without sort options.
+    ///
+    /// The direction is the one of the first conjunction of the filter, which the other branches of a union are
+    /// expected to be scanned in too.
+    pub fn tiebreaker(&self) -> Result<Vec<(usize, SortDirection)>, PlanError> {
+        let sort_fields = self.sort_fields()?;
+        let filters = match self.conjunctions()?.first() {
+            Some(conjunction) => self.collect_filters(conjunction)?,
+            None => vec![],
+        };
+        let Some(direction) = scan_direction(&sort_fields, &filters) else {
+            return Ok(vec![]);
+        };
+
+        let is_sort_field = |index: usize| {
+            sort_fields
+                .iter()
+                .any(|(field_index, _)| *field_index == index)
+        };
+        let mut tiebreaker = vec![];
+        let range_field = filters
+            .iter()
+            .find(|(filter, _)| filter.op.is_range_operator())
+            .map(|(filter, _)| filter.field_index);
+        for index in range_field
+            .into_iter()
+            .chain(self.schema.primary_index.iter().copied())
+        {
+            if !is_sort_field(index) && !tiebreaker.contains(&(index, direction)) {
+                tiebreaker.push((index, direction));
+            }
+        }
+        Ok(tiebreaker)
+    }
+
+    /// The order records are returned in: the sort options, followed by the tiebreaker, see `tiebreaker`.
+    pub fn order_with_tiebreaker(&self) -> Result<SortOptions, PlanError> {
+        let field_names = self.field_names();
+        let mut options = self.order_by.0.clone();
+        for (index, direction) in self.tiebreaker()? {
+            options.push(SortOption::new(field_names[index].clone(), direction));
+        }
+        Ok(SortOptions(options))
+    }
+
+    pub fn sort_fields(&self) -> Result<Vec<(usize, SortDirection)>, PlanError> {
+        self.order_by
+            .0
+            .iter()
+            .map(|order| {
+                self.field(&order.field_name)
+                    .map(|(field_index, _, _)| (field_index, order.direction))
+                    .ok_or_else(|| PlanError::FieldNotFound(order.field_name.clone()))
+            })
+            .collect()
+    }
+
+    fn plan_conjunction(&self, conjunction: &[&FilterExpression]) -> Result<Plan, PlanError> {
         // Collect all the filters.
         // TODO: Handle filters like And([a > 0, a < 10]).
-        let mut filters = vec![];
-        if let Some(expression) = &self.filter {
-            collect_filters(self.schema, expression, &mut filters)?;
-        }
+        let mut filters = self.collect_filters(conjunction)?;
 
         // Filter the sort options.
         // TODO: Handle duplicate fields.
         let mut order_by = vec![];
         for order in &self.order_by.0 {
             // Find the field index.
-            let (field_index, _, _) =
-                get_field_index_and_type(&order.field_name, &self.schema.fields)
-                    .ok_or_else(|| PlanError::FieldNotFound(order.field_name.clone()))?;
+            let (field_index, _, _) = self
+                .field(&order.field_name)
+                .ok_or_else(|| PlanError::FieldNotFound(order.field_name.clone()))?;
             // If the field is already in a filter supported by `SortedInverted`, mark the corresponding filter.
             if seen_in_sorted_inverted_filter(field_index, order.direction, &mut filters)? {
                 continue;
@@ -56,6 +209,15 @@ impl<'a> QueryPlanner<'a> {
             order_by.push((field_index, order.direction));
         }
 
+        // A range filter that isn't sorted is scanned in the direction of the tiebreaker, see `tiebreaker`.
+        if let Some(direction) = scan_direction(&self.sort_fields()?, &filters) {
+            for filter in &mut filters {
+                if filter.0.op.is_range_operator() && filter.1.is_none() {
+                    filter.1 = Some(direction);
+                }
+            }
+        }
+
         // If no filter and sort is requested, return a SeqScan.
         if filters.is_empty() && order_by.is_empty() {
             return Ok(Plan::SeqScan(SeqScan {
@@ -75,16 +237,38 @@ impl<'a> QueryPlanner<'a> {
         let range_query = find_range_query(&mut filters, &order_by)?;
 
         // Generate some index scans that can answer this query, lazily.
-        let all_index_scans = helper::get_all_indexes(filters, range_query);
+        let mut all_index_scans = helper::get_all_indexes(filters, range_query);
 
         // Check if existing secondary indexes can satisfy any of the scans.
         let mut scans = None;
+        if let Some(index_stats) = self.index_stats {
+            let mut cheapest: Option<(f64, Vec<IndexScan>)> = None;
+            for index_scans in all_index_scans.by_ref().take(MAX_COSTED_CANDIDATES) {
+                if scans.is_none() {
+                    scans = Some(index_scans.clone());
+                }
+                if let Some((cost, index_scans)) =
+                    cheapest_indexes(&self.secondary_indexes, index_stats, index_scans)
+                {
+                    if cheapest
+                        .as_ref()
+                        .map_or(true, |(cheapest_cost, _)| cost < *cheapest_cost)
+                    {
+                        cheapest = Some((cost, index_scans));
+                    }
+                }
+            }
+
+            if let Some((_, index_scans)) = cheapest {
+                return Ok(Plan::IndexScans(index_scans));
+            }
+        }
+
         for index_scans in all_index_scans {
             if scans.is_none() {
                 scans = Some(index_scans.clone());
             }
-
-            if let Some(index_scans) = all_indexes_are_present(self.secondary_indexes, index_scans)
+            if let Some(index_scans) = all_indexes_are_present(&self.secondary_indexes, index_scans)
             {
                 return Ok(Plan::IndexScans(index_scans));
             }
@@ -92,11 +276,146 @@ impl<'a> QueryPlanner<'a> {
 
         Err(PlanError::MatchingIndexNotFound(
             describe_index_configuration(
-                &self.schema.fields,
+                &self.field_names(),
                 &scans.expect("Planner should always generate plan"),
             ),
         ))
     }
+
+    /// The index, type and nullability of the field or index expression `name`.
+    fn field(&self, name: &str) -> Option<(usize, FieldType, bool)> {
+        if let Some(field) = get_field_index_and_type(name, &self.schema.fields) {
+            return Some(field);
+        }
+        let expression = IndexExpression::parse(name, &self.schema.fields)?;
+        let position = self.expressions.iter().position(|e| *e == expression)?;
+        let field = &self.schema.fields[expression.field_index];
+        Some((
+            self.schema.fields.len() + position,
+            expression.function.result_type(field.typ)?,
+            field.nullable,
+        ))
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.schema
+            .fields
+            .iter()
+            .map(|field| field.name.clone())
+            .chain(
+                self.expressions
+                    .iter()
+                    .map(|expression| expression.name(&self.schema.fields)),
+            )
+            .collect()
+    }
+
+    fn collect_filters(
+        &self,
+        conjunction: &[&FilterExpression],
+    ) -> Result<Vec<(IndexFilter, Option<SortDirection>)>, PlanError> {
+        conjunction
+            .iter()
+            .map(|expression| {
+                let FilterExpression::Simple(field_name, operator, value) = expression else {
+                    unreachable!("Conjunctions only have simple filters");
+                };
+                let (field_index, field_type, nullable) = self
+                    .field(field_name)
+                    .ok_or_else(|| PlanError::FieldNotFound(field_name.clone()))?;
+                // Enum values are matched by their declared values, which fields of index expressions don't have.
+                let field = match self.schema.fields.get(field_index) {
+                    Some(definition) => defined_json_value_to_field(value.clone(), definition)?,
+                    None => json_value_to_field(value.clone(), field_type, nullable)?,
+                };
+                Ok((IndexFilter::new(field_index, *operator, field), None))
+            })
+            .collect()
+    }
+}
+
+impl<'a> QueryPlanner<'a> {
+    /// Binds the filter values of this planner's query to `plan`, a plan of a query of the same shape, so the query
+    /// doesn't have to be planned again.
+    ///
+    /// Returns `None` if the query has another shape, i.e. its filters don't fill the filters of `plan` one to one.
+    /// Sort options are not checked, so `plan` must be a plan for the same sort options.
+    pub fn bind(&self, plan: &Plan) -> Result<Option<Plan>, PlanError> {
+        let conjunctions = self.conjunctions()?;
+        // Unions are not prepared, so a filter of more than one conjunction has another shape.
+        let [conjunction] = conjunctions.as_slice() else {
+            return Ok(None);
+        };
+        let filters = self.collect_filters(conjunction)?;
+        if filters
+            .iter()
+            .any(|f| matches!(f.0.val, Field::Null) && f.0.op != Operator::EQ)
+        {
+            return Ok(Some(Plan::ReturnEmpty));
+        }
+        let mut filters = filters
+            .into_iter()
+            .map(|(filter, _)| Some(filter))
+            .collect::<Vec<_>>();
+        // Takes the value of the first unbound filter of `field_index` and `op`.
+        let mut take = |field_index: usize, op: Operator| {
+            filters
+                .iter_mut()
+                .find(|filter| {
+                    filter
+                        .as_ref()
+                        .map_or(false, |f| f.field_index == field_index && f.op == op)
+                })
+                .and_then(Option::take)
+                .map(|filter| filter.val)
+        };
+
+        let mut plan = plan.clone();
+        match &mut plan {
+            Plan::IndexScans(index_scans) => {
+                for index_scan in index_scans {
+                    match &mut index_scan.kind {
+                        IndexScanKind::SortedInverted {
+                            eq_filters,
+                            range_query,
+                        } => {
+                            for (field_index, value) in eq_filters {
+                                let Some(val) = take(*field_index, Operator::EQ) else {
+                                    return Ok(None);
+                                };
+                                *value = val;
+                            }
+                            if let Some(SortedInvertedRangeQuery {
+                                field_index,
+                                operator_and_value: Some((op, value)),
+                                ..
+                            }) = range_query
+                            {
+                                let Some(val) = take(*field_index, *op) else {
+                                    return Ok(None);
+                                };
+                                *value = val;
+                            }
+                        }
+                        IndexScanKind::FullText { filter } => {
+                            let Some(val) = take(filter.field_index, filter.op) else {
+                                return Ok(None);
+                            };
+                            filter.val = val;
+                        }
+                    }
+                }
+            }
+            Plan::SeqScan(_) => (),
+            // Plans for `null` values aren't plans for the shape.
+            Plan::ReturnEmpty | Plan::Union(_) => return Ok(None),
+        }
+
+        if filters.iter().any(Option::is_some) {
+            return Ok(None);
+        }
+        Ok(Some(plan))
+    }
 }
 
 fn get_field_index_and_type(
@@ -110,26 +429,42 @@ fn get_field_index_and_type(
         .map(|(i, f)| (i, f.typ, f.nullable))
 }
 
-fn collect_filters(
-    schema: &Schema,
+/// Expands `expression` to a disjunction of conjunctions of `FilterExpression::Simple`s, distributing `$and` over
+/// `$or`.
+fn disjunctive_normal_form(
     expression: &FilterExpression,
-    filters: &mut Vec<(IndexFilter, Option<SortDirection>)>,
-) -> Result<(), PlanError> {
+) -> Result<Vec<Vec<&FilterExpression>>, PlanError> {
     match expression {
-        FilterExpression::Simple(field_name, operator, value) => {
-            let (field_index, field_type, nullable) =
-                get_field_index_and_type(field_name, &schema.fields)
-                    .ok_or_else(|| PlanError::FieldNotFound(field_name.clone()))?;
-            let field = json_value_to_field(value.clone(), field_type, nullable)?;
-            filters.push((IndexFilter::new(field_index, *operator, field), None));
-        }
+        FilterExpression::Simple(..) => Ok(vec![vec![expression]]),
         FilterExpression::And(expressions) => {
+            let mut conjunctions = vec![vec![]];
             for expression in expressions {
-                collect_filters(schema, expression, filters)?;
+                let disjuncts = disjunctive_normal_form(expression)?;
+                if conjunctions.len() * disjuncts.len() > MAX_DISJUNCTS {
+                    return Err(PlanError::TooManyDisjuncts(MAX_DISJUNCTS));
+                }
+                conjunctions = conjunctions
+                    .iter()
+                    .flat_map(|conjunction| {
+                        disjuncts.iter().map(move |disjunct| {
+                            conjunction.iter().chain(disjunct).copied().collect()
+                        })
+                    })
+                    .collect();
+            }
+            Ok(conjunctions)
+        }
+        FilterExpression::Or(expressions) => {
+            let mut conjunctions = vec![];
+            for expression in expressions {
+                conjunctions.extend(disjunctive_normal_form(expression)?);
+                if conjunctions.len() > MAX_DISJUNCTS {
+                    return Err(PlanError::TooManyDisjuncts(MAX_DISJUNCTS));
+                }
             }
+            Ok(conjunctions)
         }
     }
-    Ok(())
 }
 
 fn seen_in_sorted_inverted_filter(
@@ -157,6 +492,25 @@ fn seen_in_sorted_inverted_filter(
     Ok(false)
 }
 
+/// The direction the index is scanned in for the sort options `sort_fields`: the direction of the sort option that
+/// isn't on a field of an `Eq` filter in `filters`, or of the last sort option if they all are. `None` without sort
+/// options.
+fn scan_direction(
+    sort_fields: &[(usize, SortDirection)],
+    filters: &[(IndexFilter, Option<SortDirection>)],
+) -> Option<SortDirection> {
+    let is_eq_filtered = |index: usize| {
+        filters
+            .iter()
+            .any(|(filter, _)| filter.field_index == index && filter.op == Operator::EQ)
+    };
+    sort_fields
+        .iter()
+        .find(|(field_index, _)| !is_eq_filtered(*field_index))
+        .or(sort_fields.last())
+        .map(|(_, direction)| *direction)
+}
+
 fn find_range_query(
     filters: &mut Vec<(IndexFilter, Option<SortDirection>)>,
     order_by: &[(usize, SortDirection)],
@@ -196,6 +550,36 @@ fn find_range_query(
 }
 
 impl IndexScanKind {
+    /// Estimates the number of index entries this scan reads from an index with `stats`.
+    fn estimate_cost(&self, stats: &IndexStats) -> f64 {
+        let entries = stats.entries as f64;
+        let distinct_keys = stats.distinct_keys.max(1) as f64;
+        match self {
+            IndexScanKind::FullText { .. } => entries / distinct_keys,
+            IndexScanKind::SortedInverted {
+                eq_filters,
+                range_query: None,
+            } if !eq_filters.is_empty() => entries / distinct_keys,
+            IndexScanKind::SortedInverted {
+                eq_filters,
+                range_query,
+            } => {
+                // Assume every key field contributes equally to the cardinality of the index.
+                let num_eq_filters = eq_filters.len() as f64;
+                let prefix_distinct_keys =
+                    distinct_keys.powf(num_eq_filters / (num_eq_filters + 1.0));
+                let range_selectivity = match range_query {
+                    Some(SortedInvertedRangeQuery {
+                        operator_and_value: Some(_),
+                        ..
+                    }) => RANGE_SELECTIVITY,
+                    _ => 1.0,
+                };
+                entries / prefix_distinct_keys * range_selectivity
+            }
+        }
+    }
+
     fn is_supported_by_index(&self, index: &IndexDefinition) -> bool {
         match (self, index) {
             (
@@ -259,15 +643,38 @@ fn all_indexes_are_present(
     Some(scans)
 }
 
-fn describe_index_configuration(
-    field_definitions: &[FieldDefinition],
-    indexes: &[IndexScanKind],
-) -> String {
+/// Like `all_indexes_are_present`, but picks the cheapest supporting index for every scan and returns the total cost.
+fn cheapest_indexes(
+    indexes: &[IndexDefinition],
+    index_stats: &[IndexStats],
+    index_scan_kinds: Vec<IndexScanKind>,
+) -> Option<(f64, Vec<IndexScan>)> {
+    let mut total_cost = 0.0;
+    let mut scans = vec![];
+    for index_scan_kind in index_scan_kinds {
+        let (index_id, cost) = indexes
+            .iter()
+            .zip(index_stats)
+            .enumerate()
+            .filter(|(_, (index, _))| index_scan_kind.is_supported_by_index(index))
+            .map(|(index_id, (_, stats))| (index_id, index_scan_kind.estimate_cost(stats)))
+            // `min_by` keeps the first index on ties.
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        total_cost += cost;
+        scans.push(IndexScan {
+            index_id,
+            kind: index_scan_kind,
+        });
+    }
+    Some((total_cost, scans))
+}
+
+fn describe_index_configuration(field_names: &[String], indexes: &[IndexScanKind]) -> String {
     let mut creates = vec![];
     for index in indexes {
         match index {
             IndexScanKind::FullText { filter } => {
-                let field = field_definitions[filter.field_index].name.clone();
+                let field = field_names[filter.field_index].clone();
                 creates.push(CreateSecondaryIndex {
                     index: Some(SecondaryIndex::FullText(FullText { field })),
                 });
@@ -278,11 +685,11 @@ fn describe_index_configuration(
             } => {
                 let mut fields = vec![];
                 for (field_index, _) in eq_filters {
-                    let field = field_definitions[*field_index].name.clone();
+                    let field = field_names[*field_index].clone();
                     fields.push(field);
                 }
                 if let Some(range_query) = range_query {
-                    let field = field_definitions[range_query.field_index].name.clone();
+                    let field = field_names[range_query.field_index].clone();
                     fields.push(field);
                 }
                 creates.push(CreateSecondaryIndex {
@@ -297,8 +704,6 @@ fn describe_index_configuration(
 
 #[cfg(test)]
 mod tests {
-    use crate::cache::plan::SortedInvertedRangeQuery;
-
     use super::*;
 
     #[test]
@@ -1,11 +1,18 @@
-use super::{Plan, QueryPlanner};
+use super::{IndexScanUnion, Plan, PreparedPlan, QueryPlanner};
 use crate::cache::{
     expression::{self, FilterExpression, Operator, SortDirection, SortOption, SortOptions},
     plan::{IndexScanKind, SortedInvertedRangeQuery},
-    test_utils,
+    test_utils, IndexStats,
 };
+use crate::errors::PlanError;
 
-use dozer_types::{serde_json::Value, types::Field};
+use dozer_types::{
+    serde_json::Value,
+    types::{
+        Field, FieldDefinition, FieldType, IndexDefinition, IndexExpression, IndexFunction, Schema,
+        SourceDefinition,
+    },
+};
 
 #[test]
 fn test_generate_plan_simple() {
@@ -133,3 +140,365 @@ fn test_generate_plan_empty() {
     .unwrap();
     assert!(matches!(plan, Plan::ReturnEmpty));
 }
+
+#[test]
+fn test_generate_plan_with_index_stats() {
+    let (schema, mut secondary_indexes) = test_utils::schema_1();
+    // Another composite index with the fields in reverse order.
+    secondary_indexes.push(IndexDefinition::SortedInverted(vec![1, 0]));
+    let index_stats = secondary_indexes
+        .iter()
+        .map(|index_definition| IndexStats {
+            index_definition: index_definition.clone(),
+            entries: 100,
+            distinct_keys: if index_definition == &IndexDefinition::SortedInverted(vec![1, 0]) {
+                50
+            } else {
+                10
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let filter = FilterExpression::And(vec![
+        FilterExpression::Simple("a".to_string(), expression::Operator::EQ, Value::from(1)),
+        FilterExpression::Simple(
+            "b".to_string(),
+            expression::Operator::EQ,
+            Value::from("test".to_string()),
+        ),
+    ]);
+    let order_by = Default::default();
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&filter), &order_by);
+
+    // Without statistics, the first matching index is used.
+    let Plan::IndexScans(index_scans) = planner.plan().unwrap() else {
+        panic!("IndexScan expected")
+    };
+    assert_eq!(index_scans.len(), 1);
+    assert_eq!(index_scans[0].index_id, 3);
+
+    // With statistics, the more selective index is used.
+    let Plan::IndexScans(index_scans) = planner.with_index_stats(&index_stats).plan().unwrap()
+    else {
+        panic!("IndexScan expected")
+    };
+    assert_eq!(index_scans.len(), 1);
+    assert_eq!(index_scans[0].index_id, 4);
+    assert_eq!(
+        index_scans[0].kind,
+        IndexScanKind::SortedInverted {
+            eq_filters: vec![(1, Field::String("test".to_string())), (0, Field::Int(1))],
+            range_query: None,
+        }
+    );
+}
+
+#[test]
+fn test_generate_plan_with_index_stats_beyond_costed_candidates() {
+    // 7 `Eq` filters have 5040 candidate orders, and only the last one is indexed.
+    let mut schema = Schema::default();
+    for name in ["a", "b", "c", "d", "e", "f", "g"] {
+        schema.field(
+            FieldDefinition::new(
+                name.to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+    }
+    let secondary_indexes = vec![IndexDefinition::SortedInverted(vec![6, 5, 4, 3, 2, 1, 0])];
+    let index_stats = vec![IndexStats {
+        index_definition: secondary_indexes[0].clone(),
+        entries: 100,
+        distinct_keys: 10,
+    }];
+    let filter = FilterExpression::And(
+        schema
+            .fields
+            .iter()
+            .map(|field| FilterExpression::Simple(field.name.clone(), Operator::EQ, 1.into()))
+            .collect(),
+    );
+    let order_by = Default::default();
+
+    let Plan::IndexScans(index_scans) =
+        QueryPlanner::new(&schema, &secondary_indexes, Some(&filter), &order_by)
+            .with_index_stats(&index_stats)
+            .plan()
+            .unwrap()
+    else {
+        panic!("IndexScan expected")
+    };
+    assert_eq!(index_scans.len(), 1);
+    assert_eq!(index_scans[0].index_id, 0);
+}
+
+#[test]
+fn test_generate_plan_ignores_mismatching_index_stats() {
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let filter = FilterExpression::Simple("c".into(), expression::Operator::GT, 1.into());
+    let order_by = Default::default();
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&filter), &order_by)
+        .with_index_stats(&[]);
+    let Plan::IndexScans(index_scans) = planner.plan().unwrap() else {
+        panic!("IndexScan expected")
+    };
+    assert_eq!(index_scans[0].index_id, 2);
+}
+
+#[test]
+fn test_bind_plan() {
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let filter = |a: Value, b: Value| {
+        FilterExpression::And(vec![
+            FilterExpression::Simple("a".to_string(), Operator::EQ, a),
+            FilterExpression::Simple("b".to_string(), Operator::EQ, b),
+        ])
+    };
+    let order_by = Default::default();
+    let template = filter(1.into(), "test".into());
+    let prepared = PreparedPlan::default();
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&template), &order_by);
+    assert_eq!(prepared.bind(&planner).unwrap(), None);
+    prepared.prepare(&planner.plan().unwrap());
+
+    // A query of the same shape binds to the prepared plan.
+    let query = filter(2.into(), "other".into());
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&query), &order_by);
+    assert_eq!(
+        prepared.bind(&planner).unwrap(),
+        Some(planner.plan().unwrap())
+    );
+
+    // Non-`Eq` filters of `null` return nothing.
+    let query = FilterExpression::And(vec![
+        FilterExpression::Simple("a".to_string(), Operator::EQ, 2.into()),
+        FilterExpression::Simple("c".to_string(), Operator::LT, Value::Null),
+    ]);
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&query), &order_by);
+    assert_eq!(prepared.bind(&planner).unwrap(), Some(Plan::ReturnEmpty));
+
+    // Queries of other shapes don't bind.
+    let query = FilterExpression::Simple("a".to_string(), Operator::EQ, 2.into());
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&query), &order_by);
+    assert_eq!(prepared.bind(&planner).unwrap(), None);
+    let query = filter(2.into(), "other".into());
+    let query = FilterExpression::And(vec![
+        query,
+        FilterExpression::Simple("c".to_string(), Operator::EQ, 5.into()),
+    ]);
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, Some(&query), &order_by);
+    assert_eq!(prepared.bind(&planner).unwrap(), None);
+}
+
+#[test]
+fn test_generate_plan_or() {
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let a_eq = |a: i64| FilterExpression::Simple("a".to_string(), Operator::EQ, a.into());
+    let b_eq = |b: &str| FilterExpression::Simple("b".to_string(), Operator::EQ, b.into());
+    let plan = |filter: &FilterExpression, order_by: &SortOptions| {
+        QueryPlanner::new(&schema, &secondary_indexes, Some(filter), order_by).plan()
+    };
+
+    let filter = FilterExpression::Or(vec![a_eq(1), b_eq("test")]);
+    let Plan::Union(IndexScanUnion { branches, order_by }) =
+        plan(&filter, &Default::default()).unwrap()
+    else {
+        panic!("Union expected");
+    };
+    assert_eq!(
+        branches
+            .iter()
+            .map(|index_scans| index_scans
+                .iter()
+                .map(|index_scan| index_scan.index_id)
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![vec![0], vec![1]]
+    );
+    assert!(order_by.is_empty());
+
+    // Branches are merged by the sort options.
+    let order_by = SortOptions(vec![SortOption::new(
+        "a".to_string(),
+        SortDirection::Descending,
+    )]);
+    let filter = FilterExpression::Or(vec![
+        a_eq(1),
+        FilterExpression::Simple("a".to_string(), Operator::GT, 5.into()),
+    ]);
+    let Plan::Union(index_scan_union) = plan(&filter, &order_by).unwrap() else {
+        panic!("Union expected");
+    };
+    assert_eq!(index_scan_union.branches.len(), 2);
+    assert_eq!(
+        index_scan_union.order_by,
+        vec![(0, SortDirection::Descending)]
+    );
+
+    // `$and` distributes over `$or`, and identical branches are planned once.
+    let filter = FilterExpression::And(vec![
+        a_eq(1),
+        FilterExpression::Or(vec![b_eq("test"), b_eq("test")]),
+    ]);
+    assert!(matches!(
+        plan(&filter, &Default::default()).unwrap(),
+        Plan::IndexScans(index_scans) if index_scans.len() == 1 && index_scans[0].index_id == 3
+    ));
+
+    // Branches of `null` range filters return nothing, and an empty `$or` matches nothing.
+    let filter = FilterExpression::Or(vec![
+        a_eq(1),
+        FilterExpression::Simple("c".to_string(), Operator::LT, Value::Null),
+    ]);
+    assert!(matches!(
+        plan(&filter, &Default::default()).unwrap(),
+        Plan::IndexScans(_)
+    ));
+    assert_eq!(
+        plan(&FilterExpression::Or(vec![]), &Default::default()).unwrap(),
+        Plan::ReturnEmpty
+    );
+
+    // The expansion of `$or` is limited.
+    let filter = FilterExpression::And(
+        (0..6)
+            .map(|i| FilterExpression::Or(vec![a_eq(i), b_eq("test")]))
+            .collect(),
+    );
+    assert!(matches!(
+        plan(&filter, &Default::default()),
+        Err(PlanError::TooManyDisjuncts(_))
+    ));
+
+    // Unions are not prepared.
+    let filter = FilterExpression::Or(vec![a_eq(1), b_eq("test")]);
+    let planner = QueryPlanner::new(
+        &schema,
+        &secondary_indexes,
+        Some(&filter),
+        &Default::default(),
+    );
+    let prepared = PreparedPlan::default();
+    prepared.prepare(&planner.plan().unwrap());
+    assert_eq!(prepared.bind(&planner).unwrap(), None);
+}
+
+#[test]
+fn test_generate_plan_expression_index() {
+    let (schema, mut secondary_indexes) = test_utils::schema_1();
+    secondary_indexes.push(IndexDefinition::Expression(IndexExpression {
+        function: IndexFunction::Lower,
+        field_index: 1,
+    }));
+    let plan = |filter: &FilterExpression, order_by: &SortOptions| {
+        QueryPlanner::new(&schema, &secondary_indexes, Some(filter), order_by).plan()
+    };
+
+    // Expressions are numbered after the fields.
+    let filter = FilterExpression::Simple("lower(b)".to_string(), Operator::EQ, "test".into());
+    let Plan::IndexScans(index_scans) = plan(&filter, &Default::default()).unwrap() else {
+        panic!("IndexScan expected");
+    };
+    assert_eq!(index_scans.len(), 1);
+    assert_eq!(index_scans[0].index_id, 4);
+    assert_eq!(
+        index_scans[0].kind,
+        IndexScanKind::SortedInverted {
+            eq_filters: vec![(3, Field::String("test".to_string()))],
+            range_query: None,
+        }
+    );
+
+    let order_by = SortOptions(vec![SortOption::new(
+        "lower(b)".to_string(),
+        SortDirection::Descending,
+    )]);
+    let filter = FilterExpression::Simple("lower(b)".to_string(), Operator::GT, "a".into());
+    let Plan::IndexScans(index_scans) = plan(&filter, &order_by).unwrap() else {
+        panic!("IndexScan expected");
+    };
+    assert_eq!(index_scans[0].index_id, 4);
+
+    // Expressions without an index are not fields.
+    let filter = FilterExpression::Simple("upper(b)".to_string(), Operator::EQ, "TEST".into());
+    assert!(matches!(
+        plan(&filter, &Default::default()),
+        Err(PlanError::FieldNotFound(_))
+    ));
+
+    // Expressions can't be combined with other filters without a compound index.
+    let filter = FilterExpression::And(vec![
+        FilterExpression::Simple("a".to_string(), Operator::EQ, 1.into()),
+        FilterExpression::Simple("lower(b)".to_string(), Operator::EQ, "test".into()),
+    ]);
+    assert!(matches!(
+        plan(&filter, &Default::default()),
+        Err(PlanError::MatchingIndexNotFound(_))
+    ));
+}
+
+#[test]
+fn test_tiebreaker() {
+    use SortDirection::{Ascending, Descending};
+
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let sort_options = |options: &[(&str, SortDirection)]| {
+        SortOptions(
+            options
+                .iter()
+                .map(|(name, direction)| SortOption::new(name.to_string(), *direction))
+                .collect(),
+        )
+    };
+    let tiebreaker = |filter: Option<&FilterExpression>, order_by: &SortOptions| {
+        QueryPlanner::new(&schema, &secondary_indexes, filter, order_by)
+            .tiebreaker()
+            .unwrap()
+    };
+
+    // The primary key `a` breaks ties in the direction of the sort, unless it's a sort option or there's none.
+    assert_eq!(
+        tiebreaker(None, &sort_options(&[("b", Descending)])),
+        vec![(0, Descending)]
+    );
+    assert_eq!(
+        tiebreaker(None, &sort_options(&[("b", Ascending)])),
+        vec![(0, Ascending)]
+    );
+    assert!(tiebreaker(None, &sort_options(&[("b", Descending), ("a", Ascending)])).is_empty());
+    assert!(tiebreaker(None, &sort_options(&[])).is_empty());
+
+    // Sort options on fields of `Eq` filters don't set the direction of the scan.
+    let b_eq = FilterExpression::Simple("b".to_string(), Operator::EQ, Value::from("x"));
+    assert_eq!(
+        tiebreaker(
+            Some(&b_eq),
+            &sort_options(&[("c", Ascending), ("b", Descending)])
+        ),
+        vec![(0, Ascending)]
+    );
+    // A range filter that isn't sorted orders ties before the primary key.
+    let filter = FilterExpression::And(vec![
+        b_eq,
+        FilterExpression::Simple("c".to_string(), Operator::GT, Value::from(1)),
+    ]);
+    assert_eq!(
+        tiebreaker(Some(&filter), &sort_options(&[("b", Descending)])),
+        vec![(2, Descending), (0, Descending)]
+    );
+
+    assert_eq!(
+        QueryPlanner::new(
+            &schema,
+            &secondary_indexes,
+            None,
+            &sort_options(&[("b", Descending)])
+        )
+        .order_with_tiebreaker()
+        .unwrap(),
+        sort_options(&[("b", Descending), ("a", Descending)])
+    );
+}
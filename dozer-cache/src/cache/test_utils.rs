@@ -12,6 +12,7 @@ pub fn schema_0() -> SchemaWithIndex {
                 typ: dozer_types::types::FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             }],
             primary_index: vec![0],
         },
@@ -28,18 +29,21 @@ pub fn schema_1() -> SchemaWithIndex {
                     typ: dozer_types::types::FieldType::Int,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "b".to_string(),
                     typ: dozer_types::types::FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "c".to_string(),
                     typ: dozer_types::types::FieldType::Int,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![0],
@@ -63,12 +67,14 @@ pub fn schema_full_text() -> SchemaWithIndex {
                     typ: dozer_types::types::FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "bar".to_string(),
                     typ: dozer_types::types::FieldType::Text,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![0],
@@ -86,6 +92,7 @@ pub fn schema_empty_primary_index() -> SchemaWithIndex {
                 typ: dozer_types::types::FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             }],
             primary_index: vec![],
         },
@@ -102,12 +109,14 @@ pub fn schema_multi_indices() -> SchemaWithIndex {
                     typ: dozer_types::types::FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "text".to_string(),
                     typ: dozer_types::types::FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![0],
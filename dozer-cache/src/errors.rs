@@ -71,6 +71,8 @@ pub enum CacheError {
     },
     #[error("Internal thread panic: {0}")]
     InternalThreadPanic(#[source] tokio::task::JoinError),
+    #[error("Query timed out")]
+    QueryTimeout,
 }
 
 impl CacheError {
@@ -91,6 +93,13 @@ impl CacheError {
             CacheError::Storage(StorageError::Lmdb(dozer_storage::lmdb::Error::MapFull))
         )
     }
+
+    pub fn is_readers_full(&self) -> bool {
+        matches!(
+            self,
+            CacheError::Storage(StorageError::Lmdb(dozer_storage::lmdb::Error::ReadersFull))
+        )
+    }
 }
 
 #[derive(Error, Debug)]
@@ -137,6 +146,8 @@ pub enum IndexError {
     UnsupportedMultiRangeIndex,
     #[error("Compound_index is required for fields: {0}")]
     MissingCompoundIndex(String),
+    #[error("Invalid secondary index key: {0}")]
+    InvalidKey(#[source] CompareError),
 }
 
 #[derive(Error, Debug)]
@@ -151,6 +162,8 @@ pub enum PlanError {
     ConflictingSortOptions,
     #[error("Cannot have more than one range query")]
     RangeQueryLimit,
+    #[error("Filter expands to more than {0} alternatives of $or")]
+    TooManyDisjuncts(usize),
     #[error("Matching index not found. Try to add following secondary index configuration:\n{0}")]
     MatchingIndexNotFound(String),
 }
@@ -1,4 +1,10 @@
-use crate::cache::{expression::QueryExpression, CacheRecord, RoCache};
+use std::ops::ControlFlow;
+
+use crate::cache::{
+    expression::QueryExpression,
+    plan::{Plan, PreparedPlan},
+    CacheRecord, CacheStats, RoCache,
+};
 
 use super::cache::expression::FilterExpression;
 use crate::errors::CacheError;
@@ -40,7 +46,11 @@ impl CacheReader {
     }
 
     // TODO: Implement check_access
-    fn check_access(&self, _rec: &Record, _access_filter: &AccessFilter) -> Result<(), CacheError> {
+    pub fn check_access(
+        &self,
+        _rec: &Record,
+        _access_filter: &AccessFilter,
+    ) -> Result<(), CacheError> {
         Ok(())
     }
 
@@ -74,6 +84,48 @@ impl CacheReader {
         self.cache.count(query)
     }
 
+    /// Like `query`, but calls `f` with every record in order until it breaks, see `RoCache::for_each_record`.
+    pub fn for_each_record(
+        &self,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+        f: &mut dyn FnMut(CacheRecord) -> ControlFlow<()>,
+    ) -> Result<(), CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.for_each_record(query, f)
+    }
+
+    /// Like `query`, but with the plan of a query template.
+    pub fn query_prepared(
+        &self,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+        prepared: &PreparedPlan,
+    ) -> Result<Vec<CacheRecord>, CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.query_prepared(query, prepared)
+    }
+
+    /// Like `count`, but with the plan of a query template.
+    pub fn count_prepared(
+        &self,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+        prepared: &PreparedPlan,
+    ) -> Result<usize, CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.count_prepared(query, prepared)
+    }
+
+    pub fn explain(
+        &self,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+    ) -> Result<Plan, CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.explain(query)
+    }
+
     pub fn get_phase(&self) -> Result<Phase, CacheError> {
         if self.cache.is_snapshotting_done()? {
             Ok(Phase::Streaming)
@@ -82,6 +134,10 @@ impl CacheReader {
         }
     }
 
+    pub fn get_stats(&self) -> Result<CacheStats, CacheError> {
+        self.cache.get_stats()
+    }
+
     // Apply filter if specified in access
     fn apply_access_filter(&self, query: &mut QueryExpression, access_filter: AccessFilter) {
         // TODO: Use `fields` in `access_filter`.
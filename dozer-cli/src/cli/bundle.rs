@@ -0,0 +1,246 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use dozer_types::ingestion_types::{EthProviderConfig, S3Storage};
+use dozer_types::models::api_security::ApiSecurity;
+use dozer_types::models::config::Config;
+use dozer_types::models::connection::{Connection, ConnectionConfig};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_yaml;
+
+use crate::errors::BundleError;
+
+/// The version of the bundle format, bumped when bundles written by older versions can't be imported anymore.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// An app's config, connections and SQL, with its secrets replaced by placeholders of environment variables.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Bundle {
+    pub bundle_version: u32,
+    pub config: Config,
+}
+
+/// Serializes `config` as a YAML bundle.
+///
+/// Secrets are replaced by `{{{<CONNECTION>_<FIELD>}}}` placeholders, which are filled from the environment of the
+/// instance the bundle is imported into when its config is loaded. The JWT secret of the API reads
+/// `DOZER_API_JWT_SECRET`.
+pub fn export_bundle(config: &Config) -> Result<String, BundleError> {
+    let mut config = config.clone();
+    for connection in &mut config.connections {
+        redact_connection(connection);
+    }
+    if let Some(ApiSecurity::Jwt(secret)) = config
+        .api
+        .as_mut()
+        .and_then(|api| api.api_security.as_mut())
+    {
+        *secret = placeholder("dozer_api", "jwt_secret");
+    }
+    serde_yaml::to_string(&Bundle {
+        bundle_version: BUNDLE_VERSION,
+        config,
+    })
+    .map_err(BundleError::Serialize)
+}
+
+/// Parses a YAML bundle, returning its config and the environment variables its placeholders read.
+pub fn import_bundle(bundle: &str) -> Result<(Config, Vec<String>), BundleError> {
+    let bundle: Bundle = serde_yaml::from_str(bundle).map_err(BundleError::Parse)?;
+    if bundle.bundle_version != BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(bundle.bundle_version));
+    }
+    let config_yaml = serde_yaml::to_string(&bundle.config).map_err(BundleError::Serialize)?;
+    Ok((bundle.config, placeholder_variables(&config_yaml)))
+}
+
+/// Writes `config` to the config file at `path`, which is only overwritten if `force` is set.
+pub fn write_config(config: &Config, path: &Path, force: bool) -> Result<(), BundleError> {
+    if !force && path.exists() {
+        return Err(BundleError::ConfigExists(path.to_path_buf()));
+    }
+    let config_yaml = serde_yaml::to_string(config).map_err(BundleError::Serialize)?;
+    fs::write(path, config_yaml).map_err(|e| BundleError::FileSystem(path.to_path_buf(), e))
+}
+
+/// Triple braces keep the config loader from HTML escaping the secrets.
+fn placeholder(connection_name: &str, field: &str) -> String {
+    let variable = format!("{connection_name}_{field}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{{{{{{{variable}}}}}}}")
+}
+
+fn placeholder_variables(config_yaml: &str) -> Vec<String> {
+    config_yaml
+        .split("{{")
+        .skip(1)
+        .filter_map(|part| part.split_once("}}"))
+        .map(|(variable, _)| variable.trim_matches(|c| c == '{' || c == ' ').to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn redact_connection(connection: &mut Connection) {
+    let name = connection.name.as_str();
+    let redact = |field: &str, value: &mut String| *value = placeholder(name, field);
+    match &mut connection.config {
+        Some(ConnectionConfig::Postgres(config)) => {
+            if let Some(password) = &mut config.password {
+                redact("password", password);
+            }
+            // The url may carry the password.
+            if let Some(connection_url) = &mut config.connection_url {
+                redact("connection_url", connection_url);
+            }
+        }
+        // Urls of hosted nodes carry API keys.
+        Some(ConnectionConfig::Ethereum(config)) => match &mut config.provider {
+            Some(EthProviderConfig::Log(log)) => redact("wss_url", &mut log.wss_url),
+            Some(EthProviderConfig::Trace(trace)) => redact("https_url", &mut trace.https_url),
+            None => (),
+        },
+        Some(ConnectionConfig::Snowflake(config)) => redact("password", &mut config.password),
+        Some(ConnectionConfig::S3Storage(S3Storage {
+            details: Some(details),
+            ..
+        })) => {
+            redact("access_key_id", &mut details.access_key_id);
+            redact("secret_access_key", &mut details.secret_access_key);
+        }
+        Some(ConnectionConfig::Oracle(config)) => redact("password", &mut config.password),
+        Some(ConnectionConfig::Stripe(config)) => redact("api_key", &mut config.api_key),
+        Some(ConnectionConfig::MySQL(config)) => redact("password", &mut config.password),
+        Some(ConnectionConfig::SqlServer(config)) => redact("password", &mut config.password),
+        Some(ConnectionConfig::Iceberg(config)) => {
+            if let Some(token) = &mut config.token {
+                redact("token", token);
+            }
+        }
+        Some(ConnectionConfig::Webhook(config)) => {
+            if let Some(token) = &mut config.token {
+                redact("token", token);
+            }
+        }
+        // The url may carry the password.
+        Some(ConnectionConfig::RedisStreams(config)) => redact("url", &mut config.url),
+        Some(ConnectionConfig::Nats(config)) => {
+            if let Some(token) = &mut config.token {
+                redact("token", token);
+            }
+        }
+        Some(ConnectionConfig::Mqtt(config)) => {
+            if let Some(password) = &mut config.password {
+                redact("password", password);
+            }
+        }
+        Some(ConnectionConfig::Cassandra(config)) => {
+            if let Some(password) = &mut config.password {
+                redact("password", password);
+            }
+        }
+        Some(ConnectionConfig::Elasticsearch(config)) => {
+            if let Some(password) = &mut config.password {
+                redact("password", password);
+            }
+            if let Some(api_key) = &mut config.api_key {
+                redact("api_key", api_key);
+            }
+        }
+        Some(ConnectionConfig::Salesforce(config)) => {
+            if let Some(client_secret) = &mut config.client_secret {
+                redact("client_secret", client_secret);
+            }
+            if let Some(refresh_token) = &mut config.refresh_token {
+                redact("refresh_token", refresh_token);
+            }
+            if let Some(password) = &mut config.password {
+                redact("password", password);
+            }
+        }
+        Some(ConnectionConfig::GoogleSheets(config)) => {
+            if let Some(api_key) = &mut config.api_key {
+                redact("api_key", api_key);
+            }
+        }
+        Some(ConnectionConfig::Databricks(config)) => redact("token", &mut config.token),
+        Some(
+            ConnectionConfig::Grpc(_)
+            | ConnectionConfig::Kafka(_)
+            | ConnectionConfig::S3Storage(_)
+            | ConnectionConfig::LocalStorage(_)
+            | ConnectionConfig::DeltaLake(_)
+            | ConnectionConfig::Generator(_)
+            | ConnectionConfig::Firestore(_)
+            | ConnectionConfig::Kinesis(_)
+            | ConnectionConfig::DynamoDb(_),
+        )
+        | None => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::api_config::ApiConfig;
+    use dozer_types::models::connection::PostgresConfig;
+
+    use super::*;
+
+    #[test]
+    fn test_export_import_bundle() {
+        let config = Config {
+            app_name: "films".to_string(),
+            sql: Some("SELECT film_id, title INTO films FROM film;".to_string()),
+            connections: vec![Connection {
+                name: "pg-dev".to_string(),
+                config: Some(ConnectionConfig::Postgres(PostgresConfig {
+                    user: Some("postgres".to_string()),
+                    password: Some("postgres".to_string()),
+                    host: Some("localhost".to_string()),
+                    port: Some(5432),
+                    database: Some("pagila".to_string()),
+                    ..Default::default()
+                })),
+            }],
+            api: Some(ApiConfig {
+                api_security: Some(ApiSecurity::Jwt("dev-secret".to_string())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let bundle = export_bundle(&config).unwrap();
+        assert!(!bundle.contains("dev-secret"));
+        assert!(bundle.contains("{{{PG_DEV_PASSWORD}}}"));
+
+        let (imported, variables) = import_bundle(&bundle).unwrap();
+        assert_eq!(imported.sql, config.sql);
+        let Some(ConnectionConfig::Postgres(postgres)) = &imported.connections[0].config else {
+            panic!("Expected a Postgres connection");
+        };
+        assert_eq!(postgres.user.as_deref(), Some("postgres"));
+        assert_eq!(postgres.password.as_deref(), Some("{{{PG_DEV_PASSWORD}}}"));
+        assert_eq!(variables, vec!["DOZER_API_JWT_SECRET", "PG_DEV_PASSWORD"]);
+    }
+
+    #[test]
+    fn test_import_unsupported_version() {
+        let bundle = export_bundle(&Config::default())
+            .unwrap()
+            .replace("bundle_version: 1", "bundle_version: 2");
+        assert!(matches!(
+            import_bundle(&bundle),
+            Err(BundleError::UnsupportedVersion(2))
+        ));
+    }
+}
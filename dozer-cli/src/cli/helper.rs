@@ -2,13 +2,25 @@ use crate::errors::CliError;
 use crate::errors::OrchestrationError;
 use crate::simple::SimpleOrchestrator as Dozer;
 
+use crate::cli::bundle;
+#[cfg(feature = "bench")]
+use crate::cli::types::Bench;
+use crate::cli::types::{BundleExport, BundleImport, LineageFormat};
 use crate::config_helper::combine_config;
+use crate::errors::BundleError;
 use crate::errors::CliError::{ConfigurationFilePathNotProvided, FailedToFindConfigurationFiles};
+use crate::pipeline::rollup::rollup_endpoints;
+use crate::utils::get_sql_options;
+use dozer_types::log::info;
+#[cfg(feature = "bench")]
+use dozer_types::log::warn;
 use dozer_types::models::config::default_cache_max_map_size;
 use dozer_types::prettytable::{row, Table};
 use dozer_types::{models::config::Config, serde_yaml};
 use handlebars::Handlebars;
 use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
@@ -21,6 +33,10 @@ pub fn init_dozer(
     let mut config = runtime.block_on(load_config(config_paths, config_token))?;
 
     config = apply_overrides(&config, config_overrides)?;
+    // Report an invalid timezone when the config is loaded, rather than when the SQL is first planned.
+    get_sql_options(&config)?;
+    let rollup_endpoints = rollup_endpoints(&config.endpoints);
+    config.endpoints.extend(rollup_endpoints);
 
     let cache_max_map_size = config
         .cache_max_map_size
@@ -31,6 +47,132 @@ pub fn init_dozer(
     Ok(Dozer::new(config, Arc::new(runtime)))
 }
 
+/// Loads the config as written, without the endpoints `init_dozer` derives from it.
+pub fn load_app_config(
+    config_paths: Vec<String>,
+    config_token: Option<String>,
+    config_overrides: Vec<(String, serde_json::Value)>,
+) -> Result<Config, CliError> {
+    let runtime = Runtime::new().map_err(CliError::FailedToCreateTokioRuntime)?;
+    let config = runtime.block_on(load_config(config_paths, config_token))?;
+    apply_overrides(&config, config_overrides)
+}
+
+pub fn export_bundle(
+    config_paths: Vec<String>,
+    config_token: Option<String>,
+    config_overrides: Vec<(String, serde_json::Value)>,
+    export: BundleExport,
+) -> Result<(), OrchestrationError> {
+    let config = load_app_config(config_paths, config_token, config_overrides)?;
+    let bundle = bundle::export_bundle(&config)?;
+    let output = export
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{}.bundle.yaml", config.app_name)));
+    fs::write(&output, bundle).map_err(|e| BundleError::FileSystem(output.clone(), e))?;
+    info!("Exported app {} to {:?}", config.app_name, output);
+    Ok(())
+}
+
+pub fn import_bundle(import: &BundleImport) -> Result<(), OrchestrationError> {
+    let bundle = fs::read_to_string(&import.bundle)
+        .map_err(|e| BundleError::FileSystem(import.bundle.clone(), e))?;
+    let (config, variables) = bundle::import_bundle(&bundle)?;
+    bundle::write_config(&config, &import.output, import.force)?;
+    info!("Imported app {} to {:?}", config.app_name, import.output);
+    if !variables.is_empty() {
+        info!(
+            "Set these environment variables before running it: {}",
+            variables.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "bench")]
+pub fn run_bench(bench: &Bench) -> Result<(), OrchestrationError> {
+    let baseline = bench
+        .baseline
+        .as_deref()
+        .map(dozer_bench::Report::read)
+        .transpose()?;
+    let options = dozer_bench::BenchOptions {
+        scenarios: bench.scenarios.clone(),
+        records: bench.records,
+        sample_size: bench.sample_size,
+        measurement_time: None,
+        output_directory: bench.results_dir.clone(),
+    };
+    let report = dozer_bench::run(&options)?;
+    let output = bench
+        .output
+        .clone()
+        .unwrap_or_else(dozer_bench::report::default_report_path);
+    report.write(&output)?;
+
+    let mut table = Table::new();
+    table.add_row(row!["Scenario", "Mean", "95% CI", "Std dev", "Records/s"]);
+    for result in &report.results {
+        table.add_row(row![
+            result.id,
+            format_ns(result.mean_ns),
+            format!(
+                "{} - {}",
+                format_ns(result.mean_lower_ns),
+                format_ns(result.mean_upper_ns)
+            ),
+            format_ns(result.std_dev_ns),
+            result
+                .throughput_per_sec
+                .map_or(String::new(), |throughput| format!("{throughput:.0}")),
+        ]);
+    }
+    table.printstd();
+    info!(
+        "Wrote benchmark report of version {} to {:?}",
+        report.version, output
+    );
+
+    if let Some(baseline) = baseline {
+        if baseline.records != report.records {
+            warn!(
+                "Baseline of version {} has {} records, not {}, so the comparison isn't meaningful",
+                baseline.version, baseline.records, report.records
+            );
+        }
+        let mut table = Table::new();
+        table.add_row(row![
+            "Scenario",
+            format!("Mean ({})", baseline.version),
+            format!("Mean ({})", report.version),
+            "Change"
+        ]);
+        for comparison in report.compare(&baseline) {
+            table.add_row(row![
+                comparison.id,
+                format_ns(comparison.baseline_ns),
+                format_ns(comparison.mean_ns),
+                format!("{:+.1}%", comparison.change * 100.0),
+            ]);
+        }
+        table.printstd();
+    }
+    Ok(())
+}
+
+#[cfg(feature = "bench")]
+fn format_ns(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{ns:.0} ns")
+    }
+}
+
 pub fn list_sources(
     config_paths: Vec<String>,
     config_token: Option<String>,
@@ -70,6 +212,42 @@ pub fn list_sources(
     Ok(())
 }
 
+pub fn print_lineage(dozer: &Dozer, format: LineageFormat) -> Result<(), OrchestrationError> {
+    let lineage = dozer.lineage()?;
+    match format {
+        LineageFormat::Json => {
+            let json =
+                serde_json::to_string_pretty(&lineage).map_err(CliError::SerializeLineageToJson)?;
+            println!("{json}");
+        }
+        LineageFormat::Table => {
+            let mut table = Table::new();
+            table.add_row(row!["Table", "Column", "Expression", "Sources"]);
+            for table_lineage in lineage {
+                for column in table_lineage.columns {
+                    let sources = column
+                        .sources
+                        .iter()
+                        .map(|source| match &source.table {
+                            Some(table) => format!("{table}.{}", source.column),
+                            None => source.column.clone(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    table.add_row(row![
+                        table_lineage.table,
+                        column.column,
+                        column.expression,
+                        sources
+                    ]);
+                }
+            }
+            table.printstd();
+        }
+    }
+    Ok(())
+}
+
 async fn load_config(
     config_url_or_paths: Vec<String>,
     config_token: Option<String>,
@@ -113,6 +113,12 @@ pub fn generate_connection(connection_name: &str) -> Connection {
                 database: Some("users".to_owned()),
                 sslmode: None,
                 connection_url: None,
+                publication: None,
+                trigger_based: None,
+                poll_interval_ms: None,
+                on_schema_change: None,
+                snapshot_parallelism: None,
+                snapshot_chunk_size: None,
             };
             let connection: Connection = Connection {
                 name: "postgres".to_owned(),
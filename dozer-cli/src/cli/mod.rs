@@ -1,8 +1,14 @@
+pub mod bundle;
 #[cfg(feature = "cloud")]
 pub mod cloud;
 mod helper;
 mod init;
 pub mod types;
 
-pub use helper::{init_dozer, list_sources, load_config_from_file, LOGO};
+#[cfg(feature = "bench")]
+pub use helper::run_bench;
+pub use helper::{
+    export_bundle, import_bundle, init_dozer, list_sources, load_app_config, load_config_from_file,
+    print_lineage, LOGO,
+};
 pub use init::{generate_config_repl, generate_connection};
@@ -1,10 +1,12 @@
-use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 use super::helper::{DESCRIPTION, LOGO};
 
 #[cfg(feature = "cloud")]
 use crate::cli::cloud::Cloud;
-use dozer_types::constants::DEFAULT_CONFIG_PATH_PATTERNS;
+use dozer_types::constants::{DEFAULT_CONFIG_PATH, DEFAULT_CONFIG_PATH_PATTERNS};
 
 #[derive(Parser, Debug)]
 #[command(author, version, name = "dozer")]
@@ -68,6 +70,34 @@ pub enum Commands {
     Connectors(ConnectorCommand),
     #[command(about = "Change security settings")]
     Security(Security),
+    #[command(
+        about = "Show column lineage",
+        long_about = "Show which source columns and expressions every output column of the \
+            transforms is derived from"
+    )]
+    Lineage(Lineage),
+    #[command(
+        about = "Export an endpoint",
+        long_about = "Export a consistent snapshot of an endpoint cache to a Parquet, CSV or \
+            JSONL file, optionally filtered and with selected columns"
+    )]
+    Dump(Dump),
+    #[command(about = "Manage endpoint caches")]
+    Cache(Cache),
+    #[command(
+        about = "Export or import the app as a bundle",
+        long_about = "Move an app between environments as a portable YAML bundle of its config, \
+            connections and SQL"
+    )]
+    Bundle(Bundle),
+    #[command(
+        about = "Run performance benchmarks",
+        long_about = "Run reproducible performance scenarios on generated data: snapshot \
+            throughput, join and aggregation rates of SQL pipelines, and cache query latency. \
+            Writes a JSON report that can be compared with a report of another version"
+    )]
+    #[cfg(feature = "bench")]
+    Bench(Bench),
     #[cfg(feature = "cloud")]
     #[command(about = "Deploy cloud applications")]
     Cloud(Cloud),
@@ -100,6 +130,21 @@ pub enum RunCommands {
             API endpoints through REST and GRPC (depends on configuration)"
     )]
     Api,
+    #[command(
+        about = "Run api gateway",
+        long_about = "Run api gateway. Api gateway serves the REST and GRPC endpoints of the apps \
+            configured in `api.gateway` as if they were one app"
+    )]
+    Gateway,
+    #[command(
+        about = "Run app instance on its schedule",
+        long_about = "Run the pipeline of the app at the times of the cron expression in \
+            `app.schedule`, for apps whose sources end after reading a snapshot. A run is skipped \
+            if the previous one is still running"
+    )]
+    Scheduled,
+    #[command(about = "Show the history of scheduled runs")]
+    History,
 }
 
 #[derive(Debug, Args)]
@@ -130,6 +175,74 @@ pub struct Deploy {
     pub password: Option<String>,
 }
 
+#[derive(Debug, Args)]
+pub struct Lineage {
+    #[arg(short = 'f', long, value_enum, default_value_t = LineageFormat::Table)]
+    pub format: LineageFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LineageFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct Dump {
+    /// The name of the endpoint to export.
+    pub endpoint: String,
+    /// The output file. Defaults to `<endpoint>.<format>` in the current directory.
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+    #[arg(short = 'f', long, value_enum, default_value_t = DumpFormat::Parquet)]
+    pub format: DumpFormat,
+    /// JSON query selecting the records to export. All records are exported by default.
+    #[arg(short = 'q', long)]
+    pub query: Option<String>,
+    /// Comma separated names of the columns to export. All columns are exported by default.
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    Parquet,
+    Csv,
+    Jsonl,
+}
+
+#[derive(Debug, Args)]
+pub struct Cache {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommands {
+    #[command(
+        about = "Bulk load files into an endpoint cache",
+        long_about = "Bulk load Parquet or CSV files into the cache of the latest build of an \
+            endpoint, bypassing the pipeline. File columns must match the endpoint schema. \
+            Meant for bootstrapping or disaster recovery while the app is not running. \
+            The cache must be empty, and all files are loaded at once. The app then builds \
+            the cache from the endpoint log, starting at the given log position"
+    )]
+    Load(Load),
+}
+
+#[derive(Debug, Args)]
+pub struct Load {
+    /// The name of the endpoint to load records into.
+    pub endpoint: String,
+    /// The Parquet (`.parquet`) or CSV (`.csv`) files to load.
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+    /// The position in the endpoint log the files are a snapshot of. The operations before it
+    /// must be in the files, and the ones from it on are applied to the cache by the app.
+    #[arg(long)]
+    pub log_position: u64,
+}
+
 #[derive(Debug, Args)]
 pub struct ConnectorCommand {
     #[arg(short = 'f')]
@@ -167,3 +280,69 @@ mod tests {
         );
     }
 }
+
+#[derive(Debug, Args)]
+pub struct Bundle {
+    #[command(subcommand)]
+    pub command: BundleCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BundleCommands {
+    #[command(
+        about = "Export the app to a bundle",
+        long_about = "Export the config, connections and SQL of the app to a YAML bundle. Secrets \
+            of connections and the API are replaced by placeholders, which are filled from \
+            environment variables where the bundle is imported"
+    )]
+    Export(BundleExport),
+    #[command(
+        about = "Import an app from a bundle",
+        long_about = "Write the config of a bundle to a config file, and list the environment \
+            variables its secrets are read from"
+    )]
+    Import(BundleImport),
+}
+
+#[derive(Debug, Args)]
+pub struct BundleExport {
+    /// The output file. Defaults to `<app_name>.bundle.yaml` in the current directory.
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+}
+
+#[cfg(feature = "bench")]
+#[derive(Debug, Args)]
+pub struct Bench {
+    /// The scenarios to run, comma separated: snapshot, join, aggregation or cache_query. Runs all
+    /// scenarios if not given.
+    #[arg(value_delimiter = ',')]
+    pub scenarios: Vec<String>,
+    /// Number of records of the generated tables.
+    #[arg(long, default_value_t = 100_000)]
+    pub records: usize,
+    /// Number of samples of every scenario, at least 10.
+    #[arg(long, default_value_t = 10)]
+    pub sample_size: usize,
+    /// The report file. Defaults to `dozer-bench-<version>.json` in the current directory.
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+    /// A report of a previous run to compare the results with.
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+    /// The directory benchmark results are kept in between runs.
+    #[arg(long, default_value = ".dozer/bench")]
+    pub results_dir: String,
+}
+
+#[derive(Debug, Args)]
+pub struct BundleImport {
+    /// The bundle file.
+    pub bundle: PathBuf,
+    /// The config file to write.
+    #[arg(short = 'o', long, default_value = DEFAULT_CONFIG_PATH)]
+    pub output: PathBuf,
+    /// Overwrite the config file if it exists.
+    #[arg(short = 'f', long)]
+    pub force: bool,
+}
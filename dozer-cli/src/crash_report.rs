@@ -0,0 +1,265 @@
+use std::backtrace::Backtrace;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, PanicInfo};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use dozer_core::errors::ExecutionError;
+use dozer_types::chrono::Utc;
+use dozer_types::models::config::Config;
+use dozer_types::models::crash_report::CrashReportConfig;
+use dozer_types::serde::Serialize;
+use dozer_types::serde_yaml::{self, Value};
+use dozer_types::tracing::{error, info};
+use reqwest::header::CONTENT_TYPE;
+
+use crate::cli::bundle::{export_bundle, Bundle};
+use crate::errors::OrchestrationError;
+use crate::ConnectorError;
+
+const REDACTED: &str = "[REDACTED]";
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+struct CrashReport<'a> {
+    timestamp: String,
+    dozer_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    app_name: &'a str,
+    /// Tells the crashes of an app with the same config apart from the others.
+    config_fingerprint: &'a str,
+    /// The config as YAML, with its secrets replaced by placeholders.
+    config: Option<&'a str>,
+    thread: String,
+    location: Option<String>,
+    message: String,
+    backtrace: String,
+    logs: Vec<String>,
+}
+
+/// What's known of the app before it panics.
+#[derive(Debug)]
+struct CrashReporter {
+    dir: PathBuf,
+    endpoint: Option<String>,
+    max_log_lines: usize,
+    app_name: String,
+    config: Option<String>,
+    config_fingerprint: String,
+    /// The values of the config's secrets, redacted from the panic message, backtrace and logs too.
+    secrets: Vec<String>,
+}
+
+/// Writes a crash report of every panic if `config` opts in, before the current panic hook runs.
+pub fn set_crash_report_hook(config: &Config) {
+    let Some(crash_report) = &config.crash_report else {
+        return;
+    };
+    let reporter = CrashReporter::new(config, crash_report);
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        reporter.report(panic_info);
+        previous_hook(panic_info);
+    }));
+}
+
+impl CrashReporter {
+    fn new(config: &Config, crash_report: &CrashReportConfig) -> Self {
+        let (redacted_config, secrets) = match redact_config(config) {
+            Ok((redacted_config, secrets)) => (Some(redacted_config), secrets),
+            Err(e) => {
+                error!("Crash reports won't include the config: {e}");
+                (None, vec![])
+            }
+        };
+        let mut hasher = DefaultHasher::new();
+        redacted_config.hash(&mut hasher);
+        Self {
+            dir: crash_report.dir.as_ref().map_or_else(
+                || Path::new(&config.home_dir).join("crash_reports"),
+                PathBuf::from,
+            ),
+            endpoint: crash_report.endpoint.clone(),
+            max_log_lines: crash_report.max_log_lines as usize,
+            app_name: config.app_name.clone(),
+            config: redacted_config,
+            config_fingerprint: format!("{:016x}", hasher.finish()),
+            secrets,
+        }
+    }
+
+    /// Failing to report is logged, as panicking in a panic hook aborts the process.
+    fn report(&self, panic_info: &PanicInfo) {
+        let now = Utc::now();
+        let report = CrashReport {
+            timestamp: now.to_rfc3339(),
+            dozer_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            app_name: &self.app_name,
+            config_fingerprint: &self.config_fingerprint,
+            config: self.config.as_deref(),
+            thread: thread::current().name().unwrap_or("<unnamed>").to_string(),
+            location: panic_info.location().map(ToString::to_string),
+            message: self.redact(panic_message(panic_info)),
+            backtrace: self.redact(Backtrace::force_capture().to_string()),
+            logs: dozer_tracing::recent_logs(self.max_log_lines)
+                .into_iter()
+                .map(|line| self.redact(line))
+                .collect(),
+        };
+        let report = match dozer_types::serde_json::to_string_pretty(&report) {
+            Ok(report) => report,
+            Err(e) => {
+                error!("Failed to serialize crash report: {e}");
+                return;
+            }
+        };
+
+        let path = self
+            .dir
+            .join(format!("crash-{}.json", now.format("%Y%m%dT%H%M%S%.3fZ")));
+        match fs::create_dir_all(&self.dir).and_then(|_| fs::write(&path, &report)) {
+            Ok(()) => info!("Crash report written to {path:?}"),
+            Err(e) => error!("Failed to write crash report to {path:?}: {e}"),
+        }
+        if let Some(endpoint) = &self.endpoint {
+            match post(endpoint.clone(), report) {
+                Ok(()) => info!("Crash report posted to {endpoint}"),
+                Err(e) => error!("Failed to post crash report to {endpoint}: {e}"),
+            }
+        }
+    }
+
+    fn redact(&self, mut text: String) -> String {
+        for secret in &self.secrets {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+        text
+    }
+}
+
+/// The config as YAML with its secrets replaced by placeholders, the way bundles are exported, and the secrets'
+/// values.
+fn redact_config(config: &Config) -> Result<(String, Vec<String>), String> {
+    let bundle = export_bundle(config).map_err(|e| e.to_string())?;
+    let bundle: Bundle = serde_yaml::from_str(&bundle).map_err(|e| e.to_string())?;
+    let redacted = serde_yaml::to_value(&bundle.config).map_err(|e| e.to_string())?;
+    let original = serde_yaml::to_value(config).map_err(|e| e.to_string())?;
+    let mut secrets = vec![];
+    collect_secrets(&original, &redacted, &mut secrets);
+    let redacted = serde_yaml::to_string(&redacted).map_err(|e| e.to_string())?;
+    Ok((redacted, secrets))
+}
+
+/// Strings of `original` replaced in `redacted` are secrets.
+fn collect_secrets(original: &Value, redacted: &Value, secrets: &mut Vec<String>) {
+    match (original, redacted) {
+        (Value::Mapping(original), Value::Mapping(redacted)) => {
+            for (key, value) in original {
+                if let Some(redacted_value) = redacted.get(key) {
+                    collect_secrets(value, redacted_value, secrets);
+                }
+            }
+        }
+        (Value::Sequence(original), Value::Sequence(redacted)) => {
+            for (value, redacted_value) in original.iter().zip(redacted) {
+                collect_secrets(value, redacted_value, secrets);
+            }
+        }
+        (Value::Tagged(original), Value::Tagged(redacted)) => {
+            collect_secrets(&original.value, &redacted.value, secrets);
+        }
+        (Value::String(value), Value::String(redacted_value))
+            if !value.is_empty() && value != redacted_value =>
+        {
+            secrets.push(value.clone());
+        }
+        _ => (),
+    }
+}
+
+fn panic_message(panic_info: &PanicInfo) -> String {
+    let payload = panic_info.payload();
+    if let Some(e) = payload.downcast_ref::<OrchestrationError>() {
+        e.to_string()
+    } else if let Some(e) = payload.downcast_ref::<ConnectorError>() {
+        e.to_string()
+    } else if let Some(e) = payload.downcast_ref::<ExecutionError>() {
+        e.to_string()
+    } else if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        panic_info.to_string()
+    }
+}
+
+/// Posts on a thread of its own, as the panicking thread may be running a runtime already.
+fn post(endpoint: String, report: String) -> Result<(), String> {
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        runtime
+            .block_on(async {
+                reqwest::Client::new()
+                    .post(&endpoint)
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(report)
+                    .timeout(POST_TIMEOUT)
+                    .send()
+                    .await?
+                    .error_for_status()
+            })
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .join()
+    .map_err(|_| "posting thread panicked".to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::connection::{Connection, ConnectionConfig, PostgresConfig};
+
+    use super::*;
+
+    #[test]
+    fn test_redact_config() {
+        let config = Config {
+            app_name: "films".to_string(),
+            connections: vec![Connection {
+                name: "pg".to_string(),
+                config: Some(ConnectionConfig::Postgres(PostgresConfig {
+                    user: Some("postgres".to_string()),
+                    password: Some("hunter2".to_string()),
+                    ..Default::default()
+                })),
+            }],
+            crash_report: Some(CrashReportConfig::default()),
+            ..Default::default()
+        };
+
+        let reporter = CrashReporter::new(&config, config.crash_report.as_ref().unwrap());
+        assert_eq!(reporter.secrets, vec!["hunter2"]);
+        let redacted_config = reporter.config.as_deref().unwrap();
+        assert!(!redacted_config.contains("hunter2"));
+        assert!(redacted_config.contains("{{{PG_PASSWORD}}}"));
+        assert_eq!(
+            reporter.redact("connection failed for password hunter2".to_string()),
+            "connection failed for password [REDACTED]"
+        );
+        assert_eq!(
+            reporter.dir,
+            Path::new(&config.home_dir).join("crash_reports")
+        );
+    }
+}
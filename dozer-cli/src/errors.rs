@@ -19,6 +19,7 @@ use dozer_core::errors::ExecutionError;
 use dozer_ingestion::errors::ConnectorError;
 use dozer_sql::pipeline::errors::PipelineError;
 use dozer_types::errors::internal::BoxedError;
+use dozer_types::errors::types::TypeError;
 use dozer_types::thiserror::Error;
 use dozer_types::{serde_yaml, thiserror};
 
@@ -42,6 +43,8 @@ pub enum OrchestrationError {
     NoBuildFound(String),
     #[error("Failed to create log: {0}")]
     CreateLog(#[from] dozer_cache::dozer_log::replication::Error),
+    #[error("Failed to open provenance store {0:?}: {1}")]
+    OpenProvenance(PathBuf, #[source] dozer_storage::errors::StorageError),
     #[error("Failed to login: {0}")]
     CloudLoginFailed(#[from] CloudLoginError),
     #[error("Credential Error: {0}")]
@@ -52,6 +55,8 @@ pub enum OrchestrationError {
     GenerateTokenFailed(#[source] AuthError),
     #[error("Missing api config or security input")]
     MissingSecurityConfig,
+    #[error("Missing api gateway config")]
+    MissingGatewayConfig,
     #[error("Cloud service error: {0}")]
     CloudError(#[from] CloudError),
     #[error("Failed to initialize api server: {0}")]
@@ -84,6 +89,10 @@ pub enum OrchestrationError {
     PipelineValidationError,
     #[error("Table name specified in endpoint not found: {0:?}")]
     EndpointTableNotFound(String),
+    #[error("Table {1:?} read by operator {0} not found")]
+    OperatorTableNotFound(String, String),
+    #[error("Table {1:?} read by router {0} not found")]
+    RouterTableNotFound(String, String),
     #[error("Duplicate table name found: {0:?}")]
     DuplicateTable(String),
     #[error("No endpoints initialized in the config provided")]
@@ -94,6 +103,19 @@ pub enum OrchestrationError {
     FailedToReadOrganisationName(#[source] io::Error),
     #[error(transparent)]
     LiveError(#[from] LiveError),
+    #[error("Failed to dump endpoint: {0}")]
+    DumpFailed(#[from] DumpError),
+    #[error("Failed to load endpoint cache: {0}")]
+    LoadFailed(#[from] LoadError),
+    #[error("Invalid rollup: {0}")]
+    InvalidRollup(#[from] RollupError),
+    #[error("Bundle failed: {0}")]
+    BundleFailed(#[from] BundleError),
+    #[error("Scheduled run failed: {0}")]
+    ScheduleFailed(#[from] ScheduleError),
+    #[cfg(feature = "bench")]
+    #[error("Benchmark failed: {0}")]
+    BenchFailed(#[from] dozer_bench::BenchError),
 }
 
 #[derive(Error, Debug)]
@@ -124,6 +146,10 @@ pub enum CliError {
     MissingConfigOverride(String),
     #[error("Failed to deserialize config from json: {0}")]
     DeserializeConfigFromJson(#[source] serde_json::Error),
+    #[error("Failed to serialize lineage to json: {0}")]
+    SerializeLineageToJson(#[source] serde_json::Error),
+    #[error(transparent)]
+    InvalidTimezone(TypeError),
 }
 
 #[derive(Error, Debug)]
@@ -196,6 +222,8 @@ pub enum BuildError {
     },
     #[error("Field not found at position {0}")]
     FieldNotFound(String),
+    #[error("Invalid index expression {0}. Expected lower(<string field>), upper(<string field>) or date(<timestamp field>)")]
+    InvalidIndexExpression(String),
     #[error("File system error {0:?}: {1}")]
     FileSystem(PathBuf, std::io::Error),
     #[error("Cannot load existing schema: {0}")]
@@ -262,3 +290,102 @@ pub enum CloudContextError {
     #[error("App id not found in configuration. You need to run \"deploy\" or \"set-app\" first")]
     AppIdNotFound,
 }
+
+#[derive(Debug, Error)]
+pub enum DumpError {
+    #[error("Endpoint {0} not found in the config")]
+    EndpointNotFound(String),
+    #[error("Cache of endpoint {0} not found. Have you run `dozer run`?")]
+    CacheNotFound(String),
+    #[error("Column {0} not found in endpoint")]
+    ColumnNotFound(String),
+    #[error("Failed to parse query: {0}")]
+    ParseQuery(#[source] serde_json::Error),
+    #[error("Cache error: {0}")]
+    Cache(#[from] CacheError),
+    #[error("File system error {0:?}: {1}")]
+    FileSystem(PathBuf, #[source] std::io::Error),
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] dozer_types::arrow::error::ArrowError),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Failed to convert record to JSON: {0}")]
+    ToJson(#[from] dozer_types::errors::types::CannotConvertF64ToJson),
+    #[error("Failed to write JSON: {0}")]
+    WriteJson(#[source] serde_json::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("Endpoint {0} not found in the config")]
+    EndpointNotFound(String),
+    #[error("Cannot load schema of endpoint: {0}")]
+    LoadSchema(#[source] SchemaError),
+    #[error("Unsupported file {0:?}. Only `.parquet` and `.csv` files can be loaded")]
+    UnsupportedFormat(PathBuf),
+    #[error("Column {1} of endpoint is missing in {0:?}")]
+    MissingColumn(PathBuf, String),
+    #[error("Column {1} in {0:?} is not in endpoint schema")]
+    UnexpectedColumn(PathBuf, String),
+    #[error("Column {column} in {path:?} has type {actual}, but endpoint expects {expected}")]
+    ColumnTypeMismatch {
+        path: PathBuf,
+        column: String,
+        expected: dozer_types::arrow::datatypes::DataType,
+        actual: dozer_types::arrow::datatypes::DataType,
+    },
+    #[error("Cache error: {0}")]
+    Cache(#[from] CacheError),
+    #[error("The cache of endpoint {0} is not empty. Files can only be loaded into a new cache, before the app builds it from the endpoint log")]
+    CacheNotEmpty(String),
+    #[error("File system error {0:?}: {1}")]
+    FileSystem(PathBuf, #[source] std::io::Error),
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] dozer_types::arrow::error::ArrowError),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Failed to convert record: {0}")]
+    FromArrow(#[from] dozer_types::arrow_types::errors::FromArrowError),
+}
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    #[error("Failed to serialize bundle: {0}")]
+    Serialize(#[source] serde_yaml::Error),
+    #[error("Failed to parse bundle: {0}")]
+    Parse(#[source] serde_yaml::Error),
+    #[error("Bundle version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("Config file {0:?} already exists. Use --force to overwrite it")]
+    ConfigExists(PathBuf),
+    #[error("File system error {0:?}: {1}")]
+    FileSystem(PathBuf, #[source] std::io::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum RollupError {
+    #[error("Rollup {0} has no group by columns")]
+    NoGroupBy(String),
+    #[error("Rollup {0} has no aggregates")]
+    NoAggregates(String),
+    #[error("Unknown aggregate function {1} in rollup {0}")]
+    UnknownFunction(String, String),
+    #[error("Aggregate function {1} in rollup {0} requires a column")]
+    MissingColumn(String, String),
+}
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("Missing `app.schedule` config")]
+    MissingConfig,
+    #[error("Invalid cron expression {0:?}: {1}")]
+    InvalidCron(String, #[source] cron::error::Error),
+    #[error("Failed to read run history {0:?}: {1}")]
+    ReadHistory(PathBuf, #[source] std::io::Error),
+    #[error("Failed to parse run history {0:?}: {1}")]
+    ParseHistory(PathBuf, #[source] serde_json::Error),
+    #[error("Failed to serialize run history: {0}")]
+    SerializeHistory(#[source] serde_json::Error),
+    #[error("Failed to write run history {0:?}: {1}")]
+    WriteHistory(PathBuf, #[source] std::io::Error),
+}
@@ -6,7 +6,10 @@ pub mod shutdown;
 pub mod simple;
 mod ui_helper;
 use dozer_core::{app::AppPipeline, errors::ExecutionError};
-use dozer_sql::pipeline::{builder::statement_to_pipeline, errors::PipelineError};
+use dozer_sql::pipeline::{
+    builder::{statement_to_pipeline, SqlOptions},
+    errors::PipelineError,
+};
 use dozer_types::log::debug;
 use errors::OrchestrationError;
 use shutdown::ShutdownSender;
@@ -22,6 +25,7 @@ pub mod cloud_app_context;
 mod cloud_helper;
 pub mod config_helper;
 pub mod console_helper;
+mod crash_report;
 #[cfg(feature = "cloud")]
 mod progress_printer;
 #[cfg(test)]
@@ -57,15 +61,19 @@ pub trait CloudOrchestrator {
 }
 
 // Re-exports
+pub use crash_report::set_crash_report_hook;
 pub use dozer_ingestion::{
     connectors::{get_connector, TableInfo},
     errors::ConnectorError,
 };
 pub use dozer_sql::pipeline::builder::QueryContext;
 pub use ui_helper::config_to_ui_dag;
-pub fn wrapped_statement_to_pipeline(sql: &str) -> Result<QueryContext, PipelineError> {
+pub fn wrapped_statement_to_pipeline(
+    sql: &str,
+    options: SqlOptions,
+) -> Result<QueryContext, PipelineError> {
     let mut pipeline = AppPipeline::new();
-    statement_to_pipeline(sql, &mut pipeline, None)
+    statement_to_pipeline(sql, &mut pipeline, None, options)
 }
 
 #[cfg(feature = "cloud")]
@@ -3,7 +3,7 @@ use dozer_types::errors::internal::BoxedError;
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
 
-use crate::errors::CliError;
+use crate::errors::{BundleError, CliError};
 
 #[derive(Error, Debug)]
 pub enum LiveError {
@@ -22,4 +22,6 @@ pub enum LiveError {
 
     #[error(transparent)]
     PipelineError(#[from] PipelineError),
+    #[error(transparent)]
+    BundleError(#[from] BundleError),
 }
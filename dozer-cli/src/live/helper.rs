@@ -35,6 +35,8 @@ pub fn map_insert_operation(
         new_id: None,
         new: Some(map_record(record)),
         endpoint_name,
+        seq: None,
+        changed_fields: None,
     }
 }
 
@@ -48,6 +50,8 @@ pub fn map_delete_operation(
         new: Some(map_record(record)),
         new_id: None,
         endpoint_name,
+        seq: None,
+        changed_fields: None,
     }
 }
 
@@ -56,11 +60,14 @@ pub fn map_update_operation(
     old: dozer_types::types::Record,
     new: dozer_types::types::Record,
 ) -> Operation {
+    let changed_fields = old.diff(&new).as_bytes().to_vec();
     Operation {
         typ: OperationType::Update as i32,
         old: Some(map_record(old)),
         new: Some(map_record(new)),
         new_id: None,
         endpoint_name,
+        seq: None,
+        changed_fields: Some(changed_fields),
     }
 }
@@ -5,8 +5,9 @@ use dozer_types::{
     grpc_types::{
         live::{
             code_service_server::{CodeService, CodeServiceServer},
-            CommonRequest, CommonResponse, DotResponse, LiveResponse, RunSqlRequest,
-            SchemasResponse, SourcesRequest, SqlRequest, SqlResponse,
+            BundleResponse, CommonRequest, CommonResponse, DotResponse, ImportBundleRequest,
+            ImportBundleResponse, LineageResponse, LiveResponse, RunSqlRequest, SchemasResponse,
+            SourcesRequest, SqlRequest, SqlResponse, ValidateSqlRequest, ValidateSqlResponse,
         },
         types::Operation,
     },
@@ -144,6 +145,21 @@ impl CodeService for LiveServer {
         }
     }
 
+    async fn validate_sql(
+        &self,
+        request: Request<ValidateSqlRequest>,
+    ) -> Result<Response<ValidateSqlResponse>, Status> {
+        let state = self.state.clone();
+        let request = request.into_inner();
+        let handle = std::thread::spawn(move || state.validate_sql(request.sql, request.sources));
+        let res = handle.join().unwrap();
+
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
     async fn run_sql(
         &self,
         request: Request<RunSqlRequest>,
@@ -167,6 +183,44 @@ impl CodeService for LiveServer {
         self.state.stop_sql();
         Ok(Response::new(CommonResponse {}))
     }
+
+    async fn lineage(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<LineageResponse>, Status> {
+        let res = self.state.get_lineage();
+
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn export_bundle(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<BundleResponse>, Status> {
+        let state = self.state.clone();
+        let handle = std::thread::spawn(move || state.export_bundle());
+        let res = handle.join().unwrap();
+
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn import_bundle(
+        &self,
+        request: Request<ImportBundleRequest>,
+    ) -> Result<Response<ImportBundleResponse>, Status> {
+        let res = self.state.import_bundle(request.into_inner().bundle);
+
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
 }
 
 pub async fn serve(
@@ -1,3 +1,5 @@
+use std::path::Path;
+use std::sync::Arc;
 use std::thread::JoinHandle;
 
 use clap::Parser;
@@ -10,8 +12,13 @@ use dozer_core::{app::AppPipeline, dag_schemas::DagSchemas, petgraph::dot};
 use dozer_ingestion::connectors::get_connector;
 use dozer_sql::pipeline::builder::statement_to_pipeline;
 use dozer_types::{
+    constants::DEFAULT_CONFIG_PATH,
     grpc_types::{
-        live::{DotResponse, LiveApp, LiveResponse, Schema, SchemasResponse, SqlResponse},
+        live::{
+            BundleResponse, ColumnLineage, DotResponse, ImportBundleResponse, LineageResponse,
+            LiveApp, LiveResponse, Schema, SchemasResponse, SourceColumn, SqlError, SqlResponse,
+            TableLineage, ValidateSqlResponse,
+        },
         types::Operation,
     },
     indicatif::MultiProgress,
@@ -24,13 +31,17 @@ use dozer_types::{
 };
 
 use crate::{
-    cli::{init_dozer, types::Cli},
+    cli::{
+        bundle::{export_bundle, import_bundle, write_config},
+        init_dozer, load_app_config,
+        types::Cli,
+    },
     errors::OrchestrationError,
     live::helper::map_operation,
-    pipeline::PipelineBuilder,
+    pipeline::{information_schema::INFORMATION_SCHEMA, PipelineBuilder, SchemaDriftMonitor},
     shutdown::{self, ShutdownReceiver, ShutdownSender},
     simple::SimpleOrchestrator,
-    utils::get_app_grpc_config,
+    utils::{get_app_grpc_config, get_sql_options},
 };
 
 use super::LiveError;
@@ -126,28 +137,109 @@ impl LiveState {
         generate_dot(dozer).map_err(|e| LiveError::BuildError(Box::new(e)))
     }
 
+    pub fn get_lineage(&self) -> Result<LineageResponse, LiveError> {
+        let dozer = self.get_dozer()?;
+        let lineage = dozer
+            .lineage()
+            .map_err(|e| LiveError::BuildError(Box::new(e)))?;
+        let tables = lineage
+            .into_iter()
+            .map(|table| TableLineage {
+                table: table.table,
+                columns: table
+                    .columns
+                    .into_iter()
+                    .map(|column| ColumnLineage {
+                        column: column.column,
+                        expression: column.expression,
+                        sources: column
+                            .sources
+                            .into_iter()
+                            .map(|source| SourceColumn {
+                                table: source.table,
+                                column: source.column,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(LineageResponse { tables })
+    }
+
+    pub fn export_bundle(&self) -> Result<BundleResponse, LiveError> {
+        let cli = Cli::parse();
+        let config = load_app_config(cli.config_paths, cli.config_token, cli.config_overrides)?;
+        Ok(BundleResponse {
+            bundle: export_bundle(&config)?,
+        })
+    }
+
+    /// Writes the config of the bundle over the live app's config file, which is then rebuilt by the watcher.
+    pub fn import_bundle(&self, bundle: String) -> Result<ImportBundleResponse, LiveError> {
+        let (config, variables) = import_bundle(&bundle)?;
+        write_config(&config, Path::new(DEFAULT_CONFIG_PATH), true)?;
+        Ok(ImportBundleResponse {
+            app_name: config.app_name,
+            variables,
+        })
+    }
+
     pub fn build_sql(&self, sql: String) -> Result<SchemasResponse, LiveError> {
         let mut dozer = self.get_dozer()?;
+        set_information_schema(&mut dozer, &sql)?;
+        set_sql(&mut dozer, sql).map_err(|e| LiveError::BuildError(Box::new(e)))?;
+        get_endpoint_schemas(dozer).map_err(|e| LiveError::BuildError(Box::new(e)))
+    }
 
-        let context = statement_to_pipeline(&sql, &mut AppPipeline::new(), None)
-            .map_err(LiveError::PipelineError)?;
-
-        //overwrite sql
-        dozer.config.sql = Some(sql);
+    /// Plans `sql` against `sources`, or all sources if empty, without running it.
+    ///
+    /// Invalid SQL is not an error of the call, but reported in the response.
+    pub fn validate_sql(
+        &self,
+        sql: String,
+        sources: Vec<String>,
+    ) -> Result<ValidateSqlResponse, LiveError> {
+        let mut dozer = self.get_dozer()?;
+        set_information_schema(&mut dozer, &sql)?;
 
-        dozer.config.endpoints = vec![];
-        let endpoints = context.output_tables_map.keys().collect::<Vec<_>>();
-        for endpoint in endpoints {
-            let endpoint = ApiEndpoint {
-                name: endpoint.to_string(),
-                table_name: endpoint.to_string(),
-                path: format!("/{}", endpoint),
-                ..Default::default()
-            };
-            dozer.config.endpoints.push(endpoint);
+        let mut errors = vec![];
+        if !sources.is_empty() {
+            for name in &sources {
+                if !dozer
+                    .config
+                    .sources
+                    .iter()
+                    .any(|source| &source.name == name)
+                {
+                    errors.push(sql_error(format!("Source not found: {name}")));
+                }
+            }
+            dozer
+                .config
+                .sources
+                .retain(|source| sources.contains(&source.name));
+        }
+        if !errors.is_empty() {
+            return Ok(ValidateSqlResponse {
+                schemas: Default::default(),
+                errors,
+            });
         }
 
-        get_endpoint_schemas(dozer).map_err(|e| LiveError::BuildError(Box::new(e)))
+        let result = set_sql(&mut dozer, sql)
+            .map_err(|e| e.to_string())
+            .and_then(|()| get_endpoint_schemas(dozer).map_err(|e| e.to_string()));
+        Ok(match result {
+            Ok(SchemasResponse { schemas }) => ValidateSqlResponse {
+                schemas,
+                errors: vec![],
+            },
+            Err(message) => ValidateSqlResponse {
+                schemas: Default::default(),
+                errors: vec![sql_error(message)],
+            },
+        })
     }
 
     pub fn run_sql(
@@ -156,7 +248,8 @@ impl LiveState {
         endpoints: Vec<String>,
         sender: tokio::sync::mpsc::Sender<Result<Operation, tonic::Status>>,
     ) -> Result<(), LiveError> {
-        let dozer = self.get_dozer()?;
+        let mut dozer = self.get_dozer()?;
+        set_information_schema(&mut dozer, &sql)?;
 
         // kill if a handle already exists
         self.stop_sql();
@@ -178,6 +271,61 @@ impl LiveState {
     }
 }
 
+/// Lets `sql` read the `information_schema` tables, describing the app as configured, if it refers to them.
+fn set_information_schema(dozer: &mut SimpleOrchestrator, sql: &str) -> Result<(), LiveError> {
+    if sql.to_lowercase().contains(INFORMATION_SCHEMA) {
+        let information_schema = dozer
+            .describe_tables()
+            .map_err(|e| LiveError::BuildError(Box::new(e)))?;
+        dozer.information_schema = Some(Arc::new(information_schema));
+    }
+    Ok(())
+}
+
+/// Replaces the SQL of `dozer`, with an endpoint for each of its output tables.
+fn set_sql(dozer: &mut SimpleOrchestrator, sql: String) -> Result<(), OrchestrationError> {
+    let context = statement_to_pipeline(
+        &sql,
+        &mut AppPipeline::new(),
+        None,
+        get_sql_options(&dozer.config)?,
+    )?;
+
+    //overwrite sql
+    dozer.config.sql = Some(sql);
+
+    dozer.config.endpoints = vec![];
+    let endpoints = context.output_tables_map.keys().collect::<Vec<_>>();
+    for endpoint in endpoints {
+        let endpoint = ApiEndpoint {
+            name: endpoint.to_string(),
+            table_name: endpoint.to_string(),
+            path: format!("/{}", endpoint),
+            ..Default::default()
+        };
+        dozer.config.endpoints.push(endpoint);
+    }
+    Ok(())
+}
+
+/// Takes the position from messages of the SQL parser, which end like "at Line: 1, Column 8".
+fn sql_error(message: String) -> SqlError {
+    const LINE: &str = "Line: ";
+    let position = message.rfind(LINE).and_then(|start| {
+        let (line, column) = message[start + LINE.len()..].split_once(", Column")?;
+        let column = column.trim_start_matches(':').trim_start();
+        let end = column
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(column.len());
+        Some((line.parse().ok()?, column[..end].parse().ok()?))
+    });
+    SqlError {
+        line: position.map(|(line, _)| line),
+        column: position.map(|(_, column)| column),
+        message,
+    }
+}
+
 pub async fn get_source_schemas(
     dozer: SimpleOrchestrator,
     connection_name: String,
@@ -229,9 +377,15 @@ pub fn get_endpoint_schemas(
         &dozer.config.connections,
         &dozer.config.sources,
         dozer.config.sql.as_deref(),
+        get_sql_options(&dozer.config)?,
+        &dozer.config.operators,
+        &dozer.config.routers,
+        dozer.operator_registry.clone(),
         endpoint_and_logs,
         MultiProgress::new(),
-    );
+        SchemaDriftMonitor::default(),
+    )
+    .information_schema(dozer.information_schema.clone());
     let dag = builder.build(dozer.runtime.clone())?;
     // Populate schemas.
     let dag_schemas = DagSchemas::new(dag)?;
@@ -266,9 +420,15 @@ pub fn generate_dot(dozer: SimpleOrchestrator) -> Result<DotResponse, Orchestrat
         &dozer.config.connections,
         &dozer.config.sources,
         dozer.config.sql.as_deref(),
+        get_sql_options(&dozer.config)?,
+        &dozer.config.operators,
+        &dozer.config.routers,
+        dozer.operator_registry.clone(),
         endpoint_and_logs,
         MultiProgress::new(),
-    );
+        SchemaDriftMonitor::default(),
+    )
+    .information_schema(dozer.information_schema.clone());
     let dag = builder.build(dozer.runtime.clone())?;
     // Populate schemas.
 
@@ -363,3 +523,46 @@ pub fn run_sql(
 
     Ok(handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::config::Config;
+
+    use super::*;
+
+    #[test]
+    fn test_sql_error_position() {
+        let error = sql_error(
+            "sql parser error: Expected end of statement, found: FORM at Line: 2, Column 10"
+                .to_string(),
+        );
+        assert_eq!((error.line, error.column), (Some(2), Some(10)));
+
+        let error = sql_error("Table not found: films".to_string());
+        assert_eq!((error.line, error.column), (None, None));
+        assert_eq!(error.message, "Table not found: films");
+    }
+
+    #[test]
+    fn test_validate_sql_with_unknown_source() {
+        let runtime = Arc::new(tokio::runtime::Runtime::new().unwrap());
+        let state = LiveState::new();
+        state.set_dozer(SimpleOrchestrator::new(Config::default(), runtime));
+
+        let response = state
+            .validate_sql(
+                "SELECT id INTO films_out FROM films".to_string(),
+                vec!["films".to_string()],
+            )
+            .unwrap();
+        assert!(response.schemas.is_empty());
+        assert_eq!(
+            response
+                .errors
+                .iter()
+                .map(|error| error.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Source not found: films"]
+        );
+    }
+}
@@ -2,13 +2,18 @@ use clap::Parser;
 #[cfg(feature = "cloud")]
 use dozer_cli::cli::cloud::CloudCommands;
 use dozer_cli::cli::generate_config_repl;
-use dozer_cli::cli::types::{Cli, Commands, ConnectorCommand, RunCommands, SecurityCommands};
-use dozer_cli::cli::{init_dozer, list_sources, LOGO};
+#[cfg(feature = "bench")]
+use dozer_cli::cli::run_bench;
+use dozer_cli::cli::types::{
+    Bundle, BundleCommands, CacheCommands, Cli, Commands, ConnectorCommand, Lineage, RunCommands,
+    SecurityCommands,
+};
+use dozer_cli::cli::{export_bundle, import_bundle, init_dozer, list_sources, print_lineage, LOGO};
 use dozer_cli::errors::{CliError, CloudError, OrchestrationError};
 use dozer_cli::simple::SimpleOrchestrator;
 #[cfg(feature = "cloud")]
 use dozer_cli::CloudOrchestrator;
-use dozer_cli::{live, set_ctrl_handler, set_panic_hook, shutdown};
+use dozer_cli::{live, set_crash_report_hook, set_ctrl_handler, set_panic_hook, shutdown};
 use dozer_types::models::telemetry::TelemetryConfig;
 use dozer_types::tracing::{error, info};
 use serde::Deserialize;
@@ -23,6 +28,11 @@ use dozer_types::log::{debug, warn};
 use std::time::Duration;
 use std::{env, process};
 
+/// Counts the allocations of every pipeline node, reported by the internal pipeline server.
+#[cfg(feature = "memory-profiling")]
+#[global_allocator]
+static ALLOCATOR: dozer_core::memory::ProfilingAllocator = dozer_core::memory::ProfilingAllocator;
+
 fn main() {
     set_panic_hook();
 
@@ -125,6 +135,7 @@ fn run() -> Result<(), OrchestrationError> {
 
     let cli = parse_and_generate()?;
     let mut dozer = init_orchestrator(&cli)?;
+    set_crash_report_hook(&dozer.config);
     let (shutdown_sender, shutdown_receiver) = shutdown::new(&dozer.runtime);
     set_ctrl_handler(shutdown_sender);
 
@@ -148,11 +159,22 @@ fn run() -> Result<(), OrchestrationError> {
 
                     dozer.run_api(shutdown_receiver)
                 }
+                RunCommands::Gateway => {
+                    render_logo();
+
+                    dozer.run_gateway(shutdown_receiver)
+                }
                 RunCommands::App => {
                     render_logo();
 
                     dozer.run_apps(shutdown_receiver, None)
                 }
+                RunCommands::Scheduled => {
+                    render_logo();
+
+                    dozer.run_scheduled(shutdown_receiver)
+                }
+                RunCommands::History => dozer.run_history(),
             },
             Commands::Security(security) => match security.command {
                 SecurityCommands::GenerateToken => {
@@ -172,6 +194,26 @@ fn run() -> Result<(), OrchestrationError> {
                 cli.config_overrides,
                 filter,
             ),
+            Commands::Lineage(Lineage { format }) => print_lineage(&dozer, format),
+            Commands::Dump(dump) => dozer.dump(dump),
+            Commands::Cache(cache) => match cache.command {
+                CacheCommands::Load(load) => dozer.load(load),
+            },
+            Commands::Bundle(bundle) => match bundle.command {
+                BundleCommands::Export(export) => export_bundle(
+                    cli.config_paths,
+                    cli.config_token,
+                    cli.config_overrides,
+                    export,
+                ),
+                BundleCommands::Import(_) => {
+                    panic!("This should not happen as it is handled in parse_and_generate");
+                }
+            },
+            #[cfg(feature = "bench")]
+            Commands::Bench(_) => {
+                panic!("This should not happen as it is handled in parse_and_generate");
+            }
             Commands::Clean => dozer.clean(),
             #[cfg(feature = "cloud")]
             Commands::Cloud(cloud) => {
@@ -239,7 +281,29 @@ fn parse_and_generate() -> Result<Cli, OrchestrationError> {
                 // We need to exit here, otherwise the orchestrator will be initialized
                 process::exit(0);
             }
+        } else if let Some(Commands::Bundle(Bundle {
+            command: BundleCommands::Import(import),
+        })) = &cli.cmd
+        {
+            // The app is imported into a directory that may have no config yet.
+            Telemetry::new(None, None);
+            if let Err(e) = import_bundle(import) {
+                error!("{}", e);
+                Err(e)
+            } else {
+                process::exit(0);
+            }
         } else {
+            #[cfg(feature = "bench")]
+            if let Some(Commands::Bench(bench)) = &cli.cmd {
+                // Benchmarks run on generated data, so they need no config.
+                Telemetry::new(None, None);
+                if let Err(e) = run_bench(bench) {
+                    error!("{}", e);
+                    return Err(e);
+                }
+                process::exit(0);
+            }
             Ok(cli)
         }
     })
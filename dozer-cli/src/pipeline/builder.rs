@@ -1,30 +1,44 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use dozer_cache::dozer_log::replication::Log;
+use dozer_api::grpc::internal::internal_pipeline_server::BuildAndLog;
 use dozer_core::app::App;
 use dozer_core::app::AppPipeline;
 use dozer_core::app::PipelineEntryPoint;
-use dozer_core::node::SinkFactory;
+use dozer_core::appsource::AppSourceMappings;
+use dozer_core::dag_schemas::DagSchemas;
+use dozer_core::node::{PortHandle, ProcessorFactory, SinkFactory};
+use dozer_core::plugin::OperatorRegistry;
 use dozer_core::Dag;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_ingestion::connectors::{get_connector, get_connector_info_table};
-use dozer_sql::pipeline::builder::statement_to_pipeline;
-use dozer_sql::pipeline::builder::{OutputNodeInfo, QueryContext, SchemaSQLContext};
+use dozer_sql::pipeline::builder::{
+    router_to_processor, select_to_processor, statement_to_pipeline,
+};
+use dozer_sql::pipeline::builder::{OutputNodeInfo, QueryContext, SchemaSQLContext, SqlOptions};
+use dozer_sql::pipeline::lineage::used_source_columns;
+use dozer_types::identifier;
 use dozer_types::indicatif::MultiProgress;
 use dozer_types::log::debug;
 use dozer_types::models::api_endpoint::ApiEndpoint;
 use dozer_types::models::connection::Connection;
+use dozer_types::models::operator_config::OperatorConfig;
+use dozer_types::models::router_config::RouterConfig;
 use dozer_types::models::source::Source;
 use std::hash::Hash;
 use tokio::runtime::Runtime;
-use tokio::sync::Mutex;
 
 use crate::pipeline::dummy_sink::DummySinkFactory;
-use crate::pipeline::LogSinkFactory;
+use crate::pipeline::information_schema::{
+    is_information_schema_table, InformationSchema, InformationSchemaSourceFactory,
+    TableDescription, TableType, INFORMATION_SCHEMA,
+};
+use crate::pipeline::{LogSinkFactory, SchemaDriftMonitor};
 use crate::ui_helper::transform_to_ui_graph;
 
+use super::rollup::rollup_sql;
 use super::source_builder::SourceBuilder;
 use crate::errors::OrchestrationError;
 use dozer_types::log::{error, info};
@@ -47,34 +61,73 @@ pub struct CalculatedSources {
     pub query_context: Option<QueryContext>,
 }
 
-type OptionLog = Option<Arc<Mutex<Log>>>;
+type OptionLog = Option<BuildAndLog>;
 
 pub struct PipelineBuilder<'a> {
     connections: &'a [Connection],
     sources: &'a [Source],
     sql: Option<&'a str>,
+    /// How the SQL, and the table names of the config, are interpreted.
+    sql_options: SqlOptions,
+    operators: &'a [OperatorConfig],
+    routers: &'a [RouterConfig],
+    operator_registry: Arc<OperatorRegistry<SchemaSQLContext>>,
     /// `ApiEndpoint` and its log.
     endpoint_and_logs: Vec<(ApiEndpoint, OptionLog)>,
     progress: MultiProgress,
+    schema_drift: SchemaDriftMonitor,
+    /// The tables the `information_schema` tables describe. The SQL can't read them if not set.
+    information_schema: Option<Arc<InformationSchema>>,
+    /// The directory the last reads of the tables refreshed on a schedule are kept in.
+    refresh_dir: Option<PathBuf>,
 }
 
 impl<'a> PipelineBuilder<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         connections: &'a [Connection],
         sources: &'a [Source],
         sql: Option<&'a str>,
+        sql_options: SqlOptions,
+        operators: &'a [OperatorConfig],
+        routers: &'a [RouterConfig],
+        operator_registry: Arc<OperatorRegistry<SchemaSQLContext>>,
         endpoint_and_logs: Vec<(ApiEndpoint, OptionLog)>,
         progress: MultiProgress,
+        schema_drift: SchemaDriftMonitor,
     ) -> Self {
         Self {
             connections,
             sources,
             sql,
+            sql_options,
+            operators,
+            routers,
+            operator_registry,
             endpoint_and_logs,
             progress,
+            schema_drift,
+            information_schema: None,
+            refresh_dir: None,
         }
     }
 
+    /// Keeps the last reads of the tables refreshed on a schedule in `refresh_dir`. Pipelines reading such tables
+    /// can't be built without it.
+    pub fn refresh_dir(mut self, refresh_dir: Option<PathBuf>) -> Self {
+        self.refresh_dir = refresh_dir;
+        self
+    }
+
+    /// Lets the SQL read the `information_schema` tables, which describe the tables of `information_schema`.
+    pub fn information_schema(
+        mut self,
+        information_schema: Option<Arc<InformationSchema>>,
+    ) -> Self {
+        self.information_schema = information_schema;
+        self
+    }
+
     // Based on used_sources, map it to the connection name and create sources
     // For not breaking current functionality, current format is to be still supported.
     pub async fn get_grouped_tables(
@@ -119,15 +172,21 @@ impl<'a> PipelineBuilder<'a> {
         for table_name in original_sources {
             let mut table_found = false;
             for (connection, tables) in connector_map.iter() {
-                if let Some(source) = tables
-                    .iter()
-                    .find(|table| table.name == table_name.as_str())
+                if let Some(source) = identifier::matching(
+                    table_name,
+                    false,
+                    self.sql_options.case_sensitive,
+                    tables,
+                    |table| table.name.as_str(),
+                )
+                .first()
                 {
                     table_found = true;
-                    grouped_connections
-                        .entry(connection.clone())
-                        .or_default()
-                        .push(source.clone());
+                    // The SQL and the config may refer to the same source with different names.
+                    let sources = grouped_connections.entry(connection.clone()).or_default();
+                    if !sources.iter().any(|added| added.name == source.name) {
+                        sources.push((*source).clone());
+                    }
                 }
             }
 
@@ -143,6 +202,7 @@ impl<'a> PipelineBuilder<'a> {
     // This function is used to figure out the sources that are used in the pipeline
     // based on the SQL and API Endpoints
     pub fn calculate_sources(&self) -> Result<CalculatedSources, OrchestrationError> {
+        let case_sensitive = self.sql_options.case_sensitive;
         let mut original_sources = vec![];
 
         let mut query_ctx = None;
@@ -151,13 +211,13 @@ impl<'a> PipelineBuilder<'a> {
         let mut transformed_sources = vec![];
 
         if let Some(sql) = &self.sql {
-            let query_context = statement_to_pipeline(sql, &mut pipeline, None)
+            let query_context = statement_to_pipeline(sql, &mut pipeline, None, self.sql_options)
                 .map_err(OrchestrationError::PipelineError)?;
 
             query_ctx = Some(query_context.clone());
 
             for (name, _) in query_context.output_tables_map {
-                if transformed_sources.contains(&name) {
+                if contains_table(&transformed_sources, &name, case_sensitive) {
                     return Err(OrchestrationError::DuplicateTable(name));
                 }
                 transformed_sources.push(name.clone());
@@ -169,12 +229,46 @@ impl<'a> PipelineBuilder<'a> {
             }
         }
 
+        // Routers read a source or an SQL output.
+        for router in self.routers {
+            if !contains_table(&transformed_sources, &router.table_name, case_sensitive) {
+                original_sources.push(router.table_name.clone());
+            }
+            for name in router_tables(router) {
+                if contains_table(&transformed_sources, name, case_sensitive) {
+                    return Err(OrchestrationError::DuplicateTable(name.clone()));
+                }
+                transformed_sources.push(name.clone());
+            }
+        }
+
+        // Operators read a source or a table transformed before them.
+        for operator in self.operators {
+            if !contains_table(&transformed_sources, &operator.table_name, case_sensitive) {
+                original_sources.push(operator.table_name.clone());
+            }
+            if contains_table(&transformed_sources, &operator.name, case_sensitive) {
+                return Err(OrchestrationError::DuplicateTable(operator.name.clone()));
+            }
+            transformed_sources.push(operator.name.clone());
+        }
+
+        // Rollup tables are added by the pipeline builder.
+        for (api_endpoint, _) in &self.endpoint_and_logs {
+            for rollup in &api_endpoint.rollups {
+                if contains_table(&transformed_sources, &rollup.name, case_sensitive) {
+                    return Err(OrchestrationError::DuplicateTable(rollup.name.clone()));
+                }
+                transformed_sources.push(rollup.name.clone());
+            }
+        }
+
         // Add Used Souces if direct from source
         for (api_endpoint, _) in &self.endpoint_and_logs {
             let table_name = &api_endpoint.table_name;
 
             // Don't add if the table is a result of SQL
-            if !transformed_sources.contains(table_name) {
+            if !contains_table(&transformed_sources, table_name, case_sensitive) {
                 original_sources.push(table_name.clone());
             }
         }
@@ -188,16 +282,79 @@ impl<'a> PipelineBuilder<'a> {
         })
     }
 
+    /// The columns the SQL reads of the sources only the SQL reads, by connection and table. Sources read by routers,
+    /// operators or endpoints use all their columns, and so do all sources if the SQL can't be analyzed.
+    fn sql_used_columns(
+        &self,
+        grouped_connections: &HashMap<Connection, Vec<Source>>,
+    ) -> HashMap<(String, String), Vec<String>> {
+        let case_sensitive = self.sql_options.case_sensitive;
+        let Some(used) = self
+            .sql
+            .and_then(|sql| used_source_columns(sql, self.sql_options).ok())
+        else {
+            return HashMap::new();
+        };
+
+        let other_readers = self
+            .routers
+            .iter()
+            .map(|router| &router.table_name)
+            .chain(self.operators.iter().map(|operator| &operator.table_name))
+            .chain(
+                self.endpoint_and_logs
+                    .iter()
+                    .map(|(endpoint, _)| &endpoint.table_name),
+            )
+            .collect::<Vec<_>>();
+        let mut used_columns = HashMap::new();
+        for (connection, sources) in grouped_connections {
+            for source in sources {
+                if contains_table(other_readers.iter().copied(), &source.name, case_sensitive) {
+                    continue;
+                }
+                // Columns that can't be attributed to a table may be of any table.
+                let columns = used
+                    .iter()
+                    .filter(|column| {
+                        column.table.as_ref().map_or(true, |table| {
+                            identifier::name_matches(table, false, case_sensitive, &source.name)
+                        })
+                    })
+                    .map(|column| column.column.clone())
+                    .collect::<Vec<_>>();
+                if columns.iter().any(|column| column == "*") {
+                    continue;
+                }
+                used_columns.insert(
+                    (connection.name.clone(), source.table_name.clone()),
+                    columns,
+                );
+            }
+        }
+        used_columns
+    }
+
     // This function is used by both building and actual execution
     pub fn build(
         self,
         runtime: Arc<Runtime>,
     ) -> Result<dozer_core::Dag<SchemaSQLContext>, OrchestrationError> {
         let calculated_sources = self.calculate_sources()?;
+        let case_sensitive = self.sql_options.case_sensitive;
 
         debug!("Used Sources: {:?}", calculated_sources.original_sources);
-        let grouped_connections =
-            runtime.block_on(self.get_grouped_tables(&calculated_sources.original_sources))?;
+        // The `information_schema` tables aren't read from a connection.
+        let (information_schema_tables, original_sources): (Vec<_>, Vec<_>) = calculated_sources
+            .original_sources
+            .into_iter()
+            .partition(|name| {
+                self.information_schema.is_some()
+                    && is_information_schema_table(name, case_sensitive)
+            });
+        let grouped_connections = runtime.block_on(self.get_grouped_tables(&original_sources))?;
+        self.schema_drift
+            .set_used_columns(self.sql_used_columns(&grouped_connections));
 
         let mut pipelines: Vec<AppPipeline<SchemaSQLContext>> = vec![];
 
@@ -217,13 +374,26 @@ impl<'a> PipelineBuilder<'a> {
                 );
             }
         }
+        for name in &information_schema_tables {
+            available_output_tables.insert(
+                name.clone(),
+                OutputTableInfo::Original(OriginalTableInfo {
+                    connection_name: INFORMATION_SCHEMA.to_string(),
+                    table_name: name.clone(),
+                }),
+            );
+        }
 
         if let Some(sql) = &self.sql {
-            let query_context = statement_to_pipeline(sql, &mut pipeline, None)
+            let query_context = statement_to_pipeline(sql, &mut pipeline, None, self.sql_options)
                 .map_err(OrchestrationError::PipelineError)?;
 
             for (name, table_info) in query_context.output_tables_map {
-                if available_output_tables.contains_key(name.as_str()) {
+                if contains_table(
+                    available_output_tables.keys(),
+                    name.as_str(),
+                    case_sensitive,
+                ) {
                     return Err(OrchestrationError::DuplicateTable(name));
                 }
                 available_output_tables
@@ -231,18 +401,132 @@ impl<'a> PipelineBuilder<'a> {
             }
         }
 
+        for router in self.routers {
+            let table_info =
+                get_table(&available_output_tables, &router.table_name, case_sensitive)
+                    .ok_or_else(|| {
+                        OrchestrationError::RouterTableNotFound(
+                            router.name.clone(),
+                            router.table_name.clone(),
+                        )
+                    })?;
+
+            let processor_name = format!("router_{}", router.name);
+            let conditions = router
+                .routes
+                .iter()
+                .map(|route| route.condition.as_str())
+                .collect::<Vec<_>>();
+            let processor = router_to_processor(
+                processor_name.clone(),
+                &conditions,
+                router.default_table.is_some(),
+                self.sql_options,
+            )?;
+            add_processor_on_table(&mut pipeline, processor, &processor_name, table_info);
+
+            // Routes are on the ports in order, followed by the default table.
+            for (port, name) in router_tables(router).enumerate() {
+                if contains_table(
+                    available_output_tables.keys(),
+                    name.as_str(),
+                    case_sensitive,
+                ) {
+                    return Err(OrchestrationError::DuplicateTable(name.clone()));
+                }
+                available_output_tables.insert(
+                    name.clone(),
+                    OutputTableInfo::Transformed(OutputNodeInfo {
+                        node: processor_name.clone(),
+                        port: port as PortHandle,
+                        is_derived: false,
+                    }),
+                );
+            }
+        }
+
+        for operator in self.operators {
+            let table_info = get_table(
+                &available_output_tables,
+                &operator.table_name,
+                case_sensitive,
+            )
+            .ok_or_else(|| {
+                OrchestrationError::OperatorTableNotFound(
+                    operator.name.clone(),
+                    operator.table_name.clone(),
+                )
+            })?;
+
+            let processor_name = format!("operator_{}", operator.name);
+            let processor = self
+                .operator_registry
+                .build(&operator.operator, &processor_name, &operator.options)
+                .map_err(ExecutionError)?;
+            add_processor_on_table(&mut pipeline, processor, &processor_name, table_info);
+
+            if contains_table(
+                available_output_tables.keys(),
+                operator.name.as_str(),
+                case_sensitive,
+            ) {
+                return Err(OrchestrationError::DuplicateTable(operator.name.clone()));
+            }
+            available_output_tables.insert(
+                operator.name.clone(),
+                OutputTableInfo::Transformed(OutputNodeInfo {
+                    node: processor_name,
+                    port: DEFAULT_PORT_HANDLE,
+                    is_derived: false,
+                }),
+            );
+        }
+
+        // Rollups aggregate the same table as their endpoint.
+        for (api_endpoint, _) in &self.endpoint_and_logs {
+            for rollup in &api_endpoint.rollups {
+                let table_name = &api_endpoint.table_name;
+                let table_info = get_table(&available_output_tables, table_name, case_sensitive)
+                    .ok_or_else(|| OrchestrationError::EndpointTableNotFound(table_name.clone()))?;
+
+                let processor_name = format!("rollup_{}", rollup.name);
+                let processor = select_to_processor(
+                    processor_name.clone(),
+                    &rollup_sql(table_name, rollup)?,
+                    self.sql_options,
+                )?;
+                add_processor_on_table(&mut pipeline, processor, &processor_name, table_info);
+
+                if contains_table(
+                    available_output_tables.keys(),
+                    rollup.name.as_str(),
+                    case_sensitive,
+                ) {
+                    return Err(OrchestrationError::DuplicateTable(rollup.name.clone()));
+                }
+                available_output_tables.insert(
+                    rollup.name.clone(),
+                    OutputTableInfo::Transformed(OutputNodeInfo {
+                        node: processor_name,
+                        port: DEFAULT_PORT_HANDLE,
+                        is_derived: false,
+                    }),
+                );
+            }
+        }
+
         for (api_endpoint, log) in self.endpoint_and_logs {
             let table_name = &api_endpoint.table_name;
 
-            let table_info = available_output_tables
-                .get(table_name)
+            let table_info = get_table(&available_output_tables, table_name, case_sensitive)
                 .ok_or_else(|| OrchestrationError::EndpointTableNotFound(table_name.clone()))?;
 
             let snk_factory: Box<dyn SinkFactory<SchemaSQLContext>> = if let Some(log) = log {
                 Box::new(LogSinkFactory::new(
                     runtime.clone(),
-                    log,
-                    api_endpoint.name.clone(),
+                    log.log,
+                    api_endpoint.clone(),
+                    log.provenance,
                     self.progress.clone(),
                 ))
             } else {
@@ -275,8 +559,30 @@ impl<'a> PipelineBuilder<'a> {
 
         pipelines.push(pipeline);
 
-        let source_builder = SourceBuilder::new(grouped_connections, Some(&self.progress));
-        let asm = source_builder.build_source_manager(runtime)?;
+        let source_builder = SourceBuilder::new(
+            grouped_connections,
+            Some(&self.progress),
+            self.schema_drift.clone(),
+        )
+        .refresh_dir(self.refresh_dir.clone());
+        let mut asm = source_builder
+            .build_source_manager(runtime)?
+            .with_case_sensitive(case_sensitive);
+        if let Some(information_schema) = &self.information_schema {
+            if !information_schema_tables.is_empty() {
+                let source_factory = InformationSchemaSourceFactory::new(
+                    information_schema.clone(),
+                    &information_schema_tables,
+                    case_sensitive,
+                );
+                let mappings = AppSourceMappings::new(
+                    INFORMATION_SCHEMA.to_string(),
+                    source_factory.mappings(),
+                );
+                asm.add(Box::new(source_factory), mappings)
+                    .map_err(ExecutionError)?;
+            }
+        }
         let mut app = App::new(asm);
 
         Vec::into_iter(pipelines).for_each(|p| {
@@ -292,6 +598,172 @@ impl<'a> PipelineBuilder<'a> {
 
         Ok(dag)
     }
+
+    /// Describes the sources, the tables produced from them and the endpoints, for the `information_schema` tables.
+    ///
+    /// Every table is read by a sink of its own, which gets its schema.
+    pub fn describe_tables(
+        mut self,
+        runtime: Arc<Runtime>,
+    ) -> Result<InformationSchema, OrchestrationError> {
+        let calculated_sources = self.calculate_sources()?;
+
+        let mut sources = self
+            .sources
+            .iter()
+            .map(|source| source.name.clone())
+            .chain(calculated_sources.original_sources)
+            .collect::<Vec<_>>();
+        dedup(&mut sources);
+        // Sinks are named after their endpoint, so the sinks of the tables get prefixed names that don't collide.
+        let tables = sources
+            .into_iter()
+            .map(|name| {
+                (
+                    format!("{INFORMATION_SCHEMA}/source/{name}"),
+                    name,
+                    TableType::Source,
+                )
+            })
+            .chain(
+                calculated_sources
+                    .transformed_sources
+                    .into_iter()
+                    .map(|name| {
+                        (
+                            format!("{INFORMATION_SCHEMA}/view/{name}"),
+                            name,
+                            TableType::View,
+                        )
+                    }),
+            )
+            .chain(self.endpoint_and_logs.iter().map(|(endpoint, _)| {
+                (
+                    endpoint.name.clone(),
+                    endpoint.name.clone(),
+                    TableType::Endpoint,
+                )
+            }))
+            .collect::<Vec<_>>();
+        for (sink, name, table_type) in &tables {
+            if *table_type != TableType::Endpoint {
+                self.endpoint_and_logs.push((
+                    ApiEndpoint {
+                        name: sink.clone(),
+                        table_name: name.clone(),
+                        ..Default::default()
+                    },
+                    None,
+                ));
+            }
+        }
+
+        let dag = self.build(runtime)?;
+        let mut schemas = DagSchemas::new(dag)?.get_sink_schemas();
+        let tables = tables
+            .into_iter()
+            .filter_map(|(sink, name, table_type)| {
+                let (schema, connections) = schemas.remove(&sink)?;
+                let connection = if table_type == TableType::Source {
+                    connections.into_iter().next()
+                } else {
+                    None
+                };
+                Some(TableDescription {
+                    name,
+                    table_type,
+                    connection,
+                    schema,
+                })
+            })
+            .collect();
+        Ok(InformationSchema { tables })
+    }
+}
+
+/// Operators built into dozer. Registries passed to `SimpleOrchestrator::with_operator_registry` should start from these.
+pub fn builtin_operators() -> OperatorRegistry<SchemaSQLContext> {
+    let mut registry = OperatorRegistry::new();
+    dozer_sql::pipeline::compaction_operator::register(&mut registry)
+        .expect("Built-in operators have distinct names");
+    dozer_sql::pipeline::reorder_operator::register(&mut registry)
+        .expect("Built-in operators have distinct names");
+    #[cfg(feature = "python")]
+    dozer_sql::pipeline::python_operator::register(&mut registry)
+        .expect("Built-in operators have distinct names");
+    #[cfg(feature = "javascript")]
+    dozer_sql::pipeline::javascript_operator::register(&mut registry)
+        .expect("Built-in operators have distinct names");
+    #[cfg(feature = "enrichment")]
+    dozer_sql::pipeline::enrichment_operator::register(&mut registry)
+        .expect("Built-in operators have distinct names");
+    registry
+}
+
+/// Names of the tables `router` outputs, in the order of its output ports.
+fn router_tables(router: &RouterConfig) -> impl Iterator<Item = &String> {
+    router
+        .routes
+        .iter()
+        .map(|route| &route.name)
+        .chain(&router.default_table)
+}
+
+/// Adds a processor reading `table_info` on its default port.
+fn add_processor_on_table(
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    processor: Box<dyn ProcessorFactory<SchemaSQLContext>>,
+    processor_name: &str,
+    table_info: &OutputTableInfo,
+) {
+    match table_info {
+        OutputTableInfo::Transformed(table_info) => {
+            pipeline.add_processor(processor, processor_name, vec![]);
+            pipeline.connect_nodes(
+                &table_info.node,
+                table_info.port,
+                processor_name,
+                DEFAULT_PORT_HANDLE,
+            );
+        }
+        OutputTableInfo::Original(table_info) => {
+            pipeline.add_processor(
+                processor,
+                processor_name,
+                vec![PipelineEntryPoint::new(
+                    table_info.table_name.clone(),
+                    DEFAULT_PORT_HANDLE,
+                )],
+            );
+        }
+    }
+}
+
+/// Whether `tables` has a table `name` refers to, following the identifier rules of the app.
+fn contains_table<'a>(
+    tables: impl IntoIterator<Item = &'a String>,
+    name: &str,
+    case_sensitive: bool,
+) -> bool {
+    tables
+        .into_iter()
+        .any(|table| identifier::name_matches(name, false, case_sensitive, table))
+}
+
+/// The table `name` refers to, following the identifier rules of the app. `None` if it's ambiguous.
+fn get_table<'a>(
+    tables: &'a HashMap<String, OutputTableInfo>,
+    name: &str,
+    case_sensitive: bool,
+) -> Option<&'a OutputTableInfo> {
+    match identifier::matching(name, false, case_sensitive, tables, |(table, _)| {
+        table.as_str()
+    })
+    .as_slice()
+    {
+        [(_, table_info)] => Some(*table_info),
+        _ => None,
+    }
 }
 
 fn dedup<T: Eq + Hash + Clone>(v: &mut Vec<T>) {
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use dozer_core::{
+    epoch::Epoch, executor_operation::ProcessorOperation, node::PortHandle, node::Sink,
+    processor_record::ProcessorRecordStore,
+};
+use dozer_ingestion::ingestion::chaos::{ChaosOptions, FaultInjector};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::Provenance;
+use dozer_types::thiserror::{self, Error};
+
+#[derive(Debug, Error)]
+#[error("Injected sink write failure")]
+struct SinkWriteFailure;
+
+/// Fails writes of the wrapped sink with the configured probability. Only built with the `chaos` feature.
+#[derive(Debug)]
+pub struct ChaosSink {
+    inner: Box<dyn Sink>,
+    probability: f64,
+    injector: FaultInjector,
+}
+
+impl ChaosSink {
+    /// Wraps `sink` if `DOZER_CHAOS` configures sink failures.
+    pub fn wrap(sink: Box<dyn Sink>) -> Box<dyn Sink> {
+        match ChaosOptions::from_env() {
+            Some(options) if options.sink_failure_probability > 0.0 => Box::new(Self {
+                inner: sink,
+                probability: options.sink_failure_probability,
+                injector: FaultInjector::new(options.seed),
+            }),
+            _ => sink,
+        }
+    }
+
+    fn check(&self) -> Result<(), BoxedError> {
+        if self.injector.inject(self.probability) {
+            return Err(SinkWriteFailure.into());
+        }
+        Ok(())
+    }
+}
+
+impl Sink for ChaosSink {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError> {
+        self.check()?;
+        self.inner.commit(epoch_details)
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+    ) -> Result<(), BoxedError> {
+        self.check()?;
+        self.inner.process(from_port, record_store, op)
+    }
+
+    fn process_with_provenance(
+        &mut self,
+        from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), BoxedError> {
+        self.check()?;
+        self.inner
+            .process_with_provenance(from_port, record_store, op, provenance)
+    }
+
+    fn on_source_snapshotting_done(&mut self, connection_name: String) -> Result<(), BoxedError> {
+        self.check()?;
+        self.inner.on_source_snapshotting_done(connection_name)
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::{Duration, SystemTime};
+
+use dozer_types::chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dozer_types::models::source::EventTimeConfig;
+use dozer_types::tracing::{info, warn};
+use dozer_types::types::{Field, Operation, Record};
+use metrics::{describe_gauge, gauge};
+
+const CLOCK_SKEW_GAUGE_NAME: &str = "source_clock_skew_seconds";
+
+/// How long event times are observed for each estimate of the skew.
+const ESTIMATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Estimates the skew of a source clock from the event times of a table's operations, reports it when it exceeds
+/// `max_skew_ms`, and shifts event times by it if normalizing.
+///
+/// An event happens before it's ingested, so the greatest event time minus ingestion time in a window is the skew less
+/// the shortest delivery delay. The estimate is only meaningful for sources that send events shortly after they
+/// happen, so operations of snapshots and deletes, whose event times are those of older events, aren't observed.
+#[derive(Debug)]
+pub struct ClockSkewMonitor {
+    connection: String,
+    table: String,
+    /// Index of the event time column in the records.
+    column_index: usize,
+    max_skew_ms: i64,
+    normalize: bool,
+    window_start: Option<SystemTime>,
+    /// The greatest event time minus ingestion time in the current window, in milliseconds.
+    window_max_ms: Option<i64>,
+    /// The skew while it exceeds `max_skew_ms`, in milliseconds.
+    skew_ms: Option<i64>,
+}
+
+impl ClockSkewMonitor {
+    pub fn new(
+        connection: String,
+        table: String,
+        column_index: usize,
+        config: &EventTimeConfig,
+    ) -> Self {
+        describe_gauge!(
+            CLOCK_SKEW_GAUGE_NAME,
+            "Estimated skew of the source clock: event time minus ingestion time"
+        );
+        Self {
+            connection,
+            table,
+            column_index,
+            max_skew_ms: config.max_skew_ms as i64,
+            normalize: config.normalize,
+            window_start: None,
+            window_max_ms: None,
+            skew_ms: None,
+        }
+    }
+
+    /// The skew while it exceeds `max_skew_ms`, in milliseconds.
+    pub fn skew_ms(&self) -> Option<i64> {
+        self.skew_ms
+    }
+
+    /// Observes the event time of `op` ingested at `now`, unless it's part of a snapshot, then shifts its event times
+    /// by the skew if normalizing.
+    pub fn process(&mut self, op: &mut Operation, snapshotting: bool, now: SystemTime) {
+        if !snapshotting {
+            if let Operation::Insert { new } | Operation::Update { new, .. } = &*op {
+                if let Some(Field::Timestamp(event_time)) = new.values.get(self.column_index) {
+                    let ingestion_time = DateTime::<Utc>::from(now);
+                    self.observe(
+                        event_time.timestamp_millis() - ingestion_time.timestamp_millis(),
+                        now,
+                    );
+                }
+            }
+        }
+
+        if let (true, Some(skew_ms)) = (self.normalize, self.skew_ms) {
+            match op {
+                Operation::Insert { new } => self.shift(new, skew_ms),
+                Operation::Delete { old } => self.shift(old, skew_ms),
+                Operation::Update { old, new } => {
+                    self.shift(old, skew_ms);
+                    self.shift(new, skew_ms);
+                }
+            }
+        }
+    }
+
+    fn observe(&mut self, difference_ms: i64, now: SystemTime) {
+        let window_start = *self.window_start.get_or_insert(now);
+        let window_max_ms = self
+            .window_max_ms
+            .map_or(difference_ms, |max| max.max(difference_ms));
+        if now.duration_since(window_start).unwrap_or_default() < ESTIMATE_WINDOW {
+            self.window_max_ms = Some(window_max_ms);
+            return;
+        }
+        self.window_start = None;
+        self.window_max_ms = None;
+        self.estimate(window_max_ms);
+    }
+
+    fn estimate(&mut self, estimate_ms: i64) {
+        let labels = [
+            ("connection", self.connection.clone()),
+            ("table", self.table.clone()),
+        ];
+        gauge!(CLOCK_SKEW_GAUGE_NAME, estimate_ms as f64 / 1000.0, &labels);
+
+        if estimate_ms.abs() <= self.max_skew_ms {
+            if self.skew_ms.take().is_some() {
+                info!(
+                    connection = %self.connection,
+                    table = %self.table,
+                    skew_ms = estimate_ms,
+                    "Source clock is no longer skewed"
+                );
+            }
+            return;
+        }
+        // Keep the skew event times are shifted by unless it changed by more than `max_skew_ms`, so that delivery
+        // delays don't shift them differently on every estimate.
+        if let Some(skew_ms) = self.skew_ms {
+            if (estimate_ms - skew_ms).abs() <= self.max_skew_ms {
+                return;
+            }
+        }
+        warn!(
+            connection = %self.connection,
+            table = %self.table,
+            skew_ms = estimate_ms,
+            "Source clock is skewed: event times are {} ms {} ingestion time",
+            estimate_ms.abs(),
+            if estimate_ms > 0 { "ahead of" } else { "behind" }
+        );
+        self.skew_ms = Some(estimate_ms);
+    }
+
+    fn shift(&self, record: &mut Record, skew_ms: i64) {
+        if let Some(Field::Timestamp(event_time)) = record.values.get_mut(self.column_index) {
+            *event_time = *event_time - ChronoDuration::milliseconds(skew_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+
+    fn insert(event_time_ms: i64) -> Operation {
+        Operation::Insert {
+            new: Record::new(vec![
+                Field::Int(1),
+                Field::Timestamp(
+                    FixedOffset::east_opt(0)
+                        .unwrap()
+                        .timestamp_millis_opt(event_time_ms)
+                        .unwrap(),
+                ),
+            ]),
+        }
+    }
+
+    fn event_time_ms(op: &Operation) -> i64 {
+        let Operation::Insert { new } = op else {
+            panic!("not an insert");
+        };
+        let Field::Timestamp(event_time) = new.values[1] else {
+            panic!("not a timestamp");
+        };
+        event_time.timestamp_millis()
+    }
+
+    #[test]
+    fn test_clock_skew() {
+        let config = EventTimeConfig {
+            column: "updated_at".to_string(),
+            max_skew_ms: 1000,
+            normalize: true,
+        };
+        let mut monitor = ClockSkewMonitor::new("db".to_string(), "users".to_string(), 1, &config);
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let start_ms = 1_000_000_000;
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+
+        // Snapshot operations aren't observed.
+        monitor.process(&mut insert(start_ms + 600_000), true, at(0));
+        monitor.process(&mut insert(start_ms + 600_000), true, at(61));
+        assert_eq!(monitor.skew_ms(), None);
+
+        // The source clock is 5 minutes ahead, and events are delivered in 100 to 500 ms.
+        monitor.process(&mut insert(start_ms + 300_000 - 500), false, at(0));
+        monitor.process(&mut insert(start_ms + 330_000 - 100), false, at(30));
+        assert_eq!(monitor.skew_ms(), None);
+        let mut op = insert(start_ms + 360_000 - 300);
+        monitor.process(&mut op, false, at(60));
+        assert_eq!(monitor.skew_ms(), Some(299_900));
+        assert_eq!(event_time_ms(&op), start_ms + 60_000 - 200);
+
+        // Estimates within `max_skew_ms` of the skew keep it.
+        monitor.process(&mut insert(start_ms + 390_000 - 1000), false, at(90));
+        monitor.process(&mut insert(start_ms + 450_000 - 1000), false, at(150));
+        assert_eq!(monitor.skew_ms(), Some(299_900));
+
+        // The clock is fixed.
+        monitor.process(&mut insert(start_ms + 180_000), false, at(180));
+        monitor.process(&mut insert(start_ms + 240_000), false, at(240));
+        assert_eq!(monitor.skew_ms(), None);
+        let mut op = insert(start_ms + 250_000);
+        monitor.process(&mut op, false, at(250));
+        assert_eq!(event_time_ms(&op), start_ms + 250_000);
+    }
+}
@@ -2,44 +2,36 @@ use dozer_core::channels::SourceChannelForwarder;
 use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
 use dozer_ingestion::connectors::{get_connector, CdcType, Connector, TableInfo};
 use dozer_ingestion::errors::ConnectorError;
+use dozer_ingestion::ingestion::refresh::ScheduledRefresh;
 use dozer_ingestion::ingestion::{IngestionConfig, IngestionIterator, Ingestor};
 use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_tracing::{record_pipeline_error, PipelineErrorContext, PipelineStage};
 
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind, IngestorError};
-use dozer_types::log::info;
+use dozer_types::indicatif::MultiProgress;
+use dozer_types::ingestion_types::{
+    IngestionMessage, IngestionMessageKind, IngestorError, IngestorForwarder, SchemaDriftKind,
+    SchemaDriftSeverity,
+};
+use dozer_types::log::{info, warn};
 use dozer_types::models::connection::Connection;
+use dozer_types::models::source::{EventTimeConfig, MaxRecordSizeConfig};
 use dozer_types::parking_lot::Mutex;
 use dozer_types::thiserror::{self, Error};
 use dozer_types::tracing::{span, Level};
-use dozer_types::types::{Operation, Schema, SourceDefinition};
+use dozer_types::types::{FieldType, Operation, Schema, SourceDefinition};
 use metrics::{describe_counter, increment_counter};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::thread;
+use std::time::SystemTime;
 use tokio::runtime::Runtime;
 
-fn attach_progress(multi_pb: Option<MultiProgress>) -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
-    multi_pb.as_ref().map(|m| m.add(pb.clone()));
-    pb.set_style(
-        ProgressStyle::with_template("{spinner:.red} {msg}: {pos}: {per_sec}")
-            .unwrap()
-            // For more spinners check out the cli-spinners project:
-            // https://github.com/sindresorhus/cli-spinners/blob/master/spinners.json
-            .tick_strings(&[
-                "▹▹▹▹▹",
-                "▸▹▹▹▹",
-                "▹▸▹▹▹",
-                "▹▹▸▹▹",
-                "▹▹▹▸▹",
-                "▹▹▹▹▸",
-                "▪▪▪▪▪",
-            ]),
-    );
-    pb
-}
+use super::clock_skew::ClockSkewMonitor;
+use super::progress::SourceProgress;
+use super::record_size::RecordSizeLimit;
+use super::schema_drift::SchemaDriftMonitor;
 
 #[derive(Debug)]
 struct Table {
@@ -49,6 +41,13 @@ struct Table {
     schema: Schema,
     cdc_type: CdcType,
     port: PortHandle,
+    /// Cron expression of the scheduled refresh, if the table is re-read on a schedule instead of followed.
+    schedule: Option<String>,
+    /// Estimated number of rows, shown with the progress of the snapshot.
+    estimated_rows: Option<u64>,
+    /// The index of the event time column checked for clock skew, and its configuration.
+    event_time: Option<(usize, EventTimeConfig)>,
+    record_size_limit: Option<RecordSizeLimit>,
 }
 
 #[derive(Debug, Error)]
@@ -59,6 +58,30 @@ pub enum ConnectorSourceFactoryError {
     PortNotFoundInSource(PortHandle),
     #[error("Schema not initialized")]
     SchemaNotInitialized,
+    #[error("Column {column} of table {table} used by the pipeline changed: {kind}")]
+    SchemaDrift {
+        table: String,
+        column: String,
+        kind: SchemaDriftKind,
+    },
+    #[error("Schema of table {table} changed, the pipeline must be rebuilt")]
+    SchemaChanged { table: String },
+    #[error("Event time column {column} of table {table} must be a timestamp")]
+    InvalidEventTimeColumn { table: String, column: String },
+    #[error("Column {column} of table {table} can't be truncated, it must be a string, text or binary column")]
+    InvalidTruncateColumn { table: String, column: String },
+    #[error("Record of {size} bytes in table {table} exceeds the maximum record size of {max_bytes} bytes")]
+    RecordTooLarge {
+        table: String,
+        size: u64,
+        max_bytes: u64,
+    },
+    #[error("Failed to write dead letter to {0}: {1}")]
+    DeadLetter(String, #[source] std::io::Error),
+    #[error(
+        "Table {0} is refreshed on a schedule, but there's no directory to keep its last read in"
+    )]
+    NoRefreshDirectory(String),
 }
 
 #[derive(Debug)]
@@ -69,6 +92,9 @@ pub struct ConnectorSourceFactory {
     connector: Mutex<Option<Box<dyn Connector>>>,
     runtime: Arc<Runtime>,
     progress: Option<MultiProgress>,
+    schema_drift: SchemaDriftMonitor,
+    /// The directory the last reads of the tables refreshed on a schedule are kept in, by connection and table.
+    refresh_dir: Option<PathBuf>,
 }
 
 fn map_replication_type_to_output_port_type(typ: &CdcType) -> OutputPortType {
@@ -81,27 +107,69 @@ fn map_replication_type_to_output_port_type(typ: &CdcType) -> OutputPortType {
 
 impl ConnectorSourceFactory {
     pub async fn new(
-        table_and_ports: Vec<(TableInfo, PortHandle)>,
+        table_and_ports: Vec<(
+            TableInfo,
+            PortHandle,
+            Option<String>,
+            Option<EventTimeConfig>,
+            Option<MaxRecordSizeConfig>,
+        )>,
         connection: Connection,
         runtime: Arc<Runtime>,
         progress: Option<MultiProgress>,
+        schema_drift: SchemaDriftMonitor,
+        refresh_dir: Option<PathBuf>,
     ) -> Result<Self, ConnectorSourceFactoryError> {
         let connection_name = connection.name.clone();
 
         let connector = get_connector(connection)?;
         let tables: Vec<TableInfo> = table_and_ports
             .iter()
-            .map(|(table, _)| table.clone())
+            .map(|(table, _, _, _, _)| table.clone())
             .collect();
         let source_schemas = connector.get_schemas(&tables).await?;
+        let row_counts = match connector.estimate_row_counts(&tables).await {
+            Ok(row_counts) => row_counts,
+            Err(e) => {
+                warn!("[{connection_name}] Failed to estimate row counts: {e}");
+                vec![None; tables.len()]
+            }
+        };
 
         let mut tables = vec![];
-        for ((table, port), source_schema) in table_and_ports.into_iter().zip(source_schemas) {
+        for (
+            ((table, port, schedule, event_time, max_record_size), source_schema),
+            estimated_rows,
+        ) in table_and_ports
+            .into_iter()
+            .zip(source_schemas)
+            .zip(row_counts)
+        {
             let name = table.name;
             let columns = table.column_names;
             let source_schema = source_schema?;
             let schema = source_schema.schema;
             let cdc_type = source_schema.cdc_type;
+            let event_time = event_time
+                .map(|config| {
+                    schema
+                        .fields
+                        .iter()
+                        .position(|field| {
+                            field.name == config.column && field.typ == FieldType::Timestamp
+                        })
+                        .map(|index| (index, config.clone()))
+                        .ok_or_else(|| ConnectorSourceFactoryError::InvalidEventTimeColumn {
+                            table: name.clone(),
+                            column: config.column,
+                        })
+                })
+                .transpose()?;
+            let record_size_limit = max_record_size
+                .map(|config| {
+                    RecordSizeLimit::new(connection_name.clone(), name.clone(), &schema, &config)
+                })
+                .transpose()?;
 
             let table = Table {
                 name,
@@ -110,6 +178,10 @@ impl ConnectorSourceFactory {
                 schema,
                 cdc_type,
                 port,
+                schedule,
+                estimated_rows,
+                event_time,
+                record_size_limit,
             };
 
             tables.push(table);
@@ -121,6 +193,8 @@ impl ConnectorSourceFactory {
             connector: Mutex::new(Some(connector)),
             runtime,
             progress,
+            schema_drift,
+            refresh_dir,
         })
     }
 }
@@ -191,28 +265,93 @@ impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
             .collect();
         let ports = self.tables.iter().map(|table| table.port).collect();
 
+        let mut realtime_tables = vec![];
+        let mut refreshes = vec![];
+        for (table_index, table) in self.tables.iter().enumerate() {
+            match &table.schedule {
+                Some(cron) => {
+                    let refresh_dir = self.refresh_dir.as_ref().ok_or_else(|| {
+                        ConnectorSourceFactoryError::NoRefreshDirectory(table.name.clone())
+                    })?;
+                    let snapshot_dir =
+                        refresh_dir
+                            .join(&self.connection_name)
+                            .join(match &table.schema_name {
+                                Some(schema) => format!("{schema}.{}", table.name),
+                                None => table.name.clone(),
+                            });
+                    let refresh = ScheduledRefresh::new(
+                        cron,
+                        table.schema.primary_index.clone(),
+                        &snapshot_dir,
+                    )
+                    .map_err(ConnectorError::from)?;
+                    refreshes.push((table_index, Mutex::new(refresh)));
+                }
+                None => realtime_tables.push(table_index),
+            }
+        }
+
         let connector = self
             .connector
             .lock()
             .take()
             .expect("ConnectorSource was already built");
 
-        let mut bars = vec![];
-        for table in &self.tables {
-            let pb = attach_progress(self.progress.clone());
-            pb.set_message(table.name.clone());
-            bars.push(pb);
-        }
+        let progress = SourceProgress::new(
+            self.connection_name.clone(),
+            self.tables
+                .iter()
+                .map(|table| (table.name.clone(), table.estimated_rows))
+                .collect(),
+            self.progress.as_ref(),
+        );
+
+        let clock_skew = self
+            .tables
+            .iter()
+            .map(|table| {
+                table.event_time.as_ref().map(|(column_index, config)| {
+                    ClockSkewMonitor::new(
+                        self.connection_name.clone(),
+                        table.name.clone(),
+                        *column_index,
+                        config,
+                    )
+                })
+            })
+            .collect();
 
         Ok(Box::new(ConnectorSource {
             ingestor,
             iterator: Mutex::new(iterator),
             tables,
             ports,
+            realtime_tables,
+            refreshes,
             connector,
             runtime: self.runtime.clone(),
             connection_name: self.connection_name.clone(),
-            bars,
+            progress: Mutex::new(progress),
+            schema_drift: self.schema_drift.clone(),
+            read_columns: self
+                .tables
+                .iter()
+                .map(|table| {
+                    table
+                        .schema
+                        .fields
+                        .iter()
+                        .map(|field| field.name.clone())
+                        .collect()
+                })
+                .collect(),
+            clock_skew: Mutex::new(clock_skew),
+            record_size_limits: self
+                .tables
+                .iter()
+                .map(|table| table.record_size_limit.clone())
+                .collect(),
         }))
     }
 }
@@ -223,10 +362,21 @@ pub struct ConnectorSource {
     iterator: Mutex<IngestionIterator>,
     tables: Vec<TableInfo>,
     ports: Vec<PortHandle>,
+    /// Indexes of the tables the connector follows.
+    realtime_tables: Vec<usize>,
+    /// Indexes of the tables re-read on a schedule, with their refresh.
+    refreshes: Vec<(usize, Mutex<ScheduledRefresh>)>,
     connector: Box<dyn Connector>,
     runtime: Arc<Runtime>,
     connection_name: String,
-    bars: Vec<ProgressBar>,
+    progress: Mutex<SourceProgress>,
+    schema_drift: SchemaDriftMonitor,
+    /// The columns of each table in the schema the pipeline is built with.
+    read_columns: Vec<Vec<String>>,
+    /// The clock skew monitor of each table with an event time column.
+    clock_skew: Mutex<Vec<Option<ClockSkewMonitor>>>,
+    /// The maximum record size of each table that has one.
+    record_size_limits: Vec<Option<RecordSizeLimit>>,
 }
 
 const SOURCE_OPERATION_COUNTER_NAME: &str = "source_operation";
@@ -247,21 +397,54 @@ impl Source for ConnectorSource {
                 "Number of operation processed by source"
             );
 
-            let mut counter = vec![0; self.tables.len()];
             let t = scope.spawn(|| {
-                match self
-                    .runtime
-                    .block_on(self.connector.start(&self.ingestor, self.tables.clone()))
-                {
-                    Ok(_) => {}
-                    // If we get a channel error, it means the source sender thread has quit.
-                    // Any error handling is done in that thread.
-                    Err(ConnectorError::IngestorError(IngestorError::ChannelError(_))) => (),
-                    Err(e) => std::panic::panic_any(e),
+                if self.realtime_tables.is_empty() {
+                    return;
                 }
+                // The connector numbers the tables it's given, so map them back to all tables.
+                let ingestor = Ingestor {
+                    sender: Arc::new(Box::new(TableIndexForwarder {
+                        inner: self.ingestor.clone(),
+                        table_indexes: self.realtime_tables.clone(),
+                    })),
+                };
+                let tables = self
+                    .realtime_tables
+                    .iter()
+                    .map(|table_index| self.tables[*table_index].clone())
+                    .collect();
+                handle_connector_result(
+                    self.runtime
+                        .block_on(self.connector.start(&ingestor, tables)),
+                    &self.connection_name,
+                    None,
+                );
             });
+            let refresh_threads = self
+                .refreshes
+                .iter()
+                .map(|(table_index, refresh)| {
+                    scope.spawn(move || {
+                        let table = &self.tables[*table_index];
+                        handle_connector_result(
+                            refresh.lock().run(
+                                &self.runtime,
+                                self.connector.as_ref(),
+                                table,
+                                *table_index,
+                                &self.ingestor,
+                            ),
+                            &self.connection_name,
+                            Some(table.name.as_str()),
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
 
             let mut iterator = self.iterator.lock();
+            let mut progress = self.progress.lock();
+            let mut clock_skew = self.clock_skew.lock();
+            let mut snapshotting = false;
 
             for IngestionMessage { identifier, kind } in iterator.by_ref() {
                 let span = span!(
@@ -274,7 +457,19 @@ impl Source for ConnectorSource {
                 let _enter = span.enter();
 
                 match kind {
-                    IngestionMessageKind::OperationEvent { table_index, op } => {
+                    IngestionMessageKind::OperationEvent {
+                        table_index,
+                        mut op,
+                    } => {
+                        if let Some(limit) = &self.record_size_limits[table_index] {
+                            match limit.process(op, identifier)? {
+                                Some(limited) => op = limited,
+                                None => continue,
+                            }
+                        }
+                        if let Some(monitor) = &mut clock_skew[table_index] {
+                            monitor.process(&mut op, snapshotting, SystemTime::now());
+                        }
                         let port = self.ports[table_index];
                         let table_name = &self.tables[table_index].name;
 
@@ -306,15 +501,28 @@ impl Source for ConnectorSource {
                             port,
                         )?;
 
-                        // Update counter
-                        let counter = &mut counter[table_index];
-                        *counter += 1;
-                        if *counter % 1000 == 0 {
-                            self.bars[table_index].set_position(*counter);
-                        }
+                        progress.operation(table_index);
                     }
                     IngestionMessageKind::SnapshottingDone
                     | IngestionMessageKind::SnapshottingStarted => {
+                        snapshotting = kind == IngestionMessageKind::SnapshottingStarted;
+                        if snapshotting {
+                            progress.snapshotting_started();
+                        } else {
+                            progress.snapshotting_done();
+                        }
+                        for port in &self.ports {
+                            fw.send(
+                                IngestionMessage {
+                                    identifier,
+                                    kind: kind.clone(),
+                                },
+                                *port,
+                            )?;
+                        }
+                    }
+                    IngestionMessageKind::TransactionBegin
+                    | IngestionMessageKind::TransactionEnd => {
                         for port in &self.ports {
                             fw.send(
                                 IngestionMessage {
@@ -325,16 +533,102 @@ impl Source for ConnectorSource {
                             )?;
                         }
                     }
+                    IngestionMessageKind::SchemaDrift { table_index, drift } => {
+                        let table = &self.tables[table_index];
+                        let alert = self.schema_drift.report(
+                            &self.connection_name,
+                            &table.name,
+                            &self.read_columns[table_index],
+                            drift,
+                        );
+                        if alert.severity == SchemaDriftSeverity::Error {
+                            return Err(ConnectorSourceFactoryError::SchemaDrift {
+                                table: alert.table,
+                                column: alert.drift.column_name,
+                                kind: alert.drift.kind,
+                            }
+                            .into());
+                        }
+                    }
+                    IngestionMessageKind::SchemaChanged { table_index } => {
+                        let table = &self.tables[table_index].name;
+                        self.schema_drift
+                            .schema_changed(&self.connection_name, table);
+                        return Err(ConnectorSourceFactoryError::SchemaChanged {
+                            table: table.clone(),
+                        }
+                        .into());
+                    }
                 }
             }
 
             // If we reach here, it means the connector thread has quit and the `ingestor` has been dropped.
             // `join` will not block.
-            if let Err(e) = t.join() {
-                std::panic::panic_any(e);
+            for t in std::iter::once(t).chain(refresh_threads) {
+                if let Err(e) = t.join() {
+                    std::panic::panic_any(e);
+                }
             }
 
             Ok(())
         })
     }
 }
+
+fn handle_connector_result(
+    result: Result<(), ConnectorError>,
+    connection_name: &str,
+    table: Option<&str>,
+) {
+    match result {
+        Ok(_) => {}
+        // If we get a channel error, it means the source sender thread has quit.
+        // Any error handling is done in that thread.
+        Err(ConnectorError::IngestorError(IngestorError::ChannelError(_))) => (),
+        Err(e) => {
+            let context = PipelineErrorContext {
+                source: Some(connection_name),
+                table,
+                ..PipelineErrorContext::new(PipelineStage::Connector, connection_name)
+            };
+            record_pipeline_error(&context, &e);
+            std::panic::panic_any(e)
+        }
+    }
+}
+
+/// Maps the table indexes of a connector started on some of the tables to indexes of all tables.
+#[derive(Debug)]
+struct TableIndexForwarder {
+    inner: Ingestor,
+    table_indexes: Vec<usize>,
+}
+
+impl IngestorForwarder for TableIndexForwarder {
+    fn forward(&self, msg: IngestionMessage) -> Result<(), IngestorError> {
+        let kind = match msg.kind {
+            IngestionMessageKind::OperationEvent { table_index, op } => {
+                IngestionMessageKind::OperationEvent {
+                    table_index: self.table_indexes[table_index],
+                    op,
+                }
+            }
+            IngestionMessageKind::SchemaDrift { table_index, drift } => {
+                IngestionMessageKind::SchemaDrift {
+                    table_index: self.table_indexes[table_index],
+                    drift,
+                }
+            }
+            IngestionMessageKind::SchemaChanged { table_index } => {
+                IngestionMessageKind::SchemaChanged {
+                    table_index: self.table_indexes[table_index],
+                }
+            }
+            kind => kind,
+        };
+        self.inner.handle_message(IngestionMessage {
+            identifier: msg.identifier,
+            kind,
+        })
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::identifier;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+/// Name of the connection the `information_schema` tables are read from.
+pub const INFORMATION_SCHEMA: &str = "information_schema";
+
+const TABLES: &str = "information_schema.tables";
+const COLUMNS: &str = "information_schema.columns";
+
+const TABLES_PORT: PortHandle = 0;
+const COLUMNS_PORT: PortHandle = 1;
+
+/// Whether `name` is one of the `information_schema` tables.
+pub fn is_information_schema_table(name: &str, case_sensitive: bool) -> bool {
+    [TABLES, COLUMNS]
+        .into_iter()
+        .any(|table| identifier::name_matches(name, false, case_sensitive, table))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableType {
+    /// A table read from a connection.
+    Source,
+    /// A table produced by SQL, a router, an operator or a rollup.
+    View,
+    /// An endpoint, named after the endpoint rather than its table.
+    Endpoint,
+}
+
+impl TableType {
+    /// The `table_type` of the table in `information_schema.tables`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableType::Source => "BASE TABLE",
+            TableType::View => "VIEW",
+            TableType::Endpoint => "ENDPOINT",
+        }
+    }
+}
+
+/// A table of the app, as described by the `information_schema` tables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDescription {
+    pub name: String,
+    pub table_type: TableType,
+    /// The connection a source is read from. Not set for other tables.
+    pub connection: Option<String>,
+    pub schema: Schema,
+}
+
+/// The tables of an app, which SQL run against it can read from `information_schema.tables` and
+/// `information_schema.columns`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InformationSchema {
+    pub tables: Vec<TableDescription>,
+}
+
+impl InformationSchema {
+    /// Schema of `information_schema.tables`.
+    pub fn tables_schema() -> Schema {
+        let mut schema = Schema::new();
+        schema
+            .field(string_field(TABLES, "table_name", false), true)
+            .field(string_field(TABLES, "table_type", false), true)
+            .field(string_field(TABLES, "connection", true), false);
+        schema
+    }
+
+    /// Schema of `information_schema.columns`.
+    pub fn columns_schema() -> Schema {
+        let mut schema = Schema::new();
+        schema
+            .field(string_field(COLUMNS, "table_name", false), true)
+            .field(string_field(COLUMNS, "table_type", false), true)
+            .field(string_field(COLUMNS, "column_name", false), true)
+            .field(
+                FieldDefinition::new(
+                    "ordinal_position".to_string(),
+                    FieldType::UInt,
+                    false,
+                    source_definition(COLUMNS),
+                ),
+                false,
+            )
+            .field(string_field(COLUMNS, "data_type", false), false)
+            .field(string_field(COLUMNS, "is_nullable", false), false)
+            .field(
+                FieldDefinition::new(
+                    "is_primary_key".to_string(),
+                    FieldType::Boolean,
+                    false,
+                    source_definition(COLUMNS),
+                ),
+                false,
+            );
+        schema
+    }
+
+    /// Rows of `information_schema.tables`.
+    pub fn tables_records(&self) -> Vec<Record> {
+        self.tables
+            .iter()
+            .map(|table| {
+                Record::new(vec![
+                    Field::String(table.name.clone()),
+                    Field::String(table.table_type.as_str().to_string()),
+                    table.connection.clone().map_or(Field::Null, Field::String),
+                ])
+            })
+            .collect()
+    }
+
+    /// Rows of `information_schema.columns`. Positions start from 1.
+    pub fn columns_records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        for table in &self.tables {
+            for (index, field) in table.schema.fields.iter().enumerate() {
+                records.push(Record::new(vec![
+                    Field::String(table.name.clone()),
+                    Field::String(table.table_type.as_str().to_string()),
+                    Field::String(field.name.clone()),
+                    Field::UInt(index as u64 + 1),
+                    Field::String(field.typ.to_string()),
+                    Field::String(if field.nullable { "YES" } else { "NO" }.to_string()),
+                    Field::Boolean(table.schema.primary_index.contains(&index)),
+                ]));
+            }
+        }
+        records
+    }
+}
+
+fn source_definition(table: &str) -> SourceDefinition {
+    SourceDefinition::Table {
+        connection: INFORMATION_SCHEMA.to_string(),
+        name: table.to_string(),
+    }
+}
+
+fn string_field(table: &str, name: &str, nullable: bool) -> FieldDefinition {
+    FieldDefinition::new(
+        name.to_string(),
+        FieldType::String,
+        nullable,
+        source_definition(table),
+    )
+}
+
+/// Reads the `information_schema` tables the pipeline uses, as a snapshot that never changes.
+#[derive(Debug)]
+pub struct InformationSchemaSourceFactory {
+    information_schema: Arc<InformationSchema>,
+    /// Ports of the tables the pipeline uses.
+    ports: Vec<PortHandle>,
+}
+
+impl InformationSchemaSourceFactory {
+    /// Creates a source of the `information_schema` tables in `tables`.
+    pub fn new(
+        information_schema: Arc<InformationSchema>,
+        tables: &[String],
+        case_sensitive: bool,
+    ) -> Self {
+        let ports = [(TABLES, TABLES_PORT), (COLUMNS, COLUMNS_PORT)]
+            .into_iter()
+            .filter(|(name, _)| {
+                tables
+                    .iter()
+                    .any(|table| identifier::name_matches(table, false, case_sensitive, name))
+            })
+            .map(|(_, port)| port)
+            .collect();
+        Self {
+            information_schema,
+            ports,
+        }
+    }
+
+    /// The ports of the tables, by table name.
+    pub fn mappings(&self) -> HashMap<String, PortHandle> {
+        self.ports
+            .iter()
+            .map(|port| (self.get_output_port_name(port), *port))
+            .collect()
+    }
+}
+
+impl SourceFactory<SchemaSQLContext> for InformationSchemaSourceFactory {
+    fn get_output_schema(
+        &self,
+        port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let schema = if *port == TABLES_PORT {
+            InformationSchema::tables_schema()
+        } else {
+            InformationSchema::columns_schema()
+        };
+        Ok((schema, SchemaSQLContext::default()))
+    }
+
+    fn get_output_port_name(&self, port: &PortHandle) -> String {
+        if *port == TABLES_PORT {
+            TABLES
+        } else {
+            COLUMNS
+        }
+        .to_string()
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        self.ports
+            .iter()
+            .map(|port| OutputPortDef::new(*port, OutputPortType::Stateless))
+            .collect()
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, BoxedError> {
+        Ok(Box::new(InformationSchemaSource {
+            information_schema: self.information_schema.clone(),
+            ports: self.ports.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct InformationSchemaSource {
+    information_schema: Arc<InformationSchema>,
+    ports: Vec<PortHandle>,
+}
+
+impl Source for InformationSchemaSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, BoxedError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), BoxedError> {
+        for port in &self.ports {
+            fw.send(IngestionMessage::new_snapshotting_started(0, 0), *port)?;
+        }
+        let mut seq_no = 0;
+        for port in &self.ports {
+            let records = if *port == TABLES_PORT {
+                self.information_schema.tables_records()
+            } else {
+                self.information_schema.columns_records()
+            };
+            for record in records {
+                seq_no += 1;
+                fw.send(
+                    IngestionMessage::new_op(0, seq_no, 0, Operation::Insert { new: record }),
+                    *port,
+                )?;
+            }
+        }
+        for port in &self.ports {
+            fw.send(IngestionMessage::new_snapshotting_done(0, seq_no), *port)?;
+        }
+        Ok(())
+    }
+}
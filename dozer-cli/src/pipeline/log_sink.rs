@@ -2,6 +2,7 @@ use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use dozer_cache::dozer_log::{
     attach_progress,
+    provenance::ProvenanceStore,
     replication::{Log, LogOperation},
 };
 use dozer_core::{
@@ -12,16 +13,22 @@ use dozer_core::{
     DEFAULT_PORT_HANDLE,
 };
 use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_storage::errors::StorageError;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::indicatif::{MultiProgress, ProgressBar};
-use dozer_types::types::Schema;
+use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::node::Provenance;
+use dozer_types::types::{Field, Operation, Record, Schema};
 use tokio::{runtime::Runtime, sync::Mutex};
 
+use crate::simple::modify_schema;
+
 #[derive(Debug)]
 pub struct LogSinkFactory {
     runtime: Arc<Runtime>,
     log: Arc<Mutex<Log>>,
-    endpoint_name: String,
+    api_endpoint: ApiEndpoint,
+    provenance: Option<ProvenanceStore>,
     multi_pb: MultiProgress,
 }
 
@@ -29,13 +36,15 @@ impl LogSinkFactory {
     pub fn new(
         runtime: Arc<Runtime>,
         log: Arc<Mutex<Log>>,
-        endpoint_name: String,
+        api_endpoint: ApiEndpoint,
+        provenance: Option<ProvenanceStore>,
         multi_pb: MultiProgress,
     ) -> Self {
         Self {
             runtime,
             log,
-            endpoint_name,
+            api_endpoint,
+            provenance,
             multi_pb,
         }
     }
@@ -56,14 +65,26 @@ impl SinkFactory<SchemaSQLContext> for LogSinkFactory {
 
     fn build(
         &self,
-        _input_schemas: HashMap<PortHandle, Schema>,
+        input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, BoxedError> {
-        Ok(Box::new(LogSink::new(
+        let mut sink = LogSink::new(
             self.runtime.clone(),
             self.log.clone(),
-            self.endpoint_name.clone(),
+            self.api_endpoint.name.clone(),
             Some(self.multi_pb.clone()),
-        )))
+        );
+        if let Some(provenance) = &self.provenance {
+            let schema = input_schemas
+                .get(&DEFAULT_PORT_HANDLE)
+                .expect("Log sink has one input port");
+            // Records are keyed like the endpoint cache keys them.
+            let (schema, _) = modify_schema(schema, &self.api_endpoint)?;
+            sink = sink.with_provenance(provenance.clone(), schema.primary_index);
+        }
+        let sink: Box<dyn Sink> = Box::new(sink);
+        #[cfg(feature = "chaos")]
+        let sink = super::chaos_sink::ChaosSink::wrap(sink);
+        Ok(sink)
     }
 }
 
@@ -73,6 +94,8 @@ pub struct LogSink {
     log: Arc<Mutex<Log>>,
     pb: ProgressBar,
     counter: u64,
+    /// Where the provenance of the endpoint's records is recorded, and their primary key.
+    provenance: Option<(ProvenanceStore, Vec<usize>)>,
 }
 
 impl LogSink {
@@ -89,6 +112,49 @@ impl LogSink {
             log,
             pb,
             counter: 0,
+            provenance: None,
+        }
+    }
+
+    /// Records the provenance of the records in `store`, by the primary key `primary_index`.
+    pub fn with_provenance(mut self, store: ProvenanceStore, primary_index: Vec<usize>) -> Self {
+        self.provenance = Some((store, primary_index));
+        self
+    }
+
+    fn record_provenance(
+        &self,
+        op: &Operation,
+        provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), StorageError> {
+        let Some((store, primary_index)) = &self.provenance else {
+            return Ok(());
+        };
+        // Without a primary key records can't be looked up.
+        if primary_index.is_empty() {
+            return Ok(());
+        }
+        let key = |record: &Record| -> Vec<Field> {
+            primary_index
+                .iter()
+                .map(|index| record.values[*index].clone())
+                .collect()
+        };
+        let changed = move |key: Vec<Field>| match provenance {
+            Some(provenance) => store.insert(&key, &provenance),
+            // The record changed, but not because of a single source row.
+            None => store.remove(&key),
+        };
+        match op {
+            Operation::Delete { old } => store.remove(&key(old)),
+            Operation::Insert { new } => changed(key(new)),
+            Operation::Update { old, new } => {
+                let (old_key, new_key) = (key(old), key(new));
+                if old_key != new_key {
+                    store.remove(&old_key)?;
+                }
+                changed(new_key)
+            }
         }
     }
 
@@ -100,19 +166,31 @@ impl LogSink {
 
 impl Sink for LogSink {
     fn process(
+        &mut self,
+        from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+    ) -> Result<(), BoxedError> {
+        self.process_with_provenance(from_port, record_store, op, None)
+    }
+
+    fn process_with_provenance(
         &mut self,
         _from_port: PortHandle,
         record_store: &ProcessorRecordStore,
         op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
     ) -> Result<(), BoxedError> {
+        let op = record_store.load_operation(&op)?;
+        // Updates that change nothing aren't written, so the cache and subscribers don't see them.
+        if op.is_noop() {
+            return Ok(());
+        }
+        self.record_provenance(&op, provenance)?;
         self.runtime.block_on(async {
             let mut log = self.log.lock().await;
             log.write(
-                dozer_cache::dozer_log::replication::LogOperation::Op {
-                    op: record_store
-                        .load_operation(&op)
-                        .map_err(Into::<BoxedError>::into)?,
-                },
+                dozer_cache::dozer_log::replication::LogOperation::Op { op },
                 self.log.clone(),
             )
             .await
@@ -125,14 +203,24 @@ impl Sink for LogSink {
     fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError> {
         self.runtime.block_on(async {
             let mut log = self.log.lock().await;
-            log.write(
-                LogOperation::Commit {
-                    decision_instant: epoch_details.decision_instant,
-                },
-                self.log.clone(),
-            )
-            .await
+            let decision_instant = epoch_details.decision_instant;
+            let op = if epoch_details.open_transactions.is_empty() {
+                LogOperation::Commit { decision_instant }
+            } else {
+                LogOperation::CommitInTransaction {
+                    decision_instant,
+                    open_transactions: epoch_details
+                        .open_transactions
+                        .iter()
+                        .map(|(source, txid)| (source.clone(), *txid))
+                        .collect(),
+                }
+            };
+            log.write(op, self.log.clone()).await
         })?;
+        if let Some((store, _)) = &self.provenance {
+            store.commit()?;
+        }
         self.update_counter();
         Ok(())
     }
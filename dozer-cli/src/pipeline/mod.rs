@@ -1,11 +1,20 @@
 mod builder;
+#[cfg(feature = "chaos")]
+mod chaos_sink;
+mod clock_skew;
 pub mod connector_source;
 mod dummy_sink;
+pub mod information_schema;
 mod log_sink;
+mod progress;
+mod record_size;
+pub mod rollup;
+pub mod schema_drift;
 pub mod source_builder;
 
-pub use builder::PipelineBuilder;
+pub use builder::{builtin_operators, PipelineBuilder};
 pub use log_sink::{LogSink, LogSinkFactory};
+pub use schema_drift::SchemaDriftMonitor;
 
 #[cfg(test)]
 mod tests;
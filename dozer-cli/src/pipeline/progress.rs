@@ -0,0 +1,243 @@
+use std::time::{Duration, Instant};
+
+use dozer_types::indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use dozer_types::log::info;
+
+/// How often the progress of a table is logged when it can't be drawn.
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+/// Bars and logs are updated every this many operations of a table.
+const UPDATE_EVERY: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Waiting,
+    Snapshot,
+    Streaming,
+}
+
+impl Phase {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Waiting => "waiting",
+            Phase::Snapshot => "snapshot",
+            Phase::Streaming => "streaming",
+        }
+    }
+}
+
+/// The progress of the tables of a source: a row per table with its phase, rows per second and, while snapshotting
+/// a table whose size is known, a bar with its ETA. Progress is logged instead when the bars can't be drawn, such as
+/// when stderr is not a terminal.
+#[derive(Debug)]
+pub struct SourceProgress {
+    connection_name: String,
+    tables: Vec<TableProgress>,
+    /// Whether progress is logged instead of drawn.
+    log: bool,
+}
+
+#[derive(Debug)]
+struct TableProgress {
+    name: String,
+    /// Estimated number of rows to snapshot, if the connector knows it.
+    estimated_rows: Option<u64>,
+    phase: Phase,
+    count: u64,
+    bar: ProgressBar,
+    phase_started: Instant,
+    phase_start_count: u64,
+    last_logged: Instant,
+}
+
+impl SourceProgress {
+    /// `tables` are the names of the tables with their estimated number of rows.
+    pub fn new(
+        connection_name: String,
+        tables: Vec<(String, Option<u64>)>,
+        multi_pb: Option<&MultiProgress>,
+    ) -> Self {
+        let log = multi_pb.map_or(true, |multi_pb| multi_pb.is_hidden());
+        let now = Instant::now();
+        let tables = tables
+            .into_iter()
+            .map(|(name, estimated_rows)| {
+                let bar = match multi_pb {
+                    Some(multi_pb) => multi_pb.add(ProgressBar::new_spinner()),
+                    None => ProgressBar::hidden(),
+                };
+                bar.set_prefix(name.clone());
+                let table = TableProgress {
+                    name,
+                    estimated_rows,
+                    phase: Phase::Waiting,
+                    count: 0,
+                    bar,
+                    phase_started: now,
+                    phase_start_count: 0,
+                    last_logged: now,
+                };
+                table.set_style();
+                table
+            })
+            .collect();
+        Self {
+            connection_name,
+            tables,
+            log,
+        }
+    }
+
+    pub fn snapshotting_started(&mut self) {
+        self.set_phase(Phase::Snapshot);
+        if self.log {
+            info!(
+                "[{}] Snapshotting {} tables",
+                self.connection_name,
+                self.tables.len()
+            );
+        }
+    }
+
+    pub fn snapshotting_done(&mut self) {
+        if self.log {
+            for table in &self.tables {
+                info!(
+                    "[{}] Snapshotted {}: {} rows in {:.1}s",
+                    self.connection_name,
+                    table.name,
+                    table.phase_count(),
+                    table.phase_started.elapsed().as_secs_f64()
+                );
+            }
+        }
+        self.set_phase(Phase::Streaming);
+    }
+
+    pub fn operation(&mut self, table_index: usize) {
+        let table = &mut self.tables[table_index];
+        table.count += 1;
+        if table.count % UPDATE_EVERY != 0 {
+            return;
+        }
+        table.bar.set_position(table.count);
+        if self.log && table.last_logged.elapsed() >= LOG_INTERVAL {
+            table.last_logged = Instant::now();
+            info!("[{}] {}", self.connection_name, table.summary());
+        }
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        let now = Instant::now();
+        for table in &mut self.tables {
+            table.phase = phase;
+            table.phase_started = now;
+            table.phase_start_count = table.count;
+            table.last_logged = now;
+            table.bar.reset();
+            table.bar.set_position(table.count);
+            table.set_style();
+        }
+    }
+}
+
+impl TableProgress {
+    /// The number of rows to snapshot, if it's being snapshotted and it's known.
+    fn length(&self) -> Option<u64> {
+        match self.phase {
+            Phase::Snapshot => self
+                .estimated_rows
+                .map(|rows| self.phase_start_count + rows),
+            Phase::Waiting | Phase::Streaming => None,
+        }
+    }
+
+    fn phase_count(&self) -> u64 {
+        self.count - self.phase_start_count
+    }
+
+    fn set_style(&self) {
+        let template = match self.length() {
+            Some(length) => {
+                self.bar.set_length(length);
+                "{spinner:.red} {prefix} [{msg}] {wide_bar:.cyan/blue} {pos}/{len} {per_sec} ETA {eta}"
+            }
+            None => "{spinner:.red} {prefix} [{msg}] {pos} {per_sec}",
+        };
+        self.bar.set_style(
+            ProgressStyle::with_template(template)
+                .unwrap()
+                // For more spinners check out the cli-spinners project:
+                // https://github.com/sindresorhus/cli-spinners/blob/master/spinners.json
+                .tick_strings(&[
+                    "▹▹▹▹▹",
+                    "▸▹▹▹▹",
+                    "▹▸▹▹▹",
+                    "▹▹▸▹▹",
+                    "▹▹▹▸▹",
+                    "▹▹▹▹▸",
+                    "▪▪▪▪▪",
+                ]),
+        );
+        self.bar.set_message(self.phase.as_str());
+    }
+
+    fn summary(&self) -> String {
+        let elapsed = self.phase_started.elapsed().as_secs_f64();
+        let count = self.phase_count();
+        let rate = if elapsed > 0.0 {
+            count as f64 / elapsed
+        } else {
+            0.0
+        };
+        let mut summary = format!(
+            "{} [{}]: {count} rows, {rate:.0} rows/s",
+            self.name,
+            self.phase.as_str()
+        );
+        if let (Phase::Snapshot, Some(rows)) = (self.phase, self.estimated_rows) {
+            summary.push_str(&format!(", {count}/{rows}"));
+            if rate > 0.0 && count < rows {
+                let eta = (rows - count) as f64 / rate;
+                summary.push_str(&format!(", ETA {eta:.0}s"));
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        let mut progress = SourceProgress::new(
+            "pg".to_string(),
+            vec![
+                ("films".to_string(), Some(5000)),
+                ("actors".to_string(), None),
+            ],
+            None,
+        );
+        assert!(progress.log);
+        assert_eq!(progress.tables[0].length(), None);
+
+        progress.snapshotting_started();
+        assert_eq!(progress.tables[0].length(), Some(5000));
+        assert_eq!(progress.tables[1].length(), None);
+        for _ in 0..2000 {
+            progress.operation(0);
+        }
+        assert_eq!(progress.tables[0].bar.position(), 2000);
+        assert!(progress.tables[0]
+            .summary()
+            .starts_with("films [snapshot]: 2000 rows"));
+
+        progress.snapshotting_done();
+        progress.operation(0);
+        let films = &progress.tables[0];
+        assert_eq!(films.phase, Phase::Streaming);
+        assert_eq!(films.length(), None);
+        assert_eq!(films.phase_count(), 1);
+    }
+}
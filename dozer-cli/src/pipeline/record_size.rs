@@ -0,0 +1,365 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use dozer_types::log::warn;
+use dozer_types::models::source::{MaxRecordSizeConfig, OversizedRecordPolicy};
+use dozer_types::node::OpIdentifier;
+use dozer_types::serde_json::{self, json};
+use dozer_types::types::{Field, FieldType, Operation, Record, Schema};
+use metrics::{describe_counter, increment_counter};
+
+use super::connector_source::ConnectorSourceFactoryError;
+
+const OVERSIZED_RECORD_COUNTER_NAME: &str = "source_oversized_record";
+
+#[derive(Debug, Clone)]
+enum Policy {
+    /// Indexes of the columns to truncate, in order.
+    Truncate(Vec<usize>),
+    /// Path of the file dead letters are appended to.
+    DeadLetter(String),
+    Fail,
+}
+
+/// Enforces the maximum size of the records of a table.
+///
+/// Dead-lettered records are treated as if the source never had them: a delete of one is dropped, and an update from
+/// or to one becomes an insert or a delete of the other record.
+#[derive(Debug, Clone)]
+pub struct RecordSizeLimit {
+    connection: String,
+    table: String,
+    max_bytes: u64,
+    policy: Policy,
+}
+
+impl RecordSizeLimit {
+    pub fn new(
+        connection: String,
+        table: String,
+        schema: &Schema,
+        config: &MaxRecordSizeConfig,
+    ) -> Result<Self, ConnectorSourceFactoryError> {
+        describe_counter!(
+            OVERSIZED_RECORD_COUNTER_NAME,
+            "Number of operations of a source with records larger than its maximum record size"
+        );
+        let policy = match config.on_oversized.clone().unwrap_or_default() {
+            OversizedRecordPolicy::Truncate(truncate) => Policy::Truncate(
+                truncate
+                    .columns
+                    .into_iter()
+                    .map(|column| {
+                        schema
+                            .fields
+                            .iter()
+                            .position(|field| {
+                                field.name == column
+                                    && matches!(
+                                        field.typ,
+                                        FieldType::String | FieldType::Text | FieldType::Binary
+                                    )
+                            })
+                            .ok_or_else(|| ConnectorSourceFactoryError::InvalidTruncateColumn {
+                                table: table.clone(),
+                                column,
+                            })
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            OversizedRecordPolicy::DeadLetter(dead_letter) => Policy::DeadLetter(dead_letter.path),
+            OversizedRecordPolicy::Fail(()) => Policy::Fail,
+        };
+        Ok(Self {
+            connection,
+            table,
+            max_bytes: config.max_bytes,
+            policy,
+        })
+    }
+
+    /// Applies the policy to `op` if it has oversized records. Returns the operation to send on, if any.
+    pub fn process(
+        &self,
+        mut op: Operation,
+        identifier: OpIdentifier,
+    ) -> Result<Option<Operation>, ConnectorSourceFactoryError> {
+        let size = match &op {
+            Operation::Insert { new: record } | Operation::Delete { old: record } => {
+                record_size(record)
+            }
+            Operation::Update { old, new } => record_size(old).max(record_size(new)),
+        };
+        if size <= self.max_bytes {
+            return Ok(Some(op));
+        }
+        increment_counter!(
+            OVERSIZED_RECORD_COUNTER_NAME,
+            "connection" => self.connection.clone(),
+            "table" => self.table.clone()
+        );
+
+        match &self.policy {
+            Policy::Fail => Err(self.too_large(size)),
+            Policy::Truncate(columns) => {
+                match &mut op {
+                    Operation::Insert { new: record } | Operation::Delete { old: record } => {
+                        self.truncate(record, columns)?
+                    }
+                    Operation::Update { old, new } => {
+                        self.truncate(old, columns)?;
+                        self.truncate(new, columns)?;
+                    }
+                }
+                Ok(Some(op))
+            }
+            Policy::DeadLetter(path) => {
+                self.dead_letter(path, &op, size, identifier)?;
+                Ok(match op {
+                    Operation::Update { old, new } => match (self.fits(&old), self.fits(&new)) {
+                        (true, _) => Some(Operation::Delete { old }),
+                        (_, true) => Some(Operation::Insert { new }),
+                        _ => None,
+                    },
+                    Operation::Insert { .. } | Operation::Delete { .. } => None,
+                })
+            }
+        }
+    }
+
+    fn fits(&self, record: &Record) -> bool {
+        record_size(record) <= self.max_bytes
+    }
+
+    fn too_large(&self, size: u64) -> ConnectorSourceFactoryError {
+        ConnectorSourceFactoryError::RecordTooLarge {
+            table: self.table.clone(),
+            size,
+            max_bytes: self.max_bytes,
+        }
+    }
+
+    /// Truncates the values of `columns` in order, until the record fits.
+    fn truncate(
+        &self,
+        record: &mut Record,
+        columns: &[usize],
+    ) -> Result<(), ConnectorSourceFactoryError> {
+        for index in columns {
+            let size = record_size(record);
+            if size <= self.max_bytes {
+                return Ok(());
+            }
+            let excess = (size - self.max_bytes) as usize;
+            match &mut record.values[*index] {
+                Field::String(value) | Field::Text(value) => {
+                    let mut len = value.len().saturating_sub(excess);
+                    while !value.is_char_boundary(len) {
+                        len -= 1;
+                    }
+                    value.truncate(len);
+                }
+                Field::Binary(value) => value.truncate(value.len().saturating_sub(excess)),
+                _ => (),
+            }
+        }
+        let size = record_size(record);
+        if size <= self.max_bytes {
+            Ok(())
+        } else {
+            Err(self.too_large(size))
+        }
+    }
+
+    /// Appends `op` to the dead letter file as a json line. The file is only opened when there's a dead letter.
+    fn dead_letter(
+        &self,
+        path: &str,
+        op: &Operation,
+        size: u64,
+        identifier: OpIdentifier,
+    ) -> Result<(), ConnectorSourceFactoryError> {
+        warn!(
+            "[{}] Record of {} bytes in table {} exceeds {} bytes, writing it to {path}",
+            self.connection, size, self.table, self.max_bytes
+        );
+        let mut line = serde_json::to_vec(&json!({
+            "connection": self.connection,
+            "table": self.table,
+            "txid": identifier.txid,
+            "seq_in_tx": identifier.seq_in_tx,
+            "size": size,
+            "operation": op,
+        }))
+        .map_err(|e| ConnectorSourceFactoryError::DeadLetter(path.to_string(), e.into()))?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|e| ConnectorSourceFactoryError::DeadLetter(path.to_string(), e))
+    }
+}
+
+fn record_size(record: &Record) -> u64 {
+    record
+        .values
+        .iter()
+        .map(|field| field.encoding_len() as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::source::{DeadLetterConfig, TruncateConfig};
+    use dozer_types::types::{FieldDefinition, SourceDefinition};
+
+    use super::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema::new();
+        for (name, typ) in [
+            ("id", FieldType::Int),
+            ("name", FieldType::String),
+            ("photo", FieldType::Binary),
+        ] {
+            schema.field(
+                FieldDefinition::new(name.to_string(), typ, true, SourceDefinition::Dynamic),
+                name == "id",
+            );
+        }
+        schema
+    }
+
+    fn limit(policy: OversizedRecordPolicy) -> RecordSizeLimit {
+        let config = MaxRecordSizeConfig {
+            max_bytes: 100,
+            on_oversized: Some(policy),
+        };
+        RecordSizeLimit::new("db".to_string(), "users".to_string(), &schema(), &config).unwrap()
+    }
+
+    fn record(name: &str, photo_len: usize) -> Record {
+        Record::new(vec![
+            Field::Int(1),
+            Field::String(name.to_string()),
+            Field::Binary(vec![0; photo_len]),
+        ])
+    }
+
+    fn identifier() -> OpIdentifier {
+        OpIdentifier::new(1, 0)
+    }
+
+    #[test]
+    fn test_fail() {
+        let limit = limit(OversizedRecordPolicy::Fail(()));
+        let op = Operation::Insert {
+            new: record("alice", 10),
+        };
+        assert_eq!(limit.process(op.clone(), identifier()).unwrap(), Some(op));
+        assert!(matches!(
+            limit.process(
+                Operation::Insert {
+                    new: record("alice", 100),
+                },
+                identifier()
+            ),
+            Err(ConnectorSourceFactoryError::RecordTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncate() {
+        let limit = limit(OversizedRecordPolicy::Truncate(TruncateConfig {
+            columns: vec!["photo".to_string(), "name".to_string()],
+        }));
+        let Some(Operation::Insert { new }) = limit
+            .process(
+                Operation::Insert {
+                    new: record("alice", 200),
+                },
+                identifier(),
+            )
+            .unwrap()
+        else {
+            panic!("not an insert");
+        };
+        assert_eq!(record_size(&new), 100);
+        assert_eq!(new.values[1], Field::String("alice".to_string()));
+
+        // Strings are truncated at character boundaries.
+        let Some(Operation::Delete { old }) = limit
+            .process(
+                Operation::Delete {
+                    old: record(&"é".repeat(60), 0),
+                },
+                identifier(),
+            )
+            .unwrap()
+        else {
+            panic!("not a delete");
+        };
+        assert!(record_size(&old) <= 100);
+        assert!(matches!(&old.values[1], Field::String(name) if name.chars().all(|c| c == 'é')));
+
+        assert!(matches!(
+            RecordSizeLimit::new(
+                "db".to_string(),
+                "users".to_string(),
+                &schema(),
+                &MaxRecordSizeConfig {
+                    max_bytes: 100,
+                    on_oversized: Some(OversizedRecordPolicy::Truncate(TruncateConfig {
+                        columns: vec!["id".to_string()],
+                    })),
+                },
+            ),
+            Err(ConnectorSourceFactoryError::InvalidTruncateColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_dead_letter() {
+        let dir = tempdir::TempDir::new("record_size").unwrap();
+        let path = dir.path().join("dead_letters.jsonl");
+        let limit = limit(OversizedRecordPolicy::DeadLetter(DeadLetterConfig {
+            path: path.to_str().unwrap().to_string(),
+        }));
+
+        let small = record("alice", 10);
+        let large = record("alice", 200);
+        assert_eq!(
+            limit
+                .process(Operation::Insert { new: large.clone() }, identifier())
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            limit
+                .process(
+                    Operation::Update {
+                        old: small.clone(),
+                        new: large.clone()
+                    },
+                    identifier()
+                )
+                .unwrap(),
+            Some(Operation::Delete { old: small.clone() })
+        );
+        assert_eq!(
+            limit
+                .process(
+                    Operation::Update {
+                        old: large,
+                        new: small.clone()
+                    },
+                    identifier()
+                )
+                .unwrap(),
+            Some(Operation::Insert { new: small })
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+    }
+}
@@ -0,0 +1,158 @@
+use dozer_types::models::api_endpoint::{
+    ApiEndpoint, ApiIndex, ExpressionIndex, FullText, Rollup, RollupAggregate, SecondaryIndex,
+    SecondaryIndexConfig, SortedInverted,
+};
+
+use crate::errors::RollupError;
+
+const ROLLUP_FUNCTIONS: [&str; 5] = ["count", "sum", "min", "max", "avg"];
+
+/// Returns the companion endpoints serving the rollups of `endpoints`.
+///
+/// A companion endpoint reads the table named after its rollup, which the pipeline builder adds. It's keyed by
+/// the group by columns, and keeps the conflict resolution and the secondary indexes of its endpoint that are on
+/// columns of the rollup.
+pub fn rollup_endpoints(endpoints: &[ApiEndpoint]) -> Vec<ApiEndpoint> {
+    endpoints
+        .iter()
+        .flat_map(|endpoint| {
+            endpoint
+                .rollups
+                .iter()
+                .map(move |rollup| rollup_endpoint(endpoint, rollup))
+        })
+        .collect()
+}
+
+fn rollup_endpoint(endpoint: &ApiEndpoint, rollup: &Rollup) -> ApiEndpoint {
+    let columns = rollup_columns(rollup);
+    let secondary = endpoint
+        .index
+        .as_ref()
+        .and_then(|index| index.secondary.as_ref())
+        .map(|secondary| SecondaryIndexConfig {
+            skip_default: secondary
+                .skip_default
+                .iter()
+                .filter(|column| columns.contains(*column))
+                .cloned()
+                .collect(),
+            create: secondary
+                .create
+                .iter()
+                .filter(|create| {
+                    create.index.as_ref().map_or(false, |index| {
+                        index_columns(index)
+                            .into_iter()
+                            .all(|column| columns.iter().any(|name| name == column))
+                    })
+                })
+                .cloned()
+                .collect(),
+        });
+    ApiEndpoint {
+        name: rollup.name.clone(),
+        table_name: rollup.name.clone(),
+        path: rollup
+            .path
+            .clone()
+            .unwrap_or_else(|| format!("/{}", rollup.name)),
+        index: Some(ApiIndex {
+            primary_key: rollup.group_by.clone(),
+            secondary,
+        }),
+        conflict_resolution: endpoint.conflict_resolution,
+        ..Default::default()
+    }
+}
+
+/// The columns of the rollup's table: the group by columns, then one per aggregate.
+fn rollup_columns(rollup: &Rollup) -> Vec<String> {
+    let mut columns = rollup.group_by.clone();
+    columns.extend(rollup.aggregates.iter().map(aggregate_name));
+    columns
+}
+
+fn aggregate_name(aggregate: &RollupAggregate) -> String {
+    let function = aggregate.function.to_lowercase();
+    aggregate
+        .name
+        .clone()
+        .unwrap_or_else(|| match &aggregate.column {
+            Some(column) => format!("{function}_{column}"),
+            None => function,
+        })
+}
+
+/// The columns `index` is on. An expression index is on the argument of its function, e.g. `email` of
+/// `lower(email)`.
+fn index_columns(index: &SecondaryIndex) -> Vec<&str> {
+    match index {
+        SecondaryIndex::SortedInverted(SortedInverted { fields }) => {
+            fields.iter().map(String::as_str).collect()
+        }
+        SecondaryIndex::FullText(FullText { field }) => vec![field.as_str()],
+        SecondaryIndex::Expression(ExpressionIndex { expression }) => vec![expression
+            .split_once('(')
+            .map_or(expression.as_str(), |(_, argument)| argument)
+            .trim_end()
+            .trim_end_matches(')')
+            .trim()],
+    }
+}
+
+/// Returns the `SELECT` statement computing `rollup` over `table_name`.
+///
+/// Group by columns come first, so the aggregation processor makes them the primary key.
+pub fn rollup_sql(table_name: &str, rollup: &Rollup) -> Result<String, RollupError> {
+    if rollup.group_by.is_empty() {
+        return Err(RollupError::NoGroupBy(rollup.name.clone()));
+    }
+    if rollup.aggregates.is_empty() {
+        return Err(RollupError::NoAggregates(rollup.name.clone()));
+    }
+
+    let group_by = rollup
+        .group_by
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>();
+    let mut columns = group_by.clone();
+    for aggregate in &rollup.aggregates {
+        let function = aggregate.function.to_lowercase();
+        if !ROLLUP_FUNCTIONS.contains(&function.as_str()) {
+            return Err(RollupError::UnknownFunction(
+                rollup.name.clone(),
+                aggregate.function.clone(),
+            ));
+        }
+        let argument = match (&aggregate.column, function.as_str()) {
+            (Some(column), _) => quote_identifier(column),
+            (None, "count") => "*".to_string(),
+            (None, _) => {
+                return Err(RollupError::MissingColumn(
+                    rollup.name.clone(),
+                    aggregate.function.clone(),
+                ))
+            }
+        };
+        columns.push(format!(
+            "{}({argument}) AS {}",
+            function.to_uppercase(),
+            quote_identifier(&aggregate_name(aggregate))
+        ));
+    }
+
+    Ok(format!(
+        "SELECT {} FROM {} GROUP BY {}",
+        columns.join(", "),
+        quote_identifier(table_name),
+        group_by.join(", ")
+    ))
+}
+
+/// Quotes `name`, so it refers to exactly the column or table of that name, even if it's a keyword or has
+/// characters identifiers can't have.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
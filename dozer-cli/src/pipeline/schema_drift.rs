@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dozer_types::ingestion_types::{
+    SchemaDrift, SchemaDriftAlert, SchemaDriftKind, SchemaDriftSeverity,
+};
+use dozer_types::models::app_config::{OnColumnDropped, OnTypeChanged, SchemaDriftConfig};
+use dozer_types::parking_lot::Mutex;
+use dozer_types::tracing::{error, info, warn};
+use metrics::{describe_counter, increment_counter};
+
+const SCHEMA_DRIFT_COUNTER_NAME: &str = "schema_drift";
+
+/// Classifies schema drifts reported by connectors and keeps the alerts for the internal pipeline server.
+///
+/// Only the latest alert of each column is kept.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDriftMonitor {
+    config: SchemaDriftConfig,
+    alerts: Arc<Mutex<Vec<SchemaDriftAlert>>>,
+    /// The columns the SQL reads of the tables only the SQL reads, by connection and table. Drifts of other tables are
+    /// classified against all the columns their source reads.
+    used_columns: Arc<Mutex<HashMap<(String, String), Vec<String>>>>,
+    /// The connection and table whose schema change stopped the pipeline, for the orchestrator to rebuild it.
+    schema_change: Arc<Mutex<Option<(String, String)>>>,
+}
+
+impl SchemaDriftMonitor {
+    pub fn new(config: SchemaDriftConfig) -> Self {
+        describe_counter!(
+            SCHEMA_DRIFT_COUNTER_NAME,
+            "Number of schema drifts detected by sources"
+        );
+        Self {
+            config,
+            alerts: Default::default(),
+            used_columns: Default::default(),
+            schema_change: Default::default(),
+        }
+    }
+
+    pub fn alerts(&self) -> Arc<Mutex<Vec<SchemaDriftAlert>>> {
+        self.alerts.clone()
+    }
+
+    /// Sets the columns the pipeline reads of tables, by connection and table, which are set when it's built.
+    pub fn set_used_columns(&self, used_columns: HashMap<(String, String), Vec<String>>) {
+        *self.used_columns.lock() = used_columns;
+    }
+
+    /// Records that a connector stopped because the schema of `table` changed.
+    pub fn schema_changed(&self, connection: &str, table: &str) {
+        *self.schema_change.lock() = Some((connection.to_string(), table.to_string()));
+    }
+
+    /// Takes the connection and table whose schema change stopped the pipeline, if any.
+    pub fn take_schema_change(&self) -> Option<(String, String)> {
+        self.schema_change.lock().take()
+    }
+
+    /// Classifies `drift` against `used_columns`. Empty `used_columns` means all columns are used.
+    pub fn classify(&self, drift: &SchemaDrift, used_columns: &[String]) -> SchemaDriftSeverity {
+        let is_used =
+            used_columns.is_empty() || used_columns.iter().any(|c| c == &drift.column_name);
+        if !is_used {
+            return SchemaDriftSeverity::Info;
+        }
+
+        match &drift.kind {
+            SchemaDriftKind::ColumnAdded
+            | SchemaDriftKind::TypeChanged {
+                compatible: true, ..
+            } => SchemaDriftSeverity::Info,
+            SchemaDriftKind::ColumnDropped => {
+                match self.config.on_column_dropped.unwrap_or_default() {
+                    OnColumnDropped::Ignore(()) => SchemaDriftSeverity::Info,
+                    OnColumnDropped::Warn(()) => SchemaDriftSeverity::Warning,
+                    OnColumnDropped::Fail(()) => SchemaDriftSeverity::Error,
+                }
+            }
+            SchemaDriftKind::TypeChanged {
+                compatible: false, ..
+            } => match self.config.on_type_changed.unwrap_or_default() {
+                OnTypeChanged::Ignore(()) => SchemaDriftSeverity::Info,
+                OnTypeChanged::Warn(()) => SchemaDriftSeverity::Warning,
+                OnTypeChanged::Fail(()) => SchemaDriftSeverity::Error,
+            },
+        }
+    }
+
+    /// Classifies, logs and records `drift` of a table whose source reads `read_columns`. Returns the recorded alert.
+    pub fn report(
+        &self,
+        connection: &str,
+        table: &str,
+        read_columns: &[String],
+        drift: SchemaDrift,
+    ) -> SchemaDriftAlert {
+        let severity = match self
+            .used_columns
+            .lock()
+            .get(&(connection.to_string(), table.to_string()))
+        {
+            // Unquoted identifiers of the SQL may differ in case from the column names.
+            Some(used_columns) => {
+                if used_columns
+                    .iter()
+                    .any(|column| column.eq_ignore_ascii_case(&drift.column_name))
+                {
+                    self.classify(&drift, std::slice::from_ref(&drift.column_name))
+                } else {
+                    SchemaDriftSeverity::Info
+                }
+            }
+            None => self.classify(&drift, read_columns),
+        };
+
+        let labels = [
+            ("connection", connection.to_string()),
+            ("table", table.to_string()),
+            ("kind", drift.kind.to_string()),
+            ("severity", severity.to_string()),
+        ];
+        increment_counter!(SCHEMA_DRIFT_COUNTER_NAME, &labels);
+
+        match severity {
+            SchemaDriftSeverity::Info => info!(
+                connection,
+                table,
+                column = %drift.column_name,
+                kind = %drift.kind,
+                "Schema drift detected"
+            ),
+            SchemaDriftSeverity::Warning => warn!(
+                connection,
+                table,
+                column = %drift.column_name,
+                kind = %drift.kind,
+                "Schema drift detected on a column used by the pipeline"
+            ),
+            SchemaDriftSeverity::Error => error!(
+                connection,
+                table,
+                column = %drift.column_name,
+                kind = %drift.kind,
+                "Schema drift detected on a column used by the pipeline"
+            ),
+        }
+
+        let alert = SchemaDriftAlert {
+            connection: connection.to_string(),
+            table: table.to_string(),
+            drift,
+            severity,
+        };
+        let mut alerts = self.alerts.lock();
+        alerts.retain(|old| {
+            (&old.connection, &old.table, &old.drift.column_name)
+                != (&alert.connection, &alert.table, &alert.drift.column_name)
+        });
+        alerts.push(alert.clone());
+        alert
+    }
+}
@@ -1,4 +1,5 @@
 use crate::pipeline::connector_source::ConnectorSourceFactory;
+use crate::pipeline::SchemaDriftMonitor;
 use crate::OrchestrationError;
 use dozer_core::appsource::{AppSourceManager, AppSourceMappings};
 use dozer_ingestion::connectors::TableInfo;
@@ -6,14 +7,17 @@ use dozer_sql::pipeline::builder::SchemaSQLContext;
 
 use dozer_types::indicatif::MultiProgress;
 use dozer_types::models::connection::Connection;
-use dozer_types::models::source::Source;
+use dozer_types::models::source::{RefreshConfig, Source};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 pub struct SourceBuilder<'a> {
     grouped_connections: HashMap<Connection, Vec<Source>>,
     progress: Option<&'a MultiProgress>,
+    schema_drift: SchemaDriftMonitor,
+    refresh_dir: Option<PathBuf>,
 }
 
 const SOURCE_PORTS_RANGE_START: u16 = 1000;
@@ -22,13 +26,23 @@ impl<'a> SourceBuilder<'a> {
     pub fn new(
         grouped_connections: HashMap<Connection, Vec<Source>>,
         progress: Option<&'a MultiProgress>,
+        schema_drift: SchemaDriftMonitor,
     ) -> Self {
         Self {
             grouped_connections,
             progress,
+            schema_drift,
+            refresh_dir: None,
         }
     }
 
+    /// Keeps the last reads of the tables refreshed on a schedule in `refresh_dir`. Sources with refreshed tables
+    /// can't be built without it.
+    pub fn refresh_dir(mut self, refresh_dir: Option<PathBuf>) -> Self {
+        self.refresh_dir = refresh_dir;
+        self
+    }
+
     pub fn get_ports(&self) -> HashMap<(&str, &str), u16> {
         let mut port: u16 = SOURCE_PORTS_RANGE_START;
 
@@ -56,6 +70,10 @@ impl<'a> SourceBuilder<'a> {
             for source in sources_group {
                 ports.insert(source.name.clone(), port);
 
+                let schedule = match &source.refresh_config {
+                    Some(RefreshConfig::Schedule(schedule)) => Some(schedule.cron.clone()),
+                    Some(RefreshConfig::RealTime(_)) | None => None,
+                };
                 table_and_ports.push((
                     TableInfo {
                         schema: source.schema.clone(),
@@ -63,6 +81,9 @@ impl<'a> SourceBuilder<'a> {
                         column_names: source.columns.clone(),
                     },
                     port,
+                    schedule,
+                    source.event_time.clone(),
+                    source.max_record_size.clone(),
                 ));
 
                 port += 1;
@@ -73,6 +94,8 @@ impl<'a> SourceBuilder<'a> {
                 connection.clone(),
                 runtime.clone(),
                 self.progress.cloned(),
+                self.schema_drift.clone(),
+                self.refresh_dir.clone(),
             ))?;
 
             asm.add(
@@ -1,12 +1,18 @@
 use std::sync::Arc;
 
+use crate::errors::OrchestrationError;
 use crate::pipeline::source_builder::SourceBuilder;
-use crate::pipeline::PipelineBuilder;
+use crate::pipeline::{PipelineBuilder, SchemaDriftMonitor};
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+use dozer_sql::pipeline::builder::{select_to_processor, SchemaSQLContext, SqlOptions};
 use dozer_types::ingestion_types::{GrpcConfig, GrpcConfigSchemas};
+use dozer_types::models::api_endpoint::ApiEndpoint;
 use dozer_types::models::config::Config;
 
 use dozer_types::indicatif::MultiProgress;
 use dozer_types::models::connection::{Connection, ConnectionConfig};
+use dozer_types::models::operator_config::OperatorConfig;
+use dozer_types::models::router_config::{RouteConfig, RouterConfig};
 use dozer_types::models::source::Source;
 use tokio::runtime::Runtime;
 
@@ -33,6 +39,8 @@ fn get_default_config() -> Config {
                 connection: grpc_conn.name.clone(),
                 schema: None,
                 refresh_config: None,
+                event_time: None,
+                max_record_size: None,
             },
             Source {
                 name: "grpc_conn_customers".to_string(),
@@ -41,6 +49,8 @@ fn get_default_config() -> Config {
                 connection: grpc_conn.name,
                 schema: None,
                 refresh_config: None,
+                event_time: None,
+                max_record_size: None,
             },
         ],
         ..Default::default()
@@ -61,12 +71,17 @@ fn load_multi_sources() {
         &config.connections,
         &config.sources,
         config.sql.as_deref(),
+        SqlOptions::default(),
+        &config.operators,
+        &config.routers,
+        Default::default(),
         config
             .endpoints
             .into_iter()
             .map(|endpoint| (endpoint, None))
             .collect(),
         MultiProgress::new(),
+        SchemaDriftMonitor::default(),
     );
 
     let runtime = Runtime::new().unwrap();
@@ -74,7 +89,8 @@ fn load_multi_sources() {
         .block_on(builder.get_grouped_tables(&used_sources))
         .unwrap();
 
-    let source_builder = SourceBuilder::new(grouped_connections, None);
+    let source_builder =
+        SourceBuilder::new(grouped_connections, None, SchemaDriftMonitor::default());
     let asm = source_builder
         .build_source_manager(Arc::new(runtime))
         .unwrap();
@@ -82,3 +98,159 @@ fn load_multi_sources() {
     asm.get_endpoint(&config.sources[0].name).unwrap();
     asm.get_endpoint(&config.sources[1].name).unwrap();
 }
+
+fn filter_registry() -> OperatorRegistry<SchemaSQLContext> {
+    let mut registry = OperatorRegistry::new();
+    registry
+        .register(
+            "filter".to_string(),
+            Box::new(|id: &str, options: &OperatorOptions| {
+                let sql = format!("SELECT id, name FROM input WHERE {}", options["where"]);
+                Ok(select_to_processor(
+                    id.to_string(),
+                    &sql,
+                    SqlOptions::default(),
+                )?)
+            }),
+        )
+        .unwrap();
+    registry
+}
+
+#[test]
+fn build_custom_operators() {
+    let mut config = get_default_config();
+    config.operators = vec![
+        OperatorConfig {
+            name: "named_users".to_string(),
+            operator: "filter".to_string(),
+            table_name: "grpc_conn_users".to_string(),
+            options: [("where".to_string(), "name IS NOT NULL".to_string())].into(),
+        },
+        // Operators can read the output of earlier operators.
+        OperatorConfig {
+            name: "first_named_users".to_string(),
+            operator: "filter".to_string(),
+            table_name: "named_users".to_string(),
+            options: [("where".to_string(), "id < 100".to_string())].into(),
+        },
+    ];
+    let endpoints = vec![(
+        ApiEndpoint {
+            name: "first_named_users".to_string(),
+            table_name: "first_named_users".to_string(),
+            path: "/first_named_users".to_string(),
+            ..Default::default()
+        },
+        None,
+    )];
+    let registry = Arc::new(filter_registry());
+    let runtime = Arc::new(Runtime::new().unwrap());
+
+    let builder = PipelineBuilder::new(
+        &config.connections,
+        &config.sources,
+        None,
+        SqlOptions::default(),
+        &config.operators,
+        &config.routers,
+        registry.clone(),
+        endpoints.clone(),
+        MultiProgress::new(),
+        SchemaDriftMonitor::default(),
+    );
+    let dag = builder.build(runtime.clone()).unwrap();
+    let processors = dag
+        .processors()
+        .map(|(handle, _)| handle.id.clone())
+        .collect::<Vec<_>>();
+    assert!(processors.contains(&"operator_named_users".to_string()));
+    assert!(processors.contains(&"operator_first_named_users".to_string()));
+
+    config.operators[0].operator = "unknown".to_string();
+    let builder = PipelineBuilder::new(
+        &config.connections,
+        &config.sources,
+        None,
+        SqlOptions::default(),
+        &config.operators,
+        &config.routers,
+        registry,
+        endpoints,
+        MultiProgress::new(),
+        SchemaDriftMonitor::default(),
+    );
+    assert!(matches!(
+        builder.build(runtime),
+        Err(OrchestrationError::ExecutionError(
+            dozer_core::errors::ExecutionError::UnknownOperator(_)
+        ))
+    ));
+}
+
+#[test]
+fn build_routers() {
+    let mut config = get_default_config();
+    config.routers = vec![RouterConfig {
+        name: "users".to_string(),
+        table_name: "grpc_conn_users".to_string(),
+        routes: vec![RouteConfig {
+            name: "named_users".to_string(),
+            condition: "name IS NOT NULL".to_string(),
+        }],
+        default_table: Some("unnamed_users".to_string()),
+    }];
+    let endpoints = ["named_users", "unnamed_users"]
+        .into_iter()
+        .map(|table_name| {
+            (
+                ApiEndpoint {
+                    name: table_name.to_string(),
+                    table_name: table_name.to_string(),
+                    path: format!("/{table_name}"),
+                    ..Default::default()
+                },
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    let runtime = Arc::new(Runtime::new().unwrap());
+
+    let builder = PipelineBuilder::new(
+        &config.connections,
+        &config.sources,
+        None,
+        SqlOptions::default(),
+        &config.operators,
+        &config.routers,
+        Default::default(),
+        endpoints.clone(),
+        MultiProgress::new(),
+        SchemaDriftMonitor::default(),
+    );
+    let dag = builder.build(runtime.clone()).unwrap();
+    let processors = dag
+        .processors()
+        .map(|(handle, _)| handle.id.clone())
+        .collect::<Vec<_>>();
+    assert!(processors.contains(&"router_users".to_string()));
+
+    // Route tables must be unique.
+    config.routers[0].default_table = Some("named_users".to_string());
+    let builder = PipelineBuilder::new(
+        &config.connections,
+        &config.sources,
+        None,
+        SqlOptions::default(),
+        &config.operators,
+        &config.routers,
+        Default::default(),
+        endpoints,
+        MultiProgress::new(),
+        SchemaDriftMonitor::default(),
+    );
+    assert!(matches!(
+        builder.build(runtime),
+        Err(OrchestrationError::DuplicateTable(_))
+    ));
+}
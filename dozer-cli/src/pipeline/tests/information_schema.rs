@@ -0,0 +1,123 @@
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use crate::pipeline::information_schema::{
+    is_information_schema_table, InformationSchema, TableDescription, TableType,
+};
+
+fn users_schema() -> Schema {
+    let mut schema = Schema::new();
+    schema
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+    schema
+}
+
+fn information_schema() -> InformationSchema {
+    InformationSchema {
+        tables: vec![
+            TableDescription {
+                name: "users".to_string(),
+                table_type: TableType::Source,
+                connection: Some("postgres".to_string()),
+                schema: users_schema(),
+            },
+            TableDescription {
+                name: "users_api".to_string(),
+                table_type: TableType::Endpoint,
+                connection: None,
+                schema: users_schema(),
+            },
+        ],
+    }
+}
+
+#[test]
+fn information_schema_tables() {
+    assert!(is_information_schema_table(
+        "information_schema.tables",
+        false
+    ));
+    assert!(is_information_schema_table(
+        "information_schema.columns",
+        false
+    ));
+    assert!(!is_information_schema_table("users", false));
+    assert!(is_information_schema_table(
+        "INFORMATION_SCHEMA.TABLES",
+        false
+    ));
+    assert!(!is_information_schema_table(
+        "INFORMATION_SCHEMA.TABLES",
+        true
+    ));
+
+    let records = information_schema().tables_records();
+    assert_eq!(
+        records,
+        vec![
+            Record::new(vec![
+                Field::String("users".to_string()),
+                Field::String("BASE TABLE".to_string()),
+                Field::String("postgres".to_string()),
+            ]),
+            Record::new(vec![
+                Field::String("users_api".to_string()),
+                Field::String("ENDPOINT".to_string()),
+                Field::Null,
+            ]),
+        ]
+    );
+    assert!(records
+        .iter()
+        .all(|record| record.values.len() == InformationSchema::tables_schema().fields.len()));
+}
+
+#[test]
+fn information_schema_columns() {
+    let records = information_schema().columns_records();
+    assert_eq!(records.len(), 4);
+    assert_eq!(
+        records[0],
+        Record::new(vec![
+            Field::String("users".to_string()),
+            Field::String("BASE TABLE".to_string()),
+            Field::String("id".to_string()),
+            Field::UInt(1),
+            Field::String(FieldType::Int.to_string()),
+            Field::String("NO".to_string()),
+            Field::Boolean(true),
+        ])
+    );
+    assert_eq!(
+        records[1],
+        Record::new(vec![
+            Field::String("users".to_string()),
+            Field::String("BASE TABLE".to_string()),
+            Field::String("name".to_string()),
+            Field::UInt(2),
+            Field::String(FieldType::String.to_string()),
+            Field::String("YES".to_string()),
+            Field::Boolean(false),
+        ])
+    );
+    assert_eq!(
+        InformationSchema::columns_schema().fields.len(),
+        records[0].values.len()
+    );
+}
@@ -1 +1,4 @@
 mod builder;
+mod information_schema;
+mod rollup;
+mod schema_drift;
@@ -0,0 +1,128 @@
+use dozer_types::models::api_endpoint::{
+    ApiEndpoint, ApiIndex, ConflictResolution, CreateSecondaryIndex, FullText,
+    OnUpdateResolutionTypes, Rollup, RollupAggregate, SecondaryIndex, SecondaryIndexConfig,
+    SortedInverted,
+};
+
+use crate::errors::RollupError;
+use crate::pipeline::rollup::{rollup_endpoints, rollup_sql};
+
+fn orders_by_day() -> Rollup {
+    Rollup {
+        name: "orders_by_day".to_string(),
+        path: None,
+        group_by: vec!["day".to_string()],
+        aggregates: vec![
+            RollupAggregate {
+                function: "count".to_string(),
+                column: None,
+                name: None,
+            },
+            RollupAggregate {
+                function: "sum".to_string(),
+                column: Some("amount".to_string()),
+                name: Some("total".to_string()),
+            },
+            RollupAggregate {
+                function: "MAX".to_string(),
+                column: Some("amount".to_string()),
+                name: None,
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_rollup_sql() {
+    assert_eq!(
+        rollup_sql("orders", &orders_by_day()).unwrap(),
+        r#"SELECT "day", COUNT(*) AS "count", SUM("amount") AS "total", MAX("amount") AS "max_amount" FROM "orders" GROUP BY "day""#
+    );
+
+    let mut rollup = orders_by_day();
+    rollup.group_by = vec!["order".to_string()];
+    rollup.aggregates.truncate(1);
+    rollup.aggregates[0].name = Some("a \"b\"".to_string());
+    assert_eq!(
+        rollup_sql("Order Items", &rollup).unwrap(),
+        r#"SELECT "order", COUNT(*) AS "a ""b""" FROM "Order Items" GROUP BY "order""#
+    );
+
+    let mut rollup = orders_by_day();
+    rollup.aggregates[1].column = None;
+    assert!(matches!(
+        rollup_sql("orders", &rollup),
+        Err(RollupError::MissingColumn(_, _))
+    ));
+
+    let mut rollup = orders_by_day();
+    rollup.aggregates[0].function = "median".to_string();
+    assert!(matches!(
+        rollup_sql("orders", &rollup),
+        Err(RollupError::UnknownFunction(_, _))
+    ));
+
+    let mut rollup = orders_by_day();
+    rollup.group_by.clear();
+    assert!(matches!(
+        rollup_sql("orders", &rollup),
+        Err(RollupError::NoGroupBy(_))
+    ));
+}
+
+#[test]
+fn test_rollup_endpoints() {
+    let endpoint = ApiEndpoint {
+        name: "orders".to_string(),
+        table_name: "orders".to_string(),
+        path: "/orders".to_string(),
+        index: Some(ApiIndex {
+            primary_key: vec!["id".to_string()],
+            secondary: Some(SecondaryIndexConfig {
+                skip_default: vec!["day".to_string(), "customer".to_string()],
+                create: vec![
+                    CreateSecondaryIndex {
+                        index: Some(SecondaryIndex::SortedInverted(SortedInverted {
+                            fields: vec!["day".to_string(), "total".to_string()],
+                        })),
+                    },
+                    CreateSecondaryIndex {
+                        index: Some(SecondaryIndex::FullText(FullText {
+                            field: "customer".to_string(),
+                        })),
+                    },
+                ],
+            }),
+        }),
+        conflict_resolution: Some(ConflictResolution {
+            on_update: Some(OnUpdateResolutionTypes::Upsert(())),
+            ..Default::default()
+        }),
+        rollups: vec![orders_by_day()],
+        ..Default::default()
+    };
+    let endpoints = rollup_endpoints(std::slice::from_ref(&endpoint));
+    assert_eq!(endpoints.len(), 1);
+    assert_eq!(endpoints[0].name, "orders_by_day");
+    assert_eq!(endpoints[0].table_name, "orders_by_day");
+    assert_eq!(endpoints[0].path, "/orders_by_day");
+    // The rollup is keyed by its group by columns, and keeps the indexes of its endpoint on its own columns.
+    assert_eq!(
+        endpoints[0].index,
+        Some(ApiIndex {
+            primary_key: vec!["day".to_string()],
+            secondary: Some(SecondaryIndexConfig {
+                skip_default: vec!["day".to_string()],
+                create: vec![CreateSecondaryIndex {
+                    index: Some(SecondaryIndex::SortedInverted(SortedInverted {
+                        fields: vec!["day".to_string(), "total".to_string()],
+                    })),
+                }],
+            }),
+        })
+    );
+    assert_eq!(
+        endpoints[0].conflict_resolution,
+        endpoint.conflict_resolution
+    );
+}
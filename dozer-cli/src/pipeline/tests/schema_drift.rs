@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use dozer_types::ingestion_types::{SchemaDrift, SchemaDriftKind, SchemaDriftSeverity};
+use dozer_types::models::app_config::{OnColumnDropped, OnTypeChanged, SchemaDriftConfig};
+
+use crate::pipeline::SchemaDriftMonitor;
+
+fn drift(column_name: &str, kind: SchemaDriftKind) -> SchemaDrift {
+    SchemaDrift {
+        column_name: column_name.to_string(),
+        kind,
+    }
+}
+
+fn type_changed(compatible: bool) -> SchemaDriftKind {
+    SchemaDriftKind::TypeChanged {
+        old_type: "int8".to_string(),
+        new_type: "int4".to_string(),
+        compatible,
+    }
+}
+
+#[test]
+fn classify_unused_column_as_info() {
+    let monitor = SchemaDriftMonitor::default();
+    let used_columns = vec!["id".to_string()];
+
+    assert_eq!(
+        monitor.classify(
+            &drift("name", SchemaDriftKind::ColumnDropped),
+            &used_columns
+        ),
+        SchemaDriftSeverity::Info
+    );
+    assert_eq!(
+        monitor.classify(&drift("name", type_changed(false)), &used_columns),
+        SchemaDriftSeverity::Info
+    );
+}
+
+#[test]
+fn classify_used_column_with_default_config() {
+    let monitor = SchemaDriftMonitor::default();
+    let used_columns = vec!["id".to_string()];
+
+    assert_eq!(
+        monitor.classify(&drift("id", SchemaDriftKind::ColumnDropped), &used_columns),
+        SchemaDriftSeverity::Error
+    );
+    assert_eq!(
+        monitor.classify(&drift("id", type_changed(false)), &used_columns),
+        SchemaDriftSeverity::Error
+    );
+    assert_eq!(
+        monitor.classify(&drift("id", type_changed(true)), &used_columns),
+        SchemaDriftSeverity::Info
+    );
+    // Empty used columns means all columns are used.
+    assert_eq!(
+        monitor.classify(&drift("id", SchemaDriftKind::ColumnDropped), &[]),
+        SchemaDriftSeverity::Error
+    );
+}
+
+#[test]
+fn classify_used_column_with_config() {
+    let monitor = SchemaDriftMonitor::new(SchemaDriftConfig {
+        on_column_dropped: Some(OnColumnDropped::Warn(())),
+        on_type_changed: Some(OnTypeChanged::Ignore(())),
+    });
+
+    assert_eq!(
+        monitor.classify(&drift("id", SchemaDriftKind::ColumnDropped), &[]),
+        SchemaDriftSeverity::Warning
+    );
+    assert_eq!(
+        monitor.classify(&drift("id", type_changed(false)), &[]),
+        SchemaDriftSeverity::Info
+    );
+}
+
+#[test]
+fn report_records_alert() {
+    let monitor = SchemaDriftMonitor::default();
+    let alert = monitor.report(
+        "postgres",
+        "users",
+        &[],
+        drift("id", SchemaDriftKind::ColumnDropped),
+    );
+
+    assert_eq!(alert.severity, SchemaDriftSeverity::Error);
+    assert_eq!(monitor.alerts().lock().as_slice(), &[alert]);
+}
+
+#[test]
+fn report_keeps_latest_alert_of_column() {
+    let monitor = SchemaDriftMonitor::default();
+    let read_columns = vec!["id".to_string(), "name".to_string()];
+    monitor.report(
+        "postgres",
+        "users",
+        &read_columns,
+        drift("name", type_changed(true)),
+    );
+    monitor.report(
+        "postgres",
+        "users",
+        &read_columns,
+        drift("id", type_changed(true)),
+    );
+    let alert = monitor.report(
+        "postgres",
+        "users",
+        &read_columns,
+        drift("name", SchemaDriftKind::ColumnDropped),
+    );
+
+    let alerts = monitor.alerts();
+    let alerts = alerts.lock();
+    assert_eq!(alerts.len(), 2);
+    assert_eq!(alerts[1], alert);
+}
+
+#[test]
+fn report_classifies_against_used_columns() {
+    let monitor = SchemaDriftMonitor::default();
+    monitor.set_used_columns(HashMap::from([(
+        ("postgres".to_string(), "users".to_string()),
+        vec!["ID".to_string()],
+    )]));
+    let read_columns = vec!["id".to_string(), "name".to_string()];
+
+    let alert = monitor.report(
+        "postgres",
+        "users",
+        &read_columns,
+        drift("name", SchemaDriftKind::ColumnDropped),
+    );
+    assert_eq!(alert.severity, SchemaDriftSeverity::Info);
+    let alert = monitor.report(
+        "postgres",
+        "users",
+        &read_columns,
+        drift("id", SchemaDriftKind::ColumnDropped),
+    );
+    assert_eq!(alert.severity, SchemaDriftSeverity::Error);
+    // Tables without used columns are classified against the columns their source reads.
+    let alert = monitor.report(
+        "postgres",
+        "orders",
+        &read_columns,
+        drift("name", SchemaDriftKind::ColumnDropped),
+    );
+    assert_eq!(alert.severity, SchemaDriftSeverity::Error);
+}
+
+#[test]
+fn schema_change_is_taken_once() {
+    let monitor = SchemaDriftMonitor::default();
+    assert_eq!(monitor.take_schema_change(), None);
+
+    monitor.clone().schema_changed("postgres", "public.users");
+    assert_eq!(
+        monitor.take_schema_change(),
+        Some(("postgres".to_string(), "public.users".to_string()))
+    );
+    assert_eq!(monitor.take_schema_change(), None);
+}
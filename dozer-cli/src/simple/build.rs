@@ -10,11 +10,14 @@ use dozer_types::{
     log::info,
     models::{
         api_endpoint::{
-            ApiEndpoint, FullText, SecondaryIndex, SecondaryIndexConfig, SortedInverted,
+            ApiEndpoint, ExpressionIndex, FullText, SecondaryIndex, SecondaryIndexConfig,
+            SortedInverted,
         },
         app_config::LogStorage,
     },
-    types::{FieldDefinition, FieldType, IndexDefinition, Schema, SchemaWithIndex},
+    types::{
+        FieldDefinition, FieldType, IndexDefinition, IndexExpression, Schema, SchemaWithIndex,
+    },
 };
 
 use crate::errors::BuildError;
@@ -129,6 +132,10 @@ pub fn modify_schema(
 
     schema.primary_index = index;
 
+    for deprecated_field in &api_endpoint.deprecated_fields {
+        field_index_from_field_name(&schema.fields, &deprecated_field.field)?;
+    }
+
     let secondary_index_config = get_secondary_index_config(api_endpoint);
     let secondary_indexes = generate_secondary_indexes(&schema.fields, &secondary_index_config)?;
 
@@ -191,7 +198,9 @@ fn generate_secondary_indexes(
             | FieldType::Timestamp
             | FieldType::Date
             | FieldType::Point
-            | FieldType::Duration => result.push(IndexDefinition::SortedInverted(vec![index])),
+            | FieldType::Duration
+            | FieldType::Money
+            | FieldType::Enum => result.push(IndexDefinition::SortedInverted(vec![index])),
 
             // Create sorted inverted and full text indexes for string fields.
             FieldType::String => {
@@ -219,6 +228,11 @@ fn generate_secondary_indexes(
                     let field = field_index_from_field_name(field_definitions, field)?;
                     result.push(IndexDefinition::FullText(field));
                 }
+                SecondaryIndex::Expression(ExpressionIndex { expression }) => {
+                    let expression = IndexExpression::parse(expression, field_definitions)
+                        .ok_or_else(|| BuildError::InvalidIndexExpression(expression.clone()))?;
+                    result.push(IndexDefinition::Expression(expression));
+                }
             }
         }
     }
@@ -0,0 +1,257 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dozer_api::cache_labels;
+use dozer_cache::cache::expression::QueryExpression;
+use dozer_cache::cache::{for_each_record, CacheRecord, LmdbRoCacheManager, RoCache};
+use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_types::arrow::compute::concat_batches;
+use dozer_types::arrow::csv;
+use dozer_types::arrow::datatypes::SchemaRef;
+use dozer_types::arrow::record_batch::RecordBatch;
+use dozer_types::arrow_types::to_arrow::{map_record_to_arrow, map_to_arrow_schema};
+use dozer_types::indexmap::IndexMap;
+use dozer_types::json_types::defined_field_to_json_value;
+use dozer_types::log::info;
+use dozer_types::models::config::Config;
+use dozer_types::types::{Record, Schema, Timezone};
+use parquet::arrow::ArrowWriter;
+
+use crate::cli::types::{Dump, DumpFormat};
+use crate::errors::{DumpError, OrchestrationError};
+use crate::utils::{get_cache_manager_options, get_timezone};
+
+/// Number of records in a record batch written to Parquet and CSV files.
+const BATCH_SIZE: usize = 1024;
+
+pub fn dump(config: &Config, dump: Dump) -> Result<(), OrchestrationError> {
+    let Dump {
+        endpoint,
+        output,
+        format,
+        query,
+        columns,
+    } = dump;
+
+    if !config.endpoints.iter().any(|e| e.name == endpoint) {
+        return Err(DumpError::EndpointNotFound(endpoint).into());
+    }
+
+    let home_dir = HomeDir::new(config.home_dir.as_ref(), config.cache_dir.clone());
+    let build_path = home_dir
+        .find_latest_build_path(&endpoint)
+        .map_err(|(path, error)| OrchestrationError::FileSystem(path.into(), error))?
+        .ok_or_else(|| OrchestrationError::NoBuildFound(endpoint.clone()))?;
+
+    let cache_manager = LmdbRoCacheManager::new(get_cache_manager_options(config))
+        .map_err(OrchestrationError::CacheInitFailed)?;
+    let cache = cache_manager
+        .open_lmdb_cache(cache_labels(
+            endpoint.clone(),
+            build_path.id.name().to_string(),
+        ))
+        .map_err(OrchestrationError::CacheInitFailed)?
+        .ok_or_else(|| DumpError::CacheNotFound(endpoint.clone()))?;
+
+    let query = match query {
+        Some(query) => serde_json::from_str(&query).map_err(DumpError::ParseQuery)?,
+        None => QueryExpression::with_no_limit(),
+    };
+
+    let schema = &cache.get_schema().0;
+    let projection = get_projection(schema, &columns)?;
+    let output_schema = Schema {
+        fields: projection
+            .iter()
+            .map(|index| schema.fields[*index].clone())
+            .collect(),
+        primary_index: vec![],
+    };
+
+    let output =
+        output.unwrap_or_else(|| PathBuf::from(format!("{endpoint}.{}", extension(format))));
+    let file = File::create(&output).map_err(|e| DumpError::FileSystem(output.clone(), e))?;
+    let timezone = get_timezone(config)?;
+    let mut writer = DumpWriter::new(format, file, &output_schema, &output, timezone)?;
+
+    let mut count = 0;
+    for_each_record(&cache, &query, |record: CacheRecord| {
+        let values = projection
+            .iter()
+            .map(|index| record.record.values[*index].clone())
+            .collect();
+        count += 1;
+        writer.write(Record::new(values))
+    })?;
+    writer.finish()?;
+
+    info!("Exported {count} records of endpoint {endpoint} to {output:?}");
+    Ok(())
+}
+
+fn get_projection(schema: &Schema, columns: &[String]) -> Result<Vec<usize>, DumpError> {
+    if columns.is_empty() {
+        return Ok((0..schema.fields.len()).collect());
+    }
+    columns
+        .iter()
+        .map(|column| {
+            schema
+                .fields
+                .iter()
+                .position(|field| &field.name == column)
+                .ok_or_else(|| DumpError::ColumnNotFound(column.clone()))
+        })
+        .collect()
+}
+
+fn extension(format: DumpFormat) -> &'static str {
+    match format {
+        DumpFormat::Parquet => "parquet",
+        DumpFormat::Csv => "csv",
+        DumpFormat::Jsonl => "jsonl",
+    }
+}
+
+enum DumpWriter<'a> {
+    Parquet {
+        writer: ArrowWriter<File>,
+        batcher: Batcher<'a>,
+    },
+    Csv {
+        writer: csv::Writer<File>,
+        batcher: Batcher<'a>,
+    },
+    Jsonl {
+        writer: BufWriter<File>,
+        schema: &'a Schema,
+        path: &'a Path,
+        timezone: Timezone,
+    },
+}
+
+impl<'a> DumpWriter<'a> {
+    fn new(
+        format: DumpFormat,
+        file: File,
+        schema: &'a Schema,
+        path: &'a Path,
+        timezone: Timezone,
+    ) -> Result<Self, DumpError> {
+        Ok(match format {
+            DumpFormat::Parquet => {
+                let batcher = Batcher::new(schema)?;
+                let writer = ArrowWriter::try_new(file, batcher.arrow_schema.clone(), None)?;
+                Self::Parquet { writer, batcher }
+            }
+            DumpFormat::Csv => Self::Csv {
+                writer: csv::Writer::new(file),
+                batcher: Batcher::new(schema)?,
+            },
+            DumpFormat::Jsonl => Self::Jsonl {
+                writer: BufWriter::new(file),
+                schema,
+                path,
+                timezone,
+            },
+        })
+    }
+
+    fn write(&mut self, record: Record) -> Result<(), DumpError> {
+        match self {
+            Self::Parquet { writer, batcher } => {
+                if let Some(batch) = batcher.push(record)? {
+                    writer.write(&batch)?;
+                }
+            }
+            Self::Csv { writer, batcher } => {
+                if let Some(batch) = batcher.push(record)? {
+                    writer.write(&batch)?;
+                }
+            }
+            Self::Jsonl {
+                writer,
+                schema,
+                path,
+                timezone,
+            } => {
+                let mut map = IndexMap::new();
+                for (field, value) in schema.fields.iter().zip(record.values) {
+                    map.insert(
+                        field.name.as_str(),
+                        defined_field_to_json_value(value, field, timezone)?,
+                    );
+                }
+                serde_json::to_writer(&mut *writer, &map).map_err(DumpError::WriteJson)?;
+                writer
+                    .write_all(b"\n")
+                    .map_err(|e| DumpError::FileSystem(path.to_path_buf(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), DumpError> {
+        match self {
+            Self::Parquet {
+                mut writer,
+                batcher,
+            } => {
+                if let Some(batch) = batcher.finish()? {
+                    writer.write(&batch)?;
+                }
+                writer.close()?;
+            }
+            Self::Csv {
+                mut writer,
+                batcher,
+            } => {
+                if let Some(batch) = batcher.finish()? {
+                    writer.write(&batch)?;
+                }
+            }
+            Self::Jsonl {
+                mut writer, path, ..
+            } => writer
+                .flush()
+                .map_err(|e| DumpError::FileSystem(path.to_path_buf(), e))?,
+        }
+        Ok(())
+    }
+}
+
+/// Collects records into record batches of `BATCH_SIZE`.
+struct Batcher<'a> {
+    schema: &'a Schema,
+    arrow_schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+}
+
+impl<'a> Batcher<'a> {
+    fn new(schema: &'a Schema) -> Result<Self, DumpError> {
+        Ok(Self {
+            schema,
+            arrow_schema: Arc::new(map_to_arrow_schema(schema)?),
+            batches: Vec::with_capacity(BATCH_SIZE),
+        })
+    }
+
+    fn push(&mut self, record: Record) -> Result<Option<RecordBatch>, DumpError> {
+        self.batches.push(map_record_to_arrow(record, self.schema)?);
+        if self.batches.len() < BATCH_SIZE {
+            return Ok(None);
+        }
+        let batch = concat_batches(&self.arrow_schema, &self.batches)?;
+        self.batches.clear();
+        Ok(Some(batch))
+    }
+
+    fn finish(self) -> Result<Option<RecordBatch>, DumpError> {
+        if self.batches.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(concat_batches(&self.arrow_schema, &self.batches)?))
+    }
+}
@@ -1,21 +1,28 @@
 use dozer_api::grpc::internal::internal_pipeline_server::BuildAndLog;
 use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_cache::dozer_log::provenance::ProvenanceStore;
 use dozer_cache::dozer_log::replication::{Log, LogOptions};
 use dozer_types::models::api_endpoint::ApiEndpoint;
 use tokio::runtime::Runtime;
 use tokio::sync::Mutex;
 
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use dozer_types::models::source::Source;
 
-use crate::pipeline::PipelineBuilder;
+use crate::pipeline::information_schema::InformationSchema;
+use crate::pipeline::{PipelineBuilder, SchemaDriftMonitor};
 use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::plugin::OperatorRegistry;
+use dozer_sql::pipeline::builder::{SchemaSQLContext, SqlOptions};
 
 use dozer_types::indicatif::MultiProgress;
 
 use dozer_types::models::connection::Connection;
+use dozer_types::models::operator_config::OperatorConfig;
+use dozer_types::models::router_config::RouterConfig;
 use OrchestrationError::ExecutionError;
 
 use crate::errors::OrchestrationError;
@@ -24,20 +31,34 @@ pub struct Executor<'a> {
     connections: &'a [Connection],
     sources: &'a [Source],
     sql: Option<&'a str>,
+    operators: &'a [OperatorConfig],
+    routers: &'a [RouterConfig],
+    operator_registry: Arc<OperatorRegistry<SchemaSQLContext>>,
     /// `ApiEndpoint` and its log.
     endpoint_and_logs: Vec<(ApiEndpoint, BuildAndLog)>,
     multi_pb: MultiProgress,
+    schema_drift: SchemaDriftMonitor,
+    information_schema: Option<Arc<InformationSchema>>,
+    sql_options: SqlOptions,
+    refresh_dir: PathBuf,
 }
 
 impl<'a> Executor<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         home_dir: &'a HomeDir,
         connections: &'a [Connection],
         sources: &'a [Source],
         sql: Option<&'a str>,
+        operators: &'a [OperatorConfig],
+        routers: &'a [RouterConfig],
+        operator_registry: Arc<OperatorRegistry<SchemaSQLContext>>,
         api_endpoints: &'a [ApiEndpoint],
         log_options: LogOptions,
         multi_pb: MultiProgress,
+        schema_drift: SchemaDriftMonitor,
+        information_schema: Option<Arc<InformationSchema>>,
+        sql_options: SqlOptions,
     ) -> Result<Executor<'a>, OrchestrationError> {
         let mut endpoint_and_logs = vec![];
         for endpoint in api_endpoints {
@@ -47,11 +68,21 @@ impl<'a> Executor<'a> {
                 .ok_or(OrchestrationError::NoBuildFound(endpoint.name.clone()))?;
             let log = Log::new(log_options.clone(), &build_path, false).await?;
             let log = Arc::new(Mutex::new(log));
+            let provenance = if endpoint.provenance.unwrap_or(false) {
+                let path = build_path.provenance_path.as_std_path();
+                Some(
+                    ProvenanceStore::open(path)
+                        .map_err(|e| OrchestrationError::OpenProvenance(path.to_path_buf(), e))?,
+                )
+            } else {
+                None
+            };
             endpoint_and_logs.push((
                 endpoint.clone(),
                 BuildAndLog {
                     build: build_path,
                     log,
+                    provenance,
                 },
             ));
         }
@@ -60,8 +91,15 @@ impl<'a> Executor<'a> {
             connections,
             sources,
             sql,
+            operators,
+            routers,
+            operator_registry,
             endpoint_and_logs,
             multi_pb,
+            schema_drift,
+            information_schema,
+            sql_options,
+            refresh_dir: home_dir.refresh_dir().as_std_path().to_path_buf(),
         })
     }
 
@@ -78,12 +116,19 @@ impl<'a> Executor<'a> {
             self.connections,
             self.sources,
             self.sql,
+            self.sql_options,
+            self.operators,
+            self.routers,
+            self.operator_registry.clone(),
             self.endpoint_and_logs
                 .iter()
-                .map(|(endpoint, log)| (endpoint.clone(), Some(log.log.clone())))
+                .map(|(endpoint, log)| (endpoint.clone(), Some(log.clone())))
                 .collect(),
             self.multi_pb.clone(),
-        );
+            self.schema_drift.clone(),
+        )
+        .information_schema(self.information_schema.clone())
+        .refresh_dir(Some(self.refresh_dir.clone()));
 
         let dag = builder.build(runtime)?;
         let exec = DagExecutor::new(dag, executor_options)?;
@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use dozer_api::{cache_labels, cache_write_options, num_partitions, open_or_create_cache};
+use dozer_cache::cache::expression::QueryExpression;
+use dozer_cache::cache::{LmdbRwCacheManager, RwCache};
+use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_cache::dozer_log::schemas::load_schema;
+use dozer_types::arrow::csv;
+use dozer_types::arrow::datatypes::{Schema as ArrowSchema, SchemaRef};
+use dozer_types::arrow::error::ArrowError;
+use dozer_types::arrow::record_batch::RecordBatch;
+use dozer_types::arrow_types::from_arrow::map_record_batch_to_dozer_records;
+use dozer_types::arrow_types::to_arrow::map_to_arrow_schema;
+use dozer_types::log::info;
+use dozer_types::models::config::Config;
+use dozer_types::types::Schema;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::cli::types::Load;
+use crate::errors::{LoadError, OrchestrationError};
+use crate::utils::get_cache_manager_options;
+
+type RecordBatches = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>;
+
+/// Loads files into an empty endpoint cache, and marks it as built up to `log_position` of the endpoint log, with the
+/// snapshots of all its connections done, so the app continues building it from there.
+pub fn load(config: &Config, load: Load) -> Result<(), OrchestrationError> {
+    let Load {
+        endpoint,
+        files,
+        log_position,
+    } = load;
+
+    let api_endpoint = config
+        .endpoints
+        .iter()
+        .find(|e| e.name == endpoint)
+        .ok_or_else(|| LoadError::EndpointNotFound(endpoint.clone()))?;
+
+    let home_dir = HomeDir::new(config.home_dir.as_ref(), config.cache_dir.clone());
+    let build_path = home_dir
+        .find_latest_build_path(&endpoint)
+        .map_err(|(path, error)| OrchestrationError::FileSystem(path.into(), error))?
+        .ok_or_else(|| OrchestrationError::NoBuildFound(endpoint.clone()))?;
+    let build_schema = load_schema(&build_path.schema_path).map_err(LoadError::LoadSchema)?;
+
+    let cache_manager = LmdbRwCacheManager::new(get_cache_manager_options(config))
+        .map_err(OrchestrationError::CacheInitFailed)?;
+    let mut cache = open_or_create_cache(
+        &cache_manager,
+        cache_labels(endpoint.clone(), build_path.id.name().to_string()),
+        (build_schema.schema, build_schema.secondary_indexes),
+        &build_schema.connections,
+        cache_write_options(api_endpoint),
+        num_partitions(api_endpoint),
+    )
+    .map_err(OrchestrationError::CacheInitFailed)?;
+    check_cache_is_empty(&*cache, &endpoint)?;
+
+    let schema = cache.get_schema().0.clone();
+    let arrow_schema = Arc::new(map_to_arrow_schema(&schema).map_err(LoadError::Arrow)?);
+    for path in &files {
+        let count = load_file(&mut *cache, &schema, &arrow_schema, path)?;
+        info!("Loaded {count} records from {path:?} into endpoint {endpoint}");
+    }
+    mark_loaded(&mut *cache, log_position, &build_schema.connections)?;
+
+    // Secondary indexes are built in background threads, which must finish before we exit.
+    cache_manager.wait_until_indexing_catchup();
+    Ok(())
+}
+
+/// A cache that has records, or was built from the endpoint log, already has some of the operations of the log.
+fn check_cache_is_empty(cache: &dyn RwCache, endpoint: &str) -> Result<(), LoadError> {
+    if cache.get_metadata()?.is_some() || cache.count(&QueryExpression::with_no_limit())? > 0 {
+        return Err(LoadError::CacheNotEmpty(endpoint.to_string()));
+    }
+    Ok(())
+}
+
+/// Without metadata, the cache would be built from the start of the endpoint log, applying the operations already in
+/// the loaded files again. Without the connections' snapshots done, the endpoint would report it's still snapshotting.
+fn mark_loaded(
+    cache: &mut dyn RwCache,
+    log_position: u64,
+    connections: &HashSet<String>,
+) -> Result<(), LoadError> {
+    cache.set_metadata(log_position)?;
+    for connection in connections {
+        cache.set_connection_snapshotting_done(connection)?;
+    }
+    cache.commit()?;
+    Ok(())
+}
+
+/// Inserts all records in a file into the cache and commits them. Returns the number of records loaded.
+fn load_file(
+    cache: &mut dyn RwCache,
+    schema: &Schema,
+    arrow_schema: &SchemaRef,
+    path: &Path,
+) -> Result<usize, LoadError> {
+    let mut file = File::open(path).map_err(|e| LoadError::FileSystem(path.to_path_buf(), e))?;
+
+    let (projection, batches): (Vec<usize>, RecordBatches) =
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("parquet") => {
+                let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+                let projection = get_projection(path, arrow_schema, builder.schema(), true)?;
+                (projection, Box::new(builder.build()?))
+            }
+            Some("csv") => {
+                // CSV files are untyped, so we only read the header and parse the columns as the endpoint schema says.
+                let (header, _) = csv::reader::Format::default()
+                    .with_header(true)
+                    .infer_schema(&mut file, Some(0))?;
+                file.seek(SeekFrom::Start(0))
+                    .map_err(|e| LoadError::FileSystem(path.to_path_buf(), e))?;
+                let file_schema = ArrowSchema::new(
+                    header
+                        .fields()
+                        .iter()
+                        .map(|column| {
+                            arrow_schema
+                                .field_with_name(column.name())
+                                .cloned()
+                                .map_err(|_| {
+                                    LoadError::UnexpectedColumn(
+                                        path.to_path_buf(),
+                                        column.name().clone(),
+                                    )
+                                })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+                let projection = get_projection(path, arrow_schema, &file_schema, false)?;
+                let reader = csv::ReaderBuilder::new(Arc::new(file_schema))
+                    .has_header(true)
+                    .build(file)?;
+                (projection, Box::new(reader))
+            }
+            _ => return Err(LoadError::UnsupportedFormat(path.to_path_buf())),
+        };
+
+    let mut count = 0;
+    for batch in batches {
+        let columns = batch?.project(&projection)?.columns().to_vec();
+        // Rebuilding the batch with the endpoint schema rejects nulls in non-nullable columns.
+        let batch = RecordBatch::try_new(arrow_schema.clone(), columns)?;
+        for record in map_record_batch_to_dozer_records(batch, schema)? {
+            cache.insert(&record)?;
+            count += 1;
+        }
+    }
+    cache.commit()?;
+    Ok(count)
+}
+
+/// Returns the index of every endpoint column in the file schema.
+///
+/// Extra columns in the file are rejected, and so are column type mismatches if `check_types` is true.
+fn get_projection(
+    path: &Path,
+    arrow_schema: &ArrowSchema,
+    file_schema: &ArrowSchema,
+    check_types: bool,
+) -> Result<Vec<usize>, LoadError> {
+    if let Some(column) = file_schema
+        .fields()
+        .iter()
+        .find(|column| arrow_schema.field_with_name(column.name()).is_err())
+    {
+        return Err(LoadError::UnexpectedColumn(
+            path.to_path_buf(),
+            column.name().clone(),
+        ));
+    }
+
+    arrow_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let (index, column) = file_schema.column_with_name(field.name()).ok_or_else(|| {
+                LoadError::MissingColumn(path.to_path_buf(), field.name().clone())
+            })?;
+            if check_types && column.data_type() != field.data_type() {
+                return Err(LoadError::ColumnTypeMismatch {
+                    path: path.to_path_buf(),
+                    column: field.name().clone(),
+                    expected: field.data_type().clone(),
+                    actual: column.data_type().clone(),
+                });
+            }
+            Ok(index)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::{RoCache, RwCacheManager};
+    use dozer_types::arrow::datatypes::{DataType, Field as ArrowField};
+    use dozer_types::labels::Labels;
+    use dozer_types::types::{Field, FieldDefinition, FieldType, SourceDefinition};
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema {
+            fields: vec![
+                FieldDefinition::new(
+                    "id".to_string(),
+                    FieldType::UInt,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                FieldDefinition::new(
+                    "name".to_string(),
+                    FieldType::String,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+            ],
+            primary_index: vec![0],
+        }
+    }
+
+    #[test]
+    fn test_get_projection() {
+        let path = Path::new("films.parquet");
+        let arrow_schema = map_to_arrow_schema(&schema()).unwrap();
+        let id = ArrowField::new("id", DataType::UInt64, false);
+        let name = ArrowField::new("name", DataType::Utf8, true);
+
+        let file_schema = ArrowSchema::new(vec![name.clone(), id.clone()]);
+        assert_eq!(
+            get_projection(path, &arrow_schema, &file_schema, true).unwrap(),
+            vec![1, 0]
+        );
+
+        let file_schema = ArrowSchema::new(vec![id.clone()]);
+        assert!(matches!(
+            get_projection(path, &arrow_schema, &file_schema, true),
+            Err(LoadError::MissingColumn(_, column)) if column == "name"
+        ));
+
+        let file_schema = ArrowSchema::new(vec![
+            id.clone(),
+            name,
+            ArrowField::new("rating", DataType::Utf8, true),
+        ]);
+        assert!(matches!(
+            get_projection(path, &arrow_schema, &file_schema, true),
+            Err(LoadError::UnexpectedColumn(_, column)) if column == "rating"
+        ));
+
+        // Types are only checked for typed formats.
+        let file_schema =
+            ArrowSchema::new(vec![id, ArrowField::new("name", DataType::LargeUtf8, true)]);
+        assert!(matches!(
+            get_projection(path, &arrow_schema, &file_schema, true),
+            Err(LoadError::ColumnTypeMismatch { column, .. }) if column == "name"
+        ));
+        assert_eq!(
+            get_projection(path, &arrow_schema, &file_schema, false).unwrap(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_load_csv_file() {
+        let dir = TempDir::new("load_csv_file").unwrap();
+        let path = dir.path().join("films.csv");
+        std::fs::write(&path, "name,id\nAlien,1\nHeat,2\n").unwrap();
+
+        let schema = schema();
+        let cache_manager = LmdbRwCacheManager::new(Default::default()).unwrap();
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        let mut cache = cache_manager
+            .create_cache(
+                labels,
+                schema.clone(),
+                vec![],
+                &Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let arrow_schema = Arc::new(map_to_arrow_schema(&schema).unwrap());
+
+        // Columns are read by name, in any order.
+        assert_eq!(
+            load_file(&mut *cache, &schema, &arrow_schema, &path).unwrap(),
+            2
+        );
+        assert_eq!(
+            cache.get(&Field::UInt(1).encode()).unwrap().record.values,
+            vec![Field::UInt(1), Field::String("Alien".to_string())]
+        );
+        assert_eq!(
+            cache.get(&Field::UInt(2).encode()).unwrap().record.values,
+            vec![Field::UInt(2), Field::String("Heat".to_string())]
+        );
+
+        let path = dir.path().join("films.json");
+        std::fs::write(&path, "[]").unwrap();
+        assert!(matches!(
+            load_file(&mut *cache, &schema, &arrow_schema, &path),
+            Err(LoadError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_mark_loaded() {
+        let cache_manager = LmdbRwCacheManager::new(Default::default()).unwrap();
+        let mut labels = Labels::new();
+        labels.push("endpoint", "films");
+        let connections = HashSet::from(["postgres".to_string()]);
+        let mut cache = cache_manager
+            .create_cache(labels, schema(), vec![], &connections, Default::default())
+            .unwrap();
+        check_cache_is_empty(&*cache, "films").unwrap();
+
+        mark_loaded(&mut *cache, 42, &connections).unwrap();
+        assert_eq!(cache.get_metadata().unwrap(), Some(42));
+        assert!(cache.is_snapshotting_done().unwrap());
+
+        // A cache built up to a log position can't be loaded into again.
+        assert!(matches!(
+            check_cache_is_empty(&*cache, "films"),
+            Err(LoadError::CacheNotEmpty(endpoint)) if endpoint == "films"
+        ));
+    }
+}
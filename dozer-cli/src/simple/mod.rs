@@ -2,10 +2,14 @@ mod executor;
 pub mod orchestrator;
 pub use orchestrator::SimpleOrchestrator;
 mod build;
+pub use build::modify_schema;
 #[cfg(feature = "cloud")]
 mod cloud;
 #[cfg(feature = "cloud")]
 mod cloud_orchestrator;
+mod dump;
 mod helper;
+mod load;
+pub mod schedule;
 #[cfg(feature = "cloud")]
 mod token_layer;
@@ -1,23 +1,31 @@
 use super::executor::{run_dag_executor, Executor};
-use crate::errors::OrchestrationError;
-use crate::pipeline::PipelineBuilder;
+use crate::cli::types::{Dump, Load};
+use crate::errors::{OrchestrationError, ScheduleError};
+use crate::pipeline::information_schema::{InformationSchema, TableType};
+use crate::pipeline::{builtin_operators, PipelineBuilder, SchemaDriftMonitor};
 use crate::shutdown::ShutdownReceiver;
-use crate::simple::build;
 use crate::simple::helper::validate_config;
+use crate::simple::schedule::{self, Scheduler};
+use crate::simple::{build, dump, load};
 use crate::utils::{
     get_api_security_config, get_app_grpc_config, get_cache_manager_options, get_executor_options,
-    get_grpc_config, get_log_options, get_rest_config,
+    get_grpc_config, get_log_options, get_rest_config, get_schema_drift_config, get_sql_options,
+    get_timezone,
 };
 
 use crate::{flatten_join_handle, join_handle_map_err};
-use dozer_api::auth::{Access, Authorizer};
+use dozer_api::alerts::AlertManager;
+use dozer_api::auth::{Access, Authorizer, JwtSecrets};
+use dozer_api::gateway::{self, Gateway};
 use dozer_api::grpc::internal::internal_pipeline_server::start_internal_pipeline_server;
+use dozer_api::metrics_history::MetricsHistory;
 use dozer_api::{grpc, rest, CacheEndpoint};
 use dozer_cache::cache::LmdbRwCacheManager;
 use dozer_cache::dozer_log::home_dir::HomeDir;
 use dozer_cache::dozer_log::schemas::BuildSchema;
 use dozer_core::app::AppPipeline;
 use dozer_core::dag_schemas::DagSchemas;
+use dozer_core::plugin::OperatorRegistry;
 use futures::future::join_all;
 
 use crate::console_helper::get_colored_text;
@@ -26,8 +34,9 @@ use crate::console_helper::PURPLE;
 use crate::console_helper::RED;
 use dozer_core::errors::ExecutionError;
 use dozer_ingestion::connectors::{get_connector, SourceSchema, TableInfo};
-use dozer_sql::pipeline::builder::statement_to_pipeline;
+use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use dozer_sql::pipeline::errors::PipelineError;
+use dozer_sql::pipeline::lineage::{extract_lineage, TableLineage};
 use dozer_types::crossbeam::channel::{self, Sender};
 use dozer_types::indicatif::{MultiProgress, ProgressDrawTarget};
 use dozer_types::log::info;
@@ -40,6 +49,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use tokio::runtime::Runtime;
@@ -50,6 +60,10 @@ pub struct SimpleOrchestrator {
     pub config: Config,
     pub runtime: Arc<Runtime>,
     pub multi_pb: MultiProgress,
+    /// Custom operators the config can use.
+    pub operator_registry: Arc<OperatorRegistry<SchemaSQLContext>>,
+    /// The tables the SQL can read from the `information_schema` tables. The SQL can't read them if not set.
+    pub information_schema: Option<Arc<InformationSchema>>,
 }
 
 impl SimpleOrchestrator {
@@ -63,9 +77,19 @@ impl SimpleOrchestrator {
             config,
             runtime,
             multi_pb: MultiProgress::with_draw_target(progress_draw_target),
+            operator_registry: Arc::new(builtin_operators()),
+            information_schema: None,
         }
     }
 
+    pub fn with_operator_registry(
+        mut self,
+        operator_registry: OperatorRegistry<SchemaSQLContext>,
+    ) -> Self {
+        self.operator_registry = Arc::new(operator_registry);
+        self
+    }
+
     pub fn run_api(&mut self, shutdown: ShutdownReceiver) -> Result<(), OrchestrationError> {
         describe_histogram!(
             dozer_api::API_LATENCY_HISTOGRAM_NAME,
@@ -96,15 +120,21 @@ impl SimpleOrchestrator {
                 LmdbRwCacheManager::new(get_cache_manager_options(&self.config))
                     .map_err(OrchestrationError::CacheInitFailed)?,
             );
+            let timezone = get_timezone(&self.config)?;
             let mut cache_endpoints = vec![];
             for endpoint in &self.config.endpoints {
                 let (cache_endpoint, handle) = CacheEndpoint::new(
                     app_server_addr.clone(),
-                    &*cache_manager,
+                    cache_manager.clone(),
                     endpoint.clone(),
                     Box::pin(shutdown.create_shutdown_future()),
                     operations_sender.clone(),
                     Some(self.multi_pb.clone()),
+                    self.config
+                        .app
+                        .as_ref()
+                        .and_then(|app| app.log_kafka.clone()),
+                    timezone,
                 )
                 .await?;
                 let cache_name = endpoint.name.clone();
@@ -118,10 +148,44 @@ impl SimpleOrchestrator {
                 cache_endpoints.push(Arc::new(cache_endpoint));
             }
 
+            // Snapshot the endpoint metrics periodically if the history is enabled.
+            let metrics_history = self
+                .config
+                .api
+                .as_ref()
+                .and_then(|api| api.metrics_history.as_ref())
+                .map(|options| {
+                    Arc::new(MetricsHistory::open(
+                        PathBuf::from(&self.config.cache_dir).join("metrics_history"),
+                        options,
+                    ))
+                });
+            if let Some(metrics_history) = &metrics_history {
+                tokio::spawn(metrics_history.clone().record_periodically(
+                    cache_endpoints.clone(),
+                    shutdown.create_shutdown_future(),
+                ));
+            }
+
+            // The REST and gRPC servers share the JWT secrets, so a rotation applies to both.
+            let security = get_api_security_config(&self.config)
+                .map(|api_security| Arc::new(JwtSecrets::from(api_security)));
+
+            // Evaluate the alert rules periodically if there are any.
+            let alerts = self.config.api.as_ref().and_then(|api| api.alerts.as_ref());
+            if let Some(alerts) = alerts {
+                let alert_manager =
+                    Arc::new(AlertManager::new(alerts).map_err(OrchestrationError::ApiInitFailed)?);
+                tokio::spawn(alert_manager.evaluate_periodically(
+                    cache_endpoints.clone(),
+                    shutdown.create_shutdown_future(),
+                ));
+            }
+
             // Initialize API Server
             let rest_config = get_rest_config(&self.config);
             let rest_handle = if rest_config.enabled {
-                let security = get_api_security_config(&self.config).cloned();
+                let security = security.clone();
                 let cache_endpoints_for_rest = cache_endpoints.clone();
                 let shutdown_for_rest = shutdown.create_shutdown_future();
                 let api_server = rest::ApiServer::new(rest_config, security);
@@ -136,8 +200,8 @@ impl SimpleOrchestrator {
             // Initialize gRPC Server
             let grpc_config = get_grpc_config(&self.config);
             let grpc_handle = if grpc_config.enabled {
-                let api_security = get_api_security_config(&self.config).cloned();
-                let grpc_server = grpc::ApiServer::new(grpc_config, api_security, flags);
+                let grpc_server =
+                    grpc::ApiServer::new(grpc_config, security, flags, metrics_history);
                 let shutdown = shutdown.create_shutdown_future();
                 tokio::spawn(async move {
                     grpc_server
@@ -162,10 +226,86 @@ impl SimpleOrchestrator {
         Ok(())
     }
 
+    pub fn run_gateway(&mut self, shutdown: ShutdownReceiver) -> Result<(), OrchestrationError> {
+        let gateway_options = self
+            .config
+            .api
+            .as_ref()
+            .and_then(|api| api.gateway.as_ref())
+            .ok_or(OrchestrationError::MissingGatewayConfig)?;
+        let gateway = Arc::new(Gateway::new(gateway_options)?);
+
+        self.runtime.block_on(async {
+            tokio::spawn(
+                gateway
+                    .clone()
+                    .refresh_periodically(shutdown.create_shutdown_future()),
+            );
+
+            let rest_config = get_rest_config(&self.config);
+            let rest_handle = if rest_config.enabled {
+                let server = gateway::rest::run(
+                    gateway.clone(),
+                    rest_config,
+                    shutdown.create_shutdown_future(),
+                )?;
+                tokio::spawn(server.map_err(OrchestrationError::ApiServeFailed))
+            } else {
+                tokio::spawn(async move { Ok::<(), OrchestrationError>(()) })
+            };
+
+            let grpc_config = get_grpc_config(&self.config);
+            let grpc_handle = if grpc_config.enabled {
+                let shutdown = shutdown.create_shutdown_future();
+                tokio::spawn(async move {
+                    gateway::grpc::run(gateway, grpc_config, shutdown)
+                        .await
+                        .map_err(OrchestrationError::ApiInitFailed)
+                })
+            } else {
+                tokio::spawn(async move { Ok::<(), OrchestrationError>(()) })
+            };
+
+            let mut futures = FuturesUnordered::new();
+            futures.push(flatten_join_handle(rest_handle));
+            futures.push(flatten_join_handle(grpc_handle));
+            while let Some(result) = futures.next().await {
+                result?;
+            }
+
+            Ok::<(), OrchestrationError>(())
+        })
+    }
+
+    /// Runs the pipeline. A connector that stops because the schema of a table changed gets the endpoints rebuilt with
+    /// the new schema and the pipeline restarted.
     pub fn run_apps(
         &mut self,
         shutdown: ShutdownReceiver,
+        mut api_notifier: Option<Sender<bool>>,
+    ) -> Result<(), OrchestrationError> {
+        let schema_drift = SchemaDriftMonitor::new(get_schema_drift_config(&self.config));
+        loop {
+            let result = self.run_apps_once(&shutdown, api_notifier.take(), schema_drift.clone());
+            let Err(e) = result else {
+                return Ok(());
+            };
+            let Some((connection, table)) = schema_drift.take_schema_change() else {
+                return Err(e);
+            };
+            info!("Schema of table {table} of connection {connection} changed, rebuilding the endpoints");
+            self.build(false)?;
+            if !shutdown.get_running_flag().load(Ordering::Relaxed) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn run_apps_once(
+        &mut self,
+        shutdown: &ShutdownReceiver,
         api_notifier: Option<Sender<bool>>,
+        schema_drift: SchemaDriftMonitor,
     ) -> Result<(), OrchestrationError> {
         let home_dir = HomeDir::new(self.config.home_dir.as_ref(), self.config.cache_dir.clone());
         let executor = self.runtime.block_on(Executor::new(
@@ -173,9 +313,15 @@ impl SimpleOrchestrator {
             &self.config.connections,
             &self.config.sources,
             self.config.sql.as_deref(),
+            &self.config.operators,
+            &self.config.routers,
+            self.operator_registry.clone(),
             &self.config.endpoints,
             get_log_options(&self.config),
             self.multi_pb.clone(),
+            schema_drift.clone(),
+            self.information_schema.clone(),
+            get_sql_options(&self.config)?,
         ))?;
         let dag_executor = executor
             .create_dag_executor(self.runtime.clone(), get_executor_options(&self.config))?;
@@ -183,6 +329,7 @@ impl SimpleOrchestrator {
         let app_grpc_config = get_app_grpc_config(&self.config);
         let internal_server_future = start_internal_pipeline_server(
             executor.endpoint_and_logs().to_vec(),
+            schema_drift.alerts(),
             &app_grpc_config,
             shutdown.create_shutdown_future(),
         );
@@ -271,9 +418,15 @@ impl SimpleOrchestrator {
             &self.config.connections,
             &self.config.sources,
             self.config.sql.as_deref(),
+            get_sql_options(&self.config)?,
+            &self.config.operators,
+            &self.config.routers,
+            self.operator_registry.clone(),
             endpoint_and_logs,
             self.multi_pb.clone(),
-        );
+            SchemaDriftMonitor::default(),
+        )
+        .information_schema(self.information_schema.clone());
         let dag = builder.build(self.runtime.clone())?;
         // Populate schemas.
         let dag_schemas = DagSchemas::new(dag)?;
@@ -303,12 +456,19 @@ impl SimpleOrchestrator {
                 .find(|e| e.name == *endpoint_name)
                 .expect("Sink name must be the same as endpoint name");
             let (schema, secondary_indexes) = build::modify_schema(&schema, endpoint)?;
+            let naming = endpoint.naming.as_ref();
             let schema = BuildSchema {
                 schema,
                 secondary_indexes,
                 enable_token,
                 enable_on_event,
                 connections,
+                grpc_service_name: naming.and_then(|naming| naming.grpc_service.clone()),
+                grpc_message_name: naming.and_then(|naming| naming.grpc_message.clone()),
+                camel_case_fields: naming
+                    .and_then(|naming| naming.camel_case_fields)
+                    .unwrap_or(false),
+                deprecated_fields: endpoint.deprecated_fields.clone(),
             };
 
             futures.push(build::build(
@@ -327,6 +487,90 @@ impl SimpleOrchestrator {
         Ok(())
     }
 
+    /// Column lineage of the transform output tables that are exposed as endpoints. The schemas of the sources are
+    /// read from their connections, to resolve the columns of joins and wildcards.
+    pub fn lineage(&self) -> Result<Vec<TableLineage>, OrchestrationError> {
+        let Some(sql) = self.config.sql.as_deref() else {
+            return Ok(vec![]);
+        };
+        let source_columns: HashMap<_, Vec<_>> = self
+            .describe_tables()?
+            .tables
+            .into_iter()
+            .filter(|table| table.table_type == TableType::Source)
+            .map(|table| {
+                let columns = table
+                    .schema
+                    .fields
+                    .into_iter()
+                    .map(|field| field.name)
+                    .collect();
+                (table.name, columns)
+            })
+            .collect();
+        let lineage = extract_lineage(sql, get_sql_options(&self.config)?, &source_columns)?
+            .into_iter()
+            .filter(|table| {
+                self.config
+                    .endpoints
+                    .iter()
+                    .any(|endpoint| endpoint.table_name == table.table)
+            })
+            .collect();
+        Ok(lineage)
+    }
+
+    /// Describes the sources, the tables produced from them and the endpoints of the app, for SQL run against it to
+    /// read from the `information_schema` tables.
+    pub fn describe_tables(&self) -> Result<InformationSchema, OrchestrationError> {
+        let endpoint_and_logs = self
+            .config
+            .endpoints
+            .iter()
+            .map(|endpoint| (endpoint.clone(), None))
+            .collect();
+        PipelineBuilder::new(
+            &self.config.connections,
+            &self.config.sources,
+            self.config.sql.as_deref(),
+            get_sql_options(&self.config)?,
+            &self.config.operators,
+            &self.config.routers,
+            self.operator_registry.clone(),
+            endpoint_and_logs,
+            self.multi_pb.clone(),
+            SchemaDriftMonitor::default(),
+        )
+        .describe_tables(self.runtime.clone())
+    }
+
+    /// Exports the cache of an endpoint to a file.
+    pub fn dump(&self, dump: Dump) -> Result<(), OrchestrationError> {
+        dump::dump(&self.config, dump)
+    }
+
+    /// Bulk loads files into the cache of an endpoint.
+    pub fn load(&self, load: Load) -> Result<(), OrchestrationError> {
+        load::load(&self.config, load)
+    }
+
+    /// Runs the pipeline at the times of `app.schedule`, recording each run in the run history.
+    pub fn run_scheduled(&mut self, shutdown: ShutdownReceiver) -> Result<(), OrchestrationError> {
+        let schedule = self
+            .config
+            .app
+            .as_ref()
+            .and_then(|app| app.schedule.as_ref())
+            .ok_or(ScheduleError::MissingConfig)?;
+        let scheduler = Scheduler::new(schedule, &self.config.home_dir)?;
+        scheduler.run(self, shutdown)
+    }
+
+    /// Prints the history of the scheduled runs.
+    pub fn run_history(&self) -> Result<(), OrchestrationError> {
+        schedule::print_run_history(&self.config)
+    }
+
     // Cleaning the entire folder as there will be inconsistencies
     // between pipeline, cache and generated proto files.
     pub fn clean(&mut self) -> Result<(), OrchestrationError> {
@@ -376,8 +620,8 @@ impl SimpleOrchestrator {
     }
 }
 
-pub fn validate_sql(sql: String) -> Result<(), PipelineError> {
-    statement_to_pipeline(&sql, &mut AppPipeline::new(), None).map_or_else(
+pub fn validate_sql(sql: String, options: SqlOptions) -> Result<(), PipelineError> {
+    statement_to_pipeline(&sql, &mut AppPipeline::new(), None, options).map_or_else(
         |e| {
             error!(
                 "[sql][{}] Transforms validation error: {}",
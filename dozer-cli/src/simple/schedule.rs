@@ -0,0 +1,375 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use cron::Schedule;
+use dozer_api::errors::GrpcError;
+use dozer_api::grpc::internal::internal_pipeline_server::start_internal_pipeline_server;
+use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_types::chrono::{DateTime, Utc};
+use dozer_types::log::{error, info, warn};
+use dozer_types::models::app_config::{default_run_history_size, RunScheduleConfig};
+use dozer_types::models::config::Config;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::prettytable::{row, table};
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json;
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+
+use super::executor::{run_dag_executor, Executor};
+use super::SimpleOrchestrator;
+use crate::errors::{OrchestrationError, ScheduleError};
+use crate::pipeline::SchemaDriftMonitor;
+use crate::shutdown::ShutdownReceiver;
+use crate::utils::{
+    get_app_grpc_config, get_executor_options, get_log_options, get_schema_drift_config,
+    get_sql_options,
+};
+
+/// The file in the home directory the history of scheduled runs is kept in.
+pub const RUN_HISTORY_FILE_NAME: &str = "run_history.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub duration_in_millis: u64,
+    /// Record operations written to the endpoints.
+    pub operations: u64,
+    pub status: RunStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum RunStatus {
+    Succeeded,
+    Failed(String),
+    /// The previous run was still running at the scheduled time.
+    Skipped,
+}
+
+/// The most recent scheduled runs, oldest first, persisted so they survive restarts.
+#[derive(Debug)]
+pub struct RunHistory {
+    path: PathBuf,
+    size: usize,
+    runs: VecDeque<RunRecord>,
+}
+
+impl RunHistory {
+    pub fn open(path: PathBuf, size: usize) -> Result<Self, ScheduleError> {
+        let runs = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| ScheduleError::ParseHistory(path.clone(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => return Err(ScheduleError::ReadHistory(path, e)),
+        };
+        Ok(Self { path, size, runs })
+    }
+
+    pub fn runs(&self) -> &VecDeque<RunRecord> {
+        &self.runs
+    }
+
+    /// Adds a run, dropping the oldest ones beyond the history size, and persists the history.
+    pub fn record(&mut self, run: RunRecord) -> Result<(), ScheduleError> {
+        self.runs.push_back(run);
+        while self.runs.len() > self.size {
+            self.runs.pop_front();
+        }
+        self.save()
+    }
+
+    /// Written to a temporary file first, so a crash doesn't leave a partial history.
+    fn save(&self) -> Result<(), ScheduleError> {
+        let bytes =
+            serde_json::to_vec_pretty(&self.runs).map_err(ScheduleError::SerializeHistory)?;
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, bytes)
+            .and_then(|()| fs::rename(&temp_path, &self.path))
+            .map_err(|e| ScheduleError::WriteHistory(self.path.clone(), e))
+    }
+}
+
+pub fn run_history_path(home_dir: &str) -> PathBuf {
+    Path::new(home_dir).join(RUN_HISTORY_FILE_NAME)
+}
+
+/// Prints the scheduled runs of the app, oldest first.
+pub fn print_run_history(config: &Config) -> Result<(), OrchestrationError> {
+    let history = RunHistory::open(run_history_path(&config.home_dir), usize::MAX)?;
+    let mut table = table!(["started at", "status", "duration (ms)", "operations"]);
+    for run in history.runs() {
+        let status = match &run.status {
+            RunStatus::Succeeded => "succeeded".to_string(),
+            RunStatus::Failed(error) => format!("failed: {error}"),
+            RunStatus::Skipped => "skipped".to_string(),
+        };
+        table.add_row(row![
+            run.started_at.to_rfc3339(),
+            status,
+            run.duration_in_millis,
+            run.operations
+        ]);
+    }
+    table.printstd();
+    Ok(())
+}
+
+/// Runs the pipeline of a batch-style app, whose sources end after reading their snapshot, at the times of a cron
+/// schedule.
+///
+/// Each run creates a new build of the endpoints if the last one has data, and serves its logs to API instances
+/// until the next run starts. A run is skipped if the previous one is still running at its time.
+pub struct Scheduler {
+    schedule: Schedule,
+    history: Arc<Mutex<RunHistory>>,
+}
+
+impl Scheduler {
+    pub fn new(config: &RunScheduleConfig, home_dir: &str) -> Result<Self, ScheduleError> {
+        let schedule = Schedule::from_str(&config.cron)
+            .map_err(|e| ScheduleError::InvalidCron(config.cron.clone(), e))?;
+        let size = config.history_size.unwrap_or_else(default_run_history_size) as usize;
+        let history = RunHistory::open(run_history_path(home_dir), size)?;
+        Ok(Self {
+            schedule,
+            history: Arc::new(Mutex::new(history)),
+        })
+    }
+
+    /// Runs `orchestrator`'s pipeline at every scheduled time, until `shutdown` or the schedule has no more times.
+    pub fn run(
+        &self,
+        orchestrator: &SimpleOrchestrator,
+        shutdown: ShutdownReceiver,
+    ) -> Result<(), OrchestrationError> {
+        let runtime = orchestrator.runtime.clone();
+        let mut current: Option<JoinHandle<Option<LogServer>>> = None;
+        let mut log_server: Option<LogServer> = None;
+
+        for time in self.schedule.upcoming(Utc) {
+            let wait = (time - Utc::now()).to_std().unwrap_or_default();
+            let shutdown_future = shutdown.create_shutdown_future();
+            let is_shutdown = runtime.block_on(async {
+                tokio::select! {
+                    _ = shutdown_future => true,
+                    _ = tokio::time::sleep(wait) => false,
+                }
+            });
+            if is_shutdown {
+                break;
+            }
+
+            if current.as_ref().map_or(false, |run| !run.is_finished()) {
+                warn!("Skipping run scheduled at {time}, the previous run is still running");
+                self.record(RunRecord {
+                    started_at: time,
+                    duration_in_millis: 0,
+                    operations: 0,
+                    status: RunStatus::Skipped,
+                });
+                continue;
+            }
+            if let Some(run) = current.take() {
+                log_server = run.join().expect("Scheduled run panicked");
+            }
+            // The next run serves its own logs on the same address.
+            if let Some(log_server) = log_server.take() {
+                log_server.stop(&runtime);
+            }
+
+            let mut orchestrator = orchestrator.clone();
+            let shutdown = shutdown.clone();
+            let history = self.history.clone();
+            current = Some(thread::spawn(move || {
+                info!("Starting run scheduled at {time}");
+                let started = Instant::now();
+                let result = run_once(&mut orchestrator, &shutdown);
+                let duration_in_millis = started.elapsed().as_millis() as u64;
+                let (operations, status, log_server) = match result {
+                    Ok((operations, log_server)) => {
+                        info!("Run scheduled at {time} wrote {operations} operations in {duration_in_millis} ms");
+                        (operations, RunStatus::Succeeded, Some(log_server))
+                    }
+                    Err(e) => {
+                        error!("Run scheduled at {time} failed: {e}");
+                        (0, RunStatus::Failed(e.to_string()), None)
+                    }
+                };
+                record(
+                    &history,
+                    RunRecord {
+                        started_at: time,
+                        duration_in_millis,
+                        operations,
+                        status,
+                    },
+                );
+                log_server
+            }));
+        }
+
+        if let Some(run) = current {
+            log_server = run.join().expect("Scheduled run panicked");
+        }
+        if let Some(log_server) = log_server {
+            log_server.stop(&runtime);
+        }
+        Ok(())
+    }
+
+    fn record(&self, run: RunRecord) {
+        record(&self.history, run)
+    }
+}
+
+/// A failure to persist the history doesn't stop the schedule.
+fn record(history: &Mutex<RunHistory>, run: RunRecord) {
+    if let Err(e) = history.lock().record(run) {
+        error!("Failed to record scheduled run: {e}");
+    }
+}
+
+/// The internal server serving the endpoint logs of a run to API instances.
+struct LogServer {
+    stop: oneshot::Sender<()>,
+    handle: tokio::task::JoinHandle<Result<(), GrpcError>>,
+}
+
+impl LogServer {
+    /// Waits for the server to release its address.
+    fn stop(self, runtime: &Runtime) {
+        drop(self.stop);
+        match runtime.block_on(self.handle) {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => error!("Internal server of the last run failed: {e}"),
+            Err(e) => error!("Internal server of the last run panicked: {e}"),
+        }
+    }
+}
+
+/// Builds the app and runs its pipeline until its sources end, returning the number of record operations written to
+/// the endpoints and the server of their logs.
+fn run_once(
+    orchestrator: &mut SimpleOrchestrator,
+    shutdown: &ShutdownReceiver,
+) -> Result<(u64, LogServer), OrchestrationError> {
+    orchestrator.build(false)?;
+
+    let runtime = orchestrator.runtime.clone();
+    let config = &orchestrator.config;
+    let home_dir = HomeDir::new(config.home_dir.as_ref(), config.cache_dir.clone());
+    let schema_drift = SchemaDriftMonitor::new(get_schema_drift_config(config));
+    let executor = runtime.block_on(Executor::new(
+        &home_dir,
+        &config.connections,
+        &config.sources,
+        config.sql.as_deref(),
+        &config.operators,
+        &config.routers,
+        orchestrator.operator_registry.clone(),
+        &config.endpoints,
+        get_log_options(config),
+        orchestrator.multi_pb.clone(),
+        schema_drift.clone(),
+        orchestrator.information_schema.clone(),
+        get_sql_options(config)?,
+    ))?;
+    let dag_executor =
+        executor.create_dag_executor(runtime.clone(), get_executor_options(config))?;
+
+    let endpoint_and_logs = executor.endpoint_and_logs().to_vec();
+    let app_grpc_config = get_app_grpc_config(config);
+    let (stop, stopped) = oneshot::channel::<()>();
+    let shutdown_future = shutdown.create_shutdown_future();
+    let server_endpoint_and_logs = endpoint_and_logs.clone();
+    let handle = runtime.spawn(async move {
+        start_internal_pipeline_server(
+            server_endpoint_and_logs,
+            schema_drift.alerts(),
+            &app_grpc_config,
+            async move {
+                tokio::select! {
+                    _ = shutdown_future => (),
+                    _ = stopped => (),
+                }
+            },
+        )
+        .await
+    });
+    let log_server = LogServer { stop, handle };
+
+    if let Err(e) = run_dag_executor(dag_executor, shutdown.get_running_flag()) {
+        log_server.stop(&runtime);
+        return Err(e);
+    }
+
+    let operations = runtime.block_on(async {
+        let mut operations = 0;
+        for (_, build_and_log) in &endpoint_and_logs {
+            operations += build_and_log.log.lock().await.num_ops_written() as u64;
+        }
+        operations
+    });
+    Ok((operations, log_server))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::chrono::TimeZone;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn run(hour: u32, status: RunStatus) -> RunRecord {
+        RunRecord {
+            started_at: Utc.with_ymd_and_hms(2023, 7, 1, hour, 0, 0).unwrap(),
+            duration_in_millis: 1500,
+            operations: 42,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_run_history() {
+        let dir = TempDir::new("run_history").unwrap();
+        let path = dir.path().join(RUN_HISTORY_FILE_NAME);
+
+        let mut history = RunHistory::open(path.clone(), 2).unwrap();
+        assert!(history.runs().is_empty());
+        history.record(run(1, RunStatus::Succeeded)).unwrap();
+        history.record(run(2, RunStatus::Skipped)).unwrap();
+        history
+            .record(run(3, RunStatus::Failed("Connection refused".to_string())))
+            .unwrap();
+
+        // The oldest runs are dropped, and the history is kept across restarts.
+        let history = RunHistory::open(path, 2).unwrap();
+        assert_eq!(
+            history.runs(),
+            &VecDeque::from(vec![
+                run(2, RunStatus::Skipped),
+                run(3, RunStatus::Failed("Connection refused".to_string()))
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_cron() {
+        let dir = TempDir::new("run_history").unwrap();
+        let config = RunScheduleConfig {
+            cron: "every day".to_string(),
+            history_size: None,
+        };
+        assert!(matches!(
+            Scheduler::new(&config, dir.path().to_str().unwrap()),
+            Err(ScheduleError::InvalidCron(..))
+        ));
+    }
+}
@@ -7,13 +7,17 @@ use dozer_core::{
     petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences},
     Dag,
 };
-use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use dozer_types::{
     grpc_types::cloud::{QueryEdge, QueryGraph, QueryNode, QueryNodeType},
     models::{config::Config, connection::Connection, source::Source},
 };
 
-use crate::{errors::OrchestrationError, pipeline::source_builder::SourceBuilder};
+use crate::{
+    errors::OrchestrationError,
+    pipeline::{source_builder::SourceBuilder, SchemaDriftMonitor},
+    utils::get_sql_options,
+};
 
 #[derive(Debug)]
 struct UISourceFactory {
@@ -53,10 +57,11 @@ fn prepare_pipeline_dag(
     sql: String,
     connection_sources: HashMap<Connection, Vec<Source>>,
     connection_source_ports: HashMap<(&str, &str), u16>,
+    sql_options: SqlOptions,
 ) -> Result<Dag<SchemaSQLContext>, OrchestrationError> {
     let mut pipeline = AppPipeline::new();
     let mut asm: AppSourceManager<dozer_sql::pipeline::builder::SchemaSQLContext> =
-        AppSourceManager::new();
+        AppSourceManager::new().with_case_sensitive(sql_options.case_sensitive);
     connection_sources.iter().for_each(|cs| {
         let (connection, sources) = cs;
         let ports = sources
@@ -80,7 +85,7 @@ fn prepare_pipeline_dag(
             AppSourceMappings::new(connection.name.to_string(), ports_with_source_name),
         );
     });
-    statement_to_pipeline(&sql, &mut pipeline, None)?;
+    statement_to_pipeline(&sql, &mut pipeline, None, sql_options)?;
     let mut app = App::new(asm);
     app.add_pipeline(pipeline);
     let sql_dag = app.into_dag()?;
@@ -153,6 +158,7 @@ pub fn transform_to_ui_graph(input_dag: &Dag<SchemaSQLContext>) -> QueryGraph {
 }
 
 pub fn config_to_ui_dag(config: Config) -> Result<QueryGraph, OrchestrationError> {
+    let sql_options = get_sql_options(&config)?;
     let sql = config.sql.unwrap_or("".to_string());
     let mut connection_sources: HashMap<Connection, Vec<Source>> = HashMap::new();
     for source in config.sources {
@@ -165,8 +171,17 @@ pub fn config_to_ui_dag(config: Config) -> Result<QueryGraph, OrchestrationError
         let sources_same_connection = connection_sources.entry(connection).or_insert(vec![]);
         sources_same_connection.push(source);
     }
-    let source_builder = SourceBuilder::new(connection_sources.clone(), None);
+    let source_builder = SourceBuilder::new(
+        connection_sources.clone(),
+        None,
+        SchemaDriftMonitor::default(),
+    );
     let connection_source_ports = source_builder.get_ports();
-    let sql_dag = prepare_pipeline_dag(sql, connection_sources, connection_source_ports)?;
+    let sql_dag = prepare_pipeline_dag(
+        sql,
+        connection_sources,
+        connection_source_ports,
+        sql_options,
+    )?;
     Ok(transform_to_ui_graph(&sql_dag))
 }
@@ -1,5 +1,7 @@
+use crate::errors::CliError;
 use dozer_cache::{cache::CacheManagerOptions, dozer_log::replication::LogOptions};
 use dozer_core::executor::ExecutorOptions;
+use dozer_sql::pipeline::builder::SqlOptions;
 use dozer_types::models::{
     api_config::{
         default_api_grpc, default_api_rest, default_app_grpc, AppGrpcOptions, GrpcApiOptions,
@@ -9,9 +11,14 @@ use dozer_types::models::{
     app_config::{
         default_app_buffer_size, default_commit_size, default_commit_timeout,
         default_error_threshold, default_log_entry_max_size, default_log_max_num_immutable_entries,
+        SchemaDriftConfig,
+    },
+    config::{
+        default_cache_index_stats_ttl_secs, default_cache_max_map_size, default_cache_max_readers,
+        Config,
     },
-    config::{default_cache_max_map_size, Config},
 };
+use dozer_types::types::Timezone;
 use std::time::Duration;
 
 fn get_cache_max_map_size(config: &Config) -> u64 {
@@ -20,6 +27,20 @@ fn get_cache_max_map_size(config: &Config) -> u64 {
         .unwrap_or_else(default_cache_max_map_size)
 }
 
+fn get_cache_max_readers(config: &Config) -> u32 {
+    config
+        .cache_max_readers
+        .unwrap_or_else(default_cache_max_readers)
+}
+
+fn get_cache_index_stats_ttl(config: &Config) -> Duration {
+    Duration::from_secs(
+        config
+            .cache_index_stats_ttl_secs
+            .unwrap_or_else(default_cache_index_stats_ttl_secs),
+    )
+}
+
 fn get_commit_time_threshold(config: &Config) -> Duration {
     Duration::from_millis(
         config
@@ -69,6 +90,7 @@ pub fn get_log_options(config: &Config) -> LogOptions {
         storage_config,
         entry_max_size,
         max_num_immutable_entries,
+        kafka: app.and_then(|app| app.log_kafka.clone()),
     }
 }
 
@@ -109,13 +131,51 @@ pub fn get_executor_options(config: &Config) -> ExecutorOptions {
         channel_buffer_sz: get_buffer_size(config) as usize,
         commit_time_threshold: get_commit_time_threshold(config),
         error_threshold: Some(get_error_threshold(config)),
+        record_provenance: config
+            .endpoints
+            .iter()
+            .any(|endpoint| endpoint.provenance.unwrap_or(false)),
     }
 }
 
+pub fn get_schema_drift_config(config: &Config) -> SchemaDriftConfig {
+    config
+        .app
+        .as_ref()
+        .and_then(|app| app.schema_drift)
+        .unwrap_or_default()
+}
+
+pub fn get_timezone(config: &Config) -> Result<Timezone, CliError> {
+    config
+        .app
+        .as_ref()
+        .and_then(|app| app.timezone.as_deref())
+        .map_or(Ok(Timezone::Utc), str::parse)
+        .map_err(CliError::InvalidTimezone)
+}
+
+pub fn get_sql_options(config: &Config) -> Result<SqlOptions, CliError> {
+    Ok(SqlOptions {
+        case_sensitive: config
+            .app
+            .as_ref()
+            .and_then(|app| app.case_sensitive_identifiers)
+            .unwrap_or(false),
+        timezone: get_timezone(config)?,
+    })
+}
+
 pub fn get_cache_manager_options(config: &Config) -> CacheManagerOptions {
     CacheManagerOptions {
         path: Some(config.cache_dir.clone().into()),
         max_size: get_cache_max_map_size(config) as usize,
+        max_readers: get_cache_max_readers(config),
+        index_stats_ttl: get_cache_index_stats_ttl(config),
+        parallel_scan_threshold: config
+            .cache_parallel_scan_threshold
+            .map(|threshold| threshold as usize),
+        partition_paths: config.cache_partition_dirs.iter().map(Into::into).collect(),
         ..CacheManagerOptions::default()
     }
 }
@@ -149,8 +149,11 @@ impl<T: Clone> App<T> {
 
         // Connect to all pipelines
         for (source_name, target_endpoint) in entry_points {
-            let source_endpoint =
-                appsource::get_endpoint_from_mappings(&self.sources.mappings, &source_name)?;
+            let source_endpoint = appsource::get_endpoint_from_mappings(
+                &self.sources.mappings,
+                &source_name,
+                self.sources.case_sensitive,
+            )?;
             dag.connect(source_endpoint, target_endpoint)?;
         }
 
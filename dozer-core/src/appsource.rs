@@ -1,3 +1,4 @@
+use dozer_types::identifier;
 use dozer_types::node::NodeHandle;
 
 use crate::errors::ExecutionError;
@@ -28,6 +29,8 @@ impl AppSourceMappings {
 pub struct AppSourceManager<T> {
     pub(crate) sources: Vec<Box<dyn SourceFactory<T>>>,
     pub(crate) mappings: Vec<AppSourceMappings>,
+    /// Whether source names only match names spelled exactly like them.
+    pub(crate) case_sensitive: bool,
 }
 
 impl<T> Default for AppSourceManager<T> {
@@ -35,6 +38,7 @@ impl<T> Default for AppSourceManager<T> {
         Self {
             sources: vec![],
             mappings: vec![],
+            case_sensitive: false,
         }
     }
 }
@@ -59,29 +63,47 @@ impl<T> AppSourceManager<T> {
     }
 
     pub fn get_endpoint(&self, source_name: &str) -> Result<Endpoint, ExecutionError> {
-        get_endpoint_from_mappings(&self.mappings, source_name)
+        get_endpoint_from_mappings(&self.mappings, source_name, self.case_sensitive)
     }
 
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Makes source names only match names spelled exactly like them, as the app's identifiers do.
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
 }
 
+/// Finds the source `source_name` refers to, following the identifier rules of the app.
 pub fn get_endpoint_from_mappings(
     mappings: &[AppSourceMappings],
     source_name: &str,
+    case_sensitive: bool,
 ) -> Result<Endpoint, ExecutionError> {
-    let mut found: Vec<Endpoint> = mappings
-        .iter()
-        .filter_map(|mapping| {
-            mapping.mappings.get(source_name).map(|output_port| {
-                Endpoint::new(
-                    NodeHandle::new(None, mapping.connection.clone()),
-                    *output_port,
-                )
-            })
-        })
-        .collect();
+    let sources = mappings.iter().flat_map(|mapping| {
+        mapping
+            .mappings
+            .iter()
+            .map(move |(name, output_port)| (mapping, name, output_port))
+    });
+    let mut found: Vec<Endpoint> = identifier::matching(
+        source_name,
+        false,
+        case_sensitive,
+        sources,
+        |(_, name, _)| name.as_str(),
+    )
+    .into_iter()
+    .map(|(mapping, _, output_port)| {
+        Endpoint::new(
+            NodeHandle::new(None, mapping.connection.clone()),
+            *output_port,
+        )
+    })
+    .collect();
 
     match found.len() {
         0 => Err(InvalidSourceIdentifier(source_name.to_string())),
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
     time::SystemTime,
 };
@@ -16,6 +17,9 @@ pub struct Epoch {
     pub common_info: EpochCommonInfo,
     pub details: SourceStates,
     pub decision_instant: SystemTime,
+    /// The sources in the middle of a transaction when the epoch was closed, with the id of the transaction. Sinks
+    /// that expose data atomically hold such epochs until the transactions end.
+    pub open_transactions: HashMap<NodeHandle, u64>,
 }
 
 impl Epoch {
@@ -32,6 +36,7 @@ impl Epoch {
             },
             details,
             decision_instant,
+            open_transactions: Default::default(),
         }
     }
 
@@ -48,6 +53,7 @@ impl Epoch {
                 .into_iter()
                 .collect(),
             decision_instant,
+            open_transactions: Default::default(),
         }
     }
 }
@@ -1,7 +1,16 @@
 use std::sync::atomic::AtomicU32;
 
-use dozer_types::tracing::error_span;
-use dozer_types::{errors::internal::BoxedError, log::error};
+use dozer_tracing::{record_pipeline_error, OperationId, PipelineErrorContext, PipelineStage};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::NodeHandle;
+
+/// The operation an error happened on, recorded with the error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorOrigin<'a> {
+    /// The connection and table the operation came from, if known.
+    pub source_table: Option<&'a (String, String)>,
+    pub operation_id: Option<OperationId>,
+}
 
 /// `ErrorManager` records and counts the number of errors happened.
 ///
@@ -27,9 +36,25 @@ impl ErrorManager {
         }
     }
 
-    pub fn report(&self, error: BoxedError) {
-        error_span!("reported error", error = true, e = error);
-        error!("{}", error);
+    /// Records the error as a span event, with where it happened.
+    pub fn report(
+        &self,
+        error: BoxedError,
+        stage: PipelineStage,
+        node: &NodeHandle,
+        origin: ErrorOrigin,
+    ) {
+        let node = node.to_string();
+        let context = PipelineErrorContext {
+            stage,
+            node: &node,
+            source: origin
+                .source_table
+                .map(|(connection, _)| connection.as_str()),
+            table: origin.source_table.map(|(_, table)| table.as_str()),
+            operation_id: origin.operation_id,
+        };
+        record_pipeline_error(&context, &*error);
 
         let count = self.count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         if let Some(threshold) = self.threshold {
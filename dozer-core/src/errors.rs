@@ -29,6 +29,10 @@ pub enum ExecutionError {
     AmbiguousSourceIdentifier(String),
     #[error("Invalid AppSource connection {0}. Already exists.")]
     AppSourceConnectionAlreadyExists(String),
+    #[error("Operator {0} is already registered")]
+    OperatorAlreadyRegistered(String),
+    #[error("Unknown operator {0}")]
+    UnknownOperator(String),
     #[error("Factory error: {0}")]
     Factory(#[source] BoxedError),
     #[error("Source error: {0}")]
@@ -23,6 +23,7 @@ use daggy::petgraph::{
     visit::{EdgeRef, IntoEdges, IntoEdgesDirected, IntoNodeIdentifiers},
     Direction,
 };
+use dozer_types::types::{Schema, SourceDefinition};
 
 pub type SharedRecordWriter = Rc<RefCell<Option<Box<dyn RecordWriter>>>>;
 
@@ -38,6 +39,10 @@ pub struct EdgeType {
     pub input_port: PortHandle,
     /// The receiver from receiving data from upstream.
     pub receiver: Receiver<ExecutorOperation>,
+    /// The connection and table of the records on this edge, if they all come from one source table.
+    pub source_table: Option<(String, String)>,
+    /// The primary key of the records on this edge.
+    pub primary_index: Vec<usize>,
 }
 
 #[derive(Debug)]
@@ -110,6 +115,8 @@ impl ExecutionDag {
                 record_writer,
                 input_port: edge.input_port,
                 receiver,
+                source_table: source_table(&edge.schema),
+                primary_index: edge.schema.primary_index.clone(),
             };
             edges.push(Some(edge));
         }
@@ -194,10 +201,16 @@ impl ExecutionDag {
         (senders, record_writers)
     }
 
+    /// Returns the input ports of a node, their receivers and the source tables of their records.
+    #[allow(clippy::type_complexity)]
     pub fn collect_receivers(
         &mut self,
         node_index: daggy::NodeIndex,
-    ) -> (Vec<PortHandle>, Vec<Receiver<ExecutorOperation>>) {
+    ) -> (
+        Vec<PortHandle>,
+        Vec<Receiver<ExecutorOperation>>,
+        Vec<Option<(String, String)>>,
+    ) {
         let edge_indexes = self
             .graph
             .edges_directed(node_index, Direction::Incoming)
@@ -206,6 +219,7 @@ impl ExecutionDag {
 
         let mut input_ports = Vec::new();
         let mut receivers = Vec::new();
+        let mut source_tables = Vec::new();
         for edge_index in edge_indexes {
             let edge = self
                 .graph
@@ -213,7 +227,47 @@ impl ExecutionDag {
                 .expect("We don't modify graph structure, only modify the edge weight");
             input_ports.push(edge.input_port);
             receivers.push(edge.receiver.clone());
+            source_tables.push(edge.source_table.clone());
         }
-        (input_ports, receivers)
+        (input_ports, receivers, source_tables)
+    }
+
+    /// Returns the source tables of the records a node outputs, by output port.
+    pub fn collect_output_source_tables(
+        &self,
+        node_index: daggy::NodeIndex,
+    ) -> HashMap<PortHandle, (String, String)> {
+        self.graph
+            .edges(node_index)
+            .filter_map(|edge| {
+                let edge = edge.weight();
+                Some((edge.output_port, edge.source_table.clone()?))
+            })
+            .collect()
     }
+
+    /// Returns the primary key of the records a node outputs, by output port.
+    pub fn collect_output_primary_indexes(
+        &self,
+        node_index: daggy::NodeIndex,
+    ) -> HashMap<PortHandle, Vec<usize>> {
+        self.graph
+            .edges(node_index)
+            .map(|edge| {
+                let edge = edge.weight();
+                (edge.output_port, edge.primary_index.clone())
+            })
+            .collect()
+    }
+}
+
+fn source_table(schema: &Schema) -> Option<(String, String)> {
+    let mut tables = schema.fields.iter().map(|field| match &field.source {
+        SourceDefinition::Table { connection, name } => Some((connection, name)),
+        _ => None,
+    });
+    let first = tables.next()??;
+    tables
+        .all(|table| table == Some(first))
+        .then(|| (first.0.clone(), first.1.clone()))
 }
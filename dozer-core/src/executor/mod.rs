@@ -1,6 +1,7 @@
 use crate::builder_dag::{BuilderDag, NodeKind};
 use crate::dag_schemas::DagSchemas;
 use crate::errors::ExecutionError;
+use crate::memory;
 use crate::Dag;
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
@@ -23,6 +24,8 @@ pub struct ExecutorOptions {
     pub channel_buffer_sz: usize,
     pub commit_time_threshold: Duration,
     pub error_threshold: Option<u32>,
+    /// Whether operations carry the source row that caused them, for sinks to record provenance.
+    pub record_provenance: bool,
 }
 
 impl Default for ExecutorOptions {
@@ -32,6 +35,7 @@ impl Default for ExecutorOptions {
             channel_buffer_sz: 20_000,
             commit_time_threshold: Duration::from_millis(50),
             error_threshold: Some(0),
+            record_provenance: false,
         }
     }
 }
@@ -159,22 +163,28 @@ fn start_source(
     source_listener: SourceListenerNode,
 ) -> Result<JoinHandle<()>, ExecutionError> {
     let handle = source_sender.handle().clone();
+    let sender_name = format!("{handle}-sender");
+    let listener_name = format!("{handle}-listener");
 
     let _st_handle = Builder::new()
-        .name(format!("{handle}-sender"))
-        .spawn(move || match source_sender.run() {
-            Ok(_) => {}
-            // Channel disconnection means the source listener has quit.
-            // Maybe it quit gracefully so we don't need to panic.
-            Err(ExecutionError::CannotSendToChannel) => {}
-            // Other errors result in panic.
-            Err(e) => std::panic::panic_any(e),
+        .name(sender_name.clone())
+        .spawn(move || {
+            memory::set_current_node(&sender_name);
+            match source_sender.run() {
+                Ok(_) => {}
+                // Channel disconnection means the source listener has quit.
+                // Maybe it quit gracefully so we don't need to panic.
+                Err(ExecutionError::CannotSendToChannel) => {}
+                // Other errors result in panic.
+                Err(e) => std::panic::panic_any(e),
+            }
         })
         .map_err(ExecutionError::CannotSpawnWorkerThread)?;
 
     Builder::new()
-        .name(format!("{handle}-listener"))
+        .name(listener_name.clone())
         .spawn(move || {
+            memory::set_current_node(&listener_name);
             if let Err(e) = source_listener.run() {
                 std::panic::panic_any(e);
             }
@@ -183,9 +193,11 @@ fn start_source(
 }
 
 fn start_processor(processor: ProcessorNode) -> Result<JoinHandle<()>, ExecutionError> {
+    let handle = processor.handle().to_string();
     Builder::new()
-        .name(processor.handle().to_string())
+        .name(handle.clone())
         .spawn(move || {
+            memory::set_current_node(&handle);
             if let Err(e) = processor.run() {
                 std::panic::panic_any(e);
             }
@@ -194,9 +206,11 @@ fn start_processor(processor: ProcessorNode) -> Result<JoinHandle<()>, Execution
 }
 
 fn start_sink(sink: SinkNode) -> Result<JoinHandle<()>, ExecutionError> {
+    let handle = sink.handle().to_string();
     Builder::new()
-        .name(sink.handle().to_string())
-        .spawn(|| {
+        .name(handle.clone())
+        .spawn(move || {
+            memory::set_current_node(&handle);
             if let Err(e) = sink.run() {
                 std::panic::panic_any(e);
             }
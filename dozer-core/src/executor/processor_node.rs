@@ -3,10 +3,11 @@ use std::{borrow::Cow, mem::swap};
 
 use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
-use dozer_types::node::NodeHandle;
+use dozer_tracing::{OperationId, PipelineStage};
+use dozer_types::node::{NodeHandle, Provenance};
 
 use crate::epoch::Epoch;
-use crate::error_manager::ErrorManager;
+use crate::error_manager::{ErrorManager, ErrorOrigin};
 use crate::executor_operation::{ExecutorOperation, ProcessorOperation};
 use crate::processor_record::ProcessorRecordStore;
 use crate::{
@@ -27,6 +28,12 @@ pub struct ProcessorNode {
     port_handles: Vec<PortHandle>,
     /// Input data channels.
     receivers: Vec<Receiver<ExecutorOperation>>,
+    /// The connection and table of the records on each input port, if they all come from one source table.
+    source_tables: Vec<Option<(String, String)>>,
+    /// The epoch the operations received are in.
+    epoch_id: u64,
+    /// The index of the next operation in the epoch.
+    op_index: u64,
     /// The processor.
     processor: Box<dyn Processor>,
     /// This node's output channel manager, for forwarding data, writing metadata and writing port state.
@@ -47,7 +54,7 @@ impl ProcessorNode {
             panic!("Must pass in a processor node");
         };
 
-        let (port_handles, receivers) = dag.collect_receivers(node_index);
+        let (port_handles, receivers, source_tables) = dag.collect_receivers(node_index);
 
         let (senders, _) = dag.collect_senders_and_record_writers(node_index);
 
@@ -62,6 +69,9 @@ impl ProcessorNode {
             node_handle,
             port_handles,
             receivers,
+            source_tables,
+            epoch_id: 0,
+            op_index: 0,
             processor,
             channel_manager,
             record_store: dag.record_store().clone(),
@@ -91,22 +101,57 @@ impl ReceiverLoop for ProcessorNode {
         Cow::Owned(self.port_handles[index].to_string())
     }
 
-    fn on_op(&mut self, index: usize, op: ProcessorOperation) -> Result<(), ExecutionError> {
+    fn on_op(
+        &mut self,
+        index: usize,
+        op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), ExecutionError> {
+        self.channel_manager.set_provenance(provenance);
         if let Err(e) = self.processor.process(
             self.port_handles[index],
             &self.record_store,
             op,
             &mut self.channel_manager,
         ) {
-            self.error_manager.report(e);
+            let origin = ErrorOrigin {
+                source_table: self.source_tables[index].as_ref(),
+                operation_id: Some(OperationId::Epoch {
+                    epoch_id: self.epoch_id,
+                    index: self.op_index,
+                }),
+            };
+            self.error_manager
+                .report(e, PipelineStage::Processor, &self.node_handle, origin);
         }
+        self.op_index += 1;
         Ok(())
     }
 
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
+        // Operations output on flush aren't caused by a single source row.
+        self.channel_manager.set_provenance(None);
+        if let Err(e) = self
+            .processor
+            .flush(&self.record_store, &mut self.channel_manager)
+        {
+            self.error_manager.report(
+                e,
+                PipelineStage::Processor,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
+        }
         if let Err(e) = self.processor.commit(epoch) {
-            self.error_manager.report(e);
+            self.error_manager.report(
+                e,
+                PipelineStage::Processor,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
         }
+        self.epoch_id = epoch.common_info.id + 1;
+        self.op_index = 0;
         self.channel_manager.store_and_send_commit(epoch)
     }
 
@@ -114,7 +159,45 @@ impl ReceiverLoop for ProcessorNode {
         self.channel_manager.send_terminate()
     }
 
-    fn on_snapshotting_done(&mut self, connection_name: String) -> Result<(), ExecutionError> {
+    fn on_snapshotting_started(
+        &mut self,
+        index: usize,
+        connection_name: String,
+    ) -> Result<(), ExecutionError> {
+        if let Err(e) = self
+            .processor
+            .on_source_snapshotting_started(self.port_handles[index], &connection_name)
+        {
+            self.error_manager.report(
+                e,
+                PipelineStage::Processor,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
+        }
+        self.channel_manager
+            .send_snapshotting_started(connection_name)
+    }
+
+    fn on_snapshotting_done(
+        &mut self,
+        index: usize,
+        connection_name: String,
+    ) -> Result<(), ExecutionError> {
+        self.channel_manager.set_provenance(None);
+        if let Err(e) = self.processor.on_source_snapshotting_done(
+            self.port_handles[index],
+            &connection_name,
+            &self.record_store,
+            &mut self.channel_manager,
+        ) {
+            self.error_manager.report(
+                e,
+                PipelineStage::Processor,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
+        }
         self.channel_manager.send_snapshotting_done(connection_name)
     }
 }
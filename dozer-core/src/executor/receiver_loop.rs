@@ -1,8 +1,10 @@
 use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 use crossbeam::channel::{Receiver, Select};
 use dozer_types::log::debug;
+use dozer_types::node::Provenance;
 
 use crate::{
     epoch::Epoch,
@@ -20,14 +22,29 @@ pub trait ReceiverLoop: Name {
     fn receivers(&mut self) -> Vec<Receiver<ExecutorOperation>>;
     /// Returns the name of the receiver at `index`. Used for logging.
     fn receiver_name(&self, index: usize) -> Cow<str>;
-    /// Responds to `op` from the receiver at `index`.
-    fn on_op(&mut self, index: usize, op: ProcessorOperation) -> Result<(), ExecutionError>;
+    /// Responds to `op` from the receiver at `index`, caused by the source row `provenance` if it's recorded.
+    fn on_op(
+        &mut self,
+        index: usize,
+        op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), ExecutionError>;
     /// Responds to `commit` of `epoch`.
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError>;
     /// Responds to `terminate`.
     fn on_terminate(&mut self) -> Result<(), ExecutionError>;
-    /// Responds to `SnapshottingDone`.
-    fn on_snapshotting_done(&mut self, connection_name: String) -> Result<(), ExecutionError>;
+    /// Responds to `SnapshottingStarted` from the receiver at `index`.
+    fn on_snapshotting_started(
+        &mut self,
+        index: usize,
+        connection_name: String,
+    ) -> Result<(), ExecutionError>;
+    /// Responds to `SnapshottingDone` from the receiver at `index`.
+    fn on_snapshotting_done(
+        &mut self,
+        index: usize,
+        connection_name: String,
+    ) -> Result<(), ExecutionError>;
 
     /// The loop implementation, calls [`on_op`], [`on_commit`] and [`on_terminate`] at appropriate times.
     fn receiver_loop(&mut self) -> Result<(), ExecutionError> {
@@ -49,8 +66,8 @@ pub trait ReceiverLoop: Name {
                 .map_err(|_| ExecutionError::CannotReceiveFromChannel)?;
 
             match op {
-                ExecutorOperation::Op { op } => {
-                    self.on_op(index, op)?;
+                ExecutorOperation::Op { op, provenance } => {
+                    self.on_op(index, op, provenance)?;
                 }
                 ExecutorOperation::Commit { epoch } => {
                     assert_eq!(epoch.common_info.id, common_epoch.common_info.id);
@@ -63,6 +80,9 @@ pub trait ReceiverLoop: Name {
                     common_epoch.common_info = epoch.common_info;
                     common_epoch.decision_instant = epoch.decision_instant;
                     common_epoch.details.extend(epoch.details);
+                    common_epoch
+                        .open_transactions
+                        .extend(epoch.open_transactions);
 
                     if commits_received == receivers.len() {
                         self.on_commit(&common_epoch)?;
@@ -90,8 +110,11 @@ pub trait ReceiverLoop: Name {
                         return Ok(());
                     }
                 }
+                ExecutorOperation::SnapshottingStarted { connection_name } => {
+                    self.on_snapshotting_started(index, connection_name)?;
+                }
                 ExecutorOperation::SnapshottingDone { connection_name } => {
-                    self.on_snapshotting_done(connection_name)?;
+                    self.on_snapshotting_done(index, connection_name)?;
                 }
             }
         }
@@ -108,6 +131,7 @@ fn init_select(receivers: &Vec<Receiver<ExecutorOperation>>) -> Select {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::mem::swap;
 
     use crossbeam::channel::{unbounded, Sender};
@@ -124,6 +148,7 @@ mod tests {
         receivers: Vec<Receiver<ExecutorOperation>>,
         ops: Vec<(usize, ProcessorOperation)>,
         commits: Vec<Epoch>,
+        snapshotting_started: Vec<(usize, String)>,
         snapshotting_done: Vec<String>,
         num_terminations: usize,
     }
@@ -145,7 +170,12 @@ mod tests {
             Cow::Owned(format!("receiver_{index}"))
         }
 
-        fn on_op(&mut self, index: usize, op: ProcessorOperation) -> Result<(), ExecutionError> {
+        fn on_op(
+            &mut self,
+            index: usize,
+            op: ProcessorOperation,
+            _provenance: Option<Arc<Provenance>>,
+        ) -> Result<(), ExecutionError> {
             self.ops.push((index, op));
             Ok(())
         }
@@ -160,7 +190,20 @@ mod tests {
             Ok(())
         }
 
-        fn on_snapshotting_done(&mut self, connection_name: String) -> Result<(), ExecutionError> {
+        fn on_snapshotting_started(
+            &mut self,
+            index: usize,
+            connection_name: String,
+        ) -> Result<(), ExecutionError> {
+            self.snapshotting_started.push((index, connection_name));
+            Ok(())
+        }
+
+        fn on_snapshotting_done(
+            &mut self,
+            _index: usize,
+            connection_name: String,
+        ) -> Result<(), ExecutionError> {
             self.snapshotting_done.push(connection_name);
             Ok(())
         }
@@ -174,6 +217,7 @@ mod tests {
                     receivers,
                     ops: vec![],
                     commits: vec![],
+                    snapshotting_started: vec![],
                     snapshotting_done: vec![],
                     num_terminations: 0,
                 },
@@ -206,6 +250,21 @@ mod tests {
         assert_eq!(test_loop.snapshotting_done, vec![connection_name])
     }
 
+    #[test]
+    fn receiver_loop_forwards_snapshotting_started() {
+        let connection_name = "test_connection".to_string();
+        let (mut test_loop, senders) = TestReceiverLoop::new(2);
+        senders[1]
+            .send(ExecutorOperation::SnapshottingStarted {
+                connection_name: connection_name.clone(),
+            })
+            .unwrap();
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        senders[1].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+        assert_eq!(test_loop.snapshotting_started, vec![(1, connection_name)])
+    }
+
     #[test]
     fn receiver_loop_forwards_op() {
         let (mut test_loop, senders) = TestReceiverLoop::new(2);
@@ -218,6 +277,7 @@ mod tests {
                 op: ProcessorOperation::Insert {
                     new: record.clone(),
                 },
+                provenance: None,
             })
             .unwrap();
         senders[0].send(ExecutorOperation::Terminate).unwrap();
@@ -283,6 +343,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receiver_loop_merges_open_transactions() {
+        let (mut test_loop, senders) = TestReceiverLoop::new(2);
+        let decision_instant = SystemTime::now();
+        let sources = [
+            NodeHandle::new(None, "a".to_string()),
+            NodeHandle::new(None, "b".to_string()),
+        ];
+        // The transactions of the two sources interleave.
+        let open_transactions = [
+            vec![(sources[0].clone(), 1)],
+            vec![(sources[0].clone(), 1), (sources[1].clone(), 7)],
+            vec![(sources[1].clone(), 7)],
+            vec![],
+        ];
+        for (id, open_transactions) in open_transactions.iter().enumerate() {
+            for (sender, source) in senders.iter().zip(&sources) {
+                let mut epoch = Epoch::new(id as u64, Default::default(), None, decision_instant);
+                epoch.open_transactions = open_transactions
+                    .iter()
+                    .filter(|(open_source, _)| open_source == source)
+                    .cloned()
+                    .collect();
+                sender.send(ExecutorOperation::Commit { epoch }).unwrap();
+            }
+        }
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        senders[1].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+
+        // An epoch has the open transactions of all its sources.
+        assert_eq!(
+            test_loop
+                .commits
+                .into_iter()
+                .map(|epoch| epoch.open_transactions)
+                .collect::<Vec<_>>(),
+            open_transactions
+                .into_iter()
+                .map(|open_transactions| open_transactions.into_iter().collect())
+                .collect::<Vec<HashMap<_, _>>>()
+        );
+    }
+
     #[test]
     #[should_panic]
     fn receiver_loop_panics_on_inconsistent_commit_epoch() {
@@ -2,13 +2,17 @@ use std::{borrow::Cow, mem::swap, sync::Arc};
 
 use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
-use dozer_types::{log::debug, node::NodeHandle};
+use dozer_tracing::{OperationId, PipelineStage};
+use dozer_types::{
+    log::debug,
+    node::{NodeHandle, Provenance},
+};
 use metrics::{describe_histogram, histogram};
 
 use crate::{
     builder_dag::NodeKind,
     epoch::{Epoch, EpochManager},
-    error_manager::ErrorManager,
+    error_manager::{ErrorManager, ErrorOrigin},
     errors::ExecutionError,
     executor_operation::{ExecutorOperation, ProcessorOperation},
     node::{PortHandle, Sink},
@@ -26,6 +30,12 @@ pub struct SinkNode {
     port_handles: Vec<PortHandle>,
     /// Input data channels.
     receivers: Vec<Receiver<ExecutorOperation>>,
+    /// The connection and table of the records on each input port, if they all come from one source table.
+    source_tables: Vec<Option<(String, String)>>,
+    /// The epoch the operations received are in.
+    epoch_id: u64,
+    /// The index of the next operation in the epoch.
+    op_index: u64,
     /// The sink.
     sink: Box<dyn Sink>,
     /// Where all the records from ingested data are stored.
@@ -46,7 +56,7 @@ impl SinkNode {
             panic!("Must pass in a sink node");
         };
 
-        let (port_handles, receivers) = dag.collect_receivers(node_index);
+        let (port_handles, receivers, source_tables) = dag.collect_receivers(node_index);
 
         describe_histogram!(
             PIPELINE_LATENCY_HISTOGRAM_NAME,
@@ -57,6 +67,9 @@ impl SinkNode {
             node_handle,
             port_handles,
             receivers,
+            source_tables,
+            epoch_id: 0,
+            op_index: 0,
             sink,
             epoch_manager: dag.epoch_manager().clone(),
             error_manager: dag.error_manager().clone(),
@@ -85,24 +98,46 @@ impl ReceiverLoop for SinkNode {
         Cow::Owned(self.port_handles[index].to_string())
     }
 
-    fn on_op(&mut self, index: usize, op: ProcessorOperation) -> Result<(), ExecutionError> {
-        if let Err(e) = self.sink.process(
+    fn on_op(
+        &mut self,
+        index: usize,
+        op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), ExecutionError> {
+        if let Err(e) = self.sink.process_with_provenance(
             self.port_handles[index],
             self.epoch_manager.record_store(),
             op,
+            provenance,
         ) {
-            self.error_manager.report(e);
+            let origin = ErrorOrigin {
+                source_table: self.source_tables[index].as_ref(),
+                operation_id: Some(OperationId::Epoch {
+                    epoch_id: self.epoch_id,
+                    index: self.op_index,
+                }),
+            };
+            self.error_manager
+                .report(e, PipelineStage::Sink, &self.node_handle, origin);
         }
+        self.op_index += 1;
         Ok(())
     }
 
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
         debug!("[{}] Checkpointing - {}", self.node_handle, epoch);
         if let Err(e) = self.sink.commit(epoch) {
-            self.error_manager.report(e);
+            self.error_manager.report(
+                e,
+                PipelineStage::Sink,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
         }
 
         self.epoch_manager.finalize_epoch(epoch);
+        self.epoch_id = epoch.common_info.id + 1;
+        self.op_index = 0;
 
         if let Ok(duration) = epoch.decision_instant.elapsed() {
             histogram!(PIPELINE_LATENCY_HISTOGRAM_NAME, duration, "endpoint" => self.node_handle.id.clone());
@@ -115,9 +150,26 @@ impl ReceiverLoop for SinkNode {
         Ok(())
     }
 
-    fn on_snapshotting_done(&mut self, connection_name: String) -> Result<(), ExecutionError> {
+    fn on_snapshotting_started(
+        &mut self,
+        _index: usize,
+        _connection_name: String,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn on_snapshotting_done(
+        &mut self,
+        _index: usize,
+        connection_name: String,
+    ) -> Result<(), ExecutionError> {
         if let Err(e) = self.sink.on_source_snapshotting_done(connection_name) {
-            self.error_manager.report(e);
+            self.error_manager.report(
+                e,
+                PipelineStage::Sink,
+                &self.node_handle,
+                ErrorOrigin::default(),
+            );
         }
         Ok(())
     }
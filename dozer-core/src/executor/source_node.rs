@@ -7,6 +7,7 @@ use std::{
 };
 
 use crossbeam::channel::{bounded, Receiver, RecvTimeoutError, Sender};
+use dozer_tracing::{record_pipeline_error, PipelineErrorContext, PipelineStage};
 use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::{
     log::debug,
@@ -56,6 +57,13 @@ impl Node for SourceSenderNode {
                 .map(|op_id| (op_id.txid, op_id.seq_in_tx)),
         );
         debug!("[{}-sender] Quit", self.node_handle);
+        if let Err(e) = &result {
+            let node = self.node_handle.to_string();
+            record_pipeline_error(
+                &PipelineErrorContext::new(PipelineStage::Source, &node),
+                e.as_ref(),
+            );
+        }
         result.map_err(ExecutionError::Source)
     }
 }
@@ -168,10 +176,16 @@ pub fn create_source_nodes(
 
     // Create source sender node.
     let (senders, record_writers) = dag.collect_senders_and_record_writers(node_index);
+    let port_tables = dag.collect_output_source_tables(node_index);
+    let port_primary_indexes = options
+        .record_provenance
+        .then(|| dag.collect_output_primary_indexes(node_index));
     let state_writer = StateWriter::new(record_writers);
     let channel_manager = SourceChannelManager::new(
         node_handle.clone(),
         senders,
+        port_tables,
+        port_primary_indexes,
         Some(state_writer),
         options.commit_sz,
         options.commit_time_threshold,
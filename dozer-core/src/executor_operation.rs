@@ -1,3 +1,7 @@
+use std::sync::Arc;
+
+use dozer_types::node::Provenance;
+
 use crate::{epoch::Epoch, processor_record::ProcessorRecord};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -17,8 +21,19 @@ pub enum ProcessorOperation {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ExecutorOperation {
-    Op { op: ProcessorOperation },
-    Commit { epoch: Epoch },
+    Op {
+        op: ProcessorOperation,
+        /// The source row of the operation that caused `op`, if provenance is recorded.
+        provenance: Option<Arc<Provenance>>,
+    },
+    Commit {
+        epoch: Epoch,
+    },
     Terminate,
-    SnapshottingDone { connection_name: String },
+    SnapshottingStarted {
+        connection_name: String,
+    },
+    SnapshottingDone {
+        connection_name: String,
+    },
 }
@@ -1,6 +1,6 @@
 use crate::channels::ProcessorChannelForwarder;
 use crate::epoch::{Epoch, EpochManager};
-use crate::error_manager::ErrorManager;
+use crate::error_manager::{ErrorManager, ErrorOrigin};
 use crate::errors::ExecutionError;
 use crate::errors::ExecutionError::InvalidPortHandle;
 use crate::executor_operation::{ExecutorOperation, ProcessorOperation};
@@ -8,9 +8,11 @@ use crate::node::PortHandle;
 use crate::record_store::{RecordWriter, RecordWriterError};
 
 use crossbeam::channel::Sender;
+use dozer_tracing::{OperationId, PipelineStage};
 use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
 use dozer_types::log::debug;
-use dozer_types::node::NodeHandle;
+use dozer_types::node::{NodeHandle, OpIdentifier, Provenance};
+use dozer_types::types::{Operation, Record};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -41,6 +43,8 @@ impl StateWriter {
 #[derive(Debug)]
 struct ChannelManager {
     owner: NodeHandle,
+    /// The stage errors of the owner are reported in.
+    stage: PipelineStage,
     senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
     state_writer: Option<StateWriter>,
     error_manager: Arc<ErrorManager>,
@@ -51,13 +55,16 @@ impl ChannelManager {
     fn send_op(
         &mut self,
         mut op: ProcessorOperation,
+        provenance: Option<Arc<Provenance>>,
         port_id: PortHandle,
+        origin: ErrorOrigin,
     ) -> Result<(), ExecutionError> {
         if let Some(state_writer) = self.state_writer.as_mut() {
             match state_writer.store_op(op, &port_id) {
                 Ok(new_op) => op = new_op,
                 Err(e) => {
-                    self.error_manager.report(e.into());
+                    self.error_manager
+                        .report(e.into(), self.stage, &self.owner, origin);
                     return Ok(());
                 }
             }
@@ -68,7 +75,7 @@ impl ChannelManager {
             .get(&port_id)
             .ok_or(InvalidPortHandle(port_id))?;
 
-        let exec_op = ExecutorOperation::Op { op };
+        let exec_op = ExecutorOperation::Op { op, provenance };
 
         if let Some((last_sender, senders)) = senders.split_last() {
             for sender in senders {
@@ -90,6 +97,18 @@ impl ChannelManager {
         Ok(())
     }
 
+    fn send_snapshotting_started(&self, connection_name: String) -> Result<(), ExecutionError> {
+        for senders in self.senders.values() {
+            for sender in senders {
+                sender.send(ExecutorOperation::SnapshottingStarted {
+                    connection_name: connection_name.clone(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn send_snapshotting_done(&self, connection_name: String) -> Result<(), ExecutionError> {
         for senders in self.senders.values() {
             for sender in senders {
@@ -117,12 +136,14 @@ impl ChannelManager {
     }
     fn new(
         owner: NodeHandle,
+        stage: PipelineStage,
         senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
         state_writer: Option<StateWriter>,
         error_manager: Arc<ErrorManager>,
     ) -> Self {
         Self {
             owner,
+            stage,
             senders,
             state_writer,
             error_manager,
@@ -134,6 +155,10 @@ impl ChannelManager {
 pub(crate) struct SourceChannelManager {
     source_handle: NodeHandle,
     manager: ChannelManager,
+    /// The connection and table of the records sent on each output port, if known.
+    port_tables: HashMap<PortHandle, (String, String)>,
+    /// The primary key of the records sent on each output port, if provenance is recorded.
+    port_primary_indexes: Option<HashMap<PortHandle, Vec<usize>>>,
     curr_txid: u64,
     curr_seq_in_tx: u64,
     commit_sz: u32,
@@ -141,12 +166,17 @@ pub(crate) struct SourceChannelManager {
     max_duration_between_commits: Duration,
     last_commit_instant: SystemTime,
     epoch_manager: Arc<EpochManager>,
+    /// Whether the source is between `TransactionBegin` and `TransactionEnd`.
+    in_transaction: bool,
 }
 
 impl SourceChannelManager {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         owner: NodeHandle,
         senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+        port_tables: HashMap<PortHandle, (String, String)>,
+        port_primary_indexes: Option<HashMap<PortHandle, Vec<usize>>>,
         state_writer: Option<StateWriter>,
         commit_sz: u32,
         max_duration_between_commits: Duration,
@@ -154,7 +184,15 @@ impl SourceChannelManager {
         error_manager: Arc<ErrorManager>,
     ) -> Self {
         Self {
-            manager: ChannelManager::new(owner.clone(), senders, state_writer, error_manager),
+            manager: ChannelManager::new(
+                owner.clone(),
+                PipelineStage::Source,
+                senders,
+                state_writer,
+                error_manager,
+            ),
+            port_tables,
+            port_primary_indexes,
             // FIXME: Read curr_txid and curr_seq_in_tx from persisted state.
             curr_txid: 0,
             curr_seq_in_tx: 0,
@@ -164,6 +202,7 @@ impl SourceChannelManager {
             max_duration_between_commits,
             last_commit_instant: SystemTime::now(),
             epoch_manager,
+            in_transaction: false,
         }
     }
 
@@ -181,13 +220,19 @@ impl SourceChannelManager {
             .epoch_manager
             .wait_for_epoch_close(request_termination, self.num_uncommitted_ops > 0);
         if let Some(common_info) = epoch.common_info {
-            self.manager.send_commit(&Epoch::from(
+            let mut source_epoch = Epoch::from(
                 common_info,
                 self.source_handle.clone(),
                 self.curr_txid,
                 self.curr_seq_in_tx,
                 epoch.decision_instant,
-            ))?;
+            );
+            if self.in_transaction {
+                source_epoch
+                    .open_transactions
+                    .insert(self.source_handle.clone(), self.curr_txid);
+            }
+            self.manager.send_commit(&source_epoch)?;
         }
         self.num_uncommitted_ops = 0;
         self.last_commit_instant = epoch.decision_instant;
@@ -216,9 +261,19 @@ impl SourceChannelManager {
         self.curr_seq_in_tx = message.identifier.seq_in_tx;
         match message.kind {
             IngestionMessageKind::OperationEvent { op, .. } => {
+                let origin = ErrorOrigin {
+                    source_table: self.port_tables.get(&port),
+                    operation_id: Some(OperationId::Source {
+                        txid: self.curr_txid,
+                        seq_in_tx: self.curr_seq_in_tx,
+                    }),
+                };
+                let provenance = self.provenance(&op, port);
                 self.manager.send_op(
                     self.epoch_manager.record_store().create_operation(&op)?,
+                    provenance,
                     port,
+                    origin,
                 )?;
                 self.num_uncommitted_ops += 1;
                 self.trigger_commit_if_needed(request_termination)
@@ -230,20 +285,61 @@ impl SourceChannelManager {
                 self.commit(request_termination)
             }
             IngestionMessageKind::SnapshottingStarted => {
-                // TODO "implement handle for snapshotting started"
+                self.manager
+                    .send_snapshotting_started(self.source_handle.id.clone())?;
+                Ok(false)
+            }
+            IngestionMessageKind::SchemaDrift { .. }
+            | IngestionMessageKind::SchemaChanged { .. } => {
+                // Schema changes are handled by the source, records keep the original schema.
+                Ok(false)
+            }
+            IngestionMessageKind::TransactionBegin => {
+                self.in_transaction = true;
                 Ok(false)
             }
+            IngestionMessageKind::TransactionEnd => {
+                self.in_transaction = false;
+                // Counted so that the end of a transaction whose operations were all committed in earlier epochs is
+                // committed too.
+                self.num_uncommitted_ops += 1;
+                self.trigger_commit_if_needed(request_termination)
+            }
         }
     }
 
     pub fn terminate(&mut self) -> Result<(), ExecutionError> {
         self.manager.send_terminate()
     }
+
+    /// The source row of `op`, if provenance is recorded and the records sent on `port` have a table and a primary key.
+    fn provenance(&self, op: &Operation, port: PortHandle) -> Option<Arc<Provenance>> {
+        let primary_index = self.port_primary_indexes.as_ref()?.get(&port)?;
+        let (connection, table) = self.port_tables.get(&port)?;
+        if primary_index.is_empty() {
+            return None;
+        }
+        let record: &Record = match op {
+            Operation::Insert { new } | Operation::Update { new, .. } => new,
+            Operation::Delete { old } => old,
+        };
+        Some(Arc::new(Provenance {
+            connection: connection.clone(),
+            table: table.clone(),
+            primary_key: primary_index
+                .iter()
+                .map(|index| record.values[*index].clone())
+                .collect(),
+            op_id: OpIdentifier::new(self.curr_txid, self.curr_seq_in_tx),
+        }))
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct ProcessorChannelManager {
     manager: ChannelManager,
+    /// The source row of the operation being processed, attached to the operations it outputs.
+    provenance: Option<Arc<Provenance>>,
 }
 
 impl ProcessorChannelManager {
@@ -254,10 +350,22 @@ impl ProcessorChannelManager {
         error_manager: Arc<ErrorManager>,
     ) -> Self {
         Self {
-            manager: ChannelManager::new(owner, senders, state_writer, error_manager),
+            manager: ChannelManager::new(
+                owner,
+                PipelineStage::Processor,
+                senders,
+                state_writer,
+                error_manager,
+            ),
+            provenance: None,
         }
     }
 
+    /// Sets the source row of the operation being processed.
+    pub fn set_provenance(&mut self, provenance: Option<Arc<Provenance>>) {
+        self.provenance = provenance;
+    }
+
     pub fn store_and_send_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
         self.manager.send_commit(epoch)
     }
@@ -266,6 +374,10 @@ impl ProcessorChannelManager {
         self.manager.send_terminate()
     }
 
+    pub fn send_snapshotting_started(&self, connection_name: String) -> Result<(), ExecutionError> {
+        self.manager.send_snapshotting_started(connection_name)
+    }
+
     pub fn send_snapshotting_done(&self, connection_name: String) -> Result<(), ExecutionError> {
         self.manager.send_snapshotting_done(connection_name)
     }
@@ -274,7 +386,7 @@ impl ProcessorChannelManager {
 impl ProcessorChannelForwarder for ProcessorChannelManager {
     fn send(&mut self, op: ProcessorOperation, port: PortHandle) {
         self.manager
-            .send_op(op, port)
+            .send_op(op, self.provenance.clone(), port, ErrorOrigin::default())
             .unwrap_or_else(|e| panic!("Failed to send operation: {e}"))
     }
 }
@@ -13,9 +13,12 @@ pub mod executor;
 pub mod executor_operation;
 pub mod forwarder;
 mod hash_map_to_vec;
+pub mod memory;
 pub mod node;
+pub mod plugin;
 pub mod processor_record;
 pub mod record_store;
+pub mod test_harness;
 
 #[cfg(test)]
 pub mod tests;
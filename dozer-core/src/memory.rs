@@ -0,0 +1,230 @@
+//! Memory profiling of the pipeline nodes.
+//!
+//! The executor tags every node thread with its node. With the `memory-profiling` feature, binaries can install
+//! [`ProfilingAllocator`] as their global allocator to count the allocations of every tagged thread. Memory is
+//! attributed to the node that allocated it, even if another node frees it, so the bytes a node still holds estimate
+//! the state it keeps resident.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Number of nodes that can be profiled. Allocations of threads tagged with further nodes are untracked.
+const MAX_NODES: usize = 256;
+
+struct Counters {
+    allocated_bytes: AtomicU64,
+    freed_bytes: AtomicU64,
+    allocations: AtomicU64,
+}
+
+impl Counters {
+    const fn new() -> Self {
+        Self {
+            allocated_bytes: AtomicU64::new(0),
+            freed_bytes: AtomicU64::new(0),
+            allocations: AtomicU64::new(0),
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_COUNTERS: Counters = Counters::new();
+
+/// Counters of every slot. Slot 0 counts the allocations of untagged threads, slot `i` those of `NODES[i - 1]`.
+static COUNTERS: [Counters; MAX_NODES + 1] = [NEW_COUNTERS; MAX_NODES + 1];
+
+/// The nodes tagged so far, in tagging order. Nodes of a rebuilt pipeline keep their slot.
+static NODES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Set on the first allocation counted by [`ProfilingAllocator`].
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Slot of the node the current thread runs.
+    static CURRENT_SLOT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Tags the current thread with `node`, so that its allocations are counted for it.
+pub fn set_current_node(node: &str) {
+    let slot = {
+        let mut nodes = NODES.lock().unwrap_or_else(|e| e.into_inner());
+        match nodes.iter().position(|n| n == node) {
+            Some(index) => index + 1,
+            None if nodes.len() < MAX_NODES => {
+                nodes.push(node.to_string());
+                nodes.len()
+            }
+            None => 0,
+        }
+    };
+    CURRENT_SLOT.with(|current| current.set(slot));
+}
+
+/// Whether allocations are counted, that is the binary installed [`ProfilingAllocator`].
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Allocation counters of a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeMemory {
+    pub node: String,
+    /// Bytes allocated by the node since it was first tagged.
+    pub allocated_bytes: u64,
+    /// Bytes allocated by the node that were freed since, by any node.
+    pub freed_bytes: u64,
+    /// Number of allocations by the node since it was first tagged.
+    pub allocations: u64,
+}
+
+impl NodeMemory {
+    /// Bytes allocated by the node that are still held, an estimate of its resident state.
+    pub fn resident_bytes(&self) -> u64 {
+        self.allocated_bytes.saturating_sub(self.freed_bytes)
+    }
+}
+
+/// The counters of every node tagged so far, in tagging order. All zero if allocations aren't counted.
+pub fn node_memory() -> Vec<NodeMemory> {
+    let nodes = NODES.lock().unwrap_or_else(|e| e.into_inner());
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let counters = &COUNTERS[index + 1];
+            NodeMemory {
+                node: node.clone(),
+                allocated_bytes: counters.allocated_bytes.load(Ordering::Relaxed),
+                freed_bytes: counters.freed_bytes.load(Ordering::Relaxed),
+                allocations: counters.allocations.load(Ordering::Relaxed),
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "memory-profiling")]
+pub use allocator::ProfilingAllocator;
+
+#[cfg(feature = "memory-profiling")]
+mod allocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::mem::size_of;
+    use std::sync::atomic::Ordering;
+
+    use super::{COUNTERS, CURRENT_SLOT, ENABLED};
+
+    /// A global allocator counting the allocations of every node, on top of the system allocator.
+    ///
+    /// Every allocation is prefixed with a header holding the slot of the node that made it, so that it's counted as
+    /// freed for that node.
+    pub struct ProfilingAllocator;
+
+    /// Size of the header, which keeps the allocation aligned.
+    fn header_size(layout: &Layout) -> usize {
+        layout.align().max(size_of::<usize>())
+    }
+
+    impl ProfilingAllocator {
+        unsafe fn alloc_counted(layout: Layout, alloc: impl FnOnce(Layout) -> *mut u8) -> *mut u8 {
+            let header = header_size(&layout);
+            let Some(outer) = layout
+                .size()
+                .checked_add(header)
+                .and_then(|size| Layout::from_size_align(size, layout.align()).ok())
+            else {
+                return std::ptr::null_mut();
+            };
+            let base = alloc(outer);
+            if base.is_null() {
+                return base;
+            }
+
+            if !ENABLED.load(Ordering::Relaxed) {
+                ENABLED.store(true, Ordering::Relaxed);
+            }
+            // The thread local is gone while the thread exits.
+            let slot = CURRENT_SLOT.try_with(|slot| slot.get()).unwrap_or(0);
+            let counters = &COUNTERS[slot];
+            counters
+                .allocated_bytes
+                .fetch_add(layout.size() as u64, Ordering::Relaxed);
+            counters.allocations.fetch_add(1, Ordering::Relaxed);
+
+            let ptr = base.add(header);
+            (ptr.sub(size_of::<usize>()) as *mut usize).write_unaligned(slot);
+            ptr
+        }
+    }
+
+    unsafe impl GlobalAlloc for ProfilingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            Self::alloc_counted(layout, |outer| System.alloc(outer))
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            Self::alloc_counted(layout, |outer| System.alloc_zeroed(outer))
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let header = header_size(&layout);
+            let slot = (ptr.sub(size_of::<usize>()) as *const usize).read_unaligned();
+            COUNTERS[slot]
+                .freed_bytes
+                .fetch_add(layout.size() as u64, Ordering::Relaxed);
+            System.dealloc(
+                ptr.sub(header),
+                Layout::from_size_align_unchecked(layout.size() + header, layout.align()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_memory() {
+        let handle = std::thread::spawn(|| {
+            set_current_node("test_node_memory");
+            set_current_node("test_node_memory");
+        });
+        handle.join().unwrap();
+
+        let nodes = node_memory();
+        let node = nodes
+            .iter()
+            .find(|node| node.node == "test_node_memory")
+            .unwrap();
+        assert_eq!(nodes.iter().filter(|n| n.node == node.node).count(), 1);
+        assert!(node.resident_bytes() <= node.allocated_bytes);
+    }
+
+    #[cfg(feature = "memory-profiling")]
+    #[test]
+    fn test_profiling_allocator() {
+        use std::alloc::{GlobalAlloc, Layout};
+
+        std::thread::spawn(|| {
+            set_current_node("test_profiling_allocator");
+            let layout = Layout::from_size_align(100, 32).unwrap();
+            unsafe {
+                let ptr = ProfilingAllocator.alloc(layout);
+                assert_eq!(ptr as usize % 32, 0);
+                ProfilingAllocator.dealloc(ptr, layout);
+            }
+        })
+        .join()
+        .unwrap();
+
+        let node = node_memory()
+            .into_iter()
+            .find(|node| node.node == "test_profiling_allocator")
+            .unwrap();
+        assert_eq!(node.allocated_bytes, 100);
+        assert_eq!(node.freed_bytes, 100);
+        assert_eq!(node.allocations, 1);
+        assert!(is_enabled());
+    }
+}
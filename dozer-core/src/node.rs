@@ -4,9 +4,11 @@ use crate::executor_operation::ProcessorOperation;
 use crate::processor_record::ProcessorRecordStore;
 
 use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::Provenance;
 use dozer_types::types::Schema;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
 
 pub type PortHandle = u16;
 
@@ -87,6 +89,37 @@ pub trait Processor: Send + Sync + Debug {
         op: ProcessorOperation,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), BoxedError>;
+
+    /// Sends the operations the processor has buffered. Called before every commit.
+    fn flush(
+        &mut self,
+        _record_store: &ProcessorRecordStore,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    /// Called when source `connection_name`, which feeds input `port`, starts its initial snapshot.
+    fn on_source_snapshotting_started(
+        &mut self,
+        _port: PortHandle,
+        _connection_name: &str,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    /// Called when source `connection_name`, which feeds input `port`, finishes its initial snapshot.
+    ///
+    /// Operations sent here go downstream before the notification.
+    fn on_source_snapshotting_done(
+        &mut self,
+        _port: PortHandle,
+        _connection_name: &str,
+        _record_store: &ProcessorRecordStore,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
 }
 
 pub trait SinkFactory<T>: Send + Sync + Debug {
@@ -107,5 +140,16 @@ pub trait Sink: Send + Sync + Debug {
         op: ProcessorOperation,
     ) -> Result<(), BoxedError>;
 
+    /// Processes `op`, caused by the source row `provenance` if the executor records provenance.
+    fn process_with_provenance(
+        &mut self,
+        from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        _provenance: Option<Arc<Provenance>>,
+    ) -> Result<(), BoxedError> {
+        self.process(from_port, record_store, op)
+    }
+
     fn on_source_snapshotting_done(&mut self, connection_name: String) -> Result<(), BoxedError>;
 }
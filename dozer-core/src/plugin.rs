@@ -0,0 +1,91 @@
+//! The public surface for custom Rust operators.
+//!
+//! An operator is a `ProcessorFactory` registered by name in an `OperatorRegistry`. Operators listed in the
+//! `operators` section of the config are built from the registry and inserted after the SQL transformations,
+//! reading a source, SQL output or earlier operator table. Custom sources are added with `AppSourceManager::add`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+
+use dozer_types::errors::internal::BoxedError;
+
+pub use crate::appsource::{AppSourceManager, AppSourceMappings};
+pub use crate::channels::{ProcessorChannelForwarder, SourceChannelForwarder};
+pub use crate::epoch::Epoch;
+pub use crate::errors::ExecutionError;
+pub use crate::executor_operation::ProcessorOperation;
+pub use crate::node::{
+    OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Sink, SinkFactory,
+    Source, SourceFactory,
+};
+pub use crate::processor_record::{ProcessorRecord, ProcessorRecordStore};
+pub use crate::DEFAULT_PORT_HANDLE;
+
+/// Options of an operator, as written in the config.
+pub type OperatorOptions = BTreeMap<String, String>;
+
+/// Builds an operator's processor factory from the id of its node and its options.
+///
+/// The factory reads `DEFAULT_PORT_HANDLE` and its output on `DEFAULT_PORT_HANDLE` becomes the operator's table.
+pub type OperatorConstructor<T> = Box<
+    dyn Fn(&str, &OperatorOptions) -> Result<Box<dyn ProcessorFactory<T>>, BoxedError>
+        + Send
+        + Sync,
+>;
+
+/// Operators available to the config, by name.
+pub struct OperatorRegistry<T> {
+    constructors: HashMap<String, OperatorConstructor<T>>,
+}
+
+impl<T> Default for OperatorRegistry<T> {
+    fn default() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Debug for OperatorRegistry<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperatorRegistry")
+            .field("operators", &self.constructors.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<T> OperatorRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        name: String,
+        constructor: OperatorConstructor<T>,
+    ) -> Result<(), ExecutionError> {
+        if self.constructors.contains_key(&name) {
+            return Err(ExecutionError::OperatorAlreadyRegistered(name));
+        }
+        self.constructors.insert(name, constructor);
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+
+    /// Builds the processor factory of a node with `id`, running operator `name`.
+    pub fn build(
+        &self,
+        name: &str,
+        id: &str,
+        options: &OperatorOptions,
+    ) -> Result<Box<dyn ProcessorFactory<T>>, ExecutionError> {
+        let constructor = self
+            .constructors
+            .get(name)
+            .ok_or_else(|| ExecutionError::UnknownOperator(name.to_string()))?;
+        constructor(id, options).map_err(ExecutionError::Factory)
+    }
+}
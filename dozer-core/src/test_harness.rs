@@ -0,0 +1,383 @@
+//! Deterministic replay of scripted operations through a single processor, for testing processor implementations.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::SourceStates;
+use dozer_types::types::{FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition};
+
+use crate::channels::ProcessorChannelForwarder;
+use crate::epoch::Epoch;
+use crate::executor_operation::ProcessorOperation;
+use crate::node::{PortHandle, Processor, ProcessorFactory};
+use crate::processor_record::ProcessorRecordStore;
+use crate::DEFAULT_PORT_HANDLE;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A step of a script fed into a processor.
+pub enum ScriptStep {
+    /// Sends an operation to an input port.
+    Op { port: PortHandle, op: Operation },
+    /// Flushes the processor and commits an epoch.
+    Commit,
+    /// Notifies the processor that a source feeding input `port` started its initial snapshot.
+    SnapshottingStarted {
+        port: PortHandle,
+        connection_name: String,
+    },
+    /// Notifies the processor that a source feeding input `port` finished its initial snapshot.
+    SnapshottingDone {
+        port: PortHandle,
+        connection_name: String,
+    },
+    /// Rebuilds the processor from its factory, as after a pipeline restart.
+    ///
+    /// Processors don't persist their state, so the rebuilt processor is fed the operations and snapshotting
+    /// notifications committed so far, epoch by epoch, without recording its output, which downstream nodes have
+    /// already seen. Steps after the last commit are dropped, like the executor does; sources deliver them again.
+    Restart,
+}
+
+impl ScriptStep {
+    pub fn insert(port: PortHandle, new: Record) -> Self {
+        Self::Op {
+            port,
+            op: Operation::Insert { new },
+        }
+    }
+
+    pub fn update(port: PortHandle, old: Record, new: Record) -> Self {
+        Self::Op {
+            port,
+            op: Operation::Update { old, new },
+        }
+    }
+
+    pub fn delete(port: PortHandle, old: Record) -> Self {
+        Self::Op {
+            port,
+            op: Operation::Delete { old },
+        }
+    }
+
+    pub fn snapshotting_started(port: PortHandle, connection_name: &str) -> Self {
+        Self::SnapshottingStarted {
+            port,
+            connection_name: connection_name.to_string(),
+        }
+    }
+
+    pub fn snapshotting_done(port: PortHandle, connection_name: &str) -> Self {
+        Self::SnapshottingDone {
+            port,
+            connection_name: connection_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An operation the processor sent to an output port.
+pub struct Emitted {
+    pub port: PortHandle,
+    pub op: Operation,
+}
+
+impl Emitted {
+    pub fn insert(port: PortHandle, new: Record) -> Self {
+        Self {
+            port,
+            op: Operation::Insert { new },
+        }
+    }
+
+    pub fn update(port: PortHandle, old: Record, new: Record) -> Self {
+        Self {
+            port,
+            op: Operation::Update { old, new },
+        }
+    }
+
+    pub fn delete(port: PortHandle, old: Record) -> Self {
+        Self {
+            port,
+            op: Operation::Delete { old },
+        }
+    }
+}
+
+/// A schema of non-nullable columns from a dynamic source, with the columns at `primary_index` as its primary key.
+pub fn schema(columns: &[(&str, FieldType)], primary_index: &[usize]) -> Schema {
+    let mut schema = Schema::new();
+    for (index, (name, typ)) in columns.iter().enumerate() {
+        schema.field(
+            FieldDefinition::new(name.to_string(), *typ, false, SourceDefinition::Dynamic),
+            primary_index.contains(&index),
+        );
+    }
+    schema
+}
+
+/// Feeds scripted operations, commits and restarts into a processor built from `factory`, recording its output.
+///
+/// Epochs get consecutive ids starting from 0 and deterministic decision instants, so runs are reproducible.
+#[derive(Debug)]
+pub struct ProcessorTestHarness<'a, T> {
+    factory: &'a dyn ProcessorFactory<T>,
+    input_schemas: HashMap<PortHandle, Schema>,
+    output_schemas: HashMap<PortHandle, Schema>,
+    record_store: ProcessorRecordStore,
+    processor: Box<dyn Processor>,
+    next_epoch_id: u64,
+    /// Operations and snapshotting notifications of every epoch committed since the start, replayed on restart.
+    committed: Vec<Vec<ScriptStep>>,
+    uncommitted: Vec<ScriptStep>,
+}
+
+impl<'a, T> ProcessorTestHarness<'a, T> {
+    pub fn new(
+        factory: &'a dyn ProcessorFactory<T>,
+        input_schemas: HashMap<PortHandle, (Schema, T)>,
+    ) -> Result<Self, BoxedError> {
+        let mut output_schemas = HashMap::new();
+        for port in factory.get_output_ports() {
+            let (schema, _) = factory.get_output_schema(&port.handle, &input_schemas)?;
+            output_schemas.insert(port.handle, schema);
+        }
+        let input_schemas = input_schemas
+            .into_iter()
+            .map(|(port, (schema, _))| (port, schema))
+            .collect::<HashMap<_, _>>();
+
+        let record_store = ProcessorRecordStore::new()?;
+        let processor =
+            factory.build(input_schemas.clone(), output_schemas.clone(), &record_store)?;
+        Ok(Self {
+            factory,
+            input_schemas,
+            output_schemas,
+            record_store,
+            processor,
+            next_epoch_id: 0,
+            committed: vec![],
+            uncommitted: vec![],
+        })
+    }
+
+    /// Builds the processor of a factory with a single input on `DEFAULT_PORT_HANDLE`.
+    pub fn single_input(
+        factory: &'a dyn ProcessorFactory<T>,
+        schema: Schema,
+        context: T,
+    ) -> Result<Self, BoxedError> {
+        Self::new(
+            factory,
+            HashMap::from([(DEFAULT_PORT_HANDLE, (schema, context))]),
+        )
+    }
+
+    pub fn output_schema(&self, port: PortHandle) -> Option<&Schema> {
+        self.output_schemas.get(&port)
+    }
+
+    /// Sends `op` to input `port`, returning what the processor emitted.
+    pub fn process(&mut self, port: PortHandle, op: Operation) -> Result<Vec<Emitted>, BoxedError> {
+        let emitted = process(
+            self.processor.as_mut(),
+            &self.record_store,
+            port,
+            op.clone(),
+        )?;
+        self.uncommitted.push(ScriptStep::Op { port, op });
+        Ok(emitted)
+    }
+
+    /// Notifies the processor that source `connection_name` feeding input `port` started its initial snapshot.
+    pub fn snapshotting_started(
+        &mut self,
+        port: PortHandle,
+        connection_name: &str,
+    ) -> Result<(), BoxedError> {
+        self.processor
+            .on_source_snapshotting_started(port, connection_name)?;
+        self.uncommitted.push(ScriptStep::SnapshottingStarted {
+            port,
+            connection_name: connection_name.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Notifies the processor that source `connection_name` feeding input `port` finished its initial snapshot,
+    /// returning what the processor emitted.
+    pub fn snapshotting_done(
+        &mut self,
+        port: PortHandle,
+        connection_name: &str,
+    ) -> Result<Vec<Emitted>, BoxedError> {
+        let emitted = snapshotting_done(
+            self.processor.as_mut(),
+            &self.record_store,
+            port,
+            connection_name,
+        )?;
+        self.uncommitted.push(ScriptStep::SnapshottingDone {
+            port,
+            connection_name: connection_name.to_string(),
+        });
+        Ok(emitted)
+    }
+
+    /// Flushes the processor and commits an epoch, returning what the processor emitted when flushed.
+    pub fn commit(&mut self) -> Result<Vec<Emitted>, BoxedError> {
+        let emitted = flush(self.processor.as_mut(), &self.record_store)?;
+        let epoch = Epoch::new(
+            self.next_epoch_id,
+            SourceStates::new(),
+            None,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.next_epoch_id),
+        );
+        self.processor.commit(&epoch)?;
+        self.next_epoch_id += 1;
+        self.committed.push(std::mem::take(&mut self.uncommitted));
+        Ok(emitted)
+    }
+
+    /// See `ScriptStep::Restart`.
+    pub fn restart(&mut self) -> Result<(), BoxedError> {
+        self.uncommitted.clear();
+        self.record_store = ProcessorRecordStore::new()?;
+        self.processor = self.factory.build(
+            self.input_schemas.clone(),
+            self.output_schemas.clone(),
+            &self.record_store,
+        )?;
+        for epoch in &self.committed {
+            for step in epoch {
+                match step {
+                    ScriptStep::Op { port, op } => {
+                        process(
+                            self.processor.as_mut(),
+                            &self.record_store,
+                            *port,
+                            op.clone(),
+                        )?;
+                    }
+                    ScriptStep::SnapshottingStarted {
+                        port,
+                        connection_name,
+                    } => {
+                        self.processor
+                            .on_source_snapshotting_started(*port, connection_name)?;
+                    }
+                    ScriptStep::SnapshottingDone {
+                        port,
+                        connection_name,
+                    } => {
+                        snapshotting_done(
+                            self.processor.as_mut(),
+                            &self.record_store,
+                            *port,
+                            connection_name,
+                        )?;
+                    }
+                    ScriptStep::Commit | ScriptStep::Restart => {
+                        unreachable!("Only operations and notifications are committed")
+                    }
+                }
+            }
+            flush(self.processor.as_mut(), &self.record_store)?;
+        }
+        Ok(())
+    }
+
+    /// Runs `script`, returning everything the processor emitted in order.
+    pub fn run(
+        &mut self,
+        script: impl IntoIterator<Item = ScriptStep>,
+    ) -> Result<Vec<Emitted>, BoxedError> {
+        let mut emitted = vec![];
+        for step in script {
+            match step {
+                ScriptStep::Op { port, op } => emitted.extend(self.process(port, op)?),
+                ScriptStep::Commit => emitted.extend(self.commit()?),
+                ScriptStep::SnapshottingStarted {
+                    port,
+                    connection_name,
+                } => self.snapshotting_started(port, &connection_name)?,
+                ScriptStep::SnapshottingDone {
+                    port,
+                    connection_name,
+                } => emitted.extend(self.snapshotting_done(port, &connection_name)?),
+                ScriptStep::Restart => self.restart()?,
+            }
+        }
+        Ok(emitted)
+    }
+
+    /// Runs `script` and panics if the processor didn't emit exactly `expected`.
+    pub fn assert_output(
+        &mut self,
+        script: impl IntoIterator<Item = ScriptStep>,
+        expected: &[Emitted],
+    ) {
+        let emitted = self.run(script).expect("Failed to run script");
+        assert_eq!(emitted, expected, "Unexpected processor output");
+    }
+}
+
+fn process(
+    processor: &mut dyn Processor,
+    record_store: &ProcessorRecordStore,
+    port: PortHandle,
+    op: Operation,
+) -> Result<Vec<Emitted>, BoxedError> {
+    let op = record_store.create_operation(&op)?;
+    let mut forwarder = RecordingForwarder::default();
+    processor.process(port, record_store, op, &mut forwarder)?;
+    forwarder.emitted(record_store)
+}
+
+fn snapshotting_done(
+    processor: &mut dyn Processor,
+    record_store: &ProcessorRecordStore,
+    port: PortHandle,
+    connection_name: &str,
+) -> Result<Vec<Emitted>, BoxedError> {
+    let mut forwarder = RecordingForwarder::default();
+    processor.on_source_snapshotting_done(port, connection_name, record_store, &mut forwarder)?;
+    forwarder.emitted(record_store)
+}
+
+fn flush(
+    processor: &mut dyn Processor,
+    record_store: &ProcessorRecordStore,
+) -> Result<Vec<Emitted>, BoxedError> {
+    let mut forwarder = RecordingForwarder::default();
+    processor.flush(record_store, &mut forwarder)?;
+    forwarder.emitted(record_store)
+}
+
+#[derive(Debug, Default)]
+struct RecordingForwarder {
+    ops: Vec<(PortHandle, ProcessorOperation)>,
+}
+
+impl RecordingForwarder {
+    fn emitted(self, record_store: &ProcessorRecordStore) -> Result<Vec<Emitted>, BoxedError> {
+        self.ops
+            .into_iter()
+            .map(|(port, op)| {
+                Ok(Emitted {
+                    port,
+                    op: record_store.load_operation(&op)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl ProcessorChannelForwarder for RecordingForwarder {
+    fn send(&mut self, op: ProcessorOperation, port: PortHandle) {
+        self.ops.push((port, op));
+    }
+}
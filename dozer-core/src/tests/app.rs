@@ -130,6 +130,59 @@ fn test_apps_source_manager_lookup_multiple_ports() {
     assert_eq!(r.port, 2_u16);
 }
 
+#[test]
+fn test_apps_source_manager_lookup_ignores_case() {
+    let mut asm = AppSourceManager::new();
+    asm.add(
+        Box::new(NoneSourceFactory {}),
+        AppSourceMappings::new(
+            "conn1".to_string(),
+            vec![("Table1".to_string(), 1_u16), ("table1".to_string(), 2_u16)]
+                .into_iter()
+                .collect(),
+        ),
+    )
+    .unwrap();
+    asm.add(
+        Box::new(NoneSourceFactory {}),
+        AppSourceMappings::new(
+            "conn2".to_string(),
+            vec![("Table2".to_string(), 3_u16)].into_iter().collect(),
+        ),
+    )
+    .unwrap();
+
+    let r = asm.get_endpoint("TABLE2").unwrap();
+    assert_eq!(r.node.id, "conn2");
+    assert_eq!(r.port, 3_u16);
+
+    // Sources spelled exactly like the name win.
+    let r = asm.get_endpoint("Table1").unwrap();
+    assert_eq!(r.port, 1_u16);
+
+    let r = asm.get_endpoint("TABLE1");
+    assert!(r.is_err());
+}
+
+#[test]
+fn test_apps_source_manager_lookup_case_sensitive() {
+    let mut asm = AppSourceManager::new().with_case_sensitive(true);
+    asm.add(
+        Box::new(NoneSourceFactory {}),
+        AppSourceMappings::new(
+            "conn1".to_string(),
+            vec![("Table1".to_string(), 1_u16)].into_iter().collect(),
+        ),
+    )
+    .unwrap();
+
+    let r = asm.get_endpoint("Table1").unwrap();
+    assert_eq!(r.port, 1_u16);
+
+    let r = asm.get_endpoint("TABLE1");
+    assert!(r.is_err());
+}
+
 #[test]
 fn test_app_dag() {
     let latch = Arc::new(AtomicBool::new(true));
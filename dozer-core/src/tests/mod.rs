@@ -8,3 +8,4 @@ mod dag_schemas;
 pub mod processors;
 pub mod sinks;
 pub mod sources;
+mod test_harness;
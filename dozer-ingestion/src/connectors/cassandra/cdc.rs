@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use dozer_types::types::{Field, Operation, Record};
+use scylla::frame::response::result::{CqlValue, Row};
+use scylla::transport::errors::{DbError, QueryError};
+use scylla::Session;
+
+use super::schema::Table;
+use crate::errors::CassandraError;
+
+/// The columns of CDC log tables selected before the columns of the table.
+pub const LOG_COLUMNS: &str =
+    "\"cdc$stream_id\", \"cdc$time\", \"cdc$batch_seq_no\", \"cdc$operation\"";
+
+/// Values of `cdc$operation`.
+const PRE_IMAGE: i8 = 0;
+const UPDATE: i8 = 1;
+const INSERT: i8 = 2;
+const ROW_DELETE: i8 = 3;
+const PARTITION_DELETE: i8 = 4;
+const POST_IMAGE: i8 = 9;
+
+/// A row of a CDC log table. The rows written by a statement have the same stream and time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRow {
+    pub stream_id: Vec<u8>,
+    pub time: CqlValue,
+    pub operation: i8,
+    /// The values of the requested columns of the table.
+    pub values: Vec<Option<CqlValue>>,
+}
+
+impl LogRow {
+    /// Reads a row selected with `LOG_COLUMNS` first.
+    pub fn from_row(row: Row) -> Result<Self, CassandraError> {
+        let invalid = || CassandraError::UnexpectedResult("invalid CDC log row".to_string());
+        let mut columns = row.columns.into_iter();
+        let Some(Some(CqlValue::Blob(stream_id))) = columns.next() else {
+            return Err(invalid());
+        };
+        let Some(Some(time)) = columns.next() else {
+            return Err(invalid());
+        };
+        columns.next();
+        let Some(Some(CqlValue::TinyInt(operation))) = columns.next() else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            stream_id,
+            time,
+            operation,
+            values: columns.collect(),
+        })
+    }
+}
+
+/// What a statement did to a row: its log rows of each kind.
+#[derive(Debug)]
+struct RowChange {
+    key: Vec<Field>,
+    pre_image: bool,
+    operations: Vec<i8>,
+    /// The last change of the row, which has its key.
+    delta: Option<Record>,
+    post_image: Option<Record>,
+}
+
+/// Splits log rows, in the order they were read, into the rows of each statement.
+pub fn group_statements(rows: Vec<LogRow>) -> Vec<Vec<LogRow>> {
+    let mut statements: Vec<Vec<LogRow>> = vec![];
+    for row in rows {
+        match statements.last_mut() {
+            Some(statement)
+                if statement[0].stream_id == row.stream_id && statement[0].time == row.time =>
+            {
+                statement.push(row)
+            }
+            _ => statements.push(vec![row]),
+        }
+    }
+    statements
+}
+
+/// Maps the log rows of a statement to operations, one per row it changed. Inserts and updates are told apart by the
+/// pre-image, which only rows that existed before have, and carry the post-image. Their old records, and the ones of
+/// deletes, only have the primary key.
+pub fn statement_operations(
+    table: &Table,
+    rows: Vec<LogRow>,
+) -> Result<Vec<Operation>, CassandraError> {
+    let mut changes: Vec<RowChange> = vec![];
+    for row in rows {
+        let record = table.record(row.values)?;
+        let key = table
+            .primary_index
+            .iter()
+            .map(|index| record.values[*index].clone())
+            .collect::<Vec<_>>();
+        let index = match changes.iter().position(|change| change.key == key) {
+            Some(index) => index,
+            None => {
+                changes.push(RowChange {
+                    key,
+                    pre_image: false,
+                    operations: vec![],
+                    delta: None,
+                    post_image: None,
+                });
+                changes.len() - 1
+            }
+        };
+        let change = &mut changes[index];
+        match row.operation {
+            PRE_IMAGE => change.pre_image = true,
+            POST_IMAGE => change.post_image = Some(record),
+            operation => {
+                change.operations.push(operation);
+                change.delta = Some(record);
+            }
+        }
+    }
+
+    let mut operations = vec![];
+    for change in changes {
+        let Some(delta) = &change.delta else {
+            continue;
+        };
+        // Deletes of ranges of rows, or of partitions with many rows, can't be mapped to the deleted rows.
+        if change.operations.iter().any(|operation| {
+            *operation > PARTITION_DELETE
+                || (*operation == PARTITION_DELETE && table.has_clustering_key())
+        }) {
+            return Err(CassandraError::UnsupportedDelete(table.name.clone()));
+        }
+        if change
+            .operations
+            .iter()
+            .any(|operation| *operation == UPDATE || *operation == INSERT)
+        {
+            let new = change
+                .post_image
+                .ok_or_else(|| CassandraError::MissingPostImage(table.name.clone()))?;
+            operations.push(if change.pre_image {
+                Operation::Update {
+                    old: table.key_record(&new),
+                    new,
+                }
+            } else {
+                Operation::Insert { new }
+            });
+        } else if change
+            .operations
+            .iter()
+            .any(|operation| *operation == ROW_DELETE || *operation == PARTITION_DELETE)
+        {
+            operations.push(Operation::Delete {
+                old: table.key_record(delta),
+            });
+        }
+    }
+    Ok(operations)
+}
+
+/// Checks that CDC is enabled on a table with pre-images and post-images, which only ScyllaDB supports.
+pub async fn check_cdc_options(session: &Session, table: &Table) -> Result<(), CassandraError> {
+    let result = match session
+        .query(
+            "SELECT cdc FROM system_schema.scylla_tables WHERE keyspace_name = ? AND table_name = ?",
+            (&table.keyspace, &table.name),
+        )
+        .await
+    {
+        Ok(result) => result,
+        // Cassandra has no `scylla_tables`.
+        Err(QueryError::DbError(DbError::Invalid, _)) => {
+            return Err(CassandraError::CdcNotSupported)
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let options = result
+        .maybe_first_row_typed::<(Option<HashMap<String, String>>,)>()
+        .map_err(|e| CassandraError::UnexpectedResult(e.to_string()))?
+        .and_then(|(options,)| options)
+        .unwrap_or_default();
+    if has_images(&options) {
+        Ok(())
+    } else {
+        Err(CassandraError::CdcNotEnabled(table.name.clone()))
+    }
+}
+
+fn has_images(options: &HashMap<String, String>) -> bool {
+    let option = |name: &str| options.get(name).map(String::as_str);
+    option("enabled") == Some("true")
+        && matches!(option("preimage"), Some("true" | "full"))
+        && option("postimage") == Some("true")
+}
+
+/// The times of the CDC generations, as milliseconds since the Unix epoch, in order. A generation has the streams
+/// changes are written to from its time until the next generation's.
+pub async fn generation_times(session: &Session) -> Result<Vec<i64>, CassandraError> {
+    let rows = session
+        .query(
+            "SELECT time FROM system_distributed.cdc_generation_timestamps WHERE key = 'timestamps'",
+            (),
+        )
+        .await?
+        .rows
+        .unwrap_or_default();
+    let mut times = rows
+        .into_iter()
+        .map(|row| match row.columns.into_iter().next() {
+            Some(Some(CqlValue::Timestamp(time))) => Ok(time.num_milliseconds()),
+            _ => Err(CassandraError::UnexpectedResult(
+                "invalid CDC generation".to_string(),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    times.sort();
+    Ok(times)
+}
+
+/// The streams of a generation, grouped by the token range they are in.
+pub async fn generation_streams(
+    session: &Session,
+    time: i64,
+) -> Result<Vec<Vec<Vec<u8>>>, CassandraError> {
+    let rows = session
+        .query(
+            format!(
+                "SELECT streams FROM system_distributed.cdc_streams_descriptions_v2 WHERE time = {time}"
+            ),
+            (),
+        )
+        .await?
+        .rows
+        .unwrap_or_default();
+    rows.into_iter()
+        .map(|row| match row.columns.into_iter().next() {
+            Some(Some(CqlValue::Set(streams))) => streams
+                .into_iter()
+                .map(|stream| match stream {
+                    CqlValue::Blob(stream_id) => Ok(stream_id),
+                    _ => Err(CassandraError::UnexpectedResult(
+                        "invalid CDC stream".to_string(),
+                    )),
+                })
+                .collect(),
+            _ => Err(CassandraError::UnexpectedResult(
+                "invalid CDC streams".to_string(),
+            )),
+        })
+        .collect()
+}
+
+/// The query reading the log rows of `streams` written after `from` and up to `to`, in milliseconds since the Unix
+/// epoch.
+pub fn log_query(table: &Table, streams: &[Vec<u8>], from: i64, to: i64) -> String {
+    let streams = streams
+        .iter()
+        .map(|stream_id| {
+            let hex = stream_id
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>();
+            format!("0x{hex}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "SELECT {LOG_COLUMNS}, {} FROM {} WHERE \"cdc$stream_id\" IN ({streams}) \
+            AND \"cdc$time\" > maxTimeuuid({from}) AND \"cdc$time\" <= maxTimeuuid({to})",
+        table.quoted_columns(),
+        table.quoted_log_name()
+    )
+}
+
+/// The window of the generation of `time` to read after `time` and up to `to`: `to`, or the millisecond before the
+/// next generation if it's earlier. `None` if no generation started by then.
+pub fn generation_window(generations: &[i64], time: i64, to: i64) -> Option<(i64, i64)> {
+    let index = generations
+        .iter()
+        .rposition(|generation| *generation <= time + 1)?;
+    let end = generations
+        .get(index + 1)
+        .map_or(to, |next| to.min(next - 1));
+    Some((generations[index], end))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::FieldType;
+
+    use super::super::schema::{Column, ColumnKind};
+    use super::*;
+
+    fn table(clustering: bool) -> Table {
+        let column = |name: &str, kind: ColumnKind| Column {
+            name: name.to_string(),
+            kind,
+            typ: FieldType::Int,
+        };
+        Table {
+            keyspace: "films".to_string(),
+            name: "films".to_string(),
+            columns: vec![
+                column("id", ColumnKind::PartitionKey),
+                column(
+                    "year",
+                    if clustering {
+                        ColumnKind::Clustering
+                    } else {
+                        ColumnKind::Regular
+                    },
+                ),
+                column("rating", ColumnKind::Regular),
+            ],
+            primary_index: if clustering { vec![0, 1] } else { vec![0] },
+        }
+    }
+
+    fn row(time: i32, operation: i8, values: [Option<i32>; 3]) -> LogRow {
+        LogRow {
+            stream_id: vec![1],
+            time: CqlValue::Int(time),
+            operation,
+            values: values
+                .into_iter()
+                .map(|value| value.map(CqlValue::Int))
+                .collect(),
+        }
+    }
+
+    fn record(values: [Option<i64>; 3]) -> Record {
+        Record::new(
+            values
+                .into_iter()
+                .map(|value| value.map_or(Field::Null, Field::Int))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_statement_operations() {
+        let films = table(false);
+        let rows = vec![
+            row(1, UPDATE, [Some(1), None, Some(8)]),
+            row(1, POST_IMAGE, [Some(1), Some(1999), Some(8)]),
+            row(2, PRE_IMAGE, [Some(1), None, Some(8)]),
+            row(2, UPDATE, [Some(1), None, Some(9)]),
+            row(2, POST_IMAGE, [Some(1), Some(1999), Some(9)]),
+            row(3, ROW_DELETE, [Some(1), None, None]),
+            row(4, PARTITION_DELETE, [Some(2), None, None]),
+        ];
+        let operations = group_statements(rows)
+            .into_iter()
+            .map(|statement| statement_operations(&films, statement).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                vec![Operation::Insert {
+                    new: record([Some(1), Some(1999), Some(8)])
+                }],
+                vec![Operation::Update {
+                    old: record([Some(1), None, None]),
+                    new: record([Some(1), Some(1999), Some(9)])
+                }],
+                vec![Operation::Delete {
+                    old: record([Some(1), None, None])
+                }],
+                vec![Operation::Delete {
+                    old: record([Some(2), None, None])
+                }],
+            ]
+        );
+
+        // Partitions of tables with clustering keys can have many rows.
+        assert!(matches!(
+            statement_operations(
+                &table(true),
+                vec![row(4, PARTITION_DELETE, [Some(2), None, None])]
+            ),
+            Err(CassandraError::UnsupportedDelete(_))
+        ));
+        assert!(matches!(
+            statement_operations(&films, vec![row(1, INSERT, [Some(1), None, Some(8)])]),
+            Err(CassandraError::MissingPostImage(_))
+        ));
+    }
+
+    #[test]
+    fn test_has_images() {
+        let options = |preimage: &str| {
+            HashMap::from([
+                ("enabled".to_string(), "true".to_string()),
+                ("preimage".to_string(), preimage.to_string()),
+                ("postimage".to_string(), "true".to_string()),
+            ])
+        };
+        assert!(has_images(&options("true")));
+        assert!(has_images(&options("full")));
+        assert!(!has_images(&options("false")));
+        assert!(!has_images(&HashMap::new()));
+    }
+
+    #[test]
+    fn test_generation_window() {
+        let generations = [1_000, 2_000];
+        assert_eq!(generation_window(&generations, 500, 1_500), None);
+        assert_eq!(
+            generation_window(&generations, 999, 1_500),
+            Some((1_000, 1_500))
+        );
+        assert_eq!(
+            generation_window(&generations, 1_200, 2_500),
+            Some((1_000, 1_999))
+        );
+        assert_eq!(
+            generation_window(&generations, 1_999, 2_500),
+            Some((2_000, 2_500))
+        );
+    }
+
+    #[test]
+    fn test_log_query() {
+        assert_eq!(
+            log_query(&table(true), &[vec![0x0a, 0xff], vec![1]], 1_000, 2_000),
+            "SELECT \"cdc$stream_id\", \"cdc$time\", \"cdc$batch_seq_no\", \"cdc$operation\", \"id\", \"year\", \"rating\" \
+                FROM \"films\".\"films_scylla_cdc_log\" WHERE \"cdc$stream_id\" IN (0x0aff, 0x01) \
+                AND \"cdc$time\" > maxTimeuuid(1000) AND \"cdc$time\" <= maxTimeuuid(2000)"
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{CassandraConfig, IngestionMessage};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation};
+use futures::StreamExt;
+use scylla::frame::response::result::CqlValue;
+use scylla::{Session, SessionBuilder};
+use tokio::sync::mpsc;
+use tonic::async_trait;
+
+use super::cdc::{
+    check_cdc_options, generation_streams, generation_times, generation_window, group_statements,
+    log_query, statement_operations, LogRow,
+};
+use super::schema::{self, Table, TYPES};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{CassandraError, ConnectorError};
+use crate::ingestion::Ingestor;
+
+/// Snapshots the tables of a Cassandra or ScyllaDB keyspace by token ranges in parallel, then follows their changes in
+/// the CDC log tables of ScyllaDB.
+#[derive(Debug)]
+pub struct CassandraConnector {
+    name: String,
+    config: CassandraConfig,
+}
+
+impl CassandraConnector {
+    pub fn new(name: String, config: CassandraConfig) -> Self {
+        Self { name, config }
+    }
+
+    async fn session(&self) -> Result<Session, CassandraError> {
+        let mut builder = SessionBuilder::new().known_nodes(self.config.hosts.as_slice());
+        if let Some(user) = &self.config.user {
+            builder = builder.user(user, self.config.password.as_deref().unwrap_or_default());
+        }
+        Ok(builder.build().await?)
+    }
+
+    fn check_keyspace(&self, table: &TableIdentifier) -> Result<(), ConnectorError> {
+        match &table.schema {
+            Some(keyspace) if keyspace != &self.config.keyspace => Err(
+                ConnectorError::TableNotFound(table_name(Some(keyspace), &table.name)),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        if self.config.snapshot_token_ranges == 0 || self.config.snapshot_parallelism == 0 {
+            return Err(CassandraError::InvalidSnapshotParallelism.into());
+        }
+        let session = Arc::new(self.session().await?);
+        let mut source_tables = vec![];
+        for table in &tables {
+            let table = schema::get_table(
+                &session,
+                &self.config.keyspace,
+                &table.name,
+                &table.column_names,
+            )
+            .await?;
+            if self.config.cdc {
+                check_cdc_options(&session, &table).await?;
+            }
+            source_tables.push(table);
+        }
+
+        // Changes are read from when the snapshot started, by the clock of the cluster.
+        let mut read_up_to = server_time(&session).await?;
+        let mut seq_no = 0;
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+        for (table_index, table) in source_tables.iter().enumerate() {
+            self.snapshot(&session, ingestor, table_index, table, &mut seq_no)
+                .await?;
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        if !self.config.cdc {
+            return Ok(());
+        }
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        let mut generation: Option<(i64, Vec<Vec<Vec<u8>>>)> = None;
+        loop {
+            // Changes can be written to the log after the time they have, so they are read once they are old enough.
+            let to = server_time(&session).await? - self.config.cdc_delay_ms as i64;
+            if to <= read_up_to {
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            }
+            let generations = generation_times(&session).await?;
+            let Some((generation_time, to)) = generation_window(&generations, read_up_to, to)
+            else {
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            };
+            if generation.as_ref().map(|(time, _)| *time) != Some(generation_time) {
+                info!(
+                    "[{}] Reading the streams of CDC generation {}",
+                    self.name, generation_time
+                );
+                generation = Some((
+                    generation_time,
+                    generation_streams(&session, generation_time).await?,
+                ));
+            }
+            let streams = generation.as_ref().map_or(&[][..], |(_, streams)| streams);
+
+            for (table_index, table) in source_tables.iter().enumerate() {
+                for stream_ids in streams {
+                    let mut rows = session
+                        .query_iter(log_query(table, stream_ids, read_up_to, to), ())
+                        .await
+                        .map_err(CassandraError::from)?;
+                    let mut log_rows = vec![];
+                    while let Some(row) = rows.next().await {
+                        log_rows.push(LogRow::from_row(row.map_err(CassandraError::from)?)?);
+                    }
+                    for statement in group_statements(log_rows) {
+                        for op in statement_operations(table, statement)? {
+                            seq_no += 1;
+                            ingestor
+                                .handle_message(IngestionMessage::new_op(
+                                    0,
+                                    seq_no,
+                                    table_index,
+                                    op,
+                                ))
+                                .map_err(ConnectorError::IngestorError)?;
+                        }
+                    }
+                }
+            }
+            read_up_to = to;
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Inserts the rows of a table, reading its token ranges in parallel.
+    async fn snapshot(
+        &self,
+        session: &Arc<Session>,
+        ingestor: &Ingestor,
+        table_index: usize,
+        table: &Table,
+        seq_no: &mut u64,
+    ) -> Result<(), ConnectorError> {
+        let ranges = token_ranges(self.config.snapshot_token_ranges);
+        let parallelism = self.config.snapshot_parallelism as usize;
+        let query = format!(
+            "SELECT {} FROM {} WHERE token({key}) >= ? AND token({key}) <= ?",
+            table.quoted_columns(),
+            table.quoted_name(),
+            key = table.quoted_partition_key()
+        );
+        let (sender, mut receiver) = mpsc::channel(parallelism);
+        for worker in 0..parallelism {
+            let session = session.clone();
+            let table = table.clone();
+            let query = query.clone();
+            let ranges = ranges
+                .iter()
+                .copied()
+                .skip(worker)
+                .step_by(parallelism)
+                .collect::<Vec<_>>();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                for range in ranges {
+                    let mut rows = match session.query_iter(query.as_str(), range).await {
+                        Ok(rows) => rows,
+                        Err(e) => {
+                            let _ = sender.send(Err(e.into())).await;
+                            return;
+                        }
+                    };
+                    while let Some(row) = rows.next().await {
+                        let record = row
+                            .map_err(CassandraError::from)
+                            .and_then(|row| table.record(row.columns));
+                        // The receiver is dropped if another range failed.
+                        if sender.send(record).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        info!("[{}] Snapshotting table {}", self.name, table.name);
+        let mut count = 0;
+        while let Some(record) = receiver.recv().await {
+            *seq_no += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    0,
+                    *seq_no,
+                    table_index,
+                    Operation::Insert { new: record? },
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+            count += 1;
+        }
+        info!(
+            "[{}] Snapshotted {} rows of table {}",
+            self.name, count, table.name
+        );
+        Ok(())
+    }
+}
+
+/// Splits the token ring of the Murmur3 partitioner into `count` ranges of about the same size, with inclusive bounds.
+fn token_ranges(count: u32) -> Vec<(i64, i64)> {
+    let count = count as i128;
+    let min = i64::MIN as i128;
+    let size = i64::MAX as i128 - min + 1;
+    (0..count)
+        .map(|index| {
+            let start = min + size * index / count;
+            let end = min + size * (index + 1) / count - 1;
+            (start as i64, end as i64)
+        })
+        .collect()
+}
+
+/// The time of the cluster, in milliseconds since the Unix epoch.
+async fn server_time(session: &Session) -> Result<i64, CassandraError> {
+    let row = session
+        .query("SELECT toUnixTimestamp(now()) FROM system.local", ())
+        .await?
+        .rows
+        .and_then(|rows| rows.into_iter().next());
+    match row.and_then(|row| row.columns.into_iter().next()) {
+        Some(Some(CqlValue::BigInt(time))) => Ok(time),
+        _ => Err(CassandraError::UnexpectedResult(
+            "invalid server time".to_string(),
+        )),
+    }
+}
+
+#[async_trait]
+impl Connector for CassandraConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .chain(
+                ["list", "set", "map", "tuple", "frozen"]
+                    .into_iter()
+                    .map(|name| (name.to_string(), Some(FieldType::Json))),
+            )
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let session = self.session().await?;
+        server_time(&session).await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let session = self.session().await?;
+        Ok(schema::list_tables(&session, &self.config.keyspace)
+            .await?
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let session = self.session().await?;
+        for table in tables {
+            self.check_keyspace(table)?;
+            let table =
+                schema::get_table(&session, &self.config.keyspace, &table.name, &[]).await?;
+            if self.config.cdc {
+                check_cdc_options(&session, &table).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let session = self.session().await?;
+        let mut table_infos = vec![];
+        for table in tables {
+            self.check_keyspace(&table)?;
+            let columns =
+                schema::list_columns(&session, &self.config.keyspace, &table.name).await?;
+            if columns.is_empty() {
+                return Err(ConnectorError::TableNotFound(table.name));
+            }
+            table_infos.push(TableInfo {
+                column_names: columns.into_iter().map(|column| column.name).collect(),
+                schema: table.schema,
+                name: table.name,
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let session = self.session().await?;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            let schema = match self.check_keyspace(&TableIdentifier::new(
+                table_info.schema.clone(),
+                table_info.name.clone(),
+            )) {
+                // Updates and deletes only carry the primary key of the old row.
+                Ok(()) => schema::get_table(
+                    &session,
+                    &self.config.keyspace,
+                    &table_info.name,
+                    &table_info.column_names,
+                )
+                .await
+                .map(|table| SourceSchema::new(table.schema(), CdcType::OnlyPK))
+                .map_err(ConnectorError::from),
+                Err(e) => Err(e),
+            };
+            schemas.push(schema);
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_ranges() {
+        assert_eq!(token_ranges(1), vec![(i64::MIN, i64::MAX)]);
+        let ranges = token_ranges(4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0], (i64::MIN, -(1 << 62) - 1));
+        assert_eq!(ranges[1], (-(1 << 62), -1));
+        assert_eq!(ranges[3], ((1 << 62), i64::MAX));
+        for window in ranges.windows(2) {
+            assert_eq!(window[0].1 + 1, window[1].0);
+        }
+    }
+}
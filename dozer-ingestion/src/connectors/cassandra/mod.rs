@@ -0,0 +1,8 @@
+//! Cassandra and ScyllaDB keyspaces, whose tables are snapshotted by token ranges in parallel and then followed by
+//! polling the CDC log tables of ScyllaDB. CQL types are mapped to field types, and collections to json.
+
+mod cdc;
+mod connector;
+mod schema;
+
+pub use connector::CassandraConnector;
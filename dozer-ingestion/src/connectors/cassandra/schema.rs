@@ -0,0 +1,577 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json::{Map, Value};
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition, TimeUnit,
+};
+use scylla::frame::response::result::CqlValue;
+use scylla::Session;
+
+use crate::errors::CassandraError;
+
+/// CQL types and the types they are mapped to. Collections, tuples and user defined types are mapped to json.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("tinyint", FieldType::Int),
+    ("smallint", FieldType::Int),
+    ("int", FieldType::Int),
+    ("bigint", FieldType::Int),
+    ("counter", FieldType::Int),
+    ("varint", FieldType::I128),
+    ("float", FieldType::Float),
+    ("double", FieldType::Float),
+    ("decimal", FieldType::Decimal),
+    ("boolean", FieldType::Boolean),
+    ("ascii", FieldType::String),
+    ("text", FieldType::String),
+    ("varchar", FieldType::String),
+    ("inet", FieldType::String),
+    ("uuid", FieldType::String),
+    ("timeuuid", FieldType::String),
+    ("duration", FieldType::String),
+    ("blob", FieldType::Binary),
+    ("timestamp", FieldType::Timestamp),
+    ("date", FieldType::Date),
+    ("time", FieldType::Duration),
+];
+
+/// The kind of a column, as in `system_schema.columns.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColumnKind {
+    PartitionKey,
+    Clustering,
+    /// A regular or a static column.
+    Regular,
+}
+
+/// A column of a source table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub kind: ColumnKind,
+    pub typ: FieldType,
+}
+
+impl Column {
+    /// Converts a value of the snapshot or the CDC log.
+    pub fn convert(&self, value: Option<CqlValue>) -> Result<Field, CassandraError> {
+        let invalid = |value: &dyn std::fmt::Debug| {
+            CassandraError::InvalidValue(self.name.clone(), format!("{value:?}"))
+        };
+        let Some(value) = value else {
+            return Ok(Field::Null);
+        };
+        Ok(match (self.typ, value) {
+            (_, CqlValue::Empty) => Field::Null,
+            (FieldType::Int, CqlValue::TinyInt(value)) => Field::Int(value as i64),
+            (FieldType::Int, CqlValue::SmallInt(value)) => Field::Int(value as i64),
+            (FieldType::Int, CqlValue::Int(value)) => Field::Int(value as i64),
+            (FieldType::Int, CqlValue::BigInt(value)) => Field::Int(value),
+            (FieldType::Int, CqlValue::Counter(counter)) => Field::Int(counter.0),
+            (FieldType::I128, CqlValue::Varint(varint)) => {
+                Field::I128(varint.to_string().parse().map_err(|_| invalid(&varint))?)
+            }
+            (FieldType::Float, CqlValue::Float(value)) => Field::Float(OrderedFloat(value as f64)),
+            (FieldType::Float, CqlValue::Double(value)) => Field::Float(OrderedFloat(value)),
+            (FieldType::Decimal, CqlValue::Decimal(decimal)) => Field::Decimal(
+                Decimal::from_str(&decimal.to_string()).map_err(|_| invalid(&decimal))?,
+            ),
+            (FieldType::Boolean, CqlValue::Boolean(value)) => Field::Boolean(value),
+            (FieldType::String, CqlValue::Ascii(value) | CqlValue::Text(value)) => {
+                Field::String(value)
+            }
+            (FieldType::String, CqlValue::Inet(address)) => Field::String(address.to_string()),
+            (FieldType::String, CqlValue::Uuid(uuid) | CqlValue::Timeuuid(uuid)) => {
+                Field::String(uuid.to_string())
+            }
+            (FieldType::String, CqlValue::Duration(duration)) => Field::String(format!(
+                "{}mo{}d{}ns",
+                duration.months, duration.days, duration.nanoseconds
+            )),
+            (FieldType::Binary, CqlValue::Blob(bytes)) => Field::Binary(bytes),
+            (FieldType::Timestamp, CqlValue::Timestamp(millis)) => {
+                Field::Timestamp(timestamp(millis).ok_or_else(|| invalid(&millis))?.into())
+            }
+            (FieldType::Date, CqlValue::Date(days)) => {
+                Field::Date(date(days).ok_or_else(|| invalid(&days))?)
+            }
+            // Times are nanoseconds since midnight.
+            (FieldType::Duration, CqlValue::Time(time)) => {
+                let nanos = time
+                    .num_nanoseconds()
+                    .and_then(|nanos| u64::try_from(nanos).ok())
+                    .ok_or_else(|| invalid(&time))?;
+                Field::Duration(DozerDuration(
+                    std::time::Duration::from_nanos(nanos),
+                    TimeUnit::Nanoseconds,
+                ))
+            }
+            (FieldType::Json, value) => {
+                Field::Json(serde_json_to_json_value(to_json(&value)).map_err(|_| invalid(&value))?)
+            }
+            (_, value) => return Err(invalid(&value)),
+        })
+    }
+}
+
+/// The requested columns of a source table and the positions of its primary key among them.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub keyspace: String,
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_index: Vec<usize>,
+}
+
+impl Table {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    column.kind == ColumnKind::Regular,
+                    SourceDefinition::Dynamic,
+                ),
+                self.primary_index.contains(&index),
+            );
+        }
+        schema
+    }
+
+    /// The quoted name, to be used in queries.
+    pub fn quoted_name(&self) -> String {
+        format!("{}.{}", quote(&self.keyspace), quote(&self.name))
+    }
+
+    /// The quoted name of the CDC log table of ScyllaDB.
+    pub fn quoted_log_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote(&self.keyspace),
+            quote(&format!("{}{}", self.name, LOG_TABLE_SUFFIX))
+        )
+    }
+
+    /// The quoted requested columns, to be selected in queries.
+    pub fn quoted_columns(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The quoted partition key columns in key order, to be passed to `token()`.
+    pub fn quoted_partition_key(&self) -> String {
+        self.primary_index
+            .iter()
+            .map(|index| &self.columns[*index])
+            .filter(|column| column.kind == ColumnKind::PartitionKey)
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn has_clustering_key(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| column.kind == ColumnKind::Clustering)
+    }
+
+    /// Converts the values of the requested columns.
+    pub fn record(&self, values: Vec<Option<CqlValue>>) -> Result<Record, CassandraError> {
+        let values = self
+            .columns
+            .iter()
+            .zip(values)
+            .map(|(column, value)| column.convert(value))
+            .collect::<Result<_, _>>()?;
+        Ok(Record::new(values))
+    }
+
+    /// Keeps the primary key of a record, nulling the other values.
+    pub fn key_record(&self, record: &Record) -> Record {
+        Record::new(
+            record
+                .values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    if self.primary_index.contains(&index) {
+                        value.clone()
+                    } else {
+                        Field::Null
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Suffix of the names of the CDC log tables of ScyllaDB.
+pub const LOG_TABLE_SUFFIX: &str = "_scylla_cdc_log";
+
+/// Maps a type of `system_schema.columns`.
+pub fn map_type(cql_type: &str) -> FieldType {
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == cql_type)
+        .map_or(FieldType::Json, |(_, typ)| *typ)
+}
+
+pub fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Lists the tables of a keyspace, without the CDC log tables.
+pub async fn list_tables(session: &Session, keyspace: &str) -> Result<Vec<String>, CassandraError> {
+    let mut tables = session
+        .query(
+            "SELECT table_name FROM system_schema.tables WHERE keyspace_name = ?",
+            (keyspace,),
+        )
+        .await?
+        .rows_typed::<(String,)>()
+        .map_err(|e| CassandraError::UnexpectedResult(e.to_string()))?
+        .map(|row| row.map(|(name,)| name))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CassandraError::UnexpectedResult(e.to_string()))?;
+    tables.retain(|name| !name.ends_with(LOG_TABLE_SUFFIX));
+    tables.sort();
+    Ok(tables)
+}
+
+/// Lists the columns of a table in primary key order: the partition key, the clustering columns, then the others by
+/// name. It's empty if the table doesn't exist.
+pub async fn list_columns(
+    session: &Session,
+    keyspace: &str,
+    table: &str,
+) -> Result<Vec<Column>, CassandraError> {
+    let rows = session
+        .query(
+            "SELECT column_name, kind, position, type FROM system_schema.columns \
+                WHERE keyspace_name = ? AND table_name = ?",
+            (keyspace, table),
+        )
+        .await?
+        .rows_typed::<(String, String, i32, String)>()
+        .map_err(|e| CassandraError::UnexpectedResult(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CassandraError::UnexpectedResult(e.to_string()))?;
+    Ok(sort_columns(rows))
+}
+
+fn sort_columns(mut rows: Vec<(String, String, i32, String)>) -> Vec<Column> {
+    let kind = |kind: &str| match kind {
+        "partition_key" => ColumnKind::PartitionKey,
+        "clustering" => ColumnKind::Clustering,
+        _ => ColumnKind::Regular,
+    };
+    rows.sort_by(
+        |(name, a_kind, a_position, _), (other, b_kind, b_position, _)| {
+            (kind(a_kind), a_position, name).cmp(&(kind(b_kind), b_position, other))
+        },
+    );
+    rows.into_iter()
+        .map(|(name, column_kind, _, cql_type)| Column {
+            name,
+            kind: kind(&column_kind),
+            typ: map_type(&cql_type),
+        })
+        .collect()
+}
+
+/// Gets the `column_names` of a table, or all its columns if there are none. The primary key columns must be requested.
+pub async fn get_table(
+    session: &Session,
+    keyspace: &str,
+    table: &str,
+    column_names: &[String],
+) -> Result<Table, CassandraError> {
+    let all_columns = list_columns(session, keyspace, table).await?;
+    select_columns(keyspace, table, all_columns, column_names)
+}
+
+fn select_columns(
+    keyspace: &str,
+    table: &str,
+    all_columns: Vec<Column>,
+    column_names: &[String],
+) -> Result<Table, CassandraError> {
+    let qualified_name = || format!("{keyspace}.{table}");
+    if all_columns.is_empty() {
+        return Err(CassandraError::TableNotFound(qualified_name()));
+    }
+    let columns = if column_names.is_empty() {
+        all_columns.clone()
+    } else {
+        column_names
+            .iter()
+            .map(|column_name| {
+                all_columns
+                    .iter()
+                    .find(|column| &column.name == column_name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        CassandraError::ColumnNotFound(column_name.clone(), qualified_name())
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut primary_index = vec![];
+    for key_column in all_columns
+        .iter()
+        .filter(|column| column.kind != ColumnKind::Regular)
+    {
+        let index = columns
+            .iter()
+            .position(|column| column.name == key_column.name)
+            .ok_or_else(|| {
+                CassandraError::MissingKeyColumn(key_column.name.clone(), qualified_name())
+            })?;
+        primary_index.push(index);
+    }
+
+    Ok(Table {
+        keyspace: keyspace.to_string(),
+        name: table.to_string(),
+        columns,
+        primary_index,
+    })
+}
+
+/// Timestamps are milliseconds since the Unix epoch.
+fn timestamp(millis: Duration) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(millis.num_milliseconds()).single()
+}
+
+/// Dates are days since the Unix epoch, offset by 2^31 so they are unsigned.
+fn date(days: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(1970, 1, 1)?.checked_add_signed(Duration::days(days as i64 - (1 << 31)))
+}
+
+/// Maps a value to JSON, for the values of collections, tuples and user defined types. Maps whose keys aren't strings
+/// are arrays of key and value pairs. Blobs are arrays of bytes, and numbers that JSON can't represent are strings.
+fn to_json(value: &CqlValue) -> Value {
+    match value {
+        CqlValue::Ascii(string) | CqlValue::Text(string) => Value::String(string.clone()),
+        CqlValue::Boolean(bool) => Value::Bool(*bool),
+        CqlValue::Blob(bytes) => {
+            Value::Array(bytes.iter().map(|byte| Value::from(*byte)).collect())
+        }
+        CqlValue::Counter(counter) => Value::from(counter.0),
+        CqlValue::Decimal(decimal) => Value::String(decimal.to_string()),
+        CqlValue::Varint(varint) => Value::String(varint.to_string()),
+        CqlValue::Date(days) => {
+            date(*days).map_or(Value::Null, |date| Value::String(date.to_string()))
+        }
+        CqlValue::Timestamp(millis) => timestamp(*millis).map_or(Value::Null, |timestamp| {
+            Value::String(timestamp.to_rfc3339())
+        }),
+        CqlValue::Time(time) => time.num_nanoseconds().map_or(Value::Null, Value::from),
+        CqlValue::Duration(duration) => Value::String(format!(
+            "{}mo{}d{}ns",
+            duration.months, duration.days, duration.nanoseconds
+        )),
+        CqlValue::Double(double) => Value::from(*double),
+        CqlValue::Float(float) => Value::from(*float as f64),
+        CqlValue::Int(int) => Value::from(*int),
+        CqlValue::BigInt(int) => Value::from(*int),
+        CqlValue::SmallInt(int) => Value::from(*int),
+        CqlValue::TinyInt(int) => Value::from(*int),
+        CqlValue::Inet(address) => Value::String(address.to_string()),
+        CqlValue::Uuid(uuid) | CqlValue::Timeuuid(uuid) => Value::String(uuid.to_string()),
+        CqlValue::List(values) | CqlValue::Set(values) => {
+            Value::Array(values.iter().map(to_json).collect())
+        }
+        CqlValue::Tuple(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| value.as_ref().map_or(Value::Null, to_json))
+                .collect(),
+        ),
+        CqlValue::Map(entries) => {
+            if entries
+                .iter()
+                .all(|(key, _)| matches!(key, CqlValue::Ascii(_) | CqlValue::Text(_)))
+            {
+                Value::Object(
+                    entries
+                        .iter()
+                        .filter_map(|(key, value)| match key {
+                            CqlValue::Ascii(key) | CqlValue::Text(key) => {
+                                Some((key.clone(), to_json(value)))
+                            }
+                            _ => None,
+                        })
+                        .collect::<Map<_, _>>(),
+                )
+            } else {
+                Value::Array(
+                    entries
+                        .iter()
+                        .map(|(key, value)| Value::Array(vec![to_json(key), to_json(value)]))
+                        .collect(),
+                )
+            }
+        }
+        CqlValue::UserDefinedType { fields, .. } => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), value.as_ref().map_or(Value::Null, to_json)))
+                .collect::<Map<_, _>>(),
+        ),
+        CqlValue::Empty => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    fn column(name: &str, kind: ColumnKind, cql_type: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            kind,
+            typ: map_type(cql_type),
+        }
+    }
+
+    #[test]
+    fn test_map_type() {
+        assert_eq!(map_type("bigint"), FieldType::Int);
+        assert_eq!(map_type("timeuuid"), FieldType::String);
+        assert_eq!(map_type("frozen<list<int>>"), FieldType::Json);
+        assert_eq!(map_type("map<text, int>"), FieldType::Json);
+        assert_eq!(quote("my\"table"), "\"my\"\"table\"");
+    }
+
+    #[test]
+    fn test_sort_columns() {
+        let rows = vec![
+            (
+                "title".to_string(),
+                "regular".to_string(),
+                -1,
+                "text".to_string(),
+            ),
+            (
+                "year".to_string(),
+                "clustering".to_string(),
+                0,
+                "int".to_string(),
+            ),
+            (
+                "budget".to_string(),
+                "static".to_string(),
+                -1,
+                "decimal".to_string(),
+            ),
+            (
+                "studio".to_string(),
+                "partition_key".to_string(),
+                1,
+                "text".to_string(),
+            ),
+            (
+                "country".to_string(),
+                "partition_key".to_string(),
+                0,
+                "ascii".to_string(),
+            ),
+        ];
+        let columns = sort_columns(rows);
+        assert_eq!(
+            columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["country", "studio", "year", "budget", "title"]
+        );
+
+        let table = select_columns("films", "films", columns.clone(), &[]).unwrap();
+        assert_eq!(table.primary_index, vec![0, 1, 2]);
+        assert_eq!(table.quoted_partition_key(), "\"country\", \"studio\"");
+        assert_eq!(
+            table.quoted_log_name(),
+            "\"films\".\"films_scylla_cdc_log\""
+        );
+        assert!(table.has_clustering_key());
+
+        let names = ["title", "year", "studio", "country"].map(String::from);
+        let table = select_columns("films", "films", columns.clone(), &names).unwrap();
+        assert_eq!(table.primary_index, vec![3, 2, 1]);
+        assert_eq!(table.quoted_partition_key(), "\"country\", \"studio\"");
+        assert!(matches!(
+            select_columns("films", "films", columns, &names[..2]),
+            Err(CassandraError::MissingKeyColumn(..))
+        ));
+    }
+
+    #[test]
+    fn test_convert() {
+        let convert = |cql_type: &str, value: CqlValue| {
+            column("value", ColumnKind::Regular, cql_type)
+                .convert(Some(value))
+                .unwrap()
+        };
+        assert_eq!(convert("smallint", CqlValue::SmallInt(-3)), Field::Int(-3));
+        assert_eq!(
+            convert("text", CqlValue::Text("Matrix".to_string())),
+            Field::String("Matrix".to_string())
+        );
+        assert_eq!(
+            convert(
+                "timestamp",
+                CqlValue::Timestamp(Duration::milliseconds(1_000))
+            ),
+            Field::Timestamp(Utc.timestamp_millis_opt(1_000).unwrap().into())
+        );
+        assert_eq!(
+            convert("date", CqlValue::Date((1 << 31) + 1)),
+            Field::Date(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap())
+        );
+        assert_eq!(
+            convert("time", CqlValue::Time(Duration::seconds(90))),
+            Field::Duration(DozerDuration(
+                std::time::Duration::from_secs(90),
+                TimeUnit::Nanoseconds
+            ))
+        );
+        assert_eq!(
+            convert(
+                "map<text, frozen<list<int>>>",
+                CqlValue::Map(vec![(
+                    CqlValue::Text("ratings".to_string()),
+                    CqlValue::List(vec![CqlValue::Int(8), CqlValue::Int(9)])
+                )])
+            ),
+            Field::Json(serde_json_to_json_value(json!({ "ratings": [8, 9] })).unwrap())
+        );
+        assert_eq!(
+            convert(
+                "map<int, text>",
+                CqlValue::Map(vec![(CqlValue::Int(1), CqlValue::Text("one".to_string()))])
+            ),
+            Field::Json(serde_json_to_json_value(json!([[1, "one"]])).unwrap())
+        );
+        assert_eq!(
+            column("value", ColumnKind::Regular, "int")
+                .convert(None)
+                .unwrap(),
+            Field::Null
+        );
+        assert!(column("value", ColumnKind::Regular, "int")
+            .convert(Some(CqlValue::Text("one".to_string())))
+            .is_err());
+    }
+}
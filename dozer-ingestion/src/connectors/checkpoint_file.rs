@@ -0,0 +1,73 @@
+//! Checkpoints of Kinesis streams and DynamoDB Streams, saved as JSON files so a restarted connector resumes after
+//! them.
+
+use std::fs;
+use std::path::Path;
+
+use dozer_types::serde::de::DeserializeOwned;
+use dozer_types::serde::Serialize;
+use dozer_types::serde_json;
+
+use crate::errors::CheckpointFileError;
+
+/// Reads the checkpoint at `path`, or returns an empty one if there's no path or no file yet.
+pub fn read<T: DeserializeOwned + Default>(path: Option<&Path>) -> Result<T, CheckpointFileError> {
+    let Some(path) = path else {
+        return Ok(T::default());
+    };
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| CheckpointFileError::Invalid(path.to_path_buf(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(CheckpointFileError::Io(path.to_path_buf(), e)),
+    }
+}
+
+/// Writes the checkpoint to `path`, if there's one. Written to a temporary file first, so a crash doesn't leave a
+/// partial checkpoint.
+pub fn write<T: Serialize>(path: Option<&Path>, checkpoint: &T) -> Result<(), CheckpointFileError> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let bytes = serde_json::to_vec(checkpoint)
+        .map_err(|e| CheckpointFileError::Invalid(path.to_path_buf(), e))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, bytes)
+        .and_then(|()| fs::rename(&temp_path, path))
+        .map_err(|e| CheckpointFileError::Io(path.to_path_buf(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_file() {
+        let dir = TempDir::new("checkpoint_file").unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        // A missing file or path is an empty checkpoint.
+        assert!(read::<HashMap<String, u64>>(Some(&path))
+            .unwrap()
+            .is_empty());
+        assert!(read::<HashMap<String, u64>>(None).unwrap().is_empty());
+
+        let checkpoint = HashMap::from([("films".to_string(), 42u64)]);
+        write(Some(&path), &checkpoint).unwrap();
+        assert_eq!(
+            read::<HashMap<String, u64>>(Some(&path)).unwrap(),
+            checkpoint
+        );
+        assert!(!path.with_extension("tmp").exists());
+
+        fs::write(&path, "{").unwrap();
+        assert!(matches!(
+            read::<HashMap<String, u64>>(Some(&path)),
+            Err(CheckpointFileError::Invalid(..))
+        ));
+    }
+}
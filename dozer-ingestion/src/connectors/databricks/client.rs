@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::DatabricksConfig;
+use dozer_types::serde_json::{json, Value};
+use reqwest::{Client, RequestBuilder, Response};
+
+use crate::errors::DatabricksError;
+
+/// How long a statement is waited for in the request executing it, the longest the API allows.
+const WAIT_TIMEOUT: &str = "50s";
+
+/// How often a statement still running after `WAIT_TIMEOUT` is polled.
+const STATEMENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A row of results, whose values are strings or null.
+pub type Row = Vec<Option<String>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultColumn {
+    pub name: String,
+    /// Type of the column, e.g. `INT` or `TIMESTAMP`, without its parameters.
+    pub type_name: String,
+}
+
+/// A statement that succeeded, whose results are read by chunks.
+#[derive(Debug)]
+pub struct Statement {
+    id: String,
+    pub columns: Vec<ResultColumn>,
+    pub chunk_count: u64,
+}
+
+/// A client of the SQL Statement Execution API, executing statements on a SQL warehouse.
+#[derive(Debug)]
+pub struct DatabricksClient {
+    client: Client,
+    url: String,
+    token: String,
+    warehouse_id: String,
+    catalog: Option<String>,
+}
+
+impl DatabricksClient {
+    pub fn new(config: &DatabricksConfig) -> Self {
+        let host = config.host.trim_end_matches('/');
+        let url = if host.starts_with("http://") || host.starts_with("https://") {
+            host.to_string()
+        } else {
+            format!("https://{host}")
+        };
+        Self {
+            client: Client::new(),
+            url,
+            token: config.token.clone(),
+            warehouse_id: config.warehouse_id.clone(),
+            catalog: config.catalog.clone(),
+        }
+    }
+
+    async fn send(&self, url: String, request: RequestBuilder) -> Result<Value, DatabricksError> {
+        let response = request.bearer_auth(&self.token).send().await?;
+        Ok(check_status(url, response).await?.json().await?)
+    }
+
+    /// Executes `statement` and waits for it to succeed. Results are links to files of json arrays.
+    pub async fn execute(&self, statement: &str) -> Result<Statement, DatabricksError> {
+        let url = format!("{}/api/2.0/sql/statements/", self.url);
+        let mut body = json!({
+            "warehouse_id": self.warehouse_id,
+            "statement": statement,
+            "wait_timeout": WAIT_TIMEOUT,
+            "on_wait_timeout": "CONTINUE",
+            "disposition": "EXTERNAL_LINKS",
+            "format": "JSON_ARRAY",
+        });
+        if let Some(catalog) = &self.catalog {
+            body["catalog"] = json!(catalog);
+        }
+        let mut response = self
+            .send(url.clone(), self.client.post(&url).json(&body))
+            .await?;
+        let id = response
+            .get("statement_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| DatabricksError::UnexpectedResponse("missing statement_id".into()))?
+            .to_string();
+        loop {
+            match response.pointer("/status/state").and_then(Value::as_str) {
+                Some("SUCCEEDED") => break,
+                Some("PENDING" | "RUNNING") => {
+                    tokio::time::sleep(STATEMENT_POLL_INTERVAL).await;
+                    let url = format!("{}/api/2.0/sql/statements/{id}", self.url);
+                    response = self.send(url.clone(), self.client.get(&url)).await?;
+                }
+                state => {
+                    let message = response
+                        .pointer("/status/error/message")
+                        .and_then(Value::as_str)
+                        .or(state)
+                        .unwrap_or("unknown state");
+                    return Err(DatabricksError::StatementFailed(
+                        statement.to_string(),
+                        message.to_string(),
+                    ));
+                }
+            }
+        }
+
+        let columns = response
+            .pointer("/manifest/schema/columns")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|column| {
+                let field = |name: &str| column.get(name).and_then(Value::as_str);
+                match (field("name"), field("type_name")) {
+                    (Some(name), Some(type_name)) => Ok(ResultColumn {
+                        name: name.to_string(),
+                        type_name: type_name.to_string(),
+                    }),
+                    _ => Err(DatabricksError::UnexpectedResponse(format!(
+                        "invalid column {column}"
+                    ))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        let chunk_count = response
+            .pointer("/manifest/total_chunk_count")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        Ok(Statement {
+            id,
+            columns,
+            chunk_count,
+        })
+    }
+
+    /// The rows of chunk `index` of the results of `statement`.
+    pub async fn chunk(
+        &self,
+        statement: &Statement,
+        index: u64,
+    ) -> Result<Vec<Row>, DatabricksError> {
+        let url = format!(
+            "{}/api/2.0/sql/statements/{}/result/chunks/{index}",
+            self.url, statement.id
+        );
+        let chunk = self.send(url.clone(), self.client.get(&url)).await?;
+        let mut rows = vec![];
+        for link in chunk
+            .get("external_links")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let link = link
+                .get("external_link")
+                .and_then(Value::as_str)
+                .ok_or_else(|| {
+                    DatabricksError::UnexpectedResponse("missing external_link".into())
+                })?;
+            // Links are presigned urls of the cloud storage, which must not get the token, nor be logged.
+            let response = self.client.get(link).send().await?;
+            let data: Value = check_status(format!("results of chunk {index}"), response)
+                .await?
+                .json()
+                .await?;
+            let data = data.as_array().ok_or_else(|| {
+                DatabricksError::UnexpectedResponse("results are not an array".into())
+            })?;
+            for row in data {
+                rows.push(to_row(row)?);
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Executes `statement` and reads all its results.
+    pub async fn query(
+        &self,
+        statement: &str,
+    ) -> Result<(Vec<ResultColumn>, Vec<Row>), DatabricksError> {
+        let statement = self.execute(statement).await?;
+        let mut rows = vec![];
+        for index in 0..statement.chunk_count {
+            rows.extend(self.chunk(&statement, index).await?);
+        }
+        Ok((statement.columns, rows))
+    }
+}
+
+fn to_row(row: &Value) -> Result<Row, DatabricksError> {
+    row.as_array()
+        .ok_or_else(|| DatabricksError::UnexpectedResponse(format!("invalid row {row}")))?
+        .iter()
+        .map(|value| match value {
+            Value::Null => Ok(None),
+            Value::String(value) => Ok(Some(value.clone())),
+            value => Err(DatabricksError::UnexpectedResponse(format!(
+                "invalid value {value}"
+            ))),
+        })
+        .collect()
+}
+
+async fn check_status(url: String, response: Response) -> Result<Response, DatabricksError> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(DatabricksError::Status(
+            url,
+            status.as_u16(),
+            response.text().await.unwrap_or_default(),
+        ))
+    }
+}
@@ -0,0 +1,317 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{DatabricksConfig, IngestionMessage};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation};
+use tonic::async_trait;
+
+use super::client::DatabricksClient;
+use super::schema::{field_type, string_literal, table_name, Table, TYPES};
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, DatabricksError};
+use crate::ingestion::Ingestor;
+
+/// Reads Delta tables through a SQL warehouse, then polls their change data feed.
+///
+/// Delta tables have no primary key, so updates are deletes of the old rows and inserts of the new ones.
+#[derive(Debug)]
+pub struct DatabricksConnector {
+    name: String,
+    config: DatabricksConfig,
+}
+
+#[derive(Debug)]
+struct TableReader {
+    table: Table,
+    /// The version of the table read up to.
+    version: u64,
+}
+
+impl TableReader {
+    /// The statement reading the changes of the versions after the one read up to, until `version`. Deletes come
+    /// before inserts in each version.
+    fn changes_statement(&self, version: u64) -> String {
+        format!(
+            "SELECT {}, _change_type FROM table_changes({}, {}, {version}) \
+            ORDER BY _commit_version, \
+            CASE WHEN _change_type IN ('delete', 'update_preimage') THEN 0 ELSE 1 END",
+            self.table.select_list(),
+            string_literal(&self.table.name),
+            self.version + 1,
+        )
+    }
+
+    /// The operation of a row of the changes, whose change type follows the columns.
+    fn operation(&self, row: &[Option<String>]) -> Result<Option<Operation>, DatabricksError> {
+        let change_type = row.get(self.table.columns.len()).ok_or_else(|| {
+            DatabricksError::UnexpectedResponse(format!(
+                "{} changes without _change_type",
+                self.table.name
+            ))
+        })?;
+        let record = self.table.record(row);
+        Ok(match change_type.as_deref() {
+            Some("insert" | "update_postimage") => Some(Operation::Insert { new: record }),
+            Some("delete" | "update_preimage") => Some(Operation::Delete { old: record }),
+            _ => None,
+        })
+    }
+}
+
+impl DatabricksConnector {
+    pub fn new(name: String, config: DatabricksConfig) -> Self {
+        Self { name, config }
+    }
+
+    /// Selects the columns of `table_info` from the columns of its table.
+    async fn get_table(
+        client: &DatabricksClient,
+        table_info: &TableInfo,
+    ) -> Result<Table, DatabricksError> {
+        let name = table_name(table_info.schema.as_deref(), &table_info.name);
+        let statement = client
+            .execute(&format!("SELECT * FROM {name} LIMIT 0"))
+            .await?;
+        Table::new(name, &statement.columns, &table_info.column_names)
+    }
+
+    /// The latest version of the table `name`.
+    async fn latest_version(client: &DatabricksClient, name: &str) -> Result<u64, DatabricksError> {
+        let (columns, rows) = client
+            .query(&format!("DESCRIBE HISTORY {name} LIMIT 1"))
+            .await?;
+        columns
+            .iter()
+            .position(|column| column.name == "version")
+            .and_then(|index| rows.first()?.get(index)?.as_deref()?.parse().ok())
+            .ok_or_else(|| {
+                DatabricksError::UnexpectedResponse(format!("{name} history without version"))
+            })
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let client = DatabricksClient::new(&self.config);
+        let mut readers = vec![];
+        for table_info in &tables {
+            let table = Self::get_table(&client, table_info).await?;
+            let version = Self::latest_version(&client, &table.name).await?;
+            readers.push(TableReader { table, version });
+        }
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut seq_no = 0;
+        for (table_index, reader) in readers.iter().enumerate() {
+            info!(
+                "[{}] Reading {} at version {}",
+                self.name, reader.table.name, reader.version
+            );
+            let statement = client
+                .execute(&format!(
+                    "SELECT {} FROM {} VERSION AS OF {}",
+                    reader.table.select_list(),
+                    reader.table.name,
+                    reader.version
+                ))
+                .await?;
+            for index in 0..statement.chunk_count {
+                for row in client.chunk(&statement, index).await? {
+                    let op = Operation::Insert {
+                        new: reader.table.record(&row),
+                    };
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        info!("[{}] Polling changes every {:?}", self.name, poll_interval);
+        // Each poll with changes is a transaction.
+        let mut txid = 0;
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            let mut seq_no = 0;
+            for (table_index, reader) in readers.iter_mut().enumerate() {
+                let version = Self::latest_version(&client, &reader.table.name).await?;
+                if version <= reader.version {
+                    continue;
+                }
+                let statement = client.execute(&reader.changes_statement(version)).await?;
+                for index in 0..statement.chunk_count {
+                    for row in client.chunk(&statement, index).await? {
+                        let Some(op) = reader.operation(&row)? else {
+                            continue;
+                        };
+                        if seq_no == 0 {
+                            txid += 1;
+                        }
+                        ingestor
+                            .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                            .map_err(ConnectorError::IngestorError)?;
+                        seq_no += 1;
+                    }
+                }
+                reader.version = version;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for DatabricksConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        DatabricksClient::new(&self.config)
+            .query("SELECT 1")
+            .await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let (_, rows) = DatabricksClient::new(&self.config)
+            .query(
+                "SELECT table_schema, table_name FROM information_schema.tables \
+                WHERE table_schema <> 'information_schema' AND data_source_format = 'DELTA' \
+                ORDER BY table_schema, table_name",
+            )
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [schema, Some(name)] => Some(TableIdentifier::new(schema.clone(), name.clone())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let client = DatabricksClient::new(&self.config);
+        for table in tables {
+            let name = table_name(table.schema.as_deref(), &table.name);
+            let (_, rows) = client
+                .query(&format!(
+                    "SHOW TBLPROPERTIES {name} ('delta.enableChangeDataFeed')"
+                ))
+                .await?;
+            // The value of a property that isn't set is a message saying so.
+            let enabled = rows
+                .first()
+                .and_then(|row| row.get(1)?.as_deref())
+                .map_or(false, |value| value.eq_ignore_ascii_case("true"));
+            if !enabled {
+                return Err(DatabricksError::ChangeDataFeedDisabled(name).into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let client = DatabricksClient::new(&self.config);
+        let mut table_infos = vec![];
+        for table in tables {
+            let name = table_name(table.schema.as_deref(), &table.name);
+            let statement = client
+                .execute(&format!("SELECT * FROM {name} LIMIT 0"))
+                .await?;
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: statement
+                    .columns
+                    .into_iter()
+                    .filter(|column| field_type(&column.type_name).is_some())
+                    .map(|column| column.name)
+                    .collect(),
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let client = DatabricksClient::new(&self.config);
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                Self::get_table(&client, table_info)
+                    .await
+                    .map(|table| SourceSchema::new(table.schema(), CdcType::FullChanges))
+                    .map_err(ConnectorError::from),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::{Field, Record};
+
+    use super::super::schema::Column;
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let reader = TableReader {
+            table: Table {
+                name: table_name(Some("sales"), "orders"),
+                columns: vec![Column {
+                    name: "id".to_string(),
+                    typ: FieldType::Int,
+                }],
+            },
+            version: 4,
+        };
+        assert_eq!(
+            reader.changes_statement(7),
+            "SELECT `id`, _change_type FROM table_changes('`sales`.`orders`', 5, 7) \
+            ORDER BY _commit_version, \
+            CASE WHEN _change_type IN ('delete', 'update_preimage') THEN 0 ELSE 1 END"
+        );
+
+        let row = |change_type: &str| vec![Some("1".to_string()), Some(change_type.to_string())];
+        let record = Record::new(vec![Field::Int(1)]);
+        assert_eq!(
+            reader.operation(&row("update_preimage")).unwrap(),
+            Some(Operation::Delete {
+                old: record.clone()
+            })
+        );
+        assert_eq!(
+            reader.operation(&row("insert")).unwrap(),
+            Some(Operation::Insert { new: record })
+        );
+        assert!(reader.operation(&[Some("1".to_string())]).is_err());
+    }
+}
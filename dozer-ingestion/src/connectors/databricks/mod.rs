@@ -0,0 +1,8 @@
+//! Delta tables of a Databricks workspace, read with the SQL Statement Execution API of a SQL warehouse at a version,
+//! then followed with their change data feed from the next version.
+
+mod client;
+mod connector;
+mod schema;
+
+pub use connector::DatabricksConnector;
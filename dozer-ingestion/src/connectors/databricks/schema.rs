@@ -0,0 +1,279 @@
+use std::str::FromStr;
+
+use base64::Engine;
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offset, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use super::client::ResultColumn;
+use crate::errors::DatabricksError;
+
+/// Field types of the column types. Columns of other types, e.g. intervals, can't be read.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("BOOLEAN", FieldType::Boolean),
+    ("BYTE", FieldType::Int),
+    ("SHORT", FieldType::Int),
+    ("INT", FieldType::Int),
+    ("LONG", FieldType::Int),
+    ("FLOAT", FieldType::Float),
+    ("DOUBLE", FieldType::Float),
+    ("DECIMAL", FieldType::Decimal),
+    ("STRING", FieldType::String),
+    ("CHAR", FieldType::String),
+    ("BINARY", FieldType::Binary),
+    ("DATE", FieldType::Date),
+    ("TIMESTAMP", FieldType::Timestamp),
+    ("TIMESTAMP_NTZ", FieldType::Timestamp),
+    ("ARRAY", FieldType::Json),
+    ("MAP", FieldType::Json),
+    ("STRUCT", FieldType::Json),
+];
+
+pub fn field_type(type_name: &str) -> Option<FieldType> {
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == type_name)
+        .map(|(_, typ)| *typ)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub typ: FieldType,
+}
+
+/// The requested columns of a Delta table.
+#[derive(Debug, Clone)]
+pub struct Table {
+    /// Quoted name of the table, qualified with its schema if it has one.
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    /// Selects `column_names` of the table `name`, whose columns are `columns`.
+    pub fn new(
+        name: String,
+        columns: &[ResultColumn],
+        column_names: &[String],
+    ) -> Result<Self, DatabricksError> {
+        let columns = column_names
+            .iter()
+            .map(|column_name| {
+                let column = columns
+                    .iter()
+                    .find(|column| column.name == *column_name)
+                    .ok_or_else(|| {
+                        DatabricksError::ColumnNotFound(column_name.clone(), name.clone())
+                    })?;
+                let typ = field_type(&column.type_name).ok_or_else(|| {
+                    DatabricksError::UnsupportedType(
+                        column.name.clone(),
+                        name.clone(),
+                        column.type_name.clone(),
+                    )
+                })?;
+                Ok(Column {
+                    name: column.name.clone(),
+                    typ,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { name, columns })
+    }
+
+    /// Delta tables have no primary key, and every column is nullable.
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for column in &self.columns {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        }
+        schema
+    }
+
+    /// The quoted columns, to select them.
+    pub fn select_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The record of the first values of `row`, which are the columns in order.
+    pub fn record(&self, row: &[Option<String>]) -> Record {
+        let values = self
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| match value {
+                Some(value) => to_field(value, column.typ),
+                None => Field::Null,
+            })
+            .collect();
+        Record::new(values)
+    }
+}
+
+pub fn quote_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('`', "``"))
+}
+
+/// The quoted name of a table, qualified with its schema if it has one. The catalog is the one of the statement.
+pub fn table_name(schema: Option<&str>, name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", quote_identifier(schema), quote_identifier(name)),
+        None => quote_identifier(name),
+    }
+}
+
+pub fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Converts a value of the json array results to a field of `typ`, null if it can't be. Binary values are base64,
+/// and complex values are json.
+fn to_field(value: &str, typ: FieldType) -> Field {
+    let field = match typ {
+        FieldType::Boolean => value.parse().ok().map(Field::Boolean),
+        FieldType::Int => value.parse().ok().map(Field::Int),
+        FieldType::Float => value
+            .parse()
+            .ok()
+            .map(|value| Field::Float(OrderedFloat(value))),
+        FieldType::Decimal => Decimal::from_str(value)
+            .or_else(|_| Decimal::from_scientific(value))
+            .ok()
+            .map(Field::Decimal),
+        FieldType::String => Some(Field::String(value.to_string())),
+        FieldType::Binary => base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .ok()
+            .map(Field::Binary),
+        FieldType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(Field::Date),
+        FieldType::Timestamp => to_timestamp(value).map(Field::Timestamp),
+        FieldType::Json => serde_json::from_str(value)
+            .ok()
+            .and_then(|value| serde_json_to_json_value(value).ok())
+            .map(Field::Json),
+        _ => None,
+    };
+    field.unwrap_or(Field::Null)
+}
+
+/// Timestamps are RFC 3339, and timestamps without time zone are UTC.
+fn to_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok().or_else(|| {
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|timestamp| DateTime::from_utc(timestamp, Utc.fix()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_column(name: &str, type_name: &str) -> ResultColumn {
+        ResultColumn {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_table() {
+        let columns = [
+            result_column("id", "LONG"),
+            result_column("price", "DECIMAL"),
+            result_column("tags", "ARRAY"),
+            result_column("updated_at", "TIMESTAMP"),
+            result_column("ttl", "INTERVAL"),
+        ];
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        let table = Table::new(
+            table_name(Some("sales"), "orders"),
+            &columns,
+            &names(&["updated_at", "id", "price", "tags"]),
+        )
+        .unwrap();
+        assert_eq!(table.name, "`sales`.`orders`");
+        assert_eq!(table.select_list(), "`updated_at`, `id`, `price`, `tags`");
+        assert_eq!(
+            table.record(&[
+                Some("2023-07-01T12:30:00.000Z".to_string()),
+                Some("42".to_string()),
+                Some("9.90".to_string()),
+                None,
+                Some("insert".to_string()),
+            ]),
+            Record::new(vec![
+                Field::Timestamp(DateTime::parse_from_rfc3339("2023-07-01T12:30:00Z").unwrap()),
+                Field::Int(42),
+                Field::Decimal(Decimal::new(990, 2)),
+                Field::Null,
+            ])
+        );
+
+        assert!(matches!(
+            Table::new("orders".to_string(), &columns, &names(&["ttl"])),
+            Err(DatabricksError::UnsupportedType(..))
+        ));
+        assert!(matches!(
+            Table::new("orders".to_string(), &columns, &names(&["total"])),
+            Err(DatabricksError::ColumnNotFound(..))
+        ));
+    }
+
+    #[test]
+    fn test_to_field() {
+        assert_eq!(to_field("true", FieldType::Boolean), Field::Boolean(true));
+        assert_eq!(
+            to_field("1.5E-3", FieldType::Float),
+            Field::Float(OrderedFloat(0.0015))
+        );
+        assert_eq!(
+            to_field("2023-07-01", FieldType::Date),
+            Field::Date(NaiveDate::from_ymd_opt(2023, 7, 1).unwrap())
+        );
+        assert_eq!(
+            to_field("2023-07-01T12:30:00.5", FieldType::Timestamp),
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-07-01T12:30:00.5Z").unwrap())
+        );
+        assert_eq!(
+            to_field("AQI=", FieldType::Binary),
+            Field::Binary(vec![1, 2])
+        );
+        assert!(matches!(
+            to_field(r#"{"a":[1,2]}"#, FieldType::Json),
+            Field::Json(_)
+        ));
+        assert_eq!(to_field("n/a", FieldType::Int), Field::Null);
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(quote_identifier("my`table"), "`my``table`");
+        assert_eq!(table_name(None, "orders"), "`orders`");
+        assert_eq!(string_literal(r"`it's`.`a\b`"), r"'`it\'s`.`a\\b`'");
+    }
+}
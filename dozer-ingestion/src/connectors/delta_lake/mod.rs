@@ -3,8 +3,10 @@ use crate::errors::ConnectorError;
 mod connector;
 mod reader;
 mod schema_helper;
+mod tail;
 mod test;
 
 pub use connector::DeltaLakeConnector;
+pub use tail::diff_rows;
 
 type ConnectorResult<T> = Result<T, ConnectorError>;
@@ -1,13 +1,17 @@
+use crate::connectors::delta_lake::tail::TableTail;
 use crate::connectors::delta_lake::ConnectorResult;
 use crate::connectors::TableInfo;
 use crate::errors::ConnectorError;
 use crate::ingestion::Ingestor;
 use deltalake::datafusion::prelude::SessionContext;
 use dozer_types::arrow_types::from_arrow::{map_schema_to_dozer, map_value_to_dozer_field};
-use dozer_types::ingestion_types::{DeltaLakeConfig, IngestionMessage};
+use dozer_types::ingestion_types::{
+    default_delta_lake_poll_interval_ms, DeltaLakeConfig, IngestionMessage,
+};
 use dozer_types::types::{Operation, Record};
 use futures::StreamExt;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct DeltaLakeReader {
     config: DeltaLakeConfig,
@@ -20,11 +24,38 @@ impl DeltaLakeReader {
 
     pub async fn read(&self, table: &[TableInfo], ingestor: &Ingestor) -> ConnectorResult<()> {
         let mut seq_no = 0;
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+        seq_no += 1;
+        let mut tails = vec![];
         for (table_index, table) in table.iter().enumerate() {
-            self.read_impl(table_index, &mut seq_no, table, ingestor)
+            let tail = self
+                .read_impl(table_index, &mut seq_no, table, ingestor)
                 .await?;
+            if follows(&self.config, &table.name) {
+                tails.push(tail);
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+        seq_no += 1;
+
+        if tails.is_empty() {
+            return Ok(());
+        }
+        let poll_interval = Duration::from_millis(
+            self.config
+                .poll_interval_ms
+                .unwrap_or_else(default_delta_lake_poll_interval_ms),
+        );
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            for tail in &mut tails {
+                tail.ingest_new_versions(&mut seq_no, ingestor).await?;
+            }
         }
-        Ok(())
     }
 
     async fn read_impl(
@@ -33,16 +64,15 @@ impl DeltaLakeReader {
         seq_no: &mut u64,
         table: &TableInfo,
         ingestor: &Ingestor,
-    ) -> ConnectorResult<()> {
+    ) -> ConnectorResult<TableTail> {
         let table_path = table_path(&self.config, &table.name)?;
         let ctx = SessionContext::new();
-        let delta_table = deltalake::open_table(table_path).await?;
+        let delta_table = Arc::new(deltalake::open_table(table_path).await?);
         let cols: Vec<&str> = table.column_names.iter().map(|c| c.as_str()).collect();
-        let data = ctx
-            .read_table(Arc::new(delta_table))?
-            .select_columns(&cols)?
-            .execute_stream()
-            .await?;
+        let data_frame = ctx.read_table(delta_table.clone())?.select_columns(&cols)?;
+        let schema = map_schema_to_dozer(&data_frame.schema().into())
+            .map_err(|e| ConnectorError::InternalError(Box::new(e)))?;
+        let data = data_frame.execute_stream().await?;
 
         tokio::pin!(data);
         while let Some(Ok(batch)) = data.next().await {
@@ -71,15 +101,24 @@ impl DeltaLakeReader {
                             },
                         },
                     ))
-                    .unwrap();
+                    .map_err(ConnectorError::IngestorError)?;
 
                 *seq_no += 1;
             }
         }
-        Ok(())
+        Ok(TableTail::new(table_index, delta_table, schema))
     }
 }
 
+/// Tables are followed unless disabled.
+fn follows(config: &DeltaLakeConfig, table_name: &str) -> bool {
+    config
+        .tables
+        .iter()
+        .find(|delta_table| delta_table.name == table_name)
+        .map_or(true, |delta_table| delta_table.follow != Some(false))
+}
+
 pub fn table_path(config: &DeltaLakeConfig, table_name: &str) -> ConnectorResult<String> {
     for delta_table in config.tables.iter() {
         if delta_table.name == table_name {
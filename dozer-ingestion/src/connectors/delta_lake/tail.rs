@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deltalake::action::Action;
+use deltalake::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use deltalake::storage::ObjectStoreRef;
+use deltalake::{DeltaTable, PeekCommit};
+use dozer_types::arrow_types::from_arrow::map_value_to_dozer_field;
+use dozer_types::chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, Utc};
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::warn;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType, Operation, Record, Schema, DATE_FORMAT};
+use object_store::path::Path;
+use object_store::ObjectStore;
+
+use crate::connectors::delta_lake::ConnectorResult;
+use crate::errors::{ConnectorError, DeltaLakeError};
+use crate::ingestion::Ingestor;
+
+/// Follows the transaction log of a table from the version it was snapshotted at.
+pub struct TableTail {
+    table_index: usize,
+    table: Arc<DeltaTable>,
+    /// The last version ingested.
+    version: i64,
+    /// The columns ingested.
+    schema: Schema,
+}
+
+impl TableTail {
+    pub fn new(table_index: usize, table: Arc<DeltaTable>, schema: Schema) -> Self {
+        let version = table.version();
+        Self {
+            table_index,
+            table,
+            version,
+            schema,
+        }
+    }
+
+    /// Ingests the changes of the versions committed since the last one ingested.
+    pub async fn ingest_new_versions(
+        &mut self,
+        seq_no: &mut u64,
+        ingestor: &Ingestor,
+    ) -> ConnectorResult<()> {
+        loop {
+            let (version, actions) = match self.table.peek_next_commit(self.version).await? {
+                PeekCommit::New(version, actions) => (version, actions),
+                PeekCommit::UpToDate => return Ok(()),
+            };
+            for op in self.version_changes(actions).await? {
+                ingestor
+                    .handle_message(IngestionMessage::new_op(0, *seq_no, self.table_index, op))
+                    .map_err(ConnectorError::IngestorError)?;
+                *seq_no += 1;
+            }
+            self.version = version;
+        }
+    }
+
+    /// The operations of the data files a version removed and added. Files rewritten without changing data, like by
+    /// compactions, are skipped.
+    async fn version_changes(
+        &self,
+        actions: Vec<Action>,
+    ) -> Result<Vec<Operation>, DeltaLakeError> {
+        let store = self.table.object_store();
+        let mut removed = vec![];
+        let mut added = vec![];
+        for action in actions {
+            match action {
+                Action::remove(remove) if remove.data_change => {
+                    let partition_values = remove.partition_values.unwrap_or_default();
+                    match self
+                        .read_file(&store, &remove.path, &partition_values)
+                        .await
+                    {
+                        Ok(rows) => removed.extend(rows),
+                        // Removed files are kept until the table is vacuumed.
+                        Err(DeltaLakeError::ReadFile(
+                            path,
+                            object_store::Error::NotFound { .. },
+                        )) => {
+                            warn!("Removed data file {path} was vacuumed, its records can't be deleted")
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Action::add(add) if add.data_change => {
+                    added.extend(
+                        self.read_file(&store, &add.path, &add.partition_values)
+                            .await?,
+                    );
+                }
+                _ => (),
+            }
+        }
+        Ok(diff_rows(removed, added))
+    }
+
+    /// Reads the rows of a data file, taking the values of partition columns from the transaction log.
+    async fn read_file(
+        &self,
+        store: &ObjectStoreRef,
+        path: &str,
+        partition_values: &HashMap<String, Option<String>>,
+    ) -> Result<Vec<Vec<Field>>, DeltaLakeError> {
+        let location = Path::from_url_path(path)
+            .map_err(|e| DeltaLakeError::InvalidPath(path.to_string(), e))?;
+        let read_error = |e| DeltaLakeError::ReadFile(path.to_string(), e);
+        let bytes = store
+            .get(&location)
+            .await
+            .map_err(read_error)?
+            .bytes()
+            .await
+            .map_err(read_error)?;
+        let decode_error = |e| DeltaLakeError::DecodeFile(path.to_string(), e);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)
+            .map_err(decode_error)?
+            .build()
+            .map_err(decode_error)?;
+
+        let map_error = |e| DeltaLakeError::MapFile(path.to_string(), e);
+        let mut rows = vec![];
+        for batch in reader {
+            let batch = batch.map_err(|e| map_error(e.into()))?;
+            for row in 0..batch.num_rows() {
+                let fields = self
+                    .schema
+                    .fields
+                    .iter()
+                    .map(|field| match batch.column_by_name(&field.name) {
+                        Some(column) => {
+                            map_value_to_dozer_field(column, row, &field.name, &self.schema)
+                                .map_err(map_error)
+                        }
+                        None => map_partition_value(&field.name, field.typ, partition_values),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                rows.push(fields);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// The deletes and inserts turning the rows of the removed files into the rows of the added ones.
+///
+/// Delta rewrites whole files on updates and deletes, so rows copied from a removed file to an added one cancel out.
+pub fn diff_rows(removed: Vec<Vec<Field>>, added: Vec<Vec<Field>>) -> Vec<Operation> {
+    let mut removed_counts = HashMap::<Vec<Field>, usize>::new();
+    for row in removed {
+        *removed_counts.entry(row).or_default() += 1;
+    }
+    let mut inserts = vec![];
+    for row in added {
+        match removed_counts.get_mut(&row) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => inserts.push(Operation::Insert {
+                new: Record::new(row),
+            }),
+        }
+    }
+    let mut deletes = removed_counts
+        .into_iter()
+        .flat_map(|(row, count)| std::iter::repeat(row).take(count))
+        .collect::<Vec<_>>();
+    deletes.sort();
+    deletes
+        .into_iter()
+        .map(|row| Operation::Delete {
+            old: Record::new(row),
+        })
+        .chain(inserts)
+        .collect()
+}
+
+/// Partition values aren't in the data files, but in the transaction log as strings.
+fn map_partition_value(
+    name: &str,
+    typ: FieldType,
+    partition_values: &HashMap<String, Option<String>>,
+) -> Result<Field, DeltaLakeError> {
+    let value = match partition_values.get(name) {
+        None => return Err(DeltaLakeError::ColumnNotFound(name.to_string())),
+        Some(None) => return Ok(Field::Null),
+        Some(Some(value)) => value,
+    };
+    let invalid = || DeltaLakeError::InvalidPartitionValue(name.to_string(), value.clone());
+    Ok(match typ {
+        FieldType::Int => Field::Int(value.parse().map_err(|_| invalid())?),
+        FieldType::UInt => Field::UInt(value.parse().map_err(|_| invalid())?),
+        FieldType::Float => Field::Float(OrderedFloat(value.parse().map_err(|_| invalid())?)),
+        FieldType::Boolean => Field::Boolean(value.parse().map_err(|_| invalid())?),
+        FieldType::Decimal => Field::Decimal(value.parse().map_err(|_| invalid())?),
+        FieldType::String => Field::String(value.clone()),
+        FieldType::Text => Field::Text(value.clone()),
+        FieldType::Date => {
+            Field::Date(NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| invalid())?)
+        }
+        FieldType::Timestamp => {
+            let timestamp = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                .map_err(|_| invalid())?;
+            Field::Timestamp(DateTime::from_utc(timestamp, Utc.fix()))
+        }
+        _ => return Err(invalid()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::rust_decimal::Decimal;
+    use dozer_types::types::{FieldDefinition, SourceDefinition};
+
+    use super::*;
+
+    fn row(value: i64) -> Vec<Field> {
+        vec![Field::Int(value)]
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        assert_eq!(
+            diff_rows(
+                vec![row(2), row(3), row(4), row(4)],
+                vec![row(4), row(2), row(5)]
+            ),
+            vec![
+                Operation::Delete {
+                    old: Record::new(row(3))
+                },
+                Operation::Delete {
+                    old: Record::new(row(4))
+                },
+                Operation::Insert {
+                    new: Record::new(row(5))
+                },
+            ]
+        );
+        assert!(diff_rows(vec![row(1), row(2)], vec![row(2), row(1)]).is_empty());
+    }
+
+    #[test]
+    fn test_map_partition_value() {
+        let partition_values = HashMap::from([
+            ("year".to_string(), Some("2021".to_string())),
+            ("price".to_string(), Some("1.50".to_string())),
+            ("day".to_string(), Some("2021-03-06".to_string())),
+            ("region".to_string(), None),
+        ]);
+        let map = |name, typ| map_partition_value(name, typ, &partition_values);
+
+        assert_eq!(map("year", FieldType::Int).unwrap(), Field::Int(2021));
+        assert_eq!(
+            map("price", FieldType::Decimal).unwrap(),
+            Field::Decimal(Decimal::new(150, 2))
+        );
+        assert_eq!(
+            map("day", FieldType::Date).unwrap(),
+            Field::Date(NaiveDate::from_ymd_opt(2021, 3, 6).unwrap())
+        );
+        assert_eq!(map("region", FieldType::String).unwrap(), Field::Null);
+        assert!(matches!(
+            map("day", FieldType::Int),
+            Err(DeltaLakeError::InvalidPartitionValue(..))
+        ));
+        assert!(matches!(
+            map("month", FieldType::Int),
+            Err(DeltaLakeError::ColumnNotFound(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_version_changes() {
+        let path = "src/connectors/delta_lake/test/data/delta-0.8.0";
+        let table = deltalake::open_table_with_version(path, 0).await.unwrap();
+        let mut schema = Schema::new();
+        schema.field(
+            FieldDefinition::new(
+                "value".to_string(),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+        let tail = TableTail::new(0, Arc::new(table), schema);
+
+        // Version 1 deletes value 3, rewriting the file of values 2, 3 and 4.
+        let PeekCommit::New(version, actions) = tail.table.peek_next_commit(0).await.unwrap()
+        else {
+            panic!("Version 1 must be committed");
+        };
+        assert_eq!(version, 1);
+        assert_eq!(
+            tail.version_changes(actions).await.unwrap(),
+            vec![Operation::Delete {
+                old: Record::new(row(3))
+            }]
+        );
+    }
+}
@@ -13,9 +13,11 @@ async fn get_schema_from_deltalake() {
     let delta_table = DeltaTable {
         path: path.to_string(),
         name: table_name.to_string(),
+        follow: None,
     };
     let config = DeltaLakeConfig {
         tables: vec![delta_table],
+        poll_interval_ms: None,
     };
 
     let connector = DeltaLakeConnector::new(config);
@@ -34,9 +36,12 @@ async fn read_deltalake() {
     let delta_table = DeltaTable {
         path: path.to_string(),
         name: table_name.to_string(),
+        // Only read the snapshot, so the connector ends.
+        follow: Some(false),
     };
     let config = DeltaLakeConfig {
         tables: vec![delta_table],
+        poll_interval_ms: None,
     };
 
     let connector = DeltaLakeConnector::new(config);
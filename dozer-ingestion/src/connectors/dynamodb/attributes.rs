@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types as dynamodb_types;
+use aws_sdk_dynamodbstreams::types as streams_types;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json::{Map, Number, Value};
+
+/// Defines a function mapping an attribute value of the `types` module of an SDK to JSON, as DynamoDB and DynamoDB
+/// Streams have their own attribute value types. Numbers that JSON can't represent, e.g. with more digits than a
+/// float has, are mapped to strings, and binaries to arrays of bytes.
+macro_rules! attribute_to_json {
+    ($name:ident, $types:ident) => {
+        pub fn $name(value: &$types::AttributeValue) -> Value {
+            match value {
+                $types::AttributeValue::S(string) => Value::String(string.clone()),
+                $types::AttributeValue::N(number) => number_to_json(number),
+                $types::AttributeValue::B(blob) => bytes_to_json(blob.as_ref()),
+                $types::AttributeValue::Bool(bool) => Value::Bool(*bool),
+                $types::AttributeValue::Ss(strings) => {
+                    Value::Array(strings.iter().cloned().map(Value::String).collect())
+                }
+                $types::AttributeValue::Ns(numbers) => Value::Array(
+                    numbers
+                        .iter()
+                        .map(|number| number_to_json(number))
+                        .collect(),
+                ),
+                $types::AttributeValue::Bs(blobs) => Value::Array(
+                    blobs
+                        .iter()
+                        .map(|blob| bytes_to_json(blob.as_ref()))
+                        .collect(),
+                ),
+                $types::AttributeValue::L(values) => {
+                    Value::Array(values.iter().map($name).collect())
+                }
+                $types::AttributeValue::M(map) => Value::Object(
+                    map.iter()
+                        .map(|(name, value)| (name.clone(), $name(value)))
+                        .collect(),
+                ),
+                _ => Value::Null,
+            }
+        }
+    };
+}
+
+attribute_to_json!(attribute_to_json, dynamodb_types);
+attribute_to_json!(stream_attribute_to_json, streams_types);
+
+/// An item read by a scan, as a JSON object.
+pub fn item_to_json(item: &HashMap<String, dynamodb_types::AttributeValue>) -> Value {
+    Value::Object(
+        item.iter()
+            .map(|(name, value)| (name.clone(), attribute_to_json(value)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/// An image or the keys of a stream record, as a JSON object.
+pub fn stream_item_to_json(item: &HashMap<String, streams_types::AttributeValue>) -> Value {
+    Value::Object(
+        item.iter()
+            .map(|(name, value)| (name.clone(), stream_attribute_to_json(value)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+fn number_to_json(number: &str) -> Value {
+    let decimal = |number: &str| number.parse::<Decimal>().ok();
+    match number.parse::<Number>() {
+        Ok(json) if decimal(&json.to_string()) == decimal(number) => Value::Number(json),
+        _ => Value::String(number.to_string()),
+    }
+}
+
+fn bytes_to_json(bytes: &[u8]) -> Value {
+    Value::Array(bytes.iter().map(|byte| Value::from(*byte)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::primitives::Blob;
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_item_to_json() {
+        use dynamodb_types::AttributeValue;
+
+        let item = HashMap::from([
+            ("id".to_string(), AttributeValue::S("film-1".to_string())),
+            ("year".to_string(), AttributeValue::N("1999".to_string())),
+            ("rating".to_string(), AttributeValue::N("8.70".to_string())),
+            (
+                "budget".to_string(),
+                AttributeValue::N("63000000.123456789012345678".to_string()),
+            ),
+            ("poster".to_string(), AttributeValue::B(Blob::new([1, 2]))),
+            ("released".to_string(), AttributeValue::Bool(true)),
+            ("sequel".to_string(), AttributeValue::Null(true)),
+            (
+                "tags".to_string(),
+                AttributeValue::Ss(vec!["action".to_string()]),
+            ),
+            (
+                "cast".to_string(),
+                AttributeValue::L(vec![AttributeValue::M(HashMap::from([(
+                    "name".to_string(),
+                    AttributeValue::S("Neo".to_string()),
+                )]))]),
+            ),
+        ]);
+        assert_eq!(
+            item_to_json(&item),
+            json!({
+                "id": "film-1",
+                "year": 1999,
+                "rating": 8.7,
+                "budget": "63000000.123456789012345678",
+                "poster": [1, 2],
+                "released": true,
+                "sequel": null,
+                "tags": ["action"],
+                "cast": [{ "name": "Neo" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_stream_item_to_json() {
+        use streams_types::AttributeValue;
+
+        let keys = HashMap::from([("id".to_string(), AttributeValue::S("film-1".to_string()))]);
+        assert_eq!(stream_item_to_json(&keys), json!({ "id": "film-1" }));
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use dozer_types::serde::{Deserialize, Serialize};
+
+use crate::connectors::checkpoint_file;
+use crate::connectors::shards::ShardPosition;
+use crate::errors::DynamoDbError;
+
+/// The stream a table was snapshotted before, and how far each of its shards was read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct TableCheckpoint {
+    pub stream_arn: String,
+    /// Seconds since the Unix epoch the snapshot started at. Stream records created before were scanned by it.
+    pub snapshot_started_at: i64,
+    pub shards: HashMap<String, ShardPosition>,
+}
+
+/// The checkpoint of each snapshotted table, saved in a file so a restarted connector resumes the streams after it.
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+    path: Option<PathBuf>,
+    /// Table name to its checkpoint.
+    tables: HashMap<String, TableCheckpoint>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path`, or an empty one that isn't saved if there's no path.
+    pub fn open(path: Option<PathBuf>) -> Result<Self, DynamoDbError> {
+        let tables = checkpoint_file::read(path.as_deref())?;
+        Ok(Self { path, tables })
+    }
+
+    /// The checkpoint of `table`, if it was snapshotted before its stream `stream_arn`. A table whose stream was
+    /// disabled and enabled again has a new stream, which misses the changes in between.
+    pub fn table(&self, table: &str, stream_arn: &str) -> Option<&TableCheckpoint> {
+        self.tables
+            .get(table)
+            .filter(|checkpoint| checkpoint.stream_arn == stream_arn)
+    }
+
+    /// Records that `table` was snapshotted, forgetting how far its previous stream was read.
+    pub fn snapshotted(&mut self, table: &str, stream_arn: &str, started_at: i64) {
+        self.tables.insert(
+            table.to_string(),
+            TableCheckpoint {
+                stream_arn: stream_arn.to_string(),
+                snapshot_started_at: started_at,
+                shards: HashMap::new(),
+            },
+        );
+    }
+
+    pub fn get(&self, table: &str, shard_id: &str) -> Option<&ShardPosition> {
+        self.tables.get(table)?.shards.get(shard_id)
+    }
+
+    pub fn is_finished(&self, table: &str, shard_id: &str) -> bool {
+        self.get(table, shard_id) == Some(&ShardPosition::Finished)
+    }
+
+    /// Sets the position of a shard of a snapshotted table.
+    pub fn set(&mut self, table: &str, shard_id: &str, position: ShardPosition) {
+        if let Some(checkpoint) = self.tables.get_mut(table) {
+            checkpoint.shards.insert(shard_id.to_string(), position);
+        }
+    }
+
+    /// Forgets the shards of `table` that aren't in `shard_ids`, which were trimmed after 24 hours.
+    pub fn retain_shards(&mut self, table: &str, shard_ids: &[&str]) {
+        if let Some(checkpoint) = self.tables.get_mut(table) {
+            checkpoint
+                .shards
+                .retain(|shard_id, _| shard_ids.contains(&shard_id.as_str()));
+        }
+    }
+
+    pub fn save(&self) -> Result<(), DynamoDbError> {
+        Ok(checkpoint_file::write(self.path.as_deref(), &self.tables)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    const STREAM_ARN: &str =
+        "arn:aws:dynamodb:us-east-1:123456789012:table/films/stream/2023-06-01T00:00:00.000";
+
+    #[test]
+    fn test_checkpoint() {
+        let dir = TempDir::new("dynamodb_checkpoint").unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = Checkpoint::open(Some(path.clone())).unwrap();
+        assert_eq!(checkpoint.table("films", STREAM_ARN), None);
+        // Shards of tables that weren't snapshotted aren't saved.
+        checkpoint.set("films", "shardId-0", ShardPosition::Finished);
+        assert_eq!(checkpoint.get("films", "shardId-0"), None);
+
+        checkpoint.snapshotted("films", STREAM_ARN, 1_686_000_000);
+        checkpoint.set(
+            "films",
+            "shardId-0",
+            ShardPosition::SequenceNumber("42".to_string()),
+        );
+        checkpoint.set("films", "shardId-1", ShardPosition::Finished);
+        checkpoint.set("films", "shardId-2", ShardPosition::Finished);
+        checkpoint.retain_shards("films", &["shardId-0", "shardId-1"]);
+        checkpoint.save().unwrap();
+
+        // The checkpoint is kept across restarts.
+        let checkpoint = Checkpoint::open(Some(path)).unwrap();
+        assert_eq!(
+            checkpoint
+                .table("films", STREAM_ARN)
+                .unwrap()
+                .snapshot_started_at,
+            1_686_000_000
+        );
+        assert_eq!(
+            checkpoint.get("films", "shardId-0"),
+            Some(&ShardPosition::SequenceNumber("42".to_string()))
+        );
+        assert!(checkpoint.is_finished("films", "shardId-1"));
+        assert_eq!(checkpoint.get("films", "shardId-2"), None);
+        // A new stream of the table needs a new snapshot.
+        assert_eq!(checkpoint.table("films", "other"), None);
+    }
+}
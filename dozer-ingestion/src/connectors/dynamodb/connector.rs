@@ -0,0 +1,775 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use aws_sdk_dynamodb::config::Region;
+use aws_sdk_dynamodb::types::StreamViewType;
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodbstreams::types::{OperationType, Record, ShardIteratorType};
+use aws_sdk_dynamodbstreams::Client as StreamsClient;
+use dozer_types::chrono::Utc;
+use dozer_types::ingestion_types::{self, DynamoDbConfig, IngestionMessage};
+use dozer_types::log::{info, warn};
+use dozer_types::types::{FieldType, Operation};
+use tokio::sync::mpsc;
+use tonic::async_trait;
+
+use super::attributes::{item_to_json, stream_item_to_json};
+use super::checkpoint::Checkpoint;
+use crate::connectors::shards::{readable_shards, ShardInfo, ShardPosition};
+use crate::connectors::webhook::WebhookTable;
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, DynamoDbError, WebhookError};
+use crate::ingestion::Ingestor;
+
+/// The most segments a scan can have.
+const MAX_SCAN_SEGMENTS: u32 = 1_000_000;
+/// Stream records created this long before a snapshot started are skipped, as the snapshot scanned them. Records
+/// created since are applied even if they were scanned, so the clocks of the connector and DynamoDB can differ by as
+/// much.
+const SNAPSHOT_OVERLAP_SECS: i64 = 60;
+
+/// Snapshots DynamoDB tables with parallel scans, then follows their changes with DynamoDB Streams.
+#[derive(Debug)]
+pub struct DynamoDbConnector {
+    name: String,
+    config: DynamoDbConfig,
+}
+
+/// The key of a table and its stream, as described by DynamoDB.
+#[derive(Debug)]
+struct TableDescription {
+    key: Vec<String>,
+    stream_arn: String,
+}
+
+/// A table being ingested, how its items are mapped to records and the stream its changes are read from.
+#[derive(Debug)]
+struct TableStream {
+    name: String,
+    mapping: WebhookTable,
+    stream_arn: String,
+    ttl_deletes: bool,
+}
+
+/// A shard being read, and the iterator its next records are read with.
+#[derive(Debug)]
+struct ShardReader {
+    table_index: usize,
+    shard_id: String,
+    iterator: String,
+}
+
+impl DynamoDbConnector {
+    pub fn new(name: String, config: DynamoDbConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn table_config(&self, name: &str) -> Result<&ingestion_types::DynamoDbTable, ConnectorError> {
+        self.config
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| ConnectorError::TableNotFound(name.to_string()))
+    }
+
+    async fn clients(&self) -> (Client, StreamsClient) {
+        let mut loader = aws_config::from_env();
+        if let Some(region) = &self.config.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+        let mut builder = aws_sdk_dynamodb::config::Builder::from(&sdk_config);
+        let mut streams_builder = aws_sdk_dynamodbstreams::config::Builder::from(&sdk_config);
+        if let Some(endpoint_url) = &self.config.endpoint_url {
+            builder = builder.endpoint_url(endpoint_url);
+            streams_builder = streams_builder.endpoint_url(endpoint_url);
+        }
+        (
+            Client::from_conf(builder.build()),
+            StreamsClient::from_conf(streams_builder.build()),
+        )
+    }
+
+    /// The mapping of the table's items, whose primary key is the key of the DynamoDB table.
+    fn get_table(
+        &self,
+        table_info: &TableInfo,
+        key: Vec<String>,
+    ) -> Result<WebhookTable, ConnectorError> {
+        let config = self.table_config(&table_info.name)?;
+        let mapping = ingestion_types::WebhookTable {
+            name: config.name.clone(),
+            path: None,
+            columns: config.columns.clone(),
+            primary_key: key,
+            operation_field: None,
+        };
+        Ok(
+            WebhookTable::new(&mapping, &table_info.column_names)
+                .map_err(DynamoDbError::Mapping)?,
+        )
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        if !(1..=MAX_SCAN_SEGMENTS).contains(&self.config.scan_segments) {
+            return Err(DynamoDbError::InvalidScanSegments(self.config.scan_segments).into());
+        }
+        let (client, streams_client) = self.clients().await;
+        let mut streams = vec![];
+        for table in &tables {
+            let description = describe_table(&client, &table.name).await?;
+            streams.push(TableStream {
+                name: table.name.clone(),
+                mapping: self.get_table(table, description.key)?,
+                stream_arn: description.stream_arn,
+                ttl_deletes: self.table_config(&table.name)?.ttl_deletes,
+            });
+        }
+        let mut checkpoint =
+            Checkpoint::open(self.config.checkpoint_path.as_ref().map(PathBuf::from))?;
+        let mut seq_no = 0;
+
+        // Tables are snapshotted again if they weren't before their current stream.
+        let snapshots = streams
+            .iter()
+            .enumerate()
+            .filter(|(_, stream)| checkpoint.table(&stream.name, &stream.stream_arn).is_none())
+            .map(|(table_index, _)| table_index)
+            .collect::<Vec<_>>();
+        if !snapshots.is_empty() {
+            ingestor
+                .handle_message(IngestionMessage::new_snapshotting_started(0, seq_no))
+                .map_err(ConnectorError::IngestorError)?;
+            for table_index in snapshots {
+                let stream = &streams[table_index];
+                let started_at = Utc::now().timestamp();
+                self.snapshot(&client, ingestor, table_index, stream, &mut seq_no)
+                    .await?;
+                checkpoint.snapshotted(&stream.name, &stream.stream_arn, started_at);
+            }
+            checkpoint.save()?;
+            ingestor
+                .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        let refresh_interval = Duration::from_millis(self.config.shard_refresh_interval_ms);
+        let mut readers = vec![];
+        let mut refreshed_at: Option<Instant> = None;
+        loop {
+            if refreshed_at.map_or(true, |at| at.elapsed() >= refresh_interval) {
+                for (table_index, stream) in streams.iter().enumerate() {
+                    self.refresh_shards(
+                        &streams_client,
+                        table_index,
+                        stream,
+                        &mut checkpoint,
+                        &mut readers,
+                    )
+                    .await?;
+                }
+                refreshed_at = Some(Instant::now());
+            }
+
+            for reader in &mut readers {
+                let stream = &streams[reader.table_index];
+                let output = match streams_client
+                    .get_records()
+                    .shard_iterator(&reader.iterator)
+                    .send()
+                    .await
+                {
+                    Ok(output) => output,
+                    Err(e) => match e.as_service_error() {
+                        // Iterators expire 15 minutes after they are returned, and the records they point to are
+                        // trimmed 24 hours after they were written.
+                        Some(service_error)
+                            if service_error.is_expired_iterator_exception()
+                                || service_error.is_trimmed_data_access_exception() =>
+                        {
+                            reader.iterator = self
+                                .shard_iterator(
+                                    &streams_client,
+                                    stream,
+                                    &reader.shard_id,
+                                    checkpoint.get(&stream.name, &reader.shard_id),
+                                )
+                                .await?;
+                            continue;
+                        }
+                        // Retried at the next poll.
+                        Some(service_error) if service_error.is_limit_exceeded_exception() => {
+                            continue
+                        }
+                        _ => return Err(request_error(e).into()),
+                    },
+                };
+
+                let snapshot_started_at = checkpoint
+                    .table(&stream.name, &stream.stream_arn)
+                    .map_or(0, |table| table.snapshot_started_at);
+                for record in output.records().unwrap_or_default() {
+                    if let Some(op) = stream_operation(stream, record, snapshot_started_at)
+                        .map_err(|e| DynamoDbError::InvalidItem(stream.name.clone(), e))?
+                    {
+                        seq_no += 1;
+                        ingestor
+                            .handle_message(IngestionMessage::new_op(
+                                0,
+                                seq_no,
+                                reader.table_index,
+                                op,
+                            ))
+                            .map_err(ConnectorError::IngestorError)?;
+                    }
+                    if let Some(sequence_number) = record
+                        .dynamodb()
+                        .and_then(|stream_record| stream_record.sequence_number())
+                    {
+                        checkpoint.set(
+                            &stream.name,
+                            &reader.shard_id,
+                            ShardPosition::SequenceNumber(sequence_number.to_string()),
+                        );
+                    }
+                }
+
+                match output.next_shard_iterator() {
+                    Some(iterator) => reader.iterator = iterator.to_string(),
+                    None => {
+                        // The shard was closed, so its children can be read now.
+                        info!(
+                            "[{}] Finished shard {} of table {}",
+                            self.name, reader.shard_id, stream.name
+                        );
+                        checkpoint.set(&stream.name, &reader.shard_id, ShardPosition::Finished);
+                        refreshed_at = None;
+                    }
+                }
+            }
+            readers.retain(|reader| {
+                !checkpoint.is_finished(&streams[reader.table_index].name, &reader.shard_id)
+            });
+            checkpoint.save()?;
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Inserts the items of a table, scanning its segments in parallel.
+    async fn snapshot(
+        &self,
+        client: &Client,
+        ingestor: &Ingestor,
+        table_index: usize,
+        stream: &TableStream,
+        seq_no: &mut u64,
+    ) -> Result<(), ConnectorError> {
+        let segments = self.config.scan_segments;
+        let (sender, mut receiver) = mpsc::channel(segments as usize);
+        for segment in 0..segments {
+            let client = client.clone();
+            let name = stream.name.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let mut start_key = None;
+                loop {
+                    // Consistent reads have the writes made before the snapshot started.
+                    let output = match client
+                        .scan()
+                        .table_name(&name)
+                        .segment(segment as i32)
+                        .total_segments(segments as i32)
+                        .consistent_read(true)
+                        .set_exclusive_start_key(start_key)
+                        .send()
+                        .await
+                    {
+                        Ok(output) => output,
+                        Err(e) => {
+                            let _ = sender.send(Err(request_error(e))).await;
+                            return;
+                        }
+                    };
+                    start_key = output.last_evaluated_key().cloned();
+                    let items = output.items().unwrap_or_default().to_vec();
+                    // The receiver is dropped if another segment failed.
+                    if sender.send(Ok(items)).await.is_err() || start_key.is_none() {
+                        return;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        info!("[{}] Scanning table {}", self.name, stream.name);
+        let mut count = 0;
+        while let Some(items) = receiver.recv().await {
+            for item in items? {
+                let new = stream
+                    .mapping
+                    .record(&item_to_json(&item))
+                    .map_err(|e| DynamoDbError::InvalidItem(stream.name.clone(), e))?;
+                *seq_no += 1;
+                ingestor
+                    .handle_message(IngestionMessage::new_op(
+                        0,
+                        *seq_no,
+                        table_index,
+                        Operation::Insert { new },
+                    ))
+                    .map_err(ConnectorError::IngestorError)?;
+                count += 1;
+            }
+        }
+        info!(
+            "[{}] Scanned {} items of table {}",
+            self.name, count, stream.name
+        );
+        Ok(())
+    }
+
+    /// Starts reading the shards of the table's stream that became readable, and forgets the trimmed ones.
+    async fn refresh_shards(
+        &self,
+        client: &StreamsClient,
+        table_index: usize,
+        stream: &TableStream,
+        checkpoint: &mut Checkpoint,
+        readers: &mut Vec<ShardReader>,
+    ) -> Result<(), ConnectorError> {
+        let shards = list_shards(client, &stream.stream_arn).await?;
+        let shard_ids = shards
+            .iter()
+            .map(|shard| shard.id.as_str())
+            .collect::<Vec<_>>();
+        checkpoint.retain_shards(&stream.name, &shard_ids);
+
+        for shard in readable_shards(&shards, |id| checkpoint.is_finished(&stream.name, id)) {
+            if readers
+                .iter()
+                .any(|reader| reader.table_index == table_index && reader.shard_id == shard.id)
+            {
+                continue;
+            }
+            let iterator = self
+                .shard_iterator(
+                    client,
+                    stream,
+                    &shard.id,
+                    checkpoint.get(&stream.name, &shard.id),
+                )
+                .await?;
+            info!(
+                "[{}] Reading shard {} of table {}",
+                self.name, shard.id, stream.name
+            );
+            readers.push(ShardReader {
+                table_index,
+                shard_id: shard.id.clone(),
+                iterator,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator reading a shard after its checkpointed sequence number, or from its oldest record if there's
+    /// none or the records after it were trimmed.
+    async fn shard_iterator(
+        &self,
+        client: &StreamsClient,
+        stream: &TableStream,
+        shard_id: &str,
+        position: Option<&ShardPosition>,
+    ) -> Result<String, DynamoDbError> {
+        let request = client
+            .get_shard_iterator()
+            .stream_arn(&stream.stream_arn)
+            .shard_id(shard_id);
+        if let Some(ShardPosition::SequenceNumber(sequence_number)) = position {
+            match request
+                .clone()
+                .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+                .sequence_number(sequence_number)
+                .send()
+                .await
+            {
+                Ok(output) => return Ok(output.shard_iterator().unwrap_or_default().to_string()),
+                Err(e)
+                    if e.as_service_error()
+                        .map_or(false, |e| e.is_trimmed_data_access_exception()) =>
+                {
+                    warn!(
+                        "[{}] Records of shard {} of table {} after {} were trimmed and are lost, reading from the oldest record",
+                        self.name, shard_id, stream.name, sequence_number
+                    );
+                }
+                Err(e) => return Err(request_error(e)),
+            }
+        }
+        let output = request
+            .shard_iterator_type(ShardIteratorType::TrimHorizon)
+            .send()
+            .await
+            .map_err(request_error)?;
+        Ok(output.shard_iterator().unwrap_or_default().to_string())
+    }
+}
+
+#[async_trait]
+impl Connector for DynamoDbConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("S".to_string(), Some(FieldType::String)),
+            ("N".to_string(), Some(FieldType::Decimal)),
+            ("B".to_string(), Some(FieldType::Binary)),
+            ("BOOL".to_string(), Some(FieldType::Boolean)),
+            ("M".to_string(), Some(FieldType::Json)),
+            ("L".to_string(), Some(FieldType::Json)),
+            ("SS".to_string(), Some(FieldType::Json)),
+            ("NS".to_string(), Some(FieldType::Json)),
+            ("BS".to_string(), Some(FieldType::Json)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let (client, _) = self.clients().await;
+        client
+            .list_tables()
+            .limit(1)
+            .send()
+            .await
+            .map_err(request_error)?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .config
+            .tables
+            .iter()
+            .map(|table| TableIdentifier::from_table_name(table.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let (client, _) = self.clients().await;
+        for table in tables {
+            if table.schema.is_some() || self.table_config(&table.name).is_err() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    table.schema.as_deref(),
+                    &table.name,
+                )));
+            }
+            describe_table(&client, &table.name).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let config = self.table_config(&table.name)?;
+                Ok(TableInfo {
+                    column_names: config
+                        .columns
+                        .iter()
+                        .map(|column| column.name.clone())
+                        .collect(),
+                    schema: table.schema,
+                    name: table.name,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let (client, _) = self.clients().await;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            let schema = match describe_table(&client, &table_info.name).await {
+                // Updates and deletes only carry the key of the old item.
+                Ok(description) => self
+                    .get_table(table_info, description.key)
+                    .map(|table| SourceSchema::new(table.schema(), CdcType::OnlyPK)),
+                Err(e) => Err(e),
+            };
+            schemas.push(schema);
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+/// Describes a table, whose stream must be enabled with new images.
+async fn describe_table(client: &Client, table: &str) -> Result<TableDescription, ConnectorError> {
+    let not_found = || ConnectorError::TableNotFound(table.to_string());
+    let output = match client.describe_table().table_name(table).send().await {
+        Ok(output) => output,
+        Err(e) => {
+            return Err(match e.as_service_error() {
+                Some(service_error) if service_error.is_resource_not_found_exception() => {
+                    not_found()
+                }
+                _ => request_error(e).into(),
+            })
+        }
+    };
+    let description = output.table().ok_or_else(not_found)?;
+    let key = description
+        .key_schema()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|element| element.attribute_name())
+        .map(ToString::to_string)
+        .collect();
+
+    let specification = description.stream_specification();
+    let stream_arn = match description.latest_stream_arn() {
+        Some(stream_arn)
+            if specification.and_then(|specification| specification.stream_enabled())
+                == Some(true) =>
+        {
+            stream_arn.to_string()
+        }
+        _ => return Err(DynamoDbError::StreamNotEnabled(table.to_string()).into()),
+    };
+    if !matches!(
+        specification.and_then(|specification| specification.stream_view_type()),
+        Some(StreamViewType::NewImage | StreamViewType::NewAndOldImages)
+    ) {
+        return Err(DynamoDbError::MissingNewImages(table.to_string()).into());
+    }
+    Ok(TableDescription { key, stream_arn })
+}
+
+async fn list_shards(
+    client: &StreamsClient,
+    stream_arn: &str,
+) -> Result<Vec<ShardInfo>, DynamoDbError> {
+    let mut shards = vec![];
+    let mut last_shard_id = None;
+    loop {
+        let output = client
+            .describe_stream()
+            .stream_arn(stream_arn)
+            .set_exclusive_start_shard_id(last_shard_id)
+            .send()
+            .await
+            .map_err(request_error)?;
+        let Some(description) = output.stream_description() else {
+            return Ok(shards);
+        };
+        shards.extend(
+            description
+                .shards()
+                .unwrap_or_default()
+                .iter()
+                .map(shard_info),
+        );
+        match description.last_evaluated_shard_id() {
+            Some(shard_id) => last_shard_id = Some(shard_id.to_string()),
+            None => return Ok(shards),
+        }
+    }
+}
+
+fn shard_info(shard: &aws_sdk_dynamodbstreams::types::Shard) -> ShardInfo {
+    ShardInfo {
+        id: shard.shard_id().unwrap_or_default().to_string(),
+        parent_ids: shard
+            .parent_shard_id()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect(),
+    }
+}
+
+/// The operation of a stream record, or `None` if the snapshot scanned it or it's a deletion by time to live that
+/// isn't applied.
+fn stream_operation(
+    stream: &TableStream,
+    record: &Record,
+    snapshot_started_at: i64,
+) -> Result<Option<Operation>, WebhookError> {
+    let Some(stream_record) = record.dynamodb() else {
+        return Ok(None);
+    };
+    if stream_record
+        .approximate_creation_date_time()
+        .map_or(false, |created_at| {
+            created_at.secs() < snapshot_started_at - SNAPSHOT_OVERLAP_SECS
+        })
+    {
+        return Ok(None);
+    }
+
+    let keys = stream_record
+        .keys()
+        .map(stream_item_to_json)
+        .ok_or(WebhookError::NotAnObject)?;
+    let new_image = || {
+        stream_record
+            .new_image()
+            .map(stream_item_to_json)
+            .ok_or(WebhookError::NotAnObject)
+    };
+    match record.event_name() {
+        Some(OperationType::Insert) => Ok(Some(Operation::Insert {
+            new: stream.mapping.record(&new_image()?)?,
+        })),
+        Some(OperationType::Modify) => Ok(Some(Operation::Update {
+            old: stream.mapping.key_record(&keys)?,
+            new: stream.mapping.record(&new_image()?)?,
+        })),
+        Some(OperationType::Remove) if !stream.ttl_deletes && is_ttl_delete(record) => Ok(None),
+        Some(OperationType::Remove) => Ok(Some(Operation::Delete {
+            old: stream.mapping.key_record(&keys)?,
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// Items expired by their time to live are deleted by the DynamoDB service.
+fn is_ttl_delete(record: &Record) -> bool {
+    record.user_identity().map_or(false, |identity| {
+        identity.r#type() == Some("Service")
+            && identity.principal_id() == Some("dynamodb.amazonaws.com")
+    })
+}
+
+fn request_error(e: impl std::error::Error + Send + Sync + 'static) -> DynamoDbError {
+    DynamoDbError::Request(Box::new(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodbstreams::primitives::DateTime;
+    use aws_sdk_dynamodbstreams::types::{AttributeValue, Identity, StreamRecord};
+    use dozer_types::ingestion_types::WebhookColumn;
+    use dozer_types::rust_decimal::Decimal;
+    use dozer_types::types::{Field, Record as DozerRecord};
+
+    use super::*;
+
+    const CREATED_AT: i64 = 1_686_000_000;
+
+    fn stream(ttl_deletes: bool) -> TableStream {
+        let column = |name: &str, typ: &str| WebhookColumn {
+            name: name.to_string(),
+            typ: typ.to_string(),
+            field: None,
+            nullable: false,
+            values: vec![],
+        };
+        let config = ingestion_types::WebhookTable {
+            name: "films".to_string(),
+            path: None,
+            columns: vec![column("id", "string"), column("rating", "decimal")],
+            primary_key: vec!["id".to_string()],
+            operation_field: None,
+        };
+        TableStream {
+            name: "films".to_string(),
+            mapping: WebhookTable::new(&config, &["id".to_string(), "rating".to_string()]).unwrap(),
+            stream_arn: "arn".to_string(),
+            ttl_deletes,
+        }
+    }
+
+    fn record(event_name: OperationType, new_image: bool, identity: Option<Identity>) -> Record {
+        let id = AttributeValue::S("film-1".to_string());
+        let mut stream_record = StreamRecord::builder()
+            .keys("id", id.clone())
+            .sequence_number("100")
+            .approximate_creation_date_time(DateTime::from_secs(CREATED_AT));
+        if new_image {
+            stream_record = stream_record
+                .new_image("id", id)
+                .new_image("rating", AttributeValue::N("8.7".to_string()));
+        }
+        Record::builder()
+            .event_name(event_name)
+            .dynamodb(stream_record.build())
+            .set_user_identity(identity)
+            .build()
+    }
+
+    #[test]
+    fn test_stream_operation() {
+        let stream = stream(true);
+        let id = Field::String("film-1".to_string());
+        let new = DozerRecord::new(vec![id.clone(), Field::Decimal(Decimal::new(87, 1))]);
+        let old = DozerRecord::new(vec![id, Field::Null]);
+
+        assert_eq!(
+            stream_operation(&stream, &record(OperationType::Insert, true, None), 0).unwrap(),
+            Some(Operation::Insert { new: new.clone() })
+        );
+        assert_eq!(
+            stream_operation(&stream, &record(OperationType::Modify, true, None), 0).unwrap(),
+            Some(Operation::Update {
+                old: old.clone(),
+                new
+            })
+        );
+        assert_eq!(
+            stream_operation(&stream, &record(OperationType::Remove, false, None), 0).unwrap(),
+            Some(Operation::Delete { old })
+        );
+        assert!(matches!(
+            stream_operation(&stream, &record(OperationType::Insert, false, None), 0),
+            Err(WebhookError::NotAnObject)
+        ));
+
+        // Records created well before the snapshot were scanned by it.
+        let insert = record(OperationType::Insert, true, None);
+        assert!(
+            stream_operation(&stream, &insert, CREATED_AT + SNAPSHOT_OVERLAP_SECS)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            stream_operation(&stream, &insert, CREATED_AT + SNAPSHOT_OVERLAP_SECS + 1)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_ttl_deletes() {
+        let ttl = Identity::builder()
+            .r#type("Service")
+            .principal_id("dynamodb.amazonaws.com")
+            .build();
+        let expired = record(OperationType::Remove, false, Some(ttl));
+        assert!(is_ttl_delete(&expired));
+        assert!(!is_ttl_delete(&record(OperationType::Remove, false, None)));
+
+        assert!(matches!(
+            stream_operation(&stream(true), &expired, 0).unwrap(),
+            Some(Operation::Delete { .. })
+        ));
+        assert_eq!(stream_operation(&stream(false), &expired, 0).unwrap(), None);
+    }
+}
@@ -0,0 +1,8 @@
+//! Amazon DynamoDB tables, snapshotted with parallel scans and then followed with DynamoDB Streams. Items are mapped
+//! to records like the payloads of webhooks.
+
+mod attributes;
+mod checkpoint;
+mod connector;
+
+pub use connector::DynamoDbConnector;
@@ -0,0 +1,301 @@
+use dozer_types::ingestion_types::ElasticsearchConfig;
+use dozer_types::serde_json::{json, Value};
+use reqwest::{Client, Method};
+
+use crate::connectors::rest::Auth;
+use crate::errors::ElasticsearchError;
+
+/// How long scroll contexts are kept between requests.
+const SCROLL_KEEP_ALIVE: &str = "5m";
+
+/// A client of the REST API that Elasticsearch and OpenSearch share.
+#[derive(Debug)]
+pub struct ElasticsearchClient {
+    client: Client,
+    url: String,
+    auth: Auth,
+}
+
+/// A shard of an index with the global checkpoint of its primary, the sequence number up to which every operation has
+/// been applied to all its copies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardCheckpoint {
+    pub index: String,
+    pub shard: u32,
+    pub global_checkpoint: i64,
+}
+
+impl ElasticsearchClient {
+    pub fn new(config: &ElasticsearchConfig) -> Self {
+        let auth = match (&config.api_key, &config.user) {
+            (Some(api_key), _) => Auth::Header {
+                name: "Authorization".to_string(),
+                value: format!("ApiKey {api_key}"),
+            },
+            (None, Some(user)) => Auth::Basic {
+                username: user.clone(),
+                password: config.password.clone(),
+            },
+            (None, None) => Auth::None,
+        };
+        Self {
+            client: Client::new(),
+            url: config.url.trim_end_matches('/').to_string(),
+            auth,
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value, ElasticsearchError> {
+        let url = format!("{}{}", self.url, path);
+        let mut request = self.auth.apply(self.client.request(method, &url));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(ElasticsearchError::Status(
+                url,
+                status.as_u16(),
+                response.text().await.unwrap_or_default(),
+            ))
+        }
+    }
+
+    /// Requests the root of the cluster, which has its name and version.
+    pub async fn info(&self) -> Result<Value, ElasticsearchError> {
+        self.request(Method::GET, "/", None).await
+    }
+
+    /// The open indices, without the hidden and system ones.
+    pub async fn list_indices(&self) -> Result<Vec<String>, ElasticsearchError> {
+        let indices = self
+            .request(Method::GET, "/_cat/indices?format=json&h=index", None)
+            .await?;
+        let mut indices = indices
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|index| index.get("index").and_then(Value::as_str))
+            .filter(|index| !index.starts_with('.'))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        indices.sort();
+        Ok(indices)
+    }
+
+    /// The mappings of an index, or of the indices of an alias or a pattern, by index.
+    pub async fn mapping(&self, index: &str) -> Result<Value, ElasticsearchError> {
+        self.request(Method::GET, &format!("/{index}/_mapping"), None)
+            .await
+    }
+
+    /// The checkpoints of the shards of an index, or of the indices of an alias or a pattern.
+    pub async fn shard_checkpoints(
+        &self,
+        index: &str,
+    ) -> Result<Vec<ShardCheckpoint>, ElasticsearchError> {
+        let stats = self
+            .request(
+                Method::GET,
+                &format!("/{index}/_stats/docs?level=shards"),
+                None,
+            )
+            .await?;
+        shard_checkpoints(&stats)
+    }
+
+    /// The greatest value of a field, as milliseconds since the Unix epoch for dates, or null if no document has it.
+    pub async fn max_value(&self, index: &str, field: &str) -> Result<Value, ElasticsearchError> {
+        let response = self
+            .request(
+                Method::POST,
+                &format!("/{index}/_search"),
+                Some(json!({"size": 0, "aggs": {"cursor": {"max": {"field": field}}}})),
+            )
+            .await?;
+        Ok(response
+            .pointer("/aggregations/cursor/value")
+            .map_or(Value::Null, cursor_value))
+    }
+
+    /// Starts scrolling the hits of a search. Searches with a `preference` are run on the shards it selects, e.g.
+    /// `_shards:0`.
+    pub async fn scroll(
+        &self,
+        index: &str,
+        preference: Option<&str>,
+        body: Value,
+    ) -> Result<Scroll<'_>, ElasticsearchError> {
+        let mut path = format!("/{index}/_search?scroll={SCROLL_KEEP_ALIVE}");
+        if let Some(preference) = preference {
+            path.push_str(&format!("&preference={preference}"));
+        }
+        let response = self.request(Method::POST, &path, Some(body)).await?;
+        let (scroll_id, hits) = scroll_page(response)?;
+        Ok(Scroll {
+            client: self,
+            scroll_id,
+            first_page: Some(hits),
+        })
+    }
+
+    async fn continue_scroll(
+        &self,
+        scroll_id: &str,
+    ) -> Result<(Option<String>, Vec<Value>), ElasticsearchError> {
+        let response = self
+            .request(
+                Method::POST,
+                "/_search/scroll",
+                Some(json!({"scroll": SCROLL_KEEP_ALIVE, "scroll_id": scroll_id})),
+            )
+            .await?;
+        scroll_page(response)
+    }
+
+    async fn clear_scroll(&self, scroll_id: &str) -> Result<(), ElasticsearchError> {
+        self.request(
+            Method::DELETE,
+            "/_search/scroll",
+            Some(json!({ "scroll_id": [scroll_id] })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// The pages of hits of a search, read with the scroll API.
+#[derive(Debug)]
+pub struct Scroll<'a> {
+    client: &'a ElasticsearchClient,
+    scroll_id: Option<String>,
+    first_page: Option<Vec<Value>>,
+}
+
+impl Scroll<'_> {
+    /// The next page of hits, or `None` once all of them are read and the scroll is cleared.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Value>>, ElasticsearchError> {
+        let hits = match (self.first_page.take(), &self.scroll_id) {
+            (Some(hits), _) => hits,
+            (None, Some(scroll_id)) => {
+                let (scroll_id, hits) = self.client.continue_scroll(scroll_id).await?;
+                self.scroll_id = scroll_id;
+                hits
+            }
+            (None, None) => vec![],
+        };
+        if !hits.is_empty() {
+            return Ok(Some(hits));
+        }
+        if let Some(scroll_id) = self.scroll_id.take() {
+            self.client.clear_scroll(&scroll_id).await?;
+        }
+        Ok(None)
+    }
+}
+
+fn scroll_page(response: Value) -> Result<(Option<String>, Vec<Value>), ElasticsearchError> {
+    let scroll_id = response
+        .get("_scroll_id")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    match response.pointer("/hits/hits") {
+        Some(Value::Array(hits)) => Ok((scroll_id, hits.clone())),
+        _ => Err(ElasticsearchError::UnexpectedResponse(
+            "search response without hits".to_string(),
+        )),
+    }
+}
+
+/// Reads the global checkpoints of the primary shards from index stats with the `shards` level.
+fn shard_checkpoints(stats: &Value) -> Result<Vec<ShardCheckpoint>, ElasticsearchError> {
+    let invalid = || ElasticsearchError::UnexpectedResponse("invalid shard stats".to_string());
+    let mut checkpoints = vec![];
+    let indices = stats
+        .get("indices")
+        .and_then(Value::as_object)
+        .ok_or_else(invalid)?;
+    for (index, index_stats) in indices {
+        let shards = index_stats
+            .get("shards")
+            .and_then(Value::as_object)
+            .ok_or_else(invalid)?;
+        for (shard, copies) in shards {
+            let primary = copies
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|copy| copy.pointer("/routing/primary") == Some(&Value::Bool(true)))
+                .ok_or_else(invalid)?;
+            checkpoints.push(ShardCheckpoint {
+                index: index.clone(),
+                shard: shard.parse().map_err(|_| invalid())?,
+                global_checkpoint: primary
+                    .pointer("/seq_no/global_checkpoint")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(invalid)?,
+            });
+        }
+    }
+    checkpoints.sort_by(|a, b| (&a.index, a.shard).cmp(&(&b.index, b.shard)));
+    Ok(checkpoints)
+}
+
+/// Aggregations return doubles, which are passed back as integers when they are, as date fields expect.
+fn cursor_value(value: &Value) -> Value {
+    match value.as_f64() {
+        Some(value) if value.fract() == 0.0 && value.abs() < i64::MAX as f64 => {
+            Value::from(value as i64)
+        }
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_checkpoints() {
+        let stats = json!({"indices": {"films": {"shards": {
+            "1": [
+                {"routing": {"primary": false}, "seq_no": {"global_checkpoint": 3}},
+                {"routing": {"primary": true}, "seq_no": {"global_checkpoint": 5}},
+            ],
+            "0": [{"routing": {"primary": true}, "seq_no": {"global_checkpoint": -1}}],
+        }}}});
+        assert_eq!(
+            shard_checkpoints(&stats).unwrap(),
+            vec![
+                ShardCheckpoint {
+                    index: "films".to_string(),
+                    shard: 0,
+                    global_checkpoint: -1,
+                },
+                ShardCheckpoint {
+                    index: "films".to_string(),
+                    shard: 1,
+                    global_checkpoint: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_value() {
+        assert_eq!(
+            cursor_value(&json!(1688169600000.0)),
+            json!(1688169600000_i64)
+        );
+        assert_eq!(cursor_value(&json!(1.5)), json!(1.5));
+        assert_eq!(cursor_value(&Value::Null), Value::Null);
+    }
+}
@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{ElasticsearchConfig, IngestionMessage};
+use dozer_types::log::info;
+use dozer_types::serde_json::{json, Value};
+use dozer_types::types::{FieldType, Operation};
+use tonic::async_trait;
+
+use super::client::{ElasticsearchClient, ShardCheckpoint};
+use super::schema::{hit_id, mapping_columns, Index, TYPES};
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, ElasticsearchError};
+use crate::ingestion::Ingestor;
+
+/// Reads the indices of an Elasticsearch or OpenSearch cluster with the scroll API, then polls the documents changed
+/// since, by the sequence numbers of the shards or by a cursor field.
+///
+/// Polling doesn't give the old document, so updates only have the document id of the old record, and deleted
+/// documents aren't found.
+#[derive(Debug)]
+pub struct ElasticsearchConnector {
+    name: String,
+    config: ElasticsearchConfig,
+}
+
+/// How the changed documents of an index are found.
+#[derive(Debug)]
+enum Changes {
+    /// The operations of each shard, by sequence number.
+    SeqNo(Vec<ShardChanges>),
+    /// The documents whose `field` is at least the greatest value read so far, except those at that value already read.
+    Cursor {
+        field: String,
+        cursor: Value,
+        at_cursor: HashSet<String>,
+    },
+}
+
+/// Operations after `read_up_to` and up to `checkpoint` are read next. The checkpoint is the global checkpoint of the
+/// previous poll, as operations up to it have been refreshed and are searchable by now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ShardChanges {
+    index: String,
+    shard: u32,
+    read_up_to: i64,
+    checkpoint: i64,
+}
+
+#[derive(Debug)]
+struct IndexReader {
+    index: Index,
+    changes: Changes,
+    /// The ids of the documents read, to tell inserts from updates.
+    ids: HashSet<String>,
+}
+
+impl IndexReader {
+    /// An insert of a document not read before, or an update.
+    fn operation(&mut self, hit: &Value) -> Result<Operation, ElasticsearchError> {
+        let new = self.index.record(hit)?;
+        let id = hit_id(hit)?;
+        if self.ids.insert(id.to_string()) {
+            Ok(Operation::Insert { new })
+        } else {
+            Ok(Operation::Update {
+                old: self.index.id_record(id),
+                new,
+            })
+        }
+    }
+}
+
+impl ElasticsearchConnector {
+    pub fn new(name: String, config: ElasticsearchConfig) -> Self {
+        Self { name, config }
+    }
+
+    /// Selects the columns of `table_info` from the mapping of its index.
+    async fn get_index(
+        &self,
+        client: &ElasticsearchClient,
+        table_info: &TableInfo,
+    ) -> Result<Index, ElasticsearchError> {
+        let columns = mapping_columns(&client.mapping(&table_info.name).await?);
+        let columns = table_info
+            .column_names
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .find(|column| column.name == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        ElasticsearchError::ColumnNotFound(name.clone(), table_info.name.clone())
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Index {
+            name: table_info.name.clone(),
+            columns,
+        })
+    }
+
+    /// Where changes are read from, found before the snapshot.
+    async fn start_changes(
+        &self,
+        client: &ElasticsearchClient,
+        index: &str,
+    ) -> Result<Changes, ElasticsearchError> {
+        Ok(match &self.config.cursor_field {
+            Some(field) => Changes::Cursor {
+                field: field.clone(),
+                cursor: client.max_value(index, field).await?,
+                at_cursor: HashSet::new(),
+            },
+            None => Changes::SeqNo(
+                client
+                    .shard_checkpoints(index)
+                    .await?
+                    .into_iter()
+                    .map(|checkpoint| ShardChanges {
+                        index: checkpoint.index,
+                        shard: checkpoint.shard,
+                        read_up_to: checkpoint.global_checkpoint,
+                        checkpoint: checkpoint.global_checkpoint,
+                    })
+                    .collect(),
+            ),
+        })
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        if self.config.batch_size == 0 {
+            return Err(ElasticsearchError::InvalidBatchSize.into());
+        }
+        let client = ElasticsearchClient::new(&self.config);
+        let mut readers = vec![];
+        for table_info in &tables {
+            let index = self.get_index(&client, table_info).await?;
+            let changes = self.start_changes(&client, &index.name).await?;
+            readers.push(IndexReader {
+                index,
+                changes,
+                ids: HashSet::new(),
+            });
+        }
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut seq_no = 0;
+        for (table_index, reader) in readers.iter_mut().enumerate() {
+            info!("[{}] Reading index {}", self.name, reader.index.name);
+            let body = json!({
+                "size": self.config.batch_size,
+                "_source": reader.index.source_fields(),
+                "sort": ["_doc"],
+            });
+            let mut scroll = client.scroll(&reader.index.name, None, body).await?;
+            while let Some(hits) = scroll.next_page().await? {
+                for hit in &hits {
+                    let op = reader.operation(hit)?;
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        info!("[{}] Polling every {:?}", self.name, poll_interval);
+        // Each poll with changes is a transaction.
+        let mut txid = 0;
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            let mut seq_no = 0;
+            for (table_index, reader) in readers.iter_mut().enumerate() {
+                for op in self.poll(&client, reader).await? {
+                    if seq_no == 0 {
+                        txid += 1;
+                    }
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+    }
+
+    /// Reads the documents of an index changed since the last poll.
+    async fn poll(
+        &self,
+        client: &ElasticsearchClient,
+        reader: &mut IndexReader,
+    ) -> Result<Vec<Operation>, ElasticsearchError> {
+        let source = reader.index.source_fields();
+        let mut hits = vec![];
+        match &mut reader.changes {
+            Changes::SeqNo(shards) => {
+                let checkpoints = client.shard_checkpoints(&reader.index.name).await?;
+                for shard in shards.iter_mut() {
+                    if shard.read_up_to >= shard.checkpoint {
+                        continue;
+                    }
+                    let body = json!({
+                        "size": self.config.batch_size,
+                        "_source": source,
+                        "query": {"range": {"_seq_no": {"gt": shard.read_up_to, "lte": shard.checkpoint}}},
+                        "sort": ["_doc"],
+                    });
+                    let preference = format!("_shards:{}", shard.shard);
+                    let mut scroll = client.scroll(&shard.index, Some(&preference), body).await?;
+                    while let Some(page) = scroll.next_page().await? {
+                        hits.extend(page);
+                    }
+                    shard.read_up_to = shard.checkpoint;
+                }
+                update_checkpoints(shards, checkpoints);
+            }
+            Changes::Cursor {
+                field,
+                cursor,
+                at_cursor,
+            } => {
+                let query = if cursor.is_null() {
+                    json!({"exists": {"field": field}})
+                } else {
+                    json!({"range": {field.as_str(): {"gte": cursor}}})
+                };
+                let body = json!({
+                    "size": self.config.batch_size,
+                    "_source": source,
+                    "query": query,
+                    "sort": [{field.as_str(): "asc"}],
+                });
+                let mut scroll = client.scroll(&reader.index.name, None, body).await?;
+                while let Some(page) = scroll.next_page().await? {
+                    for hit in page {
+                        let value = hit.pointer("/sort/0").cloned().unwrap_or(Value::Null);
+                        let id = hit_id(&hit)?.to_string();
+                        match compare_cursors(&value, cursor) {
+                            Ordering::Less => continue,
+                            Ordering::Equal => {
+                                if !at_cursor.insert(id) {
+                                    continue;
+                                }
+                            }
+                            Ordering::Greater => {
+                                *cursor = value;
+                                at_cursor.clear();
+                                at_cursor.insert(id);
+                            }
+                        }
+                        hits.push(hit);
+                    }
+                }
+            }
+        }
+        hits.iter().map(|hit| reader.operation(hit)).collect()
+    }
+}
+
+/// Sets the checkpoints of the shards to the global checkpoints of this poll. Shards of indices added to an alias are
+/// read from their first operation.
+fn update_checkpoints(shards: &mut Vec<ShardChanges>, checkpoints: Vec<ShardCheckpoint>) {
+    for checkpoint in checkpoints {
+        match shards
+            .iter_mut()
+            .find(|shard| shard.index == checkpoint.index && shard.shard == checkpoint.shard)
+        {
+            Some(shard) => shard.checkpoint = checkpoint.global_checkpoint,
+            None => shards.push(ShardChanges {
+                index: checkpoint.index,
+                shard: checkpoint.shard,
+                read_up_to: -1,
+                checkpoint: checkpoint.global_checkpoint,
+            }),
+        }
+    }
+}
+
+/// Orders the sort values of a cursor field, numbers for numeric and date fields. Null is before every value.
+fn compare_cursors(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .partial_cmp(&b.as_f64())
+                .unwrap_or(Ordering::Equal),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[async_trait]
+impl Connector for ElasticsearchConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        ElasticsearchClient::new(&self.config).info().await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(ElasticsearchClient::new(&self.config)
+            .list_indices()
+            .await?
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let client = ElasticsearchClient::new(&self.config);
+        for table in tables {
+            if table.schema.is_some() {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+            client.mapping(&table.name).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let client = ElasticsearchClient::new(&self.config);
+        let mut table_infos = vec![];
+        for table in tables {
+            let columns = mapping_columns(&client.mapping(&table.name).await?);
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: columns.into_iter().map(|column| column.name).collect(),
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let client = ElasticsearchClient::new(&self.config);
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                self.get_index(&client, table_info)
+                    .await
+                    .map(|index| SourceSchema::new(index.schema(), CdcType::OnlyPK))
+                    .map_err(ConnectorError::from),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_checkpoints() {
+        let checkpoint = |index: &str, shard: u32, global_checkpoint: i64| ShardCheckpoint {
+            index: index.to_string(),
+            shard,
+            global_checkpoint,
+        };
+        let mut shards = vec![ShardChanges {
+            index: "films-1".to_string(),
+            shard: 0,
+            read_up_to: 5,
+            checkpoint: 5,
+        }];
+        update_checkpoints(
+            &mut shards,
+            vec![checkpoint("films-1", 0, 8), checkpoint("films-2", 0, 2)],
+        );
+        assert_eq!(
+            shards,
+            vec![
+                ShardChanges {
+                    index: "films-1".to_string(),
+                    shard: 0,
+                    read_up_to: 5,
+                    checkpoint: 8,
+                },
+                ShardChanges {
+                    index: "films-2".to_string(),
+                    shard: 0,
+                    read_up_to: -1,
+                    checkpoint: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_cursors() {
+        assert_eq!(
+            compare_cursors(&json!(1688169600001_i64), &json!(1688169600000_i64)),
+            Ordering::Greater
+        );
+        assert_eq!(compare_cursors(&json!(1.5), &json!(2)), Ordering::Less);
+        assert_eq!(compare_cursors(&json!(2), &json!(2)), Ordering::Equal);
+        assert_eq!(compare_cursors(&Value::Null, &json!(0)), Ordering::Less);
+    }
+}
@@ -0,0 +1,9 @@
+//! Elasticsearch and OpenSearch indices, read with the scroll API and then polled for the documents changed since, by
+//! the sequence numbers of their shards or by a cursor field. Mapping types are mapped to field types, and objects to
+//! json.
+
+mod client;
+mod connector;
+mod schema;
+
+pub use connector::ElasticsearchConnector;
@@ -0,0 +1,367 @@
+use base64::Engine;
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offset, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{
+    DozerPoint, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
+
+use crate::errors::ElasticsearchError;
+
+/// The column of the document id, the primary key of every index.
+pub const ID_COLUMN: &str = "_id";
+
+/// Field types of the mapping types. Other types, e.g. ranges and vectors, are mapped to json.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("keyword", FieldType::String),
+    ("constant_keyword", FieldType::String),
+    ("wildcard", FieldType::String),
+    ("ip", FieldType::String),
+    ("version", FieldType::String),
+    ("text", FieldType::Text),
+    ("match_only_text", FieldType::Text),
+    ("long", FieldType::Int),
+    ("integer", FieldType::Int),
+    ("short", FieldType::Int),
+    ("byte", FieldType::Int),
+    ("unsigned_long", FieldType::UInt),
+    ("double", FieldType::Float),
+    ("float", FieldType::Float),
+    ("half_float", FieldType::Float),
+    ("scaled_float", FieldType::Float),
+    ("boolean", FieldType::Boolean),
+    ("date", FieldType::Timestamp),
+    ("date_nanos", FieldType::Timestamp),
+    ("binary", FieldType::Binary),
+    ("geo_point", FieldType::Point),
+    ("object", FieldType::Json),
+    ("nested", FieldType::Json),
+    ("flattened", FieldType::Json),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub typ: FieldType,
+}
+
+/// The requested columns of an index, the document id being the first.
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+impl Index {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for column in &self.columns {
+            let is_id = column.name == ID_COLUMN;
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    !is_id,
+                    SourceDefinition::Dynamic,
+                ),
+                is_id,
+            );
+        }
+        schema
+    }
+
+    /// Maps a search hit to a record of the columns. Fields a document doesn't have, or whose value can't be mapped to
+    /// their column, are null.
+    pub fn record(&self, hit: &Value) -> Result<Record, ElasticsearchError> {
+        let id = hit_id(hit)?;
+        let source = hit.get("_source");
+        let values = self
+            .columns
+            .iter()
+            .map(|column| {
+                if column.name == ID_COLUMN {
+                    return Field::String(id.to_string());
+                }
+                source
+                    .and_then(|source| source.get(&column.name))
+                    .map_or(Field::Null, |value| to_field(value, column.typ))
+            })
+            .collect();
+        Ok(Record::new(values))
+    }
+
+    /// A record with only the document id, for updates where the old document isn't known.
+    pub fn id_record(&self, id: &str) -> Record {
+        let values = self
+            .columns
+            .iter()
+            .map(|column| {
+                if column.name == ID_COLUMN {
+                    Field::String(id.to_string())
+                } else {
+                    Field::Null
+                }
+            })
+            .collect();
+        Record::new(values)
+    }
+
+    /// The fields of `_source` to request.
+    pub fn source_fields(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|column| column.name != ID_COLUMN)
+            .map(|column| column.name.as_str())
+            .collect()
+    }
+}
+
+pub fn hit_id(hit: &Value) -> Result<&str, ElasticsearchError> {
+    hit.get("_id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ElasticsearchError::UnexpectedResponse("hit without an id".to_string()))
+}
+
+/// The columns of an index from the response of `GET /{index}/_mapping`: the document id, then the top level fields of
+/// the mappings. If the index is an alias or a pattern, the fields of all its indices are merged, the first index
+/// having a field giving its type.
+pub fn mapping_columns(mapping: &Value) -> Vec<Column> {
+    let mut columns = vec![Column {
+        name: ID_COLUMN.to_string(),
+        typ: FieldType::String,
+    }];
+    let Some(indices) = mapping.as_object() else {
+        return columns;
+    };
+    let mut names = indices.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let properties = indices[name]
+            .get("mappings")
+            .and_then(|mappings| mappings.get("properties"))
+            .and_then(Value::as_object);
+        for (field, property) in properties.into_iter().flatten() {
+            if columns.iter().any(|column| &column.name == field) {
+                continue;
+            }
+            columns.push(Column {
+                name: field.clone(),
+                typ: property_type(property),
+            });
+        }
+    }
+    columns[1..].sort_by(|a, b| a.name.cmp(&b.name));
+    columns
+}
+
+fn property_type(property: &Value) -> FieldType {
+    // Objects have no type, only properties.
+    let typ = property
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("object");
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == typ)
+        .map_or(FieldType::Json, |(_, typ)| *typ)
+}
+
+/// Converts a value of `_source` to a field of `typ`, null if it can't be. Values are as they were indexed, so numbers
+/// can be strings, and single values can be in arrays.
+pub fn to_field(value: &Value, typ: FieldType) -> Field {
+    let value = match value {
+        Value::Array(values) if typ != FieldType::Json && typ != FieldType::Point => {
+            match values.as_slice() {
+                [value] => value,
+                _ => return Field::Null,
+            }
+        }
+        value => value,
+    };
+    let field = match (typ, value) {
+        (_, Value::Null) => None,
+        (FieldType::Json, value) => serde_json_to_json_value(value.clone())
+            .ok()
+            .map(Field::Json),
+        (FieldType::String, Value::String(value)) => Some(Field::String(value.clone())),
+        (FieldType::String, Value::Number(_) | Value::Bool(_)) => {
+            Some(Field::String(value.to_string()))
+        }
+        (FieldType::Text, Value::String(value)) => Some(Field::Text(value.clone())),
+        (FieldType::Int, Value::Number(value)) => value.as_i64().map(Field::Int),
+        (FieldType::Int, Value::String(value)) => value.parse().ok().map(Field::Int),
+        (FieldType::UInt, Value::Number(value)) => value.as_u64().map(Field::UInt),
+        (FieldType::UInt, Value::String(value)) => value.parse().ok().map(Field::UInt),
+        (FieldType::Float, Value::Number(value)) => value
+            .as_f64()
+            .map(|value| Field::Float(OrderedFloat(value))),
+        (FieldType::Float, Value::String(value)) => value
+            .parse()
+            .ok()
+            .map(|value| Field::Float(OrderedFloat(value))),
+        (FieldType::Boolean, Value::Bool(value)) => Some(Field::Boolean(*value)),
+        (FieldType::Boolean, Value::String(value)) => value.parse().ok().map(Field::Boolean),
+        (FieldType::Timestamp, value) => to_timestamp(value).map(Field::Timestamp),
+        (FieldType::Binary, Value::String(value)) => base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .ok()
+            .map(Field::Binary),
+        (FieldType::Point, value) => to_point(value).map(Field::Point),
+        _ => None,
+    };
+    field.unwrap_or(Field::Null)
+}
+
+/// Dates are milliseconds since the Unix epoch, or strings in the default format of `date` fields, times without an
+/// offset being UTC.
+fn to_timestamp(value: &Value) -> Option<DateTime<FixedOffset>> {
+    let utc = |timestamp: NaiveDateTime| DateTime::from_utc(timestamp, Utc.fix());
+    match value {
+        Value::Number(millis) => NaiveDateTime::from_timestamp_millis(millis.as_i64()?).map(utc),
+        Value::String(value) => DateTime::parse_from_rfc3339(value)
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .map(utc)
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(utc)
+            })
+            .or_else(|| NaiveDateTime::from_timestamp_millis(value.parse().ok()?).map(utc)),
+        _ => None,
+    }
+}
+
+/// Geo points are objects with `lat` and `lon`, `[lon, lat]` arrays, or `"lat,lon"` strings.
+fn to_point(value: &Value) -> Option<DozerPoint> {
+    let (lon, lat) = match value {
+        Value::Object(point) => (point.get("lon")?.as_f64()?, point.get("lat")?.as_f64()?),
+        Value::Array(point) => match point.as_slice() {
+            [lon, lat] => (lon.as_f64()?, lat.as_f64()?),
+            _ => return None,
+        },
+        Value::String(point) => {
+            let (lat, lon) = point.split_once(',')?;
+            (lon.trim().parse().ok()?, lat.trim().parse().ok()?)
+        }
+        _ => return None,
+    };
+    Some(DozerPoint::from((lon, lat)))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_mapping_columns() {
+        let mapping = json!({
+            "films-2": {"mappings": {"properties": {
+                "title": {"type": "keyword"},
+                "rating": {"type": "float"},
+            }}},
+            "films-1": {"mappings": {"properties": {
+                "title": {"type": "text"},
+                "released": {"type": "date"},
+                "cast": {"properties": {"name": {"type": "keyword"}}},
+                "embedding": {"type": "dense_vector"},
+            }}},
+        });
+        let columns = mapping_columns(&mapping)
+            .into_iter()
+            .map(|column| (column.name, column.typ))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("_id".to_string(), FieldType::String),
+                ("cast".to_string(), FieldType::Json),
+                ("embedding".to_string(), FieldType::Json),
+                ("rating".to_string(), FieldType::Float),
+                ("released".to_string(), FieldType::Timestamp),
+                ("title".to_string(), FieldType::Text),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_field() {
+        assert_eq!(to_field(&json!("5"), FieldType::Int), Field::Int(5));
+        assert_eq!(to_field(&json!([5]), FieldType::Int), Field::Int(5));
+        assert_eq!(to_field(&json!([5, 6]), FieldType::Int), Field::Null);
+        assert_eq!(to_field(&json!("five"), FieldType::Int), Field::Null);
+        assert_eq!(
+            to_field(&json!(42), FieldType::String),
+            Field::String("42".to_string())
+        );
+        assert_eq!(
+            to_field(&json!(1_688_169_600_000_i64), FieldType::Timestamp),
+            to_field(&json!("2023-07-01T00:00:00Z"), FieldType::Timestamp)
+        );
+        assert_eq!(
+            to_field(&json!("2023-07-01"), FieldType::Timestamp),
+            to_field(&json!("2023-07-01T00:00:00"), FieldType::Timestamp)
+        );
+        assert_eq!(
+            to_field(&json!("2023-07-01T02:00:00+02:00"), FieldType::Timestamp),
+            to_field(&json!("1688169600000"), FieldType::Timestamp)
+        );
+        let point = Field::Point(DozerPoint::from((2.35, 48.85)));
+        assert_eq!(
+            to_field(&json!({"lat": 48.85, "lon": 2.35}), FieldType::Point),
+            point
+        );
+        assert_eq!(to_field(&json!([2.35, 48.85]), FieldType::Point), point);
+        assert_eq!(to_field(&json!("48.85, 2.35"), FieldType::Point), point);
+        assert_eq!(
+            to_field(&json!("aGVsbG8="), FieldType::Binary),
+            Field::Binary(b"hello".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let index = Index {
+            name: "films".to_string(),
+            columns: vec![
+                Column {
+                    name: ID_COLUMN.to_string(),
+                    typ: FieldType::String,
+                },
+                Column {
+                    name: "rating".to_string(),
+                    typ: FieldType::Float,
+                },
+                Column {
+                    name: "title".to_string(),
+                    typ: FieldType::Text,
+                },
+            ],
+        };
+        let hit = json!({"_id": "1", "_source": {"rating": 8.5}});
+        assert_eq!(
+            index.record(&hit).unwrap(),
+            Record::new(vec![
+                Field::String("1".to_string()),
+                Field::Float(OrderedFloat(8.5)),
+                Field::Null,
+            ])
+        );
+        assert_eq!(
+            index.id_record("1"),
+            Record::new(vec![
+                Field::String("1".to_string()),
+                Field::Null,
+                Field::Null
+            ])
+        );
+        assert_eq!(index.source_fields(), vec!["rating", "title"]);
+    }
+}
@@ -39,6 +39,7 @@ pub fn get_contract_event_schemas(
                     },
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 });
             }
 
@@ -232,72 +233,84 @@ pub fn get_eth_schema() -> Schema {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "address".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "topics".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "data".to_string(),
                 typ: FieldType::Binary,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "block_hash".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "block_number".to_string(),
                 typ: FieldType::UInt,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "transaction_hash".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "transaction_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "log_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "transaction_log_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "log_type".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "removed".to_string(),
                 typ: FieldType::Boolean,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
         ],
 
@@ -93,48 +93,56 @@ pub fn get_trace_schema() -> Schema {
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "from".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "to".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "value".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "gas".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "gas_used".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "input".to_string(),
                 typ: FieldType::Text,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             FieldDefinition {
                 name: "output".to_string(),
                 typ: FieldType::Text,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
         ],
         primary_index: vec![],
@@ -0,0 +1,344 @@
+use std::collections::HashSet;
+
+use dozer_types::ingestion_types::{FirestoreConfig, IngestionMessage};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation};
+use firestore::gcloud_sdk::google::firestore::v1::target_change::TargetChangeType;
+use firestore::{
+    FirestoreDb, FirestoreDbOptions, FirestoreListenEvent, FirestoreListenerTarget,
+    FirestoreMemListenStateStorage,
+};
+use futures::StreamExt;
+use tokio::sync::mpsc::unbounded_channel;
+use tonic::async_trait;
+
+use super::schema::{infer_columns, Collection, Column, NestedFields};
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, FirestoreError};
+use crate::ingestion::Ingestor;
+
+/// Firestore value types and the types they are mapped to. Nested maps are flattened or mapped to json.
+const TYPES: &[(&str, FieldType)] = &[
+    ("boolean", FieldType::Boolean),
+    ("integer", FieldType::Int),
+    ("double", FieldType::Float),
+    ("timestamp", FieldType::Timestamp),
+    ("string", FieldType::String),
+    ("reference", FieldType::String),
+    ("bytes", FieldType::Binary),
+    ("geopoint", FieldType::Point),
+    ("array", FieldType::Json),
+    ("map", FieldType::Json),
+];
+
+/// Listens to the documents of collections. The documents sent before every collection is current are the snapshot.
+///
+/// Listening doesn't give the old document of changes, so updates and deletes have only the document id of the old
+/// record.
+#[derive(Debug)]
+pub struct FirestoreConnector {
+    name: String,
+    config: FirestoreConfig,
+    nested_fields: NestedFields,
+}
+
+impl FirestoreConnector {
+    pub fn new(name: String, config: FirestoreConfig) -> Result<Self, ConnectorError> {
+        let nested_fields = NestedFields::parse(&config.nested_fields)?;
+        Ok(Self {
+            name,
+            config,
+            nested_fields,
+        })
+    }
+
+    async fn connect(&self) -> Result<FirestoreDb, FirestoreError> {
+        Ok(FirestoreDb::with_options(
+            FirestoreDbOptions::new(self.config.project_id.clone())
+                .with_database_id(self.config.database_id.clone()),
+        )
+        .await?)
+    }
+
+    async fn list_collections(&self, db: &FirestoreDb) -> Result<Vec<String>, FirestoreError> {
+        Ok(db
+            .fluent()
+            .list()
+            .collections()
+            .stream_all()
+            .await?
+            .collect()
+            .await)
+    }
+
+    /// Infers the columns of a collection from a sample of its documents.
+    async fn infer_columns(
+        &self,
+        db: &FirestoreDb,
+        collection: &str,
+    ) -> Result<Vec<Column>, FirestoreError> {
+        let documents = db
+            .fluent()
+            .select()
+            .from(collection)
+            .limit(self.config.schema_sample_size)
+            .query()
+            .await?;
+        if documents.is_empty() {
+            return Err(FirestoreError::EmptyCollection(collection.to_string()));
+        }
+        Ok(infer_columns(&documents, self.nested_fields))
+    }
+
+    /// Selects the columns of `table_info` from those inferred.
+    async fn get_collection(
+        &self,
+        db: &FirestoreDb,
+        table_info: &TableInfo,
+    ) -> Result<Collection, FirestoreError> {
+        let columns = self.infer_columns(db, &table_info.name).await?;
+        let columns = table_info
+            .column_names
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .find(|column| column.name == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        FirestoreError::ColumnNotFound(name.clone(), table_info.name.clone())
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Collection {
+            name: table_info.name.clone(),
+            columns,
+        })
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let db = self.connect().await?;
+        let mut collections = vec![];
+        for table_info in &tables {
+            collections.push(self.get_collection(&db, table_info).await?);
+        }
+
+        // Target ids are the table indexes plus one, as zero is not a valid target id.
+        let mut listener = db
+            .create_listener(FirestoreMemListenStateStorage::new())
+            .await
+            .map_err(FirestoreError::from)?;
+        for (table_index, collection) in collections.iter().enumerate() {
+            db.fluent()
+                .select()
+                .from(collection.name.as_str())
+                .listen()
+                .add_target(
+                    FirestoreListenerTarget::new(table_index as u32 + 1),
+                    &mut listener,
+                )
+                .map_err(FirestoreError::from)?;
+        }
+
+        // The listener calls back on its own task, so events are sent here to be ingested.
+        let (sender, mut receiver) = unbounded_channel();
+        listener
+            .start(move |event| {
+                let sender = sender.clone();
+                async move {
+                    sender.send(event)?;
+                    Ok(())
+                }
+            })
+            .await
+            .map_err(FirestoreError::from)?;
+        info!("[{}] Listening to {} collections", self.name, tables.len());
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut current_targets = HashSet::new();
+        let mut snapshotting = true;
+        let mut seq_no = 0;
+        let mut txid = 0;
+        while let Some(event) = receiver.recv().await {
+            let mut ops = vec![];
+            match event {
+                FirestoreListenEvent::TargetChange(change) => {
+                    if change.target_change_type == TargetChangeType::Current as i32 && snapshotting
+                    {
+                        // A change without targets applies to all of them.
+                        if change.target_ids.is_empty() {
+                            current_targets.extend(1..=collections.len() as i32);
+                        } else {
+                            current_targets.extend(change.target_ids);
+                        }
+                        if current_targets.len() == collections.len() {
+                            snapshotting = false;
+                            ingestor
+                                .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+                                .map_err(ConnectorError::IngestorError)?;
+                        }
+                    }
+                    if let Some(cause) = change.cause {
+                        return Err(FirestoreError::ListenError(cause.message).into());
+                    }
+                }
+                FirestoreListenEvent::DocumentChange(change) => {
+                    let Some(document) = change.document else {
+                        continue;
+                    };
+                    for (table_index, collection) in targets(&collections, &change.target_ids) {
+                        let new = collection.record(&document, self.nested_fields);
+                        let op = if snapshotting || document.create_time == document.update_time {
+                            Operation::Insert { new }
+                        } else {
+                            Operation::Update {
+                                old: collection.id_record(&document.name),
+                                new,
+                            }
+                        };
+                        ops.push((table_index, op));
+                    }
+                }
+                FirestoreListenEvent::DocumentDelete(delete) => {
+                    for (table_index, collection) in
+                        targets(&collections, &delete.removed_target_ids)
+                    {
+                        ops.push((
+                            table_index,
+                            Operation::Delete {
+                                old: collection.id_record(&delete.document),
+                            },
+                        ));
+                    }
+                }
+                FirestoreListenEvent::DocumentRemove(remove) => {
+                    for (table_index, collection) in
+                        targets(&collections, &remove.removed_target_ids)
+                    {
+                        ops.push((
+                            table_index,
+                            Operation::Delete {
+                                old: collection.id_record(&remove.document),
+                            },
+                        ));
+                    }
+                }
+                FirestoreListenEvent::Filter(_) => (),
+            }
+
+            for (table_index, op) in ops {
+                if snapshotting {
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                } else {
+                    txid += 1;
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(txid, 0, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                }
+            }
+        }
+
+        listener.shutdown().await.map_err(FirestoreError::from)?;
+        Err(FirestoreError::ListenerStopped.into())
+    }
+}
+
+/// The collections of target ids, with their table index.
+fn targets<'a>(
+    collections: &'a [Collection],
+    target_ids: &'a [i32],
+) -> impl Iterator<Item = (usize, &'a Collection)> + 'a {
+    target_ids.iter().filter_map(|target_id| {
+        let table_index = (*target_id as usize).checked_sub(1)?;
+        Some((table_index, collections.get(table_index)?))
+    })
+}
+
+#[async_trait]
+impl Connector for FirestoreConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let db = self.connect().await?;
+        self.list_collections(&db).await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let db = self.connect().await?;
+        Ok(self
+            .list_collections(&db)
+            .await?
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let db = self.connect().await?;
+        let collections = self.list_collections(&db).await?;
+        for table in tables {
+            if table.schema.is_some() || !collections.contains(&table.name) {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let db = self.connect().await?;
+        let mut table_infos = vec![];
+        for table in tables {
+            let columns = self.infer_columns(&db, &table.name).await?;
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: columns.into_iter().map(|column| column.name).collect(),
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let db = self.connect().await?;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                self.get_collection(&db, table_info)
+                    .await
+                    .map(|collection| SourceSchema::new(collection.schema(), CdcType::OnlyPK))
+                    .map_err(Into::into),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
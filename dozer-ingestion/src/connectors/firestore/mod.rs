@@ -0,0 +1,7 @@
+mod connector;
+mod schema;
+
+pub use connector::FirestoreConnector;
+
+#[cfg(test)]
+mod tests;
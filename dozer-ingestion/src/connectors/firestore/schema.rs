@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, HashMap};
+
+use base64::Engine;
+use dozer_types::chrono::{DateTime, NaiveDateTime, Offset, Utc};
+use dozer_types::json_types::JsonValue;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{
+    DozerPoint, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
+use firestore::gcloud_sdk::google::firestore::v1::value::ValueType;
+use firestore::gcloud_sdk::google::firestore::v1::{Document, Value};
+
+use crate::errors::FirestoreError;
+
+/// The column of the document id, the primary key of every collection.
+pub const ID_COLUMN: &str = "_id";
+
+/// How fields of nested maps are mapped to columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NestedFields {
+    /// A column per field, named with the path of the field joined by `.`.
+    Flatten,
+    /// A json column per map.
+    Json,
+}
+
+impl NestedFields {
+    pub fn parse(nested_fields: &str) -> Result<Self, FirestoreError> {
+        match nested_fields {
+            "flatten" => Ok(Self::Flatten),
+            "json" => Ok(Self::Json),
+            _ => Err(FirestoreError::UnknownNestedFields(
+                nested_fields.to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub typ: FieldType,
+}
+
+/// The requested columns of a collection, the document id being the first.
+#[derive(Debug, Clone)]
+pub struct Collection {
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+impl Collection {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for column in &self.columns {
+            let is_id = column.name == ID_COLUMN;
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    !is_id,
+                    SourceDefinition::Dynamic,
+                ),
+                is_id,
+            );
+        }
+        schema
+    }
+
+    /// Maps a document to a record of the columns. Fields a document doesn't have, or of another type than their
+    /// column, are null.
+    pub fn record(&self, document: &Document, nested_fields: NestedFields) -> Record {
+        let fields = flatten(&document.fields, nested_fields);
+        let values = self
+            .columns
+            .iter()
+            .map(|column| {
+                if column.name == ID_COLUMN {
+                    return Field::String(document_id(&document.name).to_string());
+                }
+                fields
+                    .get(&column.name)
+                    .map_or(Field::Null, |value| to_field(value, column.typ))
+            })
+            .collect();
+        Record::new(values)
+    }
+
+    /// A record with only the document id, for changes where the old document isn't known.
+    pub fn id_record(&self, document_name: &str) -> Record {
+        let values = self
+            .columns
+            .iter()
+            .map(|column| {
+                if column.name == ID_COLUMN {
+                    Field::String(document_id(document_name).to_string())
+                } else {
+                    Field::Null
+                }
+            })
+            .collect();
+        Record::new(values)
+    }
+}
+
+/// Infers the columns of a collection from a sample of its documents, sorted by name after the document id.
+///
+/// A field is mapped to the type of its values, a float if some are integers and others doubles, and json if its
+/// values have other different types or are all null.
+pub fn infer_columns(documents: &[Document], nested_fields: NestedFields) -> Vec<Column> {
+    let mut types: BTreeMap<String, Option<FieldType>> = BTreeMap::new();
+    for document in documents {
+        for (name, value) in flatten(&document.fields, nested_fields) {
+            let typ = value_type(value);
+            types
+                .entry(name)
+                .and_modify(|existing| *existing = merge_types(*existing, typ))
+                .or_insert(typ);
+        }
+    }
+
+    let mut columns = vec![Column {
+        name: ID_COLUMN.to_string(),
+        typ: FieldType::String,
+    }];
+    columns.extend(types.into_iter().map(|(name, typ)| Column {
+        name,
+        typ: typ.unwrap_or(FieldType::Json),
+    }));
+    columns
+}
+
+/// The id of a document is the last segment of its name, e.g.
+/// `projects/{project_id}/databases/{database_id}/documents/users/alice`.
+pub fn document_id(document_name: &str) -> &str {
+    document_name
+        .rsplit_once('/')
+        .map_or(document_name, |(_, id)| id)
+}
+
+/// Lists the fields of a document by column name, with those of nested maps if flattened.
+pub fn flatten(
+    fields: &HashMap<String, Value>,
+    nested_fields: NestedFields,
+) -> HashMap<String, &Value> {
+    let mut flattened = HashMap::new();
+    flatten_into(&mut flattened, None, fields, nested_fields);
+    flattened
+}
+
+fn flatten_into<'a>(
+    flattened: &mut HashMap<String, &'a Value>,
+    prefix: Option<&str>,
+    fields: &'a HashMap<String, Value>,
+    nested_fields: NestedFields,
+) {
+    for (name, value) in fields {
+        let name = match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.clone(),
+        };
+        match &value.value_type {
+            Some(ValueType::MapValue(map)) if nested_fields == NestedFields::Flatten => {
+                flatten_into(flattened, Some(&name), &map.fields, nested_fields)
+            }
+            _ => {
+                flattened.insert(name, value);
+            }
+        }
+    }
+}
+
+/// The type of a value, `None` if it's null.
+fn value_type(value: &Value) -> Option<FieldType> {
+    Some(match value.value_type.as_ref()? {
+        ValueType::NullValue(_) => return None,
+        ValueType::BooleanValue(_) => FieldType::Boolean,
+        ValueType::IntegerValue(_) => FieldType::Int,
+        ValueType::DoubleValue(_) => FieldType::Float,
+        ValueType::TimestampValue(_) => FieldType::Timestamp,
+        ValueType::StringValue(_) | ValueType::ReferenceValue(_) => FieldType::String,
+        ValueType::BytesValue(_) => FieldType::Binary,
+        ValueType::GeoPointValue(_) => FieldType::Point,
+        ValueType::ArrayValue(_) | ValueType::MapValue(_) => FieldType::Json,
+    })
+}
+
+fn merge_types(existing: Option<FieldType>, typ: Option<FieldType>) -> Option<FieldType> {
+    match (existing, typ) {
+        (existing, None) => existing,
+        (None, typ) => typ,
+        (Some(existing), Some(typ)) if existing == typ => Some(typ),
+        (Some(FieldType::Int), Some(FieldType::Float))
+        | (Some(FieldType::Float), Some(FieldType::Int)) => Some(FieldType::Float),
+        _ => Some(FieldType::Json),
+    }
+}
+
+/// Converts a value to a field of `typ`, null if it's of another type.
+pub fn to_field(value: &Value, typ: FieldType) -> Field {
+    let Some(value_type) = &value.value_type else {
+        return Field::Null;
+    };
+    match (value_type, typ) {
+        (_, FieldType::Json) => Field::Json(to_json(value)),
+        (ValueType::BooleanValue(value), FieldType::Boolean) => Field::Boolean(*value),
+        (ValueType::IntegerValue(value), FieldType::Int) => Field::Int(*value),
+        (ValueType::IntegerValue(value), FieldType::Float) => {
+            Field::Float(OrderedFloat(*value as f64))
+        }
+        (ValueType::DoubleValue(value), FieldType::Float) => Field::Float(OrderedFloat(*value)),
+        (ValueType::TimestampValue(value), FieldType::Timestamp) => {
+            NaiveDateTime::from_timestamp_opt(value.seconds, value.nanos as u32)
+                .map_or(Field::Null, |value| {
+                    Field::Timestamp(DateTime::from_utc(value, Utc.fix()))
+                })
+        }
+        (ValueType::StringValue(value) | ValueType::ReferenceValue(value), FieldType::String) => {
+            Field::String(value.clone())
+        }
+        (ValueType::BytesValue(value), FieldType::Binary) => Field::Binary(value.clone()),
+        (ValueType::GeoPointValue(value), FieldType::Point) => {
+            Field::Point(DozerPoint::from((value.longitude, value.latitude)))
+        }
+        _ => Field::Null,
+    }
+}
+
+fn to_json(value: &Value) -> JsonValue {
+    let Some(value_type) = &value.value_type else {
+        return JsonValue::Null;
+    };
+    match value_type {
+        ValueType::NullValue(_) => JsonValue::Null,
+        ValueType::BooleanValue(value) => JsonValue::Bool(*value),
+        ValueType::IntegerValue(value) => JsonValue::Number(OrderedFloat(*value as f64)),
+        ValueType::DoubleValue(value) => JsonValue::Number(OrderedFloat(*value)),
+        ValueType::TimestampValue(value) => {
+            NaiveDateTime::from_timestamp_opt(value.seconds, value.nanos as u32)
+                .map_or(JsonValue::Null, |value| {
+                    JsonValue::String(DateTime::<Utc>::from_utc(value, Utc).to_rfc3339())
+                })
+        }
+        ValueType::StringValue(value) | ValueType::ReferenceValue(value) => {
+            JsonValue::String(value.clone())
+        }
+        ValueType::BytesValue(value) => {
+            JsonValue::String(base64::engine::general_purpose::STANDARD.encode(value))
+        }
+        ValueType::GeoPointValue(value) => JsonValue::Object(BTreeMap::from([
+            (
+                "latitude".to_string(),
+                JsonValue::Number(OrderedFloat(value.latitude)),
+            ),
+            (
+                "longitude".to_string(),
+                JsonValue::Number(OrderedFloat(value.longitude)),
+            ),
+        ])),
+        ValueType::ArrayValue(array) => {
+            JsonValue::Array(array.values.iter().map(to_json).collect())
+        }
+        ValueType::MapValue(map) => JsonValue::Object(
+            map.fields
+                .iter()
+                .map(|(name, value)| (name.clone(), to_json(value)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dozer_types::json_types::JsonValue;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use firestore::gcloud_sdk::google::firestore::v1::value::ValueType;
+use firestore::gcloud_sdk::google::firestore::v1::{Document, MapValue, Value};
+
+use super::schema::{document_id, infer_columns, Collection, Column, NestedFields};
+
+fn value(value_type: ValueType) -> Value {
+    Value {
+        value_type: Some(value_type),
+    }
+}
+
+fn document(id: &str, fields: Vec<(&str, ValueType)>) -> Document {
+    Document {
+        name: format!("projects/p/databases/(default)/documents/users/{id}"),
+        fields: fields
+            .into_iter()
+            .map(|(name, value_type)| (name.to_string(), value(value_type)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn address(city: &str) -> ValueType {
+    ValueType::MapValue(MapValue {
+        fields: HashMap::from([(
+            "city".to_string(),
+            value(ValueType::StringValue(city.to_string())),
+        )]),
+    })
+}
+
+fn column(name: &str, typ: FieldType) -> Column {
+    Column {
+        name: name.to_string(),
+        typ,
+    }
+}
+
+#[test]
+fn test_document_id() {
+    let name = "projects/p/databases/(default)/documents/users/alice";
+    assert_eq!(document_id(name), "alice");
+    assert_eq!(document_id("alice"), "alice");
+}
+
+#[test]
+fn test_infer_columns() {
+    let documents = vec![
+        document(
+            "alice",
+            vec![
+                ("age", ValueType::IntegerValue(30)),
+                ("score", ValueType::IntegerValue(1)),
+                ("address", address("Paris")),
+                ("note", ValueType::NullValue(0)),
+            ],
+        ),
+        document(
+            "bob",
+            vec![
+                ("age", ValueType::StringValue("unknown".to_string())),
+                ("score", ValueType::DoubleValue(1.5)),
+            ],
+        ),
+    ];
+
+    assert_eq!(
+        infer_columns(&documents, NestedFields::Flatten),
+        vec![
+            column("_id", FieldType::String),
+            column("address.city", FieldType::String),
+            column("age", FieldType::Json),
+            column("note", FieldType::Json),
+            column("score", FieldType::Float),
+        ]
+    );
+    assert_eq!(
+        infer_columns(&documents, NestedFields::Json),
+        vec![
+            column("_id", FieldType::String),
+            column("address", FieldType::Json),
+            column("age", FieldType::Json),
+            column("note", FieldType::Json),
+            column("score", FieldType::Float),
+        ]
+    );
+}
+
+#[test]
+fn test_record() {
+    let collection = Collection {
+        name: "users".to_string(),
+        columns: vec![
+            column("_id", FieldType::String),
+            column("address.city", FieldType::String),
+            column("age", FieldType::Int),
+            column("score", FieldType::Float),
+        ],
+    };
+    let alice = document(
+        "alice",
+        vec![
+            ("age", ValueType::StringValue("thirty".to_string())),
+            ("score", ValueType::IntegerValue(2)),
+            ("address", address("Paris")),
+        ],
+    );
+    assert_eq!(
+        collection.record(&alice, NestedFields::Flatten).values,
+        vec![
+            Field::String("alice".to_string()),
+            Field::String("Paris".to_string()),
+            Field::Null,
+            Field::Float(OrderedFloat(2.0)),
+        ]
+    );
+    assert_eq!(
+        collection.id_record(&alice.name).values,
+        vec![
+            Field::String("alice".to_string()),
+            Field::Null,
+            Field::Null,
+            Field::Null,
+        ]
+    );
+
+    let collection = Collection {
+        name: "users".to_string(),
+        columns: vec![column("address", FieldType::Json)],
+    };
+    assert_eq!(
+        collection.record(&alice, NestedFields::Json).values,
+        vec![Field::Json(JsonValue::Object(BTreeMap::from([(
+            "city".to_string(),
+            JsonValue::String("Paris".to_string())
+        )])))]
+    );
+}
+
+#[test]
+fn test_parse_nested_fields() {
+    assert_eq!(
+        NestedFields::parse("flatten").unwrap(),
+        NestedFields::Flatten
+    );
+    assert_eq!(NestedFields::parse("json").unwrap(), NestedFields::Json);
+    assert!(NestedFields::parse("nested").is_err());
+}
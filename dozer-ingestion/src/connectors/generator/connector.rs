@@ -0,0 +1,235 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dozer_types::ingestion_types::{GeneratorConfig, GeneratorTable, IngestionMessage};
+use dozer_types::types::{FieldType, Operation, Record, Schema};
+use futures::future::try_join_all;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use tonic::async_trait;
+
+use super::table_generator::{TableGenerator, COLUMN_TYPES};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::ConnectorError;
+use crate::ingestion::Ingestor;
+
+/// Operations due at the configured rate are sent at this interval.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Produces synthetic records of declared tables at a configured rate, for load testing pipelines and caches.
+#[derive(Debug)]
+pub struct GeneratorConnector {
+    config: GeneratorConfig,
+}
+
+impl GeneratorConnector {
+    pub fn new(config: GeneratorConfig) -> Self {
+        Self { config }
+    }
+
+    fn get_table(&self, name: &str) -> Result<&GeneratorTable, ConnectorError> {
+        self.config
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| ConnectorError::TableNotFound(name.to_string()))
+    }
+
+    /// Every table gets its own random generator, so a seeded run produces the same operations regardless of scheduling.
+    fn table_generator(
+        &self,
+        table_index: usize,
+        table: &GeneratorTable,
+    ) -> Result<TableGenerator, ConnectorError> {
+        let rng = match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(table_index as u64)),
+            None => StdRng::from_entropy(),
+        };
+        Ok(TableGenerator::new(table, rng)?)
+    }
+
+    /// Returns the full schema of `table_info`'s table, and the indexes of its requested columns.
+    fn get_schema(&self, table_info: &TableInfo) -> Result<(Schema, Vec<usize>), ConnectorError> {
+        let table = self.get_table(&table_info.name)?;
+        let schema = self.table_generator(0, table)?.schema();
+        let projection = table_info
+            .column_names
+            .iter()
+            .map(|name| schema.get_field_index(name).map(|(index, _)| index))
+            .collect::<Result<_, _>>()?;
+        Ok((schema, projection))
+    }
+}
+
+#[async_trait]
+impl Connector for GeneratorConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        COLUMN_TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        for (table_index, table) in self.config.tables.iter().enumerate() {
+            self.table_generator(table_index, table)?;
+        }
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .config
+            .tables
+            .iter()
+            .map(|table| TableIdentifier::from_table_name(table.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        for table in tables {
+            if table.schema.is_some() || self.get_table(&table.name).is_err() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    table.schema.as_deref(),
+                    &table.name,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let schema = self
+                    .table_generator(0, self.get_table(&table.name)?)?
+                    .schema();
+                Ok(TableInfo {
+                    schema: table.schema,
+                    name: table.name,
+                    column_names: schema.fields.into_iter().map(|field| field.name).collect(),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let (schema, projection) = self.get_schema(table_info)?;
+                Ok(SourceSchema::new(
+                    project_schema(schema, &projection),
+                    CdcType::FullChanges,
+                ))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        let seq_no = AtomicU64::new(0);
+        let mut runs = vec![];
+        for (table_index, table_info) in tables.iter().enumerate() {
+            let table = self.get_table(&table_info.name)?;
+            let (_, projection) = self.get_schema(table_info)?;
+            let generator = self.table_generator(table_index, table)?;
+            runs.push(run_table(
+                generator,
+                table,
+                table_index,
+                projection,
+                &seq_no,
+                ingestor,
+            ));
+        }
+        try_join_all(runs).await?;
+        Ok(())
+    }
+}
+
+/// Sends operations of a table as they become due, until `max_operations` are sent.
+async fn run_table(
+    mut generator: TableGenerator,
+    table: &GeneratorTable,
+    table_index: usize,
+    projection: Vec<usize>,
+    seq_no: &AtomicU64,
+    ingestor: &Ingestor,
+) -> Result<(), ConnectorError> {
+    let start = Instant::now();
+    let mut interval = tokio::time::interval(TICK);
+    let mut sent = 0;
+    loop {
+        interval.tick().await;
+        let mut due = (start.elapsed().as_secs_f64() * table.operations_per_sec as f64) as u64;
+        if let Some(max_operations) = table.max_operations {
+            due = due.min(max_operations);
+        }
+        while sent < due {
+            let op = project_operation(generator.next_operation(), &projection);
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    0,
+                    seq_no.fetch_add(1, Ordering::Relaxed),
+                    table_index,
+                    op,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+            sent += 1;
+        }
+        if Some(sent) == table.max_operations {
+            return Ok(());
+        }
+    }
+}
+
+fn project_schema(schema: Schema, projection: &[usize]) -> Schema {
+    let primary_index = projection
+        .iter()
+        .enumerate()
+        .filter(|(_, index)| schema.primary_index.contains(index))
+        .map(|(position, _)| position)
+        .collect();
+    Schema {
+        fields: projection
+            .iter()
+            .map(|index| schema.fields[*index].clone())
+            .collect(),
+        primary_index,
+    }
+}
+
+fn project_operation(op: Operation, projection: &[usize]) -> Operation {
+    let project = |record: Record| {
+        Record::new(
+            projection
+                .iter()
+                .map(|index| record.values[*index].clone())
+                .collect(),
+        )
+    };
+    match op {
+        Operation::Insert { new } => Operation::Insert { new: project(new) },
+        Operation::Delete { old } => Operation::Delete { old: project(old) },
+        Operation::Update { old, new } => Operation::Update {
+            old: project(old),
+            new: project(new),
+        },
+    }
+}
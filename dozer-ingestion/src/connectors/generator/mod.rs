@@ -0,0 +1,7 @@
+mod connector;
+mod table_generator;
+
+pub use connector::GeneratorConnector;
+
+#[cfg(test)]
+mod tests;
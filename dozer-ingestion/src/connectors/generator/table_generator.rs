@@ -0,0 +1,206 @@
+use dozer_types::chrono::{Duration, Utc};
+use dozer_types::ingestion_types::{GeneratorColumn, GeneratorTable};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::errors::GeneratorError;
+
+/// Name of the primary key column every generated table has.
+pub const ID_COLUMN: &str = "id";
+
+/// Column types the generator supports, by their name in the config.
+pub const COLUMN_TYPES: [(&str, FieldType); 6] = [
+    ("int", FieldType::Int),
+    ("uint", FieldType::UInt),
+    ("float", FieldType::Float),
+    ("boolean", FieldType::Boolean),
+    ("string", FieldType::String),
+    ("timestamp", FieldType::Timestamp),
+];
+
+const DEFAULT_MIN: i64 = 0;
+const DEFAULT_MAX: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Distribution {
+    Uniform,
+    /// Log-uniform, so every order of magnitude is about as likely and small numbers are much more frequent.
+    Skewed,
+}
+
+#[derive(Debug)]
+struct ColumnGenerator {
+    name: String,
+    typ: FieldType,
+    min: i64,
+    max: i64,
+    distribution: Distribution,
+}
+
+impl ColumnGenerator {
+    fn new(table: &str, column: &GeneratorColumn) -> Result<Self, GeneratorError> {
+        if column.name == ID_COLUMN {
+            return Err(GeneratorError::ReservedColumn(
+                column.name.clone(),
+                table.to_string(),
+            ));
+        }
+        let typ = COLUMN_TYPES
+            .iter()
+            .find(|(name, _)| *name == column.typ)
+            .map(|(_, typ)| *typ)
+            .ok_or_else(|| {
+                GeneratorError::UnsupportedType(column.name.clone(), column.typ.clone())
+            })?;
+        let distribution = match column.distribution.as_str() {
+            "uniform" => Distribution::Uniform,
+            "skewed" => Distribution::Skewed,
+            distribution => {
+                return Err(GeneratorError::UnknownDistribution(
+                    column.name.clone(),
+                    distribution.to_string(),
+                ))
+            }
+        };
+
+        let min = column.min.unwrap_or(DEFAULT_MIN);
+        let max = column.max.unwrap_or(DEFAULT_MAX);
+        if min > max {
+            return Err(GeneratorError::InvalidRange(column.name.clone(), min, max));
+        }
+        if typ == FieldType::UInt && min < 0 {
+            return Err(GeneratorError::NegativeUInt(column.name.clone(), min));
+        }
+
+        Ok(Self {
+            name: column.name.clone(),
+            typ,
+            min,
+            max,
+            distribution,
+        })
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> i64 {
+        match self.distribution {
+            Distribution::Uniform => rng.gen_range(self.min..=self.max),
+            Distribution::Skewed => {
+                let span = self.max.abs_diff(self.min);
+                let offset = ((span as f64 + 1.0).powf(rng.gen::<f64>()) - 1.0) as u64;
+                self.min.saturating_add_unsigned(offset.min(span))
+            }
+        }
+    }
+
+    fn generate(&self, rng: &mut StdRng) -> Field {
+        match self.typ {
+            FieldType::Boolean => Field::Boolean(rng.gen()),
+            FieldType::Int => Field::Int(self.sample(rng)),
+            FieldType::UInt => Field::UInt(self.sample(rng) as u64),
+            FieldType::Float => {
+                let value = self.sample(rng) as f64 + rng.gen::<f64>();
+                Field::Float(OrderedFloat(value.min(self.max as f64)))
+            }
+            FieldType::String => Field::String(format!("{}_{}", self.name, self.sample(rng))),
+            FieldType::Timestamp => {
+                Field::Timestamp((Utc::now() + Duration::seconds(self.sample(rng))).into())
+            }
+            _ => unreachable!("Column types are checked in `ColumnGenerator::new`"),
+        }
+    }
+}
+
+/// Generates random operations on a table, keeping its live records so updates and deletes carry the old record.
+#[derive(Debug)]
+pub struct TableGenerator {
+    columns: Vec<ColumnGenerator>,
+    update_percent: u32,
+    delete_percent: u32,
+    rng: StdRng,
+    next_id: u64,
+    records: Vec<Record>,
+}
+
+impl TableGenerator {
+    pub fn new(table: &GeneratorTable, rng: StdRng) -> Result<Self, GeneratorError> {
+        if table.update_percent + table.delete_percent > 100 {
+            return Err(GeneratorError::InvalidPercentages(table.name.clone()));
+        }
+        let columns = table
+            .columns
+            .iter()
+            .map(|column| ColumnGenerator::new(&table.name, column))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            columns,
+            update_percent: table.update_percent,
+            delete_percent: table.delete_percent,
+            rng,
+            next_id: 0,
+            records: vec![],
+        })
+    }
+
+    /// The schema of the generated records, with the `id` primary key first.
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        schema.field(
+            FieldDefinition::new(
+                ID_COLUMN.to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        );
+        for column in &self.columns {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        }
+        schema
+    }
+
+    pub fn next_operation(&mut self) -> Operation {
+        let roll = self.rng.gen_range(0..100);
+        if self.records.is_empty() || roll >= self.update_percent + self.delete_percent {
+            let id = self.next_id;
+            self.next_id += 1;
+            let new = self.generate_record(id);
+            self.records.push(new.clone());
+            return Operation::Insert { new };
+        }
+
+        let index = self.rng.gen_range(0..self.records.len());
+        if roll < self.delete_percent {
+            let old = self.records.swap_remove(index);
+            Operation::Delete { old }
+        } else {
+            let Field::UInt(id) = self.records[index].values[0] else {
+                unreachable!("The first column is the `id` primary key");
+            };
+            let new = self.generate_record(id);
+            let old = std::mem::replace(&mut self.records[index], new.clone());
+            Operation::Update { old, new }
+        }
+    }
+
+    fn generate_record(&mut self, id: u64) -> Record {
+        let mut values = Vec::with_capacity(self.columns.len() + 1);
+        values.push(Field::UInt(id));
+        for column in &self.columns {
+            values.push(column.generate(&mut self.rng));
+        }
+        Record::new(values)
+    }
+}
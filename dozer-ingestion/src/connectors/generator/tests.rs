@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{
+    GeneratorColumn, GeneratorConfig, GeneratorTable, IngestionMessageKind,
+};
+use dozer_types::models::connection::{Connection, ConnectionConfig};
+use dozer_types::types::{Field, FieldType, Operation, Record};
+
+use crate::connectors::{get_connector, CdcType, Connector, TableInfo};
+use crate::errors::{ConnectorError, GeneratorError};
+use crate::ingestion::{IngestionConfig, Ingestor};
+
+fn column(name: &str, typ: &str, distribution: &str) -> GeneratorColumn {
+    GeneratorColumn {
+        name: name.to_string(),
+        typ: typ.to_string(),
+        min: Some(1),
+        max: Some(100),
+        distribution: distribution.to_string(),
+    }
+}
+
+fn config(columns: Vec<GeneratorColumn>) -> GeneratorConfig {
+    GeneratorConfig {
+        tables: vec![GeneratorTable {
+            name: "users".to_string(),
+            columns,
+            operations_per_sec: 10000,
+            max_operations: Some(200),
+            update_percent: 30,
+            delete_percent: 10,
+        }],
+        seed: Some(42),
+    }
+}
+
+fn connector(config: GeneratorConfig) -> Box<dyn Connector> {
+    get_connector(Connection {
+        config: Some(ConnectionConfig::Generator(config)),
+        name: "generator".to_string(),
+    })
+    .unwrap()
+}
+
+async fn generate(config: GeneratorConfig, column_names: Vec<String>) -> Vec<Operation> {
+    let count = config.tables[0].max_operations.unwrap() as usize;
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let tables = vec![TableInfo {
+        schema: None,
+        name: "users".to_string(),
+        column_names,
+    }];
+    tokio::spawn(async move { connector(config).start(&ingestor, tables).await.unwrap() });
+
+    let mut ops = vec![];
+    while ops.len() < count {
+        let message = iterator.next_timeout(Duration::from_secs(5)).unwrap();
+        let IngestionMessageKind::OperationEvent { table_index, op } = message.kind else {
+            panic!("unexpected message kind");
+        };
+        assert_eq!(table_index, 0);
+        ops.push(op);
+    }
+    ops
+}
+
+#[tokio::test]
+async fn test_generator_schema() {
+    let connector = connector(config(vec![
+        column("age", "uint", "uniform"),
+        column("name", "string", "skewed"),
+    ]));
+    let tables = connector
+        .list_columns(connector.list_tables().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(tables[0].column_names, vec!["id", "age", "name"]);
+
+    let schemas = connector.get_schemas(&tables).await.unwrap();
+    let schema = schemas[0].as_ref().unwrap();
+    assert_eq!(schema.cdc_type, CdcType::FullChanges);
+    assert_eq!(schema.schema.primary_index, vec![0]);
+    assert_eq!(
+        schema
+            .schema
+            .fields
+            .iter()
+            .map(|field| field.typ)
+            .collect::<Vec<_>>(),
+        vec![FieldType::UInt, FieldType::UInt, FieldType::String]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_generator_operations() {
+    let config = config(vec![
+        column("age", "uint", "uniform"),
+        column("score", "float", "skewed"),
+    ]);
+    let column_names = vec!["id".to_string(), "age".to_string()];
+    let ops = generate(config.clone(), column_names.clone()).await;
+
+    // Updates and deletes carry the current record of a live key.
+    let mut records: HashMap<Field, Record> = HashMap::new();
+    let (mut updates, mut deletes) = (0, 0);
+    for op in &ops {
+        match op {
+            Operation::Insert { new } => {
+                assert_eq!(new.values.len(), 2);
+                assert!(matches!(new.values[1], Field::UInt(1..=100)));
+                assert!(records.insert(new.values[0].clone(), new.clone()).is_none());
+            }
+            Operation::Update { old, new } => {
+                assert_eq!(old.values[0], new.values[0]);
+                assert_eq!(
+                    records.insert(new.values[0].clone(), new.clone()),
+                    Some(old.clone())
+                );
+                updates += 1;
+            }
+            Operation::Delete { old } => {
+                assert_eq!(records.remove(&old.values[0]), Some(old.clone()));
+                deletes += 1;
+            }
+        }
+    }
+    assert!(updates > 0 && deletes > 0);
+
+    // The same seed generates the same operations.
+    assert_eq!(generate(config, column_names).await, ops);
+}
+
+#[tokio::test]
+async fn test_generator_invalid_config() {
+    let error = connector(config(vec![column("age", "decimal", "uniform")]))
+        .validate_connection()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ConnectorError::GeneratorError(GeneratorError::UnsupportedType(_, _))
+    ));
+
+    let error = connector(config(vec![column("id", "int", "uniform")]))
+        .validate_connection()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ConnectorError::GeneratorError(GeneratorError::ReservedColumn(_, _))
+    ));
+}
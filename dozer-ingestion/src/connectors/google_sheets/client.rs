@@ -0,0 +1,240 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dozer_types::ingestion_types::GoogleSheetsConfig;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::serde_json::{self, Value};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::{Client, Response};
+
+use crate::errors::GoogleSheetsError;
+
+const API_URL: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets.readonly";
+
+/// How long the tokens requested are valid for, the longest Google allows.
+const TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// Tokens are requested again this long before they expire.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// The fields of a service account key file used to request access tokens.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Debug)]
+enum Auth {
+    ApiKey(String),
+    /// A service account, and its access token with the instant it should be requested again.
+    ServiceAccount {
+        key: ServiceAccountKey,
+        token: Mutex<Option<(String, Instant)>>,
+    },
+}
+
+/// A client of the Sheets API for one spreadsheet.
+#[derive(Debug)]
+pub struct SheetsClient {
+    client: Client,
+    spreadsheet_id: String,
+    auth: Auth,
+}
+
+impl SheetsClient {
+    pub fn new(config: &GoogleSheetsConfig) -> Result<Self, GoogleSheetsError> {
+        let auth = match &config.api_key {
+            Some(api_key) => Auth::ApiKey(api_key.clone()),
+            None => {
+                let path = config
+                    .credentials_path
+                    .clone()
+                    .or_else(|| std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok())
+                    .ok_or(GoogleSheetsError::MissingCredentials)?;
+                let key = std::fs::read_to_string(&path)
+                    .map_err(|e| GoogleSheetsError::ReadCredentials(path.clone(), e))?;
+                let key = serde_json::from_str(&key)
+                    .map_err(|e| GoogleSheetsError::InvalidCredentials(path, e))?;
+                Auth::ServiceAccount {
+                    key,
+                    token: Mutex::new(None),
+                }
+            }
+        };
+        Ok(Self {
+            client: Client::new(),
+            spreadsheet_id: config.spreadsheet_id.clone(),
+            auth,
+        })
+    }
+
+    /// The access token of the service account, requested again with a signed JWT when it's about to expire.
+    async fn access_token(
+        &self,
+        key: &ServiceAccountKey,
+        token: &Mutex<Option<(String, Instant)>>,
+    ) -> Result<String, GoogleSheetsError> {
+        let cached = token.lock().clone();
+        if let Some((access_token, refresh_at)) = cached {
+            if Instant::now() < refresh_at {
+                return Ok(access_token);
+            }
+        }
+
+        let iat = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = Claims {
+            iss: &key.client_email,
+            scope: SCOPE,
+            aud: &key.token_uri,
+            iat,
+            exp: iat + TOKEN_LIFETIME.as_secs(),
+        };
+        let assertion = jsonwebtoken::encode(
+            &Header::new(Algorithm::RS256),
+            &claims,
+            &EncodingKey::from_rsa_pem(key.private_key.as_bytes())?,
+        )?;
+        let response = self
+            .client
+            .post(&key.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+        let body: Value = check_status(key.token_uri.clone(), response)
+            .await?
+            .json()
+            .await?;
+        let access_token = body
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                GoogleSheetsError::UnexpectedResponse("token response without access_token".into())
+            })?
+            .to_string();
+        let lifetime = body
+            .get("expires_in")
+            .and_then(Value::as_u64)
+            .map_or(TOKEN_LIFETIME, Duration::from_secs);
+        let refresh_at = Instant::now() + lifetime.saturating_sub(TOKEN_EXPIRY_MARGIN);
+        *token.lock() = Some((access_token.clone(), refresh_at));
+        Ok(access_token)
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value, GoogleSheetsError> {
+        let url = format!("{API_URL}/{}{path}", self.spreadsheet_id);
+        let request = self.client.get(&url).query(query);
+        let request = match &self.auth {
+            Auth::ApiKey(api_key) => request.query(&[("key", api_key)]),
+            Auth::ServiceAccount { key, token } => {
+                request.bearer_auth(self.access_token(key, token).await?)
+            }
+        };
+        Ok(check_status(url, request.send().await?)
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// The titles of the sheets of the spreadsheet.
+    pub async fn list_sheets(&self) -> Result<Vec<String>, GoogleSheetsError> {
+        let spreadsheet = self
+            .get("", &[("fields", "sheets.properties.title")])
+            .await?;
+        Ok(spreadsheet
+            .get("sheets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|sheet| sheet.pointer("/properties/title").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// The rows of each sheet, header first, with their unformatted values. Dates and times are formatted strings.
+    /// Trailing empty cells and rows are left out.
+    pub async fn values(&self, sheets: &[&str]) -> Result<Vec<Vec<Vec<Value>>>, GoogleSheetsError> {
+        let ranges = sheets
+            .iter()
+            .map(|sheet| sheet_range(sheet))
+            .collect::<Vec<_>>();
+        let mut query = ranges
+            .iter()
+            .map(|range| ("ranges", range.as_str()))
+            .collect::<Vec<_>>();
+        query.extend([
+            ("majorDimension", "ROWS"),
+            ("valueRenderOption", "UNFORMATTED_VALUE"),
+            ("dateTimeRenderOption", "FORMATTED_STRING"),
+        ]);
+        let response = self.get("/values:batchGet", &query).await?;
+        let value_ranges = response
+            .get("valueRanges")
+            .and_then(Value::as_array)
+            .filter(|value_ranges| value_ranges.len() == sheets.len())
+            .ok_or_else(|| {
+                GoogleSheetsError::UnexpectedResponse("missing value ranges".to_string())
+            })?;
+        Ok(value_ranges
+            .iter()
+            .map(|value_range| {
+                value_range
+                    .get("values")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|row| row.as_array().cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// The A1 range of a whole sheet, which is its quoted title.
+fn sheet_range(title: &str) -> String {
+    format!("'{}'", title.replace('\'', "''"))
+}
+
+async fn check_status(url: String, response: Response) -> Result<Response, GoogleSheetsError> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(GoogleSheetsError::Status(
+            url,
+            status.as_u16(),
+            response.text().await.unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sheet_range() {
+        assert_eq!(sheet_range("Sheet1"), "'Sheet1'");
+        assert_eq!(sheet_range("Q1 '23"), "'Q1 ''23'");
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{
+    GoogleSheetsConfig, IngestionMessage, IngestionMessageKind, SchemaDrift, SchemaDriftKind,
+};
+use dozer_types::log::info;
+use dozer_types::node::OpIdentifier;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{FieldType, Operation, Record};
+use tonic::async_trait;
+
+use super::client::SheetsClient;
+use super::schema::{header, sheet_columns, Sheet};
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, GoogleSheetsError};
+use crate::ingestion::Ingestor;
+
+/// Polls the sheets of a Google spreadsheet and sends the rows that changed since the last poll.
+///
+/// Sheets have no primary key, so a changed row is a delete of the old row and an insert of the new one.
+#[derive(Debug)]
+pub struct GoogleSheetsConnector {
+    name: String,
+    config: GoogleSheetsConfig,
+}
+
+#[derive(Debug)]
+struct SheetReader {
+    sheet: Sheet,
+    /// The header of the last poll, to find the columns added or dropped since.
+    header: Vec<String>,
+    /// The records of the last poll, in the order of the rows.
+    records: Vec<Record>,
+}
+
+impl SheetReader {
+    /// The schema drifts and operations since the last poll. Rows not in the sheet anymore are deleted, and new rows
+    /// are inserted, each as many times as its record was removed or added.
+    fn read(&mut self, rows: &[Vec<Value>]) -> (Vec<SchemaDrift>, Vec<Operation>) {
+        let header = header(rows);
+        let mut drifts = vec![];
+        for name in &self.header {
+            if !name.is_empty()
+                && !header.contains(name)
+                && self.sheet.columns.iter().any(|column| column.name == *name)
+            {
+                drifts.push(SchemaDrift {
+                    column_name: name.clone(),
+                    kind: SchemaDriftKind::ColumnDropped,
+                });
+            }
+        }
+        for name in &header {
+            if !name.is_empty()
+                && !self.header.contains(name)
+                && !self.sheet.columns.iter().any(|column| column.name == *name)
+            {
+                drifts.push(SchemaDrift {
+                    column_name: name.clone(),
+                    kind: SchemaDriftKind::ColumnAdded,
+                });
+            }
+        }
+        self.header = header;
+
+        let records = self.sheet.records(&self.header, rows);
+        let mut counts = HashMap::<&Record, usize>::new();
+        for record in &self.records {
+            *counts.entry(record).or_default() += 1;
+        }
+        let mut inserted = vec![];
+        for record in &records {
+            match counts.get_mut(record) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => inserted.push(record),
+            }
+        }
+        let mut ops = vec![];
+        for record in &self.records {
+            if let Some(count) = counts.get_mut(record).filter(|count| **count > 0) {
+                *count -= 1;
+                ops.push(Operation::Delete {
+                    old: record.clone(),
+                });
+            }
+        }
+        ops.extend(inserted.into_iter().map(|record| Operation::Insert {
+            new: record.clone(),
+        }));
+        self.records = records;
+        (drifts, ops)
+    }
+}
+
+impl GoogleSheetsConnector {
+    pub fn new(name: String, config: GoogleSheetsConfig) -> Self {
+        Self { name, config }
+    }
+
+    /// Selects the columns of `table_info` from the header of its sheet.
+    fn get_sheet(table_info: &TableInfo, rows: &[Vec<Value>]) -> Result<Sheet, GoogleSheetsError> {
+        let columns = sheet_columns(&table_info.name, rows)?;
+        let columns = table_info
+            .column_names
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .find(|column| column.name == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        GoogleSheetsError::ColumnNotFound(name.clone(), table_info.name.clone())
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Sheet {
+            name: table_info.name.clone(),
+            columns,
+        })
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let client = SheetsClient::new(&self.config)?;
+        let sheets = tables
+            .iter()
+            .map(|table_info| table_info.name.as_str())
+            .collect::<Vec<_>>();
+        let values = client.values(&sheets).await?;
+        let mut readers = vec![];
+        for (table_info, rows) in tables.iter().zip(&values) {
+            readers.push(SheetReader {
+                sheet: Self::get_sheet(table_info, rows)?,
+                header: header(rows),
+                records: vec![],
+            });
+        }
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut seq_no = 0;
+        for (table_index, (reader, rows)) in readers.iter_mut().zip(&values).enumerate() {
+            info!("[{}] Reading sheet {}", self.name, reader.sheet.name);
+            let (_, ops) = reader.read(rows);
+            for op in ops {
+                ingestor
+                    .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                    .map_err(ConnectorError::IngestorError)?;
+                seq_no += 1;
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        info!("[{}] Polling every {:?}", self.name, poll_interval);
+        // Each poll with changes is a transaction.
+        let mut txid = 0;
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            let values = client.values(&sheets).await?;
+            let mut seq_no = 0;
+            for (table_index, (reader, rows)) in readers.iter_mut().zip(&values).enumerate() {
+                let (drifts, ops) = reader.read(rows);
+                let kinds = drifts
+                    .into_iter()
+                    .map(|drift| IngestionMessageKind::SchemaDrift { table_index, drift })
+                    .chain(
+                        ops.into_iter()
+                            .map(|op| IngestionMessageKind::OperationEvent { table_index, op }),
+                    );
+                for kind in kinds {
+                    if seq_no == 0 {
+                        txid += 1;
+                    }
+                    ingestor
+                        .handle_message(IngestionMessage {
+                            identifier: OpIdentifier::new(txid, seq_no),
+                            kind,
+                        })
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for GoogleSheetsConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("string".to_string(), Some(FieldType::String)),
+            ("integer".to_string(), Some(FieldType::Int)),
+            ("number".to_string(), Some(FieldType::Float)),
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        SheetsClient::new(&self.config)?.list_sheets().await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(SheetsClient::new(&self.config)?
+            .list_sheets()
+            .await?
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let sheets = SheetsClient::new(&self.config)?.list_sheets().await?;
+        for table in tables {
+            if table.schema.is_some() || !sheets.contains(&table.name) {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let sheets = tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .collect::<Vec<_>>();
+        let values = SheetsClient::new(&self.config)?.values(&sheets).await?;
+        let mut table_infos = vec![];
+        for (table, rows) in tables.into_iter().zip(values) {
+            let columns = sheet_columns(&table.name, &rows)?;
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: columns.into_iter().map(|column| column.name).collect(),
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let sheets = table_infos
+            .iter()
+            .map(|table_info| table_info.name.as_str())
+            .collect::<Vec<_>>();
+        let values = SheetsClient::new(&self.config)?.values(&sheets).await?;
+        Ok(table_infos
+            .iter()
+            .zip(&values)
+            .map(|(table_info, rows)| {
+                Self::get_sheet(table_info, rows)
+                    .map(|sheet| SourceSchema::new(sheet.schema(), CdcType::FullChanges))
+                    .map_err(ConnectorError::from)
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+    use dozer_types::types::Field;
+
+    use super::super::schema::Column;
+    use super::*;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<Value>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| json!(cell)).collect())
+            .collect()
+    }
+
+    fn record(code: &str) -> Record {
+        Record::new(vec![Field::String(code.to_string())])
+    }
+
+    #[test]
+    fn test_read() {
+        let mut reader = SheetReader {
+            sheet: Sheet {
+                name: "Currencies".to_string(),
+                columns: vec![Column {
+                    name: "code".to_string(),
+                    typ: FieldType::String,
+                }],
+            },
+            header: vec!["code".to_string(), "name".to_string()],
+            records: vec![],
+        };
+
+        let (drifts, ops) = reader.read(&rows(&[&["code", "name"], &["EUR"], &["USD"], &["EUR"]]));
+        assert!(drifts.is_empty());
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Insert { new: record("EUR") },
+                Operation::Insert { new: record("USD") },
+                Operation::Insert { new: record("EUR") },
+            ]
+        );
+
+        let (drifts, ops) = reader.read(&rows(&[&["code", "rate"], &["USD"], &["JPY"], &["EUR"]]));
+        assert_eq!(
+            drifts,
+            vec![SchemaDrift {
+                column_name: "rate".to_string(),
+                kind: SchemaDriftKind::ColumnAdded,
+            }]
+        );
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Delete { old: record("EUR") },
+                Operation::Insert { new: record("JPY") },
+            ]
+        );
+
+        let (drifts, ops) = reader.read(&rows(&[&["rate"], &["1.1"]]));
+        assert_eq!(
+            drifts,
+            vec![SchemaDrift {
+                column_name: "code".to_string(),
+                kind: SchemaDriftKind::ColumnDropped,
+            }]
+        );
+        assert_eq!(ops.len(), 3);
+        assert!(ops.iter().all(|op| matches!(op, Operation::Delete { .. })));
+    }
+}
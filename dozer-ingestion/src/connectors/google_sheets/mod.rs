@@ -0,0 +1,8 @@
+//! Sheets of a Google spreadsheet, polled with the Sheets API for the rows changed since the last poll. The first row
+//! of a sheet is its header, and column types are inferred from the values below it.
+
+mod client;
+mod connector;
+mod schema;
+
+pub use connector::GoogleSheetsConnector;
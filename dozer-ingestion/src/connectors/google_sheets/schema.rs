@@ -0,0 +1,229 @@
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use crate::errors::GoogleSheetsError;
+
+/// A column of a sheet, named by its header cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub typ: FieldType,
+}
+
+/// The names in the header row of a sheet. Empty header cells are empty names.
+pub fn header(rows: &[Vec<Value>]) -> Vec<String> {
+    rows.first()
+        .into_iter()
+        .flatten()
+        .map(|cell| cell_string(cell).trim().to_string())
+        .collect()
+}
+
+/// The columns of a sheet, from its header row, with types inferred from the values below. Cells below an empty
+/// header cell aren't a column.
+pub fn sheet_columns(sheet: &str, rows: &[Vec<Value>]) -> Result<Vec<Column>, GoogleSheetsError> {
+    if rows.is_empty() {
+        return Err(GoogleSheetsError::MissingHeader(sheet.to_string()));
+    }
+    let mut columns: Vec<Column> = vec![];
+    for (index, name) in header(rows).into_iter().enumerate() {
+        if name.is_empty() {
+            continue;
+        }
+        if columns.iter().any(|column| column.name == name) {
+            return Err(GoogleSheetsError::DuplicateColumn(name, sheet.to_string()));
+        }
+        let typ = infer_type(rows[1..].iter().filter_map(|row| row.get(index)));
+        columns.push(Column { name, typ });
+    }
+    Ok(columns)
+}
+
+/// Integers if all values are, numbers if all values are, booleans if all values are, and strings otherwise or if
+/// there are no values.
+fn infer_type<'a>(values: impl Iterator<Item = &'a Value>) -> FieldType {
+    let mut typ = None;
+    for value in values {
+        let value_type = match value {
+            Value::String(value) if value.is_empty() => continue,
+            Value::Bool(_) => FieldType::Boolean,
+            Value::Number(number) if number.is_i64() => FieldType::Int,
+            Value::Number(_) => FieldType::Float,
+            _ => return FieldType::String,
+        };
+        typ = Some(match (typ, value_type) {
+            (None, value_type) => value_type,
+            (Some(typ), value_type) if typ == value_type => typ,
+            (Some(FieldType::Int | FieldType::Float), FieldType::Int | FieldType::Float) => {
+                FieldType::Float
+            }
+            _ => return FieldType::String,
+        });
+    }
+    typ.unwrap_or(FieldType::String)
+}
+
+/// The selected columns of a sheet.
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+impl Sheet {
+    /// Sheets have no primary key, and every column is nullable.
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for column in &self.columns {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        }
+        schema
+    }
+
+    /// The records of the rows below the header, whose column names are `header`. Columns not in it are null, and
+    /// rows whose columns are all empty are left out.
+    pub fn records(&self, header: &[String], rows: &[Vec<Value>]) -> Vec<Record> {
+        let indexes = self
+            .columns
+            .iter()
+            .map(|column| header.iter().position(|name| *name == column.name))
+            .collect::<Vec<_>>();
+        rows.iter()
+            .skip(1)
+            .map(|row| {
+                let values = self
+                    .columns
+                    .iter()
+                    .zip(&indexes)
+                    .map(
+                        |(column, index)| match index.and_then(|index| row.get(index)) {
+                            Some(value) => to_field(value, column.typ),
+                            None => Field::Null,
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                Record::new(values)
+            })
+            .filter(|record| record.values.iter().any(|value| *value != Field::Null))
+            .collect()
+    }
+}
+
+/// A value of another type than its column is null. Any value is a string in a string column.
+fn to_field(value: &Value, typ: FieldType) -> Field {
+    match (value, typ) {
+        (Value::String(value), _) if value.is_empty() => Field::Null,
+        (Value::Null, _) => Field::Null,
+        (value, FieldType::String) => Field::String(cell_string(value)),
+        (Value::Bool(value), FieldType::Boolean) => Field::Boolean(*value),
+        (Value::Number(number), FieldType::Int) => number.as_i64().map_or(Field::Null, Field::Int),
+        (Value::Number(number), FieldType::Float) => number
+            .as_f64()
+            .map_or(Field::Null, |value| Field::Float(OrderedFloat(value))),
+        _ => Field::Null,
+    }
+}
+
+fn cell_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    fn rows(value: Value) -> Vec<Vec<Value>> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row.as_array().unwrap().clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_sheet_columns() {
+        let rows = rows(json!([
+            ["code", "name", "rate", "active", "", "note"],
+            ["EUR", "Euro", 1, true, "x", 1],
+            ["USD", "Dollar", 1.1, false],
+            [],
+            ["JPY", "", 140, "", "", "n/a"],
+        ]));
+        let column = |name: &str, typ| Column {
+            name: name.to_string(),
+            typ,
+        };
+        assert_eq!(
+            sheet_columns("Currencies", &rows).unwrap(),
+            vec![
+                column("code", FieldType::String),
+                column("name", FieldType::String),
+                column("rate", FieldType::Float),
+                column("active", FieldType::Boolean),
+                column("note", FieldType::String),
+            ]
+        );
+
+        assert!(matches!(
+            sheet_columns("Currencies", &[]),
+            Err(GoogleSheetsError::MissingHeader(_))
+        ));
+        assert!(matches!(
+            sheet_columns("Currencies", &[vec![json!("code"), json!("code")]]),
+            Err(GoogleSheetsError::DuplicateColumn(..))
+        ));
+    }
+
+    #[test]
+    fn test_records() {
+        let sheet = Sheet {
+            name: "Currencies".to_string(),
+            columns: vec![
+                Column {
+                    name: "code".to_string(),
+                    typ: FieldType::String,
+                },
+                Column {
+                    name: "rate".to_string(),
+                    typ: FieldType::Float,
+                },
+                Column {
+                    name: "digits".to_string(),
+                    typ: FieldType::Int,
+                },
+            ],
+        };
+        let rows = rows(json!([["rate", "code"], [1.5, "EUR"], ["n/a", 42], [],]));
+        assert_eq!(
+            sheet.records(&header(&rows), &rows),
+            vec![
+                Record::new(vec![
+                    Field::String("EUR".to_string()),
+                    Field::Float(OrderedFloat(1.5)),
+                    Field::Null,
+                ]),
+                Record::new(vec![
+                    Field::String("42".to_string()),
+                    Field::Null,
+                    Field::Null,
+                ]),
+            ]
+        );
+    }
+}
@@ -7,13 +7,14 @@ use dozer_types::{
     chrono,
     ingestion_types::IngestionMessage,
     ordered_float::OrderedFloat,
-    types::{Field, Operation, Record, Schema},
+    types::{DozerMoney, Field, FieldType, Operation, Record, Schema},
 };
 
 use std::collections::HashMap;
 
 use crate::ingestion::Ingestor;
 
+use dozer_types::errors::types::TypeError;
 use dozer_types::grpc_types;
 use dozer_types::grpc_types::ingest::IngestRequest;
 use dozer_types::json_types::prost_to_json_value;
@@ -58,6 +59,12 @@ impl IngestAdapter for DefaultAdapter {
             )),
         }
     }
+    fn map_batch(
+        &self,
+        requests: Vec<(usize, IngestRequest)>,
+    ) -> Result<Vec<(usize, Operation)>, ConnectorError> {
+        map_batch(requests, &self.schema_map)
+    }
 }
 
 pub fn handle_message(
@@ -66,6 +73,27 @@ pub fn handle_message(
     schema_map: &HashMap<String, SourceSchema>,
     ingestor: &'static Ingestor,
 ) -> Result<(), ConnectorError> {
+    let seq_no = req.seq_no as u64;
+    let op = map_operation(req, schema_map)?;
+    ingestor
+        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+        .map_err(ConnectorError::IngestorError)
+}
+
+pub fn map_batch(
+    requests: Vec<(usize, IngestRequest)>,
+    schema_map: &HashMap<String, SourceSchema>,
+) -> Result<Vec<(usize, Operation)>, ConnectorError> {
+    requests
+        .into_iter()
+        .map(|(table_index, req)| Ok((table_index, map_operation(req, schema_map)?)))
+        .collect()
+}
+
+fn map_operation(
+    req: IngestRequest,
+    schema_map: &HashMap<String, SourceSchema>,
+) -> Result<Operation, ConnectorError> {
     let schema = &schema_map
         .get(&req.schema_name)
         .ok_or_else(|| {
@@ -73,26 +101,24 @@ pub fn handle_message(
         })?
         .schema;
 
-    let op = match req.typ() {
+    let typ = req.typ();
+    let record = |record: Option<grpc_types::types::Record>, name: &str| {
+        record.ok_or_else(|| {
+            ConnectorError::InitializationError(format!("{name} record not found for {typ:?}"))
+        })
+    };
+    Ok(match typ {
         grpc_types::types::OperationType::Insert => Operation::Insert {
-            new: map_record(req.new.unwrap(), schema)?,
+            new: map_record(record(req.new, "new")?, schema)?,
         },
         grpc_types::types::OperationType::Delete => Operation::Delete {
-            old: map_record(req.old.unwrap(), schema)?,
+            old: map_record(record(req.old, "old")?, schema)?,
         },
         grpc_types::types::OperationType::Update => Operation::Update {
-            old: map_record(req.old.unwrap(), schema)?,
-            new: map_record(req.new.unwrap(), schema)?,
+            old: map_record(record(req.old, "old")?, schema)?,
+            new: map_record(record(req.new, "new")?, schema)?,
         },
-    };
-    ingestor
-        .handle_message(IngestionMessage::new_op(
-            0,
-            req.seq_no as u64,
-            table_index,
-            op,
-        ))
-        .map_err(ConnectorError::IngestorError)
+    })
 }
 
 fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record, ConnectorError> {
@@ -106,7 +132,8 @@ fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record,
     }
 
     for (idx, v) in rec.values.into_iter().enumerate() {
-        let typ = schema.fields[idx].typ;
+        let field_def = &schema.fields[idx];
+        let typ = field_def.typ;
 
         let val = v.value.map(|value| match (value, typ) {
             (
@@ -163,6 +190,22 @@ fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record,
             ) => Ok(dozer_types::types::Field::Decimal(Decimal::from_parts(
                 d.lo, d.mid, d.hi, d.negative, d.scale,
             ))),
+            (grpc_types::types::value::Value::MoneyValue(m), FieldType::Money) => {
+                let amount = Decimal::from_str_exact(&m.amount).map_err(|_| {
+                    TypeError::InvalidFieldValue {
+                        field_type: FieldType::Money,
+                        nullable: false,
+                        value: m.amount.clone(),
+                    }
+                })?;
+                Ok(Field::Money(DozerMoney::new(amount, m.currency.parse()?)))
+            }
+            (grpc_types::types::value::Value::EnumValue(ordinal), FieldType::Enum) => {
+                Ok(field_def.check_enum_ordinal(ordinal)?)
+            }
+            (grpc_types::types::value::Value::StringValue(value), FieldType::Enum) => {
+                Ok(field_def.enum_field(&value)?)
+            }
             (
                 grpc_types::types::value::Value::DateValue(_),
                 dozer_types::types::FieldType::UInt,
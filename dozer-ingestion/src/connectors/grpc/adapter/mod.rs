@@ -1,6 +1,10 @@
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
+use std::sync::Mutex;
 
 use dozer_types::grpc_types::ingest::{IngestArrowRequest, IngestRequest};
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::Operation;
 
 use crate::{connectors::SourceSchema, errors::ConnectorError, ingestion::Ingestor};
 
@@ -22,17 +26,39 @@ where
         msg: GrpcIngestMessage,
         ingestor: &'static Ingestor,
     ) -> Result<(), ConnectorError>;
+    /// Maps requests, with their table index, to operations, failing if any of them is invalid.
+    fn map_batch(
+        &self,
+        _requests: Vec<(usize, IngestRequest)>,
+    ) -> Result<Vec<(usize, Operation)>, ConnectorError> {
+        Err(ConnectorError::InitializationError(
+            "Batches are not supported by this adapter".to_string(),
+        ))
+    }
 }
 
 pub enum GrpcIngestMessage {
     Default(IngestRequest),
     Arrow(IngestArrowRequest),
 }
+
+/// How many idempotency keys of ingested batches are kept to deduplicate retries.
+pub const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// The transaction ids and idempotency keys of ingested batches.
+#[derive(Debug, Default)]
+struct Batches {
+    txid: u64,
+    keys: HashSet<String>,
+    order: VecDeque<String>,
+}
+
 pub struct GrpcIngestor<A>
 where
     A: IngestAdapter,
 {
     adapter: A,
+    batches: Mutex<Batches>,
 }
 impl<T> GrpcIngestor<T>
 where
@@ -40,7 +66,10 @@ where
 {
     pub fn new(schemas_str: String) -> Result<Self, ConnectorError> {
         let adapter = T::new(schemas_str)?;
-        Ok(Self { adapter })
+        Ok(Self {
+            adapter,
+            batches: Mutex::new(Batches::default()),
+        })
     }
 }
 
@@ -60,4 +89,48 @@ where
     ) -> Result<(), ConnectorError> {
         self.adapter.handle_message(table_index, msg, ingestor)
     }
+
+    /// Ingests a batch unless one with the same idempotency key was ingested, returning whether it's a duplicate.
+    ///
+    /// Batches are ingested one at a time, each between `TransactionBegin` and `TransactionEnd` with a transaction id
+    /// following those of other messages, which are all 0. All the requests are mapped before any is sent, so an
+    /// invalid batch isn't remembered and can be retried. Once the batch started to be sent, it is remembered even if
+    /// sending it fails, so a retry doesn't ingest its operations twice.
+    pub fn handle_batch(
+        &self,
+        idempotency_key: String,
+        requests: Vec<(usize, IngestRequest)>,
+        ingestor: &'static Ingestor,
+    ) -> Result<bool, ConnectorError> {
+        let mut batches = self.batches.lock().unwrap();
+        if batches.keys.contains(&idempotency_key) {
+            return Ok(true);
+        }
+
+        let ops = self.adapter.map_batch(requests)?;
+        let txid = batches.txid + 1;
+        ingestor
+            .handle_message(IngestionMessage::new_transaction_begin(txid, 0))
+            .map_err(ConnectorError::IngestorError)?;
+
+        batches.txid = txid;
+        if batches.order.len() == MAX_IDEMPOTENCY_KEYS {
+            if let Some(oldest) = batches.order.pop_front() {
+                batches.keys.remove(&oldest);
+            }
+        }
+        batches.keys.insert(idempotency_key.clone());
+        batches.order.push_back(idempotency_key);
+
+        let count = ops.len() as u64;
+        for (seq_no, (table_index, op)) in (1..).zip(ops) {
+            ingestor
+                .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_transaction_end(txid, count + 1))
+            .map_err(ConnectorError::IngestorError)?;
+        Ok(false)
+    }
 }
@@ -1,16 +1,21 @@
+use std::pin::Pin;
 use std::sync::Arc;
 
+use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::{grpc_types::ingest::IngestArrowRequest, log::error};
-use futures::StreamExt;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
 use tonic::Streaming;
 
 use dozer_types::grpc_types::ingest::{
-    ingest_service_server::IngestService, IngestRequest, IngestResponse,
+    ingest_service_server::IngestService, ingest_typed_request, IngestAck, IngestBatchRequest,
+    IngestBatchResponse, IngestRequest, IngestResponse, IngestTypedRequest,
 };
 
 use crate::{connectors::TableInfo, ingestion::Ingestor};
 
 use super::adapter::{GrpcIngestMessage, GrpcIngestor, IngestAdapter};
+use super::typed::TypedTables;
 
 pub struct IngestorServiceImpl<T>
 where
@@ -41,6 +46,8 @@ impl<T> IngestService for IngestorServiceImpl<T>
 where
     T: IngestAdapter,
 {
+    type IngestTypedStream = Pin<Box<dyn Stream<Item = Result<IngestAck, tonic::Status>> + Send>>;
+
     async fn ingest(
         &self,
         request: tonic::Request<IngestRequest>,
@@ -168,4 +175,158 @@ where
         .map_err(|e| tonic::Status::internal(format!("ingestion stream error: {e}")))?;
         Ok(tonic::Response::new(IngestResponse { seq_no }))
     }
+
+    async fn ingest_batch(
+        &self,
+        request: tonic::Request<IngestBatchRequest>,
+    ) -> Result<tonic::Response<IngestBatchResponse>, tonic::Status> {
+        let req = request.into_inner();
+        if req.idempotency_key.is_empty() {
+            return Err(tonic::Status::invalid_argument(
+                "idempotency key is required",
+            ));
+        }
+
+        let count = req.requests.len() as u32;
+        let requests = req
+            .requests
+            .into_iter()
+            .map(|req| {
+                let table_index = self
+                    .tables
+                    .iter()
+                    .position(|table| table.name == req.schema_name)
+                    .ok_or(tonic::Status::not_found(format!(
+                        "schema name not found: {}",
+                        req.schema_name
+                    )))?;
+                Ok((table_index, req))
+            })
+            .collect::<Result<Vec<_>, tonic::Status>>()?;
+
+        let duplicate = self
+            .adapter
+            .handle_batch(req.idempotency_key, requests, self.ingestor)
+            .map_err(|e| tonic::Status::internal(format!("ingestion batch error: {e}")))?;
+
+        Ok(tonic::Response::new(IngestBatchResponse {
+            count,
+            duplicate,
+        }))
+    }
+
+    async fn ingest_typed(
+        &self,
+        req: tonic::Request<Streaming<IngestTypedRequest>>,
+    ) -> Result<tonic::Response<Self::IngestTypedStream>, tonic::Status> {
+        let mut in_stream = req.into_inner();
+
+        // The schemas of the started tables, by table index.
+        let schemas = self
+            .adapter
+            .get_schemas()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let schemas = self
+            .tables
+            .iter()
+            .map(|table| {
+                schemas
+                    .iter()
+                    .find(|(name, _)| name == &table.name)
+                    .map(|(name, schema)| (name.clone(), schema.schema.clone()))
+                    .ok_or_else(|| {
+                        tonic::Status::internal(format!("schema not found: {}", table.name))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ingestor = self.ingestor;
+        let (mut acks, out_stream) = mpsc::channel(1024);
+        tokio::spawn(async move {
+            let typed_tables = match in_stream.next().await {
+                Some(Ok(IngestTypedRequest {
+                    request: Some(ingest_typed_request::Request::Register(register)),
+                })) => match TypedTables::register(register, &schemas) {
+                    Ok(typed_tables) => typed_tables,
+                    Err(e) => {
+                        let _ = acks
+                            .send(Err(tonic::Status::invalid_argument(e.to_string())))
+                            .await;
+                        return;
+                    }
+                },
+                Some(Ok(_)) => {
+                    let _ = acks
+                        .send(Err(tonic::Status::failed_precondition(
+                            "the first request must register the descriptors",
+                        )))
+                        .await;
+                    return;
+                }
+                Some(Err(e)) => {
+                    error!("ingestion stream errored: {:#?}", e);
+                    return;
+                }
+                None => return,
+            };
+
+            while let Some(result) = in_stream.next().await {
+                let record = match result {
+                    Ok(IngestTypedRequest {
+                        request: Some(ingest_typed_request::Request::Record(record)),
+                    }) => record,
+                    Ok(_) => {
+                        let _ = acks
+                            .send(Err(tonic::Status::failed_precondition(
+                                "descriptors are already registered",
+                            )))
+                            .await;
+                        break;
+                    }
+                    Err(e) => {
+                        error!("ingestion stream errored: {:#?}", e);
+                        break;
+                    }
+                };
+
+                let seq_no = record.seq_no;
+                let op = schemas
+                    .iter()
+                    .position(|(name, _)| name == &record.schema_name)
+                    .ok_or_else(|| format!("schema name not found: {}", record.schema_name))
+                    .and_then(|table_index| {
+                        typed_tables
+                            .operation(table_index, record)
+                            .map(|op| (table_index, op))
+                            .map_err(|e| e.to_string())
+                    });
+                let ack = match op {
+                    Ok((table_index, op)) => {
+                        let message = IngestionMessage::new_op(0, seq_no as u64, table_index, op);
+                        if let Err(e) = ingestor.handle_message(message) {
+                            error!("ingestion stream insertion errored: {:#?}", e);
+                            let _ = acks
+                                .send(Err(tonic::Status::unavailable(e.to_string())))
+                                .await;
+                            break;
+                        }
+                        IngestAck {
+                            seq_no,
+                            error: None,
+                        }
+                    }
+                    // A rejected record doesn't end the stream, the producer decides what to do with it.
+                    Err(e) => IngestAck {
+                        seq_no,
+                        error: Some(e),
+                    },
+                };
+                if acks.send(Ok(ack)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(tonic::Response::new(Box::pin(out_stream)))
+    }
 }
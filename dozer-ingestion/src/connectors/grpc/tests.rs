@@ -6,7 +6,10 @@ use dozer_types::arrow_types::to_arrow::DOZER_SCHEMA_KEY;
 use dozer_types::{
     arrow::array::{Int32Array, StringArray},
     grpc_types::{
-        ingest::{ingest_service_client::IngestServiceClient, IngestArrowRequest, IngestRequest},
+        ingest::{
+            ingest_service_client::IngestServiceClient, IngestArrowRequest, IngestBatchRequest,
+            IngestRequest,
+        },
         types,
     },
     ingestion_types::IngestionMessageKind,
@@ -132,6 +135,109 @@ async fn ingest_grpc_default() {
     }
 }
 
+fn user(id: i64, name: &str) -> IngestRequest {
+    IngestRequest {
+        schema_name: "users".to_string(),
+        new: Some(types::Record {
+            values: vec![
+                types::Value {
+                    value: Some(types::value::Value::IntValue(id)),
+                },
+                types::Value {
+                    value: Some(types::value::Value::StringValue(name.to_string())),
+                },
+            ],
+            version: 1,
+        }),
+        ..Default::default()
+    }
+}
+
+fn inserted_id(kind: IngestionMessageKind) -> i64 {
+    match kind {
+        IngestionMessageKind::OperationEvent {
+            op: Operation::Insert { new },
+            ..
+        } => new.values[0].as_int().unwrap(),
+        _ => panic!("wrong message kind"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn ingest_grpc_batch() {
+    let schemas = json!({
+      "users": {
+        "schema": {
+            "fields": [
+            {
+                "name": "id",
+                "typ": "Int",
+                "nullable": false
+            },
+            {
+                "name": "name",
+                "typ": "String",
+                "nullable": true
+            }
+            ]
+        }
+        }
+    });
+
+    let (mut ingest_client, mut iterator) =
+        ingest_grpc(schemas, "default".to_string(), 45680).await;
+
+    let batch = IngestBatchRequest {
+        idempotency_key: "batch-1".to_string(),
+        requests: vec![user(1, "dario"), user(2, "mario")],
+    };
+    let res = ingest_client.ingest_batch(batch.clone()).await.unwrap();
+    assert_eq!(res.get_ref().count, 2);
+    assert!(!res.get_ref().duplicate);
+    let msg = iterator.next().unwrap();
+    assert_eq!(msg.identifier.txid, 1);
+    assert_eq!(msg.kind, IngestionMessageKind::TransactionBegin);
+    for (seq_no, id) in [(1, 1), (2, 2)] {
+        let msg = iterator.next().unwrap();
+        assert_eq!(msg.identifier.txid, 1);
+        assert_eq!(msg.identifier.seq_in_tx, seq_no);
+        assert_eq!(inserted_id(msg.kind), id);
+    }
+    let msg = iterator.next().unwrap();
+    assert_eq!((msg.identifier.txid, msg.identifier.seq_in_tx), (1, 3));
+    assert_eq!(msg.kind, IngestionMessageKind::TransactionEnd);
+
+    // A retried batch is not ingested again.
+    let res = ingest_client.ingest_batch(batch).await.unwrap();
+    assert!(res.get_ref().duplicate);
+
+    // A batch with an invalid record is not ingested at all.
+    let mut invalid = user(4, "vario");
+    invalid.new.as_mut().unwrap().values.pop();
+    ingest_client
+        .ingest_batch(IngestBatchRequest {
+            idempotency_key: "batch-2".to_string(),
+            requests: vec![user(3, "lario"), invalid],
+        })
+        .await
+        .unwrap_err();
+
+    ingest_client
+        .ingest_batch(IngestBatchRequest {
+            idempotency_key: "batch-3".to_string(),
+            requests: vec![user(5, "zario")],
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        iterator.next().unwrap().kind,
+        IngestionMessageKind::TransactionBegin
+    );
+    let msg = iterator.next().unwrap();
+    assert_eq!(msg.identifier.txid, 2);
+    assert_eq!(inserted_id(msg.kind), 5);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_serialize_arrow_schema() {
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use dozer_types::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use dozer_types::grpc_types::ingest::{RegisterDescriptors, TypedRecord};
+use dozer_types::grpc_types::types::OperationType;
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json;
+use dozer_types::types::{Field, FieldType, Operation, Record, Schema};
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, Kind, MessageDescriptor, Value,
+};
+
+use crate::errors::ConnectorError;
+
+const TIMESTAMP_MESSAGE: &str = "google.protobuf.Timestamp";
+
+/// The messages a client registered for the tables, which it then sends records of.
+#[derive(Debug)]
+pub struct TypedTables {
+    /// The message of each registered table and the message field of each column, by table index.
+    tables: HashMap<usize, TypedTable>,
+}
+
+#[derive(Debug)]
+struct TypedTable {
+    message: MessageDescriptor,
+    columns: Vec<(FieldDescriptor, FieldType, bool)>,
+}
+
+impl TypedTables {
+    /// Checks that the message of each table has a field of a compatible type for each of its columns. `schemas` are
+    /// the names and schemas of the tables, by table index.
+    pub fn register(
+        request: RegisterDescriptors,
+        schemas: &[(String, Schema)],
+    ) -> Result<Self, ConnectorError> {
+        let invalid = |message: String| ConnectorError::InitializationError(message);
+        let pool = DescriptorPool::decode(request.file_descriptor_set.as_slice())
+            .map_err(|e| invalid(format!("invalid descriptor set: {e}")))?;
+
+        let mut tables = HashMap::new();
+        for (table_name, message_name) in request.messages {
+            let table_index = schemas
+                .iter()
+                .position(|(name, _)| name == &table_name)
+                .ok_or_else(|| invalid(format!("schema name not found: {table_name}")))?;
+            let message = pool
+                .get_message_by_name(&message_name)
+                .ok_or_else(|| invalid(format!("message not found: {message_name}")))?;
+            let columns = schemas[table_index]
+                .1
+                .fields
+                .iter()
+                .map(|column| {
+                    let field = message.get_field_by_name(&column.name).ok_or_else(|| {
+                        invalid(format!(
+                            "message {message_name} has no field {}",
+                            column.name
+                        ))
+                    })?;
+                    if !is_compatible(&field, column.typ) {
+                        return Err(invalid(format!(
+                            "field {} of message {message_name} cannot be mapped to {}",
+                            column.name, column.typ
+                        )));
+                    }
+                    Ok((field, column.typ, column.nullable))
+                })
+                .collect::<Result<_, _>>()?;
+            tables.insert(table_index, TypedTable { message, columns });
+        }
+        Ok(Self { tables })
+    }
+
+    pub fn operation(
+        &self,
+        table_index: usize,
+        record: TypedRecord,
+    ) -> Result<Operation, ConnectorError> {
+        let table = self.tables.get(&table_index).ok_or_else(|| {
+            ConnectorError::InitializationError(format!(
+                "no message registered for {}",
+                record.schema_name
+            ))
+        })?;
+        let typ = record.typ();
+        let decode = |bytes: Option<Vec<u8>>, name: &str| {
+            let bytes = bytes.ok_or_else(|| {
+                ConnectorError::InitializationError(format!("{name} record not found for {typ:?}"))
+            })?;
+            table.record(&bytes)
+        };
+        Ok(match typ {
+            OperationType::Insert => Operation::Insert {
+                new: decode(record.new, "new")?,
+            },
+            OperationType::Delete => Operation::Delete {
+                old: decode(record.old, "old")?,
+            },
+            OperationType::Update => Operation::Update {
+                old: decode(record.old, "old")?,
+                new: decode(record.new, "new")?,
+            },
+        })
+    }
+}
+
+impl TypedTable {
+    fn record(&self, bytes: &[u8]) -> Result<Record, ConnectorError> {
+        let message = DynamicMessage::decode(self.message.clone(), bytes).map_err(|e| {
+            ConnectorError::InitializationError(format!(
+                "invalid {} message: {e}",
+                self.message.full_name()
+            ))
+        })?;
+        let values = self
+            .columns
+            .iter()
+            .map(|(field, typ, nullable)| {
+                if field.supports_presence() && !message.has_field(field) {
+                    return if *nullable {
+                        Ok(Field::Null)
+                    } else {
+                        Err(ConnectorError::InitializationError(format!(
+                            "field {} is not nullable",
+                            field.name()
+                        )))
+                    };
+                }
+                map_value(&message.get_field(field), *typ).map_err(|e| {
+                    ConnectorError::InitializationError(format!(
+                        "invalid value of field {}: {e}",
+                        field.name()
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Record::new(values))
+    }
+}
+
+fn is_compatible(field: &FieldDescriptor, typ: FieldType) -> bool {
+    if field.is_list() || field.is_map() {
+        return false;
+    }
+    match (field.kind(), typ) {
+        (Kind::Uint32 | Kind::Uint64 | Kind::Fixed32 | Kind::Fixed64, FieldType::UInt) => true,
+        (
+            Kind::Int32
+            | Kind::Int64
+            | Kind::Sint32
+            | Kind::Sint64
+            | Kind::Sfixed32
+            | Kind::Sfixed64,
+            FieldType::Int,
+        ) => true,
+        (Kind::Double | Kind::Float, FieldType::Float) => true,
+        (Kind::Bool, FieldType::Boolean) => true,
+        (
+            Kind::String,
+            FieldType::String
+            | FieldType::Text
+            | FieldType::Decimal
+            | FieldType::Date
+            | FieldType::Timestamp
+            | FieldType::Json,
+        ) => true,
+        (Kind::Bytes, FieldType::Binary) => true,
+        (Kind::Message(message), FieldType::Timestamp) => message.full_name() == TIMESTAMP_MESSAGE,
+        (Kind::Message(_), FieldType::Json) => true,
+        _ => false,
+    }
+}
+
+/// Maps a value of a field `is_compatible` with `typ`.
+fn map_value(value: &Value, typ: FieldType) -> Result<Field, String> {
+    Ok(match (value, typ) {
+        (Value::U32(value), FieldType::UInt) => Field::UInt(*value as u64),
+        (Value::U64(value), FieldType::UInt) => Field::UInt(*value),
+        (Value::I32(value), FieldType::Int) => Field::Int(*value as i64),
+        (Value::I64(value), FieldType::Int) => Field::Int(*value),
+        (Value::F32(value), FieldType::Float) => Field::Float(OrderedFloat(*value as f64)),
+        (Value::F64(value), FieldType::Float) => Field::Float(OrderedFloat(*value)),
+        (Value::Bool(value), FieldType::Boolean) => Field::Boolean(*value),
+        (Value::String(value), FieldType::String) => Field::String(value.clone()),
+        (Value::String(value), FieldType::Text) => Field::Text(value.clone()),
+        (Value::String(value), FieldType::Decimal) => {
+            Field::Decimal(value.parse::<Decimal>().map_err(|e| e.to_string())?)
+        }
+        (Value::String(value), FieldType::Date) => {
+            Field::Date(NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| e.to_string())?)
+        }
+        (Value::String(value), FieldType::Timestamp) => {
+            Field::Timestamp(DateTime::parse_from_rfc3339(value).map_err(|e| e.to_string())?)
+        }
+        (Value::String(value), FieldType::Json) => {
+            let value = serde_json::from_str(value).map_err(|e| e.to_string())?;
+            Field::Json(serde_json_to_json_value(value).map_err(|e| e.to_string())?)
+        }
+        (Value::Bytes(value), FieldType::Binary) => Field::Binary(value.to_vec()),
+        (Value::Message(message), FieldType::Timestamp) => {
+            let part = |name| {
+                message
+                    .get_field_by_name(name)
+                    .map(|value| value.into_owned())
+            };
+            let seconds = part("seconds")
+                .and_then(|value| value.as_i64())
+                .unwrap_or(0);
+            let nanos = part("nanos").and_then(|value| value.as_i32()).unwrap_or(0);
+            let timestamp = NaiveDateTime::from_timestamp_opt(seconds, nanos as u32)
+                .ok_or_else(|| format!("timestamp out of range: {seconds}.{nanos}"))?;
+            Field::Timestamp(DateTime::<Utc>::from_utc(timestamp, Utc).into())
+        }
+        (Value::Message(message), FieldType::Json) => {
+            let value = serde_json::to_value(message).map_err(|e| e.to_string())?;
+            Field::Json(serde_json_to_json_value(value).map_err(|e| e.to_string())?)
+        }
+        (value, typ) => return Err(format!("{value:?} cannot be mapped to {typ}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::{FieldDefinition, SourceDefinition};
+    use prost::Message;
+    use prost_reflect::prost_types::{
+        field_descriptor_proto, DescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+        FileDescriptorSet,
+    };
+
+    use super::*;
+
+    fn descriptor_set() -> Vec<u8> {
+        let field = |name: &str, number, typ: field_descriptor_proto::Type| FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(typ as i32),
+            label: Some(field_descriptor_proto::Label::Optional as i32),
+            json_name: Some(name.to_string()),
+            ..Default::default()
+        };
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("films.proto".to_string()),
+                package: Some("films".to_string()),
+                syntax: Some("proto3".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Film".to_string()),
+                    field: vec![
+                        field("id", 1, field_descriptor_proto::Type::Int64),
+                        field("title", 2, field_descriptor_proto::Type::String),
+                        field("rating", 3, field_descriptor_proto::Type::Double),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    fn schemas(rating_type: FieldType) -> Vec<(String, Schema)> {
+        let mut schema = Schema::new();
+        for (name, typ) in [
+            ("id", FieldType::Int),
+            ("title", FieldType::String),
+            ("rating", rating_type),
+        ] {
+            schema.field(
+                FieldDefinition::new(name.to_string(), typ, true, SourceDefinition::Dynamic),
+                name == "id",
+            );
+        }
+        vec![("films".to_string(), schema)]
+    }
+
+    fn register(rating_type: FieldType) -> Result<TypedTables, ConnectorError> {
+        TypedTables::register(
+            RegisterDescriptors {
+                file_descriptor_set: descriptor_set(),
+                messages: [("films".to_string(), "films.Film".to_string())]
+                    .into_iter()
+                    .collect(),
+            },
+            &schemas(rating_type),
+        )
+    }
+
+    #[test]
+    fn test_typed_record() {
+        let tables = register(FieldType::Float).unwrap();
+        let message = tables.tables[&0].message.clone();
+        let mut film = DynamicMessage::new(message);
+        film.set_field_by_name("id", Value::I64(1));
+        film.set_field_by_name("title", Value::String("Alien".to_string()));
+        film.set_field_by_name("rating", Value::F64(8.5));
+
+        let op = tables
+            .operation(
+                0,
+                TypedRecord {
+                    schema_name: "films".to_string(),
+                    typ: OperationType::Insert as i32,
+                    old: None,
+                    new: Some(film.encode_to_vec()),
+                    seq_no: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            op,
+            Operation::Insert {
+                new: Record::new(vec![
+                    Field::Int(1),
+                    Field::String("Alien".to_string()),
+                    Field::Float(OrderedFloat(8.5)),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_incompatible_field() {
+        assert!(register(FieldType::Boolean).is_err());
+    }
+}
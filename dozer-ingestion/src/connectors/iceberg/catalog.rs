@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use dozer_types::ingestion_types::IcebergConfig;
+use dozer_types::serde::de::DeserializeOwned;
+use dozer_types::serde::Deserialize;
+use reqwest::{Client, StatusCode};
+use url::Url;
+
+use super::metadata::TableMetadata;
+use crate::errors::IcebergError;
+
+/// A client of the Iceberg REST catalog API.
+#[derive(Debug)]
+pub struct RestCatalog {
+    client: Client,
+    /// The catalog uri, with the `/v1` path and the prefix of the warehouse.
+    base_url: Url,
+    token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct CatalogConfig {
+    #[serde(default)]
+    defaults: HashMap<String, String>,
+    #[serde(default)]
+    overrides: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct ListNamespacesResponse {
+    namespaces: Vec<Vec<String>>,
+    #[serde(default, rename = "next-page-token")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct TableIdentifier {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct ListTablesResponse {
+    identifiers: Vec<TableIdentifier>,
+    #[serde(default, rename = "next-page-token")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct LoadTableResponse {
+    metadata: TableMetadata,
+}
+
+impl RestCatalog {
+    /// Reads the configuration of the catalog, which may prefix the paths of the warehouse.
+    pub async fn connect(config: &IcebergConfig) -> Result<Self, IcebergError> {
+        let base_url = format!("{}/v1", config.catalog_uri.trim_end_matches('/'));
+        let mut catalog = Self {
+            client: Client::new(),
+            base_url: Url::parse(&base_url)
+                .map_err(|_| IcebergError::InvalidCatalogUri(config.catalog_uri.clone()))?,
+            token: config.token.clone(),
+        };
+        let mut query = vec![];
+        if let Some(warehouse) = &config.warehouse {
+            query.push(("warehouse", warehouse.clone()));
+        }
+        let catalog_config: CatalogConfig = catalog.get(&["config"], &query).await?;
+        if let Some(prefix) = catalog_config
+            .overrides
+            .get("prefix")
+            .or(catalog_config.defaults.get("prefix"))
+        {
+            catalog
+                .base_url
+                .path_segments_mut()
+                .map_err(|_| IcebergError::InvalidCatalogUri(config.catalog_uri.clone()))?
+                .extend(prefix.split('/').filter(|segment| !segment.is_empty()));
+        }
+        Ok(catalog)
+    }
+
+    /// All the namespaces, nested ones included, with their levels joined by `.`.
+    pub async fn list_namespaces(&self) -> Result<Vec<String>, IcebergError> {
+        let mut namespaces = vec![];
+        let mut parents = vec![None];
+        while let Some(parent) = parents.pop() {
+            let mut page_token = None;
+            loop {
+                let mut query = vec![];
+                if let Some(parent) = &parent {
+                    query.push(("parent", namespace_levels(parent)));
+                }
+                if let Some(page_token) = page_token {
+                    query.push(("pageToken", page_token));
+                }
+                let response: ListNamespacesResponse = self.get(&["namespaces"], &query).await?;
+                for levels in response.namespaces {
+                    let namespace = levels.join(".");
+                    // Catalogs without nested namespaces ignore the parent.
+                    if parent.as_ref() == Some(&namespace) || namespaces.contains(&namespace) {
+                        continue;
+                    }
+                    parents.push(Some(namespace.clone()));
+                    namespaces.push(namespace);
+                }
+                match response.next_page_token {
+                    Some(token) => page_token = Some(token),
+                    None => break,
+                }
+            }
+        }
+        Ok(namespaces)
+    }
+
+    pub async fn list_tables(&self, namespace: &str) -> Result<Vec<String>, IcebergError> {
+        let namespace = namespace_levels(namespace);
+        let path = ["namespaces", &namespace, "tables"];
+        let mut tables = vec![];
+        let mut page_token = None;
+        loop {
+            let query = page_token
+                .map(|token| vec![("pageToken", token)])
+                .unwrap_or_default();
+            let response: ListTablesResponse = self.get(&path, &query).await?;
+            tables.extend(response.identifiers.into_iter().map(|table| table.name));
+            match response.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return Ok(tables),
+            }
+        }
+    }
+
+    pub async fn load_table(
+        &self,
+        namespace: &str,
+        table: &str,
+    ) -> Result<TableMetadata, IcebergError> {
+        let levels = namespace_levels(namespace);
+        let path = ["namespaces", &levels, "tables", table];
+        match self.get::<LoadTableResponse>(&path, &[]).await {
+            Ok(response) => Ok(response.metadata),
+            Err(IcebergError::Request(e)) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                Err(IcebergError::TableNotFound(format!("{namespace}.{table}")))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        query: &[(&str, String)],
+    ) -> Result<T, IcebergError> {
+        let mut url = self.base_url.clone();
+        // Urls with a host always have path segments.
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.extend(path);
+        }
+        let mut request = self.client.get(url).query(query);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+}
+
+/// Namespace levels are separated by the unit separator in requests.
+fn namespace_levels(namespace: &str) -> String {
+    namespace.replace('.', "\u{1f}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_url() {
+        let mut url = Url::parse("http://localhost:8181/v1/warehouse").unwrap();
+        let levels = namespace_levels("analytics.films");
+        url.path_segments_mut()
+            .unwrap()
+            .extend(["namespaces", &levels, "tables", "new releases"]);
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8181/v1/warehouse/namespaces/analytics%1Ffilms/tables/new%20releases"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{IcebergConfig, IngestionMessage};
+use dozer_types::log::info;
+use dozer_types::types::FieldType;
+use tonic::async_trait;
+
+use super::catalog::RestCatalog;
+use super::metadata::{map_schema, TableMetadata};
+use super::reader::TableReader;
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, IcebergError};
+use crate::ingestion::Ingestor;
+
+/// Reads the tables of an Iceberg REST catalog, and follows the snapshots committed to them.
+#[derive(Debug)]
+pub struct IcebergConnector {
+    name: String,
+    config: IcebergConfig,
+}
+
+impl IcebergConnector {
+    pub fn new(name: String, config: IcebergConfig) -> Self {
+        Self { name, config }
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let catalog = RestCatalog::connect(&self.config).await?;
+        let mut seq_no = 0;
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+        seq_no += 1;
+
+        let mut readers = vec![];
+        for (table_index, table) in tables.iter().enumerate() {
+            let metadata = load_table(&catalog, table.schema.as_deref(), &table.name).await?;
+            let schema = map_schema(metadata.current_schema()?, &table.column_names)?;
+            let mut reader = TableReader::new(table_index, schema);
+            reader
+                .read_snapshot(&metadata, &mut seq_no, ingestor)
+                .await?;
+            readers.push(reader);
+        }
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+        seq_no += 1;
+
+        if !self.config.follow {
+            return Ok(());
+        }
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            for (table, reader) in tables.iter().zip(&mut readers) {
+                let metadata = load_table(&catalog, table.schema.as_deref(), &table.name).await?;
+                reader
+                    .read_new_snapshots(&metadata, &mut seq_no, ingestor)
+                    .await?;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for IcebergConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+            ("int".to_string(), Some(FieldType::Int)),
+            ("long".to_string(), Some(FieldType::Int)),
+            ("float".to_string(), Some(FieldType::Float)),
+            ("double".to_string(), Some(FieldType::Float)),
+            ("decimal".to_string(), None),
+            ("date".to_string(), Some(FieldType::Date)),
+            ("time".to_string(), Some(FieldType::Int)),
+            ("timestamp".to_string(), Some(FieldType::Timestamp)),
+            ("timestamptz".to_string(), Some(FieldType::Timestamp)),
+            ("string".to_string(), Some(FieldType::String)),
+            ("uuid".to_string(), Some(FieldType::Binary)),
+            ("fixed".to_string(), Some(FieldType::Binary)),
+            ("binary".to_string(), Some(FieldType::Binary)),
+            ("struct".to_string(), None),
+            ("list".to_string(), None),
+            ("map".to_string(), None),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        RestCatalog::connect(&self.config).await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let catalog = RestCatalog::connect(&self.config).await?;
+        let mut tables = vec![];
+        for namespace in catalog.list_namespaces().await? {
+            for table in catalog.list_tables(&namespace).await? {
+                tables.push(TableIdentifier::new(Some(namespace.clone()), table));
+            }
+        }
+        info!("[{}] Found {} tables", self.name, tables.len());
+        Ok(tables)
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let catalog = RestCatalog::connect(&self.config).await?;
+        for table in tables {
+            load_table(&catalog, table.schema.as_deref(), &table.name).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let catalog = RestCatalog::connect(&self.config).await?;
+        let mut table_infos = vec![];
+        for table in tables {
+            let metadata = load_table(&catalog, table.schema.as_deref(), &table.name).await?;
+            let column_names = metadata
+                .current_schema()?
+                .fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect();
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names,
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let catalog = RestCatalog::connect(&self.config).await?;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            let metadata =
+                load_table(&catalog, table_info.schema.as_deref(), &table_info.name).await?;
+            // Rewritten rows are deleted and inserted whole.
+            schemas.push(
+                metadata
+                    .current_schema()
+                    .and_then(|schema| map_schema(schema, &table_info.column_names))
+                    .map(|schema| SourceSchema::new(schema, CdcType::FullChanges))
+                    .map_err(ConnectorError::from),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+/// Tables are in a namespace.
+async fn load_table(
+    catalog: &RestCatalog,
+    namespace: Option<&str>,
+    table: &str,
+) -> Result<TableMetadata, ConnectorError> {
+    let Some(namespace) = namespace else {
+        return Err(ConnectorError::TableNotFound(table_name(None, table)));
+    };
+    catalog
+        .load_table(namespace, table)
+        .await
+        .map_err(|e| match e {
+            IcebergError::TableNotFound(name) => ConnectorError::TableNotFound(name),
+            e => e.into(),
+        })
+}
@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use deltalake::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use dozer_types::arrow_types::from_arrow::map_value_to_dozer_field;
+use dozer_types::bytes::Bytes;
+use dozer_types::serde::Deserialize;
+use dozer_types::types::{Field, Schema};
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use url::Url;
+
+use crate::errors::IcebergError;
+
+/// An entry of a manifest list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ManifestFile {
+    pub manifest_path: String,
+    /// 0 for data files, 1 for delete files. Format version 1 only has data files.
+    #[serde(default)]
+    pub content: i32,
+    pub added_snapshot_id: Option<i64>,
+}
+
+/// An entry of a manifest.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ManifestEntry {
+    pub status: i32,
+    /// Inherited from the manifest if `None`.
+    pub snapshot_id: Option<i64>,
+    pub data_file: DataFile,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct DataFile {
+    #[serde(default)]
+    pub content: i32,
+    pub file_path: String,
+    pub file_format: String,
+}
+
+pub const STATUS_ADDED: i32 = 1;
+pub const STATUS_DELETED: i32 = 2;
+
+/// Reads a table file. Locations are urls of S3 objects or local files, or local paths.
+pub async fn read_file(location: &str) -> Result<Bytes, IcebergError> {
+    let (store, path) = resolve(location)?;
+    let read_error = |e| IcebergError::ReadFile(location.to_string(), e);
+    store
+        .get(&path)
+        .await
+        .map_err(read_error)?
+        .bytes()
+        .await
+        .map_err(read_error)
+}
+
+fn resolve(location: &str) -> Result<(Arc<dyn ObjectStore>, Path), IcebergError> {
+    let invalid = || IcebergError::InvalidLocation(location.to_string());
+    let url = match Url::parse(location) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            let path = Path::parse(location).map_err(|_| invalid())?;
+            return Ok((Arc::new(LocalFileSystem::new()), path));
+        }
+        Err(_) => return Err(invalid()),
+    };
+    let path = Path::from_url_path(url.path()).map_err(|_| invalid())?;
+    match url.scheme() {
+        "s3" | "s3a" | "s3n" => {
+            let bucket = url.host_str().ok_or_else(invalid)?;
+            let store = AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(|e| IcebergError::ReadFile(location.to_string(), e))?;
+            Ok((Arc::new(store), path))
+        }
+        "file" => Ok((Arc::new(LocalFileSystem::new()), path)),
+        _ => Err(invalid()),
+    }
+}
+
+pub async fn read_manifest_list(location: &str) -> Result<Vec<ManifestFile>, IcebergError> {
+    decode_avro(location, &read_file(location).await?)
+}
+
+pub async fn read_manifest(location: &str) -> Result<Vec<ManifestEntry>, IcebergError> {
+    decode_avro(location, &read_file(location).await?)
+}
+
+fn decode_avro<T: for<'de> Deserialize<'de>>(
+    location: &str,
+    bytes: &[u8],
+) -> Result<Vec<T>, IcebergError> {
+    let decode_error = |e| IcebergError::DecodeManifest(location.to_string(), e);
+    apache_avro::Reader::new(bytes)
+        .map_err(decode_error)?
+        .map(|value| apache_avro::from_value(&value.map_err(decode_error)?).map_err(decode_error))
+        .collect()
+}
+
+/// Reads the columns of `schema` from a data file. Columns added to the table after the file was written are null.
+pub async fn read_data_file(
+    data_file: &DataFile,
+    schema: &Schema,
+) -> Result<Vec<Vec<Field>>, IcebergError> {
+    let location = &data_file.file_path;
+    if data_file.content != 0 {
+        return Err(IcebergError::DeleteFileNotSupported(location.clone()));
+    }
+    if !data_file.file_format.eq_ignore_ascii_case("parquet") {
+        return Err(IcebergError::UnsupportedFileFormat(
+            location.clone(),
+            data_file.file_format.clone(),
+        ));
+    }
+    decode_parquet(location, read_file(location).await?, schema)
+}
+
+fn decode_parquet(
+    location: &str,
+    bytes: Bytes,
+    schema: &Schema,
+) -> Result<Vec<Vec<Field>>, IcebergError> {
+    let decode_error = |e| IcebergError::DecodeDataFile(location.to_string(), e);
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)
+        .map_err(decode_error)?
+        .build()
+        .map_err(decode_error)?;
+
+    let map_error = |e| IcebergError::MapDataFile(location.to_string(), e);
+    let mut rows = vec![];
+    for batch in reader {
+        let batch = batch.map_err(|e| map_error(e.into()))?;
+        for row in 0..batch.num_rows() {
+            let fields = schema
+                .fields
+                .iter()
+                .map(|field| match batch.column_by_name(&field.name) {
+                    Some(column) => map_value_to_dozer_field(column, row, &field.name, schema)
+                        .map_err(map_error),
+                    None => Ok(Field::Null),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(fields);
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::types::Value as AvroValue;
+    use deltalake::arrow::array::{Int64Array, StringArray};
+    use deltalake::arrow::record_batch::RecordBatch;
+    use deltalake::parquet::arrow::ArrowWriter;
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use super::*;
+
+    const MANIFEST_ENTRY_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "manifest_entry",
+        "fields": [
+            {"name": "status", "type": "int"},
+            {"name": "snapshot_id", "type": ["null", "long"], "default": null},
+            {"name": "data_file", "type": {
+                "type": "record",
+                "name": "r2",
+                "fields": [
+                    {"name": "content", "type": "int"},
+                    {"name": "file_path", "type": "string"},
+                    {"name": "file_format", "type": "string"},
+                    {"name": "record_count", "type": "long"}
+                ]
+            }}
+        ]
+    }"#;
+
+    #[test]
+    fn test_decode_manifest() {
+        let schema = apache_avro::Schema::parse_str(MANIFEST_ENTRY_SCHEMA).unwrap();
+        let mut writer = apache_avro::Writer::new(&schema, vec![]);
+        for (status, snapshot_id) in [(1, Some(7)), (0, None)] {
+            let snapshot_id = match snapshot_id {
+                Some(id) => AvroValue::Union(1, Box::new(AvroValue::Long(id))),
+                None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+            };
+            writer
+                .append(AvroValue::Record(vec![
+                    ("status".to_string(), AvroValue::Int(status)),
+                    ("snapshot_id".to_string(), snapshot_id),
+                    (
+                        "data_file".to_string(),
+                        AvroValue::Record(vec![
+                            ("content".to_string(), AvroValue::Int(0)),
+                            (
+                                "file_path".to_string(),
+                                AvroValue::String(
+                                    "s3://warehouse/films/data/1.parquet".to_string(),
+                                ),
+                            ),
+                            (
+                                "file_format".to_string(),
+                                AvroValue::String("PARQUET".to_string()),
+                            ),
+                            ("record_count".to_string(), AvroValue::Long(2)),
+                        ]),
+                    ),
+                ]))
+                .unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+
+        let entries: Vec<ManifestEntry> = decode_avro("manifest.avro", &bytes).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].status, STATUS_ADDED);
+        assert_eq!(entries[0].snapshot_id, Some(7));
+        assert_eq!(
+            entries[0].data_file.file_path,
+            "s3://warehouse/films/data/1.parquet"
+        );
+        assert_eq!(entries[1].snapshot_id, None);
+    }
+
+    #[test]
+    fn test_decode_parquet() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "id",
+                Arc::new(Int64Array::from(vec![1, 2])) as deltalake::arrow::array::ArrayRef,
+            ),
+            ("title", Arc::new(StringArray::from(vec!["a", "b"])) as _),
+        ])
+        .unwrap();
+        let mut bytes = vec![];
+        let mut writer = ArrowWriter::try_new(&mut bytes, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut schema = Schema::new();
+        for (name, typ) in [
+            ("title", FieldType::String),
+            ("id", FieldType::Int),
+            ("rating", FieldType::Float),
+        ] {
+            schema.field(
+                FieldDefinition::new(name.to_string(), typ, true, SourceDefinition::Dynamic),
+                false,
+            );
+        }
+        assert_eq!(
+            decode_parquet("1.parquet", Bytes::from(bytes), &schema).unwrap(),
+            vec![
+                vec![Field::String("a".to_string()), Field::Int(1), Field::Null],
+                vec![Field::String("b".to_string()), Field::Int(2), Field::Null],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let (_, path) = resolve("s3://warehouse/db/films/metadata/snap-1.avro").unwrap();
+        assert_eq!(path.as_ref(), "db/films/metadata/snap-1.avro");
+        let (_, path) = resolve("file:///tmp/films/data/1.parquet").unwrap();
+        assert_eq!(path.as_ref(), "tmp/films/data/1.parquet");
+        assert!(matches!(
+            resolve("gs://warehouse/films"),
+            Err(IcebergError::InvalidLocation(..))
+        ));
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use dozer_types::serde::Deserialize;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+
+use crate::errors::IcebergError;
+
+/// The parts of the metadata of a table that are read, in format version 1 or 2.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "kebab-case")]
+pub struct TableMetadata {
+    pub location: String,
+    #[serde(default)]
+    pub current_schema_id: Option<i32>,
+    #[serde(default)]
+    pub schemas: Vec<IcebergSchema>,
+    /// Format version 1 may only have the current schema.
+    #[serde(default)]
+    pub schema: Option<IcebergSchema>,
+    /// Format version 1 has -1 if there is no snapshot.
+    #[serde(default)]
+    pub current_snapshot_id: Option<i64>,
+    #[serde(default)]
+    pub snapshots: Vec<Snapshot>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "kebab-case")]
+pub struct IcebergSchema {
+    #[serde(default)]
+    pub schema_id: i32,
+    pub fields: Vec<NestedField>,
+    #[serde(default)]
+    pub identifier_field_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct NestedField {
+    pub id: i32,
+    pub name: String,
+    pub required: bool,
+    /// A primitive type name, or an object describing a struct, list or map.
+    #[serde(rename = "type")]
+    pub typ: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "kebab-case")]
+pub struct Snapshot {
+    pub snapshot_id: i64,
+    #[serde(default)]
+    pub parent_snapshot_id: Option<i64>,
+    #[serde(default)]
+    pub manifest_list: Option<String>,
+    #[serde(default)]
+    pub summary: HashMap<String, String>,
+}
+
+impl TableMetadata {
+    pub fn current_schema(&self) -> Result<&IcebergSchema, IcebergError> {
+        self.schemas
+            .iter()
+            .find(|schema| Some(schema.schema_id) == self.current_schema_id)
+            .or(self.schema.as_ref())
+            .ok_or(IcebergError::SchemaNotFound(self.current_schema_id))
+    }
+
+    pub fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_snapshot_id.and_then(|id| self.snapshot(id))
+    }
+
+    fn snapshot(&self, snapshot_id: i64) -> Option<&Snapshot> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.snapshot_id == snapshot_id)
+    }
+
+    /// The snapshots committed after `snapshot_id`, oldest first, or all the ancestors of the current snapshot if
+    /// `snapshot_id` is `None`.
+    pub fn snapshots_since(
+        &self,
+        snapshot_id: Option<i64>,
+    ) -> Result<Vec<&Snapshot>, IcebergError> {
+        let mut snapshots = vec![];
+        let mut next = self.current_snapshot();
+        while let Some(snapshot) = next {
+            if Some(snapshot.snapshot_id) == snapshot_id {
+                snapshots.reverse();
+                return Ok(snapshots);
+            }
+            snapshots.push(snapshot);
+            // Expired snapshots end the history.
+            next = snapshot
+                .parent_snapshot_id
+                .and_then(|parent_id| self.snapshot(parent_id));
+        }
+        match snapshot_id {
+            Some(snapshot_id) => Err(IcebergError::SnapshotNotAncestor(snapshot_id)),
+            None => {
+                snapshots.reverse();
+                Ok(snapshots)
+            }
+        }
+    }
+}
+
+impl Snapshot {
+    /// Compactions replace data files without changing the rows of the table.
+    pub fn changes_rows(&self) -> bool {
+        self.summary.get("operation").map(String::as_str) != Some("replace")
+    }
+}
+
+/// Maps the columns of an Iceberg schema, keyed by its identifier fields if they are all columns.
+pub fn map_schema(schema: &IcebergSchema, columns: &[String]) -> Result<Schema, IcebergError> {
+    let mut dozer_schema = Schema::new();
+    for column in columns {
+        let field = schema
+            .fields
+            .iter()
+            .find(|field| &field.name == column)
+            .ok_or_else(|| IcebergError::ColumnNotFound(column.clone()))?;
+        dozer_schema.field(
+            FieldDefinition::new(
+                field.name.clone(),
+                map_type(field)?,
+                !field.required,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+    }
+
+    let primary_index = schema
+        .identifier_field_ids
+        .iter()
+        .map(|id| {
+            let field = schema.fields.iter().find(|field| field.id == *id)?;
+            columns.iter().position(|column| column == &field.name)
+        })
+        .collect::<Option<Vec<_>>>();
+    if let Some(primary_index) = primary_index {
+        dozer_schema.primary_index = primary_index;
+    }
+    Ok(dozer_schema)
+}
+
+/// Types are mapped the way their parquet columns are read.
+fn map_type(field: &NestedField) -> Result<FieldType, IcebergError> {
+    let unsupported = || IcebergError::UnsupportedType(field.name.clone(), field.typ.to_string());
+    let Value::String(typ) = &field.typ else {
+        return Err(unsupported());
+    };
+    Ok(match typ.as_str() {
+        "boolean" => FieldType::Boolean,
+        "int" | "long" | "time" => FieldType::Int,
+        "float" | "double" => FieldType::Float,
+        "date" => FieldType::Date,
+        "timestamp" | "timestamptz" => FieldType::Timestamp,
+        "string" => FieldType::String,
+        "uuid" | "binary" => FieldType::Binary,
+        typ if typ.starts_with("fixed[") => FieldType::Binary,
+        _ => return Err(unsupported()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json;
+
+    use super::*;
+
+    const METADATA: &str = r#"{
+        "format-version": 2,
+        "table-uuid": "9c12d441-03fe-4693-9a96-a0705ddf69c1",
+        "location": "s3://warehouse/db/films",
+        "last-sequence-number": 3,
+        "current-schema-id": 1,
+        "schemas": [
+            {"type": "struct", "schema-id": 0, "fields": [
+                {"id": 1, "name": "id", "required": true, "type": "long"}
+            ]},
+            {"type": "struct", "schema-id": 1, "identifier-field-ids": [1], "fields": [
+                {"id": 1, "name": "id", "required": true, "type": "long"},
+                {"id": 2, "name": "title", "required": false, "type": "string"},
+                {"id": 3, "name": "released_at", "required": false, "type": "timestamptz"},
+                {"id": 4, "name": "tags", "required": false, "type": {
+                    "type": "list", "element-id": 5, "element": "string", "element-required": false
+                }}
+            ]}
+        ],
+        "current-snapshot-id": 3,
+        "snapshots": [
+            {"snapshot-id": 1, "manifest-list": "s3://warehouse/db/films/metadata/snap-1.avro",
+                "summary": {"operation": "append"}},
+            {"snapshot-id": 2, "parent-snapshot-id": 1,
+                "manifest-list": "s3://warehouse/db/films/metadata/snap-2.avro",
+                "summary": {"operation": "overwrite"}},
+            {"snapshot-id": 3, "parent-snapshot-id": 2,
+                "manifest-list": "s3://warehouse/db/films/metadata/snap-3.avro",
+                "summary": {"operation": "replace"}}
+        ]
+    }"#;
+
+    fn snapshot_ids(snapshots: Vec<&Snapshot>) -> Vec<i64> {
+        snapshots
+            .into_iter()
+            .map(|snapshot| snapshot.snapshot_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshots_since() {
+        let metadata: TableMetadata = serde_json::from_str(METADATA).unwrap();
+        assert_eq!(
+            snapshot_ids(metadata.snapshots_since(None).unwrap()),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            snapshot_ids(metadata.snapshots_since(Some(1)).unwrap()),
+            vec![2, 3]
+        );
+        assert!(metadata.snapshots_since(Some(3)).unwrap().is_empty());
+        assert!(matches!(
+            metadata.snapshots_since(Some(4)),
+            Err(IcebergError::SnapshotNotAncestor(4))
+        ));
+        assert!(!metadata.current_snapshot().unwrap().changes_rows());
+    }
+
+    #[test]
+    fn test_map_schema() {
+        let metadata: TableMetadata = serde_json::from_str(METADATA).unwrap();
+        let schema = metadata.current_schema().unwrap();
+
+        let columns = vec!["title".to_string(), "id".to_string()];
+        let dozer_schema = map_schema(schema, &columns).unwrap();
+        assert_eq!(
+            dozer_schema
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.typ, field.nullable))
+                .collect::<Vec<_>>(),
+            vec![
+                ("title", FieldType::String, true),
+                ("id", FieldType::Int, false)
+            ]
+        );
+        assert_eq!(dozer_schema.primary_index, vec![1]);
+
+        // The identifier field isn't selected.
+        let columns = vec!["released_at".to_string()];
+        let dozer_schema = map_schema(schema, &columns).unwrap();
+        assert_eq!(dozer_schema.fields[0].typ, FieldType::Timestamp);
+        assert!(dozer_schema.primary_index.is_empty());
+
+        assert!(matches!(
+            map_schema(schema, &["tags".to_string()]),
+            Err(IcebergError::UnsupportedType(..))
+        ));
+        assert!(matches!(
+            map_schema(schema, &["rating".to_string()]),
+            Err(IcebergError::ColumnNotFound(..))
+        ));
+    }
+}
@@ -0,0 +1,7 @@
+mod catalog;
+mod connector;
+mod files;
+mod metadata;
+mod reader;
+
+pub use connector::IcebergConnector;
@@ -0,0 +1,151 @@
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::{Operation, Record, Schema};
+
+use super::files::{
+    read_data_file, read_manifest, read_manifest_list, DataFile, ManifestFile, STATUS_ADDED,
+    STATUS_DELETED,
+};
+use super::metadata::{Snapshot, TableMetadata};
+use crate::connectors::delta_lake::diff_rows;
+use crate::errors::{ConnectorError, IcebergError};
+use crate::ingestion::Ingestor;
+
+/// Reads a table from a snapshot on, keeping the snapshot it read up to.
+#[derive(Debug)]
+pub struct TableReader {
+    table_index: usize,
+    /// The columns read.
+    schema: Schema,
+    /// `None` until a snapshot of the table is read.
+    snapshot_id: Option<i64>,
+}
+
+impl TableReader {
+    pub fn new(table_index: usize, schema: Schema) -> Self {
+        Self {
+            table_index,
+            schema,
+            snapshot_id: None,
+        }
+    }
+
+    /// Inserts the rows of the current snapshot.
+    pub async fn read_snapshot(
+        &mut self,
+        metadata: &TableMetadata,
+        seq_no: &mut u64,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let Some(snapshot) = metadata.current_snapshot() else {
+            return Ok(());
+        };
+        for data_file in live_data_files(snapshot).await? {
+            for row in read_data_file(&data_file, &self.schema).await? {
+                self.send(
+                    Operation::Insert {
+                        new: Record::new(row),
+                    },
+                    seq_no,
+                    ingestor,
+                )?;
+            }
+        }
+        self.snapshot_id = Some(snapshot.snapshot_id);
+        Ok(())
+    }
+
+    /// Ingests the changes of the snapshots committed since the one read up to.
+    pub async fn read_new_snapshots(
+        &mut self,
+        metadata: &TableMetadata,
+        seq_no: &mut u64,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        for snapshot in metadata.snapshots_since(self.snapshot_id)? {
+            if snapshot.changes_rows() {
+                let (deleted_files, added_files) = changed_data_files(snapshot).await?;
+                let mut removed = vec![];
+                for data_file in &deleted_files {
+                    removed.extend(read_data_file(data_file, &self.schema).await?);
+                }
+                let mut added = vec![];
+                for data_file in &added_files {
+                    added.extend(read_data_file(data_file, &self.schema).await?);
+                }
+                for op in diff_rows(removed, added) {
+                    self.send(op, seq_no, ingestor)?;
+                }
+            }
+            self.snapshot_id = Some(snapshot.snapshot_id);
+        }
+        Ok(())
+    }
+
+    fn send(
+        &self,
+        op: Operation,
+        seq_no: &mut u64,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        ingestor
+            .handle_message(IngestionMessage::new_op(0, *seq_no, self.table_index, op))
+            .map_err(ConnectorError::IngestorError)?;
+        *seq_no += 1;
+        Ok(())
+    }
+}
+
+async fn manifests(snapshot: &Snapshot) -> Result<Vec<ManifestFile>, IcebergError> {
+    let manifest_list = snapshot
+        .manifest_list
+        .as_ref()
+        .ok_or(IcebergError::ManifestListNotFound(snapshot.snapshot_id))?;
+    let manifests = read_manifest_list(manifest_list).await?;
+    // Deletes of merge-on-read tables are applied when reading, which isn't supported.
+    match manifests.iter().find(|manifest| manifest.content != 0) {
+        Some(manifest) => Err(IcebergError::DeleteFileNotSupported(
+            manifest.manifest_path.clone(),
+        )),
+        None => Ok(manifests),
+    }
+}
+
+/// The data files of the table at `snapshot`.
+async fn live_data_files(snapshot: &Snapshot) -> Result<Vec<DataFile>, IcebergError> {
+    let mut data_files = vec![];
+    for manifest in manifests(snapshot).await? {
+        for entry in read_manifest(&manifest.manifest_path).await? {
+            if entry.status != STATUS_DELETED {
+                data_files.push(entry.data_file);
+            }
+        }
+    }
+    Ok(data_files)
+}
+
+/// The data files `snapshot` deleted and added. Only the manifests written by the snapshot have them.
+async fn changed_data_files(
+    snapshot: &Snapshot,
+) -> Result<(Vec<DataFile>, Vec<DataFile>), IcebergError> {
+    let mut deleted = vec![];
+    let mut added = vec![];
+    for manifest in manifests(snapshot).await? {
+        if manifest
+            .added_snapshot_id
+            .map_or(false, |id| id != snapshot.snapshot_id)
+        {
+            continue;
+        }
+        for entry in read_manifest(&manifest.manifest_path).await? {
+            if entry.snapshot_id.or(manifest.added_snapshot_id) != Some(snapshot.snapshot_id) {
+                continue;
+            }
+            match entry.status {
+                STATUS_ADDED => added.push(entry.data_file),
+                STATUS_DELETED => deleted.push(entry.data_file),
+                _ => (),
+            }
+        }
+    }
+    Ok((deleted, added))
+}
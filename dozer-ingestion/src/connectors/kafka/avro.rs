@@ -0,0 +1,257 @@
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
+use dozer_types::chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json;
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition, TimeUnit,
+};
+
+use crate::errors::KafkaError::AvroError;
+use crate::errors::KafkaSchemaError::{
+    DecimalConvertError, InvalidDateError, InvalidJsonError, InvalidTimestampError,
+    SchemaDefinitionNotFound, TypeNotSupported,
+};
+use crate::errors::{KafkaError, KafkaSchemaError};
+
+/// Days from 0001-01-01 to 1970-01-01, which Avro dates count from.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Maps the Avro value schema of a topic to a dozer schema, with the fields of the key schema as primary index.
+pub fn map_schema(
+    value_schema: &AvroSchema,
+    key_schema: &AvroSchema,
+) -> Result<Schema, KafkaSchemaError> {
+    let AvroSchema::Record { fields, .. } = value_schema else {
+        return Err(SchemaDefinitionNotFound);
+    };
+    let key_fields = match key_schema {
+        AvroSchema::Record { fields, .. } => fields.iter().map(|f| f.name.as_str()).collect(),
+        _ => vec![],
+    };
+
+    let mut schema = Schema::new();
+    for field in fields {
+        let (typ, nullable) = map_type(&field.schema)?;
+        schema.field(
+            FieldDefinition::new(field.name.clone(), typ, nullable, SourceDefinition::Dynamic),
+            key_fields.contains(&field.name.as_str()),
+        );
+    }
+    Ok(schema)
+}
+
+/// Maps an Avro type to a dozer type and whether it's nullable. Unions of `null` and one type are that type,
+/// nullable. Other unions and nested types are json.
+fn map_type(schema: &AvroSchema) -> Result<(FieldType, bool), KafkaSchemaError> {
+    let AvroSchema::Union(union) = schema else {
+        return Ok((map_non_union_type(schema)?, false));
+    };
+    let variants = non_null_variants(union.variants());
+    let nullable = variants.len() < union.variants().len();
+    match variants.as_slice() {
+        [variant] => Ok((map_type(variant)?.0, nullable)),
+        _ => Ok((FieldType::Json, nullable)),
+    }
+}
+
+fn map_non_union_type(schema: &AvroSchema) -> Result<FieldType, KafkaSchemaError> {
+    match schema {
+        AvroSchema::Boolean => Ok(FieldType::Boolean),
+        AvroSchema::Int | AvroSchema::Long => Ok(FieldType::Int),
+        AvroSchema::Float | AvroSchema::Double => Ok(FieldType::Float),
+        AvroSchema::Bytes | AvroSchema::Fixed { .. } => Ok(FieldType::Binary),
+        AvroSchema::String | AvroSchema::Enum { .. } | AvroSchema::Uuid => Ok(FieldType::String),
+        AvroSchema::Decimal { .. } => Ok(FieldType::Decimal),
+        AvroSchema::Date => Ok(FieldType::Date),
+        AvroSchema::TimestampMillis | AvroSchema::TimestampMicros => Ok(FieldType::Timestamp),
+        AvroSchema::TimeMillis | AvroSchema::TimeMicros => Ok(FieldType::Duration),
+        AvroSchema::Array(_)
+        | AvroSchema::Map(_)
+        | AvroSchema::Record { .. }
+        | AvroSchema::Ref { .. }
+        | AvroSchema::Union(_) => Ok(FieldType::Json),
+        AvroSchema::Null => Err(TypeNotSupported("null".to_string())),
+        AvroSchema::Duration => Err(TypeNotSupported("duration".to_string())),
+    }
+}
+
+fn non_null_variants(variants: &[AvroSchema]) -> Vec<&AvroSchema> {
+    variants
+        .iter()
+        .filter(|variant| !matches!(variant, AvroSchema::Null))
+        .collect()
+}
+
+/// Maps a record written with any version of a topic's value schema to the fields of `schema`'s dozer schema.
+///
+/// The record is resolved to `schema` first, so fields added since it was written take their defaults and fields
+/// removed since are dropped.
+pub fn map_record(value: AvroValue, schema: &AvroSchema) -> Result<Vec<Field>, KafkaError> {
+    let value = value.resolve(schema).map_err(AvroError)?;
+    let (AvroValue::Record(values), AvroSchema::Record { fields, .. }) = (value, schema) else {
+        return Err(SchemaDefinitionNotFound.into());
+    };
+    values
+        .into_iter()
+        .zip(fields)
+        .map(|((_, value), field)| map_value(value, &field.schema))
+        .collect()
+}
+
+fn map_value(value: AvroValue, schema: &AvroSchema) -> Result<Field, KafkaError> {
+    Ok(match (value, schema) {
+        (AvroValue::Null, _) => Field::Null,
+        (AvroValue::Union(index, value), AvroSchema::Union(union)) => {
+            if non_null_variants(union.variants()).len() > 1 {
+                return map_json(*value);
+            }
+            let variant = union
+                .variants()
+                .get(index as usize)
+                .ok_or(SchemaDefinitionNotFound)?;
+            return map_value(*value, variant);
+        }
+        (AvroValue::Boolean(value), _) => Field::Boolean(value),
+        (AvroValue::Int(value), _) => Field::Int(value.into()),
+        (AvroValue::Long(value), _) => Field::Int(value),
+        (AvroValue::Float(value), _) => Field::Float(OrderedFloat(value.into())),
+        (AvroValue::Double(value), _) => Field::Float(OrderedFloat(value)),
+        (AvroValue::Bytes(value), _) | (AvroValue::Fixed(_, value), _) => Field::Binary(value),
+        (AvroValue::String(value), _) | (AvroValue::Enum(_, value), _) => Field::String(value),
+        (AvroValue::Uuid(value), _) => Field::String(value.to_string()),
+        (AvroValue::Decimal(value), AvroSchema::Decimal { scale, .. }) => {
+            let bytes = Vec::<u8>::try_from(&value).map_err(AvroError)?;
+            Field::Decimal(map_decimal(&bytes, *scale)?)
+        }
+        (AvroValue::Date(days), _) => Field::Date(
+            days.checked_add(UNIX_EPOCH_DAYS_FROM_CE)
+                .and_then(NaiveDate::from_num_days_from_ce_opt)
+                .ok_or(InvalidDateError)?,
+        ),
+        (AvroValue::TimestampMillis(millis), _) => {
+            map_timestamp(NaiveDateTime::from_timestamp_millis(millis))?
+        }
+        (AvroValue::TimestampMicros(micros), _) => {
+            map_timestamp(NaiveDateTime::from_timestamp_micros(micros))?
+        }
+        (AvroValue::TimeMillis(millis), _) => Field::Duration(DozerDuration(
+            std::time::Duration::from_millis(millis.try_into().map_err(|_| InvalidTimestampError)?),
+            TimeUnit::Milliseconds,
+        )),
+        (AvroValue::TimeMicros(micros), _) => Field::Duration(DozerDuration(
+            std::time::Duration::from_micros(micros.try_into().map_err(|_| InvalidTimestampError)?),
+            TimeUnit::Microseconds,
+        )),
+        (value @ (AvroValue::Array(_) | AvroValue::Map(_) | AvroValue::Record(_)), _) => {
+            return map_json(value)
+        }
+        (value, _) => return Err(TypeNotSupported(format!("{value:?}")).into()),
+    })
+}
+
+/// Avro decimals are the big-endian two's complement of the unscaled value.
+fn map_decimal(bytes: &[u8], scale: usize) -> Result<Decimal, KafkaSchemaError> {
+    if bytes.len() > 16 {
+        return Err(TypeNotSupported("decimal wider than 128 bits".to_string()));
+    }
+    let negative = bytes.first().map_or(false, |byte| byte & 0x80 != 0);
+    let mut unscaled = [if negative { 0xFF } else { 0 }; 16];
+    unscaled[16 - bytes.len()..].copy_from_slice(bytes);
+    Decimal::try_from_i128_with_scale(i128::from_be_bytes(unscaled), scale as u32)
+        .map_err(DecimalConvertError)
+}
+
+fn map_timestamp(timestamp: Option<NaiveDateTime>) -> Result<Field, KafkaSchemaError> {
+    let timestamp = timestamp.ok_or(InvalidTimestampError)?;
+    Ok(Field::Timestamp(DateTime::from_utc(timestamp, Utc.fix())))
+}
+
+fn map_json(value: AvroValue) -> Result<Field, KafkaError> {
+    let value = serde_json::Value::try_from(value).map_err(AvroError)?;
+    Ok(Field::Json(
+        serde_json_to_json_value(value).map_err(|e| InvalidJsonError(e.to_string()))?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Key",
+        "fields": [{"name": "id", "type": "long"}]
+    }"#;
+
+    const VALUE_SCHEMA_V1: &str = r#"{
+        "type": "record",
+        "name": "Value",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "price", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}}
+        ]
+    }"#;
+
+    const VALUE_SCHEMA_V2: &str = r#"{
+        "type": "record",
+        "name": "Value",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "price", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+            {"name": "name", "type": ["null", "string"], "default": null},
+            {"name": "created_at", "type": {"type": "long", "logicalType": "timestamp-millis"}, "default": 0}
+        ]
+    }"#;
+
+    #[test]
+    fn test_map_schema() {
+        let key_schema = AvroSchema::parse_str(KEY_SCHEMA).unwrap();
+        let value_schema = AvroSchema::parse_str(VALUE_SCHEMA_V2).unwrap();
+        let schema = map_schema(&value_schema, &key_schema).unwrap();
+
+        let fields = schema
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ, field.nullable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", FieldType::Int, false),
+                ("price", FieldType::Decimal, false),
+                ("name", FieldType::String, true),
+                ("created_at", FieldType::Timestamp, false),
+            ]
+        );
+        assert_eq!(schema.primary_index, vec![0]);
+    }
+
+    #[test]
+    fn test_map_record_written_with_older_schema() {
+        let value = AvroValue::Record(vec![
+            ("id".to_string(), AvroValue::Long(1)),
+            (
+                "price".to_string(),
+                AvroValue::Decimal(vec![0xFF, 0x85].into()),
+            ),
+        ]);
+        assert!(value.validate(&AvroSchema::parse_str(VALUE_SCHEMA_V1).unwrap()));
+
+        let schema = AvroSchema::parse_str(VALUE_SCHEMA_V2).unwrap();
+        assert_eq!(
+            map_record(value, &schema).unwrap(),
+            vec![
+                Field::Int(1),
+                Field::Decimal(Decimal::new(-123, 2)),
+                Field::Null,
+                Field::Timestamp(DateTime::from_utc(
+                    NaiveDateTime::from_timestamp_millis(0).unwrap(),
+                    Utc.fix()
+                )),
+            ]
+        );
+    }
+}
@@ -10,6 +10,9 @@ use rdkafka::util::Timeout;
 
 use tonic::async_trait;
 
+use crate::connectors::kafka::debezium::no_schema_registry::NoSchemaRegistry;
+use crate::connectors::kafka::debezium::schema_registry::SchemaRegistry;
+use crate::connectors::kafka::debezium::stream_consumer::DebeziumStreamConsumer;
 use crate::connectors::kafka::no_schema_registry_basic::NoSchemaRegistryBasic;
 
 use crate::connectors::kafka::schema_registry_basic::SchemaRegistryBasic;
@@ -31,8 +34,18 @@ impl KafkaConnector {
         &self,
         table_names: Option<&[String]>,
     ) -> Result<Vec<SourceSchema>, ConnectorError> {
+        if self.config.debezium.unwrap_or(false) {
+            return match &self.config.schema_registry_url {
+                Some(schema_registry_url) => {
+                    SchemaRegistry::get_schema(table_names, schema_registry_url.clone()).await
+                }
+                None => NoSchemaRegistry::get_schema(table_names, self.config.broker.clone()).await,
+            };
+        }
         if let Some(schema_registry_url) = &self.config.schema_registry_url {
             SchemaRegistryBasic::get_schema(table_names, schema_registry_url.clone()).await
+        } else if let Some(sample_size) = self.config.schema_sample_size {
+            NoSchemaRegistryBasic::infer_schema(table_names, &self.config.broker, sample_size)
         } else {
             NoSchemaRegistryBasic::get_schema(table_names)
         }
@@ -130,19 +143,17 @@ impl Connector for KafkaConnector {
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
     ) -> Result<(), ConnectorError> {
-        let broker = self.config.broker.to_owned();
-        run(broker, tables, ingestor, &self.config.schema_registry_url).await
+        run(&self.config, tables, ingestor).await
     }
 }
 
 async fn run(
-    broker: String,
+    config: &KafkaConfig,
     tables: Vec<TableInfo>,
     ingestor: &Ingestor,
-    schema_registry_url: &Option<String>,
 ) -> Result<(), ConnectorError> {
     let con: BaseConsumer = ClientConfig::new()
-        .set("bootstrap.servers", broker)
+        .set("bootstrap.servers", &config.broker)
         .set("group.id", "dozer")
         .set("enable.auto.commit", "true")
         .create()
@@ -152,8 +163,19 @@ async fn run(
     con.subscribe(topics.iter().as_slice())
         .map_err(KafkaConnectionError)?;
 
-    let consumer = StreamConsumerBasic::default();
+    if config.debezium.unwrap_or(false) {
+        return DebeziumStreamConsumer::default()
+            .run(con, ingestor, tables, &config.schema_registry_url)
+            .await;
+    }
+
+    let consumer = match config.schema_sample_size {
+        Some(sample_size) => {
+            StreamConsumerBasic::with_inferred_schemas(config.broker.clone(), sample_size)
+        }
+        None => StreamConsumerBasic::default(),
+    };
     consumer
-        .run(con, ingestor, tables, schema_registry_url)
+        .run(con, ingestor, tables, &config.schema_registry_url)
         .await
 }
@@ -0,0 +1,249 @@
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::connectors::kafka::avro::map_record;
+use crate::connectors::kafka::debezium::mapper::convert_value_to_schema;
+use crate::connectors::kafka::debezium::schema::map_schema;
+use crate::connectors::kafka::debezium::stream_consumer::{DebeziumMessage, DebeziumSchemaStruct};
+use crate::errors::KafkaError;
+use crate::errors::KafkaSchemaError::{self, FieldNotFound, SchemaDefinitionNotFound};
+
+/// Maps the `op` of a change event and its rows to the operation it describes, or `None` for events without one,
+/// like truncates.
+///
+/// Updates of tables that don't log old rows have no `before` row, and update the record with the key of the `after`
+/// row. A change of the key is sent as a delete and a create.
+pub fn map_operation(
+    op: &str,
+    before: Option<Vec<Field>>,
+    after: Option<Vec<Field>>,
+) -> Result<Option<Operation>, KafkaSchemaError> {
+    match (op, before, after) {
+        // Creates, and reads of the initial snapshot.
+        ("c" | "r", _, Some(new)) => Ok(Some(Operation::Insert {
+            new: Record::new(new),
+        })),
+        ("u", before, Some(new)) => Ok(Some(Operation::Update {
+            old: Record::new(before.unwrap_or_else(|| new.clone())),
+            new: Record::new(new),
+        })),
+        ("d", Some(old), _) => Ok(Some(Operation::Delete {
+            old: Record::new(old),
+        })),
+        ("c" | "r" | "u", _, None) => Err(FieldNotFound("after".to_string())),
+        ("d", None, _) => Err(FieldNotFound("before".to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// Maps a json change event to the operation it describes, with the schema the event embeds.
+pub fn map_json_event(
+    event: DebeziumMessage,
+    key_schema: &DebeziumSchemaStruct,
+) -> Result<Option<Operation>, KafkaSchemaError> {
+    let (schema, fields_map) = map_schema(&event.schema, key_schema)?;
+    let row = |value: Option<Value>| {
+        value
+            .filter(|value| !value.is_null())
+            .map(|value| convert_value_to_schema(value, &schema, &fields_map))
+            .transpose()
+    };
+    let op = event.payload.op.ok_or(FieldNotFound("op".to_string()))?;
+    map_operation(&op, row(event.payload.before)?, row(event.payload.after)?)
+}
+
+/// The schema of the rows of an Avro change event schema.
+///
+/// Debezium defines the row record in `before` and refers to it by name in `after`, so the first of them that
+/// defines it is used.
+pub fn avro_row_schema(envelope: &AvroSchema) -> Result<&AvroSchema, KafkaSchemaError> {
+    let AvroSchema::Record { fields, .. } = envelope else {
+        return Err(SchemaDefinitionNotFound);
+    };
+    fields
+        .iter()
+        .filter(|field| field.name == "before" || field.name == "after")
+        .find_map(|field| match &field.schema {
+            AvroSchema::Union(union) => union
+                .variants()
+                .iter()
+                .find(|variant| matches!(variant, AvroSchema::Record { .. })),
+            schema @ AvroSchema::Record { .. } => Some(schema),
+            _ => None,
+        })
+        .ok_or(SchemaDefinitionNotFound)
+}
+
+/// Maps an Avro change event to the operation it describes, with rows of `row_schema`.
+pub fn map_avro_event(
+    event: AvroValue,
+    row_schema: &AvroSchema,
+) -> Result<Option<Operation>, KafkaError> {
+    let AvroValue::Record(fields) = event else {
+        return Err(SchemaDefinitionNotFound.into());
+    };
+    let (mut op, mut before, mut after) = (None, None, None);
+    for (name, value) in fields {
+        match name.as_str() {
+            "op" => op = Some(value),
+            "before" => before = map_avro_row(value, row_schema)?,
+            "after" => after = map_avro_row(value, row_schema)?,
+            _ => (),
+        }
+    }
+    let op = match op {
+        Some(AvroValue::String(op) | AvroValue::Enum(_, op)) => op,
+        _ => return Err(FieldNotFound("op".to_string()).into()),
+    };
+    Ok(map_operation(&op, before, after)?)
+}
+
+fn map_avro_row(
+    value: AvroValue,
+    row_schema: &AvroSchema,
+) -> Result<Option<Vec<Field>>, KafkaError> {
+    match value {
+        AvroValue::Null => Ok(None),
+        AvroValue::Union(_, value) => map_avro_row(*value, row_schema),
+        value => map_record(value, row_schema).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json;
+
+    use super::*;
+
+    const ENVELOPE_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Envelope",
+        "fields": [
+            {"name": "before", "type": ["null", {
+                "type": "record",
+                "name": "Value",
+                "fields": [
+                    {"name": "id", "type": "long"},
+                    {"name": "name", "type": ["null", "string"], "default": null}
+                ]
+            }], "default": null},
+            {"name": "after", "type": ["null", "Value"], "default": null},
+            {"name": "op", "type": "string"},
+            {"name": "ts_ms", "type": ["null", "long"], "default": null}
+        ]
+    }"#;
+
+    fn avro_row(id: i64, name: &str) -> AvroValue {
+        AvroValue::Union(
+            1,
+            Box::new(AvroValue::Record(vec![
+                ("id".to_string(), AvroValue::Long(id)),
+                (
+                    "name".to_string(),
+                    AvroValue::Union(1, Box::new(AvroValue::String(name.to_string()))),
+                ),
+            ])),
+        )
+    }
+
+    fn avro_event(op: &str, before: Option<AvroValue>, after: Option<AvroValue>) -> AvroValue {
+        let null = AvroValue::Union(0, Box::new(AvroValue::Null));
+        AvroValue::Record(vec![
+            ("before".to_string(), before.unwrap_or_else(|| null.clone())),
+            ("after".to_string(), after.unwrap_or_else(|| null.clone())),
+            ("op".to_string(), AvroValue::String(op.to_string())),
+            ("ts_ms".to_string(), null),
+        ])
+    }
+
+    fn row(id: i64, name: &str) -> Record {
+        Record::new(vec![Field::Int(id), Field::String(name.to_string())])
+    }
+
+    #[test]
+    fn test_map_operation() {
+        let values = |id| Some(row(id, "a").values);
+        assert_eq!(
+            map_operation("r", None, values(1)).unwrap(),
+            Some(Operation::Insert { new: row(1, "a") })
+        );
+        assert_eq!(
+            map_operation("u", None, values(1)).unwrap(),
+            Some(Operation::Update {
+                old: row(1, "a"),
+                new: row(1, "a")
+            })
+        );
+        assert_eq!(
+            map_operation("d", values(1), None).unwrap(),
+            Some(Operation::Delete { old: row(1, "a") })
+        );
+        assert_eq!(map_operation("t", None, None).unwrap(), None);
+        assert_eq!(
+            map_operation("c", None, None),
+            Err(FieldNotFound("after".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_map_avro_event() {
+        let envelope = AvroSchema::parse_str(ENVELOPE_SCHEMA).unwrap();
+        let row_schema = avro_row_schema(&envelope).unwrap();
+
+        let event = avro_event("c", None, Some(avro_row(1, "a")));
+        assert!(event.validate(&envelope));
+        assert_eq!(
+            map_avro_event(event, row_schema).unwrap(),
+            Some(Operation::Insert { new: row(1, "a") })
+        );
+
+        let event = avro_event("u", Some(avro_row(1, "a")), Some(avro_row(1, "b")));
+        assert_eq!(
+            map_avro_event(event, row_schema).unwrap(),
+            Some(Operation::Update {
+                old: row(1, "a"),
+                new: row(1, "b")
+            })
+        );
+
+        let event = avro_event("d", Some(avro_row(1, "b")), None);
+        assert_eq!(
+            map_avro_event(event, row_schema).unwrap(),
+            Some(Operation::Delete { old: row(1, "b") })
+        );
+    }
+
+    #[test]
+    fn test_map_json_event() {
+        let event: DebeziumMessage = serde_json::from_str(
+            r#"{
+                "schema": {
+                    "type": "struct",
+                    "fields": [
+                        {"type": "struct", "optional": true, "field": "before", "fields": [
+                            {"type": "int32", "optional": false, "field": "id"},
+                            {"type": "string", "optional": true, "field": "name"}
+                        ]},
+                        {"type": "struct", "optional": true, "field": "after", "fields": [
+                            {"type": "int32", "optional": false, "field": "id"},
+                            {"type": "string", "optional": true, "field": "name"}
+                        ]},
+                        {"type": "string", "optional": false, "field": "op"}
+                    ]
+                },
+                "payload": {"before": {"id": 1, "name": "a"}, "after": null, "op": "d"}
+            }"#,
+        )
+        .unwrap();
+        let key_schema: DebeziumSchemaStruct = serde_json::from_str(
+            r#"{"type": "struct", "fields": [{"type": "int32", "optional": false, "field": "id"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            map_json_event(event, &key_schema).unwrap(),
+            Some(Operation::Delete { old: row(1, "a") })
+        );
+    }
+}
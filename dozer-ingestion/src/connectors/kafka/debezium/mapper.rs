@@ -317,24 +317,28 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "description".to_string(),
                     typ: FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "weight".to_string(),
                     typ: FieldType::Float,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![],
@@ -406,12 +410,14 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![],
@@ -1,3 +1,4 @@
+pub mod envelope;
 pub mod mapper;
 pub mod no_schema_registry;
 pub mod schema;
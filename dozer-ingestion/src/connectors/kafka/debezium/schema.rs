@@ -78,6 +78,7 @@ pub fn map_schema(
                                 typ,
                                 nullable: f.optional.map_or(false, |o| o),
                                 source: SourceDefinition::Dynamic,
+                                enum_values: vec![],
                             })
                         })
                         .collect(),
@@ -197,12 +198,14 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![0],
@@ -1,17 +1,21 @@
 #![allow(clippy::type_complexity)]
 
+use crate::connectors::kafka::avro;
+use crate::connectors::kafka::debezium::envelope::avro_row_schema;
 use crate::connectors::kafka::debezium::schema::map_type;
 use crate::connectors::kafka::debezium::stream_consumer::DebeziumSchemaStruct;
 use crate::connectors::{CdcType, SourceSchema};
-use crate::errors::KafkaError::{JsonDecodeError, SchemaRegistryFetchError};
+use crate::errors::KafkaError::{AvroError, JsonDecodeError, SchemaRegistryFetchError};
 use crate::errors::KafkaSchemaError::TypeNotSupported;
 use crate::errors::{ConnectorError, KafkaError, KafkaSchemaError};
+use apache_avro::Schema as AvroSchema;
 use dozer_types::serde_json;
 use dozer_types::serde_json::Value;
-use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+use dozer_types::types::FieldType;
 use schema_registry_converter::async_impl::schema_registry::SrSettings;
-use schema_registry_converter::schema_registry_common::SubjectNameStrategy;
-use std::collections::HashMap;
+use schema_registry_converter::schema_registry_common::{
+    RegisteredSchema, SchemaType, SubjectNameStrategy,
+};
 
 pub struct SchemaRegistry {}
 
@@ -60,20 +64,56 @@ impl SchemaRegistry {
         }
     }
 
+    /// Fetches the latest schema of the key or value of a topic.
+    pub async fn fetch(
+        sr_settings: &SrSettings,
+        table_name: &str,
+        is_key: bool,
+    ) -> Result<RegisteredSchema, KafkaError> {
+        schema_registry_converter::async_impl::schema_registry::get_schema_by_subject(
+            sr_settings,
+            &SubjectNameStrategy::TopicNameStrategy(table_name.to_string(), is_key),
+        )
+        .await
+        .map_err(SchemaRegistryFetchError)
+    }
+
     pub async fn fetch_struct(
         sr_settings: &SrSettings,
         table_name: &str,
         is_key: bool,
     ) -> Result<DebeziumSchemaStruct, KafkaError> {
-        let schema_result =
-            schema_registry_converter::async_impl::schema_registry::get_schema_by_subject(
-                sr_settings,
-                &SubjectNameStrategy::TopicNameStrategy(table_name.to_string(), is_key),
-            )
-            .await
-            .map_err(SchemaRegistryFetchError)?;
+        let schema_result = Self::fetch(sr_settings, table_name, is_key).await?;
+        Self::parse_struct(&schema_result)
+    }
+
+    pub fn parse_struct(schema: &RegisteredSchema) -> Result<DebeziumSchemaStruct, KafkaError> {
+        serde_json::from_str::<DebeziumSchemaStruct>(&schema.schema).map_err(JsonDecodeError)
+    }
+
+    /// Returns the schema of the rows of a topic of Avro change events, with the Avro schema to decode them with.
+    pub async fn get_single_schema(
+        sr_settings: &SrSettings,
+        table_name: &str,
+    ) -> Result<(SourceSchema, AvroSchema), ConnectorError> {
+        let key = Self::fetch(sr_settings, table_name, true).await?;
+        let value = Self::fetch(sr_settings, table_name, false).await?;
+        if !matches!(value.schema_type, SchemaType::Avro) {
+            return Err(KafkaError::KafkaSchemaError(TypeNotSupported(format!(
+                "{:?} change events",
+                value.schema_type
+            )))
+            .into());
+        }
 
-        serde_json::from_str::<DebeziumSchemaStruct>(&schema_result.schema).map_err(JsonDecodeError)
+        let key_schema = AvroSchema::parse_str(&key.schema).map_err(AvroError)?;
+        let envelope = AvroSchema::parse_str(&value.schema).map_err(AvroError)?;
+        let row_schema = avro_row_schema(&envelope)
+            .map_err(|e| ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e)))?
+            .clone();
+        let schema = avro::map_schema(&row_schema, &key_schema)
+            .map_err(|e| ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e)))?;
+        Ok((SourceSchema::new(schema, CdcType::FullChanges), row_schema))
     }
 
     pub async fn get_schema(
@@ -81,57 +121,11 @@ impl SchemaRegistry {
         schema_registry_url: String,
     ) -> Result<Vec<SourceSchema>, ConnectorError> {
         let sr_settings = SrSettings::new(schema_registry_url);
-        match table_names {
-            None => Ok(vec![]),
-            Some(tables) => match tables.get(0) {
-                None => Ok(vec![]),
-                Some(table) => {
-                    let key_result =
-                        SchemaRegistry::fetch_struct(&sr_settings, table, true).await?;
-                    let schema_result =
-                        SchemaRegistry::fetch_struct(&sr_settings, table, false).await?;
-
-                    let pk_fields = key_result.fields.map_or(vec![], |fields| {
-                        fields
-                            .iter()
-                            .map(|f| f.name.clone().map_or("".to_string(), |name| name))
-                            .collect()
-                    });
-
-                    let fields = schema_result.fields.map_or(vec![], |f| f);
-                    let mut pk_keys_indexes = vec![];
-                    let mut fields_schema_map: HashMap<String, &DebeziumSchemaStruct> =
-                        HashMap::new();
-
-                    let defined_fields: Result<Vec<FieldDefinition>, ConnectorError> = fields
-                        .iter()
-                        .enumerate()
-                        .map(|(idx, f)| {
-                            let (typ, nullable) = Self::map_typ(f).map_err(|e| {
-                                ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
-                            })?;
-                            let name = f.name.clone().unwrap();
-                            if pk_fields.contains(&name) {
-                                pk_keys_indexes.push(idx);
-                            }
-                            fields_schema_map.insert(name.clone(), f);
-                            Ok(FieldDefinition {
-                                name,
-                                typ,
-                                nullable,
-                                source: SourceDefinition::Dynamic,
-                            })
-                        })
-                        .collect();
-
-                    let schema = Schema {
-                        fields: defined_fields?,
-                        primary_index: pk_keys_indexes,
-                    };
-
-                    Ok(vec![SourceSchema::new(schema, CdcType::FullChanges)])
-                }
-            },
+        let mut schemas = vec![];
+        for table_name in table_names.unwrap_or_default() {
+            let (schema, _) = Self::get_single_schema(&sr_settings, table_name).await?;
+            schemas.push(schema);
         }
+        Ok(schemas)
     }
 }
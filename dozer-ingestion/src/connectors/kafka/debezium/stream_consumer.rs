@@ -1,16 +1,21 @@
-use crate::connectors::kafka::debezium::mapper::convert_value_to_schema;
-use crate::connectors::kafka::debezium::schema::map_schema;
+use crate::connectors::kafka::debezium::envelope::{map_avro_event, map_json_event};
+use crate::connectors::kafka::debezium::schema_registry::SchemaRegistry;
 use crate::connectors::kafka::stream_consumer::StreamConsumer;
-use crate::errors::KafkaError::{BytesConvertError, JsonDecodeError};
-use crate::errors::{ConnectorError, KafkaError, KafkaStreamError};
+use crate::errors::KafkaError::{
+    AvroDecodeError, BytesConvertError, JsonDecodeError, KafkaStreamError, TopicNotDefined,
+};
+use crate::errors::KafkaStreamError::PollingError;
+use crate::errors::{ConnectorError, KafkaError};
 use crate::ingestion::Ingestor;
 use dozer_types::ingestion_types::IngestionMessage;
+use std::collections::HashMap;
 
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::serde_json;
 use dozer_types::serde_json::Value;
-use dozer_types::types::{Operation, Record};
 use rdkafka::consumer::BaseConsumer;
+use schema_registry_converter::async_impl::avro::AvroDecoder;
+use schema_registry_converter::async_impl::schema_registry::SrSettings;
 
 use crate::connectors::TableInfo;
 use rdkafka::Message;
@@ -76,116 +81,79 @@ pub struct DebeziumMessage {
     pub payload: DebeziumPayload,
 }
 
+/// Ingests Debezium change events as the operations they describe. Events are json with embedded schemas, or Avro
+/// with schemas from the schema registry.
 #[derive(Default)]
 pub struct DebeziumStreamConsumer {}
 
-impl DebeziumStreamConsumer {}
-
 #[async_trait]
 impl StreamConsumer for DebeziumStreamConsumer {
     async fn run(
         &self,
         con: BaseConsumer,
         ingestor: &Ingestor,
-        _tables: Vec<TableInfo>,
-        _schema_registry_url: &Option<String>,
+        tables: Vec<TableInfo>,
+        schema_registry_url: &Option<String>,
     ) -> Result<(), ConnectorError> {
-        loop {
-            let m = con
-                .poll(None)
-                .unwrap()
-                .map_err(|e| KafkaError::KafkaStreamError(KafkaStreamError::PollingError(e)))?;
-
-            if let (Some(message), Some(key)) = (m.payload(), m.key()) {
-                let mut value_struct: DebeziumMessage =
-                    serde_json::from_str(std::str::from_utf8(message).map_err(BytesConvertError)?)
-                        .map_err(JsonDecodeError)?;
-                let key_struct: DebeziumMessage =
-                    serde_json::from_str(std::str::from_utf8(key).map_err(BytesConvertError)?)
-                        .map_err(JsonDecodeError)?;
-
-                let (schema, fields_map) = map_schema(&value_struct.schema, &key_struct.schema)
-                    .map_err(|e| ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e)))?;
+        let sr_settings = schema_registry_url
+            .as_ref()
+            .map(|url| SrSettings::new(url.clone()));
+        // The table index and, with a schema registry, the Avro schema of the rows of each topic.
+        let mut topics = HashMap::new();
+        for (table_index, table) in tables.into_iter().enumerate() {
+            let row_schema = match &sr_settings {
+                Some(sr_settings) => Some(
+                    SchemaRegistry::get_single_schema(sr_settings, &table.name)
+                        .await?
+                        .1,
+                ),
+                None => None,
+            };
+            topics.insert(table.name, (table_index, row_schema));
+        }
+        let avro_decoder = sr_settings.map(AvroDecoder::new);
 
-                // When update happens before is null.
-                // If PK value changes, then debezium creates two events - delete and insert
-                if value_struct.payload.before.is_none()
-                    && value_struct.payload.op == Some("u".to_string())
-                {
-                    value_struct.payload.before = value_struct.payload.after.clone();
+        let mut seq_no = 0;
+        loop {
+            // Detached, as borrowed messages can't be held across awaits.
+            let Some(result) = con.poll(None).map(|result| result.map(|m| m.detach())) else {
+                continue;
+            };
+            let m = result.map_err(|e| KafkaStreamError(PollingError(e)))?;
+            let (table_index, row_schema) = topics
+                .get(m.topic())
+                .ok_or(ConnectorError::KafkaError(TopicNotDefined))?;
+            // Deletes are followed by a tombstone without payload, for log compaction.
+            let (Some(message), Some(key)) = (m.payload(), m.key()) else {
+                continue;
+            };
+
+            let operation = match (row_schema, &avro_decoder) {
+                (Some(row_schema), Some(avro_decoder)) => {
+                    let decoded = avro_decoder
+                        .decode(Some(message))
+                        .await
+                        .map_err(AvroDecodeError)?;
+                    map_avro_event(decoded.value, row_schema)?
                 }
-
-                match (value_struct.payload.after, value_struct.payload.before) {
-                    (Some(new_payload), Some(old_payload)) => {
-                        let new = convert_value_to_schema(new_payload, &schema, &fields_map)
-                            .map_err(|e| {
-                                ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
-                            })?;
-                        let old = convert_value_to_schema(old_payload, &schema, &fields_map)
-                            .map_err(|e| {
-                                ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
-                            })?;
-
-                        ingestor
-                            .handle_message(IngestionMessage::new_op(
-                                0,
-                                0,
-                                0,
-                                Operation::Update {
-                                    old: Record {
-                                        values: old,
-                                        lifetime: None,
-                                    },
-                                    new: Record {
-                                        values: new,
-                                        lifetime: None,
-                                    },
-                                },
-                            ))
-                            .map_err(ConnectorError::IngestorError)?;
-                    }
-                    (None, Some(old_payload)) => {
-                        let old = convert_value_to_schema(old_payload, &schema, &fields_map)
-                            .map_err(|e| {
-                                ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
-                            })?;
-
-                        ingestor
-                            .handle_message(IngestionMessage::new_op(
-                                0,
-                                0,
-                                0,
-                                Operation::Delete {
-                                    old: Record {
-                                        values: old,
-                                        lifetime: None,
-                                    },
-                                },
-                            ))
-                            .map_err(ConnectorError::IngestorError)?;
-                    }
-                    (Some(new_payload), None) => {
-                        let new = convert_value_to_schema(new_payload, &schema, &fields_map)
-                            .map_err(|e| {
-                                ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
-                            })?;
-
-                        ingestor
-                            .handle_message(IngestionMessage::new_op(
-                                0,
-                                0,
-                                0,
-                                Operation::Insert {
-                                    new: Record {
-                                        values: new,
-                                        lifetime: None,
-                                    },
-                                },
-                            ))
-                            .map_err(ConnectorError::IngestorError)?;
-                    }
-                    (None, None) => {}
+                _ => {
+                    let event: DebeziumMessage = serde_json::from_str(
+                        std::str::from_utf8(message).map_err(BytesConvertError)?,
+                    )
+                    .map_err(JsonDecodeError)?;
+                    let key: DebeziumMessage =
+                        serde_json::from_str(std::str::from_utf8(key).map_err(BytesConvertError)?)
+                            .map_err(JsonDecodeError)?;
+                    map_json_event(event, &key.schema)
+                        .map_err(|e| ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e)))?
                 }
+            };
+
+            if let Some(operation) = operation {
+                seq_no += 1;
+                ingestor
+                    .handle_message(IngestionMessage::new_op(0, seq_no, *table_index, operation))
+                    .map_err(ConnectorError::IngestorError)?;
             }
         }
     }
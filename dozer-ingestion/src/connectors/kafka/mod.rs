@@ -1,3 +1,4 @@
+pub mod avro;
 pub mod connector;
 pub mod debezium;
 pub mod no_schema_registry_basic;
@@ -1,28 +1,47 @@
 #![allow(clippy::type_complexity)]
 
+use std::time::Duration;
+
+use crate::connectors::schema_inference::SchemaInferrer;
 use crate::connectors::{CdcType, SourceSchema};
 
 use crate::errors::ConnectorError;
+use crate::errors::KafkaError::{JsonDecodeError, KafkaConnectionError, KafkaStreamError};
+use crate::errors::KafkaStreamError::PollingError;
 
+use dozer_types::serde_json;
+use dozer_types::serde_json::Value;
 use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::util::Timeout;
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
+
+/// How long to wait for the next message when sampling, after which the end of the topic is assumed.
+const SAMPLE_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct NoSchemaRegistryBasic {}
 
 impl NoSchemaRegistryBasic {
+    fn key_field() -> FieldDefinition {
+        FieldDefinition {
+            name: "key".to_string(),
+            typ: FieldType::String,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+            enum_values: vec![],
+        }
+    }
+
     pub fn get_single_schema() -> SourceSchema {
         let schema = Schema {
             fields: vec![
-                FieldDefinition {
-                    name: "key".to_string(),
-                    typ: FieldType::String,
-                    nullable: false,
-                    source: SourceDefinition::Dynamic,
-                },
+                Self::key_field(),
                 FieldDefinition {
                     name: "message".to_string(),
                     typ: FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 },
             ],
             primary_index: vec![0],
@@ -42,4 +61,67 @@ impl NoSchemaRegistryBasic {
 
         Ok(schemas)
     }
+
+    /// Infers the schema of the json messages of a topic from its first `sample_size` messages. The key is the first
+    /// column, followed by the fields of the messages.
+    pub fn infer_single_schema(
+        broker: &str,
+        topic: &str,
+        sample_size: u32,
+    ) -> Result<SourceSchema, ConnectorError> {
+        // The partitions are read from the beginning without committing offsets, so sampling doesn't move the
+        // consumer group and gives the same schema every time.
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", broker)
+            .set("group.id", "dozer-schema-inference")
+            .set("enable.auto.commit", "false")
+            .create()
+            .map_err(KafkaConnectionError)?;
+        let metadata = consumer
+            .fetch_metadata(Some(topic), Timeout::After(Duration::from_secs(60)))
+            .map_err(KafkaConnectionError)?;
+        let mut partitions = TopicPartitionList::new();
+        for partition in metadata
+            .topics()
+            .iter()
+            .flat_map(|topic| topic.partitions())
+        {
+            partitions
+                .add_partition_offset(topic, partition.id(), Offset::Beginning)
+                .map_err(KafkaConnectionError)?;
+        }
+        consumer.assign(&partitions).map_err(KafkaConnectionError)?;
+
+        let mut inferrer = SchemaInferrer::new();
+        while inferrer.records() < sample_size as usize {
+            let Some(result) = consumer.poll(SAMPLE_TIMEOUT) else {
+                break;
+            };
+            let message = result.map_err(|e| KafkaStreamError(PollingError(e)))?;
+            if let Some(payload) = message.payload() {
+                let value: Value = serde_json::from_slice(payload).map_err(JsonDecodeError)?;
+                inferrer.sample(&value)?;
+            }
+        }
+
+        let mut fields = vec![Self::key_field()];
+        fields.extend(inferrer.fields()?);
+        let schema = Schema {
+            fields,
+            primary_index: vec![0],
+        };
+        Ok(SourceSchema::new(schema, CdcType::FullChanges))
+    }
+
+    pub fn infer_schema(
+        table_names: Option<&[String]>,
+        broker: &str,
+        sample_size: u32,
+    ) -> Result<Vec<SourceSchema>, ConnectorError> {
+        table_names
+            .unwrap_or_default()
+            .iter()
+            .map(|topic| Self::infer_single_schema(broker, topic, sample_size))
+            .collect()
+    }
 }
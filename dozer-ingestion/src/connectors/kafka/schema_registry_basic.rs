@@ -3,24 +3,51 @@
 use crate::connectors::kafka::debezium::stream_consumer::DebeziumSchemaStruct;
 use crate::connectors::{CdcType, SourceSchema};
 
+use crate::errors::KafkaError::AvroError;
 use crate::errors::{ConnectorError, KafkaError};
 
 use dozer_types::types::{FieldDefinition, Schema, SourceDefinition};
 
+use crate::connectors::kafka::avro;
 use crate::connectors::kafka::debezium::schema_registry::SchemaRegistry;
+use apache_avro::Schema as AvroSchema;
 use schema_registry_converter::async_impl::schema_registry::SrSettings;
+use schema_registry_converter::schema_registry_common::SchemaType;
 use std::collections::HashMap;
 
 pub struct SchemaRegistryBasic {}
 
 impl SchemaRegistryBasic {
+    /// Returns the schema of a topic with the schemas of its fields to decode json messages, or, if the topic is
+    /// registered with Avro schemas, with the value schema to decode Avro messages with.
     pub async fn get_single_schema(
         table_name: &str,
         schema_registry_url: &str,
-    ) -> Result<(SourceSchema, HashMap<String, DebeziumSchemaStruct>), ConnectorError> {
+    ) -> Result<
+        (
+            SourceSchema,
+            HashMap<String, DebeziumSchemaStruct>,
+            Option<AvroSchema>,
+        ),
+        ConnectorError,
+    > {
         let sr_settings = SrSettings::new(schema_registry_url.to_string());
-        let key_result = SchemaRegistry::fetch_struct(&sr_settings, table_name, true).await?;
-        let schema_result = SchemaRegistry::fetch_struct(&sr_settings, table_name, false).await?;
+        let key = SchemaRegistry::fetch(&sr_settings, table_name, true).await?;
+        let value = SchemaRegistry::fetch(&sr_settings, table_name, false).await?;
+        if let (SchemaType::Avro, SchemaType::Avro) = (&key.schema_type, &value.schema_type) {
+            let key_schema = AvroSchema::parse_str(&key.schema).map_err(AvroError)?;
+            let value_schema = AvroSchema::parse_str(&value.schema).map_err(AvroError)?;
+            let schema = avro::map_schema(&value_schema, &key_schema)
+                .map_err(|e| ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e)))?;
+            return Ok((
+                SourceSchema::new(schema, CdcType::FullChanges),
+                HashMap::new(),
+                Some(value_schema),
+            ));
+        }
+
+        let key_result = SchemaRegistry::parse_struct(&key)?;
+        let schema_result = SchemaRegistry::parse_struct(&value)?;
 
         let pk_fields = key_result.fields.map_or(vec![], |fields| {
             fields
@@ -50,6 +77,7 @@ impl SchemaRegistryBasic {
                     typ,
                     nullable,
                     source: SourceDefinition::Dynamic,
+                    enum_values: vec![],
                 })
             })
             .collect();
@@ -62,6 +90,7 @@ impl SchemaRegistryBasic {
         Ok((
             SourceSchema::new(schema, CdcType::FullChanges),
             fields_schema_map,
+            None,
         ))
     }
 
@@ -72,7 +101,8 @@ impl SchemaRegistryBasic {
         let mut schemas = vec![];
         if let Some(tables) = table_names {
             for table_name in tables.iter() {
-                let (schema, _) = Self::get_single_schema(table_name, &schema_registry_url).await?;
+                let (schema, _, _) =
+                    Self::get_single_schema(table_name, &schema_registry_url).await?;
                 schemas.push(schema);
             }
         }
@@ -1,9 +1,11 @@
+use crate::connectors::kafka::avro::map_record;
 use crate::connectors::kafka::debezium::mapper::convert_value_to_schema;
 use std::collections::HashMap;
 
 use crate::connectors::kafka::stream_consumer::StreamConsumer;
+use crate::connectors::schema_inference::record_values;
 use crate::errors::KafkaError::{
-    BytesConvertError, JsonDecodeError, KafkaStreamError, TopicNotDefined,
+    AvroDecodeError, BytesConvertError, JsonDecodeError, KafkaStreamError, TopicNotDefined,
 };
 use crate::errors::{ConnectorError, KafkaError};
 use crate::ingestion::Ingestor;
@@ -14,6 +16,8 @@ use dozer_types::serde_json;
 use dozer_types::serde_json::Value;
 use dozer_types::types::{Field, Operation, Record};
 use rdkafka::consumer::BaseConsumer;
+use schema_registry_converter::async_impl::avro::AvroDecoder;
+use schema_registry_converter::async_impl::schema_registry::SrSettings;
 
 use crate::connectors::kafka::no_schema_registry_basic::NoSchemaRegistryBasic;
 use crate::connectors::kafka::schema_registry_basic::SchemaRegistryBasic;
@@ -77,9 +81,18 @@ pub struct Payload {
 }
 
 #[derive(Default)]
-pub struct StreamConsumerBasic {}
+pub struct StreamConsumerBasic {
+    /// The broker and sample size to infer the schema of json messages from, without a schema registry.
+    inferred_schemas: Option<(String, u32)>,
+}
 
-impl StreamConsumerBasic {}
+impl StreamConsumerBasic {
+    pub fn with_inferred_schemas(broker: String, sample_size: u32) -> Self {
+        Self {
+            inferred_schemas: Some((broker, sample_size)),
+        }
+    }
+}
 
 #[async_trait]
 impl StreamConsumer for StreamConsumerBasic {
@@ -94,70 +107,100 @@ impl StreamConsumer for StreamConsumerBasic {
         for (table_index, table) in tables.into_iter().enumerate() {
             let schema = if let Some(url) = schema_registry_url {
                 SchemaRegistryBasic::get_single_schema(&table.name, url).await?
+            } else if let Some((broker, sample_size)) = &self.inferred_schemas {
+                (
+                    NoSchemaRegistryBasic::infer_single_schema(broker, &table.name, *sample_size)?,
+                    HashMap::new(),
+                    None,
+                )
             } else {
-                (NoSchemaRegistryBasic::get_single_schema(), HashMap::new())
+                (
+                    NoSchemaRegistryBasic::get_single_schema(),
+                    HashMap::new(),
+                    None,
+                )
             };
 
             schemas.insert(table.name.clone(), (table_index, schema));
         }
+        // Caches the schemas Avro messages were written with, by the id they're prefixed with.
+        let avro_decoder = schema_registry_url
+            .as_ref()
+            .map(|url| AvroDecoder::new(SrSettings::new(url.clone())));
 
         let mut counter = 0;
         loop {
-            if let Some(result) = con.poll(None) {
-                let m = result.map_err(|e| KafkaStreamError(PollingError(e)))?;
-                match schemas.get(m.topic()) {
-                    None => return Err(ConnectorError::KafkaError(TopicNotDefined)),
-                    Some((table_index, (schema, fields_map))) => {
-                        if let (Some(message), Some(key)) = (m.payload(), m.key()) {
-                            let new = match schema_registry_url {
-                                None => {
-                                    let value =
-                                        std::str::from_utf8(message).map_err(BytesConvertError)?;
-                                    let key =
-                                        std::str::from_utf8(key).map_err(BytesConvertError)?;
-
+            // Detached, as borrowed messages can't be held across awaits.
+            let Some(result) = con.poll(None).map(|result| result.map(|m| m.detach())) else {
+                continue;
+            };
+            let m = result.map_err(|e| KafkaStreamError(PollingError(e)))?;
+            match schemas.get(m.topic()) {
+                None => return Err(ConnectorError::KafkaError(TopicNotDefined)),
+                Some((table_index, (schema, fields_map, avro_schema))) => {
+                    if let (Some(message), Some(key)) = (m.payload(), m.key()) {
+                        let new = match (schema_registry_url, avro_schema, &avro_decoder) {
+                            (None, _, _) => {
+                                let value =
+                                    std::str::from_utf8(message).map_err(BytesConvertError)?;
+                                let key = std::str::from_utf8(key).map_err(BytesConvertError)?;
+
+                                if self.inferred_schemas.is_some() {
+                                    let value: Value =
+                                        serde_json::from_str(value).map_err(JsonDecodeError)?;
+                                    let mut values = vec![Field::String(key.to_string())];
+                                    values
+                                        .extend(record_values(&schema.schema.fields[1..], &value)?);
+                                    values
+                                } else {
                                     vec![
                                         Field::String(key.to_string()),
                                         Field::String(value.to_string()),
                                     ]
                                 }
-                                Some(_) => {
-                                    let value_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(message).map_err(BytesConvertError)?,
-                                    )
-                                    .map_err(JsonDecodeError)?;
-                                    let _key_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(key).map_err(BytesConvertError)?,
-                                    )
-                                    .map_err(JsonDecodeError)?;
-
-                                    convert_value_to_schema(
-                                        value_struct,
-                                        &schema.schema,
-                                        fields_map,
-                                    )
+                            }
+                            // Avro messages start with a zero magic byte, which json messages can't.
+                            (Some(_), Some(avro_schema), Some(avro_decoder))
+                                if message.first() == Some(&0) =>
+                            {
+                                let decoded = avro_decoder
+                                    .decode(Some(message))
+                                    .await
+                                    .map_err(AvroDecodeError)?;
+                                map_record(decoded.value, avro_schema)?
+                            }
+                            (Some(_), _, _) => {
+                                let value_struct: Value = serde_json::from_str(
+                                    std::str::from_utf8(message).map_err(BytesConvertError)?,
+                                )
+                                .map_err(JsonDecodeError)?;
+                                let _key_struct: Value = serde_json::from_str(
+                                    std::str::from_utf8(key).map_err(BytesConvertError)?,
+                                )
+                                .map_err(JsonDecodeError)?;
+
+                                convert_value_to_schema(value_struct, &schema.schema, fields_map)
                                     .map_err(|e| {
                                         ConnectorError::KafkaError(KafkaError::KafkaSchemaError(e))
                                     })?
-                                }
-                            };
-
-                            ingestor
-                                .handle_message(IngestionMessage::new_op(
-                                    0,
-                                    counter,
-                                    *table_index,
-                                    Operation::Insert {
-                                        new: Record {
-                                            values: new,
-                                            lifetime: None,
-                                        },
+                            }
+                        };
+
+                        ingestor
+                            .handle_message(IngestionMessage::new_op(
+                                0,
+                                counter,
+                                *table_index,
+                                Operation::Insert {
+                                    new: Record {
+                                        values: new,
+                                        lifetime: None,
                                     },
-                                ))
-                                .map_err(ConnectorError::IngestorError)?;
+                                },
+                            ))
+                            .map_err(ConnectorError::IngestorError)?;
 
-                            counter += 1;
-                        }
+                        counter += 1;
                     }
                 }
             }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::connectors::checkpoint_file;
+use crate::connectors::shards::ShardPosition;
+use crate::errors::KinesisError;
+
+/// How far each shard of each stream was read, saved in a file so a restarted connector resumes after it.
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+    path: Option<PathBuf>,
+    /// Stream name to shard id to position.
+    positions: HashMap<String, HashMap<String, ShardPosition>>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path`, or an empty one that isn't saved if there's no path.
+    pub fn open(path: Option<PathBuf>) -> Result<Self, KinesisError> {
+        let positions = checkpoint_file::read(path.as_deref())?;
+        Ok(Self { path, positions })
+    }
+
+    pub fn get(&self, stream: &str, shard_id: &str) -> Option<&ShardPosition> {
+        self.positions.get(stream)?.get(shard_id)
+    }
+
+    pub fn is_finished(&self, stream: &str, shard_id: &str) -> bool {
+        self.get(stream, shard_id) == Some(&ShardPosition::Finished)
+    }
+
+    pub fn set(&mut self, stream: &str, shard_id: &str, position: ShardPosition) {
+        self.positions
+            .entry(stream.to_string())
+            .or_default()
+            .insert(shard_id.to_string(), position);
+    }
+
+    /// Forgets the shards of `stream` that aren't in `shard_ids`, which expired with the stream's retention period.
+    pub fn retain_shards(&mut self, stream: &str, shard_ids: &[&str]) {
+        if let Some(positions) = self.positions.get_mut(stream) {
+            positions.retain(|shard_id, _| shard_ids.contains(&shard_id.as_str()));
+        }
+    }
+
+    pub fn save(&self) -> Result<(), KinesisError> {
+        Ok(checkpoint_file::write(
+            self.path.as_deref(),
+            &self.positions,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_checkpoint() {
+        let dir = TempDir::new("kinesis_checkpoint").unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = Checkpoint::open(Some(path.clone())).unwrap();
+        assert_eq!(checkpoint.get("films", "shardId-0"), None);
+        checkpoint.set(
+            "films",
+            "shardId-0",
+            ShardPosition::SequenceNumber("42".to_string()),
+        );
+        checkpoint.set("films", "shardId-1", ShardPosition::Finished);
+        checkpoint.set("films", "shardId-2", ShardPosition::Finished);
+        checkpoint.retain_shards("films", &["shardId-0", "shardId-1"]);
+        checkpoint.save().unwrap();
+
+        // The checkpoint is kept across restarts.
+        let checkpoint = Checkpoint::open(Some(path)).unwrap();
+        assert_eq!(
+            checkpoint.get("films", "shardId-0"),
+            Some(&ShardPosition::SequenceNumber("42".to_string()))
+        );
+        assert!(checkpoint.is_finished("films", "shardId-1"));
+        assert_eq!(checkpoint.get("films", "shardId-2"), None);
+    }
+}
@@ -0,0 +1,360 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use aws_sdk_kinesis::config::Region;
+use aws_sdk_kinesis::types::ShardIteratorType;
+use aws_sdk_kinesis::Client;
+use dozer_types::ingestion_types::{IngestionMessage, KinesisConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use tonic::async_trait;
+
+use super::checkpoint::Checkpoint;
+use super::schema::{map_record, stream_schema, COLUMNS};
+use crate::connectors::shards::{readable_shards, ShardInfo, ShardPosition};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, KinesisError};
+use crate::ingestion::Ingestor;
+
+/// Reads the records of Kinesis streams, following their shards through resharding.
+#[derive(Debug)]
+pub struct KinesisConnector {
+    name: String,
+    config: KinesisConfig,
+}
+
+/// A shard being read, and the iterator its next records are read with.
+#[derive(Debug)]
+struct ShardReader {
+    table_index: usize,
+    shard_id: String,
+    iterator: String,
+}
+
+impl KinesisConnector {
+    pub fn new(name: String, config: KinesisConfig) -> Self {
+        Self { name, config }
+    }
+
+    async fn client(&self) -> Client {
+        let mut loader = aws_config::from_env();
+        if let Some(region) = &self.config.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+        let mut builder = aws_sdk_kinesis::config::Builder::from(&sdk_config);
+        if let Some(endpoint_url) = &self.config.endpoint_url {
+            builder = builder.endpoint_url(endpoint_url);
+        }
+        Client::from_conf(builder.build())
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let client = self.client().await;
+        let projections = tables
+            .iter()
+            .map(|table| stream_schema(&table.column_names).map(|(_, projection)| projection))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut checkpoint =
+            Checkpoint::open(self.config.checkpoint_path.as_ref().map(PathBuf::from))?;
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        let refresh_interval = Duration::from_millis(self.config.shard_refresh_interval_ms);
+
+        let mut readers = vec![];
+        let mut refreshed_at: Option<Instant> = None;
+        let mut seq_no = 0;
+        loop {
+            if refreshed_at.map_or(true, |at| at.elapsed() >= refresh_interval) {
+                for (table_index, table) in tables.iter().enumerate() {
+                    self.refresh_shards(
+                        &client,
+                        table_index,
+                        &table.name,
+                        &mut checkpoint,
+                        &mut readers,
+                    )
+                    .await?;
+                }
+                refreshed_at = Some(Instant::now());
+            }
+
+            for reader in &mut readers {
+                let stream = &tables[reader.table_index].name;
+                let output = match client
+                    .get_records()
+                    .shard_iterator(&reader.iterator)
+                    .send()
+                    .await
+                {
+                    Ok(output) => output,
+                    Err(e) => match e.as_service_error() {
+                        // Iterators expire five minutes after they are returned.
+                        Some(service_error) if service_error.is_expired_iterator_exception() => {
+                            reader.iterator = shard_iterator(
+                                &client,
+                                stream,
+                                &reader.shard_id,
+                                checkpoint.get(stream, &reader.shard_id),
+                            )
+                            .await?;
+                            continue;
+                        }
+                        // Retried at the next poll.
+                        Some(service_error)
+                            if service_error.is_provisioned_throughput_exceeded_exception() =>
+                        {
+                            continue
+                        }
+                        _ => return Err(request_error(e).into()),
+                    },
+                };
+
+                for record in output.records().unwrap_or_default() {
+                    let values =
+                        map_record(&reader.shard_id, record, &projections[reader.table_index])?;
+                    seq_no += 1;
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(
+                            0,
+                            seq_no,
+                            reader.table_index,
+                            Operation::Insert {
+                                new: Record::new(values),
+                            },
+                        ))
+                        .map_err(ConnectorError::IngestorError)?;
+                    if let Some(sequence_number) = record.sequence_number() {
+                        checkpoint.set(
+                            stream,
+                            &reader.shard_id,
+                            ShardPosition::SequenceNumber(sequence_number.to_string()),
+                        );
+                    }
+                }
+
+                match output.next_shard_iterator() {
+                    Some(iterator) => reader.iterator = iterator.to_string(),
+                    None => {
+                        // The shard was closed by resharding, so its children can be read now.
+                        info!(
+                            "[{}] Finished shard {} of stream {}",
+                            self.name, reader.shard_id, stream
+                        );
+                        checkpoint.set(stream, &reader.shard_id, ShardPosition::Finished);
+                        refreshed_at = None;
+                    }
+                }
+            }
+            readers.retain(|reader| {
+                !checkpoint.is_finished(&tables[reader.table_index].name, &reader.shard_id)
+            });
+            checkpoint.save()?;
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Starts reading the shards of `stream` that became readable, and forgets the expired ones.
+    async fn refresh_shards(
+        &self,
+        client: &Client,
+        table_index: usize,
+        stream: &str,
+        checkpoint: &mut Checkpoint,
+        readers: &mut Vec<ShardReader>,
+    ) -> Result<(), ConnectorError> {
+        let shards = list_shards(client, stream).await?;
+        let shard_ids = shards
+            .iter()
+            .map(|shard| shard.id.as_str())
+            .collect::<Vec<_>>();
+        checkpoint.retain_shards(stream, &shard_ids);
+
+        for shard in readable_shards(&shards, |id| checkpoint.is_finished(stream, id)) {
+            if readers
+                .iter()
+                .any(|reader| reader.table_index == table_index && reader.shard_id == shard.id)
+            {
+                continue;
+            }
+            let iterator =
+                shard_iterator(client, stream, &shard.id, checkpoint.get(stream, &shard.id))
+                    .await?;
+            info!(
+                "[{}] Reading shard {} of stream {}",
+                self.name, shard.id, stream
+            );
+            readers.push(ShardReader {
+                table_index,
+                shard_id: shard.id.clone(),
+                iterator,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Connector for KinesisConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        COLUMNS
+            .iter()
+            .map(|(name, typ, _)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        self.client()
+            .await
+            .list_streams()
+            .limit(1)
+            .send()
+            .await
+            .map_err(request_error)?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let client = self.client().await;
+        let mut streams: Vec<String> = vec![];
+        loop {
+            let output = client
+                .list_streams()
+                .set_exclusive_start_stream_name(streams.last().cloned())
+                .send()
+                .await
+                .map_err(request_error)?;
+            streams.extend(output.stream_names().unwrap_or_default().iter().cloned());
+            if !output.has_more_streams().unwrap_or_default() {
+                break;
+            }
+        }
+        Ok(streams
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let client = self.client().await;
+        for table in tables {
+            let not_found =
+                || ConnectorError::TableNotFound(table_name(table.schema.as_deref(), &table.name));
+            if table.schema.is_some() {
+                return Err(not_found());
+            }
+            if let Err(e) = client
+                .describe_stream_summary()
+                .stream_name(&table.name)
+                .send()
+                .await
+            {
+                return Err(match e.as_service_error() {
+                    Some(service_error) if service_error.is_resource_not_found_exception() => {
+                        not_found()
+                    }
+                    _ => request_error(e).into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        Ok(tables
+            .into_iter()
+            .map(|table| TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: COLUMNS
+                    .iter()
+                    .map(|(name, _, _)| name.to_string())
+                    .collect(),
+            })
+            .collect())
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let (schema, _) = stream_schema(&table_info.column_names)?;
+                Ok(SourceSchema::new(schema, CdcType::Nothing))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+async fn list_shards(client: &Client, stream: &str) -> Result<Vec<ShardInfo>, KinesisError> {
+    let mut shards = vec![];
+    let mut next_token = None;
+    loop {
+        // The stream name can't be sent with a token of the next page.
+        let request = match next_token {
+            Some(token) => client.list_shards().next_token(token),
+            None => client.list_shards().stream_name(stream),
+        };
+        let output = request.send().await.map_err(request_error)?;
+        shards.extend(output.shards().unwrap_or_default().iter().map(shard_info));
+        match output.next_token() {
+            Some(token) => next_token = Some(token.to_string()),
+            None => return Ok(shards),
+        }
+    }
+}
+
+fn shard_info(shard: &aws_sdk_kinesis::types::Shard) -> ShardInfo {
+    ShardInfo {
+        id: shard.shard_id().unwrap_or_default().to_string(),
+        parent_ids: shard
+            .parent_shard_id()
+            .into_iter()
+            .chain(shard.adjacent_parent_shard_id())
+            .map(ToString::to_string)
+            .collect(),
+    }
+}
+
+/// Returns an iterator reading a shard after its checkpointed sequence number, or from its oldest record.
+async fn shard_iterator(
+    client: &Client,
+    stream: &str,
+    shard_id: &str,
+    position: Option<&ShardPosition>,
+) -> Result<String, KinesisError> {
+    let request = client
+        .get_shard_iterator()
+        .stream_name(stream)
+        .shard_id(shard_id);
+    let request = match position {
+        Some(ShardPosition::SequenceNumber(sequence_number)) => request
+            .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+            .starting_sequence_number(sequence_number),
+        _ => request.shard_iterator_type(ShardIteratorType::TrimHorizon),
+    };
+    let output = request.send().await.map_err(request_error)?;
+    Ok(output.shard_iterator().unwrap_or_default().to_string())
+}
+
+fn request_error(e: impl std::error::Error + Send + Sync + 'static) -> KinesisError {
+    KinesisError::Request(Box::new(e))
+}
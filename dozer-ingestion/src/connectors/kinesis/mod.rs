@@ -0,0 +1,5 @@
+mod checkpoint;
+mod connector;
+mod schema;
+
+pub use connector::KinesisConnector;
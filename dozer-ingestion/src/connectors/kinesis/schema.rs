@@ -0,0 +1,140 @@
+use dozer_types::chrono::{DateTime, NaiveDateTime, Offset, Utc};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+use crate::errors::KinesisError;
+
+/// The columns of every stream, and whether they are nullable.
+pub const COLUMNS: [(&str, FieldType, bool); 5] = [
+    ("partition_key", FieldType::String, false),
+    ("sequence_number", FieldType::String, false),
+    ("shard_id", FieldType::String, false),
+    ("data", FieldType::String, false),
+    ("arrival_timestamp", FieldType::Timestamp, true),
+];
+
+/// Returns the schema of the requested columns of a stream, all of them if none are requested, and their indexes in
+/// [`COLUMNS`].
+///
+/// Sequence numbers are only unique within a shard, so the primary index is the shard id and sequence number, when
+/// both are requested.
+pub fn stream_schema(column_names: &[String]) -> Result<(Schema, Vec<usize>), KinesisError> {
+    let projection = if column_names.is_empty() {
+        (0..COLUMNS.len()).collect()
+    } else {
+        column_names
+            .iter()
+            .map(|name| {
+                COLUMNS
+                    .iter()
+                    .position(|(column, _, _)| column == name)
+                    .ok_or_else(|| KinesisError::ColumnNotFound(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut schema = Schema::new();
+    for &index in &projection {
+        let (name, typ, nullable) = COLUMNS[index];
+        schema.fields.push(FieldDefinition::new(
+            name.to_string(),
+            typ,
+            nullable,
+            SourceDefinition::Dynamic,
+        ));
+    }
+    let key = ["shard_id", "sequence_number"]
+        .map(|name| schema.fields.iter().position(|field| field.name == name));
+    if let [Some(shard_id), Some(sequence_number)] = key {
+        schema.primary_index = vec![shard_id, sequence_number];
+    }
+    Ok((schema, projection))
+}
+
+/// Maps a record to the values of the columns in `projection`.
+pub fn map_record(
+    shard_id: &str,
+    record: &aws_sdk_kinesis::types::Record,
+    projection: &[usize],
+) -> Result<Vec<Field>, KinesisError> {
+    let sequence_number = record.sequence_number().unwrap_or_default();
+    let data = record.data().map_or(&[][..], |data| data.as_ref());
+    let data = std::str::from_utf8(data).map_err(|_| {
+        KinesisError::InvalidData(shard_id.to_string(), sequence_number.to_string())
+    })?;
+    let arrival_timestamp = record
+        .approximate_arrival_timestamp()
+        .and_then(|timestamp| {
+            NaiveDateTime::from_timestamp_opt(timestamp.secs(), timestamp.subsec_nanos())
+        })
+        .map_or(Field::Null, |timestamp| {
+            Field::Timestamp(DateTime::from_utc(timestamp, Utc.fix()))
+        });
+
+    let mut values = [
+        Field::String(record.partition_key().unwrap_or_default().to_string()),
+        Field::String(sequence_number.to_string()),
+        Field::String(shard_id.to_string()),
+        Field::String(data.to_string()),
+        arrival_timestamp,
+    ];
+    Ok(projection
+        .iter()
+        .map(|&index| std::mem::replace(&mut values[index], Field::Null))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_kinesis::primitives::{Blob, DateTime as AwsDateTime};
+    use aws_sdk_kinesis::types::Record;
+
+    use super::*;
+
+    #[test]
+    fn test_stream_schema() {
+        let (schema, projection) = stream_schema(&[]).unwrap();
+        assert_eq!(schema.fields.len(), COLUMNS.len());
+        assert_eq!(projection, vec![0, 1, 2, 3, 4]);
+        assert_eq!(schema.primary_index, vec![2, 1]);
+
+        let (schema, projection) =
+            stream_schema(&["data".to_string(), "sequence_number".to_string()]).unwrap();
+        assert_eq!(projection, vec![3, 1]);
+        assert!(schema.primary_index.is_empty());
+
+        assert!(matches!(
+            stream_schema(&["value".to_string()]),
+            Err(KinesisError::ColumnNotFound(name)) if name == "value"
+        ));
+    }
+
+    #[test]
+    fn test_map_record() {
+        let record = Record::builder()
+            .partition_key("film-1")
+            .sequence_number("49590338271490256608559692538361571095921575989136588898")
+            .data(Blob::new(r#"{"id":1}"#))
+            .approximate_arrival_timestamp(AwsDateTime::from_secs(1_686_000_000))
+            .build();
+        assert_eq!(
+            map_record("shardId-000000000000", &record, &[3, 2, 4]).unwrap(),
+            vec![
+                Field::String(r#"{"id":1}"#.to_string()),
+                Field::String("shardId-000000000000".to_string()),
+                Field::Timestamp(DateTime::from_utc(
+                    NaiveDateTime::from_timestamp_opt(1_686_000_000, 0).unwrap(),
+                    Utc.fix()
+                )),
+            ]
+        );
+
+        let record = Record::builder()
+            .sequence_number("1")
+            .data(Blob::new(vec![0xFF]))
+            .build();
+        assert!(matches!(
+            map_record("shardId-000000000000", &record, &[3]),
+            Err(KinesisError::InvalidData(..))
+        ));
+    }
+}
@@ -1,21 +1,88 @@
+#[cfg(feature = "cassandra")]
+pub mod cassandra;
+#[cfg(any(feature = "kinesis", feature = "dynamodb"))]
+mod checkpoint_file;
+pub mod databricks;
+#[cfg(feature = "dynamodb")]
+pub mod dynamodb;
+pub mod elasticsearch;
 #[cfg(feature = "ethereum")]
 pub mod ethereum;
+#[cfg(feature = "firestore")]
+pub mod firestore;
+#[cfg(feature = "google_sheets")]
+pub mod google_sheets;
 pub mod grpc;
+#[cfg(feature = "iceberg")]
+pub mod iceberg;
 #[cfg(feature = "kafka")]
 pub mod kafka;
+#[cfg(feature = "kinesis")]
+pub mod kinesis;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "mysql")]
+pub mod mysql;
+#[cfg(feature = "nats")]
+pub mod nats;
 pub mod object_store;
+#[cfg(feature = "oracle")]
+pub mod oracle;
 pub mod postgres;
+#[cfg(feature = "redis")]
+pub mod redis_streams;
+pub mod rest;
+#[cfg(feature = "salesforce")]
+pub mod salesforce;
+pub mod schema_inference;
+#[cfg(any(feature = "kinesis", feature = "dynamodb"))]
+mod shards;
+#[cfg(feature = "sql_server")]
+pub mod sql_server;
+pub mod webhook;
 
 use crate::connectors::postgres::connection::helper::map_connection_config;
 
 use std::fmt::Debug;
+use std::time::Duration;
 
+#[cfg(feature = "cassandra")]
+use crate::connectors::cassandra::CassandraConnector;
+use crate::connectors::databricks::DatabricksConnector;
+#[cfg(feature = "dynamodb")]
+use crate::connectors::dynamodb::DynamoDbConnector;
+use crate::connectors::elasticsearch::ElasticsearchConnector;
+#[cfg(feature = "firestore")]
+use crate::connectors::firestore::FirestoreConnector;
+#[cfg(feature = "google_sheets")]
+use crate::connectors::google_sheets::GoogleSheetsConnector;
+#[cfg(feature = "iceberg")]
+use crate::connectors::iceberg::IcebergConnector;
 #[cfg(feature = "kafka")]
 use crate::connectors::kafka::connector::KafkaConnector;
+#[cfg(feature = "kinesis")]
+use crate::connectors::kinesis::KinesisConnector;
+#[cfg(feature = "mqtt")]
+use crate::connectors::mqtt::MqttConnector;
+#[cfg(feature = "mysql")]
+use crate::connectors::mysql::MySQLConnector;
+#[cfg(feature = "nats")]
+use crate::connectors::nats::NatsConnector;
+#[cfg(feature = "oracle")]
+use crate::connectors::oracle::OracleConnector;
 use crate::connectors::postgres::connector::{PostgresConfig, PostgresConnector};
+#[cfg(feature = "redis")]
+use crate::connectors::redis_streams::RedisStreamsConnector;
+use crate::connectors::rest::{stripe, RestConnector};
+#[cfg(feature = "salesforce")]
+use crate::connectors::salesforce::SalesforceConnector;
+#[cfg(feature = "sql_server")]
+use crate::connectors::sql_server::SqlServerConnector;
+use crate::connectors::webhook::WebhookConnector;
 use crate::errors::ConnectorError;
 use crate::ingestion::Ingestor;
 
+use dozer_types::ingestion_types::{default_snapshot_chunk_size, default_snapshot_parallelism};
 use dozer_types::log::debug;
 use dozer_types::models::connection::Connection;
 use dozer_types::models::connection::ConnectionConfig;
@@ -24,12 +91,14 @@ use tonic::async_trait;
 use crate::connectors::object_store::connector::ObjectStoreConnector;
 
 use crate::connectors::delta_lake::DeltaLakeConnector;
+use crate::connectors::generator::GeneratorConnector;
 use dozer_types::prettytable::Table;
 use dozer_types::serde;
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::types::{FieldType, Schema};
 
 pub mod delta_lake;
+pub mod generator;
 pub mod snowflake;
 
 #[cfg(feature = "ethereum")]
@@ -121,6 +190,15 @@ pub trait Connector: Send + Sync + Debug {
         Ok((table_infos, schemas))
     }
 
+    /// Estimates the number of rows of each table, to show the progress of snapshots. `None` if it's unknown, which it
+    /// is for every table by default.
+    async fn estimate_row_counts(
+        &self,
+        tables: &[TableInfo],
+    ) -> Result<Vec<Option<u64>>, ConnectorError> {
+        Ok(vec![None; tables.len()])
+    }
+
     /// Starts outputting data from `tables` to `ingestor`. This method should never return unless there is an unrecoverable error.
     async fn start(
         &self,
@@ -167,11 +245,26 @@ pub fn get_connector(connection: Connection) -> Result<Box<dyn Connector>, Conne
         .config
         .ok_or_else(|| ConnectorError::MissingConfiguration(connection.name.clone()))?;
     match config {
-        ConnectionConfig::Postgres(_) => {
+        ConnectionConfig::Postgres(ref postgres) => {
+            let publication = postgres.publication.clone();
+            let trigger_poll_interval = postgres
+                .trigger_based
+                .unwrap_or(false)
+                .then(|| Duration::from_millis(postgres.poll_interval_ms.unwrap_or(1000)));
             let config = map_connection_config(&config)?;
             let postgres_config = PostgresConfig {
                 name: connection.name,
                 config,
+                publication,
+                trigger_poll_interval,
+                on_schema_change: postgres.on_schema_change.unwrap_or_default(),
+                snapshot_parallelism: postgres
+                    .snapshot_parallelism
+                    .unwrap_or_else(default_snapshot_parallelism)
+                    as usize,
+                snapshot_chunk_size: postgres
+                    .snapshot_chunk_size
+                    .unwrap_or_else(default_snapshot_chunk_size),
             };
 
             if let Some(dbname) = postgres_config.config.get_dbname() {
@@ -225,6 +318,111 @@ pub fn get_connector(connection: Connection) -> Result<Box<dyn Connector>, Conne
         ConnectionConfig::DeltaLake(delta_lake_config) => {
             Ok(Box::new(DeltaLakeConnector::new(delta_lake_config)))
         }
+        ConnectionConfig::Generator(generator_config) => {
+            Ok(Box::new(GeneratorConnector::new(generator_config)))
+        }
+        #[cfg(feature = "oracle")]
+        ConnectionConfig::Oracle(oracle_config) => Ok(Box::new(OracleConnector::new(
+            connection.name,
+            oracle_config,
+        ))),
+        #[cfg(not(feature = "oracle"))]
+        ConnectionConfig::Oracle(_) => Err(ConnectorError::OracleFeatureNotEnabled),
+        #[cfg(feature = "firestore")]
+        ConnectionConfig::Firestore(firestore_config) => Ok(Box::new(FirestoreConnector::new(
+            connection.name,
+            firestore_config,
+        )?)),
+        #[cfg(not(feature = "firestore"))]
+        ConnectionConfig::Firestore(_) => Err(ConnectorError::FirestoreFeatureNotEnabled),
+        ConnectionConfig::Stripe(stripe_config) => Ok(Box::new(RestConnector::new(
+            connection.name,
+            stripe::api(&stripe_config),
+            Duration::from_millis(stripe_config.poll_interval_ms),
+        ))),
+        #[cfg(feature = "mysql")]
+        ConnectionConfig::MySQL(mysql_config) => {
+            Ok(Box::new(MySQLConnector::new(connection.name, mysql_config)))
+        }
+        #[cfg(not(feature = "mysql"))]
+        ConnectionConfig::MySQL(_) => Err(ConnectorError::MySQLFeatureNotEnabled),
+        #[cfg(feature = "sql_server")]
+        ConnectionConfig::SqlServer(sql_server_config) => Ok(Box::new(SqlServerConnector::new(
+            connection.name,
+            sql_server_config,
+        ))),
+        #[cfg(not(feature = "sql_server"))]
+        ConnectionConfig::SqlServer(_) => Err(ConnectorError::SqlServerFeatureNotEnabled),
+        #[cfg(feature = "kinesis")]
+        ConnectionConfig::Kinesis(kinesis_config) => Ok(Box::new(KinesisConnector::new(
+            connection.name,
+            kinesis_config,
+        ))),
+        #[cfg(not(feature = "kinesis"))]
+        ConnectionConfig::Kinesis(_) => Err(ConnectorError::KinesisFeatureNotEnabled),
+        #[cfg(feature = "iceberg")]
+        ConnectionConfig::Iceberg(iceberg_config) => Ok(Box::new(IcebergConnector::new(
+            connection.name,
+            iceberg_config,
+        ))),
+        #[cfg(not(feature = "iceberg"))]
+        ConnectionConfig::Iceberg(_) => Err(ConnectorError::IcebergFeatureNotEnabled),
+        ConnectionConfig::Webhook(webhook_config) => Ok(Box::new(WebhookConnector::new(
+            connection.name,
+            webhook_config,
+        ))),
+        #[cfg(feature = "redis")]
+        ConnectionConfig::RedisStreams(redis_streams_config) => Ok(Box::new(
+            RedisStreamsConnector::new(connection.name, redis_streams_config),
+        )),
+        #[cfg(not(feature = "redis"))]
+        ConnectionConfig::RedisStreams(_) => Err(ConnectorError::RedisFeatureNotEnabled),
+        #[cfg(feature = "nats")]
+        ConnectionConfig::Nats(nats_config) => {
+            Ok(Box::new(NatsConnector::new(connection.name, nats_config)))
+        }
+        #[cfg(not(feature = "nats"))]
+        ConnectionConfig::Nats(_) => Err(ConnectorError::NatsFeatureNotEnabled),
+        #[cfg(feature = "mqtt")]
+        ConnectionConfig::Mqtt(mqtt_config) => {
+            Ok(Box::new(MqttConnector::new(connection.name, mqtt_config)))
+        }
+        #[cfg(not(feature = "mqtt"))]
+        ConnectionConfig::Mqtt(_) => Err(ConnectorError::MqttFeatureNotEnabled),
+        #[cfg(feature = "dynamodb")]
+        ConnectionConfig::DynamoDb(dynamodb_config) => Ok(Box::new(DynamoDbConnector::new(
+            connection.name,
+            dynamodb_config,
+        ))),
+        #[cfg(not(feature = "dynamodb"))]
+        ConnectionConfig::DynamoDb(_) => Err(ConnectorError::DynamoDbFeatureNotEnabled),
+        #[cfg(feature = "cassandra")]
+        ConnectionConfig::Cassandra(cassandra_config) => Ok(Box::new(CassandraConnector::new(
+            connection.name,
+            cassandra_config,
+        ))),
+        #[cfg(not(feature = "cassandra"))]
+        ConnectionConfig::Cassandra(_) => Err(ConnectorError::CassandraFeatureNotEnabled),
+        ConnectionConfig::Elasticsearch(elasticsearch_config) => Ok(Box::new(
+            ElasticsearchConnector::new(connection.name, elasticsearch_config),
+        )),
+        #[cfg(feature = "salesforce")]
+        ConnectionConfig::Salesforce(salesforce_config) => Ok(Box::new(SalesforceConnector::new(
+            connection.name,
+            salesforce_config,
+        ))),
+        #[cfg(not(feature = "salesforce"))]
+        ConnectionConfig::Salesforce(_) => Err(ConnectorError::SalesforceFeatureNotEnabled),
+        #[cfg(feature = "google_sheets")]
+        ConnectionConfig::GoogleSheets(google_sheets_config) => Ok(Box::new(
+            GoogleSheetsConnector::new(connection.name, google_sheets_config),
+        )),
+        #[cfg(not(feature = "google_sheets"))]
+        ConnectionConfig::GoogleSheets(_) => Err(ConnectorError::GoogleSheetsFeatureNotEnabled),
+        ConnectionConfig::Databricks(databricks_config) => Ok(Box::new(DatabricksConnector::new(
+            connection.name,
+            databricks_config,
+        ))),
     }
 }
 
@@ -239,6 +437,24 @@ pub fn get_connector_info_table(connection: &Connection) -> Result<Table, Connec
         Some(ConnectionConfig::Kafka(config)) => Ok(config.convert_to_table()),
         Some(ConnectionConfig::S3Storage(config)) => Ok(config.convert_to_table()),
         Some(ConnectionConfig::LocalStorage(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Generator(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Oracle(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Firestore(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Stripe(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::MySQL(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::SqlServer(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Kinesis(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Iceberg(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Webhook(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::RedisStreams(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Nats(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Mqtt(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::DynamoDb(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Cassandra(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Elasticsearch(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Salesforce(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::GoogleSheets(config)) => Ok(config.convert_to_table()),
+        Some(ConnectionConfig::Databricks(config)) => Ok(config.convert_to_table()),
         _ => Err(MissingConfiguration(connection.name.clone())),
     }
 }
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use dozer_types::bytes::Bytes;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::ingestion_types::MqttConfig;
+use rumqttc::v5;
+
+use crate::errors::MqttError;
+
+/// A client of either MQTT version, with manual acknowledgements, and its event loop.
+pub enum MqttClient {
+    V4(rumqttc::AsyncClient, rumqttc::EventLoop),
+    V5(v5::AsyncClient, v5::EventLoop),
+}
+
+pub enum MqttEvent {
+    Connected { session_present: bool },
+    Message(MqttMessage),
+    Other,
+}
+
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: Bytes,
+    publish: Publish,
+}
+
+enum Publish {
+    V4(rumqttc::Publish),
+    V5(v5::mqttbytes::v5::Publish),
+}
+
+impl MqttClient {
+    /// `capacity` is the number of requests, such as subscriptions and acknowledgements, queued until the event loop
+    /// is polled.
+    pub fn new(config: &MqttConfig, capacity: usize) -> Result<Self, MqttError> {
+        let port = u16::try_from(config.port).map_err(|_| MqttError::InvalidPort(config.port))?;
+        let keep_alive = Duration::from_secs(config.keep_alive_secs);
+        qos(config.qos)?;
+        match config.protocol_version.as_str() {
+            "3.1.1" => {
+                let mut options = rumqttc::MqttOptions::new(&config.client_id, &config.host, port);
+                options
+                    .set_keep_alive(keep_alive)
+                    .set_clean_session(config.clean_session)
+                    .set_manual_acks(true);
+                if let Some(username) = &config.username {
+                    options.set_credentials(username, config.password.as_deref().unwrap_or(""));
+                }
+                let (client, event_loop) = rumqttc::AsyncClient::new(options, capacity);
+                Ok(Self::V4(client, event_loop))
+            }
+            "5" => {
+                let mut options = v5::MqttOptions::new(&config.client_id, &config.host, port);
+                options
+                    .set_keep_alive(keep_alive)
+                    .set_clean_start(config.clean_session)
+                    .set_manual_acks(true);
+                if !config.clean_session {
+                    options.set_session_expiry_interval(Some(config.session_expiry_secs));
+                }
+                if let Some(username) = &config.username {
+                    options.set_credentials(username, config.password.as_deref().unwrap_or(""));
+                }
+                let (client, event_loop) = v5::AsyncClient::new(options, capacity);
+                Ok(Self::V5(client, event_loop))
+            }
+            version => Err(MqttError::UnsupportedProtocolVersion(version.to_string())),
+        }
+    }
+
+    /// Connects again on the next poll after an error, unless the broker refused the connection.
+    pub async fn poll(&mut self) -> Result<MqttEvent, MqttError> {
+        match self {
+            Self::V4(_, event_loop) => match event_loop.poll().await {
+                Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(conn_ack))) => {
+                    Ok(MqttEvent::Connected {
+                        session_present: conn_ack.session_present,
+                    })
+                }
+                Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => {
+                    Ok(MqttEvent::Message(MqttMessage {
+                        topic: publish.topic.clone(),
+                        payload: publish.payload.clone(),
+                        publish: Publish::V4(publish),
+                    }))
+                }
+                Ok(_) => Ok(MqttEvent::Other),
+                Err(rumqttc::ConnectionError::ConnectionRefused(code)) => {
+                    Err(MqttError::ConnectionRefused(format!("{code:?}")))
+                }
+                Err(e) => Err(connection_error(e)),
+            },
+            Self::V5(_, event_loop) => match event_loop.poll().await {
+                Ok(v5::Event::Incoming(v5::mqttbytes::v5::Packet::ConnAck(conn_ack))) => {
+                    Ok(MqttEvent::Connected {
+                        session_present: conn_ack.session_present,
+                    })
+                }
+                Ok(v5::Event::Incoming(v5::mqttbytes::v5::Packet::Publish(publish))) => {
+                    Ok(MqttEvent::Message(MqttMessage {
+                        topic: String::from_utf8_lossy(&publish.topic).into_owned(),
+                        payload: publish.payload.clone(),
+                        publish: Publish::V5(publish),
+                    }))
+                }
+                Ok(_) => Ok(MqttEvent::Other),
+                Err(v5::ConnectionError::ConnectionRefused(code)) => {
+                    Err(MqttError::ConnectionRefused(format!("{code:?}")))
+                }
+                Err(e) => Err(connection_error(e)),
+            },
+        }
+    }
+
+    /// Queues a subscription, sent on the next poll.
+    pub fn subscribe(&self, filter: &str, qos_level: u32) -> Result<(), MqttError> {
+        let (v4_qos, v5_qos) = qos(qos_level)?;
+        match self {
+            Self::V4(client, _) => client
+                .try_subscribe(filter, v4_qos)
+                .map_err(connection_error),
+            Self::V5(client, _) => client
+                .try_subscribe(filter, v5_qos)
+                .map_err(connection_error),
+        }
+    }
+
+    pub async fn ack(&self, message: &MqttMessage) -> Result<(), MqttError> {
+        match (self, &message.publish) {
+            (Self::V4(client, _), Publish::V4(publish)) => {
+                client.ack(publish).await.map_err(connection_error)
+            }
+            (Self::V5(client, _), Publish::V5(publish)) => {
+                client.ack(publish).await.map_err(connection_error)
+            }
+            _ => unreachable!("message of another protocol version"),
+        }
+    }
+}
+
+/// The QoS of both versions.
+fn qos(qos: u32) -> Result<(rumqttc::QoS, v5::mqttbytes::QoS), MqttError> {
+    match qos {
+        0 => Ok((rumqttc::QoS::AtMostOnce, v5::mqttbytes::QoS::AtMostOnce)),
+        1 => Ok((rumqttc::QoS::AtLeastOnce, v5::mqttbytes::QoS::AtLeastOnce)),
+        qos => Err(MqttError::UnsupportedQos(qos)),
+    }
+}
+
+fn connection_error(e: impl Into<BoxedError>) -> MqttError {
+    MqttError::Connection(e.into())
+}
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{self, IngestionMessage, MqttConfig};
+use dozer_types::log::{info, warn};
+use dozer_types::types::FieldType;
+use tonic::async_trait;
+
+use super::client::{MqttClient, MqttEvent};
+use crate::connectors::webhook::WebhookTable;
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, MqttError};
+use crate::ingestion::Ingestor;
+
+/// Requests queued by the client besides the subscriptions of the tables.
+const CLIENT_CAPACITY: usize = 10;
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Subscribes to the topic filters of the tables and reads their JSON messages, acknowledging them once they are
+/// ingested. The session is resumed on reconnection unless it's configured to be clean.
+#[derive(Debug)]
+pub struct MqttConnector {
+    name: String,
+    config: MqttConfig,
+}
+
+impl MqttConnector {
+    pub fn new(name: String, config: MqttConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn table_config(&self, name: &str) -> Result<&ingestion_types::MqttTable, ConnectorError> {
+        self.config
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| ConnectorError::TableNotFound(name.to_string()))
+    }
+
+    /// The table's mapping of messages to operations, which is the one of a webhook table.
+    fn get_table(&self, table_info: &TableInfo) -> Result<WebhookTable, ConnectorError> {
+        let config = self.table_config(&table_info.name)?;
+        let mapping = ingestion_types::WebhookTable {
+            name: config.name.clone(),
+            path: None,
+            columns: config.columns.clone(),
+            primary_key: config.primary_key.clone(),
+            operation_field: config.operation_field.clone(),
+        };
+        Ok(WebhookTable::new(&mapping, &table_info.column_names).map_err(MqttError::Mapping)?)
+    }
+
+    fn topic_filter(&self, table_name: &str) -> Result<String, ConnectorError> {
+        let config = self.table_config(table_name)?;
+        let filter = config
+            .topic
+            .clone()
+            .unwrap_or_else(|| table_name.to_string());
+        if !is_valid_filter(&filter) {
+            return Err(MqttError::InvalidTopicFilter(filter).into());
+        }
+        Ok(filter)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let mappings = tables
+            .iter()
+            .map(|table| self.get_table(table))
+            .collect::<Result<Vec<_>, _>>()?;
+        let filters = tables
+            .iter()
+            .map(|table| self.topic_filter(&table.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut client = MqttClient::new(&self.config, filters.len() + CLIENT_CAPACITY)?;
+
+        // A resumed session has the subscriptions of the previous run, which may have had other tables, so they're
+        // made on the first connection of every run.
+        let mut subscribed = false;
+        let mut seq_no = 0;
+        loop {
+            let message = match client.poll().await {
+                Ok(MqttEvent::Connected { session_present }) => {
+                    if session_present {
+                        info!("[{}] Resumed session {}", self.name, self.config.client_id);
+                    }
+                    if !session_present || !subscribed {
+                        for filter in &filters {
+                            client.subscribe(filter, self.config.qos)?;
+                        }
+                        subscribed = true;
+                    }
+                    continue;
+                }
+                Ok(MqttEvent::Message(message)) => message,
+                Ok(MqttEvent::Other) => continue,
+                Err(e @ MqttError::ConnectionRefused(_)) => return Err(e.into()),
+                Err(e) => {
+                    warn!("[{}] {e}, reconnecting", self.name);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+
+            for (table_index, filter) in filters.iter().enumerate() {
+                if !topic_matches(filter, &message.topic) {
+                    continue;
+                }
+                let operations = mappings[table_index]
+                    .operations(&message.payload)
+                    .map_err(|e| MqttError::InvalidMessage(message.topic.clone(), e))?;
+                for op in operations {
+                    seq_no += 1;
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                }
+            }
+            client.ack(&message).await?;
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for MqttConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("string".to_string(), Some(FieldType::String)),
+            ("number".to_string(), Some(FieldType::Float)),
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+            ("object".to_string(), Some(FieldType::Json)),
+            ("array".to_string(), Some(FieldType::Json)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let mut client = MqttClient::new(&self.config, CLIENT_CAPACITY)?;
+        let connected = async {
+            loop {
+                if let MqttEvent::Connected { .. } = client.poll().await? {
+                    return Ok::<_, MqttError>(());
+                }
+            }
+        };
+        tokio::time::timeout(CONNECTION_TIMEOUT, connected)
+            .await
+            .map_err(|_| MqttError::ConnectionTimeout)??;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .config
+            .tables
+            .iter()
+            .map(|table| TableIdentifier::from_table_name(table.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        for table in tables {
+            if table.schema.is_some() || self.table_config(&table.name).is_err() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    table.schema.as_deref(),
+                    &table.name,
+                )));
+            }
+            self.topic_filter(&table.name)?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let config = self.table_config(&table.name)?;
+                Ok(TableInfo {
+                    column_names: config
+                        .columns
+                        .iter()
+                        .map(|column| column.name.clone())
+                        .collect(),
+                    schema: table.schema,
+                    name: table.name,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let table = self.get_table(table_info)?;
+                // Updates and deletes only carry the primary key of the old row.
+                let cdc_type = if table.has_changes() {
+                    CdcType::OnlyPK
+                } else {
+                    CdcType::Nothing
+                };
+                Ok(SourceSchema::new(table.schema(), cdc_type))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+/// `+` is a whole level and `#` the whole last level.
+fn is_valid_filter(filter: &str) -> bool {
+    let levels = filter.split('/').collect::<Vec<_>>();
+    !filter.is_empty()
+        && levels
+            .iter()
+            .enumerate()
+            .all(|(index, level)| match *level {
+                "#" => index == levels.len() - 1,
+                "+" => true,
+                level => !level.contains(['#', '+']),
+            })
+}
+
+/// Whether `topic` matches the valid `filter`. Wildcards at the first level don't match the topics starting with `$`,
+/// which are the broker's.
+fn topic_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+    let mut filter_levels = filter.split('/');
+    let mut topic_levels = topic.split('/');
+    loop {
+        match (filter_levels.next(), topic_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(_)) => (),
+            (Some(filter_level), Some(topic_level)) if filter_level == topic_level => (),
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_filter() {
+        for filter in ["sensors", "sensors/+/temperature", "sensors/#", "#", "+/+"] {
+            assert!(is_valid_filter(filter), "{filter}");
+        }
+        for filter in ["", "sensors/#/temperature", "sensors+", "sensors/t#"] {
+            assert!(!is_valid_filter(filter), "{filter}");
+        }
+    }
+
+    #[test]
+    fn test_topic_matches() {
+        assert!(topic_matches(
+            "sensors/+/temperature",
+            "sensors/a/temperature"
+        ));
+        assert!(!topic_matches(
+            "sensors/+/temperature",
+            "sensors/a/b/temperature"
+        ));
+        assert!(topic_matches("sensors/#", "sensors"));
+        assert!(topic_matches("sensors/#", "sensors/a/humidity"));
+        assert!(!topic_matches("sensors", "sensors/a"));
+        assert!(topic_matches("#", "sensors/a"));
+        assert!(!topic_matches("#", "$SYS/uptime"));
+        assert!(topic_matches("$SYS/#", "$SYS/uptime"));
+    }
+}
@@ -0,0 +1,7 @@
+//! MQTT brokers, each table reading the JSON messages of a topic filter with MQTT 3.1.1 or 5. Messages are mapped to
+//! operations like the payloads of webhooks.
+
+mod client;
+mod connector;
+
+pub use connector::MqttConnector;
@@ -0,0 +1,731 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{IngestionMessage, SchemaDrift, SchemaDriftKind};
+use dozer_types::log::{info, warn};
+use dozer_types::types::Operation;
+use futures::StreamExt;
+use mysql_async::binlog::events::{EventData, RowsEventData};
+use mysql_async::binlog::row::BinlogRow;
+use mysql_async::prelude::Queryable;
+use mysql_async::{BinlogRequest, Conn, GnoInterval, Opts, Sid};
+
+use super::schema::{get_table, Table};
+use crate::errors::{ConnectorError, MySQLError};
+use crate::ingestion::Ingestor;
+
+/// How long to wait before reconnecting when the binlog connection drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A set of GTIDs, like `@@GLOBAL.gtid_executed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtidSet {
+    /// Sorted, disjoint and non adjacent transaction number ranges, with exclusive ends, by server UUID.
+    intervals: BTreeMap<[u8; 16], Vec<(u64, u64)>>,
+}
+
+impl GtidSet {
+    pub fn add(&mut self, sid: [u8; 16], gno: u64) {
+        self.add_interval(sid, gno, gno + 1);
+    }
+
+    fn add_interval(&mut self, sid: [u8; 16], start: u64, end: u64) {
+        let intervals = self.intervals.entry(sid).or_default();
+        intervals.push((start, end));
+        intervals.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(intervals.len());
+        for &(start, end) in intervals.iter() {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        *intervals = merged;
+    }
+
+    /// The set as requested from the server.
+    pub fn sids(&self) -> Vec<Sid<'static>> {
+        self.intervals
+            .iter()
+            .map(|(uuid, intervals)| {
+                intervals.iter().fold(Sid::new(*uuid), |sid, (start, end)| {
+                    sid.with_interval(GnoInterval::new(*start, *end))
+                })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for GtidSet {
+    type Err = MySQLError;
+
+    /// Parses sets like `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:11,1ba4b10c-74c5-11e1-9e33-c80aa9429562:7`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || MySQLError::InvalidGtidSet(value.to_string());
+        let mut set = GtidSet::default();
+        for gtids in value
+            .split(',')
+            .map(str::trim)
+            .filter(|gtids| !gtids.is_empty())
+        {
+            let mut parts = gtids.split(':');
+            let uuid = parse_uuid(parts.next().ok_or_else(invalid)?).ok_or_else(invalid)?;
+            for interval in parts {
+                let (start, end) = interval.split_once('-').unwrap_or((interval, interval));
+                let start: u64 = start.parse().map_err(|_| invalid())?;
+                let end: u64 = end.parse().map_err(|_| invalid())?;
+                if start == 0 || end < start {
+                    return Err(invalid());
+                }
+                set.add_interval(uuid, start, end + 1);
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl Display for GtidSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (uuid, intervals) in &self.intervals {
+            if !first {
+                write!(f, ",")?;
+            }
+            first = false;
+            write!(f, "{}", format_uuid(uuid))?;
+            for (start, end) in intervals {
+                if end - start == 1 {
+                    write!(f, ":{start}")?;
+                } else {
+                    write!(f, ":{start}-{}", end - 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_uuid(uuid: &str) -> Option<[u8; 16]> {
+    let hex = uuid.replace('-', "");
+    if hex.len() != 32 {
+        return None;
+    }
+    let mut bytes = [0; 16];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn format_uuid(uuid: &[u8; 16]) -> String {
+    let hex = uuid
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Where in the binlog to continue replication from: after a set of transactions if GTIDs are on, or else at a
+/// position of a binlog file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinlogPosition {
+    Gtid(GtidSet),
+    File { name: String, position: u64 },
+}
+
+impl Display for BinlogPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BinlogPosition::Gtid(gtid_set) => write!(f, "GTID set {gtid_set}"),
+            BinlogPosition::File { name, position } => write!(f, "{name}:{position}"),
+        }
+    }
+}
+
+/// The current binlog position, which must be read in the snapshot transaction while tables are locked.
+pub async fn current_position(conn: &mut Conn) -> Result<BinlogPosition, MySQLError> {
+    // MariaDB has no `gtid_mode` and its GTIDs are not understood, so it's replicated from file positions.
+    let gtid_mode: Option<String> = conn
+        .query_first("SELECT @@GLOBAL.gtid_mode")
+        .await
+        .ok()
+        .flatten();
+    if gtid_mode.as_deref() == Some("ON") {
+        let gtid_executed: String = conn
+            .query_first("SELECT @@GLOBAL.gtid_executed")
+            .await?
+            .unwrap_or_default();
+        return Ok(BinlogPosition::Gtid(gtid_executed.parse()?));
+    }
+
+    let (name, position): (String, u64) = conn
+        .query_first::<mysql_async::Row, _>("SHOW MASTER STATUS")
+        .await?
+        .and_then(|mut row| Some((row.take(0)?, row.take(1)?)))
+        .ok_or(MySQLError::BinlogDisabled)?;
+    Ok(BinlogPosition::File { name, position })
+}
+
+/// Follows the row changes of `tables` in the binlog.
+///
+/// Changes of a transaction are sent in chunks of `chunk_size`, between `TransactionBegin` and `TransactionEnd`. The
+/// position moves when a transaction commits, so a dropped connection is resumed at the start of the transaction, and
+/// its changes that were already sent are skipped.
+///
+/// `ALTER TABLE` statements that add or drop columns of the tables are reported as schema drifts, and the columns are
+/// read again, as their positions in binlog rows change. If columns the pipeline reads are dropped or changed,
+/// `SchemaChanged` is sent and replication stops, so that the pipeline is rebuilt.
+#[derive(Debug)]
+pub struct BinlogReader {
+    name: String,
+    opts: Opts,
+    server_id: u32,
+    tables: Vec<Table>,
+    position: BinlogPosition,
+    chunk_size: usize,
+    txn: u64,
+    /// The operations of the current transaction that are read but not sent yet.
+    operations: Vec<(usize, Operation)>,
+    /// How many operations of the current transaction were read on this connection.
+    read: u64,
+    /// How many operations of the current transaction were sent, on this connection or before it dropped.
+    sent: u64,
+}
+
+impl BinlogReader {
+    pub fn new(
+        name: String,
+        opts: Opts,
+        server_id: u32,
+        tables: Vec<Table>,
+        position: BinlogPosition,
+        chunk_size: u64,
+    ) -> Self {
+        Self {
+            name,
+            opts,
+            server_id,
+            tables,
+            position,
+            chunk_size: chunk_size.max(1) as usize,
+            txn: 0,
+            operations: vec![],
+            read: 0,
+            sent: 0,
+        }
+    }
+
+    /// Reads the binlog until an error other than a dropped connection, or until the schema of a table changes.
+    pub async fn run(&mut self, ingestor: &Ingestor) -> Result<(), ConnectorError> {
+        loop {
+            info!("[{}] Replicating from {}", self.name, self.position);
+            match self.read(ingestor).await {
+                Ok(true) => return Ok(()),
+                Err(ConnectorError::MySQLError(MySQLError::MySQL(mysql_async::Error::Io(e)))) => {
+                    warn!("[{}] Binlog connection failed: {e}", self.name);
+                }
+                Err(e) => return Err(e),
+                Ok(false) => warn!("[{}] Binlog connection closed", self.name),
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Returns true if replication stopped because the schema of a table changed.
+    async fn read(&mut self, ingestor: &Ingestor) -> Result<bool, ConnectorError> {
+        self.restart_transaction();
+        let conn = Conn::new(self.opts.clone())
+            .await
+            .map_err(MySQLError::from)?;
+        let request = match &self.position {
+            BinlogPosition::Gtid(gtid_set) => BinlogRequest::new(self.server_id)
+                .with_gtid()
+                .with_sids(gtid_set.sids()),
+            BinlogPosition::File { name, position } => BinlogRequest::new(self.server_id)
+                .with_filename(name.as_bytes().to_vec())
+                .with_pos(*position),
+        };
+        let mut stream = conn
+            .get_binlog_stream(request)
+            .await
+            .map_err(MySQLError::from)?;
+
+        let mut gtid = None;
+        while let Some(event) = stream.next().await {
+            let event = event.map_err(MySQLError::from)?;
+            let log_pos = event.header().log_pos() as u64;
+            let Some(data) = event.read_data().map_err(MySQLError::InvalidBinlogEvent)? else {
+                continue;
+            };
+            match data {
+                EventData::RotateEvent(rotate) => {
+                    if let BinlogPosition::File { name, position } = &mut self.position {
+                        *name = rotate.name().to_string();
+                        *position = rotate.position();
+                    }
+                }
+                EventData::GtidEvent(event) => gtid = Some((event.sid(), event.gno())),
+                EventData::RowsEvent(rows) => {
+                    let table_map = stream
+                        .get_tme(rows.table_id())
+                        .ok_or(MySQLError::MissingTableMap(rows.table_id()))?;
+                    let Some(table_index) =
+                        self.table_index(&table_map.database_name(), &table_map.table_name())
+                    else {
+                        continue;
+                    };
+                    // Changes of the columns that weren't parsed, e.g. made by a tool copying the table, show in the
+                    // rows first.
+                    let columns_count = table_map.columns_count() as usize;
+                    if columns_count != self.tables[table_index].column_count {
+                        if self.refresh_table(table_index, ingestor).await? {
+                            return Ok(true);
+                        }
+                        if columns_count != self.tables[table_index].column_count {
+                            return Err(MySQLError::TableChanged(
+                                self.tables[table_index].name.clone(),
+                            )
+                            .into());
+                        }
+                    }
+                    let table = &self.tables[table_index];
+                    let mut ops = vec![];
+                    for row in rows.rows(table_map) {
+                        let (before, after) = row.map_err(MySQLError::InvalidBinlogEvent)?;
+                        ops.push(operation(table, &rows, before, after)?);
+                    }
+                    for op in ops {
+                        self.push(table_index, op, ingestor)?;
+                    }
+                }
+                EventData::XidEvent(_) => {
+                    self.commit(gtid.take(), log_pos, ingestor)?;
+                }
+                // Transactions of non-transactional tables end with `COMMIT`, and DDL statements are transactions of
+                // their own.
+                EventData::QueryEvent(query) if query.query() != "BEGIN" => {
+                    self.commit(gtid.take(), log_pos, ingestor)?;
+                    if let Some(alter) = parse_alter_table(&query.query(), &query.schema()) {
+                        if self.alter_table(alter, ingestor).await? {
+                            return Ok(true);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(false)
+    }
+
+    fn table_index(&self, database: &str, name: &str) -> Option<usize> {
+        self.tables
+            .iter()
+            .position(|table| table.database == database && table.name == name)
+    }
+
+    /// Reports the columns added to or dropped from a table and reads its columns again. Returns true if the schema
+    /// changed.
+    async fn alter_table(
+        &mut self,
+        alter: AlterTable,
+        ingestor: &Ingestor,
+    ) -> Result<bool, ConnectorError> {
+        let Some(table_index) = self.table_index(&alter.database, &alter.name) else {
+            return Ok(false);
+        };
+        for drift in alter.drifts {
+            ingestor
+                .handle_message(IngestionMessage::new_schema_drift(
+                    self.txn,
+                    0,
+                    table_index,
+                    drift,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        self.refresh_table(table_index, ingestor).await
+    }
+
+    /// Reads the columns of a table again, which moved if columns were added or dropped before them. Sends
+    /// `SchemaChanged` and returns true if the columns read by the pipeline were dropped or changed.
+    ///
+    /// The columns are read as they are now, which is ahead of the binlog if the table changed again since.
+    async fn refresh_table(
+        &mut self,
+        table_index: usize,
+        ingestor: &Ingestor,
+    ) -> Result<bool, ConnectorError> {
+        let table = &self.tables[table_index];
+        let column_names = table
+            .columns
+            .iter()
+            .map(|column| column.name.clone())
+            .collect::<Vec<_>>();
+        let mut conn = Conn::new(self.opts.clone())
+            .await
+            .map_err(MySQLError::from)?;
+        match get_table(&mut conn, &table.database, &table.name, &column_names).await {
+            Ok(refreshed) if refreshed.schema() == table.schema() => {
+                info!(
+                    "[{}] Columns of table {} changed, it now has {} columns",
+                    self.name, table.name, refreshed.column_count
+                );
+                self.tables[table_index] = refreshed;
+                Ok(false)
+            }
+            Ok(_) | Err(MySQLError::ColumnNotFound(..)) | Err(MySQLError::UnsupportedType(..)) => {
+                info!(
+                    "[{}] Columns of table {} read by the pipeline changed, stopping replication to rebuild the pipeline",
+                    self.name, table.name
+                );
+                ingestor
+                    .handle_message(IngestionMessage::new_schema_changed(
+                        self.txn,
+                        0,
+                        table_index,
+                    ))
+                    .map_err(ConnectorError::IngestorError)?;
+                Ok(true)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Reads the current transaction again from its start, as a new connection does.
+    pub fn restart_transaction(&mut self) {
+        self.operations.clear();
+        self.read = 0;
+    }
+
+    /// Adds an operation of the current transaction, sending a chunk if it's full. Operations that were already sent
+    /// are skipped.
+    pub fn push(
+        &mut self,
+        table_index: usize,
+        op: Operation,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        self.read += 1;
+        if self.read <= self.sent {
+            return Ok(());
+        }
+        self.operations.push((table_index, op));
+        if self.operations.len() >= self.chunk_size {
+            self.send_chunk(ingestor)?;
+        }
+        Ok(())
+    }
+
+    fn send_chunk(&mut self, ingestor: &Ingestor) -> Result<(), ConnectorError> {
+        if self.operations.is_empty() {
+            return Ok(());
+        }
+        if self.sent == 0 {
+            self.txn += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_transaction_begin(self.txn, 0))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        for (table_index, op) in std::mem::take(&mut self.operations) {
+            self.sent += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    self.txn,
+                    self.sent,
+                    table_index,
+                    op,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        Ok(())
+    }
+
+    /// Sends the rest of the operations of the committed transaction and moves the position after it.
+    pub fn commit(
+        &mut self,
+        gtid: Option<([u8; 16], u64)>,
+        log_pos: u64,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        self.send_chunk(ingestor)?;
+        if self.sent > 0 {
+            ingestor
+                .handle_message(IngestionMessage::new_transaction_end(
+                    self.txn,
+                    self.sent + 1,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        self.read = 0;
+        self.sent = 0;
+
+        match &mut self.position {
+            BinlogPosition::Gtid(gtid_set) => {
+                if let Some((sid, gno)) = gtid {
+                    gtid_set.add(sid, gno);
+                }
+            }
+            BinlogPosition::File { position, .. } => *position = log_pos,
+        }
+        Ok(())
+    }
+}
+
+fn operation(
+    table: &Table,
+    rows: &RowsEventData,
+    before: Option<BinlogRow>,
+    after: Option<BinlogRow>,
+) -> Result<Operation, MySQLError> {
+    Ok(match (before, after) {
+        (None, Some(after)) => Operation::Insert {
+            new: table.record(after)?,
+        },
+        (Some(before), Some(after)) => Operation::Update {
+            old: table.record(before)?,
+            new: table.record(after)?,
+        },
+        (Some(before), None) => Operation::Delete {
+            old: table.record(before)?,
+        },
+        (None, None) => return Err(MySQLError::EmptyRow(rows.table_id())),
+    })
+}
+
+/// The columns added and dropped by an `ALTER TABLE` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlterTable {
+    pub database: String,
+    pub name: String,
+    pub drifts: Vec<SchemaDrift>,
+}
+
+/// Keywords that follow `ADD` or `DROP` in clauses that don't change columns.
+const NON_COLUMN_KEYWORDS: &[&str] = &[
+    "INDEX",
+    "KEY",
+    "PRIMARY",
+    "UNIQUE",
+    "FULLTEXT",
+    "SPATIAL",
+    "FOREIGN",
+    "CONSTRAINT",
+    "CHECK",
+    "PARTITION",
+];
+
+/// Parses the columns added and dropped by a statement, if it's an `ALTER TABLE`. Tables without a database belong
+/// to `default_database`, the database the statement ran in.
+pub fn parse_alter_table(query: &str, default_database: &str) -> Option<AlterTable> {
+    let tokens = tokenize(query);
+    let mut tokens = tokens.iter().peekable();
+    if !is_keyword(tokens.next()?, "ALTER") {
+        return None;
+    }
+    // MariaDB has `ALTER ONLINE IGNORE TABLE`.
+    loop {
+        let token = tokens.next()?;
+        if is_keyword(token, "TABLE") {
+            break;
+        }
+        if !is_keyword(token, "ONLINE") && !is_keyword(token, "IGNORE") {
+            return None;
+        }
+    }
+    // `IF EXISTS`
+    if tokens.next_if(|token| is_keyword(token, "IF")).is_some() {
+        tokens.next();
+    }
+    let first = identifier(tokens.next()?)?;
+    let (database, name) = if tokens.peek() == Some(&&Token::Symbol('.')) {
+        tokens.next();
+        (first, identifier(tokens.next()?)?)
+    } else {
+        (default_database.to_string(), first)
+    };
+
+    let mut clauses = vec![vec![]];
+    let mut depth = 0;
+    for token in tokens {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => depth -= 1,
+            Token::Symbol(',') if depth == 0 => {
+                clauses.push(vec![]);
+                continue;
+            }
+            _ => (),
+        }
+        if let Some(clause) = clauses.last_mut() {
+            clause.push(token);
+        }
+    }
+    let drifts = clauses
+        .iter()
+        .flat_map(|clause| clause_drifts(clause))
+        .collect();
+    Some(AlterTable {
+        database,
+        name,
+        drifts,
+    })
+}
+
+/// The columns added or dropped by a clause of `ALTER TABLE`, like `ADD COLUMN c INT` or `ADD (a INT, b INT)`.
+fn clause_drifts(clause: &[&Token]) -> Vec<SchemaDrift> {
+    let (kind, rest) = match clause {
+        [first, rest @ ..] if is_keyword(first, "ADD") => (SchemaDriftKind::ColumnAdded, rest),
+        [first, rest @ ..] if is_keyword(first, "DROP") => (SchemaDriftKind::ColumnDropped, rest),
+        _ => return vec![],
+    };
+    let rest = match rest {
+        [first, rest @ ..] if is_keyword(first, "COLUMN") => rest,
+        [first, ..]
+            if NON_COLUMN_KEYWORDS
+                .iter()
+                .any(|keyword| is_keyword(first, keyword)) =>
+        {
+            return vec![]
+        }
+        _ => rest,
+    };
+    // MariaDB has `ADD COLUMN IF NOT EXISTS` and `DROP COLUMN IF EXISTS`.
+    let rest = match rest {
+        [first, rest @ ..] if is_keyword(first, "IF") => {
+            let skipped = if matches!(rest, [not, ..] if is_keyword(not, "NOT")) {
+                2
+            } else {
+                1
+            };
+            rest.get(skipped..).unwrap_or_default()
+        }
+        _ => rest,
+    };
+
+    let column_names = match rest {
+        // Each definition of the list starts with the column name.
+        [Token::Symbol('('), definitions @ ..] => {
+            let mut column_names = vec![];
+            let mut depth = 0;
+            let mut expect_name = true;
+            for token in definitions {
+                match token {
+                    Token::Symbol('(') => depth += 1,
+                    Token::Symbol(')') => depth -= 1,
+                    Token::Symbol(',') if depth == 0 => {
+                        expect_name = true;
+                        continue;
+                    }
+                    token if expect_name => column_names.extend(identifier(token)),
+                    _ => (),
+                }
+                expect_name = false;
+            }
+            column_names
+        }
+        [name, ..] => identifier(name).into_iter().collect(),
+        [] => vec![],
+    };
+    column_names
+        .into_iter()
+        .map(|column_name| SchemaDrift {
+            column_name,
+            kind: kind.clone(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A keyword or an unquoted identifier.
+    Word(String),
+    /// An identifier quoted with backticks.
+    Quoted(String),
+    Symbol(char),
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+}
+
+fn identifier(token: &Token) -> Option<String> {
+    match token {
+        Token::Word(identifier) | Token::Quoted(identifier) => Some(identifier.clone()),
+        Token::Symbol(_) => None,
+    }
+}
+
+/// Splits a statement in words, quoted identifiers and symbols, skipping string literals and comments.
+fn tokenize(query: &str) -> Vec<Token> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut tokens = vec![];
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => {
+                let mut identifier = String::new();
+                while let Some(c) = chars.next() {
+                    if c != '`' {
+                        identifier.push(c);
+                    } else if chars.next_if_eq(&'`').is_some() {
+                        identifier.push('`');
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Quoted(identifier));
+            }
+            '\'' | '"' => {
+                while let Some(next) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c && chars.next_if_eq(&c).is_none() {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '#' => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if is_word_char(c) => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| is_word_char(*c)) {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+            c if c.is_whitespace() => (),
+            c => tokens.push(Token::Symbol(c)),
+        }
+    }
+    tokens
+}
@@ -0,0 +1,402 @@
+use dozer_types::ingestion_types::{IngestionMessage, MySQLConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use futures::future::try_join_all;
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Opts, OptsBuilder, Row, Value};
+use std::iter::once;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::Mutex;
+use tonic::async_trait;
+
+use super::binlog::{current_position, BinlogPosition, BinlogReader};
+use super::schema::{self, quote, Table, TYPES};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, MySQLError};
+use crate::ingestion::Ingestor;
+
+/// Snapshots tables in a transaction, then follows their changes in the binlog from where the snapshot was taken.
+#[derive(Debug)]
+pub struct MySQLConnector {
+    name: String,
+    config: MySQLConfig,
+}
+
+impl MySQLConnector {
+    pub fn new(name: String, config: MySQLConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn opts(&self) -> Opts {
+        OptsBuilder::default()
+            .ip_or_hostname(self.config.host.clone())
+            .tcp_port(self.config.port as u16)
+            .user(Some(self.config.user.clone()))
+            .pass(Some(self.config.password.clone()))
+            .db_name(Some(self.config.database.clone()))
+            .into()
+    }
+
+    async fn connect(&self) -> Result<Conn, MySQLError> {
+        let mut conn = Conn::new(self.opts()).await?;
+        // `timestamp` values are read in the session time zone, and `datetime` values are read as UTC.
+        conn.query_drop("SET time_zone = '+00:00'").await?;
+        Ok(conn)
+    }
+
+    /// Tables without a schema belong to the configured database.
+    fn database<'a>(&'a self, schema: Option<&'a str>) -> &'a str {
+        schema.unwrap_or(&self.config.database)
+    }
+
+    async fn get_table(
+        &self,
+        conn: &mut Conn,
+        table_info: &TableInfo,
+    ) -> Result<Table, ConnectorError> {
+        Ok(schema::get_table(
+            conn,
+            self.database(table_info.schema.as_deref()),
+            &table_info.name,
+            &table_info.column_names,
+        )
+        .await?)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let mut conn = self.connect().await?;
+        let mut source_tables = vec![];
+        for table_info in &tables {
+            source_tables.push(self.get_table(&mut conn, table_info).await?);
+        }
+
+        // Tables are split in chunks only if they are read by more than one connection.
+        let mut readers = vec![];
+        if self.config.snapshot_parallelism > 1 {
+            for _ in 0..self.config.snapshot_parallelism {
+                readers.push(self.connect().await?);
+            }
+        }
+        let position = snapshot(
+            &mut conn,
+            &mut readers,
+            &source_tables,
+            self.config.snapshot_chunk_size,
+            ingestor,
+        )
+        .await?;
+        info!("[{}] Snapshotted at {}", self.name, position);
+        drop(conn);
+        drop(readers);
+
+        BinlogReader::new(
+            self.name.clone(),
+            self.opts(),
+            self.config.server_id,
+            source_tables,
+            position,
+            self.config.transaction_chunk_size,
+        )
+        .run(ingestor)
+        .await
+    }
+}
+
+#[async_trait]
+impl Connector for MySQLConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let mut conn = self.connect().await?;
+        let (log_bin, binlog_format, binlog_row_image) = conn
+            .query_first::<(i64, String, String), _>(
+                "SELECT @@GLOBAL.log_bin, @@GLOBAL.binlog_format, @@GLOBAL.binlog_row_image",
+            )
+            .await
+            .map_err(MySQLError::from)?
+            .ok_or(MySQLError::BinlogDisabled)?;
+        if log_bin != 1 {
+            return Err(MySQLError::BinlogDisabled.into());
+        }
+        if binlog_format != "ROW" {
+            return Err(MySQLError::BinlogFormatNotRow(binlog_format).into());
+        }
+        if binlog_row_image != "FULL" {
+            return Err(MySQLError::BinlogRowImageNotFull(binlog_row_image).into());
+        }
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let mut conn = self.connect().await?;
+        let database = self.database(None);
+        Ok(schema::list_tables(&mut conn, database)
+            .await?
+            .into_iter()
+            .map(|name| TableIdentifier::new(Some(database.to_string()), name))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let mut conn = self.connect().await?;
+        for table in tables {
+            let database = self.database(table.schema.as_deref());
+            if schema::list_columns(&mut conn, database, &table.name)
+                .await?
+                .is_empty()
+            {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    Some(database),
+                    &table.name,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let mut conn = self.connect().await?;
+        let mut table_infos = vec![];
+        for table in tables {
+            let column_names = schema::list_columns(
+                &mut conn,
+                self.database(table.schema.as_deref()),
+                &table.name,
+            )
+            .await?;
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names,
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let mut conn = self.connect().await?;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                self.get_table(&mut conn, table_info)
+                    .await
+                    .map(|table| SourceSchema::new(table.schema(), CdcType::FullChanges)),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+/// Sends the rows of `tables` as of a consistent snapshot, returning the binlog position of the snapshot.
+///
+/// Tables are locked while the snapshot starts on `conn` and every reader, so no transaction commits between it and
+/// the position. With readers, `conn` splits each table with a primary key in chunks of `chunk_size` rows, which the
+/// readers read in parallel.
+async fn snapshot(
+    conn: &mut Conn,
+    readers: &mut [Conn],
+    tables: &[Table],
+    chunk_size: u64,
+    ingestor: &Ingestor,
+) -> Result<BinlogPosition, ConnectorError> {
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+        .map_err(ConnectorError::IngestorError)?;
+
+    conn.query_drop("FLUSH TABLES WITH READ LOCK")
+        .await
+        .map_err(MySQLError::from)?;
+    for conn in once(&mut *conn).chain(readers.iter_mut()) {
+        conn.query_drop("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .await
+            .map_err(MySQLError::from)?;
+        conn.query_drop("START TRANSACTION WITH CONSISTENT SNAPSHOT")
+            .await
+            .map_err(MySQLError::from)?;
+    }
+    let position = current_position(conn).await;
+    conn.query_drop("UNLOCK TABLES")
+        .await
+        .map_err(MySQLError::from)?;
+    let position = position?;
+
+    let seq_no = AtomicU64::new(0);
+    for (table_index, table) in tables.iter().enumerate() {
+        let columns = table
+            .columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!("SELECT {columns} FROM {}", table.quoted_name());
+        if readers.is_empty() || table.primary_index.is_empty() {
+            send_rows(conn, sql, vec![], table, table_index, ingestor, &seq_no).await?;
+        } else {
+            let (sender, ranges) = channel(readers.len());
+            let ranges = Mutex::new(ranges);
+            let read_chunks = readers.iter_mut().map(|reader| {
+                read_chunks(reader, &sql, table, table_index, &ranges, ingestor, &seq_no)
+            });
+            tokio::try_join!(
+                send_chunks(conn, table, chunk_size, sender),
+                try_join_all(read_chunks)
+            )?;
+        }
+    }
+    for conn in once(conn).chain(readers.iter_mut()) {
+        conn.query_drop("COMMIT").await.map_err(MySQLError::from)?;
+    }
+
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_done(0, 0))
+        .map_err(ConnectorError::IngestorError)?;
+    Ok(position)
+}
+
+/// Primary key bounds of a chunk. The lower bound is exclusive and the upper bound inclusive. `None` means unbounded.
+type ChunkRange = (Option<Vec<Value>>, Option<Vec<Value>>);
+
+/// Returns the `WHERE` clause restricting the primary key of `table` to the bounds, and its parameters.
+pub fn chunk_condition(
+    table: &Table,
+    lower: Option<&[Value]>,
+    upper: Option<&[Value]>,
+) -> (String, Vec<Value>) {
+    let key = primary_key(table);
+    let placeholders = vec!["?"; table.primary_index.len()].join(", ");
+    let mut conditions = vec![];
+    let mut params = vec![];
+    for (op, bound) in [(">", lower), ("<=", upper)] {
+        if let Some(bound) = bound {
+            conditions.push(format!("({key}) {op} ({placeholders})"));
+            params.extend_from_slice(bound);
+        }
+    }
+    if conditions.is_empty() {
+        (String::new(), params)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), params)
+    }
+}
+
+fn primary_key(table: &Table) -> String {
+    table
+        .primary_index
+        .iter()
+        .map(|index| quote(&table.columns[*index].name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Splits `table` in chunks of `chunk_size` rows by primary key and sends their ranges, in key order.
+async fn send_chunks(
+    conn: &mut Conn,
+    table: &Table,
+    chunk_size: u64,
+    ranges: Sender<ChunkRange>,
+) -> Result<(), ConnectorError> {
+    let key = primary_key(table);
+    let mut lower: Option<Vec<Value>> = None;
+    loop {
+        let (condition, params) = chunk_condition(table, lower.as_deref(), None);
+        let sql = format!(
+            "SELECT {key} FROM {}{condition} ORDER BY {key} LIMIT 1 OFFSET {}",
+            table.quoted_name(),
+            chunk_size.max(1) - 1
+        );
+        let upper = conn
+            .exec_first::<Row, _, _>(sql, params)
+            .await
+            .map_err(MySQLError::from)?
+            .map(Row::unwrap);
+
+        let is_last = upper.is_none();
+        // The readers are gone if one of them failed.
+        if ranges.send((lower, upper.clone())).await.is_err() || is_last {
+            return Ok(());
+        }
+        lower = upper;
+    }
+}
+
+/// Sends the rows of the chunks whose ranges are received from `ranges` until there are none left.
+async fn read_chunks(
+    conn: &mut Conn,
+    sql: &str,
+    table: &Table,
+    table_index: usize,
+    ranges: &Mutex<Receiver<ChunkRange>>,
+    ingestor: &Ingestor,
+    seq_no: &AtomicU64,
+) -> Result<(), ConnectorError> {
+    loop {
+        let range = ranges.lock().await.recv().await;
+        let Some((lower, upper)) = range else {
+            return Ok(());
+        };
+        let (condition, params) = chunk_condition(table, lower.as_deref(), upper.as_deref());
+        let sql = format!("{sql}{condition}");
+        send_rows(conn, sql, params, table, table_index, ingestor, seq_no).await?;
+    }
+}
+
+/// Sends the rows of `table` that `sql` selects as inserts.
+async fn send_rows(
+    conn: &mut Conn,
+    sql: String,
+    params: Vec<Value>,
+    table: &Table,
+    table_index: usize,
+    ingestor: &Ingestor,
+    seq_no: &AtomicU64,
+) -> Result<(), ConnectorError> {
+    // The binary protocol has values as the binlog has them, rather than as text.
+    let mut rows = conn
+        .exec_iter(sql, params)
+        .await
+        .map_err(MySQLError::from)?;
+    while let Some(row) = rows.next().await.map_err(MySQLError::from)? {
+        let values = table
+            .columns
+            .iter()
+            .zip(row.unwrap())
+            .map(|(column, value)| column.convert(value))
+            .collect::<Result<_, _>>()?;
+        ingestor
+            .handle_message(IngestionMessage::new_op(
+                0,
+                seq_no.fetch_add(1, Ordering::Relaxed) + 1,
+                table_index,
+                Operation::Insert {
+                    new: Record::new(values),
+                },
+            ))
+            .map_err(ConnectorError::IngestorError)?;
+    }
+    Ok(())
+}
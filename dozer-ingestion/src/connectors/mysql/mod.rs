@@ -0,0 +1,8 @@
+mod binlog;
+mod connector;
+mod schema;
+
+pub use connector::MySQLConnector;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,388 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Offset, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json;
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition, TimeUnit,
+};
+use mysql_async::binlog::row::BinlogRow;
+use mysql_async::binlog::value::BinlogValue;
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Value};
+
+use crate::errors::MySQLError;
+
+/// MySQL types and the types they are mapped to. `tinyint(1)` is mapped to boolean and unsigned integers to uint.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("tinyint", FieldType::Int),
+    ("smallint", FieldType::Int),
+    ("mediumint", FieldType::Int),
+    ("int", FieldType::Int),
+    ("bigint", FieldType::Int),
+    ("year", FieldType::Int),
+    ("decimal", FieldType::Decimal),
+    ("float", FieldType::Float),
+    ("double", FieldType::Float),
+    ("char", FieldType::String),
+    ("varchar", FieldType::String),
+    ("enum", FieldType::String),
+    ("set", FieldType::String),
+    ("tinytext", FieldType::Text),
+    ("text", FieldType::Text),
+    ("mediumtext", FieldType::Text),
+    ("longtext", FieldType::Text),
+    ("binary", FieldType::Binary),
+    ("varbinary", FieldType::Binary),
+    ("tinyblob", FieldType::Binary),
+    ("blob", FieldType::Binary),
+    ("mediumblob", FieldType::Binary),
+    ("longblob", FieldType::Binary),
+    ("bit", FieldType::Binary),
+    ("date", FieldType::Date),
+    ("datetime", FieldType::Timestamp),
+    ("timestamp", FieldType::Timestamp),
+    ("time", FieldType::Duration),
+    ("json", FieldType::Json),
+];
+
+/// A column of a source table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// The type as in `information_schema.COLUMNS.DATA_TYPE`, e.g. `int`.
+    pub data_type: String,
+    /// The full type as in `information_schema.COLUMNS.COLUMN_TYPE`, e.g. `int(10) unsigned`.
+    pub column_type: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+    /// Position in the table, which is the position of the column's values in binlog rows.
+    pub ordinal: usize,
+}
+
+impl Column {
+    /// Converts a value of the snapshot or the binlog.
+    pub fn convert(&self, value: Value) -> Result<Field, MySQLError> {
+        let invalid =
+            |value: &Value| MySQLError::InvalidValue(self.name.clone(), format!("{value:?}"));
+        Ok(match (self.typ, value) {
+            (_, Value::NULL) => Field::Null,
+            (FieldType::Boolean, Value::Int(value)) => Field::Boolean(value != 0),
+            (FieldType::Boolean, Value::UInt(value)) => Field::Boolean(value != 0),
+            (FieldType::Int, Value::Int(value)) => Field::Int(value),
+            (FieldType::Int, value @ Value::UInt(unsigned)) => {
+                Field::Int(i64::try_from(unsigned).map_err(|_| invalid(&value))?)
+            }
+            (FieldType::UInt, Value::UInt(value)) => Field::UInt(value),
+            // The binlog has no signedness, so values of unsigned columns above the signed range come negative.
+            (FieldType::UInt, Value::Int(value)) => Field::UInt(self.unsigned(value)),
+            (FieldType::Float, Value::Float(value)) => Field::Float(OrderedFloat(value as f64)),
+            (FieldType::Float, Value::Double(value)) => Field::Float(OrderedFloat(value)),
+            (FieldType::Decimal, value @ Value::Bytes(_)) => Field::Decimal(
+                utf8(&value)
+                    .and_then(|decimal| Decimal::from_str(decimal).ok())
+                    .ok_or_else(|| invalid(&value))?,
+            ),
+            // The binlog has the indexes of enum values and bitmasks of set values.
+            (FieldType::String, Value::Int(index)) if self.data_type == "enum" => Field::String(
+                self.enum_label(index as u64)
+                    .ok_or_else(|| invalid(&Value::Int(index)))?,
+            ),
+            (FieldType::String, Value::Int(bits)) if self.data_type == "set" => {
+                Field::String(self.set_labels(bits as u64))
+            }
+            (FieldType::String, value @ Value::Bytes(_)) => {
+                Field::String(utf8(&value).ok_or_else(|| invalid(&value))?.to_string())
+            }
+            (FieldType::Text, value @ Value::Bytes(_)) => {
+                Field::Text(utf8(&value).ok_or_else(|| invalid(&value))?.to_string())
+            }
+            (FieldType::Binary, Value::Bytes(bytes)) => Field::Binary(bytes),
+            (FieldType::Date, value @ Value::Date(year, month, day, ..)) => Field::Date(
+                NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                    .ok_or_else(|| invalid(&value))?,
+            ),
+            (FieldType::Timestamp, value @ Value::Date(..)) => {
+                utc_timestamp(naive_date_time(&value).ok_or_else(|| invalid(&value))?)
+            }
+            // The binlog has `timestamp` values as seconds since the epoch, with the fraction if there is one.
+            (FieldType::Timestamp, value @ Value::Bytes(_)) => utc_timestamp(
+                utf8(&value)
+                    .and_then(parse_epoch_seconds)
+                    .ok_or_else(|| invalid(&value))?,
+            ),
+            (FieldType::Duration, Value::Time(false, days, hours, minutes, seconds, micros)) => {
+                let seconds =
+                    ((days as u64 * 24 + hours as u64) * 60 + minutes as u64) * 60 + seconds as u64;
+                Field::Duration(DozerDuration(
+                    std::time::Duration::new(seconds, micros * 1000),
+                    TimeUnit::Microseconds,
+                ))
+            }
+            (FieldType::Json, value @ Value::Bytes(_)) => {
+                let json =
+                    serde_json::from_slice(value_bytes(&value)).map_err(|_| invalid(&value))?;
+                Field::Json(serde_json_to_json_value(json).map_err(|_| invalid(&value))?)
+            }
+            (_, value) => return Err(invalid(&value)),
+        })
+    }
+
+    /// Converts a value of a binlog row.
+    pub fn convert_binlog(&self, value: BinlogValue<'_>) -> Result<Field, MySQLError> {
+        match value {
+            BinlogValue::Value(value) => self.convert(value),
+            BinlogValue::Jsonb(jsonb) => {
+                let json = serde_json::Value::try_from(jsonb)
+                    .map_err(|e| MySQLError::InvalidValue(self.name.clone(), e.to_string()))?;
+                let json = serde_json_to_json_value(json)
+                    .map_err(|e| MySQLError::InvalidValue(self.name.clone(), e.to_string()))?;
+                Ok(Field::Json(json))
+            }
+            BinlogValue::JsonDiff(_) => Err(MySQLError::PartialJsonUpdate(self.name.clone())),
+        }
+    }
+
+    /// Reinterprets a signed binlog value of an unsigned integer column.
+    fn unsigned(&self, value: i64) -> u64 {
+        let bits = match self.data_type.as_str() {
+            "tinyint" => 8,
+            "smallint" => 16,
+            "mediumint" => 24,
+            "int" => 32,
+            _ => 64,
+        };
+        if bits == 64 {
+            value as u64
+        } else {
+            value as u64 & ((1 << bits) - 1)
+        }
+    }
+
+    /// The labels of an `enum` or `set` column, e.g. `a` and `b` of `enum('a','b')`.
+    fn labels(&self) -> Vec<String> {
+        let Some(labels) = self
+            .column_type
+            .split_once('(')
+            .and_then(|(_, labels)| labels.strip_suffix(')'))
+        else {
+            return vec![];
+        };
+        labels
+            .split("','")
+            .map(|label| {
+                label
+                    .trim_start_matches('\'')
+                    .trim_end_matches('\'')
+                    .replace("''", "'")
+            })
+            .collect()
+    }
+
+    fn enum_label(&self, index: u64) -> Option<String> {
+        // Index 0 is the empty string of invalid values.
+        if index == 0 {
+            return Some(String::new());
+        }
+        self.labels().into_iter().nth(index as usize - 1)
+    }
+
+    fn set_labels(&self, bits: u64) -> String {
+        self.labels()
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| bits & (1 << index) != 0)
+            .map(|(_, label)| label)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// The requested columns of a source table and the positions of its primary key among them.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub database: String,
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_index: Vec<usize>,
+    /// Number of columns of the table, including the ones not requested.
+    pub column_count: usize,
+}
+
+impl Table {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    column.nullable,
+                    SourceDefinition::Dynamic,
+                ),
+                self.primary_index.contains(&index),
+            );
+        }
+        schema
+    }
+
+    /// The quoted name, to be used in queries.
+    pub fn quoted_name(&self) -> String {
+        format!("{}.{}", quote(&self.database), quote(&self.name))
+    }
+
+    /// Converts a binlog row, which has all columns of the table.
+    pub fn record(&self, mut row: BinlogRow) -> Result<Record, MySQLError> {
+        let values = self
+            .columns
+            .iter()
+            .map(|column| {
+                let value = row.take(column.ordinal).ok_or_else(|| {
+                    MySQLError::MissingBinlogColumn(column.name.clone(), self.name.clone())
+                })?;
+                column.convert_binlog(value)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Record::new(values))
+    }
+}
+
+/// Maps a type of `information_schema.COLUMNS`, returning `None` if it's not supported.
+pub fn map_type(data_type: &str, column_type: &str) -> Option<FieldType> {
+    if data_type == "tinyint" && column_type.starts_with("tinyint(1)") {
+        return Some(FieldType::Boolean);
+    }
+    let typ = TYPES
+        .iter()
+        .find(|(name, _)| *name == data_type)
+        .map(|(_, typ)| *typ)?;
+    if typ == FieldType::Int && column_type.ends_with("unsigned") {
+        return Some(FieldType::UInt);
+    }
+    Some(typ)
+}
+
+pub fn quote(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('`', "``"))
+}
+
+pub async fn list_tables(conn: &mut Conn, database: &str) -> Result<Vec<String>, MySQLError> {
+    Ok(conn
+        .exec(
+            "SELECT TABLE_NAME FROM information_schema.TABLES \
+                WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME",
+            (database,),
+        )
+        .await?)
+}
+
+/// Lists the column names of a table, which is empty if the table doesn't exist.
+pub async fn list_columns(
+    conn: &mut Conn,
+    database: &str,
+    table: &str,
+) -> Result<Vec<String>, MySQLError> {
+    Ok(conn
+        .exec(
+            "SELECT COLUMN_NAME FROM information_schema.COLUMNS \
+                WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (database, table),
+        )
+        .await?)
+}
+
+/// Gets the `column_names` of a table.
+pub async fn get_table(
+    conn: &mut Conn,
+    database: &str,
+    table: &str,
+    column_names: &[String],
+) -> Result<Table, MySQLError> {
+    let all_columns: Vec<(String, String, String, String, String)> = conn
+        .exec(
+            "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, IS_NULLABLE, COLUMN_KEY FROM information_schema.COLUMNS \
+                WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (database, table),
+        )
+        .await?;
+
+    let mut columns = vec![];
+    let mut primary_index = vec![];
+    for column_name in column_names {
+        let (ordinal, (name, data_type, column_type, nullable, key)) = all_columns
+            .iter()
+            .enumerate()
+            .find(|(_, (name, ..))| name == column_name)
+            .ok_or_else(|| {
+                MySQLError::ColumnNotFound(column_name.clone(), format!("{database}.{table}"))
+            })?;
+        let data_type = data_type.to_lowercase();
+        let column_type = column_type.to_lowercase();
+        let typ = map_type(&data_type, &column_type)
+            .ok_or_else(|| MySQLError::UnsupportedType(name.clone(), column_type.clone()))?;
+        if key == "PRI" {
+            primary_index.push(columns.len());
+        }
+        columns.push(Column {
+            name: name.clone(),
+            data_type,
+            column_type,
+            typ,
+            nullable: nullable == "YES",
+            ordinal,
+        });
+    }
+
+    Ok(Table {
+        database: database.to_string(),
+        name: table.to_string(),
+        columns,
+        primary_index,
+        column_count: all_columns.len(),
+    })
+}
+
+/// Values of `datetime` columns have no time zone and are read as UTC, as `timestamp` values are.
+fn utc_timestamp(value: NaiveDateTime) -> Field {
+    Field::Timestamp(DateTime::from_utc(value, Utc.fix()))
+}
+
+fn naive_date_time(value: &Value) -> Option<NaiveDateTime> {
+    let Value::Date(year, month, day, hour, minute, second, micros) = *value else {
+        return None;
+    };
+    Some(NaiveDateTime::new(
+        NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)?,
+        NaiveTime::from_hms_micro_opt(hour as u32, minute as u32, second as u32, micros)?,
+    ))
+}
+
+/// Parses seconds since the epoch with an optional fraction, e.g. `1690000000.123`.
+pub fn parse_epoch_seconds(value: &str) -> Option<NaiveDateTime> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<9}").parse().ok()?
+    };
+    NaiveDateTime::from_timestamp_opt(seconds.parse().ok()?, nanos)
+}
+
+fn value_bytes(value: &Value) -> &[u8] {
+    match value {
+        Value::Bytes(bytes) => bytes,
+        _ => &[],
+    }
+}
+
+fn utf8(value: &Value) -> Option<&str> {
+    match value {
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,309 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::NaiveDate;
+use dozer_types::ingestion_types::{IngestionMessageKind, SchemaDrift, SchemaDriftKind};
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{DozerDuration, Field, FieldType, Operation, Record, TimeUnit};
+use mysql_async::{Opts, Value};
+
+use super::binlog::{parse_alter_table, AlterTable, BinlogPosition, BinlogReader, GtidSet};
+use super::connector::chunk_condition;
+use super::schema::{map_type, parse_epoch_seconds, Column, Table};
+use crate::errors::MySQLError;
+use crate::ingestion::Ingestor;
+
+fn column(data_type: &str, column_type: &str) -> Column {
+    Column {
+        name: "c".to_string(),
+        data_type: data_type.to_string(),
+        column_type: column_type.to_string(),
+        typ: map_type(data_type, column_type).unwrap(),
+        nullable: true,
+        ordinal: 0,
+    }
+}
+
+#[test]
+fn test_map_type() {
+    assert_eq!(map_type("int", "int"), Some(FieldType::Int));
+    assert_eq!(map_type("int", "int(10) unsigned"), Some(FieldType::UInt));
+    assert_eq!(map_type("tinyint", "tinyint(1)"), Some(FieldType::Boolean));
+    assert_eq!(map_type("tinyint", "tinyint(4)"), Some(FieldType::Int));
+    assert_eq!(
+        map_type("decimal", "decimal(10,2)"),
+        Some(FieldType::Decimal)
+    );
+    assert_eq!(map_type("enum", "enum('a','b')"), Some(FieldType::String));
+    assert_eq!(map_type("longtext", "longtext"), Some(FieldType::Text));
+    assert_eq!(
+        map_type("datetime", "datetime(6)"),
+        Some(FieldType::Timestamp)
+    );
+    assert_eq!(map_type("geometry", "geometry"), None);
+}
+
+#[test]
+fn test_convert_value() {
+    assert_eq!(
+        column("int", "int(10) unsigned")
+            .convert(Value::Int(-1))
+            .unwrap(),
+        Field::UInt(u32::MAX as u64)
+    );
+    assert_eq!(
+        column("bigint", "bigint unsigned")
+            .convert(Value::UInt(u64::MAX))
+            .unwrap(),
+        Field::UInt(u64::MAX)
+    );
+    assert_eq!(
+        column("decimal", "decimal(10,2)")
+            .convert(Value::Bytes(b"-12.50".to_vec()))
+            .unwrap(),
+        Field::Decimal(Decimal::from_str("-12.50").unwrap())
+    );
+    assert_eq!(
+        column("tinyint", "tinyint(1)")
+            .convert(Value::Int(1))
+            .unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        column("date", "date")
+            .convert(Value::Date(2023, 7, 1, 0, 0, 0, 0))
+            .unwrap(),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 7, 1).unwrap())
+    );
+    assert_eq!(
+        column("time", "time")
+            .convert(Value::Time(false, 1, 2, 3, 4, 5))
+            .unwrap(),
+        Field::Duration(DozerDuration(
+            std::time::Duration::new(93784, 5000),
+            TimeUnit::Microseconds
+        ))
+    );
+    assert_eq!(
+        column("int", "int").convert(Value::NULL).unwrap(),
+        Field::Null
+    );
+    assert!(matches!(
+        column("int", "int").convert(Value::Bytes(b"1".to_vec())),
+        Err(MySQLError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn test_convert_timestamp() {
+    let expected = NaiveDate::from_ymd_opt(2023, 7, 22)
+        .unwrap()
+        .and_hms_micro_opt(4, 26, 40, 123000)
+        .unwrap();
+    assert_eq!(parse_epoch_seconds("1690000000.123"), Some(expected));
+    assert_eq!(parse_epoch_seconds("1690000000.123000"), Some(expected));
+    assert_eq!(parse_epoch_seconds("1690000000.1a"), None);
+
+    let column = column("timestamp", "timestamp(3)");
+    assert_eq!(
+        column
+            .convert(Value::Bytes(b"1690000000.123".to_vec()))
+            .unwrap(),
+        column
+            .convert(Value::Date(2023, 7, 22, 4, 26, 40, 123000))
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_convert_enum_and_set() {
+    let enum_column = column("enum", "enum('small','it''s big')");
+    assert_eq!(
+        enum_column.convert(Value::Int(2)).unwrap(),
+        Field::String("it's big".to_string())
+    );
+    assert_eq!(
+        enum_column
+            .convert(Value::Bytes(b"small".to_vec()))
+            .unwrap(),
+        Field::String("small".to_string())
+    );
+    assert!(enum_column.convert(Value::Int(3)).is_err());
+
+    let set_column = column("set", "set('a','b','c')");
+    assert_eq!(
+        set_column.convert(Value::Int(0b101)).unwrap(),
+        Field::String("a,c".to_string())
+    );
+}
+
+#[test]
+fn test_chunk_condition() {
+    let mut id = column("int", "int");
+    id.name = "id".to_string();
+    let mut name = column("varchar", "varchar(10)");
+    name.name = "name".to_string();
+    let table = Table {
+        database: "db".to_string(),
+        name: "users".to_string(),
+        columns: vec![id, name],
+        primary_index: vec![0, 1],
+        column_count: 2,
+    };
+    assert_eq!(chunk_condition(&table, None, None), (String::new(), vec![]));
+
+    let lower = [Value::Int(1), Value::from("a")];
+    let upper = [Value::Int(5), Value::from("b")];
+    assert_eq!(
+        chunk_condition(&table, None, Some(&upper[..])),
+        (
+            " WHERE (`id`, `name`) <= (?, ?)".to_string(),
+            upper.to_vec()
+        )
+    );
+    assert_eq!(
+        chunk_condition(&table, Some(&lower[..]), Some(&upper[..])),
+        (
+            " WHERE (`id`, `name`) > (?, ?) AND (`id`, `name`) <= (?, ?)".to_string(),
+            [lower, upper].concat()
+        )
+    );
+}
+
+#[test]
+fn test_gtid_set() {
+    let uuid = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+    let mut set = GtidSet::from_str(&format!(
+        "{uuid}:1-5:11,\n1ba4b10c-74c5-11e1-9e33-c80aa9429562:7"
+    ))
+    .unwrap();
+    assert_eq!(
+        set.to_string(),
+        format!("1ba4b10c-74c5-11e1-9e33-c80aa9429562:7,{uuid}:1-5:11")
+    );
+
+    let sid = [
+        0x3e, 0x11, 0xfa, 0x47, 0x71, 0xca, 0x11, 0xe1, 0x9e, 0x33, 0xc8, 0x0a, 0xa9, 0x42, 0x95,
+        0x62,
+    ];
+    set.add(sid, 6);
+    set.add(sid, 10);
+    set.add(sid, 13);
+    assert_eq!(
+        set.to_string(),
+        format!("1ba4b10c-74c5-11e1-9e33-c80aa9429562:7,{uuid}:1-6:10-11:13")
+    );
+
+    assert_eq!(GtidSet::from_str("").unwrap(), GtidSet::default());
+    assert!(GtidSet::from_str(&format!("{uuid}:5-1")).is_err());
+    assert!(GtidSet::from_str("not-a-uuid:1").is_err());
+}
+
+#[test]
+fn test_transaction_chunks() {
+    let (ingestor, iterator) = Ingestor::initialize_channel(Default::default());
+    let mut reader = BinlogReader::new(
+        "mysql".to_string(),
+        Opts::from_url("mysql://root@localhost/test").unwrap(),
+        1001,
+        vec![],
+        BinlogPosition::File {
+            name: "binlog.000001".to_string(),
+            position: 4,
+        },
+        2,
+    );
+    let insert = |id| Operation::Insert {
+        new: Record::new(vec![Field::Int(id)]),
+    };
+
+    for id in 0..3 {
+        reader.push(0, insert(id), &ingestor).unwrap();
+    }
+    // The connection drops after the first chunk, and the transaction is read again from its start.
+    reader.restart_transaction();
+    for id in 0..5 {
+        reader.push(0, insert(id), &ingestor).unwrap();
+    }
+    reader.commit(None, 100, &ingestor).unwrap();
+    drop(ingestor);
+
+    let messages = iterator
+        .rx
+        .try_iter()
+        .map(|message| match message.kind {
+            IngestionMessageKind::TransactionBegin => ("begin", message.identifier.seq_in_tx),
+            IngestionMessageKind::OperationEvent {
+                op: Operation::Insert { new },
+                ..
+            } => {
+                assert_eq!(
+                    new.values,
+                    vec![Field::Int(message.identifier.seq_in_tx as i64 - 1)]
+                );
+                ("insert", message.identifier.seq_in_tx)
+            }
+            IngestionMessageKind::TransactionEnd => ("end", message.identifier.seq_in_tx),
+            kind => panic!("Unexpected message {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            ("begin", 0),
+            ("insert", 1),
+            ("insert", 2),
+            ("insert", 3),
+            ("insert", 4),
+            ("insert", 5),
+            ("end", 6),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_alter_table() {
+    let drift = |column_name: &str, kind: SchemaDriftKind| SchemaDrift {
+        column_name: column_name.to_string(),
+        kind,
+    };
+
+    assert_eq!(
+        parse_alter_table(
+            "ALTER TABLE `users` ADD COLUMN `email` varchar(255) DEFAULT 'a,b', DROP `age`",
+            "test"
+        ),
+        Some(AlterTable {
+            database: "test".to_string(),
+            name: "users".to_string(),
+            drifts: vec![
+                drift("email", SchemaDriftKind::ColumnAdded),
+                drift("age", SchemaDriftKind::ColumnDropped),
+            ],
+        })
+    );
+    assert_eq!(
+        parse_alter_table(
+            "alter table shop.orders add (total decimal(10, 2), note text), add index idx (note(10))",
+            "test"
+        ),
+        Some(AlterTable {
+            database: "shop".to_string(),
+            name: "orders".to_string(),
+            drifts: vec![
+                drift("total", SchemaDriftKind::ColumnAdded),
+                drift("note", SchemaDriftKind::ColumnAdded),
+            ],
+        })
+    );
+    assert_eq!(
+        parse_alter_table(
+            "ALTER TABLE /* comment */ t DROP PRIMARY KEY, DROP COLUMN IF EXISTS `a``b`",
+            "test"
+        )
+        .unwrap()
+        .drifts,
+        vec![drift("a`b", SchemaDriftKind::ColumnDropped)]
+    );
+    assert_eq!(parse_alter_table("CREATE TABLE t (id int)", "test"), None);
+    assert_eq!(parse_alter_table("COMMIT", "test"), None);
+}
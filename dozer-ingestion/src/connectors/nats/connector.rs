@@ -0,0 +1,274 @@
+use std::path::PathBuf;
+
+use async_nats::jetstream::consumer::{pull, AckPolicy, DeliverPolicy};
+use async_nats::jetstream::stream::Stream as JetStream;
+use async_nats::ConnectOptions;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::ingestion_types::{self, IngestionMessage, NatsConfig};
+use dozer_types::log::info;
+use dozer_types::types::FieldType;
+use futures::stream::{select_all, StreamExt};
+use tonic::async_trait;
+
+use crate::connectors::webhook::WebhookTable;
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, NatsError};
+use crate::ingestion::Ingestor;
+
+/// Reads the JSON messages of NATS JetStream subjects with durable consumers, acknowledging them once they are
+/// ingested.
+#[derive(Debug)]
+pub struct NatsConnector {
+    name: String,
+    config: NatsConfig,
+}
+
+impl NatsConnector {
+    pub fn new(name: String, config: NatsConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn table_config(&self, name: &str) -> Result<&ingestion_types::NatsTable, ConnectorError> {
+        self.config
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| ConnectorError::TableNotFound(name.to_string()))
+    }
+
+    /// The table's mapping of messages to operations, which is the one of a webhook table.
+    fn get_table(&self, table_info: &TableInfo) -> Result<WebhookTable, ConnectorError> {
+        let config = self.table_config(&table_info.name)?;
+        let mapping = ingestion_types::WebhookTable {
+            name: config.name.clone(),
+            path: None,
+            columns: config.columns.clone(),
+            primary_key: config.primary_key.clone(),
+            operation_field: config.operation_field.clone(),
+        };
+        Ok(WebhookTable::new(&mapping, &table_info.column_names).map_err(NatsError::Mapping)?)
+    }
+
+    async fn stream(&self) -> Result<JetStream, NatsError> {
+        let mut options = ConnectOptions::new();
+        if let Some(credentials_file) = &self.config.credentials_file {
+            options = options
+                .credentials_file(PathBuf::from(credentials_file))
+                .await
+                .map_err(request_error)?;
+        }
+        if let Some(token) = &self.config.token {
+            options = options.token(token.clone());
+        }
+        let client = options
+            .connect(self.config.url.as_str())
+            .await
+            .map_err(request_error)?;
+        async_nats::jetstream::new(client)
+            .get_stream(&self.config.stream)
+            .await
+            .map_err(request_error)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let deliver_policy = deliver_policy(&self.config.deliver_policy)?;
+        let mappings = tables
+            .iter()
+            .map(|table| self.get_table(table))
+            .collect::<Result<Vec<_>, _>>()?;
+        let stream = self.stream().await?;
+
+        let mut messages = vec![];
+        for (table_index, table) in tables.iter().enumerate() {
+            let config = self.table_config(&table.name)?;
+            let durable_name = durable_name(&self.config.durable_name, &table.name);
+            let consumer = stream
+                .get_or_create_consumer(
+                    &durable_name,
+                    pull::Config {
+                        durable_name: Some(durable_name.clone()),
+                        filter_subject: config
+                            .subject
+                            .clone()
+                            .unwrap_or_else(|| table.name.clone()),
+                        deliver_policy,
+                        ack_policy: AckPolicy::Explicit,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(request_error)?;
+            info!(
+                "[{}] Reading table {} with consumer {}",
+                self.name, table.name, durable_name
+            );
+            let table_messages = consumer.messages().await.map_err(request_error)?;
+            messages.push(
+                table_messages
+                    .map(move |message| (table_index, message))
+                    .boxed(),
+            );
+        }
+
+        let mut messages = select_all(messages);
+        let mut seq_no = 0;
+        while let Some((table_index, message)) = messages.next().await {
+            let message = message.map_err(request_error)?;
+            let stream_sequence = message.info().map_err(request_error)?.stream_sequence;
+            let operations = mappings[table_index]
+                .operations(&message.payload)
+                .map_err(|e| {
+                    NatsError::InvalidMessage(message.subject.to_string(), stream_sequence, e)
+                })?;
+            for op in operations {
+                seq_no += 1;
+                ingestor
+                    .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                    .map_err(ConnectorError::IngestorError)?;
+            }
+            message.ack().await.map_err(request_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Connector for NatsConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("string".to_string(), Some(FieldType::String)),
+            ("number".to_string(), Some(FieldType::Float)),
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+            ("object".to_string(), Some(FieldType::Json)),
+            ("array".to_string(), Some(FieldType::Json)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        deliver_policy(&self.config.deliver_policy)?;
+        self.stream().await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .config
+            .tables
+            .iter()
+            .map(|table| TableIdentifier::from_table_name(table.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        for table in tables {
+            if table.schema.is_some() || self.table_config(&table.name).is_err() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    table.schema.as_deref(),
+                    &table.name,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let config = self.table_config(&table.name)?;
+                Ok(TableInfo {
+                    column_names: config
+                        .columns
+                        .iter()
+                        .map(|column| column.name.clone())
+                        .collect(),
+                    schema: table.schema,
+                    name: table.name,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let table = self.get_table(table_info)?;
+                // Updates and deletes only carry the primary key of the old row.
+                let cdc_type = if table.has_changes() {
+                    CdcType::OnlyPK
+                } else {
+                    CdcType::Nothing
+                };
+                Ok(SourceSchema::new(table.schema(), cdc_type))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+fn deliver_policy(name: &str) -> Result<DeliverPolicy, NatsError> {
+    match name {
+        "all" => Ok(DeliverPolicy::All),
+        "new" => Ok(DeliverPolicy::New),
+        "last" => Ok(DeliverPolicy::Last),
+        "last_per_subject" => Ok(DeliverPolicy::LastPerSubject),
+        _ => Err(NatsError::UnknownDeliverPolicy(name.to_string())),
+    }
+}
+
+/// Consumer names can't have `.`, `*`, `>` or whitespace, so anything but letters, digits, `-` and `_` is replaced.
+fn durable_name(prefix: &str, table_name: &str) -> String {
+    format!("{prefix}_{table_name}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn request_error(e: impl Into<BoxedError>) -> NatsError {
+    NatsError::Request(e.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durable_name() {
+        assert_eq!(durable_name("dozer", "orders"), "dozer_orders");
+        assert_eq!(durable_name("dozer", "eu.orders *"), "dozer_eu_orders__");
+    }
+
+    #[test]
+    fn test_deliver_policy() {
+        assert!(matches!(deliver_policy("new"), Ok(DeliverPolicy::New)));
+        assert!(matches!(
+            deliver_policy("first"),
+            Err(NatsError::UnknownDeliverPolicy(name)) if name == "first"
+        ));
+    }
+}
@@ -0,0 +1,6 @@
+//! NATS JetStream subjects, each table reading the JSON messages of a subject with a durable consumer. Messages are
+//! mapped to operations like the payloads of webhooks.
+
+mod connector;
+
+pub use connector::NatsConnector;
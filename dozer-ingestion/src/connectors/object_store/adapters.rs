@@ -79,6 +79,9 @@ impl DozerObjectStore for S3Storage {
                 dozer_types::ingestion_types::TableConfig::Parquet(parquet_config) => {
                     parquet_config.path.clone()
                 }
+                dozer_types::ingestion_types::TableConfig::Jsonl(jsonl_config) => {
+                    jsonl_config.path.clone()
+                }
             }
         } else {
             return Err(ConnectorError::TableNotFound(table.name.clone()));
@@ -129,6 +132,9 @@ impl DozerObjectStore for LocalStorage {
                 dozer_types::ingestion_types::TableConfig::Parquet(parquet_config) => {
                     parquet_config.path.clone()
                 }
+                dozer_types::ingestion_types::TableConfig::Jsonl(jsonl_config) => {
+                    jsonl_config.path.clone()
+                }
             }
         } else {
             return Err(ConnectorError::TableNotFound(table.name.clone()));
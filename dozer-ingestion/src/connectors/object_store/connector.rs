@@ -15,6 +15,7 @@ use crate::ingestion::Ingestor;
 use super::connection::validator::validate_connection;
 use super::csv::csv_table::CsvTable;
 use super::delta::delta_table::DeltaTable;
+use super::jsonl::jsonl_table::JsonlTable;
 use super::parquet::parquet_table::ParquetTable;
 use super::table_watcher::TableWatcher;
 
@@ -146,6 +147,30 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
                                     .map_err(ConnectorError::IngestorError)
                                     .unwrap();
                             }
+                            IngestionMessageKind::SchemaDrift { table_index, drift } => {
+                                ingestor_clone
+                                    .handle_message(IngestionMessage::new_schema_drift(
+                                        0,
+                                        seq_no,
+                                        table_index,
+                                        drift,
+                                    ))
+                                    .map_err(ConnectorError::IngestorError)
+                                    .unwrap();
+                            }
+                            IngestionMessageKind::SchemaChanged { table_index } => {
+                                ingestor_clone
+                                    .handle_message(IngestionMessage::new_schema_changed(
+                                        0,
+                                        seq_no,
+                                        table_index,
+                                    ))
+                                    .map_err(ConnectorError::IngestorError)
+                                    .unwrap();
+                            }
+                            // Files aren't read in transactions.
+                            IngestionMessageKind::TransactionBegin
+                            | IngestionMessageKind::TransactionEnd => (),
                         }
                         seq_no += 1;
                     }
@@ -172,8 +197,7 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
                                 handles.push(
                                     table
                                         .snapshot(table_index, table_info, sender.clone())
-                                        .await
-                                        .unwrap(),
+                                        .await?,
                                 );
                             }
                             dozer_types::ingestion_types::TableConfig::Delta(config) => {
@@ -192,6 +216,14 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
                                         .await?,
                                 );
                             }
+                            dozer_types::ingestion_types::TableConfig::Jsonl(config) => {
+                                let table = JsonlTable::new(config.clone(), self.config.clone());
+                                handles.push(
+                                    table
+                                        .snapshot(table_index, table_info, sender.clone())
+                                        .await?,
+                                );
+                            }
                         }
                     }
                 }
@@ -211,16 +243,14 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
 
         for (table_index, table_info) in tables.iter().enumerate() {
             for table_config in self.config.tables() {
-                if table_info.name == table_config.name {
+                // Tables that aren't watched only ingest the snapshot.
+                if table_info.name == table_config.name && table_config.watch != Some(false) {
                     if let Some(config) = &table_config.config {
                         match config {
                             dozer_types::ingestion_types::TableConfig::CSV(config) => {
                                 let mut table = CsvTable::new(config.clone(), self.config.clone());
                                 table.update_state = state_hash.get(&table_index).unwrap().clone();
-                                table
-                                    .watch(table_index, table_info, sender.clone())
-                                    .await
-                                    .unwrap();
+                                table.watch(table_index, table_info, sender.clone()).await?;
                             }
                             dozer_types::ingestion_types::TableConfig::Delta(config) => {
                                 let table = DeltaTable::new(config.clone(), self.config.clone());
@@ -232,6 +262,12 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
                                 table.update_state = state_hash.get(&table_index).unwrap().clone();
                                 table.watch(table_index, table_info, sender.clone()).await?;
                             }
+                            dozer_types::ingestion_types::TableConfig::Jsonl(config) => {
+                                let mut table =
+                                    JsonlTable::new(config.clone(), self.config.clone());
+                                table.update_state = state_hash.get(&table_index).unwrap().clone();
+                                table.watch(table_index, table_info, sender.clone()).await?;
+                            }
                         }
                     }
                 }
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use deltalake::{
+    arrow::datatypes::SchemaRef,
+    datafusion::{
+        datasource::listing::{ListingOptions, ListingTableUrl},
+        prelude::SessionContext,
+    },
+    Path as DeltaPath,
+};
+use dozer_types::{
+    chrono::{DateTime, Utc},
+    ingestion_types::IngestionMessageKind,
+    tracing::info,
+};
+use futures::StreamExt;
+use object_store::{ObjectMeta, ObjectStore};
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
+use tonic::async_trait;
+
+use crate::{
+    connectors::{
+        object_store::{
+            adapters::DozerObjectStore,
+            helper::{declared_schema, is_marker_file_exist, map_listing_options},
+            table_reader::TableReader,
+            table_watcher::{FileInfo, TableWatcher},
+        },
+        TableInfo,
+    },
+    errors::{ConnectorError, ObjectStoreConnectorError, ObjectStoreObjectError},
+};
+
+const WATCHER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The settings of a table whose files are read by a [`FileTable`], implemented by the config of each file format.
+pub trait FileConfig: Clone + Send + Sync {
+    fn extension(&self) -> &str;
+
+    fn marker_file(&self) -> bool;
+
+    fn marker_extension(&self) -> &str;
+}
+
+/// A table made of the files in a folder of an object store. Files are read in the order they were last modified,
+/// and only once their marker file exists if the table uses marker files.
+pub struct FileTable<T: DozerObjectStore + Send, C: FileConfig> {
+    table_config: C,
+    store_config: T,
+    pub update_state: HashMap<DeltaPath, DateTime<Utc>>,
+}
+
+impl<T: DozerObjectStore + Send, C: FileConfig> FileTable<T, C> {
+    pub fn new(table_config: C, store_config: T) -> Self {
+        Self {
+            table_config,
+            store_config,
+            update_state: HashMap::new(),
+        }
+    }
+
+    fn files(
+        &self,
+        table_index: usize,
+        table: &TableInfo,
+    ) -> Result<TableFiles<T>, ConnectorError> {
+        let params = self.store_config.table_params(&table.name)?;
+        let store = Arc::new(params.object_store);
+
+        let listing_options = map_listing_options(&params.data_fusion_table)
+            .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+        let schema = declared_schema(&params.data_fusion_table)
+            .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+
+        let ctx = SessionContext::new();
+        ctx.runtime_env()
+            .register_object_store(&params.url, store.clone());
+
+        let marker_extension = match self.table_config.marker_file() {
+            true => self.table_config.marker_extension().to_string(),
+            false => String::new(),
+        };
+
+        Ok(TableFiles {
+            table_index,
+            table: table.clone(),
+            store,
+            source_folder: params.folder,
+            base_path: params.table_path,
+            extension: self.table_config.extension().to_string(),
+            marker_extension,
+            ctx,
+            listing_options,
+            schema,
+        })
+    }
+}
+
+#[async_trait]
+impl<T: DozerObjectStore + Send, C: FileConfig> TableWatcher for FileTable<T, C> {
+    async fn snapshot(
+        &self,
+        table_index: usize,
+        table: &TableInfo,
+        sender: Sender<Result<Option<IngestionMessageKind>, ObjectStoreConnectorError>>,
+    ) -> Result<JoinHandle<(usize, HashMap<object_store::path::Path, DateTime<Utc>>)>, ConnectorError>
+    {
+        let files = self.files(table_index, table)?;
+        let mut update_state = self.update_state.clone();
+
+        Ok(tokio::spawn(async move {
+            if let Err(e) = files.read_new_files(&mut update_state, &sender).await {
+                let _ = sender.send(Err(e)).await;
+            }
+            (table_index, update_state)
+        }))
+    }
+
+    async fn ingest(
+        &self,
+        table_index: usize,
+        table: &TableInfo,
+        sender: Sender<Result<Option<IngestionMessageKind>, ObjectStoreConnectorError>>,
+    ) -> Result<(), ConnectorError> {
+        let files = self.files(table_index, table)?;
+        // Continue from the table state after snapshot
+        let mut update_state = self.update_state.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = files.read_new_files(&mut update_state, &sender).await {
+                    // The connector stops on errors, so there is no point in watching any further.
+                    let _ = sender.send(Err(e)).await;
+                    return;
+                }
+                tokio::time::sleep(WATCHER_INTERVAL).await;
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// What is needed to list and read the files of a table, owned so it can be moved into the reading task.
+struct TableFiles<T: DozerObjectStore> {
+    table_index: usize,
+    table: TableInfo,
+    store: Arc<T::ObjectStore>,
+    source_folder: String,
+    base_path: String,
+    extension: String,
+    /// Empty if the table doesn't use marker files.
+    marker_extension: String,
+    ctx: SessionContext,
+    listing_options: ListingOptions,
+    schema: Option<SchemaRef>,
+}
+
+impl<T: DozerObjectStore> TableFiles<T> {
+    /// Reads the files that were added since `update_state` was last updated, and records them in it.
+    async fn read_new_files(
+        &self,
+        update_state: &mut HashMap<DeltaPath, DateTime<Utc>>,
+        sender: &Sender<Result<Option<IngestionMessageKind>, ObjectStoreConnectorError>>,
+    ) -> Result<(), ObjectStoreConnectorError> {
+        let listing_error =
+            |e| ObjectStoreObjectError::ListingObjectsError(self.source_folder.clone(), e);
+
+        // List objects in the bucket with the specified prefix
+        let mut stream = self
+            .store
+            .list(Some(&DeltaPath::from(self.source_folder.as_str())))
+            .await
+            .map_err(listing_error)?;
+
+        let mut new_files = vec![];
+        let mut new_marker_files = vec![];
+
+        while let Some(item) = stream.next().await {
+            // Check if any objects have been added or modified
+            let object = item.map_err(listing_error)?;
+
+            if let Some(last_modified) = update_state.get_mut(&object.location) {
+                // Scenario 1: Update on existing file
+                if *last_modified < object.last_modified {
+                    info!(
+                        "Source Object has been modified: {:?}, {:?}",
+                        object.location, object.last_modified
+                    );
+                }
+                continue;
+            }
+
+            let file_path = object.location.to_string();
+            // Skip the source folder
+            if file_path == self.source_folder {
+                continue;
+            }
+
+            if file_path.ends_with(self.extension.as_str()) {
+                // Scenario 2: New file added
+                info!(
+                    "Source Object has been added: {:?}, {:?}",
+                    object.location, object.last_modified
+                );
+
+                new_files.push(self.file_info(&file_path, &object));
+                if self.marker_extension.is_empty() {
+                    update_state.insert(object.location, object.last_modified);
+                }
+            } else if !self.marker_extension.is_empty()
+                && file_path.ends_with(self.marker_extension.as_str())
+            {
+                // Scenario 3: New marker file added
+                info!(
+                    "Source Object Marker has been added: {:?}, {:?}",
+                    object.location, object.last_modified
+                );
+
+                new_marker_files.push(self.file_info(&file_path, &object));
+                update_state.insert(object.location, object.last_modified);
+            }
+            // Skip files that do not match the extension nor marker extension
+        }
+
+        new_files.sort();
+        for file in &new_files {
+            if !self.marker_extension.is_empty()
+                && !is_marker_file_exist(new_marker_files.clone(), file)
+            {
+                continue;
+            }
+
+            let file_path = ListingTableUrl::parse(&file.name).map_err(|e| {
+                ObjectStoreObjectError::ListingPathParsingError(file.name.clone(), e)
+            })?;
+
+            TableReader::<T>::read(
+                self.table_index,
+                self.ctx.clone(),
+                file_path,
+                self.listing_options.clone(),
+                self.schema.clone(),
+                &self.table,
+                sender.clone(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    fn file_info(&self, file_path: &str, object: &ObjectMeta) -> FileInfo {
+        // Remove base folder from relative path
+        let mut components = Path::new(file_path).components();
+        components.next();
+        FileInfo {
+            name: self.base_path.clone() + &components.as_path().to_string_lossy(),
+            last_modified: object.last_modified.timestamp(),
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use crate::connectors::object_store::table_watcher::FileInfo;
 use crate::errors::ObjectStoreObjectError;
+use deltalake::arrow::datatypes::{Field, Schema, SchemaRef};
 use deltalake::datafusion::datasource::file_format::csv::CsvFormat;
+use deltalake::datafusion::datasource::file_format::json::JsonFormat;
 use deltalake::datafusion::datasource::file_format::parquet::ParquetFormat;
 use deltalake::datafusion::datasource::listing::ListingOptions;
-use dozer_types::ingestion_types::Table;
+use dozer_types::arrow_types::to_arrow::map_field_type;
+use dozer_types::ingestion_types::{Table, TableConfig};
+use dozer_types::types::FieldType;
 use std::sync::Arc;
 
 pub fn map_listing_options(
@@ -24,6 +28,11 @@ pub fn map_listing_options(
                 Ok(ListingOptions::new(Arc::new(format))
                     .with_file_extension(parquet.extension.clone()))
             }
+            dozer_types::ingestion_types::TableConfig::Jsonl(jsonl) => {
+                let format = JsonFormat::default();
+                Ok(ListingOptions::new(Arc::new(format))
+                    .with_file_extension(jsonl.extension.clone()))
+            }
         }
     } else {
         Err(ObjectStoreObjectError::FileFormatUnsupportedError(
@@ -32,6 +41,32 @@ pub fn map_listing_options(
     }
 }
 
+/// The schema declared by the columns of a table's config, so it isn't inferred from the files. Parquet files carry
+/// their schema, so only CSV and JSONL tables declare one.
+pub fn declared_schema(table: &Table) -> Result<Option<SchemaRef>, ObjectStoreObjectError> {
+    let columns = match &table.config {
+        Some(TableConfig::CSV(csv)) => &csv.columns,
+        Some(TableConfig::Jsonl(jsonl)) => &jsonl.columns,
+        _ => return Ok(None),
+    };
+    if columns.is_empty() {
+        return Ok(None);
+    }
+    let fields = columns
+        .iter()
+        .map(|column| {
+            let typ = FieldType::try_from(column.typ.as_str())
+                .map_err(|e| ObjectStoreObjectError::InvalidColumnType(column.name.clone(), e))?;
+            Ok(Field::new(
+                column.name.clone(),
+                map_field_type(typ),
+                column.nullable,
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(Arc::new(Schema::new(fields))))
+}
+
 pub fn is_marker_file_exist(marker_files: Vec<FileInfo>, info: &FileInfo) -> bool {
     for marker_file in marker_files {
         let marker_file_name = match marker_file.name.rsplit_once('.') {
@@ -0,0 +1,19 @@
+use dozer_types::ingestion_types::JsonlConfig;
+
+use crate::connectors::object_store::file_table::{FileConfig, FileTable};
+
+pub type JsonlTable<T> = FileTable<T, JsonlConfig>;
+
+impl FileConfig for JsonlConfig {
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_file(&self) -> bool {
+        self.marker_file
+    }
+
+    fn marker_extension(&self) -> &str {
+        &self.marker_extension
+    }
+}
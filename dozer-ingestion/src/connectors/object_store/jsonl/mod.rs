@@ -0,0 +1 @@
+pub(crate) mod jsonl_table;
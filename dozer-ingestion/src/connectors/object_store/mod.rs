@@ -3,7 +3,9 @@ mod connection;
 pub mod connector;
 mod csv;
 mod delta;
+mod file_table;
 mod helper;
+mod jsonl;
 mod parquet;
 mod schema_helper;
 pub mod schema_mapper;
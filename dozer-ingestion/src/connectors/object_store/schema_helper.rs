@@ -49,6 +49,7 @@ pub fn map_schema_to_dozer<'a, I: Iterator<Item = &'a Arc<Field>>>(
                 typ: mapped_field_type,
                 nullable: field.is_nullable(),
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             })
         })
         .collect()
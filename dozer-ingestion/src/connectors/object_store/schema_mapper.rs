@@ -1,10 +1,12 @@
 use crate::connectors::object_store::adapters::DozerObjectStore;
+use crate::connectors::object_store::helper::declared_schema;
 use crate::connectors::object_store::schema_helper::map_schema_to_dozer;
 use crate::connectors::{CdcType, ListOrFilterColumns, SourceSchema, SourceSchemaResult};
 use crate::errors::ObjectStoreObjectError::ListingPathParsingError;
 use crate::errors::{ConnectorError, ObjectStoreConnectorError};
 use deltalake::arrow::datatypes::SchemaRef;
 use deltalake::datafusion::datasource::file_format::csv::CsvFormat;
+use deltalake::datafusion::datasource::file_format::json::JsonFormat;
 use deltalake::datafusion::datasource::file_format::parquet::ParquetFormat;
 use deltalake::datafusion::datasource::listing::{ListingOptions, ListingTableUrl};
 use deltalake::datafusion::prelude::SessionContext;
@@ -60,6 +62,12 @@ async fn get_table_schema(
                     .with_file_extension(table_config.extension.clone());
                 get_object_schema(table, config, listing_options).await
             }
+            dozer_types::ingestion_types::TableConfig::Jsonl(table_config) => {
+                let format = JsonFormat::default();
+                let listing_options = ListingOptions::new(Arc::new(format))
+                    .with_file_extension(table_config.extension.clone());
+                get_object_schema(table, config, listing_options).await
+            }
             dozer_types::ingestion_types::TableConfig::Delta(_table_config) => {
                 get_delta_schema(table, config).await
             }
@@ -92,6 +100,13 @@ async fn get_object_schema(
         ))
     })?;
 
+    if let Some(declared_schema) = declared_schema(&params.data_fusion_table)
+        .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?
+    {
+        let schema = map_schema(declared_schema, table)?;
+        return Ok(SourceSchema::new(schema, CdcType::Nothing));
+    }
+
     let ctx = SessionContext::new();
 
     ctx.runtime_env()
@@ -6,6 +6,7 @@ use crate::errors::ObjectStoreTableReaderError::{
 };
 use crate::errors::{ConnectorError, ObjectStoreConnectorError};
 use crate::ingestion::Ingestor;
+use deltalake::arrow::datatypes::SchemaRef;
 use deltalake::datafusion::datasource::listing::{
     ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
 };
@@ -34,13 +35,17 @@ impl<T: Clone + Send + Sync> TableReader<T> {
         ctx: SessionContext,
         table_path: ListingTableUrl,
         listing_options: ListingOptions,
+        schema: Option<SchemaRef>,
         table: &TableInfo,
         sender: Sender<Result<Option<IngestionMessageKind>, ObjectStoreConnectorError>>,
     ) -> Result<(), ObjectStoreConnectorError> {
-        let resolved_schema = listing_options
-            .infer_schema(&ctx.state(), &table_path)
-            .await
-            .map_err(ObjectStoreConnectorError::InternalDataFusionError)?;
+        let resolved_schema = match schema {
+            Some(schema) => schema,
+            None => listing_options
+                .infer_schema(&ctx.state(), &table_path)
+                .await
+                .map_err(ObjectStoreConnectorError::InternalDataFusionError)?,
+        };
 
         let fields = resolved_schema.all_fields();
 
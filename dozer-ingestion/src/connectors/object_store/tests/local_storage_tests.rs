@@ -7,7 +7,7 @@ use dozer_types::ingestion_types::LocalDetails;
 use dozer_types::node::OpIdentifier;
 
 use crate::connectors::object_store::helper::map_listing_options;
-use crate::connectors::object_store::tests::test_utils::get_local_storage_config;
+use crate::connectors::object_store::tests::test_utils::{get_local_storage_config, with_columns};
 use crate::errors::ConnectorError::InitializationError;
 use crate::errors::ObjectStoreObjectError;
 use dozer_types::types::{Field, FieldType, Operation};
@@ -62,6 +62,54 @@ async fn test_get_schema_of_csv() {
     assert_eq!(fields.get(8).unwrap().typ, FieldType::String);
 }
 
+#[tokio::test]
+async fn test_get_schema_of_jsonl() {
+    let local_storage = get_local_storage_config("jsonl", "");
+
+    let connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.get(0).unwrap();
+
+    let fields = schema.schema.fields.clone();
+    assert_eq!(fields.len(), 5);
+    assert_eq!(fields.get(0).unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(1).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(2).unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(3).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(4).unwrap().typ, FieldType::Boolean);
+}
+
+#[tokio::test]
+async fn test_get_declared_schema_of_csv() {
+    let local_storage = with_columns(
+        get_local_storage_config("csv", ""),
+        &[("id", "uint"), ("item", "text"), ("name", "string")],
+    );
+
+    let connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.get(0).unwrap();
+
+    let fields = schema
+        .schema
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.typ))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", FieldType::UInt),
+            ("item", FieldType::Text),
+            ("name", FieldType::String)
+        ]
+    );
+
+    let local_storage = with_columns(get_local_storage_config("csv", ""), &[("id", "uuid")]);
+    let connector = ObjectStoreConnector::new(local_storage);
+    assert!(connector.list_all_schemas().await.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_read_parquet_file() {
     let local_storage = get_local_storage_config("parquet", "");
@@ -1,5 +1,6 @@
 use dozer_types::ingestion_types::{
-    CsvConfig, LocalDetails, LocalStorage, ParquetConfig, Table, TableConfig,
+    CsvConfig, FileColumn, JsonlConfig, LocalDetails, LocalStorage, ParquetConfig, Table,
+    TableConfig,
 };
 use std::path::PathBuf;
 
@@ -19,6 +20,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: String::new(),
                     })),
                     name: format!("all_types_{typ}"),
+                    watch: None,
                 }],
             },
             &_ => LocalStorage {
@@ -33,6 +35,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: String::from(".marker"),
                     })),
                     name: format!("{prefix}_{typ}"),
+                    watch: None,
                 }],
             },
         },
@@ -47,8 +50,10 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         path: format!("all_types_{typ}"),
                         marker_file: false,
                         marker_extension: String::new(),
+                        columns: vec![],
                     })),
                     name: format!("all_types_{typ}"),
+                    watch: None,
                 }],
             },
             &_ => LocalStorage {
@@ -61,11 +66,29 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         path: format!("{prefix}_{typ}"),
                         marker_file: true,
                         marker_extension: String::from(".marker"),
+                        columns: vec![],
                     })),
                     name: format!("{prefix}_{typ}"),
+                    watch: None,
                 }],
             },
         },
+        "jsonl" => LocalStorage {
+            details: Some(LocalDetails {
+                path: p.to_str().unwrap().to_string(),
+            }),
+            tables: vec![Table {
+                config: Some(TableConfig::Jsonl(JsonlConfig {
+                    extension: String::from(".jsonl"),
+                    path: format!("all_types_{typ}"),
+                    marker_file: false,
+                    marker_extension: String::new(),
+                    columns: vec![],
+                })),
+                name: format!("all_types_{typ}"),
+                watch: None,
+            }],
+        },
         &_ => LocalStorage {
             details: Some(LocalDetails {
                 path: p.to_str().unwrap().to_string(),
@@ -73,7 +96,26 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
             tables: vec![Table {
                 config: None,
                 name: String::new(),
+                watch: None,
             }],
         },
     }
 }
+
+/// Declares the columns of the table, instead of inferring them from the files.
+pub fn with_columns(mut local_storage: LocalStorage, columns: &[(&str, &str)]) -> LocalStorage {
+    let columns = columns
+        .iter()
+        .map(|(name, typ)| FileColumn {
+            name: name.to_string(),
+            typ: typ.to_string(),
+            nullable: true,
+        })
+        .collect();
+    match &mut local_storage.tables[0].config {
+        Some(TableConfig::CSV(config)) => config.columns = columns,
+        Some(TableConfig::Jsonl(config)) => config.columns = columns,
+        _ => panic!("Only csv and jsonl tables declare columns"),
+    }
+    local_storage
+}
@@ -0,0 +1,238 @@
+use std::thread;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{IngestionMessage, OracleConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use oracle::Connection;
+use tonic::async_trait;
+
+use super::logminer::{current_scn, LogMiner};
+use super::schema::{self, quote, read_value, Table, TYPES};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, OracleError};
+use crate::ingestion::Ingestor;
+
+/// Formats of values in redo statements, and time zone of timestamps without one, as expected by
+/// `schema::parse_value`.
+const SESSION_SETTINGS: &[&str] = &[
+    "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD HH24:MI:SS'",
+    "ALTER SESSION SET NLS_TIMESTAMP_FORMAT = 'YYYY-MM-DD HH24:MI:SS.FF'",
+    "ALTER SESSION SET NLS_TIMESTAMP_TZ_FORMAT = 'YYYY-MM-DD HH24:MI:SS.FF TZH:TZM'",
+    "ALTER SESSION SET NLS_NUMERIC_CHARACTERS = '.,'",
+    "ALTER SESSION SET TIME_ZONE = 'UTC'",
+];
+
+/// Snapshots tables as of an SCN, then follows their changes from that SCN with LogMiner.
+#[derive(Debug)]
+pub struct OracleConnector {
+    name: String,
+    config: OracleConfig,
+}
+
+impl OracleConnector {
+    pub fn new(name: String, config: OracleConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn connect(&self) -> Result<Connection, OracleError> {
+        let connection = Connection::connect(
+            &self.config.user,
+            &self.config.password,
+            format!(
+                "//{}:{}/{}",
+                self.config.host, self.config.port, self.config.service
+            ),
+        )?;
+        for sql in SESSION_SETTINGS {
+            connection.execute(sql, &[])?;
+        }
+        Ok(connection)
+    }
+
+    /// Tables without a schema belong to the user.
+    fn owner(&self, schema: Option<&str>) -> String {
+        schema.map_or_else(|| self.config.user.to_uppercase(), str::to_string)
+    }
+
+    fn get_table(
+        &self,
+        connection: &Connection,
+        table_info: &TableInfo,
+    ) -> Result<Table, ConnectorError> {
+        Ok(schema::get_table(
+            connection,
+            &self.owner(table_info.schema.as_deref()),
+            &table_info.name,
+            &table_info.column_names,
+        )?)
+    }
+
+    /// Blocks, like the client does.
+    fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let connection = self.connect()?;
+        let tables = tables
+            .iter()
+            .map(|table_info| self.get_table(&connection, table_info))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let scn = current_scn(&connection)?;
+        info!("[{}] Snapshotting as of SCN {}", self.name, scn);
+        snapshot(&connection, &tables, scn, ingestor)?;
+
+        let mut log_miner = LogMiner::new(&connection, tables, scn)?;
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        loop {
+            log_miner.mine(&connection, ingestor)?;
+            thread::sleep(poll_interval);
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for OracleConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let connection = self.connect()?;
+        let (log_mode, supplemental_logging) = connection
+            .query_row_as::<(String, String)>(
+                "SELECT LOG_MODE, SUPPLEMENTAL_LOG_DATA_ALL FROM V$DATABASE",
+                &[],
+            )
+            .map_err(OracleError::from)?;
+        if log_mode != "ARCHIVELOG" {
+            return Err(OracleError::NotInArchiveLogMode(log_mode).into());
+        }
+        if supplemental_logging != "YES" {
+            return Err(OracleError::SupplementalLoggingDisabled.into());
+        }
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let owner = self.owner(None);
+        Ok(schema::list_tables(&self.connect()?, &owner)?
+            .into_iter()
+            .map(|name| TableIdentifier::new(Some(owner.clone()), name))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let connection = self.connect()?;
+        for table in tables {
+            let owner = self.owner(table.schema.as_deref());
+            if schema::list_columns(&connection, &owner, &table.name)?.is_empty() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    Some(&owner),
+                    &table.name,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let connection = self.connect()?;
+        tables
+            .into_iter()
+            .map(|table| -> Result<TableInfo, ConnectorError> {
+                let column_names = schema::list_columns(
+                    &connection,
+                    &self.owner(table.schema.as_deref()),
+                    &table.name,
+                )?;
+                Ok(TableInfo {
+                    schema: table.schema,
+                    name: table.name,
+                    column_names,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let connection = self.connect()?;
+        Ok(table_infos
+            .iter()
+            .map(|table_info| -> SourceSchemaResult {
+                let table = self.get_table(&connection, table_info)?;
+                Ok(SourceSchema::new(table.schema(), CdcType::FullChanges))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables)
+    }
+}
+
+/// Sends the rows of `tables` as of `scn`.
+fn snapshot(
+    connection: &Connection,
+    tables: &[Table],
+    scn: u64,
+    ingestor: &Ingestor,
+) -> Result<(), ConnectorError> {
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+        .map_err(ConnectorError::IngestorError)?;
+
+    let mut seq_no = 0;
+    for (table_index, table) in tables.iter().enumerate() {
+        let columns = table
+            .columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT {columns} FROM {} AS OF SCN {scn}",
+            table.quoted_name()
+        );
+        for row in connection.query(&sql, &[]).map_err(OracleError::from)? {
+            let row = row.map_err(OracleError::from)?;
+            let values = table
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| read_value(&row, index, column))
+                .collect::<Result<_, _>>()?;
+            seq_no += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    0,
+                    seq_no,
+                    table_index,
+                    Operation::Insert {
+                        new: Record::new(values),
+                    },
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+    }
+
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_done(scn, 0))
+        .map_err(ConnectorError::IngestorError)
+}
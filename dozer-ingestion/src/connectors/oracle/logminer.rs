@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::Operation;
+use oracle::Connection;
+
+use super::redo::{parse_redo, redo_operation};
+use super::schema::Table;
+use crate::errors::{ConnectorError, OracleError};
+use crate::ingestion::Ingestor;
+
+/// `OPERATION_CODE`s of `V$LOGMNR_CONTENTS`.
+const INSERT: u32 = 1;
+const DELETE: u32 = 2;
+const UPDATE: u32 = 3;
+const COMMIT: u32 = 7;
+const ROLLBACK: u32 = 36;
+
+/// Follows the changes of tables in the redo logs.
+///
+/// LogMiner is started on the range of SCNs since the last mining, without `COMMITTED_DATA_ONLY`, so transactions that
+/// span several ranges are not lost. Their changes are kept until they commit, and then sent with the commit SCN as
+/// transaction id.
+#[derive(Debug)]
+pub struct LogMiner {
+    /// Tables by owner and name, with their index.
+    tables: HashMap<(String, String), (usize, Table)>,
+    /// Changes of open transactions by transaction id, with the id of the changed row.
+    transactions: HashMap<String, Vec<(String, usize, Operation)>>,
+    /// Transactions committed at or before this SCN are already in the snapshot.
+    snapshot_scn: u64,
+    /// Changes after this SCN are mined next.
+    last_scn: u64,
+}
+
+impl LogMiner {
+    /// Starts mining before the oldest transaction open at `snapshot_scn`, whose earlier changes are not in the snapshot
+    /// if it commits after it.
+    pub fn new(
+        connection: &Connection,
+        tables: Vec<Table>,
+        snapshot_scn: u64,
+    ) -> Result<Self, OracleError> {
+        let oldest_open = connection
+            .query_row_as::<Option<u64>>("SELECT MIN(START_SCN) FROM V$TRANSACTION", &[])?;
+        let last_scn =
+            oldest_open.map_or(snapshot_scn, |scn| scn.saturating_sub(1).min(snapshot_scn));
+        Ok(Self {
+            tables: tables
+                .into_iter()
+                .enumerate()
+                .map(|(index, table)| ((table.owner.clone(), table.name.clone()), (index, table)))
+                .collect(),
+            transactions: HashMap::new(),
+            snapshot_scn,
+            last_scn,
+        })
+    }
+
+    /// Mines the changes up to the current SCN, sending those of committed transactions to `ingestor`.
+    pub fn mine(
+        &mut self,
+        connection: &Connection,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let current_scn = current_scn(connection)?;
+        if current_scn <= self.last_scn {
+            return Ok(());
+        }
+
+        add_log_files(connection, self.last_scn)?;
+        connection
+            .execute(
+                "BEGIN DBMS_LOGMNR.START_LOGMNR(STARTSCN => :1, ENDSCN => :2, \
+                    OPTIONS => DBMS_LOGMNR.DICT_FROM_ONLINE_CATALOG); END;",
+                &[&(self.last_scn + 1), &current_scn],
+            )
+            .map_err(OracleError::from)?;
+        let result = self.read_contents(connection, ingestor);
+        connection
+            .execute("BEGIN DBMS_LOGMNR.END_LOGMNR; END;", &[])
+            .map_err(OracleError::from)?;
+        result?;
+
+        self.last_scn = current_scn;
+        Ok(())
+    }
+
+    fn read_contents(
+        &mut self,
+        connection: &Connection,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let rows = connection
+            .query_as::<(
+                u64,
+                u32,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                u32,
+                u32,
+                Option<String>,
+            )>(
+                "SELECT SCN, OPERATION_CODE, RAWTOHEX(XID), SEG_OWNER, TABLE_NAME, ROW_ID, ROLLBACK, CSF, SQL_REDO \
+                    FROM V$LOGMNR_CONTENTS WHERE OPERATION_CODE IN (1, 2, 3, 7, 36)",
+                &[],
+            )
+            .map_err(OracleError::from)?;
+
+        // Long statements are split over rows, all but the last having `CSF` set.
+        let mut redo = String::new();
+        for row in rows {
+            let (scn, operation_code, xid, owner, table_name, row_id, rollback, csf, sql_redo) =
+                row.map_err(OracleError::from)?;
+            match operation_code {
+                COMMIT => {
+                    let Some(changes) = self.transactions.remove(&xid) else {
+                        continue;
+                    };
+                    if scn <= self.snapshot_scn {
+                        continue;
+                    }
+                    for (seq_no, (_, table_index, op)) in changes.into_iter().enumerate() {
+                        ingestor
+                            .handle_message(IngestionMessage::new_op(
+                                scn,
+                                seq_no as u64,
+                                table_index,
+                                op,
+                            ))
+                            .map_err(ConnectorError::IngestorError)?;
+                    }
+                }
+                ROLLBACK => {
+                    self.transactions.remove(&xid);
+                }
+                INSERT | DELETE | UPDATE => {
+                    let (Some(owner), Some(table_name)) = (owner, table_name) else {
+                        continue;
+                    };
+                    let Some((table_index, table)) = self.tables.get(&(owner, table_name)) else {
+                        continue;
+                    };
+                    redo.push_str(sql_redo.as_deref().unwrap_or_default());
+                    if csf == 1 {
+                        continue;
+                    }
+                    let sql = std::mem::take(&mut redo);
+
+                    let row_id = row_id.unwrap_or_default();
+                    let changes = self.transactions.entry(xid).or_default();
+                    if rollback == 1 {
+                        // Undoes the last change of the row, as the transaction rolled back to a savepoint.
+                        if let Some(position) = changes.iter().rposition(|(id, ..)| *id == row_id) {
+                            changes.remove(position);
+                        }
+                        continue;
+                    }
+                    let op = redo_operation(&table.columns, &parse_redo(&sql)?, &sql)?;
+                    changes.push((row_id, *table_index, op));
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn current_scn(connection: &Connection) -> Result<u64, OracleError> {
+    Ok(connection.query_row_as::<u64>("SELECT CURRENT_SCN FROM V$DATABASE", &[])?)
+}
+
+/// Adds the online and archived logs with changes after `scn`. Archived copies of online logs are skipped.
+fn add_log_files(connection: &Connection, scn: u64) -> Result<(), OracleError> {
+    let files = connection
+        .query_as::<String>(
+            "SELECT MIN(F.MEMBER) FROM V$LOG L JOIN V$LOGFILE F ON L.GROUP# = F.GROUP# \
+                WHERE L.NEXT_CHANGE# > :1 GROUP BY L.GROUP# \
+            UNION ALL \
+            SELECT MIN(A.NAME) FROM V$ARCHIVED_LOG A \
+                WHERE A.NEXT_CHANGE# > :2 AND A.NAME IS NOT NULL AND A.SEQUENCE# NOT IN (SELECT SEQUENCE# FROM V$LOG) \
+                GROUP BY A.SEQUENCE#",
+            &[&scn, &scn],
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (index, file) in files.iter().enumerate() {
+        let sql = if index == 0 {
+            "BEGIN DBMS_LOGMNR.ADD_LOGFILE(LOGFILENAME => :1, OPTIONS => DBMS_LOGMNR.NEW); END;"
+        } else {
+            "BEGIN DBMS_LOGMNR.ADD_LOGFILE(LOGFILENAME => :1, OPTIONS => DBMS_LOGMNR.ADDFILE); END;"
+        };
+        connection.execute(sql, &[file])?;
+    }
+    Ok(())
+}
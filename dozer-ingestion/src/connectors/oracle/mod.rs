@@ -0,0 +1,9 @@
+mod connector;
+mod logminer;
+mod redo;
+mod schema;
+
+pub use connector::OracleConnector;
+
+#[cfg(test)]
+mod tests;
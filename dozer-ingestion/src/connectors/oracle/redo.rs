@@ -0,0 +1,319 @@
+//! Parsing of the statements LogMiner reconstructs from the redo logs, e.g.
+//! `update "HR"."EMP" set "NAME" = 'b' where "ID" = '1' and "NAME" = 'a' and ROWID = 'AAAR3sAAEAAAACXAAA';`.
+//!
+//! With supplemental logging of all columns, the `where` clause of updates and deletes has every column.
+
+use dozer_types::types::{Field, Operation, Record};
+
+use super::schema::{parse_value, Column};
+use crate::errors::OracleError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedoValue {
+    Null,
+    /// A string or number.
+    Literal(String),
+    /// A call like `TO_DATE('2023-01-01 00:00:00', 'YYYY-MM-DD HH24:MI:SS')` or `HEXTORAW('0a0b')`.
+    Function(String, Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedoStatement {
+    Insert {
+        values: Vec<(String, RedoValue)>,
+    },
+    Update {
+        set: Vec<(String, RedoValue)>,
+        condition: Vec<(String, RedoValue)>,
+    },
+    Delete {
+        condition: Vec<(String, RedoValue)>,
+    },
+}
+
+pub fn parse_redo(sql: &str) -> Result<RedoStatement, OracleError> {
+    Parser::new(sql)
+        .statement()
+        .ok_or_else(|| OracleError::InvalidRedo(sql.to_string()))
+}
+
+/// Converts a redo statement of a table with `columns` to an operation.
+pub fn redo_operation(
+    columns: &[Column],
+    statement: &RedoStatement,
+    sql: &str,
+) -> Result<Operation, OracleError> {
+    Ok(match statement {
+        RedoStatement::Insert { values } => Operation::Insert {
+            new: redo_record(columns, values, &[], sql)?,
+        },
+        RedoStatement::Update { set, condition } => Operation::Update {
+            old: redo_record(columns, condition, &[], sql)?,
+            new: redo_record(columns, condition, set, sql)?,
+        },
+        RedoStatement::Delete { condition } => Operation::Delete {
+            old: redo_record(columns, condition, &[], sql)?,
+        },
+    })
+}
+
+/// Builds a record from column values, taking those in `changes` over those in `values`.
+fn redo_record(
+    columns: &[Column],
+    values: &[(String, RedoValue)],
+    changes: &[(String, RedoValue)],
+    sql: &str,
+) -> Result<Record, OracleError> {
+    let values = columns
+        .iter()
+        .map(|column| {
+            let (_, value) = changes
+                .iter()
+                .chain(values)
+                .find(|(name, _)| *name == column.name)
+                .ok_or_else(|| {
+                    OracleError::MissingRedoColumn(column.name.clone(), sql.to_string())
+                })?;
+            redo_field(column, value)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Record::new(values))
+}
+
+pub fn redo_field(column: &Column, value: &RedoValue) -> Result<Field, OracleError> {
+    match value {
+        RedoValue::Null => Ok(Field::Null),
+        RedoValue::Literal(value) => parse_value(column, value),
+        RedoValue::Function(name, args) => match (name.as_str(), args.first()) {
+            (
+                "TO_DATE" | "TO_TIMESTAMP" | "TO_TIMESTAMP_TZ" | "HEXTORAW" | "TO_BINARY_FLOAT"
+                | "TO_BINARY_DOUBLE",
+                Some(value),
+            ) => parse_value(column, value),
+            _ => Err(OracleError::InvalidValue(
+                column.name.clone(),
+                format!("{name}({})", args.join(", ")),
+            )),
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A double quoted identifier.
+    Identifier(String),
+    /// A single quoted string.
+    String(String),
+    /// A keyword, function name, number or unquoted identifier.
+    Word(String),
+    Symbol(char),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(sql: &str) -> Self {
+        Self {
+            tokens: tokenize(sql).unwrap_or_default(),
+            position: 0,
+        }
+    }
+
+    fn statement(&mut self) -> Option<RedoStatement> {
+        let statement = if self.keyword("insert") {
+            self.expect_keyword("into")?;
+            self.table()?;
+            self.expect(Token::Symbol('('))?;
+            let columns = self.list(Self::identifier)?;
+            self.expect(Token::Symbol(')'))?;
+            self.expect_keyword("values")?;
+            self.expect(Token::Symbol('('))?;
+            let values = self.list(Self::value)?;
+            self.expect(Token::Symbol(')'))?;
+            if columns.len() != values.len() {
+                return None;
+            }
+            RedoStatement::Insert {
+                values: columns.into_iter().zip(values).collect(),
+            }
+        } else if self.keyword("update") {
+            self.table()?;
+            self.expect_keyword("set")?;
+            let set = self.list(Self::assignment)?;
+            RedoStatement::Update {
+                set,
+                condition: self.condition()?,
+            }
+        } else if self.keyword("delete") {
+            self.expect_keyword("from")?;
+            self.table()?;
+            RedoStatement::Delete {
+                condition: self.condition()?,
+            }
+        } else {
+            return None;
+        };
+        self.keyword(";");
+        (self.position == self.tokens.len()).then_some(statement)
+    }
+
+    /// Parses `"OWNER"."TABLE"`.
+    fn table(&mut self) -> Option<()> {
+        self.identifier()?;
+        self.expect(Token::Symbol('.'))?;
+        self.identifier()?;
+        Some(())
+    }
+
+    /// Parses `where "A" = '1' and "B" IS NULL and ROWID = '...'`, skipping the row id.
+    fn condition(&mut self) -> Option<Vec<(String, RedoValue)>> {
+        self.expect_keyword("where")?;
+        let mut condition = vec![];
+        loop {
+            if self.keyword("ROWID") {
+                self.expect(Token::Symbol('='))?;
+                self.value()?;
+            } else {
+                let column = self.identifier()?;
+                if self.keyword("IS") {
+                    self.expect_keyword("NULL")?;
+                    condition.push((column, RedoValue::Null));
+                } else {
+                    self.expect(Token::Symbol('='))?;
+                    condition.push((column, self.value()?));
+                }
+            }
+            if !self.keyword("and") {
+                return Some(condition);
+            }
+        }
+    }
+
+    fn assignment(&mut self) -> Option<(String, RedoValue)> {
+        let column = self.identifier()?;
+        self.expect(Token::Symbol('='))?;
+        Some((column, self.value()?))
+    }
+
+    fn value(&mut self) -> Option<RedoValue> {
+        match self.next()? {
+            Token::String(value) => Some(RedoValue::Literal(value)),
+            Token::Word(word) if word.eq_ignore_ascii_case("NULL") => Some(RedoValue::Null),
+            Token::Word(word) if self.tokens.get(self.position) == Some(&Token::Symbol('(')) => {
+                self.position += 1;
+                let args = if self.tokens.get(self.position) == Some(&Token::Symbol(')')) {
+                    vec![]
+                } else {
+                    self.list(Self::argument)?
+                };
+                self.expect(Token::Symbol(')'))?;
+                Some(RedoValue::Function(word.to_uppercase(), args))
+            }
+            Token::Word(word) => Some(RedoValue::Literal(word)),
+            _ => None,
+        }
+    }
+
+    fn argument(&mut self) -> Option<String> {
+        match self.next()? {
+            Token::String(value) | Token::Word(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        match self.next()? {
+            Token::Identifier(identifier) => Some(identifier),
+            _ => None,
+        }
+    }
+
+    /// Parses items separated by commas.
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.tokens.get(self.position) == Some(&Token::Symbol(',')) {
+            self.position += 1;
+            items.push(item(self)?);
+        }
+        Some(items)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Option<()> {
+        (self.next()? == expected).then_some(())
+    }
+
+    /// Consumes the keyword or symbol if it's next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = match self.tokens.get(self.position) {
+            Some(Token::Word(word)) => word.eq_ignore_ascii_case(keyword),
+            Some(Token::Symbol(symbol)) => keyword.len() == 1 && keyword.starts_with(*symbol),
+            _ => false,
+        };
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Option<()> {
+        self.keyword(keyword).then_some(())
+    }
+}
+
+fn tokenize(sql: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    let next = chars.next()?;
+                    if next == c {
+                        // Quotes are escaped by doubling them.
+                        if chars.peek() == Some(&c) {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    value.push(next);
+                }
+                tokens.push(if c == '"' {
+                    Token::Identifier(value)
+                } else {
+                    Token::String(value)
+                });
+            }
+            '(' | ')' | ',' | '=' | '.' | ';' => {
+                chars.next();
+                tokens.push(Token::Symbol(c));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()\"',=;".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Some(tokens)
+}
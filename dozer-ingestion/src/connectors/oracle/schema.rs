@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, Utc};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use oracle::{Connection, Row};
+
+use crate::errors::OracleError;
+
+/// Oracle types and the types they are mapped to. `NUMBER` without scale and of at most 18 digits is mapped to int.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("NUMBER", FieldType::Decimal),
+    ("FLOAT", FieldType::Float),
+    ("BINARY_FLOAT", FieldType::Float),
+    ("BINARY_DOUBLE", FieldType::Float),
+    ("CHAR", FieldType::String),
+    ("NCHAR", FieldType::String),
+    ("VARCHAR2", FieldType::String),
+    ("NVARCHAR2", FieldType::String),
+    ("RAW", FieldType::Binary),
+    ("DATE", FieldType::Timestamp),
+    ("TIMESTAMP", FieldType::Timestamp),
+    ("TIMESTAMP WITH TIME ZONE", FieldType::Timestamp),
+    ("TIMESTAMP WITH LOCAL TIME ZONE", FieldType::Timestamp),
+];
+
+/// A column of a source table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// The type as in `ALL_TAB_COLUMNS`, e.g. `TIMESTAMP(6) WITH TIME ZONE`.
+    pub data_type: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+}
+
+impl Column {
+    fn has_time_zone(&self) -> bool {
+        self.data_type.ends_with("TIME ZONE")
+    }
+}
+
+/// The requested columns of a source table and the positions of its primary key among them.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub owner: String,
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_index: Vec<usize>,
+}
+
+impl Table {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    column.nullable,
+                    SourceDefinition::Dynamic,
+                ),
+                self.primary_index.contains(&index),
+            );
+        }
+        schema
+    }
+
+    /// The quoted name, to be used in queries.
+    pub fn quoted_name(&self) -> String {
+        format!("{}.{}", quote(&self.owner), quote(&self.name))
+    }
+}
+
+/// Maps a type of `ALL_TAB_COLUMNS`, returning `None` if it's not supported.
+pub fn map_type(data_type: &str, precision: Option<u32>, scale: Option<i32>) -> Option<FieldType> {
+    if data_type == "NUMBER" {
+        return Some(match (precision, scale) {
+            (Some(precision), Some(0)) if precision <= 18 => FieldType::Int,
+            _ => FieldType::Decimal,
+        });
+    }
+    let data_type = base_type(data_type);
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == data_type)
+        .map(|(_, typ)| *typ)
+}
+
+/// Strips the fractional second precision of timestamp types, e.g. `TIMESTAMP(6) WITH TIME ZONE` becomes
+/// `TIMESTAMP WITH TIME ZONE`.
+pub fn base_type(data_type: &str) -> String {
+    match (data_type.find('('), data_type.find(')')) {
+        (Some(open), Some(close)) if open < close => {
+            format!("{}{}", &data_type[..open], &data_type[close + 1..])
+        }
+        _ => data_type.to_string(),
+    }
+}
+
+pub fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+pub fn list_tables(connection: &Connection, owner: &str) -> Result<Vec<String>, OracleError> {
+    let rows = connection.query_as::<String>(
+        "SELECT TABLE_NAME FROM ALL_TABLES WHERE OWNER = :1 ORDER BY TABLE_NAME",
+        &[&owner],
+    )?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Lists the column names of a table, which is empty if the table doesn't exist.
+pub fn list_columns(
+    connection: &Connection,
+    owner: &str,
+    table: &str,
+) -> Result<Vec<String>, OracleError> {
+    let rows = connection.query_as::<String>(
+        "SELECT COLUMN_NAME FROM ALL_TAB_COLUMNS WHERE OWNER = :1 AND TABLE_NAME = :2 ORDER BY COLUMN_ID",
+        &[&owner, &table],
+    )?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Gets the `column_names` of a table.
+pub fn get_table(
+    connection: &Connection,
+    owner: &str,
+    table: &str,
+    column_names: &[String],
+) -> Result<Table, OracleError> {
+    let rows = connection.query_as::<(String, String, Option<u32>, Option<i32>, String)>(
+        "SELECT COLUMN_NAME, DATA_TYPE, DATA_PRECISION, DATA_SCALE, NULLABLE FROM ALL_TAB_COLUMNS \
+            WHERE OWNER = :1 AND TABLE_NAME = :2 ORDER BY COLUMN_ID",
+        &[&owner, &table],
+    )?;
+    let mut all_columns = vec![];
+    for row in rows {
+        let (name, data_type, precision, scale, nullable) = row?;
+        all_columns.push((name, data_type, precision, scale, nullable == "Y"));
+    }
+
+    let columns = column_names
+        .iter()
+        .map(|column_name| {
+            let (name, data_type, precision, scale, nullable) = all_columns
+                .iter()
+                .find(|(name, ..)| name == column_name)
+                .ok_or_else(|| {
+                    OracleError::ColumnNotFound(column_name.clone(), format!("{owner}.{table}"))
+                })?;
+            let typ = map_type(data_type, *precision, *scale)
+                .ok_or_else(|| OracleError::UnsupportedType(name.clone(), data_type.clone()))?;
+            Ok(Column {
+                name: name.clone(),
+                data_type: data_type.clone(),
+                typ,
+                nullable: *nullable,
+            })
+        })
+        .collect::<Result<Vec<_>, OracleError>>()?;
+
+    let primary_key = connection
+        .query_as::<String>(
+            "SELECT COLS.COLUMN_NAME FROM ALL_CONSTRAINTS CONS \
+                JOIN ALL_CONS_COLUMNS COLS ON CONS.OWNER = COLS.OWNER AND CONS.CONSTRAINT_NAME = COLS.CONSTRAINT_NAME \
+                WHERE CONS.CONSTRAINT_TYPE = 'P' AND CONS.OWNER = :1 AND CONS.TABLE_NAME = :2 \
+                ORDER BY COLS.POSITION",
+            &[&owner, &table],
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    let primary_index = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| primary_key.contains(&column.name))
+        .map(|(index, _)| index)
+        .collect();
+
+    Ok(Table {
+        owner: owner.to_string(),
+        name: table.to_string(),
+        columns,
+        primary_index,
+    })
+}
+
+/// Reads the value of `column` at `index` of a row.
+pub fn read_value(row: &Row, index: usize, column: &Column) -> Result<Field, OracleError> {
+    let field = match column.typ {
+        FieldType::Int => row.get::<_, Option<i64>>(index)?.map(Field::Int),
+        FieldType::Decimal => row
+            .get::<_, Option<String>>(index)?
+            .map(|value| parse_decimal(column, &value))
+            .transpose()?,
+        FieldType::Float => row
+            .get::<_, Option<f64>>(index)?
+            .map(|value| Field::Float(OrderedFloat(value))),
+        FieldType::String => row.get::<_, Option<String>>(index)?.map(Field::String),
+        FieldType::Binary => row.get::<_, Option<Vec<u8>>>(index)?.map(Field::Binary),
+        FieldType::Timestamp if column.has_time_zone() => row
+            .get::<_, Option<DateTime<FixedOffset>>>(index)?
+            .map(Field::Timestamp),
+        FieldType::Timestamp => row
+            .get::<_, Option<NaiveDateTime>>(index)?
+            .map(utc_timestamp),
+        typ => unreachable!("Oracle columns are not mapped to {typ}"),
+    };
+    Ok(field.unwrap_or(Field::Null))
+}
+
+/// Parses a value of a redo statement, formatted as set by `connector::SESSION_SETTINGS`.
+pub fn parse_value(column: &Column, value: &str) -> Result<Field, OracleError> {
+    let invalid = || OracleError::InvalidValue(column.name.clone(), value.to_string());
+    Ok(match column.typ {
+        FieldType::Int => Field::Int(value.parse().map_err(|_| invalid())?),
+        FieldType::Decimal => parse_decimal(column, value)?,
+        FieldType::Float => Field::Float(OrderedFloat(value.parse().map_err(|_| invalid())?)),
+        FieldType::String => Field::String(value.to_string()),
+        FieldType::Binary => Field::Binary(parse_hex(value).ok_or_else(invalid)?),
+        FieldType::Timestamp if column.has_time_zone() => Field::Timestamp(
+            DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f %:z").map_err(|_| invalid())?,
+        ),
+        FieldType::Timestamp => utc_timestamp(
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").map_err(|_| invalid())?,
+        ),
+        typ => unreachable!("Oracle columns are not mapped to {typ}"),
+    })
+}
+
+/// Timestamps without time zone are read as UTC, the session time zone.
+fn utc_timestamp(value: NaiveDateTime) -> Field {
+    Field::Timestamp(DateTime::from_utc(value, Utc.fix()))
+}
+
+fn parse_decimal(column: &Column, value: &str) -> Result<Field, OracleError> {
+    // Oracle leaves out the zero before the decimal point, e.g. `-.5`.
+    let value = &match value.strip_prefix('-') {
+        Some(digits) if digits.starts_with('.') => format!("-0{digits}"),
+        _ if value.starts_with('.') => format!("0{value}"),
+        _ => value.to_string(),
+    };
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map(Field::Decimal)
+        .map_err(|_| OracleError::InvalidValue(column.name.clone(), value.to_string()))
+}
+
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
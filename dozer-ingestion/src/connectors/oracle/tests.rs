@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::DateTime;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldType, Operation, Record};
+
+use super::redo::{parse_redo, redo_operation, RedoStatement, RedoValue};
+use super::schema::{base_type, map_type, Column};
+use crate::errors::OracleError;
+
+fn column(name: &str, data_type: &str, typ: FieldType) -> Column {
+    Column {
+        name: name.to_string(),
+        data_type: data_type.to_string(),
+        typ,
+        nullable: true,
+    }
+}
+
+fn columns() -> Vec<Column> {
+    vec![
+        column("ID", "NUMBER", FieldType::Int),
+        column("NAME", "VARCHAR2", FieldType::String),
+        column("PRICE", "NUMBER", FieldType::Decimal),
+        column("CREATED", "DATE", FieldType::Timestamp),
+        column(
+            "UPDATED",
+            "TIMESTAMP(6) WITH TIME ZONE",
+            FieldType::Timestamp,
+        ),
+    ]
+}
+
+fn literal(value: &str) -> RedoValue {
+    RedoValue::Literal(value.to_string())
+}
+
+#[test]
+fn test_map_type() {
+    assert_eq!(map_type("NUMBER", Some(10), Some(0)), Some(FieldType::Int));
+    assert_eq!(
+        map_type("NUMBER", Some(10), Some(2)),
+        Some(FieldType::Decimal)
+    );
+    assert_eq!(
+        map_type("NUMBER", Some(38), Some(0)),
+        Some(FieldType::Decimal)
+    );
+    assert_eq!(map_type("NUMBER", None, None), Some(FieldType::Decimal));
+    assert_eq!(map_type("DATE", None, None), Some(FieldType::Timestamp));
+    assert_eq!(
+        map_type("TIMESTAMP(6) WITH TIME ZONE", None, Some(6)),
+        Some(FieldType::Timestamp)
+    );
+    assert_eq!(map_type("VARCHAR2", None, None), Some(FieldType::String));
+    assert_eq!(map_type("BLOB", None, None), None);
+    assert_eq!(base_type("TIMESTAMP(3)"), "TIMESTAMP");
+}
+
+#[test]
+fn test_parse_insert() {
+    let statement = parse_redo(
+        r#"insert into "SHOP"."ITEMS"("ID","NAME","PRICE","CREATED","UPDATED") values ('1','it''s',NULL,TO_DATE('2023-03-15 10:00:00', 'YYYY-MM-DD HH24:MI:SS'),TO_TIMESTAMP_TZ('2023-03-15 10:00:00.500000 +02:00', 'YYYY-MM-DD HH24:MI:SS.FF TZH:TZM'));"#,
+    )
+    .unwrap();
+    assert_eq!(
+        statement,
+        RedoStatement::Insert {
+            values: vec![
+                ("ID".to_string(), literal("1")),
+                ("NAME".to_string(), literal("it's")),
+                ("PRICE".to_string(), RedoValue::Null),
+                (
+                    "CREATED".to_string(),
+                    RedoValue::Function(
+                        "TO_DATE".to_string(),
+                        vec![
+                            "2023-03-15 10:00:00".to_string(),
+                            "YYYY-MM-DD HH24:MI:SS".to_string()
+                        ]
+                    )
+                ),
+                (
+                    "UPDATED".to_string(),
+                    RedoValue::Function(
+                        "TO_TIMESTAMP_TZ".to_string(),
+                        vec![
+                            "2023-03-15 10:00:00.500000 +02:00".to_string(),
+                            "YYYY-MM-DD HH24:MI:SS.FF TZH:TZM".to_string()
+                        ]
+                    )
+                ),
+            ]
+        }
+    );
+
+    let op = redo_operation(&columns(), &statement, "").unwrap();
+    assert_eq!(
+        op,
+        Operation::Insert {
+            new: Record::new(vec![
+                Field::Int(1),
+                Field::String("it's".to_string()),
+                Field::Null,
+                Field::Timestamp(
+                    DateTime::parse_from_rfc3339("2023-03-15T10:00:00+00:00").unwrap()
+                ),
+                Field::Timestamp(
+                    DateTime::parse_from_rfc3339("2023-03-15T10:00:00.5+02:00").unwrap()
+                ),
+            ])
+        }
+    );
+}
+
+#[test]
+fn test_parse_update_and_delete() {
+    let columns = columns();
+    let columns = &columns[..3];
+    let update = r#"update "SHOP"."ITEMS" set "PRICE" = '10.25' where "ID" = '1' and "NAME" = 'a' and "PRICE" IS NULL and ROWID = 'AAAR3sAAEAAAACXAAA';"#;
+    let statement = parse_redo(update).unwrap();
+    assert_eq!(
+        redo_operation(columns, &statement, update).unwrap(),
+        Operation::Update {
+            old: Record::new(vec![
+                Field::Int(1),
+                Field::String("a".to_string()),
+                Field::Null
+            ]),
+            new: Record::new(vec![
+                Field::Int(1),
+                Field::String("a".to_string()),
+                Field::Decimal(Decimal::from_str("10.25").unwrap())
+            ]),
+        }
+    );
+
+    let delete = r#"delete from "SHOP"."ITEMS" where "ID" = '1' and "NAME" = 'a' and "PRICE" = '-.5' and ROWID = 'AAAR3sAAEAAAACXAAA';"#;
+    let statement = parse_redo(delete).unwrap();
+    assert_eq!(
+        redo_operation(columns, &statement, delete).unwrap(),
+        Operation::Delete {
+            old: Record::new(vec![
+                Field::Int(1),
+                Field::String("a".to_string()),
+                Field::Decimal(Decimal::from_str("-0.5").unwrap())
+            ]),
+        }
+    );
+}
+
+#[test]
+fn test_invalid_redo() {
+    assert!(matches!(
+        parse_redo(r#"insert into "SHOP"."ITEMS"("ID") values ('1', '2');"#),
+        Err(OracleError::InvalidRedo(_))
+    ));
+    assert!(matches!(
+        parse_redo(r#"update "SHOP"."ITEMS" set "ID" = '1"#),
+        Err(OracleError::InvalidRedo(_))
+    ));
+
+    let delete = r#"delete from "SHOP"."ITEMS" where "ID" = '1';"#;
+    assert!(matches!(
+        redo_operation(&columns(), &parse_redo(delete).unwrap(), delete),
+        Err(OracleError::MissingRedoColumn(column, _)) if column == "NAME"
+    ));
+
+    let insert = r#"insert into "SHOP"."ITEMS"("ID") values ('x');"#;
+    assert!(matches!(
+        redo_operation(&columns()[..1], &parse_redo(insert).unwrap(), insert),
+        Err(OracleError::InvalidValue(_, _))
+    ));
+}
@@ -36,6 +36,26 @@ pub async fn validate_connection(
         Validations::WALLevel,
         Validations::Slot,
     ];
+    validate(name, config, tables, replication_info, validations_order).await
+}
+
+/// Tracking changes with triggers needs neither replication permission nor logical WAL level.
+pub async fn validate_trigger_connection(
+    name: &str,
+    config: tokio_postgres::Config,
+    tables: Option<&Vec<ListOrFilterColumns>>,
+) -> Result<(), PostgresConnectorError> {
+    let validations_order: Vec<Validations> = vec![Validations::Details, Validations::Tables];
+    validate(name, config, tables, None, validations_order).await
+}
+
+async fn validate(
+    name: &str,
+    config: tokio_postgres::Config,
+    tables: Option<&Vec<ListOrFilterColumns>>,
+    replication_info: Option<ReplicationSlotInfo>,
+    validations_order: Vec<Validations>,
+) -> Result<(), PostgresConnectorError> {
     let pb = dozer_types::indicatif::ProgressBar::new(validations_order.len() as u64);
     pb.set_style(
         ProgressStyle::with_template(&format!(
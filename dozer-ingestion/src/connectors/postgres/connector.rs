@@ -1,10 +1,16 @@
-use crate::connectors::postgres::connection::validator::validate_connection;
+use std::time::Duration;
+
+use crate::connectors::postgres::connection::validator::{
+    validate_connection, validate_trigger_connection,
+};
 use crate::connectors::postgres::iterator::PostgresIterator;
+use crate::connectors::postgres::trigger_replicator::TriggerReplicator;
 use crate::connectors::{
     Connector, ListOrFilterColumns, SourceSchemaResult, TableIdentifier, TableInfo,
 };
 use crate::errors::ConnectorError;
 use crate::ingestion::Ingestor;
+use dozer_types::models::connection::OnSchemaChange;
 use dozer_types::tracing::info;
 use postgres_types::PgLsn;
 use rand::distributions::Alphanumeric;
@@ -13,16 +19,30 @@ use tonic::async_trait;
 
 use crate::connectors::postgres::schema::helper::{SchemaHelper, DEFAULT_SCHEMA_NAME};
 use crate::errors::ConnectorError::PostgresConnectorError;
-use crate::errors::PostgresConnectorError::{CreatePublicationError, DropPublicationError};
+use crate::errors::PostgresConnectorError::{
+    CreatePublicationError, DropPublicationError, InvalidQueryError, PublicationNotFound,
+    TableNotInPublication,
+};
 use tokio_postgres::config::ReplicationMode;
-use tokio_postgres::{Client, Config};
+use tokio_postgres::{Client, Config, SimpleQueryMessage};
 
 use super::connection::helper;
+use super::helper::quote;
 
 #[derive(Clone, Debug)]
 pub struct PostgresConfig {
     pub name: String,
     pub config: Config,
+    /// Existing publication to replicate instead of creating one.
+    pub publication: Option<String>,
+    /// Track changes with triggers, polling the change table at this interval, instead of logical replication.
+    pub trigger_poll_interval: Option<Duration>,
+    /// What to do when the schema of a replicated table changes during logical replication.
+    pub on_schema_change: OnSchemaChange,
+    /// Number of connections reading each table in the initial snapshot.
+    pub snapshot_parallelism: usize,
+    /// Number of rows in a primary key chunk of a parallel snapshot.
+    pub snapshot_chunk_size: u64,
 }
 
 #[derive(Debug)]
@@ -31,6 +51,11 @@ pub struct PostgresConnector {
     replication_conn_config: Config,
     conn_config: Config,
     schema_helper: SchemaHelper,
+    publication: Option<String>,
+    trigger_poll_interval: Option<Duration>,
+    on_schema_change: OnSchemaChange,
+    snapshot_parallelism: usize,
+    snapshot_chunk_size: u64,
 }
 
 #[derive(Debug)]
@@ -41,6 +66,56 @@ pub struct ReplicationSlotInfo {
 
 pub const REPLICATION_SLOT_PREFIX: &str = "dozer_slot";
 
+/// Leaf partitions of a partitioned table, through sub-partitioned partitions.
+const LEAF_PARTITIONS_SQL: &str = "
+WITH RECURSIVE partitions(relid) AS (
+    SELECT inhrelid
+    FROM pg_inherits
+    WHERE inhparent = $1::text::regclass
+    UNION ALL
+    SELECT i.inhrelid
+    FROM partitions p
+             JOIN pg_inherits i ON i.inhparent = p.relid
+)
+SELECT n.nspname, c.relname
+FROM partitions p
+         JOIN pg_class c ON c.oid = p.relid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE c.relkind = 'r'
+  AND c.relispartition;";
+
+/// The `server_version_num` setting, e.g. 130004 for 13.4. Replication connections only support simple queries.
+async fn server_version_num(client: &Client) -> Result<i32, ConnectorError> {
+    let messages = client
+        .simple_query("SHOW server_version_num")
+        .await
+        .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+    Ok(messages
+        .iter()
+        .find_map(|message| match message {
+            SimpleQueryMessage::Row(row) => row.get(0).and_then(|value| value.parse().ok()),
+            _ => None,
+        })
+        .unwrap_or_default())
+}
+
+/// Postgres 13 and later can publish the changes of partitions as changes of their partitioned table.
+fn publication_options(server_version_num: i32) -> &'static str {
+    if server_version_num >= 130000 {
+        " WITH (publish_via_partition_root = true)"
+    } else {
+        ""
+    }
+}
+
+/// Whether the publication publishes all the leaf partitions of a table, which has none if it isn't partitioned.
+fn all_published(partitions: &[(String, String)], published: &[(String, String)]) -> bool {
+    !partitions.is_empty()
+        && partitions
+            .iter()
+            .all(|partition| published.contains(partition))
+}
+
 impl PostgresConnector {
     pub fn new(config: PostgresConfig) -> PostgresConnector {
         let mut replication_conn_config = config.config.clone();
@@ -56,6 +131,11 @@ impl PostgresConnector {
             conn_config: config.config,
             replication_conn_config,
             schema_helper: helper,
+            publication: config.publication,
+            trigger_poll_interval: config.trigger_poll_interval,
+            on_schema_change: config.on_schema_change,
+            snapshot_parallelism: config.snapshot_parallelism,
+            snapshot_chunk_size: config.snapshot_chunk_size,
         }
     }
 
@@ -89,6 +169,11 @@ impl Connector for PostgresConnector {
     }
 
     async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        if self.trigger_poll_interval.is_some() {
+            return validate_trigger_connection(&self.name, self.conn_config.clone(), None)
+                .await
+                .map_err(Into::into);
+        }
         validate_connection(&self.name, self.conn_config.clone(), None, None)
             .await
             .map_err(Into::into)
@@ -113,6 +198,15 @@ impl Connector for PostgresConnector {
                 columns: None,
             })
             .collect::<Vec<_>>();
+        if self.trigger_poll_interval.is_some() {
+            return validate_trigger_connection(
+                &self.name,
+                self.conn_config.clone(),
+                Some(&tables),
+            )
+            .await
+            .map_err(Into::into);
+        }
         validate_connection(&self.name, self.conn_config.clone(), Some(&tables), None)
             .await
             .map_err(Into::into)
@@ -161,11 +255,59 @@ impl Connector for PostgresConnector {
             .map_err(Into::into)
     }
 
+    /// The planner's estimates in `pg_class`, which are unknown for tables that were never vacuumed or analyzed.
+    async fn estimate_row_counts(
+        &self,
+        tables: &[TableInfo],
+    ) -> Result<Vec<Option<u64>>, ConnectorError> {
+        let client = helper::connect(self.conn_config.clone())
+            .await
+            .map_err(PostgresConnectorError)?;
+        let mut row_counts = vec![];
+        for table in tables {
+            let schema = table.schema.as_deref().unwrap_or(DEFAULT_SCHEMA_NAME);
+            let relation = format!(
+                "\"{}\".\"{}\"",
+                schema.replace('"', "\"\""),
+                table.name.replace('"', "\"\"")
+            );
+            let row = client
+                .query_one(
+                    "SELECT reltuples::bigint FROM pg_class WHERE oid = $1::regclass",
+                    &[&relation],
+                )
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            let row_count: i64 = row.get(0);
+            row_counts.push(u64::try_from(row_count).ok());
+        }
+        Ok(row_counts)
+    }
+
     async fn start(
         &self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
     ) -> Result<(), ConnectorError> {
+        if let Some(poll_interval) = self.trigger_poll_interval {
+            let tables = tables
+                .into_iter()
+                .map(|table| ListOrFilterColumns {
+                    schema: table.schema,
+                    name: table.name,
+                    columns: Some(table.column_names),
+                })
+                .collect::<Vec<_>>();
+            let replicator = TriggerReplicator {
+                name: self.name.clone(),
+                conn_config: self.conn_config.clone(),
+                tables: self.schema_helper.get_tables(Some(&tables)).await?,
+                ingestor,
+                poll_interval,
+            };
+            return replicator.start().await;
+        }
+
         let client = helper::connect(self.replication_conn_config.clone())
             .await
             .map_err(PostgresConnectorError)?;
@@ -173,8 +315,12 @@ impl Connector for PostgresConnector {
             .iter()
             .map(|table| TableIdentifier::new(table.schema.clone(), table.name.clone()))
             .collect::<Vec<_>>();
-        self.create_publication(client, Some(&table_identifiers))
-            .await?;
+        if self.publication.is_some() {
+            self.check_publication(&table_identifiers).await?;
+        } else {
+            self.create_publication(client, Some(&table_identifiers))
+                .await?;
+        }
 
         let lsn = PostgresConnector::get_lsn_with_offset_from_seq(self.name.clone(), None);
 
@@ -194,6 +340,9 @@ impl Connector for PostgresConnector {
             self.replication_conn_config.clone(),
             ingestor,
             self.conn_config.clone(),
+            self.on_schema_change,
+            self.snapshot_parallelism,
+            self.snapshot_chunk_size,
         );
         iterator.start(lsn).await
     }
@@ -201,7 +350,9 @@ impl Connector for PostgresConnector {
 
 impl PostgresConnector {
     fn get_publication_name(&self) -> String {
-        format!("dozer_publication_{}", self.name)
+        self.publication
+            .clone()
+            .unwrap_or_else(|| format!("dozer_publication_{}", self.name))
     }
 
     pub fn get_slot_name(&self) -> String {
@@ -249,11 +400,117 @@ impl PostgresConnector {
             .await
             .map_err(DropPublicationError)?;
 
+        let options = publication_options(server_version_num(&client).await?);
         client
-            .simple_query(format!("CREATE PUBLICATION {publication_name} FOR {table_str}").as_str())
+            .simple_query(
+                format!("CREATE PUBLICATION {publication_name} FOR {table_str}{options}").as_str(),
+            )
             .await
             .map_err(CreatePublicationError)?;
 
         Ok(())
     }
+
+    /// Checks that the configured publication exists and publishes every table, as it's not created by us.
+    async fn check_publication(
+        &self,
+        table_identifiers: &[TableIdentifier],
+    ) -> Result<(), ConnectorError> {
+        let publication_name = self.get_publication_name();
+        // Replication connections only support simple queries.
+        let client = helper::connect(self.conn_config.clone())
+            .await
+            .map_err(PostgresConnectorError)?;
+
+        let exists = client
+            .query_opt(
+                "SELECT 1 FROM pg_publication WHERE pubname = $1",
+                &[&publication_name],
+            )
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?
+            .is_some();
+        if !exists {
+            return Err(PostgresConnectorError(PublicationNotFound(
+                publication_name,
+            )));
+        }
+
+        let published = client
+            .query(
+                "SELECT schemaname, tablename FROM pg_publication_tables WHERE pubname = $1",
+                &[&publication_name],
+            )
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?
+            .into_iter()
+            .map(|row| (row.get::<_, String>(0), row.get::<_, String>(1)))
+            .collect::<Vec<_>>();
+        for table_identifier in table_identifiers {
+            let schema = table_identifier
+                .schema
+                .as_deref()
+                .unwrap_or(DEFAULT_SCHEMA_NAME);
+            if published
+                .iter()
+                .any(|(s, t)| s == schema && t == &table_identifier.name)
+            {
+                continue;
+            }
+            // A publication without `publish_via_partition_root` publishes the partitions of a partitioned table.
+            let partitions = client
+                .query(
+                    LEAF_PARTITIONS_SQL,
+                    &[&format!(
+                        "{}.{}",
+                        quote(schema),
+                        quote(&table_identifier.name)
+                    )],
+                )
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?
+                .into_iter()
+                .map(|row| (row.get::<_, String>(0), row.get::<_, String>(1)))
+                .collect::<Vec<_>>();
+            if !all_published(&partitions, &published) {
+                return Err(PostgresConnectorError(TableNotInPublication(
+                    publication_name,
+                    schema.to_string(),
+                    table_identifier.name.clone(),
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publication_options() {
+        assert_eq!(publication_options(120010), "");
+        assert_eq!(
+            publication_options(130004),
+            " WITH (publish_via_partition_root = true)"
+        );
+    }
+
+    #[test]
+    fn test_all_published() {
+        let table = |name: &str| ("public".to_string(), name.to_string());
+        let published = [table("events_2023"), table("events_2024"), table("users")];
+
+        assert!(all_published(
+            &[table("events_2023"), table("events_2024")],
+            &published
+        ));
+        assert!(!all_published(
+            &[table("events_2023"), table("events_2025")],
+            &published
+        ));
+        // Tables that aren't partitioned have no partitions to publish.
+        assert!(!all_published(&[], &published));
+    }
 }
@@ -11,7 +11,7 @@ use dozer_types::geo::Point as GeoPoint;
 use dozer_types::json_types::{serde_json_to_json_value, JsonValue};
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::{rust_decimal, serde_json, types::*};
-use postgres_types::{Type, WasNull};
+use postgres_types::{FromSql, Kind, Type, WasNull};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use std::error::Error;
@@ -107,11 +107,44 @@ pub fn postgres_type_to_field(
                 .parse::<DozerPoint>()
                 .map_err(|_| PointParseError)?,
         )),
+        _ if matches!(column_type.kind(), Kind::Enum(_)) => {
+            enum_label_to_field(&column_type, &String::from_utf8(v.to_vec())?)
+        }
         _ => Err(ColumnTypeNotSupported(column_type.name().to_string())),
     })
 }
 
+/// The ordinal of a label of an enum type, which is its position in the type's labels.
+fn enum_label_to_field(column_type: &Type, label: &str) -> Result<Field, PostgresSchemaError> {
+    let Kind::Enum(labels) = column_type.kind() else {
+        return Err(ColumnTypeNotSupported(column_type.name().to_string()));
+    };
+    labels
+        .iter()
+        .position(|known| known == label)
+        .map(|ordinal| Field::Enum(ordinal as u32))
+        .ok_or_else(|| {
+            PostgresSchemaError::UnknownEnumLabel(column_type.name().to_string(), label.to_string())
+        })
+}
+
+/// The label of an enum value, which is sent as text in both formats.
+struct EnumLabel(String);
+
+impl<'a> FromSql<'a> for EnumLabel {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Self(std::str::from_utf8(raw)?.to_string()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Enum(_))
+    }
+}
+
 pub fn postgres_type_to_dozer_type(column_type: Type) -> Result<FieldType, PostgresSchemaError> {
+    if let Kind::Enum(_) = column_type.kind() {
+        return Ok(FieldType::Enum);
+    }
     match column_type {
         Type::BOOL => Ok(FieldType::Boolean),
         Type::INT2 | Type::INT4 | Type::INT8 => Ok(FieldType::Int),
@@ -214,6 +247,12 @@ pub fn value_to_field(
             let value: Result<Uuid, _> = row.try_get(idx);
             value.map_or_else(handle_error, |val| Ok(Field::from(val.to_string())))
         }
+        _ if matches!(col_type.kind(), Kind::Enum(_)) => {
+            let value: Result<EnumLabel, _> = row.try_get(idx);
+            value.map_or_else(handle_error, |label| {
+                enum_label_to_field(col_type, &label.0)
+            })
+        }
         _ => {
             if col_type.schema() == "pg_catalog" {
                 Err(ColumnTypeNotSupported(col_type.name().to_string()))
@@ -259,14 +298,24 @@ pub fn map_schema(columns: &[Column]) -> Result<Schema, ConnectorError> {
 }
 
 pub fn convert_column_to_field(column: &Column) -> Result<FieldDefinition, PostgresSchemaError> {
+    let enum_values = match column.type_().kind() {
+        Kind::Enum(labels) => labels.clone(),
+        _ => vec![],
+    };
     postgres_type_to_dozer_type(column.type_().clone()).map(|typ| FieldDefinition {
         name: column.name().to_string(),
         typ,
         nullable: true,
         source: SourceDefinition::Dynamic,
+        enum_values,
     })
 }
 
+/// Quotes an identifier for SQL.
+pub fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,7 +331,7 @@ mod tests {
                     name: "column".to_string(),
                     flags: 0,
                     r#type: $b,
-                    column_index: 0,
+                    column_index: Some(0),
                 },
             );
             assert_eq!(value.unwrap(), $c);
@@ -403,6 +452,38 @@ mod tests {
         test_type_mapping!(Type::JSON_ARRAY, FieldType::Json);
         test_type_mapping!(Type::BOOL, FieldType::Boolean);
         test_type_mapping!(Type::POINT, FieldType::Point);
+        test_type_mapping!(mood_type(), FieldType::Enum);
+    }
+
+    fn mood_type() -> Type {
+        Type::new(
+            "mood".to_string(),
+            16385,
+            Kind::Enum(vec![
+                "sad".to_string(),
+                "ok".to_string(),
+                "happy".to_string(),
+            ]),
+            "public".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_enum_conversion() {
+        test_conversion!("happy", mood_type(), Field::Enum(2));
+        let value = postgres_type_to_field(
+            Some(&Bytes::from("angry")),
+            &TableColumn {
+                name: "column".to_string(),
+                flags: 0,
+                r#type: mood_type(),
+                column_index: Some(0),
+            },
+        );
+        assert!(matches!(
+            value,
+            Err(PostgresSchemaError::UnknownEnumLabel(typ, label)) if typ == "mood" && label == "angry"
+        ));
     }
 
     #[test]
@@ -413,7 +494,7 @@ mod tests {
                 name: "column".to_string(),
                 flags: 0,
                 r#type: Type::VARCHAR,
-                column_index: 0,
+                column_index: Some(0),
             },
         );
         assert_eq!(value.unwrap(), Field::Null);
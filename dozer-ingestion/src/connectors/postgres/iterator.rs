@@ -1,8 +1,9 @@
 use crate::connectors::ListOrFilterColumns;
-use crate::errors::{ConnectorError, PostgresConnectorError};
+use crate::errors::ConnectorError;
 use crate::ingestion::Ingestor;
 use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::log::debug;
+use dozer_types::models::connection::OnSchemaChange;
 
 use std::str::FromStr;
 
@@ -13,9 +14,7 @@ use crate::connectors::postgres::connector::REPLICATION_SLOT_PREFIX;
 use crate::connectors::postgres::replication_slot_helper::ReplicationSlotHelper;
 use crate::connectors::postgres::replicator::CDCHandler;
 use crate::connectors::postgres::snapshotter::PostgresSnapshotter;
-use crate::errors::PostgresConnectorError::{
-    InvalidQueryError, LSNNotStoredError, LsnNotReturnedFromReplicationSlot, LsnParseError,
-};
+use crate::errors::PostgresConnectorError::{InvalidQueryError, LSNNotStoredError, LsnParseError};
 use postgres_types::PgLsn;
 
 use super::schema::helper::PostgresTableInfo;
@@ -27,6 +26,9 @@ pub struct Details {
     tables: Vec<PostgresTableInfo>,
     replication_conn_config: tokio_postgres::Config,
     conn_config: tokio_postgres::Config,
+    on_schema_change: OnSchemaChange,
+    snapshot_parallelism: usize,
+    snapshot_chunk_size: u64,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -51,6 +53,9 @@ impl<'a> PostgresIterator<'a> {
         replication_conn_config: tokio_postgres::Config,
         ingestor: &'a Ingestor,
         conn_config: tokio_postgres::Config,
+        on_schema_change: OnSchemaChange,
+        snapshot_parallelism: usize,
+        snapshot_chunk_size: u64,
     ) -> Self {
         let details = Arc::new(Details {
             name,
@@ -59,6 +64,9 @@ impl<'a> PostgresIterator<'a> {
             tables,
             replication_conn_config,
             conn_config,
+            on_schema_change,
+            snapshot_parallelism,
+            snapshot_chunk_size,
         });
         PostgresIterator { details, ingestor }
     }
@@ -131,25 +139,16 @@ impl<'a> PostgresIteratorHandler<'a> {
                     .map_err(InvalidQueryError)?;
             }
 
-            client
-                .simple_query("BEGIN READ ONLY ISOLATION LEVEL REPEATABLE READ;")
-                .await
-                .map_err(|_e| {
-                    debug!("failed to begin txn for replication");
-                    PostgresConnectorError::BeginReplication
-                })?;
-
-            let replication_slot_lsn =
-                ReplicationSlotHelper::create_replication_slot(&client, &details.slot_name).await?;
-            if let Some(lsn) = replication_slot_lsn {
-                let parsed_lsn =
-                    PgLsn::from_str(&lsn).map_err(|_| LsnParseError(lsn.to_string()))?;
-                self.lsn = Some((parsed_lsn, 0));
-            } else {
-                return Err(ConnectorError::PostgresConnectorError(
-                    LsnNotReturnedFromReplicationSlot,
-                ));
-            }
+            // The exported snapshot lets every snapshot connection read the tables as of the slot's consistent
+            // point. It stays valid as long as `client` stays idle.
+            let (lsn, snapshot) =
+                ReplicationSlotHelper::create_replication_slot_exporting_snapshot(
+                    &client,
+                    &details.slot_name,
+                )
+                .await?;
+            let parsed_lsn = PgLsn::from_str(&lsn).map_err(|_| LsnParseError(lsn.to_string()))?;
+            self.lsn = Some((parsed_lsn, 0));
 
             self.state = ReplicationState::SnapshotInProgress;
 
@@ -159,6 +158,9 @@ impl<'a> PostgresIteratorHandler<'a> {
             let snapshotter = PostgresSnapshotter {
                 conn_config: details.conn_config.to_owned(),
                 ingestor: self.ingestor,
+                snapshot: Some(snapshot),
+                parallelism: details.snapshot_parallelism,
+                chunk_size: details.snapshot_chunk_size,
             };
             let tables = details
                 .tables
@@ -177,11 +179,6 @@ impl<'a> PostgresIteratorHandler<'a> {
                 .map_err(ConnectorError::IngestorError)?;
 
             debug!("\nInitialized with tables: {:?}", details.tables);
-
-            client.simple_query("COMMIT;").await.map_err(|_e| {
-                debug!("failed to commit txn for replication");
-                ConnectorError::PostgresConnectorError(PostgresConnectorError::CommitReplication)
-            })?;
         }
 
         self.state = ReplicationState::Replicating;
@@ -201,6 +198,8 @@ impl<'a> PostgresIteratorHandler<'a> {
         let tables = self.details.tables.clone();
         let mut replicator = CDCHandler {
             replication_conn_config: self.details.replication_conn_config.clone(),
+            conn_config: self.details.conn_config.clone(),
+            client: None,
             ingestor: self.ingestor,
             start_lsn: *lsn,
             begin_lsn: 0,
@@ -210,6 +209,8 @@ impl<'a> PostgresIteratorHandler<'a> {
             slot_name,
             last_commit_lsn: 0,
             seq_no: 0,
+            in_transaction: false,
+            on_schema_change: self.details.on_schema_change,
             name: self.details.name.clone(),
         };
         replicator.start(tables).await
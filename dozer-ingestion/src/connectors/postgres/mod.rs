@@ -10,4 +10,5 @@ pub mod snapshotter;
 pub mod test_utils;
 #[cfg(test)]
 pub mod tests;
+pub mod trigger_replicator;
 pub mod xlog_mapper;
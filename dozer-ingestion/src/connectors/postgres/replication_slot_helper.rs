@@ -47,6 +47,42 @@ impl ReplicationSlotHelper {
         }
     }
 
+    /// Creates the replication slot outside a transaction and exports its snapshot, so that other connections can
+    /// read the tables as of the slot's consistent point. Returns the consistent point and the snapshot name.
+    ///
+    /// The snapshot stays valid until `client` runs another query.
+    pub async fn create_replication_slot_exporting_snapshot(
+        client: &Client,
+        slot_name: &str,
+    ) -> Result<(String, String), ConnectorError> {
+        let create_replication_slot_query =
+            format!(r#"CREATE_REPLICATION_SLOT {slot_name:?} LOGICAL "pgoutput" EXPORT_SNAPSHOT"#);
+
+        let slot_query_row = client
+            .simple_query(&create_replication_slot_query)
+            .await
+            .map_err(|e| {
+                debug!("failed to create replication slot {}", slot_name);
+                ConnectorError::PostgresConnectorError(PostgresConnectorError::CreateSlotError(
+                    slot_name.to_string(),
+                    e,
+                ))
+            })?;
+
+        if let SimpleQueryMessage::Row(row) = &slot_query_row[0] {
+            match (row.get("consistent_point"), row.get("snapshot_name")) {
+                (Some(lsn), Some(snapshot_name)) => {
+                    Ok((lsn.to_string(), snapshot_name.to_string()))
+                }
+                _ => Err(ConnectorError::PostgresConnectorError(
+                    PostgresConnectorError::LsnNotReturnedFromReplicationSlot,
+                )),
+            }
+        } else {
+            Err(UnexpectedQueryMessageError)
+        }
+    }
+
     pub async fn replication_slot_exists(
         client: &Client,
         slot_name: &str,
@@ -1,32 +1,112 @@
 use crate::connectors::postgres::connection::helper;
-use crate::connectors::postgres::xlog_mapper::XlogMapper;
+use crate::connectors::postgres::helper::postgres_type_to_field;
+use crate::connectors::postgres::xlog_mapper::{TableColumns, UnchangedToast, XlogMapper};
 use crate::errors::ConnectorError;
 use crate::errors::ConnectorError::PostgresConnectorError;
 use crate::errors::PostgresConnectorError::{
-    ReplicationStreamEndError, ReplicationStreamError, UnexpectedReplicationMessageError,
+    InvalidQueryError, PostgresSchemaError, ReplicationStreamEndError, ReplicationStreamError,
+    SchemaChanged, UnexpectedReplicationMessageError,
 };
+use crate::errors::PostgresSchemaError::{JSONBParseError, ValueConversionError};
 use crate::ingestion::Ingestor;
 use dozer_types::bytes;
 use dozer_types::chrono::{TimeZone, Utc};
-use dozer_types::ingestion_types::IngestionMessage;
-use dozer_types::log::{error, info};
+use dozer_types::ingestion_types::{IngestionMessage, SchemaDriftKind};
+use dozer_types::log::{error, info, warn};
+use dozer_types::models::connection::OnSchemaChange;
+use dozer_types::serde_json;
+use dozer_types::types::Operation;
 use futures::StreamExt;
 use postgres_protocol::message::backend::ReplicationMessage::*;
 use postgres_protocol::message::backend::{LogicalReplicationMessage, ReplicationMessage};
 use postgres_types::PgLsn;
 
+use std::collections::HashMap;
 use std::time::SystemTime;
 use tokio_postgres::replication::LogicalReplicationStream;
-use tokio_postgres::Error;
+use tokio_postgres::types::{Kind, ToSql, Type};
+use tokio_postgres::{Error, SimpleQueryMessage};
 
-use super::schema::helper::PostgresTableInfo;
+use super::schema::helper::{PostgresTableInfo, SchemaHelper};
 use super::xlog_mapper::MappedReplicationMessage;
 
+const ENUM_TYPES_SQL: &str = "
+SELECT t.oid,
+       t.typname,
+       n.nspname,
+       array_to_json(ARRAY(SELECT e.enumlabel::text
+                           FROM pg_enum e
+                           WHERE e.enumtypid = t.oid
+                           ORDER BY e.enumsortorder))
+FROM pg_type t
+         JOIN pg_namespace n ON n.oid = t.typnamespace
+WHERE t.typtype = 'e';";
+
+/// The enum types by oid, with their labels. Replication connections only run simple queries.
+async fn get_enum_types(
+    client: &tokio_postgres::Client,
+) -> Result<HashMap<u32, Type>, ConnectorError> {
+    let messages = client
+        .simple_query(ENUM_TYPES_SQL)
+        .await
+        .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+    let mut enum_types = HashMap::new();
+    for message in messages {
+        let SimpleQueryMessage::Row(row) = message else {
+            continue;
+        };
+        let column = |index: usize| row.get(index).unwrap_or_default().to_string();
+        let oid = column(0)
+            .parse::<u32>()
+            .map_err(|e| PostgresSchemaError(ValueConversionError(e.to_string())))?;
+        let labels = serde_json::from_str::<Vec<String>>(&column(3))
+            .map_err(|e| PostgresSchemaError(JSONBParseError(e.to_string())))?;
+        enum_types.insert(
+            oid,
+            Type::new(column(1), oid, Kind::Enum(labels), column(2)),
+        );
+    }
+    Ok(enum_types)
+}
+
+/// The columns of the tables by relation id. The leaf partitions of a partitioned table map to its columns.
+fn tables_columns(
+    tables: Vec<PostgresTableInfo>,
+    mut partitions: HashMap<u32, Vec<u32>>,
+) -> HashMap<u32, TableColumns> {
+    tables
+        .into_iter()
+        .enumerate()
+        .flat_map(|(table_index, table_info)| {
+            let relation_ids = std::iter::once(table_info.relation_id)
+                .chain(
+                    partitions
+                        .remove(&table_info.relation_id)
+                        .unwrap_or_default(),
+                )
+                .collect::<Vec<_>>();
+            let table_columns = TableColumns {
+                table_index,
+                schema: table_info.schema,
+                name: table_info.name,
+                names: table_info.columns,
+                all_columns: table_info.all_columns,
+            };
+            relation_ids
+                .into_iter()
+                .map(move |relation_id| (relation_id, table_columns.clone()))
+        })
+        .collect()
+}
+
 pub struct CDCHandler<'a> {
     pub name: String,
     pub ingestor: &'a Ingestor,
 
     pub replication_conn_config: tokio_postgres::Config,
+    pub conn_config: tokio_postgres::Config,
+    /// Connection reading unchanged TOAST values, opened when first needed.
+    pub client: Option<tokio_postgres::Client>,
     pub publication_name: String,
     pub slot_name: String,
 
@@ -37,12 +117,17 @@ pub struct CDCHandler<'a> {
 
     pub offset: u64,
     pub seq_no: u64,
+    /// Whether `TransactionBegin` was sent for the current transaction, which is done before its first operation.
+    pub in_transaction: bool,
+    pub on_schema_change: OnSchemaChange,
 }
 
 impl<'a> CDCHandler<'a> {
     pub async fn start(&mut self, tables: Vec<PostgresTableInfo>) -> Result<(), ConnectorError> {
         let replication_conn_config = self.replication_conn_config.clone();
         let client: tokio_postgres::Client = helper::connect(replication_conn_config).await?;
+        // Queried before the connection starts streaming. Values added to the types later aren't known until restart.
+        let enum_types = get_enum_types(&client).await?;
 
         info!(
             "[{}] Starting Replication: {:?}, {:?}",
@@ -70,14 +155,16 @@ impl<'a> CDCHandler<'a> {
             .map_err(|e| ConnectorError::InternalError(Box::new(e)))?;
 
         let stream = LogicalReplicationStream::new(copy_stream);
-        let tables_columns = tables
-            .into_iter()
-            .enumerate()
-            .map(|(table_index, table_info)| {
-                (table_info.relation_id, (table_index, table_info.columns))
-            })
-            .collect();
-        let mut mapper = XlogMapper::new(tables_columns);
+        // Publications with `publish_via_partition_root` publish the changes of partitions as changes of their
+        // partitioned table. Otherwise they're mapped to it here. Partitions attached later aren't known until restart.
+        let relation_ids = tables
+            .iter()
+            .map(|table_info| table_info.relation_id)
+            .collect::<Vec<_>>();
+        let partitions = SchemaHelper::new(self.conn_config.clone())
+            .get_partitions(&relation_ids)
+            .await?;
+        let mut mapper = XlogMapper::new(tables_columns(tables, partitions), enum_types);
 
         tokio::pin!(stream);
         loop {
@@ -103,18 +190,21 @@ impl<'a> CDCHandler<'a> {
                         .await
                         .unwrap();
                 }
-            } else {
-                self.handle_replication_message(message, &mut mapper)
-                    .await?;
+            } else if self
+                .handle_replication_message(message, &mut mapper)
+                .await?
+            {
+                return Ok(());
             }
         }
     }
 
+    /// Returns whether replication stopped, because the schema of a table changed and the pipeline must be rebuilt.
     pub async fn handle_replication_message(
         &mut self,
         message: Option<Result<ReplicationMessage<LogicalReplicationMessage>, Error>>,
         mapper: &mut XlogMapper,
-    ) -> Result<(), ConnectorError> {
+    ) -> Result<bool, ConnectorError> {
         match message {
             Some(Ok(XLogData(body))) => {
                 let lsn = body.wal_start();
@@ -124,15 +214,40 @@ impl<'a> CDCHandler<'a> {
 
                 match message {
                     Some(MappedReplicationMessage::Commit(commit)) => {
+                        if self.in_transaction {
+                            self.ingestor
+                                .handle_message(IngestionMessage::new_transaction_end(
+                                    self.begin_lsn,
+                                    self.seq_no + 1,
+                                ))
+                                .map_err(ConnectorError::IngestorError)?;
+                            self.in_transaction = false;
+                        }
                         self.last_commit_lsn = commit.txid;
                     }
                     Some(MappedReplicationMessage::Begin) => {
                         self.begin_lsn = lsn;
                         self.seq_no = 0;
                     }
-                    Some(MappedReplicationMessage::Operation { table_index, op }) => {
+                    Some(MappedReplicationMessage::Operation {
+                        table_index,
+                        mut op,
+                        unchanged_toast,
+                    }) => {
                         self.seq_no += 1;
                         if self.begin_lsn != self.offset_lsn || self.offset < self.seq_no {
+                            if let Some(unchanged_toast) = unchanged_toast {
+                                self.read_unchanged_toast(&unchanged_toast, &mut op).await?;
+                            }
+                            if !self.in_transaction {
+                                self.ingestor
+                                    .handle_message(IngestionMessage::new_transaction_begin(
+                                        self.begin_lsn,
+                                        0,
+                                    ))
+                                    .map_err(ConnectorError::IngestorError)?;
+                                self.in_transaction = true;
+                            }
                             self.ingestor
                                 .handle_message(IngestionMessage::new_op(
                                     self.begin_lsn,
@@ -143,10 +258,68 @@ impl<'a> CDCHandler<'a> {
                                 .map_err(ConnectorError::IngestorError)?;
                         }
                     }
+                    Some(MappedReplicationMessage::SchemaDrift {
+                        table_index,
+                        table_name,
+                        drifts,
+                    }) => {
+                        // Compatible type changes keep the schema of the source.
+                        let changed_columns = drifts
+                            .iter()
+                            .filter(|drift| {
+                                !matches!(
+                                    drift.kind,
+                                    SchemaDriftKind::TypeChanged {
+                                        compatible: true,
+                                        ..
+                                    }
+                                )
+                            })
+                            .map(|drift| format!("{}: {}", drift.column_name, drift.kind))
+                            .collect::<Vec<_>>();
+                        for drift in drifts {
+                            self.ingestor
+                                .handle_message(IngestionMessage::new_schema_drift(
+                                    self.begin_lsn,
+                                    self.seq_no,
+                                    table_index,
+                                    drift,
+                                ))
+                                .map_err(ConnectorError::IngestorError)?;
+                        }
+
+                        if !changed_columns.is_empty() {
+                            match self.on_schema_change {
+                                OnSchemaChange::IgnoreNewColumns(()) => {}
+                                OnSchemaChange::Fail(()) => {
+                                    return Err(PostgresConnectorError(SchemaChanged(
+                                        table_name,
+                                        changed_columns.join(", "),
+                                    )));
+                                }
+                                OnSchemaChange::Evolve(()) => {
+                                    info!(
+                                        "[{}] Schema of table {} changed ({}), stopping replication to rebuild the pipeline",
+                                        self.name,
+                                        table_name,
+                                        changed_columns.join(", ")
+                                    );
+                                    self.ingestor
+                                        .handle_message(IngestionMessage::new_schema_changed(
+                                            self.begin_lsn,
+                                            self.seq_no,
+                                            table_index,
+                                        ))
+                                        .map_err(ConnectorError::IngestorError)?;
+                                    return Ok(true);
+                                }
+                            }
+                        }
+                    }
                     None => {}
                 }
 
-                Ok(())
+                Ok(false)
             }
             Some(Ok(msg)) => {
                 error!("Unexpected message: {:?}", msg);
@@ -158,4 +331,81 @@ impl<'a> CDCHandler<'a> {
             None => Err(PostgresConnectorError(ReplicationStreamEndError)),
         }
     }
+
+    /// Reads the unchanged TOAST columns of an update from the table into its new record. The values read can be
+    /// newer than the update, if the row changed since.
+    async fn read_unchanged_toast(
+        &mut self,
+        unchanged_toast: &UnchangedToast,
+        op: &mut Operation,
+    ) -> Result<(), ConnectorError> {
+        let Operation::Update { new, .. } = op else {
+            return Ok(());
+        };
+        let client = match &mut self.client {
+            Some(client) => client,
+            client => client.insert(helper::connect(self.conn_config.clone()).await?),
+        };
+
+        let key_types = vec![Type::TEXT; unchanged_toast.key.len()];
+        let statement = client
+            .prepare_typed(&unchanged_toast.query(), &key_types)
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+        let key_values = unchanged_toast
+            .key
+            .iter()
+            .map(|(_, value)| value as &(dyn ToSql + Sync))
+            .collect::<Vec<_>>();
+        let Some(row) = client
+            .query_opt(&statement, &key_values)
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?
+        else {
+            warn!(
+                "[{}] Row of {} was deleted before its unchanged TOAST values were read, they're null",
+                self.name, unchanged_toast.table
+            );
+            return Ok(());
+        };
+
+        for (row_index, (index, column)) in unchanged_toast.columns.iter().enumerate() {
+            let value = row
+                .try_get::<_, Option<String>>(row_index)
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            new.values[*index] =
+                postgres_type_to_field(value.map(bytes::Bytes::from).as_ref(), column)
+                    .map_err(PostgresSchemaError)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_columns_of_partitions() {
+        let table = |name: &str, relation_id| PostgresTableInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            relation_id,
+            columns: vec!["id".to_string()],
+            all_columns: true,
+        };
+        let tables = vec![table("users", 10), table("events", 20)];
+        let partitions = [(20, vec![21, 22])].into_iter().collect();
+
+        let tables_columns = tables_columns(tables, partitions);
+        let table_of = |relation_id| {
+            let columns = &tables_columns[&relation_id];
+            (columns.table_index, columns.name.as_str())
+        };
+        assert_eq!(tables_columns.len(), 4);
+        assert_eq!(table_of(10), (0, "users"));
+        assert_eq!(table_of(20), (1, "events"));
+        assert_eq!(table_of(21), (1, "events"));
+        assert_eq!(table_of(22), (1, "events"));
+    }
 }
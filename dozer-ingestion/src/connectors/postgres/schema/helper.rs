@@ -79,6 +79,8 @@ pub struct PostgresTableInfo {
     pub name: String,
     pub relation_id: u32,
     pub columns: Vec<String>,
+    /// Whether the source reads all columns of the table, so that columns added later are new to it.
+    pub all_columns: bool,
 }
 
 type RowsWithColumnsMap = (Vec<Row>, HashMap<SchemaTableIdentifier, Vec<String>>);
@@ -130,6 +132,7 @@ impl SchemaHelper {
                     &table_columns_map,
                     table.schema.as_deref(),
                     &table.name,
+                    table.columns.as_ref().map_or(true, |c| c.is_empty()),
                 )?);
             }
             result
@@ -142,6 +145,7 @@ impl SchemaHelper {
                         relation_id,
                         columns,
                         schema,
+                        all_columns: true,
                     },
                 )
                 .collect()
@@ -185,7 +189,12 @@ impl SchemaHelper {
             );
             client.query(&sql, &[&schemas, &table_names]).await
         } else {
-            let sql = str::replace(SQL, ":tables_name_condition", "t.table_type = 'BASE TABLE'");
+            // Partitions are replicated as part of their partitioned table.
+            let sql = str::replace(
+                SQL,
+                ":tables_name_condition",
+                "t.table_type = 'BASE TABLE' AND NOT COALESCE(pc.relispartition, false)",
+            );
             client.query(&sql, &[]).await
         };
 
@@ -194,6 +203,23 @@ impl SchemaHelper {
             .map(|rows| (rows, tables_columns_map))
     }
 
+    /// The relation ids of the leaf partitions of the partitioned tables among `relation_ids`, by table.
+    pub async fn get_partitions(
+        &self,
+        relation_ids: &[u32],
+    ) -> Result<HashMap<u32, Vec<u32>>, PostgresConnectorError> {
+        let client = helper::connect(self.conn_config.clone()).await?;
+        let rows = client
+            .query(PARTITIONS_SQL, &[&relation_ids])
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?;
+        let mut partitions = HashMap::<u32, Vec<u32>>::new();
+        for row in rows {
+            partitions.entry(row.get(0)).or_default().push(row.get(1));
+        }
+        Ok(partitions)
+    }
+
     pub async fn get_schemas(
         &self,
         tables: &[ListOrFilterColumns],
@@ -319,10 +345,11 @@ impl SchemaHelper {
         let is_column_used_in_index: bool = row.get(3);
         let replication_type_int: i8 = row.get(5);
         let type_oid: u32 = row.get(6);
+        let is_enum: bool = row.get(9);
+        let enum_values: Vec<String> = row.get(10);
 
-        // TODO: workaround - in case of custom enum
-        let typ = if type_oid == 28862 {
-            FieldType::String
+        let typ = if is_enum {
+            FieldType::Enum
         } else {
             let oid_typ = Type::from_oid(type_oid);
             oid_typ.map_or_else(
@@ -337,7 +364,8 @@ impl SchemaHelper {
         Ok(PostgresTableRow {
             schema,
             table_name,
-            field: FieldDefinition::new(column_name, typ, is_nullable, SourceDefinition::Dynamic),
+            field: FieldDefinition::new(column_name, typ, is_nullable, SourceDefinition::Dynamic)
+                .with_enum_values(enum_values),
             is_column_used_in_index,
             replication_type,
         })
@@ -346,10 +374,28 @@ impl SchemaHelper {
 
 pub const DEFAULT_SCHEMA_NAME: &str = "public";
 
+/// Leaf partitions of partitioned tables, with their partitioned table, through sub-partitioned partitions.
+const PARTITIONS_SQL: &str = "
+WITH RECURSIVE partitions(root, relid) AS (
+    SELECT i.inhparent, i.inhrelid
+    FROM pg_inherits i
+             JOIN pg_class parent ON parent.oid = i.inhparent AND parent.relkind = 'p'
+    WHERE i.inhparent = ANY ($1)
+    UNION ALL
+    SELECT p.root, i.inhrelid
+    FROM partitions p
+             JOIN pg_inherits i ON i.inhparent = p.relid
+)
+SELECT p.root, p.relid
+FROM partitions p
+         JOIN pg_class c ON c.oid = p.relid
+WHERE c.relkind = 'r';";
+
 fn find_table(
     table_columns_map: &HashMap<SchemaTableIdentifier, (u32, Vec<String>)>,
     schema_name: Option<&str>,
     table_name: &str,
+    all_columns: bool,
 ) -> Result<PostgresTableInfo, PostgresConnectorError> {
     let schema_name = schema_name.unwrap_or(DEFAULT_SCHEMA_NAME);
     let schema_table_identifier = (schema_name.to_string(), table_name.to_string());
@@ -359,6 +405,7 @@ fn find_table(
             name: schema_table_identifier.1,
             relation_id: *relation_id,
             columns: columns.clone(),
+            all_columns,
         })
     } else {
         Err(PostgresConnectorError::TablesNotFound(vec![
@@ -382,7 +429,12 @@ SELECT table_info.table_name,
        pc.relreplident,
        pt.oid                                                           AS type_oid,
        t.table_type,
-       t.table_schema
+       t.table_schema,
+       COALESCE(pt.typtype = 'e', false)                                AS is_enum,
+       ARRAY(SELECT pe.enumlabel::text
+             FROM pg_enum pe
+             WHERE pe.enumtypid = pt.oid
+             ORDER BY pe.enumsortorder)                                 AS enum_values
 FROM information_schema.columns table_info
          LEFT JOIN information_schema.tables t ON t.table_name = table_info.table_name AND t.table_schema = table_info.table_schema
          LEFT JOIN pg_namespace ns ON t.table_schema = ns.nspname
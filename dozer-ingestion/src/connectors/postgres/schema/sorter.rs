@@ -90,6 +90,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             false,
         );
@@ -99,6 +100,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             true,
         );
@@ -108,6 +110,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             },
             false,
         );
@@ -172,3 +172,47 @@ async fn test_connector_view_cannot_be_used() {
     })
     .await
 }
+
+#[tokio::test]
+#[ignore]
+#[serial]
+async fn test_connector_get_tables_of_partitioned_table() {
+    run_connector_test("postgres", |app_config| async move {
+        let client = get_client(app_config).await;
+
+        let mut rng = rand::thread_rng();
+
+        let schema = format!("schema_helper_test_{}", rng.gen::<u32>());
+
+        client.create_schema(&schema).await;
+        for query in [
+            format!("CREATE TABLE {schema}.events (id INT, region TEXT, year INT) PARTITION BY LIST (region)"),
+            format!("CREATE TABLE {schema}.events_eu PARTITION OF {schema}.events FOR VALUES IN ('eu')"),
+            format!("CREATE TABLE {schema}.events_us PARTITION OF {schema}.events FOR VALUES IN ('us') PARTITION BY RANGE (year)"),
+            format!("CREATE TABLE {schema}.events_us_2023 PARTITION OF {schema}.events_us FOR VALUES FROM (2023) TO (2024)"),
+        ] {
+            client.execute_query(&query).await;
+        }
+
+        let schema_helper = SchemaHelper::new(client.postgres_config.clone());
+        let tables = schema_helper
+            .get_tables(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|table| table.schema == schema)
+            .collect::<Vec<_>>();
+        // Partitions are replicated as part of their partitioned table.
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "events");
+
+        let relation_id = tables[0].relation_id;
+        let partitions = schema_helper.get_partitions(&[relation_id]).await.unwrap();
+        // Only the leaf partitions hold rows.
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[&relation_id].len(), 2);
+
+        client.drop_schema(&schema).await;
+    })
+    .await
+}
@@ -14,12 +14,76 @@ use dozer_types::types::Schema;
 use dozer_types::ingestion_types::IngestionMessage;
 
 use dozer_types::types::Operation;
+use futures::future::try_join_all;
 use futures::StreamExt;
-use tokio::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::Mutex;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
 
 pub struct PostgresSnapshotter<'a> {
     pub conn_config: tokio_postgres::Config,
     pub ingestor: &'a Ingestor,
+    /// Exported snapshot every connection reads the tables from, so that they all see the same data.
+    pub snapshot: Option<String>,
+    /// Number of connections reading each table, in primary key chunks. Tables without a primary key are read by one
+    /// connection.
+    pub parallelism: usize,
+    /// Number of rows in a primary key chunk.
+    pub chunk_size: u64,
+}
+
+/// Primary key bounds of a chunk, as text. The lower bound is exclusive and the upper bound inclusive. `None` means
+/// unbounded.
+type ChunkRange = (Option<Vec<String>>, Option<Vec<String>>);
+
+/// A table read in primary key chunks.
+struct ChunkedTable {
+    /// Qualified name of the table.
+    table: String,
+    /// Quoted names of the columns to read.
+    columns: String,
+    /// Quoted names of the primary key columns.
+    primary_key: Vec<String>,
+    /// Quoted types of the primary key columns, which the text bounds are cast to.
+    primary_key_types: Vec<String>,
+}
+
+impl ChunkedTable {
+    /// Returns the `where` clause restricting the primary key to the bounds, and its parameters.
+    fn condition(
+        &self,
+        lower: Option<&[String]>,
+        upper: Option<&[String]>,
+    ) -> (String, Vec<String>) {
+        let key = format!("({})", self.primary_key.join(","));
+        let mut conditions = vec![];
+        let mut params = vec![];
+        for (op, bound) in [(">", lower), ("<=", upper)] {
+            let Some(bound) = bound else {
+                continue;
+            };
+            let placeholders = self
+                .primary_key_types
+                .iter()
+                .enumerate()
+                .map(|(i, typ)| format!("${}::text::{typ}", params.len() + i + 1))
+                .collect::<Vec<_>>()
+                .join(",");
+            conditions.push(format!("{key} {op} ({placeholders})"));
+            params.extend(bound.iter().cloned());
+        }
+        if conditions.is_empty() {
+            (String::new(), params)
+        } else {
+            (format!(" where {}", conditions.join(" and ")), params)
+        }
+    }
+}
+
+fn as_params(params: &[String]) -> Vec<&(dyn ToSql + Sync)> {
+    params.iter().map(|p| p as &(dyn ToSql + Sync)).collect()
 }
 
 impl<'a> PostgresSnapshotter<'a> {
@@ -34,35 +98,53 @@ impl<'a> PostgresSnapshotter<'a> {
             .map_err(PostgresConnectorError)
     }
 
-    pub async fn sync_table(
-        schema: Schema,
-        schema_name: String,
-        table_name: String,
-        table_index: usize,
+    /// Connects and, if there's a `snapshot`, starts a read only transaction reading from it.
+    async fn connect_to_snapshot(
         conn_config: tokio_postgres::Config,
-        sender: Sender<Result<Option<(usize, Operation)>, ConnectorError>>,
-    ) -> Result<(), ConnectorError> {
-        let client_plain = connection_helper::connect(conn_config)
+        snapshot: Option<&str>,
+    ) -> Result<Client, ConnectorError> {
+        let client = connection_helper::connect(conn_config)
             .await
             .map_err(PostgresConnectorError)?;
+        if let Some(snapshot) = snapshot {
+            client
+                .simple_query(&format!(
+                    "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY; SET TRANSACTION SNAPSHOT '{snapshot}';"
+                ))
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+        }
+        Ok(client)
+    }
 
-        let column_str: Vec<String> = schema
-            .fields
-            .iter()
-            .map(|f| format!("\"{0}\"", f.name))
-            .collect();
+    async fn finish_snapshot(
+        client: &Client,
+        snapshot: Option<&str>,
+    ) -> Result<(), ConnectorError> {
+        if snapshot.is_some() {
+            client
+                .simple_query("COMMIT;")
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+        }
+        Ok(())
+    }
 
-        let column_str = column_str.join(",");
-        let query = format!("select {column_str} from {schema_name}.{table_name}");
-        let stmt = client_plain
-            .prepare(&query)
+    async fn read_rows(
+        client: &Client,
+        query: &str,
+        params: Vec<String>,
+        table_index: usize,
+        sender: &Sender<Result<Option<(usize, Operation)>, ConnectorError>>,
+    ) -> Result<(), ConnectorError> {
+        let stmt = client
+            .prepare(query)
             .await
             .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
         let columns = stmt.columns();
 
-        let empty_vec: Vec<String> = Vec::new();
-        let row_stream = client_plain
-            .query_raw(&stmt, empty_vec)
+        let row_stream = client
+            .query_raw(&stmt, params)
             .await
             .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
         tokio::pin!(row_stream);
@@ -77,6 +159,152 @@ impl<'a> PostgresSnapshotter<'a> {
                 Err(e) => return Err(PostgresConnectorError(SyncWithSnapshotError(e.to_string()))),
             }
         }
+        Ok(())
+    }
+
+    /// Splits the table in chunks of `chunk_size` rows by primary key and sends their ranges, in key order.
+    async fn send_chunks(
+        client: &Client,
+        table: &ChunkedTable,
+        chunk_size: u64,
+        ranges: Sender<ChunkRange>,
+    ) -> Result<(), ConnectorError> {
+        let key_text = table
+            .primary_key
+            .iter()
+            .map(|column| format!("{column}::text"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut lower: Option<Vec<String>> = None;
+        loop {
+            let (condition, params) = table.condition(lower.as_deref(), None);
+            let query = format!(
+                "select {key_text} from {}{condition} order by {} limit 1 offset {}",
+                table.table,
+                table.primary_key.join(","),
+                chunk_size.max(1) - 1
+            );
+            let row = client
+                .query_opt(&query, &as_params(&params))
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            let upper = row
+                .map(|row| {
+                    (0..table.primary_key.len())
+                        .map(|i| row.try_get::<_, String>(i))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+
+            let is_last = upper.is_none();
+            // The readers are gone if one of them failed.
+            if ranges.send((lower, upper.clone())).await.is_err() || is_last {
+                return Ok(());
+            }
+            lower = upper;
+        }
+    }
+
+    /// Reads the chunks whose ranges are received from `ranges` until there are none left.
+    async fn read_chunks(
+        conn_config: tokio_postgres::Config,
+        snapshot: Option<&str>,
+        table: &ChunkedTable,
+        ranges: Arc<Mutex<Receiver<ChunkRange>>>,
+        table_index: usize,
+        sender: &Sender<Result<Option<(usize, Operation)>, ConnectorError>>,
+    ) -> Result<(), ConnectorError> {
+        let client = Self::connect_to_snapshot(conn_config, snapshot).await?;
+        loop {
+            let range = ranges.lock().await.recv().await;
+            let Some((lower, upper)) = range else {
+                break;
+            };
+            let (condition, params) = table.condition(lower.as_deref(), upper.as_deref());
+            let query = format!("select {} from {}{condition}", table.columns, table.table);
+            Self::read_rows(&client, &query, params, table_index, sender).await?;
+        }
+        Self::finish_snapshot(&client, snapshot).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sync_table(
+        schema: Schema,
+        schema_name: String,
+        table_name: String,
+        table_index: usize,
+        conn_config: tokio_postgres::Config,
+        snapshot: Option<String>,
+        parallelism: usize,
+        chunk_size: u64,
+        sender: Sender<Result<Option<(usize, Operation)>, ConnectorError>>,
+    ) -> Result<(), ConnectorError> {
+        let snapshot = snapshot.as_deref();
+        let client_plain = Self::connect_to_snapshot(conn_config.clone(), snapshot).await?;
+
+        let column_str: Vec<String> = schema
+            .fields
+            .iter()
+            .map(|f| format!("\"{0}\"", f.name))
+            .collect();
+
+        let column_str = column_str.join(",");
+        let table = format!("{schema_name}.{table_name}");
+
+        if parallelism <= 1 || schema.primary_index.is_empty() {
+            let query = format!("select {column_str} from {table}");
+            Self::read_rows(&client_plain, &query, vec![], table_index, &sender).await?;
+        } else {
+            let primary_key: Vec<String> = schema
+                .primary_index
+                .iter()
+                .map(|index| helper::quote(&schema.fields[*index].name))
+                .collect();
+            let stmt = client_plain
+                .prepare(&format!(
+                    "select {} from {table} limit 0",
+                    primary_key.join(",")
+                ))
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            let primary_key_types = stmt
+                .columns()
+                .iter()
+                .map(|column| {
+                    let typ = column.type_();
+                    format!(
+                        "{}.{}",
+                        helper::quote(typ.schema()),
+                        helper::quote(typ.name())
+                    )
+                })
+                .collect();
+            let table = ChunkedTable {
+                table,
+                columns: column_str,
+                primary_key,
+                primary_key_types,
+            };
+
+            let (ranges_sender, ranges) = channel(parallelism);
+            let ranges = Arc::new(Mutex::new(ranges));
+            let readers = (0..parallelism).map(|_| {
+                Self::read_chunks(
+                    conn_config.clone(),
+                    snapshot,
+                    &table,
+                    ranges.clone(),
+                    table_index,
+                    &sender,
+                )
+            });
+            tokio::try_join!(
+                Self::send_chunks(&client_plain, &table, chunk_size, ranges_sender),
+                try_join_all(readers)
+            )?;
+        }
+        Self::finish_snapshot(&client_plain, snapshot).await?;
 
         // After table read is finished, send None as message to inform receiver loop about end of table
         sender.send(Ok(None)).await.unwrap();
@@ -96,6 +324,9 @@ impl<'a> PostgresSnapshotter<'a> {
             let schema_name = table.schema.clone().unwrap_or("public".to_string());
             let table_name = table.name.clone();
             let conn_config = self.conn_config.clone();
+            let snapshot = self.snapshot.clone();
+            let parallelism = self.parallelism;
+            let chunk_size = self.chunk_size;
             let sender = tx.clone();
             tokio::spawn(async move {
                 if let Err(e) = Self::sync_table(
@@ -104,6 +335,9 @@ impl<'a> PostgresSnapshotter<'a> {
                     table_name,
                     table_index,
                     conn_config,
+                    snapshot,
+                    parallelism,
+                    chunk_size,
                     sender.clone(),
                 )
                 .await
@@ -164,7 +398,38 @@ mod tests {
         test_util::run_connector_test,
     };
 
-    use super::PostgresSnapshotter;
+    use super::{ChunkedTable, PostgresSnapshotter};
+
+    #[test]
+    fn test_chunk_condition() {
+        let table = ChunkedTable {
+            table: "public.users".to_string(),
+            columns: r#""id","name""#.to_string(),
+            primary_key: vec![r#""id""#.to_string(), r#""name""#.to_string()],
+            primary_key_types: vec![
+                r#""pg_catalog"."int4""#.to_string(),
+                r#""pg_catalog"."text""#.to_string(),
+            ],
+        };
+        assert_eq!(table.condition(None, None), (String::new(), vec![]));
+
+        let lower = vec!["1".to_string(), "a".to_string()];
+        let upper = vec!["5".to_string(), "b".to_string()];
+        assert_eq!(
+            table.condition(None, Some(&upper[..])),
+            (
+                r#" where ("id","name") <= ($1::text::"pg_catalog"."int4",$2::text::"pg_catalog"."text")"#.to_string(),
+                upper.clone()
+            )
+        );
+        assert_eq!(
+            table.condition(Some(&lower[..]), Some(&upper[..])),
+            (
+                r#" where ("id","name") > ($1::text::"pg_catalog"."int4",$2::text::"pg_catalog"."text") and ("id","name") <= ($3::text::"pg_catalog"."int4",$4::text::"pg_catalog"."text")"#.to_string(),
+                vec!["1".to_string(), "a".to_string(), "5".to_string(), "b".to_string()]
+            )
+        );
+    }
 
     #[tokio::test]
     #[ignore]
@@ -201,6 +466,9 @@ mod tests {
             let snapshotter = PostgresSnapshotter {
                 conn_config,
                 ingestor: &ingestor,
+                snapshot: None,
+                parallelism: 1,
+                chunk_size: 100000,
             };
 
             let actual = snapshotter.sync_tables(&input_tables).await;
@@ -260,6 +528,9 @@ mod tests {
             let snapshotter = PostgresSnapshotter {
                 conn_config,
                 ingestor: &ingestor,
+                snapshot: None,
+                parallelism: 1,
+                chunk_size: 100000,
             };
 
             let actual = snapshotter.sync_tables(&input_tables).await;
@@ -306,6 +577,9 @@ mod tests {
             let snapshotter = PostgresSnapshotter {
                 conn_config,
                 ingestor: &ingestor,
+                snapshot: None,
+                parallelism: 1,
+                chunk_size: 100000,
             };
 
             let actual = snapshotter.sync_tables(&input_tables).await;
@@ -32,6 +32,11 @@ mod tests {
             let postgres_config = PostgresConfig {
                 name: "test".to_string(),
                 config: conn_config.clone(),
+                publication: None,
+                trigger_poll_interval: None,
+                on_schema_change: Default::default(),
+                snapshot_parallelism: 1,
+                snapshot_chunk_size: 100000,
             };
 
             let connector = PostgresConnector::new(postgres_config);
@@ -93,6 +98,11 @@ mod tests {
             let postgres_config = PostgresConfig {
                 name: connector_name,
                 config: conn_config.clone(),
+                publication: None,
+                trigger_poll_interval: None,
+                on_schema_change: Default::default(),
+                snapshot_parallelism: 1,
+                snapshot_chunk_size: 100000,
             };
 
             let connector = PostgresConnector::new(postgres_config);
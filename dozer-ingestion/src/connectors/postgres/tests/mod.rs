@@ -1,3 +1,4 @@
 pub mod client;
 mod continue_replication_tests;
 mod e2e;
+mod trigger_replicator_tests;
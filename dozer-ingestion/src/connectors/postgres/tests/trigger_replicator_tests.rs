@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::connectors::postgres::connection::helper;
+    use crate::connectors::postgres::connection::helper::map_connection_config;
+    use crate::connectors::postgres::schema::helper::SchemaHelper;
+    use crate::connectors::postgres::tests::client::TestPostgresClient;
+    use crate::connectors::postgres::trigger_replicator::TriggerReplicator;
+    use crate::connectors::ListOrFilterColumns;
+    use crate::ingestion::{IngestionConfig, Ingestor};
+    use crate::test_util::run_connector_test;
+    use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
+    use dozer_types::types::{Field, Operation};
+    use rand::Rng;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[ignore]
+    #[serial]
+    async fn test_connector_trigger_replicator() {
+        run_connector_test("postgres", |app_config| async move {
+            let config = app_config
+                .connections
+                .get(0)
+                .unwrap()
+                .config
+                .as_ref()
+                .unwrap();
+
+            let test_client = TestPostgresClient::new(config).await;
+            let mut rng = rand::thread_rng();
+            let table_name = format!("test_table_{}", rng.gen::<u32>());
+            test_client.create_simple_table("public", &table_name).await;
+            test_client.insert_rows(&table_name, 2, None).await;
+
+            let conn_config = map_connection_config(config).unwrap();
+            let tables = SchemaHelper::new(conn_config.clone())
+                .get_tables(Some(&[ListOrFilterColumns {
+                    schema: Some("public".to_string()),
+                    name: table_name.clone(),
+                    columns: None,
+                }]))
+                .await
+                .unwrap();
+
+            let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+            let replicator = TriggerReplicator {
+                name: "test".to_string(),
+                conn_config: conn_config.clone(),
+                tables,
+                ingestor: &ingestor,
+                poll_interval: Duration::from_millis(100),
+            };
+
+            let mut client = helper::connect(conn_config).await.unwrap();
+            replicator.provision(&mut client).await.unwrap();
+            replicator.snapshot(&mut client).await.unwrap();
+            let snapshot = (0..4).map(|_| iterator.next().unwrap()).collect::<Vec<_>>();
+            assert!(matches!(
+                snapshot[0].kind,
+                IngestionMessageKind::SnapshottingStarted
+            ));
+            assert!(matches!(
+                snapshot[3].kind,
+                IngestionMessageKind::SnapshottingDone
+            ));
+
+            // Nothing was changed since the snapshot.
+            assert!(!replicator.poll(&mut client, 1).await.unwrap());
+
+            test_client.insert_rows(&table_name, 1, Some(2)).await;
+            test_client
+                .execute_query(&format!(
+                    "UPDATE {table_name} SET name = 'updated' WHERE id = 1"
+                ))
+                .await;
+            test_client
+                .execute_query(&format!("DELETE FROM {table_name} WHERE id = 2"))
+                .await;
+            assert!(replicator.poll(&mut client, 1).await.unwrap());
+
+            let ops = (0..3)
+                .map(|_| match iterator.next().unwrap() {
+                    IngestionMessage {
+                        kind: IngestionMessageKind::OperationEvent { op, .. },
+                        ..
+                    } => op,
+                    message => panic!("Unexpected message {message:?}"),
+                })
+                .collect::<Vec<_>>();
+            assert!(matches!(&ops[0], Operation::Insert { new } if new.values[0] == Field::Int(3)));
+            assert!(matches!(
+                &ops[1],
+                Operation::Update { old, new }
+                    if old.values[1] == Field::String("Product 0".to_string())
+                        && new.values[1] == Field::String("updated".to_string())
+            ));
+            assert!(matches!(&ops[2], Operation::Delete { old } if old.values[0] == Field::Int(2)));
+
+            // The polled changes were deleted.
+            assert!(!replicator.poll(&mut client, 2).await.unwrap());
+        })
+        .await
+    }
+}
@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::info;
+use dozer_types::types::{Field, Operation, Record};
+use futures::StreamExt;
+use tokio_postgres::{Client, Column, IsolationLevel, Row};
+
+use crate::connectors::postgres::connection::helper;
+use crate::connectors::postgres::helper::{map_row_to_operation_event, quote, value_to_field};
+use crate::errors::ConnectorError::PostgresConnectorError;
+use crate::errors::PostgresConnectorError::{
+    CreateTriggersError, InvalidQueryError, PostgresSchemaError, SyncWithSnapshotError,
+};
+use crate::errors::{ConnectorError, PostgresSchemaError as SchemaError};
+use crate::ingestion::Ingestor;
+
+use super::schema::helper::PostgresTableInfo;
+
+/// Schema of the change table and the function the triggers call.
+const CHANGES_SCHEMA: &str = "dozer";
+
+/// Tracks changes of tables with triggers, for databases where logical replication isn't available to the user.
+///
+/// The triggers write every changed row to a change table as JSON, which is polled and cleaned up: the tracked changes
+/// are deleted in the transaction that reads them, and sent as one ingestion transaction.
+pub struct TriggerReplicator<'a> {
+    pub name: String,
+    pub conn_config: tokio_postgres::Config,
+    pub tables: Vec<PostgresTableInfo>,
+    pub ingestor: &'a Ingestor,
+    pub poll_interval: Duration,
+}
+
+impl<'a> TriggerReplicator<'a> {
+    pub async fn start(&self) -> Result<(), ConnectorError> {
+        let mut client = helper::connect(self.conn_config.clone()).await?;
+
+        info!("[{}] Creating change tracking triggers", self.name);
+        self.provision(&mut client).await?;
+        self.snapshot(&mut client).await?;
+
+        info!("[{}] Polling changes", self.name);
+        let mut interval = tokio::time::interval(self.poll_interval);
+        // The snapshot is transaction 0.
+        let mut txid = 1;
+        loop {
+            interval.tick().await;
+            if self.poll(&mut client, txid).await? {
+                txid += 1;
+            }
+        }
+    }
+
+    /// Creates the change table and the triggers of the tables, and drops the triggers of tables no longer tracked.
+    pub async fn provision(&self, client: &mut Client) -> Result<(), ConnectorError> {
+        let changes_table = self.changes_table();
+        let capture_function = self.capture_function();
+        let trigger = quote(&self.trigger_name());
+
+        let transaction = client
+            .transaction()
+            .await
+            .map_err(|e| PostgresConnectorError(CreateTriggersError(e)))?;
+
+        let mut sql = format!(
+            r#"
+            CREATE SCHEMA IF NOT EXISTS {schema};
+            CREATE TABLE IF NOT EXISTS {changes_table} (
+                seq BIGSERIAL PRIMARY KEY,
+                table_schema TEXT NOT NULL,
+                table_name TEXT NOT NULL,
+                op CHAR(1) NOT NULL,
+                old_row JSONB,
+                new_row JSONB
+            );
+            CREATE OR REPLACE FUNCTION {capture_function}() RETURNS TRIGGER AS $$
+            BEGIN
+                INSERT INTO {changes_table} (table_schema, table_name, op, old_row, new_row)
+                VALUES (
+                    TG_TABLE_SCHEMA,
+                    TG_TABLE_NAME,
+                    LEFT(TG_OP, 1),
+                    CASE WHEN TG_OP <> 'INSERT' THEN to_jsonb(OLD) END,
+                    CASE WHEN TG_OP <> 'DELETE' THEN to_jsonb(NEW) END
+                );
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql;
+            "#,
+            schema = quote(CHANGES_SCHEMA),
+        );
+
+        let triggered_tables = transaction
+            .query(
+                "SELECT n.nspname, c.relname FROM pg_trigger t \
+                    JOIN pg_class c ON t.tgrelid = c.oid \
+                    JOIN pg_namespace n ON c.relnamespace = n.oid \
+                    WHERE t.tgname = $1",
+                &[&self.trigger_name()],
+            )
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+        for row in triggered_tables {
+            let (schema, name) = (row.get::<_, String>(0), row.get::<_, String>(1));
+            if !self
+                .tables
+                .iter()
+                .any(|table| table.schema == schema && table.name == name)
+            {
+                sql += &format!(
+                    "DROP TRIGGER {trigger} ON {}.{};",
+                    quote(&schema),
+                    quote(&name)
+                );
+            }
+        }
+
+        for table in &self.tables {
+            let table_name = quoted_table_name(table);
+            sql += &format!(
+                "DROP TRIGGER IF EXISTS {trigger} ON {table_name}; \
+                CREATE TRIGGER {trigger} AFTER INSERT OR UPDATE OR DELETE ON {table_name} \
+                    FOR EACH ROW EXECUTE PROCEDURE {capture_function}();"
+            );
+        }
+
+        transaction
+            .batch_execute(&sql)
+            .await
+            .map_err(|e| PostgresConnectorError(CreateTriggersError(e)))?;
+        transaction
+            .commit()
+            .await
+            .map_err(|e| PostgresConnectorError(CreateTriggersError(e)))
+    }
+
+    /// Sends the rows of the tables and deletes the changes tracked until then, in one repeatable read transaction so
+    /// exactly the changes in the snapshot are deleted.
+    pub async fn snapshot(&self, client: &mut Client) -> Result<(), ConnectorError> {
+        let transaction = client
+            .build_transaction()
+            .isolation_level(IsolationLevel::RepeatableRead)
+            .start()
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+
+        self.ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+
+        let mut seq_no = 0;
+        for (table_index, table) in self.tables.iter().enumerate() {
+            let columns = table
+                .columns
+                .iter()
+                .map(|column| quote(column))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let query = format!("SELECT {columns} FROM {}", quoted_table_name(table));
+            let stmt = transaction
+                .prepare(&query)
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            let row_stream = transaction
+                .query_raw(&stmt, Vec::<String>::new())
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            tokio::pin!(row_stream);
+            while let Some(row) = row_stream.next().await {
+                let row =
+                    row.map_err(|e| PostgresConnectorError(SyncWithSnapshotError(e.to_string())))?;
+                let op = map_row_to_operation_event(&row, stmt.columns())
+                    .map_err(|e| PostgresConnectorError(PostgresSchemaError(e)))?;
+                self.ingestor
+                    .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                    .map_err(ConnectorError::IngestorError)?;
+                seq_no += 1;
+            }
+        }
+
+        transaction
+            .execute(&format!("DELETE FROM {}", self.changes_table()), &[])
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+
+        self.ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))
+    }
+
+    /// Sends the tracked changes in order as transaction `txid`, deleting them. Returns whether there were any.
+    pub async fn poll(&self, client: &mut Client, txid: u64) -> Result<bool, ConnectorError> {
+        let transaction = client
+            .transaction()
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+
+        let mut changes = vec![];
+        for (table_index, table) in self.tables.iter().enumerate() {
+            // The JSON rows are read back as rows of the table, so values have the column types.
+            let table_name = quoted_table_name(table);
+            let columns = |record: &str| {
+                table
+                    .columns
+                    .iter()
+                    .map(|column| format!("{record}.{}", quote(column)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let query = format!(
+                "WITH changes AS ( \
+                    DELETE FROM {} WHERE table_schema = $1 AND table_name = $2 \
+                    RETURNING seq, op, old_row, new_row \
+                ) \
+                SELECT changes.seq, changes.op, {}, {} FROM changes, \
+                    jsonb_populate_record(NULL::{table_name}, changes.old_row) AS old_record, \
+                    jsonb_populate_record(NULL::{table_name}, changes.new_row) AS new_record",
+                self.changes_table(),
+                columns("old_record"),
+                columns("new_record"),
+            );
+            let stmt = transaction
+                .prepare(&query)
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+            let rows = transaction
+                .query(&stmt, &[&table.schema, &table.name])
+                .await
+                .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+
+            for row in rows {
+                let seq: i64 = row.get(0);
+                let op = map_change(&row, stmt.columns(), table.columns.len())
+                    .map_err(|e| PostgresConnectorError(PostgresSchemaError(e)))?;
+                changes.push((seq, table_index, op));
+            }
+        }
+
+        let has_changes = !changes.is_empty();
+        changes.sort_by_key(|(seq, ..)| *seq);
+        for (seq_no, (_, table_index, op)) in changes.into_iter().enumerate() {
+            self.ingestor
+                .handle_message(IngestionMessage::new_op(
+                    txid,
+                    seq_no as u64,
+                    table_index,
+                    op,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| PostgresConnectorError(InvalidQueryError(e)))?;
+        Ok(has_changes)
+    }
+
+    fn changes_table(&self) -> String {
+        format!(
+            "{}.{}",
+            quote(CHANGES_SCHEMA),
+            quote(&format!("{}_changes", self.name))
+        )
+    }
+
+    fn capture_function(&self) -> String {
+        format!(
+            "{}.{}",
+            quote(CHANGES_SCHEMA),
+            quote(&format!("{}_capture", self.name))
+        )
+    }
+
+    fn trigger_name(&self) -> String {
+        format!("dozer_{}", self.name)
+    }
+}
+
+fn quoted_table_name(table: &PostgresTableInfo) -> String {
+    format!("{}.{}", quote(&table.schema), quote(&table.name))
+}
+
+/// Maps a row of `seq, op`, the old values and the new values of `count` columns to an operation.
+fn map_change(row: &Row, columns: &[Column], count: usize) -> Result<Operation, SchemaError> {
+    let op: String = row.get(1);
+    let old = || get_record(row, columns, 2, count);
+    let new = || get_record(row, columns, 2 + count, count);
+    Ok(match op.as_str() {
+        "I" => Operation::Insert { new: new()? },
+        "U" => Operation::Update {
+            old: old()?,
+            new: new()?,
+        },
+        _ => Operation::Delete { old: old()? },
+    })
+}
+
+/// Reads the `count` values from column `offset` of a row.
+fn get_record(
+    row: &Row,
+    columns: &[Column],
+    offset: usize,
+    count: usize,
+) -> Result<Record, SchemaError> {
+    let values = (offset..offset + count)
+        .map(|idx| value_to_field(row, idx, columns[idx].type_()))
+        .collect::<Result<Vec<Field>, _>>()?;
+    Ok(Record::new(values))
+}
@@ -1,5 +1,6 @@
-use crate::connectors::postgres::helper;
+use crate::connectors::postgres::helper::{self, quote};
 use crate::errors::{PostgresConnectorError, PostgresSchemaError};
+use dozer_types::ingestion_types::{SchemaDrift, SchemaDriftKind};
 use dozer_types::node::OpIdentifier;
 use dozer_types::types::{Field, Operation, Record};
 use helper::postgres_type_to_dozer_type;
@@ -10,43 +11,116 @@ use postgres_protocol::message::backend::{
     LogicalReplicationMessage, RelationBody, ReplicaIdentity, TupleData, UpdateBody, XLogDataBody,
 };
 use postgres_types::Type;
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Table {
     columns: Vec<TableColumn>,
     replica_identity: ReplicaIdentity,
+    /// Columns added to the table since the pipeline started, which aren't read.
+    new_columns: Vec<String>,
 }
 
-#[derive(Debug)]
+/// The columns of a replicated table that are read.
+#[derive(Debug, Clone)]
+pub struct TableColumns {
+    pub table_index: usize,
+    pub schema: String,
+    pub name: String,
+    pub names: Vec<String>,
+    /// Whether the source reads all columns of the table, so that columns added to it change its schema.
+    pub all_columns: bool,
+}
+
+impl TableColumns {
+    /// Qualified name of the table.
+    pub fn table_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TableColumn {
     pub name: String,
     pub flags: i8,
     pub r#type: Type,
-    pub column_index: usize,
+    /// Index of the column in replication tuples. `None` if the column has been dropped upstream.
+    pub column_index: Option<usize>,
+}
+
+/// Columns of an updated row that replication didn't send, because their TOAST values are unchanged and the table
+/// doesn't have replica identity full. They're null in the new record until read from the table by the row key.
+#[derive(Debug, Clone)]
+pub struct UnchangedToast {
+    /// Quoted name of the table.
+    pub table: String,
+    /// The key columns of the row, with their values as text.
+    pub key: Vec<(TableColumn, String)>,
+    /// The missing columns, with their index in the record.
+    pub columns: Vec<(usize, TableColumn)>,
+}
+
+impl UnchangedToast {
+    /// Query of the missing columns as text, with the key values as text parameters.
+    pub fn query(&self) -> String {
+        let columns = self
+            .columns
+            .iter()
+            .map(|(_, column)| format!("{}::text", quote(&column.name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let conditions = self
+            .key
+            .iter()
+            .enumerate()
+            .map(|(index, (column, _))| {
+                format!(
+                    "{} = ${}::{}.{}",
+                    quote(&column.name),
+                    index + 1,
+                    quote(column.r#type.schema()),
+                    quote(column.r#type.name())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        format!("SELECT {columns} FROM {} WHERE {conditions}", self.table)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum MappedReplicationMessage {
     Begin,
     Commit(OpIdentifier),
-    Operation { table_index: usize, op: Operation },
+    Operation {
+        table_index: usize,
+        op: Operation,
+        /// Set for updates with unchanged TOAST values that must be read from the table.
+        unchanged_toast: Option<Box<UnchangedToast>>,
+    },
+    SchemaDrift {
+        table_index: usize,
+        table_name: String,
+        drifts: Vec<SchemaDrift>,
+    },
 }
 
 #[derive(Debug, Default)]
 pub struct XlogMapper {
     /// Relation id to table info from replication `Relation` message.
     relations_map: HashMap<u32, Table>,
-    /// Relation id to (table index, column names).
-    tables_columns: HashMap<u32, (usize, Vec<String>)>,
+    /// Relation id to the columns read.
+    tables_columns: HashMap<u32, TableColumns>,
+    /// Type oid to enum type, with its labels, as `Relation` messages only have the oids of column types.
+    enum_types: HashMap<u32, Type>,
 }
 
 impl XlogMapper {
-    pub fn new(tables_columns: HashMap<u32, (usize, Vec<String>)>) -> Self {
+    pub fn new(tables_columns: HashMap<u32, TableColumns>, enum_types: HashMap<u32, Type>) -> Self {
         XlogMapper {
             relations_map: HashMap::<u32, Table>::new(),
             tables_columns,
+            enum_types,
         }
     }
 
@@ -56,7 +130,17 @@ impl XlogMapper {
     ) -> Result<Option<MappedReplicationMessage>, PostgresConnectorError> {
         match &message.data() {
             Relation(relation) => {
-                self.ingest_schema(relation)?;
+                let Some(drifts) = self.ingest_schema(relation)? else {
+                    return Ok(None);
+                };
+                if !drifts.is_empty() {
+                    let table_columns = &self.tables_columns[&relation.rel_id()];
+                    return Ok(Some(MappedReplicationMessage::SchemaDrift {
+                        table_index: table_columns.table_index,
+                        table_name: table_columns.table_name(),
+                        drifts,
+                    }));
+                }
             }
             Commit(commit) => {
                 return Ok(Some(MappedReplicationMessage::Commit(OpIdentifier::new(
@@ -71,7 +155,7 @@ impl XlogMapper {
                 let Some(table_columns) = self.tables_columns.get(&insert.rel_id()) else {
                     return Ok(None);
                 };
-                let table_index = table_columns.0;
+                let table_index = table_columns.table_index;
 
                 let table = self.relations_map.get(&insert.rel_id()).unwrap();
                 let new_values = insert.tuple().tuple_data();
@@ -85,20 +169,35 @@ impl XlogMapper {
                 return Ok(Some(MappedReplicationMessage::Operation {
                     table_index,
                     op: event,
+                    unchanged_toast: None,
                 }));
             }
             Update(update) => {
                 let Some(table_columns) = self.tables_columns.get(&update.rel_id()) else {
                     return Ok(None);
                 };
-                let table_index = table_columns.0;
+                let table_index = table_columns.table_index;
 
                 let table = self.relations_map.get(&update.rel_id()).unwrap();
                 let new_values = update.new_tuple().tuple_data();
 
-                let values = Self::convert_values_to_fields(table, new_values, false)?;
+                let mut values = Self::convert_values_to_fields(table, new_values, false)?;
                 let old_values = Self::convert_old_value_to_fields(table, update)?;
 
+                let unchanged_columns = Self::unchanged_toast_columns(table, new_values);
+                let unchanged_toast = if unchanged_columns.is_empty() {
+                    None
+                } else if update.old_tuple().is_some() {
+                    // With replica identity full, the old row has the unchanged values.
+                    for index in unchanged_columns {
+                        values[index] = old_values[index].clone();
+                    }
+                    None
+                } else {
+                    Self::unchanged_toast(table_columns, table, new_values, unchanged_columns)
+                        .map(Box::new)
+                };
+
                 let event = Operation::Update {
                     old: Record::new(old_values),
                     new: Record::new(values),
@@ -107,19 +206,27 @@ impl XlogMapper {
                 return Ok(Some(MappedReplicationMessage::Operation {
                     table_index,
                     op: event,
+                    unchanged_toast,
                 }));
             }
             Delete(delete) => {
                 let Some(table_columns) = self.tables_columns.get(&delete.rel_id()) else {
                     return Ok(None);
                 };
-                let table_index = table_columns.0;
+                let table_index = table_columns.table_index;
 
                 // TODO: Use only columns with .flags() = 0
                 let table = self.relations_map.get(&delete.rel_id()).unwrap();
-                let key_values = delete.key_tuple().unwrap().tuple_data();
-
-                let values = Self::convert_values_to_fields(table, key_values, true)?;
+                let values = match delete.old_tuple() {
+                    // Tables with replica identity full send the whole old row.
+                    Some(old_tuple) => {
+                        Self::convert_values_to_fields(table, old_tuple.tuple_data(), false)?
+                    }
+                    None => {
+                        let key_values = delete.key_tuple().unwrap().tuple_data();
+                        Self::convert_values_to_fields(table, key_values, true)?
+                    }
+                };
 
                 let event = Operation::Delete {
                     old: Record::new(values),
@@ -128,6 +235,7 @@ impl XlogMapper {
                 return Ok(Some(MappedReplicationMessage::Operation {
                     table_index,
                     op: event,
+                    unchanged_toast: None,
                 }));
             }
             _ => {}
@@ -136,19 +244,26 @@ impl XlogMapper {
         Ok(None)
     }
 
-    fn ingest_schema(&mut self, relation: &RelationBody) -> Result<(), PostgresConnectorError> {
+    /// Returns the drifts from the previously ingested schema of the table, or from the columns read for the first
+    /// schema.
+    fn ingest_schema(
+        &mut self,
+        relation: &RelationBody,
+    ) -> Result<Option<Vec<SchemaDrift>>, PostgresConnectorError> {
         let rel_id = relation.rel_id();
-        let Some((table_index, wanted_columns)) = self.tables_columns.get(&rel_id) else {
-            return Ok(());
+        let Some(table_columns) = self.tables_columns.get(&rel_id) else {
+            return Ok(None);
         };
+        let wanted_columns = &table_columns.names;
 
         let mut columns = vec![];
+        let mut new_columns = vec![];
         for (column_index, column) in relation.columns().iter().enumerate() {
             let column_name =
                 column
                     .name()
                     .map_err(|_| PostgresConnectorError::NonUtf8ColumnName {
-                        table_index: *table_index,
+                        table_index: table_columns.table_index,
                         column_index,
                     })?;
 
@@ -157,65 +272,119 @@ impl XlogMapper {
                     .iter()
                     .any(|column| column.as_str() == column_name)
             {
+                if table_columns.all_columns {
+                    new_columns.push(column_name.to_string());
+                }
                 continue;
             }
 
-            // TODO: workaround - in case of custom enum
             let type_oid = column.type_id() as u32;
-            let typ = if type_oid == 28862 {
-                Type::VARCHAR
-            } else {
-                Type::from_oid(type_oid).ok_or_else(|| {
-                    PostgresSchemaError::InvalidColumnType(column_name.to_string())
-                })?
-            };
+            let typ = Type::from_oid(type_oid)
+                .or_else(|| self.enum_types.get(&type_oid).cloned())
+                .ok_or_else(|| PostgresSchemaError::InvalidColumnType(column_name.to_string()))?;
 
             columns.push(TableColumn {
                 name: column_name.to_string(),
                 flags: column.flags(),
                 r#type: typ,
-                column_index,
+                column_index: Some(column_index),
             })
         }
 
-        let replica_identity = match relation.replica_identity() {
-            ReplicaIdentity::Default => ReplicaIdentity::Default,
-            ReplicaIdentity::Nothing => ReplicaIdentity::Nothing,
-            ReplicaIdentity::Full => ReplicaIdentity::Full,
-            ReplicaIdentity::Index => ReplicaIdentity::Index,
-        };
-
-        let table = Table {
-            columns,
-            replica_identity,
-        };
-
-        for c in &table.columns {
+        for c in &columns {
             postgres_type_to_dozer_type(c.r#type.clone())?;
         }
 
-        match self.relations_map.entry(rel_id) {
-            Entry::Occupied(mut entry) => {
-                // Check if type has changed.
-                for (existing_column, column) in entry.get().columns.iter().zip(&table.columns) {
-                    if existing_column.r#type != column.r#type {
-                        return Err(PostgresConnectorError::ColumnTypeChanged {
-                            table_index: *table_index,
-                            column_name: existing_column.name.clone(),
-                            old_type: existing_column.r#type.clone(),
-                            new_type: column.r#type.clone(),
+        let mut drifts = vec![];
+        // Columns added to a table whose columns are all read are reported once.
+        for column_name in &new_columns {
+            let reported = self
+                .relations_map
+                .get(&rel_id)
+                .map_or(false, |table| table.new_columns.contains(column_name));
+            if !reported {
+                drifts.push(SchemaDrift {
+                    column_name: column_name.clone(),
+                    kind: SchemaDriftKind::ColumnAdded,
+                });
+            }
+        }
+
+        let columns = match self.relations_map.get(&rel_id) {
+            // Keep the columns of the existing schema in place, so records still match it.
+            Some(existing) => {
+                let mut existing_columns = vec![];
+                for existing_column in &existing.columns {
+                    match columns.iter().find(|c| c.name == existing_column.name) {
+                        Some(column) => {
+                            if existing_column.r#type != column.r#type {
+                                let compatible =
+                                    postgres_type_to_dozer_type(existing_column.r#type.clone())?
+                                        == postgres_type_to_dozer_type(column.r#type.clone())?;
+                                drifts.push(SchemaDrift {
+                                    column_name: column.name.clone(),
+                                    kind: SchemaDriftKind::TypeChanged {
+                                        old_type: existing_column.r#type.to_string(),
+                                        new_type: column.r#type.to_string(),
+                                        compatible,
+                                    },
+                                });
+                            }
+                            existing_columns.push(TableColumn {
+                                name: column.name.clone(),
+                                flags: column.flags,
+                                r#type: column.r#type.clone(),
+                                column_index: column.column_index,
+                            });
+                        }
+                        None => {
+                            if existing_column.column_index.is_some() {
+                                drifts.push(SchemaDrift {
+                                    column_name: existing_column.name.clone(),
+                                    kind: SchemaDriftKind::ColumnDropped,
+                                });
+                            }
+                            existing_columns.push(TableColumn {
+                                name: existing_column.name.clone(),
+                                flags: existing_column.flags,
+                                r#type: existing_column.r#type.clone(),
+                                column_index: None,
+                            });
+                        }
+                    }
+                }
+
+                for column in &columns {
+                    if !existing.columns.iter().any(|c| c.name == column.name) {
+                        drifts.push(SchemaDrift {
+                            column_name: column.name.clone(),
+                            kind: SchemaDriftKind::ColumnAdded,
                         });
                     }
                 }
 
-                entry.insert(table);
-            }
-            Entry::Vacant(entry) => {
-                entry.insert(table);
+                existing_columns
             }
-        }
+            None => columns,
+        };
 
-        Ok(())
+        let replica_identity = match relation.replica_identity() {
+            ReplicaIdentity::Default => ReplicaIdentity::Default,
+            ReplicaIdentity::Nothing => ReplicaIdentity::Nothing,
+            ReplicaIdentity::Full => ReplicaIdentity::Full,
+            ReplicaIdentity::Index => ReplicaIdentity::Index,
+        };
+
+        self.relations_map.insert(
+            rel_id,
+            Table {
+                columns,
+                replica_identity,
+                new_columns,
+            },
+        );
+
+        Ok(Some(drifts))
     }
 
     fn convert_values_to_fields(
@@ -226,14 +395,19 @@ impl XlogMapper {
         let mut values: Vec<Field> = vec![];
 
         for column in &table.columns {
+            let Some(column_index) = column.column_index else {
+                values.push(Field::Null);
+                continue;
+            };
             if column.flags == 1 || !only_key {
-                let value = new_values.get(column.column_index).unwrap();
+                let value = new_values.get(column_index).unwrap();
                 match value {
                     TupleData::Null => values.push(
                         helper::postgres_type_to_field(None, column)
                             .map_err(PostgresConnectorError::PostgresSchemaError)?,
                     ),
-                    TupleData::UnchangedToast => {}
+                    // Filled in from the old row or the table.
+                    TupleData::UnchangedToast => values.push(Field::Null),
                     TupleData::Text(text) => values.push(
                         helper::postgres_type_to_field(Some(text), column)
                             .map_err(PostgresConnectorError::PostgresSchemaError)?,
@@ -253,6 +427,10 @@ impl XlogMapper {
     ) -> Result<Vec<Field>, PostgresConnectorError> {
         match table.replica_identity {
             ReplicaIdentity::Default | ReplicaIdentity::Full | ReplicaIdentity::Index => {
+                if let Some(old_tuple) = update.old_tuple() {
+                    // Tables with replica identity full send the whole old row.
+                    return Self::convert_values_to_fields(table, old_tuple.tuple_data(), false);
+                }
                 update.key_tuple().map_or_else(
                     || Self::convert_values_to_fields(table, update.new_tuple().tuple_data(), true),
                     |key_tuple| Self::convert_values_to_fields(table, key_tuple.tuple_data(), true),
@@ -261,4 +439,89 @@ impl XlogMapper {
             ReplicaIdentity::Nothing => Ok(vec![]),
         }
     }
+
+    /// Indexes in the record of the columns whose values are unchanged TOAST values in `values`.
+    fn unchanged_toast_columns(table: &Table, values: &[TupleData]) -> Vec<usize> {
+        table
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| {
+                column.column_index.map_or(false, |index| {
+                    matches!(values.get(index), Some(TupleData::UnchangedToast))
+                })
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The unchanged TOAST columns to read from the table, by the key values of the new row. `None` if the table has
+    /// no key, in which case the columns stay null.
+    fn unchanged_toast(
+        table_columns: &TableColumns,
+        table: &Table,
+        values: &[TupleData],
+        unchanged_columns: Vec<usize>,
+    ) -> Option<UnchangedToast> {
+        let key = table
+            .columns
+            .iter()
+            .filter(|column| column.flags == 1)
+            .filter_map(|column| match values.get(column.column_index?) {
+                Some(TupleData::Text(text)) => {
+                    Some((column.clone(), String::from_utf8_lossy(text).into_owned()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if key.is_empty() {
+            return None;
+        }
+        Some(UnchangedToast {
+            table: format!(
+                "{}.{}",
+                quote(&table_columns.schema),
+                quote(&table_columns.name)
+            ),
+            key,
+            columns: unchanged_columns
+                .into_iter()
+                .map(|index| (index, table.columns[index].clone()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, r#type: Type) -> TableColumn {
+        TableColumn {
+            name: name.to_string(),
+            flags: 0,
+            r#type,
+            column_index: None,
+        }
+    }
+
+    #[test]
+    fn test_unchanged_toast_query() {
+        let unchanged_toast = UnchangedToast {
+            table: "\"public\".\"users\"".to_string(),
+            key: vec![
+                (column("id", Type::INT4), "1".to_string()),
+                (column("tenant", Type::TEXT), "a".to_string()),
+            ],
+            columns: vec![
+                (1, column("bio", Type::TEXT)),
+                (3, column("avatar", Type::BYTEA)),
+            ],
+        };
+        assert_eq!(
+            unchanged_toast.query(),
+            "SELECT \"bio\"::text, \"avatar\"::text FROM \"public\".\"users\" \
+             WHERE \"id\" = $1::\"pg_catalog\".\"int4\" AND \"tenant\" = $2::\"pg_catalog\".\"text\""
+        );
+    }
 }
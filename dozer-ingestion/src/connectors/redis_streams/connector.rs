@@ -0,0 +1,238 @@
+use dozer_types::ingestion_types::{IngestionMessage, RedisStreamsConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use redis::aio::Connection;
+use redis::streams::{StreamReadOptions, StreamReadReply};
+use redis::AsyncCommands;
+use tonic::async_trait;
+
+use super::schema::{map_entry, stream_schema, COLUMNS};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, RedisStreamsError};
+use crate::ingestion::Ingestor;
+
+/// Reads the entries of Redis Streams with a consumer group, acknowledging them once they are ingested.
+#[derive(Debug)]
+pub struct RedisStreamsConnector {
+    name: String,
+    config: RedisStreamsConfig,
+}
+
+impl RedisStreamsConnector {
+    pub fn new(name: String, config: RedisStreamsConfig) -> Self {
+        Self { name, config }
+    }
+
+    async fn connection(&self) -> Result<Connection, RedisStreamsError> {
+        let client = redis::Client::open(self.config.url.as_str())?;
+        Ok(client.get_async_connection().await?)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let mut connection = self.connection().await?;
+        let projections = tables
+            .iter()
+            .map(|table| stream_schema(&table.column_names).map(|(_, projection)| projection))
+            .collect::<Result<Vec<_>, _>>()?;
+        let streams = tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .collect::<Vec<_>>();
+        for stream in &streams {
+            self.create_group(&mut connection, stream).await?;
+        }
+
+        // Entries delivered to the consumer but not acknowledged, e.g. because the connector stopped, are read again
+        // first, by reading from id `0`. New entries are read with `>` once there are none left.
+        let mut pending = true;
+        let mut seq_no = 0;
+        loop {
+            let ids = vec![if pending { "0" } else { ">" }; streams.len()];
+            let mut options = StreamReadOptions::default()
+                .group(&self.config.group, &self.config.consumer)
+                .count(self.config.batch_size as usize);
+            if !pending {
+                options = options.block(self.config.block_ms as usize);
+            }
+            let reply: StreamReadReply = connection
+                .xread_options(&streams, &ids, &options)
+                .await
+                .map_err(RedisStreamsError::Redis)?;
+
+            let mut read = 0;
+            for stream_key in reply.keys {
+                let table_index = streams.iter().position(|stream| *stream == stream_key.key);
+                let Some(table_index) = table_index else {
+                    continue;
+                };
+                if stream_key.ids.is_empty() {
+                    continue;
+                }
+                for entry in &stream_key.ids {
+                    let values = map_entry(entry, &projections[table_index])?;
+                    seq_no += 1;
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(
+                            0,
+                            seq_no,
+                            table_index,
+                            Operation::Insert {
+                                new: Record::new(values),
+                            },
+                        ))
+                        .map_err(ConnectorError::IngestorError)?;
+                }
+                let ids = stream_key
+                    .ids
+                    .iter()
+                    .map(|entry| entry.id.as_str())
+                    .collect::<Vec<_>>();
+                connection
+                    .xack::<_, _, _, usize>(&stream_key.key, &self.config.group, &ids)
+                    .await
+                    .map_err(RedisStreamsError::Redis)?;
+                read += ids.len();
+            }
+
+            if pending && read == 0 {
+                info!("[{}] Read the pending entries, reading new ones", self.name);
+                pending = false;
+            }
+        }
+    }
+
+    /// Creates the consumer group of `stream`, and the stream if it doesn't exist, unless the group exists already.
+    async fn create_group(
+        &self,
+        connection: &mut Connection,
+        stream: &str,
+    ) -> Result<(), RedisStreamsError> {
+        match connection
+            .xgroup_create_mkstream::<_, _, _, ()>(
+                stream,
+                &self.config.group,
+                &self.config.start_id,
+            )
+            .await
+        {
+            Ok(()) => {
+                info!(
+                    "[{}] Created consumer group {} of stream {}",
+                    self.name, self.config.group, stream
+                );
+                Ok(())
+            }
+            Err(e) if e.code() == Some("BUSYGROUP") => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for RedisStreamsConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        COLUMNS
+            .iter()
+            .map(|(name, typ, _)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let mut connection = self.connection().await?;
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut connection)
+            .await
+            .map_err(RedisStreamsError::Redis)?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let mut connection = self.connection().await?;
+        let mut streams = vec![];
+        let mut cursor = 0;
+        loop {
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("TYPE")
+                .arg("stream")
+                .query_async(&mut connection)
+                .await
+                .map_err(RedisStreamsError::Redis)?;
+            streams.extend(keys);
+            if next_cursor == 0 {
+                break;
+            }
+            cursor = next_cursor;
+        }
+        // A key can be returned by several iterations of a scan.
+        streams.sort();
+        streams.dedup();
+        Ok(streams
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let mut connection = self.connection().await?;
+        for table in tables {
+            let not_found =
+                || ConnectorError::TableNotFound(table_name(table.schema.as_deref(), &table.name));
+            if table.schema.is_some() {
+                return Err(not_found());
+            }
+            let key_type: String = redis::cmd("TYPE")
+                .arg(&table.name)
+                .query_async(&mut connection)
+                .await
+                .map_err(RedisStreamsError::Redis)?;
+            if key_type != "stream" {
+                return Err(not_found());
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        Ok(tables
+            .into_iter()
+            .map(|table| TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: COLUMNS
+                    .iter()
+                    .map(|(name, _, _)| name.to_string())
+                    .collect(),
+            })
+            .collect())
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let (schema, _) = stream_schema(&table_info.column_names)?;
+                Ok(SourceSchema::new(schema, CdcType::Nothing))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
@@ -0,0 +1,4 @@
+mod connector;
+mod schema;
+
+pub use connector::RedisStreamsConnector;
@@ -0,0 +1,139 @@
+use dozer_types::chrono::{DateTime, NaiveDateTime, Offset, Utc};
+use dozer_types::json_types::serde_json_to_json_value;
+use dozer_types::serde_json::{Map, Value};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use redis::streams::StreamId;
+use redis::FromRedisValue;
+
+use crate::errors::RedisStreamsError;
+
+/// The columns of every stream, and whether they are nullable.
+pub const COLUMNS: [(&str, FieldType, bool); 3] = [
+    ("id", FieldType::String, false),
+    ("timestamp", FieldType::Timestamp, false),
+    ("fields", FieldType::Json, false),
+];
+
+/// Returns the schema of the requested columns of a stream, all of them if none are requested, and their indexes in
+/// [`COLUMNS`]. The primary index is the entry id, when it's requested.
+pub fn stream_schema(column_names: &[String]) -> Result<(Schema, Vec<usize>), RedisStreamsError> {
+    let projection = if column_names.is_empty() {
+        (0..COLUMNS.len()).collect()
+    } else {
+        column_names
+            .iter()
+            .map(|name| {
+                COLUMNS
+                    .iter()
+                    .position(|(column, _, _)| column == name)
+                    .ok_or_else(|| RedisStreamsError::ColumnNotFound(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut schema = Schema::new();
+    for &index in &projection {
+        let (name, typ, nullable) = COLUMNS[index];
+        schema.fields.push(FieldDefinition::new(
+            name.to_string(),
+            typ,
+            nullable,
+            SourceDefinition::Dynamic,
+        ));
+    }
+    if let Some(id) = schema.fields.iter().position(|field| field.name == "id") {
+        schema.primary_index = vec![id];
+    }
+    Ok((schema, projection))
+}
+
+/// Maps an entry to the values of the columns in `projection`. Its fields are a json object of strings, as Redis
+/// stores them.
+pub fn map_entry(entry: &StreamId, projection: &[usize]) -> Result<Vec<Field>, RedisStreamsError> {
+    // Ids are `<milliseconds>-<sequence number>`.
+    let timestamp = entry
+        .id
+        .split_once('-')
+        .and_then(|(millis, _)| millis.parse().ok())
+        .and_then(NaiveDateTime::from_timestamp_millis)
+        .ok_or_else(|| RedisStreamsError::InvalidId(entry.id.clone()))?;
+    let mut fields = Map::new();
+    for (name, value) in &entry.map {
+        let value = String::from_redis_value(value)
+            .map_err(|_| RedisStreamsError::InvalidValue(entry.id.clone(), name.clone()))?;
+        fields.insert(name.clone(), Value::String(value));
+    }
+    let fields = serde_json_to_json_value(Value::Object(fields))
+        .expect("objects of strings are valid json values");
+
+    let mut values = [
+        Field::String(entry.id.clone()),
+        Field::Timestamp(DateTime::from_utc(timestamp, Utc.fix())),
+        Field::Json(fields),
+    ];
+    Ok(projection
+        .iter()
+        .map(|&index| std::mem::replace(&mut values[index], Field::Null))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_stream_schema() {
+        let (schema, projection) = stream_schema(&[]).unwrap();
+        assert_eq!(schema.fields.len(), COLUMNS.len());
+        assert_eq!(projection, vec![0, 1, 2]);
+        assert_eq!(schema.primary_index, vec![0]);
+
+        let (schema, projection) = stream_schema(&["fields".to_string()]).unwrap();
+        assert_eq!(projection, vec![2]);
+        assert!(schema.primary_index.is_empty());
+
+        assert!(matches!(
+            stream_schema(&["data".to_string()]),
+            Err(RedisStreamsError::ColumnNotFound(name)) if name == "data"
+        ));
+    }
+
+    #[test]
+    fn test_map_entry() {
+        let entry = StreamId {
+            id: "1686000000123-0".to_string(),
+            map: HashMap::from([("film_id".to_string(), redis::Value::Data(b"1".to_vec()))]),
+        };
+        let values = map_entry(&entry, &[1, 0, 2]).unwrap();
+        assert_eq!(
+            values[..2],
+            [
+                Field::Timestamp(DateTime::from_utc(
+                    NaiveDateTime::from_timestamp_millis(1_686_000_000_123).unwrap(),
+                    Utc.fix()
+                )),
+                Field::String("1686000000123-0".to_string()),
+            ]
+        );
+        let Field::Json(fields) = &values[2] else {
+            panic!("Expected json fields");
+        };
+        assert_eq!(
+            fields,
+            &serde_json_to_json_value(json!({ "film_id": "1" })).unwrap()
+        );
+
+        let entry = StreamId {
+            id: "latest".to_string(),
+            map: HashMap::new(),
+        };
+        assert!(matches!(
+            map_entry(&entry, &[0]),
+            Err(RedisStreamsError::InvalidId(id)) if id == "latest"
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+use dozer_types::chrono::{DateTime, NaiveDateTime, Offset, Utc};
+use dozer_types::errors::types::TypeError;
+use dozer_types::json_value_to_field;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{
+    Currency, DozerMoney, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
+use reqwest::RequestBuilder;
+
+use crate::errors::RestError;
+
+/// A REST API whose list endpoints are polled as tables.
+#[derive(Debug, Clone)]
+pub struct RestApi {
+    /// Prepended to the path of endpoints, e.g. `https://api.stripe.com/v1`.
+    pub base_url: String,
+    pub auth: Auth,
+    pub pagination: Pagination,
+    /// The parameter of the page size and its value, e.g. `limit=100`.
+    pub page_size: Option<(String, u32)>,
+    /// How many times a rate limited or unavailable request is retried.
+    pub max_retries: u32,
+    pub endpoints: Vec<Endpoint>,
+}
+
+/// How requests are authenticated.
+#[derive(Debug, Clone)]
+pub enum Auth {
+    None,
+    Bearer(String),
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// An API key in a header, e.g. `X-Api-Key`.
+    Header {
+        name: String,
+        value: String,
+    },
+    /// An API key in a query parameter.
+    Query {
+        name: String,
+        value: String,
+    },
+}
+
+impl Auth {
+    pub fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::None => request,
+            Auth::Bearer(token) => request.bearer_auth(token),
+            Auth::Basic { username, password } => request.basic_auth(username, password.as_ref()),
+            Auth::Header { name, value } => request.header(name, value),
+            Auth::Query { name, value } => request.query(&[(name, value)]),
+        }
+    }
+}
+
+/// How the pages of a list are requested.
+#[derive(Debug, Clone)]
+pub enum Pagination {
+    /// The list is a single page.
+    None,
+    /// The `item_field` of the last item is passed as `param` for the next page, while the `has_more_field` of the
+    /// response is true, e.g. Stripe's `starting_after`.
+    Cursor {
+        param: String,
+        item_field: String,
+        has_more_field: String,
+    },
+    /// Page numbers from 1 are passed as `param`, until a page is empty.
+    PageNumber { param: String },
+    /// The number of items already listed is passed as `param`, until a page is empty.
+    Offset { param: String },
+    /// The url of the next page is in the `field` of the response, until it's null.
+    NextUrl { field: String },
+}
+
+/// Only items whose `field` is at least the greatest value polled so far are requested, passing it as `param`.
+#[derive(Debug, Clone)]
+pub struct Incremental {
+    pub param: String,
+    pub field: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnType {
+    Field(FieldType),
+    /// Seconds since the Unix epoch, mapped to a timestamp.
+    UnixTimestamp,
+    /// An integer amount of the smallest unit of the currency in the item's `currency_field`, e.g. cents, mapped to
+    /// money.
+    MinorUnits {
+        currency_field: &'static str,
+    },
+    /// A string that is one of the values, mapped to an enum.
+    Enum(Vec<String>),
+}
+
+/// A column mapped from the field at `path` of items, with nested fields separated by `.`.
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub path: String,
+    pub typ: ColumnType,
+    pub nullable: bool,
+}
+
+impl Column {
+    pub fn new(name: &str, typ: ColumnType, nullable: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            path: name.to_string(),
+            typ,
+            nullable,
+        }
+    }
+
+    pub fn field_type(&self) -> FieldType {
+        match self.typ {
+            ColumnType::Field(typ) => typ,
+            ColumnType::UnixTimestamp => FieldType::Timestamp,
+            ColumnType::MinorUnits { .. } => FieldType::Money,
+            ColumnType::Enum(_) => FieldType::Enum,
+        }
+    }
+
+    pub fn enum_values(&self) -> Vec<String> {
+        match &self.typ {
+            ColumnType::Enum(values) => values.clone(),
+            _ => vec![],
+        }
+    }
+
+    pub fn value(&self, item: &Value) -> Result<Field, RestError> {
+        let value = self
+            .path
+            .split('.')
+            .try_fold(item, |value, field| value.get(field))
+            .unwrap_or(&Value::Null);
+        let invalid = |e| RestError::InvalidValue(self.name.clone(), value.to_string(), e);
+        match (&self.typ, value) {
+            (_, Value::Null) if self.nullable => Ok(Field::Null),
+            (ColumnType::UnixTimestamp, Value::Number(seconds)) => Ok(seconds
+                .as_i64()
+                .and_then(|seconds| NaiveDateTime::from_timestamp_opt(seconds, 0))
+                .map_or(Field::Null, |timestamp| {
+                    Field::Timestamp(DateTime::from_utc(timestamp, Utc.fix()))
+                })),
+            (ColumnType::MinorUnits { currency_field }, Value::Number(amount)) => {
+                let currency = item
+                    .get(*currency_field)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .parse::<Currency>()
+                    .map_err(invalid)?;
+                let amount = amount.as_i64().ok_or_else(|| {
+                    invalid(TypeError::InvalidFieldValue {
+                        field_type: FieldType::Money,
+                        nullable: self.nullable,
+                        value: amount.to_string(),
+                    })
+                })?;
+                let scale = currency.minor_units().unwrap_or(0);
+                Ok(Field::Money(DozerMoney::new(
+                    Decimal::new(amount, scale),
+                    currency,
+                )))
+            }
+            (ColumnType::Enum(values), Value::String(value)) => values
+                .iter()
+                .position(|known| known == value)
+                .map(|ordinal| Field::Enum(ordinal as u32))
+                .ok_or_else(|| {
+                    invalid(TypeError::UnknownEnumValue {
+                        field: self.name.clone(),
+                        value: value.clone(),
+                    })
+                }),
+            (_, value) => json_value_to_field(value.clone(), self.field_type(), self.nullable)
+                .map_err(invalid),
+        }
+    }
+}
+
+/// A list endpoint, polled as a table.
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    /// The table name.
+    pub name: String,
+    /// The path after the base url, e.g. `/charges`.
+    pub path: String,
+    /// Extra query parameters.
+    pub params: Vec<(String, String)>,
+    /// The field of the response with the items, or `None` if the response is the items.
+    pub items_field: Option<String>,
+    pub columns: Vec<Column>,
+    /// Names of the primary key columns.
+    pub primary_key: Vec<String>,
+    /// If not set, the whole list is requested every poll and compared with the last one.
+    pub incremental: Option<Incremental>,
+}
+
+impl Endpoint {
+    pub fn items(&self, response: Value) -> Result<Vec<Value>, RestError> {
+        let items = match &self.items_field {
+            Some(field) => response.get(field).cloned(),
+            None => Some(response),
+        };
+        match items {
+            Some(Value::Array(items)) => Ok(items),
+            _ => Err(RestError::MissingItems(
+                self.path.clone(),
+                self.items_field.clone().unwrap_or_default(),
+            )),
+        }
+    }
+
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// The schema of `columns`, which are columns of this endpoint.
+    pub fn schema(&self, columns: &[Column]) -> Schema {
+        let mut schema = Schema::new();
+        for column in columns {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.field_type(),
+                    column.nullable,
+                    SourceDefinition::Dynamic,
+                )
+                .with_enum_values(column.enum_values()),
+                self.primary_key.contains(&column.name),
+            );
+        }
+        schema
+    }
+}
+
+pub fn record(columns: &[Column], item: &Value) -> Result<Record, RestError> {
+    let values = columns
+        .iter()
+        .map(|column| column.value(item))
+        .collect::<Result<_, _>>()?;
+    Ok(Record::new(values))
+}
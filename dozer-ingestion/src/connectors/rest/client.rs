@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use dozer_types::log::warn;
+use dozer_types::serde_json::Value;
+use reqwest::{Client, Response, StatusCode};
+
+use super::api::{Endpoint, Pagination, RestApi};
+use crate::errors::RestError;
+
+/// Requests the pages of endpoints, retrying rate limited and unavailable requests.
+#[derive(Debug)]
+pub struct RestClient<'a> {
+    client: Client,
+    api: &'a RestApi,
+}
+
+impl<'a> RestClient<'a> {
+    pub fn new(api: &'a RestApi) -> Self {
+        Self {
+            client: Client::new(),
+            api,
+        }
+    }
+
+    /// Lists the items of an endpoint, from `cursor` if it's incremental.
+    pub async fn list(
+        &self,
+        endpoint: &Endpoint,
+        cursor: Option<&Value>,
+    ) -> Result<Vec<Value>, RestError> {
+        let mut url = format!("{}{}", self.api.base_url, endpoint.path);
+        let mut params = endpoint.params.clone();
+        if let Some((param, size)) = &self.api.page_size {
+            params.push((param.clone(), size.to_string()));
+        }
+        if let (Some(incremental), Some(cursor)) = (&endpoint.incremental, cursor) {
+            params.push((incremental.param.clone(), param_value(cursor)));
+        }
+
+        let mut items: Vec<Value> = vec![];
+        let mut page = 1;
+        loop {
+            let mut page_params = params.clone();
+            match &self.api.pagination {
+                Pagination::Cursor {
+                    param, item_field, ..
+                } => {
+                    if let Some(last) = items.last().and_then(|item| item.get(item_field)) {
+                        page_params.push((param.clone(), param_value(last)));
+                    }
+                }
+                Pagination::PageNumber { param } => {
+                    page_params.push((param.clone(), page.to_string()))
+                }
+                Pagination::Offset { param } => {
+                    page_params.push((param.clone(), items.len().to_string()))
+                }
+                Pagination::None | Pagination::NextUrl { .. } => (),
+            }
+
+            let response = self.get(&url, &page_params).await?;
+            let done = match &self.api.pagination {
+                Pagination::None => true,
+                Pagination::Cursor { has_more_field, .. } => !response
+                    .get(has_more_field)
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                Pagination::NextUrl { field } => {
+                    match response.get(field).and_then(Value::as_str) {
+                        // The next url has the parameters.
+                        Some(next) => {
+                            url = next.to_string();
+                            params.clear();
+                            false
+                        }
+                        None => true,
+                    }
+                }
+                Pagination::PageNumber { .. } | Pagination::Offset { .. } => false,
+            };
+            let page_items = endpoint.items(response)?;
+            let empty = page_items.is_empty();
+            items.extend(page_items);
+            if done || empty {
+                return Ok(items);
+            }
+            page += 1;
+        }
+    }
+
+    /// Requests the first item of an endpoint.
+    pub async fn check(&self, endpoint: &Endpoint) -> Result<(), RestError> {
+        let url = format!("{}{}", self.api.base_url, endpoint.path);
+        let mut params = endpoint.params.clone();
+        if let Some((param, _)) = &self.api.page_size {
+            params.push((param.clone(), "1".to_string()));
+        }
+        endpoint.items(self.get(&url, &params).await?)?;
+        Ok(())
+    }
+
+    async fn get(&self, url: &str, params: &[(String, String)]) -> Result<Value, RestError> {
+        let mut attempt = 0;
+        loop {
+            let request = self.api.auth.apply(self.client.get(url).query(params));
+            let response = request.send().await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response.json().await?);
+            }
+            if (status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+                && attempt < self.api.max_retries
+            {
+                let delay = retry_after(&response).unwrap_or_else(|| backoff(attempt));
+                warn!("Request to {url} failed with status {status}, retrying in {delay:?}");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            return Err(RestError::Status(
+                url.to_string(),
+                status.as_u16(),
+                response.text().await.unwrap_or_default(),
+            ));
+        }
+    }
+}
+
+/// Strings are passed as is, and other values as json.
+pub fn param_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The delay asked for by the server, in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Exponential backoff from 1 second, up to a minute.
+pub fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt).min(60))
+}
@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::info;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{Field, FieldType, Operation, Record};
+use tonic::async_trait;
+
+use super::api::{record, Column, Endpoint, RestApi};
+use super::client::RestClient;
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, RestError};
+use crate::ingestion::Ingestor;
+
+/// Polls the list endpoints of a REST API as tables.
+#[derive(Debug)]
+pub struct RestConnector {
+    name: String,
+    api: RestApi,
+    poll_interval: Duration,
+}
+
+impl RestConnector {
+    pub fn new(name: String, api: RestApi, poll_interval: Duration) -> Self {
+        Self {
+            name,
+            api,
+            poll_interval,
+        }
+    }
+
+    fn endpoint(&self, name: &str) -> Result<&Endpoint, ConnectorError> {
+        self.api
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.name == name)
+            .ok_or_else(|| ConnectorError::TableNotFound(name.to_string()))
+    }
+
+    /// The endpoint of a table and its requested columns.
+    fn get_table(
+        &self,
+        table_info: &TableInfo,
+    ) -> Result<(&Endpoint, Vec<Column>), ConnectorError> {
+        let endpoint = self.endpoint(&table_info.name)?;
+        let columns = table_info
+            .column_names
+            .iter()
+            .map(|name| {
+                endpoint
+                    .column(name)
+                    .cloned()
+                    .ok_or_else(|| RestError::ColumnNotFound(name.clone(), endpoint.name.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((endpoint, columns))
+    }
+}
+
+#[async_trait]
+impl Connector for RestConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+            ("integer".to_string(), Some(FieldType::Int)),
+            ("number".to_string(), Some(FieldType::Float)),
+            ("string".to_string(), Some(FieldType::String)),
+            ("unix timestamp".to_string(), Some(FieldType::Timestamp)),
+            ("object".to_string(), Some(FieldType::Json)),
+            ("array".to_string(), Some(FieldType::Json)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        // Requesting the first endpoint checks the credentials.
+        if let Some(endpoint) = self.api.endpoints.first() {
+            RestClient::new(&self.api).check(endpoint).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .api
+            .endpoints
+            .iter()
+            .map(|endpoint| TableIdentifier::from_table_name(endpoint.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        for table in tables {
+            if table.schema.is_some() {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+            self.endpoint(&table.name)?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let column_names = self
+                    .endpoint(&table.name)?
+                    .columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect();
+                Ok(TableInfo {
+                    schema: table.schema,
+                    name: table.name,
+                    column_names,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let (endpoint, columns) = self.get_table(table_info)?;
+                // Incremental endpoints only find new items.
+                let cdc_type = if endpoint.incremental.is_some() {
+                    CdcType::Nothing
+                } else {
+                    CdcType::FullChanges
+                };
+                Ok(SourceSchema::new(endpoint.schema(&columns), cdc_type))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        let client = RestClient::new(&self.api);
+        let mut pollers = tables
+            .iter()
+            .map(|table_info| {
+                let (endpoint, columns) = self.get_table(table_info)?;
+                Ok((endpoint, Poller::new(endpoint, columns)))
+            })
+            .collect::<Result<Vec<_>, ConnectorError>>()?;
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut seq_no = 0;
+        for (table_index, (endpoint, poller)) in pollers.iter_mut().enumerate() {
+            let items = client.list(endpoint, poller.cursor()).await?;
+            for op in poller.changes(items)? {
+                ingestor
+                    .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                    .map_err(ConnectorError::IngestorError)?;
+                seq_no += 1;
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        info!("[{}] Polling every {:?}", self.name, self.poll_interval);
+        // Each poll with changes is a transaction.
+        let mut txid = 0;
+        loop {
+            tokio::time::sleep(self.poll_interval).await;
+            let mut seq_no = 0;
+            for (table_index, (endpoint, poller)) in pollers.iter_mut().enumerate() {
+                let items = client.list(endpoint, poller.cursor()).await?;
+                for op in poller.changes(items)? {
+                    if seq_no == 0 {
+                        txid += 1;
+                    }
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Finds the changes of an endpoint between polls.
+#[derive(Debug)]
+pub struct Poller {
+    columns: Vec<Column>,
+    /// Positions of the primary key among the columns, or all of them if it's not requested.
+    key_indexes: Vec<usize>,
+    state: PollerState,
+}
+
+#[derive(Debug)]
+enum PollerState {
+    /// Items from the greatest cursor value polled are new, except those at that value already polled.
+    Incremental {
+        field: String,
+        cursor: Option<Value>,
+        at_cursor: HashSet<Vec<Field>>,
+    },
+    /// The last polled records by key, compared with the next ones.
+    Full {
+        records: HashMap<Vec<Field>, Record>,
+    },
+}
+
+impl Poller {
+    pub fn new(endpoint: &Endpoint, columns: Vec<Column>) -> Self {
+        let mut key_indexes = columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| endpoint.primary_key.contains(&column.name))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if key_indexes.is_empty() {
+            key_indexes = (0..columns.len()).collect();
+        }
+        let state = match &endpoint.incremental {
+            Some(incremental) => PollerState::Incremental {
+                field: incremental.field.clone(),
+                cursor: None,
+                at_cursor: HashSet::new(),
+            },
+            None => PollerState::Full {
+                records: HashMap::new(),
+            },
+        };
+        Self {
+            columns,
+            key_indexes,
+            state,
+        }
+    }
+
+    /// The value to request items from, if incremental.
+    pub fn cursor(&self) -> Option<&Value> {
+        match &self.state {
+            PollerState::Incremental { cursor, .. } => cursor.as_ref(),
+            PollerState::Full { .. } => None,
+        }
+    }
+
+    /// Maps the items of a poll to the changes since the last one.
+    pub fn changes(&mut self, items: Vec<Value>) -> Result<Vec<Operation>, RestError> {
+        let mut ops = vec![];
+        match &mut self.state {
+            PollerState::Incremental {
+                field,
+                cursor,
+                at_cursor,
+            } => {
+                let mut items = items
+                    .into_iter()
+                    .map(|item| {
+                        let value = item.get(field.as_str()).cloned().unwrap_or(Value::Null);
+                        Ok((value, record(&self.columns, &item)?))
+                    })
+                    .collect::<Result<Vec<_>, RestError>>()?;
+                items.sort_by(|(a, _), (b, _)| compare_values(a, b));
+
+                for (value, new) in items {
+                    let key = key(&new, &self.key_indexes);
+                    match cursor.as_ref().map(|cursor| compare_values(&value, cursor)) {
+                        Some(Ordering::Less) => continue,
+                        Some(Ordering::Equal) => {
+                            if !at_cursor.insert(key) {
+                                continue;
+                            }
+                        }
+                        Some(Ordering::Greater) | None => {
+                            *cursor = Some(value);
+                            at_cursor.clear();
+                            at_cursor.insert(key);
+                        }
+                    }
+                    ops.push(Operation::Insert { new });
+                }
+            }
+            PollerState::Full { records } => {
+                let mut new_records = HashMap::new();
+                for item in items {
+                    let new = record(&self.columns, &item)?;
+                    let key = key(&new, &self.key_indexes);
+                    match records.remove(&key) {
+                        Some(old) if old == new => (),
+                        Some(old) => ops.push(Operation::Update {
+                            old,
+                            new: new.clone(),
+                        }),
+                        None => ops.push(Operation::Insert { new: new.clone() }),
+                    }
+                    new_records.insert(key, new);
+                }
+                ops.extend(records.drain().map(|(_, old)| Operation::Delete { old }));
+                *records = new_records;
+            }
+        }
+        Ok(ops)
+    }
+}
+
+fn key(record: &Record, key_indexes: &[usize]) -> Vec<Field> {
+    key_indexes
+        .iter()
+        .map(|index| record.values[*index].clone())
+        .collect()
+}
+
+/// Orders numbers and strings, e.g. ISO 8601 timestamps, as cursor values.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
@@ -0,0 +1,17 @@
+//! A framework for connectors polling the list endpoints of REST APIs, e.g. of SaaS products, as tables.
+//!
+//! An API is described by a [`RestApi`]: how requests are authenticated, how lists are paginated and its endpoints,
+//! with the columns mapped from their items. Incremental endpoints are requested from the greatest cursor value
+//! polled, and their new items are inserts. Other endpoints are listed in full every poll and compared with the last
+//! poll.
+
+mod api;
+mod client;
+mod connector;
+pub mod stripe;
+
+pub use api::{Auth, Column, ColumnType, Endpoint, Incremental, Pagination, RestApi};
+pub use connector::RestConnector;
+
+#[cfg(test)]
+mod tests;
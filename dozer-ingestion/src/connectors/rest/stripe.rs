@@ -0,0 +1,130 @@
+//! Charges, customers and invoices of Stripe.
+//!
+//! Objects are listed newest first with `starting_after` pagination, and polled from the greatest `created` time
+//! polled, so changes to existing objects, e.g. refunds or payments of invoices, are not followed.
+
+use dozer_types::ingestion_types::StripeConfig;
+use dozer_types::types::FieldType;
+
+use super::{Auth, Column, ColumnType, Endpoint, Incremental, Pagination, RestApi};
+
+pub fn api(config: &StripeConfig) -> RestApi {
+    RestApi {
+        base_url: config.api_url.clone(),
+        auth: Auth::Bearer(config.api_key.clone()),
+        pagination: Pagination::Cursor {
+            param: "starting_after".to_string(),
+            item_field: "id".to_string(),
+            has_more_field: "has_more".to_string(),
+        },
+        page_size: Some(("limit".to_string(), 100)),
+        max_retries: 5,
+        endpoints: vec![
+            endpoint(
+                "charges",
+                vec![
+                    string("id", false),
+                    money("amount"),
+                    money("amount_captured"),
+                    money("amount_refunded"),
+                    string("currency", false),
+                    string("customer", true),
+                    string("description", true),
+                    string("invoice", true),
+                    string("payment_intent", true),
+                    string("receipt_email", true),
+                    string("status", false),
+                    boolean("paid"),
+                    boolean("captured"),
+                    boolean("refunded"),
+                    boolean("livemode"),
+                    timestamp("created", false),
+                    json("metadata"),
+                ],
+            ),
+            endpoint(
+                "customers",
+                vec![
+                    string("id", false),
+                    string("email", true),
+                    string("name", true),
+                    string("description", true),
+                    string("phone", true),
+                    string("currency", true),
+                    money("balance"),
+                    boolean("delinquent"),
+                    boolean("livemode"),
+                    timestamp("created", false),
+                    json("metadata"),
+                ],
+            ),
+            endpoint(
+                "invoices",
+                vec![
+                    string("id", false),
+                    string("customer", true),
+                    string("subscription", true),
+                    string("number", true),
+                    string("status", true),
+                    string("currency", false),
+                    money("amount_due"),
+                    money("amount_paid"),
+                    money("amount_remaining"),
+                    money("subtotal"),
+                    money("total"),
+                    int("attempt_count"),
+                    boolean("paid"),
+                    boolean("livemode"),
+                    timestamp("due_date", true),
+                    timestamp("period_start", true),
+                    timestamp("period_end", true),
+                    timestamp("created", false),
+                    json("metadata"),
+                ],
+            ),
+        ],
+    }
+}
+
+fn endpoint(name: &str, columns: Vec<Column>) -> Endpoint {
+    Endpoint {
+        name: name.to_string(),
+        path: format!("/{name}"),
+        params: vec![],
+        items_field: Some("data".to_string()),
+        columns,
+        primary_key: vec!["id".to_string()],
+        incremental: Some(Incremental {
+            param: "created[gte]".to_string(),
+            field: "created".to_string(),
+        }),
+    }
+}
+
+fn string(name: &str, nullable: bool) -> Column {
+    Column::new(name, ColumnType::Field(FieldType::String), nullable)
+}
+
+fn int(name: &str) -> Column {
+    Column::new(name, ColumnType::Field(FieldType::Int), true)
+}
+
+/// Amounts are integers of the smallest unit of the object's currency, e.g. cents.
+fn money(name: &str) -> Column {
+    let typ = ColumnType::MinorUnits {
+        currency_field: "currency",
+    };
+    Column::new(name, typ, true)
+}
+
+fn boolean(name: &str) -> Column {
+    Column::new(name, ColumnType::Field(FieldType::Boolean), true)
+}
+
+fn timestamp(name: &str, nullable: bool) -> Column {
+    Column::new(name, ColumnType::UnixTimestamp, nullable)
+}
+
+fn json(name: &str) -> Column {
+    Column::new(name, ColumnType::Field(FieldType::Json), true)
+}
@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use dozer_types::chrono::DateTime;
+use dozer_types::errors::types::TypeError;
+use dozer_types::serde_json::{json, Value};
+use dozer_types::types::{Field, FieldType, Operation, Record};
+
+use super::client::{backoff, param_value};
+use super::connector::Poller;
+use super::{Column, ColumnType, Endpoint, Incremental};
+use crate::errors::RestError;
+
+fn endpoint(incremental: bool) -> Endpoint {
+    Endpoint {
+        name: "charges".to_string(),
+        path: "/charges".to_string(),
+        params: vec![],
+        items_field: Some("data".to_string()),
+        columns: columns(),
+        primary_key: vec!["id".to_string()],
+        incremental: incremental.then(|| Incremental {
+            param: "created[gte]".to_string(),
+            field: "created".to_string(),
+        }),
+    }
+}
+
+fn columns() -> Vec<Column> {
+    vec![
+        Column::new("id", ColumnType::Field(FieldType::String), false),
+        Column::new("amount", ColumnType::Field(FieldType::Int), true),
+        Column::new("created", ColumnType::UnixTimestamp, false),
+    ]
+}
+
+fn charge(id: &str, amount: i64, created: i64) -> Value {
+    json!({ "id": id, "amount": amount, "created": created })
+}
+
+fn record(id: &str, amount: i64, created: &str) -> Record {
+    Record::new(vec![
+        Field::String(id.to_string()),
+        Field::Int(amount),
+        Field::Timestamp(DateTime::parse_from_rfc3339(created).unwrap()),
+    ])
+}
+
+#[test]
+fn test_column_value() {
+    let item = json!({ "id": "ch_1", "created": 1678874400, "card": { "brand": "visa" } });
+    let columns = columns();
+    assert_eq!(
+        columns[2].value(&item).unwrap(),
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-03-15T10:00:00Z").unwrap())
+    );
+    // Missing fields of nullable columns are null.
+    assert_eq!(columns[1].value(&item).unwrap(), Field::Null);
+
+    let brand = Column {
+        name: "card_brand".to_string(),
+        path: "card.brand".to_string(),
+        typ: ColumnType::Field(FieldType::String),
+        nullable: true,
+    };
+    assert_eq!(
+        brand.value(&item).unwrap(),
+        Field::String("visa".to_string())
+    );
+
+    assert!(matches!(
+        columns[1].value(&json!({ "amount": "ten" })),
+        Err(RestError::InvalidValue(column, _, _)) if column == "amount"
+    ));
+}
+
+#[test]
+fn test_minor_units_value() {
+    let amount = Column::new(
+        "amount",
+        ColumnType::MinorUnits {
+            currency_field: "currency",
+        },
+        true,
+    );
+    assert_eq!(amount.field_type(), FieldType::Money);
+    assert_eq!(
+        amount
+            .value(&json!({ "amount": 1250, "currency": "usd" }))
+            .unwrap(),
+        Field::Money("12.50 USD".parse().unwrap())
+    );
+    assert_eq!(
+        amount
+            .value(&json!({ "amount": 1250, "currency": "jpy" }))
+            .unwrap(),
+        Field::Money("1250 JPY".parse().unwrap())
+    );
+    assert!(matches!(
+        amount.value(&json!({ "amount": 1250 })),
+        Err(RestError::InvalidValue(column, _, TypeError::UnknownCurrency(_))) if column == "amount"
+    ));
+}
+
+#[test]
+fn test_enum_value() {
+    let status = Column::new(
+        "status",
+        ColumnType::Enum(vec!["pending".to_string(), "succeeded".to_string()]),
+        true,
+    );
+    assert_eq!(status.field_type(), FieldType::Enum);
+    assert_eq!(status.enum_values().len(), 2);
+    assert_eq!(
+        status.value(&json!({ "status": "succeeded" })).unwrap(),
+        Field::Enum(1)
+    );
+    assert!(matches!(
+        status.value(&json!({ "status": "failed" })),
+        Err(RestError::InvalidValue(_, _, TypeError::UnknownEnumValue { value, .. })) if value == "failed"
+    ));
+}
+
+#[test]
+fn test_endpoint_items() {
+    let endpoint = endpoint(true);
+    assert_eq!(
+        endpoint
+            .items(json!({ "data": [{ "id": "ch_1" }], "has_more": false }))
+            .unwrap(),
+        vec![json!({ "id": "ch_1" })]
+    );
+    assert!(matches!(
+        endpoint.items(json!({ "error": "invalid" })),
+        Err(RestError::MissingItems(_, _))
+    ));
+}
+
+#[test]
+fn test_incremental_poller() {
+    let endpoint = endpoint(true);
+    let mut poller = Poller::new(&endpoint, columns());
+    assert_eq!(poller.cursor(), None);
+
+    // Items are listed newest first, and sent oldest first.
+    let ops = poller
+        .changes(vec![
+            charge("ch_2", 20, 1678874401),
+            charge("ch_1", 10, 1678874400),
+        ])
+        .unwrap();
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Insert {
+                new: record("ch_1", 10, "2023-03-15T10:00:00Z")
+            },
+            Operation::Insert {
+                new: record("ch_2", 20, "2023-03-15T10:00:01Z")
+            },
+        ]
+    );
+    assert_eq!(poller.cursor(), Some(&json!(1678874401)));
+
+    // Items at the cursor are listed again, and only the new ones are sent.
+    let ops = poller
+        .changes(vec![
+            charge("ch_3", 30, 1678874401),
+            charge("ch_2", 20, 1678874401),
+        ])
+        .unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: record("ch_3", 30, "2023-03-15T10:00:01Z")
+        }]
+    );
+    assert!(poller.changes(vec![]).unwrap().is_empty());
+}
+
+#[test]
+fn test_full_poller() {
+    let endpoint = endpoint(false);
+    let mut poller = Poller::new(&endpoint, columns());
+    let ops = poller
+        .changes(vec![
+            charge("ch_1", 10, 1678874400),
+            charge("ch_2", 20, 1678874400),
+        ])
+        .unwrap();
+    assert_eq!(ops.len(), 2);
+    assert_eq!(poller.cursor(), None);
+
+    let ops = poller
+        .changes(vec![
+            charge("ch_1", 15, 1678874400),
+            charge("ch_3", 30, 1678874400),
+        ])
+        .unwrap();
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Update {
+                old: record("ch_1", 10, "2023-03-15T10:00:00Z"),
+                new: record("ch_1", 15, "2023-03-15T10:00:00Z"),
+            },
+            Operation::Insert {
+                new: record("ch_3", 30, "2023-03-15T10:00:00Z")
+            },
+            Operation::Delete {
+                old: record("ch_2", 20, "2023-03-15T10:00:00Z")
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_request_helpers() {
+    assert_eq!(param_value(&json!("ch_1")), "ch_1");
+    assert_eq!(param_value(&json!(1678874400)), "1678874400");
+    assert_eq!(backoff(0), Duration::from_secs(1));
+    assert_eq!(backoff(3), Duration::from_secs(8));
+    assert_eq!(backoff(10), Duration::from_secs(60));
+}
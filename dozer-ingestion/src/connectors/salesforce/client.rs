@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::SalesforceConfig;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde_json::{json, Value};
+use reqwest::{Client, Method, Response, StatusCode};
+
+use crate::errors::SalesforceError;
+
+/// How often the state of a query job is checked.
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An OAuth2 access token and the url of the instance it's for.
+#[derive(Debug, Clone)]
+struct Token {
+    access_token: String,
+    instance_url: String,
+}
+
+/// A client of the REST, Bulk 2.0 and Streaming APIs, which requests a new access token when the current one expires.
+#[derive(Debug)]
+pub struct SalesforceClient {
+    client: Client,
+    config: SalesforceConfig,
+    token: Mutex<Option<Token>>,
+}
+
+impl SalesforceClient {
+    pub fn new(config: SalesforceConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            token: Mutex::new(None),
+        }
+    }
+
+    async fn token(&self) -> Result<Token, SalesforceError> {
+        let token = self.token.lock().clone();
+        match token {
+            Some(token) => Ok(token),
+            None => self.refresh_token().await,
+        }
+    }
+
+    /// Requests a new access token, with the refresh token, the user's password or the client credentials.
+    async fn refresh_token(&self) -> Result<Token, SalesforceError> {
+        let config = &self.config;
+        let mut params = vec![("client_id", config.client_id.as_str())];
+        if let Some(client_secret) = &config.client_secret {
+            params.push(("client_secret", client_secret.as_str()));
+        }
+        match (&config.refresh_token, &config.username) {
+            (Some(refresh_token), _) => {
+                params.push(("grant_type", "refresh_token"));
+                params.push(("refresh_token", refresh_token.as_str()));
+            }
+            (None, Some(username)) => {
+                params.push(("grant_type", "password"));
+                params.push(("username", username.as_str()));
+                params.push(("password", config.password.as_deref().unwrap_or_default()));
+            }
+            (None, None) => params.push(("grant_type", "client_credentials")),
+        }
+
+        let url = format!(
+            "{}/services/oauth2/token",
+            config.login_url.trim_end_matches('/')
+        );
+        let response = self.client.post(&url).form(&params).send().await?;
+        let body: Value = check_status(url, response).await?.json().await?;
+        let field = |name: &str| {
+            body.get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    SalesforceError::UnexpectedResponse(format!("token response without {name}"))
+                })
+        };
+        let token = Token {
+            access_token: field("access_token")?,
+            instance_url: field("instance_url")?,
+        };
+        *self.token.lock() = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Sends a request, again with a new access token if the current one expired. Paths starting with `/services/` or
+    /// `/cometd/` are of the instance, and others of the REST API, e.g. `/sobjects`.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> Result<Response, SalesforceError> {
+        let mut token = self.token().await?;
+        let mut refreshed = false;
+        loop {
+            let url = if path.starts_with("/services/") || path.starts_with("/cometd/") {
+                format!("{}{path}", token.instance_url)
+            } else {
+                format!(
+                    "{}/services/data/v{}{path}",
+                    token.instance_url, self.config.api_version
+                )
+            };
+            let mut request = self
+                .client
+                .request(method.clone(), &url)
+                .bearer_auth(&token.access_token)
+                .query(query);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request.send().await?;
+            if response.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                token = self.refresh_token().await?;
+                refreshed = true;
+                continue;
+            }
+            return check_status(url, response).await;
+        }
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value, SalesforceError> {
+        Ok(self
+            .send(Method::GET, path, query, &[], None)
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// The resources of the API version, which checks the credentials.
+    pub async fn resources(&self) -> Result<Value, SalesforceError> {
+        self.get("/", &[]).await
+    }
+
+    /// The objects that can be queried and replicated.
+    pub async fn list_objects(&self) -> Result<Vec<String>, SalesforceError> {
+        let describe = self.get("/sobjects", &[]).await?;
+        let mut objects = describe
+            .get("sobjects")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|object| {
+                object.get("queryable") == Some(&Value::Bool(true))
+                    && object.get("replicateable") == Some(&Value::Bool(true))
+            })
+            .filter_map(|object| object.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        objects.sort();
+        Ok(objects)
+    }
+
+    pub async fn describe(&self, object: &str) -> Result<Value, SalesforceError> {
+        self.get(&format!("/sobjects/{object}/describe"), &[]).await
+    }
+
+    /// The records of a SOQL query, read with the REST API page by page.
+    pub async fn query(&self, soql: &str) -> Result<Vec<Value>, SalesforceError> {
+        let mut records = vec![];
+        let mut response = self.get("/query", &[("q", soql)]).await?;
+        loop {
+            records.extend(
+                response
+                    .get("records")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default(),
+            );
+            let next = response
+                .get("nextRecordsUrl")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            match next {
+                Some(next) => response = self.get(&next, &[]).await?,
+                None => return Ok(records),
+            }
+        }
+    }
+
+    /// Runs a Bulk API 2.0 query job until it completes, and returns its id.
+    pub async fn bulk_query(&self, soql: &str) -> Result<String, SalesforceError> {
+        let job: Value = self
+            .send(
+                Method::POST,
+                "/jobs/query",
+                &[],
+                &[],
+                Some(&json!({"operation": "query", "query": soql})),
+            )
+            .await?
+            .json()
+            .await?;
+        let id = job
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| SalesforceError::UnexpectedResponse("job without id".to_string()))?
+            .to_string();
+        loop {
+            let job = self.get(&format!("/jobs/query/{id}"), &[]).await?;
+            match job.get("state").and_then(Value::as_str) {
+                Some("JobComplete") => return Ok(id),
+                Some("Failed" | "Aborted") => {
+                    let message = job
+                        .get("errorMessage")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    return Err(SalesforceError::JobFailed(id, message.to_string()));
+                }
+                _ => tokio::time::sleep(JOB_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// A page of the CSV results of a completed query job, and the locator of the next page, if any.
+    pub async fn bulk_results(
+        &self,
+        id: &str,
+        locator: Option<&str>,
+    ) -> Result<(String, Option<String>), SalesforceError> {
+        let query = locator
+            .map(|locator| vec![("locator", locator)])
+            .unwrap_or_default();
+        let response = self
+            .send(
+                Method::GET,
+                &format!("/jobs/query/{id}/results"),
+                &query,
+                &[],
+                None,
+            )
+            .await?;
+        let locator = response
+            .headers()
+            .get("Sforce-Locator")
+            .and_then(|locator| locator.to_str().ok())
+            .filter(|locator| *locator != "null")
+            .map(str::to_string);
+        Ok((response.text().await?, locator))
+    }
+
+    /// Sends messages to the CometD endpoint of the Streaming API, with the session cookies. Returns the messages of the
+    /// response and the cookies it sets.
+    pub async fn cometd(
+        &self,
+        cookies: &str,
+        messages: &Value,
+    ) -> Result<(Vec<Value>, Vec<String>), SalesforceError> {
+        let path = format!("/cometd/{}", self.config.api_version);
+        let headers = if cookies.is_empty() {
+            vec![]
+        } else {
+            vec![("Cookie", cookies)]
+        };
+        let response = self
+            .send(Method::POST, &path, &[], &headers, Some(messages))
+            .await?;
+        let cookies = response
+            .headers()
+            .get_all("Set-Cookie")
+            .iter()
+            .filter_map(|cookie| cookie.to_str().ok())
+            .filter_map(|cookie| cookie.split(';').next())
+            .map(str::to_string)
+            .collect();
+        let messages: Value = response.json().await?;
+        match messages {
+            Value::Array(messages) => Ok((messages, cookies)),
+            _ => Err(SalesforceError::UnexpectedResponse(
+                "CometD response is not an array".to_string(),
+            )),
+        }
+    }
+}
+
+async fn check_status(url: String, response: Response) -> Result<Response, SalesforceError> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(SalesforceError::Status(
+            url,
+            status.as_u16(),
+            response.text().await.unwrap_or_default(),
+        ))
+    }
+}
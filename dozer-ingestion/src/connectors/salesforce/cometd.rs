@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dozer_types::log::warn;
+use dozer_types::serde_json::{json, Value};
+
+use super::client::SalesforceClient;
+use crate::errors::SalesforceError;
+
+/// How long to wait before starting a new session when the connection fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The header of a Change Data Capture event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    pub entity: String,
+    /// `CREATE`, `UPDATE`, `DELETE`, `UNDELETE`, the `GAP_` variants of those for events whose fields aren't known, or
+    /// `GAP_OVERFLOW` when there were too many changes to send events for.
+    pub change_type: String,
+    pub record_ids: Vec<String>,
+}
+
+/// Follows Change Data Capture channels with the long-polling transport of the Streaming API's CometD endpoint.
+#[derive(Debug)]
+pub struct CometdClient {
+    client: Arc<SalesforceClient>,
+    /// The replay id of the last event received on each channel, or -1 to receive only new events. A new session
+    /// resubscribes from there.
+    replay_ids: HashMap<String, i64>,
+    client_id: Option<String>,
+    cookies: Vec<String>,
+}
+
+impl CometdClient {
+    pub fn new(client: Arc<SalesforceClient>, channels: Vec<String>) -> Self {
+        Self {
+            client,
+            replay_ids: channels.into_iter().map(|channel| (channel, -1)).collect(),
+            client_id: None,
+            cookies: vec![],
+        }
+    }
+
+    async fn send(&mut self, message: Value) -> Result<Vec<Value>, SalesforceError> {
+        let (messages, cookies) = self
+            .client
+            .cometd(&self.cookies.join("; "), &json!([message]))
+            .await?;
+        for cookie in cookies {
+            let name = cookie.split('=').next().unwrap_or_default().to_string();
+            self.cookies
+                .retain(|existing| existing.split('=').next() != Some(name.as_str()));
+            self.cookies.push(cookie);
+        }
+        Ok(messages)
+    }
+
+    /// Starts a session and subscribes to the channels.
+    pub async fn handshake(&mut self) -> Result<(), SalesforceError> {
+        self.cookies.clear();
+        let messages = self
+            .send(json!({
+                "channel": "/meta/handshake",
+                "version": "1.0",
+                "minimumVersion": "1.0",
+                "supportedConnectionTypes": ["long-polling"],
+            }))
+            .await?;
+        let reply = meta_reply(&messages, "/meta/handshake")?;
+        if !successful(reply) {
+            return Err(SalesforceError::HandshakeFailed(error(reply)));
+        }
+        let client_id = reply
+            .get("clientId")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                SalesforceError::UnexpectedResponse("handshake without client id".to_string())
+            })?
+            .to_string();
+
+        for (channel, replay_id) in self.replay_ids.clone() {
+            let messages = self
+                .send(json!({
+                    "channel": "/meta/subscribe",
+                    "clientId": client_id,
+                    "subscription": channel,
+                    "ext": {"replay": {channel.as_str(): replay_id}},
+                }))
+                .await?;
+            let reply = meta_reply(&messages, "/meta/subscribe")?;
+            if !successful(reply) {
+                return Err(SalesforceError::SubscribeFailed(channel, error(reply)));
+            }
+        }
+        self.client_id = Some(client_id);
+        Ok(())
+    }
+
+    /// Waits for the next events. A new session is started when the server drops the current one.
+    pub async fn next_events(&mut self) -> Result<Vec<ChangeEvent>, SalesforceError> {
+        loop {
+            let Some(client_id) = self.client_id.clone() else {
+                self.handshake().await?;
+                continue;
+            };
+            let messages = self
+                .send(json!({
+                    "channel": "/meta/connect",
+                    "clientId": client_id,
+                    "connectionType": "long-polling",
+                }))
+                .await?;
+
+            let mut events = vec![];
+            for message in &messages {
+                let Some(channel) = message.get("channel").and_then(Value::as_str) else {
+                    continue;
+                };
+                if channel == "/meta/connect" {
+                    if successful(message) {
+                        continue;
+                    }
+                    if message.pointer("/advice/reconnect").and_then(Value::as_str) == Some("none")
+                    {
+                        return Err(SalesforceError::ConnectFailed(error(message)));
+                    }
+                    // E.g. `403::Unknown client` once the session expired.
+                    warn!(
+                        "Streaming API connection failed: {}, starting a new session",
+                        error(message)
+                    );
+                    self.client_id = None;
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                } else if let Some(replay_id) = self.replay_ids.get_mut(channel) {
+                    if let Some(id) = message
+                        .pointer("/data/event/replayId")
+                        .and_then(Value::as_i64)
+                    {
+                        *replay_id = id;
+                    }
+                    events.push(change_event(message)?);
+                }
+            }
+            if !events.is_empty() {
+                return Ok(events);
+            }
+        }
+    }
+}
+
+fn meta_reply<'a>(messages: &'a [Value], channel: &str) -> Result<&'a Value, SalesforceError> {
+    messages
+        .iter()
+        .find(|message| message.get("channel").and_then(Value::as_str) == Some(channel))
+        .ok_or_else(|| SalesforceError::UnexpectedResponse(format!("no reply on {channel}")))
+}
+
+fn successful(reply: &Value) -> bool {
+    reply.get("successful") == Some(&Value::Bool(true))
+}
+
+fn error(reply: &Value) -> String {
+    reply
+        .get("error")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
+fn change_event(message: &Value) -> Result<ChangeEvent, SalesforceError> {
+    let invalid = || SalesforceError::UnexpectedResponse("invalid change event".to_string());
+    let header = message
+        .pointer("/data/payload/ChangeEventHeader")
+        .ok_or_else(invalid)?;
+    let field = |name: &str| {
+        header
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(invalid)
+    };
+    Ok(ChangeEvent {
+        entity: field("entityName")?,
+        change_type: field("changeType")?,
+        record_ids: header
+            .get("recordIds")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_event() {
+        let message = json!({
+            "channel": "/data/AccountChangeEvent",
+            "data": {
+                "schema": "IeRuaY6cbI_HsV8Rv1Mc5g",
+                "payload": {
+                    "ChangeEventHeader": {
+                        "entityName": "Account",
+                        "recordIds": ["001", "002"],
+                        "changeType": "UPDATE",
+                        "changedFields": ["Name", "LastModifiedDate"],
+                    },
+                    "Name": "Acme",
+                },
+                "event": {"replayId": 6},
+            },
+        });
+        assert_eq!(
+            change_event(&message).unwrap(),
+            ChangeEvent {
+                entity: "Account".to_string(),
+                change_type: "UPDATE".to_string(),
+                record_ids: vec!["001".to_string(), "002".to_string()],
+            }
+        );
+        assert!(change_event(&json!({"channel": "/data/AccountChangeEvent"})).is_err());
+    }
+}
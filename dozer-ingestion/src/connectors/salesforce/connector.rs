@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use dozer_types::ingestion_types::{IngestionMessage, SalesforceConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use tokio::sync::mpsc::unbounded_channel;
+use tonic::async_trait;
+
+use super::client::SalesforceClient;
+use super::cometd::{ChangeEvent, CometdClient};
+use super::schema::{change_event_channel, describe_columns, map_type, SObject, TYPES};
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, SalesforceError};
+use crate::ingestion::Ingestor;
+
+/// How many changed records are queried at once, which keeps the query url short enough.
+const IDS_PER_QUERY: usize = 200;
+
+/// Reads Salesforce objects with Bulk API 2.0 query jobs, then follows their Change Data Capture events over the
+/// Streaming API.
+///
+/// Change events only have the changed fields, so the records they name are queried again, and updates only have the
+/// record id of the old record.
+#[derive(Debug)]
+pub struct SalesforceConnector {
+    name: String,
+    config: SalesforceConfig,
+}
+
+#[derive(Debug)]
+struct ObjectReader {
+    object: SObject,
+    /// The ids of the records read, to tell inserts from updates.
+    ids: HashSet<String>,
+}
+
+impl ObjectReader {
+    /// An insert of a record not read before, or an update.
+    fn upsert(&mut self, new: Record) -> Operation {
+        let id = self.object.record_id(&new).unwrap_or_default().to_string();
+        if self.ids.insert(id.clone()) {
+            Operation::Insert { new }
+        } else {
+            Operation::Update {
+                old: self.object.id_record(&id),
+                new,
+            }
+        }
+    }
+
+    /// A delete of a record read before.
+    fn delete(&mut self, id: &str) -> Option<Operation> {
+        self.ids.remove(id).then(|| Operation::Delete {
+            old: self.object.id_record(id),
+        })
+    }
+}
+
+/// The pages of results of a completed query job.
+#[derive(Debug)]
+struct BulkResults {
+    job_id: String,
+    locator: Option<String>,
+    done: bool,
+}
+
+impl BulkResults {
+    async fn new(client: &SalesforceClient, object: &SObject) -> Result<Self, SalesforceError> {
+        Ok(Self {
+            job_id: client.bulk_query(&object.soql(None)).await?,
+            locator: None,
+            done: false,
+        })
+    }
+
+    async fn next_page(
+        &mut self,
+        client: &SalesforceClient,
+        object: &SObject,
+    ) -> Result<Option<Vec<Record>>, SalesforceError> {
+        if self.done {
+            return Ok(None);
+        }
+        let (csv, locator) = client
+            .bulk_results(&self.job_id, self.locator.as_deref())
+            .await?;
+        self.done = locator.is_none();
+        self.locator = locator;
+        object.csv_records(&csv).map(Some)
+    }
+}
+
+impl SalesforceConnector {
+    pub fn new(name: String, config: SalesforceConfig) -> Self {
+        Self { name, config }
+    }
+
+    /// Selects the columns of `table_info` from the describe of its object.
+    async fn get_object(
+        &self,
+        client: &SalesforceClient,
+        table_info: &TableInfo,
+    ) -> Result<SObject, SalesforceError> {
+        let columns = describe_columns(&client.describe(&table_info.name).await?);
+        let columns = table_info
+            .column_names
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .find(|column| column.name == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        SalesforceError::ColumnNotFound(name.clone(), table_info.name.clone())
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        SObject::new(table_info.name.clone(), columns)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let client = Arc::new(SalesforceClient::new(self.config.clone()));
+        let mut readers = vec![];
+        for table_info in &tables {
+            readers.push(ObjectReader {
+                object: self.get_object(&client, table_info).await?,
+                ids: HashSet::new(),
+            });
+        }
+
+        // Subscribe before the snapshot, so that changes made while it's read aren't missed. Events are buffered until
+        // it's done.
+        let channels = readers
+            .iter()
+            .map(|reader| change_event_channel(&reader.object.name))
+            .collect();
+        let mut cometd = CometdClient::new(client.clone(), channels);
+        cometd.handshake().await?;
+        let (sender, mut receiver) = unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let events = cometd.next_events().await;
+                let failed = events.is_err();
+                // The receiver is dropped when the connector stops.
+                if sender.send(events).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+            .map_err(ConnectorError::IngestorError)?;
+        let mut seq_no = 0;
+        for (table_index, reader) in readers.iter_mut().enumerate() {
+            info!("[{}] Reading object {}", self.name, reader.object.name);
+            let mut results = BulkResults::new(&client, &reader.object).await?;
+            while let Some(records) = results.next_page(&client, &reader.object).await? {
+                for new in records {
+                    let op = reader.upsert(new);
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(0, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+        ingestor
+            .handle_message(IngestionMessage::new_snapshotting_done(0, seq_no))
+            .map_err(ConnectorError::IngestorError)?;
+
+        info!("[{}] Following change events", self.name);
+        // The events received together are a transaction.
+        let mut txid = 0;
+        while let Some(events) = receiver.recv().await {
+            let mut events = events?;
+            while let Ok(more) = receiver.try_recv() {
+                events.extend(more?);
+            }
+            let mut seq_no = 0;
+            for event in &events {
+                let Some(table_index) = readers
+                    .iter()
+                    .position(|reader| reader.object.name == event.entity)
+                else {
+                    continue;
+                };
+                for op in self
+                    .changes(&client, &mut readers[table_index], event)
+                    .await?
+                {
+                    if seq_no == 0 {
+                        txid += 1;
+                    }
+                    ingestor
+                        .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                        .map_err(ConnectorError::IngestorError)?;
+                    seq_no += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The operations of a change event. The records it names are queried, and those not found anymore are deleted.
+    async fn changes(
+        &self,
+        client: &SalesforceClient,
+        reader: &mut ObjectReader,
+        event: &ChangeEvent,
+    ) -> Result<Vec<Operation>, SalesforceError> {
+        match event.change_type.as_str() {
+            "DELETE" | "GAP_DELETE" => Ok(event
+                .record_ids
+                .iter()
+                .filter_map(|id| reader.delete(id))
+                .collect()),
+            "GAP_OVERFLOW" => self.resync(client, reader).await,
+            _ => {
+                let mut ops = vec![];
+                for ids in event.record_ids.chunks(IDS_PER_QUERY) {
+                    let condition = format!("Id IN ({})", id_list(ids));
+                    let mut found = HashSet::new();
+                    for record in client.query(&reader.object.soql(Some(&condition))).await? {
+                        let new = reader.object.json_record(&record)?;
+                        if let Some(id) = reader.object.record_id(&new) {
+                            found.insert(id.to_string());
+                        }
+                        ops.push(reader.upsert(new));
+                    }
+                    ops.extend(
+                        ids.iter()
+                            .filter(|id| !found.contains(*id))
+                            .filter_map(|id| reader.delete(id)),
+                    );
+                }
+                Ok(ops)
+            }
+        }
+    }
+
+    /// Reads a whole object again, when there were too many changes to send events for. Records not found anymore are
+    /// deleted.
+    async fn resync(
+        &self,
+        client: &SalesforceClient,
+        reader: &mut ObjectReader,
+    ) -> Result<Vec<Operation>, SalesforceError> {
+        info!(
+            "[{}] Reading object {} again",
+            self.name, reader.object.name
+        );
+        let mut ops = vec![];
+        let mut found = HashSet::new();
+        let mut results = BulkResults::new(client, &reader.object).await?;
+        while let Some(records) = results.next_page(client, &reader.object).await? {
+            for new in records {
+                if let Some(id) = reader.object.record_id(&new) {
+                    found.insert(id.to_string());
+                }
+                ops.push(reader.upsert(new));
+            }
+        }
+        let deleted = reader
+            .ids
+            .iter()
+            .filter(|id| !found.contains(*id))
+            .cloned()
+            .collect::<Vec<_>>();
+        ops.extend(deleted.iter().filter_map(|id| reader.delete(id)));
+        Ok(ops)
+    }
+}
+
+/// The quoted ids of a SOQL `IN` list.
+fn id_list(ids: &[String]) -> String {
+    ids.iter()
+        .map(|id| format!("'{}'", id.replace('\\', "\\\\").replace('\'', "\\'")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait]
+impl Connector for SalesforceConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), *typ))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        SalesforceClient::new(self.config.clone())
+            .resources()
+            .await?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(SalesforceClient::new(self.config.clone())
+            .list_objects()
+            .await?
+            .into_iter()
+            .map(TableIdentifier::from_table_name)
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let client = SalesforceClient::new(self.config.clone());
+        for table in tables {
+            if table.schema.is_some() {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+            client.describe(&table.name).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let client = SalesforceClient::new(self.config.clone());
+        let mut table_infos = vec![];
+        for table in tables {
+            let columns = describe_columns(&client.describe(&table.name).await?);
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names: columns
+                    .into_iter()
+                    .filter(|column| map_type(&column.data_type).is_some())
+                    .map(|column| column.name)
+                    .collect(),
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let client = SalesforceClient::new(self.config.clone());
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                self.get_object(&client, table_info)
+                    .await
+                    .map(|object| SourceSchema::new(object.schema(), CdcType::OnlyPK))
+                    .map_err(ConnectorError::from),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Field;
+
+    use super::super::schema::Column;
+    use super::*;
+
+    #[test]
+    fn test_object_reader() {
+        let column = |name: &str, data_type: &str| Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+        };
+        let object = SObject::new(
+            "Account".to_string(),
+            vec![column("Id", "id"), column("Name", "string")],
+        )
+        .unwrap();
+        let mut reader = ObjectReader {
+            object,
+            ids: HashSet::new(),
+        };
+        let record = |name: &str| {
+            Record::new(vec![
+                Field::String("001".to_string()),
+                Field::String(name.to_string()),
+            ])
+        };
+        let id_record = Record::new(vec![Field::String("001".to_string()), Field::Null]);
+
+        assert_eq!(
+            reader.upsert(record("Acme")),
+            Operation::Insert {
+                new: record("Acme")
+            }
+        );
+        assert_eq!(
+            reader.upsert(record("Acme Corp")),
+            Operation::Update {
+                old: id_record.clone(),
+                new: record("Acme Corp")
+            }
+        );
+        assert_eq!(reader.delete("002"), None);
+        assert_eq!(
+            reader.delete("001"),
+            Some(Operation::Delete { old: id_record })
+        );
+        assert_eq!(reader.delete("001"), None);
+    }
+
+    #[test]
+    fn test_id_list() {
+        assert_eq!(
+            id_list(&["001".to_string(), "0'2".to_string()]),
+            "'001', '0\\'2'"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Salesforce objects, read with Bulk API 2.0 query jobs and then followed with their Change Data Capture events over
+//! the Streaming API. Access tokens are requested with OAuth2 and requested again when they expire.
+
+mod client;
+mod cometd;
+mod connector;
+mod schema;
+
+pub use connector::SalesforceConnector;
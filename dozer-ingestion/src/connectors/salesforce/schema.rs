@@ -0,0 +1,346 @@
+use dozer_types::chrono::{DateTime, NaiveDate};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use crate::errors::SalesforceError;
+
+/// The field of the record id, the primary key of every object.
+pub const ID_FIELD: &str = "Id";
+
+/// Field types of the Salesforce field types. Compound fields and base64 fields can't be queried with the Bulk API.
+pub const TYPES: &[(&str, Option<FieldType>)] = &[
+    ("id", Some(FieldType::String)),
+    ("reference", Some(FieldType::String)),
+    ("string", Some(FieldType::String)),
+    ("picklist", Some(FieldType::String)),
+    ("multipicklist", Some(FieldType::String)),
+    ("combobox", Some(FieldType::String)),
+    ("email", Some(FieldType::String)),
+    ("phone", Some(FieldType::String)),
+    ("url", Some(FieldType::String)),
+    ("encryptedstring", Some(FieldType::String)),
+    ("time", Some(FieldType::String)),
+    ("anyType", Some(FieldType::String)),
+    ("textarea", Some(FieldType::Text)),
+    ("boolean", Some(FieldType::Boolean)),
+    ("int", Some(FieldType::Int)),
+    ("long", Some(FieldType::Int)),
+    ("double", Some(FieldType::Float)),
+    ("percent", Some(FieldType::Float)),
+    ("currency", Some(FieldType::Decimal)),
+    ("date", Some(FieldType::Date)),
+    ("datetime", Some(FieldType::Timestamp)),
+    ("base64", None),
+    ("address", None),
+    ("location", None),
+];
+
+pub fn map_type(typ: &str) -> Option<FieldType> {
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == typ)
+        .and_then(|(_, field_type)| *field_type)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// The Salesforce field type.
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// The fields of an object describe, supported or not.
+pub fn describe_columns(describe: &Value) -> Vec<Column> {
+    describe
+        .get("fields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|field| {
+            Some(Column {
+                name: field.get("name")?.as_str()?.to_string(),
+                data_type: field.get("type")?.as_str()?.to_string(),
+                nullable: field
+                    .get("nillable")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true),
+            })
+        })
+        .collect()
+}
+
+/// The requested columns of an object, of supported types.
+#[derive(Debug, Clone)]
+pub struct SObject {
+    pub name: String,
+    pub columns: Vec<(Column, FieldType)>,
+}
+
+impl SObject {
+    pub fn new(name: String, columns: Vec<Column>) -> Result<Self, SalesforceError> {
+        if !columns.iter().any(|column| column.name == ID_FIELD) {
+            return Err(SalesforceError::MissingId(name));
+        }
+        let columns = columns
+            .into_iter()
+            .map(|column| match map_type(&column.data_type) {
+                Some(typ) => Ok((column, typ)),
+                None => Err(SalesforceError::UnsupportedType(
+                    column.name,
+                    name.clone(),
+                    column.data_type,
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { name, columns })
+    }
+
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (column, typ) in &self.columns {
+            let is_id = column.name == ID_FIELD;
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    *typ,
+                    column.nullable && !is_id,
+                    SourceDefinition::Dynamic,
+                ),
+                is_id,
+            );
+        }
+        schema
+    }
+
+    /// The query of the columns, with an optional condition.
+    pub fn soql(&self, condition: Option<&str>) -> String {
+        let fields = self
+            .columns
+            .iter()
+            .map(|(column, _)| column.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match condition {
+            Some(condition) => format!("SELECT {fields} FROM {} WHERE {condition}", self.name),
+            None => format!("SELECT {fields} FROM {}", self.name),
+        }
+    }
+
+    /// Maps the CSV results of a Bulk API query of the columns.
+    pub fn csv_records(&self, csv: &str) -> Result<Vec<Record>, SalesforceError> {
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let mut records = vec![];
+        for row in reader.records() {
+            let row = row.map_err(|e| SalesforceError::InvalidCsv(self.name.clone(), e))?;
+            records.push(self.csv_record(&row.iter().collect::<Vec<_>>())?);
+        }
+        Ok(records)
+    }
+
+    /// Maps the values of a CSV row, in the order of the columns. Empty values are null.
+    fn csv_record(&self, values: &[&str]) -> Result<Record, SalesforceError> {
+        if values.len() != self.columns.len() {
+            return Err(SalesforceError::UnexpectedResponse(format!(
+                "{} values in a row of {} columns",
+                values.len(),
+                self.columns.len()
+            )));
+        }
+        self.columns
+            .iter()
+            .zip(values)
+            .map(|((column, typ), value)| to_field(value, &column.name, *typ))
+            .collect::<Result<_, _>>()
+            .map(Record::new)
+    }
+
+    /// Maps a record returned by the REST API.
+    pub fn json_record(&self, record: &Value) -> Result<Record, SalesforceError> {
+        self.columns
+            .iter()
+            .map(|(column, typ)| {
+                let value = match record.get(&column.name) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                };
+                to_field(&value, &column.name, *typ)
+            })
+            .collect::<Result<_, _>>()
+            .map(Record::new)
+    }
+
+    /// A record with only the record id, for updates and deletes where the old record isn't known.
+    pub fn id_record(&self, id: &str) -> Record {
+        let values = self
+            .columns
+            .iter()
+            .map(|(column, _)| {
+                if column.name == ID_FIELD {
+                    Field::String(id.to_string())
+                } else {
+                    Field::Null
+                }
+            })
+            .collect();
+        Record::new(values)
+    }
+
+    /// The record id of a record of this object.
+    pub fn record_id<'a>(&self, record: &'a Record) -> Option<&'a str> {
+        let index = self
+            .columns
+            .iter()
+            .position(|(column, _)| column.name == ID_FIELD)?;
+        record.values[index].as_string()
+    }
+}
+
+/// The Change Data Capture channel of an object, e.g. `/data/AccountChangeEvent` or `/data/Film__ChangeEvent` for
+/// `Film__c`.
+pub fn change_event_channel(object: &str) -> String {
+    match object.strip_suffix("__c") {
+        Some(name) => format!("/data/{name}__ChangeEvent"),
+        None => format!("/data/{object}ChangeEvent"),
+    }
+}
+
+fn to_field(value: &str, column: &str, typ: FieldType) -> Result<Field, SalesforceError> {
+    if value.is_empty() {
+        return Ok(Field::Null);
+    }
+    let invalid = || SalesforceError::InvalidValue(column.to_string(), value.to_string());
+    Ok(match typ {
+        FieldType::String => Field::String(value.to_string()),
+        FieldType::Text => Field::Text(value.to_string()),
+        FieldType::Boolean => Field::Boolean(value.parse().map_err(|_| invalid())?),
+        // Numbers of integer fields may be returned with a fraction of zeros.
+        FieldType::Int => Field::Int(match value.parse() {
+            Ok(value) => value,
+            Err(_) => value.parse::<f64>().map_err(|_| invalid())? as i64,
+        }),
+        FieldType::Float => Field::Float(OrderedFloat(value.parse().map_err(|_| invalid())?)),
+        FieldType::Decimal => Field::Decimal(
+            value
+                .parse::<Decimal>()
+                .or_else(|_| Decimal::from_scientific(value))
+                .map_err(|_| invalid())?,
+        ),
+        FieldType::Date => {
+            Field::Date(NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?)
+        }
+        // The Bulk API returns `2023-07-01T12:00:00.000Z`, and the REST API `2023-07-01T12:00:00.000+0000`.
+        FieldType::Timestamp => Field::Timestamp(
+            DateTime::parse_from_rfc3339(value)
+                .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z"))
+                .map_err(|_| invalid())?,
+        ),
+        _ => return Err(invalid()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::json;
+
+    use super::*;
+
+    fn object() -> SObject {
+        let column = |name: &str, data_type: &str| Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+        };
+        SObject::new(
+            "Account".to_string(),
+            vec![
+                column("Id", "id"),
+                column("Name", "string"),
+                column("NumberOfEmployees", "int"),
+                column("AnnualRevenue", "currency"),
+                column("IsDeleted", "boolean"),
+                column("LastModifiedDate", "datetime"),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_records() {
+        let object = object();
+        assert_eq!(
+            object.soql(Some("Id IN ('001')")),
+            "SELECT Id, Name, NumberOfEmployees, AnnualRevenue, IsDeleted, LastModifiedDate FROM Account WHERE Id IN ('001')"
+        );
+
+        let timestamp = DateTime::parse_from_rfc3339("2023-07-01T12:00:00Z").unwrap();
+        let expected = Record::new(vec![
+            Field::String("001".to_string()),
+            Field::String("Acme".to_string()),
+            Field::Int(50),
+            Field::Decimal(Decimal::new(125050, 2)),
+            Field::Boolean(false),
+            Field::Timestamp(timestamp),
+        ]);
+        let csv = "\"Id\",\"Name\",\"NumberOfEmployees\",\"AnnualRevenue\",\"IsDeleted\",\"LastModifiedDate\"\n\
+            \"001\",\"Acme\",\"50\",\"1250.50\",\"false\",\"2023-07-01T12:00:00.000Z\"\n\
+            \"002\",\"Acme, \"\"West\"\"\",\"\",\"\",\"true\",\"\"\n";
+        let records = object.csv_records(csv).unwrap();
+        assert_eq!(records[0], expected);
+        assert_eq!(
+            records[1].values[1],
+            Field::String("Acme, \"West\"".to_string())
+        );
+        assert_eq!(records[1].values[2], Field::Null);
+        assert_eq!(
+            object
+                .json_record(&json!({
+                    "attributes": {"type": "Account"},
+                    "Id": "001",
+                    "Name": "Acme",
+                    "NumberOfEmployees": 50,
+                    "AnnualRevenue": 1250.5,
+                    "IsDeleted": false,
+                    "LastModifiedDate": "2023-07-01T12:00:00.000+0000",
+                }))
+                .unwrap(),
+            expected
+        );
+
+        let record = object.csv_record(&["002", "", "", "", "", ""]).unwrap();
+        assert_eq!(record.values[1], Field::Null);
+        assert_eq!(object.record_id(&record), Some("002"));
+        assert!(object
+            .csv_record(&["003", "Acme", "many", "", "", ""])
+            .is_err());
+    }
+
+    #[test]
+    fn test_unsupported_columns() {
+        let column = |name: &str, data_type: &str| Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+        };
+        assert!(matches!(
+            SObject::new("Account".to_string(), vec![column("Name", "string")]),
+            Err(SalesforceError::MissingId(_))
+        ));
+        assert!(matches!(
+            SObject::new(
+                "Account".to_string(),
+                vec![column("Id", "id"), column("BillingAddress", "address")]
+            ),
+            Err(SalesforceError::UnsupportedType(..))
+        ));
+    }
+
+    #[test]
+    fn test_change_event_channel() {
+        assert_eq!(change_event_channel("Account"), "/data/AccountChangeEvent");
+        assert_eq!(change_event_channel("Film__c"), "/data/Film__ChangeEvent");
+    }
+}
@@ -0,0 +1,260 @@
+//! Infers the schema of schemaless json records, e.g. json messages of Kafka topics, from a sample of them.
+
+use std::collections::BTreeMap;
+
+use dozer_types::chrono::{DateTime, NaiveDate};
+use dozer_types::json_value_to_field;
+use dozer_types::serde_json::{Map, Value};
+use dozer_types::types::{Field, FieldDefinition, FieldType, SourceDefinition, DATE_FORMAT};
+
+use crate::errors::SchemaInferenceError;
+
+#[derive(Debug, Clone)]
+struct InferredColumn {
+    /// `None` while only nulls were sampled.
+    typ: Option<FieldType>,
+    nullable: bool,
+    /// The number of records having the field.
+    count: usize,
+}
+
+/// Widens the types of the fields of sampled records until the schema is locked.
+///
+/// Integers and floats widen to floats, dates and timestamps, both parsed from strings, to strings, and other
+/// different types to json. Fields which are null or missing in some records are nullable, and fields which are only
+/// null are json.
+#[derive(Debug, Default)]
+pub struct SchemaInferrer {
+    columns: BTreeMap<String, InferredColumn>,
+    records: usize,
+}
+
+impl SchemaInferrer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of records sampled.
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    pub fn sample(&mut self, record: &Value) -> Result<(), SchemaInferenceError> {
+        let fields = as_object(record)?;
+        for (name, value) in fields {
+            let typ = value_type(value);
+            match self.columns.get_mut(name) {
+                Some(column) => {
+                    column.typ = widen(column.typ, typ);
+                    column.nullable |= typ.is_none();
+                    column.count += 1;
+                }
+                None => {
+                    let column = InferredColumn {
+                        typ,
+                        nullable: typ.is_none(),
+                        count: 1,
+                    };
+                    self.columns.insert(name.clone(), column);
+                }
+            }
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Locks the schema, with the fields sorted by name.
+    pub fn fields(&self) -> Result<Vec<FieldDefinition>, SchemaInferenceError> {
+        if self.records == 0 {
+            return Err(SchemaInferenceError::NoRecords);
+        }
+        Ok(self
+            .columns
+            .iter()
+            .map(|(name, column)| {
+                FieldDefinition::new(
+                    name.clone(),
+                    column.typ.unwrap_or(FieldType::Json),
+                    // Fields missing in some records are nullable.
+                    column.nullable || column.count < self.records,
+                    SourceDefinition::Dynamic,
+                )
+            })
+            .collect())
+    }
+}
+
+/// Maps a record to the values of `fields`, which are locked. Fields the record doesn't have are null, and fields
+/// not in the schema are ignored.
+pub fn record_values(
+    fields: &[FieldDefinition],
+    record: &Value,
+) -> Result<Vec<Field>, SchemaInferenceError> {
+    let values = as_object(record)?;
+    fields
+        .iter()
+        .map(|field| {
+            let value = values.get(&field.name).cloned().unwrap_or(Value::Null);
+            json_value_to_field(value.clone(), field.typ, field.nullable).map_err(|e| {
+                SchemaInferenceError::TypeMismatch(field.name.clone(), value.to_string(), e)
+            })
+        })
+        .collect()
+}
+
+fn as_object(record: &Value) -> Result<&Map<String, Value>, SchemaInferenceError> {
+    match record {
+        Value::Object(fields) => Ok(fields),
+        _ => Err(SchemaInferenceError::NotAnObject(record.to_string())),
+    }
+}
+
+/// The type of a value, `None` if it's null. Strings of RFC 3339 timestamps and dates are timestamps and dates.
+fn value_type(value: &Value) -> Option<FieldType> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(_) => FieldType::Boolean,
+        Value::Number(number) if number.is_i64() => FieldType::Int,
+        Value::Number(_) => FieldType::Float,
+        Value::String(string) if DateTime::parse_from_rfc3339(string).is_ok() => {
+            FieldType::Timestamp
+        }
+        Value::String(string) if NaiveDate::parse_from_str(string, DATE_FORMAT).is_ok() => {
+            FieldType::Date
+        }
+        Value::String(_) => FieldType::String,
+        Value::Array(_) | Value::Object(_) => FieldType::Json,
+    })
+}
+
+fn widen(existing: Option<FieldType>, typ: Option<FieldType>) -> Option<FieldType> {
+    use FieldType::*;
+    match (existing, typ) {
+        (existing, None) => existing,
+        (None, typ) => typ,
+        (Some(existing), Some(typ)) if existing == typ => Some(typ),
+        (Some(Int), Some(Float)) | (Some(Float), Some(Int)) => Some(Float),
+        (Some(String | Date | Timestamp), Some(String | Date | Timestamp)) => Some(String),
+        _ => Some(Json),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::chrono::NaiveDate;
+    use dozer_types::ordered_float::OrderedFloat;
+    use dozer_types::serde_json::json;
+    use dozer_types::types::{Field, FieldType};
+
+    use super::{record_values, SchemaInferrer};
+    use crate::errors::SchemaInferenceError;
+
+    fn infer(records: &[dozer_types::serde_json::Value]) -> Vec<(String, FieldType, bool)> {
+        let mut inferrer = SchemaInferrer::new();
+        for record in records {
+            inferrer.sample(record).unwrap();
+        }
+        inferrer
+            .fields()
+            .unwrap()
+            .into_iter()
+            .map(|field| (field.name, field.typ, field.nullable))
+            .collect()
+    }
+
+    #[test]
+    fn test_infer_types() {
+        let fields = infer(&[json!({
+            "id": 1,
+            "price": 1.5,
+            "name": "apple",
+            "active": true,
+            "created": "2023-05-01T10:00:00Z",
+            "day": "2023-05-01",
+            "tags": ["fruit"],
+            "address": { "city": "Singapore" },
+        })]);
+        assert_eq!(
+            fields,
+            vec![
+                ("active".to_string(), FieldType::Boolean, false),
+                ("address".to_string(), FieldType::Json, false),
+                ("created".to_string(), FieldType::Timestamp, false),
+                ("day".to_string(), FieldType::Date, false),
+                ("id".to_string(), FieldType::Int, false),
+                ("name".to_string(), FieldType::String, false),
+                ("price".to_string(), FieldType::Float, false),
+                ("tags".to_string(), FieldType::Json, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_widen_types() {
+        let fields = infer(&[
+            json!({ "amount": 1, "code": "2023-05-01", "value": 1, "note": null }),
+            json!({ "amount": 1.5, "code": "A1", "value": "one", "note": null }),
+        ]);
+        assert_eq!(
+            fields,
+            vec![
+                ("amount".to_string(), FieldType::Float, false),
+                ("code".to_string(), FieldType::String, false),
+                ("note".to_string(), FieldType::Json, true),
+                ("value".to_string(), FieldType::Json, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nullable_fields() {
+        let fields = infer(&[
+            json!({ "id": 1, "email": "a@b.c" }),
+            json!({ "id": 2, "phone": "123" }),
+            json!({ "id": 3, "email": null, "phone": "456" }),
+        ]);
+        assert_eq!(
+            fields,
+            vec![
+                ("id".to_string(), FieldType::Int, false),
+                ("email".to_string(), FieldType::String, true),
+                ("phone".to_string(), FieldType::String, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_values() {
+        let mut inferrer = SchemaInferrer::new();
+        inferrer
+            .sample(&json!({ "id": 1, "price": 1.5, "day": "2023-05-01" }))
+            .unwrap();
+        inferrer.sample(&json!({ "id": 2 })).unwrap();
+        let fields = inferrer.fields().unwrap();
+
+        assert_eq!(
+            record_values(&fields, &json!({ "id": 3, "price": 2, "extra": true })).unwrap(),
+            vec![Field::Null, Field::Int(3), Field::Float(OrderedFloat(2.0))]
+        );
+        assert_eq!(
+            record_values(&fields, &json!({ "id": 4, "day": "2023-05-02" })).unwrap()[0],
+            Field::Date(NaiveDate::from_ymd_opt(2023, 5, 2).unwrap())
+        );
+        assert!(matches!(
+            record_values(&fields, &json!({ "id": "four" })),
+            Err(SchemaInferenceError::TypeMismatch(name, _, _)) if name == "id"
+        ));
+        assert!(matches!(
+            record_values(&fields, &json!([1])),
+            Err(SchemaInferenceError::NotAnObject(_))
+        ));
+    }
+
+    #[test]
+    fn test_no_records() {
+        assert!(matches!(
+            SchemaInferrer::new().fields(),
+            Err(SchemaInferenceError::NoRecords)
+        ));
+    }
+}
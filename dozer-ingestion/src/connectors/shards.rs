@@ -0,0 +1,80 @@
+//! Shards of Kinesis streams and DynamoDB Streams, which are read in the order they were created by resharding.
+
+use dozer_types::serde::{Deserialize, Serialize};
+
+/// How far a shard was read, kept in the connector's checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum ShardPosition {
+    /// The sequence number of the last record read.
+    SequenceNumber(String),
+    /// The shard was closed and all its records were read.
+    Finished,
+}
+
+/// A shard of a stream, and the shards it was split from or merged from by resharding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardInfo {
+    pub id: String,
+    pub parent_ids: Vec<String>,
+}
+
+/// Returns the shards that can be read, in order.
+///
+/// Records of a partition key move to the child shards when a shard is split or merged, so a child is only read once
+/// all its parents are finished. Parents that are no longer listed expired with the retention period and can't be read
+/// anymore.
+pub fn readable_shards<'a>(
+    shards: &'a [ShardInfo],
+    is_finished: impl Fn(&str) -> bool,
+) -> Vec<&'a ShardInfo> {
+    let is_listed = |id: &str| shards.iter().any(|shard| shard.id == id);
+    shards
+        .iter()
+        .filter(|shard| {
+            !is_finished(&shard.id)
+                && shard
+                    .parent_ids
+                    .iter()
+                    .all(|parent_id| is_finished(parent_id) || !is_listed(parent_id))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard(id: &str, parent_ids: &[&str]) -> ShardInfo {
+        ShardInfo {
+            id: id.to_string(),
+            parent_ids: parent_ids.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn readable(shards: &[ShardInfo], finished: &[&str]) -> Vec<String> {
+        readable_shards(shards, |id| finished.contains(&id))
+            .into_iter()
+            .map(|shard| shard.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_readable_shards() {
+        // Shard 0 was split into 1 and 2, which were merged into 3.
+        let shards = vec![
+            shard("0", &[]),
+            shard("1", &["0"]),
+            shard("2", &["0"]),
+            shard("3", &["1", "2"]),
+        ];
+        assert_eq!(readable(&shards, &[]), vec!["0"]);
+        assert_eq!(readable(&shards, &["0"]), vec!["1", "2"]);
+        assert_eq!(readable(&shards, &["0", "1"]), vec!["2"]);
+        assert_eq!(readable(&shards, &["0", "1", "2"]), vec!["3"]);
+        assert!(readable(&shards, &["0", "1", "2", "3"]).is_empty());
+
+        // Shard 0 expired.
+        assert_eq!(readable(&shards[1..], &[]), vec!["1", "2"]);
+    }
+}
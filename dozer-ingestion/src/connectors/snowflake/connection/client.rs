@@ -229,6 +229,15 @@ impl Client {
         Self { conn_string, name }
     }
 
+    /// Names the objects the client creates after `name` instead of a random name, so that they outlive the client.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self
+    }
+
     pub fn get_conn_string(&self) -> String {
         self.conn_string.clone()
     }
@@ -516,6 +525,7 @@ impl Client {
                                     typ,
                                     nullable: *nullable,
                                     source: SourceDefinition::Dynamic,
+                                    enum_values: vec![],
                                 });
                             }
                         }
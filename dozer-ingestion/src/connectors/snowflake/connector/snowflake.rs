@@ -7,16 +7,15 @@ use crate::ingestion::Ingestor;
 use dozer_types::ingestion_types::SnowflakeConfig;
 use tonic::async_trait;
 
-use crate::connectors::snowflake::stream_consumer::StreamConsumer;
+use crate::connectors::snowflake::stream_consumer::{StreamConsumer, StreamState};
 
+use dozer_types::chrono::{TimeZone, Utc};
 use dozer_types::log::{info, warn};
 
 use crate::connectors::snowflake::schema_helper::SchemaHelper;
 
 use tokio::time;
 
-use crate::errors::{SnowflakeError, SnowflakeStreamError};
-
 #[derive(Debug)]
 pub struct SnowflakeConnector {
     name: String,
@@ -128,47 +127,60 @@ async fn run(
     ingestor: &Ingestor,
     from_seq: Option<(u64, u64)>,
 ) -> Result<(), ConnectorError> {
-    // SNAPSHOT part - run it when stream table doesn't exist
-    let stream_client = Client::new(&config);
+    // Streams are named after the connection, so that a run continuing from a checkpoint reads the streams of the
+    // run before it.
+    let stream_client = Client::new(&config).with_name(&name);
     let mut interval = time::interval(Duration::from_secs(5));
 
     let mut consumer = StreamConsumer::new();
-    let mut iteration = 0;
+    // Each batch of changes read from a stream is a transaction. The first one of each new stream is the snapshot.
+    let mut txid = match from_seq {
+        None | Some((0, _)) => {
+            for table in &tables {
+                info!("[{}][{}] Creating new stream", name, table.name);
+                StreamConsumer::drop_changes_table(&stream_client, &table.name)?;
+                StreamConsumer::drop_stream(&stream_client, &table.name)?;
+                StreamConsumer::create_stream(&stream_client, &table.name)?;
+            }
+            0
+        }
+        Some((lsn, seq)) => {
+            info!("[{}] Continuing ingestion from {}/{}", name, lsn, seq);
+            lsn + 1
+        }
+    };
     loop {
         for (idx, table) in tables.iter().enumerate() {
-            // We only check stream status on first iteration
-            if iteration == 0 {
-                match from_seq {
-                    None | Some((0, _)) => {
-                        info!("[{}][{}] Creating new stream", name, table.name);
-                        StreamConsumer::drop_stream(&stream_client, &table.name)?;
-                        StreamConsumer::create_stream(&stream_client, &table.name)?;
-                    }
-                    Some((lsn, seq)) => {
-                        info!(
-                            "[{}][{}] Continuing ingestion from {}/{}",
-                            name, table.name, lsn, seq
-                        );
-                        if let Ok(false) =
-                            StreamConsumer::is_stream_created(&stream_client, &table.name)
-                        {
-                            return Err(ConnectorError::SnowflakeError(
-                                SnowflakeError::SnowflakeStreamError(
-                                    SnowflakeStreamError::StreamNotFound,
-                                ),
-                            ));
-                        }
-                    }
+            match StreamConsumer::get_stream_state(&stream_client, &table.name)? {
+                StreamState::Ready => (),
+                state => {
+                    // The changes since the offset are lost, so the table is read again from a new stream.
+                    warn!(
+                        "[{}][{}] Re-creating stream, which can't be read: {:?}. All rows are ingested again",
+                        name, table.name, state
+                    );
+                    StreamConsumer::drop_stream(&stream_client, &table.name)?;
+                    StreamConsumer::create_stream(&stream_client, &table.name)?;
                 }
             }
 
-            info!("[{}][{}] Reading from changes stream", name, table.name);
-
-            consumer.consume_stream(&stream_client, &table.name, ingestor, idx, iteration)?;
-
-            interval.tick().await;
+            let offset = StreamConsumer::get_stream_offset(&stream_client, &table.name)?;
+            let count =
+                consumer.consume_stream(&stream_client, &table.name, ingestor, idx, txid)?;
+            if count > 0 {
+                info!(
+                    "[{}][{}] Read {} changes after offset {}",
+                    name,
+                    table.name,
+                    count,
+                    offset.map_or("unknown".to_string(), |offset| Utc
+                        .timestamp_nanos(offset as i64)
+                        .to_rfc3339())
+                );
+                txid += 1;
+            }
         }
 
-        iteration += 1;
+        interval.tick().await;
     }
 }
@@ -8,6 +8,38 @@ use crate::errors::SnowflakeStreamError::{CannotDetermineAction, UnsupportedActi
 use dozer_types::types::{Field, Operation, Record};
 use odbc::create_environment_v3;
 
+/// State of the stream of a table, from `SHOW STREAMS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamState {
+    Missing,
+    Ready,
+    /// The stream can't be read anymore, with the reason. A stream becomes stale when its offset is older than the
+    /// data retention of its table, or when its table is re-created or dropped.
+    Stale(String),
+}
+
+impl StreamState {
+    /// The state of a stream from its `stale` and `invalid_reason` columns, missing if it has no row.
+    fn from_row(row: Option<&[Field]>) -> Self {
+        let Some(row) = row else {
+            return StreamState::Missing;
+        };
+        let column = |index: usize| match row.get(index) {
+            Some(Field::String(value)) => Some(value.as_str()),
+            _ => None,
+        };
+        match (column(0), column(1)) {
+            (_, Some(reason)) if !reason.is_empty() && reason != "N/A" => {
+                StreamState::Stale(reason.to_string())
+            }
+            (Some(stale), _) if stale.eq_ignore_ascii_case("true") => {
+                StreamState::Stale("offset is older than the data retention".to_string())
+            }
+            _ => StreamState::Ready,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct StreamConsumer {}
 
@@ -20,8 +52,69 @@ impl StreamConsumer {
         format!("dozer_{table_name}_{client_name}_stream")
     }
 
-    pub fn get_stream_temp_table_name(table_name: &str, client_name: &str) -> String {
-        format!("dozer_{table_name}_{client_name}_stream_temp")
+    /// The table the changes of a stream are moved to before they're read. It's kept until they're all ingested, so
+    /// that changes consumed from the stream aren't lost if the connector stops while reading them.
+    pub fn get_stream_changes_table_name(table_name: &str, client_name: &str) -> String {
+        format!("dozer_{table_name}_{client_name}_stream_changes")
+    }
+
+    pub fn get_stream_state(
+        client: &Client,
+        table_name: &str,
+    ) -> Result<StreamState, ConnectorError> {
+        let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+        let conn = env
+            .connect_with_connection_string(&client.get_conn_string())
+            .unwrap();
+
+        let stream_name = Self::get_stream_table_name(table_name, &client.get_name());
+        client.exec(&conn, format!("SHOW STREAMS LIKE '{stream_name}'"))?;
+        // The types of some columns of `SHOW` results can't be converted, so only read the ones needed as strings.
+        let result = client.fetch(
+            &conn,
+            "SELECT TO_VARCHAR(\"stale\"), TO_VARCHAR(\"invalid_reason\") \
+            FROM TABLE(RESULT_SCAN(LAST_QUERY_ID()))"
+                .to_string(),
+        )?;
+        let row = result.and_then(|(_, mut iterator)| iterator.next());
+        Ok(StreamState::from_row(row.as_deref()))
+    }
+
+    /// The offset of the stream of a table: the time of the table version it's at, in nanoseconds since the epoch.
+    pub fn get_stream_offset(
+        client: &Client,
+        table_name: &str,
+    ) -> Result<Option<u64>, ConnectorError> {
+        let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+        let conn = env
+            .connect_with_connection_string(&client.get_conn_string())
+            .unwrap();
+
+        let stream_name = Self::get_stream_table_name(table_name, &client.get_name());
+        let result = client.fetch(
+            &conn,
+            format!("SELECT TO_VARCHAR(SYSTEM$STREAM_GET_TABLE_TIMESTAMP('{stream_name}'))"),
+        )?;
+        Ok(result
+            .and_then(|(_, mut iterator)| iterator.next())
+            .and_then(|row| match row.first() {
+                Some(Field::String(offset)) => offset.parse().ok(),
+                _ => None,
+            }))
+    }
+
+    pub fn drop_changes_table(client: &Client, table_name: &str) -> Result<(), SnowflakeError> {
+        let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+        let conn = env
+            .connect_with_connection_string(&client.get_conn_string())
+            .unwrap();
+
+        let query = format!(
+            "DROP TABLE IF EXISTS {}",
+            Self::get_stream_changes_table_name(table_name, &client.get_name()),
+        );
+
+        client.exec(&conn, query).map(|_| ())
     }
 
     pub fn is_stream_created(client: &Client, table_name: &str) -> Result<bool, ConnectorError> {
@@ -110,33 +203,35 @@ impl StreamConsumer {
         }
     }
 
+    /// Moves the changes of the stream of a table to its changes table, unless changes moved before weren't all
+    /// ingested, and ingests them as transaction `txid`. Returns the number of changes.
     pub fn consume_stream(
         &mut self,
         client: &Client,
         table_name: &str,
         ingestor: &Ingestor,
         table_idx: usize,
-        iteration: u64,
-    ) -> Result<(), ConnectorError> {
+        txid: u64,
+    ) -> Result<u64, ConnectorError> {
         let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
         let conn = env
             .connect_with_connection_string(&client.get_conn_string())
             .unwrap();
 
-        let temp_table_name = Self::get_stream_temp_table_name(table_name, &client.get_name());
+        let changes_table_name =
+            Self::get_stream_changes_table_name(table_name, &client.get_name());
         let stream_name = Self::get_stream_table_name(table_name, &client.get_name());
-        let temp_table_exist = client.table_exist(&conn, &temp_table_name)?;
 
-        if !temp_table_exist {
-            let query = format!(
-                "CREATE OR REPLACE TEMP TABLE {temp_table_name} AS
-                    SELECT * FROM {stream_name} ORDER BY METADATA$ACTION;"
-            );
-
-            client.exec(&conn, query)?;
-        }
+        // Creating the table consumes the stream, advancing its offset. If the table is left from a previous run, the
+        // stream isn't consumed and the changes in the table are ingested again.
+        let query = format!(
+            "CREATE TRANSIENT TABLE IF NOT EXISTS {changes_table_name} AS
+                SELECT * FROM {stream_name} ORDER BY METADATA$ACTION;"
+        );
+        client.exec(&conn, query)?;
 
-        let result = client.fetch(&conn, format!("SELECT * FROM {temp_table_name};"))?;
+        let mut count = 0;
+        let result = client.fetch(&conn, format!("SELECT * FROM {changes_table_name};"))?;
         if let Some((schema, iterator)) = result {
             let mut truncated_schema = schema.clone();
             truncated_schema.truncate(schema.len() - 3);
@@ -148,18 +243,45 @@ impl StreamConsumer {
             for (idx, row) in iterator.enumerate() {
                 let op = Self::get_operation(row, action_idx, used_columns_for_schema)?;
                 ingestor
-                    .handle_message(IngestionMessage::new_op(
-                        iteration, idx as u64, table_idx, op,
-                    ))
+                    .handle_message(IngestionMessage::new_op(txid, idx as u64, table_idx, op))
                     .map_err(ConnectorError::IngestorError)?;
+                count += 1;
             }
         }
 
-        let query = format!("DROP TABLE {temp_table_name};");
+        let query = format!("DROP TABLE {changes_table_name};");
 
         client
             .exec(&conn, query)
             .map_err(ConnectorError::SnowflakeError)
-            .map(|_| ())
+            .map(|_| count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_state() {
+        let row = |stale: &str, invalid_reason: &str| {
+            vec![
+                Field::String(stale.to_string()),
+                Field::String(invalid_reason.to_string()),
+            ]
+        };
+        assert_eq!(StreamState::from_row(None), StreamState::Missing);
+        assert_eq!(
+            StreamState::from_row(Some(&row("false", "N/A"))),
+            StreamState::Ready
+        );
+        assert!(matches!(
+            StreamState::from_row(Some(&row("true", "N/A"))),
+            StreamState::Stale(_)
+        ));
+        assert_eq!(
+            StreamState::from_row(Some(&row("false", "Base table dropped"))),
+            StreamState::Stale("Base table dropped".to_string())
+        );
     }
 }
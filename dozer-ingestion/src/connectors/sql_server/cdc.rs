@@ -0,0 +1,304 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{IngestionMessage, SqlServerConfig};
+use dozer_types::log::{info, warn};
+use dozer_types::types::{Operation, Record};
+use tiberius::Row;
+
+use super::schema::{connect, quote, SqlServerClient, Table};
+use crate::errors::{ConnectorError, SqlServerError};
+use crate::ingestion::Ingestor;
+
+/// How long to wait before reconnecting when the connection drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A log sequence number, which orders the changes of a database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Lsn(pub [u8; 10]);
+
+impl Lsn {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SqlServerError> {
+        bytes
+            .try_into()
+            .map(Lsn)
+            .map_err(|_| SqlServerError::InvalidLsn(bytes.to_vec()))
+    }
+
+    /// The smallest LSN after this one, like `sys.fn_cdc_increment_lsn`.
+    pub fn next(&self) -> Lsn {
+        let mut bytes = self.0;
+        for byte in bytes.iter_mut().rev() {
+            let (incremented, overflow) = byte.overflowing_add(1);
+            *byte = incremented;
+            if !overflow {
+                break;
+            }
+        }
+        Lsn(bytes)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 10]
+    }
+}
+
+impl Display for Lsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for byte in self.0 {
+            write!(f, "{byte:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The highest LSN captured in change tables, or `None` if nothing is captured yet.
+pub async fn max_lsn(client: &mut SqlServerClient) -> Result<Option<Lsn>, SqlServerError> {
+    lsn(client, "SELECT sys.fn_cdc_get_max_lsn()", None).await
+}
+
+/// The lowest LSN still in the change table of a capture instance, which cleanup moves forward.
+async fn min_lsn(
+    client: &mut SqlServerClient,
+    capture_instance: &str,
+) -> Result<Option<Lsn>, SqlServerError> {
+    lsn(
+        client,
+        "SELECT sys.fn_cdc_get_min_lsn(@P1)",
+        Some(capture_instance),
+    )
+    .await
+}
+
+async fn lsn(
+    client: &mut SqlServerClient,
+    sql: &str,
+    param: Option<&str>,
+) -> Result<Option<Lsn>, SqlServerError> {
+    let stream = match param {
+        Some(param) => client.query(sql, &[&param]).await?,
+        None => client.query(sql, &[]).await?,
+    };
+    let Some(row) = stream.into_row().await? else {
+        return Ok(None);
+    };
+    // `fn_cdc_get_min_lsn` returns zeros for unknown capture instances.
+    match row.get::<&[u8], _>(0) {
+        Some(bytes) => Ok(Some(Lsn::from_bytes(bytes)?).filter(|lsn| !lsn.is_zero())),
+        None => Ok(None),
+    }
+}
+
+/// `__$operation` of change table rows.
+const OPERATION_DELETE: i32 = 1;
+const OPERATION_INSERT: i32 = 2;
+const OPERATION_UPDATE_BEFORE: i32 = 3;
+const OPERATION_UPDATE_AFTER: i32 = 4;
+
+/// A row of a change table.
+#[derive(Debug)]
+pub struct Change {
+    /// The commit LSN of the change's transaction.
+    pub lsn: Lsn,
+    /// Orders the changes of a transaction.
+    pub seqval: Lsn,
+    pub operation: i32,
+    pub table_index: usize,
+    pub record: Record,
+}
+
+/// Pairs the before and after rows of updates, returning the operations of each transaction in commit order.
+///
+/// `changes` must be sorted by LSN, sequence value and operation.
+pub fn transactions(changes: Vec<Change>) -> Vec<Vec<(usize, Operation)>> {
+    let mut transactions: Vec<Vec<(usize, Operation)>> = vec![];
+    let mut lsn = None;
+    let mut before: Option<Record> = None;
+    for change in changes {
+        if lsn != Some(change.lsn) {
+            transactions.push(vec![]);
+            lsn = Some(change.lsn);
+        }
+        let op = match change.operation {
+            OPERATION_DELETE => Operation::Delete { old: change.record },
+            OPERATION_INSERT => Operation::Insert { new: change.record },
+            OPERATION_UPDATE_BEFORE => {
+                before = Some(change.record);
+                continue;
+            }
+            OPERATION_UPDATE_AFTER => match before.take() {
+                Some(old) => Operation::Update {
+                    old,
+                    new: change.record,
+                },
+                // Without the before row, the change can only be applied as an upsert of the new row.
+                None => Operation::Insert { new: change.record },
+            },
+            _ => continue,
+        };
+        if let Some(transaction) = transactions.last_mut() {
+            transaction.push((change.table_index, op));
+        }
+    }
+    transactions
+}
+
+/// Polls the change tables of `tables` for changes after an LSN.
+///
+/// Changes are sent a transaction at a time, so a dropped connection is resumed after the last sent transaction.
+#[derive(Debug)]
+pub struct ChangeReader {
+    name: String,
+    config: SqlServerConfig,
+    tables: Vec<Table>,
+    lsn: Lsn,
+    txn: u64,
+}
+
+impl ChangeReader {
+    pub fn new(name: String, config: SqlServerConfig, tables: Vec<Table>, lsn: Lsn) -> Self {
+        Self {
+            name,
+            config,
+            tables,
+            lsn,
+            txn: 0,
+        }
+    }
+
+    /// Polls until an error other than a dropped connection.
+    pub async fn run(&mut self, ingestor: &Ingestor) -> Result<(), ConnectorError> {
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        loop {
+            info!("[{}] Reading changes after LSN {}", self.name, self.lsn);
+            match connect(&self.config).await {
+                Ok(mut client) => loop {
+                    match self.poll(&mut client, ingestor).await {
+                        Ok(()) => tokio::time::sleep(poll_interval).await,
+                        Err(ConnectorError::SqlServerError(e)) if is_connection_error(&e) => {
+                            warn!("[{}] Connection failed: {e}", self.name);
+                            break;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                },
+                Err(e) if is_connection_error(&e) => {
+                    warn!("[{}] Cannot connect: {e}", self.name);
+                }
+                Err(e) => return Err(e.into()),
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Sends the changes captured since the last poll.
+    async fn poll(
+        &mut self,
+        client: &mut SqlServerClient,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let Some(max_lsn) = max_lsn(client).await?.filter(|max_lsn| *max_lsn > self.lsn) else {
+            return Ok(());
+        };
+        let mut changes = vec![];
+        for table_index in 0..self.tables.len() {
+            changes.extend(self.read(client, table_index, max_lsn).await?);
+        }
+        changes.sort_by(|a, b| (a.lsn, a.seqval, a.operation).cmp(&(b.lsn, b.seqval, b.operation)));
+        for operations in transactions(changes) {
+            self.send(operations, ingestor)?;
+        }
+        self.lsn = max_lsn;
+        Ok(())
+    }
+
+    /// Reads the changes of a table after the current LSN, up to `max_lsn`.
+    async fn read(
+        &self,
+        client: &mut SqlServerClient,
+        table_index: usize,
+        max_lsn: Lsn,
+    ) -> Result<Vec<Change>, SqlServerError> {
+        let table = &self.tables[table_index];
+        let mut from = self.lsn.next();
+        let Some(min_lsn) = min_lsn(client, &table.capture_instance).await? else {
+            return Err(SqlServerError::TableCdcNotEnabled(table.quoted_name()));
+        };
+        if min_lsn > from {
+            // Nothing was read yet when the snapshot was taken before anything was captured.
+            if !self.lsn.is_zero() {
+                return Err(SqlServerError::ChangesCleanedUp(table.quoted_name()));
+            }
+            from = min_lsn;
+        }
+        if from > max_lsn {
+            return Ok(vec![]);
+        }
+
+        let function = quote(&format!(
+            "fn_cdc_get_all_changes_{}",
+            table.capture_instance
+        ));
+        let sql = format!(
+            "SELECT __$start_lsn, __$seqval, __$operation, {} FROM cdc.{function}(@P1, @P2, N'all update old')",
+            table.quoted_columns()
+        );
+        let rows = client
+            .query(sql, &[&from.0.to_vec(), &max_lsn.0.to_vec()])
+            .await?
+            .into_first_result()
+            .await?;
+        rows.into_iter()
+            .map(|row| change(table, table_index, row))
+            .collect()
+    }
+
+    fn send(
+        &mut self,
+        operations: Vec<(usize, Operation)>,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.txn += 1;
+        for (seq_no, (table_index, op)) in operations.into_iter().enumerate() {
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    self.txn,
+                    seq_no as u64,
+                    table_index,
+                    op,
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        Ok(())
+    }
+}
+
+fn change(table: &Table, table_index: usize, row: Row) -> Result<Change, SqlServerError> {
+    let lsn = Lsn::from_bytes(row.get::<&[u8], _>(0).unwrap_or_default())?;
+    let seqval = Lsn::from_bytes(row.get::<&[u8], _>(1).unwrap_or_default())?;
+    let operation = row.get::<i32, _>(2).unwrap_or_default();
+    let values = table
+        .columns
+        .iter()
+        .zip(row.into_iter().skip(3))
+        .map(|(column, data)| column.convert(&data))
+        .collect::<Result<_, _>>()?;
+    Ok(Change {
+        lsn,
+        seqval,
+        operation,
+        table_index,
+        record: Record::new(values),
+    })
+}
+
+fn is_connection_error(error: &SqlServerError) -> bool {
+    matches!(
+        error,
+        SqlServerError::SqlServer(tiberius::error::Error::Io { .. })
+    )
+}
@@ -0,0 +1,206 @@
+use dozer_types::ingestion_types::{IngestionMessage, SqlServerConfig};
+use dozer_types::log::info;
+use dozer_types::types::{FieldType, Operation, Record};
+use futures::TryStreamExt;
+use tonic::async_trait;
+
+use super::cdc::{max_lsn, ChangeReader, Lsn};
+use super::schema::{self, connect, SqlServerClient, Table, TYPES};
+use crate::connectors::{
+    table_name, CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, SqlServerError};
+use crate::ingestion::Ingestor;
+
+/// The schema of tables without one.
+const DEFAULT_SCHEMA: &str = "dbo";
+
+/// Snapshots tables, then polls their CDC change tables for the changes after the snapshot started.
+#[derive(Debug)]
+pub struct SqlServerConnector {
+    name: String,
+    config: SqlServerConfig,
+}
+
+impl SqlServerConnector {
+    pub fn new(name: String, config: SqlServerConfig) -> Self {
+        Self { name, config }
+    }
+
+    async fn get_table(
+        &self,
+        client: &mut SqlServerClient,
+        table_info: &TableInfo,
+    ) -> Result<Table, ConnectorError> {
+        Ok(schema::get_table(
+            client,
+            table_info.schema.as_deref().unwrap_or(DEFAULT_SCHEMA),
+            &table_info.name,
+            &table_info.column_names,
+        )
+        .await?)
+    }
+
+    async fn run(&self, ingestor: &Ingestor, tables: Vec<TableInfo>) -> Result<(), ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        let mut source_tables = vec![];
+        for table_info in &tables {
+            source_tables.push(self.get_table(&mut client, table_info).await?);
+        }
+
+        let lsn = snapshot(&mut client, &source_tables, ingestor).await?;
+        info!("[{}] Snapshotted at LSN {}", self.name, lsn);
+        drop(client);
+
+        ChangeReader::new(self.name.clone(), self.config.clone(), source_tables, lsn)
+            .run(ingestor)
+            .await
+    }
+}
+
+#[async_trait]
+impl Connector for SqlServerConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        TYPES
+            .iter()
+            .map(|(name, typ)| (name.to_string(), Some(*typ)))
+            .collect()
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        if !schema::is_cdc_enabled(&mut client).await? {
+            return Err(SqlServerError::CdcNotEnabled(self.config.database.clone()).into());
+        }
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        Ok(schema::list_tables(&mut client)
+            .await?
+            .into_iter()
+            .map(|(schema, name)| TableIdentifier::new(Some(schema), name))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        for table in tables {
+            let schema = table.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+            let column_names = schema::list_columns(&mut client, schema, &table.name).await?;
+            if column_names.is_empty() {
+                return Err(ConnectorError::TableNotFound(table_name(
+                    Some(schema),
+                    &table.name,
+                )));
+            }
+            // Checks that CDC captures the table.
+            schema::get_table(&mut client, schema, &table.name, &[]).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        let mut table_infos = vec![];
+        for table in tables {
+            let column_names = schema::list_columns(
+                &mut client,
+                table.schema.as_deref().unwrap_or(DEFAULT_SCHEMA),
+                &table.name,
+            )
+            .await?;
+            table_infos.push(TableInfo {
+                schema: table.schema,
+                name: table.name,
+                column_names,
+            });
+        }
+        Ok(table_infos)
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        let mut client = connect(&self.config).await?;
+        let mut schemas = vec![];
+        for table_info in table_infos {
+            schemas.push(
+                self.get_table(&mut client, table_info)
+                    .await
+                    .map(|table| SourceSchema::new(table.schema(), CdcType::FullChanges)),
+            );
+        }
+        Ok(schemas)
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.run(ingestor, tables).await
+    }
+}
+
+/// Sends the rows of `tables`, returning the LSN changes are read after.
+///
+/// The LSN is read before the snapshot, so changes committed while it's read are sent again after it. They leave the
+/// same records once applied, as long as duplicate inserts are resolved by the endpoint's conflict resolution.
+async fn snapshot(
+    client: &mut SqlServerClient,
+    tables: &[Table],
+    ingestor: &Ingestor,
+) -> Result<Lsn, ConnectorError> {
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_started(0, 0))
+        .map_err(ConnectorError::IngestorError)?;
+
+    let lsn = max_lsn(client).await?.unwrap_or_default();
+
+    let mut seq_no = 0;
+    for (table_index, table) in tables.iter().enumerate() {
+        let sql = format!(
+            "SELECT {} FROM {}",
+            table.quoted_columns(),
+            table.quoted_name()
+        );
+        let mut rows = client
+            .query(sql, &[])
+            .await
+            .map_err(SqlServerError::from)?
+            .into_row_stream();
+        while let Some(row) = rows.try_next().await.map_err(SqlServerError::from)? {
+            let values = table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, data)| column.convert(&data))
+                .collect::<Result<_, _>>()?;
+            seq_no += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_op(
+                    0,
+                    seq_no,
+                    table_index,
+                    Operation::Insert {
+                        new: Record::new(values),
+                    },
+                ))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+    }
+
+    ingestor
+        .handle_message(IngestionMessage::new_snapshotting_done(0, 0))
+        .map_err(ConnectorError::IngestorError)?;
+    Ok(lsn)
+}
@@ -0,0 +1,8 @@
+mod cdc;
+mod connector;
+mod schema;
+
+pub use connector::SqlServerConnector;
+
+#[cfg(test)]
+mod tests;
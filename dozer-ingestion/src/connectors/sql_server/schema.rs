@@ -0,0 +1,407 @@
+use dozer_types::chrono::{
+    DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc,
+};
+use dozer_types::ingestion_types::SqlServerConfig;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition, TimeUnit,
+};
+use tiberius::{AuthMethod, Client, ColumnData, Config, FromSql};
+use tokio::net::TcpStream;
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+use crate::errors::SqlServerError;
+
+pub type SqlServerClient = Client<Compat<TcpStream>>;
+
+pub async fn connect(config: &SqlServerConfig) -> Result<SqlServerClient, SqlServerError> {
+    let mut tiberius_config = Config::new();
+    tiberius_config.host(&config.host);
+    tiberius_config.port(config.port as u16);
+    tiberius_config.database(&config.database);
+    tiberius_config.authentication(AuthMethod::sql_server(&config.user, &config.password));
+    if config.trust_server_certificate {
+        tiberius_config.trust_cert();
+    }
+    let tcp = TcpStream::connect(tiberius_config.get_addr())
+        .await
+        .map_err(tiberius::error::Error::from)?;
+    tcp.set_nodelay(true)
+        .map_err(tiberius::error::Error::from)?;
+    Ok(Client::connect(tiberius_config, tcp.compat_write()).await?)
+}
+
+/// SQL Server types and the types they are mapped to. `uniqueidentifier` is mapped to its uppercase string form.
+pub const TYPES: &[(&str, FieldType)] = &[
+    ("bit", FieldType::Boolean),
+    ("tinyint", FieldType::UInt),
+    ("smallint", FieldType::Int),
+    ("int", FieldType::Int),
+    ("bigint", FieldType::Int),
+    ("decimal", FieldType::Decimal),
+    ("numeric", FieldType::Decimal),
+    ("money", FieldType::Decimal),
+    ("smallmoney", FieldType::Decimal),
+    ("real", FieldType::Float),
+    ("float", FieldType::Float),
+    ("char", FieldType::String),
+    ("varchar", FieldType::String),
+    ("nchar", FieldType::String),
+    ("nvarchar", FieldType::String),
+    ("uniqueidentifier", FieldType::String),
+    ("text", FieldType::Text),
+    ("ntext", FieldType::Text),
+    ("binary", FieldType::Binary),
+    ("varbinary", FieldType::Binary),
+    ("image", FieldType::Binary),
+    ("date", FieldType::Date),
+    ("datetime", FieldType::Timestamp),
+    ("datetime2", FieldType::Timestamp),
+    ("smalldatetime", FieldType::Timestamp),
+    ("datetimeoffset", FieldType::Timestamp),
+    ("time", FieldType::Duration),
+];
+
+/// A column of a source table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// The type as in `INFORMATION_SCHEMA.COLUMNS.DATA_TYPE`, e.g. `datetime2`.
+    pub data_type: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+}
+
+impl Column {
+    /// Converts a value of the snapshot or a change table.
+    pub fn convert(&self, data: &ColumnData<'static>) -> Result<Field, SqlServerError> {
+        let invalid = || SqlServerError::InvalidValue(self.name.clone(), format!("{data:?}"));
+        if is_null(data) {
+            return Ok(Field::Null);
+        }
+        Ok(match (self.typ, data) {
+            (FieldType::Boolean, ColumnData::Bit(Some(value))) => Field::Boolean(*value),
+            (FieldType::UInt, ColumnData::U8(Some(value))) => Field::UInt(*value as u64),
+            (FieldType::Int, ColumnData::U8(Some(value))) => Field::Int(*value as i64),
+            (FieldType::Int, ColumnData::I16(Some(value))) => Field::Int(*value as i64),
+            (FieldType::Int, ColumnData::I32(Some(value))) => Field::Int(*value as i64),
+            (FieldType::Int, ColumnData::I64(Some(value))) => Field::Int(*value),
+            (FieldType::Float, ColumnData::F32(Some(value))) => {
+                Field::Float(OrderedFloat(*value as f64))
+            }
+            (FieldType::Float, ColumnData::F64(Some(value))) => Field::Float(OrderedFloat(*value)),
+            (FieldType::Decimal, ColumnData::Numeric(Some(_))) => {
+                Field::Decimal(Decimal::from_sql(data).ok().flatten().ok_or_else(invalid)?)
+            }
+            // `money` values come as floats, with 4 decimal places.
+            (FieldType::Decimal, ColumnData::F64(Some(value))) => Field::Decimal(
+                Decimal::from_f64_retain(*value)
+                    .ok_or_else(invalid)?
+                    .round_dp(4),
+            ),
+            (FieldType::String, ColumnData::String(Some(value))) => {
+                Field::String(value.to_string())
+            }
+            (FieldType::String, ColumnData::Guid(Some(value))) => {
+                Field::String(value.to_string().to_uppercase())
+            }
+            (FieldType::Text, ColumnData::String(Some(value))) => Field::Text(value.to_string()),
+            (FieldType::Binary, ColumnData::Binary(Some(value))) => Field::Binary(value.to_vec()),
+            (FieldType::Date, ColumnData::Date(Some(_))) => Field::Date(
+                NaiveDate::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .ok_or_else(invalid)?,
+            ),
+            (
+                FieldType::Timestamp,
+                ColumnData::DateTime(Some(_))
+                | ColumnData::SmallDateTime(Some(_))
+                | ColumnData::DateTime2(Some(_)),
+            ) => Field::Timestamp(DateTime::from_utc(
+                NaiveDateTime::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .ok_or_else(invalid)?,
+                Utc.fix(),
+            )),
+            (FieldType::Timestamp, ColumnData::DateTimeOffset(Some(_))) => Field::Timestamp(
+                DateTime::<FixedOffset>::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .ok_or_else(invalid)?,
+            ),
+            (FieldType::Duration, ColumnData::Time(Some(_))) => {
+                let time = NaiveTime::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .ok_or_else(invalid)?;
+                Field::Duration(DozerDuration(
+                    std::time::Duration::new(
+                        time.num_seconds_from_midnight() as u64,
+                        time.nanosecond(),
+                    ),
+                    TimeUnit::Nanoseconds,
+                ))
+            }
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+fn is_null(data: &ColumnData<'_>) -> bool {
+    matches!(
+        data,
+        ColumnData::U8(None)
+            | ColumnData::I16(None)
+            | ColumnData::I32(None)
+            | ColumnData::I64(None)
+            | ColumnData::F32(None)
+            | ColumnData::F64(None)
+            | ColumnData::Bit(None)
+            | ColumnData::String(None)
+            | ColumnData::Guid(None)
+            | ColumnData::Binary(None)
+            | ColumnData::Numeric(None)
+            | ColumnData::Xml(None)
+            | ColumnData::DateTime(None)
+            | ColumnData::SmallDateTime(None)
+            | ColumnData::Time(None)
+            | ColumnData::Date(None)
+            | ColumnData::DateTime2(None)
+            | ColumnData::DateTimeOffset(None)
+    )
+}
+
+/// The requested columns of a source table, the positions of its primary key among them, and the CDC capture
+/// instance its changes are read from.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub schema: String,
+    pub name: String,
+    pub capture_instance: String,
+    pub columns: Vec<Column>,
+    pub primary_index: Vec<usize>,
+}
+
+impl Table {
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.typ,
+                    column.nullable,
+                    SourceDefinition::Dynamic,
+                ),
+                self.primary_index.contains(&index),
+            );
+        }
+        schema
+    }
+
+    /// The quoted name, to be used in queries.
+    pub fn quoted_name(&self) -> String {
+        format!("{}.{}", quote(&self.schema), quote(&self.name))
+    }
+
+    /// The requested columns, quoted and separated by commas.
+    pub fn quoted_columns(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Maps a type of `INFORMATION_SCHEMA.COLUMNS`, returning `None` if it's not supported.
+pub fn map_type(data_type: &str) -> Option<FieldType> {
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == data_type)
+        .map(|(_, typ)| *typ)
+}
+
+pub fn quote(identifier: &str) -> String {
+    format!("[{}]", identifier.replace(']', "]]"))
+}
+
+pub async fn is_cdc_enabled(client: &mut SqlServerClient) -> Result<bool, SqlServerError> {
+    Ok(client
+        .query(
+            "SELECT is_cdc_enabled FROM sys.databases WHERE name = DB_NAME()",
+            &[],
+        )
+        .await?
+        .into_row()
+        .await?
+        .and_then(|row| row.get::<bool, _>(0))
+        .unwrap_or(false))
+}
+
+pub async fn list_tables(
+    client: &mut SqlServerClient,
+) -> Result<Vec<(String, String)>, SqlServerError> {
+    Ok(client
+        .query(
+            "SELECT TABLE_SCHEMA, TABLE_NAME FROM INFORMATION_SCHEMA.TABLES \
+                WHERE TABLE_TYPE = 'BASE TABLE' AND TABLE_SCHEMA <> 'cdc' ORDER BY TABLE_SCHEMA, TABLE_NAME",
+            &[],
+        )
+        .await?
+        .into_first_result()
+        .await?
+        .iter()
+        .filter_map(|row| {
+            Some((
+                row.get::<&str, _>(0)?.to_string(),
+                row.get::<&str, _>(1)?.to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// Lists the column names of a table, which is empty if the table doesn't exist.
+pub async fn list_columns(
+    client: &mut SqlServerClient,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>, SqlServerError> {
+    Ok(client
+        .query(
+            "SELECT COLUMN_NAME FROM INFORMATION_SCHEMA.COLUMNS \
+                WHERE TABLE_SCHEMA = @P1 AND TABLE_NAME = @P2 ORDER BY ORDINAL_POSITION",
+            &[&schema, &table],
+        )
+        .await?
+        .into_first_result()
+        .await?
+        .iter()
+        .filter_map(|row| row.get::<&str, _>(0).map(str::to_string))
+        .collect())
+}
+
+/// The most recent capture instance of a table, if CDC is enabled on it.
+async fn capture_instance(
+    client: &mut SqlServerClient,
+    schema: &str,
+    table: &str,
+) -> Result<Option<String>, SqlServerError> {
+    Ok(client
+        .query(
+            "SELECT TOP 1 ct.capture_instance FROM cdc.change_tables ct \
+                JOIN sys.tables t ON ct.source_object_id = t.object_id \
+                JOIN sys.schemas s ON t.schema_id = s.schema_id \
+                WHERE s.name = @P1 AND t.name = @P2 ORDER BY ct.create_date DESC",
+            &[&schema, &table],
+        )
+        .await?
+        .into_row()
+        .await?
+        .and_then(|row| row.get::<&str, _>(0).map(str::to_string)))
+}
+
+async fn captured_columns(
+    client: &mut SqlServerClient,
+    capture_instance: &str,
+) -> Result<Vec<String>, SqlServerError> {
+    Ok(client
+        .query(
+            "SELECT cc.column_name FROM cdc.captured_columns cc \
+                JOIN cdc.change_tables ct ON cc.object_id = ct.object_id \
+                WHERE ct.capture_instance = @P1",
+            &[&capture_instance],
+        )
+        .await?
+        .into_first_result()
+        .await?
+        .iter()
+        .filter_map(|row| row.get::<&str, _>(0).map(str::to_string))
+        .collect())
+}
+
+/// Gets the `column_names` of a table, which must all be captured by CDC.
+pub async fn get_table(
+    client: &mut SqlServerClient,
+    schema: &str,
+    table: &str,
+    column_names: &[String],
+) -> Result<Table, SqlServerError> {
+    let qualified_name = format!("{schema}.{table}");
+    let all_columns = client
+        .query(
+            "SELECT COLUMN_NAME, DATA_TYPE, IS_NULLABLE FROM INFORMATION_SCHEMA.COLUMNS \
+                WHERE TABLE_SCHEMA = @P1 AND TABLE_NAME = @P2 ORDER BY ORDINAL_POSITION",
+            &[&schema, &table],
+        )
+        .await?
+        .into_first_result()
+        .await?
+        .iter()
+        .filter_map(|row| {
+            Some((
+                row.get::<&str, _>(0)?.to_string(),
+                row.get::<&str, _>(1)?.to_lowercase(),
+                row.get::<&str, _>(2)? == "YES",
+            ))
+        })
+        .collect::<Vec<_>>();
+    let primary_key = client
+        .query(
+            "SELECT kcu.COLUMN_NAME FROM INFORMATION_SCHEMA.TABLE_CONSTRAINTS tc \
+                JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE kcu ON tc.CONSTRAINT_SCHEMA = kcu.CONSTRAINT_SCHEMA \
+                    AND tc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME \
+                WHERE tc.CONSTRAINT_TYPE = 'PRIMARY KEY' AND tc.TABLE_SCHEMA = @P1 AND tc.TABLE_NAME = @P2",
+            &[&schema, &table],
+        )
+        .await?
+        .into_first_result()
+        .await?
+        .iter()
+        .filter_map(|row| row.get::<&str, _>(0).map(str::to_string))
+        .collect::<Vec<_>>();
+
+    let capture_instance = capture_instance(client, schema, table)
+        .await?
+        .ok_or_else(|| SqlServerError::TableCdcNotEnabled(qualified_name.clone()))?;
+    let captured_columns = captured_columns(client, &capture_instance).await?;
+
+    let mut columns = vec![];
+    let mut primary_index = vec![];
+    for column_name in column_names {
+        let (name, data_type, nullable) = all_columns
+            .iter()
+            .find(|(name, ..)| name == column_name)
+            .ok_or_else(|| {
+                SqlServerError::ColumnNotFound(column_name.clone(), qualified_name.clone())
+            })?;
+        if !captured_columns.contains(name) {
+            return Err(SqlServerError::ColumnNotCaptured(
+                name.clone(),
+                qualified_name,
+            ));
+        }
+        let typ = map_type(data_type)
+            .ok_or_else(|| SqlServerError::UnsupportedType(name.clone(), data_type.clone()))?;
+        if primary_key.contains(name) {
+            primary_index.push(columns.len());
+        }
+        columns.push(Column {
+            name: name.clone(),
+            data_type: data_type.clone(),
+            typ,
+            nullable: *nullable,
+        });
+    }
+
+    Ok(Table {
+        schema: schema.to_string(),
+        name: table.to_string(),
+        capture_instance,
+        columns,
+        primary_index,
+    })
+}
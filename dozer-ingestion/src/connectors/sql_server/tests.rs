@@ -0,0 +1,184 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::{NaiveDate, NaiveTime};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{DozerDuration, Field, FieldType, Operation, Record, TimeUnit};
+use tiberius::numeric::Numeric;
+use tiberius::{ColumnData, IntoSql, Uuid};
+
+use super::cdc::{transactions, Change, Lsn};
+use super::schema::{map_type, quote, Column};
+use crate::errors::SqlServerError;
+
+fn column(data_type: &str) -> Column {
+    Column {
+        name: "c".to_string(),
+        data_type: data_type.to_string(),
+        typ: map_type(data_type).unwrap(),
+        nullable: true,
+    }
+}
+
+#[test]
+fn test_map_type() {
+    assert_eq!(map_type("bit"), Some(FieldType::Boolean));
+    assert_eq!(map_type("tinyint"), Some(FieldType::UInt));
+    assert_eq!(map_type("datetime2"), Some(FieldType::Timestamp));
+    assert_eq!(map_type("uniqueidentifier"), Some(FieldType::String));
+    assert_eq!(map_type("money"), Some(FieldType::Decimal));
+    assert_eq!(map_type("geography"), None);
+}
+
+#[test]
+fn test_convert_value() {
+    assert_eq!(
+        column("int").convert(&ColumnData::I32(Some(-5))).unwrap(),
+        Field::Int(-5)
+    );
+    assert_eq!(
+        column("tinyint")
+            .convert(&ColumnData::U8(Some(255)))
+            .unwrap(),
+        Field::UInt(255)
+    );
+    assert_eq!(
+        column("float")
+            .convert(&ColumnData::F64(Some(1.5)))
+            .unwrap(),
+        Field::Float(OrderedFloat(1.5))
+    );
+    assert_eq!(
+        column("decimal")
+            .convert(&ColumnData::Numeric(Some(Numeric::new_with_scale(
+                -1250, 2
+            ))))
+            .unwrap(),
+        Field::Decimal(Decimal::from_str("-12.50").unwrap())
+    );
+    assert_eq!(
+        column("money")
+            .convert(&ColumnData::F64(Some(19.99)))
+            .unwrap(),
+        Field::Decimal(Decimal::from_str("19.99").unwrap())
+    );
+    assert_eq!(
+        column("uniqueidentifier")
+            .convert(&ColumnData::Guid(Some(
+                Uuid::parse_str("6f9619ff-8b86-d011-b42d-00c04fc964ff").unwrap()
+            )))
+            .unwrap(),
+        Field::String("6F9619FF-8B86-D011-B42D-00C04FC964FF".to_string())
+    );
+    assert_eq!(
+        column("nvarchar")
+            .convert(&ColumnData::String(Some("dozer".into())))
+            .unwrap(),
+        Field::String("dozer".to_string())
+    );
+    assert_eq!(
+        column("int").convert(&ColumnData::I32(None)).unwrap(),
+        Field::Null
+    );
+    assert!(matches!(
+        column("int").convert(&ColumnData::String(Some("1".into()))),
+        Err(SqlServerError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn test_convert_temporal_value() {
+    let date_time = NaiveDate::from_ymd_opt(2023, 7, 22)
+        .unwrap()
+        .and_hms_nano_opt(4, 26, 40, 123_456_700)
+        .unwrap();
+    let Field::Timestamp(timestamp) = column("datetime2").convert(&date_time.into_sql()).unwrap()
+    else {
+        panic!("datetime2 must be converted to a timestamp");
+    };
+    assert_eq!(timestamp.naive_utc(), date_time);
+    assert_eq!(timestamp.offset().local_minus_utc(), 0);
+
+    assert_eq!(
+        column("date")
+            .convert(&date_time.date().into_sql())
+            .unwrap(),
+        Field::Date(date_time.date())
+    );
+    assert_eq!(
+        column("time")
+            .convert(&NaiveTime::from_hms_opt(1, 2, 3).unwrap().into_sql())
+            .unwrap(),
+        Field::Duration(DozerDuration(
+            std::time::Duration::from_secs(3723),
+            TimeUnit::Nanoseconds
+        ))
+    );
+}
+
+#[test]
+fn test_lsn() {
+    let lsn = Lsn([0, 0, 0, 0x2a, 0, 0, 0, 0xf8, 0, 0xff]);
+    assert_eq!(lsn.to_string(), "0x0000002A000000F800FF");
+    assert_eq!(lsn.next(), Lsn([0, 0, 0, 0x2a, 0, 0, 0, 0xf8, 1, 0]));
+    assert!(lsn.next() > lsn);
+    assert!(Lsn::default().is_zero());
+    assert!(Lsn::from_bytes(&[0; 9]).is_err());
+}
+
+#[test]
+fn test_transactions() {
+    let change = |lsn: u8, seqval: u8, operation: i32, value: i64| Change {
+        lsn: Lsn([0, 0, 0, 0, 0, 0, 0, 0, 0, lsn]),
+        seqval: Lsn([0, 0, 0, 0, 0, 0, 0, 0, 0, seqval]),
+        operation,
+        table_index: 0,
+        record: Record::new(vec![Field::Int(value)]),
+    };
+    let transactions = transactions(vec![
+        change(1, 1, 2, 1),
+        change(1, 2, 2, 2),
+        change(2, 3, 3, 1),
+        change(2, 3, 4, 10),
+        change(2, 4, 1, 2),
+    ]);
+    assert_eq!(
+        transactions,
+        vec![
+            vec![
+                (
+                    0,
+                    Operation::Insert {
+                        new: Record::new(vec![Field::Int(1)])
+                    }
+                ),
+                (
+                    0,
+                    Operation::Insert {
+                        new: Record::new(vec![Field::Int(2)])
+                    }
+                ),
+            ],
+            vec![
+                (
+                    0,
+                    Operation::Update {
+                        old: Record::new(vec![Field::Int(1)]),
+                        new: Record::new(vec![Field::Int(10)])
+                    }
+                ),
+                (
+                    0,
+                    Operation::Delete {
+                        old: Record::new(vec![Field::Int(2)])
+                    }
+                ),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_quote() {
+    assert_eq!(quote("order]s"), "[order]]s]");
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use dozer_types::ingestion_types::{IngestionMessage, WebhookConfig};
+use dozer_types::log::{info, warn};
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde_json::json;
+use dozer_types::types::FieldType;
+use hyper::header::AUTHORIZATION;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use tonic::async_trait;
+
+use super::table::WebhookTable;
+use crate::connectors::{
+    CdcType, Connector, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+};
+use crate::errors::{ConnectorError, WebhookError};
+use crate::ingestion::Ingestor;
+
+/// Serves an HTTP endpoint, turning the JSON payloads posted to the path of a table into operations on it.
+#[derive(Debug)]
+pub struct WebhookConnector {
+    name: String,
+    config: WebhookConfig,
+}
+
+/// What the requests are handled with.
+#[derive(Debug)]
+struct State {
+    token: Option<String>,
+    /// The started tables, with their index, by path.
+    tables: HashMap<String, (usize, WebhookTable)>,
+    ingestor: Ingestor,
+    /// The next transaction id. Locked while a request's operations are ingested, so they stay together.
+    txid: Mutex<u64>,
+}
+
+impl WebhookConnector {
+    pub fn new(name: String, config: WebhookConfig) -> Self {
+        Self { name, config }
+    }
+
+    fn get_table(&self, table_info: &TableInfo) -> Result<WebhookTable, ConnectorError> {
+        let config = self
+            .config
+            .tables
+            .iter()
+            .find(|table| table.name == table_info.name)
+            .ok_or_else(|| ConnectorError::TableNotFound(table_info.name.clone()))?;
+        Ok(WebhookTable::new(config, &table_info.column_names)?)
+    }
+
+    async fn serve(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        let mut tables_by_path: HashMap<String, (usize, WebhookTable)> = HashMap::new();
+        for (table_index, table_info) in tables.iter().enumerate() {
+            let table = self.get_table(table_info)?;
+            if let Some((_, other)) = tables_by_path.get(&table.path) {
+                return Err(WebhookError::DuplicatePath(
+                    other.name.clone(),
+                    table.name.clone(),
+                    table.path.clone(),
+                )
+                .into());
+            }
+            tables_by_path.insert(table.path.clone(), (table_index, table));
+        }
+
+        let address = format!("{}:{}", self.config.host, self.config.port);
+        let socket_address: SocketAddr = address
+            .parse()
+            .map_err(|_| WebhookError::InvalidAddress(address.clone()))?;
+        let state = Arc::new(State {
+            token: self.config.token.clone(),
+            tables: tables_by_path,
+            ingestor: ingestor.clone(),
+            txid: Mutex::new(0),
+        });
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(state.clone(), request)
+                }))
+            }
+        });
+
+        info!("[{}] Receiving webhooks on http://{}", self.name, address);
+        Server::try_bind(&socket_address)
+            .map_err(WebhookError::Server)?
+            .serve(make_service)
+            .await
+            .map_err(WebhookError::Server)?;
+        Ok(())
+    }
+}
+
+async fn handle_request(
+    state: Arc<State>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    if let Some(token) = &state.token {
+        let authorization = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        if authorization != Some(format!("Bearer {token}").as_str()) {
+            return Ok(response(StatusCode::UNAUTHORIZED, "Invalid token"));
+        }
+    }
+    let Some((table_index, table)) = state.tables.get(request.uri().path()) else {
+        return Ok(response(StatusCode::NOT_FOUND, "No table at this path"));
+    };
+    if request.method() != Method::POST {
+        return Ok(response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Only POST is allowed",
+        ));
+    }
+
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(e) => return Ok(response(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+    let operations = match table.operations(&body) {
+        Ok(operations) => operations,
+        Err(e) => {
+            warn!("Rejected payload of {}: {}", table.name, e);
+            return Ok(response(StatusCode::BAD_REQUEST, &e.to_string()));
+        }
+    };
+
+    let count = operations.len();
+    let mut txid = state.txid.lock();
+    for (seq_no, op) in operations.into_iter().enumerate() {
+        let message = IngestionMessage::new_op(*txid, seq_no as u64, *table_index, op);
+        if let Err(e) = state.ingestor.handle_message(message) {
+            return Ok(response(StatusCode::SERVICE_UNAVAILABLE, &e.to_string()));
+        }
+    }
+    *txid += 1;
+    Ok(Response::new(Body::from(
+        json!({ "count": count }).to_string(),
+    )))
+}
+
+fn response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(json!({ "error": message }).to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[async_trait]
+impl Connector for WebhookConnector {
+    fn types_mapping() -> Vec<(String, Option<FieldType>)>
+    where
+        Self: Sized,
+    {
+        vec![
+            ("string".to_string(), Some(FieldType::String)),
+            ("number".to_string(), Some(FieldType::Float)),
+            ("boolean".to_string(), Some(FieldType::Boolean)),
+            ("object".to_string(), Some(FieldType::Json)),
+            ("array".to_string(), Some(FieldType::Json)),
+        ]
+    }
+
+    async fn validate_connection(&self) -> Result<(), ConnectorError> {
+        let address = format!("{}:{}", self.config.host, self.config.port);
+        address
+            .parse::<SocketAddr>()
+            .map_err(|_| WebhookError::InvalidAddress(address))?;
+        Ok(())
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableIdentifier>, ConnectorError> {
+        Ok(self
+            .config
+            .tables
+            .iter()
+            .map(|table| TableIdentifier::from_table_name(table.name.clone()))
+            .collect())
+    }
+
+    async fn validate_tables(&self, tables: &[TableIdentifier]) -> Result<(), ConnectorError> {
+        for table in tables {
+            if table.schema.is_some() || !self.config.tables.iter().any(|t| t.name == table.name) {
+                return Err(ConnectorError::TableNotFound(table.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_columns(
+        &self,
+        tables: Vec<TableIdentifier>,
+    ) -> Result<Vec<TableInfo>, ConnectorError> {
+        tables
+            .into_iter()
+            .map(|table| {
+                let config = self
+                    .config
+                    .tables
+                    .iter()
+                    .find(|t| t.name == table.name)
+                    .ok_or_else(|| ConnectorError::TableNotFound(table.name.clone()))?;
+                Ok(TableInfo {
+                    schema: table.schema,
+                    name: table.name,
+                    column_names: config
+                        .columns
+                        .iter()
+                        .map(|column| column.name.clone())
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_schemas(
+        &self,
+        table_infos: &[TableInfo],
+    ) -> Result<Vec<SourceSchemaResult>, ConnectorError> {
+        Ok(table_infos
+            .iter()
+            .map(|table_info| {
+                let table = self.get_table(table_info)?;
+                // Updates and deletes only carry the primary key of the old row.
+                let cdc_type = if table.has_changes() {
+                    CdcType::OnlyPK
+                } else {
+                    CdcType::Nothing
+                };
+                Ok(SourceSchema::new(table.schema(), cdc_type))
+            })
+            .collect())
+    }
+
+    async fn start(
+        &self,
+        ingestor: &Ingestor,
+        tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        self.serve(ingestor, tables).await
+    }
+}
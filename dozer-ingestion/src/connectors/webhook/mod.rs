@@ -0,0 +1,8 @@
+//! An HTTP endpoint receiving JSON payloads, e.g. of SaaS webhooks. Each table is posted to at its own path, and each
+//! object posted is an operation on it. A request is a transaction.
+
+mod connector;
+mod table;
+
+pub use connector::WebhookConnector;
+pub use table::WebhookTable;
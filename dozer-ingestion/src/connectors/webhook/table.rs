@@ -0,0 +1,295 @@
+use dozer_types::ingestion_types;
+use dozer_types::serde_json::{self, Value};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+use crate::connectors::rest::{Column, ColumnType};
+use crate::errors::WebhookError;
+
+/// A table whose operations are posted to `path`, with the requested columns.
+#[derive(Debug, Clone)]
+pub struct WebhookTable {
+    pub name: String,
+    pub path: String,
+    columns: Vec<Column>,
+    /// Whether each column is in the primary key.
+    primary_key: Vec<bool>,
+    operation_field: Option<String>,
+}
+
+impl WebhookTable {
+    pub fn new(
+        config: &ingestion_types::WebhookTable,
+        column_names: &[String],
+    ) -> Result<Self, WebhookError> {
+        let not_found =
+            |name: &str| WebhookError::ColumnNotFound(name.to_string(), config.name.clone());
+        let columns = column_names
+            .iter()
+            .map(|name| {
+                let column = config
+                    .columns
+                    .iter()
+                    .find(|column| &column.name == name)
+                    .ok_or_else(|| not_found(name))?;
+                let typ = FieldType::try_from(column.typ.as_str()).map_err(|e| {
+                    WebhookError::InvalidColumnType(column.name.clone(), column.typ.clone(), e)
+                })?;
+                let typ = match typ {
+                    FieldType::Enum if column.values.is_empty() => {
+                        return Err(WebhookError::InvalidColumnType(
+                            column.name.clone(),
+                            column.typ.clone(),
+                            "enum without values".to_string(),
+                        ))
+                    }
+                    FieldType::Enum => ColumnType::Enum(column.values.clone()),
+                    typ => ColumnType::Field(typ),
+                };
+                Ok(Column {
+                    name: column.name.clone(),
+                    path: column.field.clone().unwrap_or_else(|| column.name.clone()),
+                    typ,
+                    nullable: column.nullable,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Updates and deletes are applied by their primary key, so it must be requested.
+        if let Some(name) = config
+            .primary_key
+            .iter()
+            .find(|name| !column_names.contains(name))
+        {
+            return Err(not_found(name));
+        }
+        if config.operation_field.is_some() && config.primary_key.is_empty() {
+            return Err(WebhookError::MissingPrimaryKey(config.name.clone()));
+        }
+        Ok(Self {
+            name: config.name.clone(),
+            path: config
+                .path
+                .clone()
+                .unwrap_or_else(|| format!("/{}", config.name)),
+            primary_key: columns
+                .iter()
+                .map(|column| config.primary_key.contains(&column.name))
+                .collect(),
+            columns,
+            operation_field: config.operation_field.clone(),
+        })
+    }
+
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for (column, primary_key) in self.columns.iter().zip(&self.primary_key) {
+            schema.field(
+                FieldDefinition::new(
+                    column.name.clone(),
+                    column.field_type(),
+                    column.nullable,
+                    SourceDefinition::Dynamic,
+                )
+                .with_enum_values(column.enum_values()),
+                *primary_key,
+            );
+        }
+        schema
+    }
+
+    /// Whether payloads can update and delete rows.
+    pub fn has_changes(&self) -> bool {
+        self.operation_field.is_some()
+    }
+
+    /// The operations of a payload, which is an object or an array of objects.
+    pub fn operations(&self, payload: &[u8]) -> Result<Vec<Operation>, WebhookError> {
+        match serde_json::from_slice(payload).map_err(WebhookError::InvalidPayload)? {
+            Value::Array(objects) => objects
+                .iter()
+                .map(|object| self.operation(object))
+                .collect(),
+            object => Ok(vec![self.operation(&object)?]),
+        }
+    }
+
+    fn operation(&self, object: &Value) -> Result<Operation, WebhookError> {
+        if !object.is_object() {
+            return Err(WebhookError::NotAnObject);
+        }
+        let new = self.record(object)?;
+        let Some(operation_field) = &self.operation_field else {
+            return Ok(Operation::Insert { new });
+        };
+        let operation = operation_field
+            .split('.')
+            .try_fold(object, |value, field| value.get(field))
+            .unwrap_or(&Value::Null);
+        match operation.as_str() {
+            Some("insert" | "create") => Ok(Operation::Insert { new }),
+            Some("update") => Ok(Operation::Update {
+                old: self.primary_key_record(&new),
+                new,
+            }),
+            Some("delete") => Ok(Operation::Delete {
+                old: self.primary_key_record(&new),
+            }),
+            _ => Err(WebhookError::UnknownOperation(operation.to_string())),
+        }
+    }
+
+    pub fn record(&self, object: &Value) -> Result<Record, WebhookError> {
+        let values = self
+            .columns
+            .iter()
+            .map(|column| column.value(object))
+            .collect::<Result<_, _>>()?;
+        Ok(Record::new(values))
+    }
+
+    /// The record of a row with only its primary key, read from an object of the primary key's fields.
+    pub fn key_record(&self, keys: &Value) -> Result<Record, WebhookError> {
+        let values = self
+            .columns
+            .iter()
+            .zip(&self.primary_key)
+            .map(|(column, primary_key)| {
+                if *primary_key {
+                    column.value(keys)
+                } else {
+                    Ok(Field::Null)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Record::new(values))
+    }
+
+    /// The old row of an update or delete only has its primary key.
+    fn primary_key_record(&self, record: &Record) -> Record {
+        let values = record
+            .values
+            .iter()
+            .zip(&self.primary_key)
+            .map(|(value, primary_key)| {
+                if *primary_key {
+                    value.clone()
+                } else {
+                    Field::Null
+                }
+            })
+            .collect();
+        Record::new(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::ingestion_types::WebhookColumn;
+
+    use super::*;
+
+    fn config() -> ingestion_types::WebhookTable {
+        let column = |name: &str, typ: &str, field: Option<&str>| WebhookColumn {
+            name: name.to_string(),
+            typ: typ.to_string(),
+            field: field.map(str::to_string),
+            nullable: true,
+            values: vec![],
+        };
+        ingestion_types::WebhookTable {
+            name: "charges".to_string(),
+            path: None,
+            columns: vec![
+                column("id", "string", None),
+                column("amount", "int", Some("data.amount")),
+            ],
+            primary_key: vec!["id".to_string()],
+            operation_field: Some("type".to_string()),
+        }
+    }
+
+    fn column_names() -> Vec<String> {
+        vec!["id".to_string(), "amount".to_string()]
+    }
+
+    #[test]
+    fn test_operations() {
+        let table = WebhookTable::new(&config(), &column_names()).unwrap();
+        assert_eq!(table.path, "/charges");
+        assert_eq!(table.schema().primary_index, vec![0]);
+
+        let payload = br#"[
+            {"type": "create", "id": "ch_1", "data": {"amount": 100}},
+            {"type": "update", "id": "ch_1", "data": {"amount": 50}},
+            {"type": "delete", "id": "ch_1"}
+        ]"#;
+        let id = Field::String("ch_1".to_string());
+        assert_eq!(
+            table.operations(payload).unwrap(),
+            vec![
+                Operation::Insert {
+                    new: Record::new(vec![id.clone(), Field::Int(100)]),
+                },
+                Operation::Update {
+                    old: Record::new(vec![id.clone(), Field::Null]),
+                    new: Record::new(vec![id.clone(), Field::Int(50)]),
+                },
+                Operation::Delete {
+                    old: Record::new(vec![id, Field::Null]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_payloads() {
+        let table = WebhookTable::new(&config(), &column_names()).unwrap();
+        assert!(matches!(
+            table.operations(b"{"),
+            Err(WebhookError::InvalidPayload(_))
+        ));
+        assert!(matches!(
+            table.operations(b"[1]"),
+            Err(WebhookError::NotAnObject)
+        ));
+        assert!(matches!(
+            table.operations(br#"{"type": "upsert", "id": "ch_1"}"#),
+            Err(WebhookError::UnknownOperation(_))
+        ));
+        assert!(matches!(
+            table.operations(br#"{"type": "create", "id": "ch_1", "data": {"amount": "a"}}"#),
+            Err(WebhookError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_enum_column() {
+        let mut config = config();
+        config.columns[1].typ = "enum".to_string();
+        assert!(matches!(
+            WebhookTable::new(&config, &column_names()),
+            Err(WebhookError::InvalidColumnType(..))
+        ));
+
+        config.columns[1].values = vec!["small".to_string(), "large".to_string()];
+        let table = WebhookTable::new(&config, &column_names()).unwrap();
+        assert_eq!(table.schema().fields[1].enum_value(1), Some("large"));
+        assert_eq!(
+            table
+                .operations(br#"{"type": "create", "id": "ch_1", "data": {"amount": "large"}}"#)
+                .unwrap(),
+            vec![Operation::Insert {
+                new: Record::new(vec![Field::String("ch_1".to_string()), Field::Enum(1)]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_primary_key_must_be_requested() {
+        assert!(matches!(
+            WebhookTable::new(&config(), &["amount".to_string()]),
+            Err(WebhookError::ColumnNotFound(..))
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::enum_variant_names)]
 
+use dozer_storage::errors::StorageError;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
 use dozer_types::ingestion_types::IngestorError;
@@ -75,9 +76,82 @@ pub enum ConnectorError {
     #[error(transparent)]
     KafkaError(#[from] KafkaError),
 
+    #[cfg(feature = "kinesis")]
+    #[error(transparent)]
+    KinesisError(#[from] KinesisError),
+
+    #[cfg(feature = "iceberg")]
+    #[error(transparent)]
+    IcebergError(#[from] IcebergError),
+
+    #[cfg(feature = "redis")]
+    #[error(transparent)]
+    RedisStreamsError(#[from] RedisStreamsError),
+
+    #[cfg(feature = "nats")]
+    #[error(transparent)]
+    NatsError(#[from] NatsError),
+
+    #[cfg(feature = "mqtt")]
+    #[error(transparent)]
+    MqttError(#[from] MqttError),
+
+    #[cfg(feature = "dynamodb")]
+    #[error(transparent)]
+    DynamoDbError(#[from] DynamoDbError),
+
+    #[cfg(feature = "cassandra")]
+    #[error(transparent)]
+    CassandraError(#[from] CassandraError),
+
+    #[cfg(feature = "oracle")]
+    #[error(transparent)]
+    OracleError(#[from] OracleError),
+
+    #[cfg(feature = "firestore")]
+    #[error(transparent)]
+    FirestoreError(#[from] FirestoreError),
+
+    #[cfg(feature = "mysql")]
+    #[error(transparent)]
+    MySQLError(#[from] MySQLError),
+
+    #[cfg(feature = "sql_server")]
+    #[error(transparent)]
+    SqlServerError(#[from] SqlServerError),
+
     #[error(transparent)]
     ObjectStoreConnectorError(#[from] ObjectStoreConnectorError),
 
+    #[error(transparent)]
+    RestError(#[from] RestError),
+
+    #[error(transparent)]
+    ElasticsearchError(#[from] ElasticsearchError),
+
+    #[cfg(feature = "salesforce")]
+    #[error(transparent)]
+    SalesforceError(#[from] SalesforceError),
+
+    #[cfg(feature = "google_sheets")]
+    #[error(transparent)]
+    GoogleSheetsError(#[from] GoogleSheetsError),
+
+    #[error(transparent)]
+    DatabricksError(#[from] DatabricksError),
+
+    #[error(transparent)]
+    WebhookError(#[from] WebhookError),
+
+    #[error(transparent)]
+    SchemaInferenceError(#[from] SchemaInferenceError),
+
+    #[error(transparent)]
+    GeneratorError(#[from] GeneratorError),
+
+    #[error(transparent)]
+    RefreshError(#[from] RefreshError),
+
     #[error(transparent)]
     TypeError(#[from] TypeError),
 
@@ -100,14 +174,56 @@ pub enum ConnectorError {
     #[error("Delta table error: {0}")]
     DeltaTableError(#[from] DeltaTableError),
 
+    #[error(transparent)]
+    DeltaLakeError(#[from] DeltaLakeError),
+
     #[error("Datafusion error: {0}")]
     DataFusionError(#[from] DataFusionError),
 
     #[error("kafka feature is not enabled")]
     KafkaFeatureNotEnabled,
 
+    #[error("kinesis feature is not enabled")]
+    KinesisFeatureNotEnabled,
+
+    #[error("iceberg feature is not enabled")]
+    IcebergFeatureNotEnabled,
+
+    #[error("redis feature is not enabled")]
+    RedisFeatureNotEnabled,
+
+    #[error("nats feature is not enabled")]
+    NatsFeatureNotEnabled,
+
+    #[error("mqtt feature is not enabled")]
+    MqttFeatureNotEnabled,
+
+    #[error("dynamodb feature is not enabled")]
+    DynamoDbFeatureNotEnabled,
+
+    #[error("cassandra feature is not enabled")]
+    CassandraFeatureNotEnabled,
+
+    #[error("salesforce feature is not enabled")]
+    SalesforceFeatureNotEnabled,
+
+    #[error("google_sheets feature is not enabled")]
+    GoogleSheetsFeatureNotEnabled,
+
     #[error("ethereum feature is not enabled")]
     EthereumFeatureNotEnabled,
+
+    #[error("oracle feature is not enabled")]
+    OracleFeatureNotEnabled,
+
+    #[error("firestore feature is not enabled")]
+    FirestoreFeatureNotEnabled,
+
+    #[error("mysql feature is not enabled")]
+    MySQLFeatureNotEnabled,
+
+    #[error("sql_server feature is not enabled")]
+    SqlServerFeatureNotEnabled,
 }
 impl ConnectorError {
     pub fn map_serialization_error(e: serde_json::Error) -> ConnectorError {
@@ -164,6 +280,15 @@ pub enum PostgresConnectorError {
     #[error("Failed to drop publication: {0}")]
     DropPublicationError(#[source] Error),
 
+    #[error("Publication {0} not found")]
+    PublicationNotFound(String),
+
+    #[error("Publication {0} doesn't publish table {1}.{2}")]
+    TableNotInPublication(String, String, String),
+
+    #[error("Failed to create change tracking triggers: {0}")]
+    CreateTriggersError(#[source] Error),
+
     #[error("Failed to begin txn for replication")]
     BeginReplication,
 
@@ -200,6 +325,9 @@ pub enum PostgresConnectorError {
     #[error("Replication stream error")]
     ReplicationStreamEndError,
 
+    #[error("Schema of table {0} changed: {1}")]
+    SchemaChanged(String, String),
+
     #[error(transparent)]
     PostgresSchemaError(#[from] PostgresSchemaError),
 
@@ -232,14 +360,6 @@ pub enum PostgresConnectorError {
         table_index: usize,
         column_index: usize,
     },
-
-    #[error("Column type changed in table {table_index} column {column_name} from {old_type} to {new_type}")]
-    ColumnTypeChanged {
-        table_index: usize,
-        column_name: String,
-        old_type: postgres_types::Type,
-        new_type: postgres_types::Type,
-    },
 }
 
 #[derive(Error, Debug)]
@@ -271,6 +391,9 @@ pub enum PostgresSchemaError {
     #[error("JSONB parse failed: {0}")]
     JSONBParseError(String),
 
+    #[error("'{1}' is not a value of enum {0}")]
+    UnknownEnumLabel(String, String),
+
     #[error("Point parse failed")]
     PointParseError,
 
@@ -372,6 +495,12 @@ pub enum KafkaError {
     #[error("Schema registry fetch failed. Error: {0}")]
     SchemaRegistryFetchError(#[source] SRCError),
 
+    #[error("Avro error: {0}")]
+    AvroError(#[source] apache_avro::Error),
+
+    #[error("Avro decode error. Error: {0}")]
+    AvroDecodeError(#[source] SRCError),
+
     #[error("Topic not defined")]
     TopicNotDefined,
 }
@@ -425,6 +554,569 @@ pub enum KafkaSchemaError {
     InvalidTimestampError,
 }
 
+#[cfg(feature = "oracle")]
+#[derive(Error, Debug)]
+pub enum OracleError {
+    #[error("Oracle error: {0}")]
+    Oracle(#[from] oracle::Error),
+
+    #[error("LogMiner needs the database in archive log mode, but it's in {0} mode")]
+    NotInArchiveLogMode(String),
+
+    #[error("LogMiner needs supplemental logging of all columns, enable it with `ALTER DATABASE ADD SUPPLEMENTAL LOG DATA (ALL) COLUMNS`")]
+    SupplementalLoggingDisabled,
+
+    #[error("Unsupported type {1} of column {0}")]
+    UnsupportedType(String, String),
+
+    #[error("Cannot find column {0} in {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Cannot parse redo statement: {0}")]
+    InvalidRedo(String),
+
+    #[error("Column {0} is missing from redo statement: {1}")]
+    MissingRedoColumn(String, String),
+
+    #[error("Cannot parse {1} as a value of column {0}")]
+    InvalidValue(String, String),
+}
+
+#[cfg(feature = "mysql")]
+#[derive(Error, Debug)]
+pub enum MySQLError {
+    #[error("MySQL error: {0}")]
+    MySQL(#[from] mysql_async::Error),
+
+    #[error("Binlog is disabled, enable it with `log_bin`")]
+    BinlogDisabled,
+
+    #[error("Binlog format must be ROW, but it's {0}")]
+    BinlogFormatNotRow(String),
+
+    #[error("Binlog row image must be FULL, but it's {0}")]
+    BinlogRowImageNotFull(String),
+
+    #[error("Unsupported type {1} of column {0}")]
+    UnsupportedType(String, String),
+
+    #[error("Cannot find column {0} in {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Cannot convert {1} to a value of column {0}")]
+    InvalidValue(String, String),
+
+    #[error("Partial JSON update of column {0} is not supported, set `binlog_row_value_options` to empty")]
+    PartialJsonUpdate(String),
+
+    #[error("Invalid GTID set {0}")]
+    InvalidGtidSet(String),
+
+    #[error("Cannot read binlog event: {0}")]
+    InvalidBinlogEvent(#[source] std::io::Error),
+
+    #[error("Binlog rows of table id {0} come without its table map")]
+    MissingTableMap(u64),
+
+    #[error("Binlog row of table id {0} has neither before nor after image")]
+    EmptyRow(u64),
+
+    #[error("Column {0} of table {1} is missing from binlog row, set `binlog_row_image` to FULL")]
+    MissingBinlogColumn(String, String),
+
+    #[error("Columns of table {0} changed while it's replicated")]
+    TableChanged(String),
+}
+
+#[cfg(feature = "sql_server")]
+#[derive(Error, Debug)]
+pub enum SqlServerError {
+    #[error("SQL Server error: {0}")]
+    SqlServer(#[from] tiberius::error::Error),
+
+    #[error("CDC is not enabled on database {0}, enable it with `sys.sp_cdc_enable_db`")]
+    CdcNotEnabled(String),
+
+    #[error("CDC is not enabled on table {0}, enable it with `sys.sp_cdc_enable_table`")]
+    TableCdcNotEnabled(String),
+
+    #[error("Column {0} of table {1} is not captured by CDC")]
+    ColumnNotCaptured(String, String),
+
+    #[error("Unsupported type {1} of column {0}")]
+    UnsupportedType(String, String),
+
+    #[error("Cannot find column {0} in {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Cannot convert {1} to a value of column {0}")]
+    InvalidValue(String, String),
+
+    #[error("Invalid LSN {0:?}")]
+    InvalidLsn(Vec<u8>),
+
+    #[error("Changes of table {0} were cleaned up before they were read, increase the retention of its capture job")]
+    ChangesCleanedUp(String),
+}
+
+#[derive(Error, Debug)]
+pub enum DeltaLakeError {
+    #[error("Invalid path {0} of a data file: {1}")]
+    InvalidPath(String, #[source] object_store::path::Error),
+
+    #[error("Failed to read data file {0}: {1}")]
+    ReadFile(String, #[source] object_store::Error),
+
+    #[error("Failed to decode data file {0}: {1}")]
+    DecodeFile(String, #[source] deltalake::parquet::errors::ParquetError),
+
+    #[error("Failed to map data file {0}: {1}")]
+    MapFile(String, #[source] FromArrowError),
+
+    #[error("Column {0} is neither in the data files nor a partition column")]
+    ColumnNotFound(String),
+
+    #[error("Invalid value {1:?} of partition column {0}")]
+    InvalidPartitionValue(String, String),
+}
+
+#[cfg(feature = "kinesis")]
+#[derive(Error, Debug)]
+pub enum KinesisError {
+    #[error("Kinesis request failed: {0}")]
+    Request(#[source] BoxedError),
+
+    #[error("Column {0} not found, streams have columns partition_key, sequence_number, shard_id, data and arrival_timestamp")]
+    ColumnNotFound(String),
+
+    #[error("Data of record {1} in shard {0} is not utf-8")]
+    InvalidData(String, String),
+
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointFileError),
+}
+
+#[cfg(any(feature = "kinesis", feature = "dynamodb"))]
+#[derive(Error, Debug)]
+pub enum CheckpointFileError {
+    #[error("Failed to read or write checkpoint {0:?}: {1}")]
+    Io(std::path::PathBuf, #[source] std::io::Error),
+
+    #[error("Invalid checkpoint {0:?}: {1}")]
+    Invalid(std::path::PathBuf, #[source] serde_json::Error),
+}
+
+#[cfg(feature = "redis")]
+#[derive(Error, Debug)]
+pub enum RedisStreamsError {
+    #[error("Redis command failed: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Column {0} not found, streams have columns id, timestamp and fields")]
+    ColumnNotFound(String),
+
+    #[error("Invalid entry id {0}")]
+    InvalidId(String),
+
+    #[error("Value of field {1} of entry {0} is not a string")]
+    InvalidValue(String, String),
+}
+
+#[cfg(feature = "nats")]
+#[derive(Error, Debug)]
+pub enum NatsError {
+    #[error("NATS request failed: {0}")]
+    Request(#[source] BoxedError),
+
+    #[error("Unknown deliver policy {0}, expected all, new, last or last_per_subject")]
+    UnknownDeliverPolicy(String),
+
+    #[error(transparent)]
+    Mapping(#[from] WebhookError),
+
+    #[error("Invalid message {1} of subject {0}: {2}")]
+    InvalidMessage(String, u64, #[source] WebhookError),
+}
+
+#[cfg(feature = "mqtt")]
+#[derive(Error, Debug)]
+pub enum MqttError {
+    #[error("MQTT connection failed: {0}")]
+    Connection(#[source] BoxedError),
+
+    #[error("Timed out connecting to the MQTT broker")]
+    ConnectionTimeout,
+
+    #[error("MQTT broker refused the connection: {0}")]
+    ConnectionRefused(String),
+
+    #[error("Invalid port {0}")]
+    InvalidPort(u32),
+
+    #[error("Unsupported MQTT protocol version {0}, expected 3.1.1 or 5")]
+    UnsupportedProtocolVersion(String),
+
+    #[error("Unsupported QoS {0}, expected 0 or 1")]
+    UnsupportedQos(u32),
+
+    #[error("Invalid topic filter {0}")]
+    InvalidTopicFilter(String),
+
+    #[error(transparent)]
+    Mapping(#[from] WebhookError),
+
+    #[error("Invalid message of topic {0}: {1}")]
+    InvalidMessage(String, #[source] WebhookError),
+}
+
+#[cfg(feature = "dynamodb")]
+#[derive(Error, Debug)]
+pub enum DynamoDbError {
+    #[error("DynamoDB request failed: {0}")]
+    Request(#[source] BoxedError),
+
+    #[error("Table {0} has no stream, enable DynamoDB Streams on it")]
+    StreamNotEnabled(String),
+
+    #[error("Stream of table {0} doesn't have new images, its view type must be NEW_IMAGE or NEW_AND_OLD_IMAGES")]
+    MissingNewImages(String),
+
+    #[error("Invalid number of scan segments {0}, expected 1 to 1000000")]
+    InvalidScanSegments(u32),
+
+    #[error(transparent)]
+    Mapping(#[from] WebhookError),
+
+    #[error("Invalid item of table {0}: {1}")]
+    InvalidItem(String, #[source] WebhookError),
+
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointFileError),
+}
+
+#[cfg(feature = "cassandra")]
+#[derive(Error, Debug)]
+pub enum CassandraError {
+    #[error("Failed to connect to the cluster: {0}")]
+    Connect(#[from] scylla::transport::errors::NewSessionError),
+
+    #[error("Query failed: {0}")]
+    Query(#[from] scylla::transport::errors::QueryError),
+
+    #[error("Unexpected query result: {0}")]
+    UnexpectedResult(String),
+
+    #[error("Table {0} not found")]
+    TableNotFound(String),
+
+    #[error("Column {0} not found in table {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Primary key column {0} of table {1} must be one of its columns")]
+    MissingKeyColumn(String, String),
+
+    #[error("Invalid value of column {0}: {1}")]
+    InvalidValue(String, String),
+
+    #[error("snapshot_token_ranges and snapshot_parallelism must be at least 1")]
+    InvalidSnapshotParallelism,
+
+    #[error("The cluster has no CDC log tables, which only ScyllaDB has, set cdc to false to only snapshot the tables")]
+    CdcNotSupported,
+
+    #[error("CDC of table {0} isn't enabled with pre-images and post-images")]
+    CdcNotEnabled(String),
+
+    #[error("Change of table {0} has no post-image")]
+    MissingPostImage(String),
+
+    #[error("Unsupported delete of table {0}, deletes of ranges of rows and of partitions with clustering columns can't be mapped to the deleted rows")]
+    UnsupportedDelete(String),
+}
+
+#[cfg(feature = "iceberg")]
+#[derive(Error, Debug)]
+pub enum IcebergError {
+    #[error("Invalid catalog uri {0}")]
+    InvalidCatalogUri(String),
+
+    #[error("Iceberg catalog request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Table {0} not found in the catalog")]
+    TableNotFound(String),
+
+    #[error("Invalid location {0} of a table file")]
+    InvalidLocation(String),
+
+    #[error("Failed to read table file {0}: {1}")]
+    ReadFile(String, #[source] object_store::Error),
+
+    #[error("Failed to decode manifest {0}: {1}")]
+    DecodeManifest(String, #[source] apache_avro::Error),
+
+    #[error("Failed to decode data file {0}: {1}")]
+    DecodeDataFile(String, #[source] deltalake::parquet::errors::ParquetError),
+
+    #[error("Failed to map data file {0}: {1}")]
+    MapDataFile(String, #[source] FromArrowError),
+
+    #[error("Data file {0} has unsupported format {1}, only parquet is supported")]
+    UnsupportedFileFormat(String, String),
+
+    #[error("Delete file {0} is not supported, only tables with copy-on-write deletes are")]
+    DeleteFileNotSupported(String),
+
+    #[error("Column {0} has unsupported type {1}")]
+    UnsupportedType(String, String),
+
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
+
+    #[error("Schema {0:?} of the table metadata not found")]
+    SchemaNotFound(Option<i32>),
+
+    #[error("Snapshot {0} has no manifest list")]
+    ManifestListNotFound(i64),
+
+    #[error("Snapshot {0} is no longer an ancestor of the current snapshot, e.g. the table was rolled back")]
+    SnapshotNotAncestor(i64),
+}
+
+#[cfg(feature = "firestore")]
+#[derive(Error, Debug)]
+pub enum FirestoreError {
+    #[error("Firestore error: {0}")]
+    Firestore(#[from] firestore::errors::FirestoreError),
+
+    #[error("Unknown nested fields mapping {0}, expected `flatten` or `json`")]
+    UnknownNestedFields(String),
+
+    #[error("Collection {0} has no documents to infer its columns from")]
+    EmptyCollection(String),
+
+    #[error("Cannot find column {0} in collection {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Listening to changes failed: {0}")]
+    ListenError(String),
+
+    #[error("Listener stopped")]
+    ListenerStopped,
+}
+
+#[derive(Error, Debug)]
+pub enum SchemaInferenceError {
+    #[error("No records to infer the schema from")]
+    NoRecords,
+
+    #[error("Record is not a json object: {0}")]
+    NotAnObject(String),
+
+    #[error("Value {1} of field {0} doesn't match its inferred type: {2}")]
+    TypeMismatch(String, String, #[source] TypeError),
+}
+
+#[derive(Error, Debug)]
+pub enum RestError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Request to {0} failed with status {1}: {2}")]
+    Status(String, u16, String),
+
+    #[error("Response of {0} has no items at {1}")]
+    MissingItems(String, String),
+
+    #[error("Cannot find column {0} of {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Cannot map {1} to column {0}: {2}")]
+    InvalidValue(String, String, #[source] TypeError),
+}
+
+#[derive(Error, Debug)]
+pub enum ElasticsearchError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Request to {0} failed with status {1}: {2}")]
+    Status(String, u16, String),
+
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("Cannot find column {0} in index {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Batch size must be positive")]
+    InvalidBatchSize,
+}
+
+#[cfg(feature = "salesforce")]
+#[derive(Error, Debug)]
+pub enum SalesforceError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Request to {0} failed with status {1}: {2}")]
+    Status(String, u16, String),
+
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("Cannot find column {0} of {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Column {0} of {1} has unsupported type {2}")]
+    UnsupportedType(String, String, String),
+
+    #[error("Columns of {0} must include Id")]
+    MissingId(String),
+
+    #[error("Invalid value {1} of column {0}")]
+    InvalidValue(String, String),
+
+    #[error("Invalid results of {0}: {1}")]
+    InvalidCsv(String, #[source] csv::Error),
+
+    #[error("Query job {0} failed: {1}")]
+    JobFailed(String, String),
+
+    #[error("Streaming API handshake failed: {0}")]
+    HandshakeFailed(String),
+
+    #[error("Cannot subscribe to {0}: {1}")]
+    SubscribeFailed(String, String),
+
+    #[error("Streaming API connection failed: {0}")]
+    ConnectFailed(String),
+}
+
+#[cfg(feature = "google_sheets")]
+#[derive(Error, Debug)]
+pub enum GoogleSheetsError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Request to {0} failed with status {1}: {2}")]
+    Status(String, u16, String),
+
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("No credentials: set api_key, credentials_path or GOOGLE_APPLICATION_CREDENTIALS")]
+    MissingCredentials,
+
+    #[error("Cannot read key file {0}: {1}")]
+    ReadCredentials(String, #[source] std::io::Error),
+
+    #[error("Invalid key file {0}: {1}")]
+    InvalidCredentials(String, #[source] serde_json::Error),
+
+    #[error("Cannot sign token request: {0}")]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
+    #[error("Sheet {0} has no header row")]
+    MissingHeader(String),
+
+    #[error("Column {0} is in the header of sheet {1} more than once")]
+    DuplicateColumn(String, String),
+
+    #[error("Cannot find column {0} in sheet {1}")]
+    ColumnNotFound(String, String),
+}
+
+#[derive(Error, Debug)]
+pub enum DatabricksError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Request to {0} failed with status {1}: {2}")]
+    Status(String, u16, String),
+
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("Statement {0} failed: {1}")]
+    StatementFailed(String, String),
+
+    #[error("Cannot find column {0} of {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Column {0} of {1} has unsupported type {2}")]
+    UnsupportedType(String, String, String),
+
+    #[error("Change data feed is not enabled for {0}")]
+    ChangeDataFeedDisabled(String),
+}
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("Invalid address {0}")]
+    InvalidAddress(String),
+
+    #[error("Server failed: {0}")]
+    Server(#[from] hyper::Error),
+
+    #[error("Invalid type {1} of column {0}: {2}")]
+    InvalidColumnType(String, String, String),
+
+    #[error("Cannot find column {0} of {1}")]
+    ColumnNotFound(String, String),
+
+    #[error("Tables {0} and {1} are both posted to {2}")]
+    DuplicatePath(String, String, String),
+
+    #[error("Table {0} has an operation field but no primary key")]
+    MissingPrimaryKey(String),
+
+    #[error("Payload is not json: {0}")]
+    InvalidPayload(#[source] serde_json::Error),
+
+    #[error("Payload is not an object or an array of objects")]
+    NotAnObject,
+
+    #[error(transparent)]
+    InvalidValue(#[from] RestError),
+
+    #[error("Unknown operation {0}")]
+    UnknownOperation(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GeneratorError {
+    #[error("Unsupported type {1} of column {0}")]
+    UnsupportedType(String, String),
+
+    #[error("Unknown distribution {1} of column {0}")]
+    UnknownDistribution(String, String),
+
+    #[error("Column {0} has min {1} greater than max {2}")]
+    InvalidRange(String, i64, i64),
+
+    #[error("Column {0} of type uint has negative min {1}")]
+    NegativeUInt(String, i64),
+
+    #[error("Column {0} of table {1} is reserved for the primary key")]
+    ReservedColumn(String, String),
+
+    #[error("Update and delete percentages of table {0} add up to more than 100")]
+    InvalidPercentages(String),
+}
+
+#[derive(Error, Debug)]
+pub enum RefreshError {
+    #[error("Invalid cron expression {0}: {1}")]
+    InvalidCron(String, #[source] cron::error::Error),
+
+    #[error("Failed to create snapshot directory {0:?}: {1}")]
+    SnapshotDirectory(std::path::PathBuf, #[source] std::io::Error),
+
+    #[error("Snapshot storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
 #[derive(Error, Debug)]
 pub enum ObjectStoreConnectorError {
     #[error(transparent)]
@@ -486,6 +1178,12 @@ pub enum ObjectStoreObjectError {
 
     #[error("Listing path {0} error: {1}")]
     ListingPathError(String, #[source] DataFusionError),
+
+    #[error("Listing objects in {0} failed: {1}")]
+    ListingObjectsError(String, #[source] object_store::Error),
+
+    #[error("Column {0} has an invalid type: {1}")]
+    InvalidColumnType(String, String),
 }
 
 #[derive(Error, Debug)]
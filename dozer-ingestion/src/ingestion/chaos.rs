@@ -0,0 +1,179 @@
+//! Fault injection for testing that pipelines and checkpointing survive failures. Only built with the `chaos` feature.
+
+use dozer_types::ingestion_types::{IngestionMessage, IngestorError, IngestorForwarder};
+use dozer_types::log::warn;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::serde::Deserialize;
+use dozer_types::serde_json;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Environment variable holding the JSON `ChaosOptions`. Faults are only injected if it's set.
+pub const CHAOS_ENV_VAR: &str = "DOZER_CHAOS";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(crate = "dozer_types::serde", default)]
+/// Probabilities of the injected faults, each between 0 and 1.
+pub struct ChaosOptions {
+    /// Probability that forwarding a connector message fails as if the connector was disconnected.
+    pub disconnect_probability: f64,
+    /// Probability that a connector message is delivered twice.
+    pub duplicate_probability: f64,
+    /// Probability that a connector message is held back and delivered after the next one.
+    pub reorder_probability: f64,
+    /// Probability that a sink write fails.
+    pub sink_failure_probability: f64,
+    /// Seed of the random generator, to reproduce the same faults.
+    pub seed: Option<u64>,
+}
+
+impl ChaosOptions {
+    /// Reads the options from `DOZER_CHAOS`, returning `None` if it's not set or invalid.
+    pub fn from_env() -> Option<Self> {
+        let options = std::env::var(CHAOS_ENV_VAR).ok()?;
+        match serde_json::from_str(&options) {
+            Ok(options) => {
+                warn!("Injecting faults: {options:?}");
+                Some(options)
+            }
+            Err(e) => {
+                warn!("Ignoring invalid {CHAOS_ENV_VAR}: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Decides randomly whether to inject a fault.
+#[derive(Debug)]
+pub struct FaultInjector {
+    rng: Mutex<StdRng>,
+}
+
+impl FaultInjector {
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn inject(&self, probability: f64) -> bool {
+        probability > 0.0 && self.rng.lock().gen_bool(probability.min(1.0))
+    }
+}
+
+/// Forwards connector messages with disconnects, duplicates and reordering injected.
+#[derive(Debug)]
+pub struct ChaosForwarder {
+    inner: Box<dyn IngestorForwarder>,
+    options: ChaosOptions,
+    injector: FaultInjector,
+    /// The message held back to be delivered after the next one.
+    held: Mutex<Option<IngestionMessage>>,
+}
+
+impl ChaosForwarder {
+    pub fn new(inner: Box<dyn IngestorForwarder>, options: ChaosOptions) -> Self {
+        Self {
+            inner,
+            injector: FaultInjector::new(options.seed),
+            options,
+            held: Mutex::new(None),
+        }
+    }
+}
+
+impl IngestorForwarder for ChaosForwarder {
+    fn forward(&self, msg: IngestionMessage) -> Result<(), IngestorError> {
+        if self.injector.inject(self.options.disconnect_probability) {
+            return Err(IngestorError::InjectedFault("connector disconnected"));
+        }
+
+        let mut held = self.held.lock();
+        if held.is_none() && self.injector.inject(self.options.reorder_probability) {
+            *held = Some(msg);
+            return Ok(());
+        }
+
+        if self.injector.inject(self.options.duplicate_probability) {
+            self.inner.forward(msg.clone())?;
+        }
+        self.inner.forward(msg)?;
+        if let Some(msg) = held.take() {
+            self.inner.forward(msg)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ChaosForwarder {
+    fn drop(&mut self) {
+        // Deliver the held back message, as it would be if another message followed.
+        if let Some(msg) = self.held.get_mut().take() {
+            let _ = self.inner.forward(msg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossbeam::channel::unbounded;
+    use dozer_types::types::{Operation, Record};
+
+    use super::*;
+    use crate::ingestion::ChannelForwarder;
+
+    fn message(seq_no: u64) -> IngestionMessage {
+        IngestionMessage::new_op(
+            0,
+            seq_no,
+            0,
+            Operation::Insert {
+                new: Record::new(vec![]),
+            },
+        )
+    }
+
+    fn forward(options: ChaosOptions) -> (Vec<u64>, Result<(), IngestorError>) {
+        let (sender, receiver) = unbounded();
+        let forwarder = ChaosForwarder::new(Box::new(ChannelForwarder { sender }), options);
+        let result = (0..3).try_for_each(|seq_no| forwarder.forward(message(seq_no)));
+        drop(forwarder);
+        let seq_nos = receiver
+            .try_iter()
+            .map(|msg| msg.identifier.seq_in_tx)
+            .collect();
+        (seq_nos, result)
+    }
+
+    #[test]
+    fn test_chaos_forwarder() {
+        let (seq_nos, result) = forward(ChaosOptions::default());
+        assert_eq!(seq_nos, vec![0, 1, 2]);
+        assert!(result.is_ok());
+
+        let (seq_nos, _) = forward(ChaosOptions {
+            duplicate_probability: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(seq_nos, vec![0, 0, 1, 1, 2, 2]);
+
+        // Every message is delivered after the next one, so only pairs are swapped.
+        let (seq_nos, _) = forward(ChaosOptions {
+            reorder_probability: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(seq_nos, vec![1, 0, 2]);
+
+        let (seq_nos, result) = forward(ChaosOptions {
+            disconnect_probability: 1.0,
+            ..Default::default()
+        });
+        assert!(seq_nos.is_empty());
+        assert!(matches!(result, Err(IngestorError::InjectedFault(_))));
+    }
+}
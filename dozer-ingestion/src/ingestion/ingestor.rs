@@ -61,8 +61,13 @@ pub struct Ingestor {
 impl Ingestor {
     pub fn initialize_channel(config: IngestionConfig) -> (Ingestor, IngestionIterator) {
         let (tx, rx) = bounded(config.forwarder_channel_cap);
-        let sender: Arc<Box<dyn IngestorForwarder>> =
-            Arc::new(Box::new(ChannelForwarder { sender: tx }));
+        let forwarder: Box<dyn IngestorForwarder> = Box::new(ChannelForwarder { sender: tx });
+        #[cfg(feature = "chaos")]
+        let forwarder: Box<dyn IngestorForwarder> = match super::chaos::ChaosOptions::from_env() {
+            Some(options) => Box::new(super::chaos::ChaosForwarder::new(forwarder, options)),
+            None => forwarder,
+        };
+        let sender = Arc::new(forwarder);
         let ingestor = Self { sender };
 
         let iterator = IngestionIterator { rx };
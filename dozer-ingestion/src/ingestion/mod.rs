@@ -1,4 +1,7 @@
+#[cfg(feature = "chaos")]
+pub mod chaos;
 mod ingestor;
+pub mod refresh;
 
 pub use ingestor::ChannelForwarder;
 pub use ingestor::{IngestionIterator, Ingestor};
@@ -0,0 +1,445 @@
+//! Scheduled refresh of tables that are re-read as a whole instead of followed, for sources without change data capture.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+use cron::Schedule;
+use crossbeam::channel::{unbounded, Sender};
+use dozer_storage::errors::StorageError;
+use dozer_storage::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+use dozer_storage::{LmdbMap, LmdbOption, RwLmdbEnvironment};
+use dozer_types::borrow::IntoOwned;
+use dozer_types::chrono::Utc;
+use dozer_types::ingestion_types::{
+    IngestionMessage, IngestionMessageKind, IngestorError, IngestorForwarder,
+};
+use dozer_types::types::{Operation, Record};
+use tokio::runtime::Runtime;
+use tokio::sync::Notify;
+
+use super::Ingestor;
+use crate::connectors::{Connector, TableInfo};
+use crate::errors::{ConnectorError, RefreshError};
+
+/// Re-reads a table on a cron schedule and sends the inserts, updates and deletes since the last read.
+///
+/// A read ends when the connector returns or marks the end of its snapshot, so connectors that follow changes after
+/// snapshotting are read once per refresh too.
+#[derive(Debug)]
+pub struct ScheduledRefresh {
+    schedule: Schedule,
+    snapshot: SnapshotStore,
+    /// Transaction id of the operations of the next refresh.
+    txid: u64,
+}
+
+impl ScheduledRefresh {
+    /// `cron` has a seconds field, e.g. `0 */15 * * * *`. Records are matched between reads by `primary_index`,
+    /// or by all their fields if it's empty.
+    ///
+    /// The last read is kept in `snapshot_dir`, so the first refresh after a restart only sends what changed since
+    /// the last read before it.
+    pub fn new(
+        cron: &str,
+        primary_index: Vec<usize>,
+        snapshot_dir: &Path,
+    ) -> Result<Self, RefreshError> {
+        let schedule =
+            Schedule::from_str(cron).map_err(|e| RefreshError::InvalidCron(cron.to_string(), e))?;
+        Ok(Self {
+            schedule,
+            snapshot: SnapshotStore::open(snapshot_dir, primary_index)?,
+            txid: 0,
+        })
+    }
+
+    /// Refreshes `table` now and then at every scheduled time, sending its changes to `ingestor` as `table_index`.
+    ///
+    /// Returns when the schedule has no more times.
+    pub fn run(
+        &mut self,
+        runtime: &Runtime,
+        connector: &dyn Connector,
+        table: &TableInfo,
+        table_index: usize,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        loop {
+            self.refresh(runtime, connector, table, table_index, ingestor)?;
+            let Some(next) = self.schedule.upcoming(Utc).next() else {
+                return Ok(());
+            };
+            if let Ok(wait) = (next - Utc::now()).to_std() {
+                thread::sleep(wait);
+            }
+        }
+    }
+
+    /// Reads the whole `table` and sends what changed since the last read.
+    pub fn refresh(
+        &mut self,
+        runtime: &Runtime,
+        connector: &dyn Connector,
+        table: &TableInfo,
+        table_index: usize,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let (sender, receiver) = unbounded();
+        let done = Arc::new(Notify::new());
+        let reader = Ingestor {
+            sender: Arc::new(Box::new(SnapshotReader {
+                sender,
+                done: done.clone(),
+                ingestor: ingestor.clone(),
+                table_index,
+            })),
+        };
+
+        thread::scope(|scope| {
+            let table = table.clone();
+            let read = scope.spawn(move || {
+                runtime.block_on(async {
+                    tokio::select! {
+                        result = connector.start(&reader, vec![table]) => result,
+                        _ = done.notified() => Ok(()),
+                    }
+                })
+            });
+
+            // Consuming the receiver makes the connector fail to send, and return, if storing the snapshot fails.
+            let stored = receiver
+                .into_iter()
+                .try_for_each(|op| self.snapshot.read(op))
+                .map_err(RefreshError::Storage);
+            let read = read.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            stored?;
+            read
+        })?;
+
+        let mut seq_no = 0;
+        let txid = self.txid;
+        self.snapshot.finish_read(|op| {
+            seq_no += 1;
+            ingestor
+                .handle_message(IngestionMessage::new_op(txid, seq_no, table_index, op))
+                .map_err(ConnectorError::IngestorError)
+        })?;
+        self.txid += 1;
+        Ok(())
+    }
+}
+
+/// Collects the operations of a read, and forwards schema drift of the table as is.
+#[derive(Debug)]
+struct SnapshotReader {
+    sender: Sender<Operation>,
+    done: Arc<Notify>,
+    ingestor: Ingestor,
+    table_index: usize,
+}
+
+impl IngestorForwarder for SnapshotReader {
+    fn forward(&self, msg: IngestionMessage) -> Result<(), IngestorError> {
+        match msg.kind {
+            IngestionMessageKind::OperationEvent { op, .. } => self
+                .sender
+                .send(op)
+                .map_err(|e| IngestorError::ChannelError(Box::new(e))),
+            IngestionMessageKind::SnapshottingDone => {
+                self.done.notify_one();
+                Ok(())
+            }
+            // A read is applied as a whole anyway.
+            IngestionMessageKind::SnapshottingStarted
+            | IngestionMessageKind::TransactionBegin
+            | IngestionMessageKind::TransactionEnd => Ok(()),
+            IngestionMessageKind::SchemaDrift { drift, .. } => {
+                self.ingestor.handle_message(IngestionMessage {
+                    identifier: msg.identifier,
+                    kind: IngestionMessageKind::SchemaDrift {
+                        table_index: self.table_index,
+                        drift,
+                    },
+                })
+            }
+            IngestionMessageKind::SchemaChanged { .. } => {
+                self.ingestor.handle_message(IngestionMessage {
+                    identifier: msg.identifier,
+                    kind: IngestionMessageKind::SchemaChanged {
+                        table_index: self.table_index,
+                    },
+                })
+            }
+        }
+    }
+}
+
+/// The records of the last read of a table and of the read in progress, by key.
+#[derive(Debug)]
+struct SnapshotStore {
+    env: RwLmdbEnvironment,
+    /// `maps[current]` holds the last read, the other map the read in progress.
+    maps: [LmdbMap<Vec<u8>, Record>; 2],
+    current: usize,
+    /// `current`, stored with the read it refers to.
+    current_option: LmdbOption<u64>,
+    primary_index: Vec<usize>,
+}
+
+impl SnapshotStore {
+    /// Opens the store in `dir`, creating it if it doesn't exist.
+    fn open(dir: &Path, primary_index: Vec<usize>) -> Result<Self, RefreshError> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| RefreshError::SnapshotDirectory(dir.to_path_buf(), e))?;
+        let mut env =
+            LmdbEnvironmentManager::create_rw(dir, "snapshot", LmdbEnvironmentOptions::default())?;
+        let maps = [
+            LmdbMap::create(&mut env, Some("snapshot_0"))?,
+            LmdbMap::create(&mut env, Some("snapshot_1"))?,
+        ];
+        let current_option = LmdbOption::create(&mut env, Some("current"))?;
+        let current = current_option
+            .load(&*env.txn_mut()?)?
+            .map_or(0, |current| current.into_owned() as usize);
+
+        // A read interrupted by a restart is started over.
+        maps[1 - current].clear(env.txn_mut()?)?;
+        env.commit()?;
+
+        Ok(Self {
+            env,
+            maps,
+            current,
+            current_option,
+            primary_index,
+        })
+    }
+
+    /// Applies an operation of the read in progress.
+    fn read(&mut self, op: Operation) -> Result<(), StorageError> {
+        let next = self.maps[1 - self.current];
+        let txn = self.env.txn_mut()?;
+        match op {
+            Operation::Insert { new } => {
+                next.insert_overwrite(txn, &record_key(&self.primary_index, &new), &new)?;
+            }
+            Operation::Delete { old } => {
+                next.remove(txn, &record_key(&self.primary_index, &old))?;
+            }
+            Operation::Update { old, new } => {
+                next.remove(txn, &record_key(&self.primary_index, &old))?;
+                next.insert_overwrite(txn, &record_key(&self.primary_index, &new), &new)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the difference between the last read and the read in progress to `emit`, then makes the latter the last read.
+    fn finish_read(
+        &mut self,
+        mut emit: impl FnMut(Operation) -> Result<(), ConnectorError>,
+    ) -> Result<(), ConnectorError> {
+        let last = self.maps[self.current];
+        let next = self.maps[1 - self.current];
+        let txn = &*self.env.txn_mut().map_err(RefreshError::Storage)?;
+
+        for entry in next.iter(txn).map_err(RefreshError::Storage)? {
+            let (key, new) = entry.map_err(RefreshError::Storage)?;
+            match last.get(txn, &key).map_err(RefreshError::Storage)? {
+                None => emit(Operation::Insert {
+                    new: new.into_owned(),
+                })?,
+                Some(old) if old != new => emit(Operation::Update {
+                    old: old.into_owned(),
+                    new: new.into_owned(),
+                })?,
+                Some(_) => (),
+            }
+        }
+        for entry in last.iter(txn).map_err(RefreshError::Storage)? {
+            let (key, old) = entry.map_err(RefreshError::Storage)?;
+            if next
+                .get(txn, &key)
+                .map_err(RefreshError::Storage)?
+                .is_none()
+            {
+                emit(Operation::Delete {
+                    old: old.into_owned(),
+                })?;
+            }
+        }
+
+        let current = 1 - self.current;
+        let txn = self.env.txn_mut().map_err(RefreshError::Storage)?;
+        last.clear(txn).map_err(RefreshError::Storage)?;
+        self.current_option
+            .store(txn, &(current as u64))
+            .map_err(RefreshError::Storage)?;
+        self.env.commit().map_err(RefreshError::Storage)?;
+        self.current = current;
+        Ok(())
+    }
+}
+
+fn record_key(primary_index: &[usize], record: &Record) -> Vec<u8> {
+    if primary_index.is_empty() {
+        record.get_key(&(0..record.values.len()).collect())
+    } else {
+        record.get_key(&primary_index.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Field;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn record(id: i64, name: &str) -> Record {
+        Record::new(vec![Field::Int(id), Field::String(name.to_string())])
+    }
+
+    fn read(store: &mut SnapshotStore, records: &[Record]) -> Vec<Operation> {
+        for record in records {
+            store
+                .read(Operation::Insert {
+                    new: record.clone(),
+                })
+                .unwrap();
+        }
+        let mut ops = vec![];
+        store
+            .finish_read(|op| {
+                ops.push(op);
+                Ok(())
+            })
+            .unwrap();
+        ops
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let dir = TempDir::new("test_snapshot_diff").unwrap();
+        let mut store = SnapshotStore::open(dir.path(), vec![0]).unwrap();
+        assert_eq!(
+            read(
+                &mut store,
+                &[record(1, "a"), record(2, "b"), record(3, "c")]
+            ),
+            vec![
+                Operation::Insert {
+                    new: record(1, "a")
+                },
+                Operation::Insert {
+                    new: record(2, "b")
+                },
+                Operation::Insert {
+                    new: record(3, "c")
+                },
+            ]
+        );
+        assert_eq!(
+            read(
+                &mut store,
+                &[record(1, "a"), record(3, "d"), record(4, "e")]
+            ),
+            vec![
+                Operation::Update {
+                    old: record(3, "c"),
+                    new: record(3, "d"),
+                },
+                Operation::Insert {
+                    new: record(4, "e")
+                },
+                Operation::Delete {
+                    old: record(2, "b")
+                },
+            ]
+        );
+        assert_eq!(
+            read(
+                &mut store,
+                &[record(1, "a"), record(3, "d"), record(4, "e")]
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_diff_without_primary_key() {
+        let dir = TempDir::new("test_snapshot_diff_without_primary_key").unwrap();
+        let mut store = SnapshotStore::open(dir.path(), vec![]).unwrap();
+        read(&mut store, &[record(1, "a")]);
+        assert_eq!(
+            read(&mut store, &[record(1, "b")]),
+            vec![
+                Operation::Insert {
+                    new: record(1, "b")
+                },
+                Operation::Delete {
+                    old: record(1, "a")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_read_applies_changes() {
+        let dir = TempDir::new("test_snapshot_read_applies_changes").unwrap();
+        let mut store = SnapshotStore::open(dir.path(), vec![0]).unwrap();
+        store
+            .read(Operation::Insert {
+                new: record(1, "a"),
+            })
+            .unwrap();
+        store
+            .read(Operation::Update {
+                old: record(1, "a"),
+                new: record(2, "a"),
+            })
+            .unwrap();
+        assert_eq!(
+            read(&mut store, &[]),
+            vec![Operation::Insert {
+                new: record(2, "a")
+            }]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_survives_reopening() {
+        let dir = TempDir::new("test_snapshot_survives_reopening").unwrap();
+        let mut store = SnapshotStore::open(dir.path(), vec![0]).unwrap();
+        read(&mut store, &[record(1, "a")]);
+        read(&mut store, &[record(1, "a"), record(2, "b")]);
+        // Interrupted read.
+        store
+            .read(Operation::Insert {
+                new: record(3, "c"),
+            })
+            .unwrap();
+        drop(store);
+
+        let mut store = SnapshotStore::open(dir.path(), vec![0]).unwrap();
+        assert_eq!(
+            read(&mut store, &[record(1, "a"), record(2, "c")]),
+            vec![Operation::Update {
+                old: record(2, "b"),
+                new: record(2, "c"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invalid_cron() {
+        let dir = TempDir::new("test_invalid_cron").unwrap();
+        assert!(matches!(
+            ScheduledRefresh::new("every minute", vec![0], dir.path()),
+            Err(RefreshError::InvalidCron(_, _))
+        ));
+        assert!(ScheduledRefresh::new("0 */15 * * * *", vec![0], dir.path()).is_ok());
+    }
+}
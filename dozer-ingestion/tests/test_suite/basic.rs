@@ -305,6 +305,8 @@ fn assert_record_matches_schema(record: &Record, schema: &Schema, only_match_pk:
             FieldType::Json => assert!(value.as_json().is_some()),
             FieldType::Point => assert!(value.as_point().is_some()),
             FieldType::Duration => assert!(value.as_duration().is_some()),
+            FieldType::Money => assert!(value.as_money().is_some()),
+            FieldType::Enum => assert!(value.as_enum().is_some()),
         }
     }
 }
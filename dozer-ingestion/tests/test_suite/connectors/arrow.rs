@@ -289,6 +289,7 @@ fn field_type_to_arrow(field_type: FieldType) -> Option<arrow::datatypes::DataTy
         FieldType::Date => Some(arrow::datatypes::DataType::Date32),
         FieldType::Json => Some(arrow::datatypes::DataType::Utf8),
         FieldType::Point => None,
+        FieldType::Money | FieldType::Enum => None,
         FieldType::Duration => Some(arrow::datatypes::DataType::Duration(
             arrow::datatypes::TimeUnit::Nanosecond,
         )),
@@ -437,6 +438,8 @@ fn fields_to_arrow<'a, F: IntoIterator<Item = &'a Field>>(
             Arc::new(builder.finish())
         }
         FieldType::Point => panic!("Point not supported"),
+        FieldType::Money => panic!("Money not supported"),
+        FieldType::Enum => panic!("Enum not supported"),
         FieldType::Duration => {
             let mut builder = arrow::array::DurationNanosecondArray::builder(count);
             for field in fields {
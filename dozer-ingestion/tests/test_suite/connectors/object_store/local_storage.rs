@@ -97,6 +97,7 @@ fn create_connector(
                 marker_extension: String::new(),
             })),
             name: table_name,
+            watch: None,
         }],
     };
     let connector = ObjectStoreConnector::new(local_storage);
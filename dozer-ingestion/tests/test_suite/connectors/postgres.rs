@@ -135,6 +135,11 @@ async fn create_postgres_server() -> (
     let connector = PostgresConnector::new(PostgresConfig {
         name: "postgres_connector_test".to_string(),
         config: config.clone(),
+        publication: None,
+        trigger_poll_interval: None,
+        on_schema_change: Default::default(),
+        snapshot_parallelism: 1,
+        snapshot_chunk_size: 100000,
     });
 
     let client = connect(config.clone()).await.unwrap();
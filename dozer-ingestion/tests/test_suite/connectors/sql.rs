@@ -167,6 +167,7 @@ fn field_type_to_sql(field_type: FieldType) -> Option<String> {
         FieldType::Json => Some("JSONB".to_string()),
         FieldType::Point => Some("POINT".to_string()),
         FieldType::Duration => Some("DURATION".to_string()),
+        FieldType::Money | FieldType::Enum => None,
     }
 }
 
@@ -238,6 +239,8 @@ fn field_to_sql(field: &Field) -> String {
         Field::Json(b) => format!("'{b}'::jsonb"),
         Field::Point(p) => format!("'({},{})'", p.0.x(), p.0.y()),
         Field::Duration(d) => d.to_string(),
+        Field::Money(m) => format!("'{m}'"),
+        Field::Enum(e) => e.to_string(),
         Field::Null => "NULL".to_string(),
     }
 }
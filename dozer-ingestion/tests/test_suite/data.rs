@@ -9,12 +9,14 @@ pub fn records_without_primary_key() -> (FieldsAndPk, Vec<Vec<Field>>) {
             typ: FieldType::UInt,
             nullable: false,
             source: Default::default(),
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "int".to_string(),
             typ: FieldType::Int,
             nullable: false,
             source: Default::default(),
+            enum_values: vec![],
         },
     ];
 
@@ -2,7 +2,7 @@ use dozer_log::replication::LogOperation;
 use dozer_types::{
     json_types::field_to_json_value,
     serde_json::Value,
-    types::{Field, Operation, Record, Schema},
+    types::{Field, Operation, Record, Schema, Timezone},
 };
 use neon::{
     prelude::{Context, Object},
@@ -24,7 +24,7 @@ pub fn map_executor_operation<'a, C: Context<'a>>(
             result.set(cx, "type", typ)?;
             result.set(cx, "op", op)?;
         }
-        LogOperation::Commit { .. } => {
+        LogOperation::Commit { .. } | LogOperation::CommitInTransaction { .. } => {
             let typ = cx.string("commit");
             result.set(cx, "type", typ)?;
         }
@@ -87,7 +87,7 @@ fn map_record<'a, C: Context<'a>>(
 }
 
 fn map_value<'a, C: Context<'a>>(value: Field, cx: &mut C) -> JsResult<'a, JsValue> {
-    let value = match field_to_json_value(value) {
+    let value = match field_to_json_value(value, &Timezone::Utc) {
         Ok(val) => val,
         Err(error) => return cx.throw_error(error.to_string()),
     };
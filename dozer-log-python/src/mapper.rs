@@ -19,7 +19,7 @@ pub fn map_executor_operation(
             result.set_item("type", "op")?;
             result.set_item("op", map_op(op, schema, py)?)?;
         }
-        LogOperation::Commit { .. } => {
+        LogOperation::Commit { .. } | LogOperation::CommitInTransaction { .. } => {
             result.set_item("type", "commit")?;
         }
         LogOperation::SnapshottingDone { connection_name } => {
@@ -83,6 +83,8 @@ fn map_value(value: Field, py: Python) -> PyResult<Py<PyAny>> {
         Field::Json(v) => map_json_py(v, py),
         Field::Point(v) => map_point(v, py),
         Field::Duration(v) => Ok(v.to_string().to_object(py)),
+        Field::Money(v) => Ok(v.to_string().to_object(py)),
+        Field::Enum(v) => Ok(v.to_object(py)),
         Field::Null => Ok(py.None()),
     }
 }
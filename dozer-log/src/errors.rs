@@ -13,16 +13,30 @@ pub enum ReaderBuilderError {
     Storage(#[from] crate::storage::Error),
     #[error("Deserialize schema: {0}")]
     DeserializeSchema(#[from] serde_json::Error),
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+    #[error("Reading the log from Kafka requires the `kafka` feature")]
+    KafkaFeatureNotEnabled,
 }
 
 #[derive(Debug, Error)]
 pub enum ReaderError {
     #[error("Failed to deserialize log response: {0}")]
     DeserializeLogResponse(#[source] bincode::Error),
+    #[error("Unknown log response encoding: {0}")]
+    UnknownLogEncoding(i32),
     #[error("Failed to deserialize log entry: {0}")]
     DeserializeLogEntry(#[source] bincode::Error),
+    #[error("Log is not continuous: expected operation {expected}, got {actual}")]
+    LogGap { expected: u64, actual: u64 },
     #[error("Storage error: {0}")]
     Storage(#[from] crate::storage::Error),
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+    #[error("Kafka log message at offset {0} has no position or operation")]
+    InvalidKafkaMessage(i64),
     #[error("Reader thread has quit: {0:?}")]
     ReaderThreadQuit(#[source] Option<tokio::task::JoinError>),
 }
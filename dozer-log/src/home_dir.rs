@@ -5,6 +5,7 @@ pub struct HomeDir {
     api_dir: Utf8PathBuf,
     cache_dir: Utf8PathBuf,
     log_dir: Utf8PathBuf,
+    refresh_dir: Utf8PathBuf,
 }
 
 pub type Error = (Utf8PathBuf, std::io::Error);
@@ -14,13 +15,20 @@ impl HomeDir {
         let home_dir = AsRef::<Utf8Path>::as_ref(home_dir);
         let api_dir = home_dir.join("api");
         let log_dir = home_dir.join("pipeline").join("logs");
+        let refresh_dir = home_dir.join("pipeline").join("refresh");
         Self {
             api_dir,
             cache_dir: cache_dir.into(),
             log_dir,
+            refresh_dir,
         }
     }
 
+    /// The directory the last reads of the tables refreshed on a schedule are kept in.
+    pub fn refresh_dir(&self) -> &Utf8Path {
+        &self.refresh_dir
+    }
+
     pub fn create_build_dir_all(
         &self,
         endpoint_name: &str,
@@ -83,13 +91,16 @@ impl HomeDir {
             .join(&build_id.name);
         let schema_path = log_dir.join("schema.json");
         let log_path = log_dir.join("log");
+        let provenance_path = log_dir.join("provenance");
         BuildPath {
+            endpoint_name: endpoint_name.to_string(),
             id: build_id,
             api_dir,
             descriptor_path,
             log_dir,
             schema_path,
             log_path,
+            provenance_path,
         }
     }
 
@@ -221,12 +232,15 @@ fn find_latest_build_id(dir: Utf8PathBuf) -> Result<Option<BuildId>, Error> {
 
 #[derive(Debug, Clone)]
 pub struct BuildPath {
+    pub endpoint_name: String,
     pub id: BuildId,
     pub api_dir: Utf8PathBuf,
     pub descriptor_path: Utf8PathBuf,
     log_dir: Utf8PathBuf,
     pub schema_path: Utf8PathBuf,
     pub log_path: Utf8PathBuf,
+    /// The provenance of the endpoint's records, if it records it.
+    pub provenance_path: Utf8PathBuf,
 }
 
 impl BuildPath {
@@ -0,0 +1,238 @@
+//! The log of an endpoint mirrored to a compacted Kafka topic.
+//!
+//! Every operation is a message keyed by its position in the log, so API instances can bootstrap from any position
+//! and tail the topic without access to the storage of the log server.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use dozer_types::bincode;
+use dozer_types::log::error;
+use dozer_types::models::app_config::{default_log_kafka_topic_prefix, KafkaLogConfig};
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::Message;
+use rdkafka::producer::{BaseRecord, DeliveryResult, ProducerContext, ThreadedProducer};
+use rdkafka::{ClientConfig, ClientContext, Offset, TopicPartitionList};
+
+use crate::errors::ReaderError;
+use crate::replication::{Error, LogOperation};
+
+/// How long a write waits for room in the queue of the producer before retrying.
+const QUEUE_FULL_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+/// How long dropping a writer waits for queued messages to be delivered.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The topic of the log of a build of an endpoint.
+pub fn topic_name(config: &KafkaLogConfig, endpoint: &str, build_name: &str) -> String {
+    let prefix = config
+        .topic_prefix
+        .clone()
+        .unwrap_or_else(default_log_kafka_topic_prefix);
+    format!("{prefix}{endpoint}.{build_name}")
+}
+
+/// Operations are keyed by their position, big endian so keys sort like positions.
+pub fn encode_key(position: u64) -> [u8; 8] {
+    position.to_be_bytes()
+}
+
+pub fn decode_key(key: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(key.try_into().ok()?))
+}
+
+struct LoggingContext;
+
+impl ClientContext for LoggingContext {}
+
+impl ProducerContext for LoggingContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, result: &DeliveryResult<'_>, _: Self::DeliveryOpaque) {
+        if let Err((e, message)) = result {
+            error!(
+                "Failed to write log operation {:?} to Kafka topic {}: {e}",
+                message.key().and_then(decode_key),
+                message.topic()
+            );
+        }
+    }
+}
+
+pub struct KafkaLogWriter {
+    producer: ThreadedProducer<LoggingContext>,
+    topic: String,
+}
+
+impl std::fmt::Debug for KafkaLogWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaLogWriter")
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+impl KafkaLogWriter {
+    /// Creates the topic if it doesn't exist, with a single partition so offsets follow positions.
+    pub async fn new(config: &KafkaLogConfig, topic: String) -> Result<Self, KafkaError> {
+        let admin: AdminClient<DefaultClientContext> = ClientConfig::new()
+            .set("bootstrap.servers", &config.broker)
+            .create()?;
+        let new_topic =
+            NewTopic::new(&topic, 1, TopicReplication::Fixed(-1)).set("cleanup.policy", "compact");
+        for result in admin
+            .create_topics(&[new_topic], &AdminOptions::new())
+            .await?
+        {
+            match result {
+                Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => (),
+                Err((_, code)) => return Err(KafkaError::AdminOp(code)),
+            }
+        }
+
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &config.broker)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .create_with_context(LoggingContext)?;
+        Ok(Self { producer, topic })
+    }
+
+    pub async fn write(&self, position: u64, op: &LogOperation) -> Result<(), Error> {
+        let key = encode_key(position);
+        let payload = bincode::serialize(op)?;
+        loop {
+            let record = BaseRecord::to(&self.topic).key(&key[..]).payload(&payload);
+            match self.producer.send(record) {
+                Ok(()) => return Ok(()),
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), _)) => {
+                    tokio::time::sleep(QUEUE_FULL_RETRY_INTERVAL).await
+                }
+                Err((e, _)) => return Err(Error::Kafka(e)),
+            }
+        }
+    }
+}
+
+impl Drop for KafkaLogWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.producer.flush(FLUSH_TIMEOUT) {
+            error!("Failed to flush log to Kafka topic {}: {e}", self.topic);
+        }
+    }
+}
+
+pub struct KafkaLogConsumer {
+    consumer: StreamConsumer,
+    topic: String,
+    /// The position the consumer will read next, `None` before it's assigned.
+    next: Option<u64>,
+}
+
+impl std::fmt::Debug for KafkaLogConsumer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaLogConsumer")
+            .field("topic", &self.topic)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+impl KafkaLogConsumer {
+    pub fn new(config: &KafkaLogConfig, topic: String) -> Result<Self, KafkaError> {
+        let consumer = ClientConfig::new()
+            .set("bootstrap.servers", &config.broker)
+            .set("group.id", "dozer-log-reader")
+            .set("enable.auto.commit", "false")
+            .create()?;
+        Ok(Self {
+            consumer,
+            topic,
+            next: None,
+        })
+    }
+
+    /// Waits for the operation at `request.start`, then returns the operations up to `request.end` that arrive before
+    /// `timeout`.
+    pub async fn get_log(
+        &mut self,
+        request: Range<u64>,
+        timeout: Duration,
+    ) -> Result<Vec<LogOperation>, ReaderError> {
+        if self.next != Some(request.start) {
+            // Offsets are positions in a single partition, but keys are checked anyway.
+            let mut assignment = TopicPartitionList::new();
+            assignment.add_partition_offset(
+                &self.topic,
+                0,
+                Offset::Offset(request.start as i64),
+            )?;
+            self.consumer.assign(&assignment)?;
+            self.next = Some(request.start);
+        }
+
+        let mut ops = vec![];
+        let deadline = tokio::time::Instant::now() + timeout;
+        while request.start + (ops.len() as u64) < request.end {
+            let message = if ops.is_empty() {
+                self.consumer.recv().await?
+            } else {
+                match tokio::time::timeout_at(deadline, self.consumer.recv()).await {
+                    Ok(message) => message?,
+                    Err(_) => break,
+                }
+            };
+            let position = message
+                .key()
+                .and_then(decode_key)
+                .ok_or_else(|| ReaderError::InvalidKafkaMessage(message.offset()))?;
+            if position < request.start {
+                continue;
+            }
+            let expected = request.start + ops.len() as u64;
+            if position != expected {
+                return Err(ReaderError::LogGap {
+                    expected,
+                    actual: position,
+                });
+            }
+            let payload = message
+                .payload()
+                .ok_or_else(|| ReaderError::InvalidKafkaMessage(message.offset()))?;
+            ops.push(bincode::deserialize(payload).map_err(ReaderError::DeserializeLogEntry)?);
+            self.next = Some(position + 1);
+        }
+        Ok(ops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::models::app_config::KafkaLogConfig;
+
+    use super::{decode_key, encode_key, topic_name};
+
+    #[test]
+    fn test_topic_name() {
+        let mut config = KafkaLogConfig {
+            broker: "localhost:9092".to_string(),
+            topic_prefix: None,
+        };
+        assert_eq!(
+            topic_name(&config, "users", "v0001"),
+            "dozer-log-users.v0001"
+        );
+        config.topic_prefix = Some("prod.".to_string());
+        assert_eq!(topic_name(&config, "users", "v0001"), "prod.users.v0001");
+    }
+
+    #[test]
+    fn test_key() {
+        assert_eq!(decode_key(&encode_key(0)), Some(0));
+        assert_eq!(decode_key(&encode_key(258)), Some(258));
+        assert!(encode_key(255) < encode_key(256));
+        assert_eq!(decode_key(&[1, 2, 3]), None);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod errors;
 pub mod home_dir;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod provenance;
 pub mod reader;
 pub mod replication;
 pub mod schemas;
@@ -0,0 +1,129 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use dozer_storage::errors::StorageError;
+use dozer_storage::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+use dozer_storage::{LmdbEnvironment, LmdbMap, RoLmdbEnvironment, RwLmdbEnvironment};
+use dozer_types::bincode;
+use dozer_types::node::Provenance;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::types::Field;
+
+/// The source row of the last operation that changed each record of an endpoint, by the record's primary key.
+///
+/// Written by the endpoint's sink and read by the internal pipeline server. Kept in an LMDB file next to the
+/// endpoint's log, so it survives restarts. Changes are visible to readers after `commit`.
+#[derive(Debug, Clone)]
+pub struct ProvenanceStore {
+    /// Only used on the sink's thread, as LMDB write transactions are bound to the thread that began them.
+    env: Arc<Mutex<RwLmdbEnvironment>>,
+    reader: RoLmdbEnvironment,
+    /// The bincode encoded provenance by the bincode encoded primary key.
+    records: LmdbMap<Vec<u8>, Vec<u8>>,
+}
+
+impl ProvenanceStore {
+    /// Opens the store at `path`, creating it if it doesn't exist.
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        let (dir, name) = match (
+            path.parent(),
+            path.file_name().and_then(|name| name.to_str()),
+        ) {
+            (Some(dir), Some(name)) => (dir, name),
+            _ => {
+                return Err(StorageError::InvalidArgument(format!(
+                    "Invalid provenance path {}",
+                    path.display()
+                )))
+            }
+        };
+        let mut env =
+            LmdbEnvironmentManager::create_rw(dir, name, LmdbEnvironmentOptions::default())?;
+        let records = LmdbMap::create(&mut env, Some("records"))?;
+        Ok(Self {
+            reader: env.share(),
+            env: Arc::new(Mutex::new(env)),
+            records,
+        })
+    }
+
+    /// Records that the record with primary key `key` was last changed by `provenance`.
+    pub fn insert(&self, key: &[Field], provenance: &Provenance) -> Result<(), StorageError> {
+        let key = encode(key, "Vec<Field>")?;
+        let value = encode(provenance, "Provenance")?;
+        let mut env = self.env.lock();
+        self.records.insert_overwrite(env.txn_mut()?, &key, &value)
+    }
+
+    /// Forgets the record with primary key `key`, after it was deleted.
+    pub fn remove(&self, key: &[Field]) -> Result<(), StorageError> {
+        let key = encode(key, "Vec<Field>")?;
+        let mut env = self.env.lock();
+        self.records.remove(env.txn_mut()?, &key)?;
+        Ok(())
+    }
+
+    /// Makes the changes since the last commit visible to `get`.
+    pub fn commit(&self) -> Result<(), StorageError> {
+        self.env.lock().commit()
+    }
+
+    /// The provenance of the record with primary key `key`, as of the last commit.
+    pub fn get(&self, key: &[Field]) -> Result<Option<Provenance>, StorageError> {
+        let key = encode(key, "Vec<Field>")?;
+        let txn = self.reader.begin_txn()?;
+        self.records
+            .get_encoded(&txn, &key)?
+            .map(|value| {
+                bincode::deserialize(value).map_err(|e| StorageError::DeserializationError {
+                    typ: "Provenance",
+                    reason: Box::new(e),
+                })
+            })
+            .transpose()
+    }
+}
+
+fn encode<T: dozer_types::serde::Serialize + ?Sized>(
+    value: &T,
+    typ: &'static str,
+) -> Result<Vec<u8>, StorageError> {
+    bincode::serialize(value).map_err(|e| StorageError::SerializationError {
+        typ,
+        reason: Box::new(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::node::OpIdentifier;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_provenance_store() {
+        let temp_dir = TempDir::new("test_provenance_store").unwrap();
+        let path = temp_dir.path().join("provenance");
+        let store = ProvenanceStore::open(&path).unwrap();
+        let provenance = Provenance {
+            connection: "postgres".to_string(),
+            table: "users".to_string(),
+            primary_key: vec![Field::Int(1)],
+            op_id: OpIdentifier::new(10, 2),
+        };
+        let key = [Field::String("a".to_string())];
+        store.insert(&key, &provenance).unwrap();
+        store.commit().unwrap();
+        assert_eq!(store.clone().get(&key).unwrap(), Some(provenance.clone()));
+
+        // The store is read again after a restart.
+        drop(store);
+        let store = ProvenanceStore::open(&path).unwrap();
+        assert_eq!(store.get(&key).unwrap(), Some(provenance));
+
+        store.remove(&key).unwrap();
+        store.commit().unwrap();
+        assert_eq!(store.get(&key).unwrap(), None);
+    }
+}
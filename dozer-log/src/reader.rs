@@ -1,13 +1,13 @@
 use crate::attach_progress;
 use crate::errors::ReaderBuilderError;
-use crate::replication::LogOperation;
+use crate::replication::{decode_log_response, LogOperation, SUPPORTED_ENCODINGS};
 use crate::schemas::BuildSchema;
 use crate::storage::{LocalStorage, S3Storage, Storage};
 
 use super::errors::ReaderError;
 use dozer_types::grpc_types::internal::internal_pipeline_service_client::InternalPipelineServiceClient;
 use dozer_types::grpc_types::internal::{
-    storage_response, BuildRequest, LogRequest, LogResponse, StorageRequest,
+    storage_response, BuildRequest, LogEncoding, LogRequest, LogResponse, StorageRequest,
 };
 use dozer_types::indicatif::{MultiProgress, ProgressBar};
 use dozer_types::log::{debug, error};
@@ -15,6 +15,7 @@ use dozer_types::models::api_endpoint::{
     default_log_reader_batch_size, default_log_reader_buffer_size,
     default_log_reader_timeout_in_millis,
 };
+use dozer_types::models::app_config::KafkaLogConfig;
 use dozer_types::tonic::transport::Channel;
 use dozer_types::tonic::Streaming;
 use dozer_types::{bincode, serde_json};
@@ -29,6 +30,8 @@ pub struct LogReaderOptions {
     pub batch_size: u32,
     pub timeout_in_millis: u32,
     pub buffer_size: u32,
+    /// Read the log from Kafka instead of the log server, which still describes the build.
+    pub kafka: Option<KafkaLogConfig>,
 }
 
 impl LogReaderOptions {
@@ -38,6 +41,7 @@ impl LogReaderOptions {
             batch_size: default_log_reader_batch_size(),
             timeout_in_millis: default_log_reader_timeout_in_millis(),
             buffer_size: default_log_reader_buffer_size(),
+            kafka: None,
         }
     }
 }
@@ -50,8 +54,10 @@ pub struct LogReaderBuilder {
     pub schema: BuildSchema,
     /// Protobuf descriptor of this endpoint's API.
     pub descriptor: Vec<u8>,
+    /// The number of operations in the log when the builder was created.
+    pub log_end: u64,
     pub options: LogReaderOptions,
-    client: LogClient,
+    source: LogSource,
 }
 
 pub struct LogReader {
@@ -80,13 +86,23 @@ impl LogReaderBuilder {
         let build_name = build.name;
         let schema = serde_json::from_str(&build.schema_string)?;
 
-        let client = LogClient::new(client, options.endpoint.clone()).await?;
+        let source = match &options.kafka {
+            #[cfg(feature = "kafka")]
+            Some(config) => {
+                let topic = crate::kafka::topic_name(config, &options.endpoint, &build_name);
+                LogSource::Kafka(crate::kafka::KafkaLogConsumer::new(config, topic)?)
+            }
+            #[cfg(not(feature = "kafka"))]
+            Some(_) => return Err(ReaderBuilderError::KafkaFeatureNotEnabled),
+            None => LogSource::Server(LogClient::new(client, options.endpoint.clone()).await?),
+        };
 
         Ok(Self {
             build_name,
             schema,
             descriptor: build.descriptor_bytes,
-            client,
+            log_end: build.log_end,
+            source,
             options,
         })
     }
@@ -96,8 +112,9 @@ impl LogReaderBuilder {
             build_name,
             schema,
             descriptor,
-            client,
+            source,
             options,
+            ..
         } = self;
         let pb = attach_progress(multi_pb);
         pb.set_message(format!("reader: {}", options.endpoint));
@@ -105,7 +122,7 @@ impl LogReaderBuilder {
 
         let (op_sender, op_receiver) =
             tokio::sync::mpsc::channel::<(LogOperation, u64)>(options.buffer_size as usize);
-        let worker = tokio::spawn(log_reader_worker(client, pos, pb, options, op_sender));
+        let worker = tokio::spawn(log_reader_worker(source, pos, pb, options, op_sender));
         LogReader {
             build_name,
             schema,
@@ -135,6 +152,26 @@ impl LogReader {
     }
 }
 
+#[derive(Debug)]
+enum LogSource {
+    Server(LogClient),
+    #[cfg(feature = "kafka")]
+    Kafka(crate::kafka::KafkaLogConsumer),
+}
+
+impl LogSource {
+    async fn get_log(&mut self, request: LogRequest) -> Result<Vec<LogOperation>, ReaderError> {
+        match self {
+            LogSource::Server(client) => client.get_log(request).await,
+            #[cfg(feature = "kafka")]
+            LogSource::Kafka(consumer) => {
+                let timeout = std::time::Duration::from_millis(request.timeout_in_millis as u64);
+                consumer.get_log(request.start..request.end, timeout).await
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct LogClient {
     client: InternalPipelineServiceClient<Channel>,
@@ -203,8 +240,10 @@ impl LogClient {
             }
         };
         use crate::replication::LogResponse;
-        let response: LogResponse =
-            bincode::deserialize(&response.data).map_err(ReaderError::DeserializeLogResponse)?;
+        let encoding = LogEncoding::from_i32(response.encoding)
+            .ok_or(ReaderError::UnknownLogEncoding(response.encoding))?;
+        let response: LogResponse = decode_log_response(&response.data, encoding)
+            .map_err(ReaderError::DeserializeLogResponse)?;
 
         // Load response.
         let request_range = request.start..request.end;
@@ -214,6 +253,12 @@ impl LogClient {
                     "Loading persisted log entry {}, entry range {:?}, requested range {:?}",
                     persisted.key, persisted.range, request_range
                 );
+                if !persisted.range.contains(&(request_range.start as usize)) {
+                    return Err(ReaderError::LogGap {
+                        expected: request_range.start,
+                        actual: persisted.range.start as u64,
+                    });
+                }
                 // Load the persisted log entry.
                 let data = self.storage.download_object(persisted.key).await?;
                 let mut ops: Vec<LogOperation> =
@@ -259,7 +304,7 @@ async fn call_get_log_once(
 }
 
 async fn log_reader_worker(
-    mut log_client: LogClient,
+    mut source: LogSource,
     mut pos: u64,
     pb: ProgressBar,
     options: LogReaderOptions,
@@ -272,8 +317,12 @@ async fn log_reader_worker(
             start: pos,
             end: pos + options.batch_size as u64,
             timeout_in_millis: options.timeout_in_millis,
+            accepted_encodings: SUPPORTED_ENCODINGS
+                .iter()
+                .map(|encoding| *encoding as i32)
+                .collect(),
         };
-        let ops = log_client.get_log(request).await?;
+        let ops = source.get_log(request).await?;
 
         for op in ops {
             pos += 1;
@@ -0,0 +1,51 @@
+use dozer_types::bincode::{self, Options};
+use dozer_types::grpc_types::internal::LogEncoding;
+
+use super::LogResponse;
+
+/// The encodings this version reads and writes, preferred first.
+pub const SUPPORTED_ENCODINGS: [LogEncoding; 3] = [
+    LogEncoding::BincodeVarintZstd,
+    LogEncoding::BincodeVarint,
+    LogEncoding::Bincode,
+];
+
+/// Fast compression, as the log is read as it's written.
+const ZSTD_LEVEL: i32 = 1;
+
+/// The first of the encodings accepted by a client which is supported, `Bincode` if none is.
+pub fn negotiate_encoding(accepted_encodings: &[i32]) -> LogEncoding {
+    accepted_encodings
+        .iter()
+        .filter_map(|encoding| LogEncoding::from_i32(*encoding))
+        .find(|encoding| SUPPORTED_ENCODINGS.contains(encoding))
+        .unwrap_or(LogEncoding::Bincode)
+}
+
+pub fn encode_log_response(
+    response: &LogResponse,
+    encoding: LogEncoding,
+) -> Result<Vec<u8>, bincode::Error> {
+    match encoding {
+        LogEncoding::Bincode => bincode::serialize(response),
+        LogEncoding::BincodeVarint => bincode::DefaultOptions::new().serialize(response),
+        LogEncoding::BincodeVarintZstd => {
+            let data = bincode::DefaultOptions::new().serialize(response)?;
+            Ok(zstd::encode_all(data.as_slice(), ZSTD_LEVEL)?)
+        }
+    }
+}
+
+pub fn decode_log_response(
+    data: &[u8],
+    encoding: LogEncoding,
+) -> Result<LogResponse, bincode::Error> {
+    match encoding {
+        LogEncoding::Bincode => bincode::deserialize(data),
+        LogEncoding::BincodeVarint => bincode::DefaultOptions::new().deserialize(data),
+        LogEncoding::BincodeVarintZstd => {
+            let data = zstd::decode_all(data)?;
+            bincode::DefaultOptions::new().deserialize(&data)
+        }
+    }
+}
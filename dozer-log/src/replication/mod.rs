@@ -5,7 +5,8 @@ use std::time::{Duration, SystemTime};
 
 use dozer_types::grpc_types::internal::storage_response;
 use dozer_types::log::{debug, error};
-use dozer_types::models::app_config::LogStorage;
+use dozer_types::models::app_config::{KafkaLogConfig, LogStorage};
+use dozer_types::node::NodeHandle;
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::types::Operation;
 use dozer_types::{bincode, thiserror};
@@ -18,8 +19,12 @@ use crate::home_dir::BuildPath;
 
 use self::persist::{load_persisted_log_entries, persisted_log_entries_end, PersistingQueue};
 
+pub use self::encoding::{
+    decode_log_response, encode_log_response, negotiate_encoding, SUPPORTED_ENCODINGS,
+};
 pub use self::persist::create_log_storage;
 
+mod encoding;
 mod persist;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -45,6 +50,11 @@ pub enum Error {
     Serialization(#[from] bincode::Error),
     #[error("Persisting thread has quit: {0:?}")]
     PersistingThreadQuit(#[source] Option<JoinError>),
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+    #[error("Writing the log to Kafka requires the `kafka` feature")]
+    KafkaFeatureNotEnabled,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +62,8 @@ pub struct LogOptions {
     pub storage_config: LogStorage,
     pub entry_max_size: usize,
     pub max_num_immutable_entries: usize,
+    /// Also write the log to a Kafka topic.
+    pub kafka: Option<KafkaLogConfig>,
 }
 
 /// Invariant:
@@ -69,6 +81,10 @@ pub struct Log {
     queue: PersistingQueue,
     storage: storage_response::Storage,
     entry_max_size: usize,
+    /// Number of `LogOperation::Op`s written since the log was opened.
+    num_ops_written: usize,
+    #[cfg(feature = "kafka")]
+    kafka: Option<crate::kafka::KafkaLogWriter>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -94,6 +110,16 @@ impl Log {
         self.storage.clone()
     }
 
+    /// The position of the next operation written.
+    pub fn end(&self) -> usize {
+        self.in_memory.end()
+    }
+
+    /// The number of record operations written since the log was opened, excluding commits and other markers.
+    pub fn num_ops_written(&self) -> usize {
+        self.num_ops_written
+    }
+
     pub async fn new(
         options: LogOptions,
         build_path: &BuildPath,
@@ -114,6 +140,26 @@ impl Log {
         let storage_description = storage.describe();
         let queue =
             PersistingQueue::new(storage, prefix, options.max_num_immutable_entries).await?;
+
+        // A readonly log is never written, so it's not mirrored.
+        let kafka_config = options.kafka.filter(|_| !readonly);
+        #[cfg(feature = "kafka")]
+        let kafka = match kafka_config {
+            Some(config) => {
+                let topic = crate::kafka::topic_name(
+                    &config,
+                    &build_path.endpoint_name,
+                    build_path.id.name(),
+                );
+                Some(crate::kafka::KafkaLogWriter::new(&config, topic).await?)
+            }
+            None => None,
+        };
+        #[cfg(not(feature = "kafka"))]
+        if kafka_config.is_some() {
+            return Err(Error::KafkaFeatureNotEnabled);
+        }
+
         Ok(Self {
             persisted,
             in_memory,
@@ -122,6 +168,9 @@ impl Log {
             queue,
             storage: storage_description,
             entry_max_size: options.entry_max_size,
+            num_ops_written: 0,
+            #[cfg(feature = "kafka")]
+            kafka,
         })
     }
 
@@ -130,7 +179,16 @@ impl Log {
         op: LogOperation,
         this: Arc<Mutex<Log>>,
     ) -> Result<Option<JoinHandle<()>>, Error> {
+        // Mirror the operation before it can be read.
+        #[cfg(feature = "kafka")]
+        if let Some(kafka) = &self.kafka {
+            kafka.write(self.in_memory.end() as u64, &op).await?;
+        }
+
         // Record operation.
+        if matches!(op, LogOperation::Op { .. }) {
+            self.num_ops_written += 1;
+        }
         self.in_memory.ops.push(op);
 
         // Check watchers.
@@ -280,10 +338,23 @@ impl Log {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "dozer_types::serde")]
 pub enum LogOperation {
-    Op { op: Operation },
-    Commit { decision_instant: SystemTime },
-    SnapshottingDone { connection_name: String },
+    Op {
+        op: Operation,
+    },
+    Commit {
+        decision_instant: SystemTime,
+    },
+    SnapshottingDone {
+        connection_name: String,
+    },
     Terminate,
+    /// A commit in the middle of source transactions, whose operations should be exposed together with those up to
+    /// the commit where the transactions end. Declared last, so logs written before it keep decoding.
+    CommitInTransaction {
+        decision_instant: SystemTime,
+        /// The sources in the middle of a transaction, with the id of the transaction.
+        open_transactions: Vec<(NodeHandle, u64)>,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
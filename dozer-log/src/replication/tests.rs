@@ -1,12 +1,17 @@
 use std::{sync::Arc, time::Duration};
 
+use dozer_types::grpc_types::internal::LogEncoding;
 use dozer_types::models::app_config::LogStorage;
+use dozer_types::types::{Field, Operation, Record};
 use tempdir::TempDir;
 use tokio::sync::Mutex;
 
 use crate::{
     home_dir::{BuildId, HomeDir},
-    replication::{Log, LogOperation, LogResponse},
+    replication::{
+        decode_log_response, encode_log_response, negotiate_encoding, Log, LogOperation,
+        LogResponse, PersistedLogEntry,
+    },
 };
 
 use super::LogOptions;
@@ -25,6 +30,7 @@ async fn create_test_log(
             storage_config: LogStorage::Local(()),
             max_num_immutable_entries: 10,
             entry_max_size,
+            kafka: None,
         },
         &build_path,
         false,
@@ -218,3 +224,72 @@ async fn write_watch_partial_timeout() {
     let ops_read = ops_read_future.await.unwrap();
     assert_eq!(ops_read, LogResponse::Operations(vec![op]));
 }
+
+#[test]
+fn encode_decode_log_response() {
+    let ops = (0..100)
+        .map(|i| LogOperation::Op {
+            op: Operation::Insert {
+                new: Record::new(vec![Field::UInt(i), Field::String(format!("value {i}"))]),
+            },
+        })
+        .collect();
+    let responses = [
+        LogResponse::Operations(ops),
+        LogResponse::Persisted(PersistedLogEntry {
+            key: "0-100".to_string(),
+            range: 0..100,
+        }),
+    ];
+    for response in responses {
+        let mut sizes = vec![];
+        for encoding in [
+            LogEncoding::Bincode,
+            LogEncoding::BincodeVarint,
+            LogEncoding::BincodeVarintZstd,
+        ] {
+            let data = encode_log_response(&response, encoding).unwrap();
+            assert_eq!(decode_log_response(&data, encoding).unwrap(), response);
+            sizes.push(data.len());
+        }
+        assert!(sizes[1] < sizes[0]);
+    }
+}
+
+#[test]
+fn negotiate_log_encoding() {
+    // Clients which don't negotiate read bincode.
+    assert_eq!(negotiate_encoding(&[]), LogEncoding::Bincode);
+    assert_eq!(
+        negotiate_encoding(&[
+            LogEncoding::BincodeVarint as i32,
+            LogEncoding::BincodeVarintZstd as i32
+        ]),
+        LogEncoding::BincodeVarint
+    );
+    // Encodings of newer clients are skipped.
+    assert_eq!(
+        negotiate_encoding(&[100, LogEncoding::BincodeVarintZstd as i32]),
+        LogEncoding::BincodeVarintZstd
+    );
+}
+
+#[test]
+fn commit_keeps_its_serialized_form() {
+    // Logs persisted before transactions were marked must decode the same.
+    let decision_instant = std::time::SystemTime::UNIX_EPOCH;
+    let commit = LogOperation::Commit { decision_instant };
+    let data = dozer_types::bincode::serialize(&commit).unwrap();
+    assert_eq!(data[..4], 1u32.to_le_bytes());
+    assert_eq!(
+        dozer_types::bincode::deserialize::<LogOperation>(&data).unwrap(),
+        commit
+    );
+
+    let commit_in_transaction = LogOperation::CommitInTransaction {
+        decision_instant,
+        open_transactions: vec![],
+    };
+    let data = dozer_types::bincode::serialize(&commit_in_transaction).unwrap();
+    assert_eq!(data[..4], 4u32.to_le_bytes());
+}
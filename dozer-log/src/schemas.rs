@@ -2,6 +2,7 @@ use std::{collections::HashSet, fs::OpenOptions, path::Path};
 
 use camino::Utf8Path;
 use dozer_types::{
+    models::api_endpoint::DeprecatedField,
     serde::{Deserialize, Serialize},
     serde_json,
     types::{IndexDefinition, Schema},
@@ -18,6 +19,18 @@ pub struct BuildSchema {
     pub enable_token: bool,
     pub enable_on_event: bool,
     pub connections: HashSet<String>,
+    /// Name of the typed gRPC service; Default: plural PascalCase of the endpoint name
+    #[serde(default)]
+    pub grpc_service_name: Option<String>,
+    /// Name of the typed gRPC record message; Default: singular PascalCase of the endpoint name
+    #[serde(default)]
+    pub grpc_message_name: Option<String>,
+    /// Whether field names are served in camelCase
+    #[serde(default)]
+    pub camel_case_fields: bool,
+    /// Fields marked deprecated in the generated protos
+    #[serde(default)]
+    pub deprecated_fields: Vec<DeprecatedField>,
 }
 
 pub fn write_schema(schema: &BuildSchema, schema_path: &Path) -> Result<(), SchemaError> {
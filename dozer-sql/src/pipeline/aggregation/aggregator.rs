@@ -5,7 +5,8 @@ use crate::pipeline::aggregation::count::CountAggregator;
 use crate::pipeline::aggregation::max::MaxAggregator;
 use crate::pipeline::aggregation::min::MinAggregator;
 use crate::pipeline::aggregation::sum::SumAggregator;
-use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::SqlError::Operation;
+use crate::pipeline::errors::{OperationError, PipelineError};
 use enum_dispatch::enum_dispatch;
 use std::collections::BTreeMap;
 
@@ -195,6 +196,20 @@ pub fn get_aggregator_type_from_aggregation_expression(
     }
 }
 
+/// Money is ordered by currency first, so the extremes of amounts of different currencies would be meaningless.
+pub fn check_single_currency(field_map: &BTreeMap<Field, u64>) -> Result<(), PipelineError> {
+    if let (Some(Field::Money(first)), Some(Field::Money(last))) =
+        (field_map.keys().next(), field_map.keys().next_back())
+    {
+        if first.currency != last.currency {
+            return Err(PipelineError::SqlError(Operation(
+                OperationError::CurrencyMismatch(first.currency, last.currency),
+            )));
+        }
+    }
+    Ok(())
+}
+
 pub fn update_map(
     fields: &[Field],
     val_delta: u64,
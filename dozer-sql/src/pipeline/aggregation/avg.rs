@@ -9,7 +9,9 @@ use crate::pipeline::expression::execution::{Expression, ExpressionType};
 use dozer_types::arrow::datatypes::ArrowNativeTypeOp;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::{DozerDuration, Field, FieldType, Schema, SourceDefinition, TimeUnit};
+use dozer_types::types::{
+    DozerDuration, DozerMoney, Field, FieldType, Schema, SourceDefinition, TimeUnit,
+};
 use num_traits::FromPrimitive;
 
 use std::ops::Div;
@@ -25,6 +27,7 @@ pub fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         FieldType::Float => FieldType::Float,
         FieldType::Decimal => FieldType::Decimal,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Money => FieldType::Money,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
@@ -32,7 +35,8 @@ pub fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Enum => {
             return Err(PipelineError::InvalidFunctionArgumentType(
                 Avg.to_string(),
                 arg.return_type,
@@ -44,6 +48,7 @@ pub fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
                     FieldType::Float,
                     FieldType::Decimal,
                     FieldType::Duration,
+                    FieldType::Money,
                 ]),
                 0,
             ));
@@ -76,6 +81,7 @@ impl AvgAggregator {
                 float_state: 0_f64,
                 decimal_state: Decimal::from_f64(0_f64).unwrap(),
                 duration_state: std::time::Duration::new(0, 0),
+                money_state: None,
             },
             current_count: 0_u64,
             return_type: None,
@@ -208,6 +214,18 @@ fn get_average(
                     TimeUnit::Nanoseconds,
                 )))
             }
+            FieldType::Money => {
+                if *current_count == 0 {
+                    return Ok(Field::Null);
+                }
+                let m_sum = sum
+                    .to_money()?
+                    .ok_or(InvalidValue(sum.to_string().unwrap()))?;
+                Ok(Field::Money(DozerMoney::new(
+                    m_sum.amount.div(Decimal::from(*current_count)),
+                    m_sum.currency,
+                )))
+            }
             FieldType::Boolean
             | FieldType::String
             | FieldType::Text
@@ -215,7 +233,8 @@ fn get_average(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Enum => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Avg}"
             ))),
         },
@@ -69,7 +69,7 @@ fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, Pipeli
                 Count,
                 FieldType::Decimal
             ))),
-            FieldType::Duration => Ok(Field::Int(count as i64)),
+            FieldType::Duration | FieldType::Money => Ok(Field::Int(count as i64)),
             FieldType::Boolean
             | FieldType::String
             | FieldType::Text
@@ -77,7 +77,8 @@ fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, Pipeli
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Enum => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Count}"
             ))),
         },
@@ -1,4 +1,4 @@
-use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::builder::{SchemaSQLContext, SqlOptions};
 use crate::pipeline::planner::projection::CommonPlanner;
 use crate::pipeline::projection::processor::ProjectionProcessor;
 use crate::pipeline::{aggregation::processor::AggregationProcessor, errors::PipelineError};
@@ -17,19 +17,21 @@ pub struct AggregationProcessorFactory {
     id: String,
     projection: Select,
     _stateful: bool,
+    options: SqlOptions,
 }
 
 impl AggregationProcessorFactory {
-    pub fn new(id: String, projection: Select, stateful: bool) -> Self {
+    pub fn new(id: String, projection: Select, stateful: bool, options: SqlOptions) -> Self {
         Self {
             id,
             projection,
             _stateful: stateful,
+            options,
         }
     }
 
     fn get_planner(&self, input_schema: Schema) -> Result<CommonPlanner, PipelineError> {
-        let mut projection_planner = CommonPlanner::new(input_schema);
+        let mut projection_planner = CommonPlanner::new(input_schema, self.options);
         projection_planner.plan(self.projection.clone())?;
         Ok(projection_planner)
     }
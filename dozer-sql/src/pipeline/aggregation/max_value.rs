@@ -1,4 +1,4 @@
-use crate::pipeline::aggregation::aggregator::{update_val_map, Aggregator};
+use crate::pipeline::aggregation::aggregator::{check_single_currency, update_val_map, Aggregator};
 use crate::pipeline::errors::PipelineError::InvalidReturnType;
 use crate::pipeline::errors::{FieldTypes, PipelineError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType::MaxValue;
@@ -24,12 +24,14 @@ pub fn validate_max_value(
         FieldType::Timestamp => FieldType::Timestamp,
         FieldType::Date => FieldType::Date,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Money => FieldType::Money,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Enum => {
             return Err(PipelineError::InvalidFunctionArgumentType(
                 MaxValue.to_string(),
                 arg.return_type,
@@ -43,6 +45,7 @@ pub fn validate_max_value(
                     FieldType::Timestamp,
                     FieldType::Date,
                     FieldType::Duration,
+                    FieldType::Money,
                 ]),
                 0,
             ));
@@ -115,6 +118,7 @@ fn get_max_value(
     if field_map.is_empty() {
         Ok(Field::Null)
     } else {
+        check_single_currency(field_map)?;
         let val = calculate_err!(field_map.keys().max(), MaxValue).clone();
 
         match return_map.get(&val) {
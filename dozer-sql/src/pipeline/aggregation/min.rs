@@ -1,4 +1,4 @@
-use crate::pipeline::aggregation::aggregator::{update_map, Aggregator};
+use crate::pipeline::aggregation::aggregator::{check_single_currency, update_map, Aggregator};
 use crate::pipeline::errors::{FieldTypes, PipelineError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Min;
@@ -21,12 +21,14 @@ pub fn validate_min(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         FieldType::Timestamp => FieldType::Timestamp,
         FieldType::Date => FieldType::Date,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Money => FieldType::Money,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Enum => {
             return Err(PipelineError::InvalidFunctionArgumentType(
                 Min.to_string(),
                 arg.return_type,
@@ -40,6 +42,7 @@ pub fn validate_min(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
                     FieldType::Timestamp,
                     FieldType::Date,
                     FieldType::Duration,
+                    FieldType::Money,
                 ]),
                 0,
             ));
@@ -124,12 +127,21 @@ fn get_min(
                     Min,
                     val
                 ))),
+                FieldType::Money => {
+                    check_single_currency(field_map)?;
+                    Ok(Field::Money(calculate_err_field!(
+                        val.to_money()?,
+                        Min,
+                        val
+                    )))
+                }
                 FieldType::Boolean
                 | FieldType::String
                 | FieldType::Text
                 | FieldType::Binary
                 | FieldType::Json
-                | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+                | FieldType::Point
+                | FieldType::Enum => Err(PipelineError::InvalidReturnType(format!(
                     "Not supported return type {typ} for {Min}"
                 ))),
             },
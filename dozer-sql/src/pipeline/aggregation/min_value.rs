@@ -1,4 +1,4 @@
-use crate::pipeline::aggregation::aggregator::{update_val_map, Aggregator};
+use crate::pipeline::aggregation::aggregator::{check_single_currency, update_val_map, Aggregator};
 use crate::pipeline::errors::PipelineError::InvalidReturnType;
 use crate::pipeline::errors::{FieldTypes, PipelineError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType::MinValue;
@@ -24,12 +24,14 @@ pub fn validate_min_value(
         FieldType::Timestamp => FieldType::Timestamp,
         FieldType::Date => FieldType::Date,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Money => FieldType::Money,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Enum => {
             return Err(PipelineError::InvalidFunctionArgumentType(
                 MinValue.to_string(),
                 arg.return_type,
@@ -43,6 +45,7 @@ pub fn validate_min_value(
                     FieldType::Timestamp,
                     FieldType::Date,
                     FieldType::Duration,
+                    FieldType::Money,
                 ]),
                 0,
             ));
@@ -115,6 +118,7 @@ fn get_min_value(
     if field_map.is_empty() {
         Ok(Field::Null)
     } else {
+        check_single_currency(field_map)?;
         let val = calculate_err!(field_map.keys().min(), MinValue).clone();
 
         match return_map.get(&val) {
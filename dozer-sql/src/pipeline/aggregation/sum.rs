@@ -1,12 +1,15 @@
 use crate::pipeline::aggregation::aggregator::Aggregator;
-use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::errors::SqlError::Operation;
+use crate::pipeline::errors::{FieldTypes, OperationError, PipelineError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Sum;
 use crate::pipeline::expression::execution::{Expression, ExpressionType};
 use crate::{argv, calculate_err_field};
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::{DozerDuration, Field, FieldType, Schema, SourceDefinition, TimeUnit};
+use dozer_types::types::{
+    DozerDuration, DozerMoney, Field, FieldType, Schema, SourceDefinition, TimeUnit,
+};
 use num_traits::FromPrimitive;
 
 pub fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionType, PipelineError> {
@@ -20,6 +23,7 @@ pub fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         FieldType::Float => FieldType::Float,
         FieldType::Decimal => FieldType::Decimal,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Money => FieldType::Money,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
@@ -27,7 +31,8 @@ pub fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
-        | FieldType::Point => {
+        | FieldType::Point
+        | FieldType::Enum => {
             return Err(PipelineError::InvalidFunctionArgumentType(
                 Sum.to_string(),
                 arg.return_type,
@@ -39,6 +44,7 @@ pub fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
                     FieldType::Float,
                     FieldType::Decimal,
                     FieldType::Duration,
+                    FieldType::Money,
                 ]),
                 0,
             ));
@@ -67,6 +73,8 @@ pub struct SumState {
     pub(crate) float_state: f64,
     pub(crate) decimal_state: Decimal,
     pub(crate) duration_state: std::time::Duration,
+    /// The sum of the amounts of the currency of the first value, as amounts of other currencies can't be summed.
+    pub(crate) money_state: Option<DozerMoney>,
 }
 
 impl SumAggregator {
@@ -80,6 +88,7 @@ impl SumAggregator {
                 float_state: 0_f64,
                 decimal_state: Decimal::from_f64(0_f64).unwrap(),
                 duration_state: std::time::Duration::new(0, 0),
+                money_state: None,
             },
             return_type: None,
         }
@@ -214,6 +223,25 @@ pub fn get_sum(
                     TimeUnit::Nanoseconds,
                 )))
             }
+            FieldType::Money => {
+                for field in fields {
+                    let val = calculate_err_field!(field.to_money()?, Sum, field);
+                    let sum = current_state
+                        .money_state
+                        .get_or_insert(DozerMoney::new(Decimal::ZERO, val.currency));
+                    if sum.currency != val.currency {
+                        return Err(PipelineError::SqlError(Operation(
+                            OperationError::CurrencyMismatch(sum.currency, val.currency),
+                        )));
+                    }
+                    if decr {
+                        sum.amount -= val.amount;
+                    } else {
+                        sum.amount += val.amount;
+                    }
+                }
+                Ok(current_state.money_state.map_or(Field::Null, Field::Money))
+            }
             FieldType::Boolean
             | FieldType::String
             | FieldType::Text
@@ -221,7 +249,8 @@ pub fn get_sum(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
+            | FieldType::Point
+            | FieldType::Enum => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Sum}"
             ))),
         },
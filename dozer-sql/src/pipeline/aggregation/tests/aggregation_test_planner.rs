@@ -1,4 +1,5 @@
 use crate::pipeline::aggregation::processor::AggregationProcessor;
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::planner::projection::CommonPlanner;
 use crate::pipeline::tests::utils::get_select;
 use dozer_types::types::{
@@ -71,7 +72,7 @@ fn test_planner_with_aggregator() {
         )
         .clone();
 
-    let mut projection_planner = CommonPlanner::new(schema.clone());
+    let mut projection_planner = CommonPlanner::new(schema.clone(), SqlOptions::default());
     let statement = get_select(sql).unwrap();
 
     projection_planner.plan(*statement).unwrap();
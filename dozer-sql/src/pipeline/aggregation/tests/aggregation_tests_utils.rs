@@ -6,6 +6,7 @@ use dozer_types::types::{
 use std::collections::HashMap;
 
 use crate::pipeline::aggregation::processor::AggregationProcessor;
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::planner::projection::CommonPlanner;
 use crate::pipeline::tests::utils::get_select;
@@ -23,7 +24,7 @@ pub(crate) fn init_processor(
         .get(&DEFAULT_PORT_HANDLE)
         .unwrap_or_else(|| panic!("Error getting Input Schema"));
 
-    let mut projection_planner = CommonPlanner::new(input_schema.clone());
+    let mut projection_planner = CommonPlanner::new(input_schema.clone(), SqlOptions::default());
     let statement = get_select(sql).unwrap();
 
     projection_planner.plan(*statement).unwrap();
@@ -2,11 +2,14 @@ use crate::pipeline::aggregation::factory::AggregationProcessorFactory;
 use crate::pipeline::builder::PipelineError::InvalidQuery;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::builder::{ExpressionBuilder, NameOrAlias};
+use crate::pipeline::router::factory::RouterProcessorFactory;
 use crate::pipeline::selection::factory::SelectionProcessorFactory;
 use dozer_core::app::AppPipeline;
 use dozer_core::app::PipelineEntryPoint;
-use dozer_core::node::PortHandle;
+use dozer_core::node::{PortHandle, ProcessorFactory};
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::identifier;
+use dozer_types::types::Timezone;
 use sqlparser::ast::{Join, SetOperator, SetQuantifier, TableFactor, TableWithJoins};
 
 use sqlparser::{
@@ -17,13 +20,22 @@ use sqlparser::{
 use std::collections::HashMap;
 
 use super::errors::UnsupportedSqlError;
-use super::pipeline_builder::from_builder::insert_from_to_pipeline;
+use super::pipeline_builder::from_builder::{insert_from_to_pipeline, string_from_sql_object_name};
 
 use super::product::set::set_factory::SetProcessorFactory;
 
 #[derive(Debug, Clone, Default)]
 pub struct SchemaSQLContext {}
 
+/// Settings of the app that change how its SQL is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SqlOptions {
+    /// Whether unquoted identifiers only refer to names spelled exactly like them.
+    pub case_sensitive: bool,
+    /// The time zone `NOW()`, `CURRENT_DATE` and `DATE_TRUNC` are evaluated in.
+    pub timezone: Timezone,
+}
+
 #[derive(Debug, Clone)]
 pub struct OutputNodeInfo {
     // Name to connect in dag
@@ -54,6 +66,8 @@ pub struct QueryContext {
 
     // Processors counter
     pub processor_counter: usize,
+
+    pub options: SqlOptions,
 }
 
 impl QueryContext {
@@ -72,9 +86,13 @@ pub fn statement_to_pipeline(
     sql: &str,
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     override_name: Option<String>,
+    options: SqlOptions,
 ) -> Result<QueryContext, PipelineError> {
     let dialect = DozerDialect {};
-    let mut ctx = QueryContext::default();
+    let mut ctx = QueryContext {
+        options,
+        ..Default::default()
+    };
 
     let ast = Parser::parse_sql(&dialect, sql)
         .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
@@ -110,6 +128,61 @@ pub fn statement_to_pipeline(
     Ok(ctx)
 }
 
+/// Builds a processor computing a single `SELECT` statement over the records of its input port.
+///
+/// The `FROM` clause only documents the input, so it's not resolved. `WHERE` and `INTO` are not supported.
+pub fn select_to_processor(
+    id: String,
+    sql: &str,
+    options: SqlOptions,
+) -> Result<Box<dyn ProcessorFactory<SchemaSQLContext>>, PipelineError> {
+    let dialect = DozerDialect {};
+    let ast = Parser::parse_sql(&dialect, sql)
+        .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
+
+    if let [Statement::Query(query)] = ast.as_slice() {
+        if let SetExpr::Select(select) = query.body.as_ref() {
+            if select.selection.is_none() && select.into.is_none() {
+                return Ok(Box::new(AggregationProcessorFactory::new(
+                    id,
+                    *select.clone(),
+                    false,
+                    options,
+                )));
+            }
+        }
+    }
+    Err(PipelineError::UnsupportedSqlError(
+        UnsupportedSqlError::GenericError(sql.to_string()),
+    ))
+}
+
+/// Builds a processor sending the records of its input port to output port `i` if they satisfy `conditions[i]`,
+/// and to port `conditions.len()` if they satisfy none and `has_default`.
+pub fn router_to_processor(
+    id: String,
+    conditions: &[&str],
+    has_default: bool,
+    options: SqlOptions,
+) -> Result<Box<dyn ProcessorFactory<SchemaSQLContext>>, PipelineError> {
+    let dialect = DozerDialect {};
+    let conditions = conditions
+        .iter()
+        .map(|condition| {
+            Parser::new(&dialect)
+                .try_with_sql(condition)
+                .and_then(|mut parser| parser.parse_expr())
+                .map_err(|err| PipelineError::InternalError(Box::new(err)))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Box::new(RouterProcessorFactory::new(
+        id,
+        conditions,
+        has_default,
+        options,
+    )))
+}
+
 fn query_to_pipeline(
     table_info: &TableInfo,
     query: &Query,
@@ -145,7 +218,7 @@ fn query_to_pipeline(
                     UnsupportedSqlError::CteFromError,
                 ));
             }
-            let table_name = table.alias.name.to_string();
+            let table_name = ExpressionBuilder::normalize_ident(&table.alias.name);
             if query_ctx
                 .pipeline_map
                 .contains_key(&(pipeline_idx, table_name.clone()))
@@ -182,7 +255,10 @@ fn query_to_pipeline(
         }
         SetExpr::Query(query) => {
             let query_name = format!("subquery_{}", query_ctx.get_next_processor_id());
-            let mut ctx = QueryContext::default();
+            let mut ctx = QueryContext {
+                options: query_ctx.options,
+                ..Default::default()
+            };
             query_to_pipeline(
                 &TableInfo {
                     name: NameOrAlias(query_name, None),
@@ -277,14 +353,22 @@ fn select_to_pipeline(
         }
     }
 
-    let aggregation =
-        AggregationProcessorFactory::new(gen_agg_name.clone(), select.clone(), stateful);
+    let aggregation = AggregationProcessorFactory::new(
+        gen_agg_name.clone(),
+        select.clone(),
+        stateful,
+        query_ctx.options,
+    );
 
     pipeline.add_processor(Box::new(aggregation), &gen_agg_name, vec![]);
 
     // Where clause
     if let Some(selection) = select.selection {
-        let selection = SelectionProcessorFactory::new(gen_selection_name.to_owned(), selection);
+        let selection = SelectionProcessorFactory::new(
+            gen_selection_name.to_owned(),
+            selection,
+            query_ctx.options,
+        );
 
         pipeline.add_processor(Box::new(selection), &gen_selection_name, vec![]);
 
@@ -320,7 +404,7 @@ fn select_to_pipeline(
     );
 
     let output_table_name = if let Some(into) = select.into {
-        Some(into.name.to_string())
+        Some(string_from_sql_object_name(&into.name))
     } else {
         table_info.override_name.clone()
     };
@@ -561,12 +645,28 @@ pub fn get_from_source(
 ) -> Result<NameOrAlias, PipelineError> {
     match relation {
         TableFactor::Table { name, alias, .. } => {
-            let input_name = name
+            let mut input_name = name
                 .0
                 .iter()
                 .map(ExpressionBuilder::normalize_ident)
                 .collect::<Vec<String>>()
                 .join(".");
+            // Refer to a table created by the queries, like a `WITH` query, by the name it was created with.
+            let quoted = name.0.iter().any(|ident| ident.quote_style.is_some());
+            if let Some(table_name) = identifier::matching(
+                &input_name,
+                quoted,
+                query_ctx.options.case_sensitive,
+                query_ctx
+                    .pipeline_map
+                    .keys()
+                    .filter(|(idx, _)| *idx == pipeline_idx),
+                |(_, table_name)| table_name.as_str(),
+            )
+            .first()
+            {
+                input_name = table_name.1.clone();
+            }
             let alias_name = alias
                 .as_ref()
                 .map(|a| ExpressionBuilder::fullname_from_ident(&[a.name.clone()]));
@@ -609,12 +709,12 @@ pub fn get_from_source(
 mod tests {
     use dozer_core::app::AppPipeline;
 
-    use super::statement_to_pipeline;
+    use super::{statement_to_pipeline, SqlOptions};
     #[test]
     #[should_panic]
     fn disallow_zero_outgoing_ndes() {
         let sql = "select * from film";
-        statement_to_pipeline(sql, &mut AppPipeline::new(), None).unwrap();
+        statement_to_pipeline(sql, &mut AppPipeline::new(), None, SqlOptions::default()).unwrap();
     }
     #[test]
     fn parse_sql_pipeline() {
@@ -673,7 +773,9 @@ mod tests {
                 from  stocks join tbl on tbl.id = stocks.id;
             "#;
 
-        let context = statement_to_pipeline(sql, &mut AppPipeline::new(), None).unwrap();
+        let context =
+            statement_to_pipeline(sql, &mut AppPipeline::new(), None, SqlOptions::default())
+                .unwrap();
 
         // Should create as many output tables as into statements
         let mut output_keys = context.output_tables_map.keys().collect::<Vec<_>>();
@@ -689,4 +791,37 @@ mod tests {
         expected_keys.sort();
         assert_eq!(output_keys, expected_keys);
     }
+
+    #[test]
+    fn resolve_with_query_names_ignoring_case() {
+        let sql = r#"
+                with Tickers as (select id, ticker from stocks)
+                select id into tickers_table from TICKERS;
+
+                with "Tickers" as (select id, ticker from stocks)
+                select id into quoted_tickers_table from "TICKERS";
+            "#;
+
+        let context =
+            statement_to_pipeline(sql, &mut AppPipeline::new(), None, SqlOptions::default())
+                .unwrap();
+
+        // Quoted names only refer to the `WITH` query spelled exactly like them.
+        assert_eq!(context.used_sources, vec!["stocks", "stocks", "TICKERS"]);
+    }
+    #[test]
+    fn resolve_with_query_names_case_sensitive() {
+        let sql = r#"
+                with Tickers as (select id, ticker from stocks)
+                select id into tickers_table from TICKERS;
+            "#;
+
+        let options = SqlOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let context = statement_to_pipeline(sql, &mut AppPipeline::new(), None, options).unwrap();
+
+        assert_eq!(context.used_sources, vec!["stocks", "TICKERS"]);
+    }
 }
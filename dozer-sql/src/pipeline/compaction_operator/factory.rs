@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use dozer_core::plugin::OperatorOptions;
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::Schema;
+
+use super::processor::CompactionProcessor;
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{CompactionOperatorError, PipelineError};
+
+const DEFAULT_INTERVAL_MS: u64 = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionOperatorOptions {
+    pub interval: Duration,
+    /// `None` to use the input's primary key.
+    pub key_columns: Option<Vec<String>>,
+}
+
+impl CompactionOperatorOptions {
+    pub fn parse(options: &OperatorOptions) -> Result<Self, CompactionOperatorError> {
+        let interval_ms = match options.get("interval_ms") {
+            None => DEFAULT_INTERVAL_MS,
+            Some(interval_ms) => interval_ms
+                .parse()
+                .map_err(|_| CompactionOperatorError::InvalidInterval(interval_ms.clone()))?,
+        };
+        let key_columns = options.get("key_columns").map(|columns| {
+            columns
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        Ok(Self {
+            interval: Duration::from_millis(interval_ms),
+            key_columns,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompactionProcessorFactory {
+    id: String,
+    options: CompactionOperatorOptions,
+}
+
+impl CompactionProcessorFactory {
+    pub fn new(id: String, options: CompactionOperatorOptions) -> Self {
+        Self { id, options }
+    }
+
+    fn key_indexes(&self, schema: &Schema) -> Result<Vec<usize>, CompactionOperatorError> {
+        let key_indexes = match &self.options.key_columns {
+            None => schema.primary_index.clone(),
+            Some(columns) => columns
+                .iter()
+                .map(|column| {
+                    schema
+                        .get_field_index(column)
+                        .map(|(index, _)| index)
+                        .map_err(|_| CompactionOperatorError::UnknownKeyColumn(column.clone()))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        if key_indexes.is_empty() {
+            return Err(CompactionOperatorError::NoKey);
+        }
+        Ok(key_indexes)
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for CompactionProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let (schema, context) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        self.key_indexes(schema)?;
+        Ok((schema.clone(), context.clone()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok(Box::new(CompactionProcessor::new(
+            self.key_indexes(schema)?,
+            self.options.interval,
+        )))
+    }
+
+    fn type_name(&self) -> String {
+        "Compaction".to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
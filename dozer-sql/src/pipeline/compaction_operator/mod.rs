@@ -0,0 +1,41 @@
+//! An operator compacting bursts of changes to the same key into a single change, reducing the writes chatty
+//! sources cause in caches and sinks.
+//!
+//! Configured as operator `compaction`, with options:
+//! - `interval_ms`: how long changes to a key are held after its first pending change. Defaults to 1000.
+//! - `key_columns`: the columns identifying a record, separated by commas. Defaults to the input's primary key.
+//!
+//! The last write wins: once the interval ends, the record downstream last saw for the key is replaced by its latest
+//! record with a single update, insert or delete, or nothing if they're equal. Intervals are checked on every
+//! operation and commit, so a change is sent at most a commit timeout after its interval ends.
+
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::ProcessorFactory;
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+
+use crate::pipeline::builder::SchemaSQLContext;
+
+mod factory;
+mod processor;
+
+#[cfg(test)]
+mod tests;
+
+pub use factory::{CompactionOperatorOptions, CompactionProcessorFactory};
+
+/// Name the operator is registered under.
+pub const OPERATOR_NAME: &str = "compaction";
+
+pub fn register(registry: &mut OperatorRegistry<SchemaSQLContext>) -> Result<(), ExecutionError> {
+    registry.register(
+        OPERATOR_NAME.to_string(),
+        Box::new(|id: &str, options: &OperatorOptions| {
+            let factory: Box<dyn ProcessorFactory<SchemaSQLContext>> =
+                Box::new(CompactionProcessorFactory::new(
+                    id.to_string(),
+                    CompactionOperatorOptions::parse(options)?,
+                ));
+            Ok(factory)
+        }),
+    )
+}
@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::indexmap::IndexMap;
+use dozer_types::types::{Field, Operation, Record};
+
+/// The pending change of a key.
+#[derive(Debug)]
+struct Pending {
+    /// When the first pending change arrived.
+    since: Instant,
+    /// The record downstream last saw, if any.
+    old: Option<Record>,
+    /// The latest record, `None` if deleted.
+    new: Option<Record>,
+}
+
+#[derive(Debug)]
+pub struct CompactionProcessor {
+    key_indexes: Vec<usize>,
+    interval: Duration,
+    /// Ordered by the arrival of the first pending change, so due keys are at the front.
+    pending: IndexMap<Vec<Field>, Pending>,
+}
+
+impl CompactionProcessor {
+    pub fn new(key_indexes: Vec<usize>, interval: Duration) -> Self {
+        Self {
+            key_indexes,
+            interval,
+            pending: IndexMap::new(),
+        }
+    }
+
+    fn key(&self, record: &Record) -> Vec<Field> {
+        record.get_fields_by_indexes(&self.key_indexes)
+    }
+
+    fn pending(&mut self, key: Vec<Field>, old: Option<Record>) -> &mut Pending {
+        self.pending.entry(key).or_insert_with(|| Pending {
+            since: Instant::now(),
+            old,
+            new: None,
+        })
+    }
+
+    fn add(&mut self, op: Operation) {
+        match op {
+            Operation::Insert { new } => {
+                self.pending(self.key(&new), None).new = Some(new);
+            }
+            Operation::Delete { old } => {
+                self.pending(self.key(&old), Some(old)).new = None;
+            }
+            Operation::Update { old, new } => {
+                let (old_key, new_key) = (self.key(&old), self.key(&new));
+                if old_key == new_key {
+                    self.pending(new_key, Some(old)).new = Some(new);
+                } else {
+                    // Changing the key deletes the record of the old key and inserts one of the new key.
+                    self.pending(old_key, Some(old)).new = None;
+                    self.pending(new_key, None).new = Some(new);
+                }
+            }
+        }
+    }
+
+    /// Sends the changes of keys whose interval ended.
+    fn send_due(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        let now = Instant::now();
+        while let Some((_, pending)) = self.pending.get_index(0) {
+            if now.duration_since(pending.since) < self.interval {
+                break;
+            }
+            let (_, pending) = self.pending.shift_remove_index(0).expect("Checked above");
+            let op = match (pending.old, pending.new) {
+                (Some(old), Some(new)) if old != new => Operation::Update { old, new },
+                (Some(old), None) => Operation::Delete { old },
+                (None, Some(new)) => Operation::Insert { new },
+                _ => continue,
+            };
+            fw.send(record_store.create_operation(&op)?, DEFAULT_PORT_HANDLE);
+        }
+        Ok(())
+    }
+}
+
+impl Processor for CompactionProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.add(record_store.load_operation(&op)?);
+        self.send_due(record_store, fw)
+    }
+
+    fn flush(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.send_due(record_store, fw)
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_core::test_harness::{schema, Emitted, ProcessorTestHarness, ScriptStep};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use super::{CompactionOperatorOptions, CompactionProcessorFactory};
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::CompactionOperatorError;
+
+fn input_schema() -> Schema {
+    schema(&[("id", FieldType::Int), ("value", FieldType::Int)], &[0])
+}
+
+fn record(id: i64, value: i64) -> Record {
+    Record::new(vec![Field::Int(id), Field::Int(value)])
+}
+
+fn update(id: i64, old: i64, new: i64) -> ScriptStep {
+    ScriptStep::update(DEFAULT_PORT_HANDLE, record(id, old), record(id, new))
+}
+
+fn updated(id: i64, old: i64, new: i64) -> Emitted {
+    Emitted::update(DEFAULT_PORT_HANDLE, record(id, old), record(id, new))
+}
+
+fn factory(interval_ms: &str) -> CompactionProcessorFactory {
+    let options: OperatorOptions = [("interval_ms".to_string(), interval_ms.to_string())].into();
+    CompactionProcessorFactory::new(
+        "compaction".to_string(),
+        CompactionOperatorOptions::parse(&options).unwrap(),
+    )
+}
+
+fn harness(factory: &CompactionProcessorFactory) -> ProcessorTestHarness<'_, SchemaSQLContext> {
+    ProcessorTestHarness::single_input(factory, input_schema(), SchemaSQLContext::default())
+        .unwrap()
+}
+
+#[test]
+fn test_compaction() {
+    let factory = factory("200");
+    let mut harness = harness(&factory);
+
+    // Nothing is sent before the interval ends, even on commit.
+    harness.assert_output(
+        [
+            update(1, 0, 1),
+            update(1, 1, 2),
+            ScriptStep::insert(DEFAULT_PORT_HANDLE, record(2, 0)),
+            ScriptStep::delete(DEFAULT_PORT_HANDLE, record(2, 0)),
+            update(3, 0, 1),
+            update(3, 1, 0),
+            ScriptStep::delete(DEFAULT_PORT_HANDLE, record(4, 0)),
+            ScriptStep::insert(DEFAULT_PORT_HANDLE, record(4, 1)),
+            ScriptStep::Commit,
+        ],
+        &[],
+    );
+
+    std::thread::sleep(Duration::from_millis(250));
+    harness.assert_output([ScriptStep::Commit], &[updated(1, 0, 2), updated(4, 0, 1)]);
+}
+
+#[test]
+fn test_compaction_without_interval() {
+    let factory = factory("0");
+    harness(&factory).assert_output(
+        [update(1, 0, 1), update(1, 1, 2)],
+        &[updated(1, 0, 1), updated(1, 1, 2)],
+    );
+}
+
+#[test]
+fn test_compaction_invalid_options() {
+    let parse = |key: &str, value: &str| {
+        CompactionOperatorOptions::parse(&[(key.to_string(), value.to_string())].into())
+    };
+    assert!(matches!(
+        parse("interval_ms", "soon"),
+        Err(CompactionOperatorError::InvalidInterval(_))
+    ));
+
+    let factory = CompactionProcessorFactory::new(
+        "compaction".to_string(),
+        parse("key_columns", "name").unwrap(),
+    );
+    assert!(ProcessorTestHarness::single_input(
+        &factory,
+        input_schema(),
+        SchemaSQLContext::default()
+    )
+    .is_err());
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::Schema;
+
+use super::lookup::LookupClient;
+use super::options::EnrichmentOperatorOptions;
+use super::processor::EnrichmentProcessor;
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{EnrichmentOperatorError, PipelineError};
+
+#[derive(Debug)]
+pub struct EnrichmentProcessorFactory {
+    id: String,
+    options: EnrichmentOperatorOptions,
+}
+
+impl EnrichmentProcessorFactory {
+    pub fn new(id: String, options: EnrichmentOperatorOptions) -> Self {
+        Self { id, options }
+    }
+
+    /// The input columns followed by the appended ones, keeping the input's primary key.
+    fn output_schema(&self, input_schema: &Schema) -> Result<Schema, EnrichmentOperatorError> {
+        input_schema
+            .get_field_index(&self.options.key_column)
+            .map_err(|_| {
+                EnrichmentOperatorError::UnknownKeyColumn(self.options.key_column.clone())
+            })?;
+        let mut schema = input_schema.clone();
+        for field in &self.options.schema.fields {
+            if schema.get_field_index(&field.name).is_ok() {
+                return Err(EnrichmentOperatorError::DuplicateColumn(field.name.clone()));
+            }
+            schema.field(field.clone(), false);
+        }
+        Ok(schema)
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for EnrichmentProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let (input_schema, context) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok((self.output_schema(input_schema)?, context.clone()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        let (key_index, _) = input_schema
+            .get_field_index(&self.options.key_column)
+            .map_err(|_| {
+                EnrichmentOperatorError::UnknownKeyColumn(self.options.key_column.clone())
+            })?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(EnrichmentOperatorError::Runtime)?;
+        Ok(Box::new(EnrichmentProcessor::new(
+            self.options.clone(),
+            key_index,
+            LookupClient::new(&self.options)?,
+            runtime,
+        )))
+    }
+
+    fn type_name(&self) -> String {
+        "Enrichment".to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
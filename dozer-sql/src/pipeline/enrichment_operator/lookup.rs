@@ -0,0 +1,115 @@
+use std::fmt::{Debug, Formatter};
+
+use dozer_types::json_types::field_to_json_value;
+use dozer_types::serde_json::{self, Value};
+use dozer_types::types::{Field, Timezone};
+use redis::aio::MultiplexedConnection;
+use tokio::sync::OnceCell;
+
+use super::options::{EnrichmentOperatorOptions, EnrichmentSource};
+use crate::pipeline::errors::EnrichmentOperatorError;
+
+/// Looks up keys in the configured service, returning one JSON value for every key.
+pub enum LookupClient {
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+    Redis {
+        client: redis::Client,
+        /// Connected on the first lookup, inside the processor's runtime.
+        connection: OnceCell<MultiplexedConnection>,
+        key_prefix: String,
+    },
+}
+
+impl LookupClient {
+    pub fn new(options: &EnrichmentOperatorOptions) -> Result<Self, EnrichmentOperatorError> {
+        Ok(match options.source {
+            EnrichmentSource::Http => Self::Http {
+                client: reqwest::Client::new(),
+                url: options.url.clone(),
+            },
+            EnrichmentSource::Redis => Self::Redis {
+                client: redis::Client::open(options.url.as_str())?,
+                connection: OnceCell::new(),
+                key_prefix: options.key_prefix.clone(),
+            },
+        })
+    }
+
+    pub async fn lookup(&self, keys: &[Field]) -> Result<Vec<Value>, EnrichmentOperatorError> {
+        let values = match self {
+            Self::Http { client, url } => {
+                let keys = keys
+                    .iter()
+                    .map(|key| field_to_json_value(key.clone(), &Timezone::Utc))
+                    .collect::<Result<Vec<_>, _>>()?;
+                client
+                    .post(url)
+                    .json(&keys)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Vec<Value>>()
+                    .await?
+            }
+            Self::Redis {
+                client,
+                connection,
+                key_prefix,
+            } => {
+                let mut connection = connection
+                    .get_or_try_init(|| client.get_multiplexed_tokio_connection())
+                    .await?
+                    .clone();
+                let keys = keys
+                    .iter()
+                    .map(|key| format!("{key_prefix}{}", redis_key(key)))
+                    .collect::<Vec<_>>();
+                let values: Vec<Option<String>> = redis::cmd("MGET")
+                    .arg(keys)
+                    .query_async(&mut connection)
+                    .await?;
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        Some(value) => serde_json::from_str(&value),
+                        None => Ok(Value::Null),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(EnrichmentOperatorError::InvalidValue)?
+            }
+        };
+        if values.len() != keys.len() {
+            return Err(EnrichmentOperatorError::ResultCountMismatch(
+                keys.len(),
+                values.len(),
+            ));
+        }
+        Ok(values)
+    }
+}
+
+impl Debug for LookupClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http { url, .. } => f.debug_struct("Http").field("url", url).finish(),
+            Self::Redis {
+                client, key_prefix, ..
+            } => f
+                .debug_struct("Redis")
+                .field("client", client)
+                .field("key_prefix", key_prefix)
+                .finish(),
+        }
+    }
+}
+
+/// Strings are looked up as they are, rather than quoted.
+fn redis_key(key: &Field) -> String {
+    match key {
+        Field::String(key) | Field::Text(key) => key.clone(),
+        key => key.to_string(),
+    }
+}
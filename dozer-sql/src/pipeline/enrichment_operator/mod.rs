@@ -0,0 +1,56 @@
+//! An operator appending columns looked up by key in an external HTTP service or Redis, for data that isn't
+//! available in any ingested source.
+//!
+//! Configured as operator `enrichment`, with options:
+//! - `source`: `http` or `redis`.
+//! - `url`: the HTTP endpoint, or the Redis URL.
+//! - `key_column`: the input column whose value is looked up.
+//! - `key_prefix`: prepended to keys looked up in Redis. Optional.
+//! - `columns`: the appended columns, as `name:type` pairs separated by commas.
+//! - `batch_size`: how many keys are looked up with one request. Defaults to 100.
+//! - `concurrency`: how many requests may be in flight at once. Defaults to 4.
+//! - `cache_ttl_secs`: how long looked up values are cached. Defaults to 60, 0 disables the cache.
+//! - `timeout_ms`: how long a request may take. Defaults to 1000.
+//! - `on_error`: `null` to append nulls when a request fails or times out, or `fail` to stop the pipeline.
+//!   Defaults to `null`.
+//!
+//! The HTTP endpoint is sent a `POST` request with a JSON array of keys, and responds with a JSON array of the same
+//! length, holding an object of the appended columns or `null` for every key. Redis values are looked up with
+//! `MGET` and hold such objects as JSON strings. Columns missing from an object are null.
+//!
+//! Operations are buffered until there are enough for `concurrency` full requests, and before every commit. Deletes
+//! and updates retract the columns that were appended to the old record, even if the looked up values changed since.
+
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::ProcessorFactory;
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+
+use crate::pipeline::builder::SchemaSQLContext;
+
+mod factory;
+mod lookup;
+mod options;
+mod processor;
+
+#[cfg(test)]
+mod tests;
+
+pub use factory::EnrichmentProcessorFactory;
+pub use options::{EnrichmentOperatorOptions, EnrichmentSource, OnError};
+
+/// Name the operator is registered under.
+pub const OPERATOR_NAME: &str = "enrichment";
+
+pub fn register(registry: &mut OperatorRegistry<SchemaSQLContext>) -> Result<(), ExecutionError> {
+    registry.register(
+        OPERATOR_NAME.to_string(),
+        Box::new(|id: &str, options: &OperatorOptions| {
+            let factory: Box<dyn ProcessorFactory<SchemaSQLContext>> =
+                Box::new(EnrichmentProcessorFactory::new(
+                    id.to_string(),
+                    EnrichmentOperatorOptions::parse(options)?,
+                ));
+            Ok(factory)
+        }),
+    )
+}
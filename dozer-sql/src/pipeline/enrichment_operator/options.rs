@@ -0,0 +1,98 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::Schema;
+
+use crate::pipeline::errors::EnrichmentOperatorError;
+use crate::pipeline::operator_options::{parse_schema, required, ALL_TYPES, DEFAULT_BATCH_SIZE};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnrichmentSource {
+    Http,
+    Redis,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// Appends nulls for the keys of a failed request.
+    Null,
+    /// Fails the processor.
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichmentOperatorOptions {
+    pub source: EnrichmentSource,
+    pub url: String,
+    pub key_column: String,
+    pub key_prefix: String,
+    /// The appended columns.
+    pub schema: Schema,
+    pub batch_size: usize,
+    pub concurrency: usize,
+    pub cache_ttl: Duration,
+    pub timeout: Duration,
+    pub on_error: OnError,
+}
+
+impl EnrichmentOperatorOptions {
+    pub fn parse(options: &OperatorOptions) -> Result<Self, EnrichmentOperatorError> {
+        let source = match required(options, "source")? {
+            "http" => EnrichmentSource::Http,
+            "redis" => EnrichmentSource::Redis,
+            source => return Err(EnrichmentOperatorError::UnknownSource(source.to_string())),
+        };
+        let on_error = match options.get("on_error").map(String::as_str) {
+            None | Some("null") => OnError::Null,
+            Some("fail") => OnError::Fail,
+            Some(on_error) => {
+                return Err(EnrichmentOperatorError::UnknownOnError(
+                    on_error.to_string(),
+                ))
+            }
+        };
+        Ok(Self {
+            source,
+            url: required(options, "url")?.to_string(),
+            key_column: required(options, "key_column")?.to_string(),
+            key_prefix: options.get("key_prefix").cloned().unwrap_or_default(),
+            schema: parse_schema(options, &ALL_TYPES)?,
+            batch_size: parse_number(options, "batch_size", DEFAULT_BATCH_SIZE, 1)?,
+            concurrency: parse_number(options, "concurrency", DEFAULT_CONCURRENCY, 1)?,
+            cache_ttl: Duration::from_secs(parse_number(
+                options,
+                "cache_ttl_secs",
+                DEFAULT_CACHE_TTL_SECS,
+                0,
+            )?),
+            timeout: Duration::from_millis(parse_number(
+                options,
+                "timeout_ms",
+                DEFAULT_TIMEOUT_MS,
+                1,
+            )?),
+            on_error,
+        })
+    }
+}
+
+fn parse_number<T: FromStr + PartialOrd + From<u8>>(
+    options: &OperatorOptions,
+    name: &'static str,
+    default: T,
+    min: u8,
+) -> Result<T, EnrichmentOperatorError> {
+    match options.get(name) {
+        None => Ok(default),
+        Some(value) => value
+            .parse()
+            .ok()
+            .filter(|number| *number >= T::from(min))
+            .ok_or_else(|| EnrichmentOperatorError::InvalidNumber(name, value.clone())),
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::helper::json_value_to_field;
+use dozer_types::log::warn;
+use dozer_types::serde_json::Value;
+use dozer_types::types::{Field, Operation, Record};
+use futures::stream::{self, StreamExt};
+use tokio::runtime::Runtime;
+
+use super::lookup::LookupClient;
+use super::options::{EnrichmentOperatorOptions, OnError};
+use crate::pipeline::errors::{EnrichmentOperatorError, PipelineError};
+
+#[derive(Debug)]
+pub struct EnrichmentProcessor {
+    options: EnrichmentOperatorOptions,
+    key_index: usize,
+    client: LookupClient,
+    runtime: Runtime,
+    buffer: Vec<Operation>,
+    /// Appended values by key, with when they were looked up.
+    cache: HashMap<Field, (Vec<Field>, Instant)>,
+    /// Appended values of every live input record, so deletes and updates retract what was emitted.
+    emitted: HashMap<Vec<Field>, Vec<Vec<Field>>>,
+}
+
+impl EnrichmentProcessor {
+    pub fn new(
+        options: EnrichmentOperatorOptions,
+        key_index: usize,
+        client: LookupClient,
+        runtime: Runtime,
+    ) -> Self {
+        Self {
+            options,
+            key_index,
+            client,
+            runtime,
+            buffer: vec![],
+            cache: HashMap::new(),
+            emitted: HashMap::new(),
+        }
+    }
+
+    fn send_buffer(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        let ops = std::mem::take(&mut self.buffer);
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let cache_ttl = self.options.cache_ttl;
+        self.cache
+            .retain(|_, (_, looked_up)| now.duration_since(*looked_up) < cache_ttl);
+        // Old records retract what was emitted for them, so they only need a lookup if nothing was.
+        let mut keys = HashSet::new();
+        let mut live = HashMap::new();
+        for op in &ops {
+            let (old, new) = match op {
+                Operation::Insert { new } => (None, Some(new)),
+                Operation::Delete { old } => (Some(old), None),
+                Operation::Update { old, new } => (Some(old), Some(new)),
+            };
+            let emitted_count = |record: &Record| {
+                self.emitted
+                    .get(&record.values)
+                    .map_or(0, |emitted| emitted.len())
+            };
+            let mut needed = vec![];
+            if let Some(old) = old {
+                let count = live
+                    .entry(old.values.clone())
+                    .or_insert_with(|| emitted_count(old));
+                if *count > 0 {
+                    *count -= 1;
+                } else {
+                    needed.push(old);
+                }
+            }
+            if let Some(new) = new {
+                *live
+                    .entry(new.values.clone())
+                    .or_insert_with(|| emitted_count(new)) += 1;
+                needed.push(new);
+            }
+            for record in needed {
+                let key = &record.values[self.key_index];
+                if !self.cache.contains_key(key) {
+                    keys.insert(key.clone());
+                }
+            }
+        }
+        let looked_up = self.lookup(keys.into_iter().collect())?;
+
+        for op in ops {
+            let op = match op {
+                Operation::Insert { new } => Operation::Insert {
+                    new: self.enrich_new(new, &looked_up),
+                },
+                Operation::Delete { old } => Operation::Delete {
+                    old: self.enrich_old(old, &looked_up),
+                },
+                Operation::Update { old, new } => Operation::Update {
+                    old: self.enrich_old(old, &looked_up),
+                    new: self.enrich_new(new, &looked_up),
+                },
+            };
+            fw.send(record_store.create_operation(&op)?, DEFAULT_PORT_HANDLE);
+        }
+        Ok(())
+    }
+
+    /// Looks up `keys` with up to `concurrency` requests of up to `batch_size` keys in flight, caching the results
+    /// of successful requests.
+    fn lookup(&mut self, keys: Vec<Field>) -> Result<HashMap<Field, Vec<Field>>, PipelineError> {
+        let client = &self.client;
+        let timeout = self.options.timeout;
+        let responses = self.runtime.block_on(
+            stream::iter(keys.chunks(self.options.batch_size))
+                .map(|keys| async move {
+                    let values = tokio::time::timeout(timeout, client.lookup(keys))
+                        .await
+                        .unwrap_or(Err(EnrichmentOperatorError::Timeout(timeout)));
+                    (keys, values)
+                })
+                .buffer_unordered(self.options.concurrency)
+                .collect::<Vec<_>>(),
+        );
+
+        let now = Instant::now();
+        let mut looked_up = HashMap::new();
+        for (keys, values) in responses {
+            let values = values.and_then(|values| {
+                values
+                    .into_iter()
+                    .map(|value| self.parse_value(value))
+                    .collect::<Result<Vec<_>, _>>()
+            });
+            match values {
+                Ok(values) => {
+                    for (key, values) in keys.iter().zip(values) {
+                        if !self.options.cache_ttl.is_zero() {
+                            self.cache.insert(key.clone(), (values.clone(), now));
+                        }
+                        looked_up.insert(key.clone(), values);
+                    }
+                }
+                Err(e) if self.options.on_error == OnError::Null => {
+                    warn!("Appending nulls for {} keys: {e}", keys.len());
+                    let nulls = vec![Field::Null; self.options.schema.fields.len()];
+                    for key in keys {
+                        looked_up.insert(key.clone(), nulls.clone());
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(looked_up)
+    }
+
+    fn parse_value(&self, value: Value) -> Result<Vec<Field>, EnrichmentOperatorError> {
+        let mut object = match value {
+            Value::Null => Default::default(),
+            Value::Object(object) => object,
+            value => return Err(EnrichmentOperatorError::NotAnObject(value)),
+        };
+        self.options
+            .schema
+            .fields
+            .iter()
+            .map(|field| match object.remove(&field.name) {
+                Some(Value::Null) | None => Ok(Field::Null),
+                Some(value) => json_value_to_field(value, field.typ, true).map_err(Into::into),
+            })
+            .collect()
+    }
+
+    fn appended_values(
+        &self,
+        record: &Record,
+        looked_up: &HashMap<Field, Vec<Field>>,
+    ) -> Vec<Field> {
+        let key = &record.values[self.key_index];
+        self.cache
+            .get(key)
+            .map(|(values, _)| values)
+            .or_else(|| looked_up.get(key))
+            .expect("Every key is cached or looked up")
+            .clone()
+    }
+
+    fn enrich_new(&mut self, mut new: Record, looked_up: &HashMap<Field, Vec<Field>>) -> Record {
+        let values = self.appended_values(&new, looked_up);
+        self.emitted
+            .entry(new.values.clone())
+            .or_default()
+            .push(values.clone());
+        new.values.extend(values);
+        new
+    }
+
+    fn enrich_old(&mut self, mut old: Record, looked_up: &HashMap<Field, Vec<Field>>) -> Record {
+        let emitted = match self.emitted.get_mut(&old.values) {
+            Some(emitted) => {
+                let values = emitted.pop();
+                if emitted.is_empty() {
+                    self.emitted.remove(&old.values);
+                }
+                values
+            }
+            None => None,
+        };
+        // Records emitted before a restart weren't seen by this processor.
+        let values = emitted.unwrap_or_else(|| self.appended_values(&old, looked_up));
+        old.values.extend(values);
+        old
+    }
+}
+
+impl Processor for EnrichmentProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.buffer.push(record_store.load_operation(&op)?);
+        if self.buffer.len() >= self.options.batch_size * self.options.concurrency {
+            self.send_buffer(record_store, fw)?;
+        }
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.send_buffer(record_store, fw)
+    }
+}
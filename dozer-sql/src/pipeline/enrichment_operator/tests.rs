@@ -0,0 +1,191 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_core::test_harness::{schema, Emitted, ProcessorTestHarness, ScriptStep};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::serde_json::{self, json, Value};
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use super::{EnrichmentOperatorOptions, EnrichmentProcessorFactory, EnrichmentSource, OnError};
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{EnrichmentOperatorError, OperatorOptionsError};
+
+fn options(pairs: &[(&str, &str)]) -> OperatorOptions {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Serves lookups of user ids, answering `{"name": "user <id>"}`, and counts the requests.
+fn serve_users() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/users", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let keys: Vec<Value> = serde_json::from_slice(&body).unwrap();
+            let values = keys
+                .iter()
+                .map(|key| json!({ "name": format!("user {key}") }))
+                .collect::<Vec<_>>();
+            let response = serde_json::to_string(&values).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+        }
+    });
+    (url, requests)
+}
+
+fn input_schema() -> Schema {
+    schema(&[("user_id", FieldType::Int)], &[])
+}
+
+fn factory(url: &str, on_error: &str) -> EnrichmentProcessorFactory {
+    let options = EnrichmentOperatorOptions::parse(&options(&[
+        ("source", "http"),
+        ("url", url),
+        ("key_column", "user_id"),
+        ("columns", "name:string"),
+        ("on_error", on_error),
+    ]))
+    .unwrap();
+    EnrichmentProcessorFactory::new("enrichment".to_string(), options)
+}
+
+fn harness(factory: &EnrichmentProcessorFactory) -> ProcessorTestHarness<'_, SchemaSQLContext> {
+    ProcessorTestHarness::single_input(factory, input_schema(), SchemaSQLContext::default())
+        .unwrap()
+}
+
+fn insert(user_id: i64) -> ScriptStep {
+    ScriptStep::insert(DEFAULT_PORT_HANDLE, Record::new(vec![Field::Int(user_id)]))
+}
+
+fn enriched(user_id: i64, name: Option<&str>) -> Record {
+    Record::new(vec![
+        Field::Int(user_id),
+        name.map_or(Field::Null, |name| Field::String(name.to_string())),
+    ])
+}
+
+#[test]
+fn test_parse_options() {
+    let parsed = EnrichmentOperatorOptions::parse(&options(&[
+        ("source", "redis"),
+        ("url", "redis://localhost"),
+        ("key_column", "user_id"),
+        ("key_prefix", "user:"),
+        ("columns", "name:string, score:float"),
+        ("concurrency", "8"),
+        ("cache_ttl_secs", "0"),
+        ("on_error", "fail"),
+    ]))
+    .unwrap();
+    assert_eq!(parsed.source, EnrichmentSource::Redis);
+    assert_eq!(parsed.key_prefix, "user:");
+    assert_eq!(parsed.schema.fields.len(), 2);
+    assert_eq!(parsed.batch_size, 100);
+    assert_eq!(parsed.concurrency, 8);
+    assert!(parsed.cache_ttl.is_zero());
+    assert_eq!(parsed.on_error, OnError::Fail);
+
+    let parse = |pairs: &[(&str, &str)]| {
+        let mut pairs = pairs.to_vec();
+        pairs.extend([("url", "http://localhost"), ("key_column", "user_id")]);
+        EnrichmentOperatorOptions::parse(&options(&pairs)).unwrap_err()
+    };
+    assert!(matches!(
+        parse(&[("source", "ftp"), ("columns", "name:string")]),
+        EnrichmentOperatorError::UnknownSource(_)
+    ));
+    assert!(matches!(
+        parse(&[("source", "http")]),
+        EnrichmentOperatorError::Options(OperatorOptionsError::MissingOption("columns"))
+    ));
+    assert!(matches!(
+        parse(&[
+            ("source", "http"),
+            ("columns", "name:string"),
+            ("concurrency", "0")
+        ]),
+        EnrichmentOperatorError::InvalidNumber("concurrency", _)
+    ));
+}
+
+#[test]
+fn test_enrichment_caches_lookups_and_retracts_emitted_values() {
+    let (url, requests) = serve_users();
+    let factory = factory(&url, "fail");
+    let mut harness = harness(&factory);
+    assert_eq!(
+        harness.output_schema(DEFAULT_PORT_HANDLE).unwrap().fields[1].name,
+        "name"
+    );
+
+    harness.assert_output(
+        [
+            insert(1),
+            insert(2),
+            insert(1),
+            ScriptStep::Commit,
+            ScriptStep::delete(DEFAULT_PORT_HANDLE, Record::new(vec![Field::Int(2)])),
+            ScriptStep::Commit,
+        ],
+        &[
+            Emitted::insert(DEFAULT_PORT_HANDLE, enriched(1, Some("user 1"))),
+            Emitted::insert(DEFAULT_PORT_HANDLE, enriched(2, Some("user 2"))),
+            Emitted::insert(DEFAULT_PORT_HANDLE, enriched(1, Some("user 1"))),
+            Emitted::delete(DEFAULT_PORT_HANDLE, enriched(2, Some("user 2"))),
+        ],
+    );
+    // Both keys fit in one request, and the delete retracts what was emitted.
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_enrichment_lookup_failure() {
+    // Nothing listens on the port once the listener is dropped.
+    let url = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/users", listener.local_addr().unwrap())
+    };
+
+    let factory_null = factory(&url, "null");
+    harness(&factory_null).assert_output(
+        [insert(1), ScriptStep::Commit],
+        &[Emitted::insert(DEFAULT_PORT_HANDLE, enriched(1, None))],
+    );
+
+    let factory_fail = factory(&url, "fail");
+    assert!(harness(&factory_fail)
+        .run([insert(1), ScriptStep::Commit])
+        .is_err());
+}
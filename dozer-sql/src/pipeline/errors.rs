@@ -4,10 +4,12 @@ use dozer_core::node::PortHandle;
 use dozer_storage::errors::StorageError;
 use dozer_types::chrono::RoundingError;
 use dozer_types::errors::internal::BoxedError;
+#[cfg(any(feature = "javascript", feature = "enrichment"))]
+use dozer_types::errors::types::CannotConvertF64ToJson;
 use dozer_types::errors::types::TypeError;
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
-use dozer_types::types::{Field, FieldType};
+use dozer_types::types::{Currency, Field, FieldType};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone)]
@@ -88,6 +90,19 @@ pub enum PipelineError {
     #[cfg(feature = "python")]
     #[error("Python Error: {0}")]
     PythonErr(dozer_types::pyo3::PyErr),
+    #[cfg(feature = "python")]
+    #[error("Python operator: {0}")]
+    PythonOperatorError(#[from] PythonOperatorError),
+    #[cfg(feature = "javascript")]
+    #[error("JavaScript operator: {0}")]
+    JavaScriptOperatorError(#[from] JavaScriptOperatorError),
+    #[cfg(feature = "enrichment")]
+    #[error("Enrichment operator: {0}")]
+    EnrichmentOperatorError(#[from] EnrichmentOperatorError),
+    #[error("Compaction operator: {0}")]
+    CompactionOperatorError(#[from] CompactionOperatorError),
+    #[error("Reorder operator: {0}")]
+    ReorderOperatorError(#[from] ReorderOperatorError),
 
     // Error forwarding
     #[error("Internal type error: {0}")]
@@ -162,6 +177,9 @@ pub enum PipelineError {
     #[error("Window: {0}")]
     TableOperatorError(#[from] TableOperatorError),
 
+    #[error("The transform returned {1} results for {0} records")]
+    TransformResultCountMismatch(usize, usize),
+
     #[error("Invalid port handle: {0}")]
     InvalidPortHandle(PortHandle),
     #[error("JOIN processor received a Record from a wrong input: {0}")]
@@ -205,6 +223,8 @@ pub enum SqlError {
     WindowError(String),
     #[error("SQL Error: Invalid column name {0}.")]
     InvalidColumn(String),
+    #[error("SQL Error: Invalid column name {0}. Did you mean {1}?")]
+    MisspelledColumn(String, String),
     #[error(transparent)]
     Operation(#[from] OperationError),
 }
@@ -221,6 +241,8 @@ pub enum OperationError {
     DivisionByZeroOrOverflow,
     #[error("SQL Error: Modulo operation cannot be done.")]
     ModuloByZeroOrOverflow,
+    #[error("SQL Error: Amounts of {0} and {1} cannot be combined, convert them to the same currency first.")]
+    CurrencyMismatch(Currency, Currency),
 }
 
 #[derive(Error, Debug)]
@@ -393,3 +415,114 @@ pub enum TableOperatorError {
     #[error("Storage error")]
     Storage(#[from] StorageError),
 }
+
+#[derive(Error, Debug)]
+pub enum OperatorOptionsError {
+    #[error("Missing option {0}")]
+    MissingOption(&'static str),
+    #[error("Invalid column {0}, expected name:type")]
+    InvalidColumn(String),
+    #[error("Unsupported type {1} of column {0}")]
+    UnsupportedType(String, String),
+    #[error("Primary key column {0} is not an output column")]
+    UnknownPrimaryKey(String),
+    #[error("Invalid batch size {0}, expected a positive integer")]
+    InvalidBatchSize(String),
+}
+
+#[derive(Error, Debug)]
+pub enum CompactionOperatorError {
+    #[error("Invalid interval_ms {0}, expected a non-negative integer")]
+    InvalidInterval(String),
+    #[error("Key column {0} is not an input column")]
+    UnknownKeyColumn(String),
+    #[error("The input has no primary key, specify key_columns")]
+    NoKey,
+}
+
+#[derive(Error, Debug)]
+pub enum ReorderOperatorError {
+    #[error(transparent)]
+    Options(#[from] OperatorOptionsError),
+    #[error("Invalid max_delay_ms {0}, expected a non-negative integer")]
+    InvalidMaxDelay(String),
+    #[error("Invalid max_buffered {0}, expected a positive integer")]
+    InvalidMaxBuffered(String),
+    #[error("Column {0} is not an input column")]
+    UnknownColumn(String),
+    #[error("The input has no primary key, specify key_columns")]
+    NoKey,
+}
+
+#[cfg(feature = "python")]
+#[derive(Error, Debug)]
+pub enum PythonOperatorError {
+    #[error(transparent)]
+    Options(#[from] OperatorOptionsError),
+    #[error("Unknown mode {0}, expected record or batch")]
+    UnknownMode(String),
+}
+
+#[cfg(feature = "javascript")]
+#[derive(Error, Debug)]
+pub enum JavaScriptOperatorError {
+    #[error(transparent)]
+    Options(#[from] OperatorOptionsError),
+    #[error("Invalid function name {0}, expected a JavaScript identifier")]
+    InvalidFunctionName(String),
+    #[error("Invalid {0} {1}, expected a positive integer")]
+    InvalidNumber(&'static str, String),
+    #[error("Failed to read script {0}: {1}")]
+    ReadScript(String, #[source] std::io::Error),
+    #[error("Failed to start the JavaScript runtime: {0}")]
+    SpawnRuntime(#[source] std::io::Error),
+    #[error("Script error: {0}")]
+    Script(String),
+    #[error("The JavaScript runtime stopped")]
+    RuntimeStopped,
+    #[error("The script was terminated after running for {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("The script was terminated for exceeding the heap limit of {0} MB")]
+    HeapLimit(usize),
+    #[error("Invalid result of the transform function: {0}")]
+    InvalidResult(#[source] dozer_types::serde_json::Error),
+    #[error("The transform function returned {0}, expected an object or null")]
+    NotAnObject(dozer_types::serde_json::Value),
+    #[error(transparent)]
+    CannotConvertF64ToJson(#[from] CannotConvertF64ToJson),
+}
+
+#[cfg(feature = "enrichment")]
+#[derive(Error, Debug)]
+pub enum EnrichmentOperatorError {
+    #[error(transparent)]
+    Options(#[from] OperatorOptionsError),
+    #[error("Unknown source {0}, expected http or redis")]
+    UnknownSource(String),
+    #[error("Unknown on_error {0}, expected null or fail")]
+    UnknownOnError(String),
+    #[error("Invalid {0} {1}")]
+    InvalidNumber(&'static str, String),
+    #[error("Key column {0} is not an input column")]
+    UnknownKeyColumn(String),
+    #[error("Appended column {0} is already an input column")]
+    DuplicateColumn(String),
+    #[error("Failed to create the lookup runtime: {0}")]
+    Runtime(#[source] std::io::Error),
+    #[error("HTTP lookup failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Redis lookup failed: {0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("Lookup timed out after {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("Lookup returned {1} values for {0} keys")]
+    ResultCountMismatch(usize, usize),
+    #[error("Invalid looked up value: {0}")]
+    InvalidValue(#[source] dozer_types::serde_json::Error),
+    #[error("Lookup returned {0}, expected an object or null")]
+    NotAnObject(dozer_types::serde_json::Value),
+    #[error(transparent)]
+    CannotConvertF64ToJson(#[from] CannotConvertF64ToJson),
+    #[error(transparent)]
+    Type(#[from] TypeError),
+}
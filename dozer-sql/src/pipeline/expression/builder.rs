@@ -1,4 +1,5 @@
 use dozer_types::{
+    identifier,
     ordered_float::OrderedFloat,
     types::{Field, FieldDefinition, Schema, SourceDefinition},
 };
@@ -8,6 +9,7 @@ use sqlparser::ast::{
     UnaryOperator as SqlUnaryOperator, Value as SqlValue,
 };
 
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::errors::PipelineError::{
     InvalidArgument, InvalidExpression, InvalidFunction, InvalidNestedAggregationFunction,
     InvalidOperator, InvalidValue,
@@ -15,7 +17,7 @@ use crate::pipeline::errors::PipelineError::{
 use crate::pipeline::errors::{PipelineError, SqlError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::conditional::ConditionalExpressionType;
-use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::datetime::{date_trunc_field, DateTimeFunctionType};
 
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::execution::Expression::{
@@ -34,20 +36,23 @@ pub struct ExpressionBuilder {
     // Must be an aggregation function
     pub aggregations: Vec<Expression>,
     pub offset: usize,
+    pub options: SqlOptions,
 }
 
 impl ExpressionBuilder {
-    pub fn new(offset: usize) -> Self {
+    pub fn new(offset: usize, options: SqlOptions) -> Self {
         Self {
             aggregations: Vec::new(),
             offset,
+            options,
         }
     }
 
-    pub fn from(offset: usize, aggregations: Vec<Expression>) -> Self {
+    pub fn from(offset: usize, aggregations: Vec<Expression>, options: SqlOptions) -> Self {
         Self {
             aggregations,
             offset,
+            options,
         }
     }
 
@@ -78,8 +83,8 @@ impl ExpressionBuilder {
                 trim_what,
                 schema,
             ),
-            SqlExpr::Identifier(ident) => Self::parse_sql_column(&[ident.clone()], schema),
-            SqlExpr::CompoundIdentifier(ident) => Self::parse_sql_column(ident, schema),
+            SqlExpr::Identifier(ident) => self.parse_sql_column(&[ident.clone()], schema),
+            SqlExpr::CompoundIdentifier(ident) => self.parse_sql_column(ident, schema),
             SqlExpr::Value(SqlValue::Number(n, _)) => Self::parse_sql_number(n),
             SqlExpr::Value(SqlValue::Null) => Ok(Expression::Literal(Field::Null)),
             SqlExpr::Value(SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s)) => {
@@ -146,15 +151,15 @@ impl ExpressionBuilder {
         }
     }
 
-    fn parse_sql_column(ident: &[Ident], schema: &Schema) -> Result<Expression, PipelineError> {
+    fn parse_sql_column(
+        &self,
+        ident: &[Ident],
+        schema: &Schema,
+    ) -> Result<Expression, PipelineError> {
         let (src_field, src_table_or_alias, src_connection) = match ident.len() {
-            1 => (&ident[0].value, None, None),
-            2 => (&ident[1].value, Some(&ident[0].value), None),
-            3 => (
-                &ident[2].value,
-                Some(&ident[1].value),
-                Some(&ident[0].value),
-            ),
+            1 => (&ident[0], None, None),
+            2 => (&ident[1], Some(&ident[0]), None),
+            3 => (&ident[2], Some(&ident[1]), Some(&ident[0])),
             _ => {
                 return Err(PipelineError::SqlError(SqlError::InvalidColumn(
                     ident
@@ -170,34 +175,54 @@ impl ExpressionBuilder {
             .fields
             .iter()
             .enumerate()
-            .filter(|(_idx, f)| &f.name == src_field)
+            .filter(|(_idx, f)| self.ident_matches(src_field, &f.name))
             .collect();
 
+        if matching_by_field.is_empty() {
+            if let Some(suggestion) = identifier::did_you_mean(
+                &src_field.value,
+                schema.fields.iter().map(|f| f.name.as_str()),
+            ) {
+                return Err(PipelineError::SqlError(SqlError::MisspelledColumn(
+                    Self::fullname_from_ident(ident),
+                    suggestion.to_string(),
+                )));
+            }
+        }
+
         match matching_by_field.len() {
             1 => Ok(Expression::Column {
                 index: matching_by_field[0].0,
             }),
             _ => match src_table_or_alias {
-                None => Err(PipelineError::SqlError(SqlError::InvalidColumn(
-                    ident
-                        .iter()
-                        .map(|e| e.value.as_str())
-                        .collect::<Vec<&str>>()
-                        .join("."),
-                ))),
+                None => match self.narrow_columns(src_field, matching_by_field).as_slice() {
+                    [(index, _)] => Ok(Expression::Column { index: *index }),
+                    _ => Err(PipelineError::SqlError(SqlError::InvalidColumn(
+                        ident
+                            .iter()
+                            .map(|e| e.value.as_str())
+                            .collect::<Vec<&str>>()
+                            .join("."),
+                    ))),
+                },
                 Some(src_table_or_alias) => {
-                    let matching_by_table_or_alias: Vec<(usize, &FieldDefinition)> =
-                        matching_by_field
-                            .into_iter()
-                            .filter(|(_idx, field)| match &field.source {
-                                SourceDefinition::Alias { name } => name == src_table_or_alias,
-                                SourceDefinition::Table {
-                                    name,
-                                    connection: _,
-                                } => name == src_table_or_alias,
-                                _ => false,
-                            })
-                            .collect();
+                    let matching_by_table_or_alias: Vec<(usize, &FieldDefinition)> = self
+                        .narrow_columns(
+                            src_field,
+                            matching_by_field
+                                .into_iter()
+                                .filter(|(_idx, field)| match &field.source {
+                                    SourceDefinition::Alias { name } => {
+                                        self.ident_matches(src_table_or_alias, name)
+                                    }
+                                    SourceDefinition::Table {
+                                        name,
+                                        connection: _,
+                                    } => self.ident_matches(src_table_or_alias, name),
+                                    _ => false,
+                                })
+                                .collect(),
+                        );
 
                     match matching_by_table_or_alias.len() {
                         1 => Ok(Expression::Column {
@@ -217,7 +242,7 @@ impl ExpressionBuilder {
                                             SourceDefinition::Table {
                                                 name: _,
                                                 connection,
-                                            } => connection == src_connection,
+                                            } => self.ident_matches(src_connection, connection),
                                             _ => false,
                                         })
                                         .collect();
@@ -242,6 +267,21 @@ impl ExpressionBuilder {
         }
     }
 
+    /// Of fields only differing in case, keeps the ones spelled exactly like `ident`.
+    fn narrow_columns<'a>(
+        &self,
+        ident: &Ident,
+        fields: Vec<(usize, &'a FieldDefinition)>,
+    ) -> Vec<(usize, &'a FieldDefinition)> {
+        identifier::matching(
+            &ident.value,
+            ident.quote_style.is_some(),
+            self.options.case_sensitive,
+            fields,
+            |(_idx, f)| f.name.as_str(),
+        )
+    }
+
     fn parse_sql_trim_function(
         &mut self,
         parse_aggregations: bool,
@@ -351,13 +391,50 @@ impl ExpressionBuilder {
         }
     }
 
-    fn datetime_expr_check(&mut self, function_name: String) -> Result<Expression, PipelineError> {
-        match DateTimeFunctionType::new(function_name.as_str()) {
+    fn datetime_expr_check(
+        &mut self,
+        function_name: String,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+    ) -> Result<Expression, PipelineError> {
+        if function_name == "date_trunc" {
+            return self.parse_sql_date_trunc(parse_aggregations, sql_function, schema);
+        }
+        match DateTimeFunctionType::new(function_name.as_str(), self.options.timezone) {
             Ok(dtf) => Ok(Now { fun: dtf }),
             Err(_e) => Err(InvalidFunction(function_name)),
         }
     }
 
+    /// `DATE_TRUNC(unit, timestamp)`, whose unit must be a string literal.
+    fn parse_sql_date_trunc(
+        &mut self,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+    ) -> Result<Expression, PipelineError> {
+        let [unit, arg] = sql_function.args.as_slice() else {
+            return Err(if sql_function.args.len() > 2 {
+                PipelineError::TooManyArguments("DATE_TRUNC".to_string())
+            } else {
+                PipelineError::NotEnoughArguments("DATE_TRUNC".to_string())
+            });
+        };
+        let field = match self.parse_sql_function_arg(parse_aggregations, unit, schema)? {
+            Expression::Literal(Field::String(unit)) => date_trunc_field(&unit)
+                .ok_or_else(|| InvalidArgument(format!("DATE_TRUNC unit {unit}")))?,
+            unit => return Err(InvalidArgument(format!("DATE_TRUNC unit {unit:?}"))),
+        };
+        Ok(Expression::DateTimeFunction {
+            fun: DateTimeFunctionType::DateTrunc {
+                field,
+                timezone: self.options.timezone,
+            },
+            arg: Box::new(self.parse_sql_function_arg(parse_aggregations, arg, schema)?),
+        })
+    }
+
     fn json_func_check(
         &mut self,
         function_name: String,
@@ -455,7 +532,12 @@ impl ExpressionBuilder {
             return conditional_check;
         }
 
-        let datetime_check = self.datetime_expr_check(function_name.clone());
+        let datetime_check = self.datetime_expr_check(
+            function_name.clone(),
+            parse_aggregations,
+            sql_function,
+            schema,
+        );
         if datetime_check.is_ok() {
             return datetime_check;
         }
@@ -728,6 +810,10 @@ impl ExpressionBuilder {
         ident_tokens.join(".")
     }
 
+    fn ident_matches(&self, ident: &Ident, name: &str) -> bool {
+        ident_matches(ident, self.options.case_sensitive, name)
+    }
+
     pub(crate) fn normalize_ident(id: &Ident) -> String {
         match id.quote_style {
             Some(_) => id.value.clone(),
@@ -797,6 +883,16 @@ impl ExpressionBuilder {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct NameOrAlias(pub String, pub Option<String>);
 
+/// Whether the identifier refers to `name`, following the identifier rules of the app.
+pub fn ident_matches(ident: &Ident, case_sensitive: bool, name: &str) -> bool {
+    identifier::name_matches(
+        &ident.value,
+        ident.quote_style.is_some(),
+        case_sensitive,
+        name,
+    )
+}
+
 pub fn extend_schema_source_def(schema: &Schema, name: &NameOrAlias) -> Schema {
     let mut output_schema = schema.clone();
     let mut fields = vec![];
@@ -281,6 +281,7 @@ impl CastOperatorType {
                     FieldType::UInt,
                     FieldType::U128,
                     FieldType::Json,
+                    FieldType::Money,
                 ],
                 FieldType::String,
             ),
@@ -299,6 +300,7 @@ impl CastOperatorType {
                     FieldType::UInt,
                     FieldType::U128,
                     FieldType::Json,
+                    FieldType::Money,
                 ],
                 FieldType::Text,
             ),
@@ -1,8 +1,9 @@
-use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::SqlError::Operation;
+use crate::pipeline::errors::{OperationError, PipelineError};
 use dozer_types::chrono::{DateTime, NaiveDate};
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::Record;
-use dozer_types::types::{DozerDuration, DozerPoint, Field, Schema, TimeUnit};
+use dozer_types::types::{DozerDuration, DozerMoney, DozerPoint, Field, Schema, TimeUnit};
 use num_traits::cast::*;
 use std::str::FromStr;
 use std::time::Duration;
@@ -19,6 +20,14 @@ macro_rules! define_comparison {
         ) -> Result<Field, PipelineError> {
             let left_p = left.evaluate(&record, schema)?;
             let right_p = right.evaluate(&record, schema)?;
+            if let Some(result) = evaluate_money_comparison($op, &left_p, &right_p, $function) {
+                return result;
+            }
+            if let Some(result) =
+                evaluate_enum_comparison($op, schema, (left, &left_p), (right, &right_p), $function)
+            {
+                return result;
+            }
 
             match left_p {
                 Field::Null => Ok(Field::Null),
@@ -103,6 +112,8 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_)
                     | Field::Null => Ok(Field::Null),
                 },
@@ -148,7 +159,9 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -196,7 +209,9 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -242,7 +257,9 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -292,7 +309,9 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -333,6 +352,8 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -405,6 +426,8 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -494,11 +517,9 @@ macro_rules! define_comparison {
                         })?;
                         Ok(Field::Boolean($function(left_val, right_v)))
                     }
-                    Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
+                    Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+                        PipelineError::InvalidTypeComparison(left_p, right_p, $op.to_string()),
+                    ),
                 },
                 Field::Timestamp(left_v) => match right_p {
                     Field::Timestamp(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
@@ -523,6 +544,8 @@ macro_rules! define_comparison {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -553,6 +576,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -579,7 +604,9 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Date(_)
-                    | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Duration(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
@@ -609,17 +636,17 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Json(_)
                     | Field::Date(_)
-                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                    | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
                     )),
                 },
-                Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                    left_p,
-                    right_p,
-                    $op.to_string(),
-                )),
+                Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+                    PipelineError::InvalidTypeComparison(left_p, right_p, $op.to_string()),
+                ),
             }
         }
     };
@@ -635,6 +662,16 @@ pub fn evaluate_lt(
 ) -> Result<Field, PipelineError> {
     let left_p = left.evaluate(record, schema)?;
     let right_p = right.evaluate(record, schema)?;
+    if let Some(result) = evaluate_money_comparison("<", &left_p, &right_p, |l, r| l < r) {
+        return result;
+    }
+    if let Some(result) =
+        evaluate_enum_comparison("<", schema, (left, &left_p), (right, &right_p), |l, r| {
+            l < r
+        })
+    {
+        return result;
+    }
 
     match left_p {
         Field::Null => Ok(Field::Null),
@@ -695,6 +732,8 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
         },
@@ -737,7 +776,9 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -782,7 +823,9 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -825,7 +868,9 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -869,7 +914,9 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -909,6 +956,8 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -969,6 +1018,8 @@ pub fn evaluate_lt(
                 | Field::Date(_)
                 | Field::Json(_)
                 | Field::Point(_)
+                | Field::Money(_)
+                | Field::Enum(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -1046,11 +1097,9 @@ pub fn evaluate_lt(
                 })?;
                 Ok(Field::Boolean(left_val < right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, "<".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v < right_v)),
@@ -1072,6 +1121,8 @@ pub fn evaluate_lt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1098,6 +1149,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1124,7 +1177,9 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
@@ -1150,17 +1205,17 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 "<".to_string(),
             )),
         },
-        Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            "<".to_string(),
-        )),
+        Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+            PipelineError::InvalidTypeComparison(left_p, right_p, "<".to_string()),
+        ),
     }
 }
 
@@ -1172,6 +1227,16 @@ pub fn evaluate_gt(
 ) -> Result<Field, PipelineError> {
     let left_p = left.evaluate(record, schema)?;
     let right_p = right.evaluate(record, schema)?;
+    if let Some(result) = evaluate_money_comparison(">", &left_p, &right_p, |l, r| l > r) {
+        return result;
+    }
+    if let Some(result) =
+        evaluate_enum_comparison(">", schema, (left, &left_p), (right, &right_p), |l, r| {
+            l > r
+        })
+    {
+        return result;
+    }
 
     match left_p {
         Field::Null => Ok(Field::Null),
@@ -1229,6 +1294,8 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
         },
@@ -1271,7 +1338,9 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1316,7 +1385,9 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1359,7 +1430,9 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1403,7 +1476,9 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1443,6 +1518,8 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1503,6 +1580,8 @@ pub fn evaluate_gt(
                 | Field::Date(_)
                 | Field::Json(_)
                 | Field::Point(_)
+                | Field::Money(_)
+                | Field::Enum(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -1580,11 +1659,9 @@ pub fn evaluate_gt(
                 })?;
                 Ok(Field::Boolean(left_val > right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, ">".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v > right_v)),
@@ -1606,6 +1683,8 @@ pub fn evaluate_gt(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1632,6 +1711,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1658,7 +1739,9 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
@@ -1684,20 +1767,91 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Json(_)
             | Field::Date(_)
-            | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+            | Field::Point(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
                 ">".to_string(),
             )),
         },
-        Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            ">".to_string(),
-        )),
+        Field::Binary(_) | Field::Json(_) | Field::Money(_) | Field::Enum(_) => Err(
+            PipelineError::InvalidTypeComparison(left_p, right_p, ">".to_string()),
+        ),
     }
 }
 
+/// Compares the amounts of money of the same currency, or `None` if neither side is money. Strings are parsed as
+/// money, e.g. `'12.50 USD'`.
+fn evaluate_money_comparison(
+    op: &str,
+    left: &Field,
+    right: &Field,
+    function: impl Fn(Decimal, Decimal) -> bool,
+) -> Option<Result<Field, PipelineError>> {
+    let parse = |field: &Field| match field {
+        Field::Money(money) => Ok(*money),
+        Field::String(value) | Field::Text(value) => DozerMoney::from_str(value)
+            .map_err(|_| PipelineError::UnableToCast(value.clone(), "Money".to_string())),
+        _ => Err(PipelineError::InvalidTypeComparison(
+            left.clone(),
+            right.clone(),
+            op.to_string(),
+        )),
+    };
+    let result = match (left, right) {
+        (Field::Money(_), Field::Null) | (Field::Null, Field::Money(_)) => Ok(Field::Null),
+        (Field::Money(_), _) | (_, Field::Money(_)) => parse(left).and_then(|left_v| {
+            let right_v = parse(right)?;
+            if left_v.currency != right_v.currency {
+                return Err(PipelineError::SqlError(Operation(
+                    OperationError::CurrencyMismatch(left_v.currency, right_v.currency),
+                )));
+            }
+            Ok(Field::Boolean(function(left_v.amount, right_v.amount)))
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Compares enums by their ordinals, i.e. in the order of their declared values, or `None` if neither side is an
+/// enum. A string compared to an enum column is one of the column's declared values, e.g. `status = 'active'`.
+fn evaluate_enum_comparison(
+    op: &str,
+    schema: &Schema,
+    (left, left_p): (&Expression, &Field),
+    (right, right_p): (&Expression, &Field),
+    function: impl Fn(u32, u32) -> bool,
+) -> Option<Result<Field, PipelineError>> {
+    let ordinal = |field: &Field, other: &Expression| match field {
+        Field::Enum(ordinal) => Ok(*ordinal),
+        Field::String(value) | Field::Text(value) => {
+            let definition = match other {
+                Expression::Column { index } => schema.fields.get(*index),
+                _ => None,
+            };
+            definition
+                .and_then(|definition| definition.enum_ordinal(value))
+                .ok_or_else(|| PipelineError::UnableToCast(value.clone(), "Enum".to_string()))
+        }
+        _ => Err(PipelineError::InvalidTypeComparison(
+            left_p.clone(),
+            right_p.clone(),
+            op.to_string(),
+        )),
+    };
+    let result = match (left_p, right_p) {
+        (Field::Enum(_), Field::Null) | (Field::Null, Field::Enum(_)) => Ok(Field::Null),
+        (Field::Enum(_), _) | (_, Field::Enum(_)) => ordinal(left_p, right).and_then(|left_v| {
+            let right_v = ordinal(right_p, left)?;
+            Ok(Field::Boolean(function(left_v, right_v)))
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
 define_comparison!(evaluate_eq, "=", |l, r| { l == r });
 define_comparison!(evaluate_ne, "!=", |l, r| { l != r });
 define_comparison!(evaluate_lte, "<=", |l, r| { l <= r });
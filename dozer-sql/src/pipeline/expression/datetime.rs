@@ -5,14 +5,14 @@ use crate::pipeline::errors::{FieldTypes, PipelineError};
 use crate::pipeline::expression::datetime::PipelineError::InvalidValue;
 use crate::pipeline::expression::execution::{Expression, ExpressionType};
 
-use dozer_types::chrono::{DateTime, Datelike, FixedOffset, Offset, Timelike, Utc};
+use dozer_types::chrono::{DateTime, Datelike, Duration, FixedOffset, Offset, Timelike, Utc};
 use dozer_types::types::Record;
-use dozer_types::types::{DozerDuration, Field, FieldType, Schema, TimeUnit};
+use dozer_types::types::{DozerDuration, Field, FieldType, Schema, TimeUnit, Timezone};
 use num_traits::ToPrimitive;
 use sqlparser::ast::DateTimeField;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DateTimeFunctionType {
     Extract {
         field: sqlparser::ast::DateTimeField,
@@ -20,7 +20,15 @@ pub enum DateTimeFunctionType {
     Interval {
         field: sqlparser::ast::DateTimeField,
     },
-    Now,
+    /// Truncates to the start of the `field`, in `timezone`.
+    DateTrunc {
+        field: sqlparser::ast::DateTimeField,
+        timezone: Timezone,
+    },
+    /// The current time in `timezone`.
+    Now { timezone: Timezone },
+    /// The current date in `timezone`.
+    CurrentDate { timezone: Timezone },
 }
 
 impl Display for DateTimeFunctionType {
@@ -32,7 +40,11 @@ impl Display for DateTimeFunctionType {
             DateTimeFunctionType::Interval { field } => {
                 f.write_str(format!("INTERVAL {field}").as_str())
             }
-            DateTimeFunctionType::Now => f.write_str("NOW".to_string().as_str()),
+            DateTimeFunctionType::DateTrunc { field, .. } => {
+                f.write_str(format!("DATE_TRUNC {field}").as_str())
+            }
+            DateTimeFunctionType::Now { .. } => f.write_str("NOW".to_string().as_str()),
+            DateTimeFunctionType::CurrentDate { .. } => f.write_str("CURRENT_DATE"),
         }
     }
 }
@@ -73,8 +85,16 @@ pub(crate) fn get_datetime_function_type(
             dozer_types::types::SourceDefinition::Dynamic,
             false,
         )),
-        DateTimeFunctionType::Now => Ok(ExpressionType::new(
-            FieldType::Timestamp,
+        DateTimeFunctionType::DateTrunc { .. } | DateTimeFunctionType::Now { .. } => {
+            Ok(ExpressionType::new(
+                FieldType::Timestamp,
+                false,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        DateTimeFunctionType::CurrentDate { .. } => Ok(ExpressionType::new(
+            FieldType::Date,
             false,
             dozer_types::types::SourceDefinition::Dynamic,
             false,
@@ -83,9 +103,13 @@ pub(crate) fn get_datetime_function_type(
 }
 
 impl DateTimeFunctionType {
-    pub(crate) fn new(name: &str) -> Result<DateTimeFunctionType, PipelineError> {
+    pub(crate) fn new(
+        name: &str,
+        timezone: Timezone,
+    ) -> Result<DateTimeFunctionType, PipelineError> {
         match name {
-            "now" => Ok(DateTimeFunctionType::Now),
+            "now" => Ok(DateTimeFunctionType::Now { timezone }),
+            "current_date" => Ok(DateTimeFunctionType::CurrentDate { timezone }),
             _ => Err(InvalidFunction(name.to_string())),
         }
     }
@@ -103,15 +127,100 @@ impl DateTimeFunctionType {
             DateTimeFunctionType::Interval { field } => {
                 evaluate_interval(schema, field, arg, record)
             }
-            DateTimeFunctionType::Now => self.evaluate_now(),
+            DateTimeFunctionType::DateTrunc { field, timezone } => {
+                evaluate_date_trunc(schema, field, timezone, arg, record)
+            }
+            DateTimeFunctionType::Now { .. } | DateTimeFunctionType::CurrentDate { .. } => {
+                self.evaluate_now()
+            }
         }
     }
 
+    /// The current time or date in the time zone of the function.
     pub(crate) fn evaluate_now(&self) -> Result<Field, PipelineError> {
-        Ok(Field::Timestamp(DateTime::<FixedOffset>::from(Utc::now())))
+        match self {
+            DateTimeFunctionType::Now { timezone } => Ok(Field::Timestamp(timezone.now())),
+            DateTimeFunctionType::CurrentDate { timezone } => {
+                Ok(Field::Date(timezone.now().date_naive()))
+            }
+            _ => Err(InvalidFunction(self.to_string())),
+        }
+    }
+}
+
+/// The units `DATE_TRUNC` can truncate to.
+pub(crate) fn date_trunc_field(unit: &str) -> Option<DateTimeField> {
+    match unit.to_lowercase().as_str() {
+        "year" => Some(DateTimeField::Year),
+        "quarter" => Some(DateTimeField::Quarter),
+        "month" => Some(DateTimeField::Month),
+        "week" => Some(DateTimeField::Week),
+        "day" => Some(DateTimeField::Day),
+        "hour" => Some(DateTimeField::Hour),
+        "minute" => Some(DateTimeField::Minute),
+        "second" => Some(DateTimeField::Second),
+        _ => None,
     }
 }
 
+/// Truncates a timestamp to the start of its `field` in `timezone`, e.g. the midnight of its day there. Weeks start
+/// on Monday.
+pub(crate) fn date_trunc(
+    ts: &DateTime<FixedOffset>,
+    field: &DateTimeField,
+    timezone: &Timezone,
+) -> Option<DateTime<FixedOffset>> {
+    let local = timezone.convert(ts).naive_local();
+    let date = local.date();
+    let truncated = match field {
+        DateTimeField::Year => date.with_ordinal(1)?.and_hms_opt(0, 0, 0)?,
+        DateTimeField::Quarter => date
+            .with_day(1)?
+            .with_month(date.month0() / 3 * 3 + 1)?
+            .and_hms_opt(0, 0, 0)?,
+        DateTimeField::Month => date.with_day(1)?.and_hms_opt(0, 0, 0)?,
+        DateTimeField::Week => (date
+            - Duration::days(date.weekday().num_days_from_monday() as i64))
+        .and_hms_opt(0, 0, 0)?,
+        DateTimeField::Day => date.and_hms_opt(0, 0, 0)?,
+        DateTimeField::Hour => date.and_hms_opt(local.hour(), 0, 0)?,
+        DateTimeField::Minute => date.and_hms_opt(local.hour(), local.minute(), 0)?,
+        DateTimeField::Second => date.and_hms_opt(local.hour(), local.minute(), local.second())?,
+        _ => return None,
+    };
+    Some(timezone.resolve_local(&truncated))
+}
+
+pub(crate) fn evaluate_date_trunc(
+    schema: &Schema,
+    field: &DateTimeField,
+    timezone: &Timezone,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = arg.evaluate(record, schema)?;
+    let ts = match &value {
+        Field::Timestamp(ts) => *ts,
+        // Dates are local dates of the time zone.
+        Field::Date(date) => timezone.resolve_local(&date.and_hms_opt(0, 0, 0).unwrap()),
+        Field::Null => return Ok(Field::Null),
+        _ => {
+            return Err(InvalidFunctionArgument(
+                DateTimeFunctionType::DateTrunc {
+                    field: *field,
+                    timezone: *timezone,
+                }
+                .to_string(),
+                value,
+                0,
+            ))
+        }
+    };
+    date_trunc(&ts, field, timezone)
+        .map(Field::Timestamp)
+        .ok_or_else(|| InvalidValue(format!("Unable to truncate {value} to {field}")))
+}
+
 pub(crate) fn evaluate_date_part(
     schema: &Schema,
     field: &sqlparser::ast::DateTimeField,
@@ -141,6 +250,8 @@ pub(crate) fn evaluate_date_part(
         | Field::Json(_)
         | Field::Point(_)
         | Field::Duration(_)
+        | Field::Money(_)
+        | Field::Enum(_)
         | Field::Null => {
             return Err(InvalidFunctionArgument(
                 DateTimeFunctionType::Extract { field: *field }.to_string(),
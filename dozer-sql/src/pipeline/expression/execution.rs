@@ -351,6 +351,14 @@ impl Expression {
         }
     }
 
+    /// The declared values of an enum this expression evaluates to, which only columns keep.
+    pub fn enum_values(&self, schema: &Schema) -> Vec<String> {
+        match self {
+            Expression::Column { index } => schema.fields[*index].enum_values.clone(),
+            _ => vec![],
+        }
+    }
+
     pub fn get_type(&self, schema: &Schema) -> Result<ExpressionType, PipelineError> {
         match self {
             Expression::Literal(field) => {
@@ -417,8 +425,12 @@ impl Expression {
             Expression::DateTimeFunction { fun, arg } => {
                 get_datetime_function_type(fun, arg, schema)
             }
-            Expression::Now { fun: _ } => Ok(ExpressionType::new(
-                FieldType::Timestamp,
+            Expression::Now { fun } => Ok(ExpressionType::new(
+                if matches!(fun, DateTimeFunctionType::CurrentDate { .. }) {
+                    FieldType::Date
+                } else {
+                    FieldType::Timestamp
+                },
                 false,
                 dozer_types::types::SourceDefinition::Dynamic,
                 false,
@@ -471,6 +483,8 @@ fn get_field_type(field: &Field) -> Option<FieldType> {
         Field::Date(_) => Some(FieldType::Date),
         Field::Point(_) => Some(FieldType::Point),
         Field::Duration(_) => Some(FieldType::Duration),
+        Field::Money(_) => Some(FieldType::Money),
+        Field::Enum(_) => Some(FieldType::Enum),
         Field::Null => None,
     }
 }
@@ -601,6 +615,39 @@ fn get_binary_operator_type(
                     SourceDefinition::Dynamic,
                     false,
                 )),
+                (FieldType::Money, FieldType::Money)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Money,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (
+                    FieldType::Money,
+                    FieldType::UInt
+                    | FieldType::U128
+                    | FieldType::Int
+                    | FieldType::I128
+                    | FieldType::Float
+                    | FieldType::Decimal,
+                )
+                | (
+                    FieldType::UInt
+                    | FieldType::U128
+                    | FieldType::Int
+                    | FieldType::I128
+                    | FieldType::Float
+                    | FieldType::Decimal,
+                    FieldType::Money,
+                ) if matches!(operator, BinaryOperatorType::Mul) => Ok(ExpressionType::new(
+                    FieldType::Money,
+                    false,
+                    SourceDefinition::Dynamic,
+                    false,
+                )),
                 (FieldType::Int, FieldType::Int)
                 | (FieldType::Int, FieldType::UInt)
                 | (FieldType::UInt, FieldType::Int) => Ok(ExpressionType::new(
@@ -701,12 +748,27 @@ fn get_binary_operator_type(
                 | (FieldType::U128, FieldType::Decimal)
                 | (FieldType::Int, FieldType::Decimal)
                 | (FieldType::I128, FieldType::Decimal)
-                | (FieldType::Float, FieldType::Decimal) => Ok(ExpressionType::new(
+                | (FieldType::Float, FieldType::Decimal)
+                | (FieldType::Money, FieldType::Money) => Ok(ExpressionType::new(
                     FieldType::Decimal,
                     false,
                     SourceDefinition::Dynamic,
                     false,
                 )),
+                (
+                    FieldType::Money,
+                    FieldType::UInt
+                    | FieldType::U128
+                    | FieldType::Int
+                    | FieldType::I128
+                    | FieldType::Float
+                    | FieldType::Decimal,
+                ) => Ok(ExpressionType::new(
+                    FieldType::Money,
+                    false,
+                    SourceDefinition::Dynamic,
+                    false,
+                )),
                 (left_field_type, right_field_type) => {
                     Err(PipelineError::InvalidExpression(format!(
                         "cannot apply {operator:?} to {left_field_type:?} and {right_field_type:?}"
@@ -29,7 +29,9 @@ pub fn evaluate_and(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
         },
         Field::Boolean(false) => match r_field {
             Field::Boolean(true) => Ok(Field::Boolean(false)),
@@ -48,7 +50,9 @@ pub fn evaluate_and(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
         },
         Field::Null => Ok(Field::Boolean(false)),
         Field::UInt(_)
@@ -64,7 +68,9 @@ pub fn evaluate_and(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
-        | Field::Duration(_) => Err(PipelineError::InvalidType(l_field, "AND".to_string())),
+        | Field::Duration(_)
+        | Field::Money(_)
+        | Field::Enum(_) => Err(PipelineError::InvalidType(l_field, "AND".to_string())),
     }
 }
 
@@ -94,7 +100,9 @@ pub fn evaluate_or(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
         },
         Field::Boolean(false) | Field::Null => match right.evaluate(record, schema)? {
             Field::Boolean(false) => Ok(Field::Boolean(false)),
@@ -113,7 +121,9 @@ pub fn evaluate_or(
             | Field::Date(_)
             | Field::Json(_)
             | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
+            | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
         },
         Field::UInt(_)
         | Field::U128(_)
@@ -128,7 +138,9 @@ pub fn evaluate_or(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
-        | Field::Duration(_) => Err(PipelineError::InvalidType(l_field, "OR".to_string())),
+        | Field::Duration(_)
+        | Field::Money(_)
+        | Field::Enum(_) => Err(PipelineError::InvalidType(l_field, "OR".to_string())),
     }
 }
 
@@ -155,6 +167,8 @@ pub fn evaluate_not(
         | Field::Date(_)
         | Field::Json(_)
         | Field::Point(_)
-        | Field::Duration(_) => Err(PipelineError::InvalidType(value_p, "NOT".to_string())),
+        | Field::Duration(_)
+        | Field::Money(_)
+        | Field::Enum(_) => Err(PipelineError::InvalidType(value_p, "NOT".to_string())),
     }
 }
@@ -5,7 +5,7 @@ use crate::pipeline::expression::execution::Expression;
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::Record;
 use dozer_types::types::Schema;
-use dozer_types::types::{DozerDuration, TimeUnit};
+use dozer_types::types::{DozerDuration, DozerMoney, TimeUnit};
 use dozer_types::{chrono, ordered_float::OrderedFloat, types::Field};
 use num_traits::{FromPrimitive, Zero};
 use std::num::Wrapping;
@@ -21,6 +21,9 @@ macro_rules! define_math_operator {
         ) -> Result<Field, PipelineError> {
             let left_p = left.evaluate(&record, schema)?;
             let right_p = right.evaluate(&record, schema)?;
+            if let Some(result) = evaluate_money_operator($op, &left_p, &right_p) {
+                return result;
+            }
 
             match left_p {
                 Field::Duration(left_v) => {
@@ -88,6 +91,8 @@ macro_rules! define_math_operator {
                         | Field::Date(_)
                         | Field::Json(_)
                         | Field::Point(_)
+                        | Field::Money(_)
+                        | Field::Enum(_)
                         | Field::Null => Err(PipelineError::InvalidTypeComparison(
                             left_p,
                             right_p,
@@ -173,6 +178,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Null => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -378,6 +385,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -625,6 +634,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -850,6 +861,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1120,6 +1133,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1361,6 +1376,8 @@ macro_rules! define_math_operator {
                     | Field::Date(_)
                     | Field::Json(_)
                     | Field::Point(_)
+                    | Field::Money(_)
+                    | Field::Enum(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -1492,6 +1509,8 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Money(_)
+                                | Field::Enum(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1582,6 +1601,8 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Money(_)
+                                | Field::Enum(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1672,6 +1693,8 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Money(_)
+                                | Field::Enum(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1745,6 +1768,8 @@ macro_rules! define_math_operator {
                                 | Field::Date(_)
                                 | Field::Json(_)
                                 | Field::Point(_)
+                                | Field::Money(_)
+                                | Field::Enum(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
                                     right_p,
@@ -1767,7 +1792,9 @@ macro_rules! define_math_operator {
                 | Field::Binary(_)
                 | Field::Date(_)
                 | Field::Json(_)
-                | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                | Field::Point(_)
+                | Field::Money(_)
+                | Field::Enum(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
                     $op.to_string(),
@@ -1777,6 +1804,81 @@ macro_rules! define_math_operator {
     };
 }
 
+/// The result of `op` if either operand is money, which can be added to, subtracted from or divided by money of the
+/// same currency, and multiplied or divided by a number.
+fn evaluate_money_operator(
+    op: &str,
+    left: &Field,
+    right: &Field,
+) -> Option<Result<Field, PipelineError>> {
+    let invalid = || {
+        Err(PipelineError::InvalidTypeComparison(
+            left.clone(),
+            right.clone(),
+            op.to_string(),
+        ))
+    };
+    let operation_error = |error| PipelineError::SqlError(Operation(error));
+    let result = match (left, right) {
+        (Field::Money(_), Field::Null) | (Field::Null, Field::Money(_)) => Ok(Field::Null),
+        (Field::Money(left_v), Field::Money(right_v)) => {
+            if left_v.currency != right_v.currency {
+                return Some(Err(operation_error(OperationError::CurrencyMismatch(
+                    left_v.currency,
+                    right_v.currency,
+                ))));
+            }
+            let money = |amount| Field::Money(DozerMoney::new(amount, left_v.currency));
+            match op {
+                "+" => left_v
+                    .amount
+                    .checked_add(right_v.amount)
+                    .map(money)
+                    .ok_or(operation_error(OperationError::AdditionOverflow)),
+                "-" => left_v
+                    .amount
+                    .checked_sub(right_v.amount)
+                    .map(money)
+                    .ok_or(operation_error(OperationError::SubtractionOverflow)),
+                "/" => left_v
+                    .amount
+                    .checked_div(right_v.amount)
+                    .map(Field::Decimal)
+                    .ok_or(operation_error(OperationError::DivisionByZeroOrOverflow)),
+                _ => invalid(),
+            }
+        }
+        (Field::Money(money), number) | (number, Field::Money(money)) => {
+            let factor = match number {
+                Field::UInt(_)
+                | Field::U128(_)
+                | Field::Int(_)
+                | Field::I128(_)
+                | Field::Float(_)
+                | Field::Decimal(_) => number.to_decimal(),
+                _ => None,
+            };
+            let Some(factor) = factor else {
+                return Some(invalid());
+            };
+            let amount = match (op, left.as_money().is_some()) {
+                ("*", _) => money
+                    .amount
+                    .checked_mul(factor)
+                    .ok_or(operation_error(OperationError::MultiplicationOverflow)),
+                ("/", true) => money
+                    .amount
+                    .checked_div(factor)
+                    .ok_or(operation_error(OperationError::DivisionByZeroOrOverflow)),
+                _ => return Some(invalid()),
+            };
+            amount.map(|amount| Field::Money(DozerMoney::new(amount, money.currency)))
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
 define_math_operator!(evaluate_add, "+", |a, b| { a + b }, 0);
 define_math_operator!(evaluate_sub, "-", |a, b| { a - b }, 0);
 define_math_operator!(evaluate_mul, "*", |a, b| { a * b }, 0);
@@ -1796,6 +1898,7 @@ pub fn evaluate_plus(
         Field::I128(v) => Ok(Field::I128(v)),
         Field::Float(v) => Ok(Field::Float(v)),
         Field::Decimal(v) => Ok(Field::Decimal(v)),
+        Field::Money(v) => Ok(Field::Money(v)),
         Field::Boolean(_)
         | Field::String(_)
         | Field::Text(_)
@@ -1805,6 +1908,7 @@ pub fn evaluate_plus(
         | Field::Json(_)
         | Field::Point(_)
         | Field::Duration(_)
+        | Field::Enum(_)
         | Field::Null => Err(PipelineError::InvalidType(
             expression_result,
             "+".to_string(),
@@ -1826,6 +1930,7 @@ pub fn evaluate_minus(
         Field::Float(v) => Ok(Field::Float(-v)),
         Field::Decimal(v) => Ok(Field::Decimal(v.neg())),
         Field::Timestamp(dt) => Ok(Field::Timestamp(dt.checked_add_signed(chrono::Duration::nanoseconds(1)).unwrap())),
+        Field::Money(v) => Ok(Field::Money(DozerMoney::new(v.amount.neg(), v.currency))),
         Field::Boolean(_)
         | Field::String(_)
         | Field::Text(_)
@@ -1835,6 +1940,7 @@ pub fn evaluate_minus(
         | Field::Json(_)
         | Field::Point(_)
         | Field::Duration(_)
+| Field::Enum(_)
         | Field::Null => Err(PipelineError::InvalidType(
             expression_result,
             "-".to_string(),
@@ -1,6 +1,9 @@
 use crate::argv;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::execution::{Expression, ExpressionType};
+use crate::pipeline::expression::scalar::money::{
+    evaluate_amount, evaluate_convert_currency, evaluate_currency, evaluate_money, validate_money,
+};
 use crate::pipeline::expression::scalar::number::{evaluate_abs, evaluate_round};
 use crate::pipeline::expression::scalar::string::{
     evaluate_concat, evaluate_length, evaluate_to_char, evaluate_ucase, validate_concat,
@@ -18,6 +21,10 @@ pub enum ScalarFunctionType {
     Concat,
     Length,
     ToChar,
+    Money,
+    Currency,
+    Amount,
+    ConvertCurrency,
 }
 
 impl Display for ScalarFunctionType {
@@ -29,6 +36,10 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
             ScalarFunctionType::ToChar => f.write_str("TO_CHAR"),
+            ScalarFunctionType::Money => f.write_str("MONEY"),
+            ScalarFunctionType::Currency => f.write_str("CURRENCY"),
+            ScalarFunctionType::Amount => f.write_str("AMOUNT"),
+            ScalarFunctionType::ConvertCurrency => f.write_str("CONVERT_CURRENCY"),
         }
     }
 }
@@ -62,6 +73,10 @@ pub(crate) fn get_scalar_function_type(
             false,
         )),
         ScalarFunctionType::ToChar => argv!(args, 0, ScalarFunctionType::ToChar)?.get_type(schema),
+        ScalarFunctionType::Money
+        | ScalarFunctionType::Currency
+        | ScalarFunctionType::Amount
+        | ScalarFunctionType::ConvertCurrency => validate_money(args, schema, function.clone()),
     }
 }
 
@@ -74,6 +89,10 @@ impl ScalarFunctionType {
             "concat" => Ok(ScalarFunctionType::Concat),
             "length" => Ok(ScalarFunctionType::Length),
             "to_char" => Ok(ScalarFunctionType::ToChar),
+            "money" => Ok(ScalarFunctionType::Money),
+            "currency" => Ok(ScalarFunctionType::Currency),
+            "amount" => Ok(ScalarFunctionType::Amount),
+            "convert_currency" => Ok(ScalarFunctionType::ConvertCurrency),
             _ => Err(PipelineError::InvalidFunction(name.to_string())),
         }
     }
@@ -107,6 +126,27 @@ impl ScalarFunctionType {
                 argv!(args, 1, ScalarFunctionType::ToChar)?,
                 record,
             ),
+            ScalarFunctionType::Money => evaluate_money(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Money)?,
+                argv!(args, 1, ScalarFunctionType::Money)?,
+                record,
+            ),
+            ScalarFunctionType::Currency => evaluate_currency(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Currency)?,
+                record,
+            ),
+            ScalarFunctionType::Amount => {
+                evaluate_amount(schema, argv!(args, 0, ScalarFunctionType::Amount)?, record)
+            }
+            ScalarFunctionType::ConvertCurrency => evaluate_convert_currency(
+                schema,
+                argv!(args, 0, ScalarFunctionType::ConvertCurrency)?,
+                argv!(args, 1, ScalarFunctionType::ConvertCurrency)?,
+                argv!(args, 2, ScalarFunctionType::ConvertCurrency)?,
+                record,
+            ),
         }
     }
 }
@@ -1,3 +1,4 @@
 pub mod common;
+pub mod money;
 pub mod number;
 pub mod string;
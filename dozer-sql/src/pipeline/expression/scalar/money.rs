@@ -0,0 +1,191 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::arg_utils::validate_arg_type;
+use crate::pipeline::expression::execution::{Expression, ExpressionType};
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::{arg_decimal, argv};
+use dozer_types::types::Record;
+use dozer_types::types::{Currency, DozerMoney, Field, FieldType, Schema, SourceDefinition};
+
+const NUMBER_TYPES: [FieldType; 6] = [
+    FieldType::UInt,
+    FieldType::U128,
+    FieldType::Int,
+    FieldType::I128,
+    FieldType::Float,
+    FieldType::Decimal,
+];
+
+pub(crate) fn validate_money(
+    args: &[Expression],
+    schema: &Schema,
+    function: ScalarFunctionType,
+) -> Result<ExpressionType, PipelineError> {
+    let return_type = match function {
+        ScalarFunctionType::Money => {
+            validate_arg_type(
+                argv!(args, 0, function)?,
+                NUMBER_TYPES.to_vec(),
+                schema,
+                function.clone(),
+                0,
+            )?;
+            validate_arg_type(
+                argv!(args, 1, function)?,
+                vec![FieldType::String, FieldType::Text],
+                schema,
+                function,
+                1,
+            )?;
+            FieldType::Money
+        }
+        ScalarFunctionType::Currency => {
+            validate_arg_type(
+                argv!(args, 0, function)?,
+                vec![FieldType::Money],
+                schema,
+                function,
+                0,
+            )?;
+            FieldType::String
+        }
+        ScalarFunctionType::Amount => {
+            validate_arg_type(
+                argv!(args, 0, function)?,
+                vec![FieldType::Money],
+                schema,
+                function,
+                0,
+            )?;
+            FieldType::Decimal
+        }
+        ScalarFunctionType::ConvertCurrency => {
+            validate_arg_type(
+                argv!(args, 0, function)?,
+                vec![FieldType::Money],
+                schema,
+                function.clone(),
+                0,
+            )?;
+            validate_arg_type(
+                argv!(args, 1, function)?,
+                vec![FieldType::String, FieldType::Text],
+                schema,
+                function.clone(),
+                1,
+            )?;
+            validate_arg_type(
+                argv!(args, 2, function)?,
+                NUMBER_TYPES.to_vec(),
+                schema,
+                function,
+                2,
+            )?;
+            FieldType::Money
+        }
+        _ => return Err(PipelineError::InvalidFunction(function.to_string())),
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `MONEY(amount, currency)` makes money of an amount and an ISO 4217 currency code.
+pub(crate) fn evaluate_money(
+    schema: &Schema,
+    amount: &Expression,
+    currency: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let amount = amount.evaluate(record, schema)?;
+    let currency = currency.evaluate(record, schema)?;
+    if amount == Field::Null || currency == Field::Null {
+        return Ok(Field::Null);
+    }
+    let amount = arg_decimal!(amount, ScalarFunctionType::Money, 0)?;
+    let currency = parse_currency(currency, ScalarFunctionType::Money, 1)?;
+    Ok(Field::Money(DozerMoney::new(amount, currency)))
+}
+
+/// `CURRENCY(money)` is the currency code of money.
+pub(crate) fn evaluate_currency(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    match arg.evaluate(record, schema)? {
+        Field::Money(money) => Ok(Field::String(money.currency.to_string())),
+        Field::Null => Ok(Field::Null),
+        value => Err(PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::Currency.to_string(),
+            value,
+            0,
+        )),
+    }
+}
+
+/// `AMOUNT(money)` is the amount of money as a decimal.
+pub(crate) fn evaluate_amount(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    match arg.evaluate(record, schema)? {
+        Field::Money(money) => Ok(Field::Decimal(money.amount)),
+        Field::Null => Ok(Field::Null),
+        value => Err(PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::Amount.to_string(),
+            value,
+            0,
+        )),
+    }
+}
+
+/// `CONVERT_CURRENCY(money, currency, rate)` converts money to another currency at the rate of units of the other
+/// currency per unit of the money's, rounded to the minor unit of the other currency. Rates usually come from a
+/// table of rates joined on the currencies, e.g.
+/// `CONVERT_CURRENCY(o.total, 'EUR', r.rate) ... JOIN rates r ON CURRENCY(o.total) = r.source AND r.target = 'EUR'`.
+pub(crate) fn evaluate_convert_currency(
+    schema: &Schema,
+    money: &Expression,
+    currency: &Expression,
+    rate: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let money = money.evaluate(record, schema)?;
+    let currency = currency.evaluate(record, schema)?;
+    let rate = rate.evaluate(record, schema)?;
+    if money == Field::Null || currency == Field::Null || rate == Field::Null {
+        return Ok(Field::Null);
+    }
+    let Field::Money(money) = money else {
+        return Err(PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::ConvertCurrency.to_string(),
+            money,
+            0,
+        ));
+    };
+    let currency = parse_currency(currency, ScalarFunctionType::ConvertCurrency, 1)?;
+    let rate = arg_decimal!(rate, ScalarFunctionType::ConvertCurrency, 2)?;
+    let amount = money.amount.checked_mul(rate).ok_or_else(|| {
+        PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::ConvertCurrency.to_string(),
+            Field::Decimal(rate),
+            2,
+        )
+    })?;
+    Ok(Field::Money(DozerMoney::new(amount, currency).round()))
+}
+
+fn parse_currency(
+    field: Field,
+    function: ScalarFunctionType,
+    idx: usize,
+) -> Result<Currency, PipelineError> {
+    field
+        .to_string()
+        .and_then(|code| code.parse::<Currency>().ok())
+        .ok_or_else(|| PipelineError::InvalidFunctionArgument(function.to_string(), field, idx))
+}
@@ -4,7 +4,7 @@ use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::Record;
-use dozer_types::types::{Field, FieldType, Schema};
+use dozer_types::types::{DozerMoney, Field, FieldType, Schema};
 use num_traits::{Float, ToPrimitive};
 
 pub(crate) fn evaluate_abs(
@@ -20,6 +20,7 @@ pub(crate) fn evaluate_abs(
         Field::I128(i) => Ok(Field::I128(i.abs())),
         Field::Float(f) => Ok(Field::Float(f.abs())),
         Field::Decimal(d) => Ok(Field::Decimal(d.abs())),
+        Field::Money(m) => Ok(Field::Money(DozerMoney::new(m.amount.abs(), m.currency))),
         Field::Boolean(_)
         | Field::String(_)
         | Field::Text(_)
@@ -29,6 +30,7 @@ pub(crate) fn evaluate_abs(
         | Field::Json(_)
         | Field::Point(_)
         | Field::Duration(_)
+        | Field::Enum(_)
         | Field::Null => Err(InvalidFunctionArgument(
             ScalarFunctionType::Abs.to_string(),
             value,
@@ -71,6 +73,8 @@ pub(crate) fn evaluate_round(
             | Field::Json(_)
             | Field::Point(_)
             | Field::Duration(_)
+            | Field::Money(_)
+            | Field::Enum(_)
             | Field::Null => {} // Truncate value to 0 decimals
         }
     }
@@ -83,6 +87,10 @@ pub(crate) fn evaluate_round(
         Field::I128(i) => Ok(Field::I128(i)),
         Field::Float(f) => Ok(Field::Float((f * order).round() / order)),
         Field::Decimal(d) => Ok(Field::Decimal(d.round_dp(places as u32))),
+        Field::Money(m) => Ok(Field::Money(DozerMoney::new(
+            m.amount.round_dp(places as u32),
+            m.currency,
+        ))),
         Field::Null => Ok(Field::Null),
         Field::Boolean(_)
         | Field::String(_)
@@ -92,7 +100,8 @@ pub(crate) fn evaluate_round(
         | Field::Binary(_)
         | Field::Json(_)
         | Field::Point(_)
-        | Field::Duration(_) => Err(InvalidFunctionArgument(
+        | Field::Duration(_)
+        | Field::Enum(_) => Err(InvalidFunctionArgument(
             ScalarFunctionType::Round.to_string(),
             value,
             0,
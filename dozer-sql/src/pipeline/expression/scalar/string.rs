@@ -50,7 +50,9 @@ pub(crate) fn evaluate_ucase(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::Text(ret),
+        | FieldType::Duration
+        | FieldType::Money
+        | FieldType::Enum => Field::Text(ret),
     })
 }
 
@@ -112,7 +114,9 @@ pub(crate) fn evaluate_concat(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::String(res_str),
+        | FieldType::Duration
+        | FieldType::Money
+        | FieldType::Enum => Field::String(res_str),
     })
 }
 
@@ -196,7 +200,9 @@ pub(crate) fn evaluate_trim(
         | FieldType::Binary
         | FieldType::Json
         | FieldType::Point
-        | FieldType::Duration => Field::Text(retval),
+        | FieldType::Duration
+        | FieldType::Money
+        | FieldType::Enum => Field::Text(retval),
     })
 }
 
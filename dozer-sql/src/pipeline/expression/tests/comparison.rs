@@ -693,3 +693,50 @@ fn test_comparison_logical_date() {
     );
     assert_eq!(f, Field::Boolean(true));
 }
+
+#[test]
+fn test_enum_comparison() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("status"),
+                FieldType::Enum,
+                false,
+                SourceDefinition::Dynamic,
+            )
+            .with_enum_values(vec![
+                "pending".to_string(),
+                "active".to_string(),
+                "closed".to_string(),
+            ]),
+            false,
+        )
+        .clone();
+    let row = Record::new(vec![Field::Enum(1)]);
+    let status = Expression::Column { index: 0 };
+
+    let string = |value: &str| Literal(Field::String(value.to_string()));
+    assert_eq!(
+        evaluate_eq(&schema, &status, &string("active"), &row).unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate_ne(&schema, &string("closed"), &status, &row).unwrap(),
+        Field::Boolean(true)
+    );
+    // Enums are ordered by their declared values.
+    assert_eq!(
+        evaluate_lt(&schema, &status, &string("closed"), &row).unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate_gt(&schema, &status, &Literal(Field::Enum(0)), &row).unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate_eq(&schema, &status, &Literal(Field::Null), &row).unwrap(),
+        Field::Null
+    );
+    assert!(evaluate_eq(&schema, &status, &string("deleted"), &row).is_err());
+    assert!(evaluate_eq(&schema, &status, &Literal(Field::Int(1)), &row).is_err());
+}
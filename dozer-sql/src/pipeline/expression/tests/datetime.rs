@@ -1,18 +1,21 @@
-use crate::pipeline::expression::datetime::evaluate_date_part;
+use crate::pipeline::builder::SqlOptions;
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::datetime::{date_trunc, evaluate_date_part};
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::mathematical::{
     evaluate_add, evaluate_div, evaluate_mod, evaluate_mul, evaluate_sub,
 };
 use crate::pipeline::expression::tests::test_common::*;
+use crate::pipeline::tests::utils::get_select;
 use dozer_types::chrono;
 use dozer_types::chrono::{DateTime, Datelike, NaiveDate};
 use dozer_types::types::Record;
 use dozer_types::types::{
-    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition, TimeUnit,
+    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition, TimeUnit, Timezone,
 };
 use num_traits::ToPrimitive;
 use proptest::prelude::*;
-use sqlparser::ast::DateTimeField;
+use sqlparser::ast::{DateTimeField, SelectItem};
 
 #[test]
 fn test_time() {
@@ -323,3 +326,126 @@ fn test_now() {
     );
     assert!(f.to_timestamp().is_ok())
 }
+
+#[test]
+fn test_current_date() {
+    let f = run_fct(
+        "SELECT CURRENT_DATE() FROM users",
+        Schema::default()
+            .field(
+                FieldDefinition::new(
+                    String::from("ts1"),
+                    FieldType::Timestamp,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone(),
+        vec![],
+    );
+    assert!(matches!(f, Field::Date(_)))
+}
+
+#[test]
+fn test_date_trunc() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let f = run_fct(
+        "SELECT DATE_TRUNC('month', ts) FROM users",
+        schema.clone(),
+        vec![Field::Timestamp(
+            DateTime::parse_from_rfc3339("2023-05-17T13:24:56Z").unwrap(),
+        )],
+    );
+    assert_eq!(
+        f,
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-01T00:00:00Z").unwrap())
+    );
+    let f = run_fct(
+        "SELECT DATE_TRUNC('week', ts) FROM users",
+        schema,
+        vec![Field::Timestamp(
+            DateTime::parse_from_rfc3339("2023-05-17T13:24:56Z").unwrap(),
+        )],
+    );
+    assert_eq!(
+        f,
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-15T00:00:00Z").unwrap())
+    );
+}
+
+#[test]
+fn test_date_trunc_timezone() {
+    let paris = "Europe/Paris".parse::<Timezone>().unwrap();
+    // 23:30 UTC on April 30th is already May 1st in Paris.
+    let ts = DateTime::parse_from_rfc3339("2023-04-30T23:30:00Z").unwrap();
+    assert_eq!(
+        date_trunc(&ts, &DateTimeField::Day, &paris)
+            .unwrap()
+            .to_rfc3339(),
+        "2023-05-01T00:00:00+02:00"
+    );
+    assert_eq!(
+        date_trunc(&ts, &DateTimeField::Month, &paris)
+            .unwrap()
+            .to_rfc3339(),
+        "2023-05-01T00:00:00+02:00"
+    );
+    // The start of the year in Paris is in winter time.
+    assert_eq!(
+        date_trunc(&ts, &DateTimeField::Year, &paris)
+            .unwrap()
+            .to_rfc3339(),
+        "2023-01-01T00:00:00+01:00"
+    );
+    assert_eq!(
+        date_trunc(&ts, &DateTimeField::Hour, &Timezone::Utc)
+            .unwrap()
+            .to_rfc3339(),
+        "2023-04-30T23:00:00+00:00"
+    );
+}
+
+#[test]
+fn test_date_trunc_in_options_timezone() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let options = SqlOptions {
+        timezone: "Europe/Paris".parse().unwrap(),
+        ..Default::default()
+    };
+    let select = get_select("SELECT DATE_TRUNC('day', ts) FROM users").unwrap();
+    let SelectItem::UnnamedExpr(expr) = &select.projection[0] else {
+        panic!("Invalid expr");
+    };
+    let expression = ExpressionBuilder::new(schema.fields.len(), options)
+        .build(false, expr, &schema)
+        .unwrap();
+
+    let record = Record::new(vec![Field::Timestamp(
+        DateTime::parse_from_rfc3339("2023-04-30T23:30:00Z").unwrap(),
+    )]);
+    assert_eq!(
+        expression.evaluate(&record, &schema).unwrap(),
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-01T00:00:00+02:00").unwrap())
+    );
+}
@@ -1,4 +1,4 @@
-use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::builder::{SchemaSQLContext, SqlOptions};
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::mathematical::evaluate_sub;
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
@@ -142,8 +142,11 @@ fn test_alias() {
         .clone();
 
     let select = get_select("SELECT count(fn) AS alias1, ln as alias2 FROM t1").unwrap();
-    let processor_factory =
-        ProjectionProcessorFactory::_new("projection_id".to_owned(), select.projection);
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        select.projection,
+        SqlOptions::default(),
+    );
     let r = processor_factory
         .get_output_schema(
             &DEFAULT_PORT_HANDLE,
@@ -203,8 +206,11 @@ fn test_wildcard() {
         .clone();
 
     let select = get_select("SELECT * FROM t1").unwrap();
-    let processor_factory =
-        ProjectionProcessorFactory::_new("projection_id".to_owned(), select.projection);
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        select.projection,
+        SqlOptions::default(),
+    );
     let r = processor_factory
         .get_output_schema(
             &DEFAULT_PORT_HANDLE,
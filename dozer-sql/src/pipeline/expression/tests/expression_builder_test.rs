@@ -1,3 +1,4 @@
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::expression::builder::ExpressionBuilder;
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::operator::BinaryOperatorType;
@@ -32,7 +33,7 @@ fn test_simple_function() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -72,7 +73,7 @@ fn test_simple_aggr_function() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -115,7 +116,7 @@ fn test_2_nested_aggr_function() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -164,7 +165,7 @@ fn test_3_nested_aggr_function() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -219,7 +220,7 @@ fn test_3_nested_aggr_function_dup() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -277,7 +278,7 @@ fn test_3_nested_aggr_function_and_sum() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -342,7 +343,7 @@ fn test_3_nested_aggr_function_and_sum_3() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -404,7 +405,7 @@ fn test_wrong_nested_aggregations() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let _e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -441,7 +442,7 @@ fn test_name_resolution() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -484,7 +485,7 @@ fn test_alias_resolution() {
         )
         .to_owned();
 
-    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
     let e = match &get_select(sql).unwrap().projection[0] {
         SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
         _ => panic!("Invalid expr"),
@@ -508,3 +509,130 @@ fn test_alias_resolution() {
         }
     );
 }
+
+#[test]
+fn test_case_insensitive_name_resolution() {
+    let sql = r#"SELECT CONCAT(users.ID, "id", name, ORDERS.Id) FROM t0"#;
+    let users = SourceDefinition::Table {
+        connection: "connection1".to_string(),
+        name: "Users".to_string(),
+    };
+    let orders = SourceDefinition::Table {
+        connection: "connection1".to_string(),
+        name: "Orders".to_string(),
+    };
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new("Id".to_string(), FieldType::String, false, users.clone()),
+            false,
+        )
+        .field(
+            FieldDefinition::new("id".to_string(), FieldType::String, false, orders),
+            false,
+        )
+        .field(
+            FieldDefinition::new("Name".to_string(), FieldType::String, false, users),
+            false,
+        )
+        .to_owned();
+
+    let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
+    let e = match &get_select(sql).unwrap().projection[0] {
+        SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
+        _ => panic!("Invalid expr"),
+    };
+
+    assert_eq!(
+        e,
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Concat,
+            args: vec![
+                Expression::Column { index: 0 },
+                Expression::Column { index: 1 },
+                Expression::Column { index: 2 },
+                Expression::Column { index: 1 }
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_case_sensitive_name_resolution() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                "Name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let options = SqlOptions {
+        case_sensitive: true,
+        ..Default::default()
+    };
+    let build = |sql: &str| {
+        let mut builder = ExpressionBuilder::new(schema.fields.len(), options);
+        match &get_select(sql).unwrap().projection[0] {
+            SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema),
+            _ => panic!("Invalid expr"),
+        }
+    };
+
+    assert_eq!(
+        build("SELECT Name FROM t0").unwrap(),
+        Expression::Column { index: 0 }
+    );
+    assert!(build("SELECT name FROM t0").is_err());
+}
+
+#[test]
+fn test_misspelled_column() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                "customer_name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "Amount".to_string(),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let build = |sql: &str| {
+        let mut builder = ExpressionBuilder::new(schema.fields.len(), SqlOptions::default());
+        match &get_select(sql).unwrap().projection[0] {
+            SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema),
+            _ => panic!("Invalid expr"),
+        }
+    };
+
+    assert_eq!(
+        build("SELECT custmer_name FROM t0")
+            .unwrap_err()
+            .to_string(),
+        "Sql: SQL Error: Invalid column name custmer_name. Did you mean customer_name?"
+    );
+    // Quoted identifiers keep their case.
+    assert_eq!(
+        build(r#"SELECT "amount" FROM t0"#).unwrap_err().to_string(),
+        "Sql: SQL Error: Invalid column name amount. Did you mean Amount?"
+    );
+    assert_eq!(
+        build("SELECT price FROM t0").unwrap_err().to_string(),
+        "Sql: SQL Error: Invalid column name price."
+    );
+}
@@ -23,6 +23,8 @@ mod logical;
 #[cfg(test)]
 mod mathematical;
 #[cfg(test)]
+mod money;
+#[cfg(test)]
 mod number;
 #[cfg(test)]
 mod point;
@@ -0,0 +1,149 @@
+use crate::pipeline::errors::SqlError::Operation;
+use crate::pipeline::errors::{OperationError, PipelineError};
+use crate::pipeline::expression::comparison::{evaluate_eq, evaluate_lt};
+use crate::pipeline::expression::execution::Expression::Literal;
+use crate::pipeline::expression::mathematical::{
+    evaluate_add, evaluate_div, evaluate_mul, evaluate_sub,
+};
+use crate::pipeline::expression::tests::test_common::*;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{
+    DozerMoney, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
+
+fn money(amount: &str) -> Field {
+    Field::Money(amount.parse().unwrap())
+}
+
+#[test]
+fn test_money_math() {
+    let row = Record::new(vec![]);
+    let schema = Schema::default();
+    let usd1 = Box::new(Literal(money("12.50 USD")));
+    let usd2 = Box::new(Literal(money("2.25 USD")));
+    let eur = Box::new(Literal(money("1.00 EUR")));
+    let two = Box::new(Literal(Field::Int(2)));
+    let null = Box::new(Literal(Field::Null));
+
+    assert_eq!(
+        evaluate_add(&schema, &usd1, &usd2, &row).unwrap(),
+        money("14.75 USD")
+    );
+    assert_eq!(
+        evaluate_sub(&schema, &usd1, &usd2, &row).unwrap(),
+        money("10.25 USD")
+    );
+    assert_eq!(
+        evaluate_mul(&schema, &two, &usd1, &row).unwrap(),
+        money("25.00 USD")
+    );
+    assert_eq!(
+        evaluate_div(&schema, &usd1, &two, &row).unwrap(),
+        money("6.25 USD")
+    );
+    assert_eq!(
+        evaluate_div(&schema, &usd1, &usd2, &row).unwrap(),
+        Field::Decimal(Decimal::new(125, 1) / Decimal::new(225, 2))
+    );
+    assert_eq!(
+        evaluate_add(&schema, &usd1, &null, &row).unwrap(),
+        Field::Null
+    );
+    assert!(matches!(
+        evaluate_add(&schema, &usd1, &eur, &row),
+        Err(PipelineError::SqlError(Operation(OperationError::CurrencyMismatch(left, right))))
+            if left.code() == "USD" && right.code() == "EUR"
+    ));
+    assert!(matches!(
+        evaluate_mul(&schema, &usd1, &usd2, &row),
+        Err(PipelineError::InvalidTypeComparison(_, _, _))
+    ));
+}
+
+#[test]
+fn test_money_comparison() {
+    let row = Record::new(vec![]);
+    let schema = Schema::default();
+    let usd1 = Box::new(Literal(money("12.50 USD")));
+    let usd2 = Box::new(Literal(money("2.25 USD")));
+    let eur = Box::new(Literal(money("1.00 EUR")));
+    let string = Box::new(Literal(Field::String("12.5 usd".to_string())));
+
+    assert_eq!(
+        evaluate_lt(&schema, &usd2, &usd1, &row).unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate_eq(&schema, &usd1, &string, &row).unwrap(),
+        Field::Boolean(true)
+    );
+    assert!(matches!(
+        evaluate_lt(&schema, &eur, &usd1, &row),
+        Err(PipelineError::SqlError(Operation(
+            OperationError::CurrencyMismatch(_, _)
+        )))
+    ));
+}
+
+#[test]
+fn test_money_functions() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("price"),
+                FieldType::Decimal,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("rate"),
+                FieldType::Decimal,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let input = vec![
+        Field::Decimal(Decimal::new(1999, 2)),
+        Field::Decimal(Decimal::new(92345, 5)),
+    ];
+
+    let f = run_fct(
+        "SELECT MONEY(price, 'usd') FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, money("19.99 USD"));
+
+    let f = run_fct(
+        "SELECT CURRENCY(MONEY(price, 'JPY')) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("JPY".to_string()));
+
+    let f = run_fct(
+        "SELECT AMOUNT(MONEY(price, 'USD')) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::Decimal(Decimal::new(1999, 2)));
+
+    // 19.99 * 0.92345 = 18.4597655, rounded to cents.
+    let f = run_fct(
+        "SELECT CONVERT_CURRENCY(MONEY(price, 'USD'), 'EUR', rate) FROM USERS",
+        schema,
+        input,
+    );
+    assert_eq!(
+        f,
+        Field::Money(DozerMoney::new(
+            Decimal::new(1846, 2),
+            "EUR".parse().unwrap()
+        ))
+    );
+}
@@ -1,4 +1,4 @@
-use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::builder::{SchemaSQLContext, SqlOptions};
 use crate::pipeline::{projection::factory::ProjectionProcessorFactory, tests::utils::get_select};
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::executor_operation::ProcessorOperation;
@@ -28,8 +28,11 @@ pub(crate) fn run_fct(sql: &str, schema: Schema, input: Vec<Field>) -> Field {
     let record_store = ProcessorRecordStore::new().unwrap();
 
     let select = get_select(sql).unwrap();
-    let processor_factory =
-        ProjectionProcessorFactory::_new("projection_id".to_owned(), select.projection);
+    let processor_factory = ProjectionProcessorFactory::_new(
+        "projection_id".to_owned(),
+        select.projection,
+        SqlOptions::default(),
+    );
     processor_factory
         .get_output_schema(
             &DEFAULT_PORT_HANDLE,
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::Schema;
+
+use super::options::JavaScriptOperatorOptions;
+use super::transform::JavaScriptTransform;
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{JavaScriptOperatorError, PipelineError};
+use crate::pipeline::record_transform::RecordTransformProcessor;
+
+#[derive(Debug)]
+pub struct JavaScriptProcessorFactory {
+    id: String,
+    options: JavaScriptOperatorOptions,
+}
+
+impl JavaScriptProcessorFactory {
+    pub fn new(id: String, options: JavaScriptOperatorOptions) -> Self {
+        Self { id, options }
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for JavaScriptProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let (_, context) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok((self.options.schema.clone(), context.clone()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?
+            .clone();
+        let source = std::fs::read_to_string(&self.options.script)
+            .map_err(|e| JavaScriptOperatorError::ReadScript(self.options.script.clone(), e))?;
+        let transform = JavaScriptTransform::new(
+            input_schema,
+            self.options.schema.clone(),
+            source,
+            &self.options.function,
+            self.options.timeout,
+            self.options.max_heap_size_mb,
+        )?;
+        Ok(Box::new(RecordTransformProcessor::new(
+            transform,
+            self.options.batch_size,
+        )))
+    }
+
+    fn type_name(&self) -> String {
+        "JavaScript".to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
@@ -0,0 +1,53 @@
+//! An operator running a JavaScript function on the records of a table.
+//!
+//! Configured as operator `javascript`, with options:
+//! - `script`: path to the script defining the function.
+//! - `function`: the function's name. Defaults to `transform`.
+//! - `columns`: the output schema, as `name:type` pairs separated by commas.
+//! - `primary_key`: the output columns forming the primary key, separated by commas. Optional.
+//! - `batch_size`: how many operations are buffered before their records are sent to the runtime at once.
+//!   Buffered operations are also sent before every commit. Defaults to 100.
+//! - `timeout_ms`: how long loading the script, or a call with a batch, may run before the script is terminated
+//!   and the operator fails. Defaults to 1000.
+//! - `max_heap_size_mb`: the heap limit of the script. A script exceeding it is terminated and the operator fails.
+//!   Defaults to 128.
+//!
+//! The function is called with every record as an object from column names to values, in their JSON
+//! representation, and returns an object of the output columns, or `null` to drop the record. It must be
+//! deterministic, see `RecordTransform`.
+//!
+//! Scripts run in a V8 isolate without any Deno extensions, so they can't access the file system or network.
+
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::ProcessorFactory;
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+
+use crate::pipeline::builder::SchemaSQLContext;
+
+mod factory;
+mod options;
+mod transform;
+
+#[cfg(test)]
+mod tests;
+
+pub use factory::JavaScriptProcessorFactory;
+pub use options::JavaScriptOperatorOptions;
+pub use transform::JavaScriptTransform;
+
+/// Name the operator is registered under.
+pub const OPERATOR_NAME: &str = "javascript";
+
+pub fn register(registry: &mut OperatorRegistry<SchemaSQLContext>) -> Result<(), ExecutionError> {
+    registry.register(
+        OPERATOR_NAME.to_string(),
+        Box::new(|id: &str, options: &OperatorOptions| {
+            let factory: Box<dyn ProcessorFactory<SchemaSQLContext>> =
+                Box::new(JavaScriptProcessorFactory::new(
+                    id.to_string(),
+                    JavaScriptOperatorOptions::parse(options)?,
+                ));
+            Ok(factory)
+        }),
+    )
+}
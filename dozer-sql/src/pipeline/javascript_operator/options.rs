@@ -0,0 +1,71 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::Schema;
+use regex::Regex;
+
+use crate::pipeline::errors::JavaScriptOperatorError;
+use crate::pipeline::operator_options::{parse_batch_size, parse_schema, required, ALL_TYPES};
+
+const DEFAULT_FUNCTION: &str = "transform";
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+const DEFAULT_MAX_HEAP_SIZE_MB: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaScriptOperatorOptions {
+    pub script: String,
+    pub function: String,
+    pub schema: Schema,
+    pub batch_size: usize,
+    /// How long loading the script, or a call with a batch, may run.
+    pub timeout: Duration,
+    pub max_heap_size_mb: usize,
+}
+
+impl JavaScriptOperatorOptions {
+    pub fn parse(options: &OperatorOptions) -> Result<Self, JavaScriptOperatorError> {
+        let function = options
+            .get("function")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_FUNCTION);
+        // The function is referenced by name in the generated wrapper script.
+        let identifier = Regex::new(r"^[A-Za-z_$][A-Za-z0-9_$]*$").expect("Valid regex");
+        if !identifier.is_match(function) {
+            return Err(JavaScriptOperatorError::InvalidFunctionName(
+                function.to_string(),
+            ));
+        }
+        Ok(Self {
+            script: required(options, "script")?.to_string(),
+            function: function.to_string(),
+            schema: parse_schema(options, &ALL_TYPES)?,
+            batch_size: parse_batch_size(options)?,
+            timeout: Duration::from_millis(parse_positive(
+                options,
+                "timeout_ms",
+                DEFAULT_TIMEOUT_MS,
+            )?),
+            max_heap_size_mb: parse_positive(
+                options,
+                "max_heap_size_mb",
+                DEFAULT_MAX_HEAP_SIZE_MB,
+            )?,
+        })
+    }
+}
+
+fn parse_positive<T: FromStr + PartialOrd + Default>(
+    options: &OperatorOptions,
+    name: &'static str,
+    default: T,
+) -> Result<T, JavaScriptOperatorError> {
+    match options.get(name) {
+        None => Ok(default),
+        Some(value) => value
+            .parse()
+            .ok()
+            .filter(|number| *number > T::default())
+            .ok_or_else(|| JavaScriptOperatorError::InvalidNumber(name, value.clone())),
+    }
+}
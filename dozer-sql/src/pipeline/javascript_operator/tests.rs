@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use super::{JavaScriptOperatorOptions, JavaScriptTransform};
+use crate::pipeline::errors::{JavaScriptOperatorError, OperatorOptionsError, PipelineError};
+use crate::pipeline::record_transform::RecordTransform;
+
+fn options(pairs: &[(&str, &str)]) -> OperatorOptions {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn schema(fields: &[(&str, FieldType)]) -> Schema {
+    let mut schema = Schema::new();
+    for (name, typ) in fields {
+        schema.field(
+            FieldDefinition::new(name.to_string(), *typ, true, SourceDefinition::Dynamic),
+            false,
+        );
+    }
+    schema
+}
+
+#[test]
+fn test_parse_options() {
+    let parsed = JavaScriptOperatorOptions::parse(&options(&[
+        ("script", "scoring.js"),
+        ("columns", "id:int, at:timestamp"),
+        ("primary_key", "id"),
+    ]))
+    .unwrap();
+    assert_eq!(parsed.script, "scoring.js");
+    assert_eq!(parsed.function, "transform");
+    assert_eq!(parsed.batch_size, 100);
+    assert_eq!(parsed.timeout, Duration::from_millis(1000));
+    assert_eq!(parsed.max_heap_size_mb, 128);
+    assert_eq!(parsed.schema.primary_index, vec![0]);
+    assert_eq!(parsed.schema.fields[1].typ, FieldType::Timestamp);
+
+    assert!(matches!(
+        JavaScriptOperatorOptions::parse(&options(&[("columns", "id:int")])).unwrap_err(),
+        JavaScriptOperatorError::Options(OperatorOptionsError::MissingOption("script"))
+    ));
+    assert!(matches!(
+        JavaScriptOperatorOptions::parse(&options(&[
+            ("script", "scoring.js"),
+            ("function", "transform()"),
+            ("columns", "id:int"),
+        ]))
+        .unwrap_err(),
+        JavaScriptOperatorError::InvalidFunctionName(_)
+    ));
+    assert!(matches!(
+        JavaScriptOperatorOptions::parse(&options(&[
+            ("script", "scoring.js"),
+            ("columns", "id:int"),
+            ("timeout_ms", "0"),
+        ]))
+        .unwrap_err(),
+        JavaScriptOperatorError::InvalidNumber("timeout_ms", _)
+    ));
+}
+
+#[test]
+fn test_javascript_transform() {
+    let mut transform = JavaScriptTransform::new(
+        schema(&[("name", FieldType::String), ("score", FieldType::Int)]),
+        schema(&[("label", FieldType::String), ("double", FieldType::Int)]),
+        "function transform(record) {
+            if (record.score < 0) return null;
+            return { label: record.name.toUpperCase(), double: record.score * 2 };
+        }"
+        .to_string(),
+        "transform",
+        Duration::from_secs(1),
+        128,
+    )
+    .unwrap();
+
+    let alice = Record::new(vec![Field::String("alice".to_string()), Field::Int(21)]);
+    let bob = Record::new(vec![Field::String("bob".to_string()), Field::Int(-1)]);
+    let results = transform.transform(&[&alice, &bob]).unwrap();
+    assert_eq!(
+        results,
+        vec![
+            Some(Record::new(vec![
+                Field::String("ALICE".to_string()),
+                Field::Int(42)
+            ])),
+            None
+        ]
+    );
+}
+
+#[test]
+fn test_javascript_errors() {
+    let new = |source: &str| {
+        JavaScriptTransform::new(
+            schema(&[("score", FieldType::Int)]),
+            schema(&[("score", FieldType::Int)]),
+            source.to_string(),
+            "transform",
+            Duration::from_millis(500),
+            16,
+        )
+    };
+    assert!(matches!(
+        new("throw new Error('broken');").unwrap_err(),
+        JavaScriptOperatorError::Script(_)
+    ));
+
+    let record = Record::new(vec![Field::Int(1)]);
+    let mut transform = new("const transform = (record) => record.score;").unwrap();
+    assert!(matches!(
+        transform.transform(&[&record]).unwrap_err(),
+        PipelineError::JavaScriptOperatorError(JavaScriptOperatorError::NotAnObject(_))
+    ));
+
+    // A script can't reach the host.
+    let mut transform =
+        new("const transform = (record) => ({ score: fetch('http://localhost') });").unwrap();
+    assert!(matches!(
+        transform.transform(&[&record]).unwrap_err(),
+        PipelineError::JavaScriptOperatorError(JavaScriptOperatorError::Script(_))
+    ));
+
+    // Scripts running too long are terminated, when loading or transforming.
+    assert!(matches!(
+        new("while (true) {}").unwrap_err(),
+        JavaScriptOperatorError::Timeout(_)
+    ));
+    let mut transform = new("const transform = (record) => { while (true) {} };").unwrap();
+    assert!(matches!(
+        transform.transform(&[&record]).unwrap_err(),
+        PipelineError::JavaScriptOperatorError(JavaScriptOperatorError::Timeout(_))
+    ));
+
+    // So are scripts exceeding the heap limit, instead of aborting the process.
+    let mut transform = new(
+        "const chunks = [];
+        const transform = (record) => { while (true) chunks.push(new Array(1000000).fill(record.score)); };",
+    )
+    .unwrap();
+    assert!(matches!(
+        transform.transform(&[&record]).unwrap_err(),
+        PipelineError::JavaScriptOperatorError(JavaScriptOperatorError::HeapLimit(16))
+    ));
+}
@@ -0,0 +1,220 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use deno_core::{v8, JsRuntime, RuntimeOptions};
+use dozer_types::crossbeam::channel::{bounded, Receiver, RecvTimeoutError, Sender};
+use dozer_types::helper::json_value_to_field;
+use dozer_types::json_types::field_to_json_value;
+use dozer_types::serde_json::{self, Map, Value};
+use dozer_types::types::{Field, Record, Schema, Timezone};
+
+use crate::pipeline::errors::{JavaScriptOperatorError, PipelineError};
+use crate::pipeline::record_transform::RecordTransform;
+
+const SCRIPT_NAME: &str = "[dozer:script]";
+const CALL_NAME: &str = "[dozer:transform]";
+
+/// Calls a JavaScript function on records, in a runtime owned by a dedicated thread.
+///
+/// A `JsRuntime` can't be sent between threads, while processors are built and run on different ones. Batches are
+/// sent to the runtime thread as JSON, and it stops when the transform is dropped.
+///
+/// Loading the script and every call must finish within `timeout`, or the script is terminated. A script growing its
+/// heap past `max_heap_size_mb` is terminated too, and the runtime stops.
+#[derive(Debug)]
+pub struct JavaScriptTransform {
+    input_schema: Schema,
+    output_schema: Schema,
+    timeout: Duration,
+    isolate: v8::IsolateHandle,
+    requests: Sender<String>,
+    responses: Receiver<Result<String, JavaScriptOperatorError>>,
+}
+
+impl JavaScriptTransform {
+    /// Starts a runtime running `source`, failing if the script throws.
+    pub fn new(
+        input_schema: Schema,
+        output_schema: Schema,
+        source: String,
+        function: &str,
+        timeout: Duration,
+        max_heap_size_mb: usize,
+    ) -> Result<Self, JavaScriptOperatorError> {
+        let source = format!("{source}\n{}", wrapper(function));
+        let (isolate_sender, isolate) = bounded(1);
+        let (requests, request_receiver) = bounded(0);
+        let (response_sender, responses) = bounded(0);
+        std::thread::Builder::new()
+            .name("javascript-operator".to_string())
+            .spawn(move || {
+                run_runtime(
+                    source,
+                    max_heap_size_mb,
+                    isolate_sender,
+                    request_receiver,
+                    response_sender,
+                )
+            })
+            .map_err(JavaScriptOperatorError::SpawnRuntime)?;
+
+        let isolate = isolate
+            .recv()
+            .map_err(|_| JavaScriptOperatorError::RuntimeStopped)?;
+        let transform = Self {
+            input_schema,
+            output_schema,
+            timeout,
+            isolate,
+            requests,
+            responses,
+        };
+        // The runtime thread first reports whether the script loaded.
+        transform.response()?;
+        Ok(transform)
+    }
+
+    fn to_object(&self, record: &Record) -> Result<Value, JavaScriptOperatorError> {
+        let mut object = Map::new();
+        for (field, value) in self.input_schema.fields.iter().zip(&record.values) {
+            object.insert(
+                field.name.clone(),
+                field_to_json_value(value.clone(), &Timezone::Utc)?,
+            );
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn parse_result(&self, result: Value) -> Result<Option<Record>, PipelineError> {
+        let mut object = match result {
+            Value::Null => return Ok(None),
+            Value::Object(object) => object,
+            value => return Err(JavaScriptOperatorError::NotAnObject(value).into()),
+        };
+        let values = self
+            .output_schema
+            .fields
+            .iter()
+            .map(|field| match object.remove(&field.name) {
+                Some(Value::Null) | None => Ok(Field::Null),
+                Some(value) => json_value_to_field(value, field.typ, true),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Record::new(values)))
+    }
+
+    fn call(&self, records: String) -> Result<String, JavaScriptOperatorError> {
+        self.requests
+            .send(records)
+            .map_err(|_| JavaScriptOperatorError::RuntimeStopped)?;
+        self.response()
+    }
+
+    /// Waits for the runtime thread's response, terminating the script if it takes longer than `timeout`.
+    fn response(&self) -> Result<String, JavaScriptOperatorError> {
+        match self.responses.recv_timeout(self.timeout) {
+            Ok(response) => response,
+            Err(RecvTimeoutError::Disconnected) => Err(JavaScriptOperatorError::RuntimeStopped),
+            Err(RecvTimeoutError::Timeout) => {
+                self.isolate.terminate_execution();
+                // The terminated script fails, unless it finished just before being terminated.
+                self.responses
+                    .recv()
+                    .map_err(|_| JavaScriptOperatorError::RuntimeStopped)?
+                    .map_err(|_| JavaScriptOperatorError::Timeout(self.timeout))
+            }
+        }
+    }
+}
+
+impl RecordTransform for JavaScriptTransform {
+    fn transform(&mut self, records: &[&Record]) -> Result<Vec<Option<Record>>, PipelineError> {
+        let records = records
+            .iter()
+            .map(|record| self.to_object(record))
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = self.call(Value::Array(records).to_string())?;
+        let results: Vec<Value> =
+            serde_json::from_str(&results).map_err(JavaScriptOperatorError::InvalidResult)?;
+        results
+            .into_iter()
+            .map(|result| self.parse_result(result))
+            .collect()
+    }
+}
+
+/// Defines `__dozerTransform`, taking and returning a JSON array, so only strings cross the runtime boundary.
+fn wrapper(function: &str) -> String {
+    format!(
+        "globalThis.__dozerTransform = (records) => JSON.stringify(JSON.parse(records).map((record) => {{
+            const result = {function}(record);
+            return result === undefined ? null : result;
+        }}));"
+    )
+}
+
+/// Runs on the runtime thread. No extensions are registered, so scripts have no access to the file system or network.
+fn run_runtime(
+    source: String,
+    max_heap_size_mb: usize,
+    isolate: Sender<v8::IsolateHandle>,
+    requests: Receiver<String>,
+    responses: Sender<Result<String, JavaScriptOperatorError>>,
+) {
+    let max_heap_size = max_heap_size_mb * 1024 * 1024;
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+        create_params: Some(v8::CreateParams::default().heap_limits(0, max_heap_size)),
+        ..Default::default()
+    });
+    let handle = runtime.v8_isolate().thread_safe_handle();
+    if isolate.send(handle.clone()).is_err() {
+        return;
+    }
+    // V8 aborts the process when the heap limit is reached, so the script is terminated just before, with some more
+    // heap to unwind it.
+    let out_of_memory = Arc::new(AtomicBool::new(false));
+    runtime.add_near_heap_limit_callback({
+        let out_of_memory = out_of_memory.clone();
+        move |current_limit, _| {
+            out_of_memory.store(true, Ordering::SeqCst);
+            handle.terminate_execution();
+            current_limit * 2
+        }
+    });
+
+    let mut execute = |name: &'static str, code: String| {
+        // A previous call may have been terminated just after it finished.
+        runtime.v8_isolate().cancel_terminate_execution();
+        runtime
+            .execute_script(name, code.into())
+            .map(|value| {
+                let scope = &mut runtime.handle_scope();
+                v8::Local::new(scope, value).to_rust_string_lossy(scope)
+            })
+            .map_err(|e| {
+                if out_of_memory.load(Ordering::SeqCst) {
+                    JavaScriptOperatorError::HeapLimit(max_heap_size_mb)
+                } else {
+                    JavaScriptOperatorError::Script(e.to_string())
+                }
+            })
+    };
+
+    let loaded = execute(SCRIPT_NAME, source).map(|_| String::new());
+    let failed = loaded.is_err();
+    if responses.send(loaded).is_err() || failed {
+        return;
+    }
+
+    for records in requests {
+        // The records are passed as a string literal, so they're never evaluated as code.
+        let call = format!("__dozerTransform({})", Value::String(records));
+        let result = execute(CALL_NAME, call);
+        // The heap may be exhausted for good, so the runtime stops.
+        let out_of_memory = matches!(result, Err(JavaScriptOperatorError::HeapLimit(_)));
+        if responses.send(result).is_err() || out_of_memory {
+            return;
+        }
+    }
+}
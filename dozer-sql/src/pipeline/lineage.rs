@@ -0,0 +1,505 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use dozer_types::serde::{self, Deserialize, Serialize};
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, Ident, JoinConstraint, JoinOperator, Query, Select,
+    SelectItem, SetExpr, Statement, TableFactor,
+};
+use sqlparser::{dialect::DozerDialect, parser::Parser};
+
+use super::builder::SqlOptions;
+use super::errors::{PipelineError, UnsupportedSqlError};
+use super::expression::builder::ident_matches;
+use super::pipeline_builder::from_builder::string_from_sql_object_name;
+
+/// A column of a source table (or of a table the lineage could not resolve) that contributes to an output column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct SourceColumn {
+    /// Name of the source table. `None` if the column reference is ambiguous.
+    pub table: Option<String>,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct ColumnLineage {
+    /// Name of the output column.
+    pub column: String,
+    /// The SQL expression producing the column.
+    pub expression: String,
+    /// Source columns referenced by `expression`, after resolving CTEs, subqueries and intermediate tables.
+    pub sources: Vec<SourceColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct TableLineage {
+    /// Name of the output table, as specified in `SELECT ... INTO`.
+    pub table: String,
+    pub columns: Vec<ColumnLineage>,
+}
+
+/// Extracts column level lineage of every output table (`SELECT ... INTO`) in `sql`.
+///
+/// Output tables of earlier statements can be referenced by later statements, in which case the lineage is
+/// resolved through them down to the source tables.
+///
+/// `source_columns` has the column names of the source tables, which resolve unqualified columns of joins and expand
+/// wildcards. Columns of source tables missing from it can only be resolved if they are qualified or the table is
+/// the only one they could come from.
+pub fn extract_lineage(
+    sql: &str,
+    options: SqlOptions,
+    source_columns: &HashMap<String, Vec<String>>,
+) -> Result<Vec<TableLineage>, PipelineError> {
+    let result = analyze(sql, options, source_columns, &RefCell::default())?;
+    if result.is_empty() {
+        return Err(PipelineError::NoIntoProvided);
+    }
+    Ok(result)
+}
+
+/// Source columns referenced anywhere in `sql`, including filters, join conditions and groupings. Columns that can't
+/// be attributed to a table have no table, and a `*` column stands for all columns of its table.
+pub fn used_source_columns(
+    sql: &str,
+    options: SqlOptions,
+) -> Result<Vec<SourceColumn>, PipelineError> {
+    let used = RefCell::default();
+    analyze(sql, options, &HashMap::new(), &used)?;
+    Ok(used.into_inner())
+}
+
+fn analyze(
+    sql: &str,
+    options: SqlOptions,
+    source_columns: &HashMap<String, Vec<String>>,
+    used: &RefCell<Vec<SourceColumn>>,
+) -> Result<Vec<TableLineage>, PipelineError> {
+    let dialect = DozerDialect {};
+    let ast = Parser::parse_sql(&dialect, sql)
+        .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
+
+    let mut output_tables: HashMap<String, Vec<ColumnLineage>> = HashMap::new();
+    let mut result = vec![];
+    for statement in ast {
+        match statement {
+            Statement::Query(query) => {
+                let context = Context {
+                    tables: output_tables.clone(),
+                    source_columns,
+                    options,
+                    used,
+                };
+                let (columns, into) = query_lineage(&query, &context)?;
+                if let Some(table) = into {
+                    output_tables.insert(table.clone(), columns.clone());
+                    result.push(TableLineage { table, columns });
+                }
+            }
+            s => {
+                return Err(PipelineError::UnsupportedSqlError(
+                    UnsupportedSqlError::GenericError(s.to_string()),
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+enum RelationKind {
+    /// A source table, with its columns if they are known.
+    Source {
+        table: String,
+        columns: Option<Vec<String>>,
+    },
+    Derived(Vec<ColumnLineage>),
+}
+
+struct Relation {
+    name: String,
+    kind: RelationKind,
+}
+
+impl Relation {
+    /// Whether the relation is known to have `column`.
+    fn has_column(&self, column: &Ident, case_sensitive: bool) -> bool {
+        match &self.kind {
+            RelationKind::Source { columns, .. } => columns
+                .iter()
+                .flatten()
+                .any(|name| ident_matches(column, case_sensitive, name)),
+            RelationKind::Derived(columns) => columns
+                .iter()
+                .any(|c| ident_matches(column, case_sensitive, &c.column)),
+        }
+    }
+}
+
+/// The tables a query can reference besides source tables, i.e. output tables of earlier statements and CTEs, and
+/// the known columns of source tables.
+#[derive(Clone)]
+struct Context<'a> {
+    tables: HashMap<String, Vec<ColumnLineage>>,
+    source_columns: &'a HashMap<String, Vec<String>>,
+    options: SqlOptions,
+    /// Every source column referenced so far.
+    used: &'a RefCell<Vec<SourceColumn>>,
+}
+
+impl Context<'_> {
+    fn record_used<'b>(&self, sources: impl IntoIterator<Item = &'b SourceColumn>) {
+        let mut used = self.used.borrow_mut();
+        for source in sources {
+            push_unique(&mut *used, source.clone());
+        }
+    }
+}
+
+fn query_lineage(
+    query: &Query,
+    context: &Context,
+) -> Result<(Vec<ColumnLineage>, Option<String>), PipelineError> {
+    let mut context = context.clone();
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            let (columns, _) = query_lineage(&cte.query, &context)?;
+            context.tables.insert(cte.alias.name.value.clone(), columns);
+        }
+    }
+    set_expr_lineage(&query.body, &context)
+}
+
+fn set_expr_lineage(
+    set_expr: &SetExpr,
+    context: &Context,
+) -> Result<(Vec<ColumnLineage>, Option<String>), PipelineError> {
+    match set_expr {
+        SetExpr::Select(select) => select_lineage(select, context),
+        SetExpr::Query(query) => query_lineage(query, context),
+        SetExpr::SetOperation { left, right, .. } => {
+            let (mut columns, left_into) = set_expr_lineage(left, context)?;
+            let (right_columns, right_into) = set_expr_lineage(right, context)?;
+            // Set operations combine columns by position, so the output column takes the sources of both sides.
+            for (column, right_column) in columns.iter_mut().zip(right_columns) {
+                for source in right_column.sources {
+                    push_unique(&mut column.sources, source);
+                }
+            }
+            Ok((columns, left_into.or(right_into)))
+        }
+        _ => Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::GenericError("Unsupported query body structure".to_string()),
+        )),
+    }
+}
+
+fn select_lineage(
+    select: &Select,
+    context: &Context,
+) -> Result<(Vec<ColumnLineage>, Option<String>), PipelineError> {
+    let options = context.options;
+    let mut relations = vec![];
+    for table_with_joins in &select.from {
+        relations.push(relation_from_table_factor(
+            &table_with_joins.relation,
+            context,
+        )?);
+        for join in &table_with_joins.joins {
+            relations.push(relation_from_table_factor(&join.relation, context)?);
+        }
+    }
+
+    let mut filters = select
+        .selection
+        .iter()
+        .chain(&select.group_by)
+        .chain(&select.having)
+        .collect::<Vec<_>>();
+    for join in select.from.iter().flat_map(|table| &table.joins) {
+        match &join.join_operator {
+            JoinOperator::Inner(JoinConstraint::On(expr))
+            | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+            | JoinOperator::RightOuter(JoinConstraint::On(expr))
+            | JoinOperator::FullOuter(JoinConstraint::On(expr)) => filters.push(expr),
+            _ => (),
+        }
+    }
+    for expr in filters {
+        context.record_used(&expr_sources(expr, &relations, options));
+    }
+
+    let mut columns = vec![];
+    for item in &select.projection {
+        match item {
+            SelectItem::UnnamedExpr(expr) => columns.push(ColumnLineage {
+                column: expr_name(expr),
+                expression: expr.to_string(),
+                sources: expr_sources(expr, &relations, options),
+            }),
+            SelectItem::ExprWithAlias { expr, alias } => columns.push(ColumnLineage {
+                column: alias.value.clone(),
+                expression: expr.to_string(),
+                sources: expr_sources(expr, &relations, options),
+            }),
+            SelectItem::QualifiedWildcard(name, _) => {
+                let name = string_from_sql_object_name(name);
+                for relation in relations.iter().filter(|r| r.name == name) {
+                    columns.extend(wildcard_lineage(relation));
+                }
+            }
+            SelectItem::Wildcard(_) => {
+                for relation in &relations {
+                    columns.extend(wildcard_lineage(relation));
+                }
+            }
+        }
+    }
+    context.record_used(columns.iter().flat_map(|column| &column.sources));
+
+    let into = select
+        .into
+        .as_ref()
+        .map(|into| string_from_sql_object_name(&into.name));
+    Ok((columns, into))
+}
+
+fn relation_from_table_factor(
+    table_factor: &TableFactor,
+    context: &Context,
+) -> Result<Relation, PipelineError> {
+    match table_factor {
+        TableFactor::Table {
+            name, alias, args, ..
+        } => {
+            // Table functions (e.g. `TUMBLE(table, ...)`) take the underlying table as the first argument.
+            let table_name = match args.as_ref().and_then(|args| args.first()) {
+                Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(ident)))) => {
+                    ident.value.clone()
+                }
+                _ => string_from_sql_object_name(name),
+            };
+            let kind = match context.tables.get(&table_name) {
+                Some(columns) => RelationKind::Derived(columns.clone()),
+                None => RelationKind::Source {
+                    table: table_name.clone(),
+                    columns: context.source_columns.get(&table_name).cloned(),
+                },
+            };
+            Ok(Relation {
+                name: alias
+                    .as_ref()
+                    .map_or(table_name, |alias| alias.name.value.clone()),
+                kind,
+            })
+        }
+        TableFactor::Derived {
+            subquery, alias, ..
+        } => {
+            let (columns, _) = query_lineage(subquery, context)?;
+            Ok(Relation {
+                name: alias
+                    .as_ref()
+                    .map_or_else(String::new, |alias| alias.name.value.clone()),
+                kind: RelationKind::Derived(columns),
+            })
+        }
+        _ => Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::GenericError(table_factor.to_string()),
+        )),
+    }
+}
+
+fn wildcard_lineage(relation: &Relation) -> Vec<ColumnLineage> {
+    match &relation.kind {
+        RelationKind::Derived(columns) => columns.clone(),
+        RelationKind::Source {
+            table,
+            columns: Some(columns),
+        } => columns
+            .iter()
+            .map(|column| ColumnLineage {
+                column: column.clone(),
+                expression: format!("{}.{column}", relation.name),
+                sources: vec![SourceColumn {
+                    table: Some(table.clone()),
+                    column: column.clone(),
+                }],
+            })
+            .collect(),
+        RelationKind::Source {
+            table,
+            columns: None,
+        } => vec![ColumnLineage {
+            column: "*".to_string(),
+            expression: format!("{}.*", relation.name),
+            sources: vec![SourceColumn {
+                table: Some(table.clone()),
+                column: "*".to_string(),
+            }],
+        }],
+    }
+}
+
+fn expr_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map_or_else(|| expr.to_string(), |ident| ident.value.clone()),
+        _ => expr.to_string(),
+    }
+}
+
+fn expr_sources(expr: &Expr, relations: &[Relation], options: SqlOptions) -> Vec<SourceColumn> {
+    let mut columns = vec![];
+    collect_columns(expr, &mut columns);
+
+    let mut sources = vec![];
+    for idents in columns {
+        for source in resolve_column(&idents, relations, options) {
+            push_unique(&mut sources, source);
+        }
+    }
+    sources
+}
+
+fn collect_columns(expr: &Expr, columns: &mut Vec<Vec<Ident>>) {
+    match expr {
+        Expr::Identifier(ident) => columns.push(vec![ident.clone()]),
+        Expr::CompoundIdentifier(idents) => columns.push(idents.clone()),
+        Expr::BinaryOp { left, right, .. } => {
+            collect_columns(left, columns);
+            collect_columns(right, columns);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => collect_columns(expr, columns),
+        Expr::Trim {
+            expr, trim_what, ..
+        } => {
+            collect_columns(expr, columns);
+            if let Some(trim_what) = trim_what {
+                collect_columns(trim_what, columns);
+            }
+        }
+        Expr::Like { expr, pattern, .. } => {
+            collect_columns(expr, columns);
+            collect_columns(pattern, columns);
+        }
+        Expr::InList { expr, list, .. } => {
+            collect_columns(expr, columns);
+            for item in list {
+                collect_columns(item, columns);
+            }
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            collect_columns(expr, columns);
+            collect_columns(low, columns);
+            collect_columns(high, columns);
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            for expr in operand.iter().chain(else_result.iter()) {
+                collect_columns(expr, columns);
+            }
+            for expr in conditions.iter().chain(results.iter()) {
+                collect_columns(expr, columns);
+            }
+        }
+        Expr::Function(function) => {
+            for arg in &function.args {
+                match arg {
+                    FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(expr),
+                        ..
+                    }
+                    | FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                        collect_columns(expr, columns)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn resolve_column(
+    idents: &[Ident],
+    relations: &[Relation],
+    options: SqlOptions,
+) -> Vec<SourceColumn> {
+    let (column_ident, relation_ident) = match idents {
+        [column] => (column, None),
+        [.., relation, column] => (column, Some(relation)),
+        [] => return vec![],
+    };
+    let column = &column_ident.value;
+
+    let candidates = match relation_ident {
+        Some(ident) => relations
+            .iter()
+            .filter(|r| ident_matches(ident, options.case_sensitive, &r.name))
+            .collect(),
+        None if relations.len() == 1 => relations.iter().collect(),
+        // With multiple relations, an unqualified column comes from the relation known to have it, or else from the
+        // only source table with unknown columns.
+        None => {
+            let having = relations
+                .iter()
+                .filter(|r| r.has_column(column_ident, options.case_sensitive))
+                .collect::<Vec<_>>();
+            if having.is_empty() {
+                relations
+                    .iter()
+                    .filter(|r| matches!(r.kind, RelationKind::Source { columns: None, .. }))
+                    .collect()
+            } else {
+                having
+            }
+        }
+    };
+
+    match candidates.as_slice() {
+        [relation] => match &relation.kind {
+            RelationKind::Source { table, .. } => vec![SourceColumn {
+                table: Some(table.clone()),
+                column: column.clone(),
+            }],
+            RelationKind::Derived(columns) => columns
+                .iter()
+                .find(|c| ident_matches(column_ident, options.case_sensitive, &c.column))
+                .map_or_else(
+                    || {
+                        vec![SourceColumn {
+                            table: None,
+                            column: column.clone(),
+                        }]
+                    },
+                    |c| c.sources.clone(),
+                ),
+        },
+        _ => vec![SourceColumn {
+            table: None,
+            column: column.clone(),
+        }],
+    }
+}
+
+fn push_unique(sources: &mut Vec<SourceColumn>, source: SourceColumn) {
+    if !sources.contains(&source) {
+        sources.push(source);
+    }
+}
@@ -1,11 +1,23 @@
 mod aggregation;
 pub mod builder;
+pub mod compaction_operator;
+#[cfg(feature = "enrichment")]
+pub mod enrichment_operator;
 pub mod errors;
 mod expression;
+#[cfg(feature = "javascript")]
+pub mod javascript_operator;
+pub mod lineage;
+pub mod operator_options;
 mod pipeline_builder;
 mod planner;
 mod product;
 mod projection;
+#[cfg(feature = "python")]
+pub mod python_operator;
+pub mod record_transform;
+pub mod reorder_operator;
+mod router;
 mod selection;
 mod table_operator;
 mod window;
@@ -0,0 +1,98 @@
+//! Options shared by the built-in operators configured in the `operators` section.
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+
+use crate::pipeline::errors::OperatorOptionsError;
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// Every field type, for operators exchanging records as JSON, which every type converts to.
+pub const ALL_TYPES: [FieldType; 16] = [
+    FieldType::UInt,
+    FieldType::U128,
+    FieldType::Int,
+    FieldType::I128,
+    FieldType::Float,
+    FieldType::Boolean,
+    FieldType::String,
+    FieldType::Text,
+    FieldType::Binary,
+    FieldType::Decimal,
+    FieldType::Timestamp,
+    FieldType::Date,
+    FieldType::Json,
+    FieldType::Point,
+    FieldType::Duration,
+    FieldType::Money,
+];
+
+pub fn required<'a>(
+    options: &'a OperatorOptions,
+    name: &'static str,
+) -> Result<&'a str, OperatorOptionsError> {
+    options
+        .get(name)
+        .map(String::as_str)
+        .ok_or(OperatorOptionsError::MissingOption(name))
+}
+
+/// Parses the output schema from `columns`, `name:type` pairs separated by commas, and the optional
+/// `primary_key`, column names separated by commas.
+pub fn parse_schema(
+    options: &OperatorOptions,
+    supported_types: &[FieldType],
+) -> Result<Schema, OperatorOptionsError> {
+    let primary_key = options
+        .get("primary_key")
+        .map(|columns| split(columns))
+        .unwrap_or_default();
+    let mut schema = Schema::new();
+    for column in split(required(options, "columns")?) {
+        let (name, typ) = column
+            .split_once(':')
+            .ok_or_else(|| OperatorOptionsError::InvalidColumn(column.to_string()))?;
+        let (name, typ) = (name.trim(), typ.trim());
+        let field_type = FieldType::try_from(typ)
+            .ok()
+            .filter(|field_type| supported_types.contains(field_type))
+            .ok_or_else(|| {
+                OperatorOptionsError::UnsupportedType(name.to_string(), typ.to_string())
+            })?;
+        schema.field(
+            FieldDefinition::new(
+                name.to_string(),
+                field_type,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            primary_key.contains(&name),
+        );
+    }
+    if let Some(column) = primary_key
+        .iter()
+        .find(|column| schema.get_field_index(column).is_err())
+    {
+        return Err(OperatorOptionsError::UnknownPrimaryKey(column.to_string()));
+    }
+    Ok(schema)
+}
+
+/// Parses `batch_size`, how many operations are buffered before the function is called.
+pub fn parse_batch_size(options: &OperatorOptions) -> Result<usize, OperatorOptionsError> {
+    match options.get("batch_size") {
+        None => Ok(DEFAULT_BATCH_SIZE),
+        Some(batch_size) => batch_size
+            .parse()
+            .ok()
+            .filter(|batch_size| *batch_size > 0)
+            .ok_or_else(|| OperatorOptionsError::InvalidBatchSize(batch_size.clone())),
+    }
+}
+
+fn split(list: &str) -> Vec<&str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
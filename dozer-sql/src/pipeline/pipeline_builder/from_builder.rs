@@ -134,8 +134,11 @@ fn insert_table_operator_processor_to_pipeline(
             operator.name,
             query_context.get_next_processor_id()
         );
-        let processor =
-            TableOperatorProcessorFactory::new(processor_name.clone(), operator.clone());
+        let processor = TableOperatorProcessorFactory::new(
+            processor_name.clone(),
+            operator.clone(),
+            query_context.options,
+        );
 
         let source_name = processor
             .get_source_name()
@@ -172,8 +175,11 @@ fn insert_table_operator_processor_to_pipeline(
         })
     } else if operator.name.to_uppercase() == "TUMBLE" || operator.name.to_uppercase() == "HOP" {
         let window_processor_name = format!("window_{}", query_context.get_next_processor_id());
-        let window_processor =
-            WindowProcessorFactory::new(window_processor_name.clone(), operator.clone());
+        let window_processor = WindowProcessorFactory::new(
+            window_processor_name.clone(),
+            operator.clone(),
+            query_context.options,
+        );
 
         let window_source_name = window_processor.get_source_name()?;
         let mut window_entry_points = vec![];
@@ -55,6 +55,7 @@ pub(crate) fn insert_join_to_pipeline(
             left_name_or_alias.clone(),
             right_name_or_alias,
             join.join_operator.clone(),
+            query_context.options,
         );
 
         let mut pipeline_entry_points = vec![];
@@ -185,8 +186,11 @@ fn insert_table_operator_to_pipeline(
             table_operator.name,
             query_context.get_next_processor_id()
         );
-        let processor =
-            TableOperatorProcessorFactory::new(processor_name.clone(), table_operator.clone());
+        let processor = TableOperatorProcessorFactory::new(
+            processor_name.clone(),
+            table_operator.clone(),
+            query_context.options,
+        );
 
         let source_name = processor
             .get_source_name()
@@ -214,8 +218,11 @@ fn insert_table_operator_to_pipeline(
     {
         // for now, we only support window operators
         let window_processor_name = format!("window_{}", query_context.get_next_processor_id());
-        let window_processor_factory =
-            WindowProcessorFactory::new(window_processor_name.clone(), table_operator.clone());
+        let window_processor_factory = WindowProcessorFactory::new(
+            window_processor_name.clone(),
+            table_operator.clone(),
+            query_context.options,
+        );
         let window_source_name = window_processor_factory.get_source_name()?;
         let mut window_entry_points = vec![];
 
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::builder::ExpressionBuilder;
 use crate::pipeline::expression::execution::Expression;
@@ -23,6 +24,7 @@ pub struct CommonPlanner {
     pub having: Option<Expression>,
     pub groupby: Vec<Expression>,
     pub projection_output: Vec<Expression>,
+    options: SqlOptions,
 }
 
 impl CommonPlanner {
@@ -33,12 +35,15 @@ impl CommonPlanner {
         output_schema: &mut Schema,
     ) -> Result<(), PipelineError> {
         let expr_type = expr.get_type(input_schema)?;
-        output_schema.fields.push(FieldDefinition::new(
-            alias.unwrap_or_else(|| expr.to_string(input_schema)),
-            expr_type.return_type,
-            expr_type.nullable,
-            expr_type.source,
-        ));
+        output_schema.fields.push(
+            FieldDefinition::new(
+                alias.unwrap_or_else(|| expr.to_string(input_schema)),
+                expr_type.return_type,
+                expr_type.nullable,
+                expr_type.source,
+            )
+            .with_enum_values(expr.enum_values(input_schema)),
+        );
 
         Ok(())
     }
@@ -73,6 +78,7 @@ impl CommonPlanner {
         for (expr, alias) in expr_items {
             let mut builder = ExpressionBuilder::new(
                 self.input_schema.fields.len() + self.aggregation_output.len(),
+                self.options,
             );
             let projection_expression = builder.build(true, &expr, &self.input_schema)?;
 
@@ -109,6 +115,7 @@ impl CommonPlanner {
         for (expr, alias) in expr_items {
             let mut builder = ExpressionBuilder::new(
                 self.input_schema.fields.len() + self.aggregation_output.len(),
+                self.options,
             );
             let projection_expression = builder.build(true, &expr, &self.input_schema)?;
 
@@ -138,6 +145,7 @@ impl CommonPlanner {
         let mut builder = ExpressionBuilder::from(
             self.input_schema.fields.len(),
             self.aggregation_output.clone(),
+            self.options,
         );
         let having_expression = builder.build(true, &expr, &self.input_schema)?;
 
@@ -167,6 +175,7 @@ impl CommonPlanner {
         for expr in expr_items {
             let mut builder = ExpressionBuilder::new(
                 self.input_schema.fields.len() + self.aggregation_output.len(),
+                self.options,
             );
             let groupby_expression = builder.build(false, &expr, &self.input_schema)?;
             self.groupby.push(groupby_expression.clone());
@@ -206,7 +215,7 @@ impl CommonPlanner {
         Ok(())
     }
 
-    pub fn new(input_schema: Schema) -> Self {
+    pub fn new(input_schema: Schema, options: SqlOptions) -> Self {
         Self {
             input_schema: input_schema.clone(),
             post_aggregation_schema: input_schema,
@@ -215,6 +224,7 @@ impl CommonPlanner {
             having: None,
             groupby: Vec::new(),
             projection_output: Vec::new(),
+            options,
         }
     }
 }
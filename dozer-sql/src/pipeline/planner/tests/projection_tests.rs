@@ -1,5 +1,6 @@
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::operator::BinaryOperatorType;
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
@@ -39,7 +40,7 @@ fn test_basic_projection() {
         )
         .to_owned();
 
-    let mut projection_planner = CommonPlanner::new(schema);
+    let mut projection_planner = CommonPlanner::new(schema, SqlOptions::default());
     let statement = get_select(sql).unwrap();
 
     projection_planner.plan(*statement).unwrap();
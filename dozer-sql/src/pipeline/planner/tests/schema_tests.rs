@@ -1,3 +1,4 @@
+use crate::pipeline::builder::SqlOptions;
 use crate::pipeline::planner::projection::CommonPlanner;
 use crate::pipeline::tests::utils::get_select;
 use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
@@ -44,7 +45,7 @@ fn test_schema_index_partial_group_by() {
         )
         .to_owned();
 
-    let mut projection_planner = CommonPlanner::new(schema);
+    let mut projection_planner = CommonPlanner::new(schema, SqlOptions::default());
     let statement = get_select(sql).unwrap();
 
     projection_planner.plan(*statement).unwrap();
@@ -97,7 +98,7 @@ fn test_schema_index_full_group_by() {
         )
         .to_owned();
 
-    let mut projection_planner = CommonPlanner::new(schema);
+    let mut projection_planner = CommonPlanner::new(schema, SqlOptions::default());
     let statement = get_select(sql).unwrap();
 
     projection_planner.plan(*statement).unwrap();
@@ -7,6 +7,7 @@ use dozer_core::{
 };
 use dozer_types::{
     errors::internal::BoxedError,
+    identifier,
     types::{FieldDefinition, Record, Schema},
 };
 use sqlparser::ast::{
@@ -14,7 +15,10 @@ use sqlparser::ast::{
     JoinOperator as SqlJoinOperator,
 };
 
-use crate::pipeline::{builder::SchemaSQLContext, expression::builder::extend_schema_source_def};
+use crate::pipeline::{
+    builder::{SchemaSQLContext, SqlOptions},
+    expression::builder::{extend_schema_source_def, ident_matches},
+};
 use crate::pipeline::{errors::JoinError, expression::builder::NameOrAlias};
 use crate::pipeline::{errors::PipelineError, expression::builder::ExpressionBuilder};
 
@@ -32,6 +36,7 @@ pub struct JoinProcessorFactory {
     left: Option<NameOrAlias>,
     right: Option<NameOrAlias>,
     join_operator: SqlJoinOperator,
+    options: SqlOptions,
 }
 
 impl JoinProcessorFactory {
@@ -40,12 +45,14 @@ impl JoinProcessorFactory {
         left: Option<NameOrAlias>,
         right: Option<NameOrAlias>,
         join_operator: SqlJoinOperator,
+        options: SqlOptions,
     ) -> Self {
         Self {
             id,
             left,
             right,
             join_operator,
+            options,
         }
     }
 }
@@ -170,8 +177,12 @@ impl ProcessorFactory<SchemaSQLContext> for JoinProcessorFactory {
             right_schema.primary_index.clone()
         };
 
-        let (left_join_key_indexes, right_join_key_indexes) =
-            parse_join_constraint(expression, &left_schema, &right_schema)?;
+        let (left_join_key_indexes, right_join_key_indexes) = parse_join_constraint(
+            expression,
+            &left_schema,
+            &right_schema,
+            self.options.case_sensitive,
+        )?;
 
         let left_default_record = Record::nulls_from_schema(&left_schema);
         let left_default_record = record_store.create_record(&left_default_record)?;
@@ -223,6 +234,7 @@ fn parse_join_constraint(
     expression: &sqlparser::ast::Expr,
     left_join_table: &Schema,
     right_join_table: &Schema,
+    case_sensitive: bool,
 ) -> Result<(Vec<usize>, Vec<usize>), JoinError> {
     match expression {
         SqlExpr::BinaryOp {
@@ -232,10 +244,14 @@ fn parse_join_constraint(
         } => match op {
             BinaryOperator::And => {
                 let (mut left_keys, mut right_keys) =
-                    parse_join_constraint(left, left_join_table, right_join_table)?;
-
-                let (mut left_keys_from_right, mut right_keys_from_right) =
-                    parse_join_constraint(right, left_join_table, right_join_table)?;
+                    parse_join_constraint(left, left_join_table, right_join_table, case_sensitive)?;
+
+                let (mut left_keys_from_right, mut right_keys_from_right) = parse_join_constraint(
+                    right,
+                    left_join_table,
+                    right_join_table,
+                    case_sensitive,
+                )?;
                 left_keys.append(&mut left_keys_from_right);
                 right_keys.append(&mut right_keys_from_right);
 
@@ -245,13 +261,21 @@ fn parse_join_constraint(
                 let mut left_key_indexes = vec![];
                 let mut right_key_indexes = vec![];
 
-                let (left_arr, right_arr) =
-                    parse_join_eq_expression(left, left_join_table, right_join_table)?;
+                let (left_arr, right_arr) = parse_join_eq_expression(
+                    left,
+                    left_join_table,
+                    right_join_table,
+                    case_sensitive,
+                )?;
                 left_key_indexes.extend(left_arr);
                 right_key_indexes.extend(right_arr);
 
-                let (left_arr, right_arr) =
-                    parse_join_eq_expression(right, left_join_table, right_join_table)?;
+                let (left_arr, right_arr) = parse_join_eq_expression(
+                    right,
+                    left_join_table,
+                    right_join_table,
+                    case_sensitive,
+                )?;
                 left_key_indexes.extend(left_arr);
                 right_key_indexes.extend(right_arr);
 
@@ -267,13 +291,16 @@ fn parse_join_eq_expression(
     expr: &SqlExpr,
     left_join_table: &Schema,
     right_join_table: &Schema,
+    case_sensitive: bool,
 ) -> Result<(Vec<usize>, Vec<usize>), JoinError> {
     let mut left_key_indexes = vec![];
     let mut right_key_indexes = vec![];
     let (left_keys, right_keys) = match expr.clone() {
-        SqlExpr::Identifier(ident) => parse_identifier(&[ident], left_join_table, right_join_table),
+        SqlExpr::Identifier(ident) => {
+            parse_identifier(&[ident], left_join_table, right_join_table, case_sensitive)
+        }
         SqlExpr::CompoundIdentifier(ident) => {
-            parse_identifier(&ident, left_join_table, right_join_table)
+            parse_identifier(&ident, left_join_table, right_join_table, case_sensitive)
         }
         _ => {
             return Err(JoinError::UnsupportedJoinConstraint(
@@ -295,10 +322,11 @@ fn parse_identifier(
     ident: &[Ident],
     left_join_schema: &Schema,
     right_join_schema: &Schema,
+    case_sensitive: bool,
 ) -> Result<(Option<usize>, Option<usize>), JoinError> {
-    let left_idx = get_field_index(ident, left_join_schema)?;
+    let left_idx = get_field_index(ident, left_join_schema, case_sensitive)?;
 
-    let right_idx = get_field_index(ident, right_join_schema)?;
+    let right_idx = get_field_index(ident, right_join_schema, case_sensitive)?;
 
     match (left_idx, right_idx) {
         (None, None) => Err(JoinError::InvalidFieldSpecified(
@@ -312,38 +340,56 @@ fn parse_identifier(
     }
 }
 
-pub fn get_field_index(ident: &[Ident], schema: &Schema) -> Result<Option<usize>, JoinError> {
+pub fn get_field_index(
+    ident: &[Ident],
+    schema: &Schema,
+    case_sensitive: bool,
+) -> Result<Option<usize>, JoinError> {
     let tables_matches = |table_ident: &Ident, fd: &FieldDefinition| -> bool {
         match fd.source.clone() {
             dozer_types::types::SourceDefinition::Table {
                 connection: _,
                 name,
-            } => name == table_ident.value,
-            dozer_types::types::SourceDefinition::Alias { name } => name == table_ident.value,
+            } => ident_matches(table_ident, case_sensitive, &name),
+            dozer_types::types::SourceDefinition::Alias { name } => {
+                ident_matches(table_ident, case_sensitive, &name)
+            }
             dozer_types::types::SourceDefinition::Dynamic => false,
         }
     };
 
     let field_index = match ident.len() {
         1 => {
-            let field_index = schema
-                .fields
-                .iter()
-                .enumerate()
-                .find(|(_, f)| f.name == ident[0].value)
-                .map(|(idx, fd)| (idx, fd.clone()));
+            let field_index = identifier::matching(
+                &ident[0].value,
+                ident[0].quote_style.is_some(),
+                case_sensitive,
+                schema.fields.iter().enumerate(),
+                |(_, f)| f.name.as_str(),
+            )
+            .into_iter()
+            .next()
+            .map(|(idx, fd)| (idx, fd.clone()));
             field_index
         }
         2 => {
             let table_name = ident.first().expect("table_name is expected");
             let field_name = ident.last().expect("field_name is expected");
 
-            let index = schema
-                .fields
-                .iter()
-                .enumerate()
-                .find(|(_, f)| tables_matches(table_name, f) && f.name == field_name.value)
-                .map(|(idx, fd)| (idx, fd.clone()));
+            let index = identifier::matching(
+                &field_name.value,
+                field_name.quote_style.is_some(),
+                case_sensitive,
+                schema
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| tables_matches(table_name, f)),
+                |(_, f)| f.name.as_str(),
+            )
+            .into_iter()
+            .next()
+            .map(|(idx, fd)| (idx, fd.clone()));
             index
         }
         // 3 => {
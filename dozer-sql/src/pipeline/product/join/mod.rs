@@ -5,4 +5,7 @@ pub mod factory;
 pub(crate) mod operator;
 mod processor;
 
+#[cfg(test)]
+mod tests;
+
 type JoinResult<T> = Result<T, JoinError>;
@@ -1,10 +1,13 @@
+use std::collections::HashSet;
+
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::epoch::Epoch;
 use dozer_core::executor_operation::ProcessorOperation;
 use dozer_core::node::{PortHandle, Processor};
-use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::processor_record::{ProcessorRecord, ProcessorRecordStore};
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_types::errors::internal::BoxedError;
+use dozer_types::indexmap::IndexMap;
 use dozer_types::labels::Labels;
 use dozer_types::types::Lifetime;
 use metrics::{
@@ -20,6 +23,14 @@ use super::operator::{JoinAction, JoinBranch, JoinOperator};
 pub struct ProductProcessor {
     join_operator: JoinOperator,
     labels: Labels,
+    /// Sources doing their initial snapshot, with the input port they reach the join through.
+    snapshotting: HashSet<(PortHandle, String)>,
+    /// Net count of every output record while sources are snapshotting.
+    ///
+    /// While one side is still loading, the other side's records are joined with nothing, or with part of what they
+    /// will match, and those rows are retracted again. Holding the output back until all snapshots are done
+    /// cancels them out, so only the joined snapshots are emitted.
+    held_output: IndexMap<ProcessorRecord, i64>,
 }
 
 const LEFT_LOOKUP_SIZE: &str = "product.left_lookup_size";
@@ -59,6 +70,66 @@ impl ProductProcessor {
         Self {
             join_operator,
             labels,
+            snapshotting: HashSet::new(),
+            held_output: IndexMap::new(),
+        }
+    }
+
+    fn send(
+        &mut self,
+        records: Vec<(JoinAction, ProcessorRecord)>,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) {
+        for (action, record) in records {
+            if !self.snapshotting.is_empty() {
+                let count = self.held_output.entry(record).or_default();
+                match action {
+                    JoinAction::Insert => *count += 1,
+                    JoinAction::Delete => *count -= 1,
+                }
+                continue;
+            }
+
+            match action {
+                JoinAction::Insert => {
+                    fw.send(
+                        ProcessorOperation::Insert { new: record },
+                        DEFAULT_PORT_HANDLE,
+                    );
+                }
+                JoinAction::Delete => {
+                    fw.send(
+                        ProcessorOperation::Delete { old: record },
+                        DEFAULT_PORT_HANDLE,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Sends the net output held back while sources were snapshotting.
+    fn release_held_output(&mut self, fw: &mut dyn ProcessorChannelForwarder) {
+        let held_output = std::mem::take(&mut self.held_output);
+        // Retractions only remain for rows emitted before a snapshot was announced.
+        for (record, count) in held_output.iter().filter(|(_, count)| **count < 0) {
+            for _ in 0..-count {
+                fw.send(
+                    ProcessorOperation::Delete {
+                        old: record.clone(),
+                    },
+                    DEFAULT_PORT_HANDLE,
+                );
+            }
+        }
+        for (record, count) in held_output.into_iter().filter(|(_, count)| *count > 0) {
+            for _ in 0..count {
+                fw.send(
+                    ProcessorOperation::Insert {
+                        new: record.clone(),
+                    },
+                    DEFAULT_PORT_HANDLE,
+                );
+            }
         }
     }
 
@@ -156,23 +227,35 @@ impl Processor for ProductProcessor {
             increment_counter!(UNSATISFIED_JOINS, self.labels.clone());
         }
 
-        for (action, record) in records {
-            match action {
-                JoinAction::Insert => {
-                    fw.send(
-                        ProcessorOperation::Insert { new: record },
-                        DEFAULT_PORT_HANDLE,
-                    );
-                }
-                JoinAction::Delete => {
-                    fw.send(
-                        ProcessorOperation::Delete { old: record },
-                        DEFAULT_PORT_HANDLE,
-                    );
-                }
-            }
-        }
+        self.send(records, fw);
+
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        port: PortHandle,
+        connection_name: &str,
+    ) -> Result<(), BoxedError> {
+        self.snapshotting
+            .insert((port, connection_name.to_string()));
+        Ok(())
+    }
 
+    fn on_source_snapshotting_done(
+        &mut self,
+        port: PortHandle,
+        connection_name: &str,
+        _record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        if self
+            .snapshotting
+            .remove(&(port, connection_name.to_string()))
+            && self.snapshotting.is_empty()
+        {
+            self.release_held_output(fw);
+        }
         Ok(())
     }
 }
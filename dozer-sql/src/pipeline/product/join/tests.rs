@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use dozer_core::test_harness::{schema, Emitted, ProcessorTestHarness, ScriptStep};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, FieldType, Record, Schema};
+use sqlparser::ast::{
+    BinaryOperator, Expr as SqlExpr, Ident, JoinConstraint as SqlJoinConstraint,
+    JoinOperator as SqlJoinOperator,
+};
+
+use super::factory::{JoinProcessorFactory, LEFT_JOIN_PORT, RIGHT_JOIN_PORT};
+use crate::pipeline::builder::{SchemaSQLContext, SqlOptions};
+
+fn int_schema(columns: &[&str]) -> Schema {
+    let columns = columns
+        .iter()
+        .map(|column| (*column, FieldType::Int))
+        .collect::<Vec<_>>();
+    schema(&columns, &[])
+}
+
+/// `users LEFT JOIN orders ON id = user_id`
+fn factory() -> JoinProcessorFactory {
+    let constraint = SqlExpr::BinaryOp {
+        left: Box::new(SqlExpr::Identifier(Ident::new("id"))),
+        op: BinaryOperator::Eq,
+        right: Box::new(SqlExpr::Identifier(Ident::new("user_id"))),
+    };
+    JoinProcessorFactory::new(
+        "join".to_string(),
+        None,
+        None,
+        SqlJoinOperator::LeftOuter(SqlJoinConstraint::On(constraint)),
+        SqlOptions::default(),
+    )
+}
+
+fn harness(factory: &JoinProcessorFactory) -> ProcessorTestHarness<'_, SchemaSQLContext> {
+    ProcessorTestHarness::new(
+        factory,
+        HashMap::from([
+            (
+                LEFT_JOIN_PORT,
+                (int_schema(&["id"]), SchemaSQLContext::default()),
+            ),
+            (
+                RIGHT_JOIN_PORT,
+                (
+                    int_schema(&["user_id", "amount"]),
+                    SchemaSQLContext::default(),
+                ),
+            ),
+        ]),
+    )
+    .unwrap()
+}
+
+fn record(values: &[Option<i64>]) -> Record {
+    Record::new(
+        values
+            .iter()
+            .map(|value| value.map_or(Field::Null, Field::Int))
+            .collect(),
+    )
+}
+
+fn insert(port: u16, values: &[Option<i64>]) -> ScriptStep {
+    ScriptStep::insert(port, record(values))
+}
+
+fn started(port: u16) -> ScriptStep {
+    ScriptStep::snapshotting_started(port, "db")
+}
+
+fn done(port: u16) -> ScriptStep {
+    ScriptStep::snapshotting_done(port, "db")
+}
+
+fn emitted_insert(values: &[Option<i64>]) -> Emitted {
+    Emitted::insert(DEFAULT_PORT_HANDLE, record(values))
+}
+
+#[test]
+fn test_join_holds_output_during_snapshot() {
+    let factory = factory();
+    let mut harness = harness(&factory);
+    harness.assert_output(
+        [
+            started(LEFT_JOIN_PORT),
+            started(RIGHT_JOIN_PORT),
+            insert(LEFT_JOIN_PORT, &[Some(1)]),
+            insert(LEFT_JOIN_PORT, &[Some(2)]),
+            ScriptStep::Commit,
+            insert(RIGHT_JOIN_PORT, &[Some(1), Some(10)]),
+            done(LEFT_JOIN_PORT),
+            insert(RIGHT_JOIN_PORT, &[Some(1), Some(20)]),
+        ],
+        &[],
+    );
+
+    // Once both sides are loaded, only the converged join is emitted, without the unmatched row of user 1.
+    harness.assert_output(
+        [done(RIGHT_JOIN_PORT)],
+        &[
+            emitted_insert(&[Some(2), None, None]),
+            emitted_insert(&[Some(1), Some(1), Some(10)]),
+            emitted_insert(&[Some(1), Some(1), Some(20)]),
+        ],
+    );
+
+    harness.assert_output(
+        [insert(LEFT_JOIN_PORT, &[Some(3)])],
+        &[emitted_insert(&[Some(3), None, None])],
+    );
+}
+
+#[test]
+fn test_join_without_snapshot() {
+    let factory = factory();
+    let mut harness = harness(&factory);
+    harness.assert_output(
+        [insert(LEFT_JOIN_PORT, &[Some(1)])],
+        &[emitted_insert(&[Some(1), None, None])],
+    );
+}
@@ -111,16 +111,22 @@ fn validate_set_operation_input_schemas(
         if !is_similar_fields(left, right) {
             return Err(PipelineError::SetError(SetError::InvalidInputSchemas));
         }
-        output_fields.push(FieldDefinition::new(
-            left.name.clone(),
-            left.typ,
-            left.nullable,
-            SourceDefinition::Dynamic,
-        ));
+        output_fields.push(
+            FieldDefinition::new(
+                left.name.clone(),
+                left.typ,
+                left.nullable,
+                SourceDefinition::Dynamic,
+            )
+            .with_enum_values(left.enum_values.clone()),
+        );
     }
     Ok(output_fields)
 }
 
 fn is_similar_fields(left: &FieldDefinition, right: &FieldDefinition) -> bool {
-    left.name == right.name && left.typ == right.typ && left.nullable == right.nullable
+    left.name == right.name
+        && left.typ == right.typ
+        && left.nullable == right.nullable
+        && left.enum_values == right.enum_values
 }
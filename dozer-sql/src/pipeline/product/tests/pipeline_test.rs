@@ -18,7 +18,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use crate::pipeline::window::tests::pipeline_test::TestSink;
 
 const USER_PORT: u16 = 0 as PortHandle;
@@ -428,7 +428,7 @@ fn test_pipeline_builder() {
         "SELECT  name, dname, salary \
         FROM user JOIN department ON user.department_id = department.did JOIN country ON user.country_id = country.cid ",
         &mut pipeline,
-        Some("results".to_string()),
+        Some("results".to_string()), SqlOptions::default(),
     )
         .unwrap();
 
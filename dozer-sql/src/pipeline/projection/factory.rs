@@ -11,7 +11,7 @@ use dozer_types::{
 };
 use sqlparser::ast::{Expr, Ident, SelectItem};
 
-use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::builder::{SchemaSQLContext, SqlOptions};
 use crate::pipeline::{
     errors::PipelineError,
     expression::{builder::ExpressionBuilder, execution::Expression},
@@ -23,12 +23,17 @@ use super::processor::ProjectionProcessor;
 pub struct ProjectionProcessorFactory {
     select: Vec<SelectItem>,
     id: String,
+    options: SqlOptions,
 }
 
 impl ProjectionProcessorFactory {
     /// Creates a new [`ProjectionProcessorFactory`].
-    pub fn _new(id: String, select: Vec<SelectItem>) -> Self {
-        Self { select, id }
+    pub fn _new(id: String, select: Vec<SelectItem>, options: SqlOptions) -> Self {
+        Self {
+            select,
+            id,
+            options,
+        }
     }
 }
 
@@ -72,13 +77,13 @@ impl ProcessorFactory<SchemaSQLContext> for ProjectionProcessorFactory {
                         })
                         .collect();
                     for f in fields {
-                        if let Ok(res) = parse_sql_select_item(&f, input_schema) {
+                        if let Ok(res) = parse_sql_select_item(&f, input_schema, self.options) {
                             select_expr.push(res)
                         }
                     }
                 }
                 _ => {
-                    if let Ok(res) = parse_sql_select_item(s, input_schema) {
+                    if let Ok(res) = parse_sql_select_item(s, input_schema, self.options) {
                         select_expr.push(res)
                     }
                 }
@@ -90,12 +95,15 @@ impl ProcessorFactory<SchemaSQLContext> for ProjectionProcessorFactory {
         for e in select_expr.iter() {
             let field_name = e.0.clone();
             let field_type = e.1.get_type(input_schema)?;
-            fields.push(FieldDefinition::new(
-                field_name,
-                field_type.return_type,
-                field_type.nullable,
-                field_type.source,
-            ));
+            fields.push(
+                FieldDefinition::new(
+                    field_name,
+                    field_type.return_type,
+                    field_type.nullable,
+                    field_type.source,
+                )
+                .with_enum_values(e.1.enum_values(input_schema)),
+            );
         }
         output_schema.fields = fields;
 
@@ -116,7 +124,7 @@ impl ProcessorFactory<SchemaSQLContext> for ProjectionProcessorFactory {
         match self
             .select
             .iter()
-            .map(|item| parse_sql_select_item(item, schema))
+            .map(|item| parse_sql_select_item(item, schema, self.options))
             .collect::<Result<Vec<(String, Expression)>, PipelineError>>()
         {
             Ok(expressions) => Ok(Box::new(ProjectionProcessor::new(
@@ -131,16 +139,17 @@ impl ProcessorFactory<SchemaSQLContext> for ProjectionProcessorFactory {
 pub(crate) fn parse_sql_select_item(
     sql: &SelectItem,
     schema: &Schema,
+    options: SqlOptions,
 ) -> Result<(String, Expression), PipelineError> {
     match sql {
         SelectItem::UnnamedExpr(sql_expr) => {
-            match ExpressionBuilder::new(0).parse_sql_expression(true, sql_expr, schema) {
+            match ExpressionBuilder::new(0, options).parse_sql_expression(true, sql_expr, schema) {
                 Ok(expr) => Ok((sql_expr.to_string(), expr)),
                 Err(error) => Err(error),
             }
         }
         SelectItem::ExprWithAlias { expr, alias } => {
-            match ExpressionBuilder::new(0).parse_sql_expression(true, expr, schema) {
+            match ExpressionBuilder::new(0, options).parse_sql_expression(true, expr, schema) {
                 Ok(expr) => Ok((alias.value.clone(), expr)),
                 Err(error) => Err(error),
             }
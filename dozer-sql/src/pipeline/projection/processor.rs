@@ -83,6 +83,10 @@ impl Processor for ProjectionProcessor {
             Operation::Insert { ref new } => self.insert(new)?,
             Operation::Update { ref old, ref new } => self.update(old, new)?,
         };
+        // Updates of columns that aren't projected don't change the output.
+        if output_op.is_noop() {
+            return Ok(());
+        }
         let output_op = record_store.create_operation(&output_op)?;
         fw.send(output_op, DEFAULT_PORT_HANDLE);
         Ok(())
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::pyo3::{PyObject, Python, ToPyObject};
+use dozer_types::types::Schema;
+
+use super::options::PythonOperatorOptions;
+use super::transform::PythonTransform;
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::python_udf::{import_module, prepare_python_env};
+use crate::pipeline::record_transform::RecordTransformProcessor;
+
+#[derive(Debug)]
+pub struct PythonProcessorFactory {
+    id: String,
+    options: PythonOperatorOptions,
+}
+
+impl PythonProcessorFactory {
+    pub fn new(id: String, options: PythonOperatorOptions) -> Self {
+        Self { id, options }
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for PythonProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let (_, context) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok((self.options.schema.clone(), context.clone()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?
+            .clone();
+
+        let env_path = prepare_python_env()?;
+        let function = Python::with_gil(|py| -> Result<PyObject, PipelineError> {
+            let module = import_module(py, &env_path, &self.options.module)?;
+            Ok(module
+                .getattr(self.options.function.as_str())?
+                .to_object(py))
+        })?;
+
+        Ok(Box::new(RecordTransformProcessor::new(
+            PythonTransform::new(input_schema, self.options.clone(), function),
+            self.options.batch_size,
+        )))
+    }
+
+    fn type_name(&self) -> String {
+        "Python".to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
@@ -0,0 +1,45 @@
+//! An operator running a Python function on the records of a table.
+//!
+//! Configured as operator `python`, with options:
+//! - `module`: the module defining the function, imported from `$VIRTUAL_ENV` like Python UDFs.
+//! - `function`: the function's name.
+//! - `columns`: the output schema, as `name:type` pairs separated by commas.
+//! - `primary_key`: the output columns forming the primary key, separated by commas. Optional.
+//! - `mode`: `record` to call the function with every record, or `batch` to call it with a list of records and
+//!   get a list of the same length back. Defaults to `record`.
+//! - `batch_size`: how many operations are buffered before the function is called with the GIL acquired once.
+//!   Buffered operations are also sent before every commit. Defaults to 100.
+//!
+//! Records are passed as dicts from column names to values, and results are dicts of the output columns, or
+//! `None` to drop the record. The function must be deterministic, see `RecordTransform`.
+
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::ProcessorFactory;
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+
+use crate::pipeline::builder::SchemaSQLContext;
+
+mod factory;
+mod options;
+mod transform;
+
+#[cfg(test)]
+mod tests;
+
+pub use factory::PythonProcessorFactory;
+pub use options::{PythonOperatorMode, PythonOperatorOptions};
+
+/// Name the operator is registered under.
+pub const OPERATOR_NAME: &str = "python";
+
+pub fn register(registry: &mut OperatorRegistry<SchemaSQLContext>) -> Result<(), ExecutionError> {
+    registry.register(
+        OPERATOR_NAME.to_string(),
+        Box::new(|id: &str, options: &OperatorOptions| {
+            let factory: Box<dyn ProcessorFactory<SchemaSQLContext>> = Box::new(
+                PythonProcessorFactory::new(id.to_string(), PythonOperatorOptions::parse(options)?),
+            );
+            Ok(factory)
+        }),
+    )
+}
@@ -0,0 +1,52 @@
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::{FieldType, Schema};
+
+use crate::pipeline::errors::PythonOperatorError;
+use crate::pipeline::operator_options::{parse_batch_size, parse_schema, required};
+
+/// Output column types the results of the function can be converted to.
+const SUPPORTED_TYPES: [FieldType; 9] = [
+    FieldType::UInt,
+    FieldType::U128,
+    FieldType::Int,
+    FieldType::I128,
+    FieldType::Float,
+    FieldType::Boolean,
+    FieldType::String,
+    FieldType::Text,
+    FieldType::Binary,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PythonOperatorMode {
+    /// The function is called with every record.
+    Record,
+    /// The function is called with a list of records.
+    Batch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PythonOperatorOptions {
+    pub module: String,
+    pub function: String,
+    pub schema: Schema,
+    pub mode: PythonOperatorMode,
+    pub batch_size: usize,
+}
+
+impl PythonOperatorOptions {
+    pub fn parse(options: &OperatorOptions) -> Result<Self, PythonOperatorError> {
+        let mode = match options.get("mode").map(String::as_str) {
+            None | Some("record") => PythonOperatorMode::Record,
+            Some("batch") => PythonOperatorMode::Batch,
+            Some(mode) => return Err(PythonOperatorError::UnknownMode(mode.to_string())),
+        };
+        Ok(Self {
+            module: required(options, "module")?.to_string(),
+            function: required(options, "function")?.to_string(),
+            schema: parse_schema(options, &SUPPORTED_TYPES)?,
+            mode,
+            batch_size: parse_batch_size(options)?,
+        })
+    }
+}
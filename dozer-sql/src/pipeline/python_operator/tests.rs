@@ -0,0 +1,84 @@
+use dozer_core::plugin::OperatorOptions;
+use dozer_types::types::FieldType;
+
+use super::{PythonOperatorMode, PythonOperatorOptions};
+use crate::pipeline::errors::{OperatorOptionsError, PythonOperatorError};
+
+fn options(pairs: &[(&str, &str)]) -> OperatorOptions {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_parse_options() {
+    let parsed = PythonOperatorOptions::parse(&options(&[
+        ("module", "scoring"),
+        ("function", "score"),
+        ("columns", "id: int, score:float,label:string"),
+        ("primary_key", "id"),
+        ("mode", "batch"),
+        ("batch_size", "10"),
+    ]))
+    .unwrap();
+    assert_eq!(parsed.function, "score");
+    assert_eq!(parsed.mode, PythonOperatorMode::Batch);
+    assert_eq!(parsed.batch_size, 10);
+    assert_eq!(parsed.schema.primary_index, vec![0]);
+    assert_eq!(
+        parsed
+            .schema
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("id", FieldType::Int),
+            ("score", FieldType::Float),
+            ("label", FieldType::String)
+        ]
+    );
+
+    let parsed = PythonOperatorOptions::parse(&options(&[
+        ("module", "scoring"),
+        ("function", "score"),
+        ("columns", "score:float"),
+    ]))
+    .unwrap();
+    assert_eq!(parsed.mode, PythonOperatorMode::Record);
+    assert_eq!(parsed.batch_size, 100);
+}
+
+#[test]
+fn test_parse_invalid_options() {
+    let parse = |pairs: &[(&str, &str)]| {
+        let mut pairs = pairs.to_vec();
+        pairs.extend([("module", "scoring"), ("function", "score")]);
+        PythonOperatorOptions::parse(&options(&pairs)).unwrap_err()
+    };
+    assert!(matches!(
+        PythonOperatorOptions::parse(&options(&[("columns", "score:float")])).unwrap_err(),
+        PythonOperatorError::Options(OperatorOptionsError::MissingOption("module"))
+    ));
+    assert!(matches!(
+        parse(&[("columns", "score")]),
+        PythonOperatorError::Options(OperatorOptionsError::InvalidColumn(_))
+    ));
+    assert!(matches!(
+        parse(&[("columns", "at:timestamp")]),
+        PythonOperatorError::Options(OperatorOptionsError::UnsupportedType(_, _))
+    ));
+    assert!(matches!(
+        parse(&[("columns", "score:float"), ("primary_key", "id")]),
+        PythonOperatorError::Options(OperatorOptionsError::UnknownPrimaryKey(_))
+    ));
+    assert!(matches!(
+        parse(&[("columns", "score:float"), ("mode", "stream")]),
+        PythonOperatorError::UnknownMode(_)
+    ));
+    assert!(matches!(
+        parse(&[("columns", "score:float"), ("batch_size", "0")]),
+        PythonOperatorError::Options(OperatorOptionsError::InvalidBatchSize(_))
+    ));
+}
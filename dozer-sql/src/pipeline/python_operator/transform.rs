@@ -0,0 +1,82 @@
+use dozer_types::pyo3::types::{PyDict, PyList};
+use dozer_types::pyo3::{PyAny, PyErr, PyObject, PyResult, Python, ToPyObject};
+use dozer_types::types::{Field, Record, Schema};
+
+use super::options::{PythonOperatorMode, PythonOperatorOptions};
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::python_udf::extract_field;
+use crate::pipeline::record_transform::RecordTransform;
+
+/// Calls a Python function on records, with the GIL acquired once for every batch.
+#[derive(Debug)]
+pub struct PythonTransform {
+    input_schema: Schema,
+    options: PythonOperatorOptions,
+    function: PyObject,
+}
+
+impl PythonTransform {
+    pub fn new(input_schema: Schema, options: PythonOperatorOptions, function: PyObject) -> Self {
+        Self {
+            input_schema,
+            options,
+            function,
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>, record: &Record) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        for (field, value) in self.input_schema.fields.iter().zip(&record.values) {
+            let value = match value {
+                Field::Null => py.None(),
+                Field::Json(_) | Field::Point(_) | Field::Duration(_) => {
+                    value.to_string().to_object(py)
+                }
+                value => value.to_object(py),
+            };
+            dict.set_item(&field.name, value)?;
+        }
+        Ok(dict)
+    }
+
+    fn parse_result(&self, result: &PyAny) -> Result<Option<Record>, PipelineError> {
+        if result.is_none() {
+            return Ok(None);
+        }
+        let dict: &PyDict = result.downcast().map_err(PyErr::from)?;
+        let values = self
+            .options
+            .schema
+            .fields
+            .iter()
+            .map(|field| match dict.get_item(&field.name) {
+                Some(value) if !value.is_none() => extract_field(value, &field.typ),
+                _ => Ok(Field::Null),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Record::new(values)))
+    }
+}
+
+impl RecordTransform for PythonTransform {
+    fn transform(&mut self, records: &[&Record]) -> Result<Vec<Option<Record>>, PipelineError> {
+        Python::with_gil(|py| -> Result<_, PipelineError> {
+            let args = records
+                .iter()
+                .map(|record| self.to_dict(py, record))
+                .collect::<PyResult<Vec<_>>>()?;
+            let function = self.function.as_ref(py);
+            let results = match self.options.mode {
+                PythonOperatorMode::Record => args
+                    .into_iter()
+                    .map(|arg| function.call1((arg,)))
+                    .collect::<PyResult<Vec<_>>>()?,
+                PythonOperatorMode::Batch => function.call1((PyList::new(py, args),))?.extract()?,
+            };
+            results
+                .into_iter()
+                .map(|result| self.parse_result(result))
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,114 @@
+//! A processor transforming records in batches with a function written in another language.
+
+use std::fmt::Debug;
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Operation, Record};
+
+use crate::pipeline::errors::PipelineError;
+
+/// Transforms every record into one record, or drops it.
+///
+/// Must be deterministic, as the old record of an update or delete is transformed again to find the record it
+/// replaces.
+pub trait RecordTransform: Send + Sync + Debug {
+    /// Returns one result for every record.
+    fn transform(&mut self, records: &[&Record]) -> Result<Vec<Option<Record>>, PipelineError>;
+}
+
+/// Buffers operations, transforming their records with one call once `batch_size` operations are buffered, and
+/// before every commit.
+#[derive(Debug)]
+pub struct RecordTransformProcessor<T> {
+    transform: T,
+    batch_size: usize,
+    buffer: Vec<Operation>,
+}
+
+impl<T: RecordTransform> RecordTransformProcessor<T> {
+    pub fn new(transform: T, batch_size: usize) -> Self {
+        Self {
+            transform,
+            batch_size,
+            buffer: vec![],
+        }
+    }
+
+    fn send_buffer(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        let ops = std::mem::take(&mut self.buffer);
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let records = ops
+            .iter()
+            .flat_map(|op| match op {
+                Operation::Insert { new } => vec![new],
+                Operation::Delete { old } => vec![old],
+                Operation::Update { old, new } => vec![old, new],
+            })
+            .collect::<Vec<_>>();
+        let results = self.transform.transform(&records)?;
+        if results.len() != records.len() {
+            return Err(
+                PipelineError::TransformResultCountMismatch(records.len(), results.len()).into(),
+            );
+        }
+        let mut results = results.into_iter();
+        let mut next = || results.next().expect("One result for every record");
+
+        for op in ops {
+            let op = match op {
+                Operation::Insert { .. } => next().map(|new| Operation::Insert { new }),
+                Operation::Delete { .. } => next().map(|old| Operation::Delete { old }),
+                Operation::Update { .. } => match (next(), next()) {
+                    (Some(old), Some(new)) => Some(Operation::Update { old, new }),
+                    (Some(old), None) => Some(Operation::Delete { old }),
+                    (None, Some(new)) => Some(Operation::Insert { new }),
+                    (None, None) => None,
+                },
+            };
+            if let Some(op) = op {
+                fw.send(record_store.create_operation(&op)?, DEFAULT_PORT_HANDLE);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: RecordTransform> Processor for RecordTransformProcessor<T> {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.buffer.push(record_store.load_operation(&op)?);
+        if self.buffer.len() >= self.batch_size {
+            self.send_buffer(record_store, fw)?;
+        }
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.send_buffer(record_store, fw)
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use dozer_core::plugin::OperatorOptions;
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::Schema;
+
+use super::processor::ReorderProcessor;
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{PipelineError, ReorderOperatorError};
+use crate::pipeline::operator_options::required;
+
+const DEFAULT_MAX_DELAY_MS: u64 = 1000;
+const DEFAULT_MAX_BUFFERED: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorderOperatorOptions {
+    pub sequence_column: String,
+    /// `None` to use the input's primary key.
+    pub key_columns: Option<Vec<String>>,
+    pub max_delay: Duration,
+    pub max_buffered: usize,
+}
+
+impl ReorderOperatorOptions {
+    pub fn parse(options: &OperatorOptions) -> Result<Self, ReorderOperatorError> {
+        let sequence_column = required(options, "sequence_column")?.to_string();
+        let key_columns = options.get("key_columns").map(|columns| {
+            columns
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let max_delay_ms = match options.get("max_delay_ms") {
+            None => DEFAULT_MAX_DELAY_MS,
+            Some(max_delay_ms) => max_delay_ms
+                .parse()
+                .map_err(|_| ReorderOperatorError::InvalidMaxDelay(max_delay_ms.clone()))?,
+        };
+        let max_buffered = match options.get("max_buffered") {
+            None => DEFAULT_MAX_BUFFERED,
+            Some(max_buffered) => max_buffered
+                .parse()
+                .ok()
+                .filter(|max_buffered| *max_buffered > 0)
+                .ok_or_else(|| ReorderOperatorError::InvalidMaxBuffered(max_buffered.clone()))?,
+        };
+        Ok(Self {
+            sequence_column,
+            key_columns,
+            max_delay: Duration::from_millis(max_delay_ms),
+            max_buffered,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ReorderProcessorFactory {
+    id: String,
+    options: ReorderOperatorOptions,
+}
+
+impl ReorderProcessorFactory {
+    pub fn new(id: String, options: ReorderOperatorOptions) -> Self {
+        Self { id, options }
+    }
+
+    /// Returns the indexes of the key columns and of the sequence column.
+    fn indexes(&self, schema: &Schema) -> Result<(Vec<usize>, usize), ReorderOperatorError> {
+        let index = |column: &String| {
+            schema
+                .get_field_index(column)
+                .map(|(index, _)| index)
+                .map_err(|_| ReorderOperatorError::UnknownColumn(column.clone()))
+        };
+        let key_indexes = match &self.options.key_columns {
+            None => schema.primary_index.clone(),
+            Some(columns) => columns.iter().map(index).collect::<Result<_, _>>()?,
+        };
+        if key_indexes.is_empty() {
+            return Err(ReorderOperatorError::NoKey);
+        }
+        Ok((key_indexes, index(&self.options.sequence_column)?))
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for ReorderProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let (schema, context) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        self.indexes(schema)?;
+        Ok((schema.clone(), context.clone()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        let (key_indexes, sequence_index) = self.indexes(schema)?;
+        Ok(Box::new(ReorderProcessor::new(
+            key_indexes,
+            sequence_index,
+            self.options.max_delay,
+            self.options.max_buffered,
+        )))
+    }
+
+    fn type_name(&self) -> String {
+        "Reorder".to_string()
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
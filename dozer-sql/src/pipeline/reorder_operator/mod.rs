@@ -0,0 +1,45 @@
+//! An operator putting operations of the same key back in order before stateful processing, for sources that can't
+//! guarantee ordering, such as Kafka topics with a key spread over several partitions or Kinesis streams being resharded.
+//!
+//! Configured as operator `reorder`, with options:
+//! - `sequence_column`: the column ordering the operations of a key, such as an event time or sequence number.
+//! - `key_columns`: the columns identifying a record, separated by commas. Defaults to the input's primary key.
+//! - `max_delay_ms`: how long an operation waits for earlier ones of its key. Defaults to 1000.
+//! - `max_buffered`: how many operations of a key wait at most. The earliest is sent when one more arrives.
+//!   Defaults to 100.
+//!
+//! An operation is sent with every earlier buffered one of its key once it waited `max_delay_ms`. Operations arriving
+//! after later ones were sent can't be put back in order and are sent once they waited too. Deletes are ordered by the
+//! sequence of the deleted record. Delays are checked on every operation and commit, so an operation is sent at most a
+//! commit timeout after its delay ends.
+
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::ProcessorFactory;
+use dozer_core::plugin::{OperatorOptions, OperatorRegistry};
+
+use crate::pipeline::builder::SchemaSQLContext;
+
+mod factory;
+mod processor;
+
+#[cfg(test)]
+mod tests;
+
+pub use factory::{ReorderOperatorOptions, ReorderProcessorFactory};
+
+/// Name the operator is registered under.
+pub const OPERATOR_NAME: &str = "reorder";
+
+pub fn register(registry: &mut OperatorRegistry<SchemaSQLContext>) -> Result<(), ExecutionError> {
+    registry.register(
+        OPERATOR_NAME.to_string(),
+        Box::new(|id: &str, options: &OperatorOptions| {
+            let factory: Box<dyn ProcessorFactory<SchemaSQLContext>> =
+                Box::new(ReorderProcessorFactory::new(
+                    id.to_string(),
+                    ReorderOperatorOptions::parse(options)?,
+                ));
+            Ok(factory)
+        }),
+    )
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::Field;
+
+/// Where an operation goes in the order of its key: its sequence, then its arrival.
+type Position = (Field, u64);
+
+#[derive(Debug)]
+pub struct ReorderProcessor {
+    key_indexes: Vec<usize>,
+    sequence_index: usize,
+    max_delay: Duration,
+    max_buffered: usize,
+    /// Buffered operations of every key, in order.
+    buffers: HashMap<Vec<Field>, BTreeMap<Position, ProcessorOperation>>,
+    /// Buffered operations in arrival order, with when they arrived, so due ones are at the front.
+    arrivals: VecDeque<(Instant, Vec<Field>, Position)>,
+    next_arrival: u64,
+}
+
+impl ReorderProcessor {
+    pub fn new(
+        key_indexes: Vec<usize>,
+        sequence_index: usize,
+        max_delay: Duration,
+        max_buffered: usize,
+    ) -> Self {
+        Self {
+            key_indexes,
+            sequence_index,
+            max_delay,
+            max_buffered,
+            buffers: HashMap::new(),
+            arrivals: VecDeque::new(),
+            next_arrival: 0,
+        }
+    }
+
+    /// Sends the operations that waited `max_delay`, with every earlier one of their key.
+    fn send_due(&mut self, fw: &mut dyn ProcessorChannelForwarder) {
+        let now = Instant::now();
+        while let Some((arrived, _, _)) = self.arrivals.front() {
+            if now.duration_since(*arrived) < self.max_delay {
+                break;
+            }
+            let (_, key, position) = self.arrivals.pop_front().expect("Checked above");
+            // The operation may have been sent already, because its key had too many buffered.
+            let Some(buffer) = self.buffers.get_mut(&key) else {
+                continue;
+            };
+            while let Some(first) = buffer.keys().next().cloned() {
+                if first > position {
+                    break;
+                }
+                let op = buffer.remove(&first).expect("Checked above");
+                fw.send(op, DEFAULT_PORT_HANDLE);
+            }
+            if buffer.is_empty() {
+                self.buffers.remove(&key);
+            }
+        }
+    }
+}
+
+impl Processor for ReorderProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        let record = match &op {
+            ProcessorOperation::Insert { new } | ProcessorOperation::Update { new, .. } => new,
+            ProcessorOperation::Delete { old } => old,
+        };
+        let record = record_store.load_record(record)?;
+        let key = record.get_fields_by_indexes(&self.key_indexes);
+        let position = (
+            record.values[self.sequence_index].clone(),
+            self.next_arrival,
+        );
+        self.next_arrival += 1;
+
+        let buffer = self.buffers.entry(key.clone()).or_default();
+        buffer.insert(position.clone(), op);
+        self.arrivals.push_back((Instant::now(), key, position));
+        if buffer.len() > self.max_buffered {
+            let first = buffer.keys().next().cloned().expect("Buffer is not empty");
+            let op = buffer.remove(&first).expect("Checked above");
+            fw.send(op, DEFAULT_PORT_HANDLE);
+        }
+
+        self.send_due(fw);
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        _record_store: &ProcessorRecordStore,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        self.send_due(fw);
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use dozer_core::plugin::OperatorOptions;
+use dozer_core::test_harness::{schema, Emitted, ProcessorTestHarness, ScriptStep};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use super::{ReorderOperatorOptions, ReorderProcessorFactory};
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{OperatorOptionsError, ReorderOperatorError};
+
+fn input_schema() -> Schema {
+    schema(&[("id", FieldType::Int), ("seq", FieldType::Int)], &[0])
+}
+
+fn record(id: i64, seq: i64) -> Record {
+    Record::new(vec![Field::Int(id), Field::Int(seq)])
+}
+
+fn insert(id: i64, seq: i64) -> ScriptStep {
+    ScriptStep::insert(DEFAULT_PORT_HANDLE, record(id, seq))
+}
+
+fn inserted(id: i64, seq: i64) -> Emitted {
+    Emitted::insert(DEFAULT_PORT_HANDLE, record(id, seq))
+}
+
+fn options(options: &[(&str, &str)]) -> Result<ReorderOperatorOptions, ReorderOperatorError> {
+    let options: OperatorOptions = options
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    ReorderOperatorOptions::parse(&options)
+}
+
+fn factory(max_delay_ms: &str, max_buffered: &str) -> ReorderProcessorFactory {
+    ReorderProcessorFactory::new(
+        "reorder".to_string(),
+        options(&[
+            ("sequence_column", "seq"),
+            ("max_delay_ms", max_delay_ms),
+            ("max_buffered", max_buffered),
+        ])
+        .unwrap(),
+    )
+}
+
+fn harness(factory: &ReorderProcessorFactory) -> ProcessorTestHarness<'_, SchemaSQLContext> {
+    ProcessorTestHarness::single_input(factory, input_schema(), SchemaSQLContext::default())
+        .unwrap()
+}
+
+#[test]
+fn test_reorder_by_delay() {
+    let factory = factory("200", "100");
+    let mut harness = harness(&factory);
+
+    // Nothing is sent before the delay ends, even on commit.
+    harness.assert_output(
+        [
+            insert(1, 3),
+            insert(1, 1),
+            insert(2, 5),
+            insert(1, 2),
+            ScriptStep::Commit,
+        ],
+        &[],
+    );
+
+    std::thread::sleep(Duration::from_millis(250));
+    harness.assert_output(
+        [ScriptStep::Commit],
+        &[
+            inserted(1, 1),
+            inserted(1, 2),
+            inserted(1, 3),
+            inserted(2, 5),
+        ],
+    );
+}
+
+#[test]
+fn test_reorder_by_count() {
+    let factory = factory("60000", "2");
+    harness(&factory).assert_output(
+        [insert(1, 3), insert(1, 2), insert(2, 4), insert(1, 1)],
+        &[inserted(1, 1)],
+    );
+}
+
+#[test]
+fn test_reorder_invalid_options() {
+    assert!(matches!(
+        options(&[]),
+        Err(ReorderOperatorError::Options(
+            OperatorOptionsError::MissingOption("sequence_column")
+        ))
+    ));
+    assert!(matches!(
+        options(&[("sequence_column", "seq"), ("max_buffered", "0")]),
+        Err(ReorderOperatorError::InvalidMaxBuffered(_))
+    ));
+    assert!(matches!(
+        options(&[("sequence_column", "seq"), ("max_delay_ms", "-1")]),
+        Err(ReorderOperatorError::InvalidMaxDelay(_))
+    ));
+
+    let factory = ReorderProcessorFactory::new(
+        "reorder".to_string(),
+        options(&[("sequence_column", "time")]).unwrap(),
+    );
+    assert!(ProcessorTestHarness::single_input(
+        &factory,
+        input_schema(),
+        SchemaSQLContext::default()
+    )
+    .is_err());
+}
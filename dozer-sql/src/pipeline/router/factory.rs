@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::{
+    builder::{SchemaSQLContext, SqlOptions},
+    errors::PipelineError,
+};
+use dozer_core::processor_record::ProcessorRecordStore;
+use dozer_core::{
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::{errors::internal::BoxedError, types::Schema};
+use sqlparser::ast::Expr as SqlExpr;
+
+use super::processor::RouterProcessor;
+
+/// Routes records to one output port per condition, in order, followed by a default port if `has_default`.
+#[derive(Debug)]
+pub struct RouterProcessorFactory {
+    id: String,
+    conditions: Vec<SqlExpr>,
+    has_default: bool,
+    options: SqlOptions,
+}
+
+impl RouterProcessorFactory {
+    pub fn new(
+        id: String,
+        conditions: Vec<SqlExpr>,
+        has_default: bool,
+        options: SqlOptions,
+    ) -> Self {
+        Self {
+            id,
+            conditions,
+            has_default,
+            options,
+        }
+    }
+
+    fn num_ports(&self) -> usize {
+        self.conditions.len() + usize::from(self.has_default)
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for RouterProcessorFactory {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn type_name(&self) -> String {
+        "Router".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        (0..self.num_ports())
+            .map(|port| OutputPortDef::new(port as PortHandle, OutputPortType::Stateless))
+            .collect()
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok(schema.clone())
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _record_store: &ProcessorRecordStore,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|condition| {
+                ExpressionBuilder::new(schema.fields.len(), self.options)
+                    .build(false, condition, schema)
+            })
+            .collect::<Result<_, _>>()?;
+        let default_port = self
+            .has_default
+            .then_some(self.conditions.len() as PortHandle);
+        Ok(Box::new(RouterProcessor::new(
+            schema.clone(),
+            conditions,
+            default_port,
+        )))
+    }
+}
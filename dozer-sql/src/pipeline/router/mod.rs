@@ -0,0 +1,5 @@
+pub mod factory;
+pub mod processor;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use crate::pipeline::expression::execution::Expression;
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor_operation::ProcessorOperation;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::processor_record::{ProcessorRecord, ProcessorRecordStore};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Field, Schema};
+
+#[derive(Debug)]
+pub struct RouterProcessor {
+    input_schema: Schema,
+    /// The condition of the route on port `i` is `conditions[i]`.
+    conditions: Vec<Expression>,
+    /// Receives the records satisfying no condition.
+    default_port: Option<PortHandle>,
+}
+
+impl RouterProcessor {
+    pub fn new(
+        input_schema: Schema,
+        conditions: Vec<Expression>,
+        default_port: Option<PortHandle>,
+    ) -> Self {
+        Self {
+            input_schema,
+            conditions,
+            default_port,
+        }
+    }
+
+    /// Returns the ports `record` is routed to.
+    fn route(
+        &self,
+        record_store: &ProcessorRecordStore,
+        record: &ProcessorRecord,
+    ) -> Result<Vec<PortHandle>, BoxedError> {
+        let record = record_store.load_record(record)?;
+        let mut ports = vec![];
+        for (port, condition) in self.conditions.iter().enumerate() {
+            if condition.evaluate(&record, &self.input_schema)? == Field::Boolean(true) {
+                ports.push(port as PortHandle);
+            }
+        }
+        if ports.is_empty() {
+            ports.extend(self.default_port);
+        }
+        Ok(ports)
+    }
+}
+
+impl Processor for RouterProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        record_store: &ProcessorRecordStore,
+        op: ProcessorOperation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        match op {
+            ProcessorOperation::Delete { old } => {
+                for port in self.route(record_store, &old)? {
+                    fw.send(ProcessorOperation::Delete { old: old.clone() }, port);
+                }
+            }
+            ProcessorOperation::Insert { new } => {
+                for port in self.route(record_store, &new)? {
+                    fw.send(ProcessorOperation::Insert { new: new.clone() }, port);
+                }
+            }
+            ProcessorOperation::Update { old, new } => {
+                let old_ports = self.route(record_store, &old)?;
+                let new_ports = self.route(record_store, &new)?;
+                // Like a selection per route: a record leaving a route is deleted from it, one entering is inserted.
+                for port in 0..self.conditions.len() as PortHandle + 1 {
+                    let op = match (old_ports.contains(&port), new_ports.contains(&port)) {
+                        (true, true) => ProcessorOperation::Update {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                        (true, false) => ProcessorOperation::Delete { old: old.clone() },
+                        (false, true) => ProcessorOperation::Insert { new: new.clone() },
+                        (false, false) => continue,
+                    };
+                    fw.send(op, port);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use dozer_core::test_harness::{schema, Emitted, ProcessorTestHarness, ScriptStep};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use crate::pipeline::builder::{router_to_processor, SchemaSQLContext, SqlOptions};
+
+fn input_schema() -> Schema {
+    schema(
+        &[("region", FieldType::String), ("amount", FieldType::Int)],
+        &[],
+    )
+}
+
+fn record(region: &str, amount: i64) -> Record {
+    Record::new(vec![Field::String(region.to_string()), Field::Int(amount)])
+}
+
+#[test]
+fn test_router() {
+    let factory = router_to_processor(
+        "router".to_string(),
+        &["amount > 0", "region = 'eu'"],
+        true,
+        SqlOptions::default(),
+    )
+    .unwrap();
+    let mut harness = ProcessorTestHarness::single_input(
+        factory.as_ref(),
+        input_schema(),
+        SchemaSQLContext::default(),
+    )
+    .unwrap();
+    assert_eq!(harness.output_schema(2), Some(&input_schema()));
+
+    harness.assert_output(
+        [
+            ScriptStep::insert(DEFAULT_PORT_HANDLE, record("eu", 5)),
+            ScriptStep::insert(DEFAULT_PORT_HANDLE, record("us", -1)),
+            ScriptStep::update(DEFAULT_PORT_HANDLE, record("eu", 5), record("eu", -5)),
+            ScriptStep::delete(DEFAULT_PORT_HANDLE, record("us", -1)),
+        ],
+        &[
+            // A record satisfying several conditions is sent to every route.
+            Emitted::insert(0, record("eu", 5)),
+            Emitted::insert(1, record("eu", 5)),
+            Emitted::insert(2, record("us", -1)),
+            Emitted::delete(0, record("eu", 5)),
+            Emitted::update(1, record("eu", 5), record("eu", -5)),
+            Emitted::delete(2, record("us", -1)),
+        ],
+    );
+}
+
+#[test]
+fn test_router_invalid_condition() {
+    assert!(router_to_processor(
+        "router".to_string(),
+        &["amount >"],
+        false,
+        SqlOptions::default()
+    )
+    .is_err());
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use crate::pipeline::expression::builder::ExpressionBuilder;
-use crate::pipeline::{builder::SchemaSQLContext, errors::PipelineError};
+use crate::pipeline::{
+    builder::{SchemaSQLContext, SqlOptions},
+    errors::PipelineError,
+};
 use dozer_core::processor_record::ProcessorRecordStore;
 use dozer_core::{
     node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
@@ -16,12 +19,17 @@ use super::processor::SelectionProcessor;
 pub struct SelectionProcessorFactory {
     statement: SqlExpr,
     id: String,
+    options: SqlOptions,
 }
 
 impl SelectionProcessorFactory {
     /// Creates a new [`SelectionProcessorFactory`].
-    pub fn new(id: String, statement: SqlExpr) -> Self {
-        Self { statement, id }
+    pub fn new(id: String, statement: SqlExpr, options: SqlOptions) -> Self {
+        Self {
+            statement,
+            id,
+            options,
+        }
     }
 }
 
@@ -64,7 +72,11 @@ impl ProcessorFactory<SchemaSQLContext> for SelectionProcessorFactory {
             .get(&DEFAULT_PORT_HANDLE)
             .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
 
-        match ExpressionBuilder::new(schema.fields.len()).build(false, &self.statement, schema) {
+        match ExpressionBuilder::new(schema.fields.len(), self.options).build(
+            false,
+            &self.statement,
+            schema,
+        ) {
             Ok(expression) => Ok(Box::new(SelectionProcessor::new(
                 schema.clone(),
                 expression,
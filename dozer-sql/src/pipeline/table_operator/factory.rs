@@ -9,7 +9,7 @@ use dozer_types::{errors::internal::BoxedError, types::Schema};
 use sqlparser::ast::{Expr, FunctionArg, FunctionArgExpr, Value};
 
 use crate::pipeline::{
-    builder::SchemaSQLContext,
+    builder::{SchemaSQLContext, SqlOptions},
     errors::{PipelineError, TableOperatorError},
     expression::{builder::ExpressionBuilder, execution::Expression},
     pipeline_builder::from_builder::TableOperatorDescriptor,
@@ -28,14 +28,16 @@ pub struct TableOperatorProcessorFactory {
     id: String,
     table: TableOperatorDescriptor,
     name: String,
+    options: SqlOptions,
 }
 
 impl TableOperatorProcessorFactory {
-    pub fn new(id: String, table: TableOperatorDescriptor) -> Self {
+    pub fn new(id: String, table: TableOperatorDescriptor, options: SqlOptions) -> Self {
         Self {
             id: id.clone(),
             table,
             name: id,
+            options,
         }
     }
 
@@ -80,7 +82,7 @@ impl ProcessorFactory<SchemaSQLContext> for TableOperatorProcessorFactory {
             .clone();
 
         let output_schema =
-            match operator_from_descriptor(&self.table, &input_schema)? {
+            match operator_from_descriptor(&self.table, &input_schema, self.options)? {
                 Some(operator) => operator
                     .get_output_schema(&input_schema)
                     .map_err(PipelineError::TableOperatorError)?,
@@ -108,7 +110,7 @@ impl ProcessorFactory<SchemaSQLContext> for TableOperatorProcessorFactory {
             ))?
             .clone();
 
-        match operator_from_descriptor(&self.table, &input_schema)? {
+        match operator_from_descriptor(&self.table, &input_schema, self.options)? {
             Some(operator) => Ok(Box::new(TableOperatorProcessor::new(
                 self.id.clone(),
                 operator,
@@ -127,9 +129,10 @@ impl ProcessorFactory<SchemaSQLContext> for TableOperatorProcessorFactory {
 pub(crate) fn operator_from_descriptor(
     descriptor: &TableOperatorDescriptor,
     schema: &Schema,
+    options: SqlOptions,
 ) -> Result<Option<TableOperatorType>, PipelineError> {
     if &descriptor.name.to_uppercase() == "TTL" {
-        let operator = lifetime_from_descriptor(descriptor, schema)?;
+        let operator = lifetime_from_descriptor(descriptor, schema, options)?;
 
         Ok(Some(operator.into()))
     } else {
@@ -140,6 +143,7 @@ pub(crate) fn operator_from_descriptor(
 fn lifetime_from_descriptor(
     descriptor: &TableOperatorDescriptor,
     schema: &Schema,
+    options: SqlOptions,
 ) -> Result<LifetimeTableOperator, TableOperatorError> {
     let expression_arg = descriptor
         .args
@@ -154,7 +158,7 @@ fn lifetime_from_descriptor(
             descriptor.name.to_owned(),
         ))?;
 
-    let expression = get_expression(descriptor.name.to_owned(), expression_arg, schema)?;
+    let expression = get_expression(descriptor.name.to_owned(), expression_arg, schema, options)?;
     let duration = get_interval(descriptor.name.to_owned(), duration_arg)?;
 
     let operator = LifetimeTableOperator::new(None, expression, duration);
@@ -204,6 +208,7 @@ fn get_expression(
     function_name: String,
     interval_arg: &FunctionArg,
     schema: &Schema,
+    options: SqlOptions,
 ) -> Result<Expression, TableOperatorError> {
     match interval_arg {
         FunctionArg::Named { name, arg: _ } => {
@@ -215,7 +220,7 @@ fn get_expression(
         }
         FunctionArg::Unnamed(arg_expr) => match arg_expr {
             FunctionArgExpr::Expr(expr) => {
-                let mut builder = ExpressionBuilder::new(schema.fields.len());
+                let mut builder = ExpressionBuilder::new(schema.fields.len(), options);
                 let expression = builder.build(false, expr, schema).map_err(|_| {
                     TableOperatorError::InvalidReference(expr.to_string(), function_name)
                 })?;
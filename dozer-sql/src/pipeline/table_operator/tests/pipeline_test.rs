@@ -19,7 +19,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use crate::pipeline::product::tests::pipeline_test::TestSinkFactory;
 
 const TRIPS_PORT: u16 = 0 as PortHandle;
@@ -40,6 +40,7 @@ fn test_lifetime_pipeline() {
                 JOIN zones puz ON trips.pu_location_id = puz.location_id",
         &mut pipeline,
         Some("results".to_string()),
+        SqlOptions::default(),
     )
     .unwrap();
 
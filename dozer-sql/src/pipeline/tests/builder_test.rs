@@ -5,7 +5,8 @@ use dozer_core::epoch::Epoch;
 use dozer_core::executor::{DagExecutor, ExecutorOptions};
 use dozer_core::executor_operation::ProcessorOperation;
 use dozer_core::node::{
-    OutputPortDef, OutputPortType, PortHandle, Sink, SinkFactory, Source, SourceFactory,
+    OutputPortDef, OutputPortType, PortHandle, ProcessorFactory, Sink, SinkFactory, Source,
+    SourceFactory,
 };
 use dozer_core::processor_record::ProcessorRecordStore;
 use dozer_core::DEFAULT_PORT_HANDLE;
@@ -22,7 +23,9 @@ use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
-use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use crate::pipeline::builder::{
+    select_to_processor, statement_to_pipeline, SchemaSQLContext, SqlOptions,
+};
 
 /// Test Source
 #[derive(Debug)]
@@ -191,6 +194,7 @@ fn test_pipeline_builder() {
          WHERE Spending >= 1",
         &mut pipeline,
         Some("results".to_string()),
+        SqlOptions::default(),
     )
     .unwrap();
 
@@ -237,3 +241,31 @@ fn test_pipeline_builder() {
     let elapsed = now.elapsed();
     debug!("Elapsed: {:.2?}", elapsed);
 }
+
+#[test]
+fn test_select_to_processor() {
+    let (input_schema, ctx) = TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])
+        .get_output_schema(&DEFAULT_PORT_HANDLE)
+        .unwrap();
+    let input_schemas = HashMap::from([(DEFAULT_PORT_HANDLE, (input_schema, ctx))]);
+
+    let processor = select_to_processor(
+        "rollup".to_string(),
+        "SELECT Country, SUM(Spending) AS total FROM users GROUP BY Country",
+        SqlOptions::default(),
+    )
+    .unwrap();
+    let (schema, _) = processor
+        .get_output_schema(&DEFAULT_PORT_HANDLE, &input_schemas)
+        .unwrap();
+    assert_eq!(schema.fields[0].name, "Country");
+    assert_eq!(schema.fields[1].name, "total");
+    assert_eq!(schema.primary_index, vec![0]);
+
+    assert!(select_to_processor(
+        "rollup".to_string(),
+        "SELECT Country, SUM(Spending) FROM users WHERE Spending >= 1 GROUP BY Country",
+        SqlOptions::default(),
+    )
+    .is_err());
+}
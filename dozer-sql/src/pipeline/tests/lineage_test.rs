@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SqlOptions;
+use crate::pipeline::lineage::{extract_lineage, used_source_columns, SourceColumn};
+
+fn source(table: Option<&str>, column: &str) -> SourceColumn {
+    SourceColumn {
+        table: table.map(ToString::to_string),
+        column: column.to_string(),
+    }
+}
+
+#[test]
+fn test_lineage_simple_projection() {
+    let lineage = extract_lineage(
+        "SELECT id, name AS customer_name, UPPER(city) AS city FROM customers INTO results;",
+        SqlOptions::default(),
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(lineage.len(), 1);
+    assert_eq!(lineage[0].table, "results");
+    let columns = &lineage[0].columns;
+    assert_eq!(columns[0].column, "id");
+    assert_eq!(columns[0].sources, vec![source(Some("customers"), "id")]);
+    assert_eq!(columns[1].column, "customer_name");
+    assert_eq!(columns[1].sources, vec![source(Some("customers"), "name")]);
+    assert_eq!(columns[2].column, "city");
+    assert_eq!(columns[2].expression, "UPPER(city)");
+    assert_eq!(columns[2].sources, vec![source(Some("customers"), "city")]);
+}
+
+#[test]
+fn test_lineage_join_and_cte() {
+    let lineage = extract_lineage(
+        "WITH paid AS (SELECT order_id, amount * rate AS total FROM payments) \
+        SELECT o.id, p.total + o.fee AS charged FROM orders o JOIN paid p ON o.id = p.order_id \
+        INTO charges;",
+        SqlOptions::default(),
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    let columns = &lineage[0].columns;
+    assert_eq!(columns[0].sources, vec![source(Some("orders"), "id")]);
+    assert_eq!(
+        columns[1].sources,
+        vec![
+            source(Some("payments"), "amount"),
+            source(Some("payments"), "rate"),
+            source(Some("orders"), "fee"),
+        ]
+    );
+}
+
+#[test]
+fn test_lineage_through_output_table() {
+    let lineage = extract_lineage(
+        "SELECT id, price * quantity AS total FROM items INTO totals; \
+        SELECT id, SUM(total) AS revenue FROM totals GROUP BY id INTO revenue;",
+        SqlOptions::default(),
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(lineage.len(), 2);
+    assert_eq!(lineage[1].table, "revenue");
+    assert_eq!(
+        lineage[1].columns[1].sources,
+        vec![
+            source(Some("items"), "price"),
+            source(Some("items"), "quantity")
+        ]
+    );
+}
+
+#[test]
+fn test_lineage_ambiguous_column() {
+    let lineage = extract_lineage(
+        "SELECT name FROM customers c JOIN orders o ON c.id = o.customer_id INTO results;",
+        SqlOptions::default(),
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(lineage[0].columns[0].sources, vec![source(None, "name")]);
+}
+
+#[test]
+fn test_lineage_with_source_columns() {
+    let source_columns = HashMap::from([
+        (
+            "customers".to_string(),
+            vec!["id".to_string(), "name".to_string()],
+        ),
+        (
+            "orders".to_string(),
+            vec!["customer_id".to_string(), "amount".to_string()],
+        ),
+    ]);
+    let lineage = extract_lineage(
+        "SELECT name, amount, c.* FROM customers c JOIN orders o ON c.id = o.customer_id INTO results;",
+        SqlOptions::default(),
+        &source_columns,
+    )
+    .unwrap();
+
+    let columns = &lineage[0].columns;
+    assert_eq!(
+        columns
+            .iter()
+            .map(|column| (column.column.as_str(), column.sources.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("name", vec![source(Some("customers"), "name")]),
+            ("amount", vec![source(Some("orders"), "amount")]),
+            ("id", vec![source(Some("customers"), "id")]),
+            ("name", vec![source(Some("customers"), "name")]),
+        ]
+    );
+    assert_eq!(columns[2].expression, "c.id");
+}
+
+#[test]
+fn test_used_source_columns() {
+    let used = used_source_columns(
+        "SELECT c.name, SUM(o.amount) AS total FROM customers c JOIN orders o ON c.id = o.customer_id \
+        WHERE o.status = 'paid' GROUP BY c.name INTO totals; \
+        SELECT * FROM products INTO all_products;",
+        SqlOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        used,
+        vec![
+            source(Some("orders"), "status"),
+            source(Some("customers"), "name"),
+            source(Some("customers"), "id"),
+            source(Some("orders"), "customer_id"),
+            source(Some("orders"), "amount"),
+            source(Some("products"), "*"),
+        ]
+    );
+}
@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod builder_test;
 
+#[cfg(test)]
+mod lineage_test;
+
 #[cfg(test)]
 pub mod utils;
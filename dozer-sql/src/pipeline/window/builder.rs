@@ -1,12 +1,13 @@
 use dozer_types::{
     chrono::Duration,
+    identifier,
     types::{FieldDefinition, Schema},
 };
 use sqlparser::ast::{Expr, FunctionArg, FunctionArgExpr, Ident, ObjectName, Value};
 
 use crate::pipeline::{
     errors::{JoinError, PipelineError, WindowError},
-    expression::builder::ExpressionBuilder,
+    expression::builder::{ident_matches, ExpressionBuilder},
     pipeline_builder::from_builder::TableOperatorDescriptor,
 };
 
@@ -23,9 +24,10 @@ const ARG_HOP_INTERVAL: usize = 3;
 pub(crate) fn window_from_table_operator(
     operator: &TableOperatorDescriptor,
     schema: &Schema,
+    case_sensitive: bool,
 ) -> Result<Option<WindowType>, WindowError> {
     if operator.name.to_uppercase() == "TUMBLE" {
-        let column_index = get_window_column_index(&operator.args, schema)?;
+        let column_index = get_window_column_index(&operator.args, schema, case_sensitive)?;
         let interval_arg = operator
             .args
             .get(ARG_TUMBLE_INTERVAL)
@@ -37,7 +39,7 @@ pub(crate) fn window_from_table_operator(
             interval,
         }))
     } else if operator.name.to_uppercase() == "HOP" {
-        let column_index = get_window_column_index(&operator.args, schema)?;
+        let column_index = get_window_column_index(&operator.args, schema, case_sensitive)?;
         let hop_arg = operator
             .args
             .get(ARG_HOP_SIZE)
@@ -151,7 +153,11 @@ fn get_window_source_name(arg: &FunctionArg) -> Result<String, WindowError> {
     }
 }
 
-fn get_window_column_index(args: &[FunctionArg], schema: &Schema) -> Result<usize, WindowError> {
+fn get_window_column_index(
+    args: &[FunctionArg],
+    schema: &Schema,
+    case_sensitive: bool,
+) -> Result<usize, WindowError> {
     let column_arg = args
         .get(ARG_COLUMN)
         .ok_or(WindowError::WindowMissingColumnArgument)?;
@@ -164,14 +170,14 @@ fn get_window_column_index(args: &[FunctionArg], schema: &Schema) -> Result<usiz
             FunctionArgExpr::Expr(expr) => match expr {
                 Expr::Identifier(ident) => {
                     let column_name = ExpressionBuilder::normalize_ident(ident);
-                    let index = get_field_index(&[ident.clone()], schema)
+                    let index = get_field_index(&[ident.clone()], schema, case_sensitive)
                         .map_err(|_| WindowError::WindowInvalidColumn(column_name.clone()))?;
 
                     Ok(index.ok_or(WindowError::WindowInvalidColumn(column_name))?)
                 }
                 Expr::CompoundIdentifier(ident) => {
                     let column_name = ExpressionBuilder::fullname_from_ident(ident);
-                    let index = get_field_index(ident, schema)
+                    let index = get_field_index(ident, schema, case_sensitive)
                         .map_err(|_| WindowError::WindowInvalidColumn(column_name.clone()))?;
 
                     Ok(index.ok_or(WindowError::WindowInvalidColumn(column_name))?)
@@ -221,38 +227,56 @@ pub fn string_from_sql_object_name(name: &ObjectName) -> String {
         .join(".")
 }
 
-pub fn get_field_index(ident: &[Ident], schema: &Schema) -> Result<Option<usize>, PipelineError> {
+pub fn get_field_index(
+    ident: &[Ident],
+    schema: &Schema,
+    case_sensitive: bool,
+) -> Result<Option<usize>, PipelineError> {
     let tables_matches = |table_ident: &Ident, fd: &FieldDefinition| -> bool {
         match fd.source.clone() {
             dozer_types::types::SourceDefinition::Table {
                 connection: _,
                 name,
-            } => name == table_ident.value,
-            dozer_types::types::SourceDefinition::Alias { name } => name == table_ident.value,
+            } => ident_matches(table_ident, case_sensitive, &name),
+            dozer_types::types::SourceDefinition::Alias { name } => {
+                ident_matches(table_ident, case_sensitive, &name)
+            }
             dozer_types::types::SourceDefinition::Dynamic => false,
         }
     };
 
     let field_index = match ident.len() {
         1 => {
-            let field_index = schema
-                .fields
-                .iter()
-                .enumerate()
-                .find(|(_, f)| f.name == ident[0].value)
-                .map(|(idx, fd)| (idx, fd.clone()));
+            let field_index = identifier::matching(
+                &ident[0].value,
+                ident[0].quote_style.is_some(),
+                case_sensitive,
+                schema.fields.iter().enumerate(),
+                |(_, f)| f.name.as_str(),
+            )
+            .into_iter()
+            .next()
+            .map(|(idx, fd)| (idx, fd.clone()));
             field_index
         }
         2 => {
             let table_name = ident.first().expect("table_name is expected");
             let field_name = ident.last().expect("field_name is expected");
 
-            let index = schema
-                .fields
-                .iter()
-                .enumerate()
-                .find(|(_, f)| tables_matches(table_name, f) && f.name == field_name.value)
-                .map(|(idx, fd)| (idx, fd.clone()));
+            let index = identifier::matching(
+                &field_name.value,
+                field_name.quote_style.is_some(),
+                case_sensitive,
+                schema
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| tables_matches(table_name, f)),
+                |(_, f)| f.name.as_str(),
+            )
+            .into_iter()
+            .next()
+            .map(|(idx, fd)| (idx, fd.clone()));
             index
         }
         // 3 => {
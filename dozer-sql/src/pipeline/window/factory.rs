@@ -8,7 +8,7 @@ use dozer_core::{
 use dozer_types::{errors::internal::BoxedError, types::Schema};
 
 use crate::pipeline::{
-    builder::SchemaSQLContext,
+    builder::{SchemaSQLContext, SqlOptions},
     errors::{PipelineError, WindowError},
     pipeline_builder::from_builder::TableOperatorDescriptor,
 };
@@ -22,11 +22,12 @@ use super::{
 pub struct WindowProcessorFactory {
     id: String,
     table: TableOperatorDescriptor,
+    options: SqlOptions,
 }
 
 impl WindowProcessorFactory {
-    pub fn new(id: String, table: TableOperatorDescriptor) -> Self {
-        Self { id, table }
+    pub fn new(id: String, table: TableOperatorDescriptor, options: SqlOptions) -> Self {
+        Self { id, table, options }
     }
 
     pub(crate) fn get_source_name(&self) -> Result<String, PipelineError> {
@@ -66,8 +67,12 @@ impl ProcessorFactory<SchemaSQLContext> for WindowProcessorFactory {
             ))?
             .clone();
 
-        let output_schema = match window_from_table_operator(&self.table, &input_schema.0)
-            .map_err(PipelineError::WindowError)?
+        let output_schema = match window_from_table_operator(
+            &self.table,
+            &input_schema.0,
+            self.options.case_sensitive,
+        )
+        .map_err(PipelineError::WindowError)?
         {
             Some(window) => window
                 .get_output_schema(&input_schema.0)
@@ -91,7 +96,7 @@ impl ProcessorFactory<SchemaSQLContext> for WindowProcessorFactory {
             ))?
             .clone();
 
-        match window_from_table_operator(&self.table, &input_schema)
+        match window_from_table_operator(&self.table, &input_schema, self.options.case_sensitive)
             .map_err(PipelineError::WindowError)?
         {
             Some(window) => Ok(Box::new(WindowProcessor::new(self.id.clone(), window))),
@@ -22,7 +22,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use crate::pipeline::product::tests::pipeline_test::TestSinkFactory;
 
 const TRIPS_PORT: u16 = 0 as PortHandle;
@@ -45,6 +45,7 @@ fn test_pipeline_builder() {
         FROM HOP(taxi_trips, completed_at, '1 MINUTE', '2 MINUTES') trips ",
         &mut pipeline,
         Some("results".to_string()),
+        SqlOptions::default(),
     )
     .unwrap();
 
@@ -74,6 +74,20 @@ impl<K: LmdbKey, V: LmdbVal> LmdbMap<K, V> {
         }
     }
 
+    /// Like `get`, but returns the encoded value, so it can be decoded after `txn` is moved or on another thread.
+    pub fn get_encoded<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: K::Encode<'_>,
+    ) -> Result<Option<&'a [u8]>, StorageError> {
+        let key = key.encode()?;
+        match txn.get(self.db, &key) {
+            Ok(value) => Ok(Some(value)),
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Returns if the key was actually inserted.
     pub fn insert(
         &self,
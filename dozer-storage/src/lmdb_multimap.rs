@@ -2,7 +2,7 @@ use std::ops::Bound;
 
 use dozer_types::borrow::Cow;
 use lmdb::{Cursor, Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags};
-use lmdb_sys::{MDB_LAST_DUP, MDB_SET};
+use lmdb_sys::{MDB_FIRST, MDB_LAST_DUP, MDB_NEXT_NODUP, MDB_SET};
 
 use crate::{
     errors::StorageError,
@@ -63,6 +63,21 @@ impl<K: LmdbKey, V: LmdbKey> LmdbMultimap<K, V> {
         Ok(txn.stat(self.db)?.entries())
     }
 
+    /// Counts the distinct keys. This iterates over all keys, so it's linear in the number of keys.
+    pub fn count_keys<T: Transaction>(&self, txn: &T) -> Result<usize, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        let mut count = 0;
+        let mut op = MDB_FIRST;
+        loop {
+            match cursor.get(None, None, op) {
+                Ok(_) => count += 1,
+                Err(lmdb::Error::NotFound) => return Ok(count),
+                Err(err) => return Err(err.into()),
+            }
+            op = MDB_NEXT_NODUP;
+        }
+    }
+
     pub fn get_first<'a, T: Transaction>(
         &self,
         txn: &'a T,
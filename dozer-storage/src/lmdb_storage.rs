@@ -58,6 +58,13 @@ pub trait LmdbEnvironment {
     fn begin_txn(&self) -> Result<RoTransaction<'_>, StorageError> {
         self.env().begin_ro_txn().map_err(Into::into)
     }
+
+    /// Returns the number of bytes used in the memory map, which can be less than the map size.
+    fn used_bytes(&self) -> Result<u64, StorageError> {
+        let info = self.env().info()?;
+        let stat = self.env().stat()?;
+        Ok((info.last_pgno() as u64 + 1) * stat.page_size() as u64)
+    }
 }
 
 #[derive(Debug)]
@@ -61,6 +61,17 @@ fn convert_filter(filter: Option<&FilterExpression>) -> Document {
                     insert_filter_to_document_recursive(document, filter)
                 }
             }
+            FilterExpression::Or(filters) => {
+                let filters = filters
+                    .iter()
+                    .map(|filter| {
+                        let mut document = Document::new();
+                        insert_filter_to_document_recursive(&mut document, filter);
+                        document
+                    })
+                    .collect::<Vec<_>>();
+                document.insert("$or", filters);
+            }
         }
     }
 
@@ -352,6 +352,8 @@ fn grpc_type_matches(grpc_type: i32, field_type: FieldType) -> bool {
         FieldType::Json => grpc_type == Type::Json as i32,
         FieldType::Point => grpc_type == Type::Point as i32,
         FieldType::Duration => grpc_type == Type::Duration as i32,
+        FieldType::Money => grpc_type == Type::Money as i32,
+        FieldType::Enum => grpc_type == Type::Enum as i32,
     }
 }
 
@@ -368,7 +370,8 @@ fn oapi_type_matches(oapi_type: &dozer_api::openapiv3::Type, field_type: FieldTy
             | FieldType::Text
             | FieldType::Decimal
             | FieldType::Timestamp
-            | FieldType::Date,
+            | FieldType::Date
+            | FieldType::Enum,
         ) => {
             if field_type == FieldType::Timestamp {
                 string_type.format == VariantOrUnknownOrEmpty::Item(StringFormat::DateTime)
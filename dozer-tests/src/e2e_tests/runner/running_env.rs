@@ -353,6 +353,24 @@ fn write_dozer_config_for_running_in_docker_compose(
             ConnectionConfig::S3Storage(_) => {}
             ConnectionConfig::LocalStorage(_) => {}
             ConnectionConfig::DeltaLake(_) => {}
+            ConnectionConfig::Generator(_) => {}
+            ConnectionConfig::Oracle(_) => {}
+            ConnectionConfig::Firestore(_) => {}
+            ConnectionConfig::Stripe(_) => {}
+            ConnectionConfig::MySQL(_) => {}
+            ConnectionConfig::SqlServer(_) => {}
+            ConnectionConfig::Kinesis(_) => {}
+            ConnectionConfig::Iceberg(_) => {}
+            ConnectionConfig::Webhook(_) => {}
+            ConnectionConfig::RedisStreams(_) => {}
+            ConnectionConfig::Nats(_) => {}
+            ConnectionConfig::Mqtt(_) => {}
+            ConnectionConfig::DynamoDb(_) => {}
+            ConnectionConfig::Cassandra(_) => {}
+            ConnectionConfig::Elasticsearch(_) => {}
+            ConnectionConfig::Salesforce(_) => {}
+            ConnectionConfig::GoogleSheets(_) => {}
+            ConnectionConfig::Databricks(_) => {}
         }
     }
 
@@ -14,7 +14,7 @@ use dozer_core::{Dag, DEFAULT_PORT_HANDLE};
 
 use dozer_core::executor::{DagExecutor, ExecutorOptions};
 
-use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext, SqlOptions};
 use dozer_types::crossbeam::channel::{Receiver, Sender};
 
 use dozer_types::errors::internal::BoxedError;
@@ -286,8 +286,13 @@ impl TestPipeline {
     ) -> Result<TestPipeline, ExecutionError> {
         let mut pipeline = AppPipeline::new();
 
-        let transform_response =
-            statement_to_pipeline(&sql, &mut pipeline, Some("results".to_string())).unwrap();
+        let transform_response = statement_to_pipeline(
+            &sql,
+            &mut pipeline,
+            Some("results".to_string()),
+            SqlOptions::default(),
+        )
+        .unwrap();
 
         let output_table = transform_response.output_tables_map.get("results").unwrap();
         let (sender, receiver) =
@@ -28,6 +28,7 @@ pub fn get_schema(columns: &[rusqlite::Column]) -> Schema {
                 },
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                enum_values: vec![],
             }
         })
         .collect();
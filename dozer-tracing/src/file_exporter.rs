@@ -0,0 +1,207 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use dozer_types::chrono::{DateTime, SecondsFormat, Utc};
+use dozer_types::models::telemetry::FileTelemetryConfig;
+use dozer_types::serde_json::{self, json, Map};
+use dozer_types::tonic::codegen::futures_core::future::BoxFuture;
+use opentelemetry::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry::trace::{Status, TraceError};
+use opentelemetry::{Key, Value};
+
+/// Writes spans as JSON lines, to stdout or a file rolled over by size.
+#[derive(Debug)]
+pub struct FileExporter {
+    writer: SpanWriter,
+}
+
+#[derive(Debug)]
+enum SpanWriter {
+    Stdout,
+    File(RollingFile),
+}
+
+impl FileExporter {
+    pub fn new(config: &FileTelemetryConfig) -> io::Result<Self> {
+        let writer = match &config.path {
+            Some(path) => SpanWriter::File(RollingFile::open(
+                PathBuf::from(path),
+                config.max_file_size_bytes,
+                config.max_files,
+            )?),
+            None => SpanWriter::Stdout,
+        };
+        Ok(Self { writer })
+    }
+
+    fn write_batch(&mut self, batch: Vec<SpanData>) -> io::Result<()> {
+        let mut lines = String::new();
+        for span_data in batch {
+            lines.push_str(&span_json(span_data).to_string());
+            lines.push('\n');
+        }
+        match &mut self.writer {
+            SpanWriter::Stdout => io::stdout().lock().write_all(lines.as_bytes()),
+            SpanWriter::File(file) => file.write(lines.as_bytes()),
+        }
+    }
+}
+
+impl SpanExporter for FileExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        let result = self
+            .write_batch(batch)
+            .map_err(|e| TraceError::Other(Box::new(e)));
+        Box::pin(async move { result })
+    }
+}
+
+/// A file rolled over to `<path>.1` when writing to it would make it larger than `max_size` bytes. Older files are
+/// shifted to `<path>.2` and so on, up to `<path>.<max_files>`.
+#[derive(Debug)]
+struct RollingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: u32,
+    file: File,
+    size: u64,
+}
+
+impl RollingFile {
+    fn open(path: PathBuf, max_size: u64, max_files: u32) -> io::Result<Self> {
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + bytes.len() as u64 > self.max_size {
+            self.roll_over()?;
+        }
+        self.file.write_all(bytes)?;
+        self.size += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn roll_over(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = rolled_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(from, rolled_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, rolled_path(&self.path, 1))?;
+        }
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rolled_path(path: &Path, index: u32) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{index}"));
+    path.into()
+}
+
+fn span_json(span_data: SpanData) -> serde_json::Value {
+    let span_context = &span_data.span_context;
+    let status = match &span_data.status {
+        Status::Unset => json!("unset"),
+        Status::Ok => json!("ok"),
+        Status::Error { description } => json!({ "error": description }),
+    };
+    let events = span_data
+        .events
+        .iter()
+        .map(|event| {
+            json!({
+                "name": event.name,
+                "timestamp": timestamp(event.timestamp),
+                "attributes": attributes_json(
+                    event.attributes.iter().map(|kv| (&kv.key, &kv.value))
+                ),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "trace_id": format!("{:032x}", span_context.trace_id()),
+        "span_id": format!("{:016x}", span_context.span_id()),
+        "parent_span_id": format!("{:016x}", span_data.parent_span_id),
+        "name": span_data.name,
+        "kind": format!("{:?}", span_data.span_kind),
+        "start_time": timestamp(span_data.start_time),
+        "end_time": timestamp(span_data.end_time),
+        "status": status,
+        "attributes": attributes_json(span_data.attributes.iter()),
+        "events": events,
+        "resource": attributes_json(span_data.resource.iter()),
+    })
+}
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn attributes_json<'a>(
+    attributes: impl Iterator<Item = (&'a Key, &'a Value)>,
+) -> serde_json::Value {
+    let attributes = attributes
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Bool(value) => json!(value),
+                Value::I64(value) => json!(value),
+                Value::F64(value) => json!(value),
+                value => json!(value.as_str()),
+            };
+            (key.to_string(), value)
+        })
+        .collect::<Map<_, _>>();
+    serde_json::Value::Object(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_file() {
+        let dir = std::env::temp_dir().join(format!("dozer-spans-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("spans.jsonl");
+
+        let mut file = RollingFile::open(path.clone(), 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write(line.as_bytes()).unwrap();
+        }
+        let read = |index| {
+            let path = match index {
+                0 => path.clone(),
+                index => rolled_path(&path, index),
+            };
+            fs::read_to_string(path).unwrap()
+        };
+        assert_eq!(read(0), "fourth\n");
+        assert_eq!(read(1), "third\n");
+        assert_eq!(read(2), "second\n");
+        assert!(!rolled_path(&path, 3).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use dozer_types::chrono::{DateTime, Utc};
+use dozer_types::serde_json;
 use dozer_types::types::{Field, Record};
 use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
 use opentelemetry::sdk::export::trace::SpanData;
@@ -8,6 +9,12 @@ pub(crate) fn map_span_data(span_data: SpanData) -> (Record, Vec<Record>) {
     let end_time: DateTime<Utc> = span_data.end_time.into();
 
     let span_id = u64::from_be_bytes(span_data.span_context.span_id().to_bytes());
+    // Attributes of the app, like its environment and tags.
+    let resource = span_data
+        .resource
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.as_str().into()))
+        .collect::<serde_json::Map<_, _>>();
     let span_record = Record {
         values: vec![
             Field::UInt(span_id),
@@ -16,6 +23,7 @@ pub(crate) fn map_span_data(span_data: SpanData) -> (Record, Vec<Record>) {
             Field::UInt(u64::from_be_bytes(span_data.parent_span_id.to_bytes())),
             Field::Timestamp(start_time.into()),
             Field::Timestamp(end_time.into()),
+            Field::Text(serde_json::Value::Object(resource).to_string()),
         ],
         lifetime: None,
     };
@@ -23,11 +31,18 @@ pub(crate) fn map_span_data(span_data: SpanData) -> (Record, Vec<Record>) {
     let mut events = vec![];
     for evt in span_data.events {
         let ts: DateTime<Utc> = evt.timestamp.into();
+        // Structured fields of the event, like the ones of pipeline errors.
+        let attributes = evt
+            .attributes
+            .iter()
+            .map(|kv| (kv.key.to_string(), kv.value.as_str().into()))
+            .collect::<serde_json::Map<_, _>>();
         let record = Record {
             values: vec![
                 Field::UInt(span_id),
                 Field::Text(evt.name.to_string()),
                 Field::Timestamp(ts.into()),
+                Field::Text(serde_json::Value::Object(attributes).to_string()),
             ],
             lifetime: None,
         };
@@ -44,36 +59,49 @@ pub fn spans_schema() -> Schema {
             typ: FieldType::UInt,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "trace_id".to_string(),
             typ: FieldType::Binary,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "name".to_string(),
             typ: FieldType::Text,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "parent_id".to_string(),
             typ: FieldType::UInt,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "start_time".to_string(),
             typ: FieldType::Timestamp,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "end_time".to_string(),
             typ: FieldType::Timestamp,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
+        },
+        FieldDefinition {
+            name: "resource".to_string(),
+            typ: FieldType::Text,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
     ];
 
@@ -90,18 +118,28 @@ pub fn events_schema() -> Schema {
             typ: FieldType::UInt,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "name".to_string(),
             typ: FieldType::Text,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
         FieldDefinition {
             name: "timestamp".to_string(),
             typ: FieldType::Timestamp,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
+        },
+        FieldDefinition {
+            name: "attributes".to_string(),
+            typ: FieldType::Text,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         },
     ];
 
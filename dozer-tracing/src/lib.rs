@@ -1,4 +1,9 @@
 mod telemetry;
 pub use telemetry::{init_telemetry, init_telemetry_closure, shutdown_telemetry};
 mod exporter;
+mod file_exporter;
 mod helper;
+mod pipeline_error;
+mod recent_logs;
+pub use pipeline_error::{record_pipeline_error, OperationId, PipelineErrorContext, PipelineStage};
+pub use recent_logs::recent_logs;
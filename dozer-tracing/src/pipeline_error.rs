@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use dozer_types::tracing::{error, error_span};
+
+/// The part of a pipeline an error happened in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    /// A connector, reading from the source database.
+    Connector,
+    /// A source node, forwarding the operations of a connector.
+    Source,
+    Processor,
+    Sink,
+}
+
+impl PipelineStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::Connector => "connector",
+            PipelineStage::Source => "source",
+            PipelineStage::Processor => "processor",
+            PipelineStage::Sink => "sink",
+        }
+    }
+}
+
+/// Identifies the operation that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationId {
+    /// An operation a connector sent, by its position in the source.
+    Source { txid: u64, seq_in_tx: u64 },
+    /// An operation a processor or sink received, by the epoch it's in and its index in the epoch.
+    Epoch { epoch_id: u64, index: u64 },
+}
+
+impl Display for OperationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationId::Source { txid, seq_in_tx } => write!(f, "{txid}:{seq_in_tx}"),
+            OperationId::Epoch { epoch_id, index } => write!(f, "epoch {epoch_id}:{index}"),
+        }
+    }
+}
+
+/// Where in a pipeline an error happened.
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineErrorContext<'a> {
+    pub stage: PipelineStage,
+    /// The node the error happened in.
+    pub node: &'a str,
+    /// The connection the failed operation came from, if known.
+    pub source: Option<&'a str>,
+    /// The table the failed operation was on, if known.
+    pub table: Option<&'a str>,
+    pub operation_id: Option<OperationId>,
+}
+
+impl<'a> PipelineErrorContext<'a> {
+    pub fn new(stage: PipelineStage, node: &'a str) -> Self {
+        Self {
+            stage,
+            node,
+            source: None,
+            table: None,
+            operation_id: None,
+        }
+    }
+}
+
+/// Records a pipeline error as an event of a `pipeline_error` span, so tracing backends can group errors by its
+/// fields. Unknown fields are empty.
+pub fn record_pipeline_error(context: &PipelineErrorContext, error: &dyn std::error::Error) {
+    let span = error_span!("pipeline_error", stage = context.stage.as_str());
+    let _enter = span.enter();
+    error!(
+        stage = context.stage.as_str(),
+        node = context.node,
+        source = context.source.unwrap_or_default(),
+        table = context.table.unwrap_or_default(),
+        operation_id = context
+            .operation_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        error_kind = error_kind(error),
+        "{error}"
+    );
+}
+
+/// The variant of the error enum, e.g. `ColumnNotFound` for `ColumnNotFound("id")`. Transparent wrappers, which are
+/// named after the error they wrap, are skipped.
+fn error_kind(error: &dyn std::error::Error) -> String {
+    let debug = format!("{error:?}");
+    let mut kinds = debug.split(|c: char| !(c.is_alphanumeric() || c == '_'));
+    let mut kind = kinds.next().unwrap_or_default();
+    for inner in kinds.by_ref() {
+        if inner.is_empty() {
+            continue;
+        }
+        if !kind.ends_with("Error") || !inner.starts_with(char::is_uppercase) {
+            break;
+        }
+        kind = inner;
+    }
+    kind.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::thiserror::{self, Error};
+
+    use super::*;
+
+    #[derive(Debug, Error)]
+    enum SqlError {
+        #[error("Column {0} not found")]
+        ColumnNotFound(String),
+    }
+
+    #[derive(Debug, Error)]
+    enum PipelineError {
+        #[error(transparent)]
+        SqlError(#[from] SqlError),
+        #[error("Channel closed")]
+        ChannelClosed,
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let error = SqlError::ColumnNotFound("id".to_string());
+        assert_eq!(error_kind(&error), "ColumnNotFound");
+        assert_eq!(error_kind(&PipelineError::from(error)), "ColumnNotFound");
+        assert_eq!(error_kind(&PipelineError::ChannelClosed), "ChannelClosed");
+    }
+}
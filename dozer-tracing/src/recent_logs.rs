@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Write};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use dozer_types::tracing::field::{Field, Visit};
+use dozer_types::tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// The number of lines kept.
+const CAPACITY: usize = 1000;
+
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Keeps the most recent log lines in memory, for crash reports.
+#[derive(Debug)]
+pub struct RecentLogsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}:", metadata.level(), metadata.target());
+        event.record(&mut LineVisitor(&mut line));
+
+        let mut logs = lock();
+        if logs.len() == CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+}
+
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let _ = if field.name() == "message" {
+            write!(self.0, " {value:?}")
+        } else {
+            write!(self.0, " {}={value:?}", field.name())
+        };
+    }
+}
+
+/// The `count` most recent log lines, oldest first.
+pub fn recent_logs(count: usize) -> Vec<String> {
+    let logs = lock();
+    logs.iter()
+        .skip(logs.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+/// A panic while the lock is held leaves the lines intact, so poisoning is ignored.
+fn lock() -> MutexGuard<'static, VecDeque<String>> {
+    RECENT_LOGS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::tracing::{info, subscriber};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_recent_logs() {
+        let subscriber = tracing_subscriber::registry().with(RecentLogsLayer);
+        subscriber::with_default(subscriber, || {
+            for index in 0..3 {
+                info!(index, "line");
+            }
+        });
+        let logs = recent_logs(2);
+        assert_eq!(logs.len(), 2);
+        assert!(logs[0].starts_with("INFO "));
+        assert!(logs[0].contains(" line"));
+        assert!(logs[0].contains(" index=1"));
+        assert!(logs[1].contains(" index=2"));
+    }
+}
@@ -1,19 +1,25 @@
+use std::time::Duration;
+
 use dozer_types::log::{debug, error};
 use dozer_types::models::telemetry::{
-    DozerTelemetryConfig, JaegerTelemetryConfig, TelemetryConfig, TelemetryTraceConfig,
+    DozerTelemetryConfig, FileTelemetryConfig, JaegerTelemetryConfig, TelemetryConfig,
+    TelemetryResourceConfig, TelemetryTraceConfig,
 };
 use dozer_types::tracing::Subscriber;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use opentelemetry::sdk;
 use opentelemetry::sdk::trace::{BatchConfig, BatchSpanProcessor, Sampler};
+use opentelemetry::sdk::Resource;
 use opentelemetry::trace::TracerProvider;
-use opentelemetry::{global, sdk::propagation::TraceContextPropagator};
+use opentelemetry::{global, sdk::propagation::TraceContextPropagator, KeyValue};
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{fmt, EnvFilter, Layer};
 
 use crate::exporter::DozerExporter;
+use crate::file_exporter::FileExporter;
+use crate::recent_logs::RecentLogsLayer;
 // Init telemetry by setting a global handler
 pub fn init_telemetry(app_name: Option<&str>, telemetry_config: Option<TelemetryConfig>) {
     // log errors from open telemetry
@@ -58,23 +64,31 @@ fn create_subscriber(
 ) -> impl Subscriber {
     let app_name = app_name.unwrap_or("dozer");
 
-    let fmt_filter = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new("info"))
-        .unwrap();
+    let fmt_filter = || {
+        EnvFilter::try_from_default_env()
+            .or_else(|_| EnvFilter::try_new("info"))
+            .unwrap()
+    };
 
     let layers = telemetry_config.map_or((None, None), |c| {
         let trace_filter = EnvFilter::try_from_env("DOZER_TRACE_FILTER")
             .or_else(|_| EnvFilter::try_new("dozer=trace"))
             .unwrap();
+        let resource = resource(app_name, c.resource.as_ref());
         match &c.trace {
             None => (None, None),
             Some(TelemetryTraceConfig::Dozer(config)) => (
-                Some(get_dozer_tracer(config).with_filter(trace_filter)),
+                Some(get_dozer_tracer(config, resource).with_filter(trace_filter)),
                 None,
             ),
             Some(TelemetryTraceConfig::Jaeger(config)) => (
                 None,
-                Some(get_jaeger_tracer(app_name, config).with_filter(trace_filter)),
+                Some(get_jaeger_tracer(app_name, config, resource).with_filter(trace_filter)),
+            ),
+            // Both tracers are OpenTelemetry layers, so the file one takes the first slot.
+            Some(TelemetryTraceConfig::File(config)) => (
+                Some(get_file_tracer(config, resource).with_filter(trace_filter)),
+                None,
             ),
         }
     });
@@ -86,15 +100,32 @@ fn create_subscriber(
                 .without_time()
                 .with_target(!stdout_is_tty)
                 .with_ansi(stdout_is_tty)
-                .with_filter(fmt_filter),
+                .with_filter(fmt_filter()),
         )
+        // Crash reports include the lines that were printed.
+        .with(RecentLogsLayer.with_filter(fmt_filter()))
         .with(layers.0)
         .with(layers.1)
 }
 
+/// The attributes attached to every exported span.
+fn resource(app_name: &str, config: Option<&TelemetryResourceConfig>) -> Resource {
+    let mut attributes = vec![KeyValue::new("service.name", app_name.to_string())];
+    if let Some(config) = config {
+        attributes.extend(
+            config
+                .attributes()
+                .into_iter()
+                .map(|(key, value)| KeyValue::new(key, value)),
+        );
+    }
+    Resource::default().merge(&Resource::new(attributes))
+}
+
 fn get_jaeger_tracer<S>(
     app_name: &str,
     _config: &JaegerTelemetryConfig,
+    resource: Resource,
 ) -> OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>
 where
     S: for<'span> tracing_subscriber::registry::LookupSpan<'span>
@@ -104,6 +135,7 @@ where
 
     let tracer = opentelemetry_jaeger::new_agent_pipeline()
         .with_service_name(app_name)
+        .with_trace_config(sdk::trace::config().with_resource(resource))
         .install_simple()
         .expect("Failed to install OpenTelemetry tracer.");
 
@@ -112,6 +144,7 @@ where
 
 fn get_dozer_tracer<S>(
     config: &DozerTelemetryConfig,
+    resource: Resource,
 ) -> OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>
 where
     S: for<'span> tracing_subscriber::registry::LookupSpan<'span>
@@ -120,20 +153,59 @@ where
     let builder = sdk::trace::TracerProvider::builder();
     let sample_percent = config.sample_percent as f64 / 100.0;
     let exporter = DozerExporter::new(config.clone());
-    let batch_config = BatchConfig::default()
-        .with_max_concurrent_exports(100000)
-        .with_max_concurrent_exports(5);
     let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(sample_percent)));
     let batch_processor =
         BatchSpanProcessor::builder(exporter, opentelemetry::runtime::TokioCurrentThread)
-            .with_batch_config(batch_config)
+            .with_batch_config(batch_config(config))
             .build();
 
     let tracer_provider = builder
-        .with_config(opentelemetry::sdk::trace::Config {
-            sampler: Box::new(sampler),
-            ..Default::default()
-        })
+        .with_config(
+            sdk::trace::config()
+                .with_sampler(sampler)
+                .with_resource(resource),
+        )
+        .with_span_processor(batch_processor)
+        .build();
+
+    let tracer = tracer_provider.versioned_tracer(
+        "opentelemetry-dozer",
+        Some(env!("CARGO_PKG_VERSION")),
+        None,
+    );
+    let _ = global::set_tracer_provider(tracer_provider);
+    tracing_opentelemetry::layer().with_tracer(tracer)
+}
+
+fn batch_config(config: &DozerTelemetryConfig) -> BatchConfig {
+    BatchConfig::default()
+        .with_max_queue_size(config.max_queue_size as usize)
+        .with_max_export_batch_size(config.max_export_batch_size.min(config.max_queue_size) as usize)
+        .with_max_export_timeout(Duration::from_millis(config.export_timeout_ms))
+        .with_scheduled_delay(Duration::from_millis(config.scheduled_delay_ms))
+        .with_max_concurrent_exports(5)
+}
+
+fn get_file_tracer<S>(
+    config: &FileTelemetryConfig,
+    resource: Resource,
+) -> OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>
+where
+    S: for<'span> tracing_subscriber::registry::LookupSpan<'span>
+        + dozer_types::tracing::Subscriber,
+{
+    let sample_percent = config.sample_percent as f64 / 100.0;
+    let exporter = FileExporter::new(config).expect("Failed to open the span file.");
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(sample_percent)));
+    let batch_processor =
+        BatchSpanProcessor::builder(exporter, opentelemetry::runtime::TokioCurrentThread).build();
+
+    let tracer_provider = sdk::trace::TracerProvider::builder()
+        .with_config(
+            sdk::trace::config()
+                .with_sampler(sampler)
+                .with_resource(resource),
+        )
         .with_span_processor(batch_processor)
         .build();
 
@@ -145,3 +217,57 @@ where
     let _ = global::set_tracer_provider(tracer_provider);
     tracing_opentelemetry::layer().with_tracer(tracer)
 }
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{Key, Value};
+
+    use super::*;
+
+    #[test]
+    fn test_resource() {
+        let config = TelemetryResourceConfig {
+            environment: Some("production".to_string()),
+            region: None,
+            team: Some("data".to_string()),
+            tags: [
+                ("team".to_string(), "other".to_string()),
+                ("tier".to_string(), "gold".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let resource = resource("films", Some(&config));
+        let get = |key: &'static str| resource.get(Key::from_static_str(key));
+        assert_eq!(get("service.name"), Some(Value::from("films")));
+        assert_eq!(
+            get("deployment.environment"),
+            Some(Value::from("production"))
+        );
+        assert_eq!(get("team"), Some(Value::from("data")));
+        assert_eq!(get("tier"), Some(Value::from("gold")));
+        assert_eq!(get("cloud.region"), None);
+    }
+
+    #[test]
+    fn test_batch_config() {
+        let config = DozerTelemetryConfig {
+            max_queue_size: 10,
+            max_export_batch_size: 20,
+            export_timeout_ms: 100,
+            scheduled_delay_ms: 200,
+            ..Default::default()
+        };
+        // The batch size is capped by the queue size.
+        let expected = BatchConfig::default()
+            .with_max_queue_size(10)
+            .with_max_export_batch_size(10)
+            .with_max_export_timeout(Duration::from_millis(100))
+            .with_scheduled_delay(Duration::from_millis(200))
+            .with_max_concurrent_exports(5);
+        assert_eq!(
+            format!("{:?}", batch_config(&config)),
+            format!("{expected:?}")
+        );
+    }
+}
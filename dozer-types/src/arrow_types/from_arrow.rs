@@ -194,6 +194,7 @@ fn handle_with_dozer_schema(
             typ,
             nullable: field.is_nullable(),
             source: SourceDefinition::Dynamic,
+            enum_values: vec![],
         });
     }
 
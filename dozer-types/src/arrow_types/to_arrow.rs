@@ -129,6 +129,18 @@ pub fn map_record_to_arrow(
                     None as Option<&[u8]>,
                 ])) as ArrayRef
             }
+            (Field::Money(m), FieldType::Money) => {
+                Arc::new(arrow_array::StringArray::from_iter_values([m.to_string()])) as ArrayRef
+            }
+            (Field::Null, FieldType::Money) => {
+                Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
+            }
+            (Field::Enum(e), FieldType::Enum) => {
+                Arc::new(arrow_array::UInt32Array::from_iter_values([*e])) as ArrayRef
+            }
+            (Field::Null, FieldType::Enum) => {
+                Arc::new(arrow_array::UInt32Array::from(vec![None as Option<u32>])) as ArrayRef
+            }
             (a, b) => Err(arrow::error::ArrowError::InvalidArgumentError(format!(
                 "Invalid field type {b:?} for the field: {a:?}",
             )))?,
@@ -160,6 +172,8 @@ pub fn map_field_type(typ: FieldType) -> DataType {
         FieldType::Json => DataType::Utf8,
         FieldType::Point => DataType::Binary,
         FieldType::Duration => DataType::Duration(TimeUnit::Nanosecond),
+        FieldType::Money => DataType::Utf8,
+        FieldType::Enum => DataType::UInt32,
     }
 }
 
@@ -30,6 +30,12 @@ pub enum TypeError {
     DeserializationError(#[source] DeserializationError),
     #[error("Failed to calculate distance: {0}")]
     DistanceCalculationError(#[source] FailedToConvergeError),
+    #[error("Unknown ISO 4217 currency: {0}")]
+    UnknownCurrency(String),
+    #[error("Unknown value {value} of enum field {field}")]
+    UnknownEnumValue { field: String, value: String },
+    #[error("Invalid timezone {0}, expected UTC, an offset like +08:00 or an IANA name like Europe/Paris")]
+    InvalidTimezone(String),
 }
 
 #[derive(Error, Debug)]
@@ -1,4 +1,4 @@
-use crate::types::{DozerDuration, DozerPoint, Field};
+use crate::types::{DozerDuration, DozerMoney, DozerPoint, Field};
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc};
 use geo::Point;
 use ordered_float::OrderedFloat;
@@ -131,3 +131,9 @@ impl From<DozerDuration> for Field {
         Field::Duration(v)
     }
 }
+
+impl From<DozerMoney> for Field {
+    fn from(value: DozerMoney) -> Self {
+        Field::Money(value)
+    }
+}
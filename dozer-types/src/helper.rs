@@ -1,7 +1,7 @@
 use crate::errors::types::{DeserializationError, TypeError};
 use crate::json_types::{serde_json_to_json_value, JsonValue};
-use crate::types::{DozerDuration, DozerPoint, TimeUnit, DATE_FORMAT};
-use crate::types::{Field, FieldType};
+use crate::types::{DozerDuration, DozerMoney, DozerPoint, TimeUnit, DATE_FORMAT};
+use crate::types::{Field, FieldDefinition, FieldType};
 use chrono::{DateTime, NaiveDate};
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
@@ -133,10 +133,30 @@ pub fn json_value_to_field(
                     .into(),
             )),
         },
+        FieldType::Money => match value {
+            Value::String(str) => return Field::from_str(str.as_str(), typ, nullable),
+            value => serde_json::from_value(value)
+                .map_err(DeserializationError::Json)
+                .map(Field::Money),
+        },
+        FieldType::Enum => serde_json::from_value(value)
+            .map_err(DeserializationError::Json)
+            .map(Field::Enum),
     }
     .map_err(TypeError::DeserializationError)
 }
 
+/// Like `json_value_to_field`, but the value of an enum may also be one of the values declared in `definition`.
+pub fn defined_json_value_to_field(
+    value: Value,
+    definition: &FieldDefinition,
+) -> Result<Field, TypeError> {
+    match (definition.typ, value) {
+        (FieldType::Enum, Value::String(value)) => definition.enum_field(&value),
+        (typ, value) => json_value_to_field(value, typ, definition.nullable),
+    }
+}
+
 impl Field {
     pub fn from_str(value: &str, typ: FieldType, nullable: bool) -> Result<Field, TypeError> {
         match typ {
@@ -299,6 +319,26 @@ impl Field {
                     value.parse::<DozerDuration>().map(Field::Duration)
                 }
             }
+            FieldType::Money => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerMoney>().map(Field::Money)
+                }
+            }
+            FieldType::Enum => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<u32>().map(Field::Enum).map_err(|_| {
+                        TypeError::InvalidFieldValue {
+                            field_type: typ,
+                            nullable,
+                            value: value.to_string(),
+                        }
+                    })
+                }
+            }
         }
     }
 }
@@ -311,6 +351,7 @@ mod tests {
     use std::collections::BTreeMap;
 
     use super::*;
+    use crate::types::SourceDefinition;
 
     #[test]
     fn test_field_from_str() {
@@ -362,6 +403,16 @@ mod tests {
                 false,
                 Field::Point(DozerPoint(Point::new(OrderedFloat(1.0), OrderedFloat(1.0)))),
             ),
+            (
+                "12.50 usd",
+                FieldType::Money,
+                false,
+                Field::Money(DozerMoney::new(
+                    Decimal::new(1250, 2),
+                    "USD".parse().unwrap(),
+                )),
+            ),
+            ("2", FieldType::Enum, false, Field::Enum(2)),
             (
                 "{\"abc\":\"foo\"}",
                 FieldType::Json,
@@ -397,6 +448,8 @@ mod tests {
             ("null", FieldType::Point, true, Field::Null),
             ("null", FieldType::Json, true, Field::Null),
             ("null", FieldType::Duration, true, Field::Null),
+            ("null", FieldType::Money, true, Field::Null),
+            ("null", FieldType::Enum, true, Field::Null),
             ("", FieldType::UInt, true, Field::Null),
             ("", FieldType::U128, true, Field::Null),
             ("", FieldType::Int, true, Field::Null),
@@ -431,6 +484,9 @@ mod tests {
             ("null", FieldType::Date, false),
             ("null", FieldType::Point, false),
             ("null", FieldType::Duration, false),
+            ("null", FieldType::Money, false),
+            ("12.50 ABC", FieldType::Money, false),
+            ("closed", FieldType::Enum, false),
             ("", FieldType::UInt, false),
             ("", FieldType::U128, false),
             ("", FieldType::Int, false),
@@ -448,4 +504,28 @@ mod tests {
             assert!(Field::from_str(err_case.0, err_case.1, err_case.2).is_err());
         }
     }
+
+    #[test]
+    fn test_defined_json_value_to_field() {
+        let definition = FieldDefinition::new(
+            "status".to_string(),
+            FieldType::Enum,
+            true,
+            SourceDefinition::Dynamic,
+        )
+        .with_enum_values(vec!["active".to_string(), "closed".to_string()]);
+        assert_eq!(
+            defined_json_value_to_field(Value::from("closed"), &definition).unwrap(),
+            Field::Enum(1)
+        );
+        assert_eq!(
+            defined_json_value_to_field(Value::from(0), &definition).unwrap(),
+            Field::Enum(0)
+        );
+        assert_eq!(
+            defined_json_value_to_field(Value::Null, &definition).unwrap(),
+            Field::Null
+        );
+        assert!(defined_json_value_to_field(Value::from("open"), &definition).is_err());
+    }
 }
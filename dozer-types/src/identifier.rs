@@ -0,0 +1,102 @@
+//! How identifiers of the SQL and names in the config refer to sources, columns, aliases and endpoints.
+//!
+//! Quoted identifiers refer to names spelled exactly like them. Unquoted identifiers also refer to names that only
+//! differ in case, unless identifiers are case sensitive. A name spelled exactly like an identifier wins over ones
+//! only differing in case.
+
+/// Whether the identifier `value`, `quoted` or not, refers to `name`.
+pub fn name_matches(value: &str, quoted: bool, case_sensitive: bool, name: &str) -> bool {
+    value == name || (!quoted && !case_sensitive && value.to_lowercase() == name.to_lowercase())
+}
+
+/// The items whose name the identifier `value` refers to. If some are spelled exactly like it, only those.
+pub fn matching<T>(
+    value: &str,
+    quoted: bool,
+    case_sensitive: bool,
+    items: impl IntoIterator<Item = T>,
+    name: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let mut matches: Vec<T> = items
+        .into_iter()
+        .filter(|item| name_matches(value, quoted, case_sensitive, name(item)))
+        .collect();
+    if matches.iter().any(|item| name(item) == value) {
+        matches.retain(|item| name(item) == value);
+    }
+    matches
+}
+
+/// The name `value` was most likely a misspelling of, if any is close enough.
+pub fn did_you_mean<'a>(value: &str, names: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let value = value.to_lowercase();
+    let max_distance = value.chars().count().max(3) / 3;
+    names
+        .into_iter()
+        .map(|name| (edit_distance(&value, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Levenshtein distance of `a` and `b`, in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_matches() {
+        assert!(name_matches("users", false, false, "Users"));
+        assert!(name_matches("Users", true, false, "Users"));
+        assert!(!name_matches("users", true, false, "Users"));
+        assert!(!name_matches("users", false, false, "user"));
+    }
+
+    #[test]
+    fn test_name_matches_case_sensitive() {
+        assert!(name_matches("Users", false, true, "Users"));
+        assert!(!name_matches("users", false, true, "Users"));
+        assert!(matching("iD", false, true, ["Id", "id", "ID"], |name| name).is_empty());
+    }
+
+    #[test]
+    fn test_matching_prefers_exact_spelling() {
+        let names = ["Id", "id", "ID"];
+        assert_eq!(matching("id", false, false, names, |name| name), vec!["id"]);
+        assert_eq!(
+            matching("iD", false, false, names, |name| name),
+            names.to_vec()
+        );
+        assert!(matching("iD", true, false, names, |name| name).is_empty());
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let names = ["customer_id", "name", "amount"];
+        assert_eq!(did_you_mean("custmer_id", names), Some("customer_id"));
+        assert_eq!(did_you_mean("NAME", names), Some("name"));
+        assert_eq!(did_you_mean("amt", names), None);
+        assert_eq!(did_you_mean("price", names), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+    }
+}
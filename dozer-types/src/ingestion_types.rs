@@ -1,5 +1,5 @@
 use prettytable::Table as PrettyTable;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -36,6 +36,34 @@ impl IngestionMessage {
             kind: IngestionMessageKind::SnapshottingStarted,
         }
     }
+
+    pub fn new_schema_drift(txn: u64, seq_no: u64, table_index: usize, drift: SchemaDrift) -> Self {
+        Self {
+            identifier: OpIdentifier::new(txn, seq_no),
+            kind: IngestionMessageKind::SchemaDrift { table_index, drift },
+        }
+    }
+
+    pub fn new_schema_changed(txn: u64, seq_no: u64, table_index: usize) -> Self {
+        Self {
+            identifier: OpIdentifier::new(txn, seq_no),
+            kind: IngestionMessageKind::SchemaChanged { table_index },
+        }
+    }
+
+    pub fn new_transaction_begin(txn: u64, seq_no: u64) -> Self {
+        Self {
+            identifier: OpIdentifier::new(txn, seq_no),
+            kind: IngestionMessageKind::TransactionBegin,
+        }
+    }
+
+    pub fn new_transaction_end(txn: u64, seq_no: u64) -> Self {
+        Self {
+            identifier: OpIdentifier::new(txn, seq_no),
+            kind: IngestionMessageKind::TransactionEnd,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -48,12 +76,94 @@ pub enum IngestionMessageKind {
     /// A connector uses this message kind to notify Dozer that a initial snapshot of the source tables is done,
     /// and the data is up-to-date until next CDC event.
     SnapshottingDone,
+    /// A connector uses this message kind to notify Dozer that the schema of a source table changed after
+    /// ingestion started. The connector keeps mapping records to the original schema.
+    SchemaDrift {
+        table_index: usize,
+        drift: SchemaDrift,
+    },
+    /// A connector uses this message kind to notify Dozer that the schema of a source table changed and it stopped
+    /// ingesting, so that the pipeline is rebuilt with the new schema. The drifts are sent before it.
+    SchemaChanged { table_index: usize },
+    /// A connector uses this message kind to notify Dozer that the following operations belong to one source
+    /// transaction. The pipeline may commit them in several epochs, but endpoints only expose them together, after
+    /// `TransactionEnd`.
+    TransactionBegin,
+    /// A connector uses this message kind to notify Dozer that the transaction started by `TransactionBegin` is
+    /// complete.
+    TransactionEnd,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A change to one column of a source table.
+pub struct SchemaDrift {
+    pub column_name: String,
+    pub kind: SchemaDriftKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchemaDriftKind {
+    ColumnAdded,
+    ColumnDropped,
+    /// The column's source type changed. `compatible` is true if the column still maps to the same Dozer type.
+    TypeChanged {
+        old_type: String,
+        new_type: String,
+        compatible: bool,
+    },
+}
+
+impl Display for SchemaDriftKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaDriftKind::ColumnAdded => f.write_str("column_added"),
+            SchemaDriftKind::ColumnDropped => f.write_str("column_dropped"),
+            SchemaDriftKind::TypeChanged {
+                compatible: true, ..
+            } => f.write_str("compatible_type_change"),
+            SchemaDriftKind::TypeChanged {
+                compatible: false, ..
+            } => f.write_str("incompatible_type_change"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SchemaDriftSeverity {
+    /// The drift doesn't affect the pipeline.
+    Info,
+    /// The drift affects the pipeline, but ingestion continues.
+    Warning,
+    /// The drift affects the pipeline and ingestion is stopped.
+    Error,
+}
+
+impl Display for SchemaDriftSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaDriftSeverity::Info => f.write_str("info"),
+            SchemaDriftSeverity::Warning => f.write_str("warning"),
+            SchemaDriftSeverity::Error => f.write_str("error"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A schema drift classified against the columns the pipeline uses.
+pub struct SchemaDriftAlert {
+    pub connection: String,
+    pub table: String,
+    pub drift: SchemaDrift,
+    pub severity: SchemaDriftSeverity,
 }
 
 #[derive(Error, Debug)]
 pub enum IngestorError {
     #[error("Failed to send message on channel")]
     ChannelError(#[from] BoxedError),
+    /// Only raised by fault injection, see the `chaos` feature of `dozer-ingestion`.
+    #[error("Injected fault: {0}")]
+    InjectedFault(&'static str),
 }
 
 pub trait IngestorForwarder: Send + Sync + Debug {
@@ -195,6 +305,14 @@ pub struct KafkaConfig {
     pub broker: String,
     #[prost(string, optional, tag = "3")]
     pub schema_registry_url: Option<String>,
+    /// Without a schema registry, messages are json objects whose schema is inferred from the first messages of each
+    /// topic, if set. Otherwise they are ingested as strings.
+    #[prost(uint32, optional, tag = "4")]
+    pub schema_sample_size: Option<u32>,
+    /// Messages are Debezium change events, whose `before` and `after` rows are ingested as inserts, updates and
+    /// deletes according to their `op`; Default: false
+    #[prost(bool, optional, tag = "5")]
+    pub debezium: Option<bool>,
 }
 
 impl KafkaConfig {
@@ -206,7 +324,13 @@ impl KafkaConfig {
                 self.schema_registry_url
                     .as_ref()
                     .map_or("--------", |url| url)
-            ]
+            ],
+            [
+                "schema sample size",
+                self.schema_sample_size
+                    .map_or("--------".to_string(), |size| size.to_string())
+            ],
+            ["debezium", self.debezium.unwrap_or(false)]
         )
     }
 }
@@ -286,10 +410,14 @@ impl DataFusionConfig {
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
 pub struct Table {
-    #[prost(oneof = "TableConfig", tags = "1,2,3")]
+    #[prost(oneof = "TableConfig", tags = "1,2,3,5")]
     pub config: Option<TableConfig>,
     #[prost(string, tag = "4")]
     pub name: String,
+    #[prost(bool, optional, tag = "6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether files added after the snapshot are ingested; Default: true
+    pub watch: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof, Hash)]
@@ -300,6 +428,24 @@ pub enum TableConfig {
     Delta(DeltaConfig),
     #[prost(message, tag = "3")]
     Parquet(ParquetConfig),
+    #[prost(message, tag = "5")]
+    /// Files of json objects, one per line
+    Jsonl(JsonlConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A column of the files of a table, declared instead of inferred from the files.
+pub struct FileColumn {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    #[serde(rename = "type")]
+    /// One of int, uint, float, boolean, string, text, binary, timestamp, date and json
+    pub typ: String,
+    #[prost(bool, tag = "3")]
+    #[serde(default = "default_true")]
+    /// Default: true
+    pub nullable: bool,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
@@ -314,6 +460,28 @@ pub struct CsvConfig {
     #[prost(string, tag = "4")]
     #[serde(default = "default_marker")]
     pub marker_extension: String,
+    #[prost(message, repeated, tag = "5")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Columns of the files, in order; Default: inferred from the files
+    pub columns: Vec<FileColumn>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct JsonlConfig {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(string, tag = "2")]
+    pub extension: String,
+    #[prost(bool, tag = "3")]
+    #[serde(default = "default_false")]
+    pub marker_file: bool,
+    #[prost(string, tag = "4")]
+    #[serde(default = "default_marker")]
+    pub marker_extension: String,
+    #[prost(message, repeated, tag = "5")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Columns read from the fields of the objects; Default: inferred from the files
+    pub columns: Vec<FileColumn>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
@@ -400,6 +568,10 @@ pub struct DeltaTable {
     pub path: String,
     #[prost(string, tag = "2")]
     pub name: String,
+    #[prost(bool, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether the versions committed after the snapshot are ingested; Default: true
+    pub follow: Option<bool>,
 }
 
 impl DeltaTable {
@@ -412,12 +584,1146 @@ impl DeltaTable {
 pub struct DeltaLakeConfig {
     #[prost(message, repeated, tag = "1")]
     pub tables: Vec<DeltaTable>,
+    #[prost(uint64, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Interval of checking the transaction logs of the followed tables for new versions; Default: 1000
+    pub poll_interval_ms: Option<u64>,
+}
+
+pub fn default_delta_lake_poll_interval_ms() -> u64 {
+    1000
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct GeneratorConfig {
+    #[prost(message, repeated, tag = "1")]
+    pub tables: Vec<GeneratorTable>,
+    #[prost(uint64, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Seed of the random generator, to reproduce the same operations; Default: random
+    pub seed: Option<u64>,
+}
+
+impl GeneratorConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        let mut table = table!(["seed", format!("{:?}", self.seed)]);
+        for generator_table in &self.tables {
+            table.add_row(row![
+                generator_table.name,
+                format!("{} operations/s", generator_table.operations_per_sec)
+            ]);
+        }
+        table
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A table of synthetic records. Besides the declared columns, records have an `id` primary key of type uint.
+pub struct GeneratorTable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub columns: Vec<GeneratorColumn>,
+    #[prost(uint64, tag = "3", default = "100")]
+    #[serde(default = "default_generator_operations_per_sec")]
+    pub operations_per_sec: u64,
+    #[prost(uint64, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The table stops producing operations after this many; Default: unlimited
+    pub max_operations: Option<u64>,
+    #[prost(uint32, tag = "5")]
+    #[serde(default)]
+    /// Percentage of operations updating an existing record
+    pub update_percent: u32,
+    #[prost(uint32, tag = "6")]
+    #[serde(default)]
+    /// Percentage of operations deleting an existing record
+    pub delete_percent: u32,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct GeneratorColumn {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    #[serde(rename = "type")]
+    /// One of int, uint, float, boolean, string and timestamp
+    pub typ: String,
+    #[prost(int64, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Smallest generated number. Strings are suffixed with a number and timestamps are offset from now by a number of seconds; Default: 0
+    pub min: Option<i64>,
+    #[prost(int64, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Largest generated number; Default: 1000
+    pub max: Option<i64>,
+    #[prost(string, tag = "5", default = "uniform")]
+    #[serde(default = "default_generator_distribution")]
+    /// `uniform`, or `skewed` to generate small numbers much more often than large ones
+    pub distribution: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// An Oracle database, replicated with LogMiner. It must be in archive log mode with supplemental logging of all columns.
+pub struct OracleConfig {
+    #[prost(string, tag = "1")]
+    pub user: String,
+    #[prost(string, tag = "2")]
+    pub password: String,
+    #[prost(string, tag = "3")]
+    pub host: String,
+    #[prost(uint32, tag = "4", default = "1521")]
+    #[serde(default = "default_oracle_port")]
+    pub port: u32,
+    #[prost(string, tag = "5")]
+    /// Service name of the database
+    pub service: String,
+    #[prost(uint64, tag = "6", default = "1000")]
+    #[serde(default = "default_oracle_poll_interval_ms")]
+    /// How often the redo logs are mined for new changes
+    pub poll_interval_ms: u64,
+}
+
+impl OracleConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["user", self.user],
+            ["password", "************"],
+            ["host", self.host],
+            ["port", self.port],
+            ["service", self.service],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct FirestoreConfig {
+    #[prost(string, tag = "1")]
+    /// Google Cloud project of the database
+    pub project_id: String,
+    #[prost(string, tag = "2", default = "(default)")]
+    #[serde(default = "default_firestore_database_id")]
+    pub database_id: String,
+    #[prost(string, tag = "3", default = "flatten")]
+    #[serde(default = "default_firestore_nested_fields")]
+    /// How fields of nested maps are mapped: `flatten` to a column each, named with the path of the field joined by
+    /// `.`, or `json` to a json column of the map
+    pub nested_fields: String,
+    #[prost(uint32, tag = "4", default = "100")]
+    #[serde(default = "default_firestore_schema_sample_size")]
+    /// How many documents of a collection are read to infer its columns
+    pub schema_sample_size: u32,
+}
+
+impl FirestoreConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["project_id", self.project_id],
+            ["database_id", self.database_id],
+            ["nested_fields", self.nested_fields],
+            ["schema_sample_size", self.schema_sample_size]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct StripeConfig {
+    #[prost(string, tag = "1")]
+    /// Secret or restricted API key
+    pub api_key: String,
+    #[prost(uint64, tag = "2", default = "60000")]
+    #[serde(default = "default_stripe_poll_interval_ms")]
+    /// How often objects created since the last poll are requested
+    pub poll_interval_ms: u64,
+    #[prost(string, tag = "3", default = "https://api.stripe.com/v1")]
+    #[serde(default = "default_stripe_api_url")]
+    pub api_url: String,
+}
+
+impl StripeConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["api_key", "************"],
+            ["poll_interval_ms", self.poll_interval_ms],
+            ["api_url", self.api_url]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A MySQL or MariaDB database, replicated from its binlog. The binlog must be in ROW format with full row images.
+pub struct MySQLConfig {
+    #[prost(string, tag = "1")]
+    pub user: String,
+    #[prost(string, tag = "2")]
+    pub password: String,
+    #[prost(string, tag = "3")]
+    pub host: String,
+    #[prost(uint32, tag = "4", default = "3306")]
+    #[serde(default = "default_mysql_port")]
+    pub port: u32,
+    #[prost(string, tag = "5")]
+    /// Database of tables without a schema
+    pub database: String,
+    #[prost(uint32, tag = "6", default = "1001")]
+    #[serde(default = "default_mysql_server_id")]
+    /// Server id the binlog is read as, which must differ from the ids of the server and its replicas
+    pub server_id: u32,
+    #[prost(uint64, tag = "7", default = "10000")]
+    #[serde(default = "default_mysql_transaction_chunk_size")]
+    /// Operations of a transaction are sent in chunks of this size, so that large transactions aren't held in memory; Default: 10000
+    pub transaction_chunk_size: u64,
+    #[prost(uint32, tag = "8", default = "1")]
+    #[serde(default = "default_snapshot_parallelism")]
+    /// Number of connections reading each table in the initial snapshot, each reading chunks of the primary key range. Tables without a primary key are read by one connection; Default: 1
+    pub snapshot_parallelism: u32,
+    #[prost(uint64, tag = "9", default = "100000")]
+    #[serde(default = "default_snapshot_chunk_size")]
+    /// Number of rows in a primary key chunk of a parallel snapshot; Default: 100000
+    pub snapshot_chunk_size: u64,
+}
+
+impl MySQLConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["user", self.user],
+            ["password", "************"],
+            ["host", self.host],
+            ["port", self.port],
+            ["database", self.database],
+            ["server_id", self.server_id],
+            ["transaction_chunk_size", self.transaction_chunk_size],
+            ["snapshot_parallelism", self.snapshot_parallelism],
+            ["snapshot_chunk_size", self.snapshot_chunk_size]
+        )
+    }
+}
+
+fn default_mysql_port() -> u32 {
+    3306
+}
+
+fn default_mysql_server_id() -> u32 {
+    1001
+}
+
+fn default_mysql_transaction_chunk_size() -> u64 {
+    10000
+}
+
+pub fn default_snapshot_parallelism() -> u32 {
+    1
+}
+
+pub fn default_snapshot_chunk_size() -> u64 {
+    100000
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A SQL Server database, replicated from the change tables of CDC. CDC must be enabled on the database and tables.
+pub struct SqlServerConfig {
+    #[prost(string, tag = "1")]
+    pub user: String,
+    #[prost(string, tag = "2")]
+    pub password: String,
+    #[prost(string, tag = "3")]
+    pub host: String,
+    #[prost(uint32, tag = "4", default = "1433")]
+    #[serde(default = "default_sql_server_port")]
+    pub port: u32,
+    #[prost(string, tag = "5")]
+    pub database: String,
+    #[prost(bool, tag = "6")]
+    #[serde(default)]
+    /// Accepts the server certificate without validating it, e.g. a self-signed one; Default: false
+    pub trust_server_certificate: bool,
+    #[prost(uint64, tag = "7", default = "1000")]
+    #[serde(default = "default_sql_server_poll_interval_ms")]
+    /// How often change tables are polled for new changes; Default: 1000
+    pub poll_interval_ms: u64,
+}
+
+impl SqlServerConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["user", self.user],
+            ["password", "************"],
+            ["host", self.host],
+            ["port", self.port],
+            ["database", self.database],
+            ["trust_server_certificate", self.trust_server_certificate],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Amazon Kinesis Data Streams, ingested as tables of their records. Credentials are read from the environment, like
+/// the AWS CLI does.
+pub struct KinesisConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: the region of the environment
+    pub region: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Url of a Kinesis compatible endpoint, e.g. LocalStack; Default: the AWS endpoint of the region
+    pub endpoint_url: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// File the sequence number read up to in each shard is saved in, so a restarted connector resumes after it; Default: None, shards are read from their oldest record
+    pub checkpoint_path: Option<String>,
+    #[prost(uint64, tag = "4", default = "1000")]
+    #[serde(default = "default_kinesis_poll_interval_ms")]
+    /// How often shards are polled for new records; Default: 1000
+    pub poll_interval_ms: u64,
+    #[prost(uint64, tag = "5", default = "60000")]
+    #[serde(default = "default_kinesis_shard_refresh_interval_ms")]
+    /// How often shards are listed, to read the ones created by resharding; Default: 60000
+    pub shard_refresh_interval_ms: u64,
+}
+
+impl KinesisConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["region", self.region.as_deref().unwrap_or("--------")],
+            [
+                "endpoint_url",
+                self.endpoint_url.as_deref().unwrap_or("--------")
+            ],
+            [
+                "checkpoint_path",
+                self.checkpoint_path.as_deref().unwrap_or("--------")
+            ],
+            ["poll_interval_ms", self.poll_interval_ms],
+            ["shard_refresh_interval_ms", self.shard_refresh_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Apache Iceberg tables of a REST catalog, ingested as tables named by their namespace and name. Data files on S3 are
+/// read with the credentials of the environment, like the AWS CLI does.
+pub struct IcebergConfig {
+    #[prost(string, tag = "1")]
+    /// Url of the catalog, without the `/v1` path
+    pub catalog_uri: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Warehouse sent to the catalog; Default: None
+    pub warehouse: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Bearer token authenticating with the catalog; Default: None
+    pub token: Option<String>,
+    #[prost(bool, tag = "4", default = "true")]
+    #[serde(default = "default_iceberg_follow")]
+    /// Whether the snapshots committed after the first one read are ingested; Default: true
+    pub follow: bool,
+    #[prost(uint64, tag = "5", default = "10000")]
+    #[serde(default = "default_iceberg_poll_interval_ms")]
+    /// How often the catalog is checked for new snapshots of the tables; Default: 10000
+    pub poll_interval_ms: u64,
+}
+
+impl IcebergConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["catalog_uri", self.catalog_uri],
+            ["warehouse", self.warehouse.as_deref().unwrap_or("--------")],
+            ["follow", self.follow],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// An HTTP endpoint receiving JSON payloads, e.g. of SaaS webhooks. Each table is posted to at its own path, and each
+/// object posted is an operation on it.
+pub struct WebhookConfig {
+    #[prost(string, tag = "1", default = "0.0.0.0")]
+    #[serde(default = "default_ingest_host")]
+    pub host: String,
+    #[prost(uint32, tag = "2", default = "8090")]
+    #[serde(default = "default_webhook_port")]
+    pub port: u32,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Token requests must carry as `Authorization: Bearer <token>`; Default: None, requests aren't authenticated
+    pub token: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    pub tables: Vec<WebhookTable>,
+}
+
+impl WebhookConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["host", self.host],
+            ["port", self.port],
+            [
+                "token",
+                self.token.as_ref().map_or("--------", |_| "************")
+            ],
+            [
+                "tables",
+                self.tables
+                    .iter()
+                    .map(|table| table.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct WebhookTable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Path payloads of the table are posted to; Default: `/<name>`
+    pub path: Option<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub columns: Vec<WebhookColumn>,
+    #[prost(string, repeated, tag = "4")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Names of the primary key columns; Default: none
+    pub primary_key: Vec<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Field of payloads telling if they insert, update or delete a row, by their primary key. Its values are `insert`
+    /// or `create`, `update`, and `delete`; Default: None, payloads are inserts
+    pub operation_field: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A column mapped from a field of the payloads.
+pub struct WebhookColumn {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    #[serde(rename = "type")]
+    /// One of int, uint, float, boolean, string, text, binary, decimal, timestamp, date, json, point, money and enum
+    pub typ: String,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Field of the payloads, with nested fields separated by `.`; Default: the column name
+    pub field: Option<String>,
+    #[prost(bool, tag = "4")]
+    #[serde(default = "default_true")]
+    /// Default: true
+    pub nullable: bool,
+    #[prost(string, repeated, tag = "5")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The values of an enum column, in order
+    pub values: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Redis Streams, ingested as tables of their entries. Entries are read with a consumer group, which keeps the entries
+/// read up to, and acknowledged once ingested.
+pub struct RedisStreamsConfig {
+    #[prost(string, tag = "1")]
+    /// Url of the server, e.g. `redis://:<password>@localhost:6379/0`, or `rediss://` for TLS
+    pub url: String,
+    #[prost(string, tag = "2", default = "dozer")]
+    #[serde(default = "default_redis_streams_group")]
+    /// Consumer group reading the streams, created if it doesn't exist; Default: dozer
+    pub group: String,
+    #[prost(string, tag = "3", default = "dozer")]
+    #[serde(default = "default_redis_streams_consumer")]
+    /// Name of the connector in the consumer group; Default: dozer
+    pub consumer: String,
+    #[prost(string, tag = "4", default = "0")]
+    #[serde(default = "default_redis_streams_start_id")]
+    /// Id the consumer group starts reading a stream after when it's created, `0` for the whole stream or `$` for new
+    /// entries only; Default: 0
+    pub start_id: String,
+    #[prost(uint64, tag = "5", default = "100")]
+    #[serde(default = "default_redis_streams_batch_size")]
+    /// Maximum number of entries read from a stream at once; Default: 100
+    pub batch_size: u64,
+    #[prost(uint64, tag = "6", default = "5000")]
+    #[serde(default = "default_redis_streams_block_ms")]
+    /// How long a read waits for new entries; Default: 5000
+    pub block_ms: u64,
+}
+
+impl RedisStreamsConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["url", "************"],
+            ["group", self.group],
+            ["consumer", self.consumer],
+            ["start_id", self.start_id],
+            ["batch_size", self.batch_size],
+            ["block_ms", self.block_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// NATS JetStream subjects, each table reading the JSON messages of a subject with a durable consumer, which keeps the
+/// messages read up to. Messages are acknowledged once ingested.
+pub struct NatsConfig {
+    #[prost(string, tag = "1")]
+    /// Url of the server, e.g. `nats://localhost:4222`
+    pub url: String,
+    #[prost(string, tag = "2")]
+    /// JetStream stream the subjects are in
+    pub stream: String,
+    #[prost(string, tag = "3", default = "dozer")]
+    #[serde(default = "default_nats_durable_name")]
+    /// Prefix of the durable consumer of each table, named `<durable_name>_<table>`; Default: dozer
+    pub durable_name: String,
+    #[prost(string, tag = "4", default = "all")]
+    #[serde(default = "default_nats_deliver_policy")]
+    /// Where a consumer starts reading its subject when it's created, one of `all`, `new`, `last` and
+    /// `last_per_subject`; Default: all
+    pub deliver_policy: String,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Credentials file authenticating with the server; Default: None
+    pub credentials_file: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Token authenticating with the server; Default: None
+    pub token: Option<String>,
+    #[prost(message, repeated, tag = "7")]
+    pub tables: Vec<NatsTable>,
+}
+
+impl NatsConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["url", self.url],
+            ["stream", self.stream],
+            ["durable_name", self.durable_name],
+            ["deliver_policy", self.deliver_policy],
+            [
+                "credentials_file",
+                self.credentials_file.as_deref().unwrap_or("--------")
+            ],
+            [
+                "token",
+                self.token.as_ref().map_or("--------", |_| "************")
+            ],
+            [
+                "tables",
+                self.tables
+                    .iter()
+                    .map(|table| table.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A table of the JSON messages of a subject, whose columns are mapped like the ones of webhook tables.
+pub struct NatsTable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Subject of the messages, which may have wildcards; Default: the table name
+    pub subject: Option<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub columns: Vec<WebhookColumn>,
+    #[prost(string, repeated, tag = "4")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Names of the primary key columns; Default: none
+    pub primary_key: Vec<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Field of messages telling if they insert, update or delete a row, by their primary key. Its values are `insert`
+    /// or `create`, `update`, and `delete`; Default: None, messages are inserts
+    pub operation_field: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct MqttConfig {
+    #[prost(string, tag = "1")]
+    /// Host of the broker
+    pub host: String,
+    #[prost(uint32, tag = "2", default = "1883")]
+    #[serde(default = "default_mqtt_port")]
+    /// Port of the broker; Default: 1883
+    pub port: u32,
+    #[prost(string, tag = "3", default = "dozer")]
+    #[serde(default = "default_mqtt_client_id")]
+    /// Client id of the connector, which identifies its session on the broker; Default: dozer
+    pub client_id: String,
+    #[prost(string, tag = "4", default = "3.1.1")]
+    #[serde(default = "default_mqtt_protocol_version")]
+    /// MQTT version, `3.1.1` or `5`; Default: 3.1.1
+    pub protocol_version: String,
+    #[prost(uint32, tag = "5", default = "1")]
+    #[serde(default = "default_mqtt_qos")]
+    /// QoS of the subscriptions, `0` or `1`; Default: 1
+    pub qos: u32,
+    #[prost(bool, tag = "6")]
+    #[serde(default)]
+    /// Start a new session on every connection instead of resuming the previous one, dropping the messages
+    /// published while disconnected; Default: false
+    pub clean_session: bool,
+    #[prost(uint32, tag = "7", default = "3600")]
+    #[serde(default = "default_mqtt_session_expiry_secs")]
+    /// How long the broker keeps the session once disconnected, with MQTT 5. MQTT 3.1.1 brokers keep sessions until
+    /// they are cleaned; Default: 3600
+    pub session_expiry_secs: u32,
+    #[prost(uint64, tag = "8", default = "30")]
+    #[serde(default = "default_mqtt_keep_alive_secs")]
+    /// Keep alive interval of the connection; Default: 30
+    pub keep_alive_secs: u64,
+    #[prost(string, optional, tag = "9")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Username authenticating with the broker; Default: None
+    pub username: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Password authenticating with the broker; Default: None
+    pub password: Option<String>,
+    #[prost(message, repeated, tag = "11")]
+    pub tables: Vec<MqttTable>,
+}
+
+impl MqttConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["host", self.host],
+            ["port", self.port],
+            ["client_id", self.client_id],
+            ["protocol_version", self.protocol_version],
+            ["qos", self.qos],
+            ["clean_session", self.clean_session],
+            ["session_expiry_secs", self.session_expiry_secs],
+            ["keep_alive_secs", self.keep_alive_secs],
+            ["username", self.username.as_deref().unwrap_or("--------")],
+            [
+                "password",
+                self.password
+                    .as_ref()
+                    .map_or("--------", |_| "************")
+            ],
+            [
+                "tables",
+                self.tables
+                    .iter()
+                    .map(|table| table.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A table of the JSON messages of topics, whose columns are mapped like the ones of webhook tables.
+pub struct MqttTable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Topic filter of the messages, which may have the wildcards `+` and `#`; Default: the table name
+    pub topic: Option<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub columns: Vec<WebhookColumn>,
+    #[prost(string, repeated, tag = "4")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Names of the primary key columns; Default: none
+    pub primary_key: Vec<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Field of messages telling if they insert, update or delete a row, by their primary key. Its values are `insert`
+    /// or `create`, `update`, and `delete`; Default: None, messages are inserts
+    pub operation_field: Option<String>,
+}
+
+fn default_mqtt_port() -> u32 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "dozer".to_string()
+}
+
+fn default_mqtt_protocol_version() -> String {
+    "3.1.1".to_string()
+}
+
+fn default_mqtt_qos() -> u32 {
+    1
+}
+
+fn default_mqtt_session_expiry_secs() -> u32 {
+    3600
+}
+
+fn default_mqtt_keep_alive_secs() -> u64 {
+    30
+}
+
+fn default_dynamodb_scan_segments() -> u32 {
+    4
+}
+
+fn default_dynamodb_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_dynamodb_shard_refresh_interval_ms() -> u64 {
+    60000
+}
+
+fn default_cassandra_snapshot_token_ranges() -> u32 {
+    64
+}
+
+fn default_cassandra_snapshot_parallelism() -> u32 {
+    8
+}
+
+fn default_cassandra_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_cassandra_cdc_delay_ms() -> u64 {
+    30000
+}
+
+fn default_elasticsearch_batch_size() -> u32 {
+    1000
+}
+
+fn default_elasticsearch_poll_interval_ms() -> u64 {
+    5000
+}
+
+fn default_nats_durable_name() -> String {
+    "dozer".to_string()
+}
+
+fn default_nats_deliver_policy() -> String {
+    "all".to_string()
+}
+
+fn default_redis_streams_group() -> String {
+    "dozer".to_string()
+}
+
+fn default_redis_streams_consumer() -> String {
+    "dozer".to_string()
+}
+
+fn default_redis_streams_start_id() -> String {
+    "0".to_string()
+}
+
+fn default_redis_streams_batch_size() -> u64 {
+    100
+}
+
+fn default_redis_streams_block_ms() -> u64 {
+    5000
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Amazon DynamoDB tables, snapshotted with parallel scans and then followed with DynamoDB Streams, which must be
+/// enabled on the tables with the view type `NEW_IMAGE` or `NEW_AND_OLD_IMAGES`. Credentials are read from the
+/// environment, like the AWS CLI does.
+pub struct DynamoDbConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: the region of the environment
+    pub region: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Url of a DynamoDB compatible endpoint, e.g. LocalStack, used for DynamoDB Streams too; Default: the AWS endpoint of the region
+    pub endpoint_url: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// File the sequence number read up to in each shard of the streams is saved in, so a restarted connector resumes after it instead of snapshotting the tables again; Default: None
+    pub checkpoint_path: Option<String>,
+    #[prost(uint32, tag = "4", default = "4")]
+    #[serde(default = "default_dynamodb_scan_segments")]
+    /// Number of segments of a table scanned in parallel by the snapshot; Default: 4
+    pub scan_segments: u32,
+    #[prost(uint64, tag = "5", default = "1000")]
+    #[serde(default = "default_dynamodb_poll_interval_ms")]
+    /// How often shards are polled for new stream records; Default: 1000
+    pub poll_interval_ms: u64,
+    #[prost(uint64, tag = "6", default = "60000")]
+    #[serde(default = "default_dynamodb_shard_refresh_interval_ms")]
+    /// How often shards are listed, to read the ones that replace the closed ones; Default: 60000
+    pub shard_refresh_interval_ms: u64,
+    #[prost(message, repeated, tag = "7")]
+    pub tables: Vec<DynamoDbTable>,
+}
+
+impl DynamoDbConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["region", self.region.as_deref().unwrap_or("--------")],
+            [
+                "endpoint_url",
+                self.endpoint_url.as_deref().unwrap_or("--------")
+            ],
+            [
+                "checkpoint_path",
+                self.checkpoint_path.as_deref().unwrap_or("--------")
+            ],
+            ["scan_segments", self.scan_segments],
+            ["poll_interval_ms", self.poll_interval_ms],
+            ["shard_refresh_interval_ms", self.shard_refresh_interval_ms],
+            [
+                "tables",
+                self.tables
+                    .iter()
+                    .map(|table| table.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// A DynamoDB table, whose attributes are mapped to columns like the fields of webhook payloads. Its primary key is the
+/// key of the DynamoDB table.
+pub struct DynamoDbTable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub columns: Vec<WebhookColumn>,
+    #[prost(bool, tag = "3", default = "true")]
+    #[serde(default = "default_true")]
+    /// Whether items deleted by their time to live are deleted from the table, instead of being kept; Default: true
+    pub ttl_deletes: bool,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// The tables of a Cassandra or ScyllaDB keyspace, snapshotted by token ranges in parallel and then followed with the
+/// CDC log tables of ScyllaDB, which must be enabled on the tables with pre-images and post-images.
+pub struct CassandraConfig {
+    #[prost(string, repeated, tag = "1")]
+    /// Nodes of the cluster to connect to, as `host:port`
+    pub hosts: Vec<String>,
+    #[prost(string, tag = "2")]
+    pub keyspace: String,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub user: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub password: Option<String>,
+    #[prost(uint32, tag = "5", default = "64")]
+    #[serde(default = "default_cassandra_snapshot_token_ranges")]
+    /// Number of ranges the token ring is split into by the snapshot; Default: 64
+    pub snapshot_token_ranges: u32,
+    #[prost(uint32, tag = "6", default = "8")]
+    #[serde(default = "default_cassandra_snapshot_parallelism")]
+    /// Number of token ranges read at a time by the snapshot; Default: 8
+    pub snapshot_parallelism: u32,
+    #[prost(bool, tag = "7", default = "true")]
+    #[serde(default = "default_true")]
+    /// Whether changes are read from the CDC log tables after the snapshot, which Cassandra doesn't have; Default: true
+    pub cdc: bool,
+    #[prost(uint64, tag = "8", default = "1000")]
+    #[serde(default = "default_cassandra_poll_interval_ms")]
+    /// How often the CDC log tables are polled; Default: 1000
+    pub poll_interval_ms: u64,
+    #[prost(uint64, tag = "9", default = "30000")]
+    #[serde(default = "default_cassandra_cdc_delay_ms")]
+    /// How old changes are before they are read, as they can be written to the log after the time they have; Default: 30000
+    pub cdc_delay_ms: u64,
+}
+
+impl CassandraConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["hosts", self.hosts.join(", ")],
+            ["keyspace", self.keyspace],
+            ["user", self.user.as_deref().unwrap_or("--------")],
+            ["password", "************"],
+            ["snapshot_token_ranges", self.snapshot_token_ranges],
+            ["snapshot_parallelism", self.snapshot_parallelism],
+            ["cdc", self.cdc],
+            ["poll_interval_ms", self.poll_interval_ms],
+            ["cdc_delay_ms", self.cdc_delay_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// The indices of an Elasticsearch or OpenSearch cluster, read with the scroll API and then polled for the documents
+/// changed since.
+pub struct ElasticsearchConfig {
+    #[prost(string, tag = "1")]
+    /// Url of the cluster, e.g. `http://localhost:9200`
+    pub url: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub user: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub password: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Encoded API key, used instead of the user and password; Default: None
+    pub api_key: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Date or numeric field of the documents set when they change, e.g. `updated_at`, whose values greater than or
+    /// equal to the greatest one read so far are polled; Default: None, polling the operations of each shard by
+    /// sequence number
+    pub cursor_field: Option<String>,
+    #[prost(uint32, tag = "6", default = "1000")]
+    #[serde(default = "default_elasticsearch_batch_size")]
+    /// Number of documents read per request; Default: 1000
+    pub batch_size: u32,
+    #[prost(uint64, tag = "7", default = "5000")]
+    #[serde(default = "default_elasticsearch_poll_interval_ms")]
+    /// How often changed documents are polled, which should be longer than the refresh interval of the indices;
+    /// Default: 5000
+    pub poll_interval_ms: u64,
+}
+
+impl ElasticsearchConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["url", self.url],
+            ["user", self.user.as_deref().unwrap_or("--------")],
+            ["password", "************"],
+            ["api_key", "************"],
+            [
+                "cursor_field",
+                self.cursor_field.as_deref().unwrap_or("--------")
+            ],
+            ["batch_size", self.batch_size],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Salesforce objects, read with the Bulk API 2.0 and then followed with their Change Data Capture events. Change Data
+/// Capture must be enabled for the objects.
+///
+/// Access tokens are requested from the connected app with the refresh token if there's one, or else with the user's
+/// password if there's a user, or else with the client credentials.
+pub struct SalesforceConfig {
+    #[prost(string, tag = "1", default = "https://login.salesforce.com")]
+    #[serde(default = "default_salesforce_login_url")]
+    /// Url tokens are requested from, e.g. `https://test.salesforce.com` for sandboxes, or the My Domain url for the
+    /// client credentials flow; Default: https://login.salesforce.com
+    pub login_url: String,
+    #[prost(string, tag = "2")]
+    /// Consumer key of the connected app
+    pub client_id: String,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Consumer secret of the connected app; Default: None
+    pub client_secret: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub refresh_token: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub username: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Password of the user, followed by their security token unless the login IP is trusted; Default: None
+    pub password: Option<String>,
+    #[prost(string, tag = "7", default = "58.0")]
+    #[serde(default = "default_salesforce_api_version")]
+    /// Default: 58.0
+    pub api_version: String,
+}
+
+impl SalesforceConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["login_url", self.login_url],
+            ["client_id", self.client_id],
+            ["client_secret", "************"],
+            ["refresh_token", "************"],
+            ["username", self.username.as_deref().unwrap_or("--------")],
+            ["password", "************"],
+            ["api_version", self.api_version]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Sheets of a Google spreadsheet, polled for changed rows. The first row of a sheet is its header.
+///
+/// Requests are authorized with the API key if there's one, which only reads spreadsheets shared with anyone with the
+/// link, or else as the service account of the key file at `credentials_path` or in `GOOGLE_APPLICATION_CREDENTIALS`.
+pub struct GoogleSheetsConfig {
+    #[prost(string, tag = "1")]
+    /// Id of the spreadsheet, in its url after `/spreadsheets/d/`
+    pub spreadsheet_id: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Key file of a service account the spreadsheet is shared with; Default: GOOGLE_APPLICATION_CREDENTIALS
+    pub credentials_path: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Default: None
+    pub api_key: Option<String>,
+    #[prost(uint64, tag = "4", default = "60000")]
+    #[serde(default = "default_google_sheets_poll_interval_ms")]
+    /// Default: 60000
+    pub poll_interval_ms: u64,
+}
+
+impl GoogleSheetsConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["spreadsheet_id", self.spreadsheet_id],
+            [
+                "credentials_path",
+                self.credentials_path.as_deref().unwrap_or("--------")
+            ],
+            ["api_key", "************"],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+/// Delta tables of a Databricks workspace, read through a SQL warehouse and then followed with their change data feed,
+/// which must be enabled for the tables.
+pub struct DatabricksConfig {
+    #[prost(string, tag = "1")]
+    /// Hostname of the workspace, e.g. `adb-1234567890123456.7.azuredatabricks.net`
+    pub host: String,
+    #[prost(string, tag = "2")]
+    /// Personal access token, or access token of a service principal
+    pub token: String,
+    #[prost(string, tag = "3")]
+    /// Id of the SQL warehouse, in its connection details after `/sql/1.0/warehouses/`
+    pub warehouse_id: String,
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Catalog of the tables; Default: the default catalog of the workspace
+    pub catalog: Option<String>,
+    #[prost(uint64, tag = "5", default = "10000")]
+    #[serde(default = "default_databricks_poll_interval_ms")]
+    /// Default: 10000
+    pub poll_interval_ms: u64,
+}
+
+impl DatabricksConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["host", self.host],
+            ["token", "************"],
+            ["warehouse_id", self.warehouse_id],
+            ["catalog", self.catalog.as_deref().unwrap_or("--------")],
+            ["poll_interval_ms", self.poll_interval_ms]
+        )
+    }
+}
+
+fn default_databricks_poll_interval_ms() -> u64 {
+    10000
+}
+
+fn default_google_sheets_poll_interval_ms() -> u64 {
+    60000
+}
+
+fn default_salesforce_login_url() -> String {
+    "https://login.salesforce.com".to_owned()
+}
+
+fn default_salesforce_api_version() -> String {
+    "58.0".to_owned()
+}
+
+fn default_webhook_port() -> u32 {
+    8090
+}
+
+fn default_iceberg_follow() -> bool {
+    true
+}
+
+fn default_iceberg_poll_interval_ms() -> u64 {
+    10000
+}
+
+fn default_kinesis_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_kinesis_shard_refresh_interval_ms() -> u64 {
+    60000
+}
+
+fn default_sql_server_port() -> u32 {
+    1433
+}
+
+fn default_sql_server_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_stripe_poll_interval_ms() -> u64 {
+    60000
+}
+
+fn default_stripe_api_url() -> String {
+    "https://api.stripe.com/v1".to_owned()
+}
+
+fn default_firestore_database_id() -> String {
+    "(default)".to_owned()
+}
+
+fn default_firestore_nested_fields() -> String {
+    "flatten".to_owned()
+}
+
+fn default_firestore_schema_sample_size() -> u32 {
+    100
+}
+
+fn default_oracle_port() -> u32 {
+    1521
+}
+
+fn default_oracle_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_generator_operations_per_sec() -> u64 {
+    100
+}
+
+fn default_generator_distribution() -> String {
+    "uniform".to_owned()
 }
 
 fn default_false() -> bool {
     false
 }
 
+fn default_true() -> bool {
+    true
+}
+
 fn default_marker() -> String {
     String::from(".marker")
 }
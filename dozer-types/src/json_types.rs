@@ -1,5 +1,5 @@
 use crate::errors::types::{CannotConvertF64ToJson, DeserializationError};
-use crate::types::{DozerDuration, Field, DATE_FORMAT};
+use crate::types::{DozerDuration, DozerMoney, Field, FieldDefinition, Timezone, DATE_FORMAT};
 use chrono::SecondsFormat;
 use ordered_float::OrderedFloat;
 use prost_types::value::Kind;
@@ -261,8 +261,18 @@ fn convert_duration_to_object(d: &DozerDuration) -> Value {
     Value::Object(m)
 }
 
-/// Should be consistent with `convert_cache_type_to_schema_type`.
-pub fn field_to_json_value(field: Field) -> Result<Value, CannotConvertF64ToJson> {
+fn convert_money_to_object(money: &DozerMoney) -> Value {
+    let mut m = Map::new();
+    m.insert("amount".to_string(), Value::from(money.amount.to_string()));
+    m.insert("currency".to_string(), Value::from(money.currency.code()));
+    Value::Object(m)
+}
+
+/// Should be consistent with `convert_cache_type_to_schema_type`. Timestamps are rendered in `timezone`.
+pub fn field_to_json_value(
+    field: Field,
+    timezone: &Timezone,
+) -> Result<Value, CannotConvertF64ToJson> {
     match field {
         Field::UInt(n) => Ok(Value::from(n)),
         Field::U128(n) => Ok(Value::String(n.to_string())),
@@ -275,16 +285,34 @@ pub fn field_to_json_value(field: Field) -> Result<Value, CannotConvertF64ToJson
         Field::Binary(b) => Ok(Value::from(b)),
         Field::Decimal(n) => Ok(Value::String(n.to_string())),
         Field::Timestamp(ts) => Ok(Value::String(
-            ts.to_rfc3339_opts(SecondsFormat::Millis, true),
+            timezone
+                .convert(&ts)
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
         )),
         Field::Date(n) => Ok(Value::String(n.format(DATE_FORMAT).to_string())),
         Field::Json(b) => json_value_to_serde_json(b),
         Field::Point(point) => Ok(convert_x_y_to_object(&point.0.x_y())),
         Field::Duration(d) => Ok(convert_duration_to_object(&d)),
+        Field::Money(m) => Ok(convert_money_to_object(&m)),
+        Field::Enum(e) => Ok(Value::from(e)),
         Field::Null => Ok(Value::Null),
     }
 }
 
+/// Like `field_to_json_value`, but an enum is its declared value in `definition` instead of its ordinal.
+pub fn defined_field_to_json_value(
+    field: Field,
+    definition: &FieldDefinition,
+    timezone: &Timezone,
+) -> Result<Value, CannotConvertF64ToJson> {
+    match field {
+        Field::Enum(ordinal) => Ok(definition
+            .enum_value(ordinal)
+            .map_or_else(|| Value::from(ordinal), Value::from)),
+        field => field_to_json_value(field, timezone),
+    }
+}
+
 pub fn json_value_to_serde_json(value: JsonValue) -> Result<Value, CannotConvertF64ToJson> {
     match value {
         JsonValue::Null => Ok(Value::Null),
@@ -391,7 +419,7 @@ mod tests {
         json_value_to_field,
         ordered_float::OrderedFloat,
         rust_decimal::Decimal,
-        types::{DozerPoint, Field, FieldType, TimeUnit},
+        types::{DozerMoney, DozerPoint, Field, FieldType, SourceDefinition, TimeUnit},
     };
 
     use std::time::Duration;
@@ -400,7 +428,7 @@ mod tests {
 
     fn test_field_conversion(field_type: FieldType, field: Field) {
         // Convert the field to a JSON value.
-        let value = field_to_json_value(field.clone());
+        let value = field_to_json_value(field.clone(), &Timezone::Utc);
 
         // Convert the JSON value back to a Field.
         let deserialized = json_value_to_field(value.unwrap(), field_type, true).unwrap();
@@ -455,9 +483,51 @@ mod tests {
                     TimeUnit::Nanoseconds,
                 )),
             ),
+            (
+                FieldType::Money,
+                Field::Money("12.34 USD".parse::<DozerMoney>().unwrap()),
+            ),
+            (FieldType::Enum, Field::Enum(3)),
         ];
         for (field_type, field) in fields {
             test_field_conversion(field_type, field);
         }
     }
+
+    #[test]
+    fn test_timestamp_to_json_value_in_timezone() {
+        let timestamp = Field::Timestamp(Utc.fix().with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap());
+        let paris = "Europe/Paris".parse::<Timezone>().unwrap();
+        assert_eq!(
+            field_to_json_value(timestamp.clone(), &Timezone::Utc).unwrap(),
+            Value::from("2023-07-01T12:00:00.000Z")
+        );
+        assert_eq!(
+            field_to_json_value(timestamp, &paris).unwrap(),
+            Value::from("2023-07-01T14:00:00.000+02:00")
+        );
+    }
+
+    #[test]
+    fn test_defined_field_to_json_value() {
+        let definition = FieldDefinition::new(
+            "status".to_string(),
+            FieldType::Enum,
+            false,
+            SourceDefinition::Dynamic,
+        )
+        .with_enum_values(vec!["active".to_string(), "closed".to_string()]);
+        assert_eq!(
+            defined_field_to_json_value(Field::Enum(1), &definition, &Timezone::Utc).unwrap(),
+            Value::from("closed")
+        );
+        assert_eq!(
+            defined_field_to_json_value(Field::Enum(2), &definition, &Timezone::Utc).unwrap(),
+            Value::from(2)
+        );
+        assert_eq!(
+            defined_field_to_json_value(Field::UInt(1), &definition, &Timezone::Utc).unwrap(),
+            Value::from(1)
+        );
+    }
 }
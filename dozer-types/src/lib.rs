@@ -3,6 +3,7 @@ pub mod constants;
 pub mod errors;
 pub mod field_type;
 pub mod helper;
+pub mod identifier;
 pub mod ingestion_types;
 pub mod json_types;
 pub mod labels;
@@ -16,6 +16,21 @@ pub struct ApiConfig {
     #[prost(message, tag = "4")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app_grpc: Option<AppGrpcOptions>,
+
+    #[prost(message, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Serves the endpoints of other dozer apps behind the REST and gRPC servers of `dozer run gateway`; Default: None
+    pub gateway: Option<GatewayOptions>,
+
+    #[prost(message, tag = "6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Keeps periodic snapshots of the endpoint metrics in the cache directory, served by the `getMetricsHistory` gRPC method; Default: None
+    pub metrics_history: Option<MetricsHistoryOptions>,
+
+    #[prost(message, tag = "7")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Rules evaluated periodically against the endpoint metrics, notifying channels when they start or stop firing; Default: None
+    pub alerts: Option<AlertsOptions>,
 }
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
 pub struct RestApiOptions {
@@ -61,6 +76,184 @@ pub struct AppGrpcOptions {
     pub host: String,
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct GatewayOptions {
+    #[prost(message, repeated, tag = "1")]
+    /// The apps whose endpoints are served. Apps serving the same endpoint share its requests
+    pub apps: Vec<GatewayApp>,
+
+    #[prost(uint64, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How often the endpoints and health of the apps are refreshed; Default: 5000
+    pub refresh_interval_in_millis: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct GatewayApp {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Url of the REST server of the app, e.g. http://orders:8080
+    pub rest_url: Option<String>,
+
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Url of the gRPC server of the app, e.g. http://orders:50051
+    pub grpc_url: Option<String>,
+
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Token the gateway discovers the endpoints of the app with, if the app has API security. Requests are forwarded with their own token
+    pub token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct MetricsHistoryOptions {
+    #[prost(uint64, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How often the metrics are snapshotted; Default: 60000
+    pub interval_in_millis: Option<u64>,
+
+    #[prost(uint64, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How long snapshots are kept; Default: 24
+    pub retention_in_hours: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct AlertsOptions {
+    #[prost(message, repeated, tag = "1")]
+    /// The conditions alerted on
+    pub rules: Vec<AlertRule>,
+
+    #[prost(message, repeated, tag = "2")]
+    /// Where alerts are sent
+    pub channels: Vec<AlertChannel>,
+
+    #[prost(message, repeated, tag = "3")]
+    #[serde(default)]
+    /// Time windows in which alerts aren't sent
+    pub silences: Vec<AlertSilence>,
+
+    #[prost(uint64, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How often the rules are evaluated; Default: 30000
+    pub evaluation_interval_in_millis: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct AlertRule {
+    #[prost(string, tag = "1")]
+    /// The name of the rule, included in its alerts
+    pub name: String,
+
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The endpoint the rule applies to; Default: all endpoints
+    pub endpoint: Option<String>,
+
+    #[prost(oneof = "AlertCondition", tags = "3,4,5")]
+    /// When the rule fires
+    pub condition: Option<AlertCondition>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Oneof)]
+pub enum AlertCondition {
+    /// The end-to-end latency of the last changes committed to the endpoint cache is over this many milliseconds
+    #[prost(uint64, tag = "3")]
+    LagInMillis(u64),
+    /// More than this percentage of the REST and typed gRPC requests of the endpoint failed since the last evaluation
+    #[prost(uint32, tag = "4")]
+    ErrorPercentage(u32),
+    /// No changes were committed to the endpoint cache for more than this many seconds
+    #[prost(uint64, tag = "5")]
+    StaleInSeconds(u64),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct AlertChannel {
+    #[prost(oneof = "AlertChannelType", tags = "1,2,3")]
+    pub channel: Option<AlertChannelType>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Oneof)]
+pub enum AlertChannelType {
+    /// Url alerts are posted to as json
+    #[prost(string, tag = "1")]
+    Webhook(String),
+    /// Incoming webhook url of a Slack channel
+    #[prost(string, tag = "2")]
+    Slack(String),
+    /// Mail server and recipients alerts are emailed to
+    #[prost(message, tag = "3")]
+    Email(EmailAlertChannel),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct EmailAlertChannel {
+    #[prost(string, tag = "1")]
+    /// Host of the SMTP server, connected to with STARTTLS
+    pub smtp_host: String,
+
+    #[prost(uint32, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Port of the SMTP server; Default: 587
+    pub smtp_port: Option<u32>,
+
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// User name to authenticate with; Default: no authentication
+    pub username: Option<String>,
+
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Password to authenticate with
+    pub password: Option<String>,
+
+    #[prost(string, tag = "5")]
+    /// Sender address, e.g. Dozer <alerts@example.com>
+    pub from: String,
+
+    #[prost(string, repeated, tag = "6")]
+    /// Recipient addresses
+    pub to: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct AlertSilence {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The rule silenced; Default: all rules
+    pub rule: Option<String>,
+
+    #[prost(string, tag = "2")]
+    /// Start of the window, as an RFC 3339 timestamp, e.g. 2023-06-01T22:00:00Z
+    pub starts_at: String,
+
+    #[prost(string, tag = "3")]
+    /// End of the window, as an RFC 3339 timestamp
+    pub ends_at: String,
+}
+
+pub fn default_alerts_evaluation_interval_in_millis() -> u64 {
+    30000
+}
+
+pub fn default_alerts_smtp_port() -> u32 {
+    587
+}
+
+pub fn default_metrics_history_interval_in_millis() -> u64 {
+    60000
+}
+
+pub fn default_metrics_history_retention_in_hours() -> u64 {
+    24
+}
+
+pub fn default_gateway_refresh_interval_in_millis() -> u64 {
+    5000
+}
+
 fn default_app_grpc_port() -> u32 {
     50053
 }
@@ -22,7 +22,7 @@ pub struct SecondaryIndexConfig {
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
 pub struct CreateSecondaryIndex {
-    #[prost(oneof = "SecondaryIndex", tags = "1,2")]
+    #[prost(oneof = "SecondaryIndex", tags = "1,2,3")]
     pub index: Option<SecondaryIndex>,
 }
 
@@ -32,6 +32,8 @@ pub enum SecondaryIndex {
     SortedInverted(SortedInverted),
     #[prost(message, tag = "2")]
     FullText(FullText),
+    #[prost(message, tag = "3")]
+    Expression(ExpressionIndex),
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
@@ -46,6 +48,13 @@ pub struct FullText {
     pub field: String,
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ExpressionIndex {
+    /// A function of a field, e.g. `lower(email)`. Functions are `lower` and `upper` of strings and `date` of timestamps
+    #[prost(string, tag = "1")]
+    pub expression: String,
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, ::prost::Oneof)]
 pub enum OnInsertResolutionTypes {
     #[prost(message, tag = "1")]
@@ -144,6 +153,212 @@ pub struct ApiEndpoint {
     #[prost(optional, message)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_reader_options: Option<LogReaderOptions>,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Queries taking longer than this are logged as warnings with their query plan; Default: 1000
+    pub slow_query_threshold_in_millis: Option<u64>,
+
+    #[prost(optional, message)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Partitions the endpoint cache per tenant
+    pub tenancy: Option<TenancyOptions>,
+
+    #[prost(optional, message)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Keeps recent changes queryable with `GET <path>/changes`, so briefly disconnected clients can catch up. Not supported with tenancy
+    pub change_retention: Option<ChangeRetentionOptions>,
+
+    #[prost(message, repeated)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Aggregates of the endpoint's records maintained incrementally, each served by a companion endpoint
+    pub rollups: Vec<Rollup>,
+
+    #[prost(optional, message)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Counts reads by primary key, to report hot keys in stats and keep their records in memory. Not supported with tenancy
+    pub hot_keys: Option<HotKeyOptions>,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Bounds the records of hot keys kept in memory to this many bytes. Without `hot_keys`, only this bounds them. Not supported with tenancy
+    pub read_cache_size_in_bytes: Option<u64>,
+
+    #[prost(optional, message)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Tunes how reads and cache writes of the endpoint are scheduled, for high-concurrency deployments
+    pub concurrency: Option<ConcurrencyOptions>,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Queries and counts running longer than this are aborted with a timeout error. Requests can ask for a shorter timeout with the `x-dozer-query-timeout-ms` header; Default: no timeout
+    pub query_timeout_in_millis: Option<u64>,
+
+    #[prost(optional, uint32)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Hash partitions the endpoint cache by primary key across this many LMDB environments, spread over `cache_partition_dirs`. Queries run on every partition and their results are merged. Only applies to newly created caches and is ignored with tenancy; Default: 1
+    pub partitions: Option<u32>,
+
+    #[prost(optional, message)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Customizes the REST paths, gRPC names and field names the endpoint is served with
+    pub naming: Option<EndpointNaming>,
+
+    #[prost(message, repeated)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Fields marked deprecated in the generated OpenAPI and protos. Responses to queries referring to them count the references in the `x-dozer-deprecated-fields` header
+    pub deprecated_fields: Vec<DeprecatedField>,
+
+    #[prost(optional, bool)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Records the source row of the last operation that changed each record, served with `GET <path>/{id}/provenance`. Kept in memory by the app, and rebuilt from the source when it restarts; Default: false
+    pub provenance: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct DeprecatedField {
+    #[prost(string)]
+    /// name of the deprecated field; Type: String
+    pub field: String,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Tells clients what to use instead, e.g. "Use released_at"
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct EndpointNaming {
+    #[prost(string, repeated)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// More REST paths the endpoint is served at, e.g. /v1/films
+    pub rest_aliases: Vec<String>,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Name of the typed gRPC service, which also prefixes its request and response messages; Default: plural PascalCase of the endpoint name
+    pub grpc_service: Option<String>,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Name of the typed gRPC record message; Default: singular PascalCase of the endpoint name
+    pub grpc_message: Option<String>,
+
+    #[prost(optional, bool)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Serves field names in camelCase, e.g. firstName for first_name, in REST and gRPC responses and queries; Default: false
+    pub camel_case_fields: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ConcurrencyOptions {
+    #[prost(optional, uint32)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Runs the queries and counts of the endpoint on at most this many dedicated threads, so slow queries don't hold the workers of the API servers; Default: run on the workers
+    pub read_threads: Option<u32>,
+
+    #[prost(optional, uint32)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of log commits applied to the cache in one write transaction while more operations are queued. Larger batches make fewer, larger commits when catching up; Default: 1
+    pub max_commits_per_txn: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct HotKeyOptions {
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of keys whose reads are counted. When full, the least recently read key is replaced; Default: 1000
+    pub max_tracked_keys: Option<u64>,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of records of the most recently read keys kept in memory, 0 to disable; Default: 100
+    pub read_cache_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct Rollup {
+    #[prost(string)]
+    /// name of the companion endpoint and its table - e.g: orders_by_day; Type: String
+    pub name: String,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// path of the companion endpoint; Default: /<name>
+    pub path: Option<String>,
+
+    #[prost(string, repeated)]
+    /// The columns to group by, which become the primary key of the rollup
+    pub group_by: Vec<String>,
+
+    #[prost(message, repeated)]
+    pub aggregates: Vec<RollupAggregate>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct RollupAggregate {
+    #[prost(string)]
+    /// One of count, sum, min, max and avg; Type: String
+    pub function: String,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The aggregated column. Without it, `count` counts records
+    pub column: Option<String>,
+
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Name of the output column; Default: <function>_<column>, or count if counting records
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ChangeRetentionOptions {
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of changes kept; Default: 10000
+    pub max_changes: Option<u64>,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Changes older than this are dropped; Default: 3600
+    pub max_age_in_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TenancyOptions {
+    #[prost(string)]
+    /// The column identifying the tenant of a record. Every tenant's records are stored in a separate cache, and queried with tokens carrying a `tenant` claim; Type: String
+    pub column: String,
+
+    #[prost(optional, uint64)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Inserts into a tenant cache holding this many records are dropped
+    pub max_records_per_tenant: Option<u64>,
+}
+
+pub fn default_slow_query_threshold_in_millis() -> u64 {
+    1000
+}
+
+pub fn default_change_retention_max_changes() -> u64 {
+    10000
+}
+
+pub fn default_change_retention_max_age_in_secs() -> u64 {
+    3600
+}
+
+pub fn default_hot_keys_max_tracked_keys() -> u64 {
+    1000
+}
+
+pub fn default_hot_keys_read_cache_size() -> u64 {
+    100
+}
+
+pub fn default_max_commits_per_txn() -> u32 {
+    1
 }
 
 pub fn default_log_reader_batch_size() -> u32 {
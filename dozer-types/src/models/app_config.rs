@@ -35,6 +35,33 @@ pub struct AppConfig {
     /// How many errors we can tolerate before bringing down the app.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_threshold: Option<u32>,
+
+    /// How to react to schema changes of source columns used by the pipeline.
+    #[prost(message, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_drift: Option<SchemaDriftConfig>,
+
+    /// Also write the log of each endpoint to a compacted Kafka topic, which API instances read it from.
+    #[prost(message, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_kafka: Option<KafkaLogConfig>,
+
+    /// Run the pipeline at scheduled times with `dozer run scheduled`, for apps whose sources end after a snapshot.
+    #[prost(message, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<RunScheduleConfig>,
+
+    /// Time zone `NOW()`, `CURRENT_DATE` and `DATE_TRUNC` are evaluated in and API timestamps are rendered in: `UTC`,
+    /// an offset like `+08:00` or an IANA name like `Europe/Paris`. Default: UTC
+    #[prost(string, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Match unquoted identifiers of the SQL, and table names in the config, to the names of sources, columns, aliases
+    /// and endpoints case sensitively. Quoted identifiers always match their exact spelling. Default: false
+    #[prost(bool, optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case_sensitive_identifiers: Option<bool>,
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, prost::Oneof)]
@@ -53,12 +80,83 @@ pub struct S3Storage {
     pub bucket_name: String,
 }
 
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, prost::Message)]
+pub struct KafkaLogConfig {
+    #[prost(string, tag = "1")]
+    pub broker: String,
+
+    /// The log of a build of an endpoint is written to topic `{topic_prefix}{endpoint}.{build}`.
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_prefix: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, prost::Message)]
+pub struct SchemaDriftConfig {
+    #[prost(oneof = "OnColumnDropped", tags = "1,2,3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_column_dropped: Option<OnColumnDropped>,
+
+    #[prost(oneof = "OnTypeChanged", tags = "4,5,6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_type_changed: Option<OnTypeChanged>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, prost::Oneof)]
+pub enum OnColumnDropped {
+    #[prost(message, tag = "1")]
+    Ignore(()),
+    #[prost(message, tag = "2")]
+    Warn(()),
+    #[prost(message, tag = "3")]
+    Fail(()),
+}
+
+impl Default for OnColumnDropped {
+    fn default() -> Self {
+        OnColumnDropped::Fail(())
+    }
+}
+
+/// Only applies to type changes that change the mapped Dozer type. Compatible type changes are always reported as info.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, prost::Oneof)]
+pub enum OnTypeChanged {
+    #[prost(message, tag = "4")]
+    Ignore(()),
+    #[prost(message, tag = "5")]
+    Warn(()),
+    #[prost(message, tag = "6")]
+    Fail(()),
+}
+
+impl Default for OnTypeChanged {
+    fn default() -> Self {
+        OnTypeChanged::Fail(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, prost::Message)]
+pub struct RunScheduleConfig {
+    /// cron expression with a seconds field, e.g. `0 0 2 * * *` for every day at 2am UTC
+    #[prost(string, tag = "1")]
+    pub cron: String,
+
+    /// Number of runs kept in the run history; Default: 100
+    #[prost(uint32, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_size: Option<u32>,
+}
+
 impl Default for LogStorage {
     fn default() -> Self {
         Self::Local(())
     }
 }
 
+pub fn default_log_kafka_topic_prefix() -> String {
+    "dozer-log-".to_string()
+}
+
 pub fn default_log_entry_max_size() -> u64 {
     100_000
 }
@@ -82,3 +180,7 @@ pub fn default_commit_timeout() -> u64 {
 pub fn default_error_threshold() -> u32 {
     0
 }
+
+pub fn default_run_history_size() -> u32 {
+    100
+}
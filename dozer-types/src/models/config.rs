@@ -2,9 +2,12 @@ use std::path::Path;
 
 use super::{
     api_config::ApiConfig, api_endpoint::ApiEndpoint, app_config::AppConfig, cloud::Cloud,
-    connection::Connection, flags::Flags, source::Source, telemetry::TelemetryConfig,
+    connection::Connection, crash_report::CrashReportConfig, flags::Flags, source::Source,
+    telemetry::TelemetryConfig,
 };
 use crate::constants::DEFAULT_HOME_DIR;
+use crate::models::operator_config::OperatorConfig;
+use crate::models::router_config::RouterConfig;
 use crate::models::udf_config::UdfConfig;
 use prettytable::Table as PrettyTable;
 use serde::{Deserialize, Serialize};
@@ -81,6 +84,41 @@ pub struct Config {
     /// UDF specific configuration (eg. !Onnx)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub udfs: Vec<UdfConfig>,
+
+    #[prost(message, repeated, tag = "16")]
+    /// custom Rust operators, applied after the SQL transformations and routers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operators: Vec<OperatorConfig>,
+
+    #[prost(message, repeated, tag = "17")]
+    /// routers splitting a table into several, applied after the SQL transformations and before operators
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routers: Vec<RouterConfig>,
+
+    /// Cache lmdb max readers, the maximum number of concurrent read transactions per cache
+    #[prost(uint32, optional, tag = "18")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_max_readers: Option<u32>,
+
+    /// Directories, e.g. on separate disks, the partitions of partitioned endpoint caches are spread over. Default: cache_dir
+    #[prost(string, repeated, tag = "19")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cache_partition_dirs: Vec<String>,
+
+    #[prost(message, optional, tag = "20")]
+    /// opt-in crash reports, written when the app panics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crash_report: Option<CrashReportConfig>,
+
+    /// How long, in seconds, the index statistics a cache query plan is chosen by are reused before they're collected again. Default: 10
+    #[prost(uint64, optional, tag = "21")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_index_stats_ttl_secs: Option<u64>,
+
+    /// Cache queries scanning all records that can return more records than this decode them on multiple threads. Default: disabled
+    #[prost(uint64, optional, tag = "22")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_parallel_scan_threshold: Option<u64>,
 }
 
 pub fn default_home_dir() -> String {
@@ -102,6 +140,14 @@ pub fn default_cache_max_map_size() -> u64 {
     1024 * 1024 * 1024
 }
 
+pub fn default_cache_max_readers() -> u32 {
+    1000
+}
+
+pub fn default_cache_index_stats_ttl_secs() -> u64 {
+    10
+}
+
 impl Config {
     pub fn convert_to_table(&self) -> PrettyTable {
         let mut table = table!();
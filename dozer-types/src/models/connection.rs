@@ -1,5 +1,9 @@
 use crate::ingestion_types::{
-    DeltaLakeConfig, EthConfig, GrpcConfig, KafkaConfig, LocalStorage, S3Storage, SnowflakeConfig,
+    CassandraConfig, DatabricksConfig, DeltaLakeConfig, DynamoDbConfig, ElasticsearchConfig,
+    EthConfig, FirestoreConfig, GeneratorConfig, GoogleSheetsConfig, GrpcConfig, IcebergConfig,
+    KafkaConfig, KinesisConfig, LocalStorage, MqttConfig, MySQLConfig, NatsConfig, OracleConfig,
+    RedisStreamsConfig, S3Storage, SalesforceConfig, SnowflakeConfig, SqlServerConfig,
+    StripeConfig, WebhookConfig,
 };
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -15,7 +19,10 @@ use tokio_postgres::Config;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
 pub struct Connection {
-    #[prost(oneof = "ConnectionConfig", tags = "1,2,3,4,5,6,7,8")]
+    #[prost(
+        oneof = "ConnectionConfig",
+        tags = "1,2,3,4,5,6,7,8,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27"
+    )]
     /// authentication config - depends on db_type
     pub config: Option<ConnectionConfig>,
     #[prost(string, tag = "9")]
@@ -38,6 +45,51 @@ pub struct PostgresConfig {
     pub sslmode: Option<String>,
     #[prost(string, optional, tag = "7")]
     pub connection_url: Option<String>,
+    /// Existing publication to replicate, for managed services where the user can't create publications.
+    /// If not set, a publication of the source tables is created.
+    #[prost(string, optional, tag = "8")]
+    pub publication: Option<String>,
+    /// Track changes with triggers writing to a change table instead of logical replication, for databases where
+    /// replication isn't available to the user. Default: false
+    #[prost(bool, optional, tag = "9")]
+    pub trigger_based: Option<bool>,
+    /// How often the change table is polled when tracking changes with triggers. Default: 1000
+    #[prost(uint64, optional, tag = "10")]
+    pub poll_interval_ms: Option<u64>,
+    /// What to do when columns of a replicated table are added, dropped or change type during logical replication.
+    /// Default: IgnoreNewColumns
+    #[prost(oneof = "OnSchemaChange", tags = "11,12,13")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_schema_change: Option<OnSchemaChange>,
+    /// Number of connections reading each table in the initial snapshot, each reading chunks of the primary key range.
+    /// Tables without a primary key are read by one connection. Default: 1
+    #[prost(uint32, optional, tag = "14")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_parallelism: Option<u32>,
+    /// Number of rows in a primary key chunk of a parallel snapshot. Default: 100000
+    #[prost(uint64, optional, tag = "15")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_chunk_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, ::prost::Oneof, Hash)]
+pub enum OnSchemaChange {
+    /// Stop replicating with an error.
+    #[prost(message, tag = "11")]
+    Fail(()),
+    /// Keep the schema the pipeline started with: new columns aren't read and dropped columns are read as null.
+    #[prost(message, tag = "12")]
+    IgnoreNewColumns(()),
+    /// Stop replicating, rebuild the endpoints with the new schema and restart the pipeline, which snapshots the
+    /// tables again.
+    #[prost(message, tag = "13")]
+    Evolve(()),
+}
+
+impl Default for OnSchemaChange {
+    fn default() -> Self {
+        OnSchemaChange::IgnoreNewColumns(())
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
@@ -190,4 +242,58 @@ pub enum ConnectionConfig {
     #[prost(message, tag = "8")]
     /// In yaml, present as tag" `!DeltaLake`
     DeltaLake(DeltaLakeConfig),
+    #[prost(message, tag = "10")]
+    /// In yaml, present as tag: `!Generator`
+    Generator(GeneratorConfig),
+    #[prost(message, tag = "11")]
+    /// In yaml, present as tag: `!Oracle`
+    Oracle(OracleConfig),
+    #[prost(message, tag = "12")]
+    /// In yaml, present as tag: `!Firestore`
+    Firestore(FirestoreConfig),
+    #[prost(message, tag = "13")]
+    /// In yaml, present as tag: `!Stripe`
+    Stripe(StripeConfig),
+    #[prost(message, tag = "14")]
+    /// In yaml, present as tag: `!MySQL`
+    MySQL(MySQLConfig),
+    #[prost(message, tag = "15")]
+    /// In yaml, present as tag: `!SqlServer`
+    SqlServer(SqlServerConfig),
+    #[prost(message, tag = "16")]
+    /// In yaml, present as tag: `!Kinesis`
+    Kinesis(KinesisConfig),
+    #[prost(message, tag = "17")]
+    /// In yaml, present as tag: `!Iceberg`
+    Iceberg(IcebergConfig),
+    #[prost(message, tag = "18")]
+    /// In yaml, present as tag: `!Webhook`
+    Webhook(WebhookConfig),
+    #[prost(message, tag = "19")]
+    /// In yaml, present as tag: `!RedisStreams`
+    RedisStreams(RedisStreamsConfig),
+    #[prost(message, tag = "20")]
+    /// In yaml, present as tag: `!Nats`
+    Nats(NatsConfig),
+    #[prost(message, tag = "21")]
+    /// In yaml, present as tag: `!Mqtt`
+    Mqtt(MqttConfig),
+    #[prost(message, tag = "22")]
+    /// In yaml, present as tag: `!DynamoDb`
+    DynamoDb(DynamoDbConfig),
+    #[prost(message, tag = "23")]
+    /// In yaml, present as tag: `!Cassandra`
+    Cassandra(CassandraConfig),
+    #[prost(message, tag = "24")]
+    /// In yaml, present as tag: `!Elasticsearch`
+    Elasticsearch(ElasticsearchConfig),
+    #[prost(message, tag = "25")]
+    /// In yaml, present as tag: `!Salesforce`
+    Salesforce(SalesforceConfig),
+    #[prost(message, tag = "26")]
+    /// In yaml, present as tag: `!GoogleSheets`
+    GoogleSheets(GoogleSheetsConfig),
+    #[prost(message, tag = "27")]
+    /// In yaml, present as tag: `!Databricks`
+    Databricks(DatabricksConfig),
 }
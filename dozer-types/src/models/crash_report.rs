@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+/// Writes a report of every panic, with its backtrace, the recent logs and the app's config with its secrets
+/// redacted
+pub struct CrashReportConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// directory the reports are written to; Default: <home_dir>/crash_reports
+    pub dir: Option<String>,
+
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// url the reports are also posted to, as JSON
+    pub endpoint: Option<String>,
+
+    #[prost(uint32, tag = "3")]
+    #[serde(default = "default_max_log_lines")]
+    /// number of the most recent log lines included in a report; Default: 200
+    pub max_log_lines: u32,
+}
+
+pub fn default_max_log_lines() -> u32 {
+    200
+}
@@ -5,7 +5,10 @@ pub mod app_config;
 pub mod cloud;
 pub mod config;
 pub mod connection;
+pub mod crash_report;
 pub mod flags;
+pub mod operator_config;
+pub mod router_config;
 pub mod source;
 pub mod telemetry;
 pub mod udf_config;
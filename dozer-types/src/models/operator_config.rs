@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use crate::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+/// A custom Rust operator, registered by name in an `OperatorRegistry` of `dozer-core`
+pub struct OperatorConfig {
+    #[prost(string, tag = "1")]
+    /// name of the table the operator outputs, usable by endpoints and later operators
+    pub name: String,
+
+    #[prost(string, tag = "2")]
+    /// name the operator was registered under
+    pub operator: String,
+
+    #[prost(string, tag = "3")]
+    /// name of the source, SQL output, route or operator table the operator reads
+    pub table_name: String,
+
+    #[prost(btree_map = "string, string", tag = "4")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    /// options passed to the operator when it's built
+    pub options: BTreeMap<String, String>,
+}
@@ -0,0 +1,33 @@
+use crate::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+/// Sends the records of a table to the routes whose condition they satisfy, each route being a table of its own
+pub struct RouterConfig {
+    #[prost(string, tag = "1")]
+    /// name of the router
+    pub name: String,
+
+    #[prost(string, tag = "2")]
+    /// name of the source or SQL output the router reads
+    pub table_name: String,
+
+    #[prost(message, repeated, tag = "3")]
+    /// routes a record can be sent to; a record satisfying several conditions is sent to all of them
+    pub routes: Vec<RouteConfig>,
+
+    #[prost(string, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// name of the table of the records satisfying no route's condition
+    pub default_table: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct RouteConfig {
+    #[prost(string, tag = "1")]
+    /// name of the table of the route's records, usable by endpoints and operators
+    pub name: String,
+
+    #[prost(string, tag = "2")]
+    /// SQL condition on the columns of the router's table, e.g. `region = 'eu' AND amount > 0`
+    pub condition: String,
+}
@@ -19,11 +19,19 @@ pub struct Source {
     #[prost(string, optional, tag = "5")]
     #[serde(default)]
     pub schema: Option<String>,
-    #[prost(oneof = "RefreshConfig", tags = "7")]
+    #[prost(oneof = "RefreshConfig", tags = "7, 8")]
     #[serde(default = "default_refresh_config")]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// setting for how to refresh the data; Default: RealTime
     pub refresh_config: Option<RefreshConfig>,
+    #[prost(message, optional, tag = "9")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// column with the time of events by the source's clock, checked for clock skew; Default: None
+    pub event_time: Option<EventTimeConfig>,
+    #[prost(message, optional, tag = "10")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// limit on the size of the records of the source, and what to do with larger ones; Default: None
+    pub max_record_size: Option<MaxRecordSizeConfig>,
 }
 
 fn default_refresh_config() -> Option<RefreshConfig> {
@@ -64,6 +72,8 @@ pub enum RefreshConfig {
     // CronExpression { expression: String },
     #[prost(message, tag = "7")]
     RealTime(RealTimeConfig),
+    #[prost(message, tag = "8")]
+    Schedule(ScheduleConfig),
 }
 impl Default for RefreshConfig {
     fn default() -> Self {
@@ -72,3 +82,77 @@ impl Default for RefreshConfig {
 }
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
 pub struct RealTimeConfig {}
+
+/// Compares the event times of a source with the time they're ingested at, to detect a source clock ahead of or behind
+/// Dozer's.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct EventTimeConfig {
+    #[prost(string, tag = "1")]
+    /// timestamp column with the time events happened at; Type: String
+    pub column: String,
+    #[prost(uint64, tag = "2", default = "60000")]
+    #[serde(default = "default_max_clock_skew_ms")]
+    /// skew above which the source clock is reported as skewed, in milliseconds; Default: 60000
+    pub max_skew_ms: u64,
+    #[prost(bool, tag = "3")]
+    #[serde(default)]
+    /// whether to shift event times by the skew while the source clock is skewed; Default: false
+    pub normalize: bool,
+}
+
+fn default_max_clock_skew_ms() -> u64 {
+    60000
+}
+
+/// Limits the size of the records of a source, so that a single huge row doesn't hold up the channels of the pipeline
+/// and the cache.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct MaxRecordSizeConfig {
+    #[prost(uint64, tag = "1")]
+    /// maximum size of a record, in bytes of its encoded fields; Type: u64
+    pub max_bytes: u64,
+    #[prost(oneof = "OversizedRecordPolicy", tags = "2, 3, 4")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// what to do with records larger than `max_bytes`; Default: Fail
+    pub on_oversized: Option<OversizedRecordPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum OversizedRecordPolicy {
+    #[prost(message, tag = "2")]
+    Truncate(TruncateConfig),
+    #[prost(message, tag = "3")]
+    DeadLetter(DeadLetterConfig),
+    #[prost(message, tag = "4")]
+    Fail(()),
+}
+
+impl Default for OversizedRecordPolicy {
+    fn default() -> Self {
+        OversizedRecordPolicy::Fail(())
+    }
+}
+
+/// Truncates the values of string, text or binary columns until the record fits. Records that still don't fit fail.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TruncateConfig {
+    #[prost(string, repeated, tag = "1")]
+    /// columns that can be truncated, truncated in this order; Type: String[]
+    pub columns: Vec<String>,
+}
+
+/// Leaves oversized records out of the pipeline, and appends the operations they're part of to a file as json lines.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct DeadLetterConfig {
+    #[prost(string, tag = "1")]
+    /// file the operations are appended to; Type: String
+    pub path: String,
+}
+
+/// Re-reads the whole table on a schedule instead of following its changes, emitting the difference from the last read.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ScheduleConfig {
+    #[prost(string, tag = "1")]
+    /// cron expression with a seconds field, e.g. `0 */15 * * * *` for every 15 minutes; Type: String
+    pub cron: String,
+}
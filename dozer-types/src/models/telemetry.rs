@@ -1,10 +1,53 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
 pub struct TelemetryConfig {
-    #[prost(oneof = "TelemetryTraceConfig", tags = "1, 2")]
+    #[prost(oneof = "TelemetryTraceConfig", tags = "1, 2, 5")]
     pub trace: Option<TelemetryTraceConfig>,
     #[prost(oneof = "TelemetryMetricsConfig", tags = "3")]
     pub metrics: Option<TelemetryMetricsConfig>,
+    #[prost(message, optional, tag = "4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// attributes of the app attached to every exported span, to tell apps apart in the tracing backend
+    pub resource: Option<TelemetryResourceConfig>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
+pub struct TelemetryResourceConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// the deployment environment, e.g. `production`; exported as `deployment.environment`
+    pub environment: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// the region the app runs in; exported as `cloud.region`
+    pub region: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// the team owning the app; exported as `team`
+    pub team: Option<String>,
+    #[prost(btree_map = "string, string", tag = "4")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    /// custom attributes, exported under their keys
+    pub tags: BTreeMap<String, String>,
+}
+
+impl TelemetryResourceConfig {
+    /// The attributes, by their exported keys. Custom tags can't override the declared attributes.
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        let mut attributes = self.tags.clone();
+        for (key, value) in [
+            ("deployment.environment", &self.environment),
+            ("cloud.region", &self.region),
+            ("team", &self.team),
+        ] {
+            if let Some(value) = value {
+                attributes.insert(key.to_string(), value.clone());
+            }
+        }
+        attributes
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Oneof)]
@@ -13,6 +56,9 @@ pub enum TelemetryTraceConfig {
     Dozer(DozerTelemetryConfig),
     #[prost(message, tag = "2")]
     Jaeger(JaegerTelemetryConfig),
+    #[prost(message, tag = "5")]
+    /// Spans written as JSON lines, for environments without a collector
+    File(FileTelemetryConfig),
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
@@ -26,11 +72,46 @@ pub struct DozerTelemetryConfig {
     #[prost(uint32, tag = "3")]
     #[serde(default = "default_sample_ratio")]
     pub sample_percent: u32,
+    #[prost(uint32, tag = "4")]
+    #[serde(default = "default_max_queue_size")]
+    /// spans buffered before export; spans finished when the buffer is full are dropped
+    pub max_queue_size: u32,
+    #[prost(uint32, tag = "5")]
+    #[serde(default = "default_max_export_batch_size")]
+    /// spans exported in one batch, at most `max_queue_size`
+    pub max_export_batch_size: u32,
+    #[prost(uint64, tag = "6")]
+    #[serde(default = "default_export_timeout_ms")]
+    /// time an export can take before it's cancelled
+    pub export_timeout_ms: u64,
+    #[prost(uint64, tag = "7")]
+    #[serde(default = "default_scheduled_delay_ms")]
+    /// delay between two exports of the buffered spans
+    pub scheduled_delay_ms: u64,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
 pub struct JaegerTelemetryConfig {}
 
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
+pub struct FileTelemetryConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// file the spans are written to; spans are written to stdout if not set
+    pub path: Option<String>,
+    #[prost(uint64, tag = "2")]
+    #[serde(default = "default_max_file_size_bytes")]
+    /// size the file is rolled over at, to `<path>.1`, `<path>.2` and so on
+    pub max_file_size_bytes: u64,
+    #[prost(uint32, tag = "3")]
+    #[serde(default = "default_max_files")]
+    /// rolled over files kept, the oldest is deleted
+    pub max_files: u32,
+    #[prost(uint32, tag = "4")]
+    #[serde(default = "default_sample_ratio")]
+    pub sample_percent: u32,
+}
+
 fn default_grpc_adapter() -> String {
     "arrow".to_owned()
 }
@@ -43,8 +124,64 @@ fn default_sample_ratio() -> u32 {
     10
 }
 
+fn default_max_file_size_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_max_files() -> u32 {
+    5
+}
+
+fn default_max_queue_size() -> u32 {
+    100000
+}
+
+fn default_max_export_batch_size() -> u32 {
+    512
+}
+
+fn default_export_timeout_ms() -> u64 {
+    30000
+}
+
+fn default_scheduled_delay_ms() -> u64 {
+    5000
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Oneof)]
 pub enum TelemetryMetricsConfig {
     #[prost(message, tag = "1")]
     Prometheus(()),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dozer_telemetry_config_defaults() {
+        let config: DozerTelemetryConfig = serde_yaml::from_str("sample_percent: 50").unwrap();
+        assert_eq!(config.sample_percent, 50);
+        assert_eq!(config.max_queue_size, 100000);
+        assert_eq!(config.max_export_batch_size, 512);
+        assert_eq!(config.export_timeout_ms, 30000);
+        assert_eq!(config.scheduled_delay_ms, 5000);
+    }
+
+    #[test]
+    fn test_dozer_telemetry_config_batch_processor() {
+        let config: DozerTelemetryConfig = serde_yaml::from_str(
+            r#"
+max_queue_size: 1000
+max_export_batch_size: 100
+export_timeout_ms: 1000
+scheduled_delay_ms: 500
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.max_queue_size, 1000);
+        assert_eq!(config.max_export_batch_size, 100);
+        assert_eq!(config.export_timeout_ms, 1000);
+        assert_eq!(config.scheduled_delay_ms, 500);
+    }
+}
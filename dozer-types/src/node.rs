@@ -1,5 +1,7 @@
 use serde::{self, Deserialize, Serialize};
 
+use crate::types::Field;
+
 use std::{
     collections::HashMap,
     fmt::{Display, Formatter},
@@ -92,6 +94,17 @@ impl OpIdentifier {
 
 pub type SourceStates = HashMap<NodeHandle, OpIdentifier>;
 
+/// The source row of the operation that last changed a record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub connection: String,
+    pub table: String,
+    /// Primary key values of the source row.
+    pub primary_key: Vec<Field>,
+    /// Identifier of the operation in the source.
+    pub op_id: OpIdentifier,
+}
+
 #[test]
 fn test_handle_to_from_bytes() {
     let original = NodeHandle::new(Some(10), 100.to_string());
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Operation, Record};
+
+/// A set of field indexes, stored as a bitmask: field `i` is bit `i % 8` of byte `i / 8`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldMask(Vec<u8>);
+
+impl FieldMask {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        let byte = index / 8;
+        if self.0.len() <= byte {
+            self.0.resize(byte + 1, 0);
+        }
+        self.0[byte] |= 1 << (index % 8);
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.0
+            .get(index / 8)
+            .map_or(false, |byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|byte| *byte == 0)
+    }
+
+    /// The indexes in the mask, in order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.0.len() * 8).filter(|index| self.contains(*index))
+    }
+}
+
+impl FromIterator<usize> for FieldMask {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut mask = Self::default();
+        for index in iter {
+            mask.insert(index);
+        }
+        mask
+    }
+}
+
+impl Record {
+    /// The fields of `other` that differ from the ones of this record. Fields only one of the records has differ.
+    pub fn diff(&self, other: &Record) -> FieldMask {
+        (0..self.values.len().max(other.values.len()))
+            .filter(|index| self.values.get(*index) != other.values.get(*index))
+            .collect()
+    }
+}
+
+impl Operation {
+    /// The fields an update changes, or `None` for inserts and deletes. The old record of an update may only have its
+    /// primary key, with the other fields null, in which case they are changed unless they're null.
+    pub fn changed_fields(&self) -> Option<FieldMask> {
+        match self {
+            Operation::Update { old, new } => Some(old.diff(new)),
+            Operation::Insert { .. } | Operation::Delete { .. } => None,
+        }
+    }
+
+    /// Whether this is an update that changes nothing, which can be skipped.
+    pub fn is_noop(&self) -> bool {
+        matches!(self, Operation::Update { old, new } if old == new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Field;
+
+    use super::*;
+
+    #[test]
+    fn test_field_mask() {
+        let mut mask = FieldMask::default();
+        assert!(mask.is_empty());
+        mask.insert(1);
+        mask.insert(9);
+        assert_eq!(mask.as_bytes(), &[0b10, 0b10]);
+        assert!(mask.contains(9));
+        assert!(!mask.contains(8));
+        assert!(!mask.contains(100));
+        assert_eq!(mask.iter().collect::<Vec<_>>(), vec![1, 9]);
+        assert_eq!(FieldMask::from_bytes(vec![0b10, 0b10]), mask);
+        assert_eq!([1, 9].into_iter().collect::<FieldMask>(), mask);
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = Record::new(vec![Field::Int(1), Field::Null, Field::Boolean(true)]);
+        let new = Record::new(vec![Field::Int(1), Field::Int(2), Field::Boolean(false)]);
+        assert_eq!(old.diff(&new).iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            old.diff(&Record::new(vec![Field::Int(1)]))
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let update = Operation::Update {
+            old: old.clone(),
+            new,
+        };
+        assert_eq!(
+            update.changed_fields().unwrap().iter().collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(!update.is_noop());
+        assert!(Operation::Update {
+            old: old.clone(),
+            new: old.clone()
+        }
+        .is_noop());
+        assert_eq!(Operation::Insert { new: old }.changed_fields(), None);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::errors::types::{DeserializationError, TypeError};
 use crate::json_types::JsonValue;
 use crate::types::{
-    DozerDuration, DozerPoint, FieldDefinition, Schema, SourceDefinition, TimeUnit,
+    DozerDuration, DozerMoney, DozerPoint, FieldDefinition, Schema, SourceDefinition, TimeUnit,
 };
 #[allow(unused_imports)]
 use chrono::{DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, TimeZone, Utc};
@@ -33,6 +33,10 @@ pub enum Field {
     Point(DozerPoint),
     Duration(DozerDuration),
     Null,
+    // Variants are serialized by their index in persisted logs, so new ones go last.
+    Money(DozerMoney),
+    /// The ordinal of a value of an enum field, its position in the field's declared values.
+    Enum(u32),
 }
 
 impl Field {
@@ -54,6 +58,8 @@ impl Field {
             Field::Json(b) => bincode::serialize(b).unwrap().len(),
             Field::Point(_p) => 16,
             Field::Duration(_) => 17,
+            Field::Money(_) => 19,
+            Field::Enum(_) => 4,
             Field::Null => 0,
         }
     }
@@ -75,6 +81,8 @@ impl Field {
             Field::Json(b) => Cow::Owned(bincode::serialize(b).unwrap()),
             Field::Point(p) => Cow::Owned(p.to_bytes().into()),
             Field::Duration(d) => Cow::Owned(d.to_bytes().into()),
+            Field::Money(m) => Cow::Owned(m.to_bytes().into()),
+            Field::Enum(e) => Cow::Owned(e.to_be_bytes().into()),
             Field::Null => Cow::Owned([].into()),
         }
     }
@@ -158,6 +166,13 @@ impl Field {
                 DozerDuration::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
             )),
             15 => Ok(Field::Null),
+            16 => Ok(Field::Money(
+                DozerMoney::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
+            )),
+            17 => Ok(Field::Enum(u32::from_be_bytes(
+                val.try_into()
+                    .map_err(|_| DeserializationError::BadDataLength)?,
+            ))),
             other => Err(DeserializationError::UnrecognisedFieldType(other)),
         }
     }
@@ -180,6 +195,8 @@ impl Field {
             Field::Point(_) => 13,
             Field::Duration(_) => 14,
             Field::Null => 15,
+            Field::Money(_) => 16,
+            Field::Enum(_) => 17,
         }
     }
 
@@ -324,6 +341,20 @@ impl Field {
         }
     }
 
+    pub fn as_money(&self) -> Option<DozerMoney> {
+        match self {
+            Field::Money(m) => Some(*m),
+            _ => None,
+        }
+    }
+
+    pub fn as_enum(&self) -> Option<u32> {
+        match self {
+            Field::Enum(e) => Some(*e),
+            _ => None,
+        }
+    }
+
     pub fn as_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -477,6 +508,7 @@ impl Field {
             Field::Timestamp(t) => Some(t.to_rfc3339()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Json(j) => Some(j.to_string()),
+            Field::Money(m) => Some(m.to_string()),
             Field::Null => Some("".to_string()),
             _ => None,
         }
@@ -501,6 +533,7 @@ impl Field {
             Field::Timestamp(t) => Some(t.to_rfc3339()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Json(j) => Some(j.to_string()),
+            Field::Money(m) => Some(m.to_string()),
             Field::Null => Some("".to_string()),
             _ => None,
         }
@@ -614,6 +647,20 @@ impl Field {
         }
     }
 
+    /// Money, or a string such as `12.50 USD`.
+    pub fn to_money(&self) -> Result<Option<DozerMoney>, TypeError> {
+        match self {
+            Field::Money(m) => Ok(Some(*m)),
+            Field::String(s) | Field::Text(s) => s.parse().map(Some),
+            Field::Null => Ok(None),
+            _ => Err(TypeError::InvalidFieldValue {
+                field_type: FieldType::Money,
+                nullable: false,
+                value: format!("{:?}", self),
+            }),
+        }
+    }
+
     pub fn to_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -640,6 +687,8 @@ impl Display for Field {
             Field::Json(v) => f.write_str(&format!("{v}")),
             Field::Point(v) => f.write_str(&format!("{v} (Point)")),
             Field::Duration(d) => f.write_str(&format!("{:?} {:?} (Duration)", d.0, d.1)),
+            Field::Money(m) => f.write_str(&format!("{m} (Money)")),
+            Field::Enum(e) => f.write_str(&format!("{e} (Enum)")),
             Field::Null => f.write_str("NULL"),
         }
     }
@@ -681,6 +730,10 @@ pub enum FieldType {
     Point,
     /// Duration up to nanoseconds.
     Duration,
+    /// A decimal amount of an ISO 4217 currency.
+    Money,
+    /// One of the values declared by the field definition, stored as its ordinal.
+    Enum,
 }
 
 impl TryFrom<&str> for FieldType {
@@ -706,6 +759,8 @@ impl TryFrom<&str> for FieldType {
             "jsonb_array" => FieldType::Json,
             "point" => FieldType::Point,
             "duration" => FieldType::Duration,
+            "money" => FieldType::Money,
+            "enum" => FieldType::Enum,
             _ => return Err(format!("Unsupported '{value}' type")),
         };
 
@@ -731,6 +786,8 @@ impl Display for FieldType {
             FieldType::Json => f.write_str("json"),
             FieldType::Point => f.write_str("point"),
             FieldType::Duration => f.write_str("duration"),
+            FieldType::Money => f.write_str("money"),
+            FieldType::Enum => f.write_str("enum"),
         }
     }
 }
@@ -1007,6 +1064,8 @@ impl pyo3::ToPyObject for Field {
             Field::Json(_val) => todo!(),
             Field::Point(_val) => todo!(),
             Field::Duration(_d) => todo!(),
+            Field::Money(val) => val.to_string().to_object(py),
+            Field::Enum(val) => val.to_object(py),
             Field::Null => unreachable!(),
         }
     }
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Field, FieldDefinition, FieldType};
+
+/// A function whose values of a field can be indexed, see `IndexDefinition::Expression`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum IndexFunction {
+    /// Lowercase of a string.
+    Lower,
+    /// Uppercase of a string.
+    Upper,
+    /// Date of a timestamp.
+    Date,
+}
+
+impl IndexFunction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IndexFunction::Lower => "lower",
+            IndexFunction::Upper => "upper",
+            IndexFunction::Date => "date",
+        }
+    }
+
+    /// Parses a call like `lower(email)` to the function and the name of the field it's called with.
+    pub fn parse_call(expression: &str) -> Option<(Self, &str)> {
+        let (name, rest) = expression.trim().split_once('(')?;
+        let field = rest.strip_suffix(')')?.trim();
+        let function = match name.trim() {
+            "lower" => IndexFunction::Lower,
+            "upper" => IndexFunction::Upper,
+            "date" => IndexFunction::Date,
+            _ => return None,
+        };
+        Some((function, field))
+    }
+
+    /// The type of the values of this function of a field of type `typ`, `None` if the function doesn't take `typ`.
+    pub fn result_type(&self, typ: FieldType) -> Option<FieldType> {
+        match (self, typ) {
+            (IndexFunction::Lower | IndexFunction::Upper, FieldType::String | FieldType::Text) => {
+                Some(typ)
+            }
+            (IndexFunction::Date, FieldType::Timestamp | FieldType::Date) => Some(FieldType::Date),
+            _ => None,
+        }
+    }
+
+    /// The value of this function of `field`. Values of types the function doesn't take are `null`.
+    pub fn evaluate(&self, field: &Field) -> Field {
+        match (self, field) {
+            (IndexFunction::Lower, Field::String(string)) => Field::String(string.to_lowercase()),
+            (IndexFunction::Lower, Field::Text(string)) => Field::Text(string.to_lowercase()),
+            (IndexFunction::Upper, Field::String(string)) => Field::String(string.to_uppercase()),
+            (IndexFunction::Upper, Field::Text(string)) => Field::Text(string.to_uppercase()),
+            (IndexFunction::Date, Field::Timestamp(timestamp)) => {
+                Field::Date(timestamp.date_naive())
+            }
+            (IndexFunction::Date, Field::Date(date)) => Field::Date(*date),
+            _ => Field::Null,
+        }
+    }
+}
+
+/// A function of a field, e.g. `lower(email)`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct IndexExpression {
+    pub function: IndexFunction,
+    pub field_index: usize,
+}
+
+impl IndexExpression {
+    /// Parses a call like `lower(email)` of a field of `fields`. Returns `None` if it's not a call of a field the
+    /// function takes.
+    pub fn parse(expression: &str, fields: &[FieldDefinition]) -> Option<Self> {
+        let (function, field_name) = IndexFunction::parse_call(expression)?;
+        let field_index = fields.iter().position(|field| field.name == field_name)?;
+        function.result_type(fields[field_index].typ)?;
+        Some(Self {
+            function,
+            field_index,
+        })
+    }
+
+    /// The name queries refer to the values of this expression by.
+    pub fn name(&self, fields: &[FieldDefinition]) -> String {
+        format!(
+            "{}({})",
+            self.function.name(),
+            fields[self.field_index].name
+        )
+    }
+
+    pub fn evaluate(&self, values: &[Field]) -> Field {
+        values
+            .get(self.field_index)
+            .map_or(Field::Null, |field| self.function.evaluate(field))
+    }
+}
@@ -11,14 +11,22 @@ use crate::errors::types::TypeError;
 use prettytable::{Cell, Row, Table};
 use serde::{self, Deserialize, Serialize};
 
+mod diff;
 pub mod field;
+pub mod index_expression;
+mod money;
+mod timezone;
 
 #[cfg(test)]
 mod tests;
 
 use crate::errors::internal::BoxedError;
 use crate::errors::types::TypeError::InvalidFieldValue;
+pub use diff::FieldMask;
 pub use field::{field_test_cases, Field, FieldType, DATE_FORMAT};
+pub use index_expression::{IndexExpression, IndexFunction};
+pub use money::{Currency, DozerMoney};
+pub use timezone::Timezone;
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum SourceDefinition {
@@ -40,6 +48,9 @@ pub struct FieldDefinition {
     pub nullable: bool,
     #[serde(default)]
     pub source: SourceDefinition,
+    /// The values of an enum field, whose ordinals are their positions in this list.
+    #[serde(default)]
+    pub enum_values: Vec<String>,
 }
 
 impl FieldDefinition {
@@ -49,6 +60,46 @@ impl FieldDefinition {
             typ,
             nullable,
             source,
+            enum_values: vec![],
+        }
+    }
+
+    pub fn with_enum_values(mut self, values: Vec<String>) -> Self {
+        self.enum_values = values;
+        self
+    }
+
+    /// The value of an ordinal of this enum field.
+    pub fn enum_value(&self, ordinal: u32) -> Option<&str> {
+        self.enum_values.get(ordinal as usize).map(String::as_str)
+    }
+
+    /// The ordinal of a value of this enum field.
+    pub fn enum_ordinal(&self, value: &str) -> Option<u32> {
+        self.enum_values
+            .iter()
+            .position(|known| known == value)
+            .map(|ordinal| ordinal as u32)
+    }
+
+    /// The enum field of a value, which must be one of the declared values.
+    pub fn enum_field(&self, value: &str) -> Result<Field, TypeError> {
+        self.enum_ordinal(value)
+            .map(Field::Enum)
+            .ok_or_else(|| TypeError::UnknownEnumValue {
+                field: self.name.clone(),
+                value: value.to_string(),
+            })
+    }
+
+    /// Checks that an ordinal is one of a declared value.
+    pub fn check_enum_ordinal(&self, ordinal: u32) -> Result<Field, TypeError> {
+        match self.enum_value(ordinal) {
+            Some(_) => Ok(Field::Enum(ordinal)),
+            None => Err(TypeError::UnknownEnumValue {
+                field: self.name.clone(),
+                value: ordinal.to_string(),
+            }),
         }
     }
 
@@ -135,6 +186,9 @@ pub enum IndexDefinition {
     SortedInverted(Vec<usize>),
     /// Full text index, supporting `Contains`, `MatchesAny` and `MatchesAll` filter on exactly one field.
     FullText(usize),
+    /// Sorted index on the values of an expression of a field, supporting the same filters as a single field
+    /// `SortedInverted` index. Queries refer to the values by the name of the expression, e.g. `lower(email)`.
+    Expression(IndexExpression),
 }
 
 pub type SchemaWithIndex = (Schema, Vec<IndexDefinition>);
@@ -0,0 +1,362 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::types::TypeError;
+use crate::types::FieldType;
+
+/// Active ISO 4217 currency codes, sorted, with the digits of their minor unit. Funds and precious metals have no
+/// minor unit.
+const CURRENCIES: &[(&str, Option<u32>)] = &[
+    ("AED", Some(2)),
+    ("AFN", Some(2)),
+    ("ALL", Some(2)),
+    ("AMD", Some(2)),
+    ("ANG", Some(2)),
+    ("AOA", Some(2)),
+    ("ARS", Some(2)),
+    ("AUD", Some(2)),
+    ("AWG", Some(2)),
+    ("AZN", Some(2)),
+    ("BAM", Some(2)),
+    ("BBD", Some(2)),
+    ("BDT", Some(2)),
+    ("BGN", Some(2)),
+    ("BHD", Some(3)),
+    ("BIF", Some(0)),
+    ("BMD", Some(2)),
+    ("BND", Some(2)),
+    ("BOB", Some(2)),
+    ("BOV", Some(2)),
+    ("BRL", Some(2)),
+    ("BSD", Some(2)),
+    ("BTN", Some(2)),
+    ("BWP", Some(2)),
+    ("BYN", Some(2)),
+    ("BZD", Some(2)),
+    ("CAD", Some(2)),
+    ("CDF", Some(2)),
+    ("CHE", Some(2)),
+    ("CHF", Some(2)),
+    ("CHW", Some(2)),
+    ("CLF", Some(4)),
+    ("CLP", Some(0)),
+    ("CNY", Some(2)),
+    ("COP", Some(2)),
+    ("COU", Some(2)),
+    ("CRC", Some(2)),
+    ("CUC", Some(2)),
+    ("CUP", Some(2)),
+    ("CVE", Some(2)),
+    ("CZK", Some(2)),
+    ("DJF", Some(0)),
+    ("DKK", Some(2)),
+    ("DOP", Some(2)),
+    ("DZD", Some(2)),
+    ("EGP", Some(2)),
+    ("ERN", Some(2)),
+    ("ETB", Some(2)),
+    ("EUR", Some(2)),
+    ("FJD", Some(2)),
+    ("FKP", Some(2)),
+    ("GBP", Some(2)),
+    ("GEL", Some(2)),
+    ("GHS", Some(2)),
+    ("GIP", Some(2)),
+    ("GMD", Some(2)),
+    ("GNF", Some(0)),
+    ("GTQ", Some(2)),
+    ("GYD", Some(2)),
+    ("HKD", Some(2)),
+    ("HNL", Some(2)),
+    ("HTG", Some(2)),
+    ("HUF", Some(2)),
+    ("IDR", Some(2)),
+    ("ILS", Some(2)),
+    ("INR", Some(2)),
+    ("IQD", Some(3)),
+    ("IRR", Some(2)),
+    ("ISK", Some(0)),
+    ("JMD", Some(2)),
+    ("JOD", Some(3)),
+    ("JPY", Some(0)),
+    ("KES", Some(2)),
+    ("KGS", Some(2)),
+    ("KHR", Some(2)),
+    ("KMF", Some(0)),
+    ("KPW", Some(2)),
+    ("KRW", Some(0)),
+    ("KWD", Some(3)),
+    ("KYD", Some(2)),
+    ("KZT", Some(2)),
+    ("LAK", Some(2)),
+    ("LBP", Some(2)),
+    ("LKR", Some(2)),
+    ("LRD", Some(2)),
+    ("LSL", Some(2)),
+    ("LYD", Some(3)),
+    ("MAD", Some(2)),
+    ("MDL", Some(2)),
+    ("MGA", Some(2)),
+    ("MKD", Some(2)),
+    ("MMK", Some(2)),
+    ("MNT", Some(2)),
+    ("MOP", Some(2)),
+    ("MRU", Some(2)),
+    ("MUR", Some(2)),
+    ("MVR", Some(2)),
+    ("MWK", Some(2)),
+    ("MXN", Some(2)),
+    ("MXV", Some(2)),
+    ("MYR", Some(2)),
+    ("MZN", Some(2)),
+    ("NAD", Some(2)),
+    ("NGN", Some(2)),
+    ("NIO", Some(2)),
+    ("NOK", Some(2)),
+    ("NPR", Some(2)),
+    ("NZD", Some(2)),
+    ("OMR", Some(3)),
+    ("PAB", Some(2)),
+    ("PEN", Some(2)),
+    ("PGK", Some(2)),
+    ("PHP", Some(2)),
+    ("PKR", Some(2)),
+    ("PLN", Some(2)),
+    ("PYG", Some(0)),
+    ("QAR", Some(2)),
+    ("RON", Some(2)),
+    ("RSD", Some(2)),
+    ("RUB", Some(2)),
+    ("RWF", Some(0)),
+    ("SAR", Some(2)),
+    ("SBD", Some(2)),
+    ("SCR", Some(2)),
+    ("SDG", Some(2)),
+    ("SEK", Some(2)),
+    ("SGD", Some(2)),
+    ("SHP", Some(2)),
+    ("SLE", Some(2)),
+    ("SLL", Some(2)),
+    ("SOS", Some(2)),
+    ("SRD", Some(2)),
+    ("SSP", Some(2)),
+    ("STN", Some(2)),
+    ("SVC", Some(2)),
+    ("SYP", Some(2)),
+    ("SZL", Some(2)),
+    ("THB", Some(2)),
+    ("TJS", Some(2)),
+    ("TMT", Some(2)),
+    ("TND", Some(3)),
+    ("TOP", Some(2)),
+    ("TRY", Some(2)),
+    ("TTD", Some(2)),
+    ("TWD", Some(2)),
+    ("TZS", Some(2)),
+    ("UAH", Some(2)),
+    ("UGX", Some(0)),
+    ("USD", Some(2)),
+    ("USN", Some(2)),
+    ("UYI", Some(0)),
+    ("UYU", Some(2)),
+    ("UYW", Some(4)),
+    ("UZS", Some(2)),
+    ("VED", Some(2)),
+    ("VES", Some(2)),
+    ("VND", Some(0)),
+    ("VUV", Some(0)),
+    ("WST", Some(2)),
+    ("XAF", Some(0)),
+    ("XAG", None),
+    ("XAU", None),
+    ("XBA", None),
+    ("XBB", None),
+    ("XBC", None),
+    ("XBD", None),
+    ("XCD", Some(2)),
+    ("XDR", None),
+    ("XOF", Some(0)),
+    ("XPD", None),
+    ("XPF", Some(0)),
+    ("XPT", None),
+    ("XSU", None),
+    ("XTS", None),
+    ("XUA", None),
+    ("XXX", None),
+    ("YER", Some(2)),
+    ("ZAR", Some(2)),
+    ("ZMW", Some(2)),
+    ("ZWL", Some(2)),
+];
+
+/// An ISO 4217 currency code, e.g. `USD`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub fn code(&self) -> &str {
+        // Codes are checked to be ASCII letters.
+        std::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+
+    /// Digits after the decimal separator of the minor unit, e.g. 2 for cents, or `None` if it has no minor unit.
+    pub fn minor_units(&self) -> Option<u32> {
+        let index = CURRENCIES
+            .binary_search_by(|(code, _)| code.cmp(&self.code()))
+            .expect("currencies are known");
+        CURRENCIES[index].1
+    }
+}
+
+impl FromStr for Currency {
+    type Err = TypeError;
+
+    /// Codes are case insensitive.
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let code = str.trim().to_ascii_uppercase();
+        if CURRENCIES
+            .binary_search_by(|(known, _)| known.cmp(&code.as_str()))
+            .is_err()
+        {
+            return Err(TypeError::UnknownCurrency(str.to_string()));
+        }
+        let mut bytes = [0; 3];
+        bytes.copy_from_slice(code.as_bytes());
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = TypeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.code().to_string()
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// An amount of a currency. Amounts of different currencies can't be added, subtracted or compared, except for
+/// ordering values deterministically, by currency first.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DozerMoney {
+    pub currency: Currency,
+    pub amount: Decimal,
+}
+
+impl DozerMoney {
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { currency, amount }
+    }
+
+    /// Rounds the amount half away from zero to the currency's minor unit, if it has one.
+    pub fn round(&self) -> Self {
+        match self.currency.minor_units() {
+            Some(units) => Self::new(
+                self.amount
+                    .round_dp_with_strategy(units, RoundingStrategy::MidpointAwayFromZero),
+                self.currency,
+            ),
+            None => *self,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 19] {
+        let mut result = [0_u8; 19];
+        result[0..3].copy_from_slice(&self.currency.0);
+        result[3..19].copy_from_slice(&self.amount.serialize());
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypeError> {
+        let invalid = || TypeError::InvalidFieldValue {
+            field_type: FieldType::Money,
+            nullable: false,
+            value: format!("{bytes:?}"),
+        };
+        if bytes.len() != 19 {
+            return Err(invalid());
+        }
+        let currency = std::str::from_utf8(&bytes[0..3])
+            .map_err(|_| invalid())?
+            .parse()?;
+        let amount = Decimal::deserialize(bytes[3..19].try_into().map_err(|_| invalid())?);
+        Ok(Self::new(amount, currency))
+    }
+}
+
+impl Display for DozerMoney {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+impl FromStr for DozerMoney {
+    type Err = TypeError;
+
+    /// Parses an amount and a currency code separated by whitespace, in either order, e.g. `12.50 USD`.
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let error = || TypeError::InvalidFieldValue {
+            field_type: FieldType::Money,
+            nullable: false,
+            value: str.to_string(),
+        };
+        let mut parts = str.split_whitespace();
+        let (Some(first), Some(second), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(error());
+        };
+        let (amount, currency) = match Decimal::from_str_exact(first) {
+            Ok(amount) => (amount, second),
+            Err(_) => (Decimal::from_str_exact(second).map_err(|_| error())?, first),
+        };
+        Ok(Self::new(amount, currency.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currencies_sorted() {
+        assert!(CURRENCIES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_currency() {
+        let usd = "usd".parse::<Currency>().unwrap();
+        assert_eq!(usd.code(), "USD");
+        assert_eq!(usd.minor_units(), Some(2));
+        assert_eq!("JPY".parse::<Currency>().unwrap().minor_units(), Some(0));
+        assert_eq!("XAU".parse::<Currency>().unwrap().minor_units(), None);
+        assert!(matches!(
+            "ABC".parse::<Currency>(),
+            Err(TypeError::UnknownCurrency(code)) if code == "ABC"
+        ));
+    }
+
+    #[test]
+    fn test_money() {
+        let money = "12.345 EUR".parse::<DozerMoney>().unwrap();
+        assert_eq!(money, "EUR 12.345".parse().unwrap());
+        assert_eq!(money.to_string(), "12.345 EUR");
+        assert_eq!(money.round().amount, Decimal::new(1235, 2));
+        assert_eq!(DozerMoney::from_bytes(&money.to_bytes()).unwrap(), money);
+        assert!("12.34".parse::<DozerMoney>().is_err());
+        assert!("12.34 EUR USD".parse::<DozerMoney>().is_err());
+    }
+}
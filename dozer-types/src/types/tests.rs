@@ -1,4 +1,8 @@
-use crate::types::{field_test_cases, DozerDuration, DozerPoint, Field, TimeUnit};
+use crate::types::{
+    field_test_cases, DozerDuration, DozerMoney, DozerPoint, Field, FieldDefinition, FieldType,
+    IndexDefinition, IndexExpression, IndexFunction, Schema, SchemaWithIndex, SourceDefinition,
+    TimeUnit,
+};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
@@ -13,6 +17,91 @@ fn data_encoding_len_must_agree_with_encode() {
     }
 }
 
+#[test]
+fn test_money_encoding() {
+    let field = Field::Money("-12.34 JPY".parse::<DozerMoney>().unwrap());
+    let bytes = field.encode();
+    assert_eq!(bytes.len(), field.encoding_len());
+    assert_eq!(Field::decode(&bytes).unwrap(), field);
+}
+
+#[test]
+fn test_enum_encoding() {
+    let field = Field::Enum(258);
+    let bytes = field.encode();
+    assert_eq!(bytes.len(), field.encoding_len());
+    assert_eq!(Field::decode(&bytes).unwrap(), field);
+}
+
+#[test]
+fn test_serialized_variant_indexes() {
+    // Logs persisted before `Money` and `Enum` existed must decode the same.
+    let variant_index = |field: Field| bincode::serialize(&field).unwrap()[..4].to_vec();
+    assert_eq!(
+        variant_index(Field::Duration(DozerDuration(
+            std::time::Duration::from_secs(1),
+            TimeUnit::Seconds,
+        ))),
+        14u32.to_le_bytes()
+    );
+    assert_eq!(variant_index(Field::Null), 15u32.to_le_bytes());
+    assert_eq!(
+        variant_index(Field::Money("1 USD".parse::<DozerMoney>().unwrap())),
+        16u32.to_le_bytes()
+    );
+    assert_eq!(variant_index(Field::Enum(0)), 17u32.to_le_bytes());
+}
+
+#[test]
+fn test_enum_values() {
+    let field = FieldDefinition::new(
+        "status".to_string(),
+        FieldType::Enum,
+        false,
+        SourceDefinition::Dynamic,
+    )
+    .with_enum_values(vec!["active".to_string(), "closed".to_string()]);
+    assert_eq!(field.enum_value(1), Some("closed"));
+    assert_eq!(field.enum_value(2), None);
+    assert_eq!(field.enum_ordinal("active"), Some(0));
+    assert_eq!(field.enum_ordinal("Active"), None);
+    assert_eq!(field.enum_field("closed").unwrap(), Field::Enum(1));
+    assert!(field.enum_field("open").is_err());
+    assert_eq!(field.check_enum_ordinal(0).unwrap(), Field::Enum(0));
+    assert!(field.check_enum_ordinal(2).is_err());
+}
+
+#[test]
+fn test_schema_with_index_bincode_round_trip() {
+    // Cache schemas are stored with bincode, which needs every field to be written.
+    let mut schema = Schema::new();
+    schema
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "status".to_string(),
+                FieldType::Enum,
+                false,
+                SourceDefinition::Dynamic,
+            )
+            .with_enum_values(vec!["active".to_string(), "closed".to_string()]),
+            false,
+        );
+    let schema: SchemaWithIndex = (schema, vec![IndexDefinition::SortedInverted(vec![0])]);
+
+    let bytes = bincode::serialize(&schema).unwrap();
+    let decoded: SchemaWithIndex = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, schema);
+}
+
 #[test]
 fn test_as_conversion() {
     let field = Field::UInt(1);
@@ -288,6 +377,14 @@ fn test_as_conversion() {
     assert!(field.as_duration().is_some());
     assert!(field.as_null().is_none());
 
+    let field = Field::Money("12.34 USD".parse::<DozerMoney>().unwrap());
+    assert!(field.as_uint().is_none());
+    assert!(field.as_string().is_none());
+    assert!(field.as_decimal().is_none());
+    assert!(field.as_duration().is_none());
+    assert!(field.as_money().is_some());
+    assert!(field.as_null().is_none());
+
     let field = Field::Null;
     assert!(field.as_uint().is_none());
     assert!(field.as_int().is_none());
@@ -581,6 +678,17 @@ fn test_to_conversion() {
     assert!(field.to_duration().is_ok());
     assert!(field.to_null().is_none());
 
+    let field = Field::Money("12.34 USD".parse::<DozerMoney>().unwrap());
+    assert!(field.to_uint().is_none());
+    assert!(field.to_float().is_none());
+    assert!(field.to_string().is_some());
+    assert!(field.to_text().is_some());
+    assert!(field.to_decimal().is_none());
+    assert!(field.to_json().is_none());
+    assert!(field.to_duration().is_err());
+    assert!(field.to_money().unwrap().is_some());
+    assert!(field.to_null().is_none());
+
     let field = Field::Null;
     assert!(field.to_uint().is_some());
     assert!(field.to_int().is_some());
@@ -599,3 +707,53 @@ fn test_to_conversion() {
     assert!(field.to_duration().is_ok());
     assert!(field.to_null().is_some());
 }
+
+#[test]
+fn test_index_expression() {
+    let fields = vec![
+        FieldDefinition::new(
+            "email".to_string(),
+            FieldType::String,
+            true,
+            SourceDefinition::Dynamic,
+        ),
+        FieldDefinition::new(
+            "created_at".to_string(),
+            FieldType::Timestamp,
+            false,
+            SourceDefinition::Dynamic,
+        ),
+    ];
+
+    let lower = IndexExpression::parse("lower(email)", &fields).unwrap();
+    assert_eq!(lower.function, IndexFunction::Lower);
+    assert_eq!(lower.name(&fields), "lower(email)");
+    assert_eq!(
+        IndexExpression::parse(" date( created_at ) ", &fields),
+        Some(IndexExpression {
+            function: IndexFunction::Date,
+            field_index: 1,
+        })
+    );
+    assert_eq!(IndexExpression::parse("date(email)", &fields), None);
+    assert_eq!(IndexExpression::parse("trim(email)", &fields), None);
+    assert_eq!(IndexExpression::parse("lower(name)", &fields), None);
+    assert_eq!(IndexExpression::parse("email", &fields), None);
+
+    let created_at = DateTime::parse_from_rfc3339("2023-05-01T23:30:00+08:00").unwrap();
+    let values = vec![
+        Field::String("Ann@Example.com".to_string()),
+        Field::Timestamp(created_at),
+    ];
+    assert_eq!(
+        lower.evaluate(&values),
+        Field::String("ann@example.com".to_string())
+    );
+    assert_eq!(
+        IndexExpression::parse("date(created_at)", &fields)
+            .unwrap()
+            .evaluate(&values),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 5, 1).unwrap())
+    );
+    assert_eq!(lower.evaluate(&[Field::Null, Field::Null]), Field::Null);
+}
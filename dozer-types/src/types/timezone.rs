@@ -0,0 +1,163 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::errors::types::TypeError;
+
+/// A time zone, which is UTC, a fixed offset from it, or a named IANA time zone with daylight saving time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Timezone {
+    #[default]
+    Utc,
+    Offset(FixedOffset),
+    Named(Tz),
+}
+
+impl Timezone {
+    /// The offset from UTC at a UTC time.
+    pub fn offset(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            Timezone::Utc => Utc.fix(),
+            Timezone::Offset(offset) => *offset,
+            Timezone::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+        }
+    }
+
+    /// The same instant, with the offset of this time zone.
+    pub fn convert(&self, timestamp: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        timestamp.with_timezone(&self.offset(&timestamp.naive_utc()))
+    }
+
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.convert(&Utc::now().into())
+    }
+
+    /// The instant of a local time of this time zone. Local times repeated when clocks go back are their first
+    /// instant, and local times skipped when clocks go forward are read with the offset before.
+    pub fn resolve_local(&self, local: &NaiveDateTime) -> DateTime<FixedOffset> {
+        let offset = match self {
+            Timezone::Named(tz) => match tz.from_local_datetime(local).earliest() {
+                Some(timestamp) => return timestamp.with_timezone(&timestamp.offset().fix()),
+                None => self.offset(&(*local - Duration::days(1))),
+            },
+            _ => self.offset(local),
+        };
+        DateTime::from_utc(*local - offset, offset)
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = TypeError;
+
+    /// Parses `UTC`, an offset like `+08:00` or `-0330`, or an IANA name like `Europe/Paris`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TypeError::InvalidTimezone(s.to_string());
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(Timezone::Utc);
+        }
+        if let Some(sign) = s.chars().next().filter(|c| *c == '+' || *c == '-') {
+            let digits = s[1..].replace(':', "");
+            if (digits.len() != 2 && digits.len() != 4)
+                || !digits.chars().all(|c| c.is_ascii_digit())
+            {
+                return Err(invalid());
+            }
+            let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+            let minutes: i32 = digits[2..].parse().unwrap_or(0);
+            let seconds = (hours * 60 + minutes) * 60;
+            return FixedOffset::east_opt(if sign == '-' { -seconds } else { seconds })
+                .map(Timezone::Offset)
+                .ok_or_else(invalid);
+        }
+        s.parse::<Tz>().map(Timezone::Named).map_err(|_| invalid())
+    }
+}
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Timezone::Utc => f.write_str("UTC"),
+            Timezone::Offset(offset) => write!(f, "{offset}"),
+            Timezone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!("UTC".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!(
+            "+08:00".parse::<Timezone>().unwrap(),
+            Timezone::Offset(FixedOffset::east_opt(8 * 3600).unwrap())
+        );
+        assert_eq!(
+            "-0330".parse::<Timezone>().unwrap(),
+            Timezone::Offset(FixedOffset::west_opt(3 * 3600 + 1800).unwrap())
+        );
+        assert_eq!(
+            "Europe/Paris".parse::<Timezone>().unwrap(),
+            Timezone::Named(Tz::Europe__Paris)
+        );
+        assert!("+8".parse::<Timezone>().is_err());
+        assert!("Mars/Olympus".parse::<Timezone>().is_err());
+        assert_eq!(
+            Timezone::Named(Tz::Europe__Paris).to_string(),
+            "Europe/Paris"
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let paris = Timezone::Named(Tz::Europe__Paris);
+        let timestamp = DateTime::parse_from_rfc3339("2023-07-01T12:00:00Z").unwrap();
+        assert_eq!(
+            paris.convert(&timestamp).to_rfc3339(),
+            "2023-07-01T14:00:00+02:00"
+        );
+        let timestamp = DateTime::parse_from_rfc3339("2023-01-01T12:00:00Z").unwrap();
+        assert_eq!(
+            paris.convert(&timestamp).to_rfc3339(),
+            "2023-01-01T13:00:00+01:00"
+        );
+        assert_eq!(
+            Timezone::Utc
+                .convert(&paris.convert(&timestamp))
+                .to_rfc3339(),
+            "2023-01-01T12:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_resolve_local() {
+        let paris = Timezone::Named(Tz::Europe__Paris);
+        assert_eq!(
+            paris.resolve_local(&local(7, 1, 0, 0)).to_rfc3339(),
+            "2023-07-01T00:00:00+02:00"
+        );
+        // Clocks go back from 03:00 to 02:00 on October 29th, so 02:30 happens twice.
+        assert_eq!(
+            paris.resolve_local(&local(10, 29, 2, 30)).to_rfc3339(),
+            "2023-10-29T02:30:00+02:00"
+        );
+        // Clocks go forward from 02:00 to 03:00 on March 26th, so 02:30 doesn't happen.
+        assert_eq!(
+            paris.resolve_local(&local(3, 26, 2, 30)).to_rfc3339(),
+            "2023-03-26T02:30:00+01:00"
+        );
+    }
+}